@@ -127,11 +127,19 @@ export class BIP32 implements BIP32Interface {
 
   /**
    * Get the private key as a Uint8Array (if available)
+   * @throws Error if the key is a secret handle
    */
   get privateKey(): Uint8Array | undefined {
     return this._wasm.private_key;
   }
 
+  /**
+   * True if the private key is kept in WASM memory and cannot be exported
+   */
+  get isSecretHandle(): boolean {
+    return this._wasm.is_secret_handle;
+  }
+
   /**
    * Get the public key as a Uint8Array
    */
@@ -170,9 +178,23 @@ export class BIP32 implements BIP32Interface {
     return new BIP32(wasm);
   }
 
+  /**
+   * Get a copy of this key whose private key cannot be exported
+   *
+   * The private key stays in WASM memory: `privateKey`, `toBase58()` and `toWIF()` throw,
+   * while signing and derivation work as usual. Derived keys are secret handles as well.
+   * Call `wasm.free()` when done to zeroize the key.
+   * @returns A new BIP32 instance in secret handle mode
+   * @throws Error if this is a public key
+   */
+  toSecretHandle(): BIP32 {
+    return new BIP32(this._wasm.to_secret_handle());
+  }
+
   /**
    * Serialize to base58 string
    * @returns The base58-encoded extended key string
+   * @throws Error if the key is a secret handle
    */
   toBase58(): string {
     return this._wasm.to_base58();
@@ -181,11 +203,21 @@ export class BIP32 implements BIP32Interface {
   /**
    * Get the WIF encoding of the private key
    * @returns The WIF-encoded private key
+   * @throws Error if the key is a secret handle
    */
   toWIF(): string {
     return this._wasm.to_wif();
   }
 
+  /**
+   * Sign a 32-byte message hash with the private key (raw ECDSA)
+   * @param messageHash - The 32-byte message hash to sign
+   * @returns The signature as a Uint8Array
+   */
+  sign(messageHash: Uint8Array): Uint8Array {
+    return this._wasm.sign(messageHash);
+  }
+
   /**
    * Derive a normal (non-hardened) child key
    * @param index - The child index
@@ -21,6 +21,7 @@ export interface ECPairInterface {
   toWIF(): string;
   sign?(messageHash: Uint8Array): Uint8Array;
   verify?(messageHash: Uint8Array, signature: Uint8Array): boolean;
+  ecdh?(otherPublicKey: Uint8Array): Uint8Array;
   signMessage?(message: string): Uint8Array;
   verifyMessage?(message: string, signature: Uint8Array): boolean;
 }
@@ -120,11 +121,31 @@ export class ECPair implements ECPairInterface {
 
   /**
    * Get the private key as a Uint8Array (if available)
+   * @throws Error if the key pair is a secret handle
    */
   get privateKey(): Uint8Array | undefined {
     return this._wasm.private_key;
   }
 
+  /**
+   * True if the private key is kept in WASM memory and cannot be exported
+   */
+  get isSecretHandle(): boolean {
+    return this._wasm.is_secret_handle;
+  }
+
+  /**
+   * Get a copy of this key pair whose private key cannot be exported
+   *
+   * The private key stays in WASM memory: `privateKey` and `toWIF()` throw, while signing
+   * works as usual. Call `wasm.free()` when done to zeroize the key.
+   * @returns A new ECPair instance in secret handle mode
+   * @throws Error if this is a public key
+   */
+  toSecretHandle(): ECPair {
+    return new ECPair(this._wasm.to_secret_handle());
+  }
+
   /**
    * Get the public key as a Uint8Array
    */
@@ -175,6 +196,16 @@ export class ECPair implements ECPairInterface {
     return this._wasm.verify(messageHash, signature);
   }
 
+  /**
+   * Derive an ECDH shared secret with another public key
+   * @param otherPublicKey - The compressed or uncompressed public key of the other party
+   * @returns 32-byte SHA-256 hash of the compressed shared point (libsecp256k1 convention)
+   * @throws Error if this is a public key
+   */
+  ecdh(otherPublicKey: Uint8Array): Uint8Array {
+    return this._wasm.ecdh(otherPublicKey);
+  }
+
   /**
    * Sign a message using Bitcoin message signing (BIP-137)
    * @param message - The message to sign
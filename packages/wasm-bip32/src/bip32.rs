@@ -5,6 +5,7 @@ use ripemd::Ripemd160;
 use sha2::{Digest, Sha256};
 use std::str::FromStr;
 use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
 
 /// Internal enum to hold either public or private extended key
 #[derive(Debug, Clone)]
@@ -133,27 +134,33 @@ impl BIP32Key {
         match self {
             BIP32Key::Public(_) => Err(WasmBip32Error::new("Cannot get WIF from public key")),
             BIP32Key::Private(xprv) => {
-                let secret_bytes = xprv.private_key().to_bytes();
+                let secret_bytes = Zeroizing::new(<[u8; 32]>::from(xprv.private_key().to_bytes()));
                 let version = if testnet { 0xefu8 } else { 0x80u8 };
 
                 // WIF format: version (1) + secret (32) + compression flag (1)
-                let mut data = Vec::with_capacity(34);
+                let mut data = Zeroizing::new(Vec::with_capacity(34));
                 data.push(version);
-                data.extend_from_slice(&secret_bytes);
+                data.extend_from_slice(&secret_bytes[..]);
                 data.push(0x01); // Always compressed
 
-                Ok(bs58::encode(&data).with_check().into_string())
+                Ok(bs58::encode(data.as_slice()).with_check().into_string())
             }
         }
     }
 }
 
 /// WASM wrapper for BIP32 extended keys
+///
+/// A private key can be turned into a secret handle with `to_secret_handle`. The private key
+/// material of a secret handle stays in WASM memory: it cannot be exported as raw bytes, WIF
+/// or xprv, only used for signing and derivation. Private keys are zeroized when the instance
+/// is dropped (`free()` on the JS side).
 #[wasm_bindgen]
 #[derive(Debug, Clone)]
 pub struct WasmBIP32 {
     key: BIP32Key,
     testnet: bool,
+    secret_handle: bool,
 }
 
 #[wasm_bindgen]
@@ -168,6 +175,7 @@ impl WasmBIP32 {
             return Ok(WasmBIP32 {
                 key: BIP32Key::Private(xprv),
                 testnet,
+                secret_handle: false,
             });
         }
 
@@ -176,6 +184,7 @@ impl WasmBIP32 {
             return Ok(WasmBIP32 {
                 key: BIP32Key::Public(xpub),
                 testnet,
+                secret_handle: false,
             });
         }
 
@@ -195,6 +204,7 @@ impl WasmBIP32 {
         Ok(WasmBIP32 {
             key: BIP32Key::Private(xprv),
             testnet,
+            secret_handle: false,
         })
     }
 
@@ -223,16 +233,42 @@ impl WasmBIP32 {
     }
 
     /// Get the private key as a Uint8Array (if available)
+    ///
+    /// Fails for secret handles.
     #[wasm_bindgen(getter)]
-    pub fn private_key(&self) -> Option<js_sys::Uint8Array> {
+    pub fn private_key(&self) -> Result<Option<js_sys::Uint8Array>, WasmBip32Error> {
         match &self.key {
-            BIP32Key::Public(_) => None,
+            BIP32Key::Public(_) => Ok(None),
             BIP32Key::Private(xprv) => {
-                Some(js_sys::Uint8Array::from(&xprv.private_key().to_bytes()[..]))
+                self.check_exportable()?;
+                let bytes = Zeroizing::new(<[u8; 32]>::from(xprv.private_key().to_bytes()));
+                Ok(Some(js_sys::Uint8Array::from(&bytes[..])))
             }
         }
     }
 
+    /// Check if the private key is kept in WASM memory and cannot be exported
+    #[wasm_bindgen(getter)]
+    pub fn is_secret_handle(&self) -> bool {
+        self.secret_handle
+    }
+
+    /// Get a copy of this key whose private key cannot be exported
+    ///
+    /// Keys derived from the handle are secret handles as well.
+    #[wasm_bindgen]
+    pub fn to_secret_handle(&self) -> Result<WasmBIP32, WasmBip32Error> {
+        if self.key.is_neutered() {
+            return Err(WasmBip32Error::new(
+                "Cannot create secret handle from public key",
+            ));
+        }
+        Ok(WasmBIP32 {
+            secret_handle: true,
+            ..self.clone()
+        })
+    }
+
     /// Get the public key as a Uint8Array (33 bytes, compressed)
     #[wasm_bindgen(getter)]
     pub fn public_key(&self) -> js_sys::Uint8Array {
@@ -273,28 +309,45 @@ impl WasmBIP32 {
             BIP32Key::Private(xprv) => WasmBIP32 {
                 key: BIP32Key::Public(xprv.public_key()),
                 testnet: self.testnet,
+                secret_handle: false,
             },
         }
     }
 
     /// Serialize to base58 string
+    ///
+    /// Fails for secret handles.
     #[wasm_bindgen]
-    pub fn to_base58(&self) -> String {
-        self.key.to_base58(self.testnet)
+    pub fn to_base58(&self) -> Result<String, WasmBip32Error> {
+        if !self.key.is_neutered() {
+            self.check_exportable()?;
+        }
+        Ok(self.key.to_base58(self.testnet))
     }
 
     /// Get the WIF encoding of the private key
+    ///
+    /// Fails for secret handles.
     #[wasm_bindgen]
     pub fn to_wif(&self) -> Result<String, WasmBip32Error> {
+        self.check_exportable()?;
         self.key.to_wif(self.testnet)
     }
 
+    /// Sign a 32-byte message hash with the private key (raw ECDSA)
+    #[wasm_bindgen]
+    pub fn sign(&self, message_hash: &[u8]) -> Result<js_sys::Uint8Array, WasmBip32Error> {
+        let signature = self.sign_raw(message_hash)?;
+        Ok(js_sys::Uint8Array::from(&signature[..]))
+    }
+
     /// Derive a normal (non-hardened) child key
     #[wasm_bindgen]
     pub fn derive(&self, index: u32) -> Result<WasmBIP32, WasmBip32Error> {
         Ok(WasmBIP32 {
             key: self.key.derive(index)?,
             testnet: self.testnet,
+            secret_handle: self.secret_handle,
         })
     }
 
@@ -304,6 +357,7 @@ impl WasmBIP32 {
         Ok(WasmBIP32 {
             key: self.key.derive_hardened(index)?,
             testnet: self.testnet,
+            secret_handle: self.secret_handle,
         })
     }
 
@@ -313,6 +367,66 @@ impl WasmBIP32 {
         Ok(WasmBIP32 {
             key: self.key.derive_path(path)?,
             testnet: self.testnet,
+            secret_handle: self.secret_handle,
         })
     }
 }
+
+impl WasmBIP32 {
+    fn check_exportable(&self) -> Result<(), WasmBip32Error> {
+        if self.secret_handle {
+            return Err(WasmBip32Error::new(
+                "Private key of a secret handle cannot be exported",
+            ));
+        }
+        Ok(())
+    }
+
+    fn sign_raw(&self, message_hash: &[u8]) -> Result<Vec<u8>, WasmBip32Error> {
+        if message_hash.len() != 32 {
+            return Err(WasmBip32Error::new("Message hash must be 32 bytes"));
+        }
+        match &self.key {
+            BIP32Key::Public(_) => Err(WasmBip32Error::new("Cannot sign with public key only")),
+            BIP32Key::Private(xprv) => crate::message::sign_raw(xprv.private_key(), message_hash),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XPRV: &str = "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi";
+
+    #[test]
+    fn test_secret_handle() {
+        let key = WasmBIP32::from_base58(XPRV).unwrap();
+        let handle = key.to_secret_handle().unwrap();
+        assert!(handle.is_secret_handle());
+        assert!(handle.to_base58().is_err());
+        assert!(handle.to_wif().is_err());
+
+        // Derived keys stay secret handles, their public keys can be exported
+        let child = handle.derive_path("m/0'/1").unwrap();
+        assert!(child.is_secret_handle());
+        assert!(child.to_wif().is_err());
+        assert_eq!(
+            child.neutered().to_base58().unwrap(),
+            key.derive_path("m/0'/1")
+                .unwrap()
+                .neutered()
+                .to_base58()
+                .unwrap()
+        );
+
+        // The handle signs like the exportable key
+        let hash = [7u8; 32];
+        assert_eq!(
+            handle.sign_raw(&hash).unwrap(),
+            key.sign_raw(&hash).unwrap()
+        );
+
+        assert!(key.neutered().to_secret_handle().is_err());
+    }
+}
@@ -1,7 +1,11 @@
 use crate::error::WasmBip32Error;
 use crate::message;
 use k256::ecdsa::{SigningKey, VerifyingKey};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::ProjectivePoint;
+use sha2::{Digest, Sha256};
 use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
 
 /// Network kind for WIF encoding
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -37,10 +41,15 @@ impl ECPairKey {
 }
 
 /// WASM wrapper for elliptic curve key pairs (always uses compressed keys)
+///
+/// A private key can be turned into a secret handle with `to_secret_handle`. The private key
+/// of a secret handle stays in WASM memory and can only be used for signing. Private keys are
+/// zeroized when the instance is dropped (`free()` on the JS side).
 #[wasm_bindgen]
 #[derive(Debug, Clone)]
 pub struct WasmECPair {
     key: ECPairKey,
+    secret_handle: bool,
 }
 
 #[wasm_bindgen]
@@ -62,6 +71,7 @@ impl WasmECPair {
                 signing_key,
                 verifying_key,
             },
+            secret_handle: false,
         })
     }
 
@@ -73,6 +83,7 @@ impl WasmECPair {
 
         Ok(WasmECPair {
             key: ECPairKey::PublicOnly(verifying_key),
+            secret_handle: false,
         })
     }
 
@@ -80,10 +91,12 @@ impl WasmECPair {
         wif_string: &str,
         expected_network: Option<NetworkKind>,
     ) -> Result<WasmECPair, WasmBip32Error> {
-        let decoded = bs58::decode(wif_string)
-            .with_check(None)
-            .into_vec()
-            .map_err(|e| WasmBip32Error::new(&format!("Invalid WIF: {}", e)))?;
+        let decoded = Zeroizing::new(
+            bs58::decode(wif_string)
+                .with_check(None)
+                .into_vec()
+                .map_err(|e| WasmBip32Error::new(&format!("Invalid WIF: {}", e)))?,
+        );
 
         if decoded.is_empty() {
             return Err(WasmBip32Error::new("Invalid WIF: empty"));
@@ -130,6 +143,7 @@ impl WasmECPair {
                 signing_key,
                 verifying_key,
             },
+            secret_handle: false,
         })
     }
 
@@ -152,11 +166,38 @@ impl WasmECPair {
     }
 
     /// Get the private key as a Uint8Array (if available)
+    ///
+    /// Fails for secret handles.
     #[wasm_bindgen(getter)]
-    pub fn private_key(&self) -> Option<js_sys::Uint8Array> {
-        self.key
-            .signing_key()
-            .map(|sk| js_sys::Uint8Array::from(&sk.to_bytes()[..]))
+    pub fn private_key(&self) -> Result<Option<js_sys::Uint8Array>, WasmBip32Error> {
+        match self.key.signing_key() {
+            None => Ok(None),
+            Some(signing_key) => {
+                self.check_exportable()?;
+                let bytes = Zeroizing::new(<[u8; 32]>::from(signing_key.to_bytes()));
+                Ok(Some(js_sys::Uint8Array::from(&bytes[..])))
+            }
+        }
+    }
+
+    /// Check if the private key is kept in WASM memory and cannot be exported
+    #[wasm_bindgen(getter)]
+    pub fn is_secret_handle(&self) -> bool {
+        self.secret_handle
+    }
+
+    /// Get a copy of this key pair whose private key cannot be exported
+    #[wasm_bindgen]
+    pub fn to_secret_handle(&self) -> Result<WasmECPair, WasmBip32Error> {
+        if self.key.signing_key().is_none() {
+            return Err(WasmBip32Error::new(
+                "Cannot create secret handle from public key",
+            ));
+        }
+        Ok(WasmECPair {
+            secret_handle: true,
+            ..self.clone()
+        })
     }
 
     /// Get the compressed public key as a Uint8Array (always 33 bytes)
@@ -167,11 +208,21 @@ impl WasmECPair {
         js_sys::Uint8Array::from(&bytes[..])
     }
 
+    fn check_exportable(&self) -> Result<(), WasmBip32Error> {
+        if self.secret_handle {
+            return Err(WasmBip32Error::new(
+                "Private key of a secret handle cannot be exported",
+            ));
+        }
+        Ok(())
+    }
+
     fn to_wif_with_network(&self, network: NetworkKind) -> Result<String, WasmBip32Error> {
         let signing_key = self
             .key
             .signing_key()
             .ok_or_else(|| WasmBip32Error::new("Cannot get WIF from public key"))?;
+        self.check_exportable()?;
 
         let version = match network {
             NetworkKind::Main => 0x80u8,
@@ -179,12 +230,12 @@ impl WasmECPair {
         };
 
         // WIF format: version (1) + secret (32) + compression flag (1)
-        let mut data = Vec::with_capacity(34);
+        let mut data = Zeroizing::new(Vec::with_capacity(34));
         data.push(version);
         data.extend_from_slice(&signing_key.to_bytes());
         data.push(0x01); // Always compressed
 
-        Ok(bs58::encode(&data).with_check().into_string())
+        Ok(bs58::encode(data.as_slice()).with_check().into_string())
     }
 
     /// Convert to WIF string (mainnet)
@@ -232,6 +283,22 @@ impl WasmECPair {
         Ok(message::verify_raw(verifying_key, message_hash, signature))
     }
 
+    /// Derive an ECDH shared secret with another public key
+    ///
+    /// Returns the SHA-256 hash of the compressed shared point, like the default hash function
+    /// of `secp256k1_ecdh` in libsecp256k1. Works for secret handles.
+    #[wasm_bindgen]
+    pub fn ecdh(&self, other_pubkey: &[u8]) -> Result<js_sys::Uint8Array, WasmBip32Error> {
+        let signing_key = self.key.signing_key().ok_or_else(|| {
+            WasmBip32Error::new("Cannot derive shared secret with public key only")
+        })?;
+        let other = VerifyingKey::from_sec1_bytes(other_pubkey)
+            .map_err(|e| WasmBip32Error::new(&format!("Invalid public key: {}", e)))?;
+
+        let secret = shared_secret(signing_key, &other);
+        Ok(js_sys::Uint8Array::from(&secret[..]))
+    }
+
     /// Sign a message using Bitcoin message signing (BIP-137)
     /// Returns 65-byte signature (1-byte header + 64-byte signature)
     #[wasm_bindgen]
@@ -253,3 +320,44 @@ impl WasmECPair {
         message::verify_bitcoin_message(verifying_key, message, signature)
     }
 }
+
+/// SHA-256 of the compressed point `other * secret`
+fn shared_secret(signing_key: &SigningKey, other: &VerifyingKey) -> Zeroizing<[u8; 32]> {
+    let point = (ProjectivePoint::from(*other.as_affine())
+        * signing_key.as_nonzero_scalar().as_ref())
+    .to_affine()
+    .to_encoded_point(true);
+    Zeroizing::new(Sha256::digest(point.as_bytes()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signing_key(byte: u8) -> SigningKey {
+        SigningKey::from_slice(&[byte; 32]).unwrap()
+    }
+
+    #[test]
+    fn test_shared_secret() {
+        let a = signing_key(1);
+        let b = signing_key(2);
+        assert_eq!(
+            shared_secret(&a, b.verifying_key()),
+            shared_secret(&b, a.verifying_key())
+        );
+        assert_ne!(
+            shared_secret(&a, b.verifying_key()),
+            shared_secret(&a, a.verifying_key())
+        );
+
+        // secret key 1 and the generator: the hash of the compressed generator
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let one = SigningKey::from_slice(&one).unwrap();
+        assert_eq!(
+            hex::encode(shared_secret(&one, one.verifying_key())),
+            hex::encode(Sha256::digest(one.verifying_key().to_sec1_bytes()))
+        );
+    }
+}
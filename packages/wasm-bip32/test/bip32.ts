@@ -136,6 +136,29 @@ describe("WasmBIP32", () => {
     assert.strictEqual(key.isNeutered(), false);
     assert.ok(key.toBase58().startsWith("tprv"));
   });
+
+  it("should keep the private key of a secret handle in wasm", () => {
+    const key = BIP32.fromSeed(new Uint8Array(32).fill(1));
+    const handle = key.toSecretHandle();
+    const messageHash = new Uint8Array(32).fill(2);
+
+    assert.strictEqual(key.isSecretHandle, false);
+    assert.strictEqual(handle.isSecretHandle, true);
+    assert.throws(() => handle.privateKey, /cannot be exported/);
+    assert.throws(() => handle.toBase58(), /cannot be exported/);
+    assert.throws(() => handle.toWIF(), /cannot be exported/);
+    assert.deepStrictEqual(handle.sign(messageHash), key.sign(messageHash));
+
+    const child = handle.derivePath("m/0'/1");
+    assert.strictEqual(child.isSecretHandle, true);
+    assert.throws(() => child.toBase58(), /cannot be exported/);
+    assert.strictEqual(
+      child.neutered().toBase58(),
+      key.derivePath("m/0'/1").neutered().toBase58(),
+    );
+
+    assert.throws(() => key.neutered().toSecretHandle(), /public key/);
+  });
 });
 
 describe("BIP32 Benchmarks: wasm-bip32 vs utxo-lib", function () {
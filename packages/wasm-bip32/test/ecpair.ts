@@ -176,4 +176,33 @@ describe("WasmECPair", () => {
 
     assert.deepStrictEqual(key1.publicKey, key2.publicKey);
   });
+
+  it("should keep the private key of a secret handle in wasm", () => {
+    const key = ECPair.fromPrivateKey(testPrivateKey);
+    const handle = key.toSecretHandle();
+    const messageHash = new Uint8Array(32).fill(1);
+
+    assert.strictEqual(key.isSecretHandle, false);
+    assert.strictEqual(handle.isSecretHandle, true);
+    assert.deepStrictEqual(handle.publicKey, key.publicKey);
+    assert.throws(() => handle.privateKey, /cannot be exported/);
+    assert.throws(() => handle.toWIF(), /cannot be exported/);
+    assert.throws(() => handle.toWIFTestnet(), /cannot be exported/);
+    assert.deepStrictEqual(handle.sign(messageHash), key.sign(messageHash));
+    assert.ok(key.verifyMessage("hello", handle.signMessage("hello")));
+
+    assert.throws(() => ECPair.fromPublicKey(key.publicKey).toSecretHandle(), /public key/);
+  });
+
+  it("should derive the same ECDH shared secret on both sides", () => {
+    const alice = ECPair.fromPrivateKey(testPrivateKey);
+    const bob = ECPair.fromPrivateKey(new Uint8Array(32).fill(0x22));
+
+    const secret = alice.ecdh(bob.publicKey);
+    assert.strictEqual(secret.length, 32);
+    assert.deepStrictEqual(bob.ecdh(alice.publicKey), secret);
+    assert.deepStrictEqual(alice.toSecretHandle().ecdh(bob.publicKey), secret);
+    assert.throws(() => ECPair.fromPublicKey(alice.publicKey).ecdh(bob.publicKey), /public key/);
+    assert.throws(() => alice.ecdh(new Uint8Array(33)), /Invalid public key/);
+  });
 });
@@ -8,7 +8,12 @@
 
 import { BuilderNamespace } from "./wasm/wasm_dot.js";
 import { DotTransaction } from "./transaction.js";
-import type { TransactionIntent, BuildContext } from "./types.js";
+import type {
+  TransactionIntent,
+  BuildContext,
+  Material,
+  UnsignedTransactionIntent,
+} from "./types.js";
 
 /**
  * Build a DOT transaction from a business-level intent and context.
@@ -50,5 +55,23 @@ export function buildTransaction(intent: TransactionIntent, context: BuildContex
   return DotTransaction.fromInner(inner);
 }
 
+/**
+ * Build a DOT transaction from an unsigned transaction intent.
+ *
+ * The intent is validated (addresses, reference block, call list) before any call is
+ * encoded, so malformed intents fail with the offending field instead of a SCALE error.
+ *
+ * @param intent - Unsigned transaction intent
+ * @param material - Chain material metadata
+ * @returns An unsigned DotTransaction ready for signing
+ */
+export function buildFromIntent(
+  intent: UnsignedTransactionIntent,
+  material: Material,
+): DotTransaction {
+  const inner = BuilderNamespace.buildFromIntent(intent, material);
+  return DotTransaction.fromInner(inner);
+}
+
 // Re-export types for convenience
-export type { TransactionIntent, BuildContext } from "./types.js";
+export type { TransactionIntent, BuildContext, UnsignedTransactionIntent } from "./types.js";
@@ -5,6 +5,8 @@
  * - Transaction parsing: parseTransaction(tx, context) → ParsedTransaction
  * - Transaction building: buildTransaction(intent, context) → DotTransaction
  * - Transaction signing: DotTransaction.fromBytes(bytes) → inspect + sign
+ * - Pure proxies: pureProxyAddress(params, material) → SS58 address
+ * - Verification: verifyTransactionMatchesIntent(tx, intent, material) → Discrepancy[]
  */
 
 import {
@@ -12,6 +14,7 @@ import {
   ParserNamespace,
   BuilderNamespace,
   AddressNamespace,
+  ProxyNamespace,
   MaterialJs,
   ValidityJs,
   ParseContextJs,
@@ -23,6 +26,7 @@ export {
   ParserNamespace,
   BuilderNamespace,
   AddressNamespace,
+  ProxyNamespace,
   MaterialJs,
   ValidityJs,
   ParseContextJs,
@@ -34,3 +38,4 @@ export * from "./transaction.js";
 export * from "./parser.js";
 export * from "./builder.js";
 export * from "./address.js";
+export * from "./proxy.js";
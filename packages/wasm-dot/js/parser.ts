@@ -8,8 +8,14 @@
  */
 
 import { ParserNamespace, MaterialJs, ParseContextJs } from "./wasm/wasm_dot.js";
-import type { DotTransaction } from "./transaction.js";
-import type { ParseContext, ParsedTransaction } from "./types.js";
+import { DotTransaction } from "./transaction.js";
+import type {
+  Discrepancy,
+  Material,
+  ParseContext,
+  ParsedTransaction,
+  UnsignedTransactionIntent,
+} from "./types.js";
 
 /**
  * Parse a DOT transaction into structured data.
@@ -39,6 +45,32 @@ export function parseTransaction(tx: DotTransaction, context?: ParseContext): Pa
   return ParserNamespace.parseFromTransaction(tx.wasm, ctx) as ParsedTransaction;
 }
 
+/**
+ * Check that a transaction is the one described by an unsigned transaction intent.
+ *
+ * Decodes the transaction and compares the signer (if signed), nonce, tip, era and the
+ * arguments of each call with the transaction built from the intent.
+ *
+ * @param tx - A DotTransaction, or unsigned signing payload / signed extrinsic bytes
+ * @param intent - Unsigned transaction intent
+ * @param material - Chain material metadata
+ * @returns The fields that differ, empty if the transaction matches
+ *
+ * @example
+ * ```typescript
+ * const discrepancies = verifyTransactionMatchesIntent(txBytes, intent, material);
+ * // [{ field: "calls[0].value", expected: "1000", actual: "2000" }]
+ * ```
+ */
+export function verifyTransactionMatchesIntent(
+  tx: DotTransaction | Uint8Array,
+  intent: UnsignedTransactionIntent,
+  material: Material,
+): Discrepancy[] {
+  const bytes = tx instanceof DotTransaction ? tx.toBytes() : tx;
+  return ParserNamespace.verifyTransactionMatchesIntent(bytes, intent, material) as Discrepancy[];
+}
+
 /**
  * Get the proxy deposit cost from runtime metadata.
  *
@@ -66,6 +98,7 @@ function createParseContext(ctx: ParseContext): ParseContextJs {
     ctx.material.specVersion,
     ctx.material.txVersion,
     ctx.material.metadata,
+    ctx.material.assets ?? null,
   );
   return new ParseContextJs(material, ctx.sender ?? null);
 }
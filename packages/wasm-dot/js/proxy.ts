@@ -0,0 +1,42 @@
+/**
+ * Pure (anonymous) proxy accounts.
+ *
+ * A pure proxy created with `proxy.createPure` has no key; its address is derived from
+ * the spawner, the proxy type, a disambiguation index and the position of the creating
+ * extrinsic on chain.
+ */
+
+import { ProxyNamespace } from "./wasm/wasm_dot.js";
+import type { Material, PureCreatedEvent, PureProxyParams } from "./types.js";
+
+/**
+ * Compute the address of a pure proxy.
+ *
+ * @param params - Spawner, proxy type, index and the block height and extrinsic index of
+ *   the createPure extrinsic
+ * @param material - Chain material (the metadata resolves the proxy type and the chain
+ *   name selects the address format)
+ * @returns SS58 address of the pure proxy
+ *
+ * @example
+ * ```typescript
+ * const address = pureProxyAddress(
+ *   { who: sender, proxyType: "Any", index: 0, blockHeight: 1000, extIndex: 2 },
+ *   material,
+ * );
+ * ```
+ */
+export function pureProxyAddress(params: PureProxyParams, material: Material): string {
+  return ProxyNamespace.pureProxyAddress(params, material);
+}
+
+/**
+ * Decode the data of a `Proxy.PureCreated` event.
+ *
+ * @param data - SCALE-encoded event fields (without pallet and event indices)
+ * @param material - Chain material
+ * @returns The pure proxy and spawner addresses, proxy type and disambiguation index
+ */
+export function decodePureCreated(data: Uint8Array, material: Material): PureCreatedEvent {
+  return ProxyNamespace.decodePureCreated(data, material) as PureCreatedEvent;
+}
@@ -131,6 +131,17 @@ export class DotTransaction {
     return this._wasm.signablePayload();
   }
 
+  /**
+   * Get the unhashed signing payload (call data, extensions, additional signed data)
+   *
+   * For external signers that need the full payload. Payloads longer than 256 bytes
+   * are signed as their Blake2-256 hash; `signablePayload()` already applies this rule.
+   * Requires context to be set via `setContext()`.
+   */
+  rawSignablePayload(): Uint8Array {
+    return this._wasm.rawSignablePayload();
+  }
+
   /**
    * Set the signing context (material, validity, reference block)
    *
@@ -144,6 +155,7 @@ export class DotTransaction {
       material.specVersion,
       material.txVersion,
       material.metadata,
+      material.assets ?? null,
     );
     const validityJs = new ValidityJs(validity.firstValid, validity.maxDuration);
     this._wasm.setContext(materialJs, validityJs, referenceBlock);
@@ -173,6 +185,19 @@ export class DotTransaction {
     this._wasm.addSignature(signature, pubkey);
   }
 
+  /**
+   * Add an externally produced signature and return the signed extrinsic
+   *
+   * Requires context to be set via `setContext()`, so the extrinsic carries the
+   * same extensions that were signed.
+   *
+   * @param signature - 64-byte Ed25519 signature over `signablePayload()`
+   * @param pubkey - 32-byte public key
+   */
+  assembleSigned(signature: Uint8Array, pubkey: Uint8Array): Uint8Array {
+    return this._wasm.assembleSigned(signature, pubkey);
+  }
+
   /**
    * Serialize to bytes
    */
@@ -219,6 +244,7 @@ function createContext(material: Material): ParseContextJs {
     material.specVersion,
     material.txVersion,
     material.metadata,
+    material.assets ?? null,
   );
   return new ParseContextJs(m, null);
 }
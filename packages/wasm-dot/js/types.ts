@@ -37,6 +37,20 @@ export interface Material {
    * SCALE decoding.
    */
   metadata: string;
+  /** Assets of the `Assets` pallet that may be transferred (AssetHub only) */
+  assets?: AssetInfo[];
+}
+
+/**
+ * Asset of the `Assets` pallet (e.g., USDT is asset 1984 on Polkadot AssetHub)
+ */
+export interface AssetInfo {
+  /** Asset id */
+  id: number;
+  /** Ticker symbol (e.g., "USDT") */
+  symbol: string;
+  /** Number of decimals of the asset amounts */
+  decimals: number;
 }
 
 /**
@@ -99,10 +113,18 @@ export interface BuildContext {
 export type TransactionIntent =
   | PaymentIntent
   | ConsolidateIntent
+  | AssetTransferIntent
   | StakeIntent
   | UnstakeIntent
   | ClaimIntent
-  | FillNonceIntent;
+  | PayoutStakersIntent
+  | PoolJoinIntent
+  | PoolBondExtraIntent
+  | PoolClaimPayoutIntent
+  | PoolUnbondIntent
+  | PoolWithdrawUnbondedIntent
+  | FillNonceIntent
+  | CreatePureProxyIntent;
 
 /** Transfer DOT to a recipient */
 export interface PaymentIntent {
@@ -124,6 +146,24 @@ export interface ConsolidateIntent {
   keepAlive?: boolean;
 }
 
+/**
+ * Transfer an asset of the `Assets` pallet, e.g. USDT or USDC on AssetHub
+ * (assets.transferKeepAlive, or assets.transfer without keepAlive).
+ *
+ * The asset must be listed in `material.assets`.
+ */
+export interface AssetTransferIntent {
+  type: "assetTransfer";
+  /** Asset id */
+  assetId: number;
+  /** Recipient address (SS58) */
+  to: string;
+  /** Amount in the smallest unit of the asset */
+  amount: bigint;
+  /** Keep sender account alive after transfer (default: true) */
+  keepAlive?: boolean;
+}
+
 /**
  * Stake DOT.
  *
@@ -170,11 +210,165 @@ export interface ClaimIntent {
   slashingSpans?: number;
 }
 
+/**
+ * Pay out the staking rewards of a validator and its nominators for an era (payoutStakers).
+ * Anyone can submit it; rewards go to the payees, not the sender.
+ */
+export interface PayoutStakersIntent {
+  type: "payoutStakers";
+  /** Validator stash address (SS58) */
+  validatorStash: string;
+  /** Era to pay out */
+  era: number;
+}
+
+/** Join a nomination pool (nominationPools.join) */
+export interface PoolJoinIntent {
+  type: "poolJoin";
+  /** Amount to bond in planck */
+  amount: bigint;
+  /** Pool to join */
+  poolId: number;
+}
+
+/** Bond more into the sender's nomination pool (nominationPools.bondExtra) */
+export interface PoolBondExtraIntent {
+  type: "poolBondExtra";
+  /** Amount to bond from the free balance, in planck. Absent re-bonds the pending rewards. */
+  amount?: bigint;
+}
+
+/** Claim the pending pool rewards to the free balance (nominationPools.claimPayout) */
+export interface PoolClaimPayoutIntent {
+  type: "poolClaimPayout";
+}
+
+/** Unbond from a nomination pool (nominationPools.unbond) */
+export interface PoolUnbondIntent {
+  type: "poolUnbond";
+  /** Unbonding points (equal to planck unless the pool was slashed) */
+  amount: bigint;
+  /** Pool member to unbond (default: sender) */
+  memberAccount?: string;
+}
+
+/** Withdraw unbonded pool funds after the unbonding period (nominationPools.withdrawUnbonded) */
+export interface PoolWithdrawUnbondedIntent {
+  type: "poolWithdrawUnbonded";
+  /** Number of slashing spans (default: 0) */
+  slashingSpans?: number;
+  /** Pool member to withdraw for (default: sender) */
+  memberAccount?: string;
+}
+
 /** Zero-value self-transfer to advance the account nonce */
 export interface FillNonceIntent {
   type: "fillNonce";
 }
 
+/**
+ * Create a pure (anonymous) proxy controlled by the sender (createPure).
+ *
+ * The new account's address depends on the block that includes the transaction;
+ * compute it with `pureProxyAddress()`.
+ */
+export interface CreatePureProxyIntent {
+  type: "createPureProxy";
+  /** Proxy type as named in the runtime (default: "Any") */
+  proxyType?: string;
+  /** Announcement delay in blocks (default: 0) */
+  delay?: number;
+  /** Disambiguation index for several pure proxies created in one extrinsic (default: 0) */
+  index?: number;
+}
+
+// =============================================================================
+// Unsigned Transaction Intent (serializable call list)
+// =============================================================================
+
+/**
+ * Unsigned transaction without chain material, accepted by `buildFromIntent`.
+ *
+ * Lists individual calls rather than business operations; more than one call is
+ * wrapped in batchAll.
+ */
+export interface UnsignedTransactionIntent {
+  /** Sender address (SS58 encoded) */
+  sender: string;
+  /** Account nonce */
+  nonce: number;
+  /** Tip in planck (default: 0) */
+  tip?: bigint;
+  /** Validity window */
+  validity: Partial<Validity> & Pick<Validity, "firstValid">;
+  /** Reference block hash for mortality */
+  referenceBlock: string;
+  /** Calls in execution order */
+  calls: CallIntent[];
+}
+
+export type CallIntent =
+  | TransferCallIntent
+  | AssetTransferCallIntent
+  | StakingCallIntent
+  | NominationPoolsCallIntent
+  | ProxyCallIntent;
+
+/** Balances transfer. Without `amount`, transfers the whole balance (transferAll). */
+export interface TransferCallIntent {
+  type: "transfer";
+  /** Recipient address (SS58) */
+  to: string;
+  /** Amount in planck */
+  amount?: bigint;
+  /** Prevent the sender account from being reaped (default: true) */
+  keepAlive?: boolean;
+}
+
+/** Assets pallet transfer. The asset must be listed in `material.assets`. */
+export interface AssetTransferCallIntent {
+  type: "assetTransfer";
+  /** Asset id */
+  assetId: number;
+  /** Recipient address (SS58) */
+  to: string;
+  /** Amount in the smallest unit of the asset */
+  amount: bigint;
+  /** Prevent the sender account from being reaped (default: true) */
+  keepAlive?: boolean;
+}
+
+/** Staking pallet call */
+export type StakingCallIntent = { type: "staking" } & (
+  | { action: "bond"; amount: bigint; payee?: StakePayee }
+  | { action: "bondExtra"; amount: bigint }
+  | { action: "unbond"; amount: bigint }
+  | { action: "chill" }
+  | { action: "withdrawUnbonded"; slashingSpans?: number }
+  | { action: "payoutStakers"; validatorStash: string; era: number }
+);
+
+/** Nomination pools pallet call. Without `amount`, bondExtra re-bonds the pending rewards. */
+export type NominationPoolsCallIntent = { type: "nominationPools" } & (
+  | { action: "join"; amount: bigint; poolId: number }
+  | { action: "bondExtra"; amount?: bigint }
+  | { action: "claimPayout" }
+  | { action: "unbond"; memberAccount: string; amount: bigint }
+  | { action: "withdrawUnbonded"; memberAccount: string; slashingSpans?: number }
+);
+
+/** Proxy pallet call */
+export interface ProxyCallIntent {
+  type: "proxy";
+  action: "add" | "remove";
+  /** Proxy delegate address (SS58) */
+  delegate: string;
+  /** Proxy type as named in the runtime (default: "Staking") */
+  proxyType?: string;
+  /** Announcement delay in blocks (default: 0) */
+  delay?: number;
+}
+
 // =============================================================================
 // Parsed Transaction Types
 // =============================================================================
@@ -200,6 +394,16 @@ export interface ParsedMethod {
   args: Record<string, unknown>;
 }
 
+/**
+ * Account address in both SS58 and raw public key form
+ */
+export interface ParsedAddress {
+  /** SS58 address in the network's prefix */
+  address: string;
+  /** Public key (0x-prefixed hex, 32 bytes) */
+  publicKey: string;
+}
+
 /**
  * Parsed transaction data (raw decode output from Rust, no business logic)
  */
@@ -208,6 +412,12 @@ export interface ParsedTransaction {
   id: string | null;
   /** Sender address (SS58 encoded) */
   sender: string | null;
+  /** Sender public key (0x-prefixed hex, 32 bytes) */
+  senderPublicKey: string | null;
+  /** SS58 prefix used to encode the addresses in this transaction */
+  addressPrefix: number;
+  /** Transfer destinations, including those of nested batch and proxy calls */
+  destinations: ParsedAddress[];
   /** Account nonce */
   nonce: number;
   /** Tip amount (in planck, as string for BigInt) */
@@ -220,6 +430,56 @@ export interface ParsedTransaction {
   isSigned: boolean;
 }
 
+/**
+ * A field of a transaction that does not match the intent it was checked against
+ */
+export interface Discrepancy {
+  /** Path of the field (e.g., "nonce", "calls[0].dest") */
+  field: string;
+  /** Value derived from the intent */
+  expected: string;
+  /** Value decoded from the transaction */
+  actual: string;
+}
+
+// =============================================================================
+// Pure Proxy Types
+// =============================================================================
+
+/**
+ * Inputs of the pure proxy address derivation
+ */
+export interface PureProxyParams {
+  /** Account that sent the createPure extrinsic (SS58) */
+  who: string;
+  /** Proxy type as named in the runtime (e.g., "Any") */
+  proxyType: string;
+  /** Disambiguation index passed to createPure (default: 0) */
+  index?: number;
+  /** Height of the block that included the extrinsic */
+  blockHeight: number;
+  /** Index of the extrinsic within that block */
+  extIndex: number;
+}
+
+/**
+ * Decoded `Proxy.PureCreated` event data
+ */
+export interface PureCreatedEvent {
+  /** The new pure proxy account (SS58) */
+  pure: string;
+  /** The spawning account (SS58) */
+  who: string;
+  /** Proxy type name */
+  proxyType: string;
+  /** Disambiguation index passed to createPure */
+  disambiguationIndex: number;
+  /** Block height of the creation, reported by newer runtimes */
+  at?: number;
+  /** Extrinsic index of the creation, reported by newer runtimes */
+  extrinsicIndex?: number;
+}
+
 /**
  * SS58 address format prefixes.
  * Using a numeric union type rather than an enum so that callers can pass
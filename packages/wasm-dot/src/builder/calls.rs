@@ -1,12 +1,11 @@
 //! Call encoding using subxt dynamic API
 //!
-//! Two entry points:
-//! - `encode_intent()`: public — accepts a business-level `TransactionIntent`,
-//!   composes it into calls, and encodes (batching if needed)
+//! Entry points:
+//! - `encode_calls()`: internal — encodes a sequence of `CallIntent`s (batching if needed)
 //! - `encode_call()`: internal — encodes a single `CallIntent` to call data bytes
 
 use crate::address::decode_ss58;
-use crate::builder::types::{intent_to_calls, CallIntent, StakePayee, TransactionIntent};
+use crate::builder::types::{CallIntent, StakePayee};
 use crate::error::WasmDotError;
 use subxt_core::{
     ext::scale_value::{Composite, Value},
@@ -14,23 +13,17 @@ use subxt_core::{
     tx::payload::{dynamic, Payload},
 };
 
-/// Encode a business-level intent to call data bytes.
-///
-/// Handles composition: single-call intents are encoded directly,
-/// multi-call intents (e.g., stake with proxy) are wrapped in batchAll.
-pub fn encode_intent(
-    intent: &TransactionIntent,
-    sender: &str,
+/// Encode a sequence of call-level intents, wrapping more than one call in batchAll.
+pub(crate) fn encode_calls(
+    calls: &[CallIntent],
     metadata: &Metadata,
 ) -> Result<Vec<u8>, WasmDotError> {
-    let calls = intent_to_calls(intent, sender)?;
-
     match calls.len() {
         0 => Err(WasmDotError::InvalidInput(
             "Intent produced no calls".to_string(),
         )),
         1 => encode_call(&calls[0], metadata),
-        _ => encode_batch(&calls, metadata),
+        _ => encode_batch(calls, metadata),
     }
 }
 
@@ -50,6 +43,12 @@ fn encode_call(call: &CallIntent, metadata: &Metadata) -> Result<Vec<u8>, WasmDo
             balances(method, to, *amount)?
         }
         CallIntent::TransferAll { to, keep_alive } => transfer_all(to, *keep_alive)?,
+        CallIntent::AssetTransfer {
+            asset_id,
+            to,
+            amount,
+            keep_alive,
+        } => asset_transfer(*asset_id, to, *amount, *keep_alive)?,
         CallIntent::Bond { amount, payee } => staking_bond(*amount, payee)?,
         CallIntent::BondExtra { amount } => staking_bond_extra(*amount),
         CallIntent::Unbond { amount } => staking_unbond(*amount),
@@ -57,6 +56,21 @@ fn encode_call(call: &CallIntent, metadata: &Metadata) -> Result<Vec<u8>, WasmDo
             staking_withdraw_unbonded(*slashing_spans)
         }
         CallIntent::Chill => staking_chill(),
+        CallIntent::PayoutStakers {
+            validator_stash,
+            era,
+        } => staking_payout_stakers(validator_stash, *era)?,
+        CallIntent::PoolJoin { amount, pool_id } => pool_join(*amount, *pool_id),
+        CallIntent::PoolBondExtra { amount } => pool_bond_extra(*amount),
+        CallIntent::PoolClaimPayout => pool_claim_payout(),
+        CallIntent::PoolUnbond {
+            member_account,
+            amount,
+        } => pool_unbond(member_account, *amount)?,
+        CallIntent::PoolWithdrawUnbonded {
+            member_account,
+            slashing_spans,
+        } => pool_withdraw_unbonded(member_account, *slashing_spans)?,
         CallIntent::AddProxy {
             delegate,
             proxy_type,
@@ -67,6 +81,11 @@ fn encode_call(call: &CallIntent, metadata: &Metadata) -> Result<Vec<u8>, WasmDo
             proxy_type,
             delay,
         } => proxy_remove(delegate, proxy_type, *delay)?,
+        CallIntent::CreatePure {
+            proxy_type,
+            delay,
+            index,
+        } => proxy_create_pure(proxy_type, *delay, *index),
     };
 
     payload
@@ -107,6 +126,34 @@ fn transfer_all(
     ))
 }
 
+// =============================================================================
+// Assets pallet
+// =============================================================================
+
+/// The asset id is a `Compact<u32>` (`AssetIdParameter`) and the amount a `Compact<u128>`;
+/// the compact encoding is applied by the dynamic encoder from the metadata types.
+fn asset_transfer(
+    asset_id: u32,
+    to: &str,
+    amount: u64,
+    keep_alive: bool,
+) -> Result<subxt_core::tx::payload::DynamicPayload, WasmDotError> {
+    let method = if keep_alive {
+        "transfer_keep_alive"
+    } else {
+        "transfer"
+    };
+    Ok(dynamic(
+        "Assets",
+        method,
+        named([
+            ("id", Value::u128(asset_id as u128)),
+            ("target", multi_address(to)?),
+            ("amount", Value::u128(amount as u128)),
+        ]),
+    ))
+}
+
 // =============================================================================
 // Staking pallet
 // =============================================================================
@@ -165,6 +212,82 @@ fn staking_chill() -> subxt_core::tx::payload::DynamicPayload {
     dynamic("Staking", "chill", Composite::Unnamed(vec![]))
 }
 
+fn staking_payout_stakers(
+    validator_stash: &str,
+    era: u32,
+) -> Result<subxt_core::tx::payload::DynamicPayload, WasmDotError> {
+    Ok(dynamic(
+        "Staking",
+        "payout_stakers",
+        named([
+            ("validator_stash", account_id(validator_stash)?),
+            ("era", Value::u128(era as u128)),
+        ]),
+    ))
+}
+
+// =============================================================================
+// NominationPools pallet
+// =============================================================================
+
+fn pool_join(amount: u64, pool_id: u32) -> subxt_core::tx::payload::DynamicPayload {
+    dynamic(
+        "NominationPools",
+        "join",
+        named([
+            ("amount", Value::u128(amount as u128)),
+            ("pool_id", Value::u128(pool_id as u128)),
+        ]),
+    )
+}
+
+fn pool_bond_extra(amount: Option<u64>) -> subxt_core::tx::payload::DynamicPayload {
+    let extra = match amount {
+        Some(amount) => Value::unnamed_variant("FreeBalance", [Value::u128(amount as u128)]),
+        None => Value::unnamed_variant("Rewards", []),
+    };
+    dynamic("NominationPools", "bond_extra", named([("extra", extra)]))
+}
+
+fn pool_claim_payout() -> subxt_core::tx::payload::DynamicPayload {
+    dynamic(
+        "NominationPools",
+        "claim_payout",
+        Composite::Unnamed(vec![]),
+    )
+}
+
+fn pool_unbond(
+    member_account: &str,
+    unbonding_points: u64,
+) -> Result<subxt_core::tx::payload::DynamicPayload, WasmDotError> {
+    Ok(dynamic(
+        "NominationPools",
+        "unbond",
+        named([
+            ("member_account", multi_address(member_account)?),
+            ("unbonding_points", Value::u128(unbonding_points as u128)),
+        ]),
+    ))
+}
+
+fn pool_withdraw_unbonded(
+    member_account: &str,
+    num_slashing_spans: u32,
+) -> Result<subxt_core::tx::payload::DynamicPayload, WasmDotError> {
+    Ok(dynamic(
+        "NominationPools",
+        "withdraw_unbonded",
+        named([
+            ("member_account", multi_address(member_account)?),
+            (
+                "num_slashing_spans",
+                Value::u128(num_slashing_spans as u128),
+            ),
+        ]),
+    ))
+}
+
 // =============================================================================
 // Proxy pallet
 // =============================================================================
@@ -201,6 +324,22 @@ fn proxy_remove(
     ))
 }
 
+fn proxy_create_pure(
+    proxy_type: &str,
+    delay: u32,
+    index: u16,
+) -> subxt_core::tx::payload::DynamicPayload {
+    dynamic(
+        "Proxy",
+        "create_pure",
+        named([
+            ("proxy_type", Value::unnamed_variant(proxy_type, [])),
+            ("delay", Value::u128(delay as u128)),
+            ("index", Value::u128(index as u128)),
+        ]),
+    )
+}
+
 // =============================================================================
 // Utility pallet (batch)
 // =============================================================================
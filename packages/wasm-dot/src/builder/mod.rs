@@ -9,9 +9,9 @@ pub mod types;
 
 use crate::error::WasmDotError;
 use crate::transaction::Transaction;
-use crate::types::{Era, Validity};
-use calls::encode_intent;
-use types::{BuildContext, TransactionIntent};
+use crate::types::{Era, Material, UnsignedTransactionIntent, Validity};
+use calls::encode_calls;
+use types::{intent_to_calls, BuildContext, CallIntent, TransactionIntent};
 
 /// Build a transaction from a business-level intent and context.
 ///
@@ -26,8 +26,70 @@ pub fn build_transaction(
     let metadata = decode_metadata(&context.material.metadata)?;
 
     // Compose intent into calls and encode (batching if needed)
-    let call_data = encode_intent(&intent, &context.sender, &metadata)?;
+    let calls = intent_to_calls(&intent, &context.sender)?;
+    check_assets(&calls, &context.material)?;
+    let call_data = encode_calls(&calls, &metadata)?;
 
+    build_from_call_data(call_data, context)
+}
+
+/// Build a transaction from an [`UnsignedTransactionIntent`] serialized as JSON.
+///
+/// The intent is validated (addresses, reference block, non-empty calls) before any
+/// call is encoded, so malformed input is reported by field rather than as a SCALE error.
+pub fn build_from_intent_json(json: &str, material: Material) -> Result<Transaction, WasmDotError> {
+    let intent: UnsignedTransactionIntent = serde_json::from_str(json)
+        .map_err(|e| WasmDotError::InvalidInput(format!("Invalid intent JSON: {}", e)))?;
+    build_from_intent(intent, material)
+}
+
+/// Build a transaction from an [`UnsignedTransactionIntent`], validating it first.
+pub fn build_from_intent(
+    intent: UnsignedTransactionIntent,
+    material: Material,
+) -> Result<Transaction, WasmDotError> {
+    intent.validate()?;
+
+    let metadata = decode_metadata(&material.metadata)?;
+    let calls: Vec<CallIntent> = intent.calls.iter().map(CallIntent::from).collect();
+    check_assets(&calls, &material)?;
+    let call_data = encode_calls(&calls, &metadata)?;
+
+    build_from_call_data(
+        call_data,
+        BuildContext {
+            sender: intent.sender,
+            nonce: intent.nonce,
+            tip: intent.tip,
+            material,
+            validity: intent.validity,
+            reference_block: intent.reference_block,
+        },
+    )
+}
+
+/// Reject transfers of assets that are not listed in the material.
+///
+/// Asset ids are plain numbers, so an id that is off by one would silently transfer
+/// another asset. Listing the assets in the material ties each id to its symbol.
+fn check_assets(calls: &[CallIntent], material: &Material) -> Result<(), WasmDotError> {
+    for call in calls {
+        if let CallIntent::AssetTransfer { asset_id, .. } = call {
+            if material.asset(*asset_id).is_none() {
+                return Err(WasmDotError::InvalidInput(format!(
+                    "Asset {} is not listed in material.assets",
+                    asset_id
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn build_from_call_data(
+    call_data: Vec<u8>,
+    context: BuildContext,
+) -> Result<Transaction, WasmDotError> {
     // Calculate era from validity
     let era = compute_era(&context.validity);
 
@@ -56,6 +118,196 @@ fn compute_era(validity: &Validity) -> Era {
 
 #[cfg(test)]
 mod tests {
-    // Tests require real metadata - will be added with test fixtures
-    // For now, unit tests are in calls.rs for the encoding logic
+    use super::*;
+    use crate::types::AssetInfo;
+
+    #[test]
+    fn test_asset_transfer_requires_listed_asset() {
+        let mut context: BuildContext = serde_json::from_value(serde_json::json!({
+            "sender": "5EGoFA95omzemRssELLDjVenNZ68aXyUeqtKQScXSEBvVJkr",
+            "nonce": 0,
+            "material": {
+                "genesisHash": "0xe143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e",
+                "chainName": "Westend",
+                "specName": "westend",
+                "specVersion": 9420,
+                "txVersion": 16,
+                "metadata": hex::encode(include_bytes!("../../test-fixtures/westend_metadata.scale")),
+            },
+            "validity": { "firstValid": 1000 },
+            "referenceBlock": "0xe143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e",
+        }))
+        .unwrap();
+        let intent = TransactionIntent::AssetTransfer {
+            asset_id: 1984,
+            to: "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty".to_string(),
+            amount: 1_000_000,
+            keep_alive: true,
+        };
+
+        let err = build_transaction(intent.clone(), context.clone()).unwrap_err();
+        assert!(err.to_string().contains("not listed"), "{}", err);
+
+        // The relay chain has no Assets pallet
+        context.material.assets.push(AssetInfo {
+            id: 1984,
+            symbol: "USDT".to_string(),
+            decimals: 6,
+        });
+        let err = build_transaction(intent, context).unwrap_err();
+        assert!(err.to_string().contains("Assets"), "{}", err);
+    }
+
+    /// Westend metadata with an `Assets` pallet at the index and call indices of AssetHub
+    /// (pallet 50, transfer 8, transfer_keep_alive 9)
+    fn asset_hub_metadata() -> String {
+        use frame_metadata::{v14::PalletCallMetadata, RuntimeMetadata, RuntimeMetadataPrefixed};
+        use parity_scale_codec::{Decode, Encode};
+        use scale_info::{
+            Field, Path, PortableType, Type, TypeDef, TypeDefCompact, TypeDefPrimitive,
+            TypeDefVariant, Variant,
+        };
+
+        let bytes = include_bytes!("../../test-fixtures/westend_metadata.scale");
+        let mut prefixed = RuntimeMetadataPrefixed::decode(&mut &bytes[..]).unwrap();
+        let RuntimeMetadata::V14(metadata) = &mut prefixed.1 else {
+            panic!("Expected V14 metadata");
+        };
+        let types = &mut metadata.types.types;
+
+        // Reuse the MultiAddress and Compact<u128> types of balances.transfer_keep_alive
+        let balances = metadata
+            .pallets
+            .iter()
+            .find(|p| p.name == "Balances")
+            .unwrap();
+        let balances_calls = balances.calls.as_ref().unwrap().ty.id;
+        let TypeDef::Variant(calls) = &types[balances_calls as usize].ty.type_def else {
+            panic!("Expected a call enum");
+        };
+        let fields = &calls
+            .variants
+            .iter()
+            .find(|v| v.name == "transfer_keep_alive")
+            .unwrap()
+            .fields;
+        let (target, amount) = (fields[0].ty, fields[1].ty);
+
+        let mut push = |ty: Type<scale_info::form::PortableForm>| {
+            let id = types.len() as u32;
+            types.push(PortableType { id, ty });
+            id
+        };
+        let u32_id = push(Type::new(
+            Path::default(),
+            [],
+            TypeDefPrimitive::U32,
+            vec![],
+        ));
+        let compact_u32 = push(Type::new(
+            Path::default(),
+            [],
+            TypeDefCompact::new(u32_id.into()),
+            vec![],
+        ));
+        let transfer = |name: &str, index| {
+            let fields = vec![
+                Field::new(Some("id".into()), compact_u32.into(), None, vec![]),
+                Field::new(Some("target".into()), target, None, vec![]),
+                Field::new(Some("amount".into()), amount, None, vec![]),
+            ];
+            Variant::new(name.into(), fields, index, vec![])
+        };
+        let assets_calls = push(Type::new(
+            Path::default(),
+            [],
+            TypeDefVariant::new([transfer("transfer", 8), transfer("transfer_keep_alive", 9)]),
+            vec![],
+        ));
+
+        let mut assets = balances.clone();
+        assets.name = "Assets".into();
+        assets.index = 50;
+        assets.storage = None;
+        assets.event = None;
+        assets.constants = Vec::new();
+        assets.error = None;
+        assets.calls = Some(PalletCallMetadata {
+            ty: assets_calls.into(),
+        });
+        metadata.pallets.push(assets);
+
+        hex::encode(prefixed.encode())
+    }
+
+    #[test]
+    fn test_asset_transfer_round_trip() {
+        use crate::parser::parse_from_transaction;
+        use crate::types::ParseContext;
+
+        const SENDER: &str = "5EGoFA95omzemRssELLDjVenNZ68aXyUeqtKQScXSEBvVJkr";
+        const RECIPIENT: &str = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty";
+        const GENESIS: &str = "0xe143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e";
+
+        let material: Material = serde_json::from_value(serde_json::json!({
+            "genesisHash": GENESIS,
+            "chainName": "AssetHub",
+            "specName": "statemint",
+            "specVersion": 1_002_000,
+            "txVersion": 15,
+            "metadata": asset_hub_metadata(),
+            "assets": [{ "id": 1984, "symbol": "USDT", "decimals": 6 }],
+        }))
+        .unwrap();
+        let context = BuildContext {
+            sender: SENDER.to_string(),
+            nonce: 0,
+            tip: 0,
+            material: material.clone(),
+            validity: Validity {
+                first_valid: 1000,
+                max_duration: 2400,
+            },
+            reference_block: GENESIS.to_string(),
+        };
+
+        for (keep_alive, name, method_index) in
+            [(true, "transferKeepAlive", 9), (false, "transfer", 8)]
+        {
+            let intent = TransactionIntent::AssetTransfer {
+                asset_id: 1984,
+                to: RECIPIENT.to_string(),
+                amount: 2_500_000,
+                keep_alive,
+            };
+            let tx = build_transaction(intent, context.clone()).unwrap();
+
+            // pallet 50, call, Compact(1984) = 0x011f, MultiAddress::Id
+            let (recipient_key, _) = crate::address::decode_ss58(RECIPIENT).unwrap();
+            let mut call_data = vec![50, method_index, 0x01, 0x1f, 0x00];
+            call_data.extend(&recipient_key);
+            call_data.extend([0x82, 0x96, 0x98, 0x00]); // Compact(2_500_000)
+            assert_eq!(tx.call_data(), &call_data[..]);
+
+            let parse_context = ParseContext {
+                material: material.clone(),
+                sender: None,
+            };
+            let parsed = parse_from_transaction(&tx, Some(&parse_context)).unwrap();
+            assert_eq!(parsed.method.pallet, "assets");
+            assert_eq!(parsed.method.name, name);
+            assert_eq!(
+                parsed.method.args,
+                serde_json::json!({
+                    "id": 1984,
+                    "target": RECIPIENT,
+                    "amount": "2500000",
+                    "symbol": "USDT",
+                    "decimals": 6,
+                })
+            );
+            assert_eq!(parsed.destinations.len(), 1);
+            assert_eq!(parsed.destinations[0].address, RECIPIENT);
+        }
+    }
 }
@@ -8,7 +8,11 @@
 //! one or more call intents, handling batch composition automatically.
 
 use crate::error::WasmDotError;
-use crate::types::{Material, Validity};
+pub use crate::types::StakePayee;
+use crate::types::{
+    AssetTransferIntent, Intent, Material, PoolIntent, ProxyAction, StakingIntent, TransferIntent,
+    Validity,
+};
 use serde::{Deserialize, Serialize};
 
 // =============================================================================
@@ -43,6 +47,23 @@ pub enum TransactionIntent {
         keep_alive: bool,
     },
 
+    /// Transfer an asset of the `Assets` pallet, e.g. USDT or USDC on AssetHub
+    /// (assets.transferKeepAlive, or assets.transfer without keepAlive).
+    ///
+    /// The asset must be listed in `BuildContext.material.assets`.
+    AssetTransfer {
+        /// Asset id
+        #[serde(rename = "assetId")]
+        asset_id: u32,
+        /// Recipient address (SS58)
+        to: String,
+        /// Amount in the smallest unit of the asset
+        amount: u64,
+        /// Keep sender account alive after transfer (default: true)
+        #[serde(default = "default_true", rename = "keepAlive")]
+        keep_alive: bool,
+    },
+
     /// Stake DOT.
     ///
     /// - With `proxy_address`: new stake → batchAll(bond, addProxy)
@@ -88,10 +109,86 @@ pub enum TransactionIntent {
         slashing_spans: u32,
     },
 
+    /// Pay out the staking rewards of a validator and its nominators for an era
+    /// (payoutStakers). Anyone can submit it; rewards go to the payees, not the sender.
+    PayoutStakers {
+        /// Validator stash address (SS58)
+        #[serde(rename = "validatorStash")]
+        validator_stash: String,
+        /// Era to pay out
+        era: u32,
+    },
+
+    /// Join a nomination pool (nominationPools.join)
+    PoolJoin {
+        /// Amount to bond in planck
+        amount: u64,
+        /// Pool to join
+        #[serde(rename = "poolId")]
+        pool_id: u32,
+    },
+
+    /// Bond more into the sender's nomination pool (nominationPools.bondExtra).
+    ///
+    /// Absent `amount` re-bonds the pending rewards.
+    PoolBondExtra {
+        /// Amount to bond from the free balance, in planck
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        amount: Option<u64>,
+    },
+
+    /// Claim the pending pool rewards to the free balance (nominationPools.claimPayout)
+    PoolClaimPayout,
+
+    /// Unbond from a nomination pool (nominationPools.unbond)
+    PoolUnbond {
+        /// Unbonding points (equal to planck unless the pool was slashed)
+        amount: u64,
+        /// Pool member to unbond (default: sender)
+        #[serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            rename = "memberAccount"
+        )]
+        member_account: Option<String>,
+    },
+
+    /// Withdraw unbonded funds from a nomination pool after the unbonding period
+    /// (nominationPools.withdrawUnbonded)
+    PoolWithdrawUnbonded {
+        /// Number of slashing spans (default: 0)
+        #[serde(default, rename = "slashingSpans")]
+        slashing_spans: u32,
+        /// Pool member to withdraw for (default: sender)
+        #[serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            rename = "memberAccount"
+        )]
+        member_account: Option<String>,
+    },
+
     /// Zero-value self-transfer to advance the account nonce.
     ///
     /// The sender address comes from `BuildContext.sender`.
     FillNonce,
+
+    /// Create a pure (anonymous) proxy account controlled by the sender (createPure).
+    ///
+    /// The new account's address depends on the block and extrinsic index that include
+    /// the transaction; see `proxy::pure_proxy_address`.
+    CreatePureProxy {
+        /// Proxy type as named in the runtime (default: "Any")
+        #[serde(default = "default_pure_proxy_type", rename = "proxyType")]
+        proxy_type: String,
+        /// Announcement delay in blocks (default: 0)
+        #[serde(default)]
+        delay: u32,
+        /// Disambiguation index for several pure proxies created in one extrinsic
+        /// (default: 0)
+        #[serde(default)]
+        index: u16,
+    },
 }
 
 // =============================================================================
@@ -113,6 +210,12 @@ pub(crate) enum CallIntent {
         to: String,
         keep_alive: bool,
     },
+    AssetTransfer {
+        asset_id: u32,
+        to: String,
+        amount: u64,
+        keep_alive: bool,
+    },
     Bond {
         amount: u64,
         payee: StakePayee,
@@ -127,6 +230,27 @@ pub(crate) enum CallIntent {
         slashing_spans: u32,
     },
     Chill,
+    PayoutStakers {
+        validator_stash: String,
+        era: u32,
+    },
+    PoolJoin {
+        amount: u64,
+        pool_id: u32,
+    },
+    /// `None` re-bonds the pending rewards
+    PoolBondExtra {
+        amount: Option<u64>,
+    },
+    PoolClaimPayout,
+    PoolUnbond {
+        member_account: String,
+        amount: u64,
+    },
+    PoolWithdrawUnbonded {
+        member_account: String,
+        slashing_spans: u32,
+    },
     AddProxy {
         delegate: String,
         proxy_type: String,
@@ -137,6 +261,11 @@ pub(crate) enum CallIntent {
         proxy_type: String,
         delay: u32,
     },
+    CreatePure {
+        proxy_type: String,
+        delay: u32,
+        index: u16,
+    },
 }
 
 // =============================================================================
@@ -167,6 +296,18 @@ pub(crate) fn intent_to_calls(
             keep_alive: *keep_alive,
         }]),
 
+        TransactionIntent::AssetTransfer {
+            asset_id,
+            to,
+            amount,
+            keep_alive,
+        } => Ok(vec![CallIntent::AssetTransfer {
+            asset_id: *asset_id,
+            to: to.clone(),
+            amount: *amount,
+            keep_alive: *keep_alive,
+        }]),
+
         TransactionIntent::Stake {
             amount,
             payee,
@@ -215,11 +356,153 @@ pub(crate) fn intent_to_calls(
             slashing_spans: *slashing_spans,
         }]),
 
+        TransactionIntent::PayoutStakers {
+            validator_stash,
+            era,
+        } => Ok(vec![CallIntent::PayoutStakers {
+            validator_stash: validator_stash.clone(),
+            era: *era,
+        }]),
+
+        TransactionIntent::PoolJoin { amount, pool_id } => Ok(vec![CallIntent::PoolJoin {
+            amount: *amount,
+            pool_id: *pool_id,
+        }]),
+
+        TransactionIntent::PoolBondExtra { amount } => {
+            Ok(vec![CallIntent::PoolBondExtra { amount: *amount }])
+        }
+
+        TransactionIntent::PoolClaimPayout => Ok(vec![CallIntent::PoolClaimPayout]),
+
+        TransactionIntent::PoolUnbond {
+            amount,
+            member_account,
+        } => Ok(vec![CallIntent::PoolUnbond {
+            member_account: member_account.as_deref().unwrap_or(sender).to_string(),
+            amount: *amount,
+        }]),
+
+        TransactionIntent::PoolWithdrawUnbonded {
+            slashing_spans,
+            member_account,
+        } => Ok(vec![CallIntent::PoolWithdrawUnbonded {
+            member_account: member_account.as_deref().unwrap_or(sender).to_string(),
+            slashing_spans: *slashing_spans,
+        }]),
+
         TransactionIntent::FillNonce => Ok(vec![CallIntent::Transfer {
             to: sender.to_string(),
             amount: 0,
             keep_alive: true,
         }]),
+
+        TransactionIntent::CreatePureProxy {
+            proxy_type,
+            delay,
+            index,
+        } => Ok(vec![CallIntent::CreatePure {
+            proxy_type: proxy_type.clone(),
+            delay: *delay,
+            index: *index,
+        }]),
+    }
+}
+
+impl From<&Intent> for CallIntent {
+    fn from(intent: &Intent) -> Self {
+        match intent {
+            Intent::Transfer(TransferIntent {
+                to,
+                amount: Some(amount),
+                keep_alive,
+            }) => CallIntent::Transfer {
+                to: to.clone(),
+                amount: *amount,
+                keep_alive: *keep_alive,
+            },
+            Intent::Transfer(TransferIntent {
+                to,
+                amount: None,
+                keep_alive,
+            }) => CallIntent::TransferAll {
+                to: to.clone(),
+                keep_alive: *keep_alive,
+            },
+            Intent::AssetTransfer(AssetTransferIntent {
+                asset_id,
+                to,
+                amount,
+                keep_alive,
+            }) => CallIntent::AssetTransfer {
+                asset_id: *asset_id,
+                to: to.clone(),
+                amount: *amount,
+                keep_alive: *keep_alive,
+            },
+            Intent::Staking(StakingIntent::Bond { amount, payee }) => CallIntent::Bond {
+                amount: *amount,
+                payee: payee.clone(),
+            },
+            Intent::Staking(StakingIntent::BondExtra { amount }) => {
+                CallIntent::BondExtra { amount: *amount }
+            }
+            Intent::Staking(StakingIntent::Unbond { amount }) => {
+                CallIntent::Unbond { amount: *amount }
+            }
+            Intent::Staking(StakingIntent::Chill) => CallIntent::Chill,
+            Intent::Staking(StakingIntent::WithdrawUnbonded { slashing_spans }) => {
+                CallIntent::WithdrawUnbonded {
+                    slashing_spans: *slashing_spans,
+                }
+            }
+            Intent::Staking(StakingIntent::PayoutStakers {
+                validator_stash,
+                era,
+            }) => CallIntent::PayoutStakers {
+                validator_stash: validator_stash.clone(),
+                era: *era,
+            },
+            Intent::NominationPools(pool) => match pool {
+                PoolIntent::Join { amount, pool_id } => CallIntent::PoolJoin {
+                    amount: *amount,
+                    pool_id: *pool_id,
+                },
+                PoolIntent::BondExtra { amount } => CallIntent::PoolBondExtra { amount: *amount },
+                PoolIntent::ClaimPayout => CallIntent::PoolClaimPayout,
+                PoolIntent::Unbond {
+                    member_account,
+                    amount,
+                } => CallIntent::PoolUnbond {
+                    member_account: member_account.clone(),
+                    amount: *amount,
+                },
+                PoolIntent::WithdrawUnbonded {
+                    member_account,
+                    slashing_spans,
+                } => CallIntent::PoolWithdrawUnbonded {
+                    member_account: member_account.clone(),
+                    slashing_spans: *slashing_spans,
+                },
+            },
+            Intent::Proxy(proxy) => {
+                let delegate = proxy.delegate.clone();
+                let proxy_type = proxy.proxy_type.clone();
+                let delay = proxy.delay;
+                match proxy.action {
+                    ProxyAction::Add => CallIntent::AddProxy {
+                        delegate,
+                        proxy_type,
+                        delay,
+                    },
+                    ProxyAction::Remove => CallIntent::RemoveProxy {
+                        delegate,
+                        proxy_type,
+                        delay,
+                    },
+                }
+            }
+        }
     }
 }
 
@@ -246,28 +529,14 @@ pub struct BuildContext {
     pub reference_block: String,
 }
 
-/// Staking reward destination
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
-#[serde(tag = "type", rename_all = "camelCase")]
-pub enum StakePayee {
-    /// Compound rewards (re-stake)
-    #[default]
-    Staked,
-    /// Send to stash account
-    Stash,
-    /// Send to controller account
-    Controller,
-    /// Send to specific account
-    Account {
-        /// Destination address
-        address: String,
-    },
-}
-
 fn default_true() -> bool {
     true
 }
 
+fn default_pure_proxy_type() -> String {
+    "Any".to_string()
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -313,6 +582,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_deserialize_asset_transfer_intent() {
+        let json = format!(
+            r#"{{ "type": "assetTransfer", "assetId": 1984, "to": "{PROXY}", "amount": 2500000 }}"#
+        );
+        let intent: TransactionIntent = serde_json::from_str(&json).unwrap();
+        let calls = intent_to_calls(&intent, SENDER).unwrap();
+        match &calls[..] {
+            [CallIntent::AssetTransfer {
+                asset_id,
+                to,
+                amount,
+                keep_alive,
+            }] => {
+                assert_eq!(*asset_id, 1984);
+                assert_eq!(to, PROXY);
+                assert_eq!(*amount, 2_500_000);
+                assert!(*keep_alive); // default
+            }
+            _ => panic!("Expected AssetTransfer"),
+        }
+    }
+
     #[test]
     fn test_deserialize_stake_new() {
         let json = r#"{
@@ -409,6 +701,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_deserialize_payout_stakers() {
+        let json =
+            format!(r#"{{ "type": "payoutStakers", "validatorStash": "{PROXY}", "era": 1200 }}"#);
+        let intent: TransactionIntent = serde_json::from_str(&json).unwrap();
+        match intent {
+            TransactionIntent::PayoutStakers {
+                validator_stash,
+                era,
+            } => {
+                assert_eq!(validator_stash, PROXY);
+                assert_eq!(era, 1200);
+            }
+            _ => panic!("Expected PayoutStakers"),
+        }
+    }
+
+    #[test]
+    fn test_deserialize_pool_intents() {
+        let json = r#"{ "type": "poolJoin", "amount": 10000000000, "poolId": 12 }"#;
+        let intent: TransactionIntent = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            intent,
+            TransactionIntent::PoolJoin {
+                amount: 10_000_000_000,
+                pool_id: 12
+            }
+        ));
+
+        let json = r#"{ "type": "poolBondExtra" }"#;
+        let intent: TransactionIntent = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            intent,
+            TransactionIntent::PoolBondExtra { amount: None }
+        ));
+
+        let json = r#"{ "type": "poolClaimPayout" }"#;
+        let intent: TransactionIntent = serde_json::from_str(json).unwrap();
+        assert!(matches!(intent, TransactionIntent::PoolClaimPayout));
+
+        let json = r#"{ "type": "poolWithdrawUnbonded" }"#;
+        let intent: TransactionIntent = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            intent,
+            TransactionIntent::PoolWithdrawUnbonded {
+                slashing_spans: 0,
+                member_account: None
+            }
+        ));
+    }
+
     #[test]
     fn test_deserialize_fill_nonce() {
         let json = r#"{ "type": "fillNonce" }"#;
@@ -416,6 +759,25 @@ mod tests {
         assert!(matches!(intent, TransactionIntent::FillNonce));
     }
 
+    #[test]
+    fn test_deserialize_create_pure_proxy() {
+        let json = r#"{ "type": "createPureProxy", "index": 3 }"#;
+        let intent: TransactionIntent = serde_json::from_str(json).unwrap();
+        let calls = intent_to_calls(&intent, SENDER).unwrap();
+        match &calls[..] {
+            [CallIntent::CreatePure {
+                proxy_type,
+                delay,
+                index,
+            }] => {
+                assert_eq!(proxy_type, "Any"); // default
+                assert_eq!(*delay, 0);
+                assert_eq!(*index, 3);
+            }
+            _ => panic!("Expected CreatePure"),
+        }
+    }
+
     #[test]
     fn test_deserialize_context() {
         let json = r#"{
@@ -559,6 +921,78 @@ mod tests {
         assert!(matches!(calls[0], CallIntent::WithdrawUnbonded { .. }));
     }
 
+    #[test]
+    fn test_pool_unbond_defaults_to_sender() {
+        let intent = TransactionIntent::PoolUnbond {
+            amount: 1_000_000_000_000,
+            member_account: None,
+        };
+        let calls = intent_to_calls(&intent, SENDER).unwrap();
+        match &calls[..] {
+            [CallIntent::PoolUnbond {
+                member_account,
+                amount,
+            }] => {
+                assert_eq!(member_account, SENDER);
+                assert_eq!(*amount, 1_000_000_000_000);
+            }
+            _ => panic!("Expected PoolUnbond"),
+        }
+
+        let intent = TransactionIntent::PoolWithdrawUnbonded {
+            slashing_spans: 2,
+            member_account: Some(PROXY.to_string()),
+        };
+        let calls = intent_to_calls(&intent, SENDER).unwrap();
+        match &calls[..] {
+            [CallIntent::PoolWithdrawUnbonded {
+                member_account,
+                slashing_spans,
+            }] => {
+                assert_eq!(member_account, PROXY);
+                assert_eq!(*slashing_spans, 2);
+            }
+            _ => panic!("Expected PoolWithdrawUnbonded"),
+        }
+    }
+
+    #[test]
+    fn test_unsigned_intent_calls() {
+        use crate::types::ProxyIntent;
+
+        let transfer_all = Intent::Transfer(TransferIntent {
+            to: PROXY.to_string(),
+            amount: None,
+            keep_alive: false,
+        });
+        assert!(matches!(
+            CallIntent::from(&transfer_all),
+            CallIntent::TransferAll {
+                keep_alive: false,
+                ..
+            }
+        ));
+
+        let remove_proxy = Intent::Proxy(ProxyIntent {
+            action: ProxyAction::Remove,
+            delegate: PROXY.to_string(),
+            proxy_type: "Any".to_string(),
+            delay: 5,
+        });
+        match CallIntent::from(&remove_proxy) {
+            CallIntent::RemoveProxy {
+                delegate,
+                proxy_type,
+                delay,
+            } => {
+                assert_eq!(delegate, PROXY);
+                assert_eq!(proxy_type, "Any");
+                assert_eq!(delay, 5);
+            }
+            _ => panic!("Expected RemoveProxy"),
+        }
+    }
+
     #[test]
     fn test_fill_nonce_composes_to_zero_self_transfer() {
         let intent = TransactionIntent::FillNonce;
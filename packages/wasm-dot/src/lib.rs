@@ -4,6 +4,8 @@
 //! - Transaction parsing (decode extrinsics)
 //! - Signature operations (add signatures to unsigned transactions)
 //! - Transaction building from intents
+//! - Pure proxy address derivation
+//! - Verification of a transaction against an intent
 //!
 //! # Architecture
 //!
@@ -16,14 +18,18 @@ pub mod builder;
 pub mod error;
 pub mod metadata_constants;
 pub mod parser;
+pub mod proxy;
 pub mod transaction;
 pub mod types;
+pub mod verify;
 pub mod wasm;
 
 // Re-export main types for convenience
 pub use address::{decode_ss58, encode_ss58, validate_address};
 pub use error::WasmDotError;
 pub use metadata_constants::get_proxy_deposit_cost;
-pub use parser::{parse_transaction, ParsedTransaction};
+pub use parser::{parse_transaction, ParsedAddress, ParsedTransaction};
+pub use proxy::{decode_pure_created, pure_proxy_address, PureCreatedEvent, PureProxyParams};
 pub use transaction::Transaction;
-pub use types::{Material, ParseContext, Validity};
+pub use types::{AssetInfo, Material, ParseContext, Validity};
+pub use verify::{verify_transaction_matches_intent, Discrepancy};
@@ -5,10 +5,10 @@
 //! Uses runtime metadata to resolve pallet and call names from their indices.
 //! Metadata is required (enforced at the TypeScript level in `fromHex`/`fromBytes`).
 
-use crate::address::encode_ss58;
+use crate::address::{decode_ss58, encode_ss58};
 use crate::error::WasmDotError;
 use crate::transaction::Transaction;
-use crate::types::{AddressFormat, Era, ParseContext};
+use crate::types::{AddressFormat, AssetInfo, Era, ParseContext};
 use serde::{Deserialize, Serialize};
 
 /// Maximum nesting depth for batch/proxy recursive parsing.
@@ -27,6 +27,12 @@ pub struct ParsedTransaction {
     pub id: Option<String>,
     /// Sender address (SS58 encoded)
     pub sender: Option<String>,
+    /// Sender public key (0x-prefixed hex, 32 bytes)
+    pub sender_public_key: Option<String>,
+    /// SS58 prefix used to encode the addresses in this transaction
+    pub address_prefix: u16,
+    /// Transfer destinations, including those of nested batch and proxy calls
+    pub destinations: Vec<ParsedAddress>,
     /// Account nonce
     pub nonce: u32,
     /// Tip amount (in planck)
@@ -39,6 +45,16 @@ pub struct ParsedTransaction {
     pub is_signed: bool,
 }
 
+/// Account address in both SS58 and raw public key form
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedAddress {
+    /// SS58 address in the network's prefix
+    pub address: String,
+    /// Public key (0x-prefixed hex, 32 bytes)
+    pub public_key: String,
+}
+
 /// Parsed method/call data
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -75,10 +91,14 @@ pub fn parse_transaction(
     let metadata = context
         .as_ref()
         .and_then(|ctx| decode_metadata(&ctx.material.metadata).ok());
+    let assets = context
+        .as_ref()
+        .map(|ctx| ctx.material.assets.clone())
+        .unwrap_or_default();
 
     let tx = Transaction::from_bytes(bytes, context, metadata.as_ref())?;
 
-    build_parsed_transaction(&tx, prefix, metadata.as_ref())
+    build_parsed_transaction(&tx, prefix, metadata.as_ref(), &assets)
 }
 
 /// Parse a pre-deserialized Transaction into structured data.
@@ -94,25 +114,42 @@ pub fn parse_from_transaction(
         .unwrap_or(42);
 
     let metadata = context.and_then(|ctx| decode_metadata(&ctx.material.metadata).ok());
+    let assets = context.map_or(&[][..], |ctx| &ctx.material.assets);
 
-    build_parsed_transaction(tx, prefix, metadata.as_ref())
+    build_parsed_transaction(tx, prefix, metadata.as_ref(), assets)
 }
 
 /// Shared logic for building ParsedTransaction from an already-deserialized Transaction.
-fn build_parsed_transaction(
+///
+/// Asset transfers of the `assets` listed in the material are annotated with the symbol
+/// and decimals of the asset.
+pub(crate) fn build_parsed_transaction(
     tx: &Transaction,
     prefix: u16,
     metadata: Option<&subxt_core::metadata::Metadata>,
+    assets: &[AssetInfo],
 ) -> Result<ParsedTransaction, WasmDotError> {
     let sender = tx.sender(prefix);
     let id = tx.id();
 
     // Parse the call data (with optional metadata for dynamic resolution)
-    let method = parse_call_data(tx.call_data(), prefix, metadata)?;
+    let mut method = parse_call_data(tx.call_data(), prefix, metadata)?;
+    annotate_assets(&method.pallet, &method.name, &mut method.args, assets);
+
+    let mut destinations = Vec::new();
+    collect_destinations(
+        &method.pallet,
+        &method.name,
+        &method.args,
+        &mut destinations,
+    )?;
 
     Ok(ParsedTransaction {
         id,
         sender,
+        sender_public_key: tx.signer_bytes().map(|pk| format!("0x{}", hex::encode(pk))),
+        address_prefix: prefix,
+        destinations,
         nonce: tx.nonce(),
         tip: tx.tip().to_string(),
         era: tx.era().clone(),
@@ -121,6 +158,98 @@ fn build_parsed_transaction(
     })
 }
 
+/// Collect transfer destinations from a parsed call, descending into batch and proxy calls.
+///
+/// Fails if a destination is not an SS58 string, rather than leaving it out of the list.
+fn collect_destinations(
+    pallet: &str,
+    name: &str,
+    args: &serde_json::Value,
+    out: &mut Vec<ParsedAddress>,
+) -> Result<(), WasmDotError> {
+    match (pallet, name) {
+        ("balances", "transfer")
+        | ("balances", "transferAllowDeath")
+        | ("balances", "transferKeepAlive")
+        | ("balances", "transferAll") => out.push(parse_destination(&args["dest"])?),
+        ("assets", "transfer") | ("assets", "transferKeepAlive") => {
+            out.push(parse_destination(&args["target"])?)
+        }
+        ("utility", "batch") | ("utility", "batchAll") => {
+            let calls = args["calls"].as_array().ok_or_else(|| {
+                WasmDotError::InvalidTransaction(format!("{}.{} without calls", pallet, name))
+            })?;
+            for call in calls {
+                collect_nested_destinations(call, out)?;
+            }
+        }
+        ("proxy", "proxy") => collect_nested_destinations(&args["call"], out)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Render a destination argument in both SS58 and public key form.
+fn parse_destination(dest: &serde_json::Value) -> Result<ParsedAddress, WasmDotError> {
+    let address = dest.as_str().ok_or_else(|| {
+        WasmDotError::InvalidTransaction(format!("unexpected transfer destination {}", dest))
+    })?;
+    let (public_key, _) = decode_ss58(address)?;
+    Ok(ParsedAddress {
+        address: address.to_string(),
+        public_key: format!("0x{}", hex::encode(public_key)),
+    })
+}
+
+/// Collect destinations from a nested call serialized as a `ParsedMethod`.
+fn collect_nested_destinations(
+    call: &serde_json::Value,
+    out: &mut Vec<ParsedAddress>,
+) -> Result<(), WasmDotError> {
+    match (call["pallet"].as_str(), call["name"].as_str()) {
+        (Some(pallet), Some(name)) => collect_destinations(pallet, name, &call["args"], out),
+        _ => Err(WasmDotError::InvalidTransaction(format!(
+            "unexpected nested call {}",
+            call
+        ))),
+    }
+}
+
+/// Add the symbol and decimals of known assets to asset transfers, descending into batch
+/// and proxy calls.
+fn annotate_assets(pallet: &str, name: &str, args: &mut serde_json::Value, assets: &[AssetInfo]) {
+    match (pallet, name) {
+        ("assets", "transfer") | ("assets", "transferKeepAlive") => {
+            let asset = args["id"]
+                .as_u64()
+                .and_then(|id| assets.iter().find(|asset| u64::from(asset.id) == id));
+            if let Some(asset) = asset {
+                args["symbol"] = serde_json::json!(asset.symbol);
+                args["decimals"] = serde_json::json!(asset.decimals);
+            }
+        }
+        ("utility", "batch") | ("utility", "batchAll") => {
+            if let Some(calls) = args["calls"].as_array_mut() {
+                for call in calls {
+                    annotate_nested_assets(call, assets);
+                }
+            }
+        }
+        ("proxy", "proxy") => annotate_nested_assets(&mut args["call"], assets),
+        _ => {}
+    }
+}
+
+/// Annotate a nested call serialized as a `ParsedMethod`.
+fn annotate_nested_assets(call: &mut serde_json::Value, assets: &[AssetInfo]) {
+    if let (Some(pallet), Some(name)) = (
+        call["pallet"].as_str().map(str::to_string),
+        call["name"].as_str().map(str::to_string),
+    ) {
+        annotate_assets(&pallet, &name, &mut call["args"], assets);
+    }
+}
+
 // Re-use the central decode_metadata from transaction.rs
 use crate::transaction::decode_metadata;
 
@@ -251,11 +380,21 @@ fn parse_method_args_with_size(
         | ("balances", "transferAllowDeath")
         | ("balances", "transferKeepAlive") => parse_transfer_args(args_data, address_prefix),
         ("balances", "transferAll") => parse_transfer_all_args(args_data, address_prefix),
+        ("assets", "transfer") | ("assets", "transferKeepAlive") => {
+            parse_asset_transfer_args(args_data, address_prefix)
+        }
         ("staking", "bond") => parse_bond_args(args_data, address_prefix),
         ("staking", "bondExtra") | ("staking", "unbond") => parse_compact_value_args(args_data),
         ("staking", "withdrawUnbonded") => parse_withdraw_unbonded_args(args_data),
         ("staking", "chill") => Ok((serde_json::json!({}), 0)),
         ("staking", "payoutStakers") => parse_payout_stakers_args(args_data, address_prefix),
+        ("nominationpools", "join") => parse_pool_join_args(args_data),
+        ("nominationpools", "bondExtra") => parse_pool_bond_extra_args(args_data),
+        ("nominationpools", "claimPayout") => Ok((serde_json::json!({}), 0)),
+        ("nominationpools", "unbond") => parse_pool_unbond_args(args_data, address_prefix),
+        ("nominationpools", "withdrawUnbonded") => {
+            parse_pool_withdraw_unbonded_args(args_data, address_prefix)
+        }
         ("proxy", "addProxy") | ("proxy", "removeProxy") => {
             parse_proxy_args(args_data, address_prefix, metadata)
         }
@@ -317,6 +456,30 @@ fn parse_transfer_all_args(
     ))
 }
 
+/// Parse assets.transfer/transferKeepAlive arguments: compact u32 id + MultiAddress target +
+/// compact u128 amount → (json, bytes_consumed)
+fn parse_asset_transfer_args(
+    args: &[u8],
+    address_prefix: u16,
+) -> Result<(serde_json::Value, usize), WasmDotError> {
+    let (id, mut cursor) = decode_compact(args)?;
+    let id = u32::try_from(id)
+        .map_err(|_| WasmDotError::InvalidTransaction(format!("asset id {} exceeds u32", id)))?;
+    let (target, target_size) = parse_multi_address(&args[cursor..], address_prefix)?;
+    cursor += target_size;
+    let (amount, amount_size) = decode_compact(&args[cursor..])?;
+    cursor += amount_size;
+
+    Ok((
+        serde_json::json!({
+            "id": id,
+            "target": target,
+            "amount": amount.to_string()
+        }),
+        cursor,
+    ))
+}
+
 /// Parse bond arguments: value (compact u128) + payee → (json, bytes_consumed)
 ///
 /// Note: older runtimes had a controller field before value, but modern
@@ -412,6 +575,104 @@ fn parse_payout_stakers_args(
     ))
 }
 
+/// Parse nominationPools.join arguments: compact u128 amount + u32 poolId (LE)
+fn parse_pool_join_args(args: &[u8]) -> Result<(serde_json::Value, usize), WasmDotError> {
+    let (amount, cursor) = decode_compact(args)?;
+    if cursor + 4 > args.len() {
+        return Err(WasmDotError::InvalidTransaction(
+            "truncated join args".to_string(),
+        ));
+    }
+    let pool_id = u32::from_le_bytes([
+        args[cursor],
+        args[cursor + 1],
+        args[cursor + 2],
+        args[cursor + 3],
+    ]);
+    Ok((
+        serde_json::json!({
+            "amount": amount.to_string(),
+            "poolId": pool_id
+        }),
+        cursor + 4,
+    ))
+}
+
+/// Parse nominationPools.bondExtra arguments: BondExtra enum, either
+/// FreeBalance (u128 LE, not compact) or Rewards
+fn parse_pool_bond_extra_args(args: &[u8]) -> Result<(serde_json::Value, usize), WasmDotError> {
+    match args.first() {
+        Some(0) => {
+            if args.len() < 17 {
+                return Err(WasmDotError::InvalidTransaction(
+                    "truncated bondExtra args".to_string(),
+                ));
+            }
+            let mut value = [0u8; 16];
+            value.copy_from_slice(&args[1..17]);
+            Ok((
+                serde_json::json!({
+                    "extra": "FreeBalance",
+                    "value": u128::from_le_bytes(value).to_string()
+                }),
+                17,
+            ))
+        }
+        Some(1) => Ok((serde_json::json!({ "extra": "Rewards" }), 1)),
+        Some(variant) => Err(WasmDotError::InvalidTransaction(format!(
+            "Unsupported BondExtra variant: {}",
+            variant
+        ))),
+        None => Err(WasmDotError::InvalidTransaction(
+            "truncated bondExtra args".to_string(),
+        )),
+    }
+}
+
+/// Parse nominationPools.unbond arguments: MultiAddress memberAccount + compact u128 points
+fn parse_pool_unbond_args(
+    args: &[u8],
+    address_prefix: u16,
+) -> Result<(serde_json::Value, usize), WasmDotError> {
+    let (member_account, mut cursor) = parse_multi_address(args, address_prefix)?;
+    let (points, points_size) = decode_compact(&args[cursor..])?;
+    cursor += points_size;
+    Ok((
+        serde_json::json!({
+            "memberAccount": member_account,
+            "unbondingPoints": points.to_string()
+        }),
+        cursor,
+    ))
+}
+
+/// Parse nominationPools.withdrawUnbonded arguments: MultiAddress memberAccount +
+/// u32 numSlashingSpans (LE)
+fn parse_pool_withdraw_unbonded_args(
+    args: &[u8],
+    address_prefix: u16,
+) -> Result<(serde_json::Value, usize), WasmDotError> {
+    let (member_account, cursor) = parse_multi_address(args, address_prefix)?;
+    if cursor + 4 > args.len() {
+        return Err(WasmDotError::InvalidTransaction(
+            "truncated withdrawUnbonded args".to_string(),
+        ));
+    }
+    let num_slashing_spans = u32::from_le_bytes([
+        args[cursor],
+        args[cursor + 1],
+        args[cursor + 2],
+        args[cursor + 3],
+    ]);
+    Ok((
+        serde_json::json!({
+            "memberAccount": member_account,
+            "numSlashingSpans": num_slashing_spans
+        }),
+        cursor + 4,
+    ))
+}
+
 /// Parse addProxy/removeProxy arguments: MultiAddress delegate + u8 proxyType + u32 delay (LE)
 fn parse_proxy_args(
     args: &[u8],
@@ -585,7 +846,7 @@ fn resolve_proxy_type(
     let md = metadata.ok_or_else(|| {
         WasmDotError::InvalidTransaction("Metadata required to resolve proxy type".to_string())
     })?;
-    resolve_proxy_type_from_metadata(md, proxy_type_byte).ok_or_else(|| {
+    crate::proxy::proxy_type_name(md, proxy_type_byte).ok_or_else(|| {
         WasmDotError::InvalidTransaction(format!(
             "Unknown proxy type index {} in metadata",
             proxy_type_byte
@@ -593,38 +854,6 @@ fn resolve_proxy_type(
     })
 }
 
-/// Look up the ProxyType enum variant name from chain metadata.
-fn resolve_proxy_type_from_metadata(
-    metadata: &subxt_core::metadata::Metadata,
-    proxy_type_byte: u8,
-) -> Option<String> {
-    let proxy_pallet = metadata.pallet_by_name("Proxy")?;
-    let call_ty_id = proxy_pallet.call_ty_id()?;
-    let call_ty = metadata.types().resolve(call_ty_id)?;
-    if let scale_info::TypeDef::Variant(ref variants) = call_ty.type_def {
-        // Find addProxy or add_proxy variant
-        let add_proxy = variants
-            .variants
-            .iter()
-            .find(|v| v.name == "add_proxy" || v.name == "addProxy")?;
-        // Find the proxy_type field
-        let pt_field = add_proxy
-            .fields
-            .iter()
-            .find(|f| f.name.as_deref() == Some("proxy_type"))?;
-        // Resolve the ProxyType enum type
-        let pt_ty = metadata.types().resolve(pt_field.ty.id)?;
-        if let scale_info::TypeDef::Variant(ref pt_variants) = pt_ty.type_def {
-            let variant = pt_variants
-                .variants
-                .iter()
-                .find(|v| v.index == proxy_type_byte)?;
-            return Some(variant.name.clone());
-        }
-    }
-    None
-}
-
 /// Decode SCALE compact encoding, returning (value, bytes_consumed)
 fn decode_compact(bytes: &[u8]) -> Result<(u128, usize), WasmDotError> {
     if bytes.is_empty() {
@@ -716,6 +945,219 @@ mod tests {
         assert_eq!(snake_to_camel("transfer_allow_death"), "transferAllowDeath");
     }
 
+    #[test]
+    fn test_parsed_addresses() {
+        const SENDER: &str = "5EGoFA95omzemRssELLDjVenNZ68aXyUeqtKQScXSEBvVJkr";
+        const RECIPIENT: &str = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty";
+        const WESTEND_GENESIS: &str =
+            "0xe143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e";
+
+        let metadata_bytes = include_bytes!("../test-fixtures/westend_metadata.scale");
+        let material = crate::types::Material {
+            genesis_hash: WESTEND_GENESIS.to_string(),
+            chain_name: "Westend".to_string(),
+            spec_name: "westend".to_string(),
+            spec_version: 9420,
+            tx_version: 16,
+            metadata: hex::encode(metadata_bytes),
+            assets: Vec::new(),
+        };
+        let json = serde_json::json!({
+            "sender": SENDER,
+            "nonce": 0,
+            "validity": { "firstValid": 1000 },
+            "referenceBlock": WESTEND_GENESIS,
+            "calls": [
+                { "type": "transfer", "to": RECIPIENT, "amount": "1" },
+                { "type": "transfer", "to": SENDER, "amount": "2", "keepAlive": false },
+            ],
+        });
+        let mut tx =
+            crate::builder::build_from_intent_json(&json.to_string(), material.clone()).unwrap();
+        let (sender_key, _) = decode_ss58(SENDER).unwrap();
+        tx.add_signature(&sender_key, &[0u8; 64]).unwrap();
+
+        let context = ParseContext {
+            material,
+            sender: None,
+        };
+        let parsed = parse_from_transaction(&tx, Some(&context)).unwrap();
+        assert_eq!(parsed.address_prefix, 42);
+        assert_eq!(parsed.sender.as_deref(), Some(SENDER));
+        assert_eq!(
+            parsed.sender_public_key,
+            Some(format!("0x{}", hex::encode(&sender_key)))
+        );
+
+        let destinations: Vec<_> = parsed
+            .destinations
+            .iter()
+            .map(|d| d.address.as_str())
+            .collect();
+        assert_eq!(destinations, [RECIPIENT, SENDER]);
+        for destination in &parsed.destinations {
+            let (public_key, _) = decode_ss58(&destination.address).unwrap();
+            assert_eq!(
+                destination.public_key,
+                format!("0x{}", hex::encode(public_key))
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_staking_rewards_and_pool_calls() {
+        const SENDER: &str = "5EGoFA95omzemRssELLDjVenNZ68aXyUeqtKQScXSEBvVJkr";
+        const VALIDATOR: &str = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty";
+        const WESTEND_GENESIS: &str =
+            "0xe143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e";
+
+        let metadata_bytes = include_bytes!("../test-fixtures/westend_metadata.scale");
+        let material = crate::types::Material {
+            genesis_hash: WESTEND_GENESIS.to_string(),
+            chain_name: "Westend".to_string(),
+            spec_name: "westend".to_string(),
+            spec_version: 9420,
+            tx_version: 16,
+            metadata: hex::encode(metadata_bytes),
+            assets: Vec::new(),
+        };
+        let json = serde_json::json!({
+            "sender": SENDER,
+            "nonce": 0,
+            "validity": { "firstValid": 1000 },
+            "referenceBlock": WESTEND_GENESIS,
+            "calls": [
+                {
+                    "type": "staking",
+                    "action": "payoutStakers",
+                    "validatorStash": VALIDATOR,
+                    "era": 1200
+                },
+                {
+                    "type": "nominationPools",
+                    "action": "join",
+                    "amount": "10000000000",
+                    "poolId": 7
+                },
+                { "type": "nominationPools", "action": "bondExtra", "amount": "5" },
+                { "type": "nominationPools", "action": "bondExtra" },
+                { "type": "nominationPools", "action": "claimPayout" },
+                {
+                    "type": "nominationPools",
+                    "action": "unbond",
+                    "memberAccount": SENDER,
+                    "amount": "3"
+                },
+                {
+                    "type": "nominationPools",
+                    "action": "withdrawUnbonded",
+                    "memberAccount": SENDER,
+                    "slashingSpans": 1
+                },
+            ],
+        });
+        let tx =
+            crate::builder::build_from_intent_json(&json.to_string(), material.clone()).unwrap();
+        let context = ParseContext {
+            material,
+            sender: Some(SENDER.to_string()),
+        };
+        let parsed = parse_from_transaction(&tx, Some(&context)).unwrap();
+        assert!(parsed.destinations.is_empty());
+
+        let calls: Vec<_> = parsed.method.args["calls"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|call| {
+                (
+                    call["name"].as_str().unwrap().to_string(),
+                    call["args"].clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            calls,
+            [
+                (
+                    "payoutStakers".to_string(),
+                    serde_json::json!({ "validatorStash": VALIDATOR, "era": 1200 })
+                ),
+                (
+                    "join".to_string(),
+                    serde_json::json!({ "amount": "10000000000", "poolId": 7 })
+                ),
+                (
+                    "bondExtra".to_string(),
+                    serde_json::json!({ "extra": "FreeBalance", "value": "5" })
+                ),
+                (
+                    "bondExtra".to_string(),
+                    serde_json::json!({ "extra": "Rewards" })
+                ),
+                ("claimPayout".to_string(), serde_json::json!({})),
+                (
+                    "unbond".to_string(),
+                    serde_json::json!({ "memberAccount": SENDER, "unbondingPoints": "3" })
+                ),
+                (
+                    "withdrawUnbonded".to_string(),
+                    serde_json::json!({ "memberAccount": SENDER, "numSlashingSpans": 1 })
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_asset_transfer_args() {
+        use parity_scale_codec::{Compact, Encode};
+
+        const RECIPIENT: &str = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty";
+        let (public_key, _) = decode_ss58(RECIPIENT).unwrap();
+        let mut args = Compact(1984u32).encode();
+        args.push(0x00);
+        args.extend(&public_key);
+        Compact(2_500_000u128).encode_to(&mut args);
+        args.push(0xff); // next call in a batch
+
+        let (mut parsed, consumed) = parse_asset_transfer_args(&args, 42).unwrap();
+        assert_eq!(consumed, args.len() - 1);
+        assert_eq!(
+            parsed,
+            serde_json::json!({ "id": 1984, "target": RECIPIENT, "amount": "2500000" })
+        );
+
+        let mut destinations = Vec::new();
+        collect_destinations("assets", "transferKeepAlive", &parsed, &mut destinations).unwrap();
+        assert_eq!(destinations.len(), 1);
+        assert_eq!(destinations[0].address, RECIPIENT);
+
+        let index_target = serde_json::json!({ "id": 1984, "target": { "Index": 5 } });
+        let err = collect_destinations("assets", "transfer", &index_target, &mut destinations);
+        assert!(matches!(err, Err(WasmDotError::InvalidTransaction(_))));
+        let missing_dest = serde_json::json!({ "value": "1000" });
+        let err = collect_destinations(
+            "balances",
+            "transferKeepAlive",
+            &missing_dest,
+            &mut destinations,
+        );
+        assert!(matches!(err, Err(WasmDotError::InvalidTransaction(_))));
+
+        let usdt = AssetInfo {
+            id: 1984,
+            symbol: "USDT".to_string(),
+            decimals: 6,
+        };
+        annotate_assets("assets", "transfer", &mut parsed, &[]);
+        assert!(parsed.get("symbol").is_none());
+        annotate_assets("assets", "transfer", &mut parsed, &[usdt]);
+        assert_eq!(parsed["symbol"], "USDT");
+        assert_eq!(parsed["decimals"], 6);
+
+        assert!(parse_asset_transfer_args(&args[..3], 42).is_err());
+    }
+
     #[test]
     fn test_parse_call_data_without_metadata_returns_error() {
         let call_data = vec![5u8, 3u8, 0x00];
@@ -0,0 +1,248 @@
+//! Pure (anonymous) proxy accounts
+//!
+//! `proxy.createPure` spawns a keyless account whose address is derived from the
+//! spawner, the proxy type, a disambiguation index and the position of the creating
+//! extrinsic (block height and extrinsic index). This mirrors `pallet_proxy::pure_account`
+//! so wallets can compute the address without querying the chain, and decodes the
+//! `Proxy.PureCreated` event that reports it.
+
+use crate::address::{decode_ss58, encode_ss58};
+use crate::error::WasmDotError;
+use serde::{Deserialize, Serialize};
+use subxt_core::metadata::Metadata;
+
+/// Entropy prefix used by `pallet_proxy::pure_account`
+const PURE_PROXY_ENTROPY_PREFIX: &[u8; 16] = b"modlpy/proxy____";
+
+/// Size of the `PureCreated` event fields: pure, who, proxy_type, disambiguation_index
+const PURE_CREATED_LEN: usize = 32 + 32 + 1 + 2;
+
+/// Inputs of the pure proxy address derivation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PureProxyParams {
+    /// Account that sent the `createPure` extrinsic (SS58)
+    pub who: String,
+    /// Proxy type as named in the runtime (e.g., "Any")
+    pub proxy_type: String,
+    /// Disambiguation index passed to `createPure`
+    #[serde(default)]
+    pub index: u16,
+    /// Height of the block that included the extrinsic
+    pub block_height: u32,
+    /// Index of the extrinsic within that block
+    pub ext_index: u32,
+}
+
+/// Decoded `Proxy.PureCreated` event data
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PureCreatedEvent {
+    /// The new pure proxy account (SS58)
+    pub pure: String,
+    /// The spawning account (SS58)
+    pub who: String,
+    /// Proxy type name
+    pub proxy_type: String,
+    /// Disambiguation index passed to `createPure`
+    pub disambiguation_index: u16,
+    /// Block height of the creation, reported by newer runtimes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub at: Option<u32>,
+    /// Extrinsic index of the creation, reported by newer runtimes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extrinsic_index: Option<u32>,
+}
+
+/// Compute the pure proxy account id.
+///
+/// Blake2-256 of the SCALE encoding of
+/// `("modlpy/proxy____", who, height, ext_index, proxy_type, index)`.
+pub fn pure_proxy_account(
+    who: &[u8; 32],
+    proxy_type: u8,
+    index: u16,
+    block_height: u32,
+    ext_index: u32,
+) -> [u8; 32] {
+    use blake2::{digest::consts::U32, Blake2b, Digest};
+
+    let mut hasher = Blake2b::<U32>::new();
+    hasher.update(PURE_PROXY_ENTROPY_PREFIX);
+    hasher.update(who);
+    hasher.update(block_height.to_le_bytes());
+    hasher.update(ext_index.to_le_bytes());
+    hasher.update([proxy_type]);
+    hasher.update(index.to_le_bytes());
+    hasher.finalize().into()
+}
+
+/// Compute the SS58 address of a pure proxy, resolving the proxy type from metadata.
+pub fn pure_proxy_address(
+    params: &PureProxyParams,
+    metadata: &Metadata,
+    address_prefix: u16,
+) -> Result<String, WasmDotError> {
+    let (who, _) = decode_ss58(&params.who)?;
+    let who: [u8; 32] = who
+        .try_into()
+        .map_err(|_| WasmDotError::InvalidAddress("Invalid public key length".to_string()))?;
+    let proxy_type = proxy_type_index(metadata, &params.proxy_type)?;
+    let account = pure_proxy_account(
+        &who,
+        proxy_type,
+        params.index,
+        params.block_height,
+        params.ext_index,
+    );
+    encode_ss58(&account, address_prefix)
+}
+
+/// Decode the data of a `Proxy.PureCreated` event.
+///
+/// Accepts the original layout `(pure, who, proxy_type, disambiguation_index)` and the
+/// extended layout of newer runtimes, which appends the creation block height and
+/// extrinsic index.
+pub fn decode_pure_created(
+    data: &[u8],
+    metadata: &Metadata,
+    address_prefix: u16,
+) -> Result<PureCreatedEvent, WasmDotError> {
+    let (at, extrinsic_index) = match data.len() {
+        PURE_CREATED_LEN => (None, None),
+        len if len == PURE_CREATED_LEN + 8 => {
+            let rest = &data[PURE_CREATED_LEN..];
+            (
+                Some(u32::from_le_bytes([rest[0], rest[1], rest[2], rest[3]])),
+                Some(u32::from_le_bytes([rest[4], rest[5], rest[6], rest[7]])),
+            )
+        }
+        len => {
+            return Err(WasmDotError::ScaleDecodeError(format!(
+                "PureCreated event data has {} bytes, expected {} or {}",
+                len,
+                PURE_CREATED_LEN,
+                PURE_CREATED_LEN + 8
+            )))
+        }
+    };
+
+    let proxy_type = proxy_type_name(metadata, data[64]).ok_or_else(|| {
+        WasmDotError::InvalidInput(format!("Unknown proxy type index {} in metadata", data[64]))
+    })?;
+
+    Ok(PureCreatedEvent {
+        pure: encode_ss58(&data[0..32], address_prefix)?,
+        who: encode_ss58(&data[32..64], address_prefix)?,
+        proxy_type,
+        disambiguation_index: u16::from_le_bytes([data[65], data[66]]),
+        at,
+        extrinsic_index,
+    })
+}
+
+/// Variants of the runtime's `ProxyType` enum, found through the `add_proxy` call.
+fn proxy_type_variants(
+    metadata: &Metadata,
+) -> Option<&[scale_info::Variant<scale_info::form::PortableForm>]> {
+    let proxy_pallet = metadata.pallet_by_name("Proxy")?;
+    let call_ty = metadata.types().resolve(proxy_pallet.call_ty_id()?)?;
+    let scale_info::TypeDef::Variant(ref calls) = call_ty.type_def else {
+        return None;
+    };
+    let add_proxy = calls
+        .variants
+        .iter()
+        .find(|v| v.name == "add_proxy" || v.name == "addProxy")?;
+    let pt_field = add_proxy
+        .fields
+        .iter()
+        .find(|f| f.name.as_deref() == Some("proxy_type"))?;
+    let pt_ty = metadata.types().resolve(pt_field.ty.id)?;
+    match pt_ty.type_def {
+        scale_info::TypeDef::Variant(ref variants) => Some(&variants.variants),
+        _ => None,
+    }
+}
+
+/// Look up the name of a `ProxyType` variant by its index.
+pub(crate) fn proxy_type_name(metadata: &Metadata, index: u8) -> Option<String> {
+    proxy_type_variants(metadata)?
+        .iter()
+        .find(|v| v.index == index)
+        .map(|v| v.name.clone())
+}
+
+/// Look up the index of a `ProxyType` variant by its name.
+pub fn proxy_type_index(metadata: &Metadata, name: &str) -> Result<u8, WasmDotError> {
+    proxy_type_variants(metadata)
+        .ok_or_else(|| WasmDotError::InvalidInput("ProxyType not found in metadata".to_string()))?
+        .iter()
+        .find(|v| v.name == name)
+        .map(|v| v.index)
+        .ok_or_else(|| WasmDotError::InvalidInput(format!("Unknown proxy type: {}", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::decode_metadata;
+
+    const WHO: &str = "5EGoFA95omzemRssELLDjVenNZ68aXyUeqtKQScXSEBvVJkr";
+
+    fn westend_metadata() -> Metadata {
+        let metadata_bytes = include_bytes!("../test-fixtures/westend_metadata.scale");
+        decode_metadata(&hex::encode(metadata_bytes)).unwrap()
+    }
+
+    #[test]
+    fn test_pure_proxy_account() {
+        let (who, _) = decode_ss58(WHO).unwrap();
+        let who: [u8; 32] = who.try_into().unwrap();
+        let account = pure_proxy_account(&who, 0, 0, 1000, 2);
+        assert_eq!(
+            hex::encode(account),
+            "421a3eae7d31b1e796fd417660047d42522e3c3b8826e6787fc08ec4b5661b46"
+        );
+        // Every input changes the address
+        assert_ne!(pure_proxy_account(&who, 1, 0, 1000, 2), account);
+        assert_ne!(pure_proxy_account(&who, 0, 1, 1000, 2), account);
+        assert_ne!(pure_proxy_account(&who, 0, 0, 1001, 2), account);
+        assert_ne!(pure_proxy_account(&who, 0, 0, 1000, 3), account);
+    }
+
+    #[test]
+    fn test_pure_created_roundtrip() {
+        let metadata = westend_metadata();
+        let params = PureProxyParams {
+            who: WHO.to_string(),
+            proxy_type: "Any".to_string(),
+            index: 7,
+            block_height: 1000,
+            ext_index: 2,
+        };
+        let address = pure_proxy_address(&params, &metadata, 42).unwrap();
+
+        let (pure, _) = decode_ss58(&address).unwrap();
+        let (who, _) = decode_ss58(WHO).unwrap();
+        let mut data = [pure, who].concat();
+        data.push(proxy_type_index(&metadata, "Any").unwrap());
+        data.extend_from_slice(&7u16.to_le_bytes());
+
+        let event = decode_pure_created(&data, &metadata, 42).unwrap();
+        assert_eq!(event.pure, address);
+        assert_eq!(event.who, WHO);
+        assert_eq!(event.proxy_type, "Any");
+        assert_eq!(event.disambiguation_index, 7);
+        assert_eq!(event.at, None);
+
+        data.extend_from_slice(&1000u32.to_le_bytes());
+        data.extend_from_slice(&2u32.to_le_bytes());
+        let event = decode_pure_created(&data, &metadata, 42).unwrap();
+        assert_eq!(event.at, Some(1000));
+        assert_eq!(event.extrinsic_index, Some(2));
+
+        assert!(decode_pure_created(&data[..66], &metadata, 42).is_err());
+        assert!(proxy_type_index(&metadata, "NoSuchType").is_err());
+    }
+}
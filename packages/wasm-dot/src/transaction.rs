@@ -9,7 +9,7 @@ use alloc::vec::Vec;
 use subxt_core::{
     config::{
         polkadot::{PolkadotConfig, PolkadotExtrinsicParamsBuilder},
-        Config, ExtrinsicParams,
+        Config, ExtrinsicParams, ExtrinsicParamsEncoder,
     },
     error::Error as SubxtError,
     metadata::Metadata,
@@ -19,6 +19,9 @@ use subxt_core::{
 
 extern crate alloc;
 
+/// Signing payloads longer than this are signed as their Blake2-256 hash
+pub const MAX_UNHASHED_PAYLOAD_LEN: usize = 256;
+
 // =============================================================================
 // Pre-encoded call payload wrapper
 // =============================================================================
@@ -224,8 +227,9 @@ impl Transaction {
             // Format: compact(call_data_len) | call_data | extensions | additional_signed
             // This matches legacy toBroadcastFormat (construct.signingPayload from txwrapper)
             // and is decodable by both wasm-dot and polkadot-js/txwrapper.
+            // The payload is never hashed here, otherwise long call data would not round-trip.
             use parity_scale_codec::{Compact, Encode};
-            let payload = self.signable_payload()?;
+            let payload = self.raw_signable_payload()?;
             let mut result = Compact(self.call_data.len() as u32).encode();
             result.extend_from_slice(&payload);
             Ok(result)
@@ -308,9 +312,20 @@ impl Transaction {
 
     /// Get the signable payload for this transaction
     ///
-    /// Uses subxt-core's `PartialTransaction::signer_payload()` for correct construction.
-    /// This creates the bytes that must be signed to produce a valid signature.
+    /// This is the exact byte string that must be signed with Ed25519: the raw payload
+    /// from [`Self::raw_signable_payload`], or its Blake2-256 hash when the raw payload
+    /// is longer than [`MAX_UNHASHED_PAYLOAD_LEN`] bytes.
     pub fn signable_payload(&self) -> Result<Vec<u8>, WasmDotError> {
+        Ok(hash_signing_payload(self.raw_signable_payload()?))
+    }
+
+    /// Get the unhashed signing payload for this transaction
+    ///
+    /// Format: call_data | extensions | additional_signed
+    ///
+    /// Intended for external signers (e.g. HSMs) that apply the >256-byte hashing
+    /// rule themselves or need to display the full payload before signing.
+    pub fn raw_signable_payload(&self) -> Result<Vec<u8>, WasmDotError> {
         let context = self
             .context
             .as_ref()
@@ -319,16 +334,15 @@ impl Transaction {
         let client_state = context.to_client_state()?;
         let params = context.to_extrinsic_params(self.nonce, self.tip);
 
-        // Create payload from pre-encoded call data
-        let call = PreEncodedPayload(self.call_data.clone());
-
-        // Create partial transaction using subxt-core
-        let partial = tx::create_partial_signed(&call, &client_state, params)
-            .map_err(|e| WasmDotError::InvalidTransaction(e.to_string()))?;
+        // Same extension encoding that subxt-core uses for the signer payload
+        let extrinsic_params =
+            <PolkadotConfig as Config>::ExtrinsicParams::new(&client_state, params)
+                .map_err(|e| WasmDotError::InvalidTransaction(e.to_string()))?;
 
-        // Get the signer payload - this is what needs to be signed
-        // subxt-core handles the > 256 byte hashing automatically
-        Ok(partial.signer_payload())
+        let mut payload = self.call_data.clone();
+        extrinsic_params.encode_extra_to(&mut payload);
+        extrinsic_params.encode_additional_to(&mut payload);
+        Ok(payload)
     }
 
     /// Add a signature to this transaction
@@ -365,11 +379,39 @@ impl Transaction {
         Ok(())
     }
 
+    /// Add an externally produced signature and serialize the signed extrinsic
+    ///
+    /// Counterpart of [`Self::signable_payload`] for external signers. Unlike
+    /// [`Self::to_bytes`], this requires the signing context so that the extrinsic
+    /// carries the same extensions that were signed.
+    ///
+    /// # Arguments
+    /// * `pubkey` - 32-byte Ed25519 public key
+    /// * `signature` - 64-byte Ed25519 signature over the signable payload
+    pub fn assemble_signed(
+        &mut self,
+        pubkey: &[u8],
+        signature: &[u8],
+    ) -> Result<Vec<u8>, WasmDotError> {
+        if self.context.is_none() {
+            return Err(WasmDotError::MissingContext(
+                "No context set for transaction".into(),
+            ));
+        }
+        self.add_signature(pubkey, signature)?;
+        self.to_bytes()
+    }
+
     /// Get sender address (SS58 encoded)
     pub fn sender(&self, prefix: u16) -> Option<String> {
         self.signer.and_then(|pk| encode_ss58(&pk, prefix).ok())
     }
 
+    /// Get the signer public key bytes
+    pub fn signer_bytes(&self) -> Option<&[u8; 32]> {
+        self.signer.as_ref()
+    }
+
     /// Get the signature bytes
     pub fn signature_bytes(&self) -> Option<&[u8; 64]> {
         self.signature.as_ref()
@@ -449,6 +491,20 @@ pub(crate) fn decode_metadata(metadata_hex: &str) -> Result<Metadata, WasmDotErr
         .map_err(|e| WasmDotError::InvalidInput(format!("Failed to decode metadata: {}", e)))
 }
 
+/// Apply the signing payload hashing rule to a raw signing payload
+///
+/// Payloads longer than [`MAX_UNHASHED_PAYLOAD_LEN`] bytes are replaced by their
+/// Blake2-256 hash; shorter payloads are signed as-is.
+pub fn hash_signing_payload(raw: Vec<u8>) -> Vec<u8> {
+    use blake2::{digest::consts::U32, Blake2b, Digest};
+
+    if raw.len() > MAX_UNHASHED_PAYLOAD_LEN {
+        Blake2b::<U32>::digest(&raw).to_vec()
+    } else {
+        raw
+    }
+}
+
 /// Parse hex string to 32-byte hash
 fn parse_hex_hash(hex_str: &str) -> Result<[u8; 32], WasmDotError> {
     let hex_str = hex_str.strip_prefix("0x").unwrap_or(hex_str);
@@ -824,7 +880,7 @@ fn decode_era_bytes(bytes: &[u8]) -> Result<(Era, usize), WasmDotError> {
         }
         let encoded = u16::from_le_bytes([bytes[0], bytes[1]]);
         let period = 2u32 << (encoded as u32 & 0xf);
-        let quantize_factor = (period / 4).max(1);
+        let quantize_factor = (period >> 12).max(1);
         let phase = ((encoded >> 4) as u32) * quantize_factor;
         Ok((Era::Mortal { period, phase }, 2))
     }
@@ -849,4 +905,121 @@ mod tests {
         let (decoded, _) = decode_era_bytes(&mortal_bytes).unwrap();
         assert!(!decoded.is_immortal());
     }
+
+    #[test]
+    fn test_decode_mortal_era_known_answer() {
+        // Vectors of `Era::mortal` in sp-runtime: the phase of periods above 4096 is quantized
+        for (bytes, period, phase) in [([0xa5, 0x02], 64, 42), ([0x4e, 0x9c], 32768, 20000)] {
+            let (decoded, len) = decode_era_bytes(&bytes).unwrap();
+            assert_eq!(len, 2);
+            assert_eq!(decoded, Era::Mortal { period, phase });
+            assert_eq!(encode_era(&decoded), bytes);
+        }
+    }
+
+    const WESTEND_GENESIS: &str =
+        "0xe143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e";
+
+    fn westend_context() -> (Material, Metadata) {
+        let metadata_bytes = include_bytes!("../test-fixtures/westend_metadata.scale");
+        let material = Material {
+            genesis_hash: WESTEND_GENESIS.to_string(),
+            chain_name: "Westend".to_string(),
+            spec_name: "westend".to_string(),
+            spec_version: 9420,
+            tx_version: 16,
+            metadata: hex::encode(metadata_bytes),
+            assets: Vec::new(),
+        };
+        let metadata = subxt_core::metadata::decode_from(&metadata_bytes[..]).unwrap();
+        (material, metadata)
+    }
+
+    /// system.remark with a `len`-byte remark
+    fn remark_call(len: usize) -> Vec<u8> {
+        use parity_scale_codec::{Compact, Encode};
+        let mut call = vec![0x00, 0x00];
+        Compact(len as u32).encode_to(&mut call);
+        call.extend(core::iter::repeat_n(0xab, len));
+        call
+    }
+
+    fn unsigned_tx(call_data: Vec<u8>, material: &Material) -> Transaction {
+        let mut tx = Transaction::new(call_data, Era::Immortal, 5, 0);
+        tx.set_context(
+            material.clone(),
+            Validity {
+                first_valid: 1000,
+                max_duration: 2400,
+            },
+            WESTEND_GENESIS,
+        )
+        .unwrap();
+        tx
+    }
+
+    #[test]
+    fn test_signing_payload_hashing() {
+        let (material, metadata) = westend_context();
+
+        for (remark_len, hashed) in [(10, false), (300, true)] {
+            let tx = unsigned_tx(remark_call(remark_len), &material);
+            let raw = tx.raw_signable_payload().unwrap();
+            let payload = tx.signable_payload().unwrap();
+            assert!(raw.starts_with(tx.call_data()));
+            assert_eq!(raw.len() > MAX_UNHASHED_PAYLOAD_LEN, hashed);
+            if hashed {
+                assert_eq!(payload.len(), 32);
+                assert_eq!(payload, hash_signing_payload(raw.clone()));
+            } else {
+                assert_eq!(payload, raw);
+            }
+
+            // Must match the payload subxt-core signs
+            let ctx = tx.context.as_ref().unwrap();
+            let partial = tx::create_partial_signed(
+                &PreEncodedPayload(tx.call_data().to_vec()),
+                &ctx.to_client_state().unwrap(),
+                ctx.to_extrinsic_params(tx.nonce(), tx.tip()),
+            )
+            .unwrap();
+            assert_eq!(payload, partial.signer_payload());
+
+            // Unsigned serialization carries the raw payload and round-trips
+            let bytes = tx.to_bytes().unwrap();
+            let parsed = Transaction::from_bytes(
+                &bytes,
+                Some(ParseContext {
+                    material: material.clone(),
+                    sender: None,
+                }),
+                Some(&metadata),
+            )
+            .unwrap();
+            assert_eq!(parsed.call_data(), tx.call_data());
+            assert_eq!(parsed.nonce(), 5);
+        }
+    }
+
+    #[test]
+    fn test_assemble_signed() {
+        let (material, metadata) = westend_context();
+        let pubkey = [0x11u8; 32];
+        let signature = [0x22u8; 64];
+
+        let mut without_context = Transaction::new(remark_call(10), Era::Immortal, 0, 0);
+        assert!(matches!(
+            without_context.assemble_signed(&pubkey, &signature),
+            Err(WasmDotError::MissingContext(_))
+        ));
+
+        let mut tx = unsigned_tx(remark_call(300), &material);
+        assert!(tx.assemble_signed(&pubkey, &signature[..63]).is_err());
+        let bytes = tx.assemble_signed(&pubkey, &signature).unwrap();
+
+        let parsed = Transaction::from_bytes(&bytes, None, Some(&metadata)).unwrap();
+        assert!(parsed.is_signed());
+        assert_eq!(parsed.signature_bytes(), Some(&signature));
+        assert_eq!(parsed.call_data(), tx.call_data());
+    }
 }
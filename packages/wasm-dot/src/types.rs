@@ -1,5 +1,7 @@
 //! Shared types for DOT transactions
 
+use crate::address::decode_ss58;
+use crate::error::WasmDotError;
 use serde::{Deserialize, Serialize};
 
 /// Chain material metadata required for transaction encoding/decoding
@@ -24,10 +26,32 @@ pub struct Material {
     /// APIs. The hex-to-bytes decode happens once internally (in `decode_metadata`)
     /// right before SCALE decoding.
     pub metadata: String,
+    /// Assets of the `Assets` pallet that may be transferred (AssetHub only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assets: Vec<AssetInfo>,
+}
+
+impl Material {
+    /// Look up an asset of the `Assets` pallet by id
+    pub fn asset(&self, id: u32) -> Option<&AssetInfo> {
+        self.assets.iter().find(|asset| asset.id == id)
+    }
+}
+
+/// Asset of the `Assets` pallet (e.g., USDT is asset 1984 on Polkadot AssetHub)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetInfo {
+    /// Asset id
+    pub id: u32,
+    /// Ticker symbol (e.g., "USDT")
+    pub symbol: String,
+    /// Number of decimals of the asset amounts
+    pub decimals: u8,
 }
 
 /// Validity window for mortal transactions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Validity {
     /// Block number when transaction becomes valid
@@ -105,6 +129,305 @@ impl AddressFormat {
     }
 }
 
+// =============================================================================
+// Unsigned transaction intent schema
+// =============================================================================
+
+/// Serialized form of an unsigned transaction, without chain material.
+///
+/// Accepted by `builder::build_from_intent_json`. Unlike `TransactionIntent`, which
+/// describes business operations, this schema lists the individual calls, so it can
+/// express any combination of transfers, asset transfers, staking, nomination pool and proxy
+/// calls. More than one call is wrapped in `batchAll`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct UnsignedTransactionIntent {
+    /// Sender address (SS58 encoded)
+    pub sender: String,
+    /// Account nonce
+    pub nonce: u32,
+    /// Tip in planck
+    #[serde(default, with = "planck")]
+    pub tip: u64,
+    /// Validity window
+    pub validity: Validity,
+    /// Reference block hash for mortality
+    pub reference_block: String,
+    /// Calls in execution order
+    pub calls: Vec<Intent>,
+}
+
+/// A single call of an [`UnsignedTransactionIntent`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Intent {
+    Transfer(TransferIntent),
+    AssetTransfer(AssetTransferIntent),
+    Staking(StakingIntent),
+    NominationPools(PoolIntent),
+    Proxy(ProxyIntent),
+}
+
+/// Balances transfer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferIntent {
+    /// Recipient address (SS58)
+    pub to: String,
+    /// Amount in planck. Absent transfers the whole balance (transferAll).
+    #[serde(
+        default,
+        with = "planck::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub amount: Option<u64>,
+    /// Prevent the sender account from being reaped (default: true)
+    #[serde(default = "default_true")]
+    pub keep_alive: bool,
+}
+
+/// Assets pallet transfer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetTransferIntent {
+    /// Asset id, which must be listed in the chain material
+    pub asset_id: u32,
+    /// Recipient address (SS58)
+    pub to: String,
+    /// Amount in the smallest unit of the asset
+    #[serde(with = "planck")]
+    pub amount: u64,
+    /// Prevent the sender account from being reaped (default: true)
+    #[serde(default = "default_true")]
+    pub keep_alive: bool,
+}
+
+/// Staking pallet call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum StakingIntent {
+    Bond {
+        #[serde(with = "planck")]
+        amount: u64,
+        #[serde(default)]
+        payee: StakePayee,
+    },
+    BondExtra {
+        #[serde(with = "planck")]
+        amount: u64,
+    },
+    Unbond {
+        #[serde(with = "planck")]
+        amount: u64,
+    },
+    Chill,
+    WithdrawUnbonded {
+        #[serde(default, rename = "slashingSpans")]
+        slashing_spans: u32,
+    },
+    /// Pay out the rewards of a validator and its nominators for an era
+    PayoutStakers {
+        /// Validator stash address (SS58)
+        #[serde(rename = "validatorStash")]
+        validator_stash: String,
+        era: u32,
+    },
+}
+
+/// Nomination pools pallet call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum PoolIntent {
+    /// Join a pool, bonding `amount` from the free balance
+    Join {
+        #[serde(with = "planck")]
+        amount: u64,
+        #[serde(rename = "poolId")]
+        pool_id: u32,
+    },
+    /// Bond more into the pool. Absent `amount` re-bonds the pending rewards.
+    BondExtra {
+        #[serde(
+            default,
+            with = "planck::option",
+            skip_serializing_if = "Option::is_none"
+        )]
+        amount: Option<u64>,
+    },
+    /// Claim the pending rewards to the free balance
+    ClaimPayout,
+    Unbond {
+        /// Pool member address (SS58)
+        #[serde(rename = "memberAccount")]
+        member_account: String,
+        /// Unbonding points (equal to planck unless the pool was slashed)
+        #[serde(with = "planck")]
+        amount: u64,
+    },
+    WithdrawUnbonded {
+        /// Pool member address (SS58)
+        #[serde(rename = "memberAccount")]
+        member_account: String,
+        #[serde(default, rename = "slashingSpans")]
+        slashing_spans: u32,
+    },
+}
+
+/// Proxy pallet call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyIntent {
+    pub action: ProxyAction,
+    /// Proxy delegate address (SS58)
+    pub delegate: String,
+    /// Proxy type as named in the runtime (default: "Staking")
+    #[serde(default = "default_proxy_type")]
+    pub proxy_type: String,
+    /// Announcement delay in blocks
+    #[serde(default)]
+    pub delay: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProxyAction {
+    Add,
+    Remove,
+}
+
+/// Staking reward destination
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Default)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum StakePayee {
+    /// Compound rewards (re-stake)
+    #[default]
+    Staked,
+    /// Send to stash account
+    Stash,
+    /// Send to controller account
+    Controller,
+    /// Send to specific account
+    Account {
+        /// Destination address
+        address: String,
+    },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_proxy_type() -> String {
+    "Staking".to_string()
+}
+
+impl UnsignedTransactionIntent {
+    /// Check addresses and hashes so that malformed intents fail before call encoding
+    pub fn validate(&self) -> Result<(), WasmDotError> {
+        fn check_address(field: &str, address: &str) -> Result<(), WasmDotError> {
+            decode_ss58(address)
+                .map(|_| ())
+                .map_err(|e| WasmDotError::InvalidAddress(format!("{}: {}", field, e)))
+        }
+
+        check_address("sender", &self.sender)?;
+
+        let block = self
+            .reference_block
+            .strip_prefix("0x")
+            .unwrap_or(&self.reference_block);
+        if hex::decode(block).map(|b| b.len()) != Ok(32) {
+            return Err(WasmDotError::InvalidInput(format!(
+                "referenceBlock: expected 32-byte hex hash, got '{}'",
+                self.reference_block
+            )));
+        }
+
+        if self.calls.is_empty() {
+            return Err(WasmDotError::InvalidInput(
+                "calls: at least one call is required".to_string(),
+            ));
+        }
+
+        for (i, call) in self.calls.iter().enumerate() {
+            match call {
+                Intent::Transfer(transfer) => {
+                    check_address(&format!("calls[{}].to", i), &transfer.to)?
+                }
+                Intent::AssetTransfer(transfer) => {
+                    check_address(&format!("calls[{}].to", i), &transfer.to)?
+                }
+                Intent::Staking(StakingIntent::Bond {
+                    payee: StakePayee::Account { address },
+                    ..
+                }) => check_address(&format!("calls[{}].payee.address", i), address)?,
+                Intent::Staking(StakingIntent::PayoutStakers {
+                    validator_stash, ..
+                }) => check_address(&format!("calls[{}].validatorStash", i), validator_stash)?,
+                Intent::Staking(_) => {}
+                Intent::NominationPools(
+                    PoolIntent::Unbond { member_account, .. }
+                    | PoolIntent::WithdrawUnbonded { member_account, .. },
+                ) => check_address(&format!("calls[{}].memberAccount", i), member_account)?,
+                Intent::NominationPools(_) => {}
+                Intent::Proxy(proxy) => {
+                    check_address(&format!("calls[{}].delegate", i), &proxy.delegate)?
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Planck amounts are serialized as decimal strings, since JSON numbers above 2^53 lose
+/// precision in JavaScript. Plain numbers are accepted when deserializing.
+mod planck {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrString {
+        Number(u64),
+        String(String),
+    }
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        match NumberOrString::deserialize(deserializer)? {
+            NumberOrString::Number(n) => Ok(n),
+            NumberOrString::String(s) => s
+                .parse()
+                .map_err(|_| serde::de::Error::custom(format!("invalid planck amount '{}'", s))),
+        }
+    }
+
+    pub mod option {
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(
+            value: &Option<u64>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match value {
+                Some(value) => super::serialize(value, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<u64>, D::Error> {
+            #[derive(Deserialize)]
+            struct Wrapper(#[serde(with = "super")] u64);
+            Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|Wrapper(n)| n))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -119,6 +442,136 @@ mod tests {
         .is_immortal());
     }
 
+    const SENDER: &str = "5EGoFA95omzemRssELLDjVenNZ68aXyUeqtKQScXSEBvVJkr";
+    const PROXY: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+    const BLOCK: &str = "0xe143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e";
+
+    fn stake_with_proxy() -> UnsignedTransactionIntent {
+        UnsignedTransactionIntent {
+            sender: SENDER.to_string(),
+            nonce: 3,
+            tip: 0,
+            validity: Validity::default(),
+            reference_block: BLOCK.to_string(),
+            calls: vec![
+                Intent::Staking(StakingIntent::Bond {
+                    amount: 10_000_000_000_000_000_000,
+                    payee: StakePayee::Staked,
+                }),
+                Intent::Proxy(ProxyIntent {
+                    action: ProxyAction::Add,
+                    delegate: PROXY.to_string(),
+                    proxy_type: "Staking".to_string(),
+                    delay: 0,
+                }),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_unsigned_intent_round_trip() {
+        let intent = stake_with_proxy();
+        let json = serde_json::to_string(&intent).unwrap();
+        assert!(
+            json.contains(r#""amount":"10000000000000000000""#),
+            "{}",
+            json
+        );
+        let decoded: UnsignedTransactionIntent = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, intent);
+        decoded.validate().unwrap();
+    }
+
+    #[test]
+    fn test_unsigned_intent_from_json() {
+        let json = format!(
+            r#"{{
+                "sender": "{SENDER}",
+                "nonce": 0,
+                "validity": {{ "firstValid": 1000 }},
+                "referenceBlock": "{BLOCK}",
+                "calls": [
+                    {{ "type": "transfer", "to": "{PROXY}", "amount": 1000 }},
+                    {{ "type": "transfer", "to": "{PROXY}", "keepAlive": false }},
+                    {{ "type": "staking", "action": "withdrawUnbonded" }},
+                    {{ "type": "proxy", "action": "remove", "delegate": "{PROXY}" }},
+                    {{ "type": "assetTransfer", "assetId": 1984, "to": "{PROXY}", "amount": "5" }}
+                ]
+            }}"#
+        );
+        let intent: UnsignedTransactionIntent = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            intent.calls[0],
+            Intent::Transfer(TransferIntent {
+                to: PROXY.to_string(),
+                amount: Some(1000),
+                keep_alive: true,
+            })
+        );
+        assert!(matches!(
+            &intent.calls[1],
+            Intent::Transfer(TransferIntent {
+                amount: None,
+                keep_alive: false,
+                ..
+            })
+        ));
+        assert_eq!(
+            intent.calls[2],
+            Intent::Staking(StakingIntent::WithdrawUnbonded { slashing_spans: 0 })
+        );
+        assert!(matches!(
+            &intent.calls[3],
+            Intent::Proxy(ProxyIntent { action: ProxyAction::Remove, proxy_type, .. })
+                if proxy_type == "Staking"
+        ));
+        assert_eq!(
+            intent.calls[4],
+            Intent::AssetTransfer(AssetTransferIntent {
+                asset_id: 1984,
+                to: PROXY.to_string(),
+                amount: 5,
+                keep_alive: true,
+            })
+        );
+        intent.validate().unwrap();
+
+        assert!(serde_json::from_str::<UnsignedTransactionIntent>(
+            &json.replace(r#""nonce""#, r#""nonse""#)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_unsigned_intent_validate() {
+        let mut intent = stake_with_proxy();
+        intent.calls[1] = Intent::Proxy(ProxyIntent {
+            action: ProxyAction::Add,
+            delegate: "not-an-address".to_string(),
+            proxy_type: "Staking".to_string(),
+            delay: 0,
+        });
+        let err = intent.validate().unwrap_err().to_string();
+        assert!(err.contains("calls[1].delegate"), "{}", err);
+
+        let mut intent = stake_with_proxy();
+        intent.calls.push(Intent::NominationPools(PoolIntent::Unbond {
+            member_account: "not-an-address".to_string(),
+            amount: 1,
+        }));
+        let err = intent.validate().unwrap_err().to_string();
+        assert!(err.contains("calls[2].memberAccount"), "{}", err);
+
+        let mut intent = stake_with_proxy();
+        intent.reference_block = "0x1234".to_string();
+        let err = intent.validate().unwrap_err().to_string();
+        assert!(err.contains("referenceBlock"), "{}", err);
+
+        let mut intent = stake_with_proxy();
+        intent.calls.clear();
+        assert!(intent.validate().is_err());
+    }
+
     #[test]
     fn test_address_format_from_chain() {
         assert_eq!(
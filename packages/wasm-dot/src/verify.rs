@@ -0,0 +1,292 @@
+//! Verification of a transaction against an unsigned transaction intent
+//!
+//! `verify_transaction_matches_intent()` answers "is this the transaction I think it is"
+//! for an offline signer. The intent is built into the expected transaction, and both
+//! transactions are decoded and compared field by field: signer, nonce, tip, era and the
+//! arguments of each call (amounts, destinations, etc.). Differences are returned as a list
+//! of discrepancies instead of an error, so that callers can display all of them.
+
+use crate::address::decode_ss58;
+use crate::builder::build_from_intent;
+use crate::error::WasmDotError;
+use crate::parser::{build_parsed_transaction, ParsedMethod, ParsedTransaction};
+use crate::transaction::{decode_metadata, Transaction};
+use crate::types::{AddressFormat, Era, Material, UnsignedTransactionIntent};
+use serde::Serialize;
+
+/// A field of the transaction that does not match the intent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Discrepancy {
+    /// Path of the field (e.g., "nonce", "calls[0].dest")
+    pub field: String,
+    /// Value derived from the intent
+    pub expected: String,
+    /// Value decoded from the transaction
+    pub actual: String,
+}
+
+/// Decode `tx_bytes` (unsigned signing payload or signed extrinsic) and compare it with
+/// the transaction described by `intent`.
+///
+/// Returns an empty list if the transaction matches. The signer is only compared for
+/// signed transactions.
+pub fn verify_transaction_matches_intent(
+    tx_bytes: &[u8],
+    intent: &UnsignedTransactionIntent,
+    material: Material,
+) -> Result<Vec<Discrepancy>, WasmDotError> {
+    let metadata = decode_metadata(&material.metadata)?;
+    let prefix = AddressFormat::from_chain_name(&material.chain_name).prefix();
+
+    let assets = material.assets.clone();
+
+    let actual = Transaction::from_bytes(tx_bytes, None, Some(&metadata))?;
+    let actual = build_parsed_transaction(&actual, prefix, Some(&metadata), &assets)?;
+    let expected = build_from_intent(intent.clone(), material)?;
+    let expected = build_parsed_transaction(&expected, prefix, Some(&metadata), &assets)?;
+
+    let mut discrepancies = Vec::new();
+    if let Some(signer) = &actual.sender_public_key {
+        let (sender, _) = decode_ss58(&intent.sender)?;
+        let sender = format!("0x{}", hex::encode(sender));
+        if *signer != sender {
+            discrepancies.push(Discrepancy {
+                field: "sender".to_string(),
+                expected: intent.sender.clone(),
+                actual: actual.sender.clone().unwrap_or_default(),
+            });
+        }
+    }
+    compare_transactions(&expected, &actual, &mut discrepancies);
+    Ok(discrepancies)
+}
+
+fn compare_transactions(
+    expected: &ParsedTransaction,
+    actual: &ParsedTransaction,
+    out: &mut Vec<Discrepancy>,
+) {
+    compare(out, "nonce", expected.nonce, actual.nonce);
+    compare(out, "tip", &expected.tip, &actual.tip);
+    compare(
+        out,
+        "era",
+        era_to_string(&expected.era),
+        era_to_string(&actual.era),
+    );
+
+    let expected_calls = calls(&expected.method);
+    let actual_calls = calls(&actual.method);
+    compare(
+        out,
+        "calls.length",
+        expected_calls.len(),
+        actual_calls.len(),
+    );
+    for (i, (expected, actual)) in expected_calls.iter().zip(&actual_calls).enumerate() {
+        let expected_name = format!("{}.{}", expected.pallet, expected.name);
+        let actual_name = format!("{}.{}", actual.pallet, actual.name);
+        if expected_name != actual_name {
+            out.push(Discrepancy {
+                field: format!("calls[{}]", i),
+                expected: expected_name,
+                actual: actual_name,
+            });
+            continue;
+        }
+        compare_args(&format!("calls[{}]", i), &expected.args, &actual.args, out);
+    }
+}
+
+/// Calls of a method, unwrapping `utility.batch` and `utility.batchAll`
+fn calls(method: &ParsedMethod) -> Vec<ParsedMethod> {
+    if method.pallet == "utility" && matches!(method.name.as_str(), "batch" | "batchAll") {
+        if let Some(calls) = method.args["calls"].as_array() {
+            return calls
+                .iter()
+                .filter_map(|call| serde_json::from_value(call.clone()).ok())
+                .collect();
+        }
+    }
+    vec![method.clone()]
+}
+
+/// Compare decoded call arguments, reporting each differing leaf by its path
+fn compare_args(
+    path: &str,
+    expected: &serde_json::Value,
+    actual: &serde_json::Value,
+    out: &mut Vec<Discrepancy>,
+) {
+    use serde_json::Value;
+
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            let mut keys: Vec<_> = expected.keys().chain(actual.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                compare_args(
+                    &format!("{}.{}", path, key),
+                    expected.get(key).unwrap_or(&Value::Null),
+                    actual.get(key).unwrap_or(&Value::Null),
+                    out,
+                );
+            }
+        }
+        _ if expected != actual => out.push(Discrepancy {
+            field: path.to_string(),
+            expected: value_to_string(expected),
+            actual: value_to_string(actual),
+        }),
+        _ => {}
+    }
+}
+
+fn compare<T: PartialEq + ToString>(
+    out: &mut Vec<Discrepancy>,
+    field: &str,
+    expected: T,
+    actual: T,
+) {
+    if expected != actual {
+        out.push(Discrepancy {
+            field: field.to_string(),
+            expected: expected.to_string(),
+            actual: actual.to_string(),
+        });
+    }
+}
+
+fn value_to_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn era_to_string(era: &Era) -> String {
+    match era {
+        Era::Immortal => "immortal".to_string(),
+        Era::Mortal { period, phase } => format!("mortal(period={}, phase={})", period, phase),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Intent, TransferIntent, Validity};
+
+    const SENDER: &str = "5EGoFA95omzemRssELLDjVenNZ68aXyUeqtKQScXSEBvVJkr";
+    const RECIPIENT: &str = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty";
+    const WESTEND_GENESIS: &str =
+        "0xe143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e";
+
+    fn westend_material() -> Material {
+        Material {
+            genesis_hash: WESTEND_GENESIS.to_string(),
+            chain_name: "Westend".to_string(),
+            spec_name: "westend".to_string(),
+            spec_version: 9420,
+            tx_version: 16,
+            metadata: hex::encode(include_bytes!("../test-fixtures/westend_metadata.scale")),
+            assets: Vec::new(),
+        }
+    }
+
+    fn payment(to: &str, amount: u64) -> UnsignedTransactionIntent {
+        UnsignedTransactionIntent {
+            sender: SENDER.to_string(),
+            nonce: 7,
+            tip: 0,
+            validity: Validity {
+                first_valid: 1000,
+                max_duration: 64,
+            },
+            reference_block: WESTEND_GENESIS.to_string(),
+            calls: vec![Intent::Transfer(TransferIntent {
+                to: to.to_string(),
+                amount: Some(amount),
+                keep_alive: true,
+            })],
+        }
+    }
+
+    fn fields(discrepancies: &[Discrepancy]) -> Vec<&str> {
+        discrepancies.iter().map(|d| d.field.as_str()).collect()
+    }
+
+    #[test]
+    fn test_matching_transaction() {
+        let intent = payment(RECIPIENT, 1_000);
+        let mut tx = build_from_intent(intent.clone(), westend_material()).unwrap();
+        let unsigned = tx.to_bytes().unwrap();
+        assert!(
+            verify_transaction_matches_intent(&unsigned, &intent, westend_material())
+                .unwrap()
+                .is_empty()
+        );
+
+        let (sender, _) = decode_ss58(SENDER).unwrap();
+        tx.add_signature(&sender, &[0u8; 64]).unwrap();
+        let signed = tx.to_bytes().unwrap();
+        assert!(
+            verify_transaction_matches_intent(&signed, &intent, westend_material())
+                .unwrap()
+                .is_empty()
+        );
+
+        let mut other = intent.clone();
+        other.sender = RECIPIENT.to_string();
+        assert_eq!(
+            fields(
+                &verify_transaction_matches_intent(&signed, &other, westend_material()).unwrap()
+            ),
+            ["sender"]
+        );
+    }
+
+    #[test]
+    fn test_discrepancies() {
+        let tx = build_from_intent(payment(SENDER, 2_000), westend_material())
+            .unwrap()
+            .to_bytes()
+            .unwrap();
+
+        let mut intent = payment(RECIPIENT, 1_000);
+        intent.nonce = 8;
+        intent.tip = 5;
+        intent.validity.first_valid = 1001;
+        let discrepancies =
+            verify_transaction_matches_intent(&tx, &intent, westend_material()).unwrap();
+        assert_eq!(
+            fields(&discrepancies),
+            ["nonce", "tip", "era", "calls[0].dest", "calls[0].value"]
+        );
+        assert_eq!(
+            discrepancies[4],
+            Discrepancy {
+                field: "calls[0].value".to_string(),
+                expected: "1000".to_string(),
+                actual: "2000".to_string(),
+            }
+        );
+
+        // A different call is reported once, not argument by argument
+        intent = payment(SENDER, 2_000);
+        intent.calls[0] = Intent::Transfer(TransferIntent {
+            to: SENDER.to_string(),
+            amount: None,
+            keep_alive: true,
+        });
+        assert_eq!(
+            verify_transaction_matches_intent(&tx, &intent, westend_material()).unwrap(),
+            [Discrepancy {
+                field: "calls[0]".to_string(),
+                expected: "balances.transferAll".to_string(),
+                actual: "balances.transferKeepAlive".to_string(),
+            }]
+        );
+    }
+}
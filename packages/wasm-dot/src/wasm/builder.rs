@@ -4,9 +4,10 @@
 //! Follows wallet-platform pattern: buildTransaction(intent, context)
 
 use crate::builder::{
-    build_transaction,
+    build_from_intent, build_transaction,
     types::{BuildContext, TransactionIntent},
 };
+use crate::types::{Material, UnsignedTransactionIntent};
 use crate::wasm::transaction::WasmTransaction;
 use wasm_bindgen::prelude::*;
 
@@ -26,11 +27,16 @@ impl BuilderNamespace {
     /// # Intent Types
     /// - `payment`: Transfer DOT (to, amount, keepAlive?)
     /// - `consolidate`: Sweep all DOT (to, keepAlive?)
+    /// - `assetTransfer`: Transfer an AssetHub asset listed in material.assets
+    ///   (assetId, to, amount, keepAlive?)
     /// - `stake`: Bond DOT — with proxyAddress = new stake (bond+addProxy),
     ///   without = top-up (bondExtra)
     /// - `unstake`: Unbond DOT — stopStaking + proxyAddress = full
     ///   (removeProxy+chill+unbond), otherwise partial (unbond only)
     /// - `claim`: Withdraw unbonded (slashingSpans?)
+    /// - `payoutStakers`: Pay out validator rewards for an era (validatorStash, era)
+    /// - `poolJoin`, `poolBondExtra`, `poolClaimPayout`, `poolUnbond`,
+    ///   `poolWithdrawUnbonded`: Nomination pool membership (memberAccount defaults to sender)
     /// - `fillNonce`: Zero-value self-transfer to advance nonce
     #[wasm_bindgen(js_name = buildTransaction)]
     pub fn build_transaction_wasm(
@@ -51,4 +57,24 @@ impl BuilderNamespace {
         // Wrap in WasmTransaction
         Ok(WasmTransaction::from_inner(tx))
     }
+
+    /// Build a transaction from an unsigned transaction intent.
+    ///
+    /// - intent: `{ sender, nonce, tip?, validity, referenceBlock, calls: [...] }` where each
+    ///   call is a `transfer`, `assetTransfer`, `staking`, `nominationPools` or `proxy` intent
+    /// - material: chain material metadata
+    #[wasm_bindgen(js_name = buildFromIntent)]
+    pub fn build_from_intent_wasm(
+        intent: JsValue,
+        material: JsValue,
+    ) -> Result<WasmTransaction, JsValue> {
+        let intent: UnsignedTransactionIntent = serde_wasm_bindgen::from_value(intent)
+            .map_err(|e| JsValue::from_str(&format!("Invalid intent: {}", e)))?;
+        let material: Material = serde_wasm_bindgen::from_value(material)
+            .map_err(|e| JsValue::from_str(&format!("Invalid material: {}", e)))?;
+
+        let tx = build_from_intent(intent, material)?;
+
+        Ok(WasmTransaction::from_inner(tx))
+    }
 }
@@ -6,6 +6,7 @@
 pub mod address;
 pub mod builder;
 pub mod parser;
+pub mod proxy;
 pub mod transaction;
 pub mod try_into_js_value;
 
@@ -13,4 +14,5 @@ pub mod try_into_js_value;
 pub use address::AddressNamespace;
 pub use builder::BuilderNamespace;
 pub use parser::ParserNamespace;
+pub use proxy::ProxyNamespace;
 pub use transaction::{MaterialJs, ParseContextJs, ValidityJs, WasmTransaction};
@@ -5,6 +5,8 @@
 use serde::Serialize;
 
 use crate::parser::{parse_from_transaction, parse_transaction, ParsedTransaction};
+use crate::types::{Material, UnsignedTransactionIntent};
+use crate::verify::verify_transaction_matches_intent;
 use crate::wasm::transaction::{ParseContextJs, WasmTransaction};
 use wasm_bindgen::prelude::*;
 
@@ -69,6 +71,29 @@ impl ParserNamespace {
         to_js_value(&parsed)
     }
 
+    /// Check that a transaction matches an unsigned transaction intent.
+    ///
+    /// @param bytes - Unsigned signing payload or signed extrinsic bytes
+    /// @param intent - `UnsignedTransactionIntent`
+    /// @param material - Chain material metadata
+    /// @returns Array of `{ field, expected, actual }` discrepancies, empty if the
+    ///   transaction matches
+    #[wasm_bindgen(js_name = verifyTransactionMatchesIntent)]
+    pub fn verify_transaction_matches_intent_wasm(
+        bytes: &[u8],
+        intent: JsValue,
+        material: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        let intent: UnsignedTransactionIntent = serde_wasm_bindgen::from_value(intent)
+            .map_err(|e| JsValue::from_str(&format!("Invalid intent: {}", e)))?;
+        let material: Material = serde_wasm_bindgen::from_value(material)
+            .map_err(|e| JsValue::from_str(&format!("Invalid material: {}", e)))?;
+        let discrepancies = verify_transaction_matches_intent(bytes, &intent, material)?;
+        discrepancies
+            .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
     /// Get the proxy deposit cost from runtime metadata.
     ///
     /// Returns `ProxyDepositBase + ProxyDepositFactor` from the Proxy pallet
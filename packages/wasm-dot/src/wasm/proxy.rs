@@ -0,0 +1,50 @@
+//! WASM bindings for pure proxy operations
+//!
+//! ProxyNamespace computes pure proxy addresses and decodes `Proxy.PureCreated` events.
+
+use crate::proxy::{decode_pure_created, pure_proxy_address, PureProxyParams};
+use crate::transaction::decode_metadata;
+use crate::types::{AddressFormat, Material};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// Namespace for pure proxy operations
+#[wasm_bindgen]
+pub struct ProxyNamespace;
+
+#[wasm_bindgen]
+impl ProxyNamespace {
+    /// Compute the address of a pure proxy created with `proxy.createPure`.
+    ///
+    /// @param params - { who, proxyType, index?, blockHeight, extIndex }
+    /// @param material - Chain material (metadata resolves the proxy type, the chain name
+    ///   selects the address format)
+    /// @returns SS58 address of the pure proxy
+    #[wasm_bindgen(js_name = pureProxyAddress)]
+    pub fn pure_proxy_address_wasm(params: JsValue, material: JsValue) -> Result<String, JsValue> {
+        let params: PureProxyParams = serde_wasm_bindgen::from_value(params)
+            .map_err(|e| JsValue::from_str(&format!("Invalid params: {}", e)))?;
+        let material: Material = serde_wasm_bindgen::from_value(material)
+            .map_err(|e| JsValue::from_str(&format!("Invalid material: {}", e)))?;
+        let metadata = decode_metadata(&material.metadata)?;
+        let prefix = AddressFormat::from_chain_name(&material.chain_name).prefix();
+        Ok(pure_proxy_address(&params, &metadata, prefix)?)
+    }
+
+    /// Decode the data of a `Proxy.PureCreated` event.
+    ///
+    /// @param data - SCALE-encoded event fields (without pallet and event indices)
+    /// @param material - Chain material
+    /// @returns { pure, who, proxyType, disambiguationIndex, at?, extrinsicIndex? }
+    #[wasm_bindgen(js_name = decodePureCreated)]
+    pub fn decode_pure_created_wasm(data: &[u8], material: JsValue) -> Result<JsValue, JsValue> {
+        let material: Material = serde_wasm_bindgen::from_value(material)
+            .map_err(|e| JsValue::from_str(&format!("Invalid material: {}", e)))?;
+        let metadata = decode_metadata(&material.metadata)?;
+        let prefix = AddressFormat::from_chain_name(&material.chain_name).prefix();
+        let event = decode_pure_created(data, &metadata, prefix)?;
+        event
+            .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+}
@@ -3,7 +3,7 @@
 //! Thin wrapper around core Transaction with #[wasm_bindgen]
 
 use crate::transaction::{decode_metadata, Transaction};
-use crate::types::{Material, ParseContext, Validity};
+use crate::types::{AssetInfo, Material, ParseContext, Validity};
 use crate::WasmDotError;
 use wasm_bindgen::prelude::*;
 
@@ -113,6 +113,15 @@ impl WasmTransaction {
         Ok(format!("0x{}", hex::encode(payload)))
     }
 
+    /// Get the unhashed signing payload (call_data | extensions | additional_signed)
+    ///
+    /// For external signers; sign `signablePayload`, which applies the >256-byte
+    /// Blake2-256 hashing rule to these bytes.
+    #[wasm_bindgen(js_name = rawSignablePayload)]
+    pub fn raw_signable_payload(&self) -> Result<Vec<u8>, JsValue> {
+        self.inner.raw_signable_payload().map_err(|e| e.into())
+    }
+
     /// Set the signing context (material, validity, reference block)
     ///
     /// Required before calling signablePayload if transaction was created without context
@@ -164,6 +173,20 @@ impl WasmTransaction {
             .map_err(|e| e.into())
     }
 
+    /// Add an external signature and return the signed extrinsic bytes
+    ///
+    /// Requires the signing context to be set.
+    ///
+    /// # Arguments
+    /// * `signature` - 64-byte Ed25519 signature over `signablePayload`
+    /// * `pubkey` - 32-byte public key
+    #[wasm_bindgen(js_name = assembleSigned)]
+    pub fn assemble_signed(&mut self, signature: &[u8], pubkey: &[u8]) -> Result<Vec<u8>, JsValue> {
+        self.inner
+            .assemble_signed(pubkey, signature)
+            .map_err(|e| e.into())
+    }
+
     /// Serialize to bytes
     #[wasm_bindgen(js_name = toBytes)]
     pub fn to_bytes(&self) -> Result<Vec<u8>, JsValue> {
@@ -229,6 +252,7 @@ pub struct MaterialJs {
 
 #[wasm_bindgen]
 impl MaterialJs {
+    /// `assets` is an optional array of `{ id, symbol, decimals }` (AssetHub only)
     #[wasm_bindgen(constructor)]
     pub fn new(
         genesis_hash: &str,
@@ -237,8 +261,11 @@ impl MaterialJs {
         spec_version: u32,
         tx_version: u32,
         metadata: &str,
-    ) -> MaterialJs {
-        MaterialJs {
+        assets: JsValue,
+    ) -> Result<MaterialJs, JsValue> {
+        let assets: Option<Vec<AssetInfo>> = serde_wasm_bindgen::from_value(assets)
+            .map_err(|e| JsValue::from_str(&format!("Invalid assets: {}", e)))?;
+        Ok(MaterialJs {
             inner: Material {
                 genesis_hash: genesis_hash.to_string(),
                 chain_name: chain_name.to_string(),
@@ -246,8 +273,9 @@ impl MaterialJs {
                 spec_version,
                 tx_version,
                 metadata: metadata.to_string(),
+                assets: assets.unwrap_or_default(),
             },
-        }
+        })
     }
 }
 
@@ -1,5 +1,14 @@
 import * as assert from "assert";
-import { buildTransaction, type TransactionIntent, type BuildContext } from "../js/index.js";
+import {
+  buildTransaction,
+  buildFromIntent,
+  parseTransaction,
+  verifyTransactionMatchesIntent,
+  DotTransaction,
+  type TransactionIntent,
+  type BuildContext,
+  type UnsignedTransactionIntent,
+} from "../js/index.js";
 import { getWestendMetadata } from "./resources/westend.js";
 
 /** Convert Uint8Array to hex string (no 0x prefix) */
@@ -97,6 +106,29 @@ describe("buildTransaction", () => {
     });
   });
 
+  describe("assetTransfer", () => {
+    const intent: TransactionIntent = {
+      type: "assetTransfer",
+      assetId: 1984,
+      to: RECIPIENT,
+      amount: 1000000n,
+    };
+
+    it("should reject assets that are not listed in the material", () => {
+      assert.throws(() => buildTransaction(intent, testContext(0)), /not listed/);
+    });
+
+    it("should require the Assets pallet in the metadata", () => {
+      const context = testContext(0);
+      context.material = {
+        ...WESTEND_MATERIAL,
+        assets: [{ id: 1984, symbol: "USDT", decimals: 6 }],
+      };
+      // The Westend relay chain has no Assets pallet, only AssetHub does
+      assert.throws(() => buildTransaction(intent, context), /Assets/);
+    });
+  });
+
   describe("staking", () => {
     it("should build a stake top-up (bondExtra)", () => {
       const intent: TransactionIntent = {
@@ -183,6 +215,82 @@ describe("buildTransaction", () => {
     });
   });
 
+  describe("createPureProxy", () => {
+    it("should build a createPure call with default proxy type", () => {
+      const intent: TransactionIntent = { type: "createPureProxy", index: 3 };
+
+      const tx = buildTransaction(intent, testContext(0));
+      const parsed = parseTransaction(tx, { material: WESTEND_MATERIAL });
+      assert.strictEqual(parsed.method.pallet, "proxy");
+      assert.strictEqual(parsed.method.name, "createPure");
+      assert.deepStrictEqual(parsed.method.args, { proxy_type: "Any", delay: 0, index: 3 });
+    });
+  });
+
+  describe("payoutStakers", () => {
+    it("should build a payoutStakers call", () => {
+      const intent: TransactionIntent = {
+        type: "payoutStakers",
+        validatorStash: RECIPIENT,
+        era: 1200,
+      };
+
+      const tx = buildTransaction(intent, testContext(0));
+      const parsed = parseTransaction(tx, { material: WESTEND_MATERIAL });
+      assert.strictEqual(parsed.method.pallet, "staking");
+      assert.strictEqual(parsed.method.name, "payoutStakers");
+      assert.deepStrictEqual(parsed.method.args, { validatorStash: RECIPIENT, era: 1200 });
+    });
+  });
+
+  describe("nomination pools", () => {
+    const parseArgs = (intent: TransactionIntent) => {
+      const tx = buildTransaction(intent, testContext(0));
+      const { method } = parseTransaction(tx, { material: WESTEND_MATERIAL });
+      assert.strictEqual(method.pallet, "nominationpools");
+      return { name: method.name, args: method.args };
+    };
+
+    it("should build a join call", () => {
+      assert.deepStrictEqual(parseArgs({ type: "poolJoin", amount: 10000000000n, poolId: 7 }), {
+        name: "join",
+        args: { amount: "10000000000", poolId: 7 },
+      });
+    });
+
+    it("should build bondExtra from the free balance or the pending rewards", () => {
+      assert.deepStrictEqual(parseArgs({ type: "poolBondExtra", amount: 5n }), {
+        name: "bondExtra",
+        args: { extra: "FreeBalance", value: "5" },
+      });
+      assert.deepStrictEqual(parseArgs({ type: "poolBondExtra" }), {
+        name: "bondExtra",
+        args: { extra: "Rewards" },
+      });
+    });
+
+    it("should build a claimPayout call", () => {
+      assert.deepStrictEqual(parseArgs({ type: "poolClaimPayout" }), {
+        name: "claimPayout",
+        args: {},
+      });
+    });
+
+    it("should unbond and withdraw for the sender by default", () => {
+      assert.deepStrictEqual(parseArgs({ type: "poolUnbond", amount: 3n }), {
+        name: "unbond",
+        args: { memberAccount: SENDER, unbondingPoints: "3" },
+      });
+      assert.deepStrictEqual(
+        parseArgs({ type: "poolWithdrawUnbonded", slashingSpans: 1, memberAccount: RECIPIENT }),
+        {
+          name: "withdrawUnbonded",
+          args: { memberAccount: RECIPIENT, numSlashingSpans: 1 },
+        },
+      );
+    });
+  });
+
   describe("batch composition", () => {
     it("new stake call data should differ from top-up (bond+addProxy vs bondExtra)", () => {
       const topUp: TransactionIntent = {
@@ -234,4 +342,104 @@ describe("buildTransaction", () => {
       );
     });
   });
+
+  describe("external signing", () => {
+    it("should export the signing payload and assemble the signed extrinsic", () => {
+      const tx = buildTransaction(
+        { type: "payment", to: RECIPIENT, amount: 1000000000000n },
+        testContext(0),
+      );
+      const raw = tx.rawSignablePayload();
+      assert.ok(raw.length <= 256);
+      assert.strictEqual(toHex(raw.subarray(0, tx.callData.length)), toHex(tx.callData));
+      assert.strictEqual(toHex(tx.signablePayload()), toHex(raw));
+
+      const signed = tx.assembleSigned(new Uint8Array(64).fill(2), new Uint8Array(32).fill(1));
+      const parsed = DotTransaction.fromBytes(signed, WESTEND_MATERIAL);
+      assert.strictEqual(parsed.isSigned, true);
+      assert.strictEqual(toHex(parsed.callData), toHex(tx.callData));
+    });
+  });
+
+  describe("buildFromIntent", () => {
+    const unsignedIntent = (
+      calls: UnsignedTransactionIntent["calls"],
+    ): UnsignedTransactionIntent => ({
+      sender: SENDER,
+      nonce: 0,
+      validity: { firstValid: 1000, maxDuration: 2400 },
+      referenceBlock: REFERENCE_BLOCK,
+      calls,
+    });
+
+    it("should match buildTransaction for a new stake", () => {
+      const fromIntent = buildTransaction(
+        { type: "stake", amount: 5000000000000n, proxyAddress: RECIPIENT },
+        testContext(0),
+      );
+      const fromUnsigned = buildFromIntent(
+        unsignedIntent([
+          { type: "staking", action: "bond", amount: 5000000000000n },
+          { type: "proxy", action: "add", delegate: RECIPIENT },
+        ]),
+        WESTEND_MATERIAL,
+      );
+      assert.strictEqual(toHex(fromUnsigned.callData), toHex(fromIntent.callData));
+    });
+
+    it("should accept amounts above 2^53", () => {
+      const amount = 2n ** 53n + 1n;
+      const fromUnsigned = buildFromIntent(
+        unsignedIntent([{ type: "transfer", to: RECIPIENT, amount }]),
+        WESTEND_MATERIAL,
+      );
+      const fromIntent = buildTransaction(
+        { type: "payment", to: RECIPIENT, amount },
+        testContext(0),
+      );
+      assert.strictEqual(toHex(fromUnsigned.callData), toHex(fromIntent.callData));
+    });
+
+    it("should report the invalid field", () => {
+      assert.throws(
+        () =>
+          buildFromIntent(
+            unsignedIntent([{ type: "transfer", to: "not-an-address", amount: 1n }]),
+            WESTEND_MATERIAL,
+          ),
+        /calls\[0\]\.to/,
+      );
+    });
+  });
+
+  describe("verifyTransactionMatchesIntent", () => {
+    const payment = (amount: bigint, nonce = 0): UnsignedTransactionIntent => ({
+      sender: SENDER,
+      nonce,
+      validity: { firstValid: 1000, maxDuration: 2400 },
+      referenceBlock: REFERENCE_BLOCK,
+      calls: [{ type: "transfer", to: RECIPIENT, amount }],
+    });
+
+    it("should report no discrepancies for the transaction built from the intent", () => {
+      const tx = buildFromIntent(payment(1000n), WESTEND_MATERIAL);
+      assert.deepStrictEqual(
+        verifyTransactionMatchesIntent(tx, payment(1000n), WESTEND_MATERIAL),
+        [],
+      );
+      assert.deepStrictEqual(
+        verifyTransactionMatchesIntent(tx.toBytes(), payment(1000n), WESTEND_MATERIAL),
+        [],
+      );
+    });
+
+    it("should report the fields that differ", () => {
+      const tx = buildFromIntent(payment(2000n), WESTEND_MATERIAL);
+      const discrepancies = verifyTransactionMatchesIntent(tx, payment(1000n, 1), WESTEND_MATERIAL);
+      assert.deepStrictEqual(discrepancies, [
+        { field: "nonce", expected: "1", actual: "0" },
+        { field: "calls[0].value", expected: "1000", actual: "2000" },
+      ]);
+    });
+  });
 });
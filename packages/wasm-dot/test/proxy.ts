@@ -0,0 +1,65 @@
+import * as assert from "assert";
+import {
+  pureProxyAddress,
+  decodePureCreated,
+  decodeSs58,
+  encodeSs58,
+  AddressFormat,
+} from "../js/index.js";
+import { getWestendMetadata } from "./resources/westend.js";
+
+describe("pure proxy", () => {
+  const WHO = "5EGoFA95omzemRssELLDjVenNZ68aXyUeqtKQScXSEBvVJkr";
+
+  const WESTEND_MATERIAL = {
+    genesisHash: "0xe143f23803ac50e8f6f8e62695d1ce9e4e1d68aa36c1cd2cfd15340213f3423e",
+    chainName: "Westend",
+    specName: "westend",
+    specVersion: 9420,
+    txVersion: 16,
+    metadata: getWestendMetadata(),
+  };
+
+  it("should compute the pure proxy address", () => {
+    const address = pureProxyAddress(
+      { who: WHO, proxyType: "Any", index: 0, blockHeight: 1000, extIndex: 2 },
+      WESTEND_MATERIAL,
+    );
+    const expected = encodeSs58(
+      new Uint8Array(
+        Buffer.from("421a3eae7d31b1e796fd417660047d42522e3c3b8826e6787fc08ec4b5661b46", "hex"),
+      ),
+      AddressFormat.Substrate,
+    );
+    assert.strictEqual(address, expected);
+  });
+
+  it("should reject unknown proxy types", () => {
+    assert.throws(
+      () =>
+        pureProxyAddress(
+          { who: WHO, proxyType: "NoSuchType", blockHeight: 1000, extIndex: 2 },
+          WESTEND_MATERIAL,
+        ),
+      /Unknown proxy type/,
+    );
+  });
+
+  it("should decode PureCreated event data", () => {
+    const params = { who: WHO, proxyType: "Any", index: 5, blockHeight: 1000, extIndex: 2 };
+    const pure = pureProxyAddress(params, WESTEND_MATERIAL);
+    const data = Buffer.concat([
+      decodeSs58(pure).publicKey,
+      decodeSs58(WHO).publicKey,
+      Buffer.from([0, 5, 0]),
+    ]);
+
+    const event = decodePureCreated(new Uint8Array(data), WESTEND_MATERIAL);
+    assert.deepStrictEqual(event, {
+      pure,
+      who: WHO,
+      proxyType: "Any",
+      disambiguationIndex: 5,
+    });
+  });
+});
@@ -9,10 +9,12 @@ export * as pubkey from "./pubkey.js";
 export * as transaction from "./transaction.js";
 export * as parser from "./parser.js";
 export * as builder from "./builder.js";
+export * as validation from "./validation.js";
 
 // Top-level class exports for convenience
 export { Keypair } from "./keypair.js";
 export { Pubkey } from "./pubkey.js";
+export type { ProgramAddress } from "./pubkey.js";
 export { Transaction } from "./transaction.js";
 
 // Versioned transaction support
@@ -22,7 +24,13 @@ export type { AddressLookupTableData } from "./versioned.js";
 // Top-level function exports
 export { parseTransaction } from "./parser.js";
 export { buildFromVersionedData } from "./builder.js";
-export { buildFromIntent, buildFromIntent as buildTransactionFromIntent } from "./intentBuilder.js";
+export { validateWithdrawal, validateMemo } from "./validation.js";
+export type { WithdrawalValidation } from "./validation.js";
+export {
+  buildFromIntent,
+  buildFromIntent as buildTransactionFromIntent,
+  planTokenTransfer,
+} from "./intentBuilder.js";
 // Intent builder type exports
 export type {
   BaseIntent,
@@ -48,6 +56,8 @@ export type {
   NonceSource,
   BlockhashNonce,
   DurableNonce,
+  TokenTransferRequest,
+  TokenTransferPlan,
 } from "./intentBuilder.js";
 
 // Program ID constants (from WASM)
@@ -60,6 +70,7 @@ export {
   token_2022_program_id as token2022ProgramId,
   ata_program_id as ataProgramId,
   stake_pool_program_id as stakePoolProgramId,
+  squads_multisig_program_id as squadsMultisigProgramId,
   stake_account_space as stakeAccountSpace,
   nonce_account_space as nonceAccountSpace,
   // Sysvar addresses
@@ -70,7 +81,7 @@ export {
 } from "./wasm/wasm_solana.js";
 
 // Type exports
-export type { AccountMeta, Instruction } from "./transaction.js";
+export type { AccountMeta, Instruction, LimitsReport, LimitViolation } from "./transaction.js";
 export type {
   ParsedTransaction,
   DurableNonce as ParsedDurableNonce,
@@ -94,6 +105,10 @@ export type {
   MemoParams,
   StakePoolDepositSolParams,
   StakePoolWithdrawStakeParams,
+  SquadsVaultTransactionCreateParams,
+  SquadsProposalCreateParams,
+  SquadsProposalVoteParams,
+  SquadsVaultTransactionExecuteParams,
   UnknownInstructionParams,
 } from "./parser.js";
 
@@ -230,6 +230,38 @@ export type SolanaIntent =
   | AuthorizeIntent
   | CustomTxIntent;
 
+/** Parameters for planning an SPL token transfer */
+export interface TokenTransferRequest {
+  /** Owner of the source token account (base58) */
+  owner: string;
+  /** Recipient wallet or token account (base58) */
+  recipient: string;
+  /** Token mint (base58) */
+  mint: string;
+  /** Amount in the smallest unit of the token */
+  amount: bigint;
+  /** Decimals of the token */
+  decimalPlaces: number;
+  /** Token program ID (default: SPL Token) */
+  tokenProgramId?: string;
+  /** Payer of the account creation (default: owner) */
+  feePayer?: string;
+  /** Token accounts known to exist; their creation is skipped */
+  existingTokenAccounts?: string[];
+}
+
+/** Instructions of a planned token transfer */
+export interface TokenTransferPlan {
+  /** Source token account (associated token account of the owner) */
+  source: string;
+  /** Destination token account */
+  destination: string;
+  /** Whether the plan creates the destination with `CreateIdempotent` */
+  createsDestination: boolean;
+  /** Instructions, usable as `solInstructions` of a `customTx` intent */
+  instructions: CustomTxInstruction[];
+}
+
 // =============================================================================
 // Main Function
 // =============================================================================
@@ -283,3 +315,17 @@ export function buildFromIntent(
     generatedKeypairs: result.generatedKeypairs,
   };
 }
+
+/**
+ * Plan an SPL token transfer.
+ *
+ * The recipient token account is created with `CreateIdempotent`, so the transfer does
+ * not fail if the account is created concurrently. The creation is left out for
+ * accounts listed in `existingTokenAccounts`.
+ *
+ * @param request - Owner, recipient, mint, amount and known token accounts
+ * @returns The source and destination accounts and the instructions
+ */
+export function planTokenTransfer(request: TokenTransferRequest): TokenTransferPlan {
+  return IntentNamespace.plan_token_transfer(request) as TokenTransferPlan;
+}
@@ -192,6 +192,60 @@ export interface StakePoolWithdrawStakeParams {
   poolTokens: bigint;
 }
 
+/** Squads multisig: proposes to run `instructions` with the vault as signer */
+export interface SquadsVaultTransactionCreateParams {
+  type: "SquadsVaultTransactionCreate";
+  multisigAddress: string;
+  transactionAddress: string;
+  creatorAddress: string;
+  rentPayerAddress: string;
+  /** Vault PDA derived from the multisig and the vault index */
+  vaultAddress: string;
+  vaultIndex: number;
+  ephemeralSigners: number;
+  memo?: string;
+  /** Static account keys of the vault transaction message */
+  accountKeys: string[];
+  /**
+   * Decoded instructions of the vault transaction message. Accounts loaded from address
+   * lookup tables are returned as `<table address>:<index>`.
+   */
+  instructions: InstructionParams[];
+}
+
+/** Squads multisig: creates the proposal for a vault transaction */
+export interface SquadsProposalCreateParams {
+  type: "SquadsProposalCreate";
+  multisigAddress: string;
+  proposalAddress: string;
+  creatorAddress: string;
+  rentPayerAddress: string;
+  transactionIndex: bigint;
+  draft: boolean;
+}
+
+/** Squads multisig: approves, rejects or cancels a proposal */
+export interface SquadsProposalVoteParams {
+  type: "SquadsProposalVote";
+  multisigAddress: string;
+  memberAddress: string;
+  proposalAddress: string;
+  vote: "approve" | "reject" | "cancel";
+  memo?: string;
+}
+
+/**
+ * Squads multisig: executes an approved vault transaction. The instructions that are executed
+ * are stored in the transaction account.
+ */
+export interface SquadsVaultTransactionExecuteParams {
+  type: "SquadsVaultTransactionExecute";
+  multisigAddress: string;
+  proposalAddress: string;
+  transactionAddress: string;
+  memberAddress: string;
+}
+
 /** Account metadata for unknown instructions */
 export interface AccountMeta {
   pubkey: string;
@@ -228,6 +282,10 @@ export type InstructionParams =
   | MemoParams
   | StakePoolDepositSolParams
   | StakePoolWithdrawStakeParams
+  | SquadsVaultTransactionCreateParams
+  | SquadsProposalCreateParams
+  | SquadsProposalVoteParams
+  | SquadsVaultTransactionExecuteParams
   | UnknownInstructionParams;
 
 // =============================================================================
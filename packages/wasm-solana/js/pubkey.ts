@@ -1,4 +1,12 @@
-import { WasmPubkey } from "./wasm/wasm_solana.js";
+import { WasmPubkey, token_program_id } from "./wasm/wasm_solana.js";
+
+/**
+ * A program-derived address and the bump seed that puts it off the Ed25519 curve
+ */
+export interface ProgramAddress {
+  address: Pubkey;
+  bump: number;
+}
 
 /**
  * Solana public key (address)
@@ -67,6 +75,61 @@ export class Pubkey {
     return this._wasm.is_on_curve();
   }
 
+  /**
+   * Find the program-derived address (PDA) of `seeds` under `programId`.
+   *
+   * Equivalent to `PublicKey.findProgramAddressSync()` from `@solana/web3.js`.
+   *
+   * @param seeds - At most 15 seeds, each up to 32 bytes
+   * @param programId - Program that owns the address
+   * @returns The PDA and its bump seed
+   */
+  static findProgramAddress(seeds: Uint8Array[], programId: Pubkey): ProgramAddress {
+    const { address, bump } = WasmPubkey.find_program_address(seeds, programId._wasm) as {
+      address: string;
+      bump: number;
+    };
+    return { address: Pubkey.fromBase58(address), bump };
+  }
+
+  /**
+   * Derive the address of an account created with `createAccountWithSeed`.
+   *
+   * Equivalent to `PublicKey.createWithSeed()` from `@solana/web3.js`.
+   *
+   * @param base - Base account that signs the account creation
+   * @param seed - Seed string (at most 32 bytes)
+   * @param owner - Program that will own the account
+   */
+  static createWithSeed(base: Pubkey, seed: string, owner: Pubkey): Pubkey {
+    return new Pubkey(WasmPubkey.create_with_seed(base._wasm, seed, owner._wasm));
+  }
+
+  /**
+   * Derive the Associated Token Account (ATA) address of `owner` for `mint`.
+   *
+   * Equivalent to `getAssociatedTokenAddressSync()` from `@solana/spl-token`, but also
+   * returns the bump seed.
+   *
+   * @param owner - Owner wallet address
+   * @param mint - Token mint address
+   * @param tokenProgram - Token program, defaults to the SPL Token program. Pass the
+   *   Token-2022 program ID for Token-2022 mints.
+   * @returns The ATA address and its bump seed
+   */
+  static deriveAssociatedTokenAddress(
+    owner: Pubkey,
+    mint: Pubkey,
+    tokenProgram: Pubkey = Pubkey.fromBase58(token_program_id()),
+  ): ProgramAddress {
+    const { address, bump } = WasmPubkey.derive_associated_token_address(
+      owner._wasm,
+      mint._wasm,
+      tokenProgram._wasm,
+    ) as { address: string; bump: number };
+    return { address: Pubkey.fromBase58(address), bump };
+  }
+
   /**
    * Get the underlying WASM instance (internal use only)
    * @internal
@@ -26,6 +26,42 @@ export interface Instruction {
   data: Uint8Array;
 }
 
+/**
+ * A size or account limit exceeded by a transaction
+ */
+export type LimitViolation =
+  | {
+      kind: "transactionTooLarge";
+      message: string;
+      /** Size of the signed transaction in bytes */
+      size: number;
+      limit: number;
+      /** Bytes by which the transaction exceeds the limit */
+      bytesOver: number;
+    }
+  | { kind: "tooManyAccountKeys"; message: string; count: number; limit: number }
+  | {
+      kind: "tooManyInstructionAccounts";
+      message: string;
+      instructionIndex: number;
+      count: number;
+      limit: number;
+    };
+
+/**
+ * Result of checking the limits of a transaction
+ */
+export interface LimitsReport {
+  /** Size of the transaction once every required signature is present, in bytes */
+  size: number;
+  /** Number of account keys */
+  accountKeys: number;
+  /** Bytes over the 1232-byte packet limit (0 if the transaction fits) */
+  bytesOver: number;
+  /** Exceeded limits; empty if the transaction is within all limits */
+  violations: LimitViolation[];
+}
+
 /**
  * Solana Transaction — deserialization wrapper for signing and serialization.
  *
@@ -72,6 +108,14 @@ export class Transaction {
     return new Transaction(wasm);
   }
 
+  /**
+   * Get the wire format version. Always "legacy": v0 bytes are rejected by
+   * `fromBytes()`, use `VersionedTransaction.fromBytes()` for those.
+   */
+  get version(): "legacy" {
+    return this._wasm.version as "legacy";
+  }
+
   /**
    * Get the fee payer address as a base58 string
    * Returns null if there are no account keys (shouldn't happen for valid transactions)
@@ -231,6 +275,58 @@ export class Transaction {
     return idx ?? null;
   }
 
+  /**
+   * Estimate the transaction fee in lamports.
+   *
+   * The fee is `lamportsPerSignature` for every required signature plus the priority fee,
+   * `priorityPrice * cuLimit` micro-lamports rounded up to whole lamports.
+   *
+   * @param lamportsPerSignature - Base fee per signature (5000n on mainnet)
+   * @param priorityPrice - Compute unit price in micro-lamports (default: 0n)
+   * @param cuLimit - Compute unit limit (default: 0)
+   * @returns The fee in lamports
+   */
+  estimateFee(lamportsPerSignature: bigint, priorityPrice = 0n, cuLimit = 0): bigint {
+    return this._wasm.estimate_fee(lamportsPerSignature, priorityPrice, cuLimit);
+  }
+
+  /**
+   * Check that every account created by this transaction is rent exempt.
+   *
+   * @throws Error if a CreateAccount instruction funds the new account below the
+   *   rent-exempt minimum for its data size
+   */
+  checkRentExemption(): void {
+    this._wasm.check_rent_exemption();
+  }
+
+  /**
+   * Verify this transaction before signing it.
+   *
+   * @param expectedFeePayer - The fee payer address the signer expects
+   * @param allowedPrograms - Program IDs the transaction may call (default: the programs
+   *   used by the transaction builders)
+   * @throws Error if the fee payer differs, an instruction calls a program that is not
+   *   allowed, another account is a writable signer without being created by the
+   *   transaction, or an instruction transfers account ownership
+   */
+  sanitizeAndCheck(expectedFeePayer: string, allowedPrograms?: string[]): void {
+    this._wasm.sanitize_and_check(allowedPrograms, expectedFeePayer);
+  }
+
+  /**
+   * Check the size and account limits of this transaction before signing it.
+   *
+   * Oversized transactions are otherwise only rejected at broadcast. The signed size must
+   * fit in a 1232-byte packet, the transaction may have at most 64 account keys and each
+   * instruction at most 255 accounts.
+   *
+   * @returns The size, account key count and the exceeded limits
+   */
+  validateLimits(): LimitsReport {
+    return this._wasm.validate_limits() as LimitsReport;
+  }
+
   /**
    * Sign this transaction with a Keypair.
    *
@@ -0,0 +1,52 @@
+import {
+  validate_memo as wasmValidateMemo,
+  validate_withdrawal as wasmValidateWithdrawal,
+} from "./wasm/wasm_solana.js";
+
+/**
+ * Result of an offline withdrawal destination check
+ */
+export interface WithdrawalValidation {
+  /** Whether the address is an Ed25519 public key; otherwise it is a program-derived address */
+  onCurve: boolean;
+  /** Rent-exempt minimum of the destination account in lamports */
+  rentExemptMinimum: bigint;
+  /**
+   * Whether the amount covers the rent-exempt minimum. If not, the withdrawal only succeeds
+   * if the destination account already exists.
+   */
+  meetsRentExemptMinimum: boolean;
+}
+
+/**
+ * Validate a withdrawal destination without an RPC node.
+ *
+ * @param address - Destination address (base58)
+ * @param amountLamports - Lamports credited to the destination: the amount of a SOL withdrawal,
+ *   or the funding of the token account for a token withdrawal (0 if it exists already)
+ * @param ownerProgramExpected - Program that owns the destination: System Program, SPL Token or
+ *   Token-2022 ID, or the shorthands "system" and "token"
+ * @throws Error if the address is invalid, is a program or sysvar, or if a SOL withdrawal goes
+ *   to a program-derived address or is zero
+ */
+export function validateWithdrawal(
+  address: string,
+  amountLamports: bigint,
+  ownerProgramExpected: string,
+): WithdrawalValidation {
+  return wasmValidateWithdrawal(
+    address,
+    amountLamports,
+    ownerProgramExpected,
+  ) as WithdrawalValidation;
+}
+
+/**
+ * Validate a withdrawal memo.
+ *
+ * @throws Error if the memo is empty, contains control characters or does not fit in a
+ *   transaction
+ */
+export function validateMemo(memo: string): void {
+  wasmValidateMemo(memo);
+}
@@ -12,6 +12,7 @@ import {
   BuilderNamespace,
 } from "./wasm/wasm_solana.js";
 import type { RawVersionedTransactionData } from "./builder.js";
+import type { LimitsReport } from "./transaction.js";
 
 /**
  * Address Lookup Table data extracted from versioned transactions.
@@ -132,6 +133,13 @@ export class VersionedTransaction {
     return this.inner.is_versioned;
   }
 
+  /**
+   * Get the wire format version ("legacy" or 0), as in @solana/web3.js.
+   */
+  get version(): "legacy" | 0 {
+    return this.inner.version as "legacy" | 0;
+  }
+
   /**
    * Get the fee payer address.
    */
@@ -248,6 +256,18 @@ export class VersionedTransaction {
     return this.inner.signer_index(pubkey) ?? undefined;
   }
 
+  /**
+   * Check the size and account limits of this transaction before signing it.
+   *
+   * Same limits as `Transaction.validateLimits`; accounts loaded from address lookup tables
+   * count towards the 64 account keys.
+   *
+   * @returns The size, account key count and the exceeded limits
+   */
+  validateLimits(): LimitsReport {
+    return this.inner.validate_limits() as LimitsReport;
+  }
+
   /**
    * Get all instructions.
    * Note: For versioned transactions, account indices may reference
@@ -0,0 +1,194 @@
+//! Transaction fee estimation and rent exemption checks.
+//!
+//! The fee of a transaction is the base fee (`lamports_per_signature` for every required
+//! signature) plus the priority fee (compute unit price in micro-lamports times the compute
+//! unit limit). Accounts created by a transaction must hold at least the rent-exempt
+//! minimum for their data size, otherwise they are purged by the runtime.
+
+use crate::error::WasmSolanaError;
+use crate::instructions::SYSTEM_PROGRAM_ID;
+use solana_message::compiled_instruction::CompiledInstruction;
+use solana_message::Message;
+use solana_system_interface::instruction::SystemInstruction;
+
+/// Bytes of account metadata charged in addition to the account data
+/// (`solana_rent::ACCOUNT_STORAGE_OVERHEAD`)
+pub const ACCOUNT_STORAGE_OVERHEAD: u64 = 128;
+
+/// Default rent rate (`solana_rent::DEFAULT_LAMPORTS_PER_BYTE_YEAR`)
+pub const DEFAULT_LAMPORTS_PER_BYTE_YEAR: u64 = 3480;
+
+/// Years of rent an account must hold to be rent exempt
+/// (`solana_rent::DEFAULT_EXEMPTION_THRESHOLD`)
+pub const DEFAULT_EXEMPTION_THRESHOLD_YEARS: u64 = 2;
+
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+/// Minimum balance for an account with `data_len` bytes of data to be rent exempt.
+pub fn rent_exempt_minimum(data_len: u64) -> u64 {
+    (ACCOUNT_STORAGE_OVERHEAD + data_len)
+        * DEFAULT_LAMPORTS_PER_BYTE_YEAR
+        * DEFAULT_EXEMPTION_THRESHOLD_YEARS
+}
+
+/// Priority fee in lamports, rounded up like the runtime does.
+///
+/// # Arguments
+/// * `priority_price` - Compute unit price in micro-lamports
+/// * `cu_limit` - Compute unit limit
+pub fn priority_fee(priority_price: u64, cu_limit: u32) -> u64 {
+    let micro_lamports = priority_price as u128 * cu_limit as u128;
+    micro_lamports.div_ceil(MICRO_LAMPORTS_PER_LAMPORT) as u64
+}
+
+/// Total fee of a message in lamports.
+pub fn estimate_fee(
+    message: &Message,
+    lamports_per_signature: u64,
+    priority_price: u64,
+    cu_limit: u32,
+) -> u64 {
+    let base_fee = message.header.num_required_signatures as u64 * lamports_per_signature;
+    base_fee + priority_fee(priority_price, cu_limit)
+}
+
+/// An account created by a transaction, with its funding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreatedAccount {
+    /// Address of the new account (base58)
+    pub address: String,
+    /// Lamports transferred to the new account
+    pub lamports: u64,
+    /// Size of the account data in bytes
+    pub space: u64,
+}
+
+impl CreatedAccount {
+    pub fn rent_exempt_minimum(&self) -> u64 {
+        rent_exempt_minimum(self.space)
+    }
+
+    pub fn is_rent_exempt(&self) -> bool {
+        self.lamports >= self.rent_exempt_minimum()
+    }
+}
+
+/// Accounts created by System Program `CreateAccount` and `CreateAccountWithSeed`
+/// instructions of a message.
+pub fn created_accounts(message: &Message) -> Vec<CreatedAccount> {
+    message
+        .instructions
+        .iter()
+        .filter_map(|ix| created_account(message, ix))
+        .collect()
+}
+
+fn created_account(message: &Message, ix: &CompiledInstruction) -> Option<CreatedAccount> {
+    let program_id = message.account_keys.get(ix.program_id_index as usize)?;
+    if program_id.to_string() != SYSTEM_PROGRAM_ID {
+        return None;
+    }
+    let (lamports, space) = match bincode::deserialize::<SystemInstruction>(&ix.data).ok()? {
+        SystemInstruction::CreateAccount {
+            lamports, space, ..
+        }
+        | SystemInstruction::CreateAccountWithSeed {
+            lamports, space, ..
+        } => (lamports, space),
+        _ => return None,
+    };
+    // Accounts: [0] funding account, [1] new account
+    let new_account = message.account_keys.get(*ix.accounts.get(1)? as usize)?;
+    Some(CreatedAccount {
+        address: new_account.to_string(),
+        lamports,
+        space,
+    })
+}
+
+/// Fail if any account created by the message is funded below its rent-exempt minimum.
+pub fn check_rent_exemption(message: &Message) -> Result<(), WasmSolanaError> {
+    let underfunded: Vec<String> = created_accounts(message)
+        .iter()
+        .filter(|account| !account.is_rent_exempt())
+        .map(|account| {
+            format!(
+                "{} ({} lamports, {} required for {} bytes)",
+                account.address,
+                account.lamports,
+                account.rent_exempt_minimum(),
+                account.space
+            )
+        })
+        .collect();
+    if underfunded.is_empty() {
+        Ok(())
+    } else {
+        Err(WasmSolanaError::new(&format!(
+            "Accounts funded below the rent-exempt minimum: {}",
+            underfunded.join(", ")
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_system_interface::instruction as system_ix;
+
+    /// Size of a stake account (`StakeStateV2::size_of()`)
+    const STAKE_ACCOUNT_SPACE: u64 = 200;
+
+    fn create_stake_account_message(lamports: u64) -> Message {
+        let fee_payer: Pubkey = "DgT9qyYwYKBRDyDw3EfR12LHQCQjtNrKu2qMsXHuosmB"
+            .parse()
+            .unwrap();
+        let stake: Pubkey = "FKjSjCqByQRwSzZoMXA7bKnDbJe41YgJTHFFzBeC42bH"
+            .parse()
+            .unwrap();
+        let ix = system_ix::create_account(
+            &fee_payer,
+            &stake,
+            lamports,
+            STAKE_ACCOUNT_SPACE,
+            &solana_stake_interface::program::ID,
+        );
+        Message::new_with_blockhash(&[ix], Some(&fee_payer), &Hash::default())
+    }
+
+    #[test]
+    fn test_rent_exempt_minimum() {
+        // Values of `getMinimumBalanceForRentExemption` on mainnet
+        assert_eq!(rent_exempt_minimum(0), 890_880);
+        assert_eq!(rent_exempt_minimum(STAKE_ACCOUNT_SPACE), 2_282_880);
+    }
+
+    #[test]
+    fn test_estimate_fee() {
+        let message = create_stake_account_message(2_282_880);
+        // Fee payer and new account sign
+        assert_eq!(message.header.num_required_signatures, 2);
+        assert_eq!(estimate_fee(&message, 5000, 0, 200_000), 10_000);
+        // 200_000 CU at 1 micro-lamport each is 0.2 lamports, rounded up
+        assert_eq!(estimate_fee(&message, 5000, 1, 200_000), 10_001);
+        assert_eq!(estimate_fee(&message, 5000, 50_000, 200_000), 20_000);
+    }
+
+    #[test]
+    fn test_check_rent_exemption() {
+        let message = create_stake_account_message(2_282_880);
+        let accounts = created_accounts(&message);
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(
+            accounts[0].address,
+            "FKjSjCqByQRwSzZoMXA7bKnDbJe41YgJTHFFzBeC42bH"
+        );
+        assert!(check_rent_exemption(&message).is_ok());
+
+        let message = create_stake_account_message(2_282_879);
+        let err = check_rent_exemption(&message).unwrap_err();
+        assert!(err.to_string().contains("2282880 required"), "{}", err);
+    }
+}
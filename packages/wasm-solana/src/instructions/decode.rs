@@ -1,5 +1,6 @@
 //! Instruction decoding using official Solana interface crates.
 
+use super::squads::decode_squads_instruction;
 use super::types::*;
 use crate::intent::AuthorizeType;
 use solana_compute_budget_interface::ComputeBudgetInstruction;
@@ -24,6 +25,9 @@ pub fn decode_instruction(ctx: InstructionContext) -> ParsedInstruction {
         TOKEN_PROGRAM_ID | TOKEN_2022_PROGRAM_ID => decode_token_instruction(ctx),
         ATA_PROGRAM_ID => decode_ata_instruction(ctx),
         STAKE_POOL_PROGRAM_ID => decode_stake_pool_instruction(ctx),
+        SQUADS_MULTISIG_PROGRAM_ID => {
+            decode_squads_instruction(&ctx).unwrap_or_else(|| make_unknown(ctx))
+        }
         _ => make_unknown(ctx),
     }
 }
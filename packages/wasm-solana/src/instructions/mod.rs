@@ -3,6 +3,7 @@
 //! This module is NOT publicly exposed. It's used internally by `parseTransaction`.
 
 mod decode;
+mod squads;
 mod try_into_js_value;
 mod types;
 
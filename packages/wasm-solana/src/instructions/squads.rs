@@ -0,0 +1,378 @@
+//! Squads multisig (v4) instruction decoding.
+//!
+//! A Squads multisig spends from a vault PDA in three steps: a member creates a vault
+//! transaction holding the instructions to run and a proposal for it, the members vote on
+//! the proposal, and once it is approved a member executes the vault transaction.
+//!
+//! The instructions to run are only visible in the transaction that creates the vault
+//! transaction, as a serialized `TransactionMessage`. They are decoded into
+//! [`ParsedInstruction`]s like the instructions of the outer transaction, so that they can
+//! be verified before the proposal is approved. The execute instruction only references the
+//! vault transaction account.
+
+use super::decode::{decode_instruction, InstructionContext};
+use super::types::*;
+use crate::pubkey::{Pubkey, PubkeyExt};
+use borsh::BorshDeserialize;
+
+/// Anchor discriminators: first 8 bytes of `sha256("global:<instruction name>")`
+const VAULT_TRANSACTION_CREATE: [u8; 8] = [48, 250, 78, 168, 208, 226, 218, 211];
+const PROPOSAL_CREATE: [u8; 8] = [220, 60, 73, 224, 30, 108, 79, 159];
+const PROPOSAL_APPROVE: [u8; 8] = [144, 37, 164, 136, 188, 216, 42, 248];
+const PROPOSAL_REJECT: [u8; 8] = [243, 62, 134, 156, 230, 106, 246, 135];
+const PROPOSAL_CANCEL: [u8; 8] = [27, 42, 127, 237, 38, 163, 84, 203];
+const VAULT_TRANSACTION_EXECUTE: [u8; 8] = [194, 8, 161, 87, 153, 164, 25, 171];
+
+/// Decode a Squads multisig instruction. Returns `None` for instructions that are not
+/// decoded (e.g., multisig creation and config transactions) or are malformed.
+pub(super) fn decode_squads_instruction(ctx: &InstructionContext) -> Option<ParsedInstruction> {
+    if ctx.data.len() < 8 {
+        return None;
+    }
+    let (discriminator, args) = ctx.data.split_at(8);
+    let accounts = ctx.accounts;
+
+    match <[u8; 8]>::try_from(discriminator).ok()? {
+        VAULT_TRANSACTION_CREATE => {
+            // Accounts: [0] multisig, [1] transaction, [2] creator, [3] rent payer, [4] system
+            // Args: vault_index, ephemeral_signers, transaction_message, memo
+            let (vault_index, ephemeral_signers, message, memo) =
+                <(u8, u8, Vec<u8>, Option<String>)>::try_from_slice(args).ok()?;
+            if accounts.len() < 4 {
+                return None;
+            }
+            let message = decode_transaction_message(&message)?;
+            Some(ParsedInstruction::SquadsVaultTransactionCreate(
+                SquadsVaultTransactionCreateParams {
+                    multisig_address: accounts[0].clone(),
+                    transaction_address: accounts[1].clone(),
+                    creator_address: accounts[2].clone(),
+                    rent_payer_address: accounts[3].clone(),
+                    vault_address: vault_address(ctx.program_id, &accounts[0], vault_index)?,
+                    vault_index,
+                    ephemeral_signers,
+                    memo,
+                    account_keys: message.account_keys,
+                    instructions: message.instructions,
+                },
+            ))
+        }
+        PROPOSAL_CREATE => {
+            // Accounts: [0] multisig, [1] proposal, [2] creator, [3] rent payer, [4] system
+            // Args: transaction_index, draft
+            let (transaction_index, draft) = <(u64, bool)>::try_from_slice(args).ok()?;
+            if accounts.len() < 4 {
+                return None;
+            }
+            Some(ParsedInstruction::SquadsProposalCreate(
+                SquadsProposalCreateParams {
+                    multisig_address: accounts[0].clone(),
+                    proposal_address: accounts[1].clone(),
+                    creator_address: accounts[2].clone(),
+                    rent_payer_address: accounts[3].clone(),
+                    transaction_index,
+                    draft,
+                },
+            ))
+        }
+        d @ (PROPOSAL_APPROVE | PROPOSAL_REJECT | PROPOSAL_CANCEL) => {
+            // Accounts: [0] multisig, [1] member, [2] proposal
+            // Args: memo
+            let memo = <Option<String>>::try_from_slice(args).ok()?;
+            if accounts.len() < 3 {
+                return None;
+            }
+            let vote = match d {
+                PROPOSAL_APPROVE => SquadsVote::Approve,
+                PROPOSAL_REJECT => SquadsVote::Reject,
+                _ => SquadsVote::Cancel,
+            };
+            Some(ParsedInstruction::SquadsProposalVote(
+                SquadsProposalVoteParams {
+                    multisig_address: accounts[0].clone(),
+                    member_address: accounts[1].clone(),
+                    proposal_address: accounts[2].clone(),
+                    vote,
+                    memo,
+                },
+            ))
+        }
+        VAULT_TRANSACTION_EXECUTE => {
+            // Accounts: [0] multisig, [1] proposal, [2] transaction, [3] member,
+            // followed by the accounts of the vault transaction
+            if !args.is_empty() || accounts.len() < 4 {
+                return None;
+            }
+            Some(ParsedInstruction::SquadsVaultTransactionExecute(
+                SquadsVaultTransactionExecuteParams {
+                    multisig_address: accounts[0].clone(),
+                    proposal_address: accounts[1].clone(),
+                    transaction_address: accounts[2].clone(),
+                    member_address: accounts[3].clone(),
+                },
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Vault PDA: seeds `["multisig", multisig, "vault", vault_index]`
+fn vault_address(program_id: &str, multisig: &str, vault_index: u8) -> Option<String> {
+    let program_id = Pubkey::from_base58(program_id).ok()?;
+    let multisig = Pubkey::from_base58(multisig).ok()?;
+    let seeds: &[&[u8]] = &[b"multisig", multisig.as_ref(), b"vault", &[vault_index]];
+    let (vault, _) = Pubkey::find_program_address(seeds, &program_id);
+    Some(vault.to_string())
+}
+
+struct TransactionMessage {
+    account_keys: Vec<String>,
+    instructions: Vec<ParsedInstruction>,
+}
+
+/// Decode a Squads `TransactionMessage`.
+///
+/// The message is a compact version of a v0 message: the header has no read-only counts,
+/// and vectors are prefixed with a `u8` length (`u16` for instruction data).
+///
+/// Accounts loaded from address lookup tables cannot be resolved offline. They are
+/// returned as `<table address>:<index>`.
+fn decode_transaction_message(bytes: &[u8]) -> Option<TransactionMessage> {
+    let mut reader = Reader(bytes);
+    let _num_signers = reader.u8()?;
+    let _num_writable_signers = reader.u8()?;
+    let _num_writable_non_signers = reader.u8()?;
+
+    let num_keys = reader.u8()?;
+    let mut account_keys = Vec::with_capacity(num_keys as usize);
+    for _ in 0..num_keys {
+        account_keys.push(reader.pubkey()?);
+    }
+
+    let num_instructions = reader.u8()?;
+    let mut compiled = Vec::with_capacity(num_instructions as usize);
+    for _ in 0..num_instructions {
+        let program_id_index = reader.u8()?;
+        let len = reader.u8()? as usize;
+        let account_indexes = reader.bytes(len)?;
+        let len = reader.u16()? as usize;
+        let data = reader.bytes(len)?;
+        compiled.push((program_id_index, account_indexes, data));
+    }
+
+    // Loaded addresses follow the static keys: writable ones of all tables, then read-only ones
+    let num_lookups = reader.u8()?;
+    let mut writable = Vec::new();
+    let mut readonly = Vec::new();
+    for _ in 0..num_lookups {
+        let table = reader.pubkey()?;
+        let len = reader.u8()? as usize;
+        for index in reader.bytes(len)? {
+            writable.push(format!("{}:{}", table, index));
+        }
+        let len = reader.u8()? as usize;
+        for index in reader.bytes(len)? {
+            readonly.push(format!("{}:{}", table, index));
+        }
+    }
+    if !reader.0.is_empty() {
+        return None;
+    }
+
+    let all_keys: Vec<String> = account_keys
+        .iter()
+        .cloned()
+        .chain(writable)
+        .chain(readonly)
+        .collect();
+    let instructions = compiled
+        .into_iter()
+        .map(|(program_id_index, account_indexes, data)| {
+            let program_id = all_keys.get(program_id_index as usize)?;
+            let accounts = account_indexes
+                .iter()
+                .map(|&i| all_keys.get(i as usize).cloned())
+                .collect::<Option<Vec<_>>>()?;
+            Some(decode_instruction(InstructionContext {
+                program_id,
+                accounts: &accounts,
+                data,
+            }))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(TransactionMessage {
+        account_keys,
+        instructions,
+    })
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.bytes(2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn pubkey(&mut self) -> Option<String> {
+        let bytes = self.bytes(32)?;
+        Pubkey::from_bytes_checked(bytes)
+            .ok()
+            .map(|k| k.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const MULTISIG: &str = "DgT9qyYwYKBRDyDw3EfR12LHQCQjtNrKu2qMsXHuosmB";
+    const MEMBER: &str = "FKjSjCqByQRwSzZoMXA7bKnDbJe41YgJTHFFzBeC42bH";
+    const RECIPIENT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const TABLE: &str = "7UX2i7SucgLMQcfZ75s3VXmZZY4YRUyJN9X1RgfMoDUi";
+
+    fn accounts(addresses: &[&str]) -> Vec<String> {
+        addresses.iter().map(|a| a.to_string()).collect()
+    }
+
+    fn key(address: &str) -> [u8; 32] {
+        Pubkey::from_str(address).unwrap().to_bytes()
+    }
+
+    /// Vault transaction message: transfer of 1000 lamports from the vault to RECIPIENT,
+    /// and a memo instruction whose program is loaded from TABLE
+    fn transaction_message(vault: &str) -> Vec<u8> {
+        let mut message = vec![1, 1, 1, 3];
+        message.extend(key(vault));
+        message.extend(key(RECIPIENT));
+        message.extend(key(SYSTEM_PROGRAM_ID));
+        message.push(2);
+        let mut transfer = vec![2, 0, 0, 0];
+        transfer.extend(1000u64.to_le_bytes());
+        message.extend([2, 2, 0, 1, transfer.len() as u8, 0]);
+        message.extend(&transfer);
+        message.extend([3, 0, 2, 0, b'h', b'i']);
+        message.push(1);
+        message.extend(key(TABLE));
+        message.extend([0, 1, 5]);
+        message
+    }
+
+    fn decode(data: Vec<u8>, addresses: &[&str]) -> Option<ParsedInstruction> {
+        decode_squads_instruction(&InstructionContext {
+            program_id: SQUADS_MULTISIG_PROGRAM_ID,
+            accounts: &accounts(addresses),
+            data: &data,
+        })
+    }
+
+    #[test]
+    fn test_vault_transaction_create() {
+        let vault = vault_address(SQUADS_MULTISIG_PROGRAM_ID, MULTISIG, 0).unwrap();
+        let message = transaction_message(&vault);
+        let mut data = VAULT_TRANSACTION_CREATE.to_vec();
+        data.extend([0, 0]);
+        data.extend((message.len() as u32).to_le_bytes());
+        data.extend(&message);
+        data.push(0);
+
+        let addresses = [MULTISIG, RECIPIENT, MEMBER, MEMBER, SYSTEM_PROGRAM_ID];
+        let Some(ParsedInstruction::SquadsVaultTransactionCreate(params)) =
+            decode(data.clone(), &addresses)
+        else {
+            panic!("expected SquadsVaultTransactionCreate");
+        };
+        assert_eq!(params.vault_address, vault);
+        assert_eq!(
+            params.account_keys,
+            [vault.as_str(), RECIPIENT, SYSTEM_PROGRAM_ID]
+        );
+        assert_eq!(params.instructions.len(), 2);
+        match &params.instructions[0] {
+            ParsedInstruction::Transfer(transfer) => {
+                assert_eq!(transfer.from_address, vault);
+                assert_eq!(transfer.to_address, RECIPIENT);
+                assert_eq!(transfer.amount, 1000);
+            }
+            other => panic!("expected Transfer, got {:?}", other),
+        }
+        match &params.instructions[1] {
+            ParsedInstruction::Unknown(unknown) => {
+                assert_eq!(unknown.program_id, format!("{}:5", TABLE));
+                assert_eq!(unknown.data, b"hi");
+            }
+            other => panic!("expected Unknown, got {:?}", other),
+        }
+
+        // Trailing bytes in the message
+        let mut message = message;
+        message.push(0);
+        let mut data = VAULT_TRANSACTION_CREATE.to_vec();
+        data.extend([0, 0]);
+        data.extend((message.len() as u32).to_le_bytes());
+        data.extend(&message);
+        data.push(0);
+        assert!(decode(data, &addresses).is_none());
+    }
+
+    #[test]
+    fn test_proposal_instructions() {
+        let mut data = PROPOSAL_CREATE.to_vec();
+        data.extend(7u64.to_le_bytes());
+        data.push(0);
+        match decode(
+            data,
+            &[MULTISIG, RECIPIENT, MEMBER, MEMBER, SYSTEM_PROGRAM_ID],
+        ) {
+            Some(ParsedInstruction::SquadsProposalCreate(params)) => {
+                assert_eq!(params.proposal_address, RECIPIENT);
+                assert_eq!(params.transaction_index, 7);
+                assert!(!params.draft);
+            }
+            other => panic!("expected SquadsProposalCreate, got {:?}", other),
+        }
+
+        for (discriminator, vote) in [
+            (PROPOSAL_APPROVE, SquadsVote::Approve),
+            (PROPOSAL_REJECT, SquadsVote::Reject),
+            (PROPOSAL_CANCEL, SquadsVote::Cancel),
+        ] {
+            let mut data = discriminator.to_vec();
+            data.push(0);
+            match decode(data, &[MULTISIG, MEMBER, RECIPIENT]) {
+                Some(ParsedInstruction::SquadsProposalVote(params)) => {
+                    assert_eq!(params.member_address, MEMBER);
+                    assert_eq!(params.vote, vote);
+                }
+                other => panic!("expected SquadsProposalVote, got {:?}", other),
+            }
+        }
+
+        let data = VAULT_TRANSACTION_EXECUTE.to_vec();
+        match decode(data, &[MULTISIG, RECIPIENT, TABLE, MEMBER, RECIPIENT]) {
+            Some(ParsedInstruction::SquadsVaultTransactionExecute(params)) => {
+                assert_eq!(params.transaction_address, TABLE);
+                assert_eq!(params.member_address, MEMBER);
+            }
+            other => panic!("expected SquadsVaultTransactionExecute, got {:?}", other),
+        }
+
+        // Missing accounts, unknown discriminator
+        assert!(decode(VAULT_TRANSACTION_EXECUTE.to_vec(), &[MULTISIG]).is_none());
+        assert!(decode(vec![0; 8], &[MULTISIG]).is_none());
+    }
+}
@@ -288,6 +288,79 @@ impl TryIntoJsValue for StakePoolWithdrawStakeParams {
     }
 }
 
+// =============================================================================
+// Squads Multisig Params
+// =============================================================================
+
+impl TryIntoJsValue for SquadsVaultTransactionCreateParams {
+    fn try_to_js_value(&self) -> Result<JsValue, JsConversionError> {
+        js_obj!(
+            "type" => "SquadsVaultTransactionCreate",
+            "multisigAddress" => self.multisig_address,
+            "transactionAddress" => self.transaction_address,
+            "creatorAddress" => self.creator_address,
+            "rentPayerAddress" => self.rent_payer_address,
+            "vaultAddress" => self.vault_address,
+            "vaultIndex" => self.vault_index,
+            "ephemeralSigners" => self.ephemeral_signers,
+            "memo" => self.memo,
+            "accountKeys" => self.account_keys,
+            "instructions" => self.instructions
+        )
+    }
+}
+
+impl TryIntoJsValue for SquadsProposalCreateParams {
+    fn try_to_js_value(&self) -> Result<JsValue, JsConversionError> {
+        js_obj!(
+            "type" => "SquadsProposalCreate",
+            "multisigAddress" => self.multisig_address,
+            "proposalAddress" => self.proposal_address,
+            "creatorAddress" => self.creator_address,
+            "rentPayerAddress" => self.rent_payer_address,
+            "transactionIndex" => self.transaction_index,
+            "draft" => self.draft
+        )
+    }
+}
+
+// camelCase: matches JS/TS field naming convention
+impl TryIntoJsValue for SquadsVote {
+    fn try_to_js_value(&self) -> Result<JsValue, JsConversionError> {
+        let s = match self {
+            SquadsVote::Approve => "approve",
+            SquadsVote::Reject => "reject",
+            SquadsVote::Cancel => "cancel",
+        };
+        Ok(JsValue::from_str(s))
+    }
+}
+
+impl TryIntoJsValue for SquadsProposalVoteParams {
+    fn try_to_js_value(&self) -> Result<JsValue, JsConversionError> {
+        js_obj!(
+            "type" => "SquadsProposalVote",
+            "multisigAddress" => self.multisig_address,
+            "memberAddress" => self.member_address,
+            "proposalAddress" => self.proposal_address,
+            "vote" => self.vote,
+            "memo" => self.memo
+        )
+    }
+}
+
+impl TryIntoJsValue for SquadsVaultTransactionExecuteParams {
+    fn try_to_js_value(&self) -> Result<JsValue, JsConversionError> {
+        js_obj!(
+            "type" => "SquadsVaultTransactionExecute",
+            "multisigAddress" => self.multisig_address,
+            "proposalAddress" => self.proposal_address,
+            "transactionAddress" => self.transaction_address,
+            "memberAddress" => self.member_address
+        )
+    }
+}
+
 // =============================================================================
 // Memo & Unknown
 // =============================================================================
@@ -348,6 +421,10 @@ impl TryIntoJsValue for ParsedInstruction {
             ParsedInstruction::Memo(p) => p.try_to_js_value(),
             ParsedInstruction::StakePoolDepositSol(p) => p.try_to_js_value(),
             ParsedInstruction::StakePoolWithdrawStake(p) => p.try_to_js_value(),
+            ParsedInstruction::SquadsVaultTransactionCreate(p) => p.try_to_js_value(),
+            ParsedInstruction::SquadsProposalCreate(p) => p.try_to_js_value(),
+            ParsedInstruction::SquadsProposalVote(p) => p.try_to_js_value(),
+            ParsedInstruction::SquadsVaultTransactionExecute(p) => p.try_to_js_value(),
             ParsedInstruction::Unknown(p) => p.try_to_js_value(),
         }
     }
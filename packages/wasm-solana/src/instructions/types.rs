@@ -13,6 +13,7 @@ pub const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
 pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
 pub const ATA_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
 pub const STAKE_POOL_PROGRAM_ID: &str = "SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy";
+pub const SQUADS_MULTISIG_PROGRAM_ID: &str = "SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf";
 
 /// Sysvar Recent Blockhashes address.
 /// Required for NonceAdvance instruction to verify the nonce account's stored blockhash.
@@ -68,6 +69,12 @@ pub enum ParsedInstruction {
     StakePoolDepositSol(StakePoolDepositSolParams),
     StakePoolWithdrawStake(StakePoolWithdrawStakeParams),
 
+    // Squads multisig (v4) instructions
+    SquadsVaultTransactionCreate(SquadsVaultTransactionCreateParams),
+    SquadsProposalCreate(SquadsProposalCreateParams),
+    SquadsProposalVote(SquadsProposalVoteParams),
+    SquadsVaultTransactionExecute(SquadsVaultTransactionExecuteParams),
+
     // Fallback for unknown/custom instructions
     Unknown(UnknownInstructionParams),
 }
@@ -246,6 +253,68 @@ pub struct StakePoolWithdrawStakeParams {
     pub pool_tokens: u64,
 }
 
+// =============================================================================
+// Squads Multisig Params
+// =============================================================================
+
+/// Parameters for `vault_transaction_create`: proposes to run `instructions` with the
+/// vault as signer.
+#[derive(Debug, Clone)]
+pub struct SquadsVaultTransactionCreateParams {
+    pub multisig_address: String,
+    pub transaction_address: String,
+    pub creator_address: String,
+    pub rent_payer_address: String,
+    /// Vault PDA derived from the multisig and the vault index
+    pub vault_address: String,
+    pub vault_index: u8,
+    pub ephemeral_signers: u8,
+    pub memo: Option<String>,
+    /// Static account keys of the vault transaction message
+    pub account_keys: Vec<String>,
+    /// Decoded instructions of the vault transaction message
+    pub instructions: Vec<ParsedInstruction>,
+}
+
+/// Parameters for `proposal_create`
+#[derive(Debug, Clone)]
+pub struct SquadsProposalCreateParams {
+    pub multisig_address: String,
+    pub proposal_address: String,
+    pub creator_address: String,
+    pub rent_payer_address: String,
+    pub transaction_index: u64,
+    pub draft: bool,
+}
+
+/// Vote cast on a proposal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SquadsVote {
+    Approve,
+    Reject,
+    Cancel,
+}
+
+/// Parameters for `proposal_approve`, `proposal_reject` and `proposal_cancel`
+#[derive(Debug, Clone)]
+pub struct SquadsProposalVoteParams {
+    pub multisig_address: String,
+    pub member_address: String,
+    pub proposal_address: String,
+    pub vote: SquadsVote,
+    pub memo: Option<String>,
+}
+
+/// Parameters for `vault_transaction_execute`. The instructions that are executed are
+/// stored in the transaction account, not in the executing transaction.
+#[derive(Debug, Clone)]
+pub struct SquadsVaultTransactionExecuteParams {
+    pub multisig_address: String,
+    pub proposal_address: String,
+    pub transaction_address: String,
+    pub member_address: String,
+}
+
 // =============================================================================
 // Memo & Unknown
 // =============================================================================
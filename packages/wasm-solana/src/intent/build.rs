@@ -7,6 +7,7 @@ use crate::error::WasmSolanaError;
 use crate::keypair::{Keypair, KeypairExt};
 use crate::transaction::TransactionExt;
 
+use super::plan::token_transfer;
 use super::types::*;
 
 // Solana SDK types
@@ -26,11 +27,11 @@ use solana_system_interface::instruction as system_ix;
 
 // Well-known Solana program IDs
 // SPL Token Program: https://www.solana-program.com/docs/token
-const SPL_TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+pub(super) const SPL_TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
 // Associated Token Account Program: https://www.solana-program.com/docs/associated-token-account
 const SPL_ATA_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
 // System Program
-const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+pub(super) const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
 
 // Constants
 const STAKE_ACCOUNT_SPACE: u64 = 200;
@@ -166,7 +167,7 @@ fn build_transaction_from_instructions(
 // =============================================================================
 
 /// Derive the Associated Token Account address for `owner` + `mint` under `token_program`.
-fn derive_ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+pub(super) fn derive_ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
     let ata_program: Pubkey = SPL_ATA_PROGRAM_ID.parse().unwrap();
     let seeds = &[owner.as_ref(), token_program.as_ref(), mint.as_ref()];
     let (ata, _bump) = Pubkey::find_program_address(seeds, &ata_program);
@@ -174,7 +175,7 @@ fn derive_ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
 }
 
 /// Build a `CreateIdempotent` ATA instruction (no-op if ATA already exists).
-fn create_ata_idempotent_ix(
+pub(super) fn create_ata_idempotent_ix(
     fee_payer: &Pubkey,
     ata: &Pubkey,
     owner: &Pubkey,
@@ -210,7 +211,6 @@ fn build_payment(
         .parse()
         .map_err(|_| WasmSolanaError::new("Invalid feePayer"))?;
 
-    let system_program: Pubkey = SYSTEM_PROGRAM_ID.parse().unwrap();
     let default_token_program: Pubkey = SPL_TOKEN_PROGRAM_ID.parse().unwrap();
 
     let mut instructions = Vec::new();
@@ -255,43 +255,19 @@ fn build_payment(
                 .decimal_places
                 .ok_or_else(|| WasmSolanaError::new("Token transfer requires decimalPlaces"))?;
 
-            // Derive ATAs for sender (fee_payer) and recipient
-            let sender_ata = derive_ata(&fee_payer, &mint, &token_program);
-            let recipient_ata = derive_ata(&to_pubkey, &mint, &token_program);
-
-            // 1. CreateIdempotent ATA for the recipient (safe to always include)
-            instructions.push(create_ata_idempotent_ix(
+            // CreateIdempotent ATA for the recipient (safe to always include), then
+            // transfer_checked from the sender (fee_payer) ATA
+            let transfer = token_transfer(
+                &fee_payer,
                 &fee_payer,
-                &recipient_ata,
                 &to_pubkey,
                 &mint,
-                &system_program,
                 &token_program,
-            ));
-
-            // 2. transfer_checked
-            // Pack the instruction data via spl_token types (avoids solana crate version mismatch)
-            // then build the Instruction manually with solana_sdk types.
-            use spl_token::instruction::TokenInstruction;
-            let data = TokenInstruction::TransferChecked {
-                amount: amount_wrapper.value,
+                amount_wrapper.value,
                 decimals,
-            }
-            .pack();
-
-            // Accounts: source(w), mint(r), destination(w), authority(signer)
-            let transfer_ix = Instruction::new_with_bytes(
-                token_program,
-                &data,
-                vec![
-                    AccountMeta::new(sender_ata, false),
-                    AccountMeta::new_readonly(mint, false),
-                    AccountMeta::new(recipient_ata, false),
-                    AccountMeta::new_readonly(fee_payer, true),
-                ],
+                &[],
             );
-
-            instructions.push(transfer_ix);
+            instructions.extend(transfer.instructions);
         } else {
             // Native SOL transfer
             instructions.push(system_ix::transfer(
@@ -582,12 +558,14 @@ fn build_partial_unstake(
         .map_err(|_| WasmSolanaError::new("Failed to generate unstake address"))?;
 
     let instructions = vec![
-        // Transfer rent to new account
-        system_ix::transfer(fee_payer, &unstake_pubkey, STAKE_ACCOUNT_RENT),
-        // Allocate space
-        system_ix::allocate(&unstake_pubkey, STAKE_ACCOUNT_SPACE),
-        // Assign to stake program
-        system_ix::assign(&unstake_pubkey, &solana_stake_interface::program::ID),
+        // Create the split account, owned by the stake program
+        system_ix::create_account(
+            fee_payer,
+            &unstake_pubkey,
+            STAKE_ACCOUNT_RENT,
+            STAKE_ACCOUNT_SPACE,
+            &solana_stake_interface::program::ID,
+        ),
         // Split stake
         Instruction::new_with_bincode(
             solana_stake_interface::program::ID,
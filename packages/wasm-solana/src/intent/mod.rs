@@ -12,7 +12,9 @@
 //! ```
 
 mod build;
+mod plan;
 mod types;
 
 pub use build::build_from_intent;
+pub use plan::plan_token_transfer;
 pub use types::*;
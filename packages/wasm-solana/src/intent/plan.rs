@@ -0,0 +1,265 @@
+//! Token transfer planning.
+//!
+//! Decides which instructions an SPL token transfer needs. The recipient token account
+//! is created with `CreateIdempotent`, never with the plain `Create` instruction, which
+//! fails if the account was created in the meantime. The create instruction is left out
+//! when the caller knows the account already exists.
+
+use crate::error::WasmSolanaError;
+
+use super::build::{create_ata_idempotent_ix, derive_ata, SPL_TOKEN_PROGRAM_ID, SYSTEM_PROGRAM_ID};
+use super::types::{CustomTxInstruction, CustomTxKey, TokenTransferPlan, TokenTransferRequest};
+
+use base64::Engine;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+
+/// Instructions of a token transfer from the associated token account of the owner.
+pub(super) struct TokenTransfer {
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub creates_destination: bool,
+    pub instructions: Vec<Instruction>,
+}
+
+/// Build the instructions transferring `amount` of `mint` from `owner` to `recipient`.
+///
+/// If `recipient` is one of the `existing` token accounts, the tokens are sent to it
+/// directly. Otherwise they are sent to the associated token account of `recipient`,
+/// which is created first unless it is one of the `existing` accounts.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn token_transfer(
+    fee_payer: &Pubkey,
+    owner: &Pubkey,
+    recipient: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    amount: u64,
+    decimals: u8,
+    existing: &[Pubkey],
+) -> TokenTransfer {
+    let system_program: Pubkey = SYSTEM_PROGRAM_ID.parse().unwrap();
+    let source = derive_ata(owner, mint, token_program);
+
+    let mut instructions = Vec::new();
+    let (destination, creates_destination) = if existing.contains(recipient) {
+        (*recipient, false)
+    } else {
+        let ata = derive_ata(recipient, mint, token_program);
+        let exists = existing.contains(&ata);
+        if !exists {
+            instructions.push(create_ata_idempotent_ix(
+                fee_payer,
+                &ata,
+                recipient,
+                mint,
+                &system_program,
+                token_program,
+            ));
+        }
+        (ata, !exists)
+    };
+
+    // Pack the instruction data via spl_token types (avoids solana crate version mismatch)
+    // then build the Instruction manually with solana_sdk types.
+    use spl_token::instruction::TokenInstruction;
+    let data = TokenInstruction::TransferChecked { amount, decimals }.pack();
+
+    // Accounts: source(w), mint(r), destination(w), authority(signer)
+    instructions.push(Instruction::new_with_bytes(
+        *token_program,
+        &data,
+        vec![
+            AccountMeta::new(source, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(destination, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+    ));
+
+    TokenTransfer {
+        source,
+        destination,
+        creates_destination,
+        instructions,
+    }
+}
+
+/// Plan a token transfer, returning its instructions in `customTx` format.
+///
+/// `existingTokenAccounts` only needs to list the accounts the caller knows about; an
+/// unknown recipient account is created with `CreateIdempotent`, which is a no-op if it
+/// exists after all.
+pub fn plan_token_transfer(
+    request: &TokenTransferRequest,
+) -> Result<TokenTransferPlan, WasmSolanaError> {
+    let parse = |field: &str, value: &str| -> Result<Pubkey, WasmSolanaError> {
+        value
+            .parse()
+            .map_err(|_| WasmSolanaError::new(&format!("Invalid {}: {}", field, value)))
+    };
+
+    let owner = parse("owner", &request.owner)?;
+    let recipient = parse("recipient", &request.recipient)?;
+    let mint = parse("mint", &request.mint)?;
+    let token_program = parse(
+        "tokenProgramId",
+        request
+            .token_program_id
+            .as_deref()
+            .unwrap_or(SPL_TOKEN_PROGRAM_ID),
+    )?;
+    let fee_payer = request
+        .fee_payer
+        .as_deref()
+        .map(|fee_payer| parse("feePayer", fee_payer))
+        .transpose()?
+        .unwrap_or(owner);
+    let existing = request
+        .existing_token_accounts
+        .iter()
+        .map(|account| parse("existingTokenAccounts", account))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let transfer = token_transfer(
+        &fee_payer,
+        &owner,
+        &recipient,
+        &mint,
+        &token_program,
+        request.amount,
+        request.decimal_places,
+        &existing,
+    );
+
+    Ok(TokenTransferPlan {
+        source: transfer.source.to_string(),
+        destination: transfer.destination.to_string(),
+        creates_destination: transfer.creates_destination,
+        instructions: transfer.instructions.iter().map(to_custom_tx).collect(),
+    })
+}
+
+fn to_custom_tx(ix: &Instruction) -> CustomTxInstruction {
+    CustomTxInstruction {
+        program_id: ix.program_id.to_string(),
+        keys: ix
+            .accounts
+            .iter()
+            .map(|meta| CustomTxKey {
+                pubkey: meta.pubkey.to_string(),
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            })
+            .collect(),
+        data: base64::engine::general_purpose::STANDARD.encode(&ix.data),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent::{build_from_intent, BuildParams, Nonce};
+
+    const OWNER: &str = "DgT9qyYwYKBRDyDw3EfR12LHQCQjtNrKu2qMsXHuosmB";
+    const RECIPIENT: &str = "FKjSjCqByQRwSzZoMXA7bKnDbJe41YgJTHFFzBeC42bH";
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const ATA_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+
+    fn request(existing: Vec<String>) -> TokenTransferRequest {
+        serde_json::from_value(serde_json::json!({
+            "owner": OWNER,
+            "recipient": RECIPIENT,
+            "mint": USDC,
+            "amount": "2500000",
+            "decimalPlaces": 6,
+            "existingTokenAccounts": existing,
+        }))
+        .unwrap()
+    }
+
+    fn recipient_ata() -> String {
+        derive_ata(
+            &RECIPIENT.parse().unwrap(),
+            &USDC.parse().unwrap(),
+            &SPL_TOKEN_PROGRAM_ID.parse().unwrap(),
+        )
+        .to_string()
+    }
+
+    #[test]
+    fn test_plan_creates_unknown_recipient_account() {
+        let plan = plan_token_transfer(&request(vec![])).unwrap();
+        assert!(plan.creates_destination);
+        assert_eq!(plan.destination, recipient_ata());
+        assert_eq!(plan.instructions.len(), 2);
+
+        let create = &plan.instructions[0];
+        assert_eq!(create.program_id, ATA_PROGRAM_ID);
+        // CreateIdempotent, not Create
+        assert_eq!(
+            create.data,
+            base64::engine::general_purpose::STANDARD.encode([1])
+        );
+        assert_eq!(create.keys[0].pubkey, OWNER);
+        assert!(create.keys[0].is_signer);
+
+        let transfer = &plan.instructions[1];
+        assert_eq!(transfer.program_id, SPL_TOKEN_PROGRAM_ID);
+        let keys: Vec<_> = transfer.keys.iter().map(|k| k.pubkey.as_str()).collect();
+        assert_eq!(keys, [plan.source.as_str(), USDC, &recipient_ata(), OWNER]);
+    }
+
+    #[test]
+    fn test_plan_skips_existing_accounts() {
+        let plan = plan_token_transfer(&request(vec![recipient_ata()])).unwrap();
+        assert!(!plan.creates_destination);
+        assert_eq!(plan.destination, recipient_ata());
+        assert_eq!(plan.instructions.len(), 1);
+        assert_eq!(plan.instructions[0].program_id, SPL_TOKEN_PROGRAM_ID);
+
+        // A recipient that is itself a token account receives the tokens directly
+        let plan = plan_token_transfer(&request(vec![RECIPIENT.to_string()])).unwrap();
+        assert!(!plan.creates_destination);
+        assert_eq!(plan.destination, RECIPIENT);
+        assert_eq!(plan.instructions.len(), 1);
+    }
+
+    #[test]
+    fn test_plan_matches_payment_intent() {
+        let plan = plan_token_transfer(&request(vec![])).unwrap();
+        let intent = serde_json::json!({
+            "intentType": "customTx",
+            "solInstructions": serde_json::to_value(&plan.instructions).unwrap(),
+        });
+        let params = BuildParams {
+            fee_payer: OWNER.to_string(),
+            nonce: Nonce::Blockhash {
+                value: "GWaQEymC3Z9SHM2gkh8u12xL1zJPMHPCSVR3pSDpEXE4".to_string(),
+            },
+        };
+        let from_plan = build_from_intent(&intent, &params).unwrap().transaction;
+
+        let payment = serde_json::json!({
+            "intentType": "payment",
+            "recipients": [{
+                "address": { "address": RECIPIENT },
+                "amount": { "value": "2500000" },
+                "tokenAddress": USDC,
+                "decimalPlaces": 6
+            }]
+        });
+        let from_payment = build_from_intent(&payment, &params).unwrap().transaction;
+        assert_eq!(from_plan.message, from_payment.message);
+    }
+
+    #[test]
+    fn test_plan_rejects_invalid_addresses() {
+        let mut request = request(vec![]);
+        request
+            .existing_token_accounts
+            .push("not-a-pubkey".to_string());
+        let err = plan_token_transfer(&request).unwrap_err().to_string();
+        assert!(err.contains("existingTokenAccounts"), "{}", err);
+    }
+}
@@ -381,7 +381,7 @@ pub struct CustomTxIntent {
 }
 
 /// A single custom instruction
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CustomTxInstruction {
     /// Program ID (base58)
@@ -393,7 +393,7 @@ pub struct CustomTxInstruction {
 }
 
 /// Account key for a custom instruction
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CustomTxKey {
     /// Account public key (base58)
@@ -403,3 +403,46 @@ pub struct CustomTxKey {
     /// Whether this account is writable
     pub is_writable: bool,
 }
+
+// =============================================================================
+// Token Transfer Planning
+// =============================================================================
+
+/// Token transfer to plan with [`super::plan_token_transfer`]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenTransferRequest {
+    /// Owner of the source token account (base58)
+    pub owner: String,
+    /// Recipient wallet or token account (base58)
+    pub recipient: String,
+    /// Mint address (base58)
+    pub mint: String,
+    #[serde(deserialize_with = "deserialize_amount")]
+    pub amount: u64,
+    /// Decimal places for the token (required for transfer_checked)
+    pub decimal_places: u8,
+    /// Token program ID (defaults to SPL Token Program)
+    #[serde(default)]
+    pub token_program_id: Option<String>,
+    /// Account paying for the recipient token account (defaults to owner)
+    #[serde(default)]
+    pub fee_payer: Option<String>,
+    /// Token accounts known to exist on chain (base58)
+    #[serde(default)]
+    pub existing_token_accounts: Vec<String>,
+}
+
+/// Instructions of a token transfer and the accounts they use
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenTransferPlan {
+    /// Source token account: the associated token account of the owner
+    pub source: String,
+    /// Destination token account
+    pub destination: String,
+    /// Whether the instructions create the destination with CreateIdempotent
+    pub creates_destination: bool,
+    /// Instructions in `customTx` format
+    pub instructions: Vec<CustomTxInstruction>,
+}
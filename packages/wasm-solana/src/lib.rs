@@ -24,12 +24,16 @@
 //! ```
 
 mod error;
+pub mod fee;
 mod instructions;
 pub mod intent;
 pub mod keypair;
+pub mod limits;
 mod parser;
 pub mod pubkey;
+pub mod sanitize;
 pub mod transaction;
+pub mod validation;
 pub mod versioned;
 pub mod versioned_builder;
 pub mod wasm;
@@ -40,7 +44,8 @@ pub use keypair::{Keypair, KeypairExt};
 pub use pubkey::{Pubkey, PubkeyExt};
 pub use transaction::{Transaction, TransactionExt};
 pub use versioned::{
-    detect_transaction_version, AddressLookupTableData, TxVersion, VersionedTransactionExt,
+    detect_transaction_version, transaction_version, AddressLookupTableData, TxVersion,
+    VersionedTransactionExt,
 };
 
 // Re-export WASM types
@@ -0,0 +1,326 @@
+//! Transaction size and account limits.
+//!
+//! A transaction that exceeds one of the limits of the network is only rejected when it is
+//! broadcast, after it has been signed. [`validate_limits`] checks a message up front: the
+//! signed transaction must fit in a packet, lock at most [`MAX_ACCOUNT_KEYS`] accounts, and
+//! no instruction may reference more than [`MAX_INSTRUCTION_ACCOUNTS`] accounts.
+//!
+//! [`validate_versioned_limits`] applies the same limits to versioned (v0) messages, where the
+//! accounts loaded from address lookup tables count towards [`MAX_ACCOUNT_KEYS`].
+
+use solana_message::compiled_instruction::CompiledInstruction;
+use solana_message::{Message, VersionedMessage};
+
+/// Maximum size of a signed transaction in bytes (`PACKET_DATA_SIZE`)
+pub const PACKET_DATA_SIZE: usize = 1232;
+
+/// Maximum number of account keys of a transaction (the default
+/// `transaction_account_lock_limit` of the runtime)
+pub const MAX_ACCOUNT_KEYS: usize = 64;
+
+/// Maximum number of accounts of a single instruction (`MAX_ACCOUNTS_PER_INSTRUCTION`)
+pub const MAX_INSTRUCTION_ACCOUNTS: usize = 255;
+
+/// Size of an Ed25519 signature in bytes
+const SIGNATURE_SIZE: usize = 64;
+
+/// A limit exceeded by a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LimitViolation {
+    /// The signed transaction does not fit in a packet
+    TransactionTooLarge {
+        size: usize,
+        limit: usize,
+        bytes_over: usize,
+    },
+    /// The transaction locks too many accounts
+    TooManyAccountKeys { count: usize, limit: usize },
+    /// An instruction references too many accounts
+    TooManyInstructionAccounts {
+        instruction_index: usize,
+        count: usize,
+        limit: usize,
+    },
+}
+
+impl std::fmt::Display for LimitViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitViolation::TransactionTooLarge {
+                size,
+                limit,
+                bytes_over,
+            } => write!(
+                f,
+                "Transaction is {} bytes, {} bytes over the limit of {}",
+                size, bytes_over, limit
+            ),
+            LimitViolation::TooManyAccountKeys { count, limit } => write!(
+                f,
+                "Transaction has {} account keys, at most {} are allowed",
+                count, limit
+            ),
+            LimitViolation::TooManyInstructionAccounts {
+                instruction_index,
+                count,
+                limit,
+            } => write!(
+                f,
+                "Instruction {} has {} accounts, at most {} are allowed",
+                instruction_index, count, limit
+            ),
+        }
+    }
+}
+
+/// Sizes of a transaction and the limits they exceed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitsReport {
+    /// Size of the transaction once every required signature is present, in bytes
+    pub size: usize,
+    /// Number of account keys of the message, including those loaded from address lookup
+    /// tables
+    pub account_keys: usize,
+    /// Exceeded limits; empty if the transaction is within all limits
+    pub violations: Vec<LimitViolation>,
+}
+
+impl LimitsReport {
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Bytes by which the signed transaction exceeds [`PACKET_DATA_SIZE`] (0 if it fits).
+    pub fn bytes_over(&self) -> usize {
+        self.size.saturating_sub(PACKET_DATA_SIZE)
+    }
+}
+
+/// Size of the wire encoding of a `short_vec` length prefix.
+fn short_vec_len_size(len: usize) -> usize {
+    match len {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        _ => 3,
+    }
+}
+
+/// Size of the signed transaction carrying `message`, in bytes.
+///
+/// Missing signatures are counted, so an unsigned transaction has the size it will have
+/// once it is fully signed.
+pub fn signed_transaction_size(message: &Message) -> usize {
+    signed_size(
+        message.header.num_required_signatures,
+        message.serialize().len(),
+    )
+}
+
+fn signed_size(num_required_signatures: u8, message_size: usize) -> usize {
+    let num_signatures = num_required_signatures as usize;
+    short_vec_len_size(num_signatures) + num_signatures * SIGNATURE_SIZE + message_size
+}
+
+/// Check the size and account limits of a message.
+pub fn validate_limits(message: &Message) -> LimitsReport {
+    check_limits(
+        signed_transaction_size(message),
+        message.account_keys.len(),
+        &message.instructions,
+    )
+}
+
+/// Check the size and account limits of a legacy or versioned message.
+pub fn validate_versioned_limits(message: &VersionedMessage) -> LimitsReport {
+    let account_keys = match message {
+        VersionedMessage::Legacy(message) => message.account_keys.len(),
+        VersionedMessage::V0(message) => {
+            message.account_keys.len()
+                + message
+                    .address_table_lookups
+                    .iter()
+                    .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+                    .sum::<usize>()
+        }
+    };
+    check_limits(
+        signed_size(
+            message.header().num_required_signatures,
+            message.serialize().len(),
+        ),
+        account_keys,
+        message.instructions(),
+    )
+}
+
+fn check_limits(
+    size: usize,
+    account_keys: usize,
+    instructions: &[CompiledInstruction],
+) -> LimitsReport {
+    let mut violations = vec![];
+
+    if size > PACKET_DATA_SIZE {
+        violations.push(LimitViolation::TransactionTooLarge {
+            size,
+            limit: PACKET_DATA_SIZE,
+            bytes_over: size - PACKET_DATA_SIZE,
+        });
+    }
+    if account_keys > MAX_ACCOUNT_KEYS {
+        violations.push(LimitViolation::TooManyAccountKeys {
+            count: account_keys,
+            limit: MAX_ACCOUNT_KEYS,
+        });
+    }
+    for (instruction_index, ix) in instructions.iter().enumerate() {
+        if ix.accounts.len() > MAX_INSTRUCTION_ACCOUNTS {
+            violations.push(LimitViolation::TooManyInstructionAccounts {
+                instruction_index,
+                count: ix.accounts.len(),
+                limit: MAX_INSTRUCTION_ACCOUNTS,
+            });
+        }
+    }
+
+    LimitsReport {
+        size,
+        account_keys,
+        violations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::pubkey::Pubkey;
+    use solana_system_interface::instruction as system_ix;
+    use solana_transaction::Transaction;
+
+    fn fee_payer() -> Pubkey {
+        "DgT9qyYwYKBRDyDw3EfR12LHQCQjtNrKu2qMsXHuosmB"
+            .parse()
+            .unwrap()
+    }
+
+    fn message(instructions: &[Instruction]) -> Message {
+        Message::new_with_blockhash(instructions, Some(&fee_payer()), &Hash::default())
+    }
+
+    #[test]
+    fn test_signed_transaction_size() {
+        let ix = system_ix::transfer(&fee_payer(), &Pubkey::new_unique(), 1000);
+        let message = message(&[ix]);
+        let tx = Transaction::new_unsigned(message.clone());
+        assert_eq!(
+            signed_transaction_size(&message),
+            bincode::serialize(&tx).unwrap().len()
+        );
+
+        let report = validate_limits(&message);
+        assert!(report.is_valid());
+        assert_eq!(report.account_keys, 3);
+        assert_eq!(report.bytes_over(), 0);
+    }
+
+    #[test]
+    fn test_transaction_too_large() {
+        let ix = Instruction::new_with_bytes(Pubkey::new_unique(), &[0; 1200], vec![]);
+        let message = message(&[ix]);
+        let report = validate_limits(&message);
+        assert_eq!(report.bytes_over(), report.size - PACKET_DATA_SIZE);
+        assert_eq!(
+            report.violations,
+            vec![LimitViolation::TransactionTooLarge {
+                size: report.size,
+                limit: PACKET_DATA_SIZE,
+                bytes_over: report.bytes_over(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_too_many_accounts() {
+        let accounts: Vec<_> = (0..MAX_ACCOUNT_KEYS)
+            .map(|_| AccountMeta::new_readonly(Pubkey::new_unique(), false))
+            .collect();
+        let ix = Instruction::new_with_bytes(Pubkey::new_unique(), &[], accounts);
+        let report = validate_limits(&message(&[ix]));
+        // Fee payer, accounts and program
+        assert_eq!(report.account_keys, MAX_ACCOUNT_KEYS + 2);
+        assert!(report
+            .violations
+            .contains(&LimitViolation::TooManyAccountKeys {
+                count: MAX_ACCOUNT_KEYS + 2,
+                limit: MAX_ACCOUNT_KEYS,
+            }));
+
+        let account = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+        let ix = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![account; MAX_INSTRUCTION_ACCOUNTS + 1],
+        );
+        let transfer = system_ix::transfer(&fee_payer(), &Pubkey::new_unique(), 1000);
+        let report = validate_limits(&message(&[transfer, ix]));
+        assert_eq!(
+            report.violations,
+            vec![LimitViolation::TooManyInstructionAccounts {
+                instruction_index: 1,
+                count: MAX_INSTRUCTION_ACCOUNTS + 1,
+                limit: MAX_INSTRUCTION_ACCOUNTS,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_versioned_limits() {
+        use solana_message::{v0, AddressLookupTableAccount};
+        use solana_transaction::versioned::VersionedTransaction;
+
+        let ix = system_ix::transfer(&fee_payer(), &Pubkey::new_unique(), 1000);
+        let legacy = message(std::slice::from_ref(&ix));
+        assert_eq!(
+            validate_versioned_limits(&VersionedMessage::Legacy(legacy.clone())),
+            validate_limits(&legacy)
+        );
+
+        // Accounts loaded from a lookup table count towards the account key limit, but only
+        // take one byte each in the message
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: (0..MAX_ACCOUNT_KEYS)
+                .map(|_| Pubkey::new_unique())
+                .collect(),
+        };
+        let accounts: Vec<_> = table
+            .addresses
+            .iter()
+            .map(|address| AccountMeta::new_readonly(*address, false))
+            .collect();
+        let lookup_ix = Instruction::new_with_bytes(Pubkey::new_unique(), &[], accounts);
+        let message = VersionedMessage::V0(
+            v0::Message::try_compile(&fee_payer(), &[ix, lookup_ix], &[table], Hash::default())
+                .unwrap(),
+        );
+        let tx = VersionedTransaction {
+            signatures: vec![Default::default()],
+            message: message.clone(),
+        };
+
+        let report = validate_versioned_limits(&message);
+        assert_eq!(report.size, bincode::serialize(&tx).unwrap().len());
+        assert!(report.size < PACKET_DATA_SIZE);
+        // Fee payer, recipient, system program, program and the table accounts
+        assert_eq!(report.account_keys, MAX_ACCOUNT_KEYS + 4);
+        assert_eq!(
+            report.violations,
+            vec![LimitViolation::TooManyAccountKeys {
+                count: MAX_ACCOUNT_KEYS + 4,
+                limit: MAX_ACCOUNT_KEYS,
+            }]
+        );
+    }
+}
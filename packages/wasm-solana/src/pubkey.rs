@@ -3,15 +3,26 @@
 //! Wraps `solana_pubkey::Pubkey` for WASM compatibility.
 
 use crate::error::WasmSolanaError;
+use crate::instructions::ATA_PROGRAM_ID;
 use std::str::FromStr;
 
 /// Re-export the underlying Solana Pubkey type.
 pub use solana_pubkey::Pubkey;
+use solana_pubkey::{MAX_SEEDS, MAX_SEED_LEN};
 
 /// Extension trait for Pubkey to add WASM-friendly error handling.
 pub trait PubkeyExt {
     fn from_base58(address: &str) -> Result<Pubkey, WasmSolanaError>;
     fn from_bytes_checked(bytes: &[u8]) -> Result<Pubkey, WasmSolanaError>;
+    fn find_program_address_checked(
+        seeds: &[&[u8]],
+        program_id: &Pubkey,
+    ) -> Result<(Pubkey, u8), WasmSolanaError>;
+    fn create_with_seed_checked(
+        base: &Pubkey,
+        seed: &str,
+        owner: &Pubkey,
+    ) -> Result<Pubkey, WasmSolanaError>;
 }
 
 impl PubkeyExt for Pubkey {
@@ -36,11 +47,66 @@ impl PubkeyExt for Pubkey {
 
         Ok(Pubkey::from(array))
     }
+
+    /// Find the program-derived address (PDA) of `seeds` under `program_id`.
+    ///
+    /// Returns the address and the bump seed, the largest value that moves the address
+    /// off the Ed25519 curve. At most 15 seeds of up to 32 bytes each are allowed, since
+    /// the bump is appended as the 16th seed.
+    fn find_program_address_checked(
+        seeds: &[&[u8]],
+        program_id: &Pubkey,
+    ) -> Result<(Pubkey, u8), WasmSolanaError> {
+        if seeds.len() >= MAX_SEEDS {
+            return Err(WasmSolanaError::new(&format!(
+                "Too many seeds: expected at most {}, got {}",
+                MAX_SEEDS - 1,
+                seeds.len()
+            )));
+        }
+        if let Some(i) = seeds.iter().position(|seed| seed.len() > MAX_SEED_LEN) {
+            return Err(WasmSolanaError::new(&format!(
+                "Seed {} is too long: expected at most {} bytes, got {}",
+                i,
+                MAX_SEED_LEN,
+                seeds[i].len()
+            )));
+        }
+        Pubkey::try_find_program_address(seeds, program_id)
+            .ok_or_else(|| WasmSolanaError::new("Unable to find a viable program address bump"))
+    }
+
+    /// Derive the address of an account created with `createAccountWithSeed`.
+    ///
+    /// The address is `sha256(base || seed || owner)`; `seed` is at most 32 bytes.
+    fn create_with_seed_checked(
+        base: &Pubkey,
+        seed: &str,
+        owner: &Pubkey,
+    ) -> Result<Pubkey, WasmSolanaError> {
+        Pubkey::create_with_seed(base, seed, owner)
+            .map_err(|e| WasmSolanaError::new(&format!("Invalid seed \"{}\": {}", seed, e)))
+    }
+}
+
+/// Derive the Associated Token Account (ATA) address of `owner` for `mint`.
+///
+/// The ATA is the program-derived address of the seeds `[owner, token_program, mint]`
+/// under the Associated Token Account program. Returns the address and its bump seed.
+pub fn derive_associated_token_address(
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> (Pubkey, u8) {
+    let ata_program = Pubkey::from_str(ATA_PROGRAM_ID).expect("valid ATA program id");
+    let seeds = &[owner.as_ref(), token_program.as_ref(), mint.as_ref()];
+    Pubkey::find_program_address(seeds, &ata_program)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::instructions::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
 
     #[test]
     fn test_from_base58() {
@@ -107,4 +173,80 @@ mod tests {
         }
         panic!("Could not find an off-curve point");
     }
+
+    #[test]
+    fn test_derive_associated_token_address() {
+        let owner = Pubkey::from_base58("FKjSjCqByQRwSzZoMXA7bKnDbJe41YgJTHFFzBeC42bH").unwrap();
+        // USDC mint
+        let mint = Pubkey::from_base58("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap();
+        let ata_program = Pubkey::from_base58(ATA_PROGRAM_ID).unwrap();
+
+        for token_program_id in [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID] {
+            let token_program = Pubkey::from_base58(token_program_id).unwrap();
+            let (ata, bump) = derive_associated_token_address(&owner, &mint, &token_program);
+
+            let expected =
+                spl_associated_token_account::get_associated_token_address_with_program_id(
+                    &owner.to_bytes().into(),
+                    &mint.to_bytes().into(),
+                    &token_program.to_bytes().into(),
+                );
+            assert_eq!(ata.to_bytes(), expected.to_bytes());
+            assert!(!ata.is_on_curve());
+
+            let seeds = &[
+                owner.as_ref(),
+                token_program.as_ref(),
+                mint.as_ref(),
+                &[bump],
+            ];
+            assert_eq!(
+                Pubkey::create_program_address(seeds, &ata_program).unwrap(),
+                ata
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_program_address() {
+        let program_id = Pubkey::from_base58(ATA_PROGRAM_ID).unwrap();
+        let seeds: &[&[u8]] = &[b"withdraw", &[7u8; 32]];
+
+        let (address, bump) = Pubkey::find_program_address_checked(seeds, &program_id).unwrap();
+        assert_eq!(
+            (address, bump),
+            Pubkey::find_program_address(seeds, &program_id)
+        );
+        assert!(!address.is_on_curve());
+
+        let too_long: &[&[u8]] = &[&[0u8; 33]];
+        assert!(Pubkey::find_program_address_checked(too_long, &program_id)
+            .unwrap_err()
+            .to_string()
+            .contains("Seed 0 is too long"));
+
+        let too_many = vec![&b"seed"[..]; MAX_SEEDS];
+        assert!(Pubkey::find_program_address_checked(&too_many, &program_id)
+            .unwrap_err()
+            .to_string()
+            .contains("Too many seeds"));
+    }
+
+    #[test]
+    fn test_create_with_seed() {
+        let base = Pubkey::from_base58("FKjSjCqByQRwSzZoMXA7bKnDbJe41YgJTHFFzBeC42bH").unwrap();
+        let owner = Pubkey::from_base58("Stake11111111111111111111111111111111111111").unwrap();
+
+        let address = Pubkey::create_with_seed_checked(&base, "stake:0", &owner).unwrap();
+        assert_eq!(
+            address,
+            Pubkey::create_with_seed(&base, "stake:0", &owner).unwrap()
+        );
+        assert_ne!(
+            address,
+            Pubkey::create_with_seed_checked(&base, "stake:1", &owner).unwrap()
+        );
+
+        assert!(Pubkey::create_with_seed_checked(&base, &"x".repeat(33), &owner).is_err());
+    }
 }
@@ -0,0 +1,338 @@
+//! Message sanitization and security checks before signing.
+//!
+//! A cold signer only sees the serialized message, so it must not trust the intent that
+//! produced it. [`sanitize_and_check`] rejects messages that are malformed, call programs
+//! the signer does not know, make accounts other than the fee payer writable signers
+//! without creating them, or hand over ownership of an account.
+
+use crate::error::WasmSolanaError;
+use crate::instructions::{
+    ATA_PROGRAM_ID, COMPUTE_BUDGET_PROGRAM_ID, MEMO_PROGRAM_ID, STAKE_POOL_PROGRAM_ID,
+    STAKE_PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+};
+use solana_message::compiled_instruction::CompiledInstruction;
+use solana_message::Message;
+use solana_stake_interface::instruction::StakeInstruction;
+use solana_stake_interface::state::StakeAuthorize;
+use solana_system_interface::instruction::SystemInstruction;
+
+/// Programs used by the transactions this crate builds
+pub const DEFAULT_ALLOWED_PROGRAMS: &[&str] = &[
+    SYSTEM_PROGRAM_ID,
+    STAKE_PROGRAM_ID,
+    COMPUTE_BUDGET_PROGRAM_ID,
+    MEMO_PROGRAM_ID,
+    TOKEN_PROGRAM_ID,
+    TOKEN_2022_PROGRAM_ID,
+    ATA_PROGRAM_ID,
+    STAKE_POOL_PROGRAM_ID,
+];
+
+/// SPL Token `SetAuthority` instruction discriminator
+const TOKEN_SET_AUTHORITY: u8 = 6;
+
+/// SPL Token `AuthorityType::AccountOwner`
+const TOKEN_AUTHORITY_ACCOUNT_OWNER: u8 = 2;
+
+/// SPL Token `AuthorityType::CloseAccount`
+const TOKEN_AUTHORITY_CLOSE_ACCOUNT: u8 = 3;
+
+/// Verify a message before signing it.
+///
+/// Fails if
+/// - the message is malformed (invalid header or account indices),
+/// - the fee payer is not `expected_fee_payer`,
+/// - an instruction calls a program that is not in `allowed_programs`,
+/// - an account other than the fee payer is a writable signer without being created by
+///   the message (its funds would be at the disposal of the transaction),
+/// - an instruction transfers ownership of an account: System `Assign` of an account not
+///   created by the message, SPL Token `SetAuthority` of the account owner or close
+///   authority, or a Stake withdrawer authorization.
+///
+/// Only System `CreateAccount` and `CreateAccountWithSeed` create accounts. `Allocate` does
+/// not: it succeeds on any empty account, including the fee payer, which a following `Assign`
+/// would hand over to another program.
+pub fn sanitize_and_check(
+    message: &Message,
+    allowed_programs: &[&str],
+    expected_fee_payer: &str,
+) -> Result<(), WasmSolanaError> {
+    sanitize(message)?;
+
+    let fee_payer = message.account_keys[0].to_string();
+    if fee_payer != expected_fee_payer {
+        return Err(WasmSolanaError::new(&format!(
+            "Unexpected fee payer: expected {}, got {}",
+            expected_fee_payer, fee_payer
+        )));
+    }
+
+    let created = created_account_indices(message);
+    for index in 1..message.account_keys.len() {
+        if is_writable_signer(message, index) && !created.contains(&index) {
+            return Err(WasmSolanaError::new(&format!(
+                "Unexpected writable signer: {}",
+                message.account_keys[index]
+            )));
+        }
+    }
+
+    for (ix_index, ix) in message.instructions.iter().enumerate() {
+        let program_id = message.account_keys[ix.program_id_index as usize].to_string();
+        if !allowed_programs.contains(&program_id.as_str()) {
+            return Err(WasmSolanaError::new(&format!(
+                "Instruction {} calls program {} which is not allowed",
+                ix_index, program_id
+            )));
+        }
+        if let Some(kind) = ownership_transfer(&program_id, ix, &created) {
+            return Err(WasmSolanaError::new(&format!(
+                "Instruction {} transfers account ownership ({})",
+                ix_index, kind
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Structural checks of the message header and account indices.
+fn sanitize(message: &Message) -> Result<(), WasmSolanaError> {
+    let header = &message.header;
+    let num_keys = message.account_keys.len();
+    let num_signers = header.num_required_signatures as usize;
+    if num_signers == 0 {
+        return Err(WasmSolanaError::new("Message has no signers"));
+    }
+    if header.num_readonly_signed_accounts >= header.num_required_signatures {
+        return Err(WasmSolanaError::new("Fee payer must be writable"));
+    }
+    if num_signers + header.num_readonly_unsigned_accounts as usize > num_keys {
+        return Err(WasmSolanaError::new(
+            "Message header references more accounts than the message has",
+        ));
+    }
+    for (ix_index, ix) in message.instructions.iter().enumerate() {
+        let program_index = ix.program_id_index as usize;
+        // The fee payer can't be a program
+        if program_index == 0 || program_index >= num_keys {
+            return Err(WasmSolanaError::new(&format!(
+                "Instruction {} has an invalid program id index {}",
+                ix_index, program_index
+            )));
+        }
+        if let Some(&account) = ix.accounts.iter().find(|&&a| a as usize >= num_keys) {
+            return Err(WasmSolanaError::new(&format!(
+                "Instruction {} has an invalid account index {}",
+                ix_index, account
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn is_writable_signer(message: &Message, index: usize) -> bool {
+    let header = &message.header;
+    index < (header.num_required_signatures - header.num_readonly_signed_accounts) as usize
+}
+
+/// Indices of the accounts created by System Program instructions, other than the fee payer.
+fn created_account_indices(message: &Message) -> Vec<usize> {
+    message
+        .instructions
+        .iter()
+        .filter(|ix| {
+            message.account_keys[ix.program_id_index as usize].to_string() == SYSTEM_PROGRAM_ID
+        })
+        .filter_map(
+            |ix| match bincode::deserialize::<SystemInstruction>(&ix.data).ok()? {
+                // Accounts: [0] funding account, [1] new account
+                SystemInstruction::CreateAccount { .. }
+                | SystemInstruction::CreateAccountWithSeed { .. } => ix.accounts.get(1),
+                _ => None,
+            },
+        )
+        .map(|&a| a as usize)
+        .filter(|&index| index != 0)
+        .collect()
+}
+
+/// Describe the instruction if it transfers ownership of an account.
+fn ownership_transfer(
+    program_id: &str,
+    ix: &CompiledInstruction,
+    created: &[usize],
+) -> Option<&'static str> {
+    match program_id {
+        SYSTEM_PROGRAM_ID => {
+            let kind = match bincode::deserialize::<SystemInstruction>(&ix.data).ok()? {
+                SystemInstruction::Assign { .. } => "System Assign",
+                SystemInstruction::AssignWithSeed { .. } => "System AssignWithSeed",
+                _ => return None,
+            };
+            // Accounts: [0] assigned account
+            let account = *ix.accounts.first()? as usize;
+            (!created.contains(&account)).then_some(kind)
+        }
+        TOKEN_PROGRAM_ID | TOKEN_2022_PROGRAM_ID => match ix.data.as_slice() {
+            [TOKEN_SET_AUTHORITY, TOKEN_AUTHORITY_ACCOUNT_OWNER, ..] => {
+                Some("Token SetAuthority AccountOwner")
+            }
+            [TOKEN_SET_AUTHORITY, TOKEN_AUTHORITY_CLOSE_ACCOUNT, ..] => {
+                Some("Token SetAuthority CloseAccount")
+            }
+            _ => None,
+        },
+        STAKE_PROGRAM_ID => {
+            let stake_authorize = match bincode::deserialize::<StakeInstruction>(&ix.data).ok()? {
+                StakeInstruction::Authorize(_, stake_authorize)
+                | StakeInstruction::AuthorizeChecked(stake_authorize) => stake_authorize,
+                StakeInstruction::AuthorizeWithSeed(args) => args.stake_authorize,
+                StakeInstruction::AuthorizeCheckedWithSeed(args) => args.stake_authorize,
+                _ => return None,
+            };
+            (stake_authorize == StakeAuthorize::Withdrawer).then_some("Stake Authorize Withdrawer")
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::instruction::Instruction;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_system_interface::instruction as system_ix;
+
+    const FEE_PAYER: &str = "DgT9qyYwYKBRDyDw3EfR12LHQCQjtNrKu2qMsXHuosmB";
+    const OTHER: &str = "FKjSjCqByQRwSzZoMXA7bKnDbJe41YgJTHFFzBeC42bH";
+
+    fn pubkey(s: &str) -> Pubkey {
+        s.parse().unwrap()
+    }
+
+    fn message(instructions: &[Instruction]) -> Message {
+        Message::new_with_blockhash(instructions, Some(&pubkey(FEE_PAYER)), &Hash::default())
+    }
+
+    fn check(message: &Message) -> Result<(), WasmSolanaError> {
+        sanitize_and_check(message, DEFAULT_ALLOWED_PROGRAMS, FEE_PAYER)
+    }
+
+    #[test]
+    fn test_accepts_transfer_and_create_account() {
+        let transfer = system_ix::transfer(&pubkey(FEE_PAYER), &pubkey(OTHER), 1000);
+        assert!(check(&message(&[transfer])).is_ok());
+
+        // The new stake account signs, but it is created by the message
+        let create = system_ix::create_account(
+            &pubkey(FEE_PAYER),
+            &pubkey(OTHER),
+            2_282_880,
+            200,
+            &solana_stake_interface::program::ID,
+        );
+        assert!(check(&message(&[create])).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_fee_payer_and_programs() {
+        let transfer = system_ix::transfer(&pubkey(FEE_PAYER), &pubkey(OTHER), 1000);
+        let msg = message(&[transfer]);
+        let err = sanitize_and_check(&msg, DEFAULT_ALLOWED_PROGRAMS, OTHER).unwrap_err();
+        assert!(err.to_string().contains("Unexpected fee payer"), "{}", err);
+
+        let err = sanitize_and_check(&msg, &[STAKE_PROGRAM_ID], FEE_PAYER).unwrap_err();
+        assert!(err.to_string().contains("not allowed"), "{}", err);
+    }
+
+    #[test]
+    fn test_rejects_writable_signer() {
+        // Spends from a second account that the fee payer doesn't control
+        let transfer = system_ix::transfer(&pubkey(OTHER), &pubkey(FEE_PAYER), 1000);
+        let err = check(&message(&[transfer])).unwrap_err();
+        assert!(err.to_string().contains(OTHER), "{}", err);
+    }
+
+    #[test]
+    fn test_rejects_ownership_transfer() {
+        let assign = system_ix::assign(&pubkey(FEE_PAYER), &pubkey(OTHER));
+        let err = check(&message(&[assign])).unwrap_err();
+        assert!(err.to_string().contains("System Assign"), "{}", err);
+
+        // Assigning an account created by the message is allowed
+        let new_account = pubkey(OTHER);
+        let msg = message(&[
+            system_ix::create_account(
+                &pubkey(FEE_PAYER),
+                &new_account,
+                2_282_880,
+                200,
+                &SYSTEM_PROGRAM_ID.parse().unwrap(),
+            ),
+            system_ix::assign(&new_account, &solana_stake_interface::program::ID),
+        ]);
+        assert!(check(&msg).is_ok());
+
+        let stake = pubkey(OTHER);
+        let authorize = |stake_authorize| {
+            solana_stake_interface::instruction::authorize(
+                &stake,
+                &pubkey(FEE_PAYER),
+                &Pubkey::new_unique(),
+                stake_authorize,
+                None,
+            )
+        };
+        assert!(check(&message(&[authorize(StakeAuthorize::Staker)])).is_ok());
+        let err = check(&message(&[authorize(StakeAuthorize::Withdrawer)])).unwrap_err();
+        assert!(err.to_string().contains("Withdrawer"), "{}", err);
+
+        let token = pubkey(TOKEN_PROGRAM_ID);
+        let set_owner = Instruction::new_with_bytes(
+            token,
+            &[TOKEN_SET_AUTHORITY, TOKEN_AUTHORITY_ACCOUNT_OWNER, 0],
+            vec![
+                solana_sdk::instruction::AccountMeta::new(stake, false),
+                solana_sdk::instruction::AccountMeta::new_readonly(pubkey(FEE_PAYER), true),
+            ],
+        );
+        let err = check(&message(&[set_owner])).unwrap_err();
+        assert!(err.to_string().contains("AccountOwner"), "{}", err);
+    }
+
+    #[test]
+    fn test_rejects_allocate_and_assign() {
+        let attacker_program = Pubkey::new_unique();
+
+        // Allocate does not create the fee payer, so assigning it is an ownership transfer
+        let msg = message(&[
+            system_ix::allocate(&pubkey(FEE_PAYER), 0),
+            system_ix::assign(&pubkey(FEE_PAYER), &attacker_program),
+        ]);
+        let err = check(&msg).unwrap_err();
+        assert!(err.to_string().contains("System Assign"), "{}", err);
+
+        // Nor does it create any other signer
+        let msg = message(&[
+            system_ix::transfer(&pubkey(FEE_PAYER), &pubkey(OTHER), 2_282_880),
+            system_ix::allocate(&pubkey(OTHER), 200),
+            system_ix::assign(&pubkey(OTHER), &attacker_program),
+        ]);
+        let err = check(&msg).unwrap_err();
+        assert!(err.to_string().contains(OTHER), "{}", err);
+    }
+
+    #[test]
+    fn test_rejects_malformed_message() {
+        let transfer = system_ix::transfer(&pubkey(FEE_PAYER), &pubkey(OTHER), 1000);
+        let mut msg = message(&[transfer]);
+        msg.instructions[0].accounts.push(42);
+        let err = check(&msg).unwrap_err();
+        assert!(err.to_string().contains("invalid account index"), "{}", err);
+
+        msg.header.num_readonly_signed_accounts = 1;
+        assert!(check(&msg).is_err());
+    }
+}
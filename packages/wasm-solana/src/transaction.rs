@@ -12,6 +12,8 @@
 //! manipulation. Base64 encoding/decoding is handled in the TypeScript layer.
 
 use crate::error::WasmSolanaError;
+use crate::limits::LimitsReport;
+use crate::versioned::{ensure_no_trailing_bytes, transaction_version, TxVersion};
 use solana_address::Address;
 use solana_signature::Signature;
 use std::str::FromStr;
@@ -22,8 +24,13 @@ pub use solana_transaction::Transaction;
 /// Extension trait for Transaction to add WASM-friendly methods.
 pub trait TransactionExt {
     /// Deserialize a transaction from raw bytes (wire format).
+    ///
+    /// Rejects versioned (v0) transactions and trailing bytes.
     fn from_bytes(bytes: &[u8]) -> Result<Transaction, WasmSolanaError>;
 
+    /// Get the wire format version (always legacy).
+    fn version(&self) -> TxVersion;
+
     /// Get the fee payer address as base58 string.
     fn fee_payer_string(&self) -> Option<String>;
 
@@ -50,12 +57,49 @@ pub trait TransactionExt {
 
     /// Get the index of a pubkey in the account keys, if it's a signer.
     fn signer_index(&self, pubkey: &str) -> Option<usize>;
+
+    /// Estimate the fee in lamports: base fee for every required signature plus the
+    /// priority fee.
+    ///
+    /// `priority_price` is the compute unit price in micro-lamports and `cu_limit` the
+    /// compute unit limit.
+    fn estimate_fee(&self, lamports_per_signature: u64, priority_price: u64, cu_limit: u32) -> u64;
+
+    /// Check that every account created by the transaction is funded at or above the
+    /// rent-exempt minimum for its data size.
+    fn check_rent_exemption(&self) -> Result<(), WasmSolanaError>;
+
+    /// Verify the message before signing: fee payer, program allowlist, writable signers
+    /// and ownership transfers. See [`crate::sanitize::sanitize_and_check`].
+    fn sanitize_and_check(
+        &self,
+        allowed_programs: &[&str],
+        expected_fee_payer: &str,
+    ) -> Result<(), WasmSolanaError>;
+
+    /// Check the packet size, account key and per-instruction account limits before
+    /// signing. See [`crate::limits::validate_limits`].
+    fn validate_limits(&self) -> LimitsReport;
 }
 
 impl TransactionExt for Transaction {
     fn from_bytes(bytes: &[u8]) -> Result<Transaction, WasmSolanaError> {
-        bincode::deserialize(bytes)
-            .map_err(|e| WasmSolanaError::new(&format!("Failed to deserialize transaction: {}", e)))
+        // A v0 message deserializes "successfully" as legacy, with the version byte
+        // read as the signer count, so the version has to be checked up front.
+        if transaction_version(bytes)? != TxVersion::Legacy {
+            return Err(WasmSolanaError::new(
+                "Transaction uses the versioned (v0) wire format; deserialize it as a versioned transaction",
+            ));
+        }
+        let tx: Transaction = bincode::deserialize(bytes).map_err(|e| {
+            WasmSolanaError::new(&format!("Failed to deserialize transaction: {}", e))
+        })?;
+        ensure_no_trailing_bytes(&tx, bytes)?;
+        Ok(tx)
+    }
+
+    fn version(&self) -> TxVersion {
+        TxVersion::Legacy
     }
 
     fn fee_payer_string(&self) -> Option<String> {
@@ -92,6 +136,31 @@ impl TransactionExt for Transaction {
         signed_keys.iter().position(|x| *x == target_address)
     }
 
+    fn estimate_fee(&self, lamports_per_signature: u64, priority_price: u64, cu_limit: u32) -> u64 {
+        crate::fee::estimate_fee(
+            &self.message,
+            lamports_per_signature,
+            priority_price,
+            cu_limit,
+        )
+    }
+
+    fn check_rent_exemption(&self) -> Result<(), WasmSolanaError> {
+        crate::fee::check_rent_exemption(&self.message)
+    }
+
+    fn sanitize_and_check(
+        &self,
+        allowed_programs: &[&str],
+        expected_fee_payer: &str,
+    ) -> Result<(), WasmSolanaError> {
+        crate::sanitize::sanitize_and_check(&self.message, allowed_programs, expected_fee_payer)
+    }
+
+    fn validate_limits(&self) -> LimitsReport {
+        crate::limits::validate_limits(&self.message)
+    }
+
     fn add_signature(
         &mut self,
         pubkey: &str,
@@ -192,6 +261,24 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_reject_versioned() {
+        let legacy = BASE64_STANDARD.decode(TEST_TX_BASE64).unwrap();
+        assert_eq!(decode_test_tx().version(), TxVersion::Legacy);
+
+        // Same message with a v0 prefix and an empty lookup table list
+        let mut v0 = legacy[..65].to_vec();
+        v0.push(0x80);
+        v0.extend_from_slice(&legacy[65..]);
+        v0.push(0x00);
+        let err = Transaction::from_bytes(&v0).unwrap_err();
+        assert!(err.to_string().contains("v0"), "{}", err);
+
+        let mut trailing = legacy.clone();
+        trailing.push(0x00);
+        assert!(Transaction::from_bytes(&trailing).is_err());
+    }
+
     #[test]
     fn test_signer_index() {
         let tx = decode_test_tx();
@@ -0,0 +1,235 @@
+//! Offline validation of withdrawal destinations and memos.
+//!
+//! Withdrawal screening only has the destination address and the amount, not the state of
+//! the account on chain. [`validate_withdrawal`] checks what can be decided from these:
+//! the address is well formed and is not a program or sysvar, a SOL withdrawal goes to an
+//! Ed25519 public key rather than a program-derived address (PDA), and the amount covers
+//! the rent-exempt minimum of the destination if the withdrawal has to create it.
+//! [`validate_memo`] checks a memo before it is added to a withdrawal.
+
+use crate::error::WasmSolanaError;
+use crate::fee::rent_exempt_minimum;
+use crate::instructions::{SYSTEM_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use crate::pubkey::{Pubkey, PubkeyExt};
+use crate::sanitize::DEFAULT_ALLOWED_PROGRAMS;
+
+/// Size of an SPL Token account (`spl_token::state::Account::LEN`)
+pub const TOKEN_ACCOUNT_SPACE: u64 = 165;
+
+/// Largest memo that fits in a SOL transfer with a durable nonce and a memo instruction.
+///
+/// The transaction (one signature, six account keys, nonce advance, transfer and a memo
+/// instruction without signers) takes 325 bytes besides the memo, out of the 1232 bytes
+/// of a packet (`PACKET_DATA_SIZE`).
+pub const MAX_MEMO_BYTES: usize = 907;
+
+/// What a withdrawal destination is expected to be
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpectedOwner {
+    /// A wallet: an account owned by the System Program
+    System,
+    /// A token account, or the wallet that owns it, of the given token program
+    Token(&'static str),
+}
+
+impl ExpectedOwner {
+    /// Parse a program ID. `"system"` and `"token"` are accepted as shorthands.
+    pub fn from_program_id(program_id: &str) -> Result<Self, WasmSolanaError> {
+        match program_id {
+            "system" | SYSTEM_PROGRAM_ID => Ok(ExpectedOwner::System),
+            "token" | TOKEN_PROGRAM_ID => Ok(ExpectedOwner::Token(TOKEN_PROGRAM_ID)),
+            TOKEN_2022_PROGRAM_ID => Ok(ExpectedOwner::Token(TOKEN_2022_PROGRAM_ID)),
+            other => Err(WasmSolanaError::new(&format!(
+                "Unsupported owner program: {}",
+                other
+            ))),
+        }
+    }
+
+    /// Size of the account data of a destination owned by this program
+    fn account_space(self) -> u64 {
+        match self {
+            ExpectedOwner::System => 0,
+            ExpectedOwner::Token(_) => TOKEN_ACCOUNT_SPACE,
+        }
+    }
+}
+
+/// Result of [`validate_withdrawal`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WithdrawalValidation {
+    /// Whether the address is an Ed25519 public key; otherwise it is a PDA
+    pub on_curve: bool,
+    /// Rent-exempt minimum of the destination account
+    pub rent_exempt_minimum: u64,
+    /// Whether the amount covers the rent-exempt minimum. If not, the withdrawal only
+    /// succeeds if the destination account already exists.
+    pub meets_rent_exempt_minimum: bool,
+}
+
+/// Validate a withdrawal destination.
+///
+/// # Arguments
+/// * `address` - Destination address (base58)
+/// * `amount_lamports` - Lamports credited to the destination: the amount of a SOL
+///   withdrawal, or the funding of the token account for a token withdrawal (0 if it
+///   exists already)
+/// * `owner_program_expected` - Program that owns the destination: System Program (or
+///   `"system"`) for SOL, SPL Token (or `"token"`) or Token-2022 for tokens
+///
+/// Fails if the address is invalid, is a program or sysvar, if a SOL withdrawal goes to
+/// a PDA (no one can sign for it unless a program does) or is zero.
+pub fn validate_withdrawal(
+    address: &str,
+    amount_lamports: u64,
+    owner_program_expected: &str,
+) -> Result<WithdrawalValidation, WasmSolanaError> {
+    let owner = ExpectedOwner::from_program_id(owner_program_expected)?;
+    let pubkey = Pubkey::from_base58(address)?;
+
+    if DEFAULT_ALLOWED_PROGRAMS.contains(&address) || address.starts_with("Sysvar") {
+        return Err(WasmSolanaError::new(&format!(
+            "Destination {} is a program or sysvar address",
+            address
+        )));
+    }
+
+    let on_curve = pubkey.is_on_curve();
+    if owner == ExpectedOwner::System {
+        if !on_curve {
+            return Err(WasmSolanaError::new(&format!(
+                "Destination {} is a program-derived address, not a wallet",
+                address
+            )));
+        }
+        if amount_lamports == 0 {
+            return Err(WasmSolanaError::new("Withdrawal amount must be positive"));
+        }
+    }
+
+    let rent_exempt_minimum = rent_exempt_minimum(owner.account_space());
+    Ok(WithdrawalValidation {
+        on_curve,
+        rent_exempt_minimum,
+        meets_rent_exempt_minimum: amount_lamports >= rent_exempt_minimum,
+    })
+}
+
+/// Validate a withdrawal memo.
+///
+/// Fails if the memo is empty, contains control characters (which exchanges strip or
+/// reject, so that the deposit is not credited) or is longer than [`MAX_MEMO_BYTES`].
+pub fn validate_memo(memo: &str) -> Result<(), WasmSolanaError> {
+    if memo.is_empty() {
+        return Err(WasmSolanaError::new("Memo is empty"));
+    }
+    if let Some((index, c)) = memo.char_indices().find(|(_, c)| c.is_control()) {
+        return Err(WasmSolanaError::new(&format!(
+            "Memo contains control character U+{:04X} at byte {}",
+            c as u32, index
+        )));
+    }
+    if memo.len() > MAX_MEMO_BYTES {
+        return Err(WasmSolanaError::new(&format!(
+            "Memo is too long: expected at most {} bytes, got {}",
+            MAX_MEMO_BYTES,
+            memo.len()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::{MEMO_PROGRAM_ID, SYSVAR_RECENT_BLOCKHASHES};
+    use crate::pubkey::derive_associated_token_address;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::instruction::Instruction;
+    use solana_sdk::message::Message;
+    use solana_sdk::transaction::Transaction;
+    use solana_system_interface::instruction as system_ix;
+    use std::str::FromStr;
+
+    const WALLET: &str = "FKjSjCqByQRwSzZoMXA7bKnDbJe41YgJTHFFzBeC42bH";
+    const MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    fn ata() -> String {
+        let (ata, _) = derive_associated_token_address(
+            &Pubkey::from_str(WALLET).unwrap(),
+            &Pubkey::from_str(MINT).unwrap(),
+            &Pubkey::from_str(TOKEN_PROGRAM_ID).unwrap(),
+        );
+        ata.to_string()
+    }
+
+    #[test]
+    fn test_sol_withdrawal() {
+        let result = validate_withdrawal(WALLET, 1_000_000_000, "system").unwrap();
+        assert!(result.on_curve);
+        assert_eq!(result.rent_exempt_minimum, 890_880);
+        assert!(result.meets_rent_exempt_minimum);
+
+        let result = validate_withdrawal(WALLET, 1_000, SYSTEM_PROGRAM_ID).unwrap();
+        assert!(!result.meets_rent_exempt_minimum);
+
+        assert!(validate_withdrawal(WALLET, 0, "system").is_err());
+        let err = validate_withdrawal(&ata(), 1_000_000_000, "system").unwrap_err();
+        assert!(err.to_string().contains("program-derived"), "{}", err);
+    }
+
+    #[test]
+    fn test_token_withdrawal() {
+        let result = validate_withdrawal(&ata(), 0, TOKEN_PROGRAM_ID).unwrap();
+        assert!(!result.on_curve);
+        assert_eq!(result.rent_exempt_minimum, 2_039_280);
+        assert!(!result.meets_rent_exempt_minimum);
+
+        let result = validate_withdrawal(WALLET, 2_039_280, TOKEN_2022_PROGRAM_ID).unwrap();
+        assert!(result.on_curve);
+        assert!(result.meets_rent_exempt_minimum);
+    }
+
+    #[test]
+    fn test_invalid_destinations() {
+        assert!(validate_withdrawal("not-an-address", 1, "system").is_err());
+        assert!(validate_withdrawal(WALLET, 1_000_000_000, "stake").is_err());
+        for address in [TOKEN_PROGRAM_ID, SYSVAR_RECENT_BLOCKHASHES] {
+            let err = validate_withdrawal(address, 1_000_000_000, "token").unwrap_err();
+            assert!(err.to_string().contains("program or sysvar"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_validate_memo() {
+        assert!(validate_memo("deposit 12345").is_ok());
+        assert!(validate_memo("").is_err());
+        let err = validate_memo("12345\n").unwrap_err();
+        assert!(err.to_string().contains("U+000A at byte 5"), "{}", err);
+        assert!(validate_memo(&"a".repeat(MAX_MEMO_BYTES)).is_ok());
+        assert!(validate_memo(&"a".repeat(MAX_MEMO_BYTES + 1)).is_err());
+    }
+
+    #[test]
+    fn test_max_memo_fits_in_packet() {
+        use solana_sdk::pubkey::Pubkey;
+
+        let fee_payer = Pubkey::from_str(WALLET).unwrap();
+        let nonce = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let memo = Instruction::new_with_bytes(
+            Pubkey::from_str(MEMO_PROGRAM_ID).unwrap(),
+            "a".repeat(MAX_MEMO_BYTES).as_bytes(),
+            vec![],
+        );
+        let instructions = [
+            system_ix::advance_nonce_account(&nonce, &fee_payer),
+            system_ix::transfer(&fee_payer, &recipient, 1_000_000_000),
+            memo,
+        ];
+        let message =
+            Message::new_with_blockhash(&instructions, Some(&fee_payer), &Hash::default());
+        let tx = Transaction::new_unsigned(message);
+        assert_eq!(bincode::serialize(&tx).unwrap().len(), 1232);
+    }
+}
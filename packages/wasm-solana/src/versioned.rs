@@ -14,6 +14,7 @@
 //! Versioned transactions have a version byte with high bit set (0x80).
 
 use crate::error::WasmSolanaError;
+use crate::limits::LimitsReport;
 use solana_address::Address;
 use solana_message::VersionedMessage;
 use solana_signature::Signature;
@@ -42,39 +43,82 @@ pub struct AddressLookupTableData {
     pub readonly_indexes: Vec<u8>,
 }
 
-/// Detect the transaction version from raw bytes.
+/// Read the wire format version of a serialized transaction.
 ///
 /// # Wire Format
 ///
-/// - Legacy: Starts with compact-u16 for signature count (values 0-127 fit in single byte)
-/// - Versioned: First byte has high bit set (0x80 = version 0)
+/// Both formats start with a compact-u16 signature count followed by the 64-byte
+/// signatures. The first message byte then tells them apart:
+///
+/// - Legacy: `num_required_signatures`, which is always below 0x80
+/// - Versioned: `0x80 | version` (0x80 = version 0)
+///
+/// Returns an error for truncated input and for versions other than 0.
+pub fn transaction_version(bytes: &[u8]) -> Result<TxVersion, WasmSolanaError> {
+    let (num_signatures, prefix_len) = decode_short_u16_len(bytes)?;
+    let message_offset = prefix_len + num_signatures * 64;
+    let first_message_byte = *bytes.get(message_offset).ok_or_else(|| {
+        WasmSolanaError::new("Transaction is truncated: missing message after signatures")
+    })?;
+
+    if first_message_byte & 0x80 == 0 {
+        return Ok(TxVersion::Legacy);
+    }
+    match first_message_byte & 0x7f {
+        0 => Ok(TxVersion::V0),
+        version => Err(WasmSolanaError::new(&format!(
+            "Unsupported transaction version: {}",
+            version
+        ))),
+    }
+}
+
+/// Detect the transaction version from raw bytes.
 ///
-/// The version byte comes AFTER the signatures array in versioned transactions,
-/// but the signature count encoding differs.
+/// Lenient variant of [`transaction_version`]: input that cannot be read is reported
+/// as legacy and fails later with a proper deserialization error.
 pub fn detect_transaction_version(bytes: &[u8]) -> TxVersion {
-    // Versioned transactions use a specific serialization format where
-    // after the signatures, the message version byte has high bit set.
-    //
-    // However, detecting this reliably requires parsing the signature count first.
-    // For simplicity, we try to deserialize as VersionedTransaction which handles both.
-    //
-    // The solana-transaction crate's VersionedTransaction can deserialize both formats.
-    // We detect based on the deserialized message type.
-
-    if let Ok(tx) = bincode::deserialize::<VersionedTransaction>(bytes) {
-        match tx.message {
-            VersionedMessage::Legacy(_) => TxVersion::Legacy,
-            VersionedMessage::V0(_) => TxVersion::V0,
+    transaction_version(bytes).unwrap_or(TxVersion::Legacy)
+}
+
+/// Decode a compact-u16 (shortvec) length prefix, returning (value, bytes_consumed).
+fn decode_short_u16_len(bytes: &[u8]) -> Result<(usize, usize), WasmSolanaError> {
+    let mut value = 0usize;
+    for (i, byte) in bytes.iter().take(3).enumerate() {
+        value |= ((byte & 0x7f) as usize) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
         }
-    } else {
-        // If we can't deserialize, assume legacy (will fail later with proper error)
-        TxVersion::Legacy
     }
+    Err(WasmSolanaError::new(
+        "Transaction is truncated: invalid signature count",
+    ))
+}
+
+/// Reject input with bytes left over after deserialization.
+///
+/// bincode ignores trailing bytes, which would let the same transaction be encoded in
+/// more than one way.
+pub(crate) fn ensure_no_trailing_bytes<T: serde::Serialize>(
+    value: &T,
+    bytes: &[u8],
+) -> Result<(), WasmSolanaError> {
+    let consumed = bincode::serialized_size(value)
+        .map_err(|e| WasmSolanaError::new(&format!("Failed to size transaction: {}", e)))?;
+    if consumed != bytes.len() as u64 {
+        return Err(WasmSolanaError::new(&format!(
+            "Transaction has {} trailing bytes",
+            bytes.len() as u64 - consumed
+        )));
+    }
+    Ok(())
 }
 
 /// Extension trait for VersionedTransaction to add WASM-friendly methods.
 pub trait VersionedTransactionExt {
     /// Deserialize a transaction from raw bytes (handles both legacy and versioned).
+    ///
+    /// Rejects unsupported message versions and trailing bytes.
     fn from_bytes(bytes: &[u8]) -> Result<VersionedTransaction, WasmSolanaError>;
 
     /// Check if this is a versioned transaction (MessageV0).
@@ -112,16 +156,23 @@ pub trait VersionedTransactionExt {
 
     /// Get the index of a pubkey in the static account keys, if it's a signer.
     fn signer_index(&self, pubkey: &str) -> Option<usize>;
+
+    /// Check the packet size, account key and per-instruction account limits before
+    /// signing. See [`crate::limits::validate_versioned_limits`].
+    fn validate_limits(&self) -> LimitsReport;
 }
 
 impl VersionedTransactionExt for VersionedTransaction {
     fn from_bytes(bytes: &[u8]) -> Result<VersionedTransaction, WasmSolanaError> {
-        bincode::deserialize(bytes).map_err(|e| {
+        transaction_version(bytes)?;
+        let tx: VersionedTransaction = bincode::deserialize(bytes).map_err(|e| {
             WasmSolanaError::new(&format!(
                 "Failed to deserialize versioned transaction: {}",
                 e
             ))
-        })
+        })?;
+        ensure_no_trailing_bytes(&tx, bytes)?;
+        Ok(tx)
     }
 
     fn is_versioned(&self) -> bool {
@@ -211,6 +262,10 @@ impl VersionedTransactionExt for VersionedTransaction {
         signed_keys.iter().position(|x| *x == target_address)
     }
 
+    fn validate_limits(&self) -> LimitsReport {
+        crate::limits::validate_versioned_limits(&self.message)
+    }
+
     fn add_signature(
         &mut self,
         pubkey: &str,
@@ -327,6 +382,56 @@ mod tests {
         assert_eq!(tx.num_instructions(), tx2.num_instructions());
     }
 
+    /// The legacy test transaction re-encoded as a v0 message without lookup tables
+    fn v0_tx_bytes() -> Vec<u8> {
+        let legacy = BASE64_STANDARD.decode(LEGACY_TX_BASE64).unwrap();
+        // 1 signature: compact-u16 count byte + 64 signature bytes
+        let mut bytes = legacy[..65].to_vec();
+        bytes.push(0x80);
+        bytes.extend_from_slice(&legacy[65..]);
+        bytes.push(0x00);
+        bytes
+    }
+
+    #[test]
+    fn test_transaction_version() {
+        let legacy = BASE64_STANDARD.decode(LEGACY_TX_BASE64).unwrap();
+        assert_eq!(transaction_version(&legacy).unwrap(), TxVersion::Legacy);
+
+        let v0 = v0_tx_bytes();
+        assert_eq!(transaction_version(&v0).unwrap(), TxVersion::V0);
+        assert_eq!(detect_transaction_version(&v0), TxVersion::V0);
+
+        let mut v1 = v0.clone();
+        v1[65] = 0x81;
+        assert!(transaction_version(&v1)
+            .unwrap_err()
+            .to_string()
+            .contains("Unsupported transaction version: 1"));
+
+        assert!(transaction_version(&legacy[..65]).is_err());
+        assert!(transaction_version(&[]).is_err());
+    }
+
+    #[test]
+    fn test_v0_roundtrip() {
+        let bytes = v0_tx_bytes();
+        let tx = VersionedTransaction::from_bytes(&bytes).unwrap();
+        assert_eq!(tx.tx_version(), TxVersion::V0);
+        assert_eq!(tx.to_bytes().unwrap(), bytes);
+        assert_eq!(tx.fee_payer_string(), decode_legacy_tx().fee_payer_string());
+    }
+
+    #[test]
+    fn test_trailing_bytes_rejected() {
+        let mut bytes = v0_tx_bytes();
+        bytes.push(0x00);
+        assert!(VersionedTransaction::from_bytes(&bytes)
+            .unwrap_err()
+            .to_string()
+            .contains("trailing"));
+    }
+
     #[test]
     fn test_add_signature() {
         let mut tx = decode_legacy_tx();
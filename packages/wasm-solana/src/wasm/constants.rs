@@ -7,9 +7,9 @@ use wasm_bindgen::prelude::*;
 
 // Use re-exported constants from instructions module
 use crate::instructions::{
-    ATA_PROGRAM_ID, COMPUTE_BUDGET_PROGRAM_ID, MEMO_PROGRAM_ID, STAKE_POOL_PROGRAM_ID,
-    STAKE_PROGRAM_ID, SYSTEM_PROGRAM_ID, SYSVAR_RECENT_BLOCKHASHES, TOKEN_2022_PROGRAM_ID,
-    TOKEN_PROGRAM_ID,
+    ATA_PROGRAM_ID, COMPUTE_BUDGET_PROGRAM_ID, MEMO_PROGRAM_ID, SQUADS_MULTISIG_PROGRAM_ID,
+    STAKE_POOL_PROGRAM_ID, STAKE_PROGRAM_ID, SYSTEM_PROGRAM_ID, SYSVAR_RECENT_BLOCKHASHES,
+    TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
 };
 
 /// System Program ID
@@ -60,6 +60,12 @@ pub fn stake_pool_program_id() -> String {
     STAKE_POOL_PROGRAM_ID.to_string()
 }
 
+/// Squads Multisig (v4) Program ID
+#[wasm_bindgen]
+pub fn squads_multisig_program_id() -> String {
+    SQUADS_MULTISIG_PROGRAM_ID.to_string()
+}
+
 /// Sysvar Recent Blockhashes address
 /// Reference: https://github.com/solana-labs/solana/blob/v1.18.26/sdk/program/src/sysvar/recent_blockhashes.rs
 #[wasm_bindgen]
@@ -94,7 +100,7 @@ pub fn get_associated_token_address(
     mint_address: &str,
     token_program_id: &str,
 ) -> Result<String, JsValue> {
-    use solana_sdk::pubkey::Pubkey;
+    use crate::pubkey::{derive_associated_token_address, Pubkey};
 
     let wallet: Pubkey = wallet_address
         .parse()
@@ -106,13 +112,7 @@ pub fn get_associated_token_address(
         JsValue::from_str(&format!("Invalid token program ID: {}", token_program_id))
     })?;
 
-    // ATA PDA derivation: seeds = [wallet, token_program, mint], program = ATA_PROGRAM
-    let ata_program: Pubkey = ATA_PROGRAM_ID
-        .parse()
-        .map_err(|_| JsValue::from_str("Failed to parse ATA program ID"))?;
-
-    let seeds = &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()];
-    let (ata, _bump) = Pubkey::find_program_address(seeds, &ata_program);
+    let (ata, _bump) = derive_associated_token_address(&wallet, &mint, &token_program);
 
     Ok(ata.to_string())
 }
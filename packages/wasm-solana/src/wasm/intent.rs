@@ -73,4 +73,20 @@ impl IntentNamespace {
 
         Ok(obj.into())
     }
+
+    /// Plan an SPL token transfer.
+    ///
+    /// Returns the instructions in `customTx` format, creating the recipient token
+    /// account with `CreateIdempotent` unless it is listed in `existingTokenAccounts`.
+    #[wasm_bindgen]
+    pub fn plan_token_transfer(request: JsValue) -> Result<JsValue, JsValue> {
+        let request: intent::TokenTransferRequest = serde_wasm_bindgen::from_value(request)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse request: {}", e)))?;
+
+        let plan =
+            intent::plan_token_transfer(&request).map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&plan)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize plan: {}", e)))
+    }
 }
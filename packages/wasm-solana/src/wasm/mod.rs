@@ -5,6 +5,7 @@ mod parser;
 mod pubkey;
 mod transaction;
 pub mod try_into_js_value;
+mod validation;
 mod versioned_builder;
 
 pub use intent::IntentNamespace;
@@ -12,6 +13,7 @@ pub use keypair::WasmKeypair;
 pub use parser::ParserNamespace;
 pub use pubkey::WasmPubkey;
 pub use transaction::{is_versioned_transaction, WasmTransaction, WasmVersionedTransaction};
+pub use validation::{validate_memo, validate_withdrawal};
 pub use versioned_builder::BuilderNamespace;
 
 // Re-export constants functions
@@ -3,7 +3,8 @@
 //! Wraps `solana_pubkey::Pubkey` for JavaScript.
 
 use crate::error::WasmSolanaError;
-use crate::pubkey::{Pubkey, PubkeyExt};
+use crate::js_obj;
+use crate::pubkey::{derive_associated_token_address, Pubkey, PubkeyExt};
 use wasm_bindgen::prelude::*;
 
 /// WASM wrapper for Solana public key (address).
@@ -51,6 +52,62 @@ impl WasmPubkey {
     pub fn is_on_curve(&self) -> bool {
         self.inner.is_on_curve()
     }
+
+    /// Find the program-derived address (PDA) of `seeds` under `program_id`.
+    ///
+    /// @param seeds - Array of Uint8Array seeds (at most 15, each up to 32 bytes)
+    /// @param program_id - Program that owns the address
+    /// @returns `{ address, bump }` with the base58 PDA and its bump seed
+    #[wasm_bindgen]
+    pub fn find_program_address(
+        seeds: js_sys::Array,
+        program_id: &WasmPubkey,
+    ) -> Result<JsValue, JsValue> {
+        let seeds = seeds
+            .iter()
+            .enumerate()
+            .map(|(i, seed)| {
+                seed.dyn_into::<js_sys::Uint8Array>()
+                    .map(|seed| seed.to_vec())
+                    .map_err(|_| WasmSolanaError::new(&format!("Seed {} is not a Uint8Array", i)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let seed_refs: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+        let (address, bump) = Pubkey::find_program_address_checked(&seed_refs, &program_id.inner)?;
+        Ok(js_obj!("address" => address.to_string(), "bump" => bump)?)
+    }
+
+    /// Derive the address of an account created with `createAccountWithSeed`.
+    ///
+    /// @param base - Base account that signs the account creation
+    /// @param seed - Seed string (at most 32 bytes)
+    /// @param owner - Program that will own the account
+    #[wasm_bindgen]
+    pub fn create_with_seed(
+        base: &WasmPubkey,
+        seed: &str,
+        owner: &WasmPubkey,
+    ) -> Result<WasmPubkey, WasmSolanaError> {
+        Pubkey::create_with_seed_checked(&base.inner, seed, &owner.inner)
+            .map(|inner| WasmPubkey { inner })
+    }
+
+    /// Derive the Associated Token Account address of `owner` for `mint`.
+    ///
+    /// @param owner - Owner wallet address
+    /// @param mint - Token mint address
+    /// @param token_program - Token program (SPL Token or Token-2022)
+    /// @returns `{ address, bump }` with the base58 ATA address and its bump seed
+    #[wasm_bindgen]
+    pub fn derive_associated_token_address(
+        owner: &WasmPubkey,
+        mint: &WasmPubkey,
+        token_program: &WasmPubkey,
+    ) -> Result<JsValue, JsValue> {
+        let (address, bump) =
+            derive_associated_token_address(&owner.inner, &mint.inner, &token_program.inner);
+        Ok(js_obj!("address" => address.to_string(), "bump" => bump)?)
+    }
 }
 
 impl WasmPubkey {
@@ -6,9 +6,12 @@
 //! use `ParserNamespace.parse_transaction()` instead.
 
 use crate::error::WasmSolanaError;
+use crate::js_obj;
+use crate::limits::{LimitViolation, LimitsReport};
 use crate::transaction::{Transaction, TransactionExt};
 use crate::versioned::{detect_transaction_version, TxVersion, VersionedTransactionExt};
 use crate::wasm::keypair::WasmKeypair;
+use crate::wasm::try_into_js_value::{JsConversionError, TryIntoJsValue};
 use solana_message::VersionedMessage;
 use solana_sdk::bs58;
 use solana_transaction::versioned::VersionedTransaction;
@@ -32,6 +35,15 @@ impl WasmTransaction {
         Transaction::from_bytes(bytes).map(|inner| WasmTransaction { inner })
     }
 
+    /// Get the wire format version (always `"legacy"`).
+    ///
+    /// Versioned transactions are rejected by `from_bytes`; use
+    /// `WasmVersionedTransaction` for those.
+    #[wasm_bindgen(getter)]
+    pub fn version(&self) -> JsValue {
+        version_to_js(self.inner.version())
+    }
+
     /// Get the fee payer address as a base58 string.
     ///
     /// Returns `null` if there are no account keys (shouldn't happen for valid transactions).
@@ -136,6 +148,70 @@ impl WasmTransaction {
         self.inner.signer_index(pubkey)
     }
 
+    /// Estimate the transaction fee in lamports.
+    ///
+    /// @param lamports_per_signature - Base fee per required signature (5000 on mainnet)
+    /// @param priority_price - Compute unit price in micro-lamports
+    /// @param cu_limit - Compute unit limit
+    /// @returns Base fee plus priority fee, in lamports
+    #[wasm_bindgen]
+    pub fn estimate_fee(
+        &self,
+        lamports_per_signature: u64,
+        priority_price: u64,
+        cu_limit: u32,
+    ) -> u64 {
+        self.inner
+            .estimate_fee(lamports_per_signature, priority_price, cu_limit)
+    }
+
+    /// Check that every account created by this transaction is rent exempt.
+    ///
+    /// Throws if a `CreateAccount`/`CreateAccountWithSeed` instruction funds the new
+    /// account below the rent-exempt minimum for its data size.
+    #[wasm_bindgen]
+    pub fn check_rent_exemption(&self) -> Result<(), WasmSolanaError> {
+        self.inner.check_rent_exemption()
+    }
+
+    /// Verify this transaction before signing it.
+    ///
+    /// Throws if the fee payer is not `expected_fee_payer`, an instruction calls a program
+    /// outside `allowed_programs`, another account is a writable signer without being
+    /// created by the transaction, or an instruction transfers account ownership.
+    ///
+    /// @param allowed_programs - Allowed program IDs (base58); defaults to the programs
+    ///   used by the transaction builders when omitted
+    /// @param expected_fee_payer - Expected fee payer address (base58)
+    #[wasm_bindgen]
+    pub fn sanitize_and_check(
+        &self,
+        allowed_programs: Option<Vec<String>>,
+        expected_fee_payer: &str,
+    ) -> Result<(), WasmSolanaError> {
+        match allowed_programs {
+            Some(programs) => {
+                let programs: Vec<&str> = programs.iter().map(String::as_str).collect();
+                self.inner.sanitize_and_check(&programs, expected_fee_payer)
+            }
+            None => self.inner.sanitize_and_check(
+                crate::sanitize::DEFAULT_ALLOWED_PROGRAMS,
+                expected_fee_payer,
+            ),
+        }
+    }
+
+    /// Check the size and account limits of this transaction before signing it.
+    ///
+    /// @returns `{ size, accountKeys, bytesOver, violations }`, where each violation is
+    ///   `{ kind, message, limit, size?, bytesOver?, count?, instructionIndex? }` and
+    ///   `kind` is `"transactionTooLarge"`, `"tooManyAccountKeys"` or
+    ///   `"tooManyInstructionAccounts"`
+    #[wasm_bindgen]
+    pub fn validate_limits(&self) -> Result<JsValue, JsValue> {
+        Ok(self.inner.validate_limits().try_to_js_value()?)
+    }
+
     /// Sign this transaction with a `WasmKeypair`.
     ///
     /// Signs the transaction message and places the signature at the correct
@@ -222,10 +298,69 @@ impl WasmTransaction {
     }
 }
 
+// ============================================================================
+// Limits
+// ============================================================================
+
+impl TryIntoJsValue for LimitViolation {
+    fn try_to_js_value(&self) -> Result<JsValue, JsConversionError> {
+        let message = self.to_string();
+        match *self {
+            LimitViolation::TransactionTooLarge {
+                size,
+                limit,
+                bytes_over,
+            } => js_obj!(
+                "kind" => "transactionTooLarge",
+                "message" => message,
+                "size" => size as u32,
+                "limit" => limit as u32,
+                "bytesOver" => bytes_over as u32,
+            ),
+            LimitViolation::TooManyAccountKeys { count, limit } => js_obj!(
+                "kind" => "tooManyAccountKeys",
+                "message" => message,
+                "count" => count as u32,
+                "limit" => limit as u32,
+            ),
+            LimitViolation::TooManyInstructionAccounts {
+                instruction_index,
+                count,
+                limit,
+            } => js_obj!(
+                "kind" => "tooManyInstructionAccounts",
+                "message" => message,
+                "instructionIndex" => instruction_index as u32,
+                "count" => count as u32,
+                "limit" => limit as u32,
+            ),
+        }
+    }
+}
+
+impl TryIntoJsValue for LimitsReport {
+    fn try_to_js_value(&self) -> Result<JsValue, JsConversionError> {
+        js_obj!(
+            "size" => self.size as u32,
+            "accountKeys" => self.account_keys as u32,
+            "bytesOver" => self.bytes_over() as u32,
+            "violations" => self.violations.clone(),
+        )
+    }
+}
+
 // ============================================================================
 // Versioned Transaction Support
 // ============================================================================
 
+/// Version as a JavaScript value, following @solana/web3.js (`"legacy"` or `0`)
+fn version_to_js(version: TxVersion) -> JsValue {
+    match version {
+        TxVersion::Legacy => "legacy".into(),
+        TxVersion::V0 => 0.into(),
+    }
+}
+
 /// Detect if transaction bytes represent a versioned transaction.
 ///
 /// @param bytes - Raw transaction bytes
@@ -262,6 +397,12 @@ impl WasmVersionedTransaction {
         self.inner.is_versioned()
     }
 
+    /// Get the wire format version: `"legacy"` or `0`.
+    #[wasm_bindgen(getter)]
+    pub fn version(&self) -> JsValue {
+        version_to_js(self.inner.tx_version())
+    }
+
     /// Get the fee payer address as a base58 string.
     #[wasm_bindgen(getter)]
     pub fn fee_payer(&self) -> Option<String> {
@@ -388,6 +529,16 @@ impl WasmVersionedTransaction {
         self.inner.signer_index(pubkey)
     }
 
+    /// Check the size and account limits of this transaction before signing it.
+    ///
+    /// Accounts loaded from address lookup tables count towards the account key limit.
+    ///
+    /// @returns The same report as `WasmTransaction.validate_limits`
+    #[wasm_bindgen]
+    pub fn validate_limits(&self) -> Result<JsValue, JsValue> {
+        Ok(self.inner.validate_limits().try_to_js_value()?)
+    }
+
     /// Get all instructions as an array.
     ///
     /// Note: For versioned transactions with ALTs, account indices may
@@ -0,0 +1,35 @@
+//! WASM bindings for withdrawal validation.
+
+use crate::error::WasmSolanaError;
+use crate::js_obj;
+use wasm_bindgen::prelude::*;
+
+/// Validate a withdrawal destination.
+///
+/// @param address - Destination address (base58)
+/// @param amount_lamports - Lamports credited to the destination (SOL amount, or the
+///   funding of the token account for a token withdrawal)
+/// @param owner_program_expected - Program that owns the destination: System Program,
+///   SPL Token or Token-2022 ID, or the shorthands "system" and "token"
+/// @returns `{ onCurve, rentExemptMinimum, meetsRentExemptMinimum }`
+#[wasm_bindgen]
+pub fn validate_withdrawal(
+    address: &str,
+    amount_lamports: u64,
+    owner_program_expected: &str,
+) -> Result<JsValue, JsValue> {
+    let result =
+        crate::validation::validate_withdrawal(address, amount_lamports, owner_program_expected)?;
+    Ok(js_obj!(
+        "onCurve" => result.on_curve,
+        "rentExemptMinimum" => result.rent_exempt_minimum,
+        "meetsRentExemptMinimum" => result.meets_rent_exempt_minimum,
+    )?)
+}
+
+/// Validate a withdrawal memo. Throws if it is empty, contains control characters or
+/// does not fit in a transaction.
+#[wasm_bindgen]
+pub fn validate_memo(memo: &str) -> Result<(), WasmSolanaError> {
+    crate::validation::validate_memo(memo)
+}
@@ -8,7 +8,12 @@
 /* eslint-disable @typescript-eslint/no-explicit-any, @typescript-eslint/no-unsafe-member-access, @typescript-eslint/no-unsafe-argument */
 
 import assert from "assert";
-import { buildFromIntent, Transaction, parseTransaction } from "../dist/cjs/js/index.js";
+import {
+  buildFromIntent,
+  planTokenTransfer,
+  Transaction,
+  parseTransaction,
+} from "../dist/cjs/js/index.js";
 
 describe("buildFromIntent", function () {
   // Common test params
@@ -621,3 +626,40 @@ describe("buildFromIntent", function () {
     });
   });
 });
+
+describe("planTokenTransfer", function () {
+  const request = {
+    owner: "DgT9qyYwYKBRDyDw3EfR12LHQCQjtNrKu2qMsXHuosmB",
+    recipient: "FKjSjCqByQRwSzZoMXA7bKnDbJe41YgJTHFFzBeC42bH",
+    mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+    amount: 2500000n,
+    decimalPlaces: 6,
+  };
+
+  it("should create an unknown recipient account idempotently", function () {
+    const plan = planTokenTransfer(request);
+    assert.equal(plan.createsDestination, true);
+    assert.equal(plan.instructions.length, 2);
+    // CreateIdempotent instruction data is [1]
+    assert.equal(plan.instructions[0].data, "AQ==");
+  });
+
+  it("should skip creating existing accounts", function () {
+    const { destination } = planTokenTransfer(request);
+    const plan = planTokenTransfer({ ...request, existingTokenAccounts: [destination] });
+    assert.equal(plan.createsDestination, false);
+    assert.equal(plan.destination, destination);
+    assert.equal(plan.instructions.length, 1);
+  });
+
+  it("should build a customTx intent from the plan", function () {
+    const plan = planTokenTransfer(request);
+    const intent = { intentType: "customTx", solInstructions: plan.instructions };
+    const result = buildFromIntent(intent, {
+      feePayer: request.owner,
+      nonce: { type: "blockhash", value: "GWaQEymC3Z9SHM2gkh8u12xL1zJPMHPCSVR3pSDpEXE4" },
+    });
+    const parsed = parseTransaction(result.transaction);
+    assert.equal(parsed.instructionsData.length, 2);
+  });
+});
@@ -1,6 +1,14 @@
 import * as assert from "assert";
 import { Pubkey } from "../js/pubkey.js";
 import { Keypair } from "../js/keypair.js";
+import {
+  findWithdrawAuthorityProgramAddress,
+  getAssociatedTokenAddress,
+  stakePoolProgramId,
+  stakeProgramId,
+  token2022ProgramId,
+  tokenProgramId,
+} from "../js/index.js";
 
 describe("Pubkey", () => {
   const testAddress = "11111111111111111111111111111111";
@@ -41,4 +49,52 @@ describe("Pubkey", () => {
 
     assert.strictEqual(pubkey.isOnCurve(), true);
   });
+
+  it("should find program addresses", () => {
+    const programId = Pubkey.fromBase58(stakePoolProgramId());
+    const stakePool = Pubkey.fromBase58("Jito4APyf642JPZPx3hGc6WWJ8zPKtRbRs4P815Awbb");
+
+    const { address, bump } = Pubkey.findProgramAddress(
+      [stakePool.toBytes(), new TextEncoder().encode("withdraw")],
+      programId,
+    );
+    assert.strictEqual(
+      address.toBase58(),
+      findWithdrawAuthorityProgramAddress(stakePool.toBase58()),
+    );
+    assert.strictEqual(address.isOnCurve(), false);
+    assert.ok(bump >= 0 && bump <= 255);
+
+    assert.throws(() => Pubkey.findProgramAddress([new Uint8Array(33)], programId), /too long/);
+  });
+
+  it("should create addresses with seed", () => {
+    const base = Pubkey.fromBase58("FKjSjCqByQRwSzZoMXA7bKnDbJe41YgJTHFFzBeC42bH");
+    const owner = Pubkey.fromBase58(stakeProgramId());
+
+    const address = Pubkey.createWithSeed(base, "stake:0", owner);
+    assert.ok(address.equals(Pubkey.createWithSeed(base, "stake:0", owner)));
+    assert.ok(!address.equals(Pubkey.createWithSeed(base, "stake:1", owner)));
+    assert.throws(() => Pubkey.createWithSeed(base, "x".repeat(33), owner), /Invalid seed/);
+  });
+
+  it("should derive associated token addresses", () => {
+    const owner = Pubkey.fromBase58("FKjSjCqByQRwSzZoMXA7bKnDbJe41YgJTHFFzBeC42bH");
+    const mint = Pubkey.fromBase58("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+
+    const { address, bump } = Pubkey.deriveAssociatedTokenAddress(owner, mint);
+    assert.strictEqual(
+      address.toBase58(),
+      getAssociatedTokenAddress(owner.toBase58(), mint.toBase58(), tokenProgramId()),
+    );
+    assert.strictEqual(address.isOnCurve(), false);
+    assert.ok(bump >= 0 && bump <= 255);
+
+    const token2022 = Pubkey.deriveAssociatedTokenAddress(
+      owner,
+      mint,
+      Pubkey.fromBase58(token2022ProgramId()),
+    );
+    assert.ok(!token2022.address.equals(address));
+  });
 });
@@ -126,6 +126,48 @@ describe("Transaction", () => {
     assert.strictEqual(instr.programId, "11111111111111111111111111111111");
   });
 
+  describe("estimateFee", () => {
+    it("should charge the base fee per signature plus the priority fee", () => {
+      const tx = Transaction.fromBytes(TEST_TX_BYTES);
+      assert.strictEqual(tx.estimateFee(5000n), 5000n);
+      // 200_000 CU at 1 micro-lamport each rounds up to 1 lamport
+      assert.strictEqual(tx.estimateFee(5000n, 1n, 200_000), 5001n);
+      assert.strictEqual(tx.estimateFee(5000n, 50_000n, 200_000), 15000n);
+    });
+  });
+
+  it("should pass the rent exemption check without created accounts", () => {
+    const tx = Transaction.fromBytes(TEST_TX_BYTES);
+    assert.doesNotThrow(() => tx.checkRentExemption());
+  });
+
+  describe("validateLimits", () => {
+    it("should report the signed size of a transaction within the limits", () => {
+      const tx = Transaction.fromBytes(TEST_TX_BYTES);
+      const report = tx.validateLimits();
+      assert.strictEqual(report.size, TEST_TX_BYTES.length);
+      assert.strictEqual(report.accountKeys, tx.accountKeys().length);
+      assert.strictEqual(report.bytesOver, 0);
+      assert.deepStrictEqual(report.violations, []);
+    });
+  });
+
+  describe("sanitizeAndCheck", () => {
+    it("should accept a transfer from the expected fee payer", () => {
+      const tx = Transaction.fromBytes(TEST_TX_BYTES);
+      assert.doesNotThrow(() => tx.sanitizeAndCheck(tx.feePayer as string));
+    });
+
+    it("should reject an unexpected fee payer or program", () => {
+      const tx = Transaction.fromBytes(TEST_TX_BYTES);
+      assert.throws(
+        () => tx.sanitizeAndCheck("11111111111111111111111111111111"),
+        /Unexpected fee payer/,
+      );
+      assert.throws(() => tx.sanitizeAndCheck(tx.feePayer as string, []), /not allowed/);
+    });
+  });
+
   describe("id getter", () => {
     it("should return undefined for unsigned transaction", () => {
       const tx = Transaction.fromBytes(TEST_TX_BYTES);
@@ -0,0 +1,53 @@
+import * as assert from "assert";
+import {
+  getAssociatedTokenAddress,
+  tokenProgramId,
+  validateMemo,
+  validateWithdrawal,
+} from "../js/index.js";
+
+describe("validation", () => {
+  const wallet = "DgT9qyYwYKBRDyDw3EfR12LHQCQjtNrKu2qMsXHuosmB";
+  const usdcMint = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+  describe("validateWithdrawal", () => {
+    it("should accept a SOL withdrawal to a wallet", () => {
+      assert.deepStrictEqual(validateWithdrawal(wallet, 1000000000n, "system"), {
+        onCurve: true,
+        rentExemptMinimum: 890880n,
+        meetsRentExemptMinimum: true,
+      });
+    });
+
+    it("should report an amount below the rent-exempt minimum", () => {
+      const result = validateWithdrawal(wallet, 1000n, "system");
+      assert.strictEqual(result.meetsRentExemptMinimum, false);
+    });
+
+    it("should reject a SOL withdrawal to a program-derived address", () => {
+      const ata = getAssociatedTokenAddress(wallet, usdcMint, tokenProgramId());
+      assert.throws(() => validateWithdrawal(ata, 1000000000n, "system"), /program-derived/);
+      const result = validateWithdrawal(ata, 0n, tokenProgramId());
+      assert.strictEqual(result.onCurve, false);
+      assert.strictEqual(result.rentExemptMinimum, 2039280n);
+    });
+
+    it("should reject program addresses", () => {
+      assert.throws(
+        () => validateWithdrawal(tokenProgramId(), 1000000000n, "token"),
+        /program or sysvar/,
+      );
+    });
+  });
+
+  describe("validateMemo", () => {
+    it("should accept a plain memo", () => {
+      validateMemo("deposit 12345");
+    });
+
+    it("should reject empty memos and control characters", () => {
+      assert.throws(() => validateMemo(""), /empty/);
+      assert.throws(() => validateMemo("12345\n"), /control character/);
+    });
+  });
+});
@@ -1,5 +1,6 @@
 import * as assert from "assert";
 import { VersionedTransaction, isVersionedTransaction } from "../js/versioned.js";
+import { Transaction } from "../js/transaction.js";
 
 /** Helper to decode base64 in tests (Buffer is allowed in tests) */
 function base64ToBytes(base64: string): Uint8Array {
@@ -23,6 +24,44 @@ describe("VersionedTransaction", () => {
     });
   });
 
+  describe("wire format version", () => {
+    /** The legacy transaction re-encoded as a v0 message without lookup tables */
+    function toV0(legacy: Uint8Array): Uint8Array {
+      return Uint8Array.from([...legacy.subarray(0, 65), 0x80, ...legacy.subarray(65), 0x00]);
+    }
+
+    it("should report the version", () => {
+      const legacy = base64ToBytes(LEGACY_TX_BASE64);
+      assert.strictEqual(VersionedTransaction.fromBytes(legacy).version, "legacy");
+      assert.strictEqual(Transaction.fromBytes(legacy).version, "legacy");
+
+      const v0 = toV0(legacy);
+      assert.strictEqual(isVersionedTransaction(v0), true);
+      assert.strictEqual(VersionedTransaction.fromBytes(v0).version, 0);
+    });
+
+    it("should validate the limits of a v0 transaction", () => {
+      const legacy = base64ToBytes(LEGACY_TX_BASE64);
+      const v0 = toV0(legacy);
+      const report = VersionedTransaction.fromBytes(v0).validateLimits();
+      assert.strictEqual(report.size, v0.length);
+      assert.strictEqual(report.accountKeys, Transaction.fromBytes(legacy).accountKeys().length);
+      assert.strictEqual(report.bytesOver, 0);
+      assert.deepStrictEqual(report.violations, []);
+    });
+
+    it("should reject v0 bytes as a legacy transaction", () => {
+      const v0 = toV0(base64ToBytes(LEGACY_TX_BASE64));
+      assert.throws(() => Transaction.fromBytes(v0), /versioned \(v0\) wire format/);
+    });
+
+    it("should reject trailing bytes", () => {
+      const bytes = Uint8Array.from([...base64ToBytes(LEGACY_TX_BASE64), 0]);
+      assert.throws(() => Transaction.fromBytes(bytes), /trailing bytes/);
+      assert.throws(() => VersionedTransaction.fromBytes(bytes), /trailing bytes/);
+    });
+  });
+
   describe("legacy transaction parsing", () => {
     it("should parse legacy transaction as versioned", () => {
       const tx = VersionedTransaction.fromBytes(base64ToBytes(LEGACY_TX_BASE64));
@@ -18,3 +18,20 @@ export function fromOutputScriptWithCoin(
 ): string {
   return AddressNamespace.from_output_script_with_coin(script, coin, format);
 }
+
+/**
+ * Convert a Bitcoin Cash or eCash address to cashaddr format.
+ * Accepts legacy (base58check) or cashaddr input; the cashaddr prefix is optional.
+ * The result always includes the prefix (e.g. `bitcoincash:`).
+ */
+export function toCashaddrWithCoin(address: string, coin: CoinName): string {
+  return AddressNamespace.to_cashaddr_with_coin(address, coin);
+}
+
+/**
+ * Convert a Bitcoin Cash or eCash address to legacy (base58check) format.
+ * Accepts legacy or cashaddr input; the cashaddr prefix is optional.
+ */
+export function toLegacyAddressWithCoin(address: string, coin: CoinName): string {
+  return AddressNamespace.to_legacy_address_with_coin(address, coin);
+}
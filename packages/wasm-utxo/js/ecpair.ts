@@ -1,4 +1,6 @@
 import { WasmECPair } from "./wasm/wasm_utxo.js";
+import type { CoinName } from "./coinName.js";
+import type { UtxolibName } from "./utxolibCompat.js";
 
 /**
  * ECPairArg represents the various forms that ECPair keys can take
@@ -12,6 +14,18 @@ export type ECPairArg =
   /** WasmECPair instance */
   | WasmECPair;
 
+/**
+ * Information decoded from a WIF string
+ */
+export type WIFInfo = {
+  /** Whether the WIF marks the public key as compressed */
+  compressed: boolean;
+  /** The WIF version byte */
+  version: number;
+  /** Coin names of all networks using this version byte */
+  networks: CoinName[];
+};
+
 /**
  * ECPair interface for elliptic curve key pair operations
  */
@@ -114,6 +128,28 @@ export class ECPair implements ECPairInterface {
     return new ECPair(wasm);
   }
 
+  /**
+   * Create an ECPair from a WIF string, checking the version byte against a network
+   * @param wifString - The WIF-encoded private key string
+   * @param network - The network (coin name or utxolib name) the WIF must belong to
+   * @returns An ECPair instance
+   */
+  static fromWIFWithNetwork(wifString: string, network: CoinName | UtxolibName): ECPair {
+    const wasm = WasmECPair.from_wif_with_network(wifString, network);
+    return new ECPair(wasm);
+  }
+
+  /**
+   * Decode a WIF string without creating a key pair.
+   *
+   * Since several networks share a version byte, the result lists all candidate networks.
+   * @param wifString - The WIF-encoded private key string
+   * @returns The compressed flag, version byte and candidate networks
+   */
+  static decodeWIF(wifString: string): WIFInfo {
+    return WasmECPair.decode_wif(wifString) as WIFInfo;
+  }
+
   /**
    * Get the private key as a Uint8Array (if available)
    */
@@ -152,6 +188,15 @@ export class ECPair implements ECPairInterface {
     return this._wasm.to_wif_testnet();
   }
 
+  /**
+   * Convert to WIF string using the version byte of a network
+   * @param network - The network (coin name or utxolib name)
+   * @returns The WIF-encoded private key
+   */
+  toWIFForNetwork(network: CoinName | UtxolibName): string {
+    return this._wasm.to_wif_for_network(network);
+  }
+
   /**
    * Get the underlying WASM instance (internal use only)
    * @internal
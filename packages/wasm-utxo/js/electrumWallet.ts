@@ -0,0 +1,126 @@
+/**
+ * Electrum 2.x single-sig wallets
+ *
+ * Electrum derives receive addresses as `mpk/0/i` and change addresses as `mpk/1/i` from the
+ * wallet's master public key (mpk). Standard wallets pay to p2pkh, segwit wallets (mpk
+ * exported as zpub/vpub) to p2wpkh.
+ *
+ * The wallet is spent with the fixed-script wallet PSBT: `rootWalletKeys` holds the mpk as
+ * user key, and `addInput` adds single-sig inputs for Electrum addresses.
+ *
+ * @example
+ * ```typescript
+ * import { electrumWallet, fixedScriptWallet } from '@bitgo/wasm-utxo';
+ *
+ * const wallet = electrumWallet.ElectrumWallet.fromSeed(seedWords, "", "btc");
+ * const psbt = fixedScriptWallet.BitGoPsbt.createEmpty("btc", wallet.rootWalletKeys);
+ * wallet.addInput(psbt, { txid, vout, value }, { change: false, index: 0 });
+ * psbt.sign(0, wallet.signingKey({ change: false, index: 0 }));
+ * ```
+ */
+
+import { WasmElectrumWallet } from "./wasm/wasm_utxo.js";
+import { BIP32 } from "./bip32.js";
+import type { CoinName } from "./coinName.js";
+import { RootWalletKeys } from "./fixedScriptWallet/RootWalletKeys.js";
+import type { AddInputOptions, BitGoPsbt, NetworkName } from "./fixedScriptWallet/BitGoPsbt.js";
+
+export type ElectrumSeedType = "standard" | "segwit";
+
+/** Location of an Electrum address */
+export type ElectrumAddressId = {
+  /** True for change addresses (`m/1/i`), false for receive addresses (`m/0/i`) */
+  change: boolean;
+  index: number;
+};
+
+export class ElectrumWallet {
+  private constructor(private _wasm: WasmElectrumWallet) {}
+
+  /**
+   * Import a master public key
+   *
+   * @param mpk - xpub/tpub for standard wallets, zpub/vpub for segwit wallets
+   */
+  static fromMpk(mpk: string): ElectrumWallet {
+    return new ElectrumWallet(WasmElectrumWallet.from_mpk(mpk));
+  }
+
+  /**
+   * Import an Electrum 2.x seed (standard or segwit; only ASCII seeds are supported)
+   *
+   * @param mnemonic - The seed words
+   * @param passphrase - The seed extension, "" if none
+   * @param network - Network of the extended private key
+   */
+  static fromSeed(mnemonic: string, passphrase: string, network: NetworkName): ElectrumWallet {
+    return new ElectrumWallet(WasmElectrumWallet.from_seed(mnemonic, passphrase, network));
+  }
+
+  /** Derivation path of an address relative to the mpk */
+  static derivationPath(id: ElectrumAddressId): string {
+    return WasmElectrumWallet.derivation_path(id.change, id.index);
+  }
+
+  /** The master public key in Electrum's export format */
+  get mpk(): string {
+    return this._wasm.mpk();
+  }
+
+  get seedType(): ElectrumSeedType {
+    return this._wasm.seed_type() as ElectrumSeedType;
+  }
+
+  get scriptType(): "p2pkh" | "p2wpkh" {
+    return this._wasm.script_type() as "p2pkh" | "p2wpkh";
+  }
+
+  /** Pseudo wallet keys holding the mpk as user key, for use with BitGoPsbt */
+  get rootWalletKeys(): RootWalletKeys {
+    return RootWalletKeys.fromWasm(this._wasm.root_wallet_keys());
+  }
+
+  /** The extended private key of the mpk; undefined unless imported from a seed */
+  get xprv(): BIP32 | undefined {
+    const xprv = this._wasm.xprv();
+    return xprv ? BIP32.fromWasm(xprv) : undefined;
+  }
+
+  outputScript(id: ElectrumAddressId): Uint8Array {
+    return this._wasm.output_script(id.change, id.index);
+  }
+
+  address(id: ElectrumAddressId, network: CoinName): string {
+    return this._wasm.address(id.change, id.index, network);
+  }
+
+  /**
+   * Add an input spending an output of this wallet
+   *
+   * @returns The index of the newly added input
+   */
+  addInput(psbt: BitGoPsbt, inputOptions: AddInputOptions, id: ElectrumAddressId): number {
+    return psbt.addSingleSigInput(inputOptions, this.rootWalletKeys, {
+      signer: "user",
+      derivationPath: ElectrumWallet.derivationPath(id),
+      scriptType: this.scriptType,
+    });
+  }
+
+  /**
+   * The private key of an address, for `BitGoPsbt.sign(inputIndex, key)`
+   *
+   * @throws Error if the wallet was not imported from a seed
+   */
+  signingKey(id: ElectrumAddressId): Uint8Array {
+    const xprv = this.xprv;
+    if (xprv === undefined) {
+      throw new Error("Wallet has no private key (imported from a master public key)");
+    }
+    const privateKey = xprv.derivePath(ElectrumWallet.derivationPath(id)).privateKey;
+    if (privateKey === undefined) {
+      throw new Error("Failed to derive private key");
+    }
+    return privateKey;
+  }
+}
@@ -13,6 +13,11 @@ export type BitGoKeySubtypeMap = {
   readonly PayGoAddressAttestationProof: number;
   readonly Bip322Message: number;
   readonly WasmUtxoSignedWith: number;
+  readonly SpendLimit: number;
+  readonly DestinationWhitelistHash: number;
+  readonly ApprovalQuorumId: number;
+  readonly Label: number;
+  readonly UnconfirmedParent: number;
 };
 
 export const BitGoKeySubtype =
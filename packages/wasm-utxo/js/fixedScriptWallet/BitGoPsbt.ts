@@ -12,7 +12,9 @@ import { type ECPairArg, ECPair } from "../ecpair.js";
 import type { UtxolibName } from "../utxolibCompat.js";
 import type { CoinName } from "../coinName.js";
 import { toCoinName } from "../coinName.js";
-import type { InputScriptType } from "./scriptType.js";
+import type { InputScriptType, SingleSigScriptType } from "./scriptType.js";
+import type { Triple } from "../triple.js";
+import type { FundingKeySource, FundingOutput, FundingOutputType } from "./fundingOutput.js";
 import {
   Transaction,
   DashTransaction,
@@ -26,11 +28,34 @@ export type NetworkName = UtxolibName | CoinName;
 
 export type ScriptId = { chain: number; index: number };
 
+/** Origin of a wallet xpub relative to the root key of an external signer */
+export type KeyOrigin = {
+  /** Fingerprint of the root key as 8 hex characters */
+  masterFingerprint: string;
+  /** Path from the root key to the wallet xpub, e.g. "m/48'/0'/0'/2'" */
+  path: string;
+};
+
 export type OutPoint = {
   txid: string;
   vout: number;
 };
 
+/**
+ * Best-effort script type of an input that belongs to neither the wallet nor replay protection,
+ * or of an output that does not belong to the wallet
+ */
+export type ExternalScriptType =
+  | "p2pkh"
+  | "p2sh"
+  | "p2wpkh"
+  | "p2wsh"
+  | "p2tr"
+  | "p2pk"
+  | "p2ms"
+  | "nullData"
+  | "unknown";
+
 export type ParsedInput = {
   previousOutput: OutPoint;
   address: string;
@@ -38,10 +63,15 @@ export type ParsedInput = {
   value: bigint;
   /** Set only when the derivation path is chain-standard (chain code encodes script type per BitGo convention). */
   scriptId: ScriptId | null;
-  scriptType: InputScriptType;
+  /** "external" only when parsing with `allowExternalInputs: true` */
+  scriptType: InputScriptType | SingleSigScriptType | "external";
+  /** Detected script type when `scriptType` is "external", null otherwise */
+  externalScriptType: ExternalScriptType | null;
   sequence: number;
-  /** Full BIP32 derivation path from the wallet xpub (e.g. "0/1"). Null for replay-protection inputs. */
+  /** Full BIP32 derivation path from the wallet xpub (e.g. "0/1"). Null for replay-protection and external inputs. */
   derivationPath: string | null;
+  /** Label attached with `setInputLabel`, null if none */
+  label: string | null;
 };
 
 export type ParsedOutput = {
@@ -53,6 +83,10 @@ export type ParsedOutput = {
   paygo: boolean;
   /** Full BIP32 derivation path from the wallet xpub (e.g. "0/1"). Null for external outputs. */
   derivationPath: string | null;
+  /** Detected script type of an external output, null for wallet outputs */
+  externalScriptType: ExternalScriptType | null;
+  /** Label attached with `setOutputLabel`, null if none */
+  label: string | null;
 };
 
 export type ParsedTransaction = {
@@ -60,7 +94,41 @@ export type ParsedTransaction = {
   outputs: ParsedOutput[];
   spendAmount: bigint;
   minerFee: bigint;
+  /** Virtual size of the unsigned transaction plus the scriptSig of replay protection inputs */
   virtualSize: number;
+  /** ZIP-317 fee check; set for transparent-only Zcash transactions, null otherwise */
+  zip317Fee: Zip317Fee | null;
+  /** Vault policy fields of the PSBT; null if it has none */
+  vaultPolicy: VaultPolicy | null;
+};
+
+/**
+ * ZIP-317 conventional fee of a transparent-only Zcash transaction.
+ * Transactions paying less than `conventionalFee` are evicted from the mempool.
+ */
+export type Zip317Fee = {
+  logicalActions: bigint;
+  conventionalFee: bigint;
+  meetsMinimum: boolean;
+};
+
+/**
+ * Spend policy of a vault wallet, stored in BitGo proprietary global fields of the PSBT so the
+ * cosigner can enforce it without a lookup.
+ */
+export type VaultPolicy = {
+  /** Maximum spend amount in satoshis */
+  spendLimit: bigint | null;
+  /** 32-byte hash of the list of allowed destinations */
+  destinationWhitelistHash: Uint8Array | null;
+  /** Id of the quorum that must approve the spend */
+  approvalQuorumId: string | null;
+};
+
+export type VaultPolicyArg = {
+  spendLimit?: bigint | null;
+  destinationWhitelistHash?: Uint8Array | null;
+  approvalQuorumId?: string | null;
 };
 
 export type CreateEmptyOptions = {
@@ -70,6 +138,22 @@ export type CreateEmptyOptions = {
   lockTime?: number;
 };
 
+/**
+ * Sequence of a transaction input
+ *
+ * - `"finalNoRbf"`: 0xFFFFFFFE, does not signal replace-by-fee (default)
+ * - `"rbfEnabled"`: 0xFFFFFFFD, signals replace-by-fee (BIP-125)
+ * - `{ relativeLockBlocks }` / `{ relativeLockTime }`: BIP-68 relative lock time in blocks or
+ *   512 second intervals
+ * - a number: raw sequence number
+ */
+export type SequenceMode =
+  | "finalNoRbf"
+  | "rbfEnabled"
+  | { relativeLockBlocks: number }
+  | { relativeLockTime: number }
+  | number;
+
 export type AddInputOptions = {
   /** Previous transaction ID (hex string) */
   txid: string;
@@ -77,8 +161,8 @@ export type AddInputOptions = {
   vout: number;
   /** Value in satoshis (for witness_utxo) */
   value: bigint;
-  /** Sequence number (default: 0xFFFFFFFE for RBF) */
-  sequence?: number;
+  /** Input sequence (default: "finalNoRbf") */
+  sequence?: SequenceMode;
   /** Full previous transaction (for non-segwit strict compliance) */
   prevTx?: Uint8Array;
 };
@@ -106,6 +190,9 @@ export type SignPath = {
   cosigner: SignerKey;
 };
 
+/** Placement of the missing signatures in a half-signed legacy transaction */
+export type HalfSignedLayout = "positional" | "compact";
+
 export type AddWalletInputOptions = {
   /** Script location in wallet (chain + index) */
   scriptId: ScriptId;
@@ -113,6 +200,15 @@ export type AddWalletInputOptions = {
   signPath?: SignPath;
 };
 
+export type AddSingleSigInputOptions = {
+  /** Wallet key the output pays to */
+  signer: SignerKey;
+  /** Derivation path relative to the xpub of `signer` (e.g. "m/0/7") */
+  derivationPath: string;
+  /** Single-sig script of the output */
+  scriptType: SingleSigScriptType;
+};
+
 export type AddWalletOutputOptions = {
   /** Chain code (0/1=p2sh, 10/11=p2shP2wsh, 20/21=p2wsh, 30/31=p2tr, 40/41=p2trMusig2) */
   chain: number;
@@ -122,19 +218,298 @@ export type AddWalletOutputOptions = {
   value: bigint;
 };
 
-export type ParseTransactionOptions = {
+/**
+ * Progress reporting and cancellation for operations over all inputs of large PSBTs
+ *
+ * Inputs are processed in chunks of `progressInterval`. A cancelled operation throws an
+ * error with code `WasmUtxoError.Cancelled` and leaves every input either fully processed
+ * or untouched.
+ */
+export type ProgressOptions = {
+  /** Called with the number of processed inputs and the total number of inputs */
+  onProgress?: (processed: number, total: number) => void;
+  /** Number of inputs between calls to `onProgress`. Default: 100 */
+  progressInterval?: number;
+  /** Checked before each chunk of inputs; aborting it cancels the operation */
+  signal?: AbortSignal;
+};
+
+export type ResumableProgressOptions = ProgressOptions & {
+  /**
+   * Skip the inputs before this index, e.g. the last `processed` value reported before a
+   * cancellation. Default: 0
+   */
+  startInput?: number;
+};
+
+export type ParallelOptions = {
+  /**
+   * Shard the inputs across the wasm thread pool. Inputs are processed sequentially unless
+   * the module is built with the `parallel` feature and the thread pool was initialized (which
+   * requires SharedArrayBuffer). Cannot be combined with progress options. Default: false
+   */
+  parallel?: boolean;
+};
+
+export type ParseTransactionOptions = ProgressOptions & {
   replayProtection: ReplayProtectionArg;
   payGoPubkeys?: ECPairArg[];
+  /**
+   * Classify inputs that belong to neither the wallet nor replay protection as "external"
+   * instead of throwing (e.g. counterparty inputs in a payjoin). Default: false
+   */
+  allowExternalInputs?: boolean;
 };
 
 export type ParseOutputsOptions = {
   payGoPubkeys?: ECPairArg[];
 };
 
+export type FromBytesOptions = {
+  strict?: boolean;
+  walletKeys?: WalletKeysArg;
+};
+
+/**
+ * Limits checked by `signWithPolicy()` and `sign()` before signing. Unset limits are not
+ * checked.
+ */
+export type SigningPolicy = {
+  /** Maximum miner fee in satoshis */
+  maxFee?: bigint;
+  /** Maximum fee rate in sat/vB, measured against the unsigned transaction size */
+  maxFeeRateSatPerVb?: number;
+  /** Maximum miner fee as a percentage of the spend amount (not checked for consolidations) */
+  maxFeePercent?: number;
+  /** Require every wallet output to use a recognized wallet chain */
+  requireWalletChange?: boolean;
+};
+
+export type SignWithPolicyOptions = {
+  replayProtection: ReplayProtectionArg;
+  policy: SigningPolicy;
+  /** Sign even if the policy is violated. Default: false */
+  overridePolicy?: boolean;
+};
+
+export type SignOptions = ResumableProgressOptions &
+  ParallelOptions & {
+    /** Check this policy before signing wallet inputs with an xpriv (default: no check) */
+    signingPolicy?: SignWithPolicyOptions & { walletKeys: WalletKeysArg };
+  };
+
+export type LabeledWalletKeys = {
+  label: string;
+  walletKeys: WalletKeysArg;
+};
+
+export type LabeledParsedOutput = ParsedOutput & {
+  /** Label of the wallet the output belongs to. Null for external outputs. */
+  wallet: string | null;
+};
+
+export type PayjoinSenderOptions = {
+  /** Output script paying the payjoin receiver */
+  payeeScript: Uint8Array;
+  /** Sender output (index in the original PSBT) the receiver may reduce to cover additional fees */
+  feeContribution?: { outputIndex: number; maxAmount: bigint };
+  /** Forbid the receiver from replacing the payee output (default: false) */
+  disableOutputSubstitution?: boolean;
+  /** Minimum fee rate of the proposal in sat/vB */
+  minFeeRate?: number;
+};
+
+export type PayjoinProposalCheck = {
+  /** Indices of the inputs added by the receiver */
+  receiverInputs: number[];
+  /** Amount taken from the sender's fee output */
+  feeContribution: bigint;
+};
+
+/** Index mapping returned by `sortBip69()`: `inputs[oldIndex]` is the input's new index */
+export type Bip69Permutation = {
+  inputs: number[];
+  outputs: number[];
+};
+
+export type MissingNonWitnessUtxo = {
+  inputIndex: number;
+  /** Txid of the previous transaction */
+  txid: string;
+};
+
+/** A MuSig2 nonce that does not match its input, e.g. left over from a replaced input */
+export type StaleMusig2Nonce = {
+  inputIndex: number;
+  /** Compressed public key of the participant (hex) */
+  participantPubKey: string;
+  /** Tap output key the nonce was made for (x-only, hex) */
+  tapOutputKey: string;
+  reason: "missingParticipants" | "tapOutputKeyMismatch" | "unknownParticipant";
+};
+
+/**
+ * An outpoint spent by more than one input, returned by `findSpendConflicts()`
+ */
+export type SpendConflict = {
+  txid: string;
+  vout: number;
+  /**
+   * `duplicateInput` if all inputs are in the same PSBT (the transaction is invalid),
+   * `conflictingSpend` if the inputs are in different PSBTs (only one can confirm)
+   */
+  kind: "duplicateInput" | "conflictingSpend";
+  /** The inputs spending the outpoint, in PSBT and input order */
+  inputs: { psbtIndex: number; inputIndex: number }[];
+};
+
+/**
+ * Snapshot of a transaction taken at parse time, returned by `extractInvariants()`
+ *
+ * Pass it to `extractTransaction()` to check that nothing changed before broadcast.
+ */
+export type ExtractInvariants = {
+  /** Outpoints spent by the transaction, in input order */
+  inputs: OutPoint[];
+  /** SHA256 of the consensus encoding of the outputs (hex) */
+  outputsHash: string;
+  /** Sum of the input values minus the sum of the output values */
+  fee: bigint;
+};
+
+/**
+ * Comparison of the same spend prepared for two chains that share history (e.g. BCH and BSV)
+ */
+export type ForkReplayReport = {
+  /** Outpoints spent by both PSBTs */
+  sharedInputs: OutPoint[];
+  /** Indices of fork PSBT outputs whose script is also paid by the other PSBT */
+  sharedOutputs: number[];
+  /** Both networks use the same sighash algorithm, so signatures are valid on both chains */
+  sharedSighash: boolean;
+  /** Replay protection inputs of the fork PSBT */
+  replayProtectionInputs: { inputIndex: number; previousOutput: OutPoint; signed: boolean }[];
+  /** The fork PSBT spends a signed replay protection input that the other PSBT does not spend */
+  replayProtected: boolean;
+  /** A transaction from either PSBT could be broadcast on the other chain */
+  replayable: boolean;
+};
+
+export type RiskReportOptions = {
+  replayProtection: ReplayProtectionArg;
+  /** Flag miner fees above this amount in satoshis */
+  maxFee?: bigint;
+  /** Flag fee rates above this rate in sat/vB, measured against the unsigned transaction size */
+  maxFeeRateSatPerVb?: number;
+  /** Flag outputs below this value. Default: the network's dust limit for each output script */
+  dustThreshold?: bigint;
+};
+
+/** Red flags of a transaction, as returned by `riskReport()` */
+export type RiskReport = {
+  /** Inputs and signatures with a sighash type other than the network default */
+  nonDefaultSighash: { inputIndex: number; sighashType: number }[];
+  /** Indices of the inputs that belong to neither the wallet nor replay protection */
+  externalInputs: number[];
+  /** OP_RETURN outputs with the concatenated pushed data */
+  opReturnOutputs: { outputIndex: number; data: Uint8Array; value: bigint }[];
+  /** Outputs below the dust threshold */
+  dustOutputs: { outputIndex: number; value: bigint; dustThreshold: bigint }[];
+  minerFee: bigint;
+  virtualSize: number;
+  /** The miner fee exceeds `maxFee` or `maxFeeRateSatPerVb` */
+  feeAboveThreshold: boolean;
+  /** Addresses that are both spent from and paid to */
+  reusedAddresses: { address: string; inputIndices: number[]; outputIndices: number[] }[];
+  /** Whether any of the above is flagged */
+  hasFlags: boolean;
+};
+
+/** Algorithm of a signature digest */
+export type SighashVersion = "legacy" | "bip143" | "forkid" | "bip341" | "zip243";
+
+/** Digest that a signer has to sign, as returned by `computeSighashes()` */
+export type InputSighash = {
+  inputIndex: number;
+  /** Compressed public key, or x-only public key for taproot */
+  pubkey: Uint8Array;
+  /** Tap leaf hash for taproot script path spends; null otherwise */
+  leafHash: Uint8Array | null;
+  sighashType: number;
+  algorithm: SighashVersion;
+  digest: Uint8Array;
+};
+
+/** How the nonce of an ECDSA signature was derived, as returned by `auditSignatureNonce()` */
+export type NonceDerivation = "rfc6979" | "rfc6979LowR";
+
+/** A key whose value differs between two PSBTs, as returned by `diff()` */
+export type PsbtDiffEntry = {
+  map: "global" | "input" | "output";
+  /** Input or output index; null for the global map */
+  index: number | null;
+  change: "added" | "removed" | "changed";
+  /** Key type byte */
+  type: number;
+  /** BIP-174 name of the key type */
+  name: string;
+  /** Hex-encoded key data (without the type byte) */
+  key: string;
+  /** Hex-encoded value in this PSBT; null if the key was added */
+  oldValue: string | null;
+  /** Hex-encoded value in the other PSBT; null if the key was removed */
+  newValue: string | null;
+};
+
+/** A payment to a BIP-352 silent payment address (`sp1...`) */
+export type SilentPaymentRecipient = {
+  address: string;
+  value: bigint;
+};
+
+/** Private key of an input that contributes to the silent payment shared secret */
+export type SilentPaymentInputKey = {
+  inputIndex: number;
+  key: ECPairArg;
+};
+
+/**
+ * A stack item of a finalization template
+ *
+ * - `signature`: the partial signature of `pubkey`, including the sighash byte
+ * - `data`: literal bytes; scriptSig items use minimal pushes (OP_0, OP_1NEGATE, OP_1..OP_16)
+ * - `redeemScript` / `witnessScript`: the corresponding script of the input
+ */
+export type TemplateItem =
+  | { type: "signature"; pubkey: Uint8Array }
+  | { type: "data"; data: Uint8Array }
+  | { type: "redeemScript" }
+  | { type: "witnessScript" };
+
+/** Stack items of the final scriptSig and witness, bottom of the stack first */
+export type WitnessTemplate = {
+  scriptSig?: TemplateItem[];
+  witness?: TemplateItem[];
+};
+
 export type HydrationUnspent =
   | { chain: number; index: number; value: bigint } // wallet input
   | { pubkey: Uint8Array; value: bigint }; // P2SH-P2PK replay protection input
 
+function parallelRequested(options?: ResumableProgressOptions & ParallelOptions): boolean {
+  if (!options?.parallel) {
+    return false;
+  }
+  if (
+    options.onProgress !== undefined ||
+    options.signal !== undefined ||
+    options.startInput !== undefined
+  ) {
+    throw new Error("The parallel option cannot be combined with progress options");
+  }
+  return true;
+}
+
 export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddress {
   protected constructor(wasm: WasmBitGoPsbt) {
     super(wasm);
@@ -185,15 +560,146 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
     return new BitGoPsbt(wasmPsbt);
   }
 
+  /**
+   * Wrap an unsigned transaction created by another library
+   *
+   * The PSBT has the inputs and outputs of the transaction but no input metadata. Call
+   * `enrichInput()` or `enrichReplayProtectionInput()` for each input before signing.
+   * For Zcash, use ZcashBitGoPsbt instead.
+   *
+   * @param txBytes - The unsigned transaction
+   * @param network - Network name (utxolib name like "bitcoin" or coin name like "btc")
+   * @param walletKeys - The wallet's root keys (sets global xpubs in the PSBT)
+   * @throws Error if the transaction cannot be decoded or has a scriptSig or witness
+   */
+  static fromUnsignedTransaction(
+    txBytes: Uint8Array,
+    network: NetworkName,
+    walletKeys: WalletKeysArg,
+  ): BitGoPsbt {
+    const keys = RootWalletKeys.from(walletKeys);
+    return new BitGoPsbt(WasmBitGoPsbt.from_unsigned_tx(txBytes, network, keys.wasm));
+  }
+
   /**
    * Deserialize a PSBT from bytes
    * @param bytes - The PSBT bytes
    * @param network - The network to use for deserialization (either utxolib name like "bitcoin" or coin name like "btc")
+   * @param options - Optional settings
+   * @param options.strict - Reject PSBTs whose redeem/witness scripts, taproot control blocks
+   *   or bip32 derivations do not match the scripts they describe and the global xpubs.
+   *   Use for PSBTs received from an untrusted coordinator.
+   * @param options.walletKeys - Reject PSBTs whose global xpubs are not the xpubs of these
+   *   wallet keys. PSBTs without global xpubs are accepted.
    * @returns A BitGoPsbt instance
    */
-  static fromBytes(bytes: Uint8Array, network: NetworkName): BitGoPsbt {
-    const wasm = WasmBitGoPsbt.from_bytes(bytes, network);
-    return new BitGoPsbt(wasm);
+  static fromBytes(bytes: Uint8Array, network: NetworkName, options?: FromBytesOptions): BitGoPsbt {
+    const wasm = WasmBitGoPsbt.from_bytes(bytes, network, options?.strict);
+    const psbt = new BitGoPsbt(wasm);
+    psbt.assertWalletKeys(options?.walletKeys);
+    return psbt;
+  }
+
+  /** Throw if `walletKeys` is given and does not match the global xpubs */
+  protected assertWalletKeys(walletKeys: WalletKeysArg | undefined): void {
+    if (walletKeys === undefined || this.getGlobalXpubs().length === 0) {
+      return;
+    }
+    if (!this.walletKeysMatch(walletKeys)) {
+      throw new Error("PSBT global xpubs do not match the wallet keys");
+    }
+  }
+
+  /**
+   * Check whether the global xpubs are exactly the xpubs of the given wallet keys
+   *
+   * Key order is ignored. Returns false if the PSBT has no global xpubs.
+   *
+   * @param walletKeys - The wallet keys to compare against
+   * @returns true if the global xpubs match the wallet keys
+   */
+  walletKeysMatch(walletKeys: WalletKeysArg): boolean {
+    return this._wasm.wallet_keys_match(RootWalletKeys.from(walletKeys).wasm);
+  }
+
+  /**
+   * Rewrite the key origins of the wallet keys for hardware wallets
+   *
+   * BitGo PSBTs record each wallet xpub as its own master key (path `m`). Hardware wallets
+   * validate key origins against their registered wallet policy, so the global xpubs and
+   * all input/output derivations of keys with an origin are re-rooted under
+   * `masterFingerprint/path`. Keys with a `null` origin are left as is.
+   *
+   * BitGo parsing identifies wallet keys by their xpub fingerprints; keep the original PSBT
+   * and combine the external signatures into it.
+   *
+   * @param walletKeys - The wallet keys
+   * @param origins - Key origins in user/backup/bitgo order
+   * @throws if an origin contradicts the depth, child number or parent fingerprint of the xpub
+   */
+  exportKeyOrigins(walletKeys: WalletKeysArg, origins: Triple<KeyOrigin | null>): void {
+    this._wasm.export_key_origins(RootWalletKeys.from(walletKeys).wasm, origins);
+  }
+
+  /**
+   * Select the sign path for the available xprivs and apply it to all taproot wallet inputs
+   *
+   * User and bitgo are preferred, followed by user and backup, and backup and bitgo.
+   * p2tr/p2trMusig2 inputs that were added with another `signPath` are rewritten, e.g. MuSig2
+   * key path inputs move to the user/backup script path when signing a recovery.
+   *
+   * @param walletKeys - The wallet keys
+   * @param keys - The xprivs that will sign
+   * @returns The selected sign path
+   * @throws if fewer than two wallet keys are available, or an input is already signed for
+   *   another sign path
+   */
+  planSignPath(walletKeys: WalletKeysArg, keys: BIP32Arg[]): SignPath {
+    return this._wasm.plan_sign_path(
+      RootWalletKeys.from(walletKeys).wasm,
+      keys.map((key) => BIP32.from(key).wasm),
+    ) as SignPath;
+  }
+
+  /**
+   * Get the role of a key in the wallet of this PSBT
+   *
+   * The global xpubs are unordered; their order is recovered from the wallet inputs. Use this
+   * to catch key triples passed in the wrong order, which otherwise only fail as inputs that
+   * are not signed.
+   *
+   * @param key - The signing key (xpriv) or its xpub
+   * @returns The role of the key, or null if it is not one of the wallet keys
+   * @throws if the PSBT has no global xpubs or their order cannot be determined
+   */
+  getSignerRole(key: BIP32Arg): SignerKey | null {
+    return (this._wasm.signer_role(BIP32.from(key).wasm) as SignerKey | undefined) ?? null;
+  }
+
+  /**
+   * Serialize the PSBT to readable JSON
+   *
+   * Lists every key-value pair of the global, input and output maps (hex-encoded, with
+   * BIP-174 names), annotated with input script types, output addresses, wallet/external
+   * output classification and key derivations. Requires the `inspect` feature.
+   *
+   * @returns A JSON string that can be passed to `fromJson`
+   */
+  toJson(): string {
+    return this._wasm.to_json();
+  }
+
+  /**
+   * Rebuild a PSBT from JSON produced by `toJson`
+   *
+   * Only the raw key-value entries are read; annotations are ignored, so the round-trip
+   * is lossless. Requires the `inspect` feature.
+   *
+   * @param json - JSON string produced by `toJson`
+   * @returns A BitGoPsbt instance
+   */
+  static fromJson(json: string): BitGoPsbt {
+    return new BitGoPsbt(WasmBitGoPsbt.from_json(json));
   }
 
   /**
@@ -318,7 +824,7 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
     vout: number,
     value: bigint,
     script: Uint8Array,
-    sequence?: number,
+    sequence?: SequenceMode,
   ): number;
   addInputAtIndex(index: number, options: AddInputOptions, script: Uint8Array): number;
   addInputAtIndex(
@@ -327,7 +833,7 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
     voutOrScript: number | Uint8Array,
     value?: bigint,
     script?: Uint8Array,
-    sequence?: number,
+    sequence?: SequenceMode,
   ): number {
     if (typeof txidOrOptions === "string") {
       return this._wasm.add_input_at_index(
@@ -507,6 +1013,127 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
     );
   }
 
+  /**
+   * Add an input spending a wallet output of `parent`, a PSBT that is not broadcast yet
+   *
+   * The txid, script and value of the input are taken from the parent, and the input records
+   * the parent txid so that both transactions can be submitted as a package.
+   *
+   * @param parent - The unbroadcast parent, e.g. a withdrawal waiting for approval
+   * @param vout - Index of the wallet output of the parent (usually the change)
+   * @param walletKeys - The wallet's root keys
+   * @param options - Sign path (required for p2tr/p2trMusig2) and sequence
+   * @returns The index of the new input
+   * @throws Error if the output does not belong to the wallet or if an input of the parent
+   *   that does not spend a native segwit output is not finalized (its txid is not known yet)
+   */
+  addWalletInputFromParent(
+    parent: BitGoPsbt,
+    vout: number,
+    walletKeys: WalletKeysArg,
+    options: { signPath?: SignPath; sequence?: SequenceMode } = {},
+  ): number {
+    const keys = RootWalletKeys.from(walletKeys);
+    return this._wasm.add_wallet_input_from_parent(
+      parent.wasm,
+      vout,
+      keys.wasm,
+      options.signPath?.signer,
+      options.signPath?.cosigner,
+      options.sequence,
+    );
+  }
+
+  /**
+   * Txids of the unbroadcast parents spent by inputs added with `addWalletInputFromParent()`
+   */
+  unconfirmedParents(): string[] {
+    return this._wasm.unconfirmed_parents();
+  }
+
+  /**
+   * Set the metadata of a wallet input of a PSBT created with `fromUnsignedTransaction()`
+   *
+   * Replaces any existing metadata. The outpoint and sequence are those of the transaction.
+   *
+   * @param inputIndex - Index of the input
+   * @param walletKeys - The wallet's root keys
+   * @param options - Value of the spent output, script location, sign path (required for
+   *   p2tr/p2trMusig2) and, for p2sh, the previous transaction
+   * @throws Error if the index is out of bounds or `prevTx` is not the spent transaction
+   */
+  enrichInput(
+    inputIndex: number,
+    walletKeys: WalletKeysArg,
+    options: AddWalletInputOptions & { value: bigint; prevTx?: Uint8Array },
+  ): void {
+    const keys = RootWalletKeys.from(walletKeys);
+    this._wasm.enrich_input(
+      inputIndex,
+      keys.wasm,
+      options.scriptId.chain,
+      options.scriptId.index,
+      options.value,
+      options.signPath?.signer,
+      options.signPath?.cosigner,
+      options.prevTx,
+    );
+  }
+
+  /**
+   * Set the metadata of a replay protection input of a PSBT created with
+   * `fromUnsignedTransaction()`
+   *
+   * @param inputIndex - Index of the input
+   * @param key - ECPair containing the public key of the replay protection input
+   * @param options - Value of the spent output and optionally the previous transaction
+   */
+  enrichReplayProtectionInput(
+    inputIndex: number,
+    key: ECPairArg,
+    options: { value: bigint; prevTx?: Uint8Array },
+  ): void {
+    const ecpair = ECPair.from(key);
+    this._wasm.enrich_replay_protection_input(
+      inputIndex,
+      ecpair.wasm,
+      options.value,
+      options.prevTx,
+    );
+  }
+
+  /**
+   * Add an input spending a single-sig output of a wallet key
+   *
+   * Used to recover funds sent to a p2pkh, p2wpkh or BIP86 p2tr address of a wallet xpub
+   * (e.g. after the xpub was imported into a single-sig wallet). Sets bip32Derivation for
+   * p2pkh/p2wpkh and tapInternalKey and tapBip32Derivation for p2trKeyPath. These inputs are
+   * signed with `sign(inputIndex, privateKey)` using the key derived along `derivationPath`.
+   *
+   * @param inputOptions - Common input options (txid, vout, value, sequence, prevTx)
+   * @param walletKeys - The wallet's root keys
+   * @param singleSigOptions - Signer key, derivation path and script type
+   * @returns The index of the newly added input
+   */
+  addSingleSigInput(
+    inputOptions: AddInputOptions,
+    walletKeys: WalletKeysArg,
+    singleSigOptions: AddSingleSigInputOptions,
+  ): number {
+    const keys = RootWalletKeys.from(walletKeys);
+    return this._wasm.add_single_sig_input(
+      inputOptions.txid,
+      inputOptions.vout,
+      inputOptions.value,
+      keys.wasm,
+      singleSigOptions.signer,
+      singleSigOptions.derivationPath,
+      singleSigOptions.scriptType,
+      inputOptions.sequence,
+      inputOptions.prevTx,
+    );
+  }
+
   /**
    * Add a wallet output with full PSBT metadata
    *
@@ -557,6 +1184,59 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
     return this._wasm.add_wallet_output(options.chain, options.index, options.value, keys.wasm);
   }
 
+  /**
+   * Sort inputs and outputs following BIP-69
+   *
+   * All PSBT input/output fields move with their transaction input/output.
+   * Must be called before signing and before generating MuSig2 nonces, since reordering
+   * invalidates signatures and nonces.
+   *
+   * @returns Mapping from old to new input and output indices
+   * @throws Error if any input is already signed or has MuSig2 nonces
+   *
+   * @example
+   * ```typescript
+   * const { inputs } = psbt.sortBip69();
+   * const newIndex = inputs[oldIndex];
+   * ```
+   */
+  sortBip69(): Bip69Permutation {
+    return this._wasm.sort_bip69() as Bip69Permutation;
+  }
+
+  /**
+   * Shuffle the outputs in an order derived from a 32-byte seed
+   *
+   * Randomizes the change output position. The same seed always produces the same order,
+   * so the shuffle can be replayed for auditing. Use a seed from a secure random source.
+   * Must be called before signing and before generating MuSig2 nonces.
+   *
+   * @param seed - 32-byte seed
+   * @returns Array where `result[oldIndex]` is the output's new index
+   * @throws Error if the seed is not 32 bytes or any input is already signed or has MuSig2
+   *   nonces
+   */
+  shuffleOutputs(seed: Uint8Array): number[] {
+    return this._wasm.shuffle_outputs(seed) as number[];
+  }
+
+  /**
+   * Set the sequence of every input
+   *
+   * Use this to opt in to (or out of) replace-by-fee after the PSBT has been built.
+   * The sequence is committed to by the signatures, so this fails if any input is signed.
+   *
+   * @param mode - The sequence mode to apply to all inputs
+   *
+   * @example
+   * ```typescript
+   * psbt.setSequenceMode("rbfEnabled");
+   * ```
+   */
+  setSequenceMode(mode: SequenceMode): void {
+    this._wasm.set_sequence_mode(mode);
+  }
+
   /**
    * Add a replay protection input to the PSBT
    *
@@ -611,6 +1291,9 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
    * @param options - Options for parsing
    * @param options.replayProtection - Scripts that are allowed as inputs without wallet validation
    * @param options.payGoPubkeys - Optional public keys for PayGo attestation verification
+   * @param options.allowExternalInputs - Return unknown inputs as "external" instead of throwing
+   * @param options.onProgress - Called every `options.progressInterval` parsed inputs
+   * @param options.signal - Aborting it cancels parsing
    * @returns Parsed transaction information
    */
   parseTransactionWithWalletKeys(
@@ -624,9 +1307,35 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
       keys.wasm,
       rp.wasm,
       pubkeys,
+      options.allowExternalInputs,
+      options,
     ) as ParsedTransaction;
   }
 
+  /**
+   * Collect the red flags of the transaction into a single report for compliance screening
+   *
+   * Flags non-default sighash types, external inputs, OP_RETURN data, dust outputs, a miner
+   * fee above the thresholds and addresses that are both spent from and paid to. External
+   * inputs are reported rather than rejected.
+   *
+   * @param walletKeys - The wallet keys to use for identification
+   * @param options - Replay protection and the fee and dust thresholds
+   * @returns The risk report
+   * @throws Error if the transaction cannot be parsed
+   */
+  riskReport(walletKeys: WalletKeysArg, options: RiskReportOptions): RiskReport {
+    const keys = RootWalletKeys.from(walletKeys);
+    const rp = ReplayProtection.from(options.replayProtection, this._wasm.network());
+    return this._wasm.risk_report(
+      keys.wasm,
+      rp.wasm,
+      options.maxFee,
+      options.maxFeeRateSatPerVb,
+      options.dustThreshold,
+    ) as RiskReport;
+  }
+
   /**
    * Parse outputs with wallet keys to identify which outputs belong to a wallet
    * with the given wallet keys.
@@ -649,6 +1358,57 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
     return this._wasm.parse_outputs_with_wallet_keys(keys.wasm, pubkeys) as ParsedOutput[];
   }
 
+  /**
+   * Parse outputs against several wallets in a single pass.
+   *
+   * Each output is attributed to the first wallet (in list order) whose keys match
+   * the output's derivation info.
+   *
+   * @param wallets - Wallet keys with labels
+   * @param options - Optional options for parsing
+   * @param options.payGoPubkeys - Optional public keys for PayGo attestation verification
+   * @returns Array of parsed outputs with the label of the owning wallet
+   * @note This method does NOT validate wallet inputs. It only parses outputs.
+   */
+  parseOutputsWithLabeledWalletKeys(
+    wallets: LabeledWalletKeys[],
+    options?: ParseOutputsOptions,
+  ): LabeledParsedOutput[] {
+    // Passing wasm objects in an array transfers ownership, so pass copies
+    const keys = wallets.map((w) => RootWalletKeys.from(w.walletKeys).wasm.clone());
+    const labels = wallets.map((w) => w.label);
+    const pubkeys = options?.payGoPubkeys?.map((arg) => ECPair.from(arg).wasm);
+    return this._wasm.parse_outputs_with_labeled_wallet_keys(
+      keys,
+      labels,
+      pubkeys,
+    ) as LabeledParsedOutput[];
+  }
+
+  /**
+   * Check a BIP-78 payjoin proposal against this PSBT (the sender's original).
+   *
+   * Verifies that the receiver preserved the sender's inputs and outputs, that added
+   * receiver inputs are finalized, and that the fee taken from the sender exceeds neither
+   * the negotiated contribution, the fee the proposal adds, nor the original fee rate for
+   * the receiver inputs. This PSBT must be finalized.
+   *
+   * @param proposal - The receiver's payjoin proposal
+   * @param options - Parameters sent with the payjoin request
+   * @returns Receiver input indices and the fee contributed by the sender
+   * @throws Error if the proposal violates any sender check
+   */
+  checkPayjoinProposal(proposal: BitGoPsbt, options: PayjoinSenderOptions): PayjoinProposalCheck {
+    return this._wasm.check_payjoin_proposal(
+      proposal.wasm,
+      options.payeeScript,
+      options.feeContribution?.outputIndex,
+      options.feeContribution?.maxAmount,
+      options.disableOutputSubstitution,
+      options.minFeeRate,
+    ) as PayjoinProposalCheck;
+  }
+
   /**
    * Add a PayGo attestation to a PSBT output
    *
@@ -711,6 +1471,36 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
     return this._wasm.verify_signature_with_pub(inputIndex, wasmECPair);
   }
 
+  /**
+   * Check that the ECDSA signature made by `key` on an input uses a deterministic nonce
+   *
+   * Recomputes the RFC 6979 nonce from the private key and the sighash and compares it with
+   * the R value of the published signature, to detect signers that choose nonces another
+   * way. Taproot (Schnorr) signatures are not covered.
+   *
+   * @param inputIndex - The index of the signed input
+   * @param key - The private key that made the signature: an xpriv (BIP32Arg) for wallet
+   *   inputs or a raw privkey (ECPairArg)
+   * @returns `"rfc6979"`, `"rfc6979LowR"` (RFC 6979 with low-R grinding), or null if the
+   *   signature uses any other nonce
+   * @throws Error if the input index is out of bounds or the input has no ECDSA signature
+   *   made by the key
+   */
+  auditSignatureNonce(inputIndex: number, key: BIP32Arg | ECPairArg): NonceDerivation | null {
+    if (typeof key === "string" || ("derive" in key && typeof key.derive === "function")) {
+      const wasmKey = BIP32.from(key as BIP32Arg).wasm;
+      return this._wasm.audit_signature_nonce_with_xpriv(
+        inputIndex,
+        wasmKey,
+      ) as NonceDerivation | null;
+    }
+    const wasmECPair = ECPair.from(key as ECPairArg).wasm;
+    return this._wasm.audit_signature_nonce_with_privkey(
+      inputIndex,
+      wasmECPair,
+    ) as NonceDerivation | null;
+  }
+
   /**
    * Sign all matching inputs with a private key.
    *
@@ -724,8 +1514,12 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
    * `signInput()` after calling `generateMusig2Nonces()`.
    *
    * @param key - Either an xpriv (BIP32Arg) or a raw privkey (ECPairArg)
+   * @param options - Optional progress callback and abort signal for signing non-MuSig2 wallet
+   *   inputs, `parallel` to sign them on the wasm thread pool, or a `signingPolicy` that is
+   *   checked before any input is signed. Without `signingPolicy`, no policy is checked and
+   *   `sign()` behaves as before; the policy is only enforced for callers that pass it.
    * @returns Array of input indices that were signed
-   * @throws Error if signing fails
+   * @throws Error if signing fails or the signing policy is violated
    *
    * @example
    * ```typescript
@@ -737,7 +1531,7 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
    * const rpSignedIndices = psbt.sign(replayProtectionPrivkey);
    * ```
    */
-  sign(key: BIP32Arg | ECPairArg): number[];
+  sign(key: BIP32Arg | ECPairArg, options?: SignOptions): number[];
 
   /**
    * Sign a single input with a private key.
@@ -757,26 +1551,35 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
 
   sign(
     inputIndexOrKey: number | BIP32Arg | ECPairArg,
-    key?: BIP32Arg | ECPairArg,
+    keyOrOptions?: BIP32Arg | ECPairArg | SignOptions,
   ): number[] | void {
     // Detect which overload was called
     if (typeof inputIndexOrKey === "number") {
       // Called as sign(inputIndex, key) - deprecated single-input signing
-      if (key === undefined) {
+      if (keyOrOptions === undefined) {
         throw new Error("Key is required when signing a single input");
       }
-      this.signInput(inputIndexOrKey, key);
+      this.signInput(inputIndexOrKey, keyOrOptions as BIP32Arg | ECPairArg);
       return;
     }
 
-    // Called as sign(key) - sign all matching inputs
+    // Called as sign(key, options?) - sign all matching inputs
     const keyArg = inputIndexOrKey;
+    const options = keyOrOptions as SignOptions | undefined;
 
     if (isBIP32Arg(keyArg)) {
       // It's a BIP32Arg - sign all wallet inputs (ECDSA + MuSig2)
       const wasmKey = BIP32.from(keyArg);
+      const signingPolicy = options?.signingPolicy;
+      if (signingPolicy !== undefined && !signingPolicy.overridePolicy) {
+        this.checkSigningPolicy(signingPolicy.walletKeys, signingPolicy);
+      }
       // Sign all non-MuSig2 wallet inputs
-      const walletSigned = this._wasm.sign_all_wallet_inputs(wasmKey.wasm) as number[];
+      const walletSigned = (
+        parallelRequested(options)
+          ? this._wasm.sign_all_wallet_inputs_parallel(wasmKey.wasm)
+          : this._wasm.sign_all_wallet_inputs(wasmKey.wasm, options)
+      ) as number[];
       // Sign all MuSig2 keypath inputs (more efficient - reuses SighashCache)
       const musig2Signed = this._wasm.sign_all_musig2_inputs(wasmKey.wasm) as number[];
       return [...walletSigned, ...musig2Signed];
@@ -787,12 +1590,47 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
     }
   }
 
+  /**
+   * Sign all wallet inputs with an xpriv, refusing to sign if the signing policy is violated.
+   *
+   * The policy is checked once, before any input is signed.
+   *
+   * @param key - The xpriv to sign with
+   * @param walletKeys - The wallet keys, used to identify change outputs
+   * @param options - Replay protection, the policy and an optional override
+   * @returns Array of input indices that were signed
+   * @throws Error if the policy is violated (unless overridden) or signing fails
+   */
+  signWithPolicy(
+    key: BIP32Arg,
+    walletKeys: WalletKeysArg,
+    options: SignWithPolicyOptions,
+  ): number[] {
+    return this.sign(key, { signingPolicy: { ...options, walletKeys } });
+  }
+
+  private checkSigningPolicy(walletKeys: WalletKeysArg, options: SignWithPolicyOptions): void {
+    const keys = RootWalletKeys.from(walletKeys);
+    const rp = ReplayProtection.from(options.replayProtection, this._wasm.network());
+    const { policy } = options;
+    this._wasm.check_signing_policy(
+      keys.wasm,
+      rp.wasm,
+      policy.maxFee,
+      policy.maxFeeRateSatPerVb,
+      policy.maxFeePercent,
+      policy.requireWalletChange,
+    );
+  }
+
   /**
    * Sign a single input with a private key.
    *
    * This method signs a specific input using the provided key. It accepts either:
    * - An xpriv (BIP32Arg: base58 string, BIP32 instance, or WasmBIP32) for wallet inputs
-   * - A raw privkey (ECPairArg: Buffer, ECPair instance, or WasmECPair) for replay protection inputs
+   * - A raw privkey (ECPairArg: Buffer, ECPair instance, or WasmECPair) for replay protection
+   *   inputs, single-sig inputs (see `addSingleSigInput`), or for taproot script path inputs whose leaf script contains the key
+   *   (e.g. backup key recovery when only a WIF is available)
    *
    * **Important:** This method is NOT faster than `sign(key)` for non-MuSig2 inputs.
    * The underlying miniscript library signs all inputs regardless. This method uses a
@@ -837,6 +1675,54 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
     }
   }
 
+  /**
+   * Compute the digest that each signer of each unfinalized input has to sign
+   *
+   * For external signers (e.g. HSMs) that sign raw digests: sign `digest` as is, without
+   * hashing it again, and add the signature with `insertExternalSignature()`. MuSig2 key path
+   * inputs are not included.
+   *
+   * @returns One entry per input and signer key, ordered by input
+   * @throws Error if UTXO data or scripts needed for a sighash are missing
+   */
+  computeSighashes(): InputSighash[] {
+    return this._wasm.compute_sighashes() as InputSighash[];
+  }
+
+  /**
+   * Add a signature made over a digest from `computeSighashes()`
+   *
+   * The signature is verified against the digest before it is added, so that signatures from
+   * an HSM can be added without handing the private key to this library.
+   *
+   * @param inputIndex - The index of the signed input
+   * @param pubkey - Compressed public key, or x-only public key for taproot
+   * @param signature - ECDSA signature (DER or 64-byte compact) or 64-byte Schnorr signature,
+   *   optionally followed by the sighash type byte
+   * @param sighashType - The `sighashType` of the digest
+   * @throws Error if the key does not sign the input or the signature does not verify
+   */
+  insertExternalSignature(
+    inputIndex: number,
+    pubkey: Uint8Array,
+    signature: Uint8Array,
+    sighashType: number,
+  ): void {
+    this._wasm.insert_external_signature(inputIndex, pubkey, signature, sighashType);
+  }
+
+  /**
+   * Same as `insertExternalSignature()`, kept for existing callers
+   */
+  insertSignature(
+    inputIndex: number,
+    pubkey: Uint8Array,
+    signature: Uint8Array,
+    sighashType: number,
+  ): void {
+    this.insertExternalSignature(inputIndex, pubkey, signature, sighashType);
+  }
+
   /**
    * @deprecated - use verifySignature with the replay protection key instead
    *
@@ -865,6 +1751,267 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
     return this._wasm.verify_replay_protection_signature(inputIndex, rp.wasm);
   }
 
+  /**
+   * Compare this PSBT with the same spend prepared for a fork chain
+   *
+   * Outputs created before a chain split exist on both chains. Spends of them can be
+   * replayed on the other chain unless the fork side spends a replay protection input that
+   * only exists on the fork chain.
+   *
+   * @param forkPsbt - The PSBT for the fork network (e.g. BSV when this PSBT is for BCH)
+   * @param replayProtection - Scripts that identify replay protection inputs
+   * @returns The shared inputs and outputs and the replay protection inputs of the fork PSBT
+   * @throws Error if the networks do not share chain history
+   */
+  forkReplayReport(forkPsbt: BitGoPsbt, replayProtection: ReplayProtectionArg): ForkReplayReport {
+    const rp = ReplayProtection.from(replayProtection, forkPsbt.wasm.network());
+    return this._wasm.fork_replay_report(forkPsbt.wasm, rp.wasm) as ForkReplayReport;
+  }
+
+  /**
+   * Check that the fork PSBT cannot be replayed on the chain of this PSBT
+   *
+   * @param forkPsbt - The PSBT for the fork network
+   * @param replayProtection - Scripts that identify replay protection inputs
+   * @returns The same report as `forkReplayReport`
+   * @throws Error if the fork PSBT has no replay protection input, a replay protection input
+   *   is unsigned, or it is also spent by this PSBT
+   */
+  verifyForkReplayProtection(
+    forkPsbt: BitGoPsbt,
+    replayProtection: ReplayProtectionArg,
+  ): ForkReplayReport {
+    const rp = ReplayProtection.from(replayProtection, forkPsbt.wasm.network());
+    return this._wasm.verify_fork_replay_protection(forkPsbt.wasm, rp.wasm) as ForkReplayReport;
+  }
+
+  /**
+   * List the non-segwit inputs that are missing their previous transaction
+   *
+   * Signatures of non-segwit inputs do not commit to the spent amount, so a full PSBT
+   * carries the previous transaction (`non_witness_utxo`) for them. Fetch the listed
+   * transactions and add them before signing. Always empty for Zcash.
+   *
+   * @returns The input index and the txid of the previous transaction for each such input
+   */
+  missingNonWitnessUtxos(): MissingNonWitnessUtxo[] {
+    return this._wasm.missing_non_witness_utxos() as MissingNonWitnessUtxo[];
+  }
+
+  /**
+   * Populate `witness_utxo` from `non_witness_utxo` where it is missing
+   *
+   * PSBTs from older utxo-lib versions may carry only the previous transaction for some inputs.
+   * Each previous transaction is checked against the input outpoint and any existing
+   * `witness_utxo` before anything is changed.
+   *
+   * @returns The indices of the inputs that got a `witness_utxo`
+   * @throws Error if a previous transaction does not match its outpoint or `witness_utxo`
+   */
+  normalizeUtxos(): number[] {
+    return Array.from(this._wasm.normalize_utxos());
+  }
+
+  /**
+   * Clone this PSBT as an unsigned template for a chain that shares its history
+   *
+   * The clone spends the same inputs and pays the same output scripts with the same values.
+   * Signatures and MuSig2 nonces are removed, and the sighash type of every input is reset to
+   * the default of the target network (e.g. SIGHASH_ALL|SIGHASH_FORKID for BCH).
+   *
+   * @param network - The target network (e.g. "bch" when this PSBT is for "btc")
+   * @returns A new PSBT for `network`; this PSBT is not modified
+   * @throws Error if the networks do not share chain history, or an input or output uses a
+   *   script type the target network does not support
+   */
+  cloneForNetwork(network: NetworkName): BitGoPsbt {
+    return new BitGoPsbt(this._wasm.clone_for_network(network));
+  }
+
+  /**
+   * Get the vault policy fields of the PSBT
+   *
+   * @returns The policy, with null for fields the PSBT does not set
+   * @throws Error if a policy field is malformed
+   */
+  getVaultPolicy(): VaultPolicy {
+    return this._wasm.vault_policy() as VaultPolicy;
+  }
+
+  /**
+   * Replace the vault policy fields of the PSBT
+   *
+   * The fields are not signed; the cosigner must only trust them when the PSBT comes from an
+   * authenticated source.
+   *
+   * @param policy - The policy; missing or null fields are removed from the PSBT
+   * @throws Error if `destinationWhitelistHash` is not 32 bytes
+   */
+  setVaultPolicy(policy: VaultPolicyArg): void {
+    this._wasm.set_vault_policy(policy);
+  }
+
+  /**
+   * Get the label of an input
+   *
+   * @param inputIndex - The input index
+   * @returns The label, or null if the input has none
+   * @throws Error if the input index is out of bounds
+   */
+  getInputLabel(inputIndex: number): string | null {
+    return this._wasm.input_label(inputIndex) ?? null;
+  }
+
+  /**
+   * Attach a human-readable label to an input (e.g. "consolidation")
+   *
+   * The label is stored as a BitGo proprietary field of the input, so it travels with the PSBT
+   * through serialization, signing and combining. It is not signed.
+   *
+   * @param inputIndex - The input index
+   * @param label - The label, or null to remove it
+   * @throws Error if the input index is out of bounds
+   */
+  setInputLabel(inputIndex: number, label: string | null): void {
+    this._wasm.set_input_label(inputIndex, label ?? undefined);
+  }
+
+  /**
+   * Get the label of an output
+   *
+   * @param outputIndex - The output index
+   * @returns The label, or null if the output has none
+   * @throws Error if the output index is out of bounds
+   */
+  getOutputLabel(outputIndex: number): string | null {
+    return this._wasm.output_label(outputIndex) ?? null;
+  }
+
+  /**
+   * Attach a human-readable label to an output (e.g. "customer-withdrawal:1234")
+   *
+   * Stored like input labels; see `setInputLabel`.
+   *
+   * @param outputIndex - The output index
+   * @param label - The label, or null to remove it
+   * @throws Error if the output index is out of bounds
+   */
+  setOutputLabel(outputIndex: number, label: string | null): void {
+    this._wasm.set_output_label(outputIndex, label ?? undefined);
+  }
+
+  /**
+   * Record that an output pays to a 2-of-2 channel funding output
+   *
+   * Sets the witness script (p2wsh) or the MuSig2 participants and internal key (p2trMusig2)
+   * of the output, and the derivations of the keys in `keySources`.
+   *
+   * @param outputIndex - The output index
+   * @param type - The funding output type
+   * @param pubkeys - The compressed public keys of both parties, in either order
+   * @param keySources - Derivations of the keys we know, usually only our own
+   * @throws Error if the output index is out of bounds, the output script is not the funding
+   *   output script, or a key source is not one of `pubkeys`
+   */
+  setFundingOutput(
+    outputIndex: number,
+    type: FundingOutputType,
+    pubkeys: [Uint8Array, Uint8Array],
+    keySources: FundingKeySource[] = [],
+  ): void {
+    this._wasm.set_funding_output(outputIndex, type, pubkeys[0], pubkeys[1], keySources);
+  }
+
+  /**
+   * Record that an input spends a 2-of-2 channel funding output
+   *
+   * Same as `setFundingOutput`. If the input has a witness utxo, its script must be the
+   * funding output script.
+   */
+  setFundingInput(
+    inputIndex: number,
+    type: FundingOutputType,
+    pubkeys: [Uint8Array, Uint8Array],
+    keySources: FundingKeySource[] = [],
+  ): void {
+    this._wasm.set_funding_input(inputIndex, type, pubkeys[0], pubkeys[1], keySources);
+  }
+
+  /**
+   * Recognize a 2-of-2 channel funding output from the metadata of an output
+   *
+   * @returns The funding output, or null if the output has no funding output metadata
+   * @throws Error if the output index is out of bounds
+   */
+  getFundingOutput(outputIndex: number): FundingOutput | null {
+    return this._wasm.funding_output(outputIndex) as FundingOutput | null;
+  }
+
+  /**
+   * Recognize a 2-of-2 channel funding output from the metadata and witness utxo of an input
+   *
+   * @returns The funding output, or null if the input has no witness utxo or no funding
+   *   output metadata
+   * @throws Error if the input index is out of bounds
+   */
+  getFundingInput(inputIndex: number): FundingOutput | null {
+    return this._wasm.funding_input(inputIndex) as FundingOutput | null;
+  }
+
+  /**
+   * List the key-value differences between this PSBT and `other`
+   *
+   * Global, input and output maps are compared by position, e.g. to see what a signer added
+   * between the half-signed and the fully signed PSBT. Requires the `inspect` feature.
+   *
+   * @param other - The PSBT to compare with, usually a later stage of this one
+   * @returns The added, removed and changed keys, in serialization order within each map
+   * @throws Error if the PSBTs are for different networks
+   */
+  diff(other: BitGoPsbt): PsbtDiffEntry[] {
+    return this._wasm.diff(other.wasm) as PsbtDiffEntry[];
+  }
+
+  /**
+   * Return a copy of the PSBT that can be shared with an external signer
+   *
+   * The copy has no global xpubs and no output metadata. Inputs in `signerInputs` keep their
+   * key origins and MuSig2 data; all other inputs lose their key origins and BitGo proprietary
+   * fields. Scripts, UTXOs and signatures are kept.
+   *
+   * @param signerInputs - Indices of the inputs the external signer will sign
+   * @returns A redacted copy; this PSBT is not modified
+   * @throws Error if an input index is out of bounds
+   */
+  redact(signerInputs: number[]): BitGoPsbt {
+    return new BitGoPsbt(this._wasm.redact(Uint32Array.from(signerInputs)));
+  }
+
+  /**
+   * Add outputs paying BIP-352 silent payment addresses
+   *
+   * The taproot output keys are derived from the private keys of the inputs, so all inputs must
+   * be added first. Every P2TR, P2WPKH, P2SH-P2WPKH and P2PKH input needs a key; multisig
+   * inputs do not contribute and are ignored, but at least one eligible input is required.
+   *
+   * @param recipients - Silent payment addresses and amounts
+   * @param inputKeys - Private keys of the eligible inputs
+   * @returns The indices of the added outputs, in the order of `recipients`
+   * @throws Error if an address is invalid for the network or an input key is missing or wrong
+   */
+  addSilentPaymentOutputs(
+    recipients: SilentPaymentRecipient[],
+    inputKeys: SilentPaymentInputKey[],
+  ): number[] {
+    return Array.from(
+      this._wasm.add_silent_payment_outputs(
+        recipients,
+        Uint32Array.from(inputKeys.map((k) => k.inputIndex)),
+        inputKeys.map((k) => ECPair.from(k.key).wasm),
+      ),
+    );
+  }
+
   /**
    * Generate and store MuSig2 nonces for all MuSig2 inputs
    *
@@ -908,6 +2055,48 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
     this._wasm.generate_musig2_nonces(wasmKey.wasm, sessionId);
   }
 
+  /**
+   * Export the MuSig2 sessions created by `generateMusig2Nonces()` as an encrypted blob
+   *
+   * The blob and this PSBT allow `importMusig2Session()` to restore the signing state in
+   * another process. Sessions already used for signing are not exported.
+   *
+   * @param passphrase - Passphrase used to encrypt the session
+   * @param options.acknowledgeRisk - Must be true on mainnets
+   * @returns The encrypted session
+   *
+   * @security Signing twice with the same session and different counterparty nonces leaks the
+   * private key. Import and sign each exported session at most once.
+   */
+  exportMusig2Session(passphrase: string, options: { acknowledgeRisk?: boolean } = {}): Uint8Array {
+    return this._wasm.export_musig2_session(passphrase, options.acknowledgeRisk);
+  }
+
+  /**
+   * Restore MuSig2 sessions exported with `exportMusig2Session()`
+   *
+   * The PSBT must be the same transaction and contain the nonces of the exported session.
+   * Afterwards the MuSig2 inputs can be signed with `sign()`. Importing a session twice, or
+   * after its signer has signed, throws.
+   *
+   * @param bytes - The encrypted session
+   * @param passphrase - Passphrase the session was encrypted with
+   * @param key - The signer's xpriv
+   * @param options.acknowledgeRisk - Must be true on mainnets
+   * @returns The indices of the restored inputs
+   */
+  importMusig2Session(
+    bytes: Uint8Array,
+    passphrase: string,
+    key: BIP32Arg,
+    options: { acknowledgeRisk?: boolean } = {},
+  ): number[] {
+    const wasmKey = BIP32.from(key);
+    return Array.from(
+      this._wasm.import_musig2_session(bytes, passphrase, wasmKey.wasm, options.acknowledgeRisk),
+    );
+  }
+
   /**
    * Combine/merge data from another PSBT into this one
    *
@@ -915,7 +2104,12 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
    * source PSBT to this PSBT. This is useful for merging PSBTs during the nonce exchange
    * and signature collection phases.
    *
+   * Nonces that do not match their input in this PSBT (e.g. after the input was replaced) are
+   * skipped, and stale nonces already present in this PSBT are removed, instead of failing
+   * later at finalization.
+   *
    * @param sourcePsbt - The source PSBT containing data to merge
+   * @returns The stale nonces that were skipped or removed
    * @throws Error if networks don't match
    *
    * @example
@@ -927,8 +2121,34 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
    * psbt.sign(0, userXpriv);
    * ```
    */
-  combineMusig2Nonces(sourcePsbt: BitGoPsbt): void {
-    this._wasm.combine_musig2_nonces(sourcePsbt.wasm);
+  combineMusig2Nonces(sourcePsbt: BitGoPsbt): StaleMusig2Nonce[] {
+    return this._wasm.combine_musig2_nonces(sourcePsbt.wasm) as StaleMusig2Nonce[];
+  }
+
+  /**
+   * Remove MuSig2 nonces that do not match their input
+   *
+   * @returns The removed nonces
+   */
+  purgeStaleMusig2Nonces(): StaleMusig2Nonce[] {
+    return this._wasm.purge_stale_musig2_nonces() as StaleMusig2Nonce[];
+  }
+
+  /**
+   * Find outpoints that are spent by more than one input of a set of in-flight PSBTs
+   *
+   * A replacement of a transaction is reported as conflicting with it.
+   *
+   * @param psbts - The serialized PSBTs
+   * @param network - The network of the PSBTs
+   */
+  static findSpendConflicts(psbts: Uint8Array[], network: NetworkName): SpendConflict[] {
+    return WasmBitGoPsbt.find_spend_conflicts(psbts, network) as SpendConflict[];
+  }
+
+  /** Outpoints spent by more than one input of this PSBT */
+  duplicateInputs(): SpendConflict[] {
+    return this._wasm.duplicate_inputs() as SpendConflict[];
   }
 
   /**
@@ -939,9 +2159,10 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
    * source may be a stripped PSBT that lacks those fields.
    *
    * Copies per input: partial_sigs, tap_key_sig, tap_script_sigs, proprietary.
+   * Copies per output: proprietary.
    *
    * @param otherPsbtBytes - Raw bytes of the PSBT to merge signatures from
-   * @throws Error if PSBT parsing fails or input counts don't match
+   * @throws Error if PSBT parsing fails or input or output counts don't match
    */
   combineInputs(otherPsbtBytes: Uint8Array): void {
     this._wasm.combine_inputs(otherPsbtBytes);
@@ -950,10 +2171,68 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
   /**
    * Finalize all inputs in the PSBT
    *
-   * @throws Error if any input failed to finalize
+   * @param options - Optional progress callback and abort signal, or `parallel` to finalize
+   *   the inputs on the wasm thread pool
+   * @throws Error if any input failed to finalize, or if the operation was cancelled
+   */
+  finalizeAllInputs(options?: ResumableProgressOptions & ParallelOptions): void {
+    if (parallelRequested(options)) {
+      this._wasm.finalize_all_inputs_parallel();
+    } else {
+      this._wasm.finalize_all_inputs(options);
+    }
+  }
+
+  /**
+   * Finalize an input with a custom scriptSig and witness template
+   *
+   * For scripts the default finalizer does not support, such as CHECKDATASIG replay
+   * protection variants or CLTV-wrapped multisig. The result is not checked against the
+   * prevout script; call `verifyInputScripts()` once all inputs are finalized.
+   *
+   * @param inputIndex - The input to finalize
+   * @param template - The scriptSig and witness stack items
+   * @throws Error if a referenced signature or script is missing from the input
+   */
+  finalizeInputWithTemplate(inputIndex: number, template: WitnessTemplate): void {
+    this._wasm.finalize_input_with_template(inputIndex, template);
+  }
+
+  /**
+   * Execute the final scriptSig and witness of every input against its prevout
+   *
+   * Use this after finalizing to catch finalization bugs before broadcasting.
+   *
+   * @throws Error if an input is not finalized or its scripts fail
+   */
+  verifyInputScripts(): void {
+    this._wasm.verify_input_scripts();
+  }
+
+  /**
+   * Take a snapshot of the inputs, outputs and fee
+   *
+   * Call this when the transaction is parsed and approved, and pass the result to
+   * `extractTransaction()` to re-check it after signing and finalization.
+   *
+   * @throws Error if an input has no witness_utxo or non_witness_utxo, or outputs exceed inputs
+   */
+  extractInvariants(): ExtractInvariants {
+    return this._wasm.extract_invariants() as ExtractInvariants;
+  }
+
+  /**
+   * Digest binding an approval to this transaction
+   *
+   * SHA256 over the network, the unsigned transaction, the outputs and the fee. It does not
+   * change when the PSBT is re-serialized or signatures, MuSig2 nonces or labels are added,
+   * so an approval recorded with the digest applies to exactly one transaction.
+   *
+   * @returns The 32-byte digest
+   * @throws Error if an input has no witness_utxo or non_witness_utxo, or outputs exceed inputs
    */
-  finalizeAllInputs(): void {
-    this._wasm.finalize_all_inputs();
+  approvalDigest(): Uint8Array {
+    return this._wasm.approval_digest();
   }
 
   /**
@@ -962,12 +2241,15 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
    * @param maxFeeRate Optional maximum fee rate in **sat/vB**. `Infinity` skips
    *   the absurd-fee check; `undefined` uses rust-bitcoin's default check.
    *   Callers holding sat/kB thresholds must divide by 1000 before passing.
+   * @param invariants Optional snapshot from `extractInvariants()`. If given, extraction
+   *   fails if inputs were added or changed, any output changed, or the fee differs.
    * @returns The extracted transaction instance
-   * @throws Error if the PSBT is not fully finalized or extraction fails
+   * @throws Error if the PSBT is not fully finalized, the invariants do not hold, or
+   *   extraction fails
    */
-  extractTransaction(maxFeeRate?: number): ITransaction {
+  extractTransaction(maxFeeRate?: number, invariants?: ExtractInvariants): ITransaction {
     const networkType = this._wasm.get_network_type();
-    const wasm: unknown = this._wasm.extract_transaction(maxFeeRate);
+    const wasm: unknown = this._wasm.extract_transaction(maxFeeRate, invariants);
 
     switch (networkType) {
       case "dash":
@@ -991,6 +2273,10 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
    * - All inputs must be p2ms-based (p2sh, p2shP2wsh, or p2wsh)
    * - Each input must have exactly 1 partial signature
    *
+   * @param options.layout - `"positional"` (default) for an `OP_0` placeholder per missing
+   *   signature, `"compact"` (`OP_0 <sig> <redeemScript>`) for cosigners that expect the
+   *   missing signatures to be left out. Signatures of p2shP2wsh and p2wsh inputs are placed
+   *   in the witness either way.
    * @returns The serialized half-signed transaction bytes
    * @throws Error if any input is not a p2ms type (Taproot, replay protection, etc.)
    * @throws Error if any input has 0 or more than 1 partial signature
@@ -1004,8 +2290,8 @@ export class BitGoPsbt extends PsbtBase<WasmBitGoPsbt> implements IPsbtWithAddre
    * const halfSignedTx = psbt.getHalfSignedLegacyFormat();
    * ```
    */
-  getHalfSignedLegacyFormat(): Uint8Array {
-    return this._wasm.extract_half_signed_legacy_tx();
+  getHalfSignedLegacyFormat(options?: { layout?: HalfSignedLayout }): Uint8Array {
+    return this._wasm.extract_half_signed_legacy_tx(options?.layout);
   }
 
   /**
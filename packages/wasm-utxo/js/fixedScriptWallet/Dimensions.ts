@@ -1,10 +1,12 @@
 import { WasmDimensions } from "../wasm/wasm_utxo.js";
 import type { BitGoPsbt, InputScriptType, SignPath } from "./BitGoPsbt.js";
 import type { CoinName } from "../coinName.js";
-import type { OutputScriptType } from "./scriptType.js";
+import type { OutputScriptType, SingleSigScriptType } from "./scriptType.js";
 import { toOutputScriptWithCoin } from "../address.js";
 
-type FromInputParams = { chain: number; signPath?: SignPath } | { scriptType: InputScriptType };
+type FromInputParams =
+  | { chain: number; signPath?: SignPath }
+  | { scriptType: InputScriptType | SingleSigScriptType };
 
 /**
  * Options for input dimension calculation
@@ -17,6 +19,23 @@ export type FromInputOptions = {
   utxolibCompat?: boolean;
 };
 
+/**
+ * Inputs of a size estimate: `count` inputs of the given kind (default 1)
+ */
+export type InputSpec = FromInputParams & { count?: number };
+
+/**
+ * Outputs of a size estimate: `count` outputs of the given kind (default 1)
+ */
+export type OutputSpec = ({ scriptType: OutputScriptType } | { length: number }) & {
+  count?: number;
+};
+
+/**
+ * Virtual size bounds of a transaction
+ */
+export type VSizeEstimate = { min: number; max: number };
+
 /**
  * Dimensions class for estimating transaction virtual size.
  *
@@ -67,6 +86,29 @@ export class Dimensions {
     );
   }
 
+  /**
+   * Estimate the virtual size of a transaction from input and output specs
+   *
+   * With `{ utxolibCompat: true }` the estimate matches @bitgo/unspents `Dimensions`: besides
+   * using 72-byte signatures, each input is rounded up to whole vbytes before summing, which
+   * can exceed the vsize of the total weight by one vbyte per segwit input.
+   *
+   * @example
+   * ```typescript
+   * const { max } = Dimensions.estimateVSize(
+   *   [{ chain: 20, count: 2 }],
+   *   [{ scriptType: "p2wsh" }, { length: 22 }],
+   * );
+   * ```
+   */
+  static estimateVSize(
+    inputs: InputSpec[],
+    outputs: OutputSpec[],
+    options?: FromInputOptions,
+  ): VSizeEstimate {
+    return WasmDimensions.estimate_vsize(inputs, outputs, options?.utxolibCompat) as VSizeEstimate;
+  }
+
   /**
    * Create dimensions for a single output from script bytes
    */
@@ -37,7 +37,8 @@ export class ReplayProtection {
   /**
    * Convert ReplayProtectionArg to ReplayProtection instance
    * @param arg - The replay protection in various formats
-   * @param network - Optional network string (required for addresses variant)
+   * @param network - Optional network string. For the addresses variant, addresses are decoded
+   *   with this network if given, otherwise with whichever supported network they are valid on
    * @returns ReplayProtection instance
    */
   static from(arg: ReplayProtectionArg, network?: string): ReplayProtection {
@@ -64,10 +65,9 @@ export class ReplayProtection {
     }
 
     if ("addresses" in arg) {
-      if (!network) {
-        throw new Error("Network is required when using addresses variant");
-      }
-      const wasm = WasmReplayProtection.from_addresses(arg.addresses, network);
+      const wasm = network
+        ? WasmReplayProtection.from_addresses(arg.addresses, network)
+        : WasmReplayProtection.from_addresses_any_network(arg.addresses);
       return new ReplayProtection(wasm);
     }
 
@@ -94,14 +94,26 @@ export class ReplayProtection {
 
   /**
    * Create from addresses
+   *
+   * Without a network, each address is decoded with the supported network it is valid on, so
+   * addresses of different coins and formats (e.g. legacy and cashaddr) can be mixed.
+   *
    * @param addresses - Array of address strings
-   * @param network - Network string (e.g., "bitcoin", "testnet", "btc", "tbtc")
+   * @param network - Optional network string (e.g., "bitcoin", "testnet", "btc", "tbtc")
    * @returns ReplayProtection instance
+   * @throws if an address is not valid on the network, or on any supported network
    */
-  static fromAddresses(addresses: string[], network: string): ReplayProtection {
+  static fromAddresses(addresses: string[], network?: string): ReplayProtection {
     return ReplayProtection.from({ addresses }, network);
   }
 
+  /**
+   * Output scripts of the replay protection inputs
+   */
+  get outputScripts(): Uint8Array[] {
+    return this._wasm.output_scripts() as Uint8Array[];
+  }
+
   /**
    * Get the underlying WASM instance (internal use only)
    * @internal
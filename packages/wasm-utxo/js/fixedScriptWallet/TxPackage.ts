@@ -0,0 +1,74 @@
+import { WasmTxPackage } from "../wasm/wasm_utxo.js";
+import type { BitGoPsbt, NetworkName } from "./BitGoPsbt.js";
+
+/**
+ * Aggregate fee of a transaction package
+ */
+export type PackageFee = {
+  /** Fee of each transaction, parents first */
+  fees: bigint[];
+  totalFee: bigint;
+  /** Unsigned transactions are counted with their maximum estimated vsize */
+  totalVsize: number;
+  /** Total fee divided by total vsize, in sat/vB */
+  feeRate: number;
+};
+
+/**
+ * A child transaction and its unconfirmed parents, for package relay (`submitpackage`)
+ *
+ * The transactions are ordered parents first. The child must spend an output of every
+ * parent, e.g. a withdrawal and the CPFP spend of its anchor output.
+ */
+export class TxPackage {
+  private constructor(private _wasm: WasmTxPackage) {}
+
+  /**
+   * Create a package from a child and its parents, in any order
+   *
+   * @throws Error if the transactions are not a child with its parents
+   */
+  static create(psbts: BitGoPsbt[], network: NetworkName): TxPackage {
+    const bytes = psbts.map((psbt) => psbt.serialize());
+    return new TxPackage(WasmTxPackage.from_psbts(bytes, network));
+  }
+
+  /** The txids of the transactions, parents first */
+  get txids(): string[] {
+    return this._wasm.txids();
+  }
+
+  /** Compute the aggregate fee and fee rate of the package */
+  packageFee(): PackageFee {
+    return this._wasm.package_fee() as PackageFee;
+  }
+
+  /**
+   * Check that the package fee rate is at least `minFeeRate` sat/vB
+   *
+   * @throws Error if the package fee rate is too low
+   */
+  checkFeeRate(minFeeRate: number): PackageFee {
+    return this._wasm.check_fee_rate(minFeeRate) as PackageFee;
+  }
+
+  /**
+   * Extract the finalized transactions, parents first
+   *
+   * @throws Error if a transaction is not fully finalized
+   */
+  extractTransactions(): Uint8Array[] {
+    return this._wasm.extract_transactions();
+  }
+
+  /**
+   * The finalized transactions in `submitpackage` order (parents first)
+   *
+   * Callers hex-encode the transactions for the `submitpackage` RPC.
+   *
+   * @throws Error if a transaction is not fully finalized
+   */
+  toSubmitPackage(): Uint8Array[] {
+    return this.extractTransactions();
+  }
+}
@@ -1,11 +1,34 @@
-import { BitGoPsbt as WasmBitGoPsbt, zcash_branch_id_for_height } from "../wasm/wasm_utxo.js";
+import {
+  BitGoPsbt as WasmBitGoPsbt,
+  reset_zcash_network_upgrades,
+  set_zcash_network_upgrades,
+  zcash_branch_id_for_height,
+} from "../wasm/wasm_utxo.js";
 import { type WalletKeysArg, RootWalletKeys } from "./RootWalletKeys.js";
-import { BitGoPsbt, type CreateEmptyOptions, type HydrationUnspent } from "./BitGoPsbt.js";
+import {
+  BitGoPsbt,
+  type CreateEmptyOptions,
+  type ExtractInvariants,
+  type FromBytesOptions,
+  type HydrationUnspent,
+} from "./BitGoPsbt.js";
 import { ZcashTransaction, type ITransaction } from "../transaction.js";
 
 /** Zcash network names */
 export type ZcashNetworkName = "zcash" | "zcashTest" | "zec" | "tzec";
 
+/** Branch ID and activation height of a Zcash network upgrade */
+export type ZcashUpgradeActivation = {
+  branchId: number;
+  activationHeight: number;
+};
+
+/** Network upgrades that are not built in yet, in chronological order */
+export type ZcashNetworkUpgrades = {
+  mainnet?: ZcashUpgradeActivation[];
+  testnet?: ZcashUpgradeActivation[];
+};
+
 /** Options for creating an empty Zcash PSBT (preferred method using block height) */
 export type CreateEmptyZcashOptions = CreateEmptyOptions & {
   /** Block height to determine consensus branch ID automatically */
@@ -137,11 +160,18 @@ export class ZcashBitGoPsbt extends BitGoPsbt {
    *
    * @param bytes - The PSBT bytes
    * @param network - Zcash network name ("zcash", "zcashTest", "zec", "tzec")
+   * @param options - Optional settings (see `BitGoPsbt.fromBytes`)
    * @returns A ZcashBitGoPsbt instance
    */
-  static override fromBytes(bytes: Uint8Array, network: ZcashNetworkName): ZcashBitGoPsbt {
-    const wasm = WasmBitGoPsbt.from_bytes(bytes, network);
-    return new ZcashBitGoPsbt(wasm);
+  static override fromBytes(
+    bytes: Uint8Array,
+    network: ZcashNetworkName,
+    options?: FromBytesOptions,
+  ): ZcashBitGoPsbt {
+    const wasm = WasmBitGoPsbt.from_bytes(bytes, network, options?.strict);
+    const psbt = new ZcashBitGoPsbt(wasm);
+    psbt.assertWalletKeys(options?.walletKeys);
+    return psbt;
   }
 
   /**
@@ -280,16 +310,44 @@ export class ZcashBitGoPsbt extends BitGoPsbt {
     return zcash_branch_id_for_height(network, height);
   }
 
+  /**
+   * Add network upgrades that are not built in yet, so that PSBTs created from a block height
+   * after their activation get their branch ID. Replaces the upgrades of a previous call.
+   *
+   * @throws Error if an upgrade does not activate after the upgrade before it, or reuses a
+   *   branch ID
+   *
+   * @example
+   * ```typescript
+   * ZcashBitGoPsbt.setNetworkUpgrades({
+   *   mainnet: [{ branchId: config.branchId, activationHeight: config.activationHeight }],
+   * });
+   * ```
+   */
+  static setNetworkUpgrades(upgrades: ZcashNetworkUpgrades): void {
+    set_zcash_network_upgrades(upgrades);
+  }
+
+  /** Remove the upgrades added with `setNetworkUpgrades` */
+  static resetNetworkUpgrades(): void {
+    reset_zcash_network_upgrades();
+  }
+
   /**
    * Extract the final Zcash transaction from a finalized PSBT
    *
    * @param maxFeeRate Optional maximum fee rate in **sat/vB**. `Infinity` skips
    *   the absurd-fee check; `undefined` uses rust-bitcoin's default check.
    *   Callers holding sat/kB thresholds must divide by 1000 before passing.
+   * @param invariants Optional snapshot from `extractInvariants()`, checked before extraction
    * @returns The extracted Zcash transaction instance
-   * @throws Error if the PSBT is not fully finalized or extraction fails
+   * @throws Error if the PSBT is not fully finalized, the invariants do not hold, or
+   *   extraction fails
    */
-  override extractTransaction(maxFeeRate?: number): ZcashTransaction {
-    return ZcashTransaction.fromWasm(this.wasm.extract_zcash_transaction(maxFeeRate));
+  override extractTransaction(
+    maxFeeRate?: number,
+    invariants?: ExtractInvariants,
+  ): ZcashTransaction {
+    return ZcashTransaction.fromWasm(this.wasm.extract_zcash_transaction(maxFeeRate, invariants));
   }
 }
@@ -33,6 +33,21 @@ export function outputScript(
   }
 }
 
+/**
+ * Get the ranged output descriptor for the scripts of a chain, for registering the wallet
+ * with descriptor-based software (e.g. Bitcoin Core watch-only wallets).
+ *
+ * Keys are written as `[fingerprint]xpub/0/0/<chain>/*`. The descriptor has no checksum.
+ * p2trMusig2 chains use a BIP-390 `musig()` internal key.
+ *
+ * @param keys - The wallet keys to use
+ * @param chain - The chain to describe
+ * @throws For p2trLegacy and p2mr chains, which have no descriptor representation
+ */
+export function descriptorForChain(keys: WalletKeysArg, chain: number): string {
+  return FixedScriptWalletNamespace.descriptor_for_chain(RootWalletKeys.from(keys).wasm, chain);
+}
+
 /**
  * Create the address for a given wallet keys and chain and index and network.
  * Wrapper for outputScript that also encodes the script to an address.
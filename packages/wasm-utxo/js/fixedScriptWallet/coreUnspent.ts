@@ -0,0 +1,56 @@
+import { FixedScriptWalletNamespace } from "../wasm/wasm_utxo.js";
+import { type WalletKeysArg, RootWalletKeys } from "./RootWalletKeys.js";
+import type { NetworkName } from "./address.js";
+import type { OutputScriptType } from "./scriptType.js";
+import type { ScriptId } from "./BitGoPsbt.js";
+
+/**
+ * Unspent output as reported by Bitcoin Core `listunspent`.
+ *
+ * Bitcoin Core reports the `amount` in BTC; convert it to satoshis for `value`.
+ */
+export type CoreUnspent = {
+  txid: string;
+  vout: number;
+  /** Output script (hex) */
+  scriptPubKey: string;
+  /** Value in satoshis */
+  value: bigint;
+  /** Inferred descriptor, with or without checksum */
+  desc?: string;
+};
+
+/** A Bitcoin Core unspent matched to a wallet script, ready for `BitGoPsbt.addWalletInput` */
+export type ImportedUnspent = {
+  input: { txid: string; vout: number; value: bigint };
+  scriptId: ScriptId;
+  scriptType: OutputScriptType;
+};
+
+/**
+ * Match Bitcoin Core `listunspent` entries to the wallet scripts of `keys`.
+ *
+ * The chain and index of each unspent are recovered from the key origins of its descriptor
+ * (`[fingerprint/0/0/<chain>/<index>]`), and the wallet script at that position must match
+ * `scriptPubKey`. The descriptor checksum is verified when present.
+ *
+ * @example
+ * ```typescript
+ * for (const { input, scriptId } of importCoreUnspents(walletKeys, listUnspent, "btc")) {
+ *   psbt.addWalletInput(input, walletKeys, { scriptId });
+ * }
+ * ```
+ *
+ * @throws If an unspent has no descriptor, an invalid checksum, or does not belong to the wallet
+ */
+export function importCoreUnspents(
+  keys: WalletKeysArg,
+  unspents: CoreUnspent[],
+  network: NetworkName,
+): ImportedUnspent[] {
+  return FixedScriptWalletNamespace.import_core_unspents(
+    RootWalletKeys.from(keys).wasm,
+    unspents,
+    network,
+  ) as ImportedUnspent[];
+}
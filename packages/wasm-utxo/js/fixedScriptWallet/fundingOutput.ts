@@ -0,0 +1,61 @@
+import { FixedScriptWalletNamespace } from "../wasm/wasm_utxo.js";
+import type { NetworkName } from "./address.js";
+import type { KeyOrigin } from "./BitGoPsbt.js";
+
+/**
+ * Type of a 2-of-2 channel funding output:
+ * - "p2wsh": `2 <key1> <key2> 2 OP_CHECKMULTISIG` with sorted keys (BOLT 3)
+ * - "p2trMusig2": taproot key path spend with the MuSig2 aggregate of both keys
+ */
+export type FundingOutputType = "p2wsh" | "p2trMusig2";
+
+/** A funding output recognized from PSBT metadata */
+export type FundingOutput = {
+  type: FundingOutputType;
+  /** The compressed public keys of both parties, sorted */
+  pubkeys: [Uint8Array, Uint8Array];
+};
+
+/** Derivation of one of the funding keys, usually our own */
+export type FundingKeySource = KeyOrigin & {
+  /** Compressed public key */
+  pubkey: Uint8Array;
+};
+
+/**
+ * Get the output script of a 2-of-2 channel funding output
+ *
+ * @param type - The funding output type
+ * @param pubkeys - The compressed public keys of both parties, in either order
+ * @param network - The network, which must support the output type
+ * @throws If a key is invalid, the keys are equal, or the network does not support the type
+ */
+export function fundingOutputScript(
+  type: FundingOutputType,
+  pubkeys: [Uint8Array, Uint8Array],
+  network: NetworkName,
+): Uint8Array {
+  return FixedScriptWalletNamespace.funding_output_script(type, pubkeys[0], pubkeys[1], network);
+}
+
+/**
+ * Get the witness script of a p2wsh funding output, with the keys sorted as in BOLT 3
+ *
+ * @param pubkeys - The compressed public keys of both parties, in either order
+ */
+export function fundingWitnessScript(pubkeys: [Uint8Array, Uint8Array]): Uint8Array {
+  return FixedScriptWalletNamespace.funding_witness_script(pubkeys[0], pubkeys[1]);
+}
+
+/**
+ * Get the type of the funding output of `pubkeys` that has the output script `script`
+ *
+ * @returns The funding output type, or null if `script` is not a funding output of the keys
+ */
+export function classifyFundingOutput(
+  script: Uint8Array,
+  pubkeys: [Uint8Array, Uint8Array],
+): FundingOutputType | null {
+  const type = FixedScriptWalletNamespace.classify_funding_output(script, pubkeys[0], pubkeys[1]);
+  return (type as FundingOutputType | undefined) ?? null;
+}
@@ -3,13 +3,27 @@ import type { CoinName } from "../coinName.js";
 
 export { RootWalletKeys, type WalletKeysArg, type IWalletKeys } from "./RootWalletKeys.js";
 export { ReplayProtection, type ReplayProtectionArg } from "./ReplayProtection.js";
-export { outputScript, address } from "./address.js";
-export { Dimensions } from "./Dimensions.js";
+export { outputScript, address, descriptorForChain } from "./address.js";
+export { importCoreUnspents, type CoreUnspent, type ImportedUnspent } from "./coreUnspent.js";
+export { planMigration, type MigrationOptions } from "./migration.js";
+export { planSweep, type SweepOptions, type SweepPlan, type SweepTransaction } from "./sweep.js";
+export {
+  fundingOutputScript,
+  fundingWitnessScript,
+  classifyFundingOutput,
+  type FundingOutput,
+  type FundingOutputType,
+  type FundingKeySource,
+} from "./fundingOutput.js";
+export { Dimensions, type InputSpec, type OutputSpec, type VSizeEstimate } from "./Dimensions.js";
+export { TxPackage, type PackageFee } from "./TxPackage.js";
 export {
   outputScriptTypes,
   inputScriptTypes,
   type OutputScriptType,
   type InputScriptType,
+  singleSigScriptTypes,
+  type SingleSigScriptType,
   type ScriptType,
 } from "./scriptType.js";
 export { ChainCode, chainCodes, assertChainCode, type Scope } from "./chains.js";
@@ -21,17 +35,51 @@ export {
   type NetworkName,
   type ScriptId,
   type ParsedInput,
+  type ExternalScriptType,
   type ParsedOutput,
   type ParsedTransaction,
+  type Zip317Fee,
+  type VaultPolicy,
+  type VaultPolicyArg,
   type SignPath,
   type CreateEmptyOptions,
+  type FromBytesOptions,
   type AddInputOptions,
+  type KeyOrigin,
   type AddOutputOptions,
   type AddWalletInputOptions,
+  type AddSingleSigInputOptions,
   type AddWalletOutputOptions,
   type ParseTransactionOptions,
+  type ProgressOptions,
+  type ResumableProgressOptions,
+  type ParallelOptions,
+  type SigningPolicy,
+  type SignWithPolicyOptions,
   type ParseOutputsOptions,
+  type LabeledWalletKeys,
+  type LabeledParsedOutput,
   type HydrationUnspent,
+  type PayjoinSenderOptions,
+  type PayjoinProposalCheck,
+  type Bip69Permutation,
+  type SequenceMode,
+  type HalfSignedLayout,
+  type ForkReplayReport,
+  type RiskReportOptions,
+  type RiskReport,
+  type SighashVersion,
+  type InputSighash,
+  type MissingNonWitnessUtxo,
+  type ExtractInvariants,
+  type StaleMusig2Nonce,
+  type SpendConflict,
+  type PsbtDiffEntry,
+  type NonceDerivation,
+  type SilentPaymentRecipient,
+  type SilentPaymentInputKey,
+  type TemplateItem,
+  type WitnessTemplate,
 } from "./BitGoPsbt.js";
 
 export { BitGoKeySubtype, type PsbtKvKey } from "./BitGoKeySubtype.js";
@@ -41,6 +89,8 @@ export {
   ZcashBitGoPsbt,
   type ZcashNetworkName,
   type CreateEmptyZcashOptions,
+  type ZcashNetworkUpgrades,
+  type ZcashUpgradeActivation,
 } from "./ZcashBitGoPsbt.js";
 
 import type { ScriptType } from "./scriptType.js";
@@ -69,6 +119,8 @@ import type { ScriptType } from "./scriptType.js";
  * supportsScriptType("btc", "p2trMusig2KeyPath"); // true
  * supportsScriptType("doge", "p2trLegacy"); // false
  * ```
+ *
+ * @see getNetworkCapabilities for the script types and other capabilities of a network
  */
 export function supportsScriptType(coin: CoinName, scriptType: ScriptType): boolean {
   return FixedScriptWalletNamespace.supports_script_type(coin, scriptType);
@@ -103,3 +155,56 @@ export function createOpReturnScript(data?: Uint8Array): Uint8Array {
 export function p2shP2pkOutputScript(pubkey: Uint8Array): Uint8Array {
   return FixedScriptWalletNamespace.p2sh_p2pk_output_script(pubkey);
 }
+
+export type DerivationCacheStats = {
+  hits: bigint;
+  misses: bigint;
+  evictions: bigint;
+  size: number;
+  capacity: number;
+  enabled: boolean;
+};
+
+/**
+ * Get statistics of the wallet key derivation cache
+ *
+ * Derived wallet keys are memoized by (xpubs, chain, index) so that repeated
+ * parse/sign/verify calls on the same PSBT do not re-derive identical keys.
+ */
+export function getDerivationCacheStats(): DerivationCacheStats {
+  return FixedScriptWalletNamespace.derivation_cache_stats() as DerivationCacheStats;
+}
+
+/**
+ * Enable or disable the wallet key derivation cache
+ *
+ * Disabling drops all cached entries. Useful in memory-constrained environments.
+ */
+export function setDerivationCacheEnabled(enabled: boolean): void {
+  FixedScriptWalletNamespace.set_derivation_cache_enabled(enabled);
+}
+
+/**
+ * Set the maximum number of cached wallet key derivations (least recently used entries are evicted)
+ */
+export function setDerivationCacheCapacity(capacity: number): void {
+  FixedScriptWalletNamespace.set_derivation_cache_capacity(capacity);
+}
+
+/**
+ * Drop all cached wallet key derivations and reset the statistics
+ */
+export function clearDerivationCache(): void {
+  FixedScriptWalletNamespace.clear_derivation_cache();
+}
+
+/**
+ * Enable or disable low-R grinding of ECDSA signatures.
+ *
+ * Signatures are ground to low-R by default, so that they are at most 71 bytes. Disable to
+ * reproduce signatures of implementations without grinding. The setting applies to all
+ * `BitGoPsbt` signing methods, including parallel signing.
+ */
+export function setLowRGrinding(enabled: boolean): void {
+  FixedScriptWalletNamespace.set_low_r_grinding(enabled);
+}
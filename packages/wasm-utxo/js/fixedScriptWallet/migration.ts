@@ -0,0 +1,58 @@
+import { FixedScriptWalletNamespace } from "../wasm/wasm_utxo.js";
+import { type WalletKeysArg, RootWalletKeys } from "./RootWalletKeys.js";
+import type { NetworkName } from "./address.js";
+import { BitGoPsbt, type SequenceMode } from "./BitGoPsbt.js";
+import type { ImportedUnspent } from "./coreUnspent.js";
+
+export type MigrationOptions = {
+  /** Maximum number of inputs per transaction */
+  maxInputs: number;
+  /** Fee rate in sat/vB */
+  feeRate: number;
+  /** Derivation index of the first change address, the next transactions use the next ones */
+  changeIndex: number;
+  /** Input sequence (default: "finalNoRbf") */
+  sequence?: SequenceMode;
+};
+
+/**
+ * Plan the transactions moving p2sh and p2shP2wsh unspents to p2trMusig2 change addresses
+ * (chain 41).
+ *
+ * The unspents are split, in the given order, into transactions of at most `maxInputs`
+ * inputs. Each transaction has a single output paying the value of its inputs minus the fee
+ * for its maximum estimated vsize at `feeRate`.
+ *
+ * @example
+ * ```typescript
+ * const unspents = importCoreUnspents(walletKeys, listUnspent, "btc").filter(
+ *   ({ scriptType }) => scriptType === "p2sh" || scriptType === "p2shP2wsh",
+ * );
+ * const psbts = planMigration(walletKeys, unspents, "btc", {
+ *   maxInputs: 200,
+ *   feeRate: 2,
+ *   changeIndex: 0,
+ * });
+ * ```
+ *
+ * @returns The unsigned PSBTs
+ * @throws If an unspent is not p2sh or p2shP2wsh, the network does not support p2trMusig2, or
+ *   the inputs of a transaction do not cover its fee
+ */
+export function planMigration(
+  keys: WalletKeysArg,
+  unspents: Pick<ImportedUnspent, "input" | "scriptId">[],
+  network: NetworkName,
+  options: MigrationOptions,
+): BitGoPsbt[] {
+  const psbts = FixedScriptWalletNamespace.plan_migration(
+    RootWalletKeys.from(keys).wasm,
+    unspents,
+    network,
+    options.maxInputs,
+    options.feeRate,
+    options.changeIndex,
+    options.sequence,
+  ) as Uint8Array[];
+  return psbts.map((bytes) => BitGoPsbt.fromBytes(bytes, network));
+}
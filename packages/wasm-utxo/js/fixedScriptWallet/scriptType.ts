@@ -39,6 +39,19 @@ export const inputScriptTypes = [
  */
 export type InputScriptType = (typeof inputScriptTypes)[number];
 
+/**
+ * Single-sig input script types, for outputs paying to a single wallet key
+ *
+ * These are not 2-of-3 wallet scripts: they occur when funds are sent to an address of a
+ * wallet xpub imported into a single-sig wallet.
+ */
+export const singleSigScriptTypes = ["p2pkh", "p2wpkh", "p2trKeyPath"] as const;
+
+/**
+ * Single-sig input script type
+ */
+export type SingleSigScriptType = (typeof singleSigScriptTypes)[number];
+
 /**
  * Union of all script types that can be checked for network support
  */
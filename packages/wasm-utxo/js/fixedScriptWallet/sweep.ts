@@ -0,0 +1,85 @@
+import { FixedScriptWalletNamespace } from "../wasm/wasm_utxo.js";
+import { type WalletKeysArg, RootWalletKeys } from "./RootWalletKeys.js";
+import type { NetworkName } from "./address.js";
+import { BitGoPsbt, type SequenceMode, type SignPath } from "./BitGoPsbt.js";
+import type { ImportedUnspent } from "./coreUnspent.js";
+
+export type SweepOptions = {
+  /** Address the unspents are swept to */
+  recipient: string;
+  /** Fee rate in sat/vB */
+  feeRate: number;
+  /** Maximum weight of each signed transaction (default: 400000, the standard limit) */
+  maxWeight?: number;
+  /** Signer and cosigner of the inputs; required for p2tr and p2trMusig2 unspents */
+  signPath?: SignPath;
+  /** Input sequence (default: "finalNoRbf") */
+  sequence?: SequenceMode;
+};
+
+/** Size and value of a sweep transaction */
+export type SweepTransaction = {
+  inputCount: number;
+  /** Maximum weight of the signed transaction */
+  weight: number;
+  /** Maximum virtual size of the signed transaction */
+  vsize: number;
+  inputValue: bigint;
+  fee: bigint;
+  /** Value paid to the recipient */
+  outputValue: bigint;
+};
+
+export type SweepPlan = {
+  /** The unsigned PSBTs */
+  psbts: BitGoPsbt[];
+  /** Size and value of each PSBT */
+  transactions: SweepTransaction[];
+  inputValue: bigint;
+  fee: bigint;
+  outputValue: bigint;
+};
+
+/**
+ * Plan the transactions sweeping unspents to a single recipient.
+ *
+ * The unspents are added, in the given order, to a transaction until the next input would take
+ * the signed transaction over `maxWeight`, then a new transaction is started. Each transaction
+ * has a single output paying the value of its inputs minus the fee for its maximum estimated
+ * vsize at `feeRate`.
+ *
+ * @example
+ * ```typescript
+ * const unspents = importCoreUnspents(walletKeys, listUnspent, "btc");
+ * const { psbts, fee } = planSweep(walletKeys, unspents, "btc", {
+ *   recipient: "bc1q...",
+ *   feeRate: 10,
+ *   signPath: { signer: "user", cosigner: "backup" },
+ * });
+ * ```
+ *
+ * @throws If a single input is heavier than `maxWeight`, or the inputs of a transaction do not
+ *   cover its fee and an output above the dust limit of the network
+ */
+export function planSweep(
+  keys: WalletKeysArg,
+  unspents: Pick<ImportedUnspent, "input" | "scriptId">[],
+  network: NetworkName,
+  options: SweepOptions,
+): SweepPlan {
+  const plan = FixedScriptWalletNamespace.plan_sweep(
+    RootWalletKeys.from(keys).wasm,
+    unspents,
+    network,
+    options.recipient,
+    options.feeRate,
+    options.maxWeight,
+    options.signPath?.signer,
+    options.signPath?.cosigner,
+    options.sequence,
+  ) as Omit<SweepPlan, "psbts"> & { psbts: Uint8Array[] };
+  return {
+    ...plan,
+    psbts: plan.psbts.map((bytes) => BitGoPsbt.fromBytes(bytes, network)),
+  };
+}
@@ -10,13 +10,17 @@ void wasm;
 export * as address from "./address.js";
 export * as ast from "./ast/index.js";
 export * as bip322 from "./bip322/index.js";
+export * as electrumWallet from "./electrumWallet.js";
 export * as inscriptions from "./inscriptions.js";
 export * as message from "./message.js";
+export * as psbtEnvelope from "./psbtEnvelope.js";
+export * as networks from "./networks.js";
 export * as utxolibCompat from "./utxolibCompat.js";
 export * as fixedScriptWallet from "./fixedScriptWallet/index.js";
 export * as descriptorWallet from "./descriptorWallet/index.js";
 export * as bip32 from "./bip32.js";
 export * as ecpair from "./ecpair.js";
+export * as tracing from "./tracing.js";
 // Only the most commonly used classes and types are exported at the top level for convenience
 export { ECPair } from "./ecpair.js";
 export { BIP32 } from "./bip32.js";
@@ -38,6 +42,18 @@ export type DescriptorPkType = "derivable" | "definite" | "string";
 
 export type ScriptContext = "tap" | "segwitv0" | "legacy";
 
+/** Result of `Miniscript.compilePolicy` */
+export interface CompiledPolicy {
+  /** `sh`, `wsh` or `tr` descriptor with checksum */
+  descriptor: string;
+  /** Compiled miniscript (undefined for taproot, where the policy is split into leaves) */
+  miniscript?: string;
+  /** Size of the compiled script in bytes (undefined for taproot) */
+  scriptSize?: number;
+  /** Maximum weight of the witness or scriptSig that satisfies the descriptor */
+  maxWeightToSatisfy: number;
+}
+
 export interface ExtParamsConfig {
   drop?: boolean;
   topUnsafe?: boolean;
@@ -48,10 +64,36 @@ export interface ExtParamsConfig {
   rawPkh?: boolean;
 }
 
+export type Timelock = { height: number } | { time: number };
+
+export type SatisfactionPreview = {
+  /** Absolute timelock (block height or unix time) of the path, if any */
+  after: Timelock | null;
+  /** Relative timelock (blocks or seconds) of the path, if any */
+  older: Timelock | null;
+  /**
+   * Height of the first block that can include the spend and median time past the chain must
+   * reach, or null if the path has a relative timelock and no confirmation was passed
+   */
+  unlockAt: { height: number | null; time: number | null } | null;
+};
+
 declare module "./wasm/wasm_utxo.js" {
   interface WrapDescriptor {
     /** These are not the same types of nodes as in the ast module */
     node(): unknown;
+    /**
+     * Timelocks under which the descriptor can be satisfied with signatures of `keys`.
+     * Returns an empty array if the keys cannot satisfy the descriptor at any time.
+     *
+     * @param keys - Compressed or x-only public keys that can sign
+     * @param confirmation - Block that includes the input, with `medianTimePast` of the block
+     *   before it, to resolve relative timelocks
+     */
+    satisfactionPreview(
+      keys: Uint8Array[],
+      confirmation?: { height: number; medianTimePast: number },
+    ): SatisfactionPreview[];
   }
 
   // eslint-disable-next-line @typescript-eslint/no-namespace
@@ -84,6 +126,11 @@ declare module "./wasm/wasm_utxo.js" {
       ctx: ScriptContext,
       extParams?: ExtParamsConfig,
     ): WrapMiniscript;
+    function compilePolicy(
+      policy: string,
+      ctx: ScriptContext,
+      unspendableKey?: string,
+    ): CompiledPolicy;
   }
 
   /** BIP32 derivation data from a PSBT */
@@ -144,8 +191,42 @@ declare module "./wasm/wasm_utxo.js" {
   }
 }
 
+/**
+ * Details of a `ParseInputError.DerivationMismatch` error: the key origins of an input belong
+ * to the wallet, but the wallet keys derive a different script at their chain and index
+ */
+export type DerivationMismatchDetails = {
+  inputIndex: number;
+  /** Script of the wallet at `derivationPath` */
+  expectedScript: Uint8Array;
+  /** Script spent by the input */
+  actualScript: Uint8Array;
+  derivationPath: string;
+};
+
+/**
+ * Details of a `BitGoPsbtError`, raised when adding, signing or finalizing wallet inputs.
+ * Errors that refer to an input have `inputIndex`.
+ */
+export type BitGoPsbtErrorDetails =
+  | { inputIndex: number; inputCount: number }
+  | { value: bigint; maxMoney: bigint }
+  | { chain: number }
+  | { inputIndex: number };
+
 export interface WasmUtxoError extends Error {
+  /** Error type, e.g. `BitGoPsbtError`, or `Error` for errors without a type */
+  name: string;
+  /**
+   * Error type and variant, e.g. `BitGoPsbtError.NotSigned`. Errors caused by another error
+   * append its code, e.g. `BitGoPsbtError.Signing/SignInputError.KeyNotInTapLeaf`
+   */
   code: string;
+  /**
+   * Structured fields of the error, set for `ParseInputError.DerivationMismatch` and most
+   * `BitGoPsbtError` variants
+   */
+  details?: DerivationMismatchDetails | BitGoPsbtErrorDetails;
 }
 
 const WASM_UTXO_ERROR_SYMBOL = Symbol.for("@bitgo/wasm-utxo/error");
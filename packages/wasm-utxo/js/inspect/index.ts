@@ -46,7 +46,7 @@ export const allNetworks: CoinName[] = [
 
 /**
  * Primitive value types that can appear in a Node.
- * Buffer values are hex-encoded strings, Integer is a decimal string for BigInt support.
+ * Buffer values are hex-encoded strings; Integer, I64 and U64 values are bigints.
  */
 export type PrimitiveType =
   | "String"
@@ -68,7 +68,7 @@ export type PrimitiveType =
  */
 export interface Primitive {
   type: PrimitiveType;
-  value?: string | number | boolean;
+  value?: string | number | bigint | boolean;
 }
 
 /**
@@ -80,6 +80,29 @@ export interface Node {
   children: Node[];
 }
 
+/** Primitive types that are encoded as decimal strings in the JSON from WASM */
+const bigintTypes = new Set<PrimitiveType>(["Integer", "I64", "U64"]);
+
+/**
+ * Parse the JSON node tree returned by WASM, turning 64-bit and arbitrary precision
+ * integers into bigints (JSON numbers would lose precision above 2^53).
+ */
+function parseNodeJson(json: string): Node {
+  return JSON.parse(json, (_key, value: unknown) => {
+    if (
+      typeof value === "object" &&
+      value !== null &&
+      "type" in value &&
+      "value" in value &&
+      bigintTypes.has(value.type as PrimitiveType) &&
+      typeof value.value === "string"
+    ) {
+      return { type: value.type, value: BigInt(value.value) };
+    }
+    return value;
+  }) as Node;
+}
+
 /**
  * Parse a PSBT and return a typed node tree.
  *
@@ -90,7 +113,7 @@ export interface Node {
  */
 export function parsePsbtToNode(psbtBytes: Uint8Array, network: CoinName): Node {
   const json = wasmParsePsbtToJson(psbtBytes, network);
-  return JSON.parse(json) as Node;
+  return parseNodeJson(json);
 }
 
 /**
@@ -103,7 +126,7 @@ export function parsePsbtToNode(psbtBytes: Uint8Array, network: CoinName): Node
  */
 export function parseTxToNode(txBytes: Uint8Array, network: CoinName): Node {
   const json = wasmParseTxToJson(txBytes, network);
-  return JSON.parse(json) as Node;
+  return parseNodeJson(json);
 }
 
 /**
@@ -166,7 +189,7 @@ export function tryParseTx(
  */
 export function parsePsbtRawToNode(psbtBytes: Uint8Array, network: CoinName): Node {
   const json = wasmParsePsbtRawToJson(psbtBytes, network);
-  return JSON.parse(json) as Node;
+  return parseNodeJson(json);
 }
 
 /**
@@ -0,0 +1,100 @@
+import { NetworksNamespace } from "./wasm/wasm_utxo.js";
+import type { CoinName } from "./coinName.js";
+import type { UtxolibName } from "./utxolibCompat.js";
+import type { OutputScriptType } from "./fixedScriptWallet/scriptType.js";
+
+export type NetworkParams = {
+  coinName: CoinName;
+  utxolibName: UtxolibName;
+  mainnet: boolean;
+  /** Genesis block hash (display byte order). Only set for bitcoin networks. */
+  genesisBlockHash: string | null;
+  /** Human-readable part of segwit addresses. Null for networks without segwit. */
+  bech32Hrp: string | null;
+  /** BIP-32 extended key version bytes */
+  bip32: { public: number; private: number };
+};
+
+/**
+ * Get the chain parameters of a network
+ *
+ * @param network - Utxolib name (e.g. "bitcoinBitGoSignet") or coin name (e.g. "tbtcbgsig")
+ * @returns The network parameters
+ */
+export function getNetworkParams(network: CoinName | UtxolibName): NetworkParams {
+  return NetworksNamespace.get_network_params(network) as NetworkParams;
+}
+
+/** Capabilities of a network */
+export type NetworkCapabilities = {
+  segwit: boolean;
+  taproot: boolean;
+  p2mr: boolean;
+  /** Fork id of SIGHASH_FORKID signatures, null if the network does not use them */
+  sighashForkId: number | null;
+  /** Relative timelocks (BIP-68 sequence locks and OP_CHECKSEQUENCEVERIFY) are enforced */
+  csv: boolean;
+  /** Fixed-script wallet output script types the network supports */
+  scriptTypes: (OutputScriptType | "p2mr")[];
+  /** Address encodings the network accepts, the default encoding first */
+  addressEncodings: ("base58check" | "bech32" | "bech32m" | "cashaddr")[];
+  /** Relay fee rates and dust rule, null if the network has no table entry (Bitcoin SV) */
+  relayFeeRates: RelayFeeRates | null;
+  /** Maximum amount of a single output */
+  maxMoney: bigint;
+  /** Serialization of the transaction and proprietary fields of a PSBT */
+  psbtFormat: "bitcoin" | "dash" | "zcash";
+};
+
+/**
+ * Get the capability matrix of a network
+ *
+ * @param network - Utxolib name or coin name
+ * @returns The capabilities of the network
+ */
+export function getNetworkCapabilities(network: CoinName | UtxolibName): NetworkCapabilities {
+  return NetworksNamespace.get_capabilities(network) as NetworkCapabilities;
+}
+
+/** Default relay fee rates of a network, in satoshis per 1000 virtual bytes */
+export type RelayFeeRates = {
+  /** Minimum fee rate of a transaction */
+  minRelayFee: bigint;
+  /** Minimum fee rate increase of a replacement */
+  incrementalRelayFee: bigint;
+  /** Outputs below this value add `minRelayFee` to the minimum fee (Dogecoin only) */
+  dustOutputLimit: bigint | null;
+};
+
+/**
+ * Get the default relay fee rates of a network
+ *
+ * @param network - Utxolib name or coin name
+ * @returns The relay fee rates, or null if the network has no table entry (Bitcoin SV)
+ */
+export function getRelayFeeRates(network: CoinName | UtxolibName): RelayFeeRates | null {
+  return NetworksNamespace.get_relay_fee_rates(network) as RelayFeeRates | null;
+}
+
+/**
+ * Check that a transaction pays at least the minimum relay fee of the network
+ *
+ * @param network - Utxolib name or coin name
+ * @param fee - Fee of the transaction
+ * @param vsize - Virtual size of the transaction
+ * @param outputValues - Values of the outputs, for the Dogecoin dust rule
+ * @throws Error if the fee is below the minimum relay fee
+ */
+export function validateFeeRate(
+  network: CoinName | UtxolibName,
+  fee: bigint,
+  vsize: number,
+  outputValues: bigint[],
+): void {
+  NetworksNamespace.validate_fee_rate(
+    network,
+    fee,
+    BigInt(vsize),
+    BigUint64Array.from(outputValues),
+  );
+}
@@ -0,0 +1,74 @@
+/**
+ * Encrypted envelope for transporting serialized PSBTs
+ *
+ * The PSBT is encrypted with XChaCha20-Poly1305 under a key agreed by ECDH between the
+ * sender's and the recipient's secp256k1 keys. The envelope header carries the key ids
+ * (first 4 bytes of HASH160 of the public key) of both keys.
+ *
+ * @example
+ * ```typescript
+ * import { psbtEnvelope } from '@bitgo/wasm-utxo';
+ *
+ * const envelope = psbtEnvelope.seal(psbt.serialize(), userKey, bitgoPublicKey);
+ * const bytes = psbtEnvelope.open(envelope, bitgoKey, userPublicKey);
+ * ```
+ */
+
+import { PsbtEnvelopeNamespace } from "./wasm/wasm_utxo.js";
+import { ECPair, type ECPairArg } from "./ecpair.js";
+
+export type EnvelopeHeader = {
+  /** Key id of the sender's public key */
+  sender: Uint8Array;
+  /** Key id of the recipient's public key */
+  recipient: Uint8Array;
+  nonce: Uint8Array;
+};
+
+/**
+ * Encrypt a serialized PSBT for a recipient
+ *
+ * @param psbt - Serialized PSBT
+ * @param sender - The sender's key (must have a private key)
+ * @param recipient - The recipient's public key
+ * @returns The envelope bytes
+ */
+export function seal(psbt: Uint8Array, sender: ECPairArg, recipient: ECPairArg): Uint8Array {
+  return new Uint8Array(
+    PsbtEnvelopeNamespace.seal(psbt, ECPair.from(sender).wasm, ECPair.from(recipient).wasm),
+  );
+}
+
+/**
+ * Decrypt an envelope
+ *
+ * @param envelope - The envelope bytes
+ * @param recipient - The recipient's key (must have a private key)
+ * @param sender - The sender's public key
+ * @returns The serialized PSBT
+ * @throws Error if the envelope is not addressed from `sender` to `recipient`, or was modified
+ */
+export function open(envelope: Uint8Array, recipient: ECPairArg, sender: ECPairArg): Uint8Array {
+  return new Uint8Array(
+    PsbtEnvelopeNamespace.open(envelope, ECPair.from(recipient).wasm, ECPair.from(sender).wasm),
+  );
+}
+
+/**
+ * Read the header of an envelope without decrypting it, e.g. to look up the keys
+ *
+ * @param envelope - The envelope bytes
+ */
+export function readHeader(envelope: Uint8Array): EnvelopeHeader {
+  return PsbtEnvelopeNamespace.header(envelope) as EnvelopeHeader;
+}
+
+/**
+ * Key id of a key, as found in envelope headers
+ *
+ * @param key - Any key; only the public key is used
+ * @returns First 4 bytes of HASH160 of the compressed public key
+ */
+export function keyId(key: ECPairArg): Uint8Array {
+  return new Uint8Array(PsbtEnvelopeNamespace.key_id(ECPair.from(key).wasm));
+}
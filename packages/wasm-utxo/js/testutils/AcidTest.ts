@@ -11,13 +11,13 @@ import {
   outputScript,
   outputScriptTypes,
   p2shP2pkOutputScript,
-  supportsScriptType,
   type InputScriptType,
   type OutputScriptType,
   type ScriptId,
 } from "../fixedScriptWallet/index.js";
 import type { CoinName } from "../coinName.js";
 import { coinNames, isMainnet } from "../coinName.js";
+import { getNetworkCapabilities } from "../networks.js";
 import { getDefaultWalletKeys, getWalletKeysForSeed, getKeyTriple } from "./keys.js";
 import type { Triple } from "../triple.js";
 
@@ -176,6 +176,9 @@ export class AcidTest {
     const rootWalletKeys = getDefaultWalletKeys();
     const otherWalletKeys = getWalletKeysForSeed("too many secrets");
 
+    const { scriptTypes } = getNetworkCapabilities(coin);
+    const supportsScriptType = (scriptType: OutputScriptType) => scriptTypes.includes(scriptType);
+
     // Filter inputs based on network support
     const inputs: Input[] = inputScriptTypes
       .filter((scriptType) => {
@@ -184,9 +187,9 @@ export class AcidTest {
 
         // Map input script types to output script types for support check
         if (scriptType === "p2trMusig2KeyPath" || scriptType === "p2trMusig2ScriptPath") {
-          return supportsScriptType(coin, "p2trMusig2");
+          return supportsScriptType("p2trMusig2");
         }
-        return supportsScriptType(coin, scriptType);
+        return supportsScriptType(scriptType);
       })
       .filter(
         (scriptType) =>
@@ -200,7 +203,7 @@ export class AcidTest {
 
     // Filter outputs based on network support
     const outputs: Output[] = outputScriptTypes
-      .filter((scriptType) => supportsScriptType(coin, scriptType))
+      .filter((scriptType) => supportsScriptType(scriptType))
       .map((scriptType, index) => ({
         scriptType,
         value: BigInt(900 + index * 100), // Deterministic amounts
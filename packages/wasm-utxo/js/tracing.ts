@@ -0,0 +1,60 @@
+/**
+ * Instrumentation hooks for diagnosing slow operations
+ *
+ * Requires a WASM build with the `tracing` feature
+ * (`make js/wasm WASM_UTXO_FEATURES=tracing`).
+ */
+
+import {
+  setTraceHook as wasmSetTraceHook,
+  isTracingEnabled as wasmIsTracingEnabled,
+} from "./wasm/wasm_utxo.js";
+
+export type TraceLevel = "TRACE" | "DEBUG" | "INFO" | "WARN" | "ERROR";
+
+export type TraceFieldValue = number | boolean | string;
+
+type TraceRecordBase = {
+  /** Operation name, e.g. "deserialize", "parse", "sign", "finalize" or "extract" */
+  name: string;
+  /** Rust module that emitted the record */
+  target: string;
+  level: TraceLevel;
+  /** Structured fields such as `inputs` and `input_index` */
+  fields: Record<string, TraceFieldValue>;
+};
+
+/** A completed operation */
+export type TraceSpan = TraceRecordBase & {
+  kind: "span";
+  durationMs: number;
+};
+
+/** A log event */
+export type TraceEvent = TraceRecordBase & {
+  kind: "event";
+  /** Name of the enclosing operation */
+  span: string | null;
+};
+
+export type TraceRecord = TraceSpan | TraceEvent;
+
+/**
+ * Install a callback that receives trace records, or remove it with `undefined`.
+ *
+ * Exceptions thrown by the callback are ignored.
+ *
+ * @throws if the WASM build does not include the `tracing` feature
+ */
+export function setTraceHook(hook: ((record: TraceRecord) => void) | undefined): void {
+  wasmSetTraceHook(hook);
+}
+
+/**
+ * Check if the tracing feature is enabled in the WASM build.
+ *
+ * @returns true if the feature is enabled, false otherwise
+ */
+export function isTracingEnabled(): boolean {
+  return wasmIsTracingEnabled();
+}
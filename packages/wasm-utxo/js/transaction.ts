@@ -135,6 +135,15 @@ export class ZcashTransaction extends TransactionBase<WasmZcashTransaction> {
     return new ZcashTransaction(wasm);
   }
 
+  /**
+   * Whether the transaction has Sapling spends or outputs or Sprout JoinSplits
+   *
+   * BitGo wallets only handle transparent transactions; PSBTs cannot be created for these.
+   */
+  hasShieldedComponents(): boolean {
+    return this._wasm.has_shielded_components();
+  }
+
   /** @internal */
   get wasm(): WasmZcashTransaction {
     return this._wasm;
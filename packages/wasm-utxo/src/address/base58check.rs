@@ -23,30 +23,26 @@ impl Base58CheckCodec {
     }
 }
 
+/// Big-endian version bytes without leading zero bytes (at least one byte)
+///
+/// Most networks use a single version byte; Zcash uses two (e.g. 0x1cb8 for `t1` addresses).
+fn version_bytes(version: u32) -> Vec<u8> {
+    let bytes = version.to_be_bytes();
+    let start = bytes.iter().position(|&b| b != 0).unwrap_or(3);
+    bytes[start..].to_vec()
+}
+
 /// Encode a hash with version bytes to Base58Check format using bitcoin crate
 fn to_base58_check(hash: &[u8], version: u32) -> Result<String> {
-    let mut data = Vec::new();
-
-    // Encode version bytes (1-4 bytes depending on size)
-    if version <= 0xff {
-        data.push(version as u8);
-    } else if version <= 0xffff {
-        data.extend_from_slice(&(version as u16).to_be_bytes());
-    } else {
-        // For Zcash (up to 4 bytes)
-        let bytes = version.to_be_bytes();
-        let start = bytes.iter().position(|&b| b != 0).unwrap_or(0);
-        data.extend_from_slice(&bytes[start..]);
-    }
-
+    let mut data = version_bytes(version);
     data.extend_from_slice(hash);
 
     // Use bitcoin crate's base58 encode_check which adds the checksum
     Ok(base58::encode_check(&data))
 }
 
-/// Decode a Base58Check address to (hash, version) using bitcoin crate
-fn from_base58_check(address: &str) -> Result<(Vec<u8>, u32)> {
+/// Decode a Base58Check address to its payload (version bytes and hash) using bitcoin crate
+fn from_base58_check(address: &str) -> Result<Vec<u8>> {
     // Use bitcoin crate's base58 decode_check which verifies the checksum
     let payload =
         base58::decode_check(address).map_err(|e| AddressError::Base58Error(e.to_string()))?;
@@ -55,28 +51,7 @@ fn from_base58_check(address: &str) -> Result<(Vec<u8>, u32)> {
         return Err(AddressError::Base58Error("Empty payload".to_string()));
     }
 
-    // Extract version and hash
-    // Try different version byte lengths
-    let (version, hash) = if payload.len() >= 21 && (payload[0] == 0x1c || payload[0] == 0x1d) {
-        // Zcash uses 2-byte versions starting with 0x1c or 0x1d
-        if payload.len() >= 22 {
-            let version = u32::from_be_bytes([0, 0, payload[0], payload[1]]);
-            let hash = payload[2..].to_vec();
-            (version, hash)
-        } else {
-            // Single byte version
-            let version = payload[0] as u32;
-            let hash = payload[1..].to_vec();
-            (version, hash)
-        }
-    } else {
-        // Standard single-byte version
-        let version = payload[0] as u32;
-        let hash = payload[1..].to_vec();
-        (version, hash)
-    };
-
-    Ok((hash, version))
+    Ok(payload)
 }
 
 impl AddressCodec for Base58CheckCodec {
@@ -105,24 +80,32 @@ impl AddressCodec for Base58CheckCodec {
     }
 
     fn decode(&self, address: &str) -> Result<ScriptBuf> {
-        let (hash, version) = from_base58_check(address)?;
+        let payload = from_base58_check(address)?;
 
-        if version == self.pub_key_hash {
+        // The version width is given by the codec, so that a two-byte Zcash version is never
+        // mistaken for a one-byte version followed by a 21-byte hash
+        if let Some(hash) = payload.strip_prefix(version_bytes(self.pub_key_hash).as_slice()) {
             let hash_array: [u8; 20] = hash.try_into().map_err(|_| {
                 AddressError::InvalidAddress("Invalid pubkey hash length".to_string())
             })?;
             let pubkey_hash = PubkeyHash::from_byte_array(hash_array);
             Ok(ScriptBuf::new_p2pkh(&pubkey_hash))
-        } else if version == self.script_hash {
+        } else if let Some(hash) = payload.strip_prefix(version_bytes(self.script_hash).as_slice())
+        {
             let hash_array: [u8; 20] = hash.try_into().map_err(|_| {
                 AddressError::InvalidAddress("Invalid script hash length".to_string())
             })?;
             let script_hash = ScriptHash::from_byte_array(hash_array);
             Ok(ScriptBuf::new_p2sh(&script_hash))
         } else {
+            let version_len = version_bytes(self.pub_key_hash).len().min(payload.len());
             Err(AddressError::InvalidAddress(format!(
                 "Version mismatch: expected {} or {}, got {}",
-                self.pub_key_hash, self.script_hash, version
+                self.pub_key_hash,
+                self.script_hash,
+                payload[..version_len]
+                    .iter()
+                    .fold(0u32, |acc, &b| (acc << 8) | b as u32)
             )))
         }
     }
@@ -17,7 +17,7 @@
 //! - Base58Check encoding/decoding for all networks
 //! - Bech32/Bech32m for witness programs (P2WPKH, P2WSH, P2TR)
 //! - Cashaddr encoding/decoding for Bitcoin Cash and eCash (fully compliant with spec)
-//! - Zcash multi-byte version support
+//! - Zcash multi-byte version support (shielded addresses are rejected)
 //! - P2PKH, P2SH, P2WPKH, P2WSH, P2TR script types
 //!
 //! # Examples
@@ -37,14 +37,16 @@ mod bech32;
 pub mod cashaddr;
 pub mod networks;
 pub mod utxolib_compat;
+mod zcash;
 
 pub use base58check::Base58CheckCodec;
 pub use bech32::Bech32Codec;
 pub use cashaddr::CashAddrCodec;
 pub use networks::{
-    from_output_script_with_coin, from_output_script_with_network, to_output_script_with_coin,
-    to_output_script_with_network,
+    from_output_script_with_coin, from_output_script_with_network, to_output_script_any_network,
+    to_output_script_with_coin, to_output_script_with_network,
 };
+pub use zcash::ZcashShieldedPool;
 
 use crate::bitcoin::{Script, ScriptBuf};
 use std::fmt;
@@ -57,6 +59,8 @@ pub enum AddressError {
     Base58Error(String),
     Bech32Error(String),
     CashaddrError(String),
+    /// A well-formed Zcash shielded address; only transparent addresses have an output script
+    UnsupportedShieldedAddress(ZcashShieldedPool),
 }
 
 impl fmt::Display for AddressError {
@@ -70,6 +74,11 @@ impl fmt::Display for AddressError {
             AddressError::Base58Error(msg) => write!(f, "Base58 error: {}", msg),
             AddressError::Bech32Error(msg) => write!(f, "Bech32 error: {}", msg),
             AddressError::CashaddrError(msg) => write!(f, "Cashaddr error: {}", msg),
+            AddressError::UnsupportedShieldedAddress(pool) => write!(
+                f,
+                "Unsupported Zcash {} shielded address: only transparent addresses are supported",
+                pool
+            ),
         }
     }
 }
@@ -108,6 +117,9 @@ pub const BITCOIN_BECH32: Bech32Codec = Bech32Codec::new("bc");
 
 pub const TESTNET: Base58CheckCodec = Base58CheckCodec::new(0x6f, 0xc4);
 pub const TESTNET_BECH32: Bech32Codec = Bech32Codec::new("tb");
+// Signets (public and custom) use the testnet version bytes and hrp
+pub const SIGNET: Base58CheckCodec = Base58CheckCodec::new(0x6f, 0xc4);
+pub const SIGNET_BECH32: Bech32Codec = Bech32Codec::new("tb");
 pub const REGTEST: Base58CheckCodec = Base58CheckCodec::new(0x6f, 0xc4);
 pub const REGTEST_BECH32: Bech32Codec = Bech32Codec::new("bcrt");
 
@@ -4,14 +4,15 @@
 //! convenient functions to encode/decode addresses using network identifiers.
 
 use super::bech32::is_p2mr;
+use super::zcash::shielded_pool;
 use super::{
-    from_output_script, to_output_script_try_codecs, AddressCodec, AddressError, Result, ScriptBuf,
-    BITCOIN, BITCOIN_BECH32, BITCOIN_CASH, BITCOIN_CASH_CASHADDR, BITCOIN_CASH_TESTNET,
-    BITCOIN_CASH_TESTNET_CASHADDR, BITCOIN_GOLD, BITCOIN_GOLD_BECH32, BITCOIN_GOLD_TESTNET,
-    BITCOIN_GOLD_TESTNET_BECH32, BITCOIN_SV, BITCOIN_SV_TESTNET, DASH, DASH_TEST, DOGECOIN,
-    DOGECOIN_TEST, ECASH, ECASH_CASHADDR, ECASH_TEST, ECASH_TEST_CASHADDR, LITECOIN,
-    LITECOIN_BECH32, LITECOIN_TEST, LITECOIN_TEST_BECH32, REGTEST, REGTEST_BECH32, TESTNET,
-    TESTNET_BECH32, ZCASH, ZCASH_TEST,
+    from_output_script, to_output_script_try_codecs, AddressCodec, AddressError, Base58CheckCodec,
+    CashAddrCodec, Result, ScriptBuf, BITCOIN, BITCOIN_BECH32, BITCOIN_CASH, BITCOIN_CASH_CASHADDR,
+    BITCOIN_CASH_TESTNET, BITCOIN_CASH_TESTNET_CASHADDR, BITCOIN_GOLD, BITCOIN_GOLD_BECH32,
+    BITCOIN_GOLD_TESTNET, BITCOIN_GOLD_TESTNET_BECH32, BITCOIN_SV, BITCOIN_SV_TESTNET, DASH,
+    DASH_TEST, DOGECOIN, DOGECOIN_TEST, ECASH, ECASH_CASHADDR, ECASH_TEST, ECASH_TEST_CASHADDR,
+    LITECOIN, LITECOIN_BECH32, LITECOIN_TEST, LITECOIN_TEST_BECH32, REGTEST, REGTEST_BECH32,
+    SIGNET, SIGNET_BECH32, TESTNET, TESTNET_BECH32, ZCASH, ZCASH_TEST,
 };
 use crate::bitcoin::Script;
 use crate::fixed_script_wallet::wallet_scripts::OutputScriptType;
@@ -23,11 +24,9 @@ use miniscript::bitcoin::WitnessVersion;
 fn get_decode_codecs(network: Network) -> Vec<&'static dyn AddressCodec> {
     match network {
         Network::Bitcoin => vec![&BITCOIN, &BITCOIN_BECH32],
-        Network::BitcoinTestnet3
-        | Network::BitcoinTestnet4
-        | Network::BitcoinPublicSignet
-        | Network::BitcoinBitGoSignet => {
-            vec![&TESTNET, &TESTNET_BECH32]
+        Network::BitcoinTestnet3 | Network::BitcoinTestnet4 => vec![&TESTNET, &TESTNET_BECH32],
+        Network::BitcoinPublicSignet | Network::BitcoinBitGoSignet => {
+            vec![&SIGNET, &SIGNET_BECH32]
         }
         Network::BitcoinRegtest => vec![&REGTEST, &REGTEST_BECH32],
         Network::BitcoinCash => vec![&BITCOIN_CASH, &BITCOIN_CASH_CASHADDR],
@@ -238,16 +237,20 @@ fn get_encode_codec(
                 Ok(&BITCOIN)
             }
         }
-        Network::BitcoinTestnet3
-        | Network::BitcoinTestnet4
-        | Network::BitcoinPublicSignet
-        | Network::BitcoinBitGoSignet => {
+        Network::BitcoinTestnet3 | Network::BitcoinTestnet4 => {
             if is_witness {
                 Ok(&TESTNET_BECH32)
             } else {
                 Ok(&TESTNET)
             }
         }
+        Network::BitcoinPublicSignet | Network::BitcoinBitGoSignet => {
+            if is_witness {
+                Ok(&SIGNET_BECH32)
+            } else {
+                Ok(&SIGNET)
+            }
+        }
         Network::BitcoinRegtest => {
             if is_witness {
                 Ok(&REGTEST_BECH32)
@@ -300,11 +303,37 @@ fn get_encode_codec(
 
 /// Convert an address string to an output script using a Network.
 /// Tries multiple address formats for the given network (Base58, Bech32, CashAddr, etc.)
+/// Zcash shielded addresses fail with `AddressError::UnsupportedShieldedAddress`.
 pub fn to_output_script_with_network(address: &str, network: Network) -> Result<ScriptBuf> {
+    if let Some(pool) = shielded_pool(address, network) {
+        return Err(AddressError::UnsupportedShieldedAddress(pool));
+    }
     let codecs = get_decode_codecs(network);
     to_output_script_try_codecs(address, &codecs)
 }
 
+/// Convert an address string of any supported network to an output script.
+/// Fails if the address is not valid on any network, or if networks disagree on its script.
+pub fn to_output_script_any_network(address: &str) -> Result<ScriptBuf> {
+    let mut scripts: Vec<ScriptBuf> = Network::ALL
+        .iter()
+        .filter_map(|&network| to_output_script_with_network(address, network).ok())
+        .collect();
+    scripts.sort();
+    scripts.dedup();
+    match scripts.len() {
+        1 => Ok(scripts.remove(0)),
+        0 => Err(AddressError::InvalidAddress(format!(
+            "{} is not an address of any supported network",
+            address
+        ))),
+        _ => Err(AddressError::InvalidAddress(format!(
+            "{} decodes to different output scripts depending on the network",
+            address
+        ))),
+    }
+}
+
 /// Convert an output script to an address string using a Network.
 /// Automatically selects the appropriate format based on the script type.
 pub fn from_output_script_with_network(script: &Script, network: Network) -> Result<String> {
@@ -346,12 +375,98 @@ pub fn from_output_script_with_coin_and_format(
     from_output_script_with_network_and_format(script, network, format)
 }
 
+/// Get the base58check and cashaddr codecs of a network that supports cashaddr
+fn get_cashaddr_codecs(
+    network: Network,
+) -> Result<(&'static Base58CheckCodec, &'static CashAddrCodec)> {
+    match network {
+        Network::BitcoinCash => Ok((&BITCOIN_CASH, &BITCOIN_CASH_CASHADDR)),
+        Network::BitcoinCashTestnet => Ok((&BITCOIN_CASH_TESTNET, &BITCOIN_CASH_TESTNET_CASHADDR)),
+        Network::Ecash => Ok((&ECASH, &ECASH_CASHADDR)),
+        Network::EcashTestnet => Ok((&ECASH_TEST, &ECASH_TEST_CASHADDR)),
+        _ => Err(AddressError::UnsupportedScriptType(format!(
+            "Cashaddr format is only supported for Bitcoin Cash and eCash networks, not for {:?}",
+            network
+        ))),
+    }
+}
+
+/// Re-encode a base58check or cashaddr address with `to`, checking that the result
+/// decodes back to the same output script.
+fn convert_cashaddr_network_address(
+    address: &str,
+    network: Network,
+    to: &dyn AddressCodec,
+) -> Result<String> {
+    let (legacy, cashaddr) = get_cashaddr_codecs(network)?;
+    let script = to_output_script_try_codecs(address, &[legacy, cashaddr])?;
+    let converted = to.encode(&script)?;
+    if to.decode(&converted)? != script {
+        return Err(AddressError::InvalidAddress(format!(
+            "Address conversion did not round-trip: {}",
+            address
+        )));
+    }
+    Ok(converted)
+}
+
+/// Convert a Bitcoin Cash or eCash address to cashaddr format.
+/// Accepts legacy base58check or cashaddr input; the cashaddr prefix is optional.
+/// The result always includes the network prefix (e.g. `bitcoincash:`).
+pub fn to_cashaddr_with_network(address: &str, network: Network) -> Result<String> {
+    let (_, cashaddr) = get_cashaddr_codecs(network)?;
+    convert_cashaddr_network_address(address, network, cashaddr)
+}
+
+/// Convert a Bitcoin Cash or eCash address to legacy base58check format.
+/// Accepts legacy base58check or cashaddr input; the cashaddr prefix is optional.
+pub fn to_legacy_address_with_network(address: &str, network: Network) -> Result<String> {
+    let (legacy, _) = get_cashaddr_codecs(network)?;
+    convert_cashaddr_network_address(address, network, legacy)
+}
+
+/// Convert an address to cashaddr format using a BitGo coin name.
+pub fn to_cashaddr_with_coin(address: &str, coin: &str) -> Result<String> {
+    let network = Network::from_coin_name(coin)
+        .ok_or_else(|| AddressError::InvalidAddress(format!("Unknown coin: {}", coin)))?;
+    to_cashaddr_with_network(address, network)
+}
+
+/// Convert an address to legacy base58check format using a BitGo coin name.
+pub fn to_legacy_address_with_coin(address: &str, coin: &str) -> Result<String> {
+    let network = Network::from_coin_name(coin)
+        .ok_or_else(|| AddressError::InvalidAddress(format!("Unknown coin: {}", coin)))?;
+    to_legacy_address_with_network(address, network)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::address::ZcashShieldedPool;
     use crate::bitcoin::hashes::Hash;
     use crate::bitcoin::{PubkeyHash, ScriptBuf};
 
+    #[test]
+    fn test_bech32_hrp_matches_network() {
+        use crate::bitcoin::WPubkeyHash;
+
+        let script = ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros());
+        for &network in Network::ALL {
+            let address = from_output_script_with_network(&script, network);
+            match network.bech32_hrp() {
+                Some(hrp) => {
+                    let address = address.unwrap();
+                    assert!(address.starts_with(&format!("{}1", hrp)), "{}", address);
+                    assert_eq!(
+                        to_output_script_with_network(&address, network).unwrap(),
+                        script
+                    );
+                }
+                None => assert!(address.is_err(), "{}", network),
+            }
+        }
+    }
+
     #[test]
     fn test_to_output_script_with_network() {
         // Bitcoin mainnet P2PKH
@@ -370,6 +485,41 @@ mod tests {
         assert!(script.is_p2pkh());
     }
 
+    #[test]
+    fn test_to_output_script_any_network() {
+        let hash = hex::decode("751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+        let script = ScriptBuf::new_p2sh(&crate::bitcoin::ScriptHash::from_byte_array(
+            hash.try_into().unwrap(),
+        ));
+        for network in [
+            Network::Bitcoin,
+            Network::BitcoinTestnet3,
+            Network::Litecoin,
+            Network::Dogecoin,
+            Network::Zcash,
+        ] {
+            let addr = from_output_script_with_network(&script, network).unwrap();
+            assert_eq!(
+                to_output_script_any_network(&addr).unwrap(),
+                script,
+                "{}",
+                addr
+            );
+        }
+        let cashaddr = from_output_script_with_network_and_format(
+            &script,
+            Network::BitcoinCash,
+            AddressFormat::Cashaddr,
+        )
+        .unwrap();
+        assert_eq!(to_output_script_any_network(&cashaddr).unwrap(), script);
+
+        assert!(matches!(
+            to_output_script_any_network("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb"),
+            Err(AddressError::InvalidAddress(_))
+        ));
+    }
+
     #[test]
     fn test_from_output_script_with_network() {
         // Create a P2PKH script
@@ -436,6 +586,40 @@ mod tests {
         assert_eq!(script, decoded);
     }
 
+    #[test]
+    fn test_zcash_transparent_and_shielded() {
+        // t1 (0x1cb8) and t3 (0x1cbd) addresses round-trip through the coin name
+        for addr in [
+            "t1LcxLfh8seLbbhwEwLYwMFoY3n944fm3Zu",
+            "t3L9BWgkrgFVS6wCSK3w5vwjdhMHtfHnT6r",
+        ] {
+            let script = to_output_script_with_coin(addr, "zec").unwrap();
+            assert_eq!(from_output_script_with_coin(&script, "zec").unwrap(), addr);
+        }
+
+        let sapling =
+            "zs1z7rejlpsa98s2rrrfkwmaxu53e4ue0ulcrw0h4x5g8jl04tak0d3mm47vdtahatqrlkngh9slya";
+        assert!(matches!(
+            to_output_script_with_coin(sapling, "zec"),
+            Err(AddressError::UnsupportedShieldedAddress(
+                ZcashShieldedPool::Sapling
+            ))
+        ));
+        let sprout = "zc8E5gYid86n4bo2Usdq1cpr7PpfoJGzttwBHEEgGhGkLUg7SPPVFNB2AkRFXZ7usfphup5426dt1buMmY3fkYeRrQGLa8y";
+        assert!(matches!(
+            to_output_script_with_coin(sprout, "zec"),
+            Err(AddressError::UnsupportedShieldedAddress(
+                ZcashShieldedPool::Sprout
+            ))
+        ));
+
+        // Malformed input is not reported as shielded
+        assert!(matches!(
+            to_output_script_with_coin("t1LcxLfh8seLbbhwEwLYwMFoY3n944fm3Zv", "zec"),
+            Err(AddressError::InvalidAddress(_))
+        ));
+    }
+
     #[test]
     fn test_witness_addresses() {
         // Create a P2WPKH script
@@ -452,6 +636,61 @@ mod tests {
         assert!(addr.starts_with("ltc1"));
     }
 
+    #[test]
+    fn test_cashaddr_legacy_conversion() {
+        // Spec vectors (P2PKH and P2SH)
+        let cases = [
+            (
+                "1BpEi6DfDAUFd7GtittLSdBeYJvcoaVggu",
+                "bitcoincash:qpm2qsznhks23z7629mms6s4cwef74vcwvy22gdx6a",
+            ),
+            (
+                "3CWFddi6m4ndiGyKqzYvsFYagqDLPVMTzC",
+                "bitcoincash:ppm2qsznhks23z7629mms6s4cwef74vcwvn0h829pq",
+            ),
+        ];
+        for (legacy, cashaddr) in cases {
+            assert_eq!(
+                to_cashaddr_with_network(legacy, Network::BitcoinCash).unwrap(),
+                cashaddr
+            );
+            assert_eq!(
+                to_legacy_address_with_network(cashaddr, Network::BitcoinCash).unwrap(),
+                legacy
+            );
+            // Prefix is optional on input
+            let unprefixed = cashaddr.strip_prefix("bitcoincash:").unwrap();
+            assert_eq!(
+                to_legacy_address_with_coin(unprefixed, "bch").unwrap(),
+                legacy
+            );
+            // Converting to the same format is a no-op
+            assert_eq!(to_cashaddr_with_coin(cashaddr, "bch").unwrap(), cashaddr);
+            assert_eq!(to_legacy_address_with_coin(legacy, "bch").unwrap(), legacy);
+        }
+
+        let ecash = to_cashaddr_with_coin("1BpEi6DfDAUFd7GtittLSdBeYJvcoaVggu", "bcha").unwrap();
+        assert!(ecash.starts_with("ecash:"));
+        assert_eq!(
+            to_legacy_address_with_coin(&ecash, "bcha").unwrap(),
+            "1BpEi6DfDAUFd7GtittLSdBeYJvcoaVggu"
+        );
+    }
+
+    #[test]
+    fn test_cashaddr_conversion_errors() {
+        // Unsupported network
+        assert!(to_cashaddr_with_coin("1BpEi6DfDAUFd7GtittLSdBeYJvcoaVggu", "btc").is_err());
+        // Wrong cashaddr prefix for the network
+        assert!(to_legacy_address_with_coin(
+            "bitcoincash:qpm2qsznhks23z7629mms6s4cwef74vcwvy22gdx6a",
+            "bcha"
+        )
+        .is_err());
+        // Testnet address on mainnet
+        assert!(to_cashaddr_with_coin("mipcBbFg9gMiCh81Kj8tqqdgoZub1ZJRfn", "bch").is_err());
+    }
+
     #[test]
     fn test_cashaddr_format() {
         // Test that Cashaddr format works for Bitcoin Cash
@@ -0,0 +1,130 @@
+//! Recognition of Zcash shielded addresses
+//!
+//! Only transparent addresses (`t1`/`t3` on mainnet, `tm`/`t2` on testnet) correspond to an
+//! output script. Shielded addresses are recognized so that they can be rejected with a
+//! dedicated error instead of being reported as malformed.
+//!
+//! - Sprout: Base58Check with a two-byte version and a 64-byte payload (`zc`/`zt`)
+//! - Sapling: Bech32 with a 43-byte payload (`zs`/`ztestsapling`)
+//!
+//! https://zips.z.cash/protocol/protocol.pdf (5.6.3, 5.6.4)
+
+use crate::bitcoin::base58;
+use crate::networks::Network;
+
+/// Shielded pool of a Zcash address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZcashShieldedPool {
+    Sprout,
+    Sapling,
+}
+
+impl std::fmt::Display for ZcashShieldedPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ZcashShieldedPool::Sprout => write!(f, "Sprout"),
+            ZcashShieldedPool::Sapling => write!(f, "Sapling"),
+        }
+    }
+}
+
+/// Sprout payment address prefixes and Sapling HRPs
+/// https://github.com/zcash/zcash/blob/master/src/chainparams.cpp
+struct ShieldedParams {
+    sprout_version: [u8; 2],
+    sapling_hrp: &'static str,
+}
+
+const ZCASH_SHIELDED: ShieldedParams = ShieldedParams {
+    sprout_version: [0x16, 0x9a],
+    sapling_hrp: "zs",
+};
+
+const ZCASH_TEST_SHIELDED: ShieldedParams = ShieldedParams {
+    sprout_version: [0x16, 0xb6],
+    sapling_hrp: "ztestsapling",
+};
+
+const SPROUT_PAYLOAD_LEN: usize = 64;
+const SAPLING_PAYLOAD_LEN: usize = 43;
+
+/// Return the shielded pool of `address` if it is a well-formed shielded address of `network`
+///
+/// Returns `None` for networks other than Zcash.
+pub(crate) fn shielded_pool(address: &str, network: Network) -> Option<ZcashShieldedPool> {
+    let params = match network {
+        Network::Zcash => &ZCASH_SHIELDED,
+        Network::ZcashTestnet => &ZCASH_TEST_SHIELDED,
+        _ => return None,
+    };
+
+    if let Ok(payload) = base58::decode_check(address) {
+        return payload
+            .strip_prefix(params.sprout_version.as_slice())
+            .filter(|key| key.len() == SPROUT_PAYLOAD_LEN)
+            .map(|_| ZcashShieldedPool::Sprout);
+    }
+
+    match bech32::decode(address) {
+        Ok((hrp, data))
+            if hrp.to_lowercase() == params.sapling_hrp && data.len() == SAPLING_PAYLOAD_LEN =>
+        {
+            Some(ZcashShieldedPool::Sapling)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shielded_pool() {
+        let cases = [
+            (
+                Network::Zcash,
+                "zc8E5gYid86n4bo2Usdq1cpr7PpfoJGzttwBHEEgGhGkLUg7SPPVFNB2AkRFXZ7usfphup5426dt1buMmY3fkYeRrQGLa8y",
+                Some(ZcashShieldedPool::Sprout),
+            ),
+            (
+                Network::ZcashTestnet,
+                "ztJ1EWLKcGwF2S4NA17pAJVdco8Sdkz4AQPxt1cLTEfNuyNswJJc2BbBqYrsRZsp31xbVZwhF7c7a2L9jsF3p3ZwRWpqqyS",
+                Some(ZcashShieldedPool::Sprout),
+            ),
+            (
+                Network::Zcash,
+                "zs1z7rejlpsa98s2rrrfkwmaxu53e4ue0ulcrw0h4x5g8jl04tak0d3mm47vdtahatqrlkngh9slya",
+                Some(ZcashShieldedPool::Sapling),
+            ),
+            (
+                Network::ZcashTestnet,
+                "ztestsapling1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqfhgwqu",
+                Some(ZcashShieldedPool::Sapling),
+            ),
+            // Shielded addresses of the other network
+            (
+                Network::ZcashTestnet,
+                "zs1z7rejlpsa98s2rrrfkwmaxu53e4ue0ulcrw0h4x5g8jl04tak0d3mm47vdtahatqrlkngh9slya",
+                None,
+            ),
+            // Transparent address
+            (Network::Zcash, "t1LcxLfh8seLbbhwEwLYwMFoY3n944fm3Zu", None),
+            // Bad checksum
+            (
+                Network::Zcash,
+                "zs1z7rejlpsa98s2rrrfkwmaxu53e4ue0ulcrw0h4x5g8jl04tak0d3mm47vdtahatqrlkngh9slyb",
+                None,
+            ),
+            // Not Zcash
+            (
+                Network::Bitcoin,
+                "zs1z7rejlpsa98s2rrrfkwmaxu53e4ue0ulcrw0h4x5g8jl04tak0d3mm47vdtahatqrlkngh9slya",
+                None,
+            ),
+        ];
+        for (network, address, expected) in cases {
+            assert_eq!(shielded_pool(address, network), expected, "{}", address);
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Network capability matrix
+//!
+//! [`Network::capabilities`] collects what a network supports in one place: output script
+//! types, signature hash quirks, relative timelocks, address encodings, dust rules and the
+//! PSBT serialization the network uses. Callers that need to branch on the network should
+//! read it from here instead of matching on [`Network`] variants.
+
+use crate::fixed_script_wallet::bitgo_psbt::get_sighash_fork_id;
+use crate::fixed_script_wallet::wallet_scripts::OutputScriptType;
+use crate::networks::Network;
+use crate::relay_fee::RelayFeeRates;
+
+/// Address encoding accepted by a network
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressEncoding {
+    /// Base58Check p2pkh and p2sh addresses
+    Base58Check,
+    /// BIP-173 addresses of witness version 0
+    Bech32,
+    /// BIP-350 addresses of witness version 1 and above
+    Bech32m,
+    /// Cashaddr addresses (Bitcoin Cash and eCash)
+    Cashaddr,
+}
+
+impl AddressEncoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AddressEncoding::Base58Check => "base58check",
+            AddressEncoding::Bech32 => "bech32",
+            AddressEncoding::Bech32m => "bech32m",
+            AddressEncoding::Cashaddr => "cashaddr",
+        }
+    }
+}
+
+/// Serialization of the unsigned transaction and the proprietary fields of a PSBT
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PsbtFormat {
+    /// BIP-174 PSBT with a Bitcoin transaction
+    Bitcoin,
+    /// Dash special transactions carry a type and an extra payload
+    Dash,
+    /// Zcash v4/v5 transactions with the consensus branch id stored in the PSBT
+    Zcash,
+}
+
+impl PsbtFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PsbtFormat::Bitcoin => "bitcoin",
+            PsbtFormat::Dash => "dash",
+            PsbtFormat::Zcash => "zcash",
+        }
+    }
+}
+
+/// Capabilities of a network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkCapabilities {
+    pub segwit: bool,
+    pub taproot: bool,
+    pub p2mr: bool,
+    /// Fork id of SIGHASH_FORKID signatures, `None` if the network does not use them
+    pub sighash_fork_id: Option<u32>,
+    /// Relative timelocks (BIP-68 sequence locks and `OP_CHECKSEQUENCEVERIFY`) are enforced
+    pub csv: bool,
+    /// Fixed-script wallet output script types the network supports
+    pub script_types: Vec<OutputScriptType>,
+    /// Address encodings the network accepts, the default encoding first
+    pub address_encodings: Vec<AddressEncoding>,
+    /// Relay fee rates and dust rule, `None` if the network has no table entry
+    pub relay_fee_rates: Option<RelayFeeRates>,
+    /// Maximum amount of a single output, in satoshis
+    pub max_money: u64,
+    pub psbt_format: PsbtFormat,
+}
+
+impl NetworkCapabilities {
+    /// Check if the network supports a given fixed-script wallet script type
+    pub fn supports_script_type(&self, script_type: OutputScriptType) -> bool {
+        self.script_types.contains(&script_type)
+    }
+}
+
+impl Network {
+    pub fn capabilities(self) -> NetworkCapabilities {
+        let support = self.output_script_support();
+
+        let script_types = OutputScriptType::all()
+            .iter()
+            .copied()
+            .filter(|&script_type| support.supports_script_type(script_type))
+            .collect();
+
+        let mut address_encodings = vec![AddressEncoding::Base58Check];
+        if support.segwit {
+            address_encodings.push(AddressEncoding::Bech32);
+        }
+        if support.taproot || support.p2mr {
+            address_encodings.push(AddressEncoding::Bech32m);
+        }
+        if matches!(self.mainnet(), Network::BitcoinCash | Network::Ecash) {
+            address_encodings.push(AddressEncoding::Cashaddr);
+        }
+
+        // Bitcoin SV removed relative timelocks with the Genesis upgrade; Dogecoin and Zcash
+        // never activated BIP-68.
+        let csv = !matches!(
+            self.mainnet(),
+            Network::BitcoinSV | Network::Dogecoin | Network::Zcash
+        );
+
+        let psbt_format = match self.mainnet() {
+            Network::Dash => PsbtFormat::Dash,
+            Network::Zcash => PsbtFormat::Zcash,
+            _ => PsbtFormat::Bitcoin,
+        };
+
+        NetworkCapabilities {
+            segwit: support.segwit,
+            taproot: support.taproot,
+            p2mr: support.p2mr,
+            sighash_fork_id: get_sighash_fork_id(self),
+            csv,
+            script_types,
+            address_encodings,
+            relay_fee_rates: self.relay_fee_rates(),
+            max_money: self.max_money(),
+            psbt_format,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities() {
+        let btc = Network::Bitcoin.capabilities();
+        assert_eq!(btc.script_types, OutputScriptType::all().to_vec());
+        assert_eq!(
+            btc.address_encodings,
+            vec![
+                AddressEncoding::Base58Check,
+                AddressEncoding::Bech32,
+                AddressEncoding::Bech32m
+            ]
+        );
+        assert_eq!(btc.sighash_fork_id, None);
+        assert!(btc.csv);
+        assert_eq!(btc.psbt_format, PsbtFormat::Bitcoin);
+
+        let bch = Network::BitcoinCashTestnet.capabilities();
+        assert_eq!(bch.script_types, vec![OutputScriptType::P2sh]);
+        assert_eq!(
+            bch.address_encodings,
+            vec![AddressEncoding::Base58Check, AddressEncoding::Cashaddr]
+        );
+        assert_eq!(bch.sighash_fork_id, Some(0));
+
+        assert_eq!(
+            Network::BitcoinGold.capabilities().sighash_fork_id,
+            Some(79)
+        );
+        assert!(!Network::BitcoinSV.capabilities().csv);
+
+        let doge = Network::Dogecoin.capabilities();
+        assert!(!doge.csv);
+        assert!(doge.relay_fee_rates.unwrap().dust_output_limit.is_some());
+
+        assert_eq!(Network::Dash.capabilities().psbt_format, PsbtFormat::Dash);
+        assert_eq!(
+            Network::ZcashTestnet.capabilities().psbt_format,
+            PsbtFormat::Zcash
+        );
+    }
+
+    #[test]
+    fn test_supports_script_type_matches_output_script_support() {
+        for &network in Network::ALL {
+            let capabilities = network.capabilities();
+            let support = network.output_script_support();
+            for &script_type in OutputScriptType::all() {
+                assert_eq!(
+                    capabilities.supports_script_type(script_type),
+                    support.supports_script_type(script_type),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,426 @@
+//! Electrum 2.x single-sig wallets
+//!
+//! Electrum derives the keys of a standard wallet from its master public key (mpk) as
+//! `mpk/0/i` for receive and `mpk/1/i` for change addresses. Standard wallets pay to P2PKH,
+//! segwit wallets (mpk exported as `zpub`/`vpub`) to P2WPKH.
+//!
+//! To reuse the PSBT machinery of `fixed_script_wallet`, [`ElectrumWalletKeys`] wraps the mpk
+//! in a pseudo [`RootWalletKeys`] that holds the mpk as all three keys with an empty
+//! derivation prefix. Electrum outputs are spent as single-sig inputs of the user key (see
+//! [`crate::fixed_script_wallet::bitgo_psbt::single_sig`]).
+//!
+//! Seeds are decoded like Electrum 2.x does (PBKDF2-HMAC-SHA512 with the salt `"electrum"`
+//! and the passphrase). Only ASCII seeds are supported, since Electrum normalizes other seeds
+//! with Unicode NFKD.
+
+use crate::bitcoin::base58;
+use crate::bitcoin::bip32::{ChildNumber, DerivationPath, Xpriv, Xpub};
+use crate::bitcoin::secp256k1::{self, SecretKey};
+use crate::bitcoin::{ScriptBuf, Txid};
+use crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::SignerKey;
+use crate::fixed_script_wallet::bitgo_psbt::{BitGoPsbt, SingleSigScriptType, WalletInputOptions};
+use crate::fixed_script_wallet::RootWalletKeys;
+use crate::networks::Network;
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+
+const XPUB_VERSION: [u8; 4] = [0x04, 0x88, 0xb2, 0x1e];
+const TPUB_VERSION: [u8; 4] = [0x04, 0x35, 0x87, 0xcf];
+const ZPUB_VERSION: [u8; 4] = [0x04, 0xb2, 0x47, 0x46];
+const VPUB_VERSION: [u8; 4] = [0x04, 0x5f, 0x1c, 0xf6];
+
+const PBKDF2_ROUNDS: u32 = 2048;
+
+/// Electrum seed type, encoded in the seed version prefix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElectrumSeedType {
+    /// Seed version prefix `01`, P2PKH wallet with the mpk at `m`
+    Standard,
+    /// Seed version prefix `100`, P2WPKH wallet with the mpk at `m/0'`
+    Segwit,
+}
+
+impl ElectrumSeedType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ElectrumSeedType::Standard => "standard",
+            ElectrumSeedType::Segwit => "segwit",
+        }
+    }
+
+    pub fn script_type(self) -> SingleSigScriptType {
+        match self {
+            ElectrumSeedType::Standard => SingleSigScriptType::P2pkh,
+            ElectrumSeedType::Segwit => SingleSigScriptType::P2wpkh,
+        }
+    }
+
+    /// Path from the seed root to the mpk
+    fn account_path(self) -> DerivationPath {
+        match self {
+            ElectrumSeedType::Standard => DerivationPath::master(),
+            ElectrumSeedType::Segwit => {
+                DerivationPath::from(vec![ChildNumber::Hardened { index: 0 }])
+            }
+        }
+    }
+}
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum ElectrumWalletError {
+    /// The mpk is not a valid xpub, tpub, zpub or vpub
+    InvalidMpk(String),
+    /// The seed is not a valid Electrum 2.x seed
+    InvalidSeed(String),
+    /// The seed is valid but of a type that cannot be imported (e.g. 2FA)
+    UnsupportedSeedType(&'static str),
+    /// Key derivation or address encoding failed
+    Derivation(String),
+}
+
+impl std::fmt::Display for ElectrumWalletError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ElectrumWalletError::InvalidMpk(e) => write!(f, "Invalid master public key: {}", e),
+            ElectrumWalletError::InvalidSeed(e) => write!(f, "Invalid Electrum seed: {}", e),
+            ElectrumWalletError::UnsupportedSeedType(seed_type) => {
+                write!(f, "Unsupported Electrum seed type: {}", seed_type)
+            }
+            ElectrumWalletError::Derivation(e) => write!(f, "Derivation failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ElectrumWalletError {}
+
+crate::impl_wasm_error_code!(ElectrumWalletError);
+
+/// Master public key of an Electrum wallet
+#[derive(Debug, Clone)]
+pub struct ElectrumWalletKeys {
+    pub mpk: Xpub,
+    pub seed_type: ElectrumSeedType,
+    wallet_keys: RootWalletKeys,
+}
+
+impl ElectrumWalletKeys {
+    pub fn new(mpk: Xpub, seed_type: ElectrumSeedType) -> Self {
+        let wallet_keys = RootWalletKeys::new_with_derivation_prefixes(
+            [mpk, mpk, mpk],
+            [
+                DerivationPath::master(),
+                DerivationPath::master(),
+                DerivationPath::master(),
+            ],
+        );
+        Self {
+            mpk,
+            seed_type,
+            wallet_keys,
+        }
+    }
+
+    /// Import the master public key shown by Electrum
+    ///
+    /// `xpub`/`tpub` keys are standard wallets, `zpub`/`vpub` keys segwit wallets.
+    pub fn from_mpk(mpk: &str) -> Result<Self, ElectrumWalletError> {
+        let mut data = base58::decode_check(mpk)
+            .map_err(|e| ElectrumWalletError::InvalidMpk(e.to_string()))?;
+        if data.len() != 78 {
+            return Err(ElectrumWalletError::InvalidMpk(format!(
+                "expected 78 bytes, got {}",
+                data.len()
+            )));
+        }
+        let version: [u8; 4] = data[..4].try_into().expect("78 bytes");
+        let (version, seed_type) = match version {
+            XPUB_VERSION => (XPUB_VERSION, ElectrumSeedType::Standard),
+            TPUB_VERSION => (TPUB_VERSION, ElectrumSeedType::Standard),
+            ZPUB_VERSION => (XPUB_VERSION, ElectrumSeedType::Segwit),
+            VPUB_VERSION => (TPUB_VERSION, ElectrumSeedType::Segwit),
+            _ => {
+                return Err(ElectrumWalletError::InvalidMpk(format!(
+                    "unknown version bytes {:02x?}",
+                    version
+                )))
+            }
+        };
+        data[..4].copy_from_slice(&version);
+        let mpk =
+            Xpub::decode(&data).map_err(|e| ElectrumWalletError::InvalidMpk(e.to_string()))?;
+        Ok(Self::new(mpk, seed_type))
+    }
+
+    /// Decode an Electrum 2.x seed
+    ///
+    /// Returns the wallet keys and the extended private key of the mpk, which signs the inputs.
+    pub fn from_seed(
+        mnemonic: &str,
+        passphrase: &str,
+        network: Network,
+    ) -> Result<(Self, Xpriv), ElectrumWalletError> {
+        let mnemonic = normalize_text(mnemonic)?;
+        let passphrase = normalize_text(passphrase)?;
+        let seed_type = seed_type(&mnemonic)?;
+
+        let mut salt = b"electrum".to_vec();
+        salt.extend_from_slice(passphrase.as_bytes());
+        let mut seed = [0u8; 64];
+        pbkdf2::pbkdf2_hmac::<Sha512>(mnemonic.as_bytes(), &salt, PBKDF2_ROUNDS, &mut seed);
+
+        let secp = secp256k1::Secp256k1::new();
+        let network_kind = if network.is_testnet() {
+            crate::bitcoin::NetworkKind::Test
+        } else {
+            crate::bitcoin::NetworkKind::Main
+        };
+        let xprv = Xpriv::new_master(network_kind, &seed)
+            .and_then(|root| root.derive_priv(&secp, &seed_type.account_path()))
+            .map_err(|e| ElectrumWalletError::Derivation(e.to_string()))?;
+        let mpk = Xpub::from_priv(&secp, &xprv);
+        Ok((Self::new(mpk, seed_type), xprv))
+    }
+
+    /// The mpk in Electrum's export format (`zpub`/`vpub` for segwit wallets)
+    pub fn to_mpk_string(&self) -> String {
+        let mut data = self.mpk.encode();
+        if self.seed_type == ElectrumSeedType::Segwit {
+            let version = if data[..4] == XPUB_VERSION {
+                ZPUB_VERSION
+            } else {
+                VPUB_VERSION
+            };
+            data[..4].copy_from_slice(&version);
+        }
+        base58::encode_check(&data)
+    }
+
+    /// Pseudo wallet keys for the PSBT machinery; the mpk is the user key
+    pub fn root_wallet_keys(&self) -> &RootWalletKeys {
+        &self.wallet_keys
+    }
+
+    /// `m/0/index` for receive and `m/1/index` for change addresses
+    pub fn derivation_path(change: bool, index: u32) -> DerivationPath {
+        DerivationPath::from(vec![
+            ChildNumber::Normal {
+                index: u32::from(change),
+            },
+            ChildNumber::Normal { index },
+        ])
+    }
+
+    pub fn output_script(
+        &self,
+        change: bool,
+        index: u32,
+    ) -> Result<ScriptBuf, ElectrumWalletError> {
+        let secp = secp256k1::Secp256k1::verification_only();
+        let key = self
+            .mpk
+            .derive_pub(&secp, &Self::derivation_path(change, index))
+            .map_err(|e| ElectrumWalletError::Derivation(e.to_string()))?;
+        Ok(self
+            .seed_type
+            .script_type()
+            .output_script(&secp, &key.to_pub()))
+    }
+
+    pub fn address(
+        &self,
+        change: bool,
+        index: u32,
+        network: Network,
+    ) -> Result<String, ElectrumWalletError> {
+        let script = self.output_script(change, index)?;
+        crate::address::networks::from_output_script_with_network(&script, network)
+            .map_err(|e| ElectrumWalletError::Derivation(e.to_string()))
+    }
+
+    /// Add an input spending the output at `change`/`index` to `psbt`
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_input(
+        &self,
+        psbt: &mut BitGoPsbt,
+        txid: Txid,
+        vout: u32,
+        value: u64,
+        change: bool,
+        index: u32,
+        options: WalletInputOptions,
+    ) -> Result<usize, String> {
+        psbt.add_single_sig_input(
+            txid,
+            vout,
+            value,
+            &self.wallet_keys,
+            SignerKey::User,
+            &Self::derivation_path(change, index),
+            self.seed_type.script_type(),
+            options,
+        )
+    }
+
+    /// The private key of the output at `change`/`index`, derived from the mpk's xprv
+    pub fn signing_key(
+        xprv: &Xpriv,
+        change: bool,
+        index: u32,
+    ) -> Result<SecretKey, ElectrumWalletError> {
+        let secp = secp256k1::Secp256k1::new();
+        xprv.derive_priv(&secp, &Self::derivation_path(change, index))
+            .map(|derived| derived.private_key)
+            .map_err(|e| ElectrumWalletError::Derivation(e.to_string()))
+    }
+}
+
+/// Lowercase and collapse whitespace like Electrum's `normalize_text` for ASCII text
+fn normalize_text(text: &str) -> Result<String, ElectrumWalletError> {
+    if !text.is_ascii() {
+        return Err(ElectrumWalletError::InvalidSeed(
+            "only ASCII seeds and passphrases are supported".to_string(),
+        ));
+    }
+    Ok(text
+        .to_ascii_lowercase()
+        .split_ascii_whitespace()
+        .collect::<Vec<_>>()
+        .join(" "))
+}
+
+fn hmac_sha512(key: &[u8], data: &[u8]) -> [u8; 64] {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
+/// Seed type from the version prefix of `HMAC-SHA512("Seed version", mnemonic)`
+fn seed_type(mnemonic: &str) -> Result<ElectrumSeedType, ElectrumWalletError> {
+    let hash = hmac_sha512(b"Seed version", mnemonic.as_bytes());
+    // The prefixes are hex digits: "01" is one byte, "100"/"101"/"102" are three nibbles
+    match (hash[0], hash[1] >> 4) {
+        (0x01, _) => Ok(ElectrumSeedType::Standard),
+        (0x10, 0x0) => Ok(ElectrumSeedType::Segwit),
+        (0x10, 0x1) | (0x10, 0x2) => Err(ElectrumWalletError::UnsupportedSeedType("2fa")),
+        _ => Err(ElectrumWalletError::InvalidSeed(
+            "unknown seed version (BIP39 and Electrum 1.x seeds are not supported)".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::hashes::Hash;
+    use std::str::FromStr;
+
+    // Test vectors from Electrum's test_wallet_vertical.py
+    const STANDARD_SEED: &str =
+        "cycle rocket west magnet parrot shuffle foot correct salt library feed song";
+    const STANDARD_MPK: &str = "xpub661MyMwAqRbcFWohJWt7PHsFEJfZAvw9ZxwQoDa4SoMgsDDM1T7WK3u9E4edkC4ugRnZ8E4xDZRpk8Rnts3Nbt97dPwT52CwBdDWroaZf8U";
+    const SEGWIT_SEED: &str =
+        "bitter grass shiver impose acquire brush forget axis eager alone wine silver";
+
+    #[test]
+    fn test_standard_seed() {
+        let (keys, xprv) =
+            ElectrumWalletKeys::from_seed(STANDARD_SEED, "", Network::Bitcoin).unwrap();
+        assert_eq!(keys.seed_type, ElectrumSeedType::Standard);
+        assert_eq!(keys.to_mpk_string(), STANDARD_MPK);
+        assert_eq!(
+            keys.address(false, 0, Network::Bitcoin).unwrap(),
+            "1NNkttn1YvVGdqBW4PR6zvc3Zx3H5owKRf"
+        );
+        assert_eq!(
+            keys.address(true, 0, Network::Bitcoin).unwrap(),
+            "1KSezYMhAJMWqFbVFB2JshYg69UpmEXR4D"
+        );
+
+        let from_mpk = ElectrumWalletKeys::from_mpk(STANDARD_MPK).unwrap();
+        assert_eq!(from_mpk.mpk, keys.mpk);
+
+        // Seeds are case and whitespace insensitive
+        let (normalized, _) = ElectrumWalletKeys::from_seed(
+            &format!("  {}  ", STANDARD_SEED.to_uppercase().replace(' ', "\t")),
+            "",
+            Network::Bitcoin,
+        )
+        .unwrap();
+        assert_eq!(normalized.mpk, keys.mpk);
+
+        let secp = secp256k1::Secp256k1::new();
+        let signing_key = ElectrumWalletKeys::signing_key(&xprv, false, 0).unwrap();
+        let pubkey = keys
+            .mpk
+            .derive_pub(&secp, &ElectrumWalletKeys::derivation_path(false, 0))
+            .unwrap();
+        assert_eq!(signing_key.public_key(&secp), pubkey.public_key);
+    }
+
+    #[test]
+    fn test_segwit_seed() {
+        let (keys, _) = ElectrumWalletKeys::from_seed(SEGWIT_SEED, "", Network::Bitcoin).unwrap();
+        assert_eq!(keys.seed_type, ElectrumSeedType::Segwit);
+        assert!(keys.to_mpk_string().starts_with("zpub"));
+        assert_eq!(
+            keys.address(false, 0, Network::Bitcoin).unwrap(),
+            "bc1q3g5tmkmlvxryhh843v4dz026avatc0zzr6h3af"
+        );
+
+        let from_mpk = ElectrumWalletKeys::from_mpk(&keys.to_mpk_string()).unwrap();
+        assert_eq!(from_mpk.seed_type, ElectrumSeedType::Segwit);
+        assert_eq!(from_mpk.mpk, keys.mpk);
+    }
+
+    #[test]
+    fn test_invalid_seed() {
+        assert!(matches!(
+            ElectrumWalletKeys::from_seed("not an electrum seed", "", Network::Bitcoin),
+            Err(ElectrumWalletError::InvalidSeed(_))
+        ));
+        assert!(matches!(
+            ElectrumWalletKeys::from_mpk("xprv9s21ZrQH143K32jECVM729vWgGq4mUDJCk1ozqAStTphzQtCTuoFmFafNoG1g55iCnBTXUzz3zWnDb5CVLGiFvmaZjuazHDL8a81cPQ8KL6"),
+            Err(ElectrumWalletError::InvalidMpk(_))
+        ));
+    }
+
+    #[test]
+    fn test_sweep_psbt() {
+        let (keys, xprv) =
+            ElectrumWalletKeys::from_seed(STANDARD_SEED, "", Network::Bitcoin).unwrap();
+        let mut psbt = BitGoPsbt::new(Network::Bitcoin, keys.root_wallet_keys(), None, None);
+        for (vout, (change, index)) in [(false, 0), (true, 3)].into_iter().enumerate() {
+            keys.add_input(
+                &mut psbt,
+                Txid::all_zeros(),
+                vout as u32,
+                10_000,
+                change,
+                index,
+                WalletInputOptions::default(),
+            )
+            .unwrap();
+        }
+        psbt.add_output(
+            ScriptBuf::from_hex("0014751e76e8199196d454941c45d1b3a323f1433bd6").unwrap(),
+            19_000,
+        )
+        .unwrap();
+
+        for (input_index, (change, index)) in [(false, 0), (true, 3)].into_iter().enumerate() {
+            let key = ElectrumWalletKeys::signing_key(&xprv, change, index).unwrap();
+            psbt.sign_with_privkey(input_index, &key).unwrap();
+        }
+        psbt.finalize_mut(&secp256k1::Secp256k1::verification_only())
+            .unwrap();
+
+        // The input derivation paths are the Electrum paths
+        let path = &psbt.psbt().inputs[1]
+            .bip32_derivation
+            .values()
+            .next()
+            .unwrap()
+            .1;
+        assert_eq!(*path, DerivationPath::from_str("m/1/3").unwrap());
+    }
+}
@@ -1,6 +1,8 @@
 use core::fmt;
 
-use crate::fixed_script_wallet::bitgo_psbt::ParseTransactionError;
+use crate::fixed_script_wallet::bitgo_psbt::{
+    BitGoPsbtError, OperationCancelled, ParseTransactionError,
+};
 
 pub trait WasmErrorCode {
     fn code(&self) -> String;
@@ -19,10 +21,30 @@ macro_rules! impl_wasm_error_code {
     };
 }
 
+/// Implements `From<$t> for WasmUtxoError` for leaf error enums, keeping their code.
+/// Requires `WasmErrorCode` (see `impl_wasm_error_code!`) and `Display` on the enum.
+#[macro_export]
+macro_rules! impl_from_coded_error {
+    ($t:ty) => {
+        impl From<$t> for $crate::error::WasmUtxoError {
+            fn from(err: $t) -> Self {
+                $crate::error::WasmUtxoError::coded(&err)
+            }
+        }
+    };
+}
+
 #[derive(Debug, strum::IntoStaticStr)]
 pub enum WasmUtxoError {
     StringError(String),
+    /// A leaf error of another module, with its code
+    Coded {
+        code: String,
+        message: String,
+    },
     Parse(ParseTransactionError),
+    Cancelled(OperationCancelled),
+    BitGoPsbt(BitGoPsbtError),
 }
 
 impl std::error::Error for WasmUtxoError {}
@@ -31,7 +53,10 @@ impl fmt::Display for WasmUtxoError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             WasmUtxoError::StringError(s) => write!(f, "{}", s),
+            WasmUtxoError::Coded { message, .. } => write!(f, "{}", message),
             WasmUtxoError::Parse(e) => write!(f, "{}", e),
+            WasmUtxoError::Cancelled(e) => write!(f, "{}", e),
+            WasmUtxoError::BitGoPsbt(e) => write!(f, "{}", e),
         }
     }
 }
@@ -40,7 +65,10 @@ impl WasmErrorCode for WasmUtxoError {
     fn code(&self) -> String {
         match self {
             WasmUtxoError::StringError(_) => "WasmUtxoError.StringError".to_string(),
+            WasmUtxoError::Coded { code, .. } => code.clone(),
             WasmUtxoError::Parse(e) => e.code(),
+            WasmUtxoError::Cancelled(_) => "WasmUtxoError.Cancelled".to_string(),
+            WasmUtxoError::BitGoPsbt(e) => e.code(),
         }
     }
 }
@@ -81,11 +109,44 @@ impl From<ParseTransactionError> for WasmUtxoError {
     }
 }
 
+impl From<OperationCancelled> for WasmUtxoError {
+    fn from(err: OperationCancelled) -> Self {
+        WasmUtxoError::Cancelled(err)
+    }
+}
+
+impl From<BitGoPsbtError> for WasmUtxoError {
+    fn from(err: BitGoPsbtError) -> Self {
+        WasmUtxoError::BitGoPsbt(err)
+    }
+}
+
 impl WasmUtxoError {
     pub fn new(s: &str) -> WasmUtxoError {
         WasmUtxoError::StringError(s.to_string())
     }
 
+    /// Convert a leaf error, keeping its code
+    pub fn coded<E: WasmErrorCode + fmt::Display>(err: &E) -> WasmUtxoError {
+        WasmUtxoError::Coded {
+            code: err.code(),
+            message: err.to_string(),
+        }
+    }
+
+    /// Append `context` to the message, keeping the code
+    pub fn with_context(self, context: &str) -> WasmUtxoError {
+        match self {
+            WasmUtxoError::StringError(s) => {
+                WasmUtxoError::StringError(format!("{} ({})", s, context))
+            }
+            e => WasmUtxoError::Coded {
+                code: e.code(),
+                message: format!("{} ({})", e, context),
+            },
+        }
+    }
+
     pub fn from_errors<E: fmt::Display>(errors: impl IntoIterator<Item = E>) -> WasmUtxoError {
         let messages: Vec<String> = errors.into_iter().map(|e| e.to_string()).collect();
         WasmUtxoError::StringError(format!(
@@ -111,6 +172,87 @@ mod tests {
         assert_eq!(e.code(), "WasmUtxoError.StringError");
     }
 
+    #[test]
+    fn coded_error_keeps_code() {
+        let e = WasmUtxoError::coded(&crate::relay_fee::RelayFeeError::BelowMinRelayFee {
+            network: crate::Network::Bitcoin,
+            fee: 100,
+            min_fee: 110,
+        });
+        assert_eq!(e.code(), "RelayFeeError.BelowMinRelayFee");
+        assert_eq!(
+            e.to_string(),
+            "Fee of 100 is below the minimum relay fee of 110 on Bitcoin"
+        );
+    }
+
+    #[test]
+    fn with_context_keeps_code() {
+        let e = WasmUtxoError::from(BitGoPsbtError::NotSigned { input_index: 2 })
+            .with_context("field: psbt");
+        assert_eq!(e.code(), "BitGoPsbtError.NotSigned");
+        assert_eq!(
+            e.to_string(),
+            "Input 2 was not signed (no key found or already signed) (field: psbt)"
+        );
+
+        let e = WasmUtxoError::new("oops").with_context("field: psbt");
+        assert_eq!(e.code(), "WasmUtxoError.StringError");
+        assert_eq!(e.to_string(), "oops (field: psbt)");
+    }
+
+    #[test]
+    fn cancelled_code() {
+        let e = WasmUtxoError::from(OperationCancelled {
+            processed: 100,
+            total: 250,
+        });
+        assert_eq!(e.code(), "WasmUtxoError.Cancelled");
+        assert_eq!(e.to_string(), "Operation cancelled after 100 of 250 inputs");
+    }
+
+    #[test]
+    fn bitgo_psbt_code() {
+        let e = WasmUtxoError::from(BitGoPsbtError::NotSigned { input_index: 2 });
+        assert_eq!(e.code(), "BitGoPsbtError.NotSigned");
+        assert_eq!(
+            e.to_string(),
+            "Input 2 was not signed (no key found or already signed)"
+        );
+    }
+
+    #[test]
+    fn bitgo_psbt_nested_code() {
+        use crate::fixed_script_wallet::bitgo_psbt::{
+            FinalizeInputError, SignInputError, WalletInputError,
+        };
+
+        let e = WasmUtxoError::from(BitGoPsbtError::Signing {
+            input_index: 1,
+            error: SignInputError::KeyNotInTapLeaf,
+        });
+        assert_eq!(
+            e.code(),
+            "BitGoPsbtError.Signing/SignInputError.KeyNotInTapLeaf"
+        );
+        assert_eq!(
+            e.to_string(),
+            "Failed to sign input 1: Public key not found in any tap leaf script"
+        );
+        assert_eq!(
+            BitGoPsbtError::Finalize {
+                input_index: 0,
+                error: FinalizeInputError::MissingConsensusBranchId,
+            }
+            .code(),
+            "BitGoPsbtError.Finalize/FinalizeInputError.MissingConsensusBranchId"
+        );
+        assert_eq!(
+            BitGoPsbtError::WalletInput(WalletInputError::MissingSignPath).code(),
+            "BitGoPsbtError.WalletInput/WalletInputError.MissingSignPath"
+        );
+    }
+
     #[test]
     fn parse_input_wallet_validation_code() {
         let inner = ParseInputError::WalletValidation("no script type matches".to_string());
@@ -0,0 +1,97 @@
+//! Transactions paying a batch of wallet unspents to a single output
+//!
+//! Shared by [`super::plan_sweep`] and [`super::plan_migration`]: both split the unspents into
+//! batches, spend each batch with one transaction and pay the value of its inputs minus the fee
+//! to a single output, which must stay above the dust limit of the network.
+
+use crate::bitcoin::Script;
+use crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::SignPath;
+use crate::fixed_script_wallet::bitgo_psbt::{BitGoPsbt, SequenceMode, WalletInputOptions};
+use crate::fixed_script_wallet::core_unspent::WalletUnspent;
+use crate::fixed_script_wallet::RootWalletKeys;
+use crate::Network;
+
+/// A fee rate in sat/vB is finite and not negative
+pub(crate) fn is_valid_fee_rate(fee_rate: f64) -> bool {
+    fee_rate.is_finite() && fee_rate >= 0.0
+}
+
+/// Fee at `fee_rate` sat/vB of a transaction of `vsize` virtual bytes, rounded up
+pub(crate) fn fee_for_vsize(fee_rate: f64, vsize: u64) -> u64 {
+    (fee_rate * vsize as f64).ceil() as u64
+}
+
+/// Value of the output paying `input_value` minus `fee` to `output_script`
+///
+/// Returns `None` if the inputs do not cover the fee and an output above the dust limit of
+/// `network`.
+pub(crate) fn output_value(
+    network: Network,
+    output_script: &Script,
+    input_value: u64,
+    fee: u64,
+) -> Option<u64> {
+    input_value
+        .checked_sub(fee)
+        .filter(|&value| value >= network.dust_limit(output_script))
+}
+
+/// Build a PSBT spending `batch`, without outputs
+pub(crate) fn batch_psbt(
+    network: Network,
+    wallet_keys: &RootWalletKeys,
+    batch: &[WalletUnspent],
+    sign_path: Option<SignPath>,
+    sequence: Option<SequenceMode>,
+) -> Result<BitGoPsbt, String> {
+    let mut psbt = BitGoPsbt::new(network, wallet_keys, None, None);
+    for unspent in batch {
+        psbt.add_wallet_input(
+            unspent.txid,
+            unspent.vout,
+            unspent.value,
+            wallet_keys,
+            unspent.script_id,
+            WalletInputOptions {
+                sign_path,
+                sequence,
+                ..Default::default()
+            },
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(psbt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::hashes::Hash;
+    use crate::bitcoin::{PubkeyHash, ScriptBuf, WScriptHash};
+
+    #[test]
+    fn test_output_value() {
+        let p2pkh = ScriptBuf::new_p2pkh(&PubkeyHash::all_zeros());
+        let p2wsh = ScriptBuf::new_p2wsh(&WScriptHash::all_zeros());
+        assert_eq!(
+            output_value(Network::Bitcoin, &p2wsh, 1330, 1000),
+            Some(330)
+        );
+        assert_eq!(output_value(Network::Bitcoin, &p2wsh, 1329, 1000), None);
+        assert_eq!(output_value(Network::Bitcoin, &p2pkh, 1545, 1000), None);
+        assert_eq!(output_value(Network::Bitcoin, &p2wsh, 999, 1000), None);
+        // 0.01 DOGE for every script
+        assert_eq!(output_value(Network::Dogecoin, &p2wsh, 999_999, 0), None);
+        assert_eq!(
+            output_value(Network::Dogecoin, &p2wsh, 1_001_000, 1000),
+            Some(1_000_000)
+        );
+    }
+
+    #[test]
+    fn test_fee_for_vsize() {
+        assert_eq!(fee_for_vsize(2.0, 553), 1106);
+        assert_eq!(fee_for_vsize(1.5, 101), 152);
+        assert_eq!(fee_for_vsize(0.0, 101), 0);
+    }
+}
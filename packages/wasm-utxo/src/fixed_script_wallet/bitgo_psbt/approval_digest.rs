@@ -0,0 +1,121 @@
+//! Digest binding a human approval to a transaction
+//!
+//! Approval systems show a transaction to a person and record their decision. The record
+//! must refer to exactly one transaction, but a PSBT changes while it moves through signing:
+//! signatures, MuSig2 nonces, labels and other metadata are added, and re-serialization may
+//! reorder or drop unknown fields.
+//!
+//! [`BitGoPsbt::approval_digest`] hashes only what defines the transaction: the network, the
+//! unsigned transaction, the outputs and the fee. Two PSBTs have the same digest if and only
+//! if they spend the same outpoints with the same values into the same outputs.
+
+use miniscript::bitcoin::hashes::{sha256, Hash, HashEngine};
+
+use super::extract_invariants::ExtractInvariantError;
+use super::BitGoPsbt;
+
+/// Domain separation tag, bumped if the preimage format changes
+const APPROVAL_DIGEST_TAG: &[u8] = b"BitGoApprovalDigest/v1";
+
+fn write_bytes(engine: &mut sha256::HashEngine, bytes: &[u8]) {
+    engine.input(&(bytes.len() as u64).to_le_bytes());
+    engine.input(bytes);
+}
+
+impl BitGoPsbt {
+    /// SHA256 over the network, the unsigned transaction, the outputs and the fee
+    ///
+    /// The preimage is the concatenation of, with byte strings prefixed by their length as
+    /// a 64-bit little-endian integer:
+    ///
+    /// - the tag `BitGoApprovalDigest/v1`,
+    /// - the network name (e.g. `Bitcoin`),
+    /// - the serialized unsigned transaction (see [`BitGoPsbt::get_unsigned_tx_bytes`]),
+    /// - the number of outputs, then the value and script of each output,
+    /// - the fee.
+    ///
+    /// Integers are 64-bit little-endian. The fee commits to the input values, which the
+    /// unsigned transaction does not contain.
+    ///
+    /// Fails if the value of an input is unknown or the outputs exceed the inputs.
+    pub fn approval_digest(&self) -> Result<sha256::Hash, ExtractInvariantError> {
+        let fee = self.extract_invariants()?.fee;
+        let tx = &self.psbt().unsigned_tx;
+
+        let mut engine = sha256::Hash::engine();
+        write_bytes(&mut engine, APPROVAL_DIGEST_TAG);
+        write_bytes(&mut engine, self.network().as_str().as_bytes());
+        write_bytes(&mut engine, &self.get_unsigned_tx_bytes());
+        engine.input(&(tx.output.len() as u64).to_le_bytes());
+        for output in &tx.output {
+            engine.input(&output.value.to_sat().to_le_bytes());
+            write_bytes(&mut engine, output.script_pubkey.as_bytes());
+        }
+        engine.input(&fee.to_le_bytes());
+        Ok(sha256::Hash::from_engine(engine))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+    use crate::fixed_script_wallet::RootWalletKeys;
+    use crate::Network;
+    use miniscript::bitcoin::psbt::PsbtSighashType;
+    use miniscript::bitcoin::Amount;
+
+    fn create_psbt(network: Network, keys: &RootWalletKeys) -> BitGoPsbt {
+        WalletPsbtBuilder::new(network, keys)
+            .input(20, 0)
+            .output(20, 1, 9_000)
+            .build()
+    }
+
+    #[test]
+    fn test_stable_across_metadata() {
+        let keys = get_test_wallet_keys("approval_digest");
+        let psbt = create_psbt(Network::Bitcoin, &keys);
+        let digest = psbt.approval_digest().unwrap();
+
+        let mut changed =
+            BitGoPsbt::deserialize(&psbt.serialize().unwrap(), Network::Bitcoin).unwrap();
+        changed.set_input_label(0, Some("deposit")).unwrap();
+        changed.set_output_label(0, Some("change")).unwrap();
+        changed.psbt_mut().inputs[0].sighash_type = Some(PsbtSighashType::from_u32(1));
+        changed.psbt_mut().outputs[0].bip32_derivation.clear();
+        assert_eq!(changed.approval_digest().unwrap(), digest);
+    }
+
+    #[test]
+    fn test_binds_transaction() {
+        let keys = get_test_wallet_keys("approval_digest");
+        let psbt = create_psbt(Network::Bitcoin, &keys);
+        let digest = psbt.approval_digest().unwrap();
+
+        let mut changed = psbt.clone();
+        changed.psbt_mut().unsigned_tx.output[0].value = Amount::from_sat(8_000);
+        assert_ne!(changed.approval_digest().unwrap(), digest);
+
+        // Same transaction, different input value and therefore fee
+        let mut changed = psbt.clone();
+        changed.psbt_mut().inputs[0]
+            .witness_utxo
+            .as_mut()
+            .unwrap()
+            .value = Amount::from_sat(20_000);
+        assert_ne!(changed.approval_digest().unwrap(), digest);
+
+        let testnet = create_psbt(Network::BitcoinTestnet3, &keys);
+        assert_eq!(testnet.unsigned_txid(), psbt.unsigned_txid());
+        assert_ne!(testnet.approval_digest().unwrap(), digest);
+
+        let mut changed = psbt.clone();
+        changed.psbt_mut().inputs[0].witness_utxo = None;
+        assert!(matches!(
+            changed.approval_digest(),
+            Err(ExtractInvariantError::MissingInputValue { input_index: 0 })
+        ));
+    }
+}
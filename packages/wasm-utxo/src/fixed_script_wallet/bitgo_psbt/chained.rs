@@ -0,0 +1,275 @@
+//! Spending the change of a transaction that is not broadcast yet
+//!
+//! [`BitGoPsbt::add_wallet_input_from_parent`] adds an input spending a wallet output of
+//! another PSBT, e.g. to send a second withdrawal from the change of a first one that is still
+//! waiting for approval. The txid, script and value of the input are taken from the parent, so
+//! they cannot disagree with the transaction that will eventually be broadcast.
+//!
+//! The input is marked with the txid of its parent (proprietary key-value with subtype
+//! `UnconfirmedParent`), so that [`BitGoPsbt::unconfirmed_parents`] can tell which
+//! transactions must be submitted together with the child, see [`super::tx_package`].
+//!
+//! The txid of the parent depends on the scriptSigs of its inputs: every parent input that
+//! does not spend a native segwit output must be finalized first.
+
+use miniscript::bitcoin::hashes::Hash;
+use miniscript::bitcoin::Txid;
+
+use super::propkv::{find_kv, BitGoKeyValue, ProprietaryKeySubtype};
+use super::psbt_wallet_input::get_output_script_and_value;
+use super::tx_package::package_txid;
+use super::{BitGoPsbt, BitGoPsbtError, ParsedOutput, WalletInputOptions};
+use crate::fixed_script_wallet::RootWalletKeys;
+use crate::Network;
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum ChainedInputError {
+    /// Parent and child must be on the same network
+    NetworkMismatch {
+        parent: Network,
+        child: Network,
+    },
+    /// The txid of the parent cannot be computed from the PSBT (Zcash and Dash)
+    UnsupportedNetwork(Network),
+    OutputIndexOutOfBounds {
+        vout: u32,
+        output_count: usize,
+    },
+    /// The spent output of the parent does not belong to the wallet
+    NotWalletOutput {
+        vout: u32,
+    },
+    ParseOutput(String),
+    /// The parent input has no final scriptSig yet, so the parent txid is not known
+    ParentNotFinal {
+        input_index: usize,
+    },
+    WalletInput(BitGoPsbtError),
+}
+
+impl std::fmt::Display for ChainedInputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChainedInputError::NetworkMismatch { parent, child } => write!(
+                f,
+                "Parent is on network {}, child is on network {}",
+                parent, child
+            ),
+            ChainedInputError::UnsupportedNetwork(network) => write!(
+                f,
+                "Spending unconfirmed outputs is not supported for network {}",
+                network
+            ),
+            ChainedInputError::OutputIndexOutOfBounds { vout, output_count } => write!(
+                f,
+                "Output index {} out of bounds (total outputs: {})",
+                vout, output_count
+            ),
+            ChainedInputError::NotWalletOutput { vout } => {
+                write!(f, "Parent output {} does not belong to the wallet", vout)
+            }
+            ChainedInputError::ParseOutput(msg) => write!(f, "{}", msg),
+            ChainedInputError::ParentNotFinal { input_index } => write!(
+                f,
+                "Parent input {} must be finalized before its outputs can be spent",
+                input_index
+            ),
+            ChainedInputError::WalletInput(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ChainedInputError {}
+
+impl crate::error::WasmErrorCode for ChainedInputError {
+    fn code(&self) -> String {
+        let variant: &str = self.into();
+        match self {
+            Self::WalletInput(error) => format!("ChainedInputError.{}/{}", variant, error.code()),
+            _ => format!("ChainedInputError.{}", variant),
+        }
+    }
+}
+
+crate::impl_from_coded_error!(ChainedInputError);
+
+/// The txid the parent will have once it is broadcast
+fn parent_txid(parent: &BitGoPsbt) -> Result<Txid, ChainedInputError> {
+    let BitGoPsbt::BitcoinLike(psbt, _) = parent else {
+        return Err(ChainedInputError::UnsupportedNetwork(parent.network()));
+    };
+    for (input_index, (input, tx_in)) in psbt.inputs.iter().zip(&psbt.unsigned_tx.input).enumerate()
+    {
+        let is_native_segwit = get_output_script_and_value(input, tx_in.previous_output)
+            .is_ok_and(|(script, _)| script.is_witness_program());
+        if !is_native_segwit && input.final_script_sig.is_none() {
+            return Err(ChainedInputError::ParentNotFinal { input_index });
+        }
+    }
+    Ok(package_txid(parent))
+}
+
+impl BitGoPsbt {
+    /// Add an input spending wallet output `vout` of the unbroadcast transaction `parent`
+    ///
+    /// The prevout, script and value are taken from the parent and the derivation is
+    /// recovered from the metadata of the parent output. Returns the index of the new input.
+    pub fn add_wallet_input_from_parent(
+        &mut self,
+        parent: &BitGoPsbt,
+        vout: u32,
+        wallet_keys: &RootWalletKeys,
+        options: WalletInputOptions,
+    ) -> Result<usize, ChainedInputError> {
+        let network = self.network();
+        if parent.network() != network {
+            return Err(ChainedInputError::NetworkMismatch {
+                parent: parent.network(),
+                child: network,
+            });
+        }
+        let txid = parent_txid(parent)?;
+
+        let psbt = parent.psbt();
+        let output_count = psbt.outputs.len();
+        let (psbt_output, tx_output) = psbt
+            .outputs
+            .get(vout as usize)
+            .zip(psbt.unsigned_tx.output.get(vout as usize))
+            .ok_or(ChainedInputError::OutputIndexOutOfBounds { vout, output_count })?;
+        let parsed = ParsedOutput::parse(psbt_output, tx_output, wallet_keys, network, &[])
+            .map_err(|e| ChainedInputError::ParseOutput(e.to_string()))?;
+        let script_id = parsed
+            .script_id
+            .ok_or(ChainedInputError::NotWalletOutput { vout })?;
+
+        let index = self
+            .add_wallet_input(
+                txid,
+                vout,
+                tx_output.value.to_sat(),
+                wallet_keys,
+                script_id,
+                options,
+            )
+            .map_err(ChainedInputError::WalletInput)?;
+        let (key, value) = BitGoKeyValue::new(
+            ProprietaryKeySubtype::UnconfirmedParent,
+            vec![],
+            txid.to_byte_array().to_vec(),
+        )
+        .to_key_value();
+        self.psbt_mut().inputs[index].proprietary.insert(key, value);
+        Ok(index)
+    }
+
+    /// Txids of the unbroadcast parents spent by inputs added with
+    /// [`BitGoPsbt::add_wallet_input_from_parent`], without duplicates and in input order
+    pub fn unconfirmed_parents(&self) -> Vec<Txid> {
+        let mut txids: Vec<Txid> = vec![];
+        for input in &self.psbt().inputs {
+            let parents = find_kv(ProprietaryKeySubtype::UnconfirmedParent, &input.proprietary)
+                .filter_map(|kv| Txid::from_slice(&kv.value).ok());
+            for txid in parents {
+                if !txids.contains(&txid) {
+                    txids.push(txid);
+                }
+            }
+        }
+        txids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::bitgo_psbt::TxPackage;
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::{
+        get_test_wallet_keys, get_test_wallet_xprvs,
+    };
+    use miniscript::bitcoin::{consensus, secp256k1, OutPoint, ScriptBuf, Transaction};
+
+    const SEED: &str = "chained";
+
+    fn parent_psbt(wallet_keys: &RootWalletKeys, input_chain: u32) -> BitGoPsbt {
+        let mut psbt = WalletPsbtBuilder::new(Network::Bitcoin, wallet_keys)
+            .input_with(input_chain, 0, 100_000, WalletInputOptions::default())
+            .build();
+        psbt.add_output(ScriptBuf::from_bytes(vec![0x51]), 1_000)
+            .unwrap();
+        psbt.add_wallet_output(21, 7, 90_000, wallet_keys).unwrap();
+        psbt
+    }
+
+    #[test]
+    fn test_spend_change_of_segwit_parent() {
+        let wallet_keys = get_test_wallet_keys(SEED);
+        let parent = parent_psbt(&wallet_keys, 20);
+        let mut child = BitGoPsbt::new(Network::Bitcoin, &wallet_keys, None, None);
+
+        assert!(matches!(
+            child.add_wallet_input_from_parent(&parent, 0, &wallet_keys, Default::default()),
+            Err(ChainedInputError::NotWalletOutput { vout: 0 })
+        ));
+        assert!(matches!(
+            child.add_wallet_input_from_parent(&parent, 2, &wallet_keys, Default::default()),
+            Err(ChainedInputError::OutputIndexOutOfBounds { vout: 2, .. })
+        ));
+
+        let index = child
+            .add_wallet_input_from_parent(&parent, 1, &wallet_keys, Default::default())
+            .unwrap();
+        let prevout = child.psbt().unsigned_tx.input[index].previous_output;
+        assert_eq!(
+            prevout,
+            OutPoint {
+                txid: parent.unsigned_txid(),
+                vout: 1
+            }
+        );
+        let input = &child.psbt().inputs[index];
+        assert_eq!(
+            input.witness_utxo.as_ref(),
+            Some(&parent.psbt().unsigned_tx.output[1])
+        );
+        assert_eq!(child.unconfirmed_parents(), vec![parent.unsigned_txid()]);
+
+        child
+            .add_wallet_output(20, 0, 80_000, &wallet_keys)
+            .unwrap();
+        let package = TxPackage::new(vec![child, parent.clone()]).unwrap();
+        assert_eq!(package.txids()[0], parent.unsigned_txid());
+    }
+
+    #[test]
+    fn test_parent_inputs_must_be_final() {
+        let xprvs = get_test_wallet_xprvs(SEED);
+        let wallet_keys = get_test_wallet_keys(SEED);
+        let mut parent = parent_psbt(&wallet_keys, 0);
+        let mut child = BitGoPsbt::new(Network::Bitcoin, &wallet_keys, None, None);
+
+        assert!(matches!(
+            child.add_wallet_input_from_parent(&parent, 1, &wallet_keys, Default::default()),
+            Err(ChainedInputError::ParentNotFinal { input_index: 0 })
+        ));
+
+        parent.sign_all_with_xpriv(&xprvs[0]).unwrap();
+        parent.sign_all_with_xpriv(&xprvs[2]).unwrap();
+        parent
+            .finalize_mut(&secp256k1::Secp256k1::verification_only())
+            .unwrap();
+        let index = child
+            .add_wallet_input_from_parent(&parent, 1, &wallet_keys, Default::default())
+            .unwrap();
+
+        let unsigned_txid = parent.unsigned_txid();
+        let tx: Transaction = consensus::deserialize(&parent.extract_tx().unwrap()).unwrap();
+        assert_ne!(tx.compute_txid(), unsigned_txid);
+        assert_eq!(
+            child.psbt().unsigned_tx.input[index].previous_output.txid,
+            tx.compute_txid()
+        );
+        assert_eq!(child.unconfirmed_parents(), vec![tx.compute_txid()]);
+    }
+}
@@ -0,0 +1,413 @@
+//! Finalization of inputs with custom scripts
+//!
+//! `BitGoPsbt::finalize_input` only handles scripts that miniscript can satisfy plus the
+//! MuSig2 key path. Scripts outside of that (CHECKDATASIG replay protection variants,
+//! CLTV-wrapped multisig, ...) can be finalized with an [`InputSatisfier`] that computes the
+//! final scriptSig and witness. [`WitnessTemplate`] is a satisfier that fills a caller-given
+//! list of stack items from the partial signatures and scripts of the input.
+
+use miniscript::bitcoin::opcodes::OP_0;
+use miniscript::bitcoin::psbt::Input;
+use miniscript::bitcoin::script::{Builder, PushBytesBuf};
+use miniscript::bitcoin::{secp256k1, PublicKey, ScriptBuf, Witness};
+
+use super::BitGoPsbt;
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum CustomFinalizeError {
+    InputIndexOutOfBounds {
+        input_index: usize,
+        input_count: usize,
+    },
+    /// The template references a key without a partial signature
+    MissingSignature { pubkey: PublicKey },
+    /// The template references a script the input does not have
+    MissingScript(&'static str),
+    /// A stack item cannot be pushed in a scriptSig
+    InvalidPush(String),
+    /// Error reported by the satisfier or by the default finalizer
+    Satisfier(String),
+}
+
+impl std::fmt::Display for CustomFinalizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CustomFinalizeError::InputIndexOutOfBounds {
+                input_index,
+                input_count,
+            } => write!(
+                f,
+                "Input index {} out of bounds (total inputs: {})",
+                input_index, input_count
+            ),
+            CustomFinalizeError::MissingSignature { pubkey } => {
+                write!(f, "No partial signature for pubkey {}", pubkey)
+            }
+            CustomFinalizeError::MissingScript(script) => {
+                write!(f, "Input has no {}", script)
+            }
+            CustomFinalizeError::InvalidPush(msg) => write!(f, "Invalid scriptSig push: {}", msg),
+            CustomFinalizeError::Satisfier(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CustomFinalizeError {}
+
+crate::impl_wasm_error_code!(CustomFinalizeError);
+
+/// Computes the final scriptSig and witness of an input
+pub trait InputSatisfier {
+    /// Returns `Ok(None)` if the satisfier does not handle this input, in which case the
+    /// default finalization is used.
+    fn satisfy(
+        &self,
+        input_index: usize,
+        input: &Input,
+    ) -> Result<Option<(ScriptBuf, Witness)>, CustomFinalizeError>;
+}
+
+/// A stack item of a [`WitnessTemplate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateItem {
+    /// The partial signature of a key, including the sighash byte
+    Signature(PublicKey),
+    /// Literal bytes. In a scriptSig, items are pushed with the minimal opcode: OP_0 for an
+    /// empty item, OP_1NEGATE and OP_1 to OP_16 for single bytes 0x81 and 1 to 16.
+    Data(Vec<u8>),
+    /// The redeem script of the input
+    RedeemScript,
+    /// The witness script of the input
+    WitnessScript,
+}
+
+impl TemplateItem {
+    fn resolve(&self, input: &Input) -> Result<Vec<u8>, CustomFinalizeError> {
+        match self {
+            TemplateItem::Signature(pubkey) => input
+                .partial_sigs
+                .get(pubkey)
+                .map(|sig| sig.to_vec())
+                .ok_or(CustomFinalizeError::MissingSignature { pubkey: *pubkey }),
+            TemplateItem::Data(data) => Ok(data.clone()),
+            TemplateItem::RedeemScript => input
+                .redeem_script
+                .as_ref()
+                .map(|s| s.to_bytes())
+                .ok_or(CustomFinalizeError::MissingScript("redeem script")),
+            TemplateItem::WitnessScript => input
+                .witness_script
+                .as_ref()
+                .map(|s| s.to_bytes())
+                .ok_or(CustomFinalizeError::MissingScript("witness script")),
+        }
+    }
+}
+
+/// Push a stack item with the smallest opcode (BIP-62 minimal push rule)
+fn push_minimal(builder: Builder, item: Vec<u8>) -> Result<Builder, CustomFinalizeError> {
+    match item.as_slice() {
+        [] => Ok(builder.push_opcode(OP_0)),
+        [n @ 1..=16] => Ok(builder.push_int(i64::from(*n))),
+        [0x81] => Ok(builder.push_int(-1)),
+        _ => {
+            let push = PushBytesBuf::try_from(item)
+                .map_err(|e| CustomFinalizeError::InvalidPush(e.to_string()))?;
+            Ok(builder.push_slice(push))
+        }
+    }
+}
+
+/// Stack items of the final scriptSig and witness, bottom of the stack first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WitnessTemplate {
+    pub script_sig: Vec<TemplateItem>,
+    pub witness: Vec<TemplateItem>,
+}
+
+impl InputSatisfier for WitnessTemplate {
+    fn satisfy(
+        &self,
+        _input_index: usize,
+        input: &Input,
+    ) -> Result<Option<(ScriptBuf, Witness)>, CustomFinalizeError> {
+        let mut builder = Builder::new();
+        for item in &self.script_sig {
+            builder = push_minimal(builder, item.resolve(input)?)?;
+        }
+        let witness = self
+            .witness
+            .iter()
+            .map(|item| item.resolve(input))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Some((builder.into_script(), Witness::from_slice(&witness))))
+    }
+}
+
+impl BitGoPsbt {
+    /// Finalize an input with a custom satisfier, falling back to `finalize_input` if the
+    /// satisfier does not handle the input
+    ///
+    /// Like the default finalizer, this sets the final scriptSig and witness and removes the
+    /// fields that are no longer needed. The satisfier output is not checked against the
+    /// prevout script; use `verify_input_scripts` once all inputs are finalized.
+    pub fn finalize_input_with_satisfier<C: secp256k1::Verification>(
+        &mut self,
+        secp: &secp256k1::Secp256k1<C>,
+        input_index: usize,
+        satisfier: &dyn InputSatisfier,
+    ) -> Result<(), CustomFinalizeError> {
+        let input_count = self.psbt().inputs.len();
+        let input = self.psbt_mut().inputs.get_mut(input_index).ok_or(
+            CustomFinalizeError::InputIndexOutOfBounds {
+                input_index,
+                input_count,
+            },
+        )?;
+
+        let Some((script_sig, witness)) = satisfier.satisfy(input_index, input)? else {
+            return self
+                .finalize_input(secp, input_index)
+                .map_err(|e| CustomFinalizeError::Satisfier(e.to_string()));
+        };
+
+        input.final_script_sig = (!script_sig.is_empty()).then_some(script_sig);
+        input.final_script_witness = (!witness.is_empty()).then_some(witness);
+        input.partial_sigs.clear();
+        input.sighash_type = None;
+        input.redeem_script = None;
+        input.witness_script = None;
+        input.bip32_derivation.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::bitgo_psbt::{
+        ScriptError, SequenceMode, VerifyInputScriptsError,
+    };
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::timelocked_recovery::{
+        TimelockedRecoveryWallet, CHAIN_EXTERNAL,
+    };
+    use crate::fixed_script_wallet::wallet_keys::tests::{
+        get_test_wallet_keys, get_test_wallet_xprvs,
+    };
+    use crate::fixed_script_wallet::ScriptId;
+    use crate::networks::Network;
+    use miniscript::bitcoin::hashes::Hash;
+    use miniscript::bitcoin::Txid;
+
+    struct Unhandled;
+
+    impl InputSatisfier for Unhandled {
+        fn satisfy(
+            &self,
+            _input_index: usize,
+            _input: &Input,
+        ) -> Result<Option<(ScriptBuf, Witness)>, CustomFinalizeError> {
+            Ok(None)
+        }
+    }
+
+    fn psbt_with_input(chain: u32) -> BitGoPsbt {
+        let keys = get_test_wallet_keys("custom_satisfier");
+        WalletPsbtBuilder::new(Network::Bitcoin, &keys)
+            .input(chain, 0)
+            .build()
+    }
+
+    #[test]
+    fn test_witness_template() {
+        let secp = secp256k1::Secp256k1::new();
+        let xprvs = get_test_wallet_xprvs("custom_satisfier");
+        let mut psbt = psbt_with_input(20);
+        psbt.sign(&xprvs[0], &secp).unwrap();
+
+        let pubkey = *psbt.psbt().inputs[0].partial_sigs.keys().next().unwrap();
+        let signature = psbt.psbt().inputs[0].partial_sigs[&pubkey].to_vec();
+        let witness_script = psbt.psbt().inputs[0].witness_script.clone().unwrap();
+
+        let template = WitnessTemplate {
+            script_sig: vec![],
+            witness: vec![
+                TemplateItem::Data(vec![]),
+                TemplateItem::Signature(pubkey),
+                TemplateItem::Data(vec![1]),
+                TemplateItem::WitnessScript,
+            ],
+        };
+        psbt.finalize_input_with_satisfier(&secp, 0, &template)
+            .unwrap();
+
+        let input = &psbt.psbt().inputs[0];
+        assert!(input.final_script_sig.is_none());
+        let witness = input.final_script_witness.as_ref().unwrap().to_vec();
+        assert_eq!(
+            witness,
+            vec![vec![], signature, vec![1], witness_script.to_bytes()]
+        );
+        assert!(input.partial_sigs.is_empty());
+        assert!(input.witness_script.is_none());
+    }
+
+    #[test]
+    fn test_witness_template_errors() {
+        let secp = secp256k1::Secp256k1::new();
+        let mut psbt = psbt_with_input(0);
+        let pubkey = *psbt.psbt().inputs[0]
+            .bip32_derivation
+            .keys()
+            .next()
+            .unwrap();
+
+        let template = WitnessTemplate {
+            script_sig: vec![TemplateItem::Signature(PublicKey::new(pubkey))],
+            witness: vec![],
+        };
+        assert!(matches!(
+            psbt.finalize_input_with_satisfier(&secp, 0, &template),
+            Err(CustomFinalizeError::MissingSignature { .. })
+        ));
+        let template = WitnessTemplate {
+            script_sig: vec![TemplateItem::WitnessScript],
+            witness: vec![],
+        };
+        assert!(matches!(
+            psbt.finalize_input_with_satisfier(&secp, 0, &template),
+            Err(CustomFinalizeError::MissingScript("witness script"))
+        ));
+        assert!(matches!(
+            psbt.finalize_input_with_satisfier(&secp, 1, &template),
+            Err(CustomFinalizeError::InputIndexOutOfBounds { .. })
+        ));
+
+        // Unsigned input: the default finalizer fails
+        assert!(matches!(
+            psbt.finalize_input_with_satisfier(&secp, 0, &Unhandled),
+            Err(CustomFinalizeError::Satisfier(_))
+        ));
+    }
+
+    #[test]
+    fn test_witness_template_minimal_pushes() {
+        let secp = secp256k1::Secp256k1::new();
+        let mut psbt = psbt_with_input(0);
+        let template = WitnessTemplate {
+            script_sig: vec![
+                TemplateItem::Data(vec![]),
+                TemplateItem::Data(vec![1]),
+                TemplateItem::Data(vec![16]),
+                TemplateItem::Data(vec![0x81]),
+                TemplateItem::Data(vec![17]),
+                TemplateItem::Data(vec![0, 1]),
+            ],
+            witness: vec![],
+        };
+        psbt.finalize_input_with_satisfier(&secp, 0, &template)
+            .unwrap();
+
+        let script_sig = psbt.psbt().inputs[0].final_script_sig.clone().unwrap();
+        assert_eq!(
+            script_sig.as_bytes(),
+            [0x00, 0x51, 0x60, 0x4f, 0x01, 0x11, 0x02, 0x00, 0x01]
+        );
+        assert!(script_sig.is_push_only());
+    }
+
+    /// A PSBT spending a timelocked recovery script with `sequence`, with all signatures
+    fn timelocked_recovery_psbt(sequence: u32) -> BitGoPsbt {
+        let keys = get_test_wallet_keys("custom_satisfier");
+        let wallet = TimelockedRecoveryWallet::new(keys.clone(), 144).unwrap();
+        let script_id = ScriptId {
+            chain: CHAIN_EXTERNAL,
+            index: 0,
+        };
+        let support = Network::Bitcoin.output_script_support();
+        let script = wallet.output_script(CHAIN_EXTERNAL, 0, &support).unwrap();
+
+        let mut psbt = BitGoPsbt::new(Network::Bitcoin, &keys, None, None);
+        psbt.add_input(
+            Txid::all_zeros(),
+            0,
+            10_000,
+            script.clone(),
+            Some(SequenceMode::Custom(sequence)),
+            None,
+        )
+        .unwrap();
+        psbt.add_output(script, 9_000).unwrap();
+        wallet
+            .update_input(&mut psbt.psbt_mut().inputs[0], script_id)
+            .unwrap();
+        let secp = secp256k1::Secp256k1::new();
+        for xprv in get_test_wallet_xprvs("custom_satisfier") {
+            psbt.sign(&xprv, &secp).unwrap();
+        }
+        psbt
+    }
+
+    /// The public key of wallet key `key_index` in the first input
+    fn input_pubkey(psbt: &BitGoPsbt, key_index: usize) -> PublicKey {
+        let fingerprint = get_test_wallet_keys("custom_satisfier").xpubs[key_index].fingerprint();
+        let (pubkey, _) = psbt.psbt().inputs[0]
+            .bip32_derivation
+            .iter()
+            .find(|(_, (f, _))| *f == fingerprint)
+            .unwrap();
+        PublicKey::new(*pubkey)
+    }
+
+    #[test]
+    fn test_finalize_and_verify_timelocked_recovery() {
+        let secp = secp256k1::Secp256k1::new();
+        // OP_CHECKMULTISIG fails with empty signatures, then OP_NOTIF takes the backup key
+        // path with OP_CHECKSEQUENCEVERIFY
+        let recovery = |psbt: &BitGoPsbt| WitnessTemplate {
+            script_sig: vec![],
+            witness: vec![
+                TemplateItem::Signature(input_pubkey(psbt, 1)),
+                TemplateItem::Data(vec![]),
+                TemplateItem::Data(vec![]),
+                TemplateItem::Data(vec![]),
+                TemplateItem::WitnessScript,
+            ],
+        };
+
+        let mut psbt = timelocked_recovery_psbt(144);
+        psbt.finalize_input_with_satisfier(&secp, 0, &recovery(&psbt))
+            .unwrap();
+        psbt.verify_input_scripts(&secp).unwrap();
+
+        // Lower relative lock time, or sequence without relative lock time
+        for sequence in [143, 0xfffffffd] {
+            let mut psbt = timelocked_recovery_psbt(sequence);
+            psbt.finalize_input_with_satisfier(&secp, 0, &recovery(&psbt))
+                .unwrap();
+            assert!(matches!(
+                psbt.verify_input_scripts(&secp),
+                Err(VerifyInputScriptsError::Script {
+                    input_index: 0,
+                    error: ScriptError::UnsatisfiedLocktime { .. }
+                })
+            ));
+        }
+
+        // The 2-of-3 path does not need a relative lock time
+        let mut psbt = timelocked_recovery_psbt(0xfffffffd);
+        let template = WitnessTemplate {
+            script_sig: vec![],
+            witness: vec![
+                TemplateItem::Data(vec![]),
+                TemplateItem::Signature(input_pubkey(&psbt, 0)),
+                TemplateItem::Signature(input_pubkey(&psbt, 2)),
+                TemplateItem::WitnessScript,
+            ],
+        };
+        psbt.finalize_input_with_satisfier(&secp, 0, &template)
+            .unwrap();
+        psbt.verify_input_scripts(&secp).unwrap();
+    }
+}
@@ -0,0 +1,177 @@
+//! Key-value diff of two PSBTs
+//!
+//! [`BitGoPsbt::diff`] compares the raw key-value maps of two PSBTs and lists every key that
+//! was added, removed or changed, e.g. to see what a signer stage contributed between the
+//! half-signed and the fully signed PSBT.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use miniscript::bitcoin::psbt::raw::Pair;
+
+use super::json::is_zcash;
+use super::BitGoPsbt;
+use crate::inspect::{decode_psbt_maps, key_type_name, PsbtMapContext};
+
+/// PSBT map that contains a differing key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PsbtDiffMap {
+    Global,
+    Input(usize),
+    Output(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::IntoStaticStr)]
+#[strum(serialize_all = "camelCase")]
+pub enum PsbtDiffChange {
+    /// The key is only present in the other PSBT
+    Added,
+    /// The key is only present in this PSBT
+    Removed,
+    /// The key is present in both PSBTs with different values
+    Changed,
+}
+
+/// A key whose value differs between two PSBTs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PsbtDiffEntry {
+    pub map: PsbtDiffMap,
+    pub change: PsbtDiffChange,
+    pub key_type: u8,
+    /// BIP-174 name of the key type
+    pub name: String,
+    /// Hex-encoded key data (without the type byte)
+    pub key: String,
+    /// Hex-encoded value in this PSBT
+    pub old_value: Option<String>,
+    /// Hex-encoded value in the other PSBT
+    pub new_value: Option<String>,
+}
+
+fn diff_map(
+    map: PsbtDiffMap,
+    context: PsbtMapContext,
+    old: &[Pair],
+    new: &[Pair],
+    entries: &mut Vec<PsbtDiffEntry>,
+) {
+    // `Key::key` starts with the type byte, so ordering by it follows the serialization order
+    let old: BTreeMap<&[u8], &Pair> = old.iter().map(|p| (p.key.key.as_slice(), p)).collect();
+    let new: BTreeMap<&[u8], &Pair> = new.iter().map(|p| (p.key.key.as_slice(), p)).collect();
+    let keys: BTreeSet<&[u8]> = old.keys().chain(new.keys()).copied().collect();
+
+    for key in keys {
+        let old_pair = old.get(key);
+        let new_pair = new.get(key);
+        let change = match (old_pair, new_pair) {
+            (Some(a), Some(b)) if a.value == b.value => continue,
+            (Some(_), Some(_)) => PsbtDiffChange::Changed,
+            (Some(_), None) => PsbtDiffChange::Removed,
+            (None, _) => PsbtDiffChange::Added,
+        };
+        let key_type = key[0];
+        entries.push(PsbtDiffEntry {
+            map,
+            change,
+            key_type,
+            name: key_type_name(key_type, context),
+            key: hex::encode(&key[1..]),
+            old_value: old_pair.map(|p| hex::encode(&p.value)),
+            new_value: new_pair.map(|p| hex::encode(&p.value)),
+        });
+    }
+}
+
+impl BitGoPsbt {
+    /// List the key-value differences between this PSBT and `other`
+    ///
+    /// Maps are matched by position; inputs or outputs present in only one of the PSBTs
+    /// show all of their keys as added or removed. Within a map, keys are reported in
+    /// serialization order.
+    pub fn diff(&self, other: &BitGoPsbt) -> Result<Vec<PsbtDiffEntry>, String> {
+        let network = self.network();
+        if other.network() != network {
+            return Err(format!(
+                "Cannot diff PSBTs of different networks ({} and {})",
+                network.to_coin_name(),
+                other.network().to_coin_name()
+            ));
+        }
+        let old = decode_psbt_maps(
+            &self.serialize().map_err(|e| e.to_string())?,
+            is_zcash(network),
+        )?;
+        let new = decode_psbt_maps(
+            &other.serialize().map_err(|e| e.to_string())?,
+            is_zcash(network),
+        )?;
+
+        let mut entries = Vec::new();
+        diff_map(
+            PsbtDiffMap::Global,
+            PsbtMapContext::Global,
+            &old.global,
+            &new.global,
+            &mut entries,
+        );
+        for i in 0..old.inputs.len().max(new.inputs.len()) {
+            diff_map(
+                PsbtDiffMap::Input(i),
+                PsbtMapContext::Input,
+                old.inputs.get(i).map(Vec::as_slice).unwrap_or_default(),
+                new.inputs.get(i).map(Vec::as_slice).unwrap_or_default(),
+                &mut entries,
+            );
+        }
+        for i in 0..old.outputs.len().max(new.outputs.len()) {
+            diff_map(
+                PsbtDiffMap::Output(i),
+                PsbtMapContext::Output,
+                old.outputs.get(i).map(Vec::as_slice).unwrap_or_default(),
+                new.outputs.get(i).map(Vec::as_slice).unwrap_or_default(),
+                &mut entries,
+            );
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::test_utils::fixtures;
+    use crate::Network;
+    use base64::engine::{general_purpose::STANDARD as BASE64_STANDARD, Engine};
+
+    fn load_psbt(signature_state: fixtures::SignatureState) -> BitGoPsbt {
+        let fixture = fixtures::load_psbt_fixture_with_format_and_namespace(
+            Network::Bitcoin.to_utxolib_name(),
+            signature_state,
+            fixtures::TxFormat::Psbt,
+            fixtures::FixtureNamespace::UtxolibCompat,
+        )
+        .unwrap();
+        let bytes = BASE64_STANDARD.decode(&fixture.psbt_base64).unwrap();
+        BitGoPsbt::deserialize(&bytes, Network::Bitcoin).unwrap()
+    }
+
+    #[test]
+    fn test_diff_signer_stages() {
+        let unsigned = load_psbt(fixtures::SignatureState::Unsigned);
+        let halfsigned = load_psbt(fixtures::SignatureState::Halfsigned);
+        assert!(unsigned.diff(&unsigned).unwrap().is_empty());
+
+        let diff = unsigned.diff(&halfsigned).unwrap();
+        assert!(!diff.is_empty());
+        // Signing only touches input maps
+        assert!(diff.iter().all(|e| matches!(e.map, PsbtDiffMap::Input(_))));
+        assert!(diff.iter().any(|e| e.change == PsbtDiffChange::Added
+            && e.name == "PSBT_IN_PARTIAL_SIG"
+            && e.old_value.is_none()
+            && e.new_value.is_some()));
+
+        // The reverse diff removes what signing added
+        let reverse = halfsigned.diff(&unsigned).unwrap();
+        assert_eq!(reverse.len(), diff.len());
+        assert!(reverse.iter().all(|e| e.change != PsbtDiffChange::Added));
+    }
+}
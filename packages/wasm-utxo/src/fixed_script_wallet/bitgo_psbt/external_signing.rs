@@ -0,0 +1,697 @@
+//! Sighashes for external signers
+//!
+//! HSMs and other signing services sign a 32-byte digest and know nothing about PSBTs.
+//! [`BitGoPsbt::compute_sighashes`] returns the digest that each expected signer of each input
+//! has to sign, together with the algorithm that produced it. [`BitGoPsbt::insert_external_signature`]
+//! checks a signature made over such a digest and adds it to the input, where finalization
+//! picks it up like any other signature.
+//!
+//! The signers of an input are taken from its key origins (`bip32_derivation` and
+//! `tap_key_origins`) and, for replay protection inputs, from the P2PK redeem script. MuSig2
+//! key path spends need a nonce exchange and are not covered.
+
+use miniscript::bitcoin::hashes::Hash;
+use miniscript::bitcoin::hex::DisplayHex;
+use miniscript::bitcoin::psbt::Input;
+use miniscript::bitcoin::secp256k1::{self, schnorr, Message};
+use miniscript::bitcoin::sighash::{
+    EcdsaSighashType, Prevouts, SighashCache, SighashCacheZcashExt, TapSighashType,
+};
+use miniscript::bitcoin::{
+    ecdsa, taproot, PublicKey, Script, ScriptBuf, TapLeafHash, Transaction, TxOut, XOnlyPublicKey,
+};
+
+use super::p2tr_musig2_input::Musig2Input;
+use super::psbt_wallet_input::{collect_prevouts, get_output_script_and_value};
+use super::script_interpreter::SighashAlgorithm;
+use super::sighash::validate_sighash_type;
+use super::{get_default_ecdsa_sighash_type, BitGoPsbt};
+
+const SIGHASH_FORKID: u32 = 0x40;
+
+/// Algorithm that computes the digest of a signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SighashVersion {
+    /// Pre-segwit sighash
+    Legacy,
+    /// BIP-143 sighash of segwit v0 inputs
+    Bip143,
+    /// BIP-143 style sighash with SIGHASH_FORKID (Bitcoin Cash and forks), for all inputs
+    ForkId,
+    /// BIP-341 sighash of taproot key path and script path spends
+    Bip341,
+    /// ZIP-243 sighash (Zcash)
+    Zip243,
+}
+
+impl SighashVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SighashVersion::Legacy => "legacy",
+            SighashVersion::Bip143 => "bip143",
+            SighashVersion::ForkId => "forkid",
+            SighashVersion::Bip341 => "bip341",
+            SighashVersion::Zip243 => "zip243",
+        }
+    }
+}
+
+/// Key that signs an input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignerKey {
+    /// ECDSA key of a legacy or segwit v0 input
+    Ecdsa(PublicKey),
+    /// Schnorr key of a taproot input, signing the leaf with the given hash, or the key path
+    /// if there is none
+    Schnorr {
+        key: XOnlyPublicKey,
+        leaf_hash: Option<TapLeafHash>,
+    },
+}
+
+impl SignerKey {
+    /// Compressed public key for ECDSA, x-only public key for Schnorr
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            SignerKey::Ecdsa(key) => key.to_bytes(),
+            SignerKey::Schnorr { key, .. } => key.serialize().to_vec(),
+        }
+    }
+
+    /// Whether `pubkey` is this key. Taproot keys also match their compressed form.
+    fn matches(&self, pubkey: &[u8]) -> bool {
+        match self {
+            SignerKey::Ecdsa(key) => key.to_bytes() == pubkey,
+            SignerKey::Schnorr { key, .. } => {
+                let x_only = match pubkey.len() {
+                    33 => &pubkey[1..],
+                    _ => pubkey,
+                };
+                key.serialize() == x_only
+            }
+        }
+    }
+}
+
+/// Digest that a signer has to sign for an input, see [`BitGoPsbt::compute_sighashes`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputSighash {
+    pub input_index: usize,
+    pub signer: SignerKey,
+    /// Sighash type committed to: the `sighash_type` of the input, or the network default
+    pub sighash_type: u32,
+    pub algorithm: SighashVersion,
+    pub digest: [u8; 32],
+}
+
+fn is_finalized(input: &Input) -> bool {
+    input.final_script_sig.is_some() || input.final_script_witness.is_some()
+}
+
+/// Keys expected to sign an input
+fn signer_keys(input: &Input) -> Vec<SignerKey> {
+    if !input.bip32_derivation.is_empty() {
+        return input
+            .bip32_derivation
+            .keys()
+            .map(|key| SignerKey::Ecdsa(PublicKey::new(*key)))
+            .collect();
+    }
+    if !input.tap_key_origins.is_empty() {
+        let spent_leaves: Vec<TapLeafHash> = input
+            .tap_scripts
+            .values()
+            .map(|(script, leaf_version)| TapLeafHash::from_script(script, *leaf_version))
+            .collect();
+        let key_path = !Musig2Input::is_musig2_input(input);
+        let mut keys = Vec::new();
+        for (key, (leaf_hashes, _)) in &input.tap_key_origins {
+            if leaf_hashes.is_empty() {
+                if key_path && input.tap_internal_key == Some(*key) {
+                    keys.push(SignerKey::Schnorr {
+                        key: *key,
+                        leaf_hash: None,
+                    });
+                }
+                continue;
+            }
+            keys.extend(
+                leaf_hashes
+                    .iter()
+                    .filter(|leaf_hash| spent_leaves.contains(leaf_hash))
+                    .map(|leaf_hash| SignerKey::Schnorr {
+                        key: *key,
+                        leaf_hash: Some(*leaf_hash),
+                    }),
+            );
+        }
+        return keys;
+    }
+    // Replay protection inputs have no key origins
+    input
+        .redeem_script
+        .as_ref()
+        .and_then(|script| BitGoPsbt::extract_pubkey_from_p2pk_redeem_script(script).ok())
+        .map(SignerKey::Ecdsa)
+        .into_iter()
+        .collect()
+}
+
+/// Script code of an ECDSA signature
+///
+/// - p2shP2wsh, p2wsh: the witness script
+/// - p2sh, p2shP2pk: the redeem script
+/// - p2wpkh, p2shP2wpkh: the corresponding p2pkh script
+/// - p2pkh, p2pk: the output script
+fn script_code(input: &Input, output_script: &Script) -> Result<ScriptBuf, String> {
+    if let Some(witness_script) = &input.witness_script {
+        return Ok(witness_script.clone());
+    }
+    let script = input.redeem_script.as_deref().unwrap_or(output_script);
+    if let Some(script_code) = script.p2wpkh_script_code() {
+        Ok(script_code)
+    } else if input.redeem_script.is_some() || script.is_p2pkh() || script.is_p2pk() {
+        Ok(script.to_owned())
+    } else {
+        Err("Missing redeem script or witness script".to_string())
+    }
+}
+
+/// Remove the sighash type byte that follows a signature in a script, if there is one
+///
+/// The byte has to match `sighash_type`.
+fn strip_sighash_byte(
+    signature: &[u8],
+    has_sighash_byte: bool,
+    sighash_type: u32,
+) -> Result<&[u8], String> {
+    match signature.split_last() {
+        Some((&byte, signature)) if has_sighash_byte => {
+            if byte as u32 != sighash_type & 0xff {
+                return Err(format!(
+                    "Sighash type byte 0x{:02x} of the signature does not match 0x{:02x}",
+                    byte, sighash_type
+                ));
+            }
+            Ok(signature)
+        }
+        _ => Ok(signature),
+    }
+}
+
+/// Parse an ECDSA signature in DER or 64-byte compact encoding, optionally followed by the
+/// sighash type byte
+fn parse_ecdsa_signature(
+    signature: &[u8],
+    sighash_type: u32,
+) -> Result<secp256k1::ecdsa::Signature, String> {
+    // A DER sequence is its length byte plus two bytes long. Check for one first: DER
+    // signatures with short `r` and `s` values can be 64 or 65 bytes long, too.
+    let der_len = match signature {
+        [0x30, len, ..] => Some(*len as usize + 2),
+        _ => None,
+    }
+    .filter(|len| signature.len() == *len || signature.len() == *len + 1);
+    let (compact, has_sighash_byte) = match der_len {
+        Some(len) => (false, signature.len() == len + 1),
+        None => (matches!(signature.len(), 64 | 65), signature.len() == 65),
+    };
+    let signature = strip_sighash_byte(signature, has_sighash_byte, sighash_type)?;
+    let mut signature = if compact {
+        secp256k1::ecdsa::Signature::from_compact(signature)
+    } else {
+        secp256k1::ecdsa::Signature::from_der(signature)
+    }
+    .map_err(|e| format!("Invalid ECDSA signature: {}", e))?;
+    signature.normalize_s();
+    Ok(signature)
+}
+
+/// Parse a 64-byte Schnorr signature, optionally followed by the sighash type byte
+fn parse_schnorr_signature(
+    signature: &[u8],
+    sighash_type: u32,
+) -> Result<schnorr::Signature, String> {
+    let signature = strip_sighash_byte(signature, signature.len() == 65, sighash_type)?;
+    schnorr::Signature::from_slice(signature)
+        .map_err(|e| format!("Invalid Schnorr signature: {}", e))
+}
+
+impl BitGoPsbt {
+    /// Digest of a signature by `signer` on input `input_index` with `sighash_type`
+    fn external_signature_hash(
+        &self,
+        cache: &mut SighashCache<&Transaction>,
+        prevouts: &Result<Vec<TxOut>, String>,
+        input_index: usize,
+        signer: &SignerKey,
+        sighash_type: u32,
+    ) -> Result<(SighashVersion, [u8; 32]), String> {
+        let psbt = self.psbt();
+        let input = &psbt.inputs[input_index];
+
+        if let SignerKey::Schnorr { leaf_hash, .. } = signer {
+            let sighash_type = u8::try_from(sighash_type)
+                .ok()
+                .and_then(|t| TapSighashType::from_consensus_u8(t).ok())
+                .ok_or_else(|| format!("Invalid taproot sighash type: 0x{:02x}", sighash_type))?;
+            let prevouts = Prevouts::All(prevouts.as_ref().map_err(Clone::clone)?);
+            let digest = match leaf_hash {
+                Some(leaf_hash) => cache
+                    .taproot_script_spend_signature_hash(
+                        input_index,
+                        &prevouts,
+                        *leaf_hash,
+                        sighash_type,
+                    )
+                    .map_err(|e| format!("Failed to compute taproot sighash: {}", e))?,
+                None => cache
+                    .taproot_key_spend_signature_hash(input_index, &prevouts, sighash_type)
+                    .map_err(|e| format!("Failed to compute taproot sighash: {}", e))?,
+            };
+            return Ok((SighashVersion::Bip341, digest.to_byte_array()));
+        }
+
+        let prevout = psbt.unsigned_tx.input[input_index].previous_output;
+        let (output_script, value) =
+            get_output_script_and_value(input, prevout).map_err(|e| e.to_string())?;
+        let script_code = script_code(input, output_script)?;
+        let segwit = output_script.is_witness_program()
+            || input
+                .redeem_script
+                .as_ref()
+                .is_some_and(|script| script.is_witness_program());
+
+        match self.sighash_algorithm().map_err(|e| e.to_string())? {
+            SighashAlgorithm::Zcash {
+                consensus_branch_id,
+                version_group_id,
+                expiry_height,
+            } => {
+                let digest = cache
+                    .p2sh_signature_hash_zcash(
+                        input_index,
+                        &script_code,
+                        value,
+                        sighash_type,
+                        consensus_branch_id,
+                        version_group_id,
+                        expiry_height,
+                    )
+                    .map_err(|e| format!("Failed to compute Zcash sighash: {}", e))?;
+                Ok((SighashVersion::Zip243, digest.to_byte_array()))
+            }
+            SighashAlgorithm::ForkId(fork_id) => {
+                if sighash_type & SIGHASH_FORKID == 0 {
+                    return Err(format!(
+                        "SIGHASH_FORKID (0x40) is required. Sighash type: 0x{:02x}",
+                        sighash_type
+                    ));
+                }
+                let digest = cache
+                    .p2wsh_signature_hash_forkid(
+                        input_index,
+                        &script_code,
+                        value,
+                        sighash_type,
+                        Some(fork_id),
+                    )
+                    .map_err(|e| format!("Failed to compute FORKID sighash: {}", e))?;
+                Ok((SighashVersion::ForkId, digest.to_byte_array()))
+            }
+            SighashAlgorithm::Bitcoin if segwit => {
+                let sighash_type = EcdsaSighashType::from_standard(sighash_type)
+                    .map_err(|e| format!("Invalid sighash type: {}", e))?;
+                let digest = cache
+                    .p2wsh_signature_hash(input_index, &script_code, value, sighash_type)
+                    .map_err(|e| format!("Failed to compute sighash: {}", e))?;
+                Ok((SighashVersion::Bip143, digest.to_byte_array()))
+            }
+            SighashAlgorithm::Bitcoin => {
+                let digest = cache
+                    .legacy_signature_hash(input_index, &script_code, sighash_type)
+                    .map_err(|e| format!("Failed to compute sighash: {}", e))?;
+                Ok((SighashVersion::Legacy, digest.to_byte_array()))
+            }
+        }
+    }
+
+    /// Compute the digest that each signer of each unfinalized input has to sign
+    ///
+    /// Signatures use the `sighash_type` of the input, or SIGHASH_ALL (SIGHASH_DEFAULT for
+    /// taproot, SIGHASH_ALL|SIGHASH_FORKID on FORKID networks) if it is unset. Sign the digest
+    /// as is, without hashing it again, and add the signature with
+    /// [`BitGoPsbt::insert_external_signature`].
+    ///
+    /// # Returns
+    /// - `Ok(Vec<InputSighash>)` ordered by input
+    /// - `Err(String)` if UTXO data or scripts needed for a sighash are missing
+    pub fn compute_sighashes(&self) -> Result<Vec<InputSighash>, String> {
+        let psbt = self.psbt();
+        let default_ecdsa = get_default_ecdsa_sighash_type(self.network()).to_u32();
+        let prevouts = collect_prevouts(psbt);
+        let mut cache = SighashCache::new(&psbt.unsigned_tx);
+
+        let mut sighashes = Vec::new();
+        for (input_index, input) in psbt.inputs.iter().enumerate() {
+            if is_finalized(input) {
+                continue;
+            }
+            for signer in signer_keys(input) {
+                let sighash_type = match (input.sighash_type, signer) {
+                    (Some(sighash_type), _) => sighash_type.to_u32(),
+                    (None, SignerKey::Ecdsa(_)) => default_ecdsa,
+                    (None, SignerKey::Schnorr { .. }) => TapSighashType::Default as u32,
+                };
+                let (algorithm, digest) = self.external_signature_hash(
+                    &mut cache,
+                    &prevouts,
+                    input_index,
+                    &signer,
+                    sighash_type,
+                )?;
+                sighashes.push(InputSighash {
+                    input_index,
+                    signer,
+                    sighash_type,
+                    algorithm,
+                    digest,
+                });
+            }
+        }
+        Ok(sighashes)
+    }
+
+    /// Add a signature made over a digest from [`BitGoPsbt::compute_sighashes`]
+    ///
+    /// Lets signatures from an HSM or another signing service be added without handing the
+    /// private key to this crate.
+    ///
+    /// # Arguments
+    /// - `input_index`: The index of the signed input
+    /// - `pubkey`: The signer: a compressed public key, or an x-only public key for taproot
+    /// - `signature`: ECDSA signature (DER or 64-byte compact) or 64-byte Schnorr signature,
+    ///   optionally followed by the sighash type byte
+    /// - `sighash_type`: The sighash type the digest was computed for
+    ///
+    /// The signature is verified against the digest before it is added. For taproot script
+    /// path inputs, it is added for the leaf whose digest it signs.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the signature was added
+    /// - `Err(String)` if the key does not sign the input or the signature does not verify
+    pub fn insert_external_signature(
+        &mut self,
+        input_index: usize,
+        pubkey: &[u8],
+        signature: &[u8],
+        sighash_type: u32,
+    ) -> Result<(), String> {
+        let psbt = self.psbt();
+        if input_index >= psbt.inputs.len() {
+            return Err(format!(
+                "Input index {} out of bounds (total inputs: {})",
+                input_index,
+                psbt.inputs.len()
+            ));
+        }
+        let input = &psbt.inputs[input_index];
+        let signers: Vec<SignerKey> = signer_keys(input)
+            .into_iter()
+            .filter(|signer| signer.matches(pubkey))
+            .collect();
+        if signers.is_empty() {
+            return Err(format!(
+                "Public key {} does not sign input {}",
+                pubkey.to_lower_hex_string(),
+                input_index
+            ));
+        }
+
+        let secp = secp256k1::Secp256k1::verification_only();
+        let prevouts = collect_prevouts(psbt);
+        let mut cache = SighashCache::new(&psbt.unsigned_tx);
+        let mut verified = None;
+        for signer in signers {
+            let (_, digest) = self.external_signature_hash(
+                &mut cache,
+                &prevouts,
+                input_index,
+                &signer,
+                sighash_type,
+            )?;
+            let message = Message::from_digest(digest);
+            let valid = match signer {
+                SignerKey::Ecdsa(key) => {
+                    validate_sighash_type(sighash_type, self.network())?;
+                    let signature = parse_ecdsa_signature(signature, sighash_type)?;
+                    secp.verify_ecdsa(&message, &signature, &key.inner).is_ok()
+                }
+                SignerKey::Schnorr { key, .. } => {
+                    let signature = parse_schnorr_signature(signature, sighash_type)?;
+                    secp.verify_schnorr(&signature, &message, &key).is_ok()
+                }
+            };
+            if valid {
+                verified = Some(signer);
+                break;
+            }
+        }
+        let signer = verified.ok_or_else(|| {
+            format!(
+                "Signature of {} does not verify for input {} with sighash type 0x{:02x}",
+                pubkey.to_lower_hex_string(),
+                input_index,
+                sighash_type
+            )
+        })?;
+
+        let input = &mut self.psbt_mut().inputs[input_index];
+        match signer {
+            SignerKey::Ecdsa(key) => {
+                let signature = ecdsa::Signature {
+                    signature: parse_ecdsa_signature(signature, sighash_type)?,
+                    sighash_type,
+                };
+                input.partial_sigs.insert(key, signature);
+            }
+            SignerKey::Schnorr { key, leaf_hash } => {
+                let signature = taproot::Signature {
+                    signature: parse_schnorr_signature(signature, sighash_type)?,
+                    sighash_type: TapSighashType::from_consensus_u8(sighash_type as u8)
+                        .map_err(|e| format!("Invalid taproot sighash type: {}", e))?,
+                };
+                match leaf_hash {
+                    Some(leaf_hash) => {
+                        input.tap_script_sigs.insert((key, leaf_hash), signature);
+                    }
+                    None => input.tap_key_sig = Some(signature),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Add a signature made over a digest from [`BitGoPsbt::compute_sighashes`]
+    ///
+    /// Same as [`BitGoPsbt::insert_external_signature`], kept for existing callers.
+    pub fn insert_signature(
+        &mut self,
+        input_index: usize,
+        pubkey: &[u8],
+        signature: &[u8],
+        sighash_type: u32,
+    ) -> Result<(), String> {
+        self.insert_external_signature(input_index, pubkey, signature, sighash_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::{
+        get_test_wallet_keys, get_test_wallet_xprvs,
+    };
+    use crate::networks::Network;
+    use miniscript::bitcoin::bip32::Xpriv;
+
+    const SEED: &str = "external_signing";
+
+    fn create_psbt(network: Network, chains: &[u32]) -> BitGoPsbt {
+        let keys = get_test_wallet_keys(SEED);
+        let mut builder = WalletPsbtBuilder::new(network, &keys);
+        for chain in chains {
+            builder = builder.input(*chain, 0);
+        }
+        builder.output(20, 1, 9_000).build()
+    }
+
+    /// Private key of `xpriv` for a signer of `input`, if it derives from it
+    fn signer_privkey(
+        input: &Input,
+        signer: &SignerKey,
+        xpriv: &Xpriv,
+    ) -> Option<secp256k1::SecretKey> {
+        let secp = secp256k1::Secp256k1::new();
+        let (fingerprint, path) = match signer {
+            SignerKey::Ecdsa(key) => input.bip32_derivation.get(&key.inner)?.clone(),
+            SignerKey::Schnorr { key, .. } => input.tap_key_origins.get(key)?.1.clone(),
+        };
+        (fingerprint == xpriv.fingerprint(&secp))
+            .then(|| xpriv.derive_priv(&secp, &path).unwrap().private_key)
+    }
+
+    /// Sign every digest of the user key the way an external signer would
+    ///
+    /// ECDSA signatures are 64-byte compact, or DER followed by the sighash type byte.
+    fn sign_externally(psbt: &mut BitGoPsbt, compact: bool) -> usize {
+        let secp = secp256k1::Secp256k1::new();
+        let xprvs = get_test_wallet_xprvs(SEED);
+        let mut signed = 0;
+        for sighash in psbt.compute_sighashes().unwrap() {
+            let input = &psbt.psbt().inputs[sighash.input_index];
+            let Some(privkey) = signer_privkey(input, &sighash.signer, &xprvs[0]) else {
+                continue;
+            };
+            let message = Message::from_digest(sighash.digest);
+            let signature = match sighash.signer {
+                SignerKey::Ecdsa(_) => {
+                    let signature = secp.sign_ecdsa(&message, &privkey);
+                    if compact {
+                        signature.serialize_compact().to_vec()
+                    } else {
+                        ecdsa::Signature {
+                            signature,
+                            sighash_type: sighash.sighash_type,
+                        }
+                        .to_vec()
+                    }
+                }
+                SignerKey::Schnorr { .. } => {
+                    let keypair = secp256k1::Keypair::from_secret_key(&secp, &privkey);
+                    secp.sign_schnorr_no_aux_rand(&message, &keypair)
+                        .serialize()
+                        .to_vec()
+                }
+            };
+            psbt.insert_external_signature(
+                sighash.input_index,
+                &sighash.signer.to_bytes(),
+                &signature,
+                sighash.sighash_type,
+            )
+            .unwrap();
+            signed += 1;
+        }
+        signed
+    }
+
+    #[test]
+    fn test_compute_sighashes() {
+        let psbt = create_psbt(Network::Bitcoin, &[0, 10, 20, 30]);
+        let sighashes = psbt.compute_sighashes().unwrap();
+        let algorithms: Vec<(usize, SighashVersion)> = sighashes
+            .iter()
+            .map(|s| (s.input_index, s.algorithm))
+            .collect();
+        // Three keys for each ECDSA input, user and BitGo for the taproot leaf
+        let expected: Vec<(usize, SighashVersion)> = [
+            (0, SighashVersion::Legacy, 3),
+            (1, SighashVersion::Bip143, 3),
+            (2, SighashVersion::Bip143, 3),
+            (3, SighashVersion::Bip341, 2),
+        ]
+        .into_iter()
+        .flat_map(|(input_index, algorithm, n)| std::iter::repeat_n((input_index, algorithm), n))
+        .collect();
+        assert_eq!(algorithms, expected);
+        assert!(sighashes[..9].iter().all(|s| s.sighash_type == 0x01));
+        assert!(sighashes[9..].iter().all(|s| s.sighash_type == 0x00));
+
+        let psbt = create_psbt(Network::BitcoinCash, &[0]);
+        let sighashes = psbt.compute_sighashes().unwrap();
+        assert!(sighashes
+            .iter()
+            .all(|s| s.algorithm == SighashVersion::ForkId && s.sighash_type == 0x41));
+    }
+
+    #[test]
+    fn test_insert_external_signature() {
+        let secp = secp256k1::Secp256k1::new();
+        let xprvs = get_test_wallet_xprvs(SEED);
+        for (network, compact) in [(Network::Bitcoin, false), (Network::BitcoinCash, true)] {
+            let chains: &[u32] = match network {
+                Network::Bitcoin => &[0, 10, 20, 30],
+                _ => &[0],
+            };
+            let mut psbt = create_psbt(network, chains);
+            assert_eq!(sign_externally(&mut psbt, compact), chains.len());
+            for input_index in 0..chains.len() {
+                let xpub = miniscript::bitcoin::bip32::Xpub::from_priv(&secp, &xprvs[0]);
+                assert!(
+                    psbt.verify_signature_with_xpub(&secp, input_index, &xpub)
+                        .unwrap(),
+                    "{:?} input {}",
+                    network,
+                    input_index
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_insert_external_signature_errors() {
+        let mut psbt = create_psbt(Network::Bitcoin, &[20]);
+        let sighash = psbt.compute_sighashes().unwrap().remove(0);
+        let pubkey = sighash.signer.to_bytes();
+
+        assert!(psbt
+            .insert_external_signature(1, &pubkey, &[0; 64], 0x01)
+            .unwrap_err()
+            .contains("out of bounds"));
+        assert!(psbt
+            .insert_external_signature(0, &[2; 33], &[0; 64], 0x01)
+            .unwrap_err()
+            .contains("does not sign input 0"));
+
+        // A valid signature of a different digest
+        let secp = secp256k1::Secp256k1::new();
+        let privkey = secp256k1::SecretKey::from_slice(&[1; 32]).unwrap();
+        let signature = secp.sign_ecdsa(&Message::from_digest(sighash.digest), &privkey);
+        assert!(psbt
+            .insert_external_signature(0, &pubkey, &signature.serialize_der(), 0x01)
+            .unwrap_err()
+            .contains("does not verify"));
+        assert!(psbt
+            .insert_external_signature(0, &pubkey, &signature.serialize_der(), 0x41)
+            .is_err());
+        let mut with_sighash_byte = signature.serialize_der().to_vec();
+        with_sighash_byte.push(0x02);
+        assert!(psbt
+            .insert_external_signature(0, &pubkey, &with_sighash_byte, 0x01)
+            .unwrap_err()
+            .contains("does not match 0x01"));
+        assert!(psbt.psbt().inputs[0].partial_sigs.is_empty());
+    }
+
+    #[test]
+    fn test_parse_ecdsa_signature_short_der() {
+        // `r` and `s` with three leading zero bytes make a 64-byte DER signature
+        let mut compact = [0u8; 64];
+        compact[3..32].fill(0x11);
+        compact[35..64].fill(0x22);
+        let signature = secp256k1::ecdsa::Signature::from_compact(&compact).unwrap();
+        let der = signature.serialize_der().to_vec();
+        assert_eq!(der.len(), 64);
+        assert_eq!(parse_ecdsa_signature(&der, 0x01).unwrap(), signature);
+
+        let mut with_sighash_byte = der.clone();
+        with_sighash_byte.push(0x01);
+        assert_eq!(
+            parse_ecdsa_signature(&with_sighash_byte, 0x01).unwrap(),
+            signature
+        );
+        assert_eq!(parse_ecdsa_signature(&compact, 0x01).unwrap(), signature);
+    }
+}
@@ -0,0 +1,213 @@
+//! Invariants re-checked when extracting the final transaction
+//!
+//! Between parsing a PSBT for approval and extracting the signed transaction, the PSBT goes
+//! through signing, combining and finalization, often by other parties. Nothing in these
+//! steps re-validates what was approved.
+//!
+//! [`BitGoPsbt::extract_invariants`] takes a snapshot at parse time: the spent outpoints, a
+//! hash over the outputs and the fee. [`BitGoPsbt::verify_extract_invariants`] recomputes
+//! them right before extraction and fails if inputs were added or changed, if any output
+//! changed, or if the fee computed from the input values differs.
+
+use miniscript::bitcoin::consensus::serialize;
+use miniscript::bitcoin::hashes::{sha256, Hash};
+use miniscript::bitcoin::OutPoint;
+
+use super::tx_package::input_value;
+use super::BitGoPsbt;
+
+/// Snapshot of a transaction taken at parse time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractInvariants {
+    /// Outpoints spent by the transaction, in input order
+    pub inputs: Vec<OutPoint>,
+    /// SHA256 of the consensus encoding of the outputs
+    pub outputs_hash: sha256::Hash,
+    /// Sum of the input values minus the sum of the output values
+    pub fee: u64,
+}
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum ExtractInvariantError {
+    /// The value of an input is unknown (no witness_utxo or non_witness_utxo)
+    MissingInputValue {
+        input_index: usize,
+    },
+    /// Outputs exceed inputs
+    NegativeFee,
+    /// An input spends an outpoint that was not part of the snapshot
+    UnexpectedInput {
+        input_index: usize,
+        previous_output: OutPoint,
+    },
+    /// Inputs were removed or reordered
+    InputsChanged,
+    OutputsChanged {
+        expected: sha256::Hash,
+        actual: sha256::Hash,
+    },
+    FeeChanged {
+        expected: u64,
+        actual: u64,
+    },
+}
+
+impl std::fmt::Display for ExtractInvariantError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExtractInvariantError::MissingInputValue { input_index } => write!(
+                f,
+                "Input {} has no witness_utxo or non_witness_utxo to compute the fee",
+                input_index
+            ),
+            ExtractInvariantError::NegativeFee => write!(f, "Outputs exceed inputs"),
+            ExtractInvariantError::UnexpectedInput {
+                input_index,
+                previous_output,
+            } => write!(
+                f,
+                "Input {} spends {}, which was not part of the parsed transaction",
+                input_index, previous_output
+            ),
+            ExtractInvariantError::InputsChanged => {
+                write!(f, "Inputs were removed or reordered since parsing")
+            }
+            ExtractInvariantError::OutputsChanged { expected, actual } => write!(
+                f,
+                "Outputs changed since parsing: expected hash {}, got {}",
+                expected, actual
+            ),
+            ExtractInvariantError::FeeChanged { expected, actual } => write!(
+                f,
+                "Fee changed since parsing: expected {} sat, got {} sat",
+                expected, actual
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ExtractInvariantError {}
+
+crate::impl_wasm_error_code!(ExtractInvariantError);
+crate::impl_from_coded_error!(ExtractInvariantError);
+
+impl BitGoPsbt {
+    /// Take a snapshot of the inputs, outputs and fee, to be checked again at extraction
+    pub fn extract_invariants(&self) -> Result<ExtractInvariants, ExtractInvariantError> {
+        let tx = &self.psbt().unsigned_tx;
+        let input_total = (0..tx.input.len()).try_fold(0u64, |sum, input_index| {
+            input_value(self, input_index)
+                .map(|value| sum.saturating_add(value))
+                .ok_or(ExtractInvariantError::MissingInputValue { input_index })
+        })?;
+        let output_total = tx.output.iter().fold(0u64, |sum, output| {
+            sum.saturating_add(output.value.to_sat())
+        });
+        Ok(ExtractInvariants {
+            inputs: tx.input.iter().map(|input| input.previous_output).collect(),
+            outputs_hash: sha256::Hash::hash(&serialize(&tx.output)),
+            fee: input_total
+                .checked_sub(output_total)
+                .ok_or(ExtractInvariantError::NegativeFee)?,
+        })
+    }
+
+    /// Check that the transaction still matches a snapshot taken with
+    /// [`BitGoPsbt::extract_invariants`]
+    pub fn verify_extract_invariants(
+        &self,
+        expected: &ExtractInvariants,
+    ) -> Result<(), ExtractInvariantError> {
+        let actual = self.extract_invariants()?;
+        if let Some((input_index, previous_output)) = actual
+            .inputs
+            .iter()
+            .enumerate()
+            .find(|(_, outpoint)| !expected.inputs.contains(outpoint))
+        {
+            return Err(ExtractInvariantError::UnexpectedInput {
+                input_index,
+                previous_output: *previous_output,
+            });
+        }
+        if actual.inputs != expected.inputs {
+            return Err(ExtractInvariantError::InputsChanged);
+        }
+        if actual.outputs_hash != expected.outputs_hash {
+            return Err(ExtractInvariantError::OutputsChanged {
+                expected: expected.outputs_hash,
+                actual: actual.outputs_hash,
+            });
+        }
+        if actual.fee != expected.fee {
+            return Err(ExtractInvariantError::FeeChanged {
+                expected: expected.fee,
+                actual: actual.fee,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::bitgo_psbt::{ScriptId, WalletInputOptions};
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+    use crate::Network;
+    use miniscript::bitcoin::{Amount, Txid};
+
+    #[test]
+    fn test_extract_invariants() {
+        let keys = get_test_wallet_keys("extract_invariants");
+        let psbt = WalletPsbtBuilder::new(Network::Bitcoin, &keys)
+            .input(20, 0)
+            .output(20, 1, 9_000)
+            .build();
+        let invariants = psbt.extract_invariants().unwrap();
+        assert_eq!(invariants.fee, 1_000);
+        assert_eq!(invariants.inputs.len(), 1);
+        psbt.verify_extract_invariants(&invariants).unwrap();
+
+        let mut changed = psbt.clone();
+        changed.add_wallet_output(21, 2, 500, &keys).unwrap();
+        assert!(matches!(
+            changed.verify_extract_invariants(&invariants),
+            Err(ExtractInvariantError::OutputsChanged { .. })
+        ));
+
+        let mut changed = psbt.clone();
+        changed
+            .add_wallet_input(
+                Txid::all_zeros(),
+                1,
+                500,
+                &keys,
+                ScriptId {
+                    chain: 20,
+                    index: 1,
+                },
+                WalletInputOptions::default(),
+            )
+            .unwrap();
+        assert!(matches!(
+            changed.verify_extract_invariants(&invariants),
+            Err(ExtractInvariantError::UnexpectedInput { input_index: 1, .. })
+        ));
+
+        let mut changed = psbt.clone();
+        changed.psbt_mut().inputs[0]
+            .witness_utxo
+            .as_mut()
+            .unwrap()
+            .value = Amount::from_sat(20_000);
+        assert!(matches!(
+            changed.verify_extract_invariants(&invariants),
+            Err(ExtractInvariantError::FeeChanged {
+                expected: 1_000,
+                actual: 11_000
+            })
+        ));
+    }
+}
@@ -179,7 +179,7 @@ impl FixedScriptInput {
         Ok(())
     }
 
-    fn compute_compact_sighash(
+    pub(crate) fn compute_compact_sighash(
         psbt: &Psbt,
         index: usize,
         ctx: &SighashContext,
@@ -191,7 +191,7 @@ impl FixedScriptInput {
         match ctx {
             SighashContext::Bitcoin { fork_id } => {
                 if let Some(fid) = fork_id {
-                    psbt.sighash_forkid(index, &mut cache, *fid)
+                    super::sighash::forkid_signature_hash(psbt, index, &mut cache, *fid)
                         .map(|(msg, _)| msg)
                         .map_err(|e| format!("Input {}: FORKID sighash: {}", index, e))
                 } else {
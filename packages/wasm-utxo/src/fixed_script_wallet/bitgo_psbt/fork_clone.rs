@@ -0,0 +1,203 @@
+//! Re-targeting a PSBT template to a chain that shares history
+//!
+//! Outputs created before a chain split can be spent on both chains with the same inputs and
+//! output scripts. [`BitGoPsbt::clone_for_network`] turns the PSBT prepared for one chain into
+//! the template for the other, so fork-claim tooling does not have to rebuild it input by
+//! input. Scripts are network independent; the addresses of the clone are simply the encoding
+//! of the same scripts on the target network.
+
+use miniscript::bitcoin::sighash::TapSighashType;
+
+use super::propkv::is_musig2_key;
+use super::psbt_wallet_input::get_output_script_and_value;
+use super::{get_default_ecdsa_sighash_type, BitGoPsbt};
+use crate::Network;
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum CloneForNetworkError {
+    /// The networks are not distinct chains with a common history
+    UnrelatedNetworks {
+        network: Network,
+        target_network: Network,
+    },
+    /// The previous output of an input is missing or invalid
+    InputScript { input_index: usize, error: String },
+    /// The target network does not support the script type of an input
+    UnsupportedInputScript { input_index: usize, error: String },
+    /// The target network does not support the script type of an output
+    UnsupportedOutputScript { output_index: usize, error: String },
+}
+
+impl std::fmt::Display for CloneForNetworkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CloneForNetworkError::UnrelatedNetworks {
+                network,
+                target_network,
+            } => write!(
+                f,
+                "Networks {} and {} do not share chain history",
+                network, target_network
+            ),
+            CloneForNetworkError::InputScript { input_index, error } => write!(
+                f,
+                "Failed to get previous output script of input {}: {}",
+                input_index, error
+            ),
+            CloneForNetworkError::UnsupportedInputScript { input_index, error } => {
+                write!(f, "Input {} cannot be spent: {}", input_index, error)
+            }
+            CloneForNetworkError::UnsupportedOutputScript {
+                output_index,
+                error,
+            } => write!(f, "Output {} cannot be created: {}", output_index, error),
+        }
+    }
+}
+
+impl std::error::Error for CloneForNetworkError {}
+
+crate::impl_wasm_error_code!(CloneForNetworkError);
+
+impl BitGoPsbt {
+    /// Clone this PSBT as a template for `target_network`
+    ///
+    /// The clone spends the same inputs and pays the same output scripts with the same values.
+    /// Signatures, including MuSig2 nonces and partial signatures, are removed since they
+    /// commit to the sighash algorithm of the source network. The sighash type of non-taproot
+    /// inputs is reset to the default of the target network (`SIGHASH_ALL|SIGHASH_FORKID` on
+    /// BCH, BSV, BTG and eCash, `SIGHASH_ALL` otherwise).
+    ///
+    /// Fails if the networks did not split from a common chain, or if an input or output uses
+    /// a script type the target network does not support (e.g. segwit on BCH).
+    pub fn clone_for_network(
+        &self,
+        target_network: Network,
+    ) -> Result<BitGoPsbt, CloneForNetworkError> {
+        let network = self.network();
+        if !network.shares_history_with(target_network) {
+            return Err(CloneForNetworkError::UnrelatedNetworks {
+                network,
+                target_network,
+            });
+        }
+
+        let support = target_network.output_script_support();
+        let mut psbt = self.psbt().clone();
+        for (input_index, (tx_in, input)) in psbt
+            .unsigned_tx
+            .input
+            .iter()
+            .zip(psbt.inputs.iter_mut())
+            .enumerate()
+        {
+            let (script, _) =
+                get_output_script_and_value(input, tx_in.previous_output).map_err(|e| {
+                    CloneForNetworkError::InputScript {
+                        input_index,
+                        error: e.to_string(),
+                    }
+                })?;
+            support.assert_support(script).map_err(|e| {
+                CloneForNetworkError::UnsupportedInputScript {
+                    input_index,
+                    error: e.to_string(),
+                }
+            })?;
+            input.sighash_type = Some(if script.is_p2tr() {
+                TapSighashType::Default.into()
+            } else {
+                get_default_ecdsa_sighash_type(target_network)
+            });
+
+            input.partial_sigs.clear();
+            input.tap_key_sig = None;
+            input.tap_script_sigs.clear();
+            input.final_script_sig = None;
+            input.final_script_witness = None;
+            input.proprietary.retain(|key, _| !is_musig2_key(key));
+        }
+        for (output_index, output) in psbt.unsigned_tx.output.iter().enumerate() {
+            support.assert_support(&output.script_pubkey).map_err(|e| {
+                CloneForNetworkError::UnsupportedOutputScript {
+                    output_index,
+                    error: e.to_string(),
+                }
+            })?;
+        }
+
+        Ok(BitGoPsbt::BitcoinLike(psbt, target_network))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::{
+        get_test_wallet_keys, get_test_wallet_xprvs,
+    };
+    use miniscript::bitcoin::psbt::PsbtSighashType;
+    use miniscript::bitcoin::secp256k1;
+
+    const SEED: &str = "fork_clone";
+
+    fn wallet_psbt(network: Network, chain: u32) -> BitGoPsbt {
+        let wallet_keys = get_test_wallet_keys(SEED);
+        WalletPsbtBuilder::new(network, &wallet_keys)
+            .input(chain, 0)
+            .output(chain, 1, 9_000)
+            .build()
+    }
+
+    #[test]
+    fn test_clone_for_network() {
+        let secp = secp256k1::Secp256k1::new();
+        let xprvs = get_test_wallet_xprvs(SEED);
+        let mut btc = wallet_psbt(Network::Bitcoin, 0);
+        btc.sign(&xprvs[0], &secp).unwrap();
+
+        let bch = btc.clone_for_network(Network::BitcoinCash).unwrap();
+        assert_eq!(bch.network(), Network::BitcoinCash);
+        assert_eq!(bch.unsigned_txid(), btc.unsigned_txid());
+        assert!(bch.psbt().inputs[0].partial_sigs.is_empty());
+        assert_eq!(
+            bch.psbt().inputs[0].sighash_type,
+            Some(PsbtSighashType::from_u32(0x41))
+        );
+        // Same as building the template on BCH directly
+        assert_eq!(
+            bch.serialize().unwrap(),
+            wallet_psbt(Network::BitcoinCash, 0).serialize().unwrap()
+        );
+
+        // And back, via a fork of the fork
+        let bsv = bch.clone_for_network(Network::BitcoinSV).unwrap();
+        let btc_again = bsv.clone_for_network(Network::Bitcoin).unwrap();
+        assert_eq!(
+            btc_again.serialize().unwrap(),
+            wallet_psbt(Network::Bitcoin, 0).serialize().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_clone_for_network_rejects_unsupported() {
+        let btc = wallet_psbt(Network::Bitcoin, 0);
+        assert!(matches!(
+            btc.clone_for_network(Network::Litecoin),
+            Err(CloneForNetworkError::UnrelatedNetworks { .. })
+        ));
+        assert!(matches!(
+            btc.clone_for_network(Network::BitcoinCashTestnet),
+            Err(CloneForNetworkError::UnrelatedNetworks { .. })
+        ));
+
+        // BTG supports segwit, BCH does not
+        let p2wsh = wallet_psbt(Network::Bitcoin, 20);
+        assert!(p2wsh.clone_for_network(Network::BitcoinGold).is_ok());
+        assert!(matches!(
+            p2wsh.clone_for_network(Network::BitcoinCash),
+            Err(CloneForNetworkError::UnsupportedInputScript { input_index: 0, .. })
+        ));
+    }
+}
@@ -0,0 +1,312 @@
+//! Replay checks for a spend prepared on two chains that share history
+//!
+//! Outputs created before a chain split exist on both chains. A transaction spending them
+//! on one chain can be broadcast on the other as well if its signatures are valid there,
+//! which is the case when both chains use the same sighash algorithm (e.g. BCH and BSV).
+//! The fork side is made chain-specific by spending a replay protection input: an output
+//! that only exists on the fork chain, so the transaction is invalid on the other chain.
+
+use miniscript::bitcoin::{secp256k1, OutPoint};
+
+use super::psbt_wallet_input::get_output_script_and_value;
+use super::{sighash, BitGoPsbt};
+use crate::fixed_script_wallet::ReplayProtection;
+use crate::Network;
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum ForkReplayError {
+    /// The networks are not distinct chains with a common history
+    UnrelatedNetworks {
+        network: Network,
+        fork_network: Network,
+    },
+    /// Verifying the signature of a replay protection input failed
+    ReplayProtectionSignature { input_index: usize, error: String },
+    /// The fork PSBT does not spend a replay protection input
+    MissingReplayProtectionInput,
+    /// A replay protection input of the fork PSBT is not signed
+    UnsignedReplayProtectionInput { input_index: usize },
+    /// A replay protection input of the fork PSBT is also spent by the other PSBT
+    SharedReplayProtectionInput { input_index: usize },
+}
+
+impl std::fmt::Display for ForkReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ForkReplayError::UnrelatedNetworks {
+                network,
+                fork_network,
+            } => write!(
+                f,
+                "Networks {} and {} do not share chain history",
+                network, fork_network
+            ),
+            ForkReplayError::ReplayProtectionSignature { input_index, error } => write!(
+                f,
+                "Failed to verify replay protection signature of input {}: {}",
+                input_index, error
+            ),
+            ForkReplayError::MissingReplayProtectionInput => {
+                write!(f, "Fork PSBT does not spend a replay protection input")
+            }
+            ForkReplayError::UnsignedReplayProtectionInput { input_index } => write!(
+                f,
+                "Replay protection input {} of the fork PSBT is not signed",
+                input_index
+            ),
+            ForkReplayError::SharedReplayProtectionInput { input_index } => write!(
+                f,
+                "Replay protection input {} of the fork PSBT is also spent on the other chain",
+                input_index
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ForkReplayError {}
+
+crate::impl_wasm_error_code!(ForkReplayError);
+
+/// A replay protection input of the fork PSBT
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForkReplayProtectionInput {
+    pub input_index: usize,
+    pub previous_output: OutPoint,
+    /// The input has a valid signature (partial or final)
+    pub signed: bool,
+}
+
+/// Comparison of the same spend prepared for two chains that share history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForkReplayReport {
+    /// Outpoints spent by both PSBTs
+    pub shared_inputs: Vec<OutPoint>,
+    /// Indices of fork PSBT outputs whose script is also paid by the other PSBT
+    pub shared_outputs: Vec<usize>,
+    /// Both networks use the same sighash algorithm, so signatures are valid on both chains
+    pub shared_sighash: bool,
+    /// Replay protection inputs of the fork PSBT
+    pub replay_protection_inputs: Vec<ForkReplayProtectionInput>,
+}
+
+impl ForkReplayReport {
+    /// Returns true if the fork PSBT spends a signed replay protection input that the other
+    /// PSBT does not spend
+    pub fn is_replay_protected(&self) -> bool {
+        self.replay_protection_inputs
+            .iter()
+            .any(|input| input.signed && !self.shared_inputs.contains(&input.previous_output))
+    }
+
+    /// Returns true if a transaction from either PSBT could be broadcast on the other chain
+    pub fn is_replayable(&self) -> bool {
+        self.shared_sighash && !self.shared_inputs.is_empty() && !self.is_replay_protected()
+    }
+}
+
+impl BitGoPsbt {
+    /// Compare this PSBT with the same spend prepared for a fork chain
+    ///
+    /// # Arguments
+    /// - `fork_psbt`: The PSBT for the fork network
+    /// - `replay_protection`: Scripts that identify replay protection inputs
+    pub fn fork_replay_report<C: secp256k1::Verification>(
+        &self,
+        secp: &secp256k1::Secp256k1<C>,
+        fork_psbt: &BitGoPsbt,
+        replay_protection: &ReplayProtection,
+    ) -> Result<ForkReplayReport, ForkReplayError> {
+        let (network, fork_network) = (self.network(), fork_psbt.network());
+        if !network.shares_history_with(fork_network) {
+            return Err(ForkReplayError::UnrelatedNetworks {
+                network,
+                fork_network,
+            });
+        }
+
+        let tx = &self.psbt().unsigned_tx;
+        let fork_tx = &fork_psbt.psbt().unsigned_tx;
+        let shared_inputs = fork_tx
+            .input
+            .iter()
+            .map(|tx_in| tx_in.previous_output)
+            .filter(|outpoint| tx.input.iter().any(|i| i.previous_output == *outpoint))
+            .collect();
+        let shared_outputs = fork_tx
+            .output
+            .iter()
+            .enumerate()
+            .filter(|(_, output)| {
+                tx.output
+                    .iter()
+                    .any(|o| o.script_pubkey == output.script_pubkey)
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        let mut replay_protection_inputs = Vec::new();
+        for (input_index, (tx_in, input)) in fork_tx
+            .input
+            .iter()
+            .zip(&fork_psbt.psbt().inputs)
+            .enumerate()
+        {
+            let is_replay_protection = get_output_script_and_value(input, tx_in.previous_output)
+                .is_ok_and(|(script, _)| replay_protection.is_replay_protection_input(script));
+            if !is_replay_protection {
+                continue;
+            }
+            let signed = fork_psbt
+                .verify_replay_protection_signature(secp, input_index, replay_protection)
+                .map_err(|error| ForkReplayError::ReplayProtectionSignature {
+                    input_index,
+                    error,
+                })?;
+            replay_protection_inputs.push(ForkReplayProtectionInput {
+                input_index,
+                previous_output: tx_in.previous_output,
+                signed,
+            });
+        }
+
+        Ok(ForkReplayReport {
+            shared_inputs,
+            shared_outputs,
+            shared_sighash: sighash::get_sighash_fork_id(network)
+                == sighash::get_sighash_fork_id(fork_network),
+            replay_protection_inputs,
+        })
+    }
+
+    /// Check that the fork side of a spend prepared for two chains cannot be replayed
+    ///
+    /// Requires the fork PSBT to spend at least one replay protection input, every replay
+    /// protection input to be signed and none of them to be spent by this PSBT.
+    pub fn verify_fork_replay_protection<C: secp256k1::Verification>(
+        &self,
+        secp: &secp256k1::Secp256k1<C>,
+        fork_psbt: &BitGoPsbt,
+        replay_protection: &ReplayProtection,
+    ) -> Result<ForkReplayReport, ForkReplayError> {
+        let report = self.fork_replay_report(secp, fork_psbt, replay_protection)?;
+        if report.replay_protection_inputs.is_empty() {
+            return Err(ForkReplayError::MissingReplayProtectionInput);
+        }
+        for input in &report.replay_protection_inputs {
+            if !input.signed {
+                return Err(ForkReplayError::UnsignedReplayProtectionInput {
+                    input_index: input.input_index,
+                });
+            }
+            if report.shared_inputs.contains(&input.previous_output) {
+                return Err(ForkReplayError::SharedReplayProtectionInput {
+                    input_index: input.input_index,
+                });
+            }
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::ReplayProtectionOptions;
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+    use miniscript::bitcoin::hashes::Hash;
+    use miniscript::bitcoin::{CompressedPublicKey, Txid};
+
+    const SEED: &str = "fork_replay";
+
+    fn wallet_psbt(network: Network) -> BitGoPsbt {
+        let wallet_keys = get_test_wallet_keys(SEED);
+        WalletPsbtBuilder::new(network, &wallet_keys)
+            .input(0, 0)
+            .output(0, 1, 9_000)
+            .build()
+    }
+
+    fn replay_protection_key() -> (secp256k1::SecretKey, CompressedPublicKey) {
+        let secp = secp256k1::Secp256k1::new();
+        let privkey = secp256k1::SecretKey::from_slice(&[1u8; 32]).unwrap();
+        let pubkey = CompressedPublicKey(privkey.public_key(&secp));
+        (privkey, pubkey)
+    }
+
+    fn add_replay_protection_input(psbt: &mut BitGoPsbt, txid: Txid) -> usize {
+        let (_, pubkey) = replay_protection_key();
+        psbt.add_replay_protection_input(pubkey, txid, 0, 1_000, ReplayProtectionOptions::default())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_fork_replay_report() {
+        let secp = secp256k1::Secp256k1::new();
+        let (privkey, pubkey) = replay_protection_key();
+        let replay_protection = ReplayProtection::from_public_keys(vec![pubkey]);
+        let bch = wallet_psbt(Network::BitcoinCash);
+        let mut bsv = wallet_psbt(Network::BitcoinSV);
+
+        // BCH and BSV both use SIGHASH_FORKID with fork id 0
+        let report = bch
+            .fork_replay_report(&secp, &bsv, &replay_protection)
+            .unwrap();
+        assert_eq!(report.shared_inputs.len(), 1);
+        assert_eq!(report.shared_outputs, vec![0]);
+        assert!(report.shared_sighash);
+        assert!(report.is_replayable());
+        assert!(matches!(
+            bch.verify_fork_replay_protection(&secp, &bsv, &replay_protection),
+            Err(ForkReplayError::MissingReplayProtectionInput)
+        ));
+
+        let index = add_replay_protection_input(&mut bsv, Txid::from_byte_array([1; 32]));
+        assert!(matches!(
+            bch.verify_fork_replay_protection(&secp, &bsv, &replay_protection),
+            Err(ForkReplayError::UnsignedReplayProtectionInput { input_index }) if input_index == index
+        ));
+
+        bsv.sign_with_privkey(index, &privkey).unwrap();
+        let report = bch
+            .verify_fork_replay_protection(&secp, &bsv, &replay_protection)
+            .unwrap();
+        assert!(report.is_replay_protected());
+        assert!(!report.is_replayable());
+
+        // A replay protection input that exists on both chains does not protect the spend
+        let mut bch = bch;
+        add_replay_protection_input(&mut bch, Txid::from_byte_array([1; 32]));
+        assert!(matches!(
+            bch.verify_fork_replay_protection(&secp, &bsv, &replay_protection),
+            Err(ForkReplayError::SharedReplayProtectionInput { input_index }) if input_index == index
+        ));
+    }
+
+    #[test]
+    fn test_fork_replay_report_networks() {
+        let secp = secp256k1::Secp256k1::new();
+        let replay_protection = ReplayProtection::new(vec![]);
+        let btc = wallet_psbt(Network::Bitcoin);
+
+        // BCH signatures commit to SIGHASH_FORKID and are not valid on BTC
+        let report = btc
+            .fork_replay_report(
+                &secp,
+                &wallet_psbt(Network::BitcoinCash),
+                &replay_protection,
+            )
+            .unwrap();
+        assert!(!report.shared_sighash);
+        assert!(!report.is_replayable());
+
+        assert!(matches!(
+            btc.fork_replay_report(&secp, &wallet_psbt(Network::Litecoin), &replay_protection),
+            Err(ForkReplayError::UnrelatedNetworks { .. })
+        ));
+        assert!(matches!(
+            btc.fork_replay_report(&secp, &wallet_psbt(Network::Bitcoin), &replay_protection),
+            Err(ForkReplayError::UnrelatedNetworks { .. })
+        ));
+    }
+}
@@ -0,0 +1,438 @@
+//! Coordinator-friendly JSON representation of a PSBT
+//!
+//! [`BitGoPsbt::to_json`] lists every key-value pair of every PSBT map with its BIP-174
+//! name, and adds readable annotations next to them: input script types and previous
+//! outputs, output addresses and wallet/external classification, and key derivations.
+//!
+//! [`BitGoPsbt::from_json`] rebuilds the PSBT from the raw `entries` only. Annotations are
+//! ignored, which keeps the round-trip lossless for every network format (including Zcash
+//! and Dash) and for keys that the PSBT parser does not interpret (proprietary fields).
+
+use miniscript::bitcoin::bip32::{DerivationPath, Fingerprint};
+use miniscript::bitcoin::psbt::raw::{Key, Pair};
+use miniscript::bitcoin::psbt::{Input, Output};
+use miniscript::bitcoin::{PublicKey, Script, XOnlyPublicKey};
+use serde::{Deserialize, Serialize};
+
+use super::p2tr_musig2_input::Musig2Input;
+use super::psbt_wallet_input::{get_output_script_and_value, ExternalScriptType};
+use super::BitGoPsbt;
+use crate::inspect::{
+    decode_psbt_maps, encode_psbt_maps, is_printable_ascii, key_type_name, parse_proprietary_key,
+    PsbtMapContext, RawPsbtMaps,
+};
+use crate::Network;
+
+/// JSON representation of a PSBT
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PsbtJson {
+    /// Coin name of the network (e.g. "btc", "tzec")
+    pub network: String,
+    /// Annotation: txid of the unsigned transaction
+    #[serde(default)]
+    pub unsigned_txid: String,
+    pub global: Vec<PsbtJsonEntry>,
+    pub inputs: Vec<PsbtJsonInput>,
+    pub outputs: Vec<PsbtJsonOutput>,
+}
+
+/// Raw key-value pair of a PSBT map
+///
+/// Only `type`, `key` and `value` are read by [`BitGoPsbt::from_json`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PsbtJsonEntry {
+    #[serde(rename = "type")]
+    pub key_type: u8,
+    /// Annotation: BIP-174 name of the key type
+    #[serde(default)]
+    pub name: String,
+    /// Hex-encoded key data (without the type byte)
+    pub key: String,
+    /// Hex-encoded value
+    pub value: String,
+    /// Annotation: structure of proprietary (0xFC) keys
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proprietary: Option<PsbtJsonProprietaryKey>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PsbtJsonProprietaryKey {
+    /// Prefix as text if printable (e.g. "BITGO"), hex otherwise
+    pub prefix: String,
+    pub subtype: u8,
+    /// Hex-encoded key data following the subtype
+    pub key: String,
+}
+
+/// Key derivation from `PSBT_*_BIP32_DERIVATION` or `PSBT_*_TAP_BIP32_DERIVATION`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PsbtJsonDerivation {
+    /// Hex-encoded public key (33 bytes for ECDSA, 32 bytes for taproot)
+    pub pubkey: String,
+    pub fingerprint: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PsbtJsonInput {
+    /// Annotation: `txid:vout` of the spent output
+    #[serde(default)]
+    pub previous_output: String,
+    /// Annotation: value of the spent output in satoshis, as a decimal string
+    #[serde(default)]
+    pub value: Option<String>,
+    /// Annotation: script type inferred from the spent output and PSBT fields
+    #[serde(default)]
+    pub script_type: String,
+    #[serde(default)]
+    pub derivations: Vec<PsbtJsonDerivation>,
+    pub entries: Vec<PsbtJsonEntry>,
+}
+
+/// Annotation: whether an output pays back to the wallet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PsbtJsonDestination {
+    /// All derivations of the output match a global xpub
+    Wallet,
+    External,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PsbtJsonOutput {
+    /// Annotation: value in satoshis, as a decimal string
+    #[serde(default)]
+    pub value: String,
+    /// Annotation: hex-encoded output script
+    #[serde(default)]
+    pub script: String,
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default = "default_destination")]
+    pub destination: PsbtJsonDestination,
+    #[serde(default)]
+    pub derivations: Vec<PsbtJsonDerivation>,
+    pub entries: Vec<PsbtJsonEntry>,
+}
+
+fn default_destination() -> PsbtJsonDestination {
+    PsbtJsonDestination::External
+}
+
+pub(super) fn is_zcash(network: Network) -> bool {
+    matches!(network, Network::Zcash | Network::ZcashTestnet)
+}
+
+fn pair_to_entry(pair: &Pair, context: PsbtMapContext) -> PsbtJsonEntry {
+    // `Key::key` starts with the type byte
+    let key_data = &pair.key.key[1..];
+    let proprietary = if pair.key.type_value == 0xFC {
+        parse_proprietary_key(key_data)
+            .ok()
+            .map(|(prefix, subtype, key)| PsbtJsonProprietaryKey {
+                prefix: if is_printable_ascii(&prefix) {
+                    String::from_utf8_lossy(&prefix).to_string()
+                } else {
+                    hex::encode(&prefix)
+                },
+                subtype,
+                key: hex::encode(key),
+            })
+    } else {
+        None
+    };
+    PsbtJsonEntry {
+        key_type: pair.key.type_value,
+        name: key_type_name(pair.key.type_value, context),
+        key: hex::encode(key_data),
+        value: hex::encode(&pair.value),
+        proprietary,
+    }
+}
+
+fn entry_to_pair(entry: &PsbtJsonEntry) -> Result<Pair, String> {
+    let key_data =
+        hex::decode(&entry.key).map_err(|e| format!("Invalid key hex '{}': {}", entry.key, e))?;
+    let value = hex::decode(&entry.value)
+        .map_err(|e| format!("Invalid value hex '{}': {}", entry.value, e))?;
+    let mut key = vec![entry.key_type];
+    key.extend(key_data);
+    Ok(Pair {
+        key: Key {
+            type_value: entry.key_type,
+            key,
+        },
+        value,
+    })
+}
+
+fn entries_to_pairs(entries: &[PsbtJsonEntry]) -> Result<Vec<Pair>, String> {
+    entries.iter().map(entry_to_pair).collect()
+}
+
+fn derivations(
+    bip32_derivation: &std::collections::BTreeMap<
+        miniscript::bitcoin::secp256k1::PublicKey,
+        (Fingerprint, DerivationPath),
+    >,
+    tap_key_origins: &std::collections::BTreeMap<
+        XOnlyPublicKey,
+        (
+            Vec<miniscript::bitcoin::taproot::TapLeafHash>,
+            (Fingerprint, DerivationPath),
+        ),
+    >,
+) -> Vec<PsbtJsonDerivation> {
+    let ecdsa = bip32_derivation
+        .iter()
+        .map(|(pubkey, (fingerprint, path))| PsbtJsonDerivation {
+            pubkey: PublicKey::new(*pubkey).to_string(),
+            fingerprint: fingerprint.to_string(),
+            path: path.to_string(),
+        });
+    let taproot = tap_key_origins
+        .iter()
+        .map(|(pubkey, (_, (fingerprint, path)))| PsbtJsonDerivation {
+            pubkey: pubkey.to_string(),
+            fingerprint: fingerprint.to_string(),
+            path: path.to_string(),
+        });
+    ecdsa.chain(taproot).collect()
+}
+
+/// Best-effort script type of an input, without wallet keys
+fn input_script_type(input: &Input, script: &Script) -> &'static str {
+    if script.is_p2sh() {
+        if input.witness_script.is_some() {
+            "p2shP2wsh"
+        } else if input.redeem_script.as_ref().is_some_and(|s| s.is_p2pk()) {
+            "p2shP2pk"
+        } else {
+            "p2sh"
+        }
+    } else if script.is_p2wsh() {
+        "p2wsh"
+    } else if script.is_p2tr() && Musig2Input::is_musig2_input(input) {
+        "p2trMusig2"
+    } else if script.is_p2tr() && !input.tap_scripts.is_empty() {
+        "p2trLegacy"
+    } else {
+        ExternalScriptType::from_script(script).as_str()
+    }
+}
+
+fn output_destination(output: &Output, wallet_fingerprints: &[Fingerprint]) -> PsbtJsonDestination {
+    let mut fingerprints = output
+        .bip32_derivation
+        .values()
+        .map(|(fingerprint, _)| fingerprint)
+        .chain(
+            output
+                .tap_key_origins
+                .values()
+                .map(|(_, (fingerprint, _))| fingerprint),
+        )
+        .peekable();
+    if fingerprints.peek().is_some() && fingerprints.all(|fp| wallet_fingerprints.contains(fp)) {
+        PsbtJsonDestination::Wallet
+    } else {
+        PsbtJsonDestination::External
+    }
+}
+
+impl BitGoPsbt {
+    /// Convert the PSBT to its JSON representation
+    pub fn to_json_value(&self) -> Result<PsbtJson, String> {
+        let network = self.network();
+        let bytes = self.serialize().map_err(|e| e.to_string())?;
+        let maps = decode_psbt_maps(&bytes, is_zcash(network))?;
+        let psbt = self.psbt();
+
+        let wallet_fingerprints: Vec<Fingerprint> = psbt
+            .xpub
+            .iter()
+            .flat_map(|(xpub, (fingerprint, _))| [xpub.fingerprint(), *fingerprint])
+            .collect();
+
+        let inputs = psbt
+            .unsigned_tx
+            .input
+            .iter()
+            .zip(&psbt.inputs)
+            .zip(&maps.inputs)
+            .map(|((tx_input, input), pairs)| {
+                let prevout = tx_input.previous_output;
+                let script_and_value = get_output_script_and_value(input, prevout).ok();
+                PsbtJsonInput {
+                    previous_output: prevout.to_string(),
+                    value: script_and_value.map(|(_, value)| value.to_sat().to_string()),
+                    script_type: script_and_value
+                        .map(|(script, _)| input_script_type(input, script))
+                        .unwrap_or("unknown")
+                        .to_string(),
+                    derivations: derivations(&input.bip32_derivation, &input.tap_key_origins),
+                    entries: pairs
+                        .iter()
+                        .map(|pair| pair_to_entry(pair, PsbtMapContext::Input))
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let outputs = psbt
+            .unsigned_tx
+            .output
+            .iter()
+            .zip(&psbt.outputs)
+            .zip(&maps.outputs)
+            .map(|((tx_output, output), pairs)| PsbtJsonOutput {
+                value: tx_output.value.to_sat().to_string(),
+                script: hex::encode(tx_output.script_pubkey.as_bytes()),
+                address: crate::address::networks::from_output_script_with_network(
+                    &tx_output.script_pubkey,
+                    network,
+                )
+                .ok(),
+                destination: output_destination(output, &wallet_fingerprints),
+                derivations: derivations(&output.bip32_derivation, &output.tap_key_origins),
+                entries: pairs
+                    .iter()
+                    .map(|pair| pair_to_entry(pair, PsbtMapContext::Output))
+                    .collect(),
+            })
+            .collect();
+
+        Ok(PsbtJson {
+            network: network.to_coin_name().to_string(),
+            unsigned_txid: self.unsigned_txid().to_string(),
+            global: maps
+                .global
+                .iter()
+                .map(|pair| pair_to_entry(pair, PsbtMapContext::Global))
+                .collect(),
+            inputs,
+            outputs,
+        })
+    }
+
+    /// Serialize the PSBT to a readable JSON string
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(&self.to_json_value()?).map_err(|e| e.to_string())
+    }
+
+    /// Rebuild a PSBT from its JSON representation
+    ///
+    /// Only the raw entries are used; annotations are ignored.
+    pub fn from_json_value(json: &PsbtJson) -> Result<BitGoPsbt, String> {
+        let network = Network::from_coin_name(&json.network)
+            .ok_or_else(|| format!("Unknown network '{}'", json.network))?;
+        let maps = RawPsbtMaps {
+            global: entries_to_pairs(&json.global)?,
+            inputs: json
+                .inputs
+                .iter()
+                .map(|input| entries_to_pairs(&input.entries))
+                .collect::<Result<_, _>>()?,
+            outputs: json
+                .outputs
+                .iter()
+                .map(|output| entries_to_pairs(&output.entries))
+                .collect::<Result<_, _>>()?,
+        };
+        BitGoPsbt::deserialize(&encode_psbt_maps(&maps), network).map_err(|e| e.to_string())
+    }
+
+    /// Rebuild a PSBT from a JSON string produced by [`BitGoPsbt::to_json`]
+    pub fn from_json(json: &str) -> Result<BitGoPsbt, String> {
+        let json: PsbtJson =
+            serde_json::from_str(json).map_err(|e| format!("Invalid PSBT JSON: {}", e))?;
+        BitGoPsbt::from_json_value(&json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::test_utils::fixtures;
+    use base64::engine::{general_purpose::STANDARD as BASE64_STANDARD, Engine};
+
+    fn load_fixture(
+        network: Network,
+        format: fixtures::TxFormat,
+        signature_state: fixtures::SignatureState,
+    ) -> Vec<u8> {
+        let fixture = fixtures::load_psbt_fixture_with_format_and_namespace(
+            network.to_utxolib_name(),
+            signature_state,
+            format,
+            fixtures::FixtureNamespace::UtxolibCompat,
+        )
+        .unwrap();
+        BASE64_STANDARD.decode(&fixture.psbt_base64).unwrap()
+    }
+
+    crate::test_psbt_fixtures!(test_json_round_trip, network, format, {
+        for signature_state in [
+            fixtures::SignatureState::Unsigned,
+            fixtures::SignatureState::Halfsigned,
+        ] {
+            let bytes = load_fixture(network, format, signature_state);
+            let psbt = BitGoPsbt::deserialize(&bytes, network).unwrap();
+            let json = psbt.to_json().unwrap();
+            let round_trip = BitGoPsbt::from_json(&json).unwrap();
+            assert_eq!(round_trip.serialize().unwrap(), psbt.serialize().unwrap());
+        }
+    });
+
+    #[test]
+    fn test_json_annotations() {
+        let bytes = load_fixture(
+            Network::Bitcoin,
+            fixtures::TxFormat::Psbt,
+            fixtures::SignatureState::Halfsigned,
+        );
+        let psbt = BitGoPsbt::deserialize(&bytes, Network::Bitcoin).unwrap();
+        let json = psbt.to_json_value().unwrap();
+
+        assert_eq!(json.network, "btc");
+        assert_eq!(json.inputs.len(), psbt.psbt().inputs.len());
+        assert!(json.global.iter().any(|e| e.name == "PSBT_GLOBAL_XPUB"));
+
+        let script_types: Vec<&str> = json.inputs.iter().map(|i| i.script_type.as_str()).collect();
+        assert!(script_types.contains(&"p2sh"), "{:?}", script_types);
+        assert!(script_types.contains(&"p2wsh"), "{:?}", script_types);
+        assert!(script_types.contains(&"p2trMusig2"), "{:?}", script_types);
+
+        assert!(json
+            .outputs
+            .iter()
+            .any(|o| o.destination == PsbtJsonDestination::Wallet));
+        assert!(json
+            .inputs
+            .iter()
+            .flat_map(|i| &i.entries)
+            .filter_map(|e| e.proprietary.as_ref())
+            .any(|p| p.prefix == "BITGO"));
+    }
+
+    #[test]
+    fn test_from_json_rejects_invalid_hex() {
+        let bytes = load_fixture(
+            Network::Bitcoin,
+            fixtures::TxFormat::Psbt,
+            fixtures::SignatureState::Unsigned,
+        );
+        let psbt = BitGoPsbt::deserialize(&bytes, Network::Bitcoin).unwrap();
+        let mut json = psbt.to_json_value().unwrap();
+        json.global[0].value = "zz".to_string();
+        assert!(BitGoPsbt::from_json_value(&json)
+            .unwrap_err()
+            .contains("Invalid value hex"));
+    }
+}
@@ -0,0 +1,313 @@
+//! Key origin export for hardware wallets
+//!
+//! BitGo PSBTs describe each wallet xpub as if it were a master key: the global xpubs use
+//! the `m` path and input/output derivations carry the xpub's own fingerprint. Hardware
+//! wallets (Ledger, Trezor) validate key origins against the registered wallet policy, which
+//! is rooted at the device master key, and reject or ignore keys they cannot place.
+//!
+//! [`BitGoPsbt::export_key_origins`] rewrites these origins so that each wallet key is
+//! described relative to its actual root. Signatures are keyed by public key, so the signed
+//! export can be combined back into the original PSBT.
+
+use miniscript::bitcoin::bip32::{ChildNumber, DerivationPath, Fingerprint, KeySource, Xpub};
+use miniscript::bitcoin::psbt::Psbt;
+
+use super::BitGoPsbt;
+use crate::fixed_script_wallet::RootWalletKeys;
+
+/// Origin of a wallet xpub relative to the root key it was derived from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyOrigin {
+    /// Fingerprint of the root key
+    pub master_fingerprint: Fingerprint,
+    /// Path from the root key to the wallet xpub, e.g. `m/48'/0'/0'/2'`
+    pub account_path: DerivationPath,
+}
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum KeyOriginError {
+    /// The account path length does not match the depth of the xpub
+    DepthMismatch {
+        key_index: usize,
+        depth: u8,
+        path_len: usize,
+    },
+    /// The last account path component is not the xpub's child number
+    ChildNumberMismatch {
+        key_index: usize,
+        expected: ChildNumber,
+        actual: ChildNumber,
+    },
+    /// The master fingerprint contradicts the fingerprint recorded in the xpub
+    FingerprintMismatch {
+        key_index: usize,
+        expected: Fingerprint,
+        actual: Fingerprint,
+    },
+}
+
+impl std::fmt::Display for KeyOriginError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyOriginError::DepthMismatch {
+                key_index,
+                depth,
+                path_len,
+            } => write!(
+                f,
+                "Key {}: account path has {} components but the xpub has depth {}",
+                key_index, path_len, depth
+            ),
+            KeyOriginError::ChildNumberMismatch {
+                key_index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Key {}: account path ends in {} but the xpub child number is {}",
+                key_index, actual, expected
+            ),
+            KeyOriginError::FingerprintMismatch {
+                key_index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Key {}: master fingerprint {} does not match {} recorded in the xpub",
+                key_index, actual, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for KeyOriginError {}
+
+crate::impl_wasm_error_code!(KeyOriginError);
+
+impl KeyOrigin {
+    /// Check the origin against the depth, child number and parent fingerprint of `xpub`
+    ///
+    /// The full path cannot be verified from the xpub alone; only the parts that the xpub
+    /// records are compared.
+    pub fn validate(&self, key_index: usize, xpub: &Xpub) -> Result<(), KeyOriginError> {
+        let path = self.account_path.as_ref();
+        if path.len() != xpub.depth as usize {
+            return Err(KeyOriginError::DepthMismatch {
+                key_index,
+                depth: xpub.depth,
+                path_len: path.len(),
+            });
+        }
+        let expected_fingerprint = match path.last() {
+            None => xpub.fingerprint(),
+            Some(child) if *child != xpub.child_number => {
+                return Err(KeyOriginError::ChildNumberMismatch {
+                    key_index,
+                    expected: xpub.child_number,
+                    actual: *child,
+                })
+            }
+            // Only a direct child records the master fingerprint as its parent
+            Some(_) if path.len() == 1 => xpub.parent_fingerprint,
+            Some(_) => return Ok(()),
+        };
+        if self.master_fingerprint != expected_fingerprint {
+            return Err(KeyOriginError::FingerprintMismatch {
+                key_index,
+                expected: expected_fingerprint,
+                actual: self.master_fingerprint,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Re-root a key source recorded relative to `xpub` under `origin`
+fn rewrite_key_source(source: &mut KeySource, xpub: &Xpub, origin: &KeyOrigin) {
+    let (fingerprint, path) = source;
+    if *fingerprint == xpub.fingerprint() {
+        *path = origin.account_path.extend(&*path);
+        *fingerprint = origin.master_fingerprint;
+    }
+}
+
+fn rewrite_psbt(psbt: &mut Psbt, xpub: &Xpub, origin: &KeyOrigin) {
+    if let Some(source) = psbt.xpub.get_mut(xpub) {
+        *source = (origin.master_fingerprint, origin.account_path.clone());
+    }
+    for input in &mut psbt.inputs {
+        for source in input.bip32_derivation.values_mut() {
+            rewrite_key_source(source, xpub, origin);
+        }
+        for (_, source) in input.tap_key_origins.values_mut() {
+            rewrite_key_source(source, xpub, origin);
+        }
+    }
+    for output in &mut psbt.outputs {
+        for source in output.bip32_derivation.values_mut() {
+            rewrite_key_source(source, xpub, origin);
+        }
+        for (_, source) in output.tap_key_origins.values_mut() {
+            rewrite_key_source(source, xpub, origin);
+        }
+    }
+}
+
+impl BitGoPsbt {
+    /// Rewrite the key origins of the wallet keys for external signers
+    ///
+    /// For each wallet key with an origin, the global xpub entry is set to the origin and
+    /// every input and output derivation recorded relative to the xpub is re-rooted under
+    /// the account path with the master fingerprint. Keys without an origin are left as is.
+    ///
+    /// The result is meant for hardware wallets: BitGo parsing identifies wallet keys by
+    /// their xpub fingerprints, so keep the original PSBT and combine signatures into it.
+    pub fn export_key_origins(
+        &mut self,
+        wallet_keys: &RootWalletKeys,
+        origins: &[Option<KeyOrigin>; 3],
+    ) -> Result<(), KeyOriginError> {
+        for (key_index, (xpub, origin)) in wallet_keys.xpubs.iter().zip(origins).enumerate() {
+            if let Some(origin) = origin {
+                origin.validate(key_index, xpub)?;
+            }
+        }
+        for (xpub, origin) in wallet_keys.xpubs.iter().zip(origins) {
+            if let Some(origin) = origin {
+                rewrite_psbt(self.psbt_mut(), xpub, origin);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::bip32::Xpriv;
+    use crate::bitcoin::secp256k1::Secp256k1;
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+    use crate::Network;
+    use std::str::FromStr;
+
+    fn account_xpub(path: &str) -> (Fingerprint, DerivationPath, Xpub) {
+        let secp = Secp256k1::new();
+        let root = Xpriv::new_master(crate::bitcoin::Network::Testnet, &[7u8; 32]).unwrap();
+        let path = DerivationPath::from_str(path).unwrap();
+        let xpub = Xpub::from_priv(&secp, &root.derive_priv(&secp, &path).unwrap());
+        (root.fingerprint(&secp), path, xpub)
+    }
+
+    #[test]
+    fn test_validate() {
+        let (master, path, xpub) = account_xpub("m/48'/1'/0'/2'");
+        let origin = KeyOrigin {
+            master_fingerprint: master,
+            account_path: path,
+        };
+        origin.validate(0, &xpub).unwrap();
+
+        let wrong_depth = KeyOrigin {
+            account_path: DerivationPath::from_str("m/48'/1'/0'").unwrap(),
+            ..origin.clone()
+        };
+        assert!(matches!(
+            wrong_depth.validate(0, &xpub),
+            Err(KeyOriginError::DepthMismatch { .. })
+        ));
+
+        let wrong_child = KeyOrigin {
+            account_path: DerivationPath::from_str("m/48'/1'/0'/1'").unwrap(),
+            ..origin
+        };
+        assert!(matches!(
+            wrong_child.validate(0, &xpub),
+            Err(KeyOriginError::ChildNumberMismatch { .. })
+        ));
+
+        let (_, path, xpub) = account_xpub("m/0'");
+        let wrong_master = KeyOrigin {
+            master_fingerprint: Fingerprint::from([1, 2, 3, 4]),
+            account_path: path,
+        };
+        assert!(matches!(
+            wrong_master.validate(1, &xpub),
+            Err(KeyOriginError::FingerprintMismatch { key_index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_export_key_origins() {
+        let (master, account_path, user_xpub) = account_xpub("m/48'/1'/0'/2'");
+        let keys = get_test_wallet_keys("key_origins");
+        let wallet_keys = RootWalletKeys::new([user_xpub, keys.xpubs[1], keys.xpubs[2]]);
+
+        let mut psbt = WalletPsbtBuilder::new(Network::Bitcoin, &wallet_keys)
+            .input(20, 3)
+            .input(30, 3)
+            .output(21, 4, 5_000)
+            .build();
+        let original = psbt.clone();
+
+        let origin = KeyOrigin {
+            master_fingerprint: master,
+            account_path: account_path.clone(),
+        };
+        psbt.export_key_origins(&wallet_keys, &[Some(origin), None, None])
+            .unwrap();
+
+        let inner = psbt.psbt();
+        assert_eq!(inner.xpub[&user_xpub], (master, account_path.clone()));
+        assert_eq!(
+            inner.xpub[&wallet_keys.xpubs[1]],
+            original.psbt().xpub[&wallet_keys.xpubs[1]]
+        );
+
+        let expected_path = account_path.extend(DerivationPath::from_str("m/0/0/20/3").unwrap());
+        assert!(inner.inputs[0]
+            .bip32_derivation
+            .values()
+            .any(|source| *source == (master, expected_path.clone())));
+        let user_taproot_origin = inner.inputs[1]
+            .tap_key_origins
+            .values()
+            .find(|(_, (fp, _))| *fp == master)
+            .map(|(_, (_, path))| path.clone());
+        assert_eq!(
+            user_taproot_origin,
+            Some(account_path.extend(DerivationPath::from_str("m/0/0/30/3").unwrap()))
+        );
+        assert!(inner.outputs[0]
+            .bip32_derivation
+            .values()
+            .any(|(fp, _)| *fp == master));
+
+        // Keys without an origin keep their derivations
+        let backup_fingerprint = wallet_keys.xpubs[1].fingerprint();
+        assert_eq!(
+            inner.inputs[0]
+                .bip32_derivation
+                .values()
+                .filter(|(fp, _)| *fp == backup_fingerprint)
+                .count(),
+            1
+        );
+
+        // Exporting again is a no-op
+        let exported = psbt.clone();
+        psbt.export_key_origins(
+            &wallet_keys,
+            &[
+                Some(KeyOrigin {
+                    master_fingerprint: master,
+                    account_path,
+                }),
+                None,
+                None,
+            ],
+        )
+        .unwrap();
+        assert_eq!(psbt.serialize().unwrap(), exported.serialize().unwrap());
+    }
+}
@@ -0,0 +1,210 @@
+//! Human-readable labels on PSBT inputs and outputs
+//!
+//! A label is stored as a BitGo proprietary key-value with subtype `Label`, an empty key and
+//! the UTF-8 label as value (e.g. `consolidation` or `customer-withdrawal:1234`). Being part of
+//! the PSBT, labels survive serialization, signing and [`BitGoPsbt::combine_inputs`] and are
+//! returned with the parsed inputs and outputs.
+//!
+//! Labels are not signed and not covered by any sighash: they are annotations, not policy.
+
+use std::collections::BTreeMap;
+
+use miniscript::bitcoin::psbt::raw::ProprietaryKey;
+
+use super::propkv::{find_kv, BitGoKeyValue, ProprietaryKeySubtype};
+use super::BitGoPsbt;
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum LabelError {
+    /// The labeled input does not exist
+    InputIndexOutOfBounds {
+        input_index: usize,
+        input_count: usize,
+    },
+    /// The labeled output does not exist
+    OutputIndexOutOfBounds {
+        output_index: usize,
+        output_count: usize,
+    },
+}
+
+impl std::fmt::Display for LabelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LabelError::InputIndexOutOfBounds {
+                input_index,
+                input_count,
+            } => write!(
+                f,
+                "Input index {} out of bounds (total inputs: {})",
+                input_index, input_count
+            ),
+            LabelError::OutputIndexOutOfBounds {
+                output_index,
+                output_count,
+            } => write!(
+                f,
+                "Output index {} out of bounds (total outputs: {})",
+                output_index, output_count
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LabelError {}
+
+crate::impl_wasm_error_code!(LabelError);
+crate::impl_from_coded_error!(LabelError);
+
+/// Read the label of an input or output proprietary map
+///
+/// Labels that are not valid UTF-8 are decoded lossily rather than rejected, since they are
+/// only meant for display.
+pub(crate) fn get_label(proprietary: &BTreeMap<ProprietaryKey, Vec<u8>>) -> Option<String> {
+    find_kv(ProprietaryKeySubtype::Label, proprietary)
+        .next()
+        .map(|kv| String::from_utf8_lossy(&kv.value).into_owned())
+}
+
+fn set_label(proprietary: &mut BTreeMap<ProprietaryKey, Vec<u8>>, label: Option<&str>) {
+    let (key, _) = BitGoKeyValue::new(ProprietaryKeySubtype::Label, vec![], vec![]).to_key_value();
+    match label {
+        Some(label) => proprietary.insert(key, label.as_bytes().to_vec()),
+        None => proprietary.remove(&key),
+    };
+}
+
+impl BitGoPsbt {
+    /// Get the label of an input, `None` if it has none
+    pub fn input_label(&self, input_index: usize) -> Result<Option<String>, LabelError> {
+        let inputs = &self.psbt().inputs;
+        let input = inputs
+            .get(input_index)
+            .ok_or(LabelError::InputIndexOutOfBounds {
+                input_index,
+                input_count: inputs.len(),
+            })?;
+        Ok(get_label(&input.proprietary))
+    }
+
+    /// Set or, with `None`, remove the label of an input
+    pub fn set_input_label(
+        &mut self,
+        input_index: usize,
+        label: Option<&str>,
+    ) -> Result<(), LabelError> {
+        let inputs = &mut self.psbt_mut().inputs;
+        let input_count = inputs.len();
+        let input = inputs
+            .get_mut(input_index)
+            .ok_or(LabelError::InputIndexOutOfBounds {
+                input_index,
+                input_count,
+            })?;
+        set_label(&mut input.proprietary, label);
+        Ok(())
+    }
+
+    /// Get the label of an output, `None` if it has none
+    pub fn output_label(&self, output_index: usize) -> Result<Option<String>, LabelError> {
+        let outputs = &self.psbt().outputs;
+        let output = outputs
+            .get(output_index)
+            .ok_or(LabelError::OutputIndexOutOfBounds {
+                output_index,
+                output_count: outputs.len(),
+            })?;
+        Ok(get_label(&output.proprietary))
+    }
+
+    /// Set or, with `None`, remove the label of an output
+    pub fn set_output_label(
+        &mut self,
+        output_index: usize,
+        label: Option<&str>,
+    ) -> Result<(), LabelError> {
+        let outputs = &mut self.psbt_mut().outputs;
+        let output_count = outputs.len();
+        let output = outputs
+            .get_mut(output_index)
+            .ok_or(LabelError::OutputIndexOutOfBounds {
+                output_index,
+                output_count,
+            })?;
+        set_label(&mut output.proprietary, label);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::{
+        get_test_wallet_keys, get_test_wallet_xprvs,
+    };
+    use crate::Network;
+
+    #[test]
+    fn test_labels_survive_serialize_and_sign() {
+        let seed = "labels";
+        let wallet_keys = get_test_wallet_keys(seed);
+        let mut psbt = WalletPsbtBuilder::new(Network::Bitcoin, &wallet_keys)
+            .input(20, 0)
+            .output(20, 1, 9_000)
+            .build();
+
+        psbt.set_input_label(0, Some("consolidation")).unwrap();
+        psbt.set_output_label(0, Some("customer-withdrawal:1234"))
+            .unwrap();
+        assert!(matches!(
+            psbt.set_output_label(1, Some("x")),
+            Err(LabelError::OutputIndexOutOfBounds {
+                output_index: 1,
+                output_count: 1
+            })
+        ));
+
+        let bytes = psbt.serialize().unwrap();
+        let mut psbt = BitGoPsbt::deserialize(&bytes, Network::Bitcoin).unwrap();
+        psbt.sign_all_with_xpriv(&get_test_wallet_xprvs(seed)[0])
+            .unwrap();
+
+        assert_eq!(
+            psbt.input_label(0).unwrap().as_deref(),
+            Some("consolidation")
+        );
+        assert_eq!(
+            psbt.output_label(0).unwrap().as_deref(),
+            Some("customer-withdrawal:1234")
+        );
+
+        psbt.set_input_label(0, None).unwrap();
+        assert_eq!(psbt.input_label(0).unwrap(), None);
+    }
+
+    #[test]
+    fn test_labels_survive_combine_inputs() {
+        let wallet_keys = get_test_wallet_keys("labels");
+        let mut psbt = WalletPsbtBuilder::new(Network::Bitcoin, &wallet_keys)
+            .input(20, 0)
+            .output(20, 1, 9_000)
+            .build();
+
+        let mut labeled = psbt.clone();
+        labeled.set_input_label(0, Some("consolidation")).unwrap();
+        labeled
+            .set_output_label(0, Some("customer-withdrawal:1234"))
+            .unwrap();
+
+        psbt.combine_inputs(&labeled.serialize().unwrap()).unwrap();
+        assert_eq!(
+            psbt.input_label(0).unwrap().as_deref(),
+            Some("consolidation")
+        );
+        assert_eq!(
+            psbt.output_label(0).unwrap().as_deref(),
+            Some("customer-withdrawal:1234")
+        );
+    }
+}
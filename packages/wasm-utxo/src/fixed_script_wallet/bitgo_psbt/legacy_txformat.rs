@@ -2,7 +2,8 @@
 //!
 //! This module provides functionality to extract half-signed transactions in the
 //! legacy format used by utxo-lib and bitcoinjs-lib, where signatures are placed
-//! in scriptSig/witness with OP_0 placeholders for missing signatures.
+//! in scriptSig/witness with OP_0 placeholders for missing signatures, or in the
+//! compact layout without placeholders (see [`HalfSignedLayout`]).
 
 use crate::fixed_script_wallet::wallet_scripts::{parse_multisig_script_2_of_3, parse_p2pk_script};
 use miniscript::bitcoin::blockdata::opcodes::all::OP_PUSHBYTES_0;
@@ -11,11 +12,32 @@ use miniscript::bitcoin::psbt::Psbt;
 use miniscript::bitcoin::script::PushBytesBuf;
 use miniscript::bitcoin::{Transaction, Witness};
 
+/// Placement of the missing signatures of a half-signed 2-of-3 multisig input
+///
+/// Both layouts start with the dummy item consumed by the CHECKMULTISIG off-by-one bug:
+/// `OP_0` in a scriptSig, an empty item in a witness. For p2shP2wsh and p2wsh inputs the
+/// signature is always placed in the witness; the scriptSig of a p2shP2wsh input only pushes
+/// the redeem script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HalfSignedLayout {
+    /// An `OP_0` (empty witness item) for each missing signature, so that the signature is
+    /// at the position of its key: `OP_0 OP_0 <sig> OP_0 <redeemScript>` (utxo-lib and the
+    /// bitcoinjs-lib `TransactionBuilder`)
+    #[default]
+    Positional,
+    /// Missing signatures are left out: `OP_0 <sig> <redeemScript>`. Readers find the key
+    /// of the signature by verifying it against each wallet key.
+    Compact,
+}
+
 /// Build a half-signed transaction in legacy format from a PSBT.
 ///
 /// Returns the Transaction with signatures placed in scriptSig/witness.
 /// Use `extract_half_signed_legacy_tx` for serialized bytes.
-pub fn build_half_signed_legacy_tx(psbt: &Psbt) -> Result<Transaction, String> {
+pub fn build_half_signed_legacy_tx(
+    psbt: &Psbt,
+    layout: HalfSignedLayout,
+) -> Result<Transaction, String> {
     // Validate we have inputs and outputs
     if psbt.inputs.is_empty() || psbt.unsigned_tx.output.is_empty() {
         return Err("empty inputs or outputs".to_string());
@@ -77,12 +99,13 @@ pub fn build_half_signed_legacy_tx(psbt: &Psbt) -> Result<Transaction, String> {
             let sig_bytes = ecdsa_sig.to_vec();
 
             // Build the signatures array with the signature in the correct position
-            // Format: [OP_0, sig_or_empty, sig_or_empty, sig_or_empty]
+            // Positional: [OP_0, sig_or_empty, sig_or_empty, sig_or_empty]
+            // Compact: [OP_0, sig]
             let mut sig_stack: Vec<Vec<u8>> = vec![vec![]]; // Start with OP_0 (empty)
             for i in 0..3 {
                 if i == sig_key_index {
                     sig_stack.push(sig_bytes.clone());
-                } else {
+                } else if layout == HalfSignedLayout::Positional {
                     sig_stack.push(vec![]); // OP_0 placeholder
                 }
             }
@@ -109,19 +132,18 @@ pub fn build_half_signed_legacy_tx(psbt: &Psbt) -> Result<Transaction, String> {
                 }
             } else {
                 // p2sh only: scriptSig = [OP_0, sigs..., redeemScript]
-                let mut builder = Builder::new().push_opcode(OP_PUSHBYTES_0);
-                for i in 0..3 {
-                    if i == sig_key_index {
-                        let sig_push_bytes =
-                            PushBytesBuf::try_from(sig_bytes.clone()).map_err(|e| {
-                                format!(
-                                    "Input {}: failed to convert signature to push bytes: {}",
-                                    input_index, e
-                                )
-                            })?;
-                        builder = builder.push_slice(sig_push_bytes);
-                    } else {
+                let mut builder = Builder::new();
+                for item in sig_stack {
+                    if item.is_empty() {
                         builder = builder.push_opcode(OP_PUSHBYTES_0);
+                    } else {
+                        let sig_push_bytes = PushBytesBuf::try_from(item).map_err(|e| {
+                            format!(
+                                "Input {}: failed to convert signature to push bytes: {}",
+                                input_index, e
+                            )
+                        })?;
+                        builder = builder.push_slice(sig_push_bytes);
                     }
                 }
                 let multisig_push_bytes =
@@ -0,0 +1,164 @@
+//! Low-R ECDSA signature grinding
+//!
+//! Like Bitcoin Core, ECDSA signatures are ground until the R value is below 2^255, so that
+//! its DER encoding needs no padding byte. Signatures (with sighash byte) are then at most
+//! 71 bytes instead of 72, which makes size estimation deterministic.
+//!
+//! Signing in this crate matches rust-bitcoin and does not grind. Grinding is an explicit
+//! step, [`BitGoPsbt::grind_low_r`], run after signing on the thread that owns the PSBT. The
+//! wasm bindings grind by default.
+
+use miniscript::bitcoin::psbt::{GetKey, KeyRequest};
+use miniscript::bitcoin::secp256k1::{self, ecdsa, SecretKey};
+use miniscript::bitcoin::PublicKey;
+
+use super::fixed_script_input::{FixedScriptInput, SighashContext};
+use super::{propkv, sighash, zcash_psbt, BitGoPsbt};
+
+/// True if the R value of the signature has its top bit unset
+pub fn is_low_r(signature: &ecdsa::Signature) -> bool {
+    signature.serialize_compact()[0] < 0x80
+}
+
+impl BitGoPsbt {
+    pub(super) fn sighash_context(&self) -> Result<SighashContext, String> {
+        match self {
+            BitGoPsbt::Zcash(zcash_psbt, _) => Ok(SighashContext::Zcash {
+                consensus_branch_id: propkv::get_zec_consensus_branch_id(&zcash_psbt.psbt)
+                    .ok_or_else(|| "Missing ZecConsensusBranchId in PSBT".to_string())?,
+                version_group_id: zcash_psbt
+                    .version_group_id
+                    .unwrap_or(zcash_psbt::ZCASH_SAPLING_VERSION_GROUP_ID),
+                expiry_height: zcash_psbt.expiry_height.unwrap_or(0),
+            }),
+            _ => Ok(SighashContext::Bitcoin {
+                fork_id: sighash::get_sighash_fork_id(self.network()),
+            }),
+        }
+    }
+
+    /// Look up the private key of `pubkey` in `k`, by key origin or by public key
+    pub(super) fn get_signing_key<C: secp256k1::Signing, K: GetKey>(
+        &self,
+        k: &K,
+        secp: &secp256k1::Secp256k1<C>,
+        input_index: usize,
+        pubkey: &PublicKey,
+    ) -> Option<SecretKey> {
+        let input = &self.psbt().inputs[input_index];
+        let by_origin = input
+            .bip32_derivation
+            .get(&pubkey.inner)
+            .and_then(|origin| k.get_key(KeyRequest::Bip32(origin.clone()), secp).ok())
+            .flatten();
+        by_origin
+            .or_else(|| k.get_key(KeyRequest::Pubkey(*pubkey), secp).ok().flatten())
+            .filter(|key| key.public_key(secp) == *pubkey)
+            .map(|key| key.inner)
+    }
+
+    /// Replace the high-R ECDSA signatures made by `k` on `input_indices` with low-R ones
+    ///
+    /// Best effort: a signature is kept if its key or sighash cannot be determined.
+    pub fn grind_low_r<C, K>(
+        &mut self,
+        k: &K,
+        secp: &secp256k1::Secp256k1<C>,
+        input_indices: impl IntoIterator<Item = usize>,
+    ) where
+        C: secp256k1::Signing,
+        K: GetKey,
+    {
+        let Ok(ctx) = self.sighash_context() else {
+            return;
+        };
+        for input_index in input_indices {
+            let Some(input) = self.psbt().inputs.get(input_index) else {
+                continue;
+            };
+            let high_r: Vec<_> = input
+                .partial_sigs
+                .iter()
+                .filter(|(_, signature)| !is_low_r(&signature.signature))
+                .map(|(pubkey, signature)| (*pubkey, signature.sighash_type))
+                .collect();
+            let mut message = None;
+            for (pubkey, sighash_type) in high_r {
+                let Some(secret_key) = self.get_signing_key(k, secp, input_index, &pubkey) else {
+                    continue;
+                };
+                if message.is_none() {
+                    message =
+                        FixedScriptInput::compute_compact_sighash(self.psbt(), input_index, &ctx)
+                            .ok();
+                }
+                let Some(message) = message else {
+                    break;
+                };
+                let signature = secp.sign_ecdsa_low_r(&message, &secret_key);
+                self.psbt_mut().inputs[input_index].partial_sigs.insert(
+                    pubkey,
+                    miniscript::bitcoin::ecdsa::Signature {
+                        signature,
+                        sighash_type,
+                    },
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::{
+        get_test_wallet_keys, get_test_wallet_xprvs,
+    };
+    use crate::networks::Network;
+
+    fn signed_psbt(network: Network, chain: u32) -> BitGoPsbt {
+        let keys = get_test_wallet_keys("low_r");
+        let xprvs = get_test_wallet_xprvs("low_r");
+        let mut psbt = WalletPsbtBuilder::new(network, &keys)
+            .inputs(16, chain)
+            .build();
+        let secp = secp256k1::Secp256k1::new();
+        psbt.sign(&xprvs[0], &secp).unwrap();
+        psbt
+    }
+
+    fn signatures(psbt: &BitGoPsbt) -> Vec<ecdsa::Signature> {
+        psbt.psbt()
+            .inputs
+            .iter()
+            .flat_map(|input| input.partial_sigs.values().map(|sig| sig.signature))
+            .collect()
+    }
+
+    #[test]
+    fn test_low_r_grinding() {
+        let secp = secp256k1::Secp256k1::new();
+        let xprvs = get_test_wallet_xprvs("low_r");
+        let xpub = miniscript::bitcoin::bip32::Xpub::from_priv(&secp, &xprvs[0]);
+        for (network, chain) in [
+            (Network::Bitcoin, 0),
+            (Network::Bitcoin, 20),
+            (Network::BitcoinCash, 0),
+        ] {
+            // Without grinding, about half of the signatures are high-R
+            let mut psbt = signed_psbt(network, chain);
+            assert!(!signatures(&psbt).iter().all(is_low_r));
+
+            psbt.grind_low_r(&xprvs[0], &secp, 0..16);
+            let sigs = signatures(&psbt);
+            assert_eq!(sigs.len(), 16);
+            assert!(sigs.iter().all(is_low_r), "{:?} chain {}", network, chain);
+            for input_index in 0..16 {
+                assert!(psbt
+                    .verify_signature_with_xpub(&secp, input_index, &xpub)
+                    .unwrap());
+            }
+        }
+    }
+}
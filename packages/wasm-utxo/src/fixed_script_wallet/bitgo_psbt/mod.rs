@@ -3,25 +3,97 @@
 //! This module provides PSBT deserialization that works across different
 //! bitcoin-like networks, including those with non-standard transaction formats.
 
+pub mod approval_digest;
+pub mod chained;
+pub mod custom_satisfier;
 pub mod dash_psbt;
+#[cfg(feature = "inspect")]
+pub mod diff;
+pub mod external_signing;
+pub mod extract_invariants;
+pub mod fork_clone;
+pub mod fork_replay;
+#[cfg(feature = "inspect")]
+pub mod json;
+pub mod key_origins;
+pub mod labels;
 mod legacy_txformat;
+pub mod low_r;
+pub mod musig2_session;
+pub mod nonce_audit;
+pub mod p2tr_musig2_adaptor;
 pub mod p2tr_musig2_input;
 #[cfg(test)]
 mod p2tr_musig2_input_utxolib;
+pub mod parallel;
+pub mod progress;
 pub(crate) mod propkv;
+#[cfg(test)]
+mod proptests;
 pub mod psbt_wallet_input;
 pub mod psbt_wallet_output;
+pub mod redact;
+pub mod risk_report;
+pub mod script_interpreter;
+pub mod script_validation;
+pub mod sequence;
 mod sighash;
+pub mod sign_path;
+pub mod signer_role;
+pub mod signing_policy;
+pub mod single_sig;
+pub mod spend_conflicts;
+pub mod stale_nonces;
+pub mod tx_package;
+pub mod utxo_normalize;
+pub mod vault_policy;
+pub mod wallet_policy;
 pub mod zcash_psbt;
 
 use crate::Network;
+pub use chained::ChainedInputError;
+pub use custom_satisfier::{CustomFinalizeError, InputSatisfier, TemplateItem, WitnessTemplate};
 pub use dash_psbt::DashBitGoPsbt;
-use miniscript::bitcoin::{psbt::Psbt, secp256k1, CompressedPublicKey, FeeRate, Txid};
+#[cfg(feature = "inspect")]
+pub use diff::{PsbtDiffChange, PsbtDiffEntry, PsbtDiffMap};
+pub use external_signing::{InputSighash, SighashVersion, SignerKey};
+pub use extract_invariants::{ExtractInvariantError, ExtractInvariants};
+pub use fork_clone::CloneForNetworkError;
+pub use fork_replay::{ForkReplayError, ForkReplayProtectionInput, ForkReplayReport};
+#[cfg(feature = "inspect")]
+pub use json::{
+    PsbtJson, PsbtJsonDerivation, PsbtJsonDestination, PsbtJsonEntry, PsbtJsonInput,
+    PsbtJsonOutput, PsbtJsonProprietaryKey,
+};
+pub use key_origins::{KeyOrigin, KeyOriginError};
+pub use labels::LabelError;
+pub use legacy_txformat::HalfSignedLayout;
+pub use low_r::is_low_r;
+use miniscript::bitcoin::{psbt::Psbt, secp256k1, CompressedPublicKey, FeeRate, Txid, Weight};
+pub use musig2_session::{Musig2Session, Musig2SessionEntry, Musig2SessionError};
+pub use nonce_audit::{NonceAuditError, NonceDerivation};
+pub use progress::{CancellationToken, InputProgress, OperationCancelled, ProgressError};
 pub use propkv::{
     find_kv, get_zec_consensus_branch_id, BitGoKeyValue, ProprietaryKeySubtype,
     WasmUtxoVersionInfo, BITGO,
 };
+pub use redact::RedactError;
+pub use risk_report::{
+    DustOutput, NonDefaultSighash, OpReturnOutput, ReusedAddress, RiskReport, RiskReportOptions,
+};
+pub use script_interpreter::{ScriptError, SighashAlgorithm, VerifyInputScriptsError};
+pub use script_validation::{validate_psbt_scripts, PsbtLocation, ScriptValidationError};
+pub use sequence::SequenceMode;
 pub use sighash::{get_sighash_fork_id, validate_sighash_type};
+pub use sign_path::SignPathError;
+pub use signer_role::SignerRoleError;
+pub use signing_policy::{SigningPolicy, SigningPolicyError};
+pub use single_sig::{SingleSigInput, SingleSigScriptType};
+pub use spend_conflicts::{find_spend_conflicts, InputRef, SpendConflict, SpendConflictKind};
+pub use stale_nonces::{StaleMusig2Nonce, StaleNonceReason};
+pub use tx_package::{PackageFee, TxPackage, TxPackageError};
+pub use utxo_normalize::UtxoNormalizeError;
+pub use vault_policy::{VaultPolicy, VaultPolicyError};
 pub use zcash_psbt::{
     decode_zcash_transaction_meta, ZcashBitGoPsbt, ZcashTransactionMeta,
     ZCASH_SAPLING_VERSION_GROUP_ID,
@@ -35,6 +107,16 @@ pub enum DeserializeError {
     Psbt(miniscript::bitcoin::psbt::Error),
     /// Network-specific error message
     Network(String),
+    /// Strict validation found inconsistent scripts or key origins
+    Validation(ScriptValidationError),
+    /// The global xpubs do not match the expected wallet keys
+    WalletKeysMismatch,
+    /// The Zcash transaction has Sapling spends or outputs or Sprout JoinSplits
+    UnsupportedShieldedComponents {
+        spends: usize,
+        outputs: usize,
+        joinsplits: usize,
+    },
 }
 
 impl std::fmt::Display for DeserializeError {
@@ -43,6 +125,20 @@ impl std::fmt::Display for DeserializeError {
             DeserializeError::Consensus(e) => write!(f, "{}", e),
             DeserializeError::Psbt(e) => write!(f, "{}", e),
             DeserializeError::Network(msg) => write!(f, "{}", msg),
+            DeserializeError::Validation(e) => write!(f, "{}", e),
+            DeserializeError::WalletKeysMismatch => {
+                write!(f, "PSBT global xpubs do not match the wallet keys")
+            }
+            DeserializeError::UnsupportedShieldedComponents {
+                spends,
+                outputs,
+                joinsplits,
+            } => write!(
+                f,
+                "Zcash transaction has shielded components ({} spends, {} outputs, {} joinsplits); \
+                 only transparent transactions are supported",
+                spends, outputs, joinsplits
+            ),
         }
     }
 }
@@ -62,6 +158,23 @@ impl From<miniscript::bitcoin::psbt::Error> for DeserializeError {
     }
 }
 
+impl From<ScriptValidationError> for DeserializeError {
+    fn from(e: ScriptValidationError) -> Self {
+        DeserializeError::Validation(e)
+    }
+}
+
+/// How much of a PSBT to check when deserializing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DeserializeValidation {
+    /// Only decode the PSBT
+    #[default]
+    None,
+    /// Also run [`validate_psbt_scripts`], rejecting PSBTs whose redeem/witness scripts,
+    /// taproot control blocks or key origins do not match the scripts they describe
+    Strict,
+}
+
 #[derive(Debug, strum::IntoStaticStr)]
 pub enum SerializeError {
     /// Standard bitcoin consensus encoding error
@@ -97,10 +210,252 @@ impl From<DeserializeError> for SerializeError {
             }
             DeserializeError::Psbt(pe) => SerializeError::Network(format!("PSBT error: {}", pe)),
             DeserializeError::Network(msg) => SerializeError::Network(msg),
+            e => SerializeError::Network(e.to_string()),
+        }
+    }
+}
+
+/// Errors of adding, signing and finalizing wallet inputs
+///
+/// The messages are the same as those of the former string errors; callers that handle
+/// errors programmatically should match on the variant (or its code) instead.
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum BitGoPsbtError {
+    /// The input index is past the end of the inputs
+    InputIndexOutOfBounds { index: usize, input_count: usize },
+    /// The value exceeds the maximum amount of the network
+    ValueExceedsMaxMoney {
+        value: u64,
+        max_money: u64,
+        network: Network,
+    },
+    /// The chain code is not a wallet chain
+    InvalidChain(u32),
+    /// The scripts or PSBT fields of a wallet input cannot be created
+    WalletInput(WalletInputError),
+    /// MuSig2 inputs are signed in two rounds (see `sign_with_first_round`)
+    Musig2RequiresNonces { input_index: usize },
+    /// The key does not match the key of a replay protection input
+    PublicKeyMismatch { input_index: usize },
+    /// The key signs none of the input's scripts, or the input is already signed by it
+    NotSigned { input_index: usize },
+    /// Signing failed
+    Signing {
+        input_index: usize,
+        error: SignInputError,
+    },
+    /// The input cannot be finalized
+    Finalize {
+        input_index: usize,
+        error: FinalizeInputError,
+    },
+}
+
+impl BitGoPsbtError {
+    /// Index of the input the error refers to
+    pub fn input_index(&self) -> Option<usize> {
+        match self {
+            BitGoPsbtError::InputIndexOutOfBounds { index, .. } => Some(*index),
+            BitGoPsbtError::Musig2RequiresNonces { input_index }
+            | BitGoPsbtError::PublicKeyMismatch { input_index }
+            | BitGoPsbtError::NotSigned { input_index }
+            | BitGoPsbtError::Signing { input_index, .. }
+            | BitGoPsbtError::Finalize { input_index, .. } => Some(*input_index),
+            BitGoPsbtError::ValueExceedsMaxMoney { .. }
+            | BitGoPsbtError::InvalidChain(_)
+            | BitGoPsbtError::WalletInput(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for BitGoPsbtError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BitGoPsbtError::InputIndexOutOfBounds { index, input_count } => write!(
+                f,
+                "Input index {} out of bounds (total inputs: {})",
+                index, input_count
+            ),
+            BitGoPsbtError::ValueExceedsMaxMoney {
+                value,
+                max_money,
+                network,
+            } => write!(
+                f,
+                "Value {} exceeds the maximum of {} satoshis for {}",
+                value, max_money, network
+            ),
+            BitGoPsbtError::InvalidChain(chain) => write!(f, "no chain for {}", chain),
+            BitGoPsbtError::WalletInput(error) => write!(f, "{}", error),
+            BitGoPsbtError::Musig2RequiresNonces { .. } => write!(
+                f,
+                "MuSig2 inputs cannot be signed with raw privkey. Use sign_with_first_round instead."
+            ),
+            BitGoPsbtError::PublicKeyMismatch { .. } => write!(
+                f,
+                "Public key mismatch: derived pubkey does not match redeem_script pubkey"
+            ),
+            BitGoPsbtError::NotSigned { input_index } => write!(
+                f,
+                "Input {} was not signed (no key found or already signed)",
+                input_index
+            ),
+            BitGoPsbtError::Signing { input_index, error } => {
+                write!(f, "Failed to sign input {}: {}", input_index, error)
+            }
+            BitGoPsbtError::Finalize { error, .. } => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for BitGoPsbtError {}
+
+impl crate::error::WasmErrorCode for BitGoPsbtError {
+    fn code(&self) -> String {
+        let variant: &str = self.into();
+        match self {
+            Self::WalletInput(error) => format!("BitGoPsbtError.{}/{}", variant, error.code()),
+            Self::Signing { error, .. } => format!("BitGoPsbtError.{}/{}", variant, error.code()),
+            Self::Finalize { error, .. } => {
+                format!("BitGoPsbtError.{}/{}", variant, error.code())
+            }
+            _ => format!("BitGoPsbtError.{}", variant),
+        }
+    }
+}
+
+/// Why the scripts or PSBT fields of a wallet input cannot be created
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum WalletInputError {
+    /// The wallet keys cannot be derived at the chain and index of the input
+    Derivation(String),
+    /// The wallet scripts of the input cannot be created
+    Scripts(String),
+    /// `prev_tx` is not a valid transaction
+    PrevTx(miniscript::bitcoin::consensus::encode::Error),
+    /// p2tr and p2trMusig2 inputs need a sign path
+    MissingSignPath,
+    /// P2MR inputs cannot be signed yet
+    P2mrNotSupported,
+    /// The taproot fields of the sign path cannot be set
+    SignPath(String),
+}
+
+impl std::fmt::Display for WalletInputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WalletInputError::Derivation(error) => write!(f, "Failed to derive keys: {}", error),
+            WalletInputError::Scripts(error) => {
+                write!(f, "Failed to create wallet scripts: {}", error)
+            }
+            WalletInputError::PrevTx(error) => {
+                write!(f, "Failed to deserialize previous transaction: {}", error)
+            }
+            WalletInputError::MissingSignPath => {
+                write!(f, "sign_path is required for p2tr/p2trMusig2 inputs")
+            }
+            WalletInputError::P2mrNotSupported => {
+                write!(f, "P2MR PSBT input signing is not yet supported")
+            }
+            WalletInputError::SignPath(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for WalletInputError {}
+crate::impl_wasm_error_code!(WalletInputError);
+
+impl From<WalletInputError> for BitGoPsbtError {
+    fn from(error: WalletInputError) -> Self {
+        BitGoPsbtError::WalletInput(error)
+    }
+}
+
+/// Why an input cannot be signed with a private key
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum SignInputError {
+    /// The spent output of the input is missing or invalid
+    Utxo(psbt_wallet_input::OutputScriptError),
+    /// The spent outputs of all inputs are needed for the taproot sighash
+    Prevouts(String),
+    /// The key does not match the single-sig script of the input
+    ScriptMismatch { script_type: &'static str },
+    /// The tweaked key does not match the taproot output key of the input
+    TweakedKeyMismatch,
+    /// The key is in none of the tap leaf scripts of the input
+    KeyNotInTapLeaf,
+    /// The sighash type of the input is invalid for its script or network
+    InvalidSighashType(String),
+    /// The network requires `SIGHASH_FORKID`, but the sighash type of the input lacks it
+    MissingForkId { sighash_type: u32 },
+    /// The sighash cannot be computed
+    Sighash(String),
+    /// The PSBT has no Zcash consensus branch ID
+    MissingConsensusBranchId,
+    /// The PSBT signer rejected the input
+    Psbt(String),
+}
+
+impl std::fmt::Display for SignInputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignInputError::Utxo(error) => write!(f, "{}", error),
+            SignInputError::Prevouts(error) => write!(f, "{}", error),
+            SignInputError::ScriptMismatch { script_type } => {
+                write!(f, "Private key does not match the {} script", script_type)
+            }
+            SignInputError::TweakedKeyMismatch => {
+                write!(f, "Tweaked key does not match the taproot output key")
+            }
+            SignInputError::KeyNotInTapLeaf => {
+                write!(f, "Public key not found in any tap leaf script")
+            }
+            SignInputError::InvalidSighashType(error) => {
+                write!(f, "Invalid sighash type: {}", error)
+            }
+            SignInputError::MissingForkId { sighash_type } => write!(
+                f,
+                "Sighash type {:#x} is missing SIGHASH_FORKID",
+                sighash_type
+            ),
+            SignInputError::Sighash(error) => write!(f, "Failed to compute sighash: {}", error),
+            SignInputError::MissingConsensusBranchId => {
+                write!(f, "Missing ZecConsensusBranchId in PSBT")
+            }
+            SignInputError::Psbt(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for SignInputError {}
+crate::impl_wasm_error_code!(SignInputError);
+
+/// Why an input cannot be finalized
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum FinalizeInputError {
+    /// The MuSig2 partial signatures cannot be aggregated
+    Musig2(p2tr_musig2_input::Musig2Error),
+    /// The PSBT has no Zcash consensus branch ID
+    MissingConsensusBranchId,
+    /// The signatures of the input do not satisfy its script
+    Miniscript(String),
+}
+
+impl std::fmt::Display for FinalizeInputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FinalizeInputError::Musig2(error) => write!(f, "{}", error),
+            FinalizeInputError::MissingConsensusBranchId => {
+                write!(f, "Missing ZecConsensusBranchId in PSBT")
+            }
+            FinalizeInputError::Miniscript(error) => write!(f, "{}", error),
         }
     }
 }
 
+impl std::error::Error for FinalizeInputError {}
+crate::impl_wasm_error_code!(FinalizeInputError);
+
 #[derive(Debug, Clone)]
 pub enum BitGoPsbt {
     BitcoinLike(Psbt, Network),
@@ -111,11 +466,12 @@ pub enum BitGoPsbt {
 // Re-export types from submodules for convenience
 pub use crate::fixed_script_wallet::{ScriptId, ScriptIdWithValue};
 pub use psbt_wallet_input::{
-    InputScriptType, ParsedInput, ReplayProtectionOptions, WalletInputOptions,
+    ExternalInputPolicy, ExternalScriptType, InputScriptType, ParsedInput, ReplayProtectionOptions,
+    WalletInputOptions,
 };
-pub use psbt_wallet_output::ParsedOutput;
+pub use psbt_wallet_output::{LabeledParsedOutput, ParsedOutput};
 
-/// Describes a single input for `from_half_signed_legacy_transaction`.
+/// Describes a single input for `from_half_signed_legacy_tx`.
 pub enum HydrationUnspentInput {
     /// A regular wallet input with derivation chain, index, and value.
     Wallet(ScriptIdWithValue),
@@ -134,7 +490,12 @@ pub struct ParsedTransaction {
     pub outputs: Vec<ParsedOutput>,
     pub spend_amount: u64,
     pub miner_fee: u64,
+    /// Virtual size of the unsigned transaction plus the scriptSig of replay protection inputs
     pub virtual_size: u32,
+    /// ZIP-317 fee check; set for transparent-only Zcash transactions
+    pub zip317_fee: Option<crate::zcash::zip317::Zip317Fee>,
+    /// Vault policy fields of the PSBT; `None` if it has none
+    pub vault_policy: Option<VaultPolicy>,
 }
 
 /// Error type for transaction parsing
@@ -147,6 +508,8 @@ pub enum ParseTransactionError {
     },
     /// Input value overflow when adding to total
     InputValueOverflow { index: usize },
+    /// Total input value exceeds the maximum amount of the network
+    InputValueExceedsMaxMoney { index: usize, max_money: u64 },
     /// Failed to parse output
     Output {
         index: usize,
@@ -154,10 +517,14 @@ pub enum ParseTransactionError {
     },
     /// Output value overflow when adding to total
     OutputValueOverflow { index: usize },
+    /// Total output value exceeds the maximum amount of the network
+    OutputValueExceedsMaxMoney { index: usize, max_money: u64 },
     /// Spend amount overflow
     SpendAmountOverflow { index: usize },
     /// Fee calculation error (outputs exceed inputs)
     FeeCalculation,
+    /// The vault policy fields are malformed
+    VaultPolicy(VaultPolicyError),
 }
 
 impl std::fmt::Display for ParseTransactionError {
@@ -169,18 +536,29 @@ impl std::fmt::Display for ParseTransactionError {
             ParseTransactionError::InputValueOverflow { index } => {
                 write!(f, "Input {}: value overflow", index)
             }
+            ParseTransactionError::InputValueExceedsMaxMoney { index, max_money } => write!(
+                f,
+                "Input {}: total input value exceeds the maximum of {} satoshis",
+                index, max_money
+            ),
             ParseTransactionError::Output { index, error } => {
                 write!(f, "Output {}: {}", index, error)
             }
             ParseTransactionError::OutputValueOverflow { index } => {
                 write!(f, "Output {}: value overflow", index)
             }
+            ParseTransactionError::OutputValueExceedsMaxMoney { index, max_money } => write!(
+                f,
+                "Output {}: total output value exceeds the maximum of {} satoshis",
+                index, max_money
+            ),
             ParseTransactionError::SpendAmountOverflow { index } => {
                 write!(f, "Output {}: spend amount overflow", index)
             }
             ParseTransactionError::FeeCalculation => {
                 write!(f, "Fee calculation error: outputs exceed inputs")
             }
+            ParseTransactionError::VaultPolicy(error) => write!(f, "{}", error),
         }
     }
 }
@@ -197,18 +575,46 @@ impl crate::error::WasmErrorCode for ParseTransactionError {
             Self::Output { error, .. } => {
                 format!("ParseTransactionError.{}/{}", variant, error.code())
             }
+            Self::VaultPolicy(error) => {
+                format!("ParseTransactionError.{}/{}", variant, error.code())
+            }
             _ => format!("ParseTransactionError.{}", variant),
         }
     }
 }
 
+/// A non-segwit input without the previous transaction, see
+/// [`BitGoPsbt::missing_non_witness_utxos`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MissingNonWitnessUtxo {
+    pub input_index: usize,
+    /// Txid of the previous transaction
+    pub txid: Txid,
+}
+
+/// Whether a PSBT input spends a segwit output, judged from its scripts and UTXO fields
+fn is_segwit_input(input: &miniscript::bitcoin::psbt::Input) -> bool {
+    input.witness_script.is_some()
+        || input.tap_internal_key.is_some()
+        || !input.tap_scripts.is_empty()
+        || !input.tap_key_origins.is_empty()
+        || input
+            .redeem_script
+            .as_ref()
+            .is_some_and(|script| script.is_witness_program())
+        || input
+            .witness_utxo
+            .as_ref()
+            .is_some_and(|utxo| utxo.script_pubkey.is_witness_program())
+}
+
 /// Get the default sighash type for a network and chain type
 fn get_default_sighash_type(
     network: Network,
     chain: crate::fixed_script_wallet::Chain,
 ) -> miniscript::bitcoin::psbt::PsbtSighashType {
     use crate::fixed_script_wallet::wallet_scripts::OutputScriptType;
-    use miniscript::bitcoin::sighash::{EcdsaSighashType, TapSighashType};
+    use miniscript::bitcoin::sighash::TapSighashType;
 
     // For taproot, always use Default
     if matches!(
@@ -218,7 +624,16 @@ fn get_default_sighash_type(
         return TapSighashType::Default.into();
     }
 
-    // For non-taproot, check if network uses FORKID
+    get_default_ecdsa_sighash_type(network)
+}
+
+/// Get the default sighash type of non-taproot inputs for a network
+pub(super) fn get_default_ecdsa_sighash_type(
+    network: Network,
+) -> miniscript::bitcoin::psbt::PsbtSighashType {
+    use miniscript::bitcoin::sighash::EcdsaSighashType;
+
+    // Check if network uses FORKID
     let uses_forkid = matches!(
         network.mainnet(),
         Network::BitcoinCash | Network::BitcoinGold | Network::BitcoinSV | Network::Ecash
@@ -233,6 +648,33 @@ fn get_default_sighash_type(
     }
 }
 
+/// Check that an input or output value does not exceed the maximum amount of the network
+pub(crate) fn check_value(network: Network, value: u64) -> Result<(), String> {
+    let max_money = network.max_money();
+    if value > max_money {
+        return Err(format!(
+            "Value {} exceeds the maximum of {} satoshis for {}",
+            value, max_money, network
+        ));
+    }
+    Ok(())
+}
+
+/// Returns true if the input has signatures (including MuSig2 partial signatures) or is
+/// finalized
+pub(crate) fn has_signatures(input: &miniscript::bitcoin::psbt::Input) -> bool {
+    let has_musig2_partial_sig = p2tr_musig2_input::Musig2Input::is_musig2_input(input)
+        && p2tr_musig2_input::Musig2Input::from_input(input)
+            .map(|musig2_input| !musig2_input.partial_sigs.is_empty())
+            .unwrap_or(false);
+    !input.partial_sigs.is_empty()
+        || !input.tap_script_sigs.is_empty()
+        || input.tap_key_sig.is_some()
+        || input.final_script_sig.is_some()
+        || input.final_script_witness.is_some()
+        || has_musig2_partial_sig
+}
+
 /// Create BIP32 derivation map for all 3 wallet keys
 pub(crate) fn create_bip32_derivation(
     wallet_keys: &crate::fixed_script_wallet::RootWalletKeys,
@@ -246,15 +688,23 @@ pub(crate) fn create_bip32_derivation(
     ),
 > {
     use crate::fixed_script_wallet::derivation_path;
-    use miniscript::bitcoin::secp256k1::{PublicKey, Secp256k1};
+    use crate::fixed_script_wallet::wallet_scripts::chain_index_path;
+    use miniscript::bitcoin::secp256k1::PublicKey;
     use std::collections::BTreeMap;
 
-    let secp = Secp256k1::new();
+    // Derived via the prefix-derived keys so the derivation cache is shared with script creation
+    let derived_keys = wallet_keys
+        .derive_path(&chain_index_path(chain, index))
+        .expect("valid derivation");
     let mut map = BTreeMap::new();
 
-    for (i, xpub) in wallet_keys.xpubs.iter().enumerate() {
+    for (i, (xpub, derived)) in wallet_keys
+        .xpubs
+        .iter()
+        .zip(derived_keys.iter())
+        .enumerate()
+    {
         let path = derivation_path(&wallet_keys.derivation_prefixes[i], chain, index);
-        let derived = xpub.derive_pub(&secp, &path).expect("valid derivation");
         // Convert CompressedPublicKey to secp256k1::PublicKey
         let pubkey = PublicKey::from_slice(&derived.to_pub().to_bytes()).expect("valid public key");
         map.insert(pubkey, (xpub.fingerprint(), path));
@@ -281,16 +731,19 @@ pub(crate) fn create_tap_bip32_derivation(
     ),
 > {
     use crate::fixed_script_wallet::derivation_path;
-    use miniscript::bitcoin::secp256k1::{PublicKey, Secp256k1};
+    use crate::fixed_script_wallet::wallet_scripts::chain_index_path;
+    use miniscript::bitcoin::secp256k1::PublicKey;
     use std::collections::BTreeMap;
 
-    let secp = Secp256k1::new();
+    let derived_keys = wallet_keys
+        .derive_path(&chain_index_path(chain, index))
+        .expect("valid derivation");
     let mut map = BTreeMap::new();
 
     for &i in key_indices {
         let xpub = &wallet_keys.xpubs[i];
+        let derived = &derived_keys[i];
         let path = derivation_path(&wallet_keys.derivation_prefixes[i], chain, index);
-        let derived = xpub.derive_pub(&secp, &path).expect("valid derivation");
         // Convert CompressedPublicKey to secp256k1::PublicKey, then get x-only
         let pubkey = PublicKey::from_slice(&derived.to_pub().to_bytes()).expect("valid public key");
         let (x_only, _parity) = pubkey.x_only_public_key();
@@ -381,6 +834,56 @@ fn extract_inner_with_fee_policy(
 impl BitGoPsbt {
     /// Deserialize a PSBT from bytes, using network-specific logic
     pub fn deserialize(psbt_bytes: &[u8], network: Network) -> Result<BitGoPsbt, DeserializeError> {
+        Self::deserialize_with_validation(psbt_bytes, network, DeserializeValidation::None)
+    }
+
+    /// Deserialize a PSBT from bytes, optionally validating its scripts and key origins
+    ///
+    /// Use [`DeserializeValidation::Strict`] for PSBTs received from a coordinator that is
+    /// not fully trusted, so that tampered metadata is detected before signing.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "deserialize",
+            skip_all,
+            fields(?network, bytes = psbt_bytes.len(), inputs = tracing::field::Empty)
+        )
+    )]
+    pub fn deserialize_with_validation(
+        psbt_bytes: &[u8],
+        network: Network,
+        validation: DeserializeValidation,
+    ) -> Result<BitGoPsbt, DeserializeError> {
+        let psbt = Self::deserialize_unchecked(psbt_bytes, network)?;
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("inputs", psbt.psbt().inputs.len());
+        if validation == DeserializeValidation::Strict {
+            validate_psbt_scripts(psbt.psbt())?;
+        }
+        Ok(psbt)
+    }
+
+    /// Deserialize a PSBT from bytes and check that it belongs to `wallet_keys`
+    ///
+    /// PSBTs without global xpubs are accepted, since there is nothing to compare.
+    /// Otherwise the global xpubs must be exactly the wallet's xpubs; see
+    /// [`BitGoPsbt::wallet_keys_match`].
+    pub fn deserialize_with_wallet_keys(
+        psbt_bytes: &[u8],
+        network: Network,
+        wallet_keys: &crate::fixed_script_wallet::RootWalletKeys,
+    ) -> Result<BitGoPsbt, DeserializeError> {
+        let psbt = Self::deserialize(psbt_bytes, network)?;
+        if !psbt.psbt().xpub.is_empty() && !psbt.wallet_keys_match(wallet_keys) {
+            return Err(DeserializeError::WalletKeysMismatch);
+        }
+        Ok(psbt)
+    }
+
+    fn deserialize_unchecked(
+        psbt_bytes: &[u8],
+        network: Network,
+    ) -> Result<BitGoPsbt, DeserializeError> {
         match network {
             Network::Zcash | Network::ZcashTestnet => {
                 // Zcash uses overwintered transaction format which is not compatible
@@ -578,7 +1081,7 @@ impl BitGoPsbt {
                         script_id,
                         psbt_wallet_input::WalletInputOptions {
                             sign_path: None,
-                            sequence: Some(tx_in.sequence.0),
+                            sequence: Some(SequenceMode::from_consensus(tx_in.sequence.0)),
                             prev_tx: None,
                         },
                     )
@@ -614,7 +1117,7 @@ impl BitGoPsbt {
                         tx_in.previous_output.vout,
                         *value,
                         ReplayProtectionOptions {
-                            sequence: Some(tx_in.sequence.0),
+                            sequence: Some(SequenceMode::from_consensus(tx_in.sequence.0)),
                             prev_tx: None,
                             sighash_type: None,
                         },
@@ -679,6 +1182,23 @@ impl BitGoPsbt {
         Ok(psbt)
     }
 
+    /// Convert a half-signed legacy transaction back to a PSBT.
+    ///
+    /// Reverse of `extract_half_signed_legacy_tx`: the single signature of each wallet input
+    /// is placed in `partial_sigs` according to its position relative to the `OP_0`
+    /// placeholders in the scriptSig/witness. Fails if a wallet input does not carry
+    /// exactly one signature.
+    pub fn from_half_signed_legacy_tx(
+        tx_bytes: &[u8],
+        unspents: &[HydrationUnspentInput],
+        wallet_keys: &crate::fixed_script_wallet::RootWalletKeys,
+        network: Network,
+    ) -> Result<Self, String> {
+        let psbt = Self::from_network_format(tx_bytes, network, wallet_keys, unspents)?;
+        Self::validate_half_signed(&psbt)?;
+        Ok(psbt)
+    }
+
     /// Assemble a PSBT from a transaction and unspents — no signatures.
     pub fn from_tx_parts(
         network: Network,
@@ -696,6 +1216,119 @@ impl BitGoPsbt {
         Ok(psbt)
     }
 
+    /// Wrap an unsigned transaction created by another library.
+    ///
+    /// The PSBT has the inputs and outputs of the transaction, but no input metadata. Call
+    /// [`BitGoPsbt::enrich_input`] or [`BitGoPsbt::enrich_replay_protection_input`] for each
+    /// input before signing.
+    ///
+    /// Fails for Zcash networks and if an input has a scriptSig or witness.
+    pub fn from_unsigned_tx(
+        tx_bytes: &[u8],
+        network: Network,
+        wallet_keys: &crate::fixed_script_wallet::RootWalletKeys,
+    ) -> Result<Self, String> {
+        use miniscript::bitcoin::psbt::{Input, Output};
+        use miniscript::bitcoin::Transaction;
+
+        if matches!(network, Network::Zcash | Network::ZcashTestnet) {
+            return Err("Zcash transactions are not supported".to_string());
+        }
+        let tx: Transaction = miniscript::bitcoin::consensus::deserialize(tx_bytes)
+            .map_err(|e| format!("Failed to decode transaction: {}", e))?;
+        if let Some(i) = tx
+            .input
+            .iter()
+            .position(|tx_in| !tx_in.script_sig.is_empty() || !tx_in.witness.is_empty())
+        {
+            return Err(format!(
+                "Input {}: transaction is signed, use from_network_format instead",
+                i
+            ));
+        }
+
+        let mut psbt = Self::new(
+            network,
+            wallet_keys,
+            Some(tx.version.0),
+            Some(tx.lock_time.to_consensus_u32()),
+        );
+        let inner = psbt.psbt_mut();
+        for (index, tx_in) in tx.input.into_iter().enumerate() {
+            crate::psbt_ops::insert_input(inner, index, tx_in, Input::default())?;
+        }
+        for (index, tx_out) in tx.output.into_iter().enumerate() {
+            crate::psbt_ops::insert_output(inner, index, tx_out, Output::default())?;
+        }
+        Ok(psbt)
+    }
+
+    /// Set the metadata of a wallet input of a PSBT created with
+    /// [`BitGoPsbt::from_unsigned_tx`], replacing any existing metadata.
+    ///
+    /// The outpoint and sequence are those of the transaction. `prev_tx` must be the
+    /// transaction the input spends; it is only used for p2sh inputs, like in
+    /// [`BitGoPsbt::add_wallet_input`].
+    pub fn enrich_input(
+        &mut self,
+        input_index: usize,
+        wallet_keys: &crate::fixed_script_wallet::RootWalletKeys,
+        script_id: ScriptId,
+        value: u64,
+        sign_path: Option<psbt_wallet_input::SignPath>,
+        prev_tx: Option<&[u8]>,
+    ) -> Result<(), String> {
+        let network = self.network();
+        let psbt_input =
+            Self::wallet_psbt_input(network, value, wallet_keys, script_id, sign_path, prev_tx)
+                .map_err(|e| e.to_string())?;
+        self.replace_input_metadata(input_index, psbt_input)
+    }
+
+    /// Set the metadata of a replay protection input of a PSBT created with
+    /// [`BitGoPsbt::from_unsigned_tx`], replacing any existing metadata.
+    pub fn enrich_replay_protection_input(
+        &mut self,
+        input_index: usize,
+        pubkey: miniscript::bitcoin::CompressedPublicKey,
+        value: u64,
+        prev_tx: Option<&[u8]>,
+    ) -> Result<(), String> {
+        let options = ReplayProtectionOptions {
+            prev_tx,
+            ..Default::default()
+        };
+        let psbt_input =
+            Self::replay_protection_psbt_input(self.network(), pubkey, value, &options)?;
+        self.replace_input_metadata(input_index, psbt_input)
+    }
+
+    fn replace_input_metadata(
+        &mut self,
+        input_index: usize,
+        psbt_input: miniscript::bitcoin::psbt::Input,
+    ) -> Result<(), String> {
+        let psbt = self.psbt_mut();
+        if input_index >= psbt.inputs.len() {
+            return Err(format!(
+                "input index {input_index} out of bounds (have {} inputs)",
+                psbt.inputs.len()
+            ));
+        }
+        let previous_output = psbt.unsigned_tx.input[input_index].previous_output;
+        if let Some(prev_tx) = &psbt_input.non_witness_utxo {
+            let txid = prev_tx.compute_txid();
+            if txid != previous_output.txid {
+                return Err(format!(
+                    "Input {}: previous transaction {} does not match outpoint {}",
+                    input_index, txid, previous_output
+                ));
+            }
+        }
+        psbt.inputs[input_index] = psbt_input;
+        Ok(())
+    }
+
     /// Insert signatures from a parsed `FixedScriptInput` into this PSBT at `index`.
     pub(crate) fn add_input_signatures(
         &mut self,
@@ -718,7 +1351,7 @@ impl BitGoPsbt {
     /// * `vout` - The output index being spent
     /// * `value` - The value in satoshis of the output being spent
     /// * `script` - The output script (scriptPubKey) of the output being spent
-    /// * `sequence` - Optional sequence number (default: 0xFFFFFFFE for RBF)
+    /// * `sequence` - Optional sequence mode (default: [`SequenceMode::FinalNoRbf`])
     ///
     /// # Returns
     /// The index of the newly added input
@@ -730,15 +1363,16 @@ impl BitGoPsbt {
         vout: u32,
         value: u64,
         script: miniscript::bitcoin::ScriptBuf,
-        sequence: Option<u32>,
+        sequence: Option<SequenceMode>,
         prev_tx: Option<miniscript::bitcoin::Transaction>,
     ) -> Result<usize, String> {
-        use miniscript::bitcoin::{transaction::Sequence, Amount, OutPoint, TxIn, TxOut};
+        use miniscript::bitcoin::{Amount, OutPoint, TxIn, TxOut};
 
+        check_value(self.network(), value)?;
         let tx_in = TxIn {
             previous_output: OutPoint { txid, vout },
             script_sig: miniscript::bitcoin::ScriptBuf::new(),
-            sequence: Sequence(sequence.unwrap_or(0xFFFFFFFE)),
+            sequence: sequence.unwrap_or_default().to_sequence(),
             witness: miniscript::bitcoin::Witness::default(),
         };
         let psbt_input = miniscript::bitcoin::psbt::Input {
@@ -759,12 +1393,11 @@ impl BitGoPsbt {
         vout: u32,
         value: u64,
         script: miniscript::bitcoin::ScriptBuf,
-        sequence: Option<u32>,
+        sequence: Option<SequenceMode>,
         prev_tx: Option<miniscript::bitcoin::Transaction>,
-    ) -> usize {
+    ) -> Result<usize, String> {
         let index = self.psbt().inputs.len();
         self.add_input_at_index(index, txid, vout, value, script, sequence, prev_tx)
-            .expect("insert at len should never fail")
     }
 
     /// Add a replay protection input (p2shP2pk) to the PSBT
@@ -792,24 +1425,36 @@ impl BitGoPsbt {
         value: u64,
         options: ReplayProtectionOptions,
     ) -> Result<(), String> {
+        use miniscript::bitcoin::{OutPoint, TxIn};
+
+        let psbt_input = Self::replay_protection_psbt_input(network, pubkey, value, &options)?;
+        let tx_in = TxIn {
+            previous_output: OutPoint { txid, vout },
+            script_sig: miniscript::bitcoin::ScriptBuf::new(),
+            sequence: options.sequence.unwrap_or_default().to_sequence(),
+            witness: miniscript::bitcoin::Witness::default(),
+        };
+
+        crate::psbt_ops::insert_input(psbt, index, tx_in, psbt_input).map(|_| ())
+    }
+
+    /// PSBT input metadata (UTXO, redeem script, sighash type) of a replay protection input
+    fn replay_protection_psbt_input(
+        network: Network,
+        pubkey: miniscript::bitcoin::CompressedPublicKey,
+        value: u64,
+        options: &ReplayProtectionOptions,
+    ) -> Result<miniscript::bitcoin::psbt::Input, String> {
         use crate::fixed_script_wallet::wallet_scripts::ScriptP2shP2pk;
         use miniscript::bitcoin::consensus::Decodable;
         use miniscript::bitcoin::psbt::{Input, PsbtSighashType};
-        use miniscript::bitcoin::{
-            transaction::Sequence, Amount, OutPoint, Transaction, TxIn, TxOut,
-        };
+        use miniscript::bitcoin::{Amount, Transaction, TxOut};
 
+        check_value(network, value)?;
         let script = ScriptP2shP2pk::new(pubkey);
         let output_script = script.output_script();
         let redeem_script = script.redeem_script;
 
-        let tx_in = TxIn {
-            previous_output: OutPoint { txid, vout },
-            script_sig: miniscript::bitcoin::ScriptBuf::new(),
-            sequence: Sequence(options.sequence.unwrap_or(0xFFFFFFFE)),
-            witness: miniscript::bitcoin::Witness::default(),
-        };
-
         let sighash_type = options
             .sighash_type
             .unwrap_or_else(|| match network.mainnet() {
@@ -837,7 +1482,7 @@ impl BitGoPsbt {
             });
         }
 
-        crate::psbt_ops::insert_input(psbt, index, tx_in, psbt_input).map(|_| ())
+        Ok(psbt_input)
     }
 
     pub fn add_replay_protection_input_at_index(
@@ -870,10 +1515,9 @@ impl BitGoPsbt {
         vout: u32,
         value: u64,
         options: ReplayProtectionOptions,
-    ) -> usize {
+    ) -> Result<usize, String> {
         let index = self.psbt().inputs.len();
         self.add_replay_protection_input_at_index(index, pubkey, txid, vout, value, options)
-            .expect("insert at len should never fail")
     }
 
     /// Add an output to the PSBT
@@ -892,6 +1536,7 @@ impl BitGoPsbt {
     ) -> Result<usize, String> {
         use miniscript::bitcoin::{Amount, TxOut};
 
+        check_value(self.network(), value)?;
         let tx_out = TxOut {
             value: Amount::from_sat(value),
             script_pubkey: script,
@@ -905,10 +1550,13 @@ impl BitGoPsbt {
         )
     }
 
-    pub fn add_output(&mut self, script: miniscript::bitcoin::ScriptBuf, value: u64) -> usize {
+    pub fn add_output(
+        &mut self,
+        script: miniscript::bitcoin::ScriptBuf,
+        value: u64,
+    ) -> Result<usize, String> {
         let index = self.psbt().outputs.len();
         self.add_output_at_index(index, script, value)
-            .expect("insert at len should never fail")
     }
 
     pub fn add_output_with_address_at_index(
@@ -956,49 +1604,80 @@ impl BitGoPsbt {
         wallet_keys: &crate::fixed_script_wallet::RootWalletKeys,
         script_id: ScriptId,
         options: WalletInputOptions,
-    ) -> Result<(), String> {
-        use crate::fixed_script_wallet::to_pub_triple;
-        use crate::fixed_script_wallet::wallet_scripts::{
-            chain_index_path, OutputScriptType, WalletScripts,
-        };
-        use crate::fixed_script_wallet::Chain;
-        use miniscript::bitcoin::psbt::Input;
-        use miniscript::bitcoin::taproot::{LeafVersion, TapLeafHash};
-        use miniscript::bitcoin::{transaction::Sequence, Amount, OutPoint, TxIn, TxOut};
-        use p2tr_musig2_input::Musig2Participants;
-        use std::convert::TryFrom;
-
-        let chain = script_id.chain;
-        let derivation_index = script_id.index;
-
-        let chain_enum = Chain::try_from(chain)?;
+    ) -> Result<(), BitGoPsbtError> {
+        use miniscript::bitcoin::{OutPoint, TxIn};
 
-        let derived_keys = wallet_keys
+        let psbt_input = Self::wallet_psbt_input(
+            network,
+            value,
+            wallet_keys,
+            script_id,
+            options.sign_path,
+            options.prev_tx,
+        )?;
+        let tx_in = TxIn {
+            previous_output: OutPoint { txid, vout },
+            script_sig: miniscript::bitcoin::ScriptBuf::new(),
+            sequence: options.sequence.unwrap_or_default().to_sequence(),
+            witness: miniscript::bitcoin::Witness::default(),
+        };
+
+        let input_count = psbt.inputs.len();
+        crate::psbt_ops::insert_input(psbt, index, tx_in, psbt_input)
+            .map(|_| ())
+            .map_err(|_| BitGoPsbtError::InputIndexOutOfBounds { index, input_count })
+    }
+
+    /// PSBT input metadata (UTXO, scripts, derivations, sighash type) of a wallet input
+    fn wallet_psbt_input(
+        network: Network,
+        value: u64,
+        wallet_keys: &crate::fixed_script_wallet::RootWalletKeys,
+        script_id: ScriptId,
+        sign_path: Option<psbt_wallet_input::SignPath>,
+        prev_tx: Option<&[u8]>,
+    ) -> Result<miniscript::bitcoin::psbt::Input, BitGoPsbtError> {
+        use crate::fixed_script_wallet::to_pub_triple;
+        use crate::fixed_script_wallet::wallet_scripts::{
+            chain_index_path, OutputScriptType, WalletScripts,
+        };
+        use crate::fixed_script_wallet::Chain;
+        use miniscript::bitcoin::psbt::Input;
+        use miniscript::bitcoin::{Amount, TxOut};
+        use std::convert::TryFrom;
+
+        let max_money = network.max_money();
+        if value > max_money {
+            return Err(BitGoPsbtError::ValueExceedsMaxMoney {
+                value,
+                max_money,
+                network,
+            });
+        }
+        let chain = script_id.chain;
+        let derivation_index = script_id.index;
+
+        let chain_enum = Chain::try_from(chain).map_err(|_| BitGoPsbtError::InvalidChain(chain))?;
+
+        let derived_keys = wallet_keys
             .derive_path(&chain_index_path(chain, derivation_index))
-            .map_err(|e| format!("Failed to derive keys: {}", e))?;
+            .map_err(|e| WalletInputError::Derivation(e.to_string()))?;
         let pub_triple = to_pub_triple(&derived_keys);
 
         let script_support = network.output_script_support();
         let scripts = WalletScripts::new(&pub_triple, chain_enum.script_type, &script_support)
-            .map_err(|e| format!("Failed to create wallet scripts: {}", e))?;
+            .map_err(|e| WalletInputError::Scripts(e.to_string()))?;
 
         let output_script = scripts.output_script();
 
-        let tx_in = TxIn {
-            previous_output: OutPoint { txid, vout },
-            script_sig: miniscript::bitcoin::ScriptBuf::new(),
-            sequence: Sequence(options.sequence.unwrap_or(0xFFFFFFFE)),
-            witness: miniscript::bitcoin::Witness::default(),
-        };
-
         let mut psbt_input = Input::default();
 
         let is_segwit = chain_enum.script_type != OutputScriptType::P2sh;
 
-        if let (false, Some(tx_bytes)) = (is_segwit, options.prev_tx) {
+        if let (false, Some(tx_bytes)) = (is_segwit, prev_tx) {
             psbt_input.non_witness_utxo = Some(
                 miniscript::bitcoin::consensus::deserialize(tx_bytes)
-                    .map_err(|e| format!("Failed to deserialize previous transaction: {}", e))?,
+                    .map_err(WalletInputError::PrevTx)?,
             );
         } else {
             psbt_input.witness_utxo = Some(TxOut {
@@ -1028,77 +1707,24 @@ impl BitGoPsbt {
                 psbt_input.witness_script = Some(script.witness_script.clone());
             }
             WalletScripts::P2mr(_) => {
-                return Err("P2MR PSBT input signing is not yet supported".to_string());
+                return Err(WalletInputError::P2mrNotSupported.into());
             }
             WalletScripts::P2trLegacy(script) | WalletScripts::P2trMusig2(script) => {
-                let sign_path = options.sign_path.ok_or_else(|| {
-                    "sign_path is required for p2tr/p2trMusig2 inputs".to_string()
-                })?;
-                let signer_idx = sign_path.signer.index();
-                let cosigner_idx = sign_path.cosigner.index();
-
-                let is_musig2 = matches!(scripts, WalletScripts::P2trMusig2(_));
-                let is_backup_flow = sign_path.signer.is_backup() || sign_path.cosigner.is_backup();
-
-                if !is_musig2 || is_backup_flow {
-                    let signer_keys = [pub_triple[signer_idx], pub_triple[cosigner_idx]];
-                    let leaf_script =
-                        crate::fixed_script_wallet::wallet_scripts::build_p2tr_ns_script(
-                            &signer_keys,
-                        );
-                    let leaf_hash = TapLeafHash::from_script(&leaf_script, LeafVersion::TapScript);
-
-                    let control_block = script
-                        .spend_info
-                        .control_block(&(leaf_script.clone(), LeafVersion::TapScript))
-                        .ok_or_else(|| {
-                            "Could not find control block for leaf script".to_string()
-                        })?;
-
-                    psbt_input.tap_scripts.insert(
-                        control_block.clone(),
-                        (leaf_script.clone(), LeafVersion::TapScript),
-                    );
-
-                    psbt_input.tap_key_origins = create_tap_bip32_derivation(
-                        wallet_keys,
-                        chain,
-                        derivation_index,
-                        &[signer_idx, cosigner_idx],
-                        Some(leaf_hash),
-                    );
-                } else {
-                    let internal_key = script.spend_info.internal_key();
-                    let merkle_root = script.spend_info.merkle_root();
-
-                    psbt_input.tap_internal_key = Some(internal_key);
-                    psbt_input.tap_merkle_root = merkle_root;
-
-                    psbt_input.tap_key_origins = create_tap_bip32_derivation(
-                        wallet_keys,
-                        chain,
-                        derivation_index,
-                        &[signer_idx, cosigner_idx],
-                        None,
-                    );
-
-                    let user_key = pub_triple[0];
-                    let bitgo_key = pub_triple[2];
-
-                    let tap_output_key = script.spend_info.output_key().to_x_only_public_key();
-                    let musig2_participants = Musig2Participants {
-                        tap_output_key,
-                        tap_internal_key: internal_key,
-                        participant_pub_keys: [user_key, bitgo_key],
-                    };
-
-                    let (key, value) = musig2_participants.to_key_value().to_key_value();
-                    psbt_input.proprietary.insert(key, value);
-                }
+                let sign_path = sign_path.ok_or(WalletInputError::MissingSignPath)?;
+                sign_path::set_taproot_sign_path_fields(
+                    &mut psbt_input,
+                    wallet_keys,
+                    script_id,
+                    script,
+                    matches!(scripts, WalletScripts::P2trMusig2(_)),
+                    &pub_triple,
+                    sign_path,
+                )
+                .map_err(WalletInputError::SignPath)?;
             }
         }
 
-        crate::psbt_ops::insert_input(psbt, index, tx_in, psbt_input).map(|_| ())
+        Ok(psbt_input)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -1111,8 +1737,12 @@ impl BitGoPsbt {
         wallet_keys: &crate::fixed_script_wallet::RootWalletKeys,
         script_id: ScriptId,
         options: WalletInputOptions,
-    ) -> Result<usize, String> {
+    ) -> Result<usize, BitGoPsbtError> {
         let network = self.network();
+        let input_count = self.psbt().inputs.len();
+        if index > input_count {
+            return Err(BitGoPsbtError::InputIndexOutOfBounds { index, input_count });
+        }
         Self::add_wallet_input_to_psbt(
             self.psbt_mut(),
             index,
@@ -1135,7 +1765,7 @@ impl BitGoPsbt {
         wallet_keys: &crate::fixed_script_wallet::RootWalletKeys,
         script_id: ScriptId,
         options: WalletInputOptions,
-    ) -> Result<usize, String> {
+    ) -> Result<usize, BitGoPsbtError> {
         let index = self.psbt().inputs.len();
         self.add_wallet_input_at_index(index, txid, vout, value, wallet_keys, script_id, options)
     }
@@ -1173,6 +1803,7 @@ impl BitGoPsbt {
         use std::convert::TryFrom;
 
         let network = self.network();
+        check_value(network, value)?;
         let psbt = self.psbt_mut();
 
         let chain_enum = Chain::try_from(chain)?;
@@ -1241,6 +1872,18 @@ impl BitGoPsbt {
                     &pub_triple,
                     is_musig2,
                 );
+
+                if is_musig2 {
+                    // Same participant record as on key path inputs, so that the receiver
+                    // can check the aggregate internal key without deriving the wallet keys
+                    let musig2_participants = p2tr_musig2_input::Musig2Participants {
+                        tap_output_key: script.spend_info.output_key().to_x_only_public_key(),
+                        tap_internal_key: internal_key,
+                        participant_pub_keys: [pub_triple[0], pub_triple[2]],
+                    };
+                    let (key, value) = musig2_participants.to_key_value().to_key_value();
+                    psbt_output.proprietary.insert(key, value);
+                }
             }
         }
 
@@ -1266,6 +1909,55 @@ impl BitGoPsbt {
         crate::psbt_ops::PsbtAccess::remove_output(self, index)
     }
 
+    /// Sort inputs and outputs following BIP-69
+    ///
+    /// All per-input and per-output PSBT fields move with their transaction input/output.
+    /// Since reordering changes the sighash, this fails if any input already carries
+    /// signatures (including MuSig2 partial signatures) or is finalized, or has MuSig2
+    /// nonces, which are generated for the sighash of the unsorted transaction.
+    ///
+    /// # Returns
+    /// The permutation mapping old input/output indices to new ones
+    pub fn sort_bip69(&mut self) -> Result<crate::psbt_ops::Bip69Permutation, String> {
+        self.check_reorderable()?;
+        Ok(crate::psbt_ops::sort_bip69(self.psbt_mut()))
+    }
+
+    /// Shuffle the outputs in an order derived from `seed`
+    ///
+    /// Randomizes the position of change outputs while staying reproducible: replaying the
+    /// shuffle with the same seed on the same outputs yields the same transaction. The seed
+    /// should come from a secure random source. Like `sort_bip69`, this fails if any input
+    /// is already signed or has MuSig2 nonces.
+    ///
+    /// # Returns
+    /// The mapping `old_index -> new_index` of the outputs
+    pub fn shuffle_outputs(&mut self, seed: &[u8; 32]) -> Result<Vec<usize>, String> {
+        self.check_reorderable()?;
+        Ok(crate::psbt_ops::shuffle_outputs(self.psbt_mut(), seed))
+    }
+
+    fn check_reorderable(&self) -> Result<(), String> {
+        for (index, input) in self.psbt().inputs.iter().enumerate() {
+            if has_signatures(input) {
+                return Err(format!(
+                    "Cannot reorder signed PSBT: input {} has signatures",
+                    index
+                ));
+            }
+            if find_kv(ProprietaryKeySubtype::Musig2PubNonce, &input.proprietary)
+                .next()
+                .is_some()
+            {
+                return Err(format!(
+                    "Cannot reorder PSBT: input {} has MuSig2 nonces for the current order",
+                    index
+                ));
+            }
+        }
+        Ok(())
+    }
+
     pub fn network(&self) -> Network {
         match self {
             BitGoPsbt::BitcoinLike(_, network) => *network,
@@ -1280,15 +1972,21 @@ impl BitGoPsbt {
     /// source PSBT to this PSBT. This is useful for merging PSBTs during the nonce exchange
     /// and signature collection phases.
     ///
+    /// Nonces that do not match the destination input (see [`stale_nonces`]) are not copied,
+    /// and stale nonces already present in this PSBT are removed.
+    ///
     /// # Arguments
     /// * `source_psbt` - The source PSBT containing data to merge
     ///
     /// # Returns
-    /// Ok(()) if data was successfully merged
+    /// The stale nonces that were skipped or removed
     ///
     /// # Errors
     /// Returns error if networks don't match
-    pub fn combine_musig2_nonces(&mut self, source_psbt: &BitGoPsbt) -> Result<(), String> {
+    pub fn combine_musig2_nonces(
+        &mut self,
+        source_psbt: &BitGoPsbt,
+    ) -> Result<Vec<StaleMusig2Nonce>, String> {
         // Check network match
         if self.network() != source_psbt.network() {
             return Err(format!(
@@ -1299,19 +1997,26 @@ impl BitGoPsbt {
         }
 
         let source = source_psbt.psbt();
-        let dest = self.psbt_mut();
+        let dest_input_count = self.psbt().inputs.len();
 
         // Check that both PSBTs have the same number of inputs
-        if source.inputs.len() != dest.inputs.len() {
+        if source.inputs.len() != dest_input_count {
             return Err(format!(
                 "PSBT input count mismatch: source has {} inputs, destination has {}",
                 source.inputs.len(),
-                dest.inputs.len()
+                dest_input_count
             ));
         }
 
+        let mut stale = self
+            .purge_stale_musig2_nonces()
+            .map_err(|e| format!("Failed to check MuSig2 nonces: {}", e))?;
+        let dest = self.psbt_mut();
+
         // Copy MuSig2 nonces and partial signatures (proprietary key-values with BITGO identifier)
-        for (source_input, dest_input) in source.inputs.iter().zip(dest.inputs.iter_mut()) {
+        for (input_index, (source_input, dest_input)) in
+            source.inputs.iter().zip(dest.inputs.iter_mut()).enumerate()
+        {
             // Only process if the input is a MuSig2 input
             if !p2tr_musig2_input::Musig2Input::is_musig2_input(source_input) {
                 continue;
@@ -1321,8 +2026,21 @@ impl BitGoPsbt {
             let nonces = p2tr_musig2_input::parse_musig2_nonces(source_input)
                 .map_err(|e| format!("Failed to parse MuSig2 nonces from source: {}", e))?;
 
-            // Copy each nonce to the destination input
+            // Copy each nonce that matches the destination input
             for nonce in nonces {
+                let reason = stale_nonces::stale_nonce_reason(dest_input, &nonce)
+                    .map_err(|e| format!("Failed to check MuSig2 nonces: {}", e))?;
+                if let Some(reason) = reason {
+                    let entry = StaleMusig2Nonce {
+                        input_index,
+                        nonce,
+                        reason,
+                    };
+                    if !stale.contains(&entry) {
+                        stale.push(entry);
+                    }
+                    continue;
+                }
                 let (key, value) = nonce.to_key_value().to_key_value();
                 dest_input.proprietary.insert(key, value);
             }
@@ -1336,7 +2054,7 @@ impl BitGoPsbt {
             }
         }
 
-        Ok(())
+        Ok(stale)
     }
 
     /// Merge all input fields from a raw PSBT (given as bytes) into this PSBT.
@@ -1346,6 +2064,7 @@ impl BitGoPsbt {
     /// For all other coins the bitcoin PSBT deserializer is used.
     ///
     /// Copies per input: partial_sigs, tap_key_sig, tap_script_sigs, proprietary.
+    /// Copies per output: proprietary.
     pub fn combine_inputs(&mut self, other_bytes: &[u8]) -> Result<(), String> {
         let raw: Psbt = match self {
             BitGoPsbt::Zcash(_, network) => {
@@ -1366,6 +2085,13 @@ impl BitGoPsbt {
                 dest.inputs.len()
             ));
         }
+        if raw.outputs.len() != dest.outputs.len() {
+            return Err(format!(
+                "PSBT output count mismatch: source has {}, destination has {}",
+                raw.outputs.len(),
+                dest.outputs.len()
+            ));
+        }
 
         for (src_in, dest_in) in raw.inputs.iter().zip(dest.inputs.iter_mut()) {
             for (k, v) in &src_in.partial_sigs {
@@ -1382,6 +2108,12 @@ impl BitGoPsbt {
             }
         }
 
+        for (src_out, dest_out) in raw.outputs.iter().zip(dest.outputs.iter_mut()) {
+            for (k, v) in &src_out.proprietary {
+                dest_out.proprietary.insert(k.clone(), v.clone());
+            }
+        }
+
         Ok(())
     }
 
@@ -1425,6 +2157,10 @@ impl BitGoPsbt {
     /// * `Ok(Vec<u8>)` - The serialized transaction bytes
     /// * `Err(String)` - If transaction extraction fails (including absurd-fee
     ///   rejection when a [`Limited`][ExtractFeePolicy::Limited] policy is enforced)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "extract", skip_all, fields(inputs = self.psbt().inputs.len()))
+    )]
     pub fn extract_tx_with_fee_policy(self, policy: ExtractFeePolicy) -> Result<Vec<u8>, String> {
         use miniscript::bitcoin::consensus::serialize;
 
@@ -1454,6 +2190,10 @@ impl BitGoPsbt {
     /// # Returns
     /// * `Ok(Transaction)` - The extracted transaction
     /// * `Err(String)` - If not BitcoinLike or extraction fails
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "extract", skip_all, fields(inputs = self.psbt().inputs.len()))
+    )]
     pub fn extract_bitcoin_tx_with_fee_policy(
         self,
         policy: ExtractFeePolicy,
@@ -1479,6 +2219,10 @@ impl BitGoPsbt {
     /// # Returns
     /// * `Ok(DashTransactionParts)` - The extracted transaction parts
     /// * `Err(String)` - If not Dash or extraction fails
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "extract", skip_all, fields(inputs = self.psbt().inputs.len()))
+    )]
     pub fn extract_dash_tx_with_fee_policy(
         self,
         policy: ExtractFeePolicy,
@@ -1512,6 +2256,10 @@ impl BitGoPsbt {
     /// # Returns
     /// * `Ok(ZcashTransactionParts)` - The extracted transaction parts
     /// * `Err(String)` - If not Zcash or extraction fails
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "extract", skip_all, fields(inputs = self.psbt().inputs.len()))
+    )]
     pub fn extract_zcash_tx_with_fee_policy(
         self,
         policy: ExtractFeePolicy,
@@ -1547,16 +2295,25 @@ impl BitGoPsbt {
     /// - Returns error if any input is not a p2ms type (Taproot, replay protection, etc.)
     /// - Returns error if any input has 0 or more than 1 partial signature
     pub fn extract_half_signed_legacy_tx(&self) -> Result<Vec<u8>, String> {
+        self.extract_half_signed_legacy_tx_with_layout(HalfSignedLayout::Positional)
+    }
+
+    /// Like [`BitGoPsbt::extract_half_signed_legacy_tx`], with the placement of the missing
+    /// signatures expected by the cosigner (see [`HalfSignedLayout`])
+    pub fn extract_half_signed_legacy_tx_with_layout(
+        &self,
+        layout: HalfSignedLayout,
+    ) -> Result<Vec<u8>, String> {
         use miniscript::bitcoin::consensus::serialize;
 
         match self {
             BitGoPsbt::BitcoinLike(_, _) | BitGoPsbt::Dash(_, _) => {
-                let tx = legacy_txformat::build_half_signed_legacy_tx(self.psbt())
+                let tx = legacy_txformat::build_half_signed_legacy_tx(self.psbt(), layout)
                     .map_err(|e| e.to_string())?;
                 Ok(serialize(&tx))
             }
             BitGoPsbt::Zcash(zcash_psbt, _) => {
-                let tx = legacy_txformat::build_half_signed_legacy_tx(&zcash_psbt.psbt)
+                let tx = legacy_txformat::build_half_signed_legacy_tx(&zcash_psbt.psbt, layout)
                     .map_err(|e| e.to_string())?;
 
                 // Serialize with Zcash-specific fields
@@ -1636,11 +2393,43 @@ impl BitGoPsbt {
         )
     }
 
+    /// Returns true if the global xpubs are exactly the xpubs of `wallet_keys`
+    ///
+    /// The global xpub map is unordered, so the comparison ignores key order. Returns
+    /// false if the PSBT has no global xpubs.
+    pub fn wallet_keys_match(
+        &self,
+        wallet_keys: &crate::fixed_script_wallet::RootWalletKeys,
+    ) -> bool {
+        let global_xpubs = &self.psbt().xpub;
+        global_xpubs.len() == wallet_keys.xpubs.len()
+            && wallet_keys
+                .xpubs
+                .iter()
+                .all(|xpub| global_xpubs.contains_key(xpub))
+    }
+
     pub fn finalize_input<C: secp256k1::Verification>(
         &mut self,
         secp: &secp256k1::Secp256k1<C>,
         input_index: usize,
-    ) -> Result<(), String> {
+    ) -> Result<(), BitGoPsbtError> {
+        let input_count = self.psbt().inputs.len();
+        if input_index >= input_count {
+            return Err(BitGoPsbtError::InputIndexOutOfBounds {
+                index: input_index,
+                input_count,
+            });
+        }
+        self.finalize_input_inner(secp, input_index)
+            .map_err(|error| BitGoPsbtError::Finalize { input_index, error })
+    }
+
+    fn finalize_input_inner<C: secp256k1::Verification>(
+        &mut self,
+        secp: &secp256k1::Secp256k1<C>,
+        input_index: usize,
+    ) -> Result<(), FinalizeInputError> {
         use miniscript::psbt::PsbtExt;
 
         match self {
@@ -1648,8 +2437,9 @@ impl BitGoPsbt {
                 // Use custom bitgo p2trMusig2 input finalization for MuSig2 inputs
                 if p2tr_musig2_input::Musig2Input::is_musig2_input(&psbt.inputs[input_index]) {
                     let mut ctx = p2tr_musig2_input::Musig2Context::new(psbt, input_index)
-                        .map_err(|e| e.to_string())?;
-                    ctx.finalize_input(secp).map_err(|e| e.to_string())?;
+                        .map_err(FinalizeInputError::Musig2)?;
+                    ctx.finalize_input(secp)
+                        .map_err(FinalizeInputError::Musig2)?;
                     return Ok(());
                 }
 
@@ -1657,7 +2447,7 @@ impl BitGoPsbt {
 
                 // Finalize with fork_id support for FORKID networks
                 psbt.finalize_inp_mut_with_fork_id(secp, input_index, fork_id)
-                    .map_err(|e| e.to_string())?;
+                    .map_err(|e| FinalizeInputError::Miniscript(e.to_string()))?;
                 Ok(())
             }
             BitGoPsbt::Dash(ref mut dash_psbt, network) => {
@@ -1666,8 +2456,9 @@ impl BitGoPsbt {
                 // Use custom bitgo p2trMusig2 input finalization for MuSig2 inputs
                 if p2tr_musig2_input::Musig2Input::is_musig2_input(&psbt.inputs[input_index]) {
                     let mut ctx = p2tr_musig2_input::Musig2Context::new(psbt, input_index)
-                        .map_err(|e| e.to_string())?;
-                    ctx.finalize_input(secp).map_err(|e| e.to_string())?;
+                        .map_err(FinalizeInputError::Musig2)?;
+                    ctx.finalize_input(secp)
+                        .map_err(FinalizeInputError::Musig2)?;
                     return Ok(());
                 }
 
@@ -1675,7 +2466,7 @@ impl BitGoPsbt {
 
                 // Finalize with fork_id support for FORKID networks
                 psbt.finalize_inp_mut_with_fork_id(secp, input_index, fork_id)
-                    .map_err(|e| e.to_string())?;
+                    .map_err(|e| FinalizeInputError::Miniscript(e.to_string()))?;
                 Ok(())
             }
             BitGoPsbt::Zcash(ref mut zcash_psbt, _network) => {
@@ -1683,7 +2474,7 @@ impl BitGoPsbt {
 
                 // Extract consensus branch ID from PSBT proprietary map
                 let branch_id = propkv::get_zec_consensus_branch_id(&zcash_psbt.psbt)
-                    .ok_or_else(|| "Missing ZecConsensusBranchId in PSBT".to_string())?;
+                    .ok_or(FinalizeInputError::MissingConsensusBranchId)?;
 
                 // Extract version group ID and expiry height from ZcashPsbt
                 let version_group_id = zcash_psbt
@@ -1701,7 +2492,7 @@ impl BitGoPsbt {
                         version_group_id,
                         expiry_height,
                     )
-                    .map_err(|e| e.to_string())?;
+                    .map_err(|e| FinalizeInputError::Miniscript(e.to_string()))?;
                 Ok(())
             }
         }
@@ -1717,6 +2508,10 @@ impl BitGoPsbt {
     /// # Note
     /// This method will attempt to finalize ALL inputs, collecting errors for any that fail.
     /// It does not stop at the first error.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "finalize", skip_all, fields(inputs = self.psbt().inputs.len()))
+    )]
     pub fn finalize_mut<C: secp256k1::Verification>(
         &mut self,
         secp: &secp256k1::Secp256k1<C>,
@@ -1782,6 +2577,34 @@ impl BitGoPsbt {
         }
     }
 
+    /// Inputs that need the full previous transaction but have no `non_witness_utxo`
+    ///
+    /// Signatures of non-segwit inputs do not commit to the amount of the spent output, so a
+    /// full PSBT carries the previous transaction for them. An input counts as segwit if it
+    /// has a witness script, taproot fields, a witness program as redeem script or a witness
+    /// program as `witness_utxo` script.
+    ///
+    /// Zcash PSBTs never carry previous transactions: their sighash commits to input amounts.
+    ///
+    /// # Returns
+    /// The input index and the txid of the previous transaction to fetch for each such input
+    pub fn missing_non_witness_utxos(&self) -> Vec<MissingNonWitnessUtxo> {
+        if matches!(self, BitGoPsbt::Zcash(..)) {
+            return Vec::new();
+        }
+        let psbt = self.psbt();
+        psbt.inputs
+            .iter()
+            .zip(psbt.unsigned_tx.input.iter())
+            .enumerate()
+            .filter(|(_, (input, _))| input.non_witness_utxo.is_none() && !is_segwit_input(input))
+            .map(|(input_index, (_, tx_in))| MissingNonWitnessUtxo {
+                input_index,
+                txid: tx_in.previous_output.txid,
+            })
+            .collect()
+    }
+
     /// Add a PayGo attestation to a PSBT output
     ///
     /// # Arguments
@@ -1904,6 +2727,14 @@ impl BitGoPsbt {
     ///
     /// # Returns
     /// Ok(()) if the signature was successfully created and added to the PSBT
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "sign",
+            skip_all,
+            fields(inputs = self.psbt().inputs.len(), input_index = input_index)
+        )
+    )]
     pub fn sign_with_first_round(
         &mut self,
         input_index: usize,
@@ -1930,6 +2761,14 @@ impl BitGoPsbt {
     ///
     /// # Returns
     /// Ok(()) if the signature was successfully created and added to the PSBT
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "sign",
+            skip_all,
+            fields(inputs = self.psbt().inputs.len(), input_index = input_index)
+        )
+    )]
     pub fn sign_with_first_round_and_cache<T: std::borrow::Borrow<crate::bitcoin::Transaction>>(
         &mut self,
         input_index: usize,
@@ -1948,6 +2787,9 @@ impl BitGoPsbt {
     /// This method signs a specific input using the provided private key. It automatically
     /// detects the input type and uses the appropriate signing method:
     /// - Replay protection inputs (P2SH-P2PK): Signs with legacy P2SH sighash
+    /// - Taproot inputs with leaf scripts: Signs the key path if the key is the internal key,
+    ///   otherwise every leaf script containing the key's x-only pubkey (no bip32 derivation
+    ///   required)
     /// - Regular inputs: Uses standard PSBT signing
     /// - MuSig2 inputs: Returns error (requires FirstRound state, use sign_with_first_round)
     ///
@@ -1957,12 +2799,20 @@ impl BitGoPsbt {
     ///
     /// # Returns
     /// - `Ok(())` if signing was successful
-    /// - `Err(String)` if signing fails or input type is not supported
+    /// - `Err(BitGoPsbtError)` if signing fails or input type is not supported
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "sign",
+            skip_all,
+            fields(inputs = self.psbt().inputs.len(), input_index = input_index)
+        )
+    )]
     pub fn sign_with_privkey(
         &mut self,
         input_index: usize,
         privkey: &secp256k1::SecretKey,
-    ) -> Result<(), String> {
+    ) -> Result<(), BitGoPsbtError> {
         use miniscript::bitcoin::PublicKey;
 
         // Get network before mutable borrow
@@ -1973,28 +2823,44 @@ impl BitGoPsbt {
 
         // Check bounds
         if input_index >= psbt.inputs.len() {
-            return Err(format!(
-                "Input index {} out of bounds (total inputs: {})",
-                input_index,
-                psbt.inputs.len()
-            ));
+            return Err(BitGoPsbtError::InputIndexOutOfBounds {
+                index: input_index,
+                input_count: psbt.inputs.len(),
+            });
         }
 
         // Check if this is a MuSig2 input
         if p2tr_musig2_input::Musig2Input::is_musig2_input(&psbt.inputs[input_index]) {
-            return Err(
-                "MuSig2 inputs cannot be signed with raw privkey. Use sign_with_first_round instead."
-                    .to_string(),
-            );
+            return Err(BitGoPsbtError::Musig2RequiresNonces { input_index });
         }
 
+        let signing_error = |error| BitGoPsbtError::Signing { input_index, error };
         let secp = secp256k1::Secp256k1::new();
 
         // Derive public key from private key
-        let public_key = PublicKey::from_slice(
-            &secp256k1::PublicKey::from_secret_key(&secp, privkey).serialize(),
-        )
-        .map_err(|e| format!("Failed to derive public key: {}", e))?;
+        let public_key = PublicKey::new(secp256k1::PublicKey::from_secret_key(&secp, privkey));
+
+        // Single-sig inputs (P2PKH, P2WPKH, P2TR key path) are signed directly: the PSBT
+        // signer neither handles FORKID sighashes nor finds taproot keys without a bip32 origin.
+        // Zcash falls through to its own signer below.
+        if !matches!(network.mainnet(), Network::Zcash) {
+            let input = &psbt.inputs[input_index];
+            let prevout = psbt.unsigned_tx.input[input_index].previous_output;
+            let single_sig_type = psbt_wallet_input::get_output_script_and_value(input, prevout)
+                .ok()
+                .and_then(|(script, _)| single_sig::SingleSigScriptType::from_input(input, script));
+            if let Some(script_type) = single_sig_type {
+                return single_sig::sign_single_sig_input(
+                    psbt,
+                    input_index,
+                    script_type,
+                    privkey,
+                    network,
+                    &secp,
+                )
+                .map_err(signing_error);
+            }
+        }
 
         // Check if this is a replay protection input (P2SH-P2PK)
         if let Some(redeem_script) = &psbt.inputs[input_index].redeem_script.clone() {
@@ -2002,10 +2868,7 @@ impl BitGoPsbt {
             if let Ok(redeem_pubkey) = Self::extract_pubkey_from_p2pk_redeem_script(redeem_script) {
                 // This is a replay protection input - verify the derived pubkey matches
                 if public_key != redeem_pubkey {
-                    return Err(
-                        "Public key mismatch: derived pubkey does not match redeem_script pubkey"
-                            .to_string(),
-                    );
+                    return Err(BitGoPsbtError::PublicKeyMismatch { input_index });
                 }
 
                 // Zcash needs special handling due to ZcashPsbt fields
@@ -2022,7 +2885,8 @@ impl BitGoPsbt {
                         privkey,
                         network,
                         &secp,
-                    )?;
+                    )
+                    .map_err(signing_error)?;
 
                     // Add signature to partial_sigs
                     psbt.inputs[input_index]
@@ -2034,8 +2898,31 @@ impl BitGoPsbt {
             }
         }
 
+        // Taproot inputs with leaf scripts: the internal key signs the key path, any other
+        // key the leaves containing it. The PSBT signer only looks up keys via
+        // tap_key_origins, so a standalone key (e.g. a backup key WIF) is matched
+        // against the x-only pubkeys in the leaf scripts instead.
+        if !psbt.inputs[input_index].tap_scripts.is_empty() {
+            let (x_only_key, _) = public_key.inner.x_only_public_key();
+            let input = &psbt.inputs[input_index];
+            let origin_leaf_hashes = input
+                .tap_key_origins
+                .get(&x_only_key)
+                .map(|(leaf_hashes, _)| leaf_hashes);
+            let is_internal_key = match origin_leaf_hashes {
+                Some(leaf_hashes) => leaf_hashes.is_empty(),
+                None => input.tap_internal_key == Some(x_only_key),
+            };
+            return if is_internal_key {
+                Self::sign_taproot_key_path_input(psbt, input_index, privkey, &secp)
+            } else {
+                Self::sign_taproot_script_path_input(psbt, input_index, privkey, &secp)
+            }
+            .map_err(signing_error);
+        }
+
         // For regular inputs (non-RP, non-MuSig2), use standard signing via miniscript
-        // This will handle legacy, SegWit, and Taproot script path inputs
+        // This will handle legacy and SegWit inputs
         match self {
             BitGoPsbt::BitcoinLike(ref mut psbt, _network) => {
                 // Create a key provider that returns our single key
@@ -2058,21 +2945,18 @@ impl BitGoPsbt {
                         if signing_keys.contains_key(&input_index) {
                             Ok(())
                         } else {
-                            Err(format!(
-                                "Input {} was not signed (no key found or already signed)",
-                                input_index
-                            ))
+                            Err(BitGoPsbtError::NotSigned { input_index })
                         }
                     }
                     Err((partial_success, errors)) => {
                         // Check if there's an error for our specific input
                         if let Some(error) = errors.get(&input_index) {
-                            Err(format!("Failed to sign input {}: {:?}", input_index, error))
+                            Err(signing_error(SignInputError::Psbt(format!("{:?}", error))))
                         } else if partial_success.contains_key(&input_index) {
                             // Input was signed successfully despite other errors
                             Ok(())
                         } else {
-                            Err(format!("Input {} was not signed", input_index))
+                            Err(BitGoPsbtError::NotSigned { input_index })
                         }
                     }
                 }
@@ -2099,21 +2983,18 @@ impl BitGoPsbt {
                         if signing_keys.contains_key(&input_index) {
                             Ok(())
                         } else {
-                            Err(format!(
-                                "Input {} was not signed (no key found or already signed)",
-                                input_index
-                            ))
+                            Err(BitGoPsbtError::NotSigned { input_index })
                         }
                     }
                     Err((partial_success, errors)) => {
                         // Check if there's an error for our specific input
                         if let Some(error) = errors.get(&input_index) {
-                            Err(format!("Failed to sign input {}: {:?}", input_index, error))
+                            Err(signing_error(SignInputError::Psbt(format!("{:?}", error))))
                         } else if partial_success.contains_key(&input_index) {
                             // Input was signed successfully despite other errors
                             Ok(())
                         } else {
-                            Err(format!("Input {} was not signed", input_index))
+                            Err(BitGoPsbtError::NotSigned { input_index })
                         }
                     }
                 }
@@ -2121,7 +3002,7 @@ impl BitGoPsbt {
             BitGoPsbt::Zcash(ref mut zcash_psbt, network) => {
                 // Extract consensus branch ID from PSBT proprietary map
                 let branch_id = propkv::get_zec_consensus_branch_id(&zcash_psbt.psbt)
-                    .ok_or_else(|| "Missing ZecConsensusBranchId in PSBT".to_string())?;
+                    .ok_or_else(|| signing_error(SignInputError::MissingConsensusBranchId))?;
                 let version_group_id = zcash_psbt
                     .version_group_id
                     .unwrap_or(zcash_psbt::ZCASH_SAPLING_VERSION_GROUP_ID);
@@ -2131,11 +3012,10 @@ impl BitGoPsbt {
 
                 // Check bounds
                 if input_index >= psbt.inputs.len() {
-                    return Err(format!(
-                        "Input index {} out of bounds (total inputs: {})",
-                        input_index,
-                        psbt.inputs.len()
-                    ));
+                    return Err(BitGoPsbtError::InputIndexOutOfBounds {
+                        index: input_index,
+                        input_count: psbt.inputs.len(),
+                    });
                 }
 
                 // Check if this is a replay protection input (P2SH-P2PK)
@@ -2146,10 +3026,7 @@ impl BitGoPsbt {
                     {
                         // Verify the provided key matches the redeem script pubkey
                         if public_key != redeem_pubkey {
-                            return Err(
-                                "Public key mismatch: derived pubkey does not match redeem_script pubkey"
-                                    .to_string(),
-                            );
+                            return Err(BitGoPsbtError::PublicKeyMismatch { input_index });
                         }
 
                         // Sign directly using ZIP-243 sighash
@@ -2162,7 +3039,8 @@ impl BitGoPsbt {
                             version_group_id,
                             expiry_height,
                             &secp,
-                        )?;
+                        )
+                        .map_err(signing_error)?;
 
                         // Add signature to partial_sigs
                         psbt.inputs[input_index]
@@ -2192,19 +3070,16 @@ impl BitGoPsbt {
                         if signing_keys.contains_key(&input_index) {
                             Ok(())
                         } else {
-                            Err(format!(
-                                "Input {} was not signed (no key found or already signed)",
-                                input_index
-                            ))
+                            Err(BitGoPsbtError::NotSigned { input_index })
                         }
                     }
                     Err((partial_success, errors)) => {
                         if let Some(error) = errors.get(&input_index) {
-                            Err(format!("Failed to sign input {}: {:?}", input_index, error))
+                            Err(signing_error(SignInputError::Psbt(format!("{:?}", error))))
                         } else if partial_success.contains_key(&input_index) {
                             Ok(())
                         } else {
-                            Err(format!("Input {} was not signed", input_index))
+                            Err(BitGoPsbtError::NotSigned { input_index })
                         }
                     }
                 }
@@ -2223,6 +3098,10 @@ impl BitGoPsbt {
     /// # Returns
     /// - `Ok(Vec<usize>)` with indices of inputs that were signed
     /// - `Err(String)` if signing fails
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "sign", skip_all, fields(inputs = self.psbt().inputs.len()))
+    )]
     pub fn sign_all_replay_protection_inputs(
         &mut self,
         privkey: &secp256k1::SecretKey,
@@ -2258,7 +3137,8 @@ impl BitGoPsbt {
 
             if should_sign {
                 // Use the existing sign_with_privkey which handles RP inputs specially
-                self.sign_with_privkey(input_index, privkey)?;
+                self.sign_with_privkey(input_index, privkey)
+                    .map_err(|e| e.to_string())?;
                 signed_indices.push(input_index);
             }
         }
@@ -2283,6 +3163,14 @@ impl BitGoPsbt {
     /// # Returns
     /// - `Ok(())` if the input was signed
     /// - `Err(String)` if signing fails
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "sign",
+            skip_all,
+            fields(inputs = self.psbt().inputs.len(), input_index = input_index)
+        )
+    )]
     pub fn sign_single_input_with_privkey(
         &mut self,
         input_index: usize,
@@ -2310,7 +3198,9 @@ impl BitGoPsbt {
         if let Some(redeem_script) = &psbt.inputs[input_index].redeem_script {
             if Self::extract_pubkey_from_p2pk_redeem_script(redeem_script).is_ok() {
                 // This is a replay protection input - use direct signing
-                return self.sign_with_privkey(input_index, privkey);
+                return self
+                    .sign_with_privkey(input_index, privkey)
+                    .map_err(|e| e.to_string());
             }
         }
 
@@ -2318,7 +3208,9 @@ impl BitGoPsbt {
         let mut cloned = self.clone();
 
         // Sign on the clone (this signs all matching inputs)
-        cloned.sign_with_privkey(input_index, privkey)?;
+        cloned
+            .sign_with_privkey(input_index, privkey)
+            .map_err(|e| e.to_string())?;
 
         // Copy only the target input's signatures from the clone to self
         let cloned_input = &cloned.psbt().inputs[input_index];
@@ -2352,6 +3244,10 @@ impl BitGoPsbt {
     /// # Returns
     /// - `Ok(SigningKeysMap)` on success, mapping input index to keys used for signing
     /// - `Err((SigningKeysMap, SigningErrors))` on failure, containing both partial success info and errors
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "sign", skip_all, fields(inputs = self.psbt().inputs.len()))
+    )]
     pub fn sign<C, K>(
         &mut self,
         k: &K,
@@ -2438,7 +3334,22 @@ impl BitGoPsbt {
         let secp = secp256k1::Secp256k1::new();
 
         // Sign all inputs - miniscript handles this efficiently
-        match self.sign(xpriv, &secp) {
+        let result = self.sign(xpriv, &secp);
+        self.filter_sign_all_errors(result)
+    }
+
+    /// Drop expected signing errors for MuSig2 inputs and inputs of other keys
+    fn filter_sign_all_errors(
+        &self,
+        result: Result<
+            miniscript::bitcoin::psbt::SigningKeysMap,
+            (
+                miniscript::bitcoin::psbt::SigningKeysMap,
+                miniscript::bitcoin::psbt::SigningErrors,
+            ),
+        >,
+    ) -> Result<miniscript::bitcoin::psbt::SigningKeysMap, String> {
+        match result {
             Ok(signing_keys) => Ok(signing_keys),
             Err((partial_success, errors)) => {
                 // Filter out errors for MuSig2 inputs (they're expected to fail)
@@ -2477,6 +3388,41 @@ impl BitGoPsbt {
         }
     }
 
+    /// Check a [`SigningPolicy`] against this transaction
+    ///
+    /// # Returns
+    /// - `Ok(ParsedTransaction)` if the policy is satisfied
+    /// - `Err(SigningPolicyError)` if parsing fails or a limit is exceeded
+    pub fn check_signing_policy(
+        &self,
+        wallet_keys: &crate::fixed_script_wallet::RootWalletKeys,
+        replay_protection: &crate::fixed_script_wallet::ReplayProtection,
+        policy: &SigningPolicy,
+    ) -> Result<ParsedTransaction, SigningPolicyError> {
+        let parsed =
+            self.parse_transaction_with_wallet_keys(wallet_keys, replay_protection, &[])?;
+        policy.check(&parsed)?;
+        Ok(parsed)
+    }
+
+    /// Like `sign_all_with_xpriv`, but refuses to sign if `policy` is violated.
+    ///
+    /// Pass `override_policy = true` to sign anyway; the policy is then not evaluated.
+    pub fn sign_all_with_xpriv_and_policy(
+        &mut self,
+        xpriv: &miniscript::bitcoin::bip32::Xpriv,
+        wallet_keys: &crate::fixed_script_wallet::RootWalletKeys,
+        replay_protection: &crate::fixed_script_wallet::ReplayProtection,
+        policy: &SigningPolicy,
+        override_policy: bool,
+    ) -> Result<miniscript::bitcoin::psbt::SigningKeysMap, String> {
+        if !override_policy {
+            self.check_signing_policy(wallet_keys, replay_protection, policy)
+                .map_err(|e| format!("Signing policy violated: {}", e))?;
+        }
+        self.sign_all_with_xpriv(xpriv)
+    }
+
     /// Sign a single input with the provided xpriv, using save/restore to avoid
     /// signing other inputs.
     ///
@@ -2495,6 +3441,14 @@ impl BitGoPsbt {
     /// # Returns
     /// - `Ok(())` if the input was signed
     /// - `Err(String)` if signing fails
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "sign",
+            skip_all,
+            fields(inputs = self.psbt().inputs.len(), input_index = input_index)
+        )
+    )]
     pub fn sign_single_input_with_xpriv(
         &mut self,
         input_index: usize,
@@ -2565,31 +3519,37 @@ impl BitGoPsbt {
         &self,
         wallet_keys: &crate::fixed_script_wallet::RootWalletKeys,
         replay_protection: &crate::fixed_script_wallet::ReplayProtection,
+        external_policy: ExternalInputPolicy,
     ) -> Result<Vec<ParsedInput>, ParseTransactionError> {
-        let psbt = self.psbt();
-        let network = self.network();
-
-        psbt.unsigned_tx
-            .input
-            .iter()
-            .zip(psbt.inputs.iter())
-            .enumerate()
-            .map(|(input_index, (tx_input, psbt_input))| {
-                ParsedInput::parse(
-                    psbt_input,
-                    tx_input,
-                    wallet_keys,
-                    replay_protection,
-                    network,
-                )
-                .map_err(|error| ParseTransactionError::Input {
-                    index: input_index,
-                    error,
-                })
+        (0..self.psbt().inputs.len())
+            .map(|input_index| {
+                self.parse_input(input_index, wallet_keys, replay_protection, external_policy)
             })
             .collect()
     }
 
+    fn parse_input(
+        &self,
+        input_index: usize,
+        wallet_keys: &crate::fixed_script_wallet::RootWalletKeys,
+        replay_protection: &crate::fixed_script_wallet::ReplayProtection,
+        external_policy: ExternalInputPolicy,
+    ) -> Result<ParsedInput, ParseTransactionError> {
+        let psbt = self.psbt();
+        ParsedInput::parse(
+            &psbt.inputs[input_index],
+            &psbt.unsigned_tx.input[input_index],
+            wallet_keys,
+            replay_protection,
+            self.network(),
+            external_policy,
+        )
+        .map_err(|error| ParseTransactionError::Input {
+            index: input_index,
+            error,
+        })
+    }
+
     /// Parse outputs with wallet keys to identify which outputs belong to the wallet
     ///
     /// # Arguments
@@ -2626,30 +3586,83 @@ impl BitGoPsbt {
             .collect()
     }
 
-    /// Calculate total input value from parsed inputs
+    /// Parse outputs against several labeled wallets in a single pass.
     ///
-    /// # Returns
-    /// - `Ok(u64)` with total input value
-    /// - `Err(ParseTransactionError)` if overflow occurs
-    fn sum_input_values(parsed_inputs: &[ParsedInput]) -> Result<u64, ParseTransactionError> {
-        parsed_inputs
+    /// Each output is attributed to the first wallet (in list order) whose keys match its
+    /// derivation info.
+    ///
+    /// # Arguments
+    /// - `wallets`: `(label, wallet_keys)` pairs
+    /// - `paygo_pubkeys`: Public keys for PayGo attestation verification
+    pub fn parse_outputs_with_labeled_wallet_keys(
+        &self,
+        wallets: &[(&str, &crate::fixed_script_wallet::RootWalletKeys)],
+        paygo_pubkeys: &[secp256k1::PublicKey],
+    ) -> Result<Vec<LabeledParsedOutput>, ParseTransactionError> {
+        let psbt = self.psbt();
+        let network = self.network();
+        let wallet_keys: Vec<_> = wallets.iter().map(|(_, keys)| *keys).collect();
+
+        psbt.unsigned_tx
+            .output
             .iter()
+            .zip(psbt.outputs.iter())
             .enumerate()
-            .try_fold(0u64, |total, (index, input)| {
-                total
-                    .checked_add(input.value)
-                    .ok_or(ParseTransactionError::InputValueOverflow { index })
-            })
-    }
-
-    /// Calculate total output value and spend amount from transaction outputs and parsed outputs
-    ///
+            .map(|(output_index, (tx_output, psbt_output))| {
+                let (wallet_index, output) = ParsedOutput::parse_with_wallets(
+                    psbt_output,
+                    tx_output,
+                    &wallet_keys,
+                    network,
+                    paygo_pubkeys,
+                )
+                .map_err(|error| ParseTransactionError::Output {
+                    index: output_index,
+                    error,
+                })?;
+                Ok(LabeledParsedOutput {
+                    wallet: wallet_index.map(|i| wallets[i].0.to_string()),
+                    output,
+                })
+            })
+            .collect()
+    }
+
+    /// Calculate total input value from parsed inputs
+    ///
+    /// # Returns
+    /// - `Ok(u64)` with total input value
+    /// - `Err(ParseTransactionError)` if overflow occurs or the total exceeds `max_money`
+    fn sum_input_values(
+        parsed_inputs: &[ParsedInput],
+        max_money: u64,
+    ) -> Result<u64, ParseTransactionError> {
+        parsed_inputs
+            .iter()
+            .enumerate()
+            .try_fold(0u64, |total, (index, input)| {
+                let total = total
+                    .checked_add(input.value)
+                    .ok_or(ParseTransactionError::InputValueOverflow { index })?;
+                if total > max_money {
+                    return Err(ParseTransactionError::InputValueExceedsMaxMoney {
+                        index,
+                        max_money,
+                    });
+                }
+                Ok(total)
+            })
+    }
+
+    /// Calculate total output value and spend amount from transaction outputs and parsed outputs
+    ///
     /// # Returns
     /// - `Ok((total_value, spend_amount))` with total output value and external spend amount
-    /// - `Err(ParseTransactionError)` if overflow occurs
+    /// - `Err(ParseTransactionError)` if overflow occurs or the total exceeds `max_money`
     fn sum_output_values(
         tx_outputs: &[miniscript::bitcoin::TxOut],
         parsed_outputs: &[ParsedOutput],
+        max_money: u64,
     ) -> Result<(u64, u64), ParseTransactionError> {
         tx_outputs
             .iter()
@@ -2661,6 +3674,12 @@ impl BitGoPsbt {
                     let new_total = total_value
                         .checked_add(tx_output.value.to_sat())
                         .ok_or(ParseTransactionError::OutputValueOverflow { index })?;
+                    if new_total > max_money {
+                        return Err(ParseTransactionError::OutputValueExceedsMaxMoney {
+                            index,
+                            max_money,
+                        });
+                    }
 
                     let new_spend = if parsed_output.is_external() {
                         spend
@@ -2746,7 +3765,7 @@ impl BitGoPsbt {
     ///
     /// # Returns
     /// - `Ok(EcdsaSignature)` containing the signature and sighash type
-    /// - `Err(String)` if sighash computation fails
+    /// - `Err(SignInputError)` if sighash computation fails
     fn sign_p2sh_p2pk_input<C: secp256k1::Signing>(
         psbt: &Psbt,
         input_index: usize,
@@ -2754,7 +3773,7 @@ impl BitGoPsbt {
         privkey: &secp256k1::SecretKey,
         network: Network,
         secp: &secp256k1::Secp256k1<C>,
-    ) -> Result<miniscript::bitcoin::ecdsa::Signature, String> {
+    ) -> Result<miniscript::bitcoin::ecdsa::Signature, SignInputError> {
         use miniscript::bitcoin::{
             ecdsa::Signature as EcdsaSignature, hashes::Hash, sighash::SighashCache,
         };
@@ -2782,7 +3801,7 @@ impl BitGoPsbt {
                     sighash_type,
                     Some(fork_id),
                 )
-                .map_err(|e| format!("Failed to compute FORKID sighash: {}", e))?;
+                .map_err(|e| SignInputError::Sighash(e.to_string()))?;
             (
                 secp256k1::Message::from_digest(sighash.to_byte_array()),
                 sighash_type,
@@ -2792,7 +3811,7 @@ impl BitGoPsbt {
             let sighash_type = miniscript::bitcoin::sighash::EcdsaSighashType::All;
             let sighash = cache
                 .legacy_signature_hash(input_index, redeem_script, sighash_type.to_u32())
-                .map_err(|e| format!("Failed to compute sighash: {}", e))?;
+                .map_err(|e| SignInputError::Sighash(e.to_string()))?;
             (
                 secp256k1::Message::from_digest(sighash.to_byte_array()),
                 sighash_type.to_u32(),
@@ -2807,6 +3826,143 @@ impl BitGoPsbt {
         })
     }
 
+    /// Sign the key path of a taproot input with a raw private key
+    ///
+    /// The key is tweaked with `tap_merkle_root` and must match the output key of the spent
+    /// output. The signature is set as `tap_key_sig`.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the input was signed
+    /// - `Err(SignInputError)` if the tweaked key does not match the output key or sighash
+    ///   computation fails
+    fn sign_taproot_key_path_input<C: secp256k1::Signing>(
+        psbt: &mut Psbt,
+        input_index: usize,
+        privkey: &secp256k1::SecretKey,
+        secp: &secp256k1::Secp256k1<C>,
+    ) -> Result<(), SignInputError> {
+        use miniscript::bitcoin::{
+            hashes::Hash,
+            key::TapTweak,
+            sighash::{Prevouts, SighashCache},
+            taproot, TapSighashType,
+        };
+
+        let input = &psbt.inputs[input_index];
+        let prevout = psbt.unsigned_tx.input[input_index].previous_output;
+        let (output_script, _) = psbt_wallet_input::get_output_script_and_value(input, prevout)
+            .map_err(SignInputError::Utxo)?;
+        let keypair = secp256k1::Keypair::from_secret_key(secp, privkey)
+            .tap_tweak(secp, input.tap_merkle_root)
+            .to_inner();
+        let (output_key, _) = keypair.x_only_public_key();
+        if !output_script.is_p2tr() || output_script.as_bytes()[2..] != output_key.serialize() {
+            return Err(SignInputError::TweakedKeyMismatch);
+        }
+
+        let sighash_type = input
+            .sighash_type
+            .map(|t| t.taproot_hash_ty())
+            .transpose()
+            .map_err(|e| SignInputError::InvalidSighashType(e.to_string()))?
+            .unwrap_or(TapSighashType::Default);
+        let prevouts =
+            psbt_wallet_input::collect_prevouts(psbt).map_err(SignInputError::Prevouts)?;
+        let sighash = SighashCache::new(&psbt.unsigned_tx)
+            .taproot_key_spend_signature_hash(input_index, &Prevouts::All(&prevouts), sighash_type)
+            .map_err(|e| SignInputError::Sighash(e.to_string()))?;
+        let message = secp256k1::Message::from_digest(sighash.to_byte_array());
+        psbt.inputs[input_index].tap_key_sig = Some(taproot::Signature {
+            signature: secp.sign_schnorr_no_aux_rand(&message, &keypair),
+            sighash_type,
+        });
+        Ok(())
+    }
+
+    /// Sign a taproot script path input with a raw private key
+    ///
+    /// Adds a Schnorr signature to `tap_script_sigs` for each leaf in `tap_scripts` whose
+    /// script contains the x-only pubkey of `privkey`. This does not rely on
+    /// `tap_key_origins`, so it works for keys without a known derivation.
+    ///
+    /// # Arguments
+    /// - `psbt`: The PSBT containing the input to sign
+    /// - `input_index`: Index of the input to sign
+    /// - `privkey`: The private key to sign with
+    ///
+    /// # Returns
+    /// - `Ok(())` if at least one leaf was signed
+    /// - `Err(SignInputError)` if the key does not appear in any leaf script or sighash
+    ///   computation fails
+    fn sign_taproot_script_path_input<C: secp256k1::Signing>(
+        psbt: &mut Psbt,
+        input_index: usize,
+        privkey: &secp256k1::SecretKey,
+        secp: &secp256k1::Secp256k1<C>,
+    ) -> Result<(), SignInputError> {
+        use miniscript::bitcoin::{
+            hashes::Hash,
+            sighash::{Prevouts, SighashCache},
+            taproot, TapLeafHash, TapSighashType,
+        };
+
+        let keypair = secp256k1::Keypair::from_secret_key(secp, privkey);
+        let (x_only_key, _) = keypair.x_only_public_key();
+        let x_only_bytes = x_only_key.serialize();
+
+        let input = &psbt.inputs[input_index];
+        let leaf_hashes: Vec<TapLeafHash> = input
+            .tap_scripts
+            .values()
+            .filter(|(script, _)| {
+                script
+                    .instructions()
+                    .filter_map(Result::ok)
+                    .any(|instruction| {
+                        instruction
+                            .push_bytes()
+                            .is_some_and(|bytes| bytes.as_bytes() == x_only_bytes)
+                    })
+            })
+            .map(|(script, leaf_version)| TapLeafHash::from_script(script, *leaf_version))
+            .collect();
+
+        if leaf_hashes.is_empty() {
+            return Err(SignInputError::KeyNotInTapLeaf);
+        }
+
+        let sighash_type = input
+            .sighash_type
+            .map(|t| t.taproot_hash_ty())
+            .transpose()
+            .map_err(|e| SignInputError::InvalidSighashType(e.to_string()))?
+            .unwrap_or(TapSighashType::Default);
+
+        let prevouts =
+            psbt_wallet_input::collect_prevouts(psbt).map_err(SignInputError::Prevouts)?;
+        let mut cache = SighashCache::new(&psbt.unsigned_tx);
+        let mut signatures = Vec::with_capacity(leaf_hashes.len());
+        for leaf_hash in leaf_hashes {
+            let sighash = cache
+                .taproot_script_spend_signature_hash(
+                    input_index,
+                    &Prevouts::All(&prevouts),
+                    leaf_hash,
+                    sighash_type,
+                )
+                .map_err(|e| SignInputError::Sighash(e.to_string()))?;
+            let message = secp256k1::Message::from_digest(sighash.to_byte_array());
+            let signature = taproot::Signature {
+                signature: secp.sign_schnorr_no_aux_rand(&message, &keypair),
+                sighash_type,
+            };
+            signatures.push(((x_only_key, leaf_hash), signature));
+        }
+
+        psbt.inputs[input_index].tap_script_sigs.extend(signatures);
+        Ok(())
+    }
+
     /// Sign a P2SH-P2PK (replay protection) input using Zcash ZIP-243 sighash.
     ///
     /// # Arguments
@@ -2820,7 +3976,7 @@ impl BitGoPsbt {
     ///
     /// # Returns
     /// - `Ok(EcdsaSignature)` containing the signature and sighash type
-    /// - `Err(String)` if sighash computation fails
+    /// - `Err(SignInputError)` if sighash computation fails
     #[allow(clippy::too_many_arguments)]
     fn sign_p2sh_p2pk_input_zcash<C: secp256k1::Signing>(
         psbt: &Psbt,
@@ -2831,7 +3987,7 @@ impl BitGoPsbt {
         version_group_id: u32,
         expiry_height: u32,
         secp: &secp256k1::Secp256k1<C>,
-    ) -> Result<miniscript::bitcoin::ecdsa::Signature, String> {
+    ) -> Result<miniscript::bitcoin::ecdsa::Signature, SignInputError> {
         use miniscript::bitcoin::{
             ecdsa::Signature as EcdsaSignature, sighash::SighashCache,
             sighash::SighashCacheZcashExt,
@@ -2840,9 +3996,8 @@ impl BitGoPsbt {
         // Get input value for sighash computation
         let input = &psbt.inputs[input_index];
         let prevout = psbt.unsigned_tx.input[input_index].previous_output;
-        let value = psbt_wallet_input::get_output_script_and_value(input, prevout)
-            .map(|(_, v)| v)
-            .unwrap_or(miniscript::bitcoin::Amount::ZERO);
+        let (_, value) = psbt_wallet_input::get_output_script_and_value(input, prevout)
+            .map_err(SignInputError::Utxo)?;
 
         // Compute ZIP-243 sighash
         let mut cache = SighashCache::new(&psbt.unsigned_tx);
@@ -2857,7 +4012,7 @@ impl BitGoPsbt {
                 version_group_id,
                 expiry_height,
             )
-            .map_err(|e| format!("Failed to compute Zcash sighash: {}", e))?;
+            .map_err(|e| SignInputError::Sighash(e.to_string()))?;
 
         let message = secp256k1::Message::from_digest(sighash.to_byte_array());
 
@@ -3295,17 +4450,56 @@ impl BitGoPsbt {
         wallet_keys: &crate::fixed_script_wallet::RootWalletKeys,
         replay_protection: &crate::fixed_script_wallet::ReplayProtection,
         paygo_pubkeys: &[secp256k1::PublicKey],
+    ) -> Result<ParsedTransaction, ParseTransactionError> {
+        self.parse_transaction_with_wallet_keys_and_input_policy(
+            wallet_keys,
+            replay_protection,
+            paygo_pubkeys,
+            ExternalInputPolicy::Reject,
+        )
+    }
+
+    /// Like `parse_transaction_with_wallet_keys`, with control over inputs that
+    /// belong to neither the wallet nor replay protection.
+    ///
+    /// With `ExternalInputPolicy::Allow`, such inputs are returned as
+    /// `InputScriptType::External` (e.g. counterparty inputs in a payjoin). Their
+    /// values still count towards the miner fee.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "parse",
+            skip_all,
+            fields(inputs = self.psbt().inputs.len(), outputs = self.psbt().outputs.len())
+        )
+    )]
+    pub fn parse_transaction_with_wallet_keys_and_input_policy(
+        &self,
+        wallet_keys: &crate::fixed_script_wallet::RootWalletKeys,
+        replay_protection: &crate::fixed_script_wallet::ReplayProtection,
+        paygo_pubkeys: &[secp256k1::PublicKey],
+        external_policy: ExternalInputPolicy,
+    ) -> Result<ParsedTransaction, ParseTransactionError> {
+        let parsed_inputs = self.parse_inputs(wallet_keys, replay_protection, external_policy)?;
+        self.parse_transaction_with_parsed_inputs(parsed_inputs, wallet_keys, paygo_pubkeys)
+    }
+
+    /// Parse outputs and compute the totals for already parsed inputs
+    fn parse_transaction_with_parsed_inputs(
+        &self,
+        parsed_inputs: Vec<ParsedInput>,
+        wallet_keys: &crate::fixed_script_wallet::RootWalletKeys,
+        paygo_pubkeys: &[secp256k1::PublicKey],
     ) -> Result<ParsedTransaction, ParseTransactionError> {
         let psbt = self.psbt();
 
-        // Parse inputs and outputs
-        let parsed_inputs = self.parse_inputs(wallet_keys, replay_protection)?;
         let parsed_outputs = self.parse_outputs(wallet_keys, paygo_pubkeys)?;
 
         // Calculate totals
-        let total_input_value = Self::sum_input_values(&parsed_inputs)?;
+        let max_money = self.network().max_money();
+        let total_input_value = Self::sum_input_values(&parsed_inputs, max_money)?;
         let (total_output_value, spend_amount) =
-            Self::sum_output_values(&psbt.unsigned_tx.output, &parsed_outputs)?;
+            Self::sum_output_values(&psbt.unsigned_tx.output, &parsed_outputs, max_money)?;
 
         // Calculate miner fee
         let miner_fee = total_input_value
@@ -3314,17 +4508,102 @@ impl BitGoPsbt {
 
         // Calculate virtual size from unsigned transaction weight
         // TODO: Consider using finalized transaction size estimate for more accurate fee calculation
-        let weight = psbt.unsigned_tx.weight();
+        let weight =
+            psbt.unsigned_tx.weight() + self.replay_protection_script_sig_weight(&parsed_inputs);
         let virtual_size = weight.to_vbytes_ceil();
 
+        let zip317_fee = self.zip317_fee(&parsed_inputs, miner_fee);
+        let vault_policy = self
+            .vault_policy()
+            .map_err(ParseTransactionError::VaultPolicy)?;
+
         Ok(ParsedTransaction {
             inputs: parsed_inputs,
             outputs: parsed_outputs,
             spend_amount,
             miner_fee,
             virtual_size: virtual_size as u32,
+            zip317_fee,
+            vault_policy: (!vault_policy.is_empty()).then_some(vault_policy),
         })
     }
+
+    /// Weight of the scriptSigs of the replay protection inputs once signed
+    ///
+    /// Unsigned transaction scriptSigs are empty, so this is what signing adds to the weight of
+    /// these inputs: 4 weight units per byte, with the length prefix staying one byte. Unsigned
+    /// inputs are counted with the largest ECDSA signature.
+    fn replay_protection_script_sig_weight(&self, parsed_inputs: &[ParsedInput]) -> Weight {
+        use crate::fixed_script_wallet::wallet_scripts::P2SH_P2PK_SCRIPT_SIG_MAX_SIZE;
+
+        let bytes: usize = parsed_inputs
+            .iter()
+            .zip(self.psbt().inputs.iter())
+            .filter(|(parsed, _)| matches!(parsed.script_type, InputScriptType::P2shP2pk))
+            .map(|(_, input)| {
+                psbt_wallet_input::p2sh_p2pk_signed_script_sig_size(input)
+                    .unwrap_or(P2SH_P2PK_SCRIPT_SIG_MAX_SIZE)
+            })
+            .sum();
+        Weight::from_non_witness_data_size(bytes as u64)
+    }
+
+    /// ZIP-317 fee check for transparent-only Zcash transactions
+    ///
+    /// Unsigned inputs are counted at their finalized size with maximum-length signatures.
+    /// Returns `None` for other networks, for transactions with shielded components and
+    /// for inputs whose finalized size is unknown.
+    fn zip317_fee(
+        &self,
+        parsed_inputs: &[ParsedInput],
+        miner_fee: u64,
+    ) -> Option<crate::zcash::zip317::Zip317Fee> {
+        use crate::fixed_script_wallet::wallet_scripts::P2SH_P2PK_SCRIPT_SIG_MAX_SIZE;
+        use miniscript::bitcoin::VarInt;
+
+        // OP_0 <sig> <sig> OP_PUSHDATA1 <2-of-3 redeemScript>
+        const P2SH_SCRIPT_SIG_MAX: usize = 1 + 74 + 74 + 2 + 105;
+
+        let BitGoPsbt::Zcash(zcash_psbt, _) = self else {
+            return None;
+        };
+        // Empty sapling bundle and no joinsplits serialize as zero bytes
+        if zcash_psbt.sapling_fields.iter().any(|b| *b != 0) {
+            return None;
+        }
+
+        let psbt = &zcash_psbt.psbt;
+        let tx_in_total_size = psbt
+            .inputs
+            .iter()
+            .zip(parsed_inputs)
+            .map(|(input, parsed)| {
+                let script_sig_len = match (&input.final_script_sig, parsed.script_type) {
+                    (Some(script_sig), _) => script_sig.len(),
+                    (None, InputScriptType::P2sh) => P2SH_SCRIPT_SIG_MAX,
+                    (None, InputScriptType::P2shP2pk) => P2SH_P2PK_SCRIPT_SIG_MAX_SIZE,
+                    (None, _) => return None,
+                };
+                // prevout(36) + sequence(4) + scriptSig
+                Some(40 + VarInt::from(script_sig_len).size() + script_sig_len)
+            })
+            .sum::<Option<usize>>()?;
+        let tx_out_total_size = psbt
+            .unsigned_tx
+            .output
+            .iter()
+            .map(|output| {
+                let script_len = output.script_pubkey.len();
+                8 + VarInt::from(script_len).size() + script_len
+            })
+            .sum();
+
+        Some(crate::zcash::zip317::Zip317Fee::transparent(
+            tx_in_total_size,
+            tx_out_total_size,
+            miner_fee,
+        ))
+    }
 }
 
 impl crate::psbt_ops::PsbtAccess for BitGoPsbt {
@@ -4455,8 +5734,10 @@ mod tests {
         network: Network,
         format: fixtures::TxFormat,
         script_type: fixtures::ScriptType,
+        layout: HalfSignedLayout,
     ) -> Result<(), String> {
         use crate::fixed_script_wallet::ScriptIdWithValue;
+        use miniscript::bitcoin::consensus::deserialize;
 
         let is_p2ms = matches!(
             script_type,
@@ -4510,9 +5791,25 @@ mod tests {
 
         // Step 1: Extract to legacy
         let legacy_bytes = bitgo_psbt
-            .extract_half_signed_legacy_tx()
+            .extract_half_signed_legacy_tx_with_layout(layout)
             .map_err(|e| format!("extract_half_signed_legacy_tx failed: {}", e))?;
 
+        // Dummy, signature(s) and script
+        let expected_items = match layout {
+            HalfSignedLayout::Positional => 5,
+            HalfSignedLayout::Compact => 3,
+        };
+        let legacy_tx: Transaction = deserialize(&legacy_bytes)
+            .map_err(|e| format!("Failed to deserialize extracted tx: {}", e))?;
+        for (i, tx_in) in legacy_tx.input.iter().enumerate() {
+            let items = if tx_in.witness.is_empty() {
+                tx_in.script_sig.instructions().count()
+            } else {
+                tx_in.witness.len()
+            };
+            assert_eq!(items, expected_items, "Input {} item count", i);
+        }
+
         // Step 2: Build unspents from bip32 derivation paths in the PSBT
         // The derivation path is m/<chain>/<index>
         let unspents: Vec<HydrationUnspentInput> = psbt
@@ -4547,8 +5844,8 @@ mod tests {
 
         // Step 3: Convert back to PSBT
         let reconverted =
-            BitGoPsbt::from_network_format(&legacy_bytes, network, &wallet_keys, &unspents)
-                .map_err(|e| format!("from_network_format failed: {}", e))?;
+            BitGoPsbt::from_half_signed_legacy_tx(&legacy_bytes, &unspents, &wallet_keys, network)
+                .map_err(|e| format!("from_half_signed_legacy_tx failed: {}", e))?;
 
         // Verify: same number of inputs/outputs
         let orig_psbt = bitgo_psbt.psbt();
@@ -4613,12 +5910,15 @@ mod tests {
         network,
         format,
         {
-            test_round_trip_legacy_for_script_type(
-                network,
-                format,
-                fixtures::ScriptType::P2sh,
-            )
-            .unwrap();
+            for layout in [HalfSignedLayout::Positional, HalfSignedLayout::Compact] {
+                test_round_trip_legacy_for_script_type(
+                    network,
+                    format,
+                    fixtures::ScriptType::P2sh,
+                    layout,
+                )
+                .unwrap();
+            }
         },
         ignore: [Zcash]
     );
@@ -4628,12 +5928,15 @@ mod tests {
         network,
         format,
         {
-            test_round_trip_legacy_for_script_type(
-                network,
-                format,
-                fixtures::ScriptType::P2shP2wsh,
-            )
-            .unwrap();
+            for layout in [HalfSignedLayout::Positional, HalfSignedLayout::Compact] {
+                test_round_trip_legacy_for_script_type(
+                    network,
+                    format,
+                    fixtures::ScriptType::P2shP2wsh,
+                    layout,
+                )
+                .unwrap();
+            }
         },
         ignore: [BitcoinCash, Ecash, BitcoinGold, Dogecoin, Zcash]
     );
@@ -4643,12 +5946,15 @@ mod tests {
         network,
         format,
         {
-            test_round_trip_legacy_for_script_type(
-                network,
-                format,
-                fixtures::ScriptType::P2wsh,
-            )
-            .unwrap();
+            for layout in [HalfSignedLayout::Positional, HalfSignedLayout::Compact] {
+                test_round_trip_legacy_for_script_type(
+                    network,
+                    format,
+                    fixtures::ScriptType::P2wsh,
+                    layout,
+                )
+                .unwrap();
+            }
         },
         ignore: [BitcoinCash, Ecash, BitcoinGold, Dogecoin, Zcash]
     );
@@ -4931,6 +6237,19 @@ mod tests {
             "Virtual size should be greater than 0"
         );
 
+        // ZIP-317 applies to transparent-only Zcash transactions
+        match network {
+            Network::Zcash => {
+                let zip317_fee = parsed.zip317_fee.expect("Zcash should report ZIP-317 fee");
+                assert!(zip317_fee.logical_actions >= parsed.inputs.len() as u64);
+                assert_eq!(
+                    zip317_fee.meets_minimum,
+                    parsed.miner_fee >= zip317_fee.conventional_fee
+                );
+            }
+            _ => assert!(parsed.zip317_fee.is_none()),
+        }
+
         // Verify outputs (fixtures now have 3 external outputs)
         assert_eq!(
             external_outputs, 3,
@@ -4947,48 +6266,320 @@ mod tests {
         );
     });
 
-    #[test]
-    fn test_serialize_bitcoin_psbt() {
-        // Test that Bitcoin-like PSBTs can be serialized
-        let fixture = fixtures::load_psbt_fixture_with_network(
-            Network::Bitcoin,
+    crate::test_psbt_fixtures!(test_parse_transaction_external_inputs, network, format, {
+        let fixture = fixtures::load_psbt_fixture_with_format_and_namespace(
+            network.to_utxolib_name(),
             fixtures::SignatureState::Unsigned,
+            format,
+            fixtures::FixtureNamespace::UtxolibCompat,
         )
-        .unwrap();
-        let psbt = fixture
-            .to_bitgo_psbt(Network::Bitcoin)
+        .expect("Failed to load fixture");
+        let bitgo_psbt = fixture
+            .to_bitgo_psbt(network)
             .expect("Failed to convert to BitGo PSBT");
+        let wallet_keys = fixture
+            .get_wallet_xprvs()
+            .expect("Failed to get wallet keys")
+            .to_root_wallet_keys();
 
-        // Serialize should succeed
-        let serialized = psbt.serialize();
-        assert!(serialized.is_ok(), "Serialization should succeed");
-    }
+        // Without replay protection scripts, the p2shP2pk input is unknown to the wallet
+        let no_replay_protection = crate::fixed_script_wallet::ReplayProtection::new(vec![]);
+
+        assert!(matches!(
+            bitgo_psbt.parse_transaction_with_wallet_keys(&wallet_keys, &no_replay_protection, &[]),
+            Err(ParseTransactionError::Input {
+                error: psbt_wallet_input::ParseInputError::WalletValidation(_),
+                ..
+            })
+        ));
+
+        let parsed = bitgo_psbt
+            .parse_transaction_with_wallet_keys_and_input_policy(
+                &wallet_keys,
+                &no_replay_protection,
+                &[],
+                ExternalInputPolicy::Allow,
+            )
+            .expect("Failed to parse transaction with external inputs");
+
+        let external_inputs: Vec<_> = parsed
+            .inputs
+            .iter()
+            .filter(|i| i.script_type.is_external())
+            .collect();
+        assert!(!external_inputs.is_empty());
+        for input in external_inputs {
+            assert_eq!(
+                input.script_type,
+                InputScriptType::External(ExternalScriptType::P2sh)
+            );
+            assert!(input.script_id.is_none());
+            assert!(input.derivation_path.is_none());
+            assert!(input.value > 0);
+        }
+
+        let total_input_value: u64 = parsed.inputs.iter().map(|i| i.value).sum();
+        let total_output_value: u64 = parsed.outputs.iter().map(|o| o.value).sum();
+        assert_eq!(parsed.miner_fee, total_input_value - total_output_value);
+    });
 
     #[test]
-    fn test_serialize_zcash_psbt() {
-        // Test that Zcash PSBTs can be serialized
-        let fixture = fixtures::load_psbt_fixture_with_format_and_namespace(
-            "zcash",
-            fixtures::SignatureState::Unsigned,
-            fixtures::TxFormat::Psbt,
-            fixtures::FixtureNamespace::UtxolibCompat,
+    fn test_from_unsigned_tx_and_enrich_input() {
+        use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+        use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+        use miniscript::bitcoin::hashes::Hash;
+        use miniscript::bitcoin::CompressedPublicKey;
+        use psbt_wallet_input::{SignPath, SignerKey};
+
+        let wallet_keys = get_test_wallet_keys("from_unsigned_tx");
+        let sign_path = SignPath {
+            signer: SignerKey::User,
+            cosigner: SignerKey::Bitgo,
+        };
+        let pubkey = CompressedPublicKey(wallet_keys.user_key().public_key);
+
+        let mut expected = WalletPsbtBuilder::new(Network::BitcoinCash, &wallet_keys)
+            .input(0, 1)
+            .build();
+        expected
+            .add_replay_protection_input(
+                pubkey,
+                Txid::all_zeros(),
+                1,
+                1_000,
+                ReplayProtectionOptions::default(),
+            )
+            .unwrap();
+        expected
+            .add_output(miniscript::bitcoin::ScriptBuf::new(), 10_000)
+            .unwrap();
+
+        let tx_bytes = expected.get_unsigned_tx_bytes();
+        let mut psbt =
+            BitGoPsbt::from_unsigned_tx(&tx_bytes, Network::BitcoinCash, &wallet_keys).unwrap();
+        assert!(psbt.psbt().inputs[0].witness_utxo.is_none());
+        psbt.enrich_input(
+            0,
+            &wallet_keys,
+            ScriptId { chain: 0, index: 1 },
+            10_000,
+            None,
+            None,
         )
         .unwrap();
-        let original_bytes = BASE64_STANDARD
-            .decode(&fixture.psbt_base64)
-            .expect("Failed to decode base64");
-        let psbt = BitGoPsbt::deserialize(&original_bytes, Network::Zcash)
-            .expect("Failed to deserialize PSBT");
-
-        // Serialize should succeed
-        let serialized = psbt.serialize();
-        assert!(serialized.is_ok(), "Serialization should succeed");
+        psbt.enrich_replay_protection_input(1, pubkey, 1_000, None)
+            .unwrap();
+        assert_eq!(psbt.serialize().unwrap(), expected.serialize().unwrap());
+
+        assert!(psbt
+            .enrich_replay_protection_input(2, pubkey, 1_000, None)
+            .unwrap_err()
+            .contains("out of bounds"));
+        // The previous transaction must be the one the input spends
+        let prev_tx = miniscript::bitcoin::consensus::serialize(&psbt.psbt().unsigned_tx);
+        assert!(psbt
+            .enrich_input(
+                0,
+                &wallet_keys,
+                ScriptId { chain: 0, index: 1 },
+                10_000,
+                None,
+                Some(&prev_tx),
+            )
+            .unwrap_err()
+            .contains("does not match outpoint"));
+
+        // Taproot inputs need a sign path
+        let expected = WalletPsbtBuilder::new(Network::Bitcoin, &wallet_keys)
+            .input(40, 0)
+            .build();
+        let tx_bytes = expected.get_unsigned_tx_bytes();
+        let mut psbt =
+            BitGoPsbt::from_unsigned_tx(&tx_bytes, Network::Bitcoin, &wallet_keys).unwrap();
+        let script_id = ScriptId {
+            chain: 40,
+            index: 0,
+        };
+        assert!(psbt
+            .enrich_input(0, &wallet_keys, script_id, 10_000, None, None)
+            .is_err());
+        psbt.enrich_input(0, &wallet_keys, script_id, 10_000, Some(sign_path), None)
+            .unwrap();
+        assert_eq!(psbt.serialize().unwrap(), expected.serialize().unwrap());
     }
 
-    // Verify that the PsbtAccess::unsigned_tx_id implementation for BitGoPsbt
-    // returns the same txid as BitGoPsbt::unsigned_txid for all networks.
-    //
-    // Regression test for a bug introduced in 4.2.0 where the PsbtAccess trait's
+    #[test]
+    fn test_from_unsigned_tx_rejects_signed_tx() {
+        use miniscript::bitcoin::hashes::Hash;
+        use miniscript::bitcoin::{absolute, transaction, OutPoint, Transaction, TxIn};
+
+        let wallet_keys = crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys(
+            "from_unsigned_tx",
+        );
+        let mut tx = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::new(Txid::all_zeros(), 0),
+                ..Default::default()
+            }],
+            output: vec![],
+        };
+        tx.input[0].witness.push([1u8; 64]);
+        let tx_bytes = miniscript::bitcoin::consensus::serialize(&tx);
+        let err =
+            BitGoPsbt::from_unsigned_tx(&tx_bytes, Network::Bitcoin, &wallet_keys).unwrap_err();
+        assert!(err.contains("transaction is signed"), "{}", err);
+        assert!(BitGoPsbt::from_unsigned_tx(&tx_bytes, Network::Zcash, &wallet_keys).is_err());
+    }
+
+    #[test]
+    fn test_parse_input_derivation_mismatch() {
+        use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+        use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+        use crate::fixed_script_wallet::wallet_scripts::path_chain_index;
+
+        let wallet_keys = get_test_wallet_keys("derivation_mismatch");
+        let mut psbt = WalletPsbtBuilder::new(Network::Bitcoin, &wallet_keys)
+            .input(20, 0)
+            .input(20, 1)
+            .output(20, 2, 19_000)
+            .build();
+        let replay_protection = crate::fixed_script_wallet::ReplayProtection::new(vec![]);
+        psbt.parse_transaction_with_wallet_keys(&wallet_keys, &replay_protection, &[])
+            .unwrap();
+
+        // Input 1 spends the script of index 1 but claims the derivation of index 0
+        let expected_script = psbt.psbt().inputs[0]
+            .witness_utxo
+            .as_ref()
+            .unwrap()
+            .script_pubkey
+            .clone();
+        let actual_script = psbt.psbt().inputs[1]
+            .witness_utxo
+            .as_ref()
+            .unwrap()
+            .script_pubkey
+            .clone();
+        let key_origins = psbt.psbt().inputs[0].bip32_derivation.clone();
+        psbt.psbt_mut().inputs[1].bip32_derivation = key_origins;
+
+        match psbt.parse_transaction_with_wallet_keys(&wallet_keys, &replay_protection, &[]) {
+            Err(ParseTransactionError::Input {
+                index: 1,
+                error:
+                    psbt_wallet_input::ParseInputError::DerivationMismatch {
+                        expected_script: expected,
+                        actual_script: actual,
+                        path,
+                    },
+            }) => {
+                assert_eq!(expected, expected_script);
+                assert_eq!(actual, actual_script);
+                assert_eq!(path_chain_index(&path), Some((20, 0)));
+            }
+            other => panic!("expected DerivationMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_outputs_external_script_type() {
+        use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+        use miniscript::bitcoin::blockdata::script::Builder;
+        use miniscript::bitcoin::hashes::Hash;
+        use miniscript::bitcoin::opcodes::all::{OP_CHECKMULTISIG, OP_PUSHNUM_1, OP_PUSHNUM_2};
+        use miniscript::bitcoin::script::PushBytesBuf;
+        use miniscript::bitcoin::{PublicKey, ScriptBuf, WPubkeyHash};
+
+        let wallet_keys = get_test_wallet_keys("external_script_type");
+        let pubkey = PublicKey::new(wallet_keys.user_key().public_key);
+        let p2ms = Builder::new()
+            .push_opcode(OP_PUSHNUM_1)
+            .push_key(&pubkey)
+            .push_key(&pubkey)
+            .push_opcode(OP_PUSHNUM_2)
+            .push_opcode(OP_CHECKMULTISIG)
+            .into_script();
+        // Threshold above the number of keys
+        let invalid_p2ms = Builder::new()
+            .push_opcode(OP_PUSHNUM_2)
+            .push_key(&pubkey)
+            .push_opcode(OP_PUSHNUM_1)
+            .push_opcode(OP_CHECKMULTISIG)
+            .into_script();
+        let data = PushBytesBuf::try_from(b"hello".to_vec()).unwrap();
+
+        let mut psbt = BitGoPsbt::new(Network::Bitcoin, &wallet_keys, None, None);
+        psbt.add_wallet_output(0, 0, 1_000, &wallet_keys).unwrap();
+        for script in [
+            ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()),
+            ScriptBuf::new_p2pk(&pubkey),
+            p2ms,
+            ScriptBuf::new_op_return(&data),
+            invalid_p2ms,
+        ] {
+            psbt.add_output(script, 1_000).unwrap();
+        }
+
+        let outputs = psbt.parse_outputs(&wallet_keys, &[]).unwrap();
+        let types: Vec<_> = outputs.iter().map(|o| o.external_script_type).collect();
+        assert_eq!(
+            types,
+            [
+                None,
+                Some(ExternalScriptType::P2wpkh),
+                Some(ExternalScriptType::P2pk),
+                Some(ExternalScriptType::P2ms),
+                Some(ExternalScriptType::NullData),
+                Some(ExternalScriptType::Unknown),
+            ]
+        );
+        assert!(outputs[2].address.is_none());
+    }
+
+    #[test]
+    fn test_serialize_bitcoin_psbt() {
+        // Test that Bitcoin-like PSBTs can be serialized
+        let fixture = fixtures::load_psbt_fixture_with_network(
+            Network::Bitcoin,
+            fixtures::SignatureState::Unsigned,
+        )
+        .unwrap();
+        let psbt = fixture
+            .to_bitgo_psbt(Network::Bitcoin)
+            .expect("Failed to convert to BitGo PSBT");
+
+        // Serialize should succeed
+        let serialized = psbt.serialize();
+        assert!(serialized.is_ok(), "Serialization should succeed");
+    }
+
+    #[test]
+    fn test_serialize_zcash_psbt() {
+        // Test that Zcash PSBTs can be serialized
+        let fixture = fixtures::load_psbt_fixture_with_format_and_namespace(
+            "zcash",
+            fixtures::SignatureState::Unsigned,
+            fixtures::TxFormat::Psbt,
+            fixtures::FixtureNamespace::UtxolibCompat,
+        )
+        .unwrap();
+        let original_bytes = BASE64_STANDARD
+            .decode(&fixture.psbt_base64)
+            .expect("Failed to decode base64");
+        let psbt = BitGoPsbt::deserialize(&original_bytes, Network::Zcash)
+            .expect("Failed to deserialize PSBT");
+
+        // Serialize should succeed
+        let serialized = psbt.serialize();
+        assert!(serialized.is_ok(), "Serialization should succeed");
+    }
+
+    // Verify that the PsbtAccess::unsigned_tx_id implementation for BitGoPsbt
+    // returns the same txid as BitGoPsbt::unsigned_txid for all networks.
+    //
+    // Regression test for a bug introduced in 4.2.0 where the PsbtAccess trait's
     // default unsigned_tx_id was used for Zcash PSBTs, which hashes only the
     // stripped inner Bitcoin transaction rather than the full Zcash wire format
     // (including versionGroupId, expiryHeight, and empty sapling vectors).
@@ -5098,19 +6689,21 @@ mod tests {
 
         let replay_protection = ReplayProtection::new(replay_protection_scripts);
         let parsed_inputs = original_psbt
-            .parse_inputs(&wallet_keys, &replay_protection)
+            .parse_inputs(
+                &wallet_keys,
+                &replay_protection,
+                ExternalInputPolicy::Reject,
+            )
             .expect("Failed to parse inputs");
 
-        // Parse outputs with main wallet keys
+        // Parse outputs against the main wallet and the other wallet in one pass
         let parsed_outputs = original_psbt
-            .parse_outputs(&wallet_keys, &[])
+            .parse_outputs_with_labeled_wallet_keys(
+                &[("main", &wallet_keys), ("other", &other_wallet_keys)],
+                &[],
+            )
             .expect("Failed to parse outputs");
 
-        // Parse outputs with other wallet keys to identify outputs from different wallet
-        let parsed_outputs_other = original_psbt
-            .parse_outputs(&other_wallet_keys, &[])
-            .expect("Failed to parse outputs with other wallet keys");
-
         // Create empty PSBT with same network parameters as original (handles Zcash automatically)
         let mut reconstructed = BitGoPsbt::new_like(&original_psbt, &wallet_keys)
             .expect("Failed to create PSBT from template");
@@ -5171,7 +6764,7 @@ mod tests {
                     script_id,
                     WalletInputOptions {
                         sign_path,
-                        sequence: Some(sequence),
+                        sequence: Some(SequenceMode::from_consensus(sequence)),
                         prev_tx: prev_tx.as_deref(),
                     },
                 );
@@ -5197,63 +6790,60 @@ mod tests {
                     .as_ref()
                     .map(miniscript::bitcoin::consensus::encode::serialize);
 
-                reconstructed.add_replay_protection_input(
-                    compressed_pubkey,
-                    txid,
-                    vout,
-                    value,
-                    ReplayProtectionOptions {
-                        sequence: Some(sequence),
-                        sighash_type: orig_psbt_input.sighash_type,
-                        prev_tx: prev_tx.as_deref(),
-                    },
-                );
+                reconstructed
+                    .add_replay_protection_input(
+                        compressed_pubkey,
+                        txid,
+                        vout,
+                        value,
+                        ReplayProtectionOptions {
+                            sequence: Some(SequenceMode::from_consensus(sequence)),
+                            sighash_type: orig_psbt_input.sighash_type,
+                            prev_tx: prev_tx.as_deref(),
+                        },
+                    )
+                    .expect("add_replay_protection_input");
             }
         }
 
         // Add outputs using parsed data from both wallet key sets
-        for (output_idx, ((tx_out, parsed_output), parsed_output_other)) in original_tx
+        for (output_idx, (tx_out, parsed)) in original_tx
             .output
             .iter()
             .zip(parsed_outputs.iter())
-            .zip(parsed_outputs_other.iter())
             .enumerate()
         {
-            let value = parsed_output.value;
-
-            if let Some(script_id) = &parsed_output.script_id {
-                // Output belongs to main wallet
-                wallet_output_indices.push(output_idx);
-                let result = reconstructed.add_wallet_output(
-                    script_id.chain,
-                    script_id.index,
-                    value,
-                    &wallet_keys,
-                );
-                assert!(
-                    result.is_ok(),
-                    "Failed to add wallet output {}: {:?}",
-                    output_idx,
-                    result
-                );
-            } else if let Some(script_id) = &parsed_output_other.script_id {
+            let value = parsed.output.value;
+            let output_wallet_keys = match parsed.wallet.as_deref() {
+                Some("main") => Some(&wallet_keys),
                 // Output belongs to other wallet (from seed "too many secrets")
-                wallet_output_indices.push(output_idx);
-                let result = reconstructed.add_wallet_output(
-                    script_id.chain,
-                    script_id.index,
-                    value,
-                    &other_wallet_keys,
-                );
-                assert!(
-                    result.is_ok(),
-                    "Failed to add other wallet output {}: {:?}",
-                    output_idx,
-                    result
-                );
-            } else {
-                // External output - use add_output
-                let _idx = reconstructed.add_output(tx_out.script_pubkey.clone(), value);
+                Some("other") => Some(&other_wallet_keys),
+                _ => None,
+            };
+
+            match (output_wallet_keys, &parsed.output.script_id) {
+                (Some(keys), Some(script_id)) => {
+                    wallet_output_indices.push(output_idx);
+                    let result = reconstructed.add_wallet_output(
+                        script_id.chain,
+                        script_id.index,
+                        value,
+                        keys,
+                    );
+                    assert!(
+                        result.is_ok(),
+                        "Failed to add {:?} wallet output {}: {:?}",
+                        parsed.wallet,
+                        output_idx,
+                        result
+                    );
+                }
+                _ => {
+                    // External output - use add_output
+                    reconstructed
+                        .add_output(tx_out.script_pubkey.clone(), value)
+                        .expect("add_output");
+                }
             }
         }
 
@@ -5525,6 +7115,491 @@ mod tests {
         assert_eq!(decoded.compute_txid(), extracted_tx.compute_txid());
     }
 
+    #[test]
+    fn test_sign_with_privkey_taproot_key_path_with_scripts() {
+        use crate::fixed_script_wallet::test_utils::get_test_wallet_keys;
+        use miniscript::bitcoin::hashes::Hash;
+        use miniscript::bitcoin::opcodes::all::OP_CHECKSIG;
+        use miniscript::bitcoin::script::Builder;
+        use miniscript::bitcoin::sighash::{Prevouts, SighashCache};
+        use miniscript::bitcoin::taproot::{LeafVersion, TaprootBuilder};
+        use miniscript::bitcoin::{Amount, OutPoint, ScriptBuf, TapSighashType, TxIn, TxOut};
+
+        let secp = secp256k1::Secp256k1::new();
+        let internal_privkey = secp256k1::SecretKey::from_slice(&[1; 32]).unwrap();
+        let leaf_privkey = secp256k1::SecretKey::from_slice(&[2; 32]).unwrap();
+        let (internal_key, _) = internal_privkey.x_only_public_key(&secp);
+        let (leaf_key, _) = leaf_privkey.x_only_public_key(&secp);
+        let leaf_script = Builder::new()
+            .push_x_only_key(&leaf_key)
+            .push_opcode(OP_CHECKSIG)
+            .into_script();
+        let spend_info = TaprootBuilder::new()
+            .add_leaf(0, leaf_script.clone())
+            .unwrap()
+            .finalize(&secp, internal_key)
+            .unwrap();
+        let control_block = spend_info
+            .control_block(&(leaf_script.clone(), LeafVersion::TapScript))
+            .unwrap();
+        let witness_utxo = TxOut {
+            value: Amount::from_sat(100_000),
+            script_pubkey: ScriptBuf::new_p2tr(&secp, internal_key, spend_info.merkle_root()),
+        };
+
+        let wallet_keys = RootWalletKeys::new(get_test_wallet_keys("p2tr_key_path_with_scripts"));
+        let mut psbt = BitGoPsbt::new(Network::Bitcoin, &wallet_keys, Some(2), Some(0));
+        psbt.add_wallet_output(0, 0, 90_000, &wallet_keys)
+            .expect("add_wallet_output");
+        let inner = psbt.psbt_mut();
+        inner.unsigned_tx.input.push(TxIn {
+            previous_output: OutPoint::new(Txid::all_zeros(), 0),
+            ..Default::default()
+        });
+        inner.inputs.push(miniscript::bitcoin::psbt::Input {
+            witness_utxo: Some(witness_utxo.clone()),
+            tap_internal_key: Some(internal_key),
+            tap_merkle_root: spend_info.merkle_root(),
+            tap_scripts: std::collections::BTreeMap::from([(
+                control_block,
+                (leaf_script, LeafVersion::TapScript),
+            )]),
+            ..Default::default()
+        });
+
+        // The internal key signs the key path although the input has leaf scripts
+        psbt.sign_with_privkey(0, &internal_privkey)
+            .expect("sign key path");
+        let signature = psbt.psbt().inputs[0]
+            .tap_key_sig
+            .expect("key path signature");
+        assert!(psbt.psbt().inputs[0].tap_script_sigs.is_empty());
+        let sighash = SighashCache::new(&psbt.psbt().unsigned_tx)
+            .taproot_key_spend_signature_hash(
+                0,
+                &Prevouts::All(&[witness_utxo]),
+                TapSighashType::Default,
+            )
+            .unwrap();
+        secp.verify_schnorr(
+            &signature.signature,
+            &secp256k1::Message::from_digest(sighash.to_byte_array()),
+            &spend_info.output_key().to_inner(),
+        )
+        .expect("valid key path signature");
+
+        // The leaf key signs the script path
+        psbt.sign_with_privkey(0, &leaf_privkey)
+            .expect("sign script path");
+        assert_eq!(psbt.psbt().inputs[0].tap_script_sigs.len(), 1);
+    }
+
+    #[test]
+    fn test_sign_with_privkey_taproot_script_path_without_derivation() {
+        use crate::fixed_script_wallet::test_utils::get_test_wallet_keys;
+        use miniscript::bitcoin::bip32::{DerivationPath, Xpriv};
+        use miniscript::bitcoin::hashes::{sha256, Hash};
+        use miniscript::bitcoin::Network as BitcoinNetwork;
+
+        let seed = "p2tr_raw_privkey";
+        let wallet_keys = RootWalletKeys::new(get_test_wallet_keys(seed));
+        let mut psbt = BitGoPsbt::new(Network::Bitcoin, &wallet_keys, Some(2), Some(0));
+
+        psbt.add_wallet_input(
+            Txid::all_zeros(),
+            0,
+            100_000,
+            &wallet_keys,
+            ScriptId {
+                chain: 30,
+                index: 0,
+            },
+            WalletInputOptions {
+                sign_path: Some(psbt_wallet_input::SignPath {
+                    signer: psbt_wallet_input::SignerKey::User,
+                    cosigner: psbt_wallet_input::SignerKey::Backup,
+                }),
+                ..Default::default()
+            },
+        )
+        .expect("add_wallet_input");
+        psbt.add_wallet_output(0, 0, 90_000, &wallet_keys)
+            .expect("add_wallet_output");
+
+        // Simulate a recovery PSBT where only the raw keys are known
+        psbt.psbt_mut().inputs[0].tap_key_origins.clear();
+
+        let secp = secp256k1::Secp256k1::new();
+        let privkey_for = |i: usize| {
+            let seed_hash =
+                sha256::Hash::hash(format!("{}.{}", seed, i).as_bytes()).to_byte_array();
+            Xpriv::new_master(BitcoinNetwork::Testnet, &seed_hash)
+                .unwrap()
+                .derive_priv(&secp, &DerivationPath::from_str("m/0/0/30/0").unwrap())
+                .unwrap()
+                .private_key
+        };
+        let (user_privkey, backup_privkey, bitgo_privkey) =
+            (privkey_for(0), privkey_for(1), privkey_for(2));
+
+        psbt.sign_with_privkey(0, &backup_privkey)
+            .expect("sign with backup privkey");
+        psbt.sign_with_privkey(0, &user_privkey)
+            .expect("sign with user privkey");
+        assert_eq!(psbt.psbt().inputs[0].tap_script_sigs.len(), 2);
+
+        for privkey in [&user_privkey, &backup_privkey] {
+            let pubkey = secp256k1::PublicKey::from_secret_key(&secp, privkey);
+            assert!(psbt.verify_signature_with_pub(&secp, 0, &pubkey).unwrap());
+        }
+
+        // The bitgo key is not part of the user/backup leaf
+        let err = psbt.sign_with_privkey(0, &bitgo_privkey).unwrap_err();
+        assert!(matches!(
+            err,
+            BitGoPsbtError::Signing {
+                input_index: 0,
+                error: SignInputError::KeyNotInTapLeaf
+            }
+        ));
+        assert!(
+            err.to_string().contains("not found in any tap leaf script"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_max_money() {
+        use crate::fixed_script_wallet::test_utils::get_test_wallet_keys;
+
+        let wallet_keys = RootWalletKeys::new(get_test_wallet_keys("max_money"));
+        let max_money = Network::Bitcoin.max_money();
+        assert_eq!(max_money, 2_100_000_000_000_000);
+        assert_eq!(Network::LitecoinTestnet.max_money(), 8_400_000_000_000_000);
+
+        let mut psbt = BitGoPsbt::new(Network::Bitcoin, &wallet_keys, Some(2), Some(0));
+        let script = miniscript::bitcoin::ScriptBuf::new_op_return([0u8; 4]);
+        assert!(psbt.add_output(script.clone(), max_money + 1).is_err());
+        assert!(matches!(
+            psbt.add_wallet_input(
+                Txid::all_zeros(),
+                0,
+                i64::MAX as u64,
+                &wallet_keys,
+                ScriptId {
+                    chain: 20,
+                    index: 0
+                },
+                WalletInputOptions::default(),
+            ),
+            Err(BitGoPsbtError::ValueExceedsMaxMoney { .. })
+        ));
+        assert!(psbt
+            .add_wallet_output(20, 0, u64::MAX, &wallet_keys)
+            .is_err());
+        assert!(psbt.psbt().unsigned_tx.input.is_empty());
+        assert!(psbt.psbt().unsigned_tx.output.is_empty());
+
+        // Values that are valid individually but exceed the maximum in total
+        psbt.add_output(script.clone(), max_money).unwrap();
+        psbt.add_output(script, 1).unwrap();
+        let replay_protection = crate::fixed_script_wallet::ReplayProtection::new(vec![]);
+        assert!(matches!(
+            psbt.parse_transaction_with_wallet_keys(&wallet_keys, &replay_protection, &[]),
+            Err(ParseTransactionError::OutputValueExceedsMaxMoney { index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_bitgo_psbt_errors() {
+        use crate::error::WasmErrorCode;
+        use crate::fixed_script_wallet::test_utils::get_test_wallet_keys;
+
+        let wallet_keys = RootWalletKeys::new(get_test_wallet_keys("bitgo_psbt_errors"));
+        let mut psbt = BitGoPsbt::new(Network::Bitcoin, &wallet_keys, Some(2), Some(0));
+        let add_input = |psbt: &mut BitGoPsbt, index: usize, chain: u32| {
+            psbt.add_wallet_input_at_index(
+                index,
+                Txid::all_zeros(),
+                0,
+                10_000,
+                &wallet_keys,
+                ScriptId { chain, index: 0 },
+                WalletInputOptions::default(),
+            )
+        };
+
+        let err = add_input(&mut psbt, 1, 20).unwrap_err();
+        assert!(matches!(
+            err,
+            BitGoPsbtError::InputIndexOutOfBounds {
+                index: 1,
+                input_count: 0
+            }
+        ));
+        assert_eq!(err.code(), "BitGoPsbtError.InputIndexOutOfBounds");
+        assert!(matches!(
+            add_input(&mut psbt, 0, 2),
+            Err(BitGoPsbtError::InvalidChain(2))
+        ));
+        add_input(&mut psbt, 0, 20).unwrap();
+
+        let secp = secp256k1::Secp256k1::new();
+        let err = psbt.finalize_input(&secp, 0).unwrap_err();
+        assert!(matches!(
+            err,
+            BitGoPsbtError::Finalize {
+                input_index: 0,
+                error: FinalizeInputError::Miniscript(_)
+            }
+        ));
+        assert_eq!(err.input_index(), Some(0));
+        assert!(matches!(
+            psbt.finalize_input(&secp, 1),
+            Err(BitGoPsbtError::InputIndexOutOfBounds { index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_sort_bip69() {
+        use crate::fixed_script_wallet::test_utils::get_test_wallet_keys;
+
+        let wallet_keys = RootWalletKeys::new(get_test_wallet_keys("bip69"));
+        let mut psbt = BitGoPsbt::new(Network::Bitcoin, &wallet_keys, Some(2), Some(0));
+
+        // Displayed txids: "02..", "01..", "01.." (BIP-69 compares the displayed form)
+        let txids = [
+            Txid::from_str(&format!("02{}", "00".repeat(31))).unwrap(),
+            Txid::from_str(&format!("01{}", "00".repeat(31))).unwrap(),
+            Txid::from_str(&format!("01{}", "00".repeat(31))).unwrap(),
+        ];
+        let vouts = [0, 5, 1];
+        for (i, (txid, vout)) in txids.iter().zip(vouts).enumerate() {
+            psbt.add_wallet_input(
+                *txid,
+                vout,
+                10_000,
+                &wallet_keys,
+                ScriptId {
+                    chain: 20,
+                    index: i as u32,
+                },
+                WalletInputOptions::default(),
+            )
+            .unwrap();
+        }
+        for (index, value) in [(0, 3_000), (1, 1_000), (2, 2_000)] {
+            psbt.add_wallet_output(0, index, value, &wallet_keys)
+                .unwrap();
+        }
+
+        let before = psbt.psbt().clone();
+        let permutation = psbt.sort_bip69().unwrap();
+        assert_eq!(permutation.inputs, vec![2, 1, 0]);
+        assert_eq!(permutation.outputs, vec![2, 0, 1]);
+
+        let after = psbt.psbt();
+        for (old, &new) in permutation.inputs.iter().enumerate() {
+            assert_eq!(
+                after.unsigned_tx.input[new].previous_output,
+                before.unsigned_tx.input[old].previous_output
+            );
+            assert_eq!(after.inputs[new], before.inputs[old]);
+        }
+        for (old, &new) in permutation.outputs.iter().enumerate() {
+            assert_eq!(
+                after.unsigned_tx.output[new],
+                before.unsigned_tx.output[old]
+            );
+            assert_eq!(after.outputs[new], before.outputs[old]);
+        }
+
+        // Sorting is idempotent
+        let permutation = psbt.sort_bip69().unwrap();
+        assert_eq!(permutation.inputs, vec![0, 1, 2]);
+        assert_eq!(permutation.outputs, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_sort_bip69_rejects_signed_psbt() {
+        let fixture = fixtures::load_psbt_fixture_with_format_and_namespace(
+            Network::Bitcoin.to_utxolib_name(),
+            fixtures::SignatureState::Halfsigned,
+            fixtures::TxFormat::Psbt,
+            fixtures::FixtureNamespace::UtxolibCompat,
+        )
+        .unwrap();
+        let mut psbt = fixture.to_bitgo_psbt(Network::Bitcoin).unwrap();
+        let err = psbt.sort_bip69().unwrap_err();
+        assert!(err.contains("has signatures"), "{}", err);
+    }
+
+    #[test]
+    fn test_sort_bip69_rejects_musig2_nonces() {
+        use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+        use crate::fixed_script_wallet::wallet_keys::tests::{
+            get_test_wallet_keys, get_test_wallet_xprvs,
+        };
+
+        let wallet_keys = get_test_wallet_keys("bip69_musig2");
+        let xprvs = get_test_wallet_xprvs("bip69_musig2");
+        let mut psbt = WalletPsbtBuilder::new(Network::BitcoinTestnet3, &wallet_keys)
+            .input(40, 0)
+            .output(0, 0, 9_000)
+            .build();
+        psbt.generate_nonce_first_round(0, &xprvs[0], [1u8; 32])
+            .unwrap();
+
+        let err = psbt.sort_bip69().unwrap_err();
+        assert!(err.contains("MuSig2 nonces"), "{}", err);
+        let err = psbt.shuffle_outputs(&[0u8; 32]).unwrap_err();
+        assert!(err.contains("MuSig2 nonces"), "{}", err);
+    }
+
+    #[test]
+    fn test_shuffle_outputs() {
+        use crate::fixed_script_wallet::test_utils::get_test_wallet_keys;
+        use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+
+        let wallet_keys = RootWalletKeys::new(get_test_wallet_keys("shuffle"));
+        let mut builder = WalletPsbtBuilder::new(Network::Bitcoin, &wallet_keys).input_with(
+            20,
+            0,
+            100_000,
+            Default::default(),
+        );
+        for index in 0..8 {
+            builder = builder.output(20, index, 1_000 + u64::from(index));
+        }
+        let mut psbt = builder.build();
+
+        let before = psbt.clone();
+        let permutation = psbt.shuffle_outputs(&[1u8; 32]).unwrap();
+        let mut sorted = permutation.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..8).collect::<Vec<_>>());
+        assert_ne!(permutation, sorted);
+        for (old, &new) in permutation.iter().enumerate() {
+            assert_eq!(
+                psbt.psbt().unsigned_tx.output[new],
+                before.psbt().unsigned_tx.output[old]
+            );
+            assert_eq!(psbt.psbt().outputs[new], before.psbt().outputs[old]);
+        }
+
+        // Same seed, same order
+        let mut replay = before.clone();
+        assert_eq!(replay.shuffle_outputs(&[1u8; 32]).unwrap(), permutation);
+        assert_eq!(
+            replay.psbt().unsigned_tx.compute_txid(),
+            psbt.psbt().unsigned_tx.compute_txid()
+        );
+        let mut other = before.clone();
+        assert_ne!(other.shuffle_outputs(&[2u8; 32]).unwrap(), permutation);
+    }
+
+    #[test]
+    fn test_missing_non_witness_utxos() {
+        use crate::fixed_script_wallet::test_utils::get_test_wallet_keys;
+        use miniscript::bitcoin::hashes::Hash;
+
+        let wallet_keys = RootWalletKeys::new(get_test_wallet_keys("missing_non_witness_utxos"));
+        let prev_tx = miniscript::bitcoin::Transaction {
+            version: miniscript::bitcoin::transaction::Version::ONE,
+            lock_time: miniscript::bitcoin::absolute::LockTime::ZERO,
+            input: vec![miniscript::bitcoin::TxIn::default()],
+            output: vec![miniscript::bitcoin::TxOut::NULL],
+        };
+        let prev_tx_bytes = miniscript::bitcoin::consensus::serialize(&prev_tx);
+        let txid = |n: u8| Txid::from_byte_array([n; 32]);
+
+        let mut psbt = BitGoPsbt::new(Network::Bitcoin, &wallet_keys, Some(2), Some(0));
+        // (chain, has prev tx): p2sh, p2sh with prev tx, p2shP2wsh, p2wsh, p2tr, p2trMusig2
+        let inputs = [
+            (0, false),
+            (0, true),
+            (10, false),
+            (20, false),
+            (30, false),
+            (40, false),
+        ];
+        for (i, (chain, with_prev_tx)) in inputs.into_iter().enumerate() {
+            let options = WalletInputOptions {
+                sign_path: (chain >= 30).then_some(psbt_wallet_input::SignPath {
+                    signer: psbt_wallet_input::SignerKey::User,
+                    cosigner: psbt_wallet_input::SignerKey::Bitgo,
+                }),
+                prev_tx: with_prev_tx.then_some(prev_tx_bytes.as_slice()),
+                ..Default::default()
+            };
+            psbt.add_wallet_input(
+                txid(i as u8),
+                0,
+                10_000,
+                &wallet_keys,
+                ScriptId { chain, index: 0 },
+                options,
+            )
+            .unwrap();
+        }
+        psbt.add_replay_protection_input(
+            CompressedPublicKey(wallet_keys.xpubs[0].public_key),
+            txid(9),
+            0,
+            1_000,
+            Default::default(),
+        )
+        .unwrap();
+
+        let missing: Vec<_> = psbt
+            .missing_non_witness_utxos()
+            .into_iter()
+            .map(|m| (m.input_index, m.txid))
+            .collect();
+        assert_eq!(missing, vec![(0, txid(0)), (6, txid(9))]);
+    }
+
+    #[test]
+    fn test_virtual_size_counts_replay_protection_script_sig() {
+        use crate::fixed_script_wallet::wallet_keys::tests::{
+            get_test_wallet_keys, get_test_wallet_xprvs,
+        };
+        use crate::fixed_script_wallet::wallet_scripts::P2SH_P2PK_SCRIPT_SIG_MAX_SIZE;
+        use crate::fixed_script_wallet::ReplayProtection;
+        use miniscript::bitcoin::hashes::Hash;
+
+        let seed = "rp_virtual_size";
+        let wallet_keys = get_test_wallet_keys(seed);
+        let privkey = get_test_wallet_xprvs(seed)[0].private_key;
+        let pubkey = CompressedPublicKey(wallet_keys.xpubs[0].public_key);
+        let replay_protection = ReplayProtection::from_public_keys(vec![pubkey]);
+
+        let mut psbt = BitGoPsbt::new(Network::BitcoinCash, &wallet_keys, Some(2), Some(0));
+        psbt.add_replay_protection_input(pubkey, Txid::all_zeros(), 0, 10_000, Default::default())
+            .unwrap();
+        psbt.add_wallet_output(0, 0, 9_000, &wallet_keys).unwrap();
+        let unsigned_weight = psbt.psbt().unsigned_tx.weight();
+
+        let parsed = psbt
+            .parse_transaction_with_wallet_keys(&wallet_keys, &replay_protection, &[])
+            .unwrap();
+        assert_eq!(
+            parsed.virtual_size as u64,
+            (unsigned_weight.to_wu() + 4 * P2SH_P2PK_SCRIPT_SIG_MAX_SIZE as u64).div_ceil(4)
+        );
+
+        psbt.sign_all_replay_protection_inputs(&privkey).unwrap();
+        let parsed = psbt
+            .parse_transaction_with_wallet_keys(&wallet_keys, &replay_protection, &[])
+            .unwrap();
+        let tx = psbt
+            .finalize(&secp256k1::Secp256k1::verification_only())
+            .unwrap()
+            .extract_tx_unchecked_fee_rate();
+        assert_eq!(parsed.virtual_size as usize, tx.vsize());
+    }
+
     #[test]
     fn test_get_global_xpubs() {
         use crate::fixed_script_wallet::test_utils::get_test_wallet_keys;
@@ -5542,28 +7617,44 @@ mod tests {
         assert_eq!(sorted_input, sorted_output);
     }
 
+    #[test]
+    fn test_wallet_keys_match() {
+        use crate::fixed_script_wallet::test_utils::get_test_wallet_keys;
+
+        let xpubs = get_test_wallet_keys("test_wallet_keys_match");
+        let wallet_keys = RootWalletKeys::new(xpubs);
+        let other_keys = RootWalletKeys::new(get_test_wallet_keys("test_wallet_keys_other"));
+        let psbt = BitGoPsbt::new(Network::Bitcoin, &wallet_keys, Some(2), Some(0));
+
+        assert!(psbt.wallet_keys_match(&wallet_keys));
+        // Order of the triple does not matter for the global xpub map
+        assert!(psbt.wallet_keys_match(&RootWalletKeys::new([xpubs[2], xpubs[0], xpubs[1]])));
+        assert!(!psbt.wallet_keys_match(&other_keys));
+        // Sharing two of three keys is not a match
+        assert!(!psbt.wallet_keys_match(&RootWalletKeys::new([
+            xpubs[0],
+            xpubs[1],
+            other_keys.xpubs[2]
+        ])));
+
+        let bytes = psbt.serialize().unwrap();
+        BitGoPsbt::deserialize_with_wallet_keys(&bytes, Network::Bitcoin, &wallet_keys).unwrap();
+        assert!(matches!(
+            BitGoPsbt::deserialize_with_wallet_keys(&bytes, Network::Bitcoin, &other_keys),
+            Err(DeserializeError::WalletKeysMismatch)
+        ));
+    }
+
     #[test]
     fn test_to_wallet_keys_canonical_order() {
         use crate::fixed_script_wallet::test_utils::get_test_wallet_keys;
-        use miniscript::bitcoin::hashes::Hash;
+        use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
 
         let xpubs = get_test_wallet_keys("test_to_wallet_keys");
         let wallet_keys = RootWalletKeys::new(xpubs);
-        let mut psbt = BitGoPsbt::new(Network::Bitcoin, &wallet_keys, Some(2), Some(0));
-
-        let txid = Txid::all_zeros();
-        psbt.add_wallet_input(
-            txid,
-            0,
-            100_000,
-            &wallet_keys,
-            ScriptId {
-                chain: 10,
-                index: 0,
-            },
-            WalletInputOptions::default(),
-        )
-        .expect("add_wallet_input");
+        let psbt = WalletPsbtBuilder::new(Network::Bitcoin, &wallet_keys)
+            .input(10, 0)
+            .build();
 
         let result = to_wallet_keys(&psbt, xpubs).expect("should find correct order");
         assert_eq!(result.xpubs, xpubs);
@@ -5572,25 +7663,13 @@ mod tests {
     #[test]
     fn test_to_wallet_keys_shuffled_order() {
         use crate::fixed_script_wallet::test_utils::get_test_wallet_keys;
-        use miniscript::bitcoin::hashes::Hash;
+        use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
 
         let xpubs = get_test_wallet_keys("test_to_wallet_keys_shuffled");
         let wallet_keys = RootWalletKeys::new(xpubs);
-        let mut psbt = BitGoPsbt::new(Network::Bitcoin, &wallet_keys, Some(2), Some(0));
-
-        let txid = Txid::all_zeros();
-        psbt.add_wallet_input(
-            txid,
-            0,
-            100_000,
-            &wallet_keys,
-            ScriptId {
-                chain: 10,
-                index: 0,
-            },
-            WalletInputOptions::default(),
-        )
-        .expect("add_wallet_input");
+        let psbt = WalletPsbtBuilder::new(Network::Bitcoin, &wallet_keys)
+            .input(10, 0)
+            .build();
 
         // Shuffle the xpubs: [bitgo, user, backup] instead of [user, backup, bitgo]
         let shuffled = [xpubs[2], xpubs[0], xpubs[1]];
@@ -5704,5 +7783,103 @@ mod tests {
             ok,
             "Zcash signature over 256-byte (block-aligned) outputs preimage must verify"
         );
+
+        // Full PSBT format: the value comes from non_witness_utxo
+        move_witness_utxo_to_non_witness_utxo(&mut zcash_psbt.psbt, 0);
+        let ok = psbt_wallet_input::verify_ecdsa_signature_zcash(
+            &secp,
+            &zcash_psbt.psbt,
+            0,
+            compressed,
+            consensus_branch_id,
+            version_group_id,
+            expiry_height,
+        )
+        .expect("verify_ecdsa_signature_zcash without witness_utxo");
+        assert!(ok);
+    }
+
+    /// Replace the `witness_utxo` of an input with a previous transaction holding the output
+    ///
+    /// Returns the txid of the previous transaction.
+    fn move_witness_utxo_to_non_witness_utxo(psbt: &mut Psbt, input_index: usize) -> Txid {
+        use miniscript::bitcoin::{
+            absolute::LockTime, transaction::Version, Amount, ScriptBuf, TxIn, TxOut,
+        };
+
+        let prevout = psbt.unsigned_tx.input[input_index].previous_output;
+        let utxo = psbt.inputs[input_index].witness_utxo.take().unwrap();
+        let mut output = vec![
+            TxOut {
+                value: Amount::ZERO,
+                script_pubkey: ScriptBuf::new(),
+            };
+            prevout.vout as usize
+        ];
+        output.push(utxo);
+        let prev_tx = Transaction {
+            version: Version::ONE,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn::default()],
+            output,
+        };
+        let txid = prev_tx.compute_txid();
+        psbt.inputs[input_index].non_witness_utxo = Some(prev_tx);
+        txid
+    }
+
+    #[test]
+    fn test_verify_signature_without_witness_utxo() {
+        use crate::fixed_script_wallet::wallet_keys::tests::{
+            get_test_wallet_keys, get_test_wallet_xprvs,
+        };
+        use miniscript::bitcoin::hashes::Hash;
+
+        let seed = "verify_without_witness_utxo";
+        let wallet_keys = get_test_wallet_keys(seed);
+        let xprvs = get_test_wallet_xprvs(seed);
+        let secp = secp256k1::Secp256k1::new();
+
+        let mut psbt = BitGoPsbt::new(Network::Bitcoin, &wallet_keys, None, None);
+        for (vout, chain) in [(0, 0), (1, 20), (2, 30)] {
+            psbt.add_wallet_input(
+                Txid::all_zeros(),
+                vout,
+                10_000,
+                &wallet_keys,
+                ScriptId { chain, index: 0 },
+                WalletInputOptions {
+                    sign_path: (chain >= 30).then_some(psbt_wallet_input::SignPath {
+                        signer: psbt_wallet_input::SignerKey::User,
+                        cosigner: psbt_wallet_input::SignerKey::Bitgo,
+                    }),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        }
+        psbt.add_wallet_output(20, 1, 29_000, &wallet_keys).unwrap();
+        for input_index in 0..3 {
+            let txid = move_witness_utxo_to_non_witness_utxo(psbt.psbt_mut(), input_index);
+            psbt.psbt_mut().unsigned_tx.input[input_index]
+                .previous_output
+                .txid = txid;
+        }
+
+        psbt.sign_all_with_xpriv(&xprvs[0]).unwrap();
+        for input_index in 0..3 {
+            assert_eq!(
+                psbt.verify_signature_with_xpub(&secp, input_index, &wallet_keys.xpubs[0]),
+                Ok(true),
+                "input {}",
+                input_index
+            );
+            assert_eq!(
+                psbt.verify_signature_with_xpub(&secp, input_index, &wallet_keys.xpubs[1]),
+                Ok(false),
+                "input {}",
+                input_index
+            );
+        }
     }
 }
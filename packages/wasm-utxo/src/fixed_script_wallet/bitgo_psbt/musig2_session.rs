@@ -0,0 +1,414 @@
+//! Encrypted persistence of MuSig2 signing sessions
+//!
+//! A `musig2::FirstRound` holds a secret nonce and cannot be serialized. It is however
+//! derived deterministically from the session id, the signer key and the sighash of the
+//! input, so a session can be persisted as the list of session ids and rebuilt later with
+//! [`BitGoPsbt::restore_nonce_first_round`].
+//!
+//! The session ids are stored in a passphrase-encrypted envelope:
+//!
+//! ```text
+//! magic "BGM2" | version (1) | iterations (u32 LE) | salt (16) | nonce (24)
+//!   | ciphertext | tag (16)
+//! ```
+//!
+//! The key is derived from the passphrase with PBKDF2-HMAC-SHA256 and the session is
+//! encrypted with XChaCha20-Poly1305. The header is authenticated as associated data.
+//!
+//! Restoring a session more than once and signing with different counterparty nonces leaks
+//! the private key. A session is not restored for a signer whose partial signature is
+//! already in the PSBT, but callers must still make sure each exported session is used at
+//! most once.
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use miniscript::bitcoin::bip32::{Xpriv, Xpub};
+use miniscript::bitcoin::hashes::Hash;
+use miniscript::bitcoin::Txid;
+use sha2::Sha256;
+
+use super::p2tr_musig2_input::Musig2Input;
+use super::BitGoPsbt;
+
+const MAGIC: &[u8; 4] = b"BGM2";
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const TAG_LEN: usize = 16;
+const HEADER_LEN: usize = MAGIC.len() + 1 + 4 + SALT_LEN + NONCE_LEN;
+const ENTRY_LEN: usize = 4 + 78 + 32;
+
+/// Default number of PBKDF2 iterations used by [`Musig2Session::encrypt`]
+pub const DEFAULT_ITERATIONS: u32 = 100_000;
+/// Upper bound on the iteration count accepted when decrypting
+const MAX_ITERATIONS: u32 = 10_000_000;
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum Musig2SessionError {
+    /// The envelope is truncated or malformed
+    InvalidEnvelope(String),
+    /// The envelope was written by an unknown version
+    UnsupportedVersion(u8),
+    /// Wrong passphrase or tampered envelope
+    AuthenticationFailed,
+    /// The session belongs to a different transaction
+    TxidMismatch { expected: Txid, actual: Txid },
+    /// The rebuilt nonce is not the one stored in the PSBT
+    NonceMismatch { input_index: usize },
+    /// The signer has already signed the input with this session
+    AlreadySigned { input_index: usize },
+    /// The session of the input has already been imported into this PSBT
+    AlreadyImported { input_index: usize },
+    /// Failed to generate randomness or rebuild the nonce
+    Musig2(String),
+}
+
+impl std::fmt::Display for Musig2SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Musig2SessionError::InvalidEnvelope(msg) => {
+                write!(f, "Invalid MuSig2 session envelope: {}", msg)
+            }
+            Musig2SessionError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported MuSig2 session version {}", version)
+            }
+            Musig2SessionError::AuthenticationFailed => write!(
+                f,
+                "Failed to decrypt MuSig2 session: wrong passphrase or corrupted data"
+            ),
+            Musig2SessionError::TxidMismatch { expected, actual } => write!(
+                f,
+                "MuSig2 session is for transaction {}, but the PSBT is for {}",
+                actual, expected
+            ),
+            Musig2SessionError::NonceMismatch { input_index } => write!(
+                f,
+                "MuSig2 session does not match the nonce of input {}",
+                input_index
+            ),
+            Musig2SessionError::AlreadySigned { input_index } => write!(
+                f,
+                "MuSig2 session of input {} was already used for signing",
+                input_index
+            ),
+            Musig2SessionError::AlreadyImported { input_index } => write!(
+                f,
+                "MuSig2 session of input {} was already imported",
+                input_index
+            ),
+            Musig2SessionError::Musig2(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Musig2SessionError {}
+
+crate::impl_wasm_error_code!(Musig2SessionError);
+crate::impl_from_coded_error!(Musig2SessionError);
+
+/// Session id used to generate the nonce of one signer for one input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Musig2SessionEntry {
+    pub input_index: usize,
+    pub xpub: Xpub,
+    pub session_id: [u8; 32],
+}
+
+/// Persistable MuSig2 signing session of a transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Musig2Session {
+    pub txid: Txid,
+    pub entries: Vec<Musig2SessionEntry>,
+}
+
+fn session_cipher(passphrase: &str, salt: &[u8], iterations: u32) -> XChaCha20Poly1305 {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    XChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+impl Musig2Session {
+    fn to_plaintext(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(32 + 4 + self.entries.len() * ENTRY_LEN);
+        out.extend_from_slice(self.txid.as_byte_array());
+        out.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+        for entry in &self.entries {
+            out.extend_from_slice(&(entry.input_index as u32).to_le_bytes());
+            out.extend_from_slice(&entry.xpub.encode());
+            out.extend_from_slice(&entry.session_id);
+        }
+        out
+    }
+
+    fn from_plaintext(bytes: &[u8]) -> Result<Self, Musig2SessionError> {
+        let invalid = |msg: &str| Musig2SessionError::InvalidEnvelope(msg.to_string());
+        if bytes.len() < 36 {
+            return Err(invalid("session data too short"));
+        }
+        let txid = Txid::from_byte_array(bytes[..32].try_into().unwrap());
+        let count = u32::from_le_bytes(bytes[32..36].try_into().unwrap()) as usize;
+        let body = &bytes[36..];
+        if body.len() != count * ENTRY_LEN {
+            return Err(invalid("unexpected session data length"));
+        }
+        let entries = body
+            .chunks(ENTRY_LEN)
+            .map(|chunk| {
+                let input_index = u32::from_le_bytes(chunk[..4].try_into().unwrap()) as usize;
+                let xpub = Xpub::decode(&chunk[4..82]).map_err(|e| invalid(&e.to_string()))?;
+                let session_id = chunk[82..].try_into().unwrap();
+                Ok(Musig2SessionEntry {
+                    input_index,
+                    xpub,
+                    session_id,
+                })
+            })
+            .collect::<Result<Vec<_>, Musig2SessionError>>()?;
+        Ok(Musig2Session { txid, entries })
+    }
+
+    /// Encrypt the session with a passphrase, using a random salt and nonce
+    pub fn encrypt(&self, passphrase: &str) -> Result<Vec<u8>, Musig2SessionError> {
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        getrandom::getrandom(&mut salt)
+            .and_then(|_| getrandom::getrandom(&mut nonce))
+            .map_err(|e| {
+                Musig2SessionError::Musig2(format!("Failed to generate randomness: {}", e))
+            })?;
+        Ok(self.encrypt_with_params(passphrase, salt, nonce, DEFAULT_ITERATIONS))
+    }
+
+    /// Encrypt the session with an explicit salt, nonce and PBKDF2 iteration count
+    pub fn encrypt_with_params(
+        &self,
+        passphrase: &str,
+        salt: [u8; SALT_LEN],
+        nonce: [u8; NONCE_LEN],
+        iterations: u32,
+    ) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.extend_from_slice(&iterations.to_le_bytes());
+        out.extend_from_slice(&salt);
+        out.extend_from_slice(&nonce);
+        let ciphertext = session_cipher(passphrase, &salt, iterations)
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &self.to_plaintext(),
+                    aad: &out,
+                },
+            )
+            // Only fails for plaintexts longer than 256 GiB
+            .expect("XChaCha20-Poly1305 encryption");
+        out.extend_from_slice(&ciphertext);
+        out
+    }
+
+    /// Decrypt a session produced by [`Musig2Session::encrypt`]
+    pub fn decrypt(bytes: &[u8], passphrase: &str) -> Result<Self, Musig2SessionError> {
+        if bytes.len() < HEADER_LEN + TAG_LEN || &bytes[..4] != MAGIC {
+            return Err(Musig2SessionError::InvalidEnvelope(
+                "not a MuSig2 session envelope".to_string(),
+            ));
+        }
+        if bytes[4] != VERSION {
+            return Err(Musig2SessionError::UnsupportedVersion(bytes[4]));
+        }
+        let iterations = u32::from_le_bytes(bytes[5..9].try_into().unwrap());
+        if iterations == 0 || iterations > MAX_ITERATIONS {
+            return Err(Musig2SessionError::InvalidEnvelope(format!(
+                "invalid iteration count {}",
+                iterations
+            )));
+        }
+        let (header, ciphertext) = bytes.split_at(HEADER_LEN);
+        let salt = &header[9..9 + SALT_LEN];
+        let nonce = &header[9 + SALT_LEN..];
+
+        let plaintext = session_cipher(passphrase, salt, iterations)
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: header,
+                },
+            )
+            .map_err(|_| Musig2SessionError::AuthenticationFailed)?;
+        Self::from_plaintext(&plaintext)
+    }
+}
+
+impl BitGoPsbt {
+    /// Check that a session was created for this PSBT's unsigned transaction
+    pub fn check_musig2_session(&self, session: &Musig2Session) -> Result<(), Musig2SessionError> {
+        let expected = self.psbt().unsigned_tx.compute_txid();
+        if session.txid != expected {
+            return Err(Musig2SessionError::TxidMismatch {
+                expected,
+                actual: session.txid,
+            });
+        }
+        Ok(())
+    }
+
+    /// Rebuild the FirstRound of a MuSig2 input from its session id
+    ///
+    /// The nonce derived from `session_id` must already be present in the PSBT, which
+    /// ensures that the transaction has not changed since the nonce was generated, and the
+    /// signer must not have signed the input yet. The PSBT itself is not modified.
+    pub fn restore_nonce_first_round(
+        &self,
+        input_index: usize,
+        xpriv: &Xpriv,
+        session_id: [u8; 32],
+    ) -> Result<musig2::FirstRound, Musig2SessionError> {
+        let input = self.psbt().inputs.get(input_index).ok_or_else(|| {
+            Musig2SessionError::Musig2(format!("Input index {} out of bounds", input_index))
+        })?;
+        let musig2_input = Musig2Input::from_input(input)
+            .map_err(|e| Musig2SessionError::Musig2(e.to_string()))?;
+
+        let mut scratch = self.clone();
+        let (first_round, pub_nonce) = scratch
+            .generate_nonce_first_round(input_index, xpriv, session_id)
+            .map_err(Musig2SessionError::Musig2)?;
+
+        let pub_nonce = pub_nonce.serialize();
+        let nonce = musig2_input
+            .nonces
+            .iter()
+            .find(|n| n.pub_nonce.serialize() == pub_nonce)
+            .ok_or(Musig2SessionError::NonceMismatch { input_index })?;
+        if musig2_input
+            .partial_sigs
+            .iter()
+            .any(|sig| sig.participant_pub_key == nonce.participant_pub_key)
+        {
+            return Err(Musig2SessionError::AlreadySigned { input_index });
+        }
+        Ok(first_round)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::{
+        get_test_wallet_keys, get_test_wallet_xprvs,
+    };
+    use crate::networks::Network;
+    use miniscript::bitcoin::secp256k1::Secp256k1;
+    use miniscript::bitcoin::ScriptBuf;
+
+    fn musig2_psbt() -> (BitGoPsbt, Xpriv) {
+        let keys = get_test_wallet_keys("musig2_session");
+        let xprvs = get_test_wallet_xprvs("musig2_session");
+        let mut psbt = WalletPsbtBuilder::new(Network::BitcoinTestnet3, &keys)
+            .input(40, 0)
+            .build();
+        psbt.add_output(ScriptBuf::new_op_return([0u8; 4]), 0)
+            .unwrap();
+        (psbt, xprvs[0])
+    }
+
+    fn session(psbt: &BitGoPsbt, xpriv: &Xpriv) -> Musig2Session {
+        Musig2Session {
+            txid: psbt.psbt().unsigned_tx.compute_txid(),
+            entries: vec![Musig2SessionEntry {
+                input_index: 0,
+                xpub: Xpub::from_priv(&Secp256k1::new(), xpriv),
+                session_id: [7u8; 32],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_encrypt_roundtrip() {
+        let (psbt, xpriv) = musig2_psbt();
+        let session = session(&psbt, &xpriv);
+        let encrypted =
+            session.encrypt_with_params("hunter2", [1u8; SALT_LEN], [2u8; NONCE_LEN], 10);
+        assert_eq!(
+            Musig2Session::decrypt(&encrypted, "hunter2").unwrap(),
+            session
+        );
+        assert!(matches!(
+            Musig2Session::decrypt(&encrypted, "hunter3"),
+            Err(Musig2SessionError::AuthenticationFailed)
+        ));
+
+        for index in [9, HEADER_LEN - 1, HEADER_LEN, encrypted.len() - 1] {
+            let mut tampered = encrypted.clone();
+            tampered[index] ^= 1;
+            assert!(matches!(
+                Musig2Session::decrypt(&tampered, "hunter2"),
+                Err(Musig2SessionError::AuthenticationFailed)
+            ));
+        }
+        assert!(matches!(
+            Musig2Session::decrypt(&encrypted[..HEADER_LEN], "hunter2"),
+            Err(Musig2SessionError::InvalidEnvelope(_))
+        ));
+    }
+
+    #[test]
+    fn test_restore_first_round() {
+        let (mut psbt, xpriv) = musig2_psbt();
+        let session = session(&psbt, &xpriv);
+        psbt.check_musig2_session(&session).unwrap();
+
+        // No nonce in the PSBT yet
+        assert!(matches!(
+            psbt.restore_nonce_first_round(0, &xpriv, [7u8; 32]),
+            Err(Musig2SessionError::NonceMismatch { input_index: 0 })
+        ));
+
+        let (first_round, _) = psbt
+            .generate_nonce_first_round(0, &xpriv, [7u8; 32])
+            .unwrap();
+        let restored = psbt
+            .restore_nonce_first_round(0, &xpriv, [7u8; 32])
+            .unwrap();
+        assert_eq!(
+            restored.our_public_nonce().serialize(),
+            first_round.our_public_nonce().serialize()
+        );
+        assert!(matches!(
+            psbt.restore_nonce_first_round(0, &xpriv, [8u8; 32]),
+            Err(Musig2SessionError::NonceMismatch { input_index: 0 })
+        ));
+
+        psbt.add_output(ScriptBuf::new_op_return([1u8; 4]), 0)
+            .unwrap();
+        assert!(matches!(
+            psbt.check_musig2_session(&session),
+            Err(Musig2SessionError::TxidMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_restore_after_signing() {
+        let (mut psbt, xpriv) = musig2_psbt();
+        let bitgo_xpriv = get_test_wallet_xprvs("musig2_session")[2];
+        let (first_round, _) = psbt
+            .generate_nonce_first_round(0, &xpriv, [7u8; 32])
+            .unwrap();
+        psbt.generate_nonce_first_round(0, &bitgo_xpriv, [9u8; 32])
+            .unwrap();
+        psbt.restore_nonce_first_round(0, &xpriv, [7u8; 32])
+            .unwrap();
+
+        psbt.sign_with_first_round(0, first_round, &xpriv).unwrap();
+        assert!(matches!(
+            psbt.restore_nonce_first_round(0, &xpriv, [7u8; 32]),
+            Err(Musig2SessionError::AlreadySigned { input_index: 0 })
+        ));
+        // The cosigner has not signed yet
+        psbt.restore_nonce_first_round(0, &bitgo_xpriv, [9u8; 32])
+            .unwrap();
+    }
+}
@@ -0,0 +1,192 @@
+//! Audit of ECDSA signature nonces
+//!
+//! Signers derive ECDSA nonces deterministically from the private key and the sighash
+//! (RFC 6979). A backdoored signer could instead pick nonces that leak the private key to a
+//! third party through the published signatures. Given the private key, the expected nonce
+//! can be recomputed and compared with the R value of the published signature.
+
+use miniscript::bitcoin::psbt::GetKey;
+use miniscript::bitcoin::secp256k1;
+
+use super::fixed_script_input::FixedScriptInput;
+use super::BitGoPsbt;
+
+/// How the nonce of a signature was derived
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::IntoStaticStr)]
+#[strum(serialize_all = "camelCase")]
+pub enum NonceDerivation {
+    /// Plain RFC 6979
+    Rfc6979,
+    /// RFC 6979 with extra entropy, ground until R is low (see [`super::low_r`])
+    Rfc6979LowR,
+}
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum NonceAuditError {
+    InputIndexOutOfBounds {
+        input_index: usize,
+        input_count: usize,
+    },
+    /// The input has no ECDSA signature made by the given key
+    NoSignature {
+        input_index: usize,
+    },
+    Sighash(String),
+}
+
+impl std::fmt::Display for NonceAuditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NonceAuditError::InputIndexOutOfBounds {
+                input_index,
+                input_count,
+            } => write!(
+                f,
+                "Input index {} out of bounds (total inputs: {})",
+                input_index, input_count
+            ),
+            NonceAuditError::NoSignature { input_index } => write!(
+                f,
+                "Input {} has no ECDSA signature made by the given key",
+                input_index
+            ),
+            NonceAuditError::Sighash(e) => write!(f, "Failed to compute sighash: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for NonceAuditError {}
+
+crate::impl_wasm_error_code!(NonceAuditError);
+
+/// R value of a signature
+fn r_value(signature: &secp256k1::ecdsa::Signature) -> [u8; 32] {
+    let mut r = [0u8; 32];
+    r.copy_from_slice(&signature.serialize_compact()[..32]);
+    r
+}
+
+impl BitGoPsbt {
+    /// Check that the ECDSA signature made by `k` on an input uses a deterministic nonce
+    ///
+    /// The private key is looked up in `k` by the key origins of the input or by public key,
+    /// like when signing. Taproot (Schnorr) signatures are not covered: BIP-340 nonces mix in
+    /// auxiliary randomness and cannot be recomputed.
+    ///
+    /// # Returns
+    /// - `Ok(Some(derivation))` if the R value of the signature matches the nonce derived
+    ///   with RFC 6979, with or without low-R grinding
+    /// - `Ok(None)` if the signature uses any other nonce
+    pub fn audit_signature_nonce<C, K>(
+        &self,
+        secp: &secp256k1::Secp256k1<C>,
+        input_index: usize,
+        k: &K,
+    ) -> Result<Option<NonceDerivation>, NonceAuditError>
+    where
+        C: secp256k1::Signing,
+        K: GetKey,
+    {
+        let psbt = self.psbt();
+        let input = psbt
+            .inputs
+            .get(input_index)
+            .ok_or(NonceAuditError::InputIndexOutOfBounds {
+                input_index,
+                input_count: psbt.inputs.len(),
+            })?;
+        let (secret_key, signature) = input
+            .partial_sigs
+            .iter()
+            .find_map(|(pubkey, signature)| {
+                self.get_signing_key(k, secp, input_index, pubkey)
+                    .map(|secret_key| (secret_key, signature.signature))
+            })
+            .ok_or(NonceAuditError::NoSignature { input_index })?;
+
+        let ctx = self.sighash_context().map_err(NonceAuditError::Sighash)?;
+        let message = FixedScriptInput::compute_compact_sighash(psbt, input_index, &ctx)
+            .map_err(NonceAuditError::Sighash)?;
+
+        let r = r_value(&signature);
+        if r == r_value(&secp.sign_ecdsa(&message, &secret_key)) {
+            Ok(Some(NonceDerivation::Rfc6979))
+        } else if r == r_value(&secp.sign_ecdsa_low_r(&message, &secret_key)) {
+            Ok(Some(NonceDerivation::Rfc6979LowR))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::{
+        get_test_wallet_keys, get_test_wallet_xprvs,
+    };
+    use crate::networks::Network;
+
+    fn unsigned_psbt() -> BitGoPsbt {
+        let keys = get_test_wallet_keys("nonce_audit");
+        WalletPsbtBuilder::new(Network::Bitcoin, &keys)
+            .inputs(8, 20)
+            .build()
+    }
+
+    #[test]
+    fn test_audit_signature_nonce() {
+        let secp = secp256k1::Secp256k1::new();
+        let xprvs = get_test_wallet_xprvs("nonce_audit");
+
+        let mut ground = unsigned_psbt();
+        ground.sign(&xprvs[0], &secp).unwrap();
+        ground.grind_low_r(&xprvs[0], &secp, 0..8);
+        // Low-R signatures either needed no grinding or match the ground nonce
+        for input_index in 0..8 {
+            assert!(ground
+                .audit_signature_nonce(&secp, input_index, &xprvs[0])
+                .unwrap()
+                .is_some());
+        }
+
+        let mut psbt = unsigned_psbt();
+        psbt.sign(&xprvs[0], &secp).unwrap();
+        for input_index in 0..8 {
+            assert_eq!(
+                psbt.audit_signature_nonce(&secp, input_index, &xprvs[0])
+                    .unwrap(),
+                Some(NonceDerivation::Rfc6979)
+            );
+        }
+
+        // Replace a signature with one made with a different nonce
+        let pubkey = *psbt.psbt().inputs[0].partial_sigs.keys().next().unwrap();
+        let secret_key = psbt.get_signing_key(&xprvs[0], &secp, 0, &pubkey).unwrap();
+        let ctx = psbt.sighash_context().unwrap();
+        let message = FixedScriptInput::compute_compact_sighash(psbt.psbt(), 0, &ctx).unwrap();
+        let signature = secp.sign_ecdsa_with_noncedata(&message, &secret_key, &[42; 32]);
+        assert!(secp
+            .verify_ecdsa(&message, &signature, &pubkey.inner)
+            .is_ok());
+        psbt.psbt_mut().inputs[0]
+            .partial_sigs
+            .get_mut(&pubkey)
+            .unwrap()
+            .signature = signature;
+        assert_eq!(
+            psbt.audit_signature_nonce(&secp, 0, &xprvs[0]).unwrap(),
+            None
+        );
+
+        assert!(matches!(
+            psbt.audit_signature_nonce(&secp, 0, &xprvs[1]),
+            Err(NonceAuditError::NoSignature { input_index: 0 })
+        ));
+        assert!(matches!(
+            psbt.audit_signature_nonce(&secp, 8, &xprvs[0]),
+            Err(NonceAuditError::InputIndexOutOfBounds { .. })
+        ));
+    }
+}
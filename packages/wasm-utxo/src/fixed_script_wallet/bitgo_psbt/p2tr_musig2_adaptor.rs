@@ -0,0 +1,386 @@
+//! MuSig2 adaptor signatures for taproot keypath spends
+//!
+//! An adaptor signature is a Schnorr signature encrypted under an adaptor point `T = t·G`.
+//! It can be verified against `T` without knowing `t`, turned into a valid signature with
+//! `t`, and once the final signature is published, `t` can be recovered from it. This is
+//! the building block of atomic swaps: the spend of one chain reveals the secret that
+//! completes the spend on the other chain.
+//!
+//! The participants run the regular MuSig2 nonce exchange, then sign with
+//! [`BitGoPsbt::sign_musig2_adaptor_with_first_round`] instead of
+//! `sign_with_first_round`. Adaptor partial signatures are returned to the caller rather
+//! than stored in the PSBT, since they do not aggregate into a valid signature.
+
+use miniscript::bitcoin::bip32::{Xpriv, Xpub};
+use miniscript::bitcoin::hashes::Hash;
+use miniscript::bitcoin::secp256k1::{self, schnorr, PublicKey, SecretKey};
+use miniscript::bitcoin::sighash::{Prevouts, SighashCache, TapSighashType};
+use miniscript::bitcoin::taproot::{self, TapNodeHash};
+use miniscript::bitcoin::CompressedPublicKey;
+use musig2::{AggNonce, BinaryEncoding, KeyAggContext, LiftedSignature, PartialSignature};
+
+pub use musig2::AdaptorSignature;
+
+use super::p2tr_musig2_input::{
+    collect_prevouts, derive_xpriv_for_input_tap, get_tap_sighash_type, Musig2Context, Musig2Error,
+};
+use super::propkv::is_musig2_key;
+use super::BitGoPsbt;
+
+/// Signing session of a MuSig2 keypath input, as seen by every participant
+struct AdaptorSession {
+    key_agg_ctx: KeyAggContext,
+    agg_nonce: AggNonce,
+    message: [u8; 32],
+    sighash_type: TapSighashType,
+}
+
+fn to_point(pubkey: &PublicKey) -> Result<musig2::secp::Point, Musig2Error> {
+    musig2::secp::Point::try_from(&pubkey.serialize()[..])
+        .map_err(|e| Musig2Error::SignatureAggregation(format!("Invalid adaptor point: {}", e)))
+}
+
+fn to_scalar(secret: &SecretKey) -> Result<musig2::secp::Scalar, Musig2Error> {
+    musig2::secp::Scalar::try_from(&secret.secret_bytes()[..])
+        .map_err(|e| Musig2Error::SignatureAggregation(format!("Invalid secret key: {}", e)))
+}
+
+impl Musig2Context<'_> {
+    fn adaptor_session(&self) -> Result<AdaptorSession, Musig2Error> {
+        let input = &self.psbt.inputs[self.input_index];
+        if self.musig2_input.nonces.len() < 2 {
+            return Err(Musig2Error::MissingNonces);
+        }
+
+        let tap_merkle_root = input
+            .tap_merkle_root
+            .unwrap_or_else(|| TapNodeHash::from_byte_array([0u8; 32]));
+        let key_agg_ctx = KeyAggContext::new(self.musig2_input.get_participant_pubkeys()?)
+            .map_err(|e| {
+                Musig2Error::SignatureAggregation(format!(
+                    "Failed to create key agg context: {}",
+                    e
+                ))
+            })?
+            .with_taproot_tweak(&tap_merkle_root.to_byte_array())
+            .map_err(|e| {
+                Musig2Error::SignatureAggregation(format!("Failed to apply taproot tweak: {}", e))
+            })?;
+
+        let sighash_type = get_tap_sighash_type(input);
+        let prevouts = collect_prevouts(self.psbt)?;
+        let sighash = SighashCache::new(&self.psbt.unsigned_tx)
+            .taproot_key_spend_signature_hash(
+                self.input_index,
+                &Prevouts::All(&prevouts),
+                sighash_type,
+            )
+            .map_err(|e| {
+                Musig2Error::SignatureAggregation(format!("Failed to compute sighash: {}", e))
+            })?;
+
+        Ok(AdaptorSession {
+            key_agg_ctx,
+            agg_nonce: AggNonce::sum(self.musig2_input.get_pub_nonces()),
+            message: sighash.to_byte_array(),
+            sighash_type,
+        })
+    }
+
+    /// Create an adaptor partial signature encrypted under `adaptor_point`
+    pub fn sign_adaptor_with_first_round(
+        &self,
+        mut first_round: musig2::FirstRound,
+        xpriv: &Xpriv,
+        adaptor_point: &PublicKey,
+    ) -> Result<PartialSignature, Musig2Error> {
+        let session = self.adaptor_session()?;
+
+        let tap_key_origins = &self.psbt.inputs[self.input_index].tap_key_origins;
+        let derived_xpriv = derive_xpriv_for_input_tap(xpriv, tap_key_origins).map_err(|e| {
+            Musig2Error::SignatureAggregation(format!("Failed to derive xpriv: {}", e))
+        })?;
+        let secp = secp256k1::Secp256k1::new();
+        let signer_pub_key = Xpub::from_priv(&secp, &derived_xpriv).to_pub();
+        self.receive_other_nonces(&mut first_round, &signer_pub_key)?;
+
+        let second_round = first_round
+            .finalize_adaptor(
+                to_scalar(&derived_xpriv.private_key)?,
+                to_point(adaptor_point)?,
+                session.message,
+            )
+            .map_err(|e| {
+                Musig2Error::SignatureAggregation(format!("Failed to finalize FirstRound: {}", e))
+            })?;
+        Ok(second_round.our_signature())
+    }
+
+    /// Verify the adaptor partial signature of a participant
+    pub fn verify_adaptor_partial_signature(
+        &self,
+        participant_pub_key: &CompressedPublicKey,
+        partial_sig: PartialSignature,
+        adaptor_point: &PublicKey,
+    ) -> Result<(), Musig2Error> {
+        let session = self.adaptor_session()?;
+        let pub_nonce = self
+            .musig2_input
+            .nonces
+            .iter()
+            .find(|nonce| nonce.participant_pub_key == *participant_pub_key)
+            .ok_or(Musig2Error::MissingNonces)?;
+        let participant_point = musig2::secp::Point::try_from(&participant_pub_key.to_bytes()[..])
+            .map_err(|e| {
+                Musig2Error::SignatureAggregation(format!("Invalid participant key: {}", e))
+            })?;
+
+        musig2::adaptor::verify_partial(
+            &session.key_agg_ctx,
+            partial_sig,
+            &session.agg_nonce,
+            to_point(adaptor_point)?,
+            participant_point,
+            &pub_nonce.pub_nonce,
+            session.message,
+        )
+        .map_err(|e| {
+            Musig2Error::SignatureAggregation(format!("Invalid adaptor partial signature: {}", e))
+        })
+    }
+
+    /// Aggregate the adaptor partial signatures of all participants
+    ///
+    /// The aggregated adaptor signature is verified against the taproot output key.
+    pub fn aggregate_adaptor_signatures(
+        &self,
+        partial_sigs: &[PartialSignature],
+        adaptor_point: &PublicKey,
+    ) -> Result<AdaptorSignature, Musig2Error> {
+        let session = self.adaptor_session()?;
+        let adaptor_sig = musig2::adaptor::aggregate_partial_signatures(
+            &session.key_agg_ctx,
+            &session.agg_nonce,
+            to_point(adaptor_point)?,
+            partial_sigs.iter().copied(),
+            session.message,
+        )
+        .map_err(|e| {
+            Musig2Error::SignatureAggregation(format!("Signature aggregation failed: {}", e))
+        })?;
+        self.verify_adaptor_signature(&adaptor_sig, adaptor_point)?;
+        Ok(adaptor_sig)
+    }
+
+    /// Verify an aggregated adaptor signature against the taproot output key
+    pub fn verify_adaptor_signature(
+        &self,
+        adaptor_sig: &AdaptorSignature,
+        adaptor_point: &PublicKey,
+    ) -> Result<(), Musig2Error> {
+        let session = self.adaptor_session()?;
+        let aggregated_pubkey: musig2::secp::Point = session.key_agg_ctx.aggregated_pubkey();
+        musig2::adaptor::verify_single(
+            aggregated_pubkey,
+            adaptor_sig,
+            session.message,
+            to_point(adaptor_point)?,
+        )
+        .map_err(|e| Musig2Error::SignatureAggregation(format!("Invalid adaptor signature: {}", e)))
+    }
+
+    /// Decrypt an adaptor signature with the adaptor secret and set it as `tap_key_sig`
+    ///
+    /// The MuSig2 proprietary fields are removed, so the input can be finalized like any
+    /// single-key taproot keypath input.
+    pub fn complete_adaptor_signature(
+        &mut self,
+        adaptor_sig: &AdaptorSignature,
+        adaptor_secret: &SecretKey,
+    ) -> Result<taproot::Signature, Musig2Error> {
+        let session = self.adaptor_session()?;
+        let final_sig: LiftedSignature =
+            adaptor_sig
+                .adapt(to_scalar(adaptor_secret)?)
+                .ok_or_else(|| {
+                    Musig2Error::SignatureAggregation(
+                        "Adaptor secret yields no signature".to_string(),
+                    )
+                })?;
+        let aggregated_pubkey: musig2::secp::Point = session.key_agg_ctx.aggregated_pubkey();
+        musig2::verify_single(aggregated_pubkey, final_sig, session.message).map_err(|_| {
+            Musig2Error::SignatureAggregation(
+                "Adaptor secret does not match the adaptor point".to_string(),
+            )
+        })?;
+
+        let signature = taproot::Signature {
+            signature: schnorr::Signature::from_slice(&final_sig.to_bytes()).map_err(|e| {
+                Musig2Error::SignatureAggregation(format!("Invalid schnorr signature: {}", e))
+            })?,
+            sighash_type: session.sighash_type,
+        };
+        let input = &mut self.psbt.inputs[self.input_index];
+        input.tap_key_sig = Some(signature);
+        input.proprietary.retain(|key, _| !is_musig2_key(key));
+        Ok(signature)
+    }
+}
+
+/// Recover the adaptor secret from an adaptor signature and the published final signature
+///
+/// Returns `None` if the final signature was not produced from the adaptor signature.
+pub fn reveal_adaptor_secret(
+    adaptor_sig: &AdaptorSignature,
+    final_sig: &schnorr::Signature,
+) -> Option<SecretKey> {
+    let final_sig = LiftedSignature::from_bytes(final_sig.as_ref()).ok()?;
+    let secret = adaptor_sig.reveal_secret(&final_sig)?.not_zero().ok()?;
+    SecretKey::from_slice(&secret.serialize()).ok()
+}
+
+impl BitGoPsbt {
+    /// Create an adaptor partial signature for a MuSig2 keypath input
+    ///
+    /// Same as `sign_with_first_round`, but the partial signature is encrypted under
+    /// `adaptor_point` and returned instead of being stored in the PSBT.
+    pub fn sign_musig2_adaptor_with_first_round(
+        &mut self,
+        input_index: usize,
+        first_round: musig2::FirstRound,
+        xpriv: &Xpriv,
+        adaptor_point: &PublicKey,
+    ) -> Result<PartialSignature, String> {
+        self.musig2_context(input_index)?
+            .sign_adaptor_with_first_round(first_round, xpriv, adaptor_point)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Verify the adaptor partial signature of a participant of a MuSig2 keypath input
+    pub fn verify_musig2_adaptor_partial_signature(
+        &mut self,
+        input_index: usize,
+        participant_pub_key: &CompressedPublicKey,
+        partial_sig: PartialSignature,
+        adaptor_point: &PublicKey,
+    ) -> Result<(), String> {
+        self.musig2_context(input_index)?
+            .verify_adaptor_partial_signature(participant_pub_key, partial_sig, adaptor_point)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Aggregate the adaptor partial signatures of a MuSig2 keypath input
+    pub fn aggregate_musig2_adaptor_signatures(
+        &mut self,
+        input_index: usize,
+        partial_sigs: &[PartialSignature],
+        adaptor_point: &PublicKey,
+    ) -> Result<AdaptorSignature, String> {
+        self.musig2_context(input_index)?
+            .aggregate_adaptor_signatures(partial_sigs, adaptor_point)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Verify an aggregated adaptor signature of a MuSig2 keypath input
+    pub fn verify_musig2_adaptor_signature(
+        &mut self,
+        input_index: usize,
+        adaptor_sig: &AdaptorSignature,
+        adaptor_point: &PublicKey,
+    ) -> Result<(), String> {
+        self.musig2_context(input_index)?
+            .verify_adaptor_signature(adaptor_sig, adaptor_point)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Decrypt an adaptor signature of a MuSig2 keypath input with the adaptor secret
+    ///
+    /// Sets the final signature as `tap_key_sig`; the input can then be finalized.
+    pub fn complete_musig2_adaptor_signature(
+        &mut self,
+        input_index: usize,
+        adaptor_sig: &AdaptorSignature,
+        adaptor_secret: &SecretKey,
+    ) -> Result<taproot::Signature, String> {
+        self.musig2_context(input_index)?
+            .complete_adaptor_signature(adaptor_sig, adaptor_secret)
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::bitgo_psbt::p2tr_musig2_input::Musig2Input;
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::{
+        get_test_wallet_keys, get_test_wallet_xprvs,
+    };
+    use crate::Network;
+
+    #[test]
+    fn test_musig2_adaptor_signature() {
+        let seed = "musig2_adaptor";
+        let wallet_keys = get_test_wallet_keys(seed);
+        let xprvs = get_test_wallet_xprvs(seed);
+        let (user, bitgo) = (&xprvs[0], &xprvs[2]);
+        let mut psbt = WalletPsbtBuilder::new(Network::Bitcoin, &wallet_keys)
+            .input(40, 0)
+            .output(41, 0, 9_000)
+            .build();
+        assert!(Musig2Input::is_musig2_input(&psbt.psbt().inputs[0]));
+
+        let secp = secp256k1::Secp256k1::new();
+        let adaptor_secret = SecretKey::from_slice(&[7u8; 32]).unwrap();
+        let adaptor_point = adaptor_secret.public_key(&secp);
+
+        let (user_round, _) = psbt.generate_nonce_first_round(0, user, [1; 32]).unwrap();
+        let (bitgo_round, _) = psbt.generate_nonce_first_round(0, bitgo, [2; 32]).unwrap();
+        let user_sig = psbt
+            .sign_musig2_adaptor_with_first_round(0, user_round, user, &adaptor_point)
+            .unwrap();
+        let bitgo_sig = psbt
+            .sign_musig2_adaptor_with_first_round(0, bitgo_round, bitgo, &adaptor_point)
+            .unwrap();
+
+        let musig2_input = Musig2Input::from_input(&psbt.psbt().inputs[0]).unwrap();
+        let user_pub_key = musig2_input.participants.participant_pub_keys[0];
+        psbt.verify_musig2_adaptor_partial_signature(0, &user_pub_key, user_sig, &adaptor_point)
+            .unwrap();
+        assert!(psbt
+            .verify_musig2_adaptor_partial_signature(0, &user_pub_key, bitgo_sig, &adaptor_point)
+            .is_err());
+
+        let adaptor_sig = psbt
+            .aggregate_musig2_adaptor_signatures(0, &[user_sig, bitgo_sig], &adaptor_point)
+            .unwrap();
+        psbt.verify_musig2_adaptor_signature(0, &adaptor_sig, &adaptor_point)
+            .unwrap();
+        let other_point = SecretKey::from_slice(&[8u8; 32]).unwrap().public_key(&secp);
+        assert!(psbt
+            .verify_musig2_adaptor_signature(0, &adaptor_sig, &other_point)
+            .is_err());
+
+        // Without the adaptor secret, the signature cannot be completed
+        let wrong_secret = SecretKey::from_slice(&[8u8; 32]).unwrap();
+        assert!(psbt
+            .clone()
+            .complete_musig2_adaptor_signature(0, &adaptor_sig, &wrong_secret)
+            .is_err());
+
+        let final_sig = psbt
+            .complete_musig2_adaptor_signature(0, &adaptor_sig, &adaptor_secret)
+            .unwrap();
+        assert_eq!(psbt.psbt().inputs[0].tap_key_sig, Some(final_sig));
+        psbt.finalize_input(&secp, 0).unwrap();
+        psbt.verify_input_scripts(&secp).unwrap();
+        // Spent through the key path: the witness is the aggregated signature only
+        let witness = psbt.psbt().inputs[0].final_script_witness.as_ref().unwrap();
+        assert_eq!(witness.len(), 1);
+
+        assert_eq!(
+            reveal_adaptor_secret(&adaptor_sig, &final_sig.signature),
+            Some(adaptor_secret)
+        );
+    }
+}
@@ -10,7 +10,7 @@ use crate::fixed_script_wallet::wallet_scripts::bitgo_musig::key_agg_p2tr_musig2
 use miniscript::bitcoin::hashes::{hex, Hash};
 use miniscript::bitcoin::{
     bip32::{KeySource, Xpriv, Xpub},
-    psbt::Input,
+    psbt::{Input, Output},
     secp256k1, Psbt, TapLeafHash, XOnlyPublicKey,
 };
 use musig2::PubNonce;
@@ -463,11 +463,28 @@ impl Musig2PartialSig {
 ///
 /// Returns `None` if no participant data is found.
 pub fn parse_musig2_participants(input: &Input) -> Result<Option<Musig2Participants>, Musig2Error> {
-    let kvs: Vec<_> = find_kv(
-        ProprietaryKeySubtype::Musig2ParticipantPubKeys,
-        &input.proprietary,
-    )
-    .collect::<Vec<_>>();
+    parse_musig2_participants_from_map(&input.proprietary)
+}
+
+/// Parse MuSig2 participants from a PSBT output
+///
+/// p2trMusig2 wallet outputs carry the same participant data as inputs, so that the
+/// receiver can check the aggregate key without deriving the wallet keys.
+/// Returns `None` if no participant data is found.
+pub fn parse_musig2_output_participants(
+    output: &Output,
+) -> Result<Option<Musig2Participants>, Musig2Error> {
+    parse_musig2_participants_from_map(&output.proprietary)
+}
+
+fn parse_musig2_participants_from_map(
+    proprietary: &std::collections::BTreeMap<
+        miniscript::bitcoin::psbt::raw::ProprietaryKey,
+        Vec<u8>,
+    >,
+) -> Result<Option<Musig2Participants>, Musig2Error> {
+    let kvs: Vec<_> =
+        find_kv(ProprietaryKeySubtype::Musig2ParticipantPubKeys, proprietary).collect::<Vec<_>>();
 
     if kvs.is_empty() {
         return Ok(None);
@@ -862,29 +879,15 @@ impl<'a> Musig2Context<'a> {
         self.sign_with_first_round_impl(first_round, xpriv, sighash.to_byte_array(), sighash_type)
     }
 
-    /// Internal implementation of MuSig2 signing given a pre-computed sighash message.
-    fn sign_with_first_round_impl(
-        &mut self,
-        mut first_round: musig2::FirstRound,
-        xpriv: &Xpriv,
-        message: [u8; 32],
-        sighash_type: crate::bitcoin::sighash::TapSighashType,
+    /// Pass the public nonces of all participants other than `signer_pub_key` to `first_round`
+    pub(super) fn receive_other_nonces(
+        &self,
+        first_round: &mut musig2::FirstRound,
+        signer_pub_key: &CompressedPublicKey,
     ) -> Result<(), Musig2Error> {
-        use crate::bitcoin::bip32::Xpub;
-
-        // Derive the signer's key for this input
-        let tap_key_origins = &self.psbt.inputs[self.input_index].tap_key_origins;
-        let derived_xpriv = derive_xpriv_for_input_tap(xpriv, tap_key_origins).map_err(|e| {
-            Musig2Error::SignatureAggregation(format!("Failed to derive xpriv: {}", e))
-        })?;
-        let secp = secp256k1::Secp256k1::new();
-        let derived_xpub = Xpub::from_priv(&secp, &derived_xpriv);
-        let signer_pub_key = derived_xpub.to_pub();
-
         // Get signer index to know which nonces to receive
-        let signer_index = self.musig2_input.get_signer_index(&signer_pub_key)?;
+        let signer_index = self.musig2_input.get_signer_index(signer_pub_key)?;
 
-        // Receive nonces from all other participants
         for nonce_data in &self.musig2_input.nonces {
             let nonce_signer_index = self
                 .musig2_input
@@ -902,6 +905,30 @@ impl<'a> Musig2Context<'a> {
                     })?;
             }
         }
+        Ok(())
+    }
+
+    /// Internal implementation of MuSig2 signing given a pre-computed sighash message.
+    fn sign_with_first_round_impl(
+        &mut self,
+        mut first_round: musig2::FirstRound,
+        xpriv: &Xpriv,
+        message: [u8; 32],
+        sighash_type: crate::bitcoin::sighash::TapSighashType,
+    ) -> Result<(), Musig2Error> {
+        use crate::bitcoin::bip32::Xpub;
+
+        // Derive the signer's key for this input
+        let tap_key_origins = &self.psbt.inputs[self.input_index].tap_key_origins;
+        let derived_xpriv = derive_xpriv_for_input_tap(xpriv, tap_key_origins).map_err(|e| {
+            Musig2Error::SignatureAggregation(format!("Failed to derive xpriv: {}", e))
+        })?;
+        let secp = secp256k1::Secp256k1::new();
+        let derived_xpub = Xpub::from_priv(&secp, &derived_xpriv);
+        let signer_pub_key = derived_xpub.to_pub();
+
+        // Receive nonces from all other participants
+        self.receive_other_nonces(&mut first_round, &signer_pub_key)?;
 
         // Convert secret key to scalar
         let secret_scalar =
@@ -925,27 +952,10 @@ impl<'a> Musig2Context<'a> {
 
 /// Collect all prevouts (funding outputs) from PSBT inputs
 ///
-/// This helper extracts the TxOut for each input from either witness_utxo or non_witness_utxo.
+/// Like [`super::psbt_wallet_input::collect_prevouts`], with a MuSig2 error.
 /// Required for computing sighashes in taproot transactions.
 pub(crate) fn collect_prevouts(psbt: &Psbt) -> Result<Vec<crate::bitcoin::TxOut>, Musig2Error> {
-    let tx = &psbt.unsigned_tx;
-    psbt.inputs
-        .iter()
-        .enumerate()
-        .map(|(i, input)| {
-            if let Some(witness_utxo) = &input.witness_utxo {
-                Ok(witness_utxo.clone())
-            } else if let Some(non_witness_utxo) = &input.non_witness_utxo {
-                let output_index = tx.input[i].previous_output.vout as usize;
-                Ok(non_witness_utxo.output[output_index].clone())
-            } else {
-                Err(Musig2Error::SignatureAggregation(format!(
-                    "Missing UTXO data for input {}",
-                    i
-                )))
-            }
-        })
-        .collect()
+    super::psbt_wallet_input::collect_prevouts(psbt).map_err(Musig2Error::SignatureAggregation)
 }
 
 impl Musig2Input {
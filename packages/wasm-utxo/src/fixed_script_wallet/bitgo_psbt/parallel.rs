@@ -0,0 +1,255 @@
+//! Parallel signing and finalization
+//!
+//! [`BitGoPsbt::sign_all_with_xpriv_parallel`] and [`BitGoPsbt::finalize_mut_parallel`] split
+//! the inputs into one shard per thread. Each shard is processed on its own copy of the PSBT
+//! and the inputs of the shard are moved back afterwards, so the result is the same as with
+//! [`BitGoPsbt::sign_all_with_xpriv`] and [`BitGoPsbt::finalize_mut`].
+//!
+//! Shards only run concurrently with the `parallel` feature, on the rayon thread pool. In a
+//! wasm build, the pool has more than one thread only if the module is built with atomics and
+//! the host has started the web workers (e.g. with `initThreadPool` of wasm-bindgen-rayon),
+//! which requires `SharedArrayBuffer`. With a single thread, or without the feature, both
+//! methods fall back to the sequential implementation.
+//!
+//! Like the sequential methods, the shards sign without low-R grinding. Callers that grind
+//! pass the key to [`BitGoPsbt::grind_low_r`] once the shards are merged, so no setting has
+//! to reach the worker threads.
+
+use std::ops::Range;
+
+use miniscript::bitcoin::bip32::Xpriv;
+use miniscript::bitcoin::psbt::{SigningErrors, SigningKeysMap};
+use miniscript::bitcoin::secp256k1;
+
+use super::BitGoPsbt;
+
+/// Number of threads available for processing shards
+fn num_threads() -> usize {
+    #[cfg(feature = "parallel")]
+    {
+        rayon::current_num_threads()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        1
+    }
+}
+
+/// Split `0..total` into at most `num_shards` contiguous ranges of similar size
+fn shards(total: usize, num_shards: usize) -> Vec<Range<usize>> {
+    let size = total.div_ceil(num_shards.max(1)).max(1);
+    (0..total)
+        .step_by(size)
+        .map(|start| start..(start + size).min(total))
+        .collect()
+}
+
+impl BitGoPsbt {
+    /// Process each shard on a copy of the PSBT, then move the inputs of the shard back
+    ///
+    /// `f` must only modify the inputs of the range it is called with.
+    fn process_shards<T, F>(&mut self, shards: Vec<Range<usize>>, f: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(&mut BitGoPsbt, Range<usize>) -> T + Send + Sync,
+    {
+        let this = &*self;
+        let process = |range: Range<usize>| {
+            let mut shard = this.clone();
+            let result = f(&mut shard, range.clone());
+            (shard, range, result)
+        };
+
+        #[cfg(feature = "parallel")]
+        let processed: Vec<_> = {
+            use rayon::prelude::*;
+            shards.into_par_iter().map(process).collect()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let processed: Vec<_> = shards.into_iter().map(process).collect();
+
+        processed
+            .into_iter()
+            .map(|(mut shard, range, result)| {
+                self.psbt_mut().inputs[range.clone()]
+                    .swap_with_slice(&mut shard.psbt_mut().inputs[range]);
+                result
+            })
+            .collect()
+    }
+
+    /// Like [`BitGoPsbt::sign_all_with_xpriv`], signing the inputs on all available threads
+    ///
+    /// Each thread works on a copy of the PSBT, so memory use grows with the number of
+    /// threads. Falls back to [`BitGoPsbt::sign_all_with_xpriv`] with a single thread.
+    pub fn sign_all_with_xpriv_parallel(
+        &mut self,
+        xpriv: &Xpriv,
+    ) -> Result<SigningKeysMap, String> {
+        self.sign_all_with_xpriv_sharded(xpriv, num_threads())
+    }
+
+    fn sign_all_with_xpriv_sharded(
+        &mut self,
+        xpriv: &Xpriv,
+        num_shards: usize,
+    ) -> Result<SigningKeysMap, String> {
+        let shards = shards(self.psbt().inputs.len(), num_shards);
+        if shards.len() <= 1 {
+            return self.sign_all_with_xpriv(xpriv);
+        }
+
+        let secp = secp256k1::Secp256k1::new();
+        let results = self.process_shards(shards, |shard, range| {
+            shard.sign_input_range(xpriv, &secp, range)
+        });
+
+        let mut signing_keys = SigningKeysMap::new();
+        let mut errors = SigningErrors::new();
+        for result in results {
+            match result {
+                Ok(keys) => signing_keys.extend(keys),
+                Err((keys, shard_errors)) => {
+                    signing_keys.extend(keys);
+                    errors.extend(shard_errors);
+                }
+            }
+        }
+        let result = if errors.is_empty() {
+            Ok(signing_keys)
+        } else {
+            Err((signing_keys, errors))
+        };
+        self.filter_sign_all_errors(result)
+    }
+
+    /// Like [`BitGoPsbt::finalize_mut`], finalizing the inputs on all available threads
+    ///
+    /// Errors are reported in input order, as with [`BitGoPsbt::finalize_mut`].
+    pub fn finalize_mut_parallel<C: secp256k1::Verification>(
+        &mut self,
+        secp: &secp256k1::Secp256k1<C>,
+    ) -> Result<(), Vec<String>> {
+        self.finalize_mut_sharded(secp, num_threads())
+    }
+
+    fn finalize_mut_sharded<C: secp256k1::Verification>(
+        &mut self,
+        secp: &secp256k1::Secp256k1<C>,
+        num_shards: usize,
+    ) -> Result<(), Vec<String>> {
+        let shards = shards(self.psbt().inputs.len(), num_shards);
+        if shards.len() <= 1 {
+            return self.finalize_mut(secp);
+        }
+
+        let errors: Vec<String> = self
+            .process_shards(shards, |shard, range| {
+                range
+                    .filter_map(|index| {
+                        shard
+                            .finalize_input(secp, index)
+                            .err()
+                            .map(|e| format!("Input {}: {}", index, e))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .into_iter()
+            .flatten()
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::{
+        get_test_wallet_keys, get_test_wallet_xprvs,
+    };
+    use crate::fixed_script_wallet::RootWalletKeys;
+    use crate::Network;
+
+    const SEED: &str = "parallel";
+
+    fn wallet_psbt(wallet_keys: &RootWalletKeys) -> BitGoPsbt {
+        let mut builder = WalletPsbtBuilder::new(Network::Bitcoin, wallet_keys);
+        for (index, chain) in [0, 10, 20, 0, 10, 20, 0].into_iter().enumerate() {
+            builder = builder.input(chain, index as u32);
+        }
+        builder.output(20, 0, 1_000).build()
+    }
+
+    #[test]
+    fn test_shards() {
+        assert_eq!(shards(7, 3), vec![0..3, 3..6, 6..7]);
+        assert_eq!(shards(2, 4), vec![0..1, 1..2]);
+        assert_eq!(shards(5, 0), vec![0..5]);
+        assert!(shards(0, 4).is_empty());
+    }
+
+    #[test]
+    fn test_sharded_matches_sequential() {
+        let xprvs = get_test_wallet_xprvs(SEED);
+        let wallet_keys = get_test_wallet_keys(SEED);
+        let secp = secp256k1::Secp256k1::new();
+
+        let mut expected = wallet_psbt(&wallet_keys);
+        let expected_errors = expected.clone().finalize_mut(&secp);
+        let user_keys = expected.sign_all_with_xpriv(&xprvs[0]).unwrap();
+        let bitgo_keys = expected.sign_all_with_xpriv(&xprvs[2]).unwrap();
+        let mut finalized = expected.clone();
+        finalized.finalize_mut(&secp).unwrap();
+
+        for num_shards in [2, 3, 7] {
+            // Finalizing an unsigned PSBT reports the same errors in the same order
+            let mut psbt = wallet_psbt(&wallet_keys);
+            assert_eq!(
+                psbt.finalize_mut_sharded(&secp, num_shards),
+                expected_errors
+            );
+
+            let mut psbt = wallet_psbt(&wallet_keys);
+            assert_eq!(
+                psbt.sign_all_with_xpriv_sharded(&xprvs[0], num_shards)
+                    .unwrap(),
+                user_keys
+            );
+            assert_eq!(
+                psbt.sign_all_with_xpriv_sharded(&xprvs[2], num_shards)
+                    .unwrap(),
+                bitgo_keys
+            );
+            assert_eq!(psbt.psbt(), expected.psbt());
+
+            psbt.finalize_mut_sharded(&secp, num_shards).unwrap();
+            assert_eq!(psbt.psbt(), finalized.psbt());
+        }
+    }
+
+    #[test]
+    fn test_sharded_matches_sequential_with_grinding() {
+        let xprvs = get_test_wallet_xprvs(SEED);
+        let wallet_keys = get_test_wallet_keys(SEED);
+        let secp = secp256k1::Secp256k1::new();
+
+        let mut expected = wallet_psbt(&wallet_keys);
+        let user_keys = expected.sign_all_with_xpriv(&xprvs[0]).unwrap();
+        expected.grind_low_r(&xprvs[0], &secp, user_keys.keys().copied());
+
+        for num_shards in [2, 3, 7] {
+            let mut psbt = wallet_psbt(&wallet_keys);
+            let keys = psbt
+                .sign_all_with_xpriv_sharded(&xprvs[0], num_shards)
+                .unwrap();
+            psbt.grind_low_r(&xprvs[0], &secp, keys.keys().copied());
+            assert_eq!(psbt.psbt(), expected.psbt());
+        }
+    }
+}
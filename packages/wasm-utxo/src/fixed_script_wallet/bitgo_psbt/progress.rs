@@ -0,0 +1,392 @@
+//! Progress reporting and cancellation for operations over all inputs
+//!
+//! Signing, finalizing and parsing PSBTs with thousands of inputs can take a while.
+//! The `*_with_progress` methods process inputs in chunks of [`InputProgress::interval`],
+//! report the number of processed inputs after each chunk and check the
+//! [`CancellationToken`] before the next one.
+//!
+//! A cancelled operation leaves every input either fully processed or untouched.
+//! Signing and finalizing can be resumed with [`InputProgress::starting_at`], passing the
+//! last reported number of processed inputs.
+
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use miniscript::bitcoin::bip32::Xpriv;
+use miniscript::bitcoin::psbt::{SigningErrors, SigningKeysMap};
+use miniscript::bitcoin::secp256k1;
+
+use super::{BitGoPsbt, ExternalInputPolicy, ParseTransactionError, ParsedTransaction};
+use crate::fixed_script_wallet::{ReplayProtection, RootWalletKeys};
+
+/// Shared flag to abort a long-running operation between two chunks of inputs
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The operation was cancelled after processing `processed` of `total` inputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperationCancelled {
+    pub processed: usize,
+    pub total: usize,
+}
+
+impl std::fmt::Display for OperationCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Operation cancelled after {} of {} inputs",
+            self.processed, self.total
+        )
+    }
+}
+
+impl std::error::Error for OperationCancelled {}
+
+/// Error of a `*_with_progress` method
+#[derive(Debug)]
+pub enum ProgressError<E> {
+    Cancelled(OperationCancelled),
+    Failed(E),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for ProgressError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProgressError::Cancelled(e) => write!(f, "{}", e),
+            ProgressError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl<E> From<OperationCancelled> for ProgressError<E> {
+    fn from(e: OperationCancelled) -> Self {
+        ProgressError::Cancelled(e)
+    }
+}
+
+/// Invokes a callback with `(processed, total)` every `interval` inputs
+pub struct InputProgress<'a> {
+    interval: usize,
+    callback: &'a mut dyn FnMut(usize, usize),
+    cancellation: Option<CancellationToken>,
+    start: usize,
+}
+
+impl<'a> InputProgress<'a> {
+    /// An `interval` of 0 is treated as 1
+    pub fn new(interval: usize, callback: &'a mut dyn FnMut(usize, usize)) -> Self {
+        Self {
+            interval: interval.max(1),
+            callback,
+            cancellation: None,
+            start: 0,
+        }
+    }
+
+    /// Abort the operation before the next chunk once `token` is cancelled
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Skip the first `start` inputs when signing or finalizing
+    ///
+    /// Parsing always starts at the first input.
+    pub fn starting_at(mut self, start: usize) -> Self {
+        self.start = start;
+        self
+    }
+
+    pub fn interval(&self) -> usize {
+        self.interval
+    }
+
+    /// Input index ranges of at most `interval` inputs, covering `start..total`
+    pub(crate) fn chunks(&self, start: usize, total: usize) -> impl Iterator<Item = Range<usize>> {
+        let interval = self.interval;
+        (start.min(total)..total)
+            .step_by(interval)
+            .map(move |start| start..(start + interval).min(total))
+    }
+
+    pub(crate) fn report(&mut self, processed: usize, total: usize) {
+        (self.callback)(processed, total);
+    }
+
+    pub(crate) fn check_cancelled(
+        &self,
+        processed: usize,
+        total: usize,
+    ) -> Result<(), OperationCancelled> {
+        match &self.cancellation {
+            Some(token) if token.is_cancelled() => Err(OperationCancelled { processed, total }),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl BitGoPsbt {
+    /// Like [`BitGoPsbt::sign_all_with_xpriv`], reporting progress after each chunk of inputs
+    ///
+    /// Each chunk is a separate signing pass in which only the key origins of the inputs in
+    /// the chunk are visible to the signer.
+    pub fn sign_all_with_xpriv_with_progress(
+        &mut self,
+        xpriv: &Xpriv,
+        progress: &mut InputProgress<'_>,
+    ) -> Result<SigningKeysMap, ProgressError<String>> {
+        let secp = secp256k1::Secp256k1::new();
+        let total = self.psbt().inputs.len();
+        let mut signing_keys = SigningKeysMap::new();
+        for range in progress.chunks(progress.start, total) {
+            progress.check_cancelled(range.start, total)?;
+            let end = range.end;
+            let result = self.sign_input_range(xpriv, &secp, range);
+            signing_keys.extend(
+                self.filter_sign_all_errors(result)
+                    .map_err(ProgressError::Failed)?,
+            );
+            progress.report(end, total);
+        }
+        Ok(signing_keys)
+    }
+
+    pub(super) fn sign_input_range<C: secp256k1::Signing + secp256k1::Verification>(
+        &mut self,
+        xpriv: &Xpriv,
+        secp: &secp256k1::Secp256k1<C>,
+        range: Range<usize>,
+    ) -> Result<SigningKeysMap, (SigningKeysMap, SigningErrors)> {
+        // Without key origins the signer finds no key to request for an input
+        let hidden: Vec<_> = self
+            .psbt_mut()
+            .inputs
+            .iter_mut()
+            .enumerate()
+            .filter(|(index, _)| !range.contains(index))
+            .map(|(index, input)| {
+                (
+                    index,
+                    std::mem::take(&mut input.bip32_derivation),
+                    std::mem::take(&mut input.tap_key_origins),
+                )
+            })
+            .collect();
+
+        let result = self.sign(xpriv, secp);
+
+        let inputs = &mut self.psbt_mut().inputs;
+        for (index, bip32_derivation, tap_key_origins) in hidden {
+            inputs[index].bip32_derivation = bip32_derivation;
+            inputs[index].tap_key_origins = tap_key_origins;
+        }
+
+        let in_range = |index: &usize| range.contains(index);
+        match result {
+            Ok(keys) => Ok(keys.into_iter().filter(|(i, _)| in_range(i)).collect()),
+            Err((keys, errors)) => Err((
+                keys.into_iter().filter(|(i, _)| in_range(i)).collect(),
+                errors.into_iter().filter(|(i, _)| in_range(i)).collect(),
+            )),
+        }
+    }
+
+    /// Like [`BitGoPsbt::finalize_mut`], reporting progress every `interval` inputs
+    ///
+    /// Errors of inputs finalized before a cancellation are discarded.
+    pub fn finalize_mut_with_progress<C: secp256k1::Verification>(
+        &mut self,
+        secp: &secp256k1::Secp256k1<C>,
+        progress: &mut InputProgress<'_>,
+    ) -> Result<(), ProgressError<Vec<String>>> {
+        let total = self.psbt().inputs.len();
+        let mut errors = Vec::new();
+        for range in progress.chunks(progress.start, total) {
+            progress.check_cancelled(range.start, total)?;
+            let end = range.end;
+            for index in range {
+                if let Err(e) = self.finalize_input(secp, index) {
+                    errors.push(format!("Input {}: {}", index, e));
+                }
+            }
+            progress.report(end, total);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ProgressError::Failed(errors))
+        }
+    }
+
+    /// Like [`BitGoPsbt::parse_transaction_with_wallet_keys_and_input_policy`], reporting
+    /// progress every `interval` parsed inputs
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "parse",
+            skip_all,
+            fields(inputs = self.psbt().inputs.len(), outputs = self.psbt().outputs.len())
+        )
+    )]
+    pub fn parse_transaction_with_progress(
+        &self,
+        wallet_keys: &RootWalletKeys,
+        replay_protection: &ReplayProtection,
+        paygo_pubkeys: &[secp256k1::PublicKey],
+        external_policy: ExternalInputPolicy,
+        progress: &mut InputProgress<'_>,
+    ) -> Result<ParsedTransaction, ProgressError<ParseTransactionError>> {
+        let total = self.psbt().inputs.len();
+        let mut parsed_inputs = Vec::with_capacity(total);
+        for range in progress.chunks(0, total) {
+            progress.check_cancelled(range.start, total)?;
+            let end = range.end;
+            for index in range {
+                parsed_inputs.push(
+                    self.parse_input(index, wallet_keys, replay_protection, external_policy)
+                        .map_err(ProgressError::Failed)?,
+                );
+            }
+            progress.report(end, total);
+        }
+        self.parse_transaction_with_parsed_inputs(parsed_inputs, wallet_keys, paygo_pubkeys)
+            .map_err(ProgressError::Failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::{
+        get_test_wallet_keys, get_test_wallet_xprvs,
+    };
+    use crate::Network;
+
+    const SEED: &str = "progress";
+
+    #[test]
+    fn test_chunks() {
+        let mut callback = |_, _| {};
+        let progress = InputProgress::new(2, &mut callback);
+        let chunks: Vec<_> = progress.chunks(0, 5).collect();
+        assert_eq!(chunks, vec![0..2, 2..4, 4..5]);
+        assert_eq!(progress.chunks(3, 5).collect::<Vec<_>>(), vec![3..5]);
+        assert_eq!(progress.chunks(0, 0).count(), 0);
+        assert_eq!(progress.chunks(7, 5).count(), 0);
+        assert_eq!(InputProgress::new(0, &mut callback).interval(), 1);
+    }
+
+    fn wallet_psbt(wallet_keys: &RootWalletKeys, num_inputs: u32) -> BitGoPsbt {
+        let mut builder = WalletPsbtBuilder::new(Network::Bitcoin, wallet_keys);
+        for index in 0..num_inputs {
+            builder = builder.input(10, index);
+        }
+        builder.output(20, 0, 1_000).build()
+    }
+
+    #[test]
+    fn test_progress_matches_single_pass() {
+        let xprvs = get_test_wallet_xprvs(SEED);
+        let wallet_keys = get_test_wallet_keys(SEED);
+        let mut psbt = wallet_psbt(&wallet_keys, 5);
+        let mut expected = psbt.clone();
+        let replay_protection = ReplayProtection::new(vec![]);
+
+        let mut reports = Vec::new();
+        let mut callback = |processed, total| reports.push((processed, total));
+        let mut progress = InputProgress::new(2, &mut callback);
+
+        let parsed = psbt
+            .parse_transaction_with_progress(
+                &wallet_keys,
+                &replay_protection,
+                &[],
+                ExternalInputPolicy::Reject,
+                &mut progress,
+            )
+            .unwrap();
+        assert_eq!(parsed.inputs.len(), 5);
+
+        let signing_keys = psbt
+            .sign_all_with_xpriv_with_progress(&xprvs[0], &mut progress)
+            .unwrap();
+        assert_eq!(signing_keys.len(), 5);
+        psbt.sign_all_with_xpriv_with_progress(&xprvs[2], &mut progress)
+            .unwrap();
+        psbt.finalize_mut_with_progress(&secp256k1::Secp256k1::new(), &mut progress)
+            .unwrap();
+
+        expected.sign_all_with_xpriv(&xprvs[0]).unwrap();
+        expected.sign_all_with_xpriv(&xprvs[2]).unwrap();
+        expected.finalize_mut(&secp256k1::Secp256k1::new()).unwrap();
+        assert_eq!(psbt.extract_tx().unwrap(), expected.extract_tx().unwrap());
+
+        let per_pass = [(2, 5), (4, 5), (5, 5)];
+        assert_eq!(reports, per_pass.repeat(4));
+    }
+
+    #[test]
+    fn test_cancel_and_resume() {
+        let xprvs = get_test_wallet_xprvs(SEED);
+        let wallet_keys = get_test_wallet_keys(SEED);
+        let mut psbt = wallet_psbt(&wallet_keys, 5);
+        let mut expected = psbt.clone();
+
+        // Cancel from the callback after the first chunk
+        let token = CancellationToken::new();
+        let mut callback = |processed, _| {
+            if processed == 2 {
+                token.cancel();
+            }
+        };
+        let mut progress = InputProgress::new(2, &mut callback).with_cancellation(token.clone());
+        match psbt.sign_all_with_xpriv_with_progress(&xprvs[0], &mut progress) {
+            Err(ProgressError::Cancelled(cancelled)) => assert_eq!(
+                cancelled,
+                OperationCancelled {
+                    processed: 2,
+                    total: 5
+                }
+            ),
+            other => panic!("expected cancellation, got {:?}", other),
+        }
+        let inputs = &psbt.psbt().inputs;
+        assert!(inputs[..2]
+            .iter()
+            .all(|input| input.partial_sigs.len() == 1));
+        assert!(inputs[2..]
+            .iter()
+            .all(|input| input.partial_sigs.is_empty()));
+        assert_eq!(inputs[3].bip32_derivation.len(), 3);
+
+        let mut callback = |_, _| {};
+        let mut progress = InputProgress::new(2, &mut callback).starting_at(2);
+        let signing_keys = psbt
+            .sign_all_with_xpriv_with_progress(&xprvs[0], &mut progress)
+            .unwrap();
+        assert_eq!(
+            signing_keys.keys().copied().collect::<Vec<_>>(),
+            vec![2, 3, 4]
+        );
+
+        expected.sign_all_with_xpriv(&xprvs[0]).unwrap();
+        assert_eq!(psbt.serialize().unwrap(), expected.serialize().unwrap());
+    }
+}
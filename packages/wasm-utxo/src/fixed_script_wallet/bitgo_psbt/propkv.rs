@@ -43,6 +43,11 @@ pub enum ProprietaryKeySubtype {
     PayGoAddressAttestationProof = 0x04,
     Bip322Message = 0x05,
     WasmUtxoSignedWith = 0x06,
+    SpendLimit = 0x07,
+    DestinationWhitelistHash = 0x08,
+    ApprovalQuorumId = 0x09,
+    Label = 0x0a,
+    UnconfirmedParent = 0x0b,
 }
 
 impl ProprietaryKeySubtype {
@@ -55,6 +60,11 @@ impl ProprietaryKeySubtype {
             0x04 => Some(ProprietaryKeySubtype::PayGoAddressAttestationProof),
             0x05 => Some(ProprietaryKeySubtype::Bip322Message),
             0x06 => Some(ProprietaryKeySubtype::WasmUtxoSignedWith),
+            0x07 => Some(ProprietaryKeySubtype::SpendLimit),
+            0x08 => Some(ProprietaryKeySubtype::DestinationWhitelistHash),
+            0x09 => Some(ProprietaryKeySubtype::ApprovalQuorumId),
+            0x0a => Some(ProprietaryKeySubtype::Label),
+            0x0b => Some(ProprietaryKeySubtype::UnconfirmedParent),
             _ => None,
         }
     }
@@ -0,0 +1,87 @@
+//! Property tests over generated BitGo PSBTs
+//!
+//! See [`crate::fixed_script_wallet::test_utils::arbitrary`] for the generator.
+
+use proptest::prelude::*;
+
+use super::{BitGoPsbt, DeserializeValidation};
+use crate::fixed_script_wallet::test_utils::arbitrary::arb_psbt_spec;
+use crate::fixed_script_wallet::ReplayProtection;
+
+/// Deriving wallet scripts and signing is slow, keep the number of cases moderate
+fn config() -> ProptestConfig {
+    ProptestConfig::with_cases(64)
+}
+
+proptest! {
+    #![proptest_config(config())]
+
+    #[test]
+    fn prop_serialize_round_trip(spec in arb_psbt_spec()) {
+        let (psbt, _) = spec.build();
+        let bytes = psbt.serialize().unwrap();
+
+        let decoded = BitGoPsbt::deserialize(&bytes, spec.network).unwrap();
+        prop_assert_eq!(decoded.network(), spec.network);
+        prop_assert_eq!(decoded.unsigned_txid(), psbt.unsigned_txid());
+        prop_assert_eq!(decoded.psbt().inputs.len(), spec.inputs.len());
+        prop_assert_eq!(decoded.psbt().outputs.len(), spec.outputs.len());
+        prop_assert_eq!(decoded.serialize().unwrap(), bytes);
+    }
+
+    #[test]
+    fn prop_strict_validation_accepts_built_psbts(spec in arb_psbt_spec()) {
+        let (psbt, _) = spec.build();
+        let bytes = psbt.serialize().unwrap();
+        BitGoPsbt::deserialize_with_validation(&bytes, spec.network, DeserializeValidation::Strict)
+            .unwrap();
+    }
+
+    #[test]
+    fn prop_parse_matches_spec(spec in arb_psbt_spec()) {
+        let (psbt, wallet_keys) = spec.build();
+        let result = psbt.parse_transaction_with_wallet_keys(
+            &wallet_keys,
+            &ReplayProtection::new(vec![]),
+            &[],
+        );
+
+        // Outputs exceeding inputs are rejected, anything else must parse
+        if spec.output_total() > spec.input_total() {
+            prop_assert!(result.is_err());
+        } else {
+            let parsed = result.unwrap();
+            prop_assert_eq!(parsed.inputs.len(), spec.inputs.len());
+            prop_assert_eq!(parsed.outputs.len(), spec.outputs.len());
+            prop_assert_eq!(parsed.miner_fee, spec.input_total() - spec.output_total());
+            for (parsed, input) in parsed.inputs.iter().zip(&spec.inputs) {
+                prop_assert_eq!(parsed.value, input.value);
+            }
+        }
+    }
+
+    #[test]
+    fn prop_deserialize_corrupted_never_panics(
+        spec in arb_psbt_spec(),
+        edits in proptest::collection::vec((any::<prop::sample::Index>(), any::<u8>()), 1..8),
+        truncate in any::<prop::sample::Index>(),
+    ) {
+        let (psbt, wallet_keys) = spec.build();
+        let mut bytes = psbt.serialize().unwrap();
+        for (index, byte) in edits {
+            let index = index.index(bytes.len());
+            bytes[index] = byte;
+        }
+        bytes.truncate(truncate.index(bytes.len() + 1));
+
+        // Errors are expected; panics are not
+        if let Ok(decoded) = BitGoPsbt::deserialize(&bytes, spec.network) {
+            let _ = decoded.serialize();
+            let _ = decoded.parse_transaction_with_wallet_keys(
+                &wallet_keys,
+                &ReplayProtection::new(vec![]),
+                &[],
+            );
+        }
+    }
+}
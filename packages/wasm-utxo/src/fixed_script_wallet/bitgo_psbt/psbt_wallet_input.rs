@@ -3,9 +3,13 @@ use miniscript::bitcoin::psbt::{Input, Psbt};
 use miniscript::bitcoin::secp256k1::{self, PublicKey};
 use miniscript::bitcoin::{OutPoint, ScriptBuf, TapLeafHash, XOnlyPublicKey};
 
+use super::single_sig::SingleSigInput;
+use super::SequenceMode;
 use crate::bitcoin::bip32::KeySource;
+use crate::fixed_script_wallet::wallet_scripts::{chain_index_path, path_chain_index};
 use crate::fixed_script_wallet::{
-    OutputScriptType, ReplayProtection, RootWalletKeys, ScriptId, WalletOutputScript,
+    Chain, OutputScriptType, ReplayProtection, RootWalletKeys, ScriptId, WalletOutputScript,
+    WalletScripts,
 };
 use crate::Network;
 
@@ -175,7 +179,7 @@ pub fn verify_taproot_script_signature<
 
 /// Collect all prevouts (funding outputs) from PSBT inputs
 ///
-/// This helper extracts the TxOut for each input from either witness_utxo or non_witness_utxo.
+/// Each prevout is read with [`get_spent_output`].
 /// Required for computing sighashes in taproot transactions.
 ///
 /// # Arguments
@@ -187,23 +191,14 @@ pub fn verify_taproot_script_signature<
 pub fn collect_prevouts(
     psbt: &miniscript::bitcoin::psbt::Psbt,
 ) -> Result<Vec<miniscript::bitcoin::TxOut>, String> {
-    let tx = &psbt.unsigned_tx;
     psbt.inputs
         .iter()
+        .zip(&psbt.unsigned_tx.input)
         .enumerate()
-        .map(|(i, input)| {
-            if let Some(witness_utxo) = &input.witness_utxo {
-                Ok(witness_utxo.clone())
-            } else if let Some(non_witness_utxo) = &input.non_witness_utxo {
-                let output_index = tx.input[i].previous_output.vout as usize;
-                non_witness_utxo
-                    .output
-                    .get(output_index)
-                    .cloned()
-                    .ok_or_else(|| format!("Output index {} out of bounds", output_index))
-            } else {
-                Err(format!("Missing UTXO data for input {}", i))
-            }
+        .map(|(i, (input, tx_in))| {
+            get_spent_output(input, tx_in.previous_output)
+                .cloned()
+                .map_err(|e| format!("Input {}: {}", i, e))
         })
         .collect()
 }
@@ -299,10 +294,9 @@ pub fn verify_ecdsa_signature<C: secp256k1::Verification>(
 
         // Use appropriate sighash computation based on fork_id
         let sighash_msg = if let Some(fid) = fork_id {
-            // BCH/BTG/XEC: use sighash_forkid
-            let (msg, _) = psbt
-                .sighash_forkid(input_index, &mut cache, fid)
-                .map_err(|e| format!("Failed to compute FORKID sighash: {}", e))?;
+            // BCH/BTG/XEC/BSV: BIP143-style FORKID sighash for all script types
+            let (msg, _) =
+                super::sighash::forkid_signature_hash(psbt, input_index, &mut cache, fid)?;
             msg
         } else {
             // Standard Bitcoin: use sighash_ecdsa
@@ -347,7 +341,9 @@ pub fn verify_ecdsa_signature_zcash<C: secp256k1::Verification>(
     version_group_id: u32,
     expiry_height: u32,
 ) -> Result<bool, String> {
-    use miniscript::bitcoin::{sighash::SighashCache, PublicKey};
+    use miniscript::bitcoin::hashes::Hash;
+    use miniscript::bitcoin::sighash::{SighashCache, SighashCacheZcashExt};
+    use miniscript::bitcoin::PublicKey;
 
     let input = &psbt.inputs[input_index];
 
@@ -357,18 +353,25 @@ pub fn verify_ecdsa_signature_zcash<C: secp256k1::Verification>(
 
     // Check if there's a partial signature for this public key
     if let Some(signature) = input.partial_sigs.get(&public_key_inner) {
+        let prevout = psbt.unsigned_tx.input[input_index].previous_output;
+        let (output_script, value) = get_output_script_and_value(input, prevout)
+            .map_err(|e| format!("Failed to get spent output: {}", e))?;
+        let script_code = input.redeem_script.as_ref().unwrap_or(output_script);
+
         // Create sighash cache and compute sighash for this input using ZIP-243
         let mut cache = SighashCache::new(&psbt.unsigned_tx);
-
-        let (sighash_msg, _) = psbt
-            .sighash_zcash(
+        let sighash = cache
+            .p2sh_signature_hash_zcash(
                 input_index,
-                &mut cache,
+                script_code,
+                value,
+                signature.sighash_type,
                 consensus_branch_id,
                 version_group_id,
                 expiry_height,
             )
             .map_err(|e| format!("Failed to compute Zcash sighash: {}", e))?;
+        let sighash_msg = secp256k1::Message::from_digest(sighash.to_byte_array());
 
         // Verify the signature
         match secp.verify_ecdsa(&sighash_msg, &signature.signature, &public_key_inner.inner) {
@@ -460,6 +463,14 @@ impl SignerKey {
     pub fn is_backup(&self) -> bool {
         matches!(self, SignerKey::Backup)
     }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignerKey::User => "user",
+            SignerKey::Backup => "backup",
+            SignerKey::Bitgo => "bitgo",
+        }
+    }
 }
 
 impl std::str::FromStr for SignerKey {
@@ -488,8 +499,8 @@ pub struct SignPath {
 /// Optional parameters for replay protection inputs
 #[derive(Debug, Clone, Default)]
 pub struct ReplayProtectionOptions<'a> {
-    /// Sequence number (default: 0xFFFFFFFE for RBF)
-    pub sequence: Option<u32>,
+    /// Sequence mode (default: [`SequenceMode::FinalNoRbf`])
+    pub sequence: Option<SequenceMode>,
     /// Sighash type override (default: network-appropriate value)
     pub sighash_type: Option<miniscript::bitcoin::psbt::PsbtSighashType>,
     /// Previous transaction bytes; if provided, uses non_witness_utxo
@@ -501,8 +512,8 @@ pub struct ReplayProtectionOptions<'a> {
 pub struct WalletInputOptions<'a> {
     /// Signer and cosigner for Taproot inputs (required for p2tr/p2trMusig2)
     pub sign_path: Option<SignPath>,
-    /// Sequence number (default: 0xFFFFFFFE for RBF)
-    pub sequence: Option<u32>,
+    /// Sequence mode (default: [`SequenceMode::FinalNoRbf`])
+    pub sequence: Option<SequenceMode>,
     /// Previous transaction bytes; if provided, uses non_witness_utxo
     pub prev_tx: Option<&'a [u8]>,
 }
@@ -517,6 +528,108 @@ pub enum InputScriptType {
     P2trMusig2ScriptPath,
     P2trMusig2KeyPath,
     P2mr,
+    /// Single-sig inputs spending an output of one wallet key (see [`super::single_sig`])
+    P2pkh,
+    P2wpkh,
+    P2trKeyPath,
+    /// Input that belongs to neither the wallet nor replay protection (e.g. a
+    /// counterparty input in a collaborative transaction). Only produced when
+    /// parsing with [`ExternalInputPolicy::Allow`].
+    External(ExternalScriptType),
+}
+
+/// Best-effort script type of an external (non-wallet) input or output, detected from
+/// the output script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalScriptType {
+    P2pkh,
+    P2sh,
+    P2wpkh,
+    P2wsh,
+    P2tr,
+    /// Bare pay-to-pubkey: `<pubkey> OP_CHECKSIG`
+    P2pk,
+    /// Bare multisig: `OP_m <pubkey>... OP_n OP_CHECKMULTISIG`
+    P2ms,
+    /// `OP_RETURN` output
+    NullData,
+    Unknown,
+}
+
+impl ExternalScriptType {
+    pub fn from_script(script: &miniscript::bitcoin::Script) -> Self {
+        if script.is_p2pkh() {
+            Self::P2pkh
+        } else if script.is_p2sh() {
+            Self::P2sh
+        } else if script.is_p2wpkh() {
+            Self::P2wpkh
+        } else if script.is_p2wsh() {
+            Self::P2wsh
+        } else if script.is_p2tr() {
+            Self::P2tr
+        } else if script.is_p2pk() {
+            Self::P2pk
+        } else if is_bare_multisig(script) {
+            Self::P2ms
+        } else if script.is_op_return() {
+            Self::NullData
+        } else {
+            Self::Unknown
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::P2pkh => "p2pkh",
+            Self::P2sh => "p2sh",
+            Self::P2wpkh => "p2wpkh",
+            Self::P2wsh => "p2wsh",
+            Self::P2tr => "p2tr",
+            Self::P2pk => "p2pk",
+            Self::P2ms => "p2ms",
+            Self::NullData => "nullData",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// Whether `script` is `OP_m <pubkey>... OP_n OP_CHECKMULTISIG` with `1 <= m <= n`
+fn is_bare_multisig(script: &miniscript::bitcoin::Script) -> bool {
+    use miniscript::bitcoin::opcodes::all::OP_CHECKMULTISIG;
+
+    let Ok(instructions) = script.instructions().collect::<Result<Vec<_>, _>>() else {
+        return false;
+    };
+    let [m, pubkeys @ .., n, checkmultisig] = instructions.as_slice() else {
+        return false;
+    };
+    let pushnum = |instruction: &miniscript::bitcoin::script::Instruction| {
+        instruction.opcode().and_then(|op| op.decode_pushnum())
+    };
+    let (Some(m), Some(n)) = (pushnum(m), pushnum(n)) else {
+        return false;
+    };
+    checkmultisig.opcode() == Some(OP_CHECKMULTISIG)
+        && 1 <= m
+        && m <= n
+        && usize::from(n) == pubkeys.len()
+        && pubkeys.iter().all(|pubkey| {
+            pubkey
+                .push_bytes()
+                .is_some_and(|bytes| matches!(bytes.len(), 33 | 65))
+        })
+}
+
+/// How `ParsedInput::parse` treats inputs that match neither the wallet keys nor
+/// the replay protection scripts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExternalInputPolicy {
+    /// Fail with `ParseInputError::WalletValidation` (default)
+    #[default]
+    Reject,
+    /// Classify the input as `InputScriptType::External`
+    Allow,
 }
 
 impl InputScriptType {
@@ -551,8 +664,16 @@ impl InputScriptType {
             Self::P2trMusig2KeyPath => "p2trMusig2",
             Self::P2trMusig2ScriptPath => "p2trMusig2Script",
             Self::P2mr => "p2mr",
+            Self::P2pkh => "p2pkh",
+            Self::P2wpkh => "p2wpkh",
+            Self::P2trKeyPath => "p2trKeyPath",
+            Self::External(_) => "external",
         }
     }
+
+    pub fn is_external(self) -> bool {
+        matches!(self, Self::External(_))
+    }
 }
 
 /// Parsed input from a PSBT transaction
@@ -566,18 +687,26 @@ pub struct ParsedInput {
     pub script_type: InputScriptType,
     pub sequence: u32,
     /// Full BIP32 derivation path from the wallet xpub (e.g. `[chain, index]`).
-    /// `None` for replay-protection inputs which have no wallet derivation.
+    /// `None` for replay-protection and external inputs which have no wallet derivation.
     pub derivation_path: Option<DerivationPath>,
+    /// Label stored in the PSBT input, see [`super::labels`]
+    pub label: Option<String>,
 }
 
 impl ParsedInput {
     /// Parse a PSBT input with wallet keys to identify if it belongs to the wallet.
+    ///
+    /// Inputs without any derivation matching the wallet keys are rejected unless
+    /// `external_policy` is `ExternalInputPolicy::Allow`, in which case they are
+    /// returned as `InputScriptType::External`. Inputs whose derivation matches the
+    /// wallet keys but whose script does not are always rejected.
     pub fn parse(
         psbt_input: &Input,
         tx_input: &miniscript::bitcoin::TxIn,
         wallet_keys: &RootWalletKeys,
         replay_protection: &ReplayProtection,
         network: Network,
+        external_policy: ExternalInputPolicy,
     ) -> Result<Self, ParseInputError> {
         let (output_script, value) =
             get_output_script_and_value(psbt_input, tx_input.previous_output)
@@ -585,8 +714,20 @@ impl ParsedInput {
 
         let is_replay_protection = replay_protection.is_replay_protection_input(output_script);
 
+        let single_sig = if is_replay_protection {
+            None
+        } else {
+            SingleSigInput::from_psbt_input(wallet_keys, psbt_input, output_script)
+        };
+
         let (script_id, derivation_path, script_type) = if is_replay_protection {
             (None, None, InputScriptType::P2shP2pk)
+        } else if let Some(single_sig) = single_sig {
+            (
+                None,
+                Some(single_sig.derivation_path),
+                single_sig.script_type.into(),
+            )
         } else {
             let wos = WalletOutputScript::from_psbt(
                 wallet_keys,
@@ -596,16 +737,29 @@ impl ParsedInput {
                 output_script,
                 network,
             )
-            .map_err(ParseInputError::WalletValidation)?
-            .ok_or_else(|| {
-                ParseInputError::WalletValidation(
-                    "no derivation path matched wallet keys".to_string(),
-                )
+            .map_err(|error| {
+                derivation_mismatch(wallet_keys, psbt_input, output_script, network)
+                    .unwrap_or(ParseInputError::WalletValidation(error))
             })?;
 
-            let script_id = wos.script_id();
-            let input_type = InputScriptType::from_output_script_type(wos.script_type, psbt_input);
-            (script_id, Some(wos.derivation_path), input_type)
+            match (wos, external_policy) {
+                (Some(wos), _) => {
+                    let script_id = wos.script_id();
+                    let input_type =
+                        InputScriptType::from_output_script_type(wos.script_type, psbt_input);
+                    (script_id, Some(wos.derivation_path), input_type)
+                }
+                (None, ExternalInputPolicy::Allow) => (
+                    None,
+                    None,
+                    InputScriptType::External(ExternalScriptType::from_script(output_script)),
+                ),
+                (None, ExternalInputPolicy::Reject) => {
+                    return Err(ParseInputError::WalletValidation(
+                        "no derivation path matched wallet keys".to_string(),
+                    ))
+                }
+            }
         };
 
         let address = crate::address::networks::from_output_script_with_network(
@@ -623,10 +777,50 @@ impl ParsedInput {
             script_type,
             sequence: tx_input.sequence.0,
             derivation_path,
+            label: super::labels::get_label(&psbt_input.proprietary),
         })
     }
 }
 
+/// Error for an input whose key origins belong to the wallet but whose script is not the
+/// script of the wallet at the chain and index of the key origins
+///
+/// Returns `None` if the chain is not a standard chain, in which case the expected script
+/// is unknown.
+fn derivation_mismatch(
+    wallet_keys: &RootWalletKeys,
+    psbt_input: &Input,
+    output_script: &ScriptBuf,
+    network: Network,
+) -> Option<ParseInputError> {
+    let path = psbt_input
+        .bip32_derivation
+        .values()
+        .map(|(_, path)| path)
+        .chain(
+            psbt_input
+                .tap_key_origins
+                .values()
+                .map(|(_, (_, path))| path),
+        )
+        .next()?;
+    let (chain, index) = path_chain_index(path)?;
+    let chain = Chain::try_from(chain).ok()?;
+    let expected_script = WalletScripts::from_wallet_keys(
+        wallet_keys,
+        chain.script_type,
+        &chain_index_path(chain.value(), index),
+        &network.output_script_support(),
+    )
+    .ok()?
+    .output_script();
+    (expected_script != *output_script).then(|| ParseInputError::DerivationMismatch {
+        expected_script,
+        actual_script: output_script.clone(),
+        path: path.clone(),
+    })
+}
+
 /// Error type for parsing a single PSBT input
 #[derive(Debug, strum::IntoStaticStr)]
 pub enum ParseInputError {
@@ -638,6 +832,15 @@ pub enum ParseInputError {
     Derivation(String),
     /// Input failed wallet validation
     WalletValidation(String),
+    /// The key origins of the input belong to the wallet, but the wallet keys derive a
+    /// different script at their chain and index
+    DerivationMismatch {
+        /// Script of the wallet at `path`
+        expected_script: ScriptBuf,
+        /// Script spent by the input
+        actual_script: ScriptBuf,
+        path: DerivationPath,
+    },
     /// Failed to generate address for input
     Address(crate::address::AddressError),
     /// Failed to detect script type for input
@@ -659,6 +862,17 @@ impl std::fmt::Display for ParseInputError {
             ParseInputError::WalletValidation(error) => {
                 write!(f, "wallet validation failed: {}", error)
             }
+            ParseInputError::DerivationMismatch {
+                expected_script,
+                actual_script,
+                path,
+            } => write!(
+                f,
+                "derivation mismatch: wallet keys derive {} at {}, but the input spends {}",
+                expected_script.to_hex_string(),
+                path,
+                actual_script.to_hex_string()
+            ),
             ParseInputError::Address(error) => {
                 write!(f, "failed to generate address: {}", error)
             }
@@ -682,25 +896,46 @@ impl crate::error::WasmErrorCode for ParseInputError {
     }
 }
 
-/// Get both output script and value from a PSBT input
-pub fn get_output_script_and_value(
+/// Size of the scriptSig of a p2shP2pk (replay protection) input, if it is signed
+///
+/// Taken from the final scriptSig, or computed from the length of the partial signature.
+pub(crate) fn p2sh_p2pk_signed_script_sig_size(input: &Input) -> Option<usize> {
+    if let Some(script_sig) = &input.final_script_sig {
+        return Some(script_sig.len());
+    }
+    input.partial_sigs.values().next().map(|sig| {
+        crate::fixed_script_wallet::wallet_scripts::p2sh_p2pk_script_sig_size(sig.to_vec().len())
+    })
+}
+
+/// Output spent by a PSBT input
+///
+/// Taken from `witness_utxo`, or from `non_witness_utxo` (full PSBT format) if the input has
+/// no `witness_utxo`. Code that needs the spent script or value reads it through this
+/// function, so that it works with either format.
+pub fn get_spent_output(
     input: &Input,
     prevout: OutPoint,
-) -> Result<(&ScriptBuf, miniscript::bitcoin::Amount), OutputScriptError> {
+) -> Result<&miniscript::bitcoin::TxOut, OutputScriptError> {
     match (&input.witness_utxo, &input.non_witness_utxo) {
         // Prefer witness_utxo when both are set (common in some wallet implementations)
-        (Some(witness_utxo), _) => Ok((&witness_utxo.script_pubkey, witness_utxo.value)),
-        (None, Some(non_witness_utxo)) => {
-            let output = non_witness_utxo
-                .output
-                .get(prevout.vout as usize)
-                .ok_or(OutputScriptError::OutputIndexOutOfBounds { vout: prevout.vout })?;
-            Ok((&output.script_pubkey, output.value))
-        }
+        (Some(witness_utxo), _) => Ok(witness_utxo),
+        (None, Some(non_witness_utxo)) => non_witness_utxo
+            .output
+            .get(prevout.vout as usize)
+            .ok_or(OutputScriptError::OutputIndexOutOfBounds { vout: prevout.vout }),
         (None, None) => Err(OutputScriptError::NoUtxoFields),
     }
 }
 
+/// Get both output script and value from a PSBT input
+pub fn get_output_script_and_value(
+    input: &Input,
+    prevout: OutPoint,
+) -> Result<(&ScriptBuf, miniscript::bitcoin::Amount), OutputScriptError> {
+    get_spent_output(input, prevout).map(|output| (&output.script_pubkey, output.value))
+}
+
 fn get_output_script_from_input(
     input: &Input,
     prevout: OutPoint,
@@ -845,7 +1080,9 @@ pub fn validate_psbt_wallet_inputs(
             }
         };
 
-        if replay_protection.is_replay_protection_input(output_script) {
+        if replay_protection.is_replay_protection_input(output_script)
+            || SingleSigInput::from_psbt_input(wallet_keys, input, output_script).is_some()
+        {
             continue;
         }
 
@@ -1,6 +1,7 @@
 use miniscript::bitcoin::bip32::DerivationPath;
 use miniscript::bitcoin::psbt::Output;
 
+use super::ExternalScriptType;
 use crate::fixed_script_wallet::{RootWalletKeys, ScriptId, WalletOutputScript};
 use crate::Network;
 
@@ -15,6 +16,10 @@ pub struct ParsedOutput {
     /// Full BIP32 derivation path from the wallet xpub (e.g. `[chain, index]`).
     /// `None` for outputs that do not belong to this wallet.
     pub derivation_path: Option<DerivationPath>,
+    /// Script type of an output that does not belong to this wallet, `None` otherwise
+    pub external_script_type: Option<ExternalScriptType>,
+    /// Label stored in the PSBT output, see [`super::labels`]
+    pub label: Option<String>,
 }
 
 impl ParsedOutput {
@@ -25,21 +30,58 @@ impl ParsedOutput {
         network: Network,
         paygo_pubkeys: &[miniscript::bitcoin::secp256k1::PublicKey],
     ) -> Result<Self, ParseOutputError> {
-        let script = &tx_output.script_pubkey;
-
-        let (script_id, derivation_path) = match WalletOutputScript::from_psbt(
-            wallet_keys,
-            &psbt_output.bip32_derivation,
-            &psbt_output.tap_key_origins,
-            false,
-            script,
+        Self::parse_with_wallets(
+            psbt_output,
+            tx_output,
+            &[wallet_keys],
             network,
+            paygo_pubkeys,
         )
-        .map_err(ParseOutputError::WalletMatch)?
-        {
-            Some(wos) => (wos.script_id(), Some(wos.derivation_path)),
-            None => (None, None),
+        .map(|(_, output)| output)
+    }
+
+    /// Parse an output against several wallets at once.
+    ///
+    /// Returns the index of the first wallet the output belongs to (if any) along with
+    /// the output parsed against that wallet. Address and PayGo attestation are only
+    /// computed once regardless of the number of wallets.
+    pub fn parse_with_wallets(
+        psbt_output: &Output,
+        tx_output: &miniscript::bitcoin::TxOut,
+        wallets: &[&RootWalletKeys],
+        network: Network,
+        paygo_pubkeys: &[miniscript::bitcoin::secp256k1::PublicKey],
+    ) -> Result<(Option<usize>, Self), ParseOutputError> {
+        let script = &tx_output.script_pubkey;
+
+        let mut wallet_match = None;
+        for (wallet_index, wallet_keys) in wallets.iter().enumerate() {
+            if let Some(wos) = WalletOutputScript::from_psbt(
+                wallet_keys,
+                &psbt_output.bip32_derivation,
+                &psbt_output.tap_key_origins,
+                false,
+                script,
+                network,
+            )
+            .map_err(ParseOutputError::WalletMatch)?
+            {
+                wallet_match = Some((wallet_index, wos));
+                break;
+            }
+        }
+
+        let (wallet_index, script_id, derivation_path) = match wallet_match {
+            Some((wallet_index, wos)) => (
+                Some(wallet_index),
+                wos.script_id(),
+                Some(wos.derivation_path),
+            ),
+            None => (None, None, None),
         };
+        let external_script_type = derivation_path
+            .is_none()
+            .then(|| ExternalScriptType::from_script(script));
 
         let address =
             crate::address::networks::from_output_script_with_network(script.as_script(), network)
@@ -52,14 +94,19 @@ impl ParsedOutput {
         )
         .map_err(ParseOutputError::PayGoAttestation)?;
 
-        Ok(Self {
-            address,
-            script: script.to_bytes(),
-            value: tx_output.value.to_sat(),
-            script_id,
-            paygo,
-            derivation_path,
-        })
+        Ok((
+            wallet_index,
+            Self {
+                address,
+                script: script.to_bytes(),
+                value: tx_output.value.to_sat(),
+                script_id,
+                paygo,
+                derivation_path,
+                external_script_type,
+                label: super::labels::get_label(&psbt_output.proprietary),
+            },
+        ))
     }
 
     /// Returns true if this is an external output (not belonging to the wallet)
@@ -68,6 +115,14 @@ impl ParsedOutput {
     }
 }
 
+/// Output parsed against a list of labeled wallets
+#[derive(Debug, Clone)]
+pub struct LabeledParsedOutput {
+    /// Label of the wallet the output belongs to, `None` for external outputs
+    pub wallet: Option<String>,
+    pub output: ParsedOutput,
+}
+
 /// Error type for parsing a single PSBT output
 #[derive(Debug, strum::IntoStaticStr)]
 pub enum ParseOutputError {
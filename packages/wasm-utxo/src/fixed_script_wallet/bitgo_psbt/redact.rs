@@ -0,0 +1,183 @@
+//! Redacted copies of a PSBT for sharing with third parties
+//!
+//! A full BitGo PSBT describes the wallet it belongs to: the global xpubs, the derivation
+//! path of every wallet key and BitGo proprietary fields. An external signer only needs the
+//! key origins of the inputs it signs, so [`BitGoPsbt::redact`] strips everything else.
+
+use std::collections::BTreeMap;
+
+use miniscript::bitcoin::psbt::raw::ProprietaryKey;
+
+use super::propkv::{is_bitgo_key, is_musig2_key, ProprietaryKeySubtype};
+use super::BitGoPsbt;
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum RedactError {
+    /// An input selected for signing does not exist
+    InputIndexOutOfBounds {
+        input_index: usize,
+        input_count: usize,
+    },
+}
+
+impl std::fmt::Display for RedactError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RedactError::InputIndexOutOfBounds {
+                input_index,
+                input_count,
+            } => write!(
+                f,
+                "Input index {} out of bounds (total inputs: {})",
+                input_index, input_count
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RedactError {}
+
+crate::impl_wasm_error_code!(RedactError);
+
+/// Remove BitGo proprietary key-values for which `keep` returns false
+fn retain_bitgo_keys(
+    proprietary: &mut BTreeMap<ProprietaryKey, Vec<u8>>,
+    keep: impl Fn(&ProprietaryKey) -> bool,
+) {
+    proprietary.retain(|key, _| !is_bitgo_key(key) || keep(key));
+}
+
+impl BitGoPsbt {
+    /// Return a copy of the PSBT that can be shared with an external signer
+    ///
+    /// The copy has no global xpubs and no output metadata. Inputs in `signer_inputs` keep
+    /// their key origins and MuSig2 data so that they can still be signed; all other inputs
+    /// lose their key origins and BitGo proprietary fields. Scripts, UTXOs and signatures are
+    /// kept since they are needed to compute sighashes and finalize. For Zcash, the
+    /// consensus branch id is kept.
+    ///
+    /// # Arguments
+    /// - `signer_inputs`: Indices of the inputs the external signer will sign
+    pub fn redact(&self, signer_inputs: &[usize]) -> Result<BitGoPsbt, RedactError> {
+        let input_count = self.psbt().inputs.len();
+        if let Some(&input_index) = signer_inputs.iter().find(|&&i| i >= input_count) {
+            return Err(RedactError::InputIndexOutOfBounds {
+                input_index,
+                input_count,
+            });
+        }
+
+        let mut redacted = self.clone();
+        let psbt = redacted.psbt_mut();
+
+        psbt.xpub.clear();
+        retain_bitgo_keys(&mut psbt.proprietary, |key| {
+            key.subtype == ProprietaryKeySubtype::ZecConsensusBranchId as u8
+        });
+
+        for (input_index, input) in psbt.inputs.iter_mut().enumerate() {
+            if signer_inputs.contains(&input_index) {
+                retain_bitgo_keys(&mut input.proprietary, is_musig2_key);
+            } else {
+                input.bip32_derivation.clear();
+                input.tap_key_origins.clear();
+                retain_bitgo_keys(&mut input.proprietary, |_| false);
+            }
+        }
+
+        for output in &mut psbt.outputs {
+            *output = Default::default();
+        }
+
+        Ok(redacted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::bitgo_psbt::propkv::get_zec_consensus_branch_id;
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::{
+        get_test_wallet_keys, get_test_wallet_xprvs,
+    };
+    use crate::Network;
+
+    #[test]
+    fn test_redact() {
+        let seed = "redact";
+        let wallet_keys = get_test_wallet_keys(seed);
+        let mut psbt = WalletPsbtBuilder::new(Network::Bitcoin, &wallet_keys)
+            .input(0, 0)
+            .input(20, 0)
+            .input(30, 0)
+            .output(41, 0, 25_000)
+            .build();
+
+        assert!(matches!(
+            psbt.redact(&[3]),
+            Err(RedactError::InputIndexOutOfBounds {
+                input_index: 3,
+                input_count: 3
+            })
+        ));
+
+        let redacted = psbt.redact(&[1]).unwrap();
+        let (original, redacted_psbt) = (psbt.psbt(), redacted.psbt());
+        assert_eq!(original.unsigned_tx, redacted_psbt.unsigned_tx);
+        assert!(redacted_psbt.xpub.is_empty());
+        assert!(redacted_psbt.inputs[0].bip32_derivation.is_empty());
+        assert!(redacted_psbt.inputs[2].tap_key_origins.is_empty());
+        assert!(redacted_psbt.inputs[2].proprietary.is_empty());
+        assert_eq!(redacted_psbt.inputs[1], original.inputs[1]);
+        assert_eq!(
+            redacted_psbt.inputs[0].witness_utxo,
+            original.inputs[0].witness_utxo
+        );
+        assert!(redacted_psbt
+            .outputs
+            .iter()
+            .all(|output| *output == Default::default()));
+
+        // The external signer can sign its input but not the others
+        let mut redacted = redacted;
+        redacted
+            .sign_all_with_xpriv(&get_test_wallet_xprvs(seed)[0])
+            .unwrap();
+        let signed: Vec<_> = redacted
+            .psbt()
+            .inputs
+            .iter()
+            .map(|input| !input.partial_sigs.is_empty())
+            .collect();
+        assert_eq!(signed, vec![false, true, false]);
+
+        // The taproot input keeps its script path when it is selected
+        let mut redacted = psbt.redact(&[2]).unwrap();
+        redacted
+            .sign_all_with_xpriv(&get_test_wallet_xprvs(seed)[0])
+            .unwrap();
+        let inputs = &redacted.psbt().inputs;
+        assert_eq!(inputs[2].tap_script_sigs.len(), 1);
+        assert!(inputs[0].partial_sigs.is_empty() && inputs[1].partial_sigs.is_empty());
+    }
+
+    #[test]
+    fn test_redact_keeps_zcash_branch_id() {
+        let wallet_keys = get_test_wallet_keys("redact");
+        let psbt = BitGoPsbt::new_zcash(
+            Network::Zcash,
+            &wallet_keys,
+            0xc8e71055,
+            None,
+            None,
+            None,
+            None,
+        );
+        let redacted = psbt.redact(&[]).unwrap();
+        assert_eq!(
+            get_zec_consensus_branch_id(redacted.psbt()),
+            Some(0xc8e71055)
+        );
+    }
+}
@@ -0,0 +1,368 @@
+//! Risk report of a parsed PSBT
+//!
+//! Compliance tooling screens a transaction for patterns that are legitimate but deserve a
+//! second look. [`BitGoPsbt::risk_report`] collects them from the parsed transaction into a
+//! single [`RiskReport`]:
+//!
+//! - signatures or inputs that use a sighash type other than the network default,
+//! - inputs that belong to neither the wallet nor replay protection,
+//! - `OP_RETURN` outputs and the data they carry,
+//! - outputs below the dust limit,
+//! - a miner fee above the configured thresholds,
+//! - addresses that are both spent from and paid to.
+
+use miniscript::bitcoin::psbt::Input;
+use miniscript::bitcoin::script::Instruction;
+use miniscript::bitcoin::FeeRate;
+
+use super::signing_policy::SigningPolicy;
+use super::{get_default_ecdsa_sighash_type, BitGoPsbt, ParsedTransaction};
+
+/// Thresholds of the risk report
+#[derive(Debug, Clone, Default)]
+pub struct RiskReportOptions {
+    /// Flag miner fees above this amount in satoshis
+    pub max_fee: Option<u64>,
+    /// Flag fee rates above this rate, measured against [`ParsedTransaction::virtual_size`]
+    pub max_fee_rate: Option<FeeRate>,
+    /// Flag outputs below this value. Defaults to the dust limit of the network for the output
+    /// script, see [`crate::Network::dust_limit`].
+    pub dust_threshold: Option<u64>,
+}
+
+/// An input or signature with a sighash type other than the network default
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonDefaultSighash {
+    pub input_index: usize,
+    pub sighash_type: u32,
+}
+
+/// An `OP_RETURN` output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpReturnOutput {
+    pub output_index: usize,
+    /// Pushed data, concatenated
+    pub data: Vec<u8>,
+    pub value: u64,
+}
+
+/// An output below the dust threshold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DustOutput {
+    pub output_index: usize,
+    pub value: u64,
+    pub dust_threshold: u64,
+}
+
+/// An address that is both spent from and paid to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReusedAddress {
+    pub address: String,
+    pub input_indices: Vec<usize>,
+    pub output_indices: Vec<usize>,
+}
+
+/// Red flags of a transaction, see the [module documentation](self)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RiskReport {
+    pub non_default_sighash: Vec<NonDefaultSighash>,
+    /// Indices of the inputs that belong to neither the wallet nor replay protection
+    pub external_inputs: Vec<usize>,
+    pub op_return_outputs: Vec<OpReturnOutput>,
+    pub dust_outputs: Vec<DustOutput>,
+    pub miner_fee: u64,
+    pub virtual_size: u32,
+    /// Whether the miner fee exceeds `max_fee` or `max_fee_rate`
+    pub fee_above_threshold: bool,
+    pub reused_addresses: Vec<ReusedAddress>,
+}
+
+impl RiskReport {
+    /// Whether the report has any red flag
+    pub fn has_flags(&self) -> bool {
+        !self.non_default_sighash.is_empty()
+            || !self.external_inputs.is_empty()
+            || !self.op_return_outputs.is_empty()
+            || !self.dust_outputs.is_empty()
+            || self.fee_above_threshold
+            || !self.reused_addresses.is_empty()
+    }
+}
+
+/// Sighash types of an input: the PSBT field and the types of its signatures
+fn input_sighash_types(input: &Input) -> impl Iterator<Item = (u32, bool)> + '_ {
+    let taproot = input.tap_internal_key.is_some()
+        || !input.tap_scripts.is_empty()
+        || input
+            .witness_utxo
+            .as_ref()
+            .is_some_and(|utxo| utxo.script_pubkey.is_p2tr());
+    let field = input.sighash_type.map(|t| (t.to_u32(), taproot));
+    let ecdsa = input
+        .partial_sigs
+        .values()
+        .map(|sig| (sig.sighash_type.to_u32(), false));
+    let schnorr = input
+        .tap_key_sig
+        .iter()
+        .chain(input.tap_script_sigs.values())
+        .map(|sig| (sig.sighash_type as u32, true));
+    field.into_iter().chain(ecdsa).chain(schnorr)
+}
+
+impl BitGoPsbt {
+    /// Collect the red flags of the transaction
+    ///
+    /// `parsed` is the result of parsing this PSBT, e.g. with
+    /// [`BitGoPsbt::parse_transaction_with_wallet_keys_and_input_policy`] and
+    /// `ExternalInputPolicy::Allow`.
+    pub fn risk_report(
+        &self,
+        parsed: &ParsedTransaction,
+        options: &RiskReportOptions,
+    ) -> RiskReport {
+        let psbt = self.psbt();
+        let default_ecdsa = get_default_ecdsa_sighash_type(self.network()).to_u32();
+
+        let mut non_default_sighash = Vec::new();
+        for (input_index, input) in psbt.inputs.iter().enumerate() {
+            let mut types: Vec<u32> = input_sighash_types(input)
+                .filter(|&(sighash_type, taproot)| {
+                    if taproot {
+                        // Neither SIGHASH_DEFAULT nor SIGHASH_ALL
+                        sighash_type > 1
+                    } else {
+                        sighash_type != default_ecdsa
+                    }
+                })
+                .map(|(sighash_type, _)| sighash_type)
+                .collect();
+            types.sort_unstable();
+            types.dedup();
+            non_default_sighash.extend(types.into_iter().map(|sighash_type| NonDefaultSighash {
+                input_index,
+                sighash_type,
+            }));
+        }
+
+        let external_inputs = parsed
+            .inputs
+            .iter()
+            .enumerate()
+            .filter(|(_, input)| input.script_type.is_external())
+            .map(|(index, _)| index)
+            .collect();
+
+        let mut op_return_outputs = Vec::new();
+        let mut dust_outputs = Vec::new();
+        for (output_index, tx_out) in psbt.unsigned_tx.output.iter().enumerate() {
+            let script = &tx_out.script_pubkey;
+            let value = tx_out.value.to_sat();
+            if script.is_op_return() {
+                let data = script
+                    .instructions()
+                    .filter_map(|instruction| match instruction {
+                        Ok(Instruction::PushBytes(bytes)) => Some(bytes.as_bytes()),
+                        _ => None,
+                    })
+                    .flatten()
+                    .copied()
+                    .collect();
+                op_return_outputs.push(OpReturnOutput {
+                    output_index,
+                    data,
+                    value,
+                });
+                continue;
+            }
+            let dust_threshold = options
+                .dust_threshold
+                .unwrap_or_else(|| self.network().dust_limit(script));
+            if value < dust_threshold {
+                dust_outputs.push(DustOutput {
+                    output_index,
+                    value,
+                    dust_threshold,
+                });
+            }
+        }
+
+        let fee_policy = SigningPolicy {
+            max_fee: options.max_fee,
+            max_fee_rate: options.max_fee_rate,
+            ..Default::default()
+        };
+        let fee_above_threshold = fee_policy.check(parsed).is_err();
+
+        let mut reused_addresses: Vec<ReusedAddress> = Vec::new();
+        for (output_index, output) in parsed.outputs.iter().enumerate() {
+            let Some(address) = &output.address else {
+                continue;
+            };
+            if let Some(reused) = reused_addresses.iter_mut().find(|r| r.address == *address) {
+                reused.output_indices.push(output_index);
+                continue;
+            }
+            let input_indices: Vec<usize> = parsed
+                .inputs
+                .iter()
+                .enumerate()
+                .filter(|(_, input)| input.address == *address)
+                .map(|(index, _)| index)
+                .collect();
+            if !input_indices.is_empty() {
+                reused_addresses.push(ReusedAddress {
+                    address: address.clone(),
+                    input_indices,
+                    output_indices: vec![output_index],
+                });
+            }
+        }
+
+        RiskReport {
+            non_default_sighash,
+            external_inputs,
+            op_return_outputs,
+            dust_outputs,
+            miner_fee: parsed.miner_fee,
+            virtual_size: parsed.virtual_size,
+            fee_above_threshold,
+            reused_addresses,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::bitgo_psbt::ExternalInputPolicy;
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+    use crate::fixed_script_wallet::{ReplayProtection, RootWalletKeys};
+    use crate::Network;
+    use miniscript::bitcoin::hashes::Hash;
+    use miniscript::bitcoin::psbt::PsbtSighashType;
+    use miniscript::bitcoin::script::PushBytesBuf;
+    use miniscript::bitcoin::{PubkeyHash, ScriptBuf, Txid, WPubkeyHash};
+
+    fn parse(psbt: &BitGoPsbt, wallet_keys: &RootWalletKeys) -> ParsedTransaction {
+        psbt.parse_transaction_with_wallet_keys_and_input_policy(
+            wallet_keys,
+            &ReplayProtection::new(vec![]),
+            &[],
+            ExternalInputPolicy::Allow,
+        )
+        .unwrap()
+    }
+
+    fn wallet_psbt(wallet_keys: &RootWalletKeys) -> BitGoPsbt {
+        WalletPsbtBuilder::new(Network::Bitcoin, wallet_keys)
+            .input_with(20, 0, 100_000, Default::default())
+            .input_with(10, 0, 100_000, Default::default())
+            .build()
+    }
+
+    #[test]
+    fn test_clean_transaction() {
+        let wallet_keys = get_test_wallet_keys("risk_report");
+        let mut psbt = wallet_psbt(&wallet_keys);
+        psbt.add_wallet_output(20, 1, 150_000, &wallet_keys)
+            .unwrap();
+        psbt.add_output(ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()), 49_000)
+            .unwrap();
+
+        let parsed = parse(&psbt, &wallet_keys);
+        let report = psbt.risk_report(&parsed, &RiskReportOptions::default());
+        assert!(!report.has_flags(), "{:?}", report);
+        assert_eq!(report.miner_fee, 1_000);
+    }
+
+    #[test]
+    fn test_red_flags() {
+        let wallet_keys = get_test_wallet_keys("risk_report");
+        let mut psbt = wallet_psbt(&wallet_keys);
+        let external = ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros());
+        psbt.add_input(Txid::all_zeros(), 2, 10_000, external.clone(), None, None)
+            .unwrap();
+        psbt.psbt_mut().inputs[1].sighash_type = Some(PsbtSighashType::from_u32(0x83));
+
+        // Change back to the address of input 0
+        psbt.add_wallet_output(20, 0, 150_000, &wallet_keys)
+            .unwrap();
+        psbt.add_output(external, 200).unwrap();
+        let data = PushBytesBuf::try_from(b"hello".to_vec()).unwrap();
+        psbt.add_output(ScriptBuf::new_op_return(&data), 0).unwrap();
+
+        let parsed = parse(&psbt, &wallet_keys);
+        let report = psbt.risk_report(
+            &parsed,
+            &RiskReportOptions {
+                max_fee: Some(50_000),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            report.non_default_sighash,
+            [NonDefaultSighash {
+                input_index: 1,
+                sighash_type: 0x83
+            }]
+        );
+        assert_eq!(report.external_inputs, [2]);
+        assert_eq!(
+            report.op_return_outputs,
+            [OpReturnOutput {
+                output_index: 2,
+                data: b"hello".to_vec(),
+                value: 0,
+            }]
+        );
+        assert_eq!(
+            report.dust_outputs,
+            [DustOutput {
+                output_index: 1,
+                value: 200,
+                dust_threshold: 294,
+            }]
+        );
+        assert_eq!(report.miner_fee, 59_800);
+        assert!(report.fee_above_threshold);
+        assert_eq!(report.reused_addresses.len(), 1);
+        assert_eq!(report.reused_addresses[0].input_indices, [0]);
+        assert_eq!(report.reused_addresses[0].output_indices, [0]);
+
+        // A custom dust threshold applies to all outputs
+        let report = psbt.risk_report(
+            &parsed,
+            &RiskReportOptions {
+                dust_threshold: Some(1_000),
+                ..Default::default()
+            },
+        );
+        assert_eq!(report.dust_outputs[0].dust_threshold, 1_000);
+        assert!(!report.fee_above_threshold);
+    }
+
+    #[test]
+    fn test_dogecoin_dust_limit() {
+        let wallet_keys = get_test_wallet_keys("risk_report");
+        let mut psbt = WalletPsbtBuilder::new(Network::Dogecoin, &wallet_keys)
+            .input_with(0, 0, 10_000_000, Default::default())
+            .output(0, 1, 8_000_000)
+            .build();
+        // Above the Bitcoin Core dust limit, below 0.01 DOGE
+        psbt.add_output(ScriptBuf::new_p2pkh(&PubkeyHash::all_zeros()), 500_000)
+            .unwrap();
+
+        let parsed = parse(&psbt, &wallet_keys);
+        let report = psbt.risk_report(&parsed, &RiskReportOptions::default());
+        assert_eq!(
+            report.dust_outputs,
+            [DustOutput {
+                output_index: 1,
+                value: 500_000,
+                dust_threshold: 1_000_000,
+            }]
+        );
+    }
+}
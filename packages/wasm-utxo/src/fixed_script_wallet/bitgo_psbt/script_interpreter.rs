@@ -0,0 +1,1163 @@
+//! Script interpreter for finalized inputs
+//!
+//! Finalization bugs (a missing signature, signatures in the wrong order, a witness built for
+//! the wrong leaf) otherwise only surface when the network rejects the broadcast.
+//! [`BitGoPsbt::verify_input_scripts`] executes the final scriptSig and witness of every input
+//! against the scriptPubKey of its prevout before the transaction leaves the wallet.
+//!
+//! This is not a consensus implementation. It covers the standard templates (P2PKH, P2SH,
+//! P2WPKH, P2WSH, P2SH-P2WSH, P2TR key path and tapscript leaves) and the opcodes used by
+//! wallet scripts and custom finalized scripts: signature checks, conditionals, absolute and
+//! relative timelocks (BIP-65, BIP-112) and OP_CHECKDATASIG on Bitcoin Cash and eCash. Any
+//! other opcode fails with [`ScriptError::UnsupportedOpcode`].
+//! Signatures are checked with the sighash algorithm of the network (legacy, BIP-143,
+//! SIGHASH_FORKID, ZIP-243 or BIP-341).
+
+use miniscript::bitcoin::blockdata::opcodes::all::*;
+use miniscript::bitcoin::blockdata::opcodes::Opcode;
+use miniscript::bitcoin::blockdata::script::{Builder, Instruction};
+use miniscript::bitcoin::hashes::{hash160, hex, sha256, Hash};
+use miniscript::bitcoin::secp256k1::{self, XOnlyPublicKey};
+use miniscript::bitcoin::sighash::{EcdsaSighashType, Prevouts, SighashCache};
+use miniscript::bitcoin::taproot::{ControlBlock, LeafVersion, TapLeafHash};
+use miniscript::bitcoin::{Script, Transaction, TxOut, WitnessVersion};
+
+use super::{propkv, psbt_wallet_input, sighash, zcash_psbt, BitGoPsbt};
+
+const SIGHASH_FORKID: u32 = 0x40;
+const MAX_MULTISIG_KEYS: i64 = 20;
+const MAX_SCRIPT_NUM_LEN: usize = 4;
+/// Lock times are 5-byte script numbers so that they can hold every `u32`
+const MAX_LOCKTIME_NUM_LEN: usize = 5;
+const LOCKTIME_THRESHOLD: i64 = 500_000_000;
+
+// Bitcoin Cash reuses the opcodes of OP_CHECKSIGADD and OP_RETURN_187
+const OP_CHECKDATASIG: Opcode = OP_CHECKSIGADD;
+const OP_CHECKDATASIGVERIFY: Opcode = OP_RETURN_187;
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum ScriptError {
+    /// A script cannot be decoded into instructions
+    InvalidScript(String),
+    /// The scriptSig contains opcodes other than pushes
+    ScriptSigNotPushOnly,
+    /// The script uses an opcode outside of the supported subset
+    UnsupportedOpcode(String),
+    /// An opcode needs more stack elements than are available
+    StackUnderflow,
+    /// A stack element is not a valid minimally encoded script number
+    InvalidNumber,
+    /// OP_CHECKMULTISIG key or signature count out of range
+    InvalidMultisigCount,
+    /// The extra element consumed by OP_CHECKMULTISIG is not empty
+    NullDummy,
+    /// OP_VERIFY or a *VERIFY opcode failed
+    VerifyFailed { opcode: String },
+    /// OP_IF or OP_NOTIF without a matching OP_ENDIF, or OP_ELSE/OP_ENDIF without an OP_IF
+    UnbalancedConditional,
+    /// The argument of OP_IF or OP_NOTIF in segwit scripts is not empty or `1`
+    MinimalIf,
+    /// The argument of OP_CHECKLOCKTIMEVERIFY or OP_CHECKSEQUENCEVERIFY is negative
+    NegativeLocktime,
+    /// The lock time or sequence of the transaction does not satisfy the timelock
+    UnsatisfiedLocktime { opcode: String },
+    /// The script executed OP_RETURN
+    OpReturn,
+    /// A non-empty signature failed verification
+    InvalidSignature(String),
+    /// Computing the signature hash failed
+    Sighash(String),
+    /// The script finished with an empty stack or a false value on top
+    EvalFalse,
+    /// The script finished with more than one element on the stack
+    CleanStack { len: usize },
+    /// The redeem or witness script does not hash to the scriptPubKey
+    ScriptHashMismatch,
+    /// The witness does not fit the scriptPubKey
+    InvalidWitness(String),
+    /// The taproot control block does not commit to the leaf script
+    ControlBlockMismatch,
+}
+
+impl std::fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScriptError::InvalidScript(error) => write!(f, "Invalid script: {}", error),
+            ScriptError::ScriptSigNotPushOnly => write!(f, "scriptSig is not push-only"),
+            ScriptError::UnsupportedOpcode(opcode) => write!(f, "Unsupported opcode {}", opcode),
+            ScriptError::StackUnderflow => write!(f, "Stack underflow"),
+            ScriptError::InvalidNumber => write!(f, "Invalid script number"),
+            ScriptError::InvalidMultisigCount => {
+                write!(f, "Invalid OP_CHECKMULTISIG key or signature count")
+            }
+            ScriptError::NullDummy => write!(f, "OP_CHECKMULTISIG dummy element is not empty"),
+            ScriptError::VerifyFailed { opcode } => write!(f, "{} failed", opcode),
+            ScriptError::UnbalancedConditional => write!(f, "Unbalanced conditional"),
+            ScriptError::MinimalIf => {
+                write!(f, "OP_IF/OP_NOTIF argument must be empty or 1")
+            }
+            ScriptError::NegativeLocktime => write!(f, "Negative lock time"),
+            ScriptError::UnsatisfiedLocktime { opcode } => {
+                write!(f, "{} lock time is not satisfied", opcode)
+            }
+            ScriptError::OpReturn => write!(f, "Script executed OP_RETURN"),
+            ScriptError::InvalidSignature(error) => write!(f, "Invalid signature {}", error),
+            ScriptError::Sighash(error) => write!(f, "Failed to compute sighash: {}", error),
+            ScriptError::EvalFalse => write!(f, "Script evaluated to false"),
+            ScriptError::CleanStack { len } => write!(
+                f,
+                "Script left {} elements on the stack, expected exactly one",
+                len
+            ),
+            ScriptError::ScriptHashMismatch => {
+                write!(
+                    f,
+                    "Redeem or witness script does not match the scriptPubKey"
+                )
+            }
+            ScriptError::InvalidWitness(error) => write!(f, "Invalid witness: {}", error),
+            ScriptError::ControlBlockMismatch => {
+                write!(f, "Control block does not commit to the leaf script")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum VerifyInputScriptsError {
+    /// The data needed to compute signature hashes is missing
+    MissingSighashData(String),
+    /// The input has neither a final scriptSig nor a final witness
+    NotFinalized { input_index: usize },
+    /// Executing the scripts of the input failed
+    Script {
+        input_index: usize,
+        error: ScriptError,
+    },
+}
+
+impl std::fmt::Display for VerifyInputScriptsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerifyInputScriptsError::MissingSighashData(error) => {
+                write!(f, "Cannot verify input scripts: {}", error)
+            }
+            VerifyInputScriptsError::NotFinalized { input_index } => {
+                write!(f, "Input {} is not finalized", input_index)
+            }
+            VerifyInputScriptsError::Script { input_index, error } => {
+                write!(
+                    f,
+                    "Script verification of input {} failed: {}",
+                    input_index, error
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerifyInputScriptsError {}
+
+crate::impl_wasm_error_code!(VerifyInputScriptsError);
+
+/// Signature hash algorithm for ECDSA signatures of a network
+///
+/// Taproot signatures always use BIP-341.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SighashAlgorithm {
+    /// Legacy sighash, BIP-143 for segwit v0
+    Bitcoin,
+    /// BIP-143 style sighash with SIGHASH_FORKID (Bitcoin Cash and forks)
+    ForkId(u32),
+    /// ZIP-243 sighash
+    Zcash {
+        consensus_branch_id: u32,
+        version_group_id: u32,
+        expiry_height: u32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SigVersion {
+    Base,
+    WitnessV0,
+    Tapscript(TapLeafHash),
+}
+
+struct SignatureChecker<'a, 'c, C: secp256k1::Verification> {
+    secp: &'c secp256k1::Secp256k1<C>,
+    cache: &'c mut SighashCache<&'a Transaction>,
+    input_index: usize,
+    prevouts: &'c [TxOut],
+    algorithm: SighashAlgorithm,
+    checkdatasig: bool,
+}
+
+impl<C: secp256k1::Verification> SignatureChecker<'_, '_, C> {
+    /// Check an ECDSA signature (DER encoding followed by the sighash type byte)
+    fn check_ecdsa(
+        &mut self,
+        sig: &[u8],
+        pubkey: &[u8],
+        script_code: &Script,
+        sig_version: SigVersion,
+    ) -> Result<bool, ScriptError> {
+        let Some((&sighash_type, der)) = sig.split_last() else {
+            return Ok(false);
+        };
+        let sighash_type = sighash_type as u32;
+        let (Ok(signature), Ok(pubkey)) = (
+            secp256k1::ecdsa::Signature::from_der(der),
+            secp256k1::PublicKey::from_slice(pubkey),
+        ) else {
+            return Ok(false);
+        };
+
+        let value = self.prevouts[self.input_index].value;
+        let digest = match (self.algorithm, sig_version) {
+            (
+                SighashAlgorithm::Zcash {
+                    consensus_branch_id,
+                    version_group_id,
+                    expiry_height,
+                },
+                _,
+            ) => {
+                use miniscript::bitcoin::sighash::SighashCacheZcashExt;
+                self.cache
+                    .p2sh_signature_hash_zcash(
+                        self.input_index,
+                        script_code,
+                        value,
+                        sighash_type,
+                        consensus_branch_id,
+                        version_group_id,
+                        expiry_height,
+                    )
+                    .map_err(|e| ScriptError::Sighash(e.to_string()))?
+                    .to_byte_array()
+            }
+            (SighashAlgorithm::ForkId(fork_id), _) => {
+                if sighash_type & SIGHASH_FORKID == 0 {
+                    return Ok(false);
+                }
+                self.cache
+                    .p2wsh_signature_hash_forkid(
+                        self.input_index,
+                        script_code,
+                        value,
+                        sighash_type,
+                        Some(fork_id),
+                    )
+                    .map_err(|e| ScriptError::Sighash(e.to_string()))?
+                    .to_byte_array()
+            }
+            (SighashAlgorithm::Bitcoin, SigVersion::WitnessV0) => {
+                let Ok(sighash_type) = EcdsaSighashType::from_standard(sighash_type) else {
+                    return Ok(false);
+                };
+                self.cache
+                    .p2wsh_signature_hash(self.input_index, script_code, value, sighash_type)
+                    .map_err(|e| ScriptError::Sighash(e.to_string()))?
+                    .to_byte_array()
+            }
+            (SighashAlgorithm::Bitcoin, _) => self
+                .cache
+                .legacy_signature_hash(self.input_index, script_code, sighash_type)
+                .map_err(|e| ScriptError::Sighash(e.to_string()))?
+                .to_byte_array(),
+        };
+
+        let message = secp256k1::Message::from_digest(digest);
+        Ok(self
+            .secp
+            .verify_ecdsa(&message, &signature, &pubkey)
+            .is_ok())
+    }
+
+    /// Check a BIP-340 signature (64 bytes, or 65 bytes with an explicit sighash type)
+    ///
+    /// Key path signatures have no leaf hash.
+    fn check_schnorr(
+        &mut self,
+        sig: &[u8],
+        pubkey: &XOnlyPublicKey,
+        leaf_hash: Option<TapLeafHash>,
+    ) -> Result<bool, ScriptError> {
+        let Ok(signature) = miniscript::bitcoin::taproot::Signature::from_slice(sig) else {
+            return Ok(false);
+        };
+        let prevouts = Prevouts::All(self.prevouts);
+        let digest = match leaf_hash {
+            Some(leaf_hash) => self
+                .cache
+                .taproot_script_spend_signature_hash(
+                    self.input_index,
+                    &prevouts,
+                    leaf_hash,
+                    signature.sighash_type,
+                )
+                .map_err(|e| ScriptError::Sighash(e.to_string()))?
+                .to_byte_array(),
+            None => self
+                .cache
+                .taproot_key_spend_signature_hash(
+                    self.input_index,
+                    &prevouts,
+                    signature.sighash_type,
+                )
+                .map_err(|e| ScriptError::Sighash(e.to_string()))?
+                .to_byte_array(),
+        };
+
+        let message = secp256k1::Message::from_digest(digest);
+        Ok(self
+            .secp
+            .verify_schnorr(&signature.signature, &message, pubkey)
+            .is_ok())
+    }
+
+    /// OP_CHECKDATASIG semantics: ECDSA signature (DER encoding, without sighash type) of the
+    /// SHA256 of `message`, failing the script for non-empty invalid signatures
+    fn check_data_sig(
+        &self,
+        sig: &[u8],
+        message: &[u8],
+        pubkey: &[u8],
+    ) -> Result<bool, ScriptError> {
+        if sig.is_empty() {
+            return Ok(false);
+        }
+        let valid = match (
+            secp256k1::ecdsa::Signature::from_der(sig),
+            secp256k1::PublicKey::from_slice(pubkey),
+        ) {
+            (Ok(signature), Ok(pubkey)) => {
+                let digest = sha256::Hash::hash(message).to_byte_array();
+                let message = secp256k1::Message::from_digest(digest);
+                self.secp
+                    .verify_ecdsa(&message, &signature, &pubkey)
+                    .is_ok()
+            }
+            _ => false,
+        };
+        if !valid {
+            return Err(ScriptError::InvalidSignature(format!(
+                "in OP_CHECKDATASIG for public key {}",
+                hex::DisplayHex::to_lower_hex_string(pubkey)
+            )));
+        }
+        Ok(true)
+    }
+
+    /// BIP-65: the transaction lock time is of the same kind as `lock_time` and not lower,
+    /// and the input does not disable it with a final sequence
+    fn check_lock_time(&self, lock_time: i64) -> bool {
+        let tx = self.cache.transaction();
+        let tx_lock_time = tx.lock_time.to_consensus_u32() as i64;
+        (lock_time < LOCKTIME_THRESHOLD) == (tx_lock_time < LOCKTIME_THRESHOLD)
+            && lock_time <= tx_lock_time
+            && !tx.input[self.input_index].sequence.is_final()
+    }
+
+    /// BIP-112: the input sequence is a relative lock time of the same kind as `sequence`
+    /// and not lower. Sequences with the disable flag set always pass.
+    fn check_sequence(&self, sequence: i64) -> bool {
+        const DISABLE_FLAG: i64 = 1 << 31;
+        const TYPE_FLAG: i64 = 1 << 22;
+        const MASK: i64 = TYPE_FLAG | 0xffff;
+        if sequence & DISABLE_FLAG != 0 {
+            return true;
+        }
+        let tx = self.cache.transaction();
+        let tx_sequence = tx.input[self.input_index].sequence.to_consensus_u32() as i64;
+        tx.version.0 >= 2
+            && tx_sequence & DISABLE_FLAG == 0
+            && (sequence & TYPE_FLAG) == (tx_sequence & TYPE_FLAG)
+            && sequence & MASK <= tx_sequence & MASK
+    }
+
+    /// OP_CHECKSIG semantics, failing the script for non-empty invalid signatures
+    fn check_sig(
+        &mut self,
+        sig: &[u8],
+        pubkey: &[u8],
+        script_code: &Script,
+        sig_version: SigVersion,
+    ) -> Result<bool, ScriptError> {
+        let valid = match sig_version {
+            SigVersion::Tapscript(leaf_hash) => {
+                if pubkey.is_empty() {
+                    return Err(ScriptError::InvalidSignature(
+                        "for empty tapscript public key".to_string(),
+                    ));
+                }
+                if sig.is_empty() {
+                    return Ok(false);
+                }
+                // BIP-342: public keys of unknown type succeed
+                if pubkey.len() != 32 {
+                    return Ok(true);
+                }
+                match XOnlyPublicKey::from_slice(pubkey) {
+                    Ok(key) => self.check_schnorr(sig, &key, Some(leaf_hash))?,
+                    Err(_) => false,
+                }
+            }
+            _ => self.check_ecdsa(sig, pubkey, script_code, sig_version)?,
+        };
+        if !valid && !sig.is_empty() {
+            return Err(ScriptError::InvalidSignature(format!(
+                "for public key {}",
+                hex::DisplayHex::to_lower_hex_string(pubkey)
+            )));
+        }
+        Ok(valid)
+    }
+}
+
+fn encode_num(n: i64) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut abs = n.unsigned_abs();
+    while abs > 0 {
+        bytes.push((abs & 0xff) as u8);
+        abs >>= 8;
+    }
+    if let Some(last) = bytes.last_mut() {
+        if *last & 0x80 != 0 {
+            bytes.push(if n < 0 { 0x80 } else { 0 });
+        } else if n < 0 {
+            *last |= 0x80;
+        }
+    }
+    bytes
+}
+
+fn decode_num(bytes: &[u8]) -> Result<i64, ScriptError> {
+    decode_num_with_max_len(bytes, MAX_SCRIPT_NUM_LEN)
+}
+
+fn decode_num_with_max_len(bytes: &[u8], max_len: usize) -> Result<i64, ScriptError> {
+    let Some((&last, rest)) = bytes.split_last() else {
+        return Ok(0);
+    };
+    if bytes.len() > max_len {
+        return Err(ScriptError::InvalidNumber);
+    }
+    // Minimal encoding: the last byte may only be 0x00/0x80 to hold the sign bit
+    if last & 0x7f == 0 && rest.last().is_none_or(|b| b & 0x80 == 0) {
+        return Err(ScriptError::InvalidNumber);
+    }
+    let magnitude = bytes
+        .iter()
+        .enumerate()
+        .fold(0i64, |n, (i, b)| n | ((*b as i64) << (8 * i)))
+        & !(0x80i64 << (8 * rest.len()));
+    Ok(if last & 0x80 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    })
+}
+
+fn cast_to_bool(bytes: &[u8]) -> bool {
+    match bytes.split_last() {
+        Some((&last, rest)) => rest.iter().any(|b| *b != 0) || (last != 0 && last != 0x80),
+        None => false,
+    }
+}
+
+fn pop(stack: &mut Vec<Vec<u8>>) -> Result<Vec<u8>, ScriptError> {
+    stack.pop().ok_or(ScriptError::StackUnderflow)
+}
+
+/// Push the result of a check, or fail the script if it is a *VERIFY opcode
+fn push_result(
+    stack: &mut Vec<Vec<u8>>,
+    success: bool,
+    verify: bool,
+    opcode: Opcode,
+) -> Result<(), ScriptError> {
+    if verify {
+        if !success {
+            return Err(ScriptError::VerifyFailed {
+                opcode: opcode.to_string(),
+            });
+        }
+    } else {
+        stack.push(if success { vec![1] } else { vec![] });
+    }
+    Ok(())
+}
+
+fn check_multisig<C: secp256k1::Verification>(
+    stack: &mut Vec<Vec<u8>>,
+    script_code: &Script,
+    sig_version: SigVersion,
+    checker: &mut SignatureChecker<'_, '_, C>,
+) -> Result<bool, ScriptError> {
+    let key_count = decode_num(&pop(stack)?)?;
+    if !(0..=MAX_MULTISIG_KEYS).contains(&key_count) {
+        return Err(ScriptError::InvalidMultisigCount);
+    }
+    let mut keys = (0..key_count)
+        .map(|_| pop(stack))
+        .collect::<Result<Vec<_>, _>>()?;
+    keys.reverse();
+    let sig_count = decode_num(&pop(stack)?)?;
+    if !(0..=key_count).contains(&sig_count) {
+        return Err(ScriptError::InvalidMultisigCount);
+    }
+    let mut sigs = (0..sig_count)
+        .map(|_| pop(stack))
+        .collect::<Result<Vec<_>, _>>()?;
+    sigs.reverse();
+    if !pop(stack)?.is_empty() {
+        return Err(ScriptError::NullDummy);
+    }
+
+    // Signatures must appear in the same order as their keys
+    let (mut sig_index, mut key_index) = (0, 0);
+    let mut success = true;
+    while success && sig_index < sigs.len() {
+        if checker.check_ecdsa(&sigs[sig_index], &keys[key_index], script_code, sig_version)? {
+            sig_index += 1;
+        }
+        key_index += 1;
+        success = sigs.len() - sig_index <= keys.len() - key_index;
+    }
+    if !success && sigs.iter().any(|sig| !sig.is_empty()) {
+        return Err(ScriptError::InvalidSignature(
+            "in OP_CHECKMULTISIG (missing or out of order)".to_string(),
+        ));
+    }
+    Ok(success)
+}
+
+fn eval_script<C: secp256k1::Verification>(
+    stack: &mut Vec<Vec<u8>>,
+    script: &Script,
+    sig_version: SigVersion,
+    checker: &mut SignatureChecker<'_, '_, C>,
+) -> Result<(), ScriptError> {
+    let is_tapscript = matches!(sig_version, SigVersion::Tapscript(_));
+    // Branch conditions of the enclosing OP_IF/OP_NOTIF blocks
+    let mut conditions: Vec<bool> = Vec::new();
+    for instruction in script.instructions() {
+        let executing = conditions.iter().all(|c| *c);
+        let op = match instruction.map_err(|e| ScriptError::InvalidScript(e.to_string()))? {
+            Instruction::PushBytes(bytes) => {
+                if executing {
+                    stack.push(bytes.as_bytes().to_vec());
+                }
+                continue;
+            }
+            Instruction::Op(op) => op,
+        };
+        if !executing && !matches!(op, OP_IF | OP_NOTIF | OP_ELSE | OP_ENDIF) {
+            continue;
+        }
+        match op {
+            OP_PUSHNUM_NEG1 => stack.push(encode_num(-1)),
+            op if (OP_PUSHNUM_1.to_u8()..=OP_PUSHNUM_16.to_u8()).contains(&op.to_u8()) => {
+                stack.push(encode_num((op.to_u8() - OP_PUSHNUM_1.to_u8() + 1) as i64))
+            }
+            OP_NOP => {}
+            OP_IF | OP_NOTIF => {
+                let mut condition = false;
+                if executing {
+                    let top = pop(stack)?;
+                    if sig_version != SigVersion::Base && !matches!(top.as_slice(), [] | [1]) {
+                        return Err(ScriptError::MinimalIf);
+                    }
+                    condition = cast_to_bool(&top) == (op == OP_IF);
+                }
+                conditions.push(condition);
+            }
+            OP_ELSE => {
+                let condition = conditions
+                    .last_mut()
+                    .ok_or(ScriptError::UnbalancedConditional)?;
+                *condition = !*condition;
+            }
+            OP_ENDIF => {
+                conditions.pop().ok_or(ScriptError::UnbalancedConditional)?;
+            }
+            OP_VERIFY => {
+                let top = pop(stack)?;
+                push_result(stack, cast_to_bool(&top), true, op)?;
+            }
+            OP_RETURN => return Err(ScriptError::OpReturn),
+            OP_DUP => {
+                let top = stack.last().cloned().ok_or(ScriptError::StackUnderflow)?;
+                stack.push(top);
+            }
+            OP_IFDUP => {
+                let top = stack.last().cloned().ok_or(ScriptError::StackUnderflow)?;
+                if cast_to_bool(&top) {
+                    stack.push(top);
+                }
+            }
+            OP_DROP => {
+                pop(stack)?;
+            }
+            OP_EQUAL | OP_EQUALVERIFY => {
+                let (a, b) = (pop(stack)?, pop(stack)?);
+                push_result(stack, a == b, op == OP_EQUALVERIFY, op)?;
+            }
+            OP_HASH160 => {
+                let data = pop(stack)?;
+                stack.push(hash160::Hash::hash(&data).to_byte_array().to_vec());
+            }
+            OP_SHA256 => {
+                let data = pop(stack)?;
+                stack.push(sha256::Hash::hash(&data).to_byte_array().to_vec());
+            }
+            OP_CHECKSIG | OP_CHECKSIGVERIFY => {
+                let (pubkey, sig) = (pop(stack)?, pop(stack)?);
+                let valid = checker.check_sig(&sig, &pubkey, script, sig_version)?;
+                push_result(stack, valid, op == OP_CHECKSIGVERIFY, op)?;
+            }
+            OP_CHECKSIGADD if is_tapscript => {
+                let pubkey = pop(stack)?;
+                let n = decode_num(&pop(stack)?)?;
+                let sig = pop(stack)?;
+                let valid = checker.check_sig(&sig, &pubkey, script, sig_version)?;
+                stack.push(encode_num(n + valid as i64));
+            }
+            OP_CHECKMULTISIG | OP_CHECKMULTISIGVERIFY if !is_tapscript => {
+                let valid = check_multisig(stack, script, sig_version, checker)?;
+                push_result(stack, valid, op == OP_CHECKMULTISIGVERIFY, op)?;
+            }
+            OP_CHECKDATASIG | OP_CHECKDATASIGVERIFY if checker.checkdatasig && !is_tapscript => {
+                let (pubkey, message, sig) = (pop(stack)?, pop(stack)?, pop(stack)?);
+                let valid = checker.check_data_sig(&sig, &message, &pubkey)?;
+                push_result(stack, valid, op == OP_CHECKDATASIGVERIFY, op)?;
+            }
+            OP_CLTV | OP_CSV => {
+                let top = stack.last().ok_or(ScriptError::StackUnderflow)?;
+                let lock_time = decode_num_with_max_len(top, MAX_LOCKTIME_NUM_LEN)?;
+                if lock_time < 0 {
+                    return Err(ScriptError::NegativeLocktime);
+                }
+                let satisfied = if op == OP_CLTV {
+                    checker.check_lock_time(lock_time)
+                } else {
+                    checker.check_sequence(lock_time)
+                };
+                if !satisfied {
+                    return Err(ScriptError::UnsatisfiedLocktime {
+                        opcode: op.to_string(),
+                    });
+                }
+            }
+            _ => return Err(ScriptError::UnsupportedOpcode(op.to_string())),
+        }
+    }
+    if !conditions.is_empty() {
+        return Err(ScriptError::UnbalancedConditional);
+    }
+    Ok(())
+}
+
+/// The stack must hold exactly one true element after execution
+fn check_final_stack(stack: &[Vec<u8>]) -> Result<(), ScriptError> {
+    match stack {
+        [top] if cast_to_bool(top) => Ok(()),
+        [_] | [] => Err(ScriptError::EvalFalse),
+        _ if !cast_to_bool(&stack[stack.len() - 1]) => Err(ScriptError::EvalFalse),
+        _ => Err(ScriptError::CleanStack { len: stack.len() }),
+    }
+}
+
+fn verify_witness_program<C: secp256k1::Verification>(
+    witness: Vec<Vec<u8>>,
+    version: WitnessVersion,
+    program: &[u8],
+    is_p2sh: bool,
+    checker: &mut SignatureChecker<'_, '_, C>,
+) -> Result<(), ScriptError> {
+    match (version, program.len()) {
+        (WitnessVersion::V0, 32) => {
+            let mut stack = witness;
+            let witness_script = stack
+                .pop()
+                .ok_or_else(|| ScriptError::InvalidWitness("empty witness".to_string()))?;
+            if sha256::Hash::hash(&witness_script).as_byte_array() != program {
+                return Err(ScriptError::ScriptHashMismatch);
+            }
+            let witness_script = Script::from_bytes(&witness_script);
+            eval_script(&mut stack, witness_script, SigVersion::WitnessV0, checker)?;
+            check_final_stack(&stack)
+        }
+        (WitnessVersion::V0, 20) => {
+            if witness.len() != 2 {
+                return Err(ScriptError::InvalidWitness(format!(
+                    "P2WPKH witness has {} elements, expected 2",
+                    witness.len()
+                )));
+            }
+            let mut stack = witness;
+            let script_code = p2pkh_script(program);
+            eval_script(&mut stack, &script_code, SigVersion::WitnessV0, checker)?;
+            check_final_stack(&stack)
+        }
+        (WitnessVersion::V1, 32) if !is_p2sh => verify_taproot(witness, program, checker),
+        _ => Err(ScriptError::InvalidWitness(format!(
+            "unsupported witness program (version {}, {} bytes)",
+            version.to_num(),
+            program.len()
+        ))),
+    }
+}
+
+fn verify_taproot<C: secp256k1::Verification>(
+    mut stack: Vec<Vec<u8>>,
+    program: &[u8],
+    checker: &mut SignatureChecker<'_, '_, C>,
+) -> Result<(), ScriptError> {
+    let output_key = XOnlyPublicKey::from_slice(program)
+        .map_err(|e| ScriptError::InvalidWitness(format!("invalid taproot output key: {}", e)))?;
+    if stack.len() >= 2 && stack.last().and_then(|e| e.first()) == Some(&0x50) {
+        return Err(ScriptError::InvalidWitness(
+            "taproot annex is not supported".to_string(),
+        ));
+    }
+
+    match stack.len() {
+        0 => Err(ScriptError::InvalidWitness("empty witness".to_string())),
+        1 => {
+            // Key path spend
+            if checker.check_schnorr(&stack[0], &output_key, None)? {
+                Ok(())
+            } else {
+                Err(ScriptError::InvalidSignature(
+                    "for taproot key path".to_string(),
+                ))
+            }
+        }
+        _ => {
+            let control_block = pop(&mut stack)?;
+            let leaf_script = pop(&mut stack)?;
+            let control_block = ControlBlock::decode(&control_block).map_err(|e| {
+                ScriptError::InvalidWitness(format!("invalid control block: {}", e))
+            })?;
+            if control_block.leaf_version != LeafVersion::TapScript {
+                return Err(ScriptError::InvalidWitness(format!(
+                    "unsupported leaf version {:#04x}",
+                    control_block.leaf_version.to_consensus()
+                )));
+            }
+            let leaf_script = Script::from_bytes(&leaf_script);
+            if !control_block.verify_taproot_commitment(checker.secp, output_key, leaf_script) {
+                return Err(ScriptError::ControlBlockMismatch);
+            }
+            let leaf_hash = TapLeafHash::from_script(leaf_script, LeafVersion::TapScript);
+            eval_script(
+                &mut stack,
+                leaf_script,
+                SigVersion::Tapscript(leaf_hash),
+                checker,
+            )?;
+            check_final_stack(&stack)
+        }
+    }
+}
+
+fn p2pkh_script(pubkey_hash: &[u8]) -> miniscript::bitcoin::ScriptBuf {
+    let pubkey_hash: [u8; 20] = pubkey_hash
+        .try_into()
+        .expect("P2WPKH programs are 20 bytes");
+    Builder::new()
+        .push_opcode(OP_DUP)
+        .push_opcode(OP_HASH160)
+        .push_slice(pubkey_hash)
+        .push_opcode(OP_EQUALVERIFY)
+        .push_opcode(OP_CHECKSIG)
+        .into_script()
+}
+
+/// Execute the scriptSig and witness of input `input_index` of `tx` against its prevout
+///
+/// `prevouts` holds the spent output of every input of `tx`, in order. `checkdatasig`
+/// enables OP_CHECKDATASIG and OP_CHECKDATASIGVERIFY (Bitcoin Cash and eCash).
+pub fn verify_input<C: secp256k1::Verification>(
+    secp: &secp256k1::Secp256k1<C>,
+    cache: &mut SighashCache<&Transaction>,
+    input_index: usize,
+    prevouts: &[TxOut],
+    algorithm: SighashAlgorithm,
+    checkdatasig: bool,
+) -> Result<(), ScriptError> {
+    let tx: &Transaction = cache.transaction();
+    let tx_in = &tx.input[input_index];
+    let script_sig = tx_in.script_sig.clone();
+    let witness = tx_in.witness.to_vec();
+    let script_pubkey = prevouts[input_index].script_pubkey.clone();
+    let mut checker = SignatureChecker {
+        secp,
+        cache,
+        input_index,
+        prevouts,
+        algorithm,
+        checkdatasig,
+    };
+
+    if !script_sig.is_push_only() {
+        return Err(ScriptError::ScriptSigNotPushOnly);
+    }
+    let mut stack = Vec::new();
+    eval_script(&mut stack, &script_sig, SigVersion::Base, &mut checker)?;
+    let script_sig_stack = stack.clone();
+    eval_script(&mut stack, &script_pubkey, SigVersion::Base, &mut checker)?;
+    if !stack.last().is_some_and(|top| cast_to_bool(top)) {
+        return Err(ScriptError::EvalFalse);
+    }
+
+    if let Some(version) = script_pubkey.witness_version() {
+        if !script_sig.is_empty() {
+            return Err(ScriptError::InvalidWitness(
+                "native segwit input has a scriptSig".to_string(),
+            ));
+        }
+        let program = &script_pubkey.as_bytes()[2..];
+        return verify_witness_program(witness, version, program, false, &mut checker);
+    }
+
+    if script_pubkey.is_p2sh() {
+        stack = script_sig_stack;
+        let redeem_script = pop(&mut stack)?;
+        let redeem_script = Script::from_bytes(&redeem_script);
+        if let Some(version) = redeem_script.witness_version() {
+            if !stack.is_empty() {
+                return Err(ScriptError::InvalidWitness(
+                    "scriptSig of a wrapped segwit input must only push the redeem script"
+                        .to_string(),
+                ));
+            }
+            let program = &redeem_script.as_bytes()[2..];
+            return verify_witness_program(witness, version, program, true, &mut checker);
+        }
+        eval_script(&mut stack, redeem_script, SigVersion::Base, &mut checker)?;
+    }
+
+    if !witness.is_empty() {
+        return Err(ScriptError::InvalidWitness(
+            "non-segwit input has a witness".to_string(),
+        ));
+    }
+    check_final_stack(&stack)
+}
+
+impl BitGoPsbt {
+    pub(crate) fn sighash_algorithm(&self) -> Result<SighashAlgorithm, VerifyInputScriptsError> {
+        match self {
+            BitGoPsbt::Zcash(zcash_psbt, _) => Ok(SighashAlgorithm::Zcash {
+                consensus_branch_id: propkv::get_zec_consensus_branch_id(&zcash_psbt.psbt)
+                    .ok_or_else(|| {
+                        VerifyInputScriptsError::MissingSighashData(
+                            "Missing ZecConsensusBranchId in PSBT".to_string(),
+                        )
+                    })?,
+                version_group_id: zcash_psbt
+                    .version_group_id
+                    .unwrap_or(zcash_psbt::ZCASH_SAPLING_VERSION_GROUP_ID),
+                expiry_height: zcash_psbt.expiry_height.unwrap_or(0),
+            }),
+            _ => Ok(match sighash::get_sighash_fork_id(self.network()) {
+                Some(fork_id) => SighashAlgorithm::ForkId(fork_id),
+                None => SighashAlgorithm::Bitcoin,
+            }),
+        }
+    }
+
+    /// Execute the final scriptSig and witness of every input against its prevout
+    ///
+    /// Call this after finalization to catch finalization bugs before broadcasting.
+    /// Every input must be finalized and have UTXO data.
+    pub fn verify_input_scripts<C: secp256k1::Verification>(
+        &self,
+        secp: &secp256k1::Secp256k1<C>,
+    ) -> Result<(), VerifyInputScriptsError> {
+        let psbt = self.psbt();
+        let algorithm = self.sighash_algorithm()?;
+        let checkdatasig = matches!(
+            self.network().mainnet(),
+            crate::Network::BitcoinCash | crate::Network::Ecash
+        );
+        let prevouts = psbt_wallet_input::collect_prevouts(psbt)
+            .map_err(VerifyInputScriptsError::MissingSighashData)?;
+
+        let mut tx = psbt.unsigned_tx.clone();
+        for (input_index, (tx_in, input)) in tx.input.iter_mut().zip(&psbt.inputs).enumerate() {
+            if input.final_script_sig.is_none() && input.final_script_witness.is_none() {
+                return Err(VerifyInputScriptsError::NotFinalized { input_index });
+            }
+            tx_in.script_sig = input.final_script_sig.clone().unwrap_or_default();
+            tx_in.witness = input.final_script_witness.clone().unwrap_or_default();
+        }
+
+        let mut cache = SighashCache::new(&tx);
+        for input_index in 0..prevouts.len() {
+            verify_input(
+                secp,
+                &mut cache,
+                input_index,
+                &prevouts,
+                algorithm,
+                checkdatasig,
+            )
+            .map_err(|error| VerifyInputScriptsError::Script { input_index, error })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::test_utils::fixtures;
+    use miniscript::bitcoin::absolute::LockTime;
+    use miniscript::bitcoin::script::PushBytesBuf;
+    use miniscript::bitcoin::transaction::Version;
+    use miniscript::bitcoin::{Amount, ScriptBuf, Sequence, TxIn, Witness};
+
+    #[test]
+    fn test_script_num() {
+        for n in [0, 1, -1, 16, 127, 128, -128, 255, 256, -32768, 0x7fffffff] {
+            assert_eq!(decode_num(&encode_num(n)).unwrap(), n);
+        }
+        assert_eq!(encode_num(128), vec![0x80, 0x00]);
+        assert_eq!(encode_num(-1), vec![0x81]);
+        assert!(decode_num(&[0x01, 0x00]).is_err());
+        assert!(decode_num(&[0x00]).is_err());
+        assert!(decode_num(&[0x01, 0x02, 0x03, 0x04, 0x05]).is_err());
+        assert!(!cast_to_bool(&[0x00, 0x80]));
+        assert!(cast_to_bool(&[0x80, 0x00]));
+    }
+
+    /// Spend a P2SH output of `redeem_script` with `pushes` followed by the redeem script
+    fn verify_p2sh(
+        redeem_script: &Script,
+        pushes: &[Vec<u8>],
+        lock_time: u32,
+        sequence: u32,
+        checkdatasig: bool,
+    ) -> Result<(), ScriptError> {
+        let mut builder = Builder::new();
+        for push in pushes.iter().chain([&redeem_script.to_bytes()]) {
+            builder = builder.push_slice(PushBytesBuf::try_from(push.clone()).unwrap());
+        }
+        let tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::from_consensus(lock_time),
+            input: vec![TxIn {
+                script_sig: builder.into_script(),
+                sequence: Sequence(sequence),
+                ..Default::default()
+            }],
+            output: vec![],
+        };
+        let prevouts = [TxOut {
+            value: Amount::from_sat(10_000),
+            script_pubkey: redeem_script.to_p2sh(),
+        }];
+        verify_input(
+            &secp256k1::Secp256k1::verification_only(),
+            &mut SighashCache::new(&tx),
+            0,
+            &prevouts,
+            SighashAlgorithm::ForkId(0),
+            checkdatasig,
+        )
+    }
+
+    #[test]
+    fn test_conditionals() {
+        let script = Builder::new()
+            .push_opcode(OP_IF)
+            .push_int(2)
+            .push_opcode(OP_ELSE)
+            .push_int(3)
+            .push_opcode(OP_ENDIF)
+            .push_int(3)
+            .push_opcode(OP_EQUAL)
+            .into_script();
+        verify_p2sh(&script, &[vec![]], 0, 0xffffffff, false).unwrap();
+        assert!(matches!(
+            verify_p2sh(&script, &[vec![1]], 0, 0xffffffff, false),
+            Err(ScriptError::EvalFalse)
+        ));
+
+        let unbalanced = Builder::new()
+            .push_int(1)
+            .push_opcode(OP_IF)
+            .push_int(1)
+            .into_script();
+        assert!(matches!(
+            verify_p2sh(&unbalanced, &[], 0, 0xffffffff, false),
+            Err(ScriptError::UnbalancedConditional)
+        ));
+    }
+
+    #[test]
+    fn test_check_lock_time_verify() {
+        let script = |lock_time: i64| {
+            Builder::new()
+                .push_int(lock_time)
+                .push_opcode(OP_CLTV)
+                .push_opcode(OP_DROP)
+                .push_int(1)
+                .into_script()
+        };
+        verify_p2sh(&script(100), &[], 100, 0xfffffffe, false).unwrap();
+        for (lock_time, sequence) in [(99, 0xfffffffe), (100, 0xffffffff), (500_000_100, 0)] {
+            assert!(matches!(
+                verify_p2sh(&script(100), &[], lock_time, sequence, false),
+                Err(ScriptError::UnsatisfiedLocktime { .. })
+            ));
+        }
+        assert!(matches!(
+            verify_p2sh(&script(-1), &[], 100, 0xfffffffe, false),
+            Err(ScriptError::NegativeLocktime)
+        ));
+    }
+
+    #[test]
+    fn test_check_data_sig() {
+        let secp = secp256k1::Secp256k1::new();
+        let secret_key = secp256k1::SecretKey::from_slice(&[1; 32]).unwrap();
+        let pubkey = secp256k1::PublicKey::from_secret_key(&secp, &secret_key);
+        let message = b"message".to_vec();
+        let digest = sha256::Hash::hash(&message).to_byte_array();
+        let sig = secp
+            .sign_ecdsa(&secp256k1::Message::from_digest(digest), &secret_key)
+            .serialize_der()
+            .to_vec();
+        let script = Builder::new()
+            .push_slice(pubkey.serialize())
+            .push_opcode(OP_CHECKDATASIG)
+            .into_script();
+
+        verify_p2sh(&script, &[sig.clone(), message.clone()], 0, 0, true).unwrap();
+        assert!(matches!(
+            verify_p2sh(&script, &[sig.clone(), b"other".to_vec()], 0, 0, true),
+            Err(ScriptError::InvalidSignature(_))
+        ));
+        assert!(matches!(
+            verify_p2sh(&script, &[vec![], message.clone()], 0, 0, true),
+            Err(ScriptError::EvalFalse)
+        ));
+        // Only Bitcoin Cash and eCash have OP_CHECKDATASIG
+        assert!(matches!(
+            verify_p2sh(&script, &[sig, message], 0, 0, false),
+            Err(ScriptError::UnsupportedOpcode(_))
+        ));
+    }
+
+    fn load_finalized(network: crate::Network, format: fixtures::TxFormat) -> BitGoPsbt {
+        let fixture = fixtures::load_psbt_fixture_with_format_and_namespace(
+            network.to_utxolib_name(),
+            fixtures::SignatureState::Fullsigned,
+            format,
+            fixtures::FixtureNamespace::UtxolibCompat,
+        )
+        .expect("Failed to load fixture");
+        let mut psbt = fixture
+            .to_bitgo_psbt(network)
+            .expect("Failed to convert to BitGo PSBT");
+        psbt.finalize_mut(&secp256k1::Secp256k1::verification_only())
+            .expect("Failed to finalize PSBT");
+        psbt
+    }
+
+    fn is_signature(element: &[u8]) -> bool {
+        matches!(element.len(), 64 | 65) || (element.len() > 8 && element[0] == 0x30)
+    }
+
+    /// Flip a bit in the first signature of the final scriptSig or witness
+    fn tamper_signature(input: &mut miniscript::bitcoin::psbt::Input) {
+        if let Some(witness) = &input.final_script_witness {
+            let mut elements = witness.to_vec();
+            let sig = elements
+                .iter_mut()
+                .find(|e| is_signature(e))
+                .expect("witness has a signature");
+            sig[10] ^= 1;
+            input.final_script_witness = Some(Witness::from_slice(&elements));
+            return;
+        }
+        let script_sig = input.final_script_sig.as_ref().expect("finalized input");
+        let mut pushes: Vec<Vec<u8>> = script_sig
+            .instructions()
+            .map(|i| match i.unwrap() {
+                Instruction::PushBytes(bytes) => bytes.as_bytes().to_vec(),
+                Instruction::Op(op) => panic!("unexpected opcode {}", op),
+            })
+            .collect();
+        let sig = pushes
+            .iter_mut()
+            .find(|e| is_signature(e))
+            .expect("scriptSig has a signature");
+        sig[10] ^= 1;
+        let mut builder = Builder::new();
+        for push in pushes {
+            builder = builder.push_slice(PushBytesBuf::try_from(push).unwrap());
+        }
+        input.final_script_sig = Some(builder.into_script());
+    }
+
+    crate::test_psbt_fixtures!(test_verify_input_scripts, network, format, {
+        let secp = secp256k1::Secp256k1::verification_only();
+        let psbt = load_finalized(network, format);
+        psbt.verify_input_scripts(&secp)
+            .expect("Finalized fixture should verify");
+
+        for input_index in 0..psbt.psbt().inputs.len() {
+            let mut tampered = psbt.clone();
+            tamper_signature(&mut tampered.psbt_mut().inputs[input_index]);
+            match tampered.verify_input_scripts(&secp) {
+                Err(VerifyInputScriptsError::Script {
+                    input_index: failed,
+                    ..
+                }) => assert_eq!(failed, input_index),
+                other => panic!(
+                    "Expected input {} to fail verification, got {:?}",
+                    input_index, other
+                ),
+            }
+        }
+    });
+
+    #[test]
+    fn test_verify_input_scripts_rejects_unfinalized_and_wrong_scripts() {
+        let secp = secp256k1::Secp256k1::verification_only();
+        let network = crate::Network::Bitcoin;
+        let psbt = load_finalized(network, fixtures::TxFormat::Psbt);
+
+        let mut unfinalized = psbt.clone();
+        unfinalized.psbt_mut().inputs[0].final_script_sig = None;
+        unfinalized.psbt_mut().inputs[0].final_script_witness = None;
+        assert!(matches!(
+            unfinalized.verify_input_scripts(&secp),
+            Err(VerifyInputScriptsError::NotFinalized { input_index: 0 })
+        ));
+
+        // Native segwit inputs must have an empty scriptSig
+        let segwit_index = psbt
+            .psbt()
+            .inputs
+            .iter()
+            .position(|input| input.final_script_sig.as_ref().is_none_or(|s| s.is_empty()))
+            .expect("fixture has a native segwit input");
+        let mut wrong_script = psbt.clone();
+        wrong_script.psbt_mut().inputs[segwit_index].final_script_sig =
+            Some(ScriptBuf::from_bytes(vec![OP_PUSHNUM_1.to_u8()]));
+        assert!(matches!(
+            wrong_script.verify_input_scripts(&secp),
+            Err(VerifyInputScriptsError::Script { input_index, .. }) if input_index == segwit_index
+        ));
+    }
+}
@@ -0,0 +1,606 @@
+//! Consistency checks between PSBT metadata and the scripts it claims to spend or create
+//!
+//! A PSBT from an untrusted coordinator can carry redeem/witness scripts, taproot leaves or
+//! key derivations that do not belong to the scriptPubKey they are attached to. Signers that
+//! trust this metadata can be tricked into signing for the wrong script or key, so strict
+//! deserialization runs [`validate_psbt_scripts`] before the PSBT is handed to callers.
+
+use std::collections::BTreeMap;
+
+use miniscript::bitcoin::bip32::{KeySource, Xpub};
+use miniscript::bitcoin::psbt::{Input, Output, Psbt};
+use miniscript::bitcoin::secp256k1::{self, PublicKey, XOnlyPublicKey};
+use miniscript::bitcoin::taproot::TaprootSpendInfo;
+use miniscript::bitcoin::{Script, ScriptBuf, TapLeafHash};
+
+use super::p2tr_musig2_input::parse_musig2_output_participants;
+use super::psbt_wallet_input::get_output_script_and_value;
+
+/// Location of a PSBT map entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PsbtLocation {
+    Input(usize),
+    Output(usize),
+}
+
+impl std::fmt::Display for PsbtLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PsbtLocation::Input(index) => write!(f, "input {}", index),
+            PsbtLocation::Output(index) => write!(f, "output {}", index),
+        }
+    }
+}
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum ScriptValidationError {
+    /// The input has scripts attached but no UTXO to check them against
+    MissingUtxo { input_index: usize, error: String },
+    /// `redeem_script` does not hash to the P2SH scriptPubKey
+    RedeemScriptMismatch { location: PsbtLocation },
+    /// `witness_script` does not hash to the P2WSH program
+    WitnessScriptMismatch { location: PsbtLocation },
+    /// Taproot metadata is attached to a non-taproot scriptPubKey
+    NotTaproot { location: PsbtLocation },
+    /// A `tap_scripts` control block does not commit to the leaf script
+    ControlBlockMismatch { input_index: usize },
+    /// `tap_internal_key` and `tap_tree` do not produce the output key
+    TaprootOutputKeyMismatch { output_index: usize },
+    /// The MuSig2 participants record of an output cannot be parsed
+    InvalidMusig2Participants { output_index: usize, error: String },
+    /// The MuSig2 participants do not aggregate to the internal key, or belong to another
+    /// output key
+    Musig2ParticipantsMismatch { output_index: usize },
+    /// A key origin claims a global xpub, but the key does not derive from it
+    DerivationMismatch {
+        location: PsbtLocation,
+        pubkey: String,
+    },
+    /// Deriving a key origin path from a global xpub failed
+    Derivation {
+        location: PsbtLocation,
+        error: String,
+    },
+}
+
+impl std::fmt::Display for ScriptValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScriptValidationError::MissingUtxo { input_index, error } => {
+                write!(
+                    f,
+                    "Cannot validate scripts of input {}: {}",
+                    input_index, error
+                )
+            }
+            ScriptValidationError::RedeemScriptMismatch { location } => {
+                write!(
+                    f,
+                    "Redeem script of {} does not match its scriptPubKey",
+                    location
+                )
+            }
+            ScriptValidationError::WitnessScriptMismatch { location } => {
+                write!(
+                    f,
+                    "Witness script of {} does not match its scriptPubKey",
+                    location
+                )
+            }
+            ScriptValidationError::NotTaproot { location } => {
+                write!(
+                    f,
+                    "{} has taproot fields but a non-taproot scriptPubKey",
+                    location
+                )
+            }
+            ScriptValidationError::ControlBlockMismatch { input_index } => write!(
+                f,
+                "Control block of input {} does not commit to its leaf script",
+                input_index
+            ),
+            ScriptValidationError::TaprootOutputKeyMismatch { output_index } => write!(
+                f,
+                "Taproot internal key and tree of output {} do not match its output key",
+                output_index
+            ),
+            ScriptValidationError::InvalidMusig2Participants {
+                output_index,
+                error,
+            } => write!(
+                f,
+                "Invalid MuSig2 participants of output {}: {}",
+                output_index, error
+            ),
+            ScriptValidationError::Musig2ParticipantsMismatch { output_index } => write!(
+                f,
+                "MuSig2 participants of output {} do not match its taproot keys",
+                output_index
+            ),
+            ScriptValidationError::DerivationMismatch { location, pubkey } => write!(
+                f,
+                "Public key {} of {} does not derive from the global xpub",
+                pubkey, location
+            ),
+            ScriptValidationError::Derivation { location, error } => {
+                write!(f, "Failed to derive key origin of {}: {}", location, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScriptValidationError {}
+
+crate::impl_wasm_error_code!(ScriptValidationError);
+
+fn p2tr_output_key(script_pubkey: &Script) -> Option<XOnlyPublicKey> {
+    if !script_pubkey.is_p2tr() {
+        return None;
+    }
+    XOnlyPublicKey::from_slice(&script_pubkey.as_bytes()[2..34]).ok()
+}
+
+/// Check `redeem_script` and `witness_script` against `script_pubkey`
+fn validate_redeem_and_witness_scripts(
+    location: PsbtLocation,
+    script_pubkey: &Script,
+    redeem_script: Option<&ScriptBuf>,
+    witness_script: Option<&ScriptBuf>,
+) -> Result<(), ScriptValidationError> {
+    // The script the witness program is embedded in: the redeem script for P2SH-P2WSH,
+    // the scriptPubKey itself for native P2WSH
+    let witness_program = match redeem_script {
+        Some(redeem_script) => {
+            if ScriptBuf::new_p2sh(&redeem_script.script_hash()) != *script_pubkey {
+                return Err(ScriptValidationError::RedeemScriptMismatch { location });
+            }
+            redeem_script.as_script()
+        }
+        None => script_pubkey,
+    };
+
+    if let Some(witness_script) = witness_script {
+        if ScriptBuf::new_p2wsh(&witness_script.wscript_hash()) != *witness_program {
+            return Err(ScriptValidationError::WitnessScriptMismatch { location });
+        }
+    }
+
+    Ok(())
+}
+
+/// Derive the key a key origin points at, if it descends from a global xpub
+///
+/// Key origins carry the master fingerprint and the full path from the master key (BIP-174),
+/// so they are matched against the origin recorded for each global xpub, and the path below
+/// the xpub is derived from it. Key origins matching none of the global xpubs return `None`;
+/// they belong to keys outside the wallet (e.g. external inputs).
+fn derive_key_origin<C: secp256k1::Verification>(
+    secp: &secp256k1::Secp256k1<C>,
+    location: PsbtLocation,
+    global_xpubs: &BTreeMap<Xpub, KeySource>,
+    (fingerprint, path): &KeySource,
+) -> Result<Option<PublicKey>, ScriptValidationError> {
+    let Some((xpub, relative_path)) =
+        global_xpubs
+            .iter()
+            .find_map(|(xpub, (master_fingerprint, xpub_path))| {
+                let relative_path = path.as_ref().strip_prefix(xpub_path.as_ref())?;
+                (master_fingerprint == fingerprint).then_some((xpub, relative_path))
+            })
+    else {
+        return Ok(None);
+    };
+    xpub.derive_pub(secp, &relative_path)
+        .map(|derived| Some(derived.public_key))
+        .map_err(|e| ScriptValidationError::Derivation {
+            location,
+            error: e.to_string(),
+        })
+}
+
+/// Check that every key origin pointing at a global xpub derives to the stated key
+fn validate_key_origins<C: secp256k1::Verification>(
+    secp: &secp256k1::Secp256k1<C>,
+    location: PsbtLocation,
+    global_xpubs: &BTreeMap<Xpub, KeySource>,
+    bip32_derivation: &BTreeMap<PublicKey, KeySource>,
+    tap_key_origins: &BTreeMap<XOnlyPublicKey, (Vec<TapLeafHash>, KeySource)>,
+) -> Result<(), ScriptValidationError> {
+    for (pubkey, source) in bip32_derivation {
+        match derive_key_origin(secp, location, global_xpubs, source)? {
+            Some(derived) if derived != *pubkey => {
+                return Err(ScriptValidationError::DerivationMismatch {
+                    location,
+                    pubkey: pubkey.to_string(),
+                })
+            }
+            _ => {}
+        }
+    }
+    for (pubkey, (_, source)) in tap_key_origins {
+        match derive_key_origin(secp, location, global_xpubs, source)? {
+            Some(derived) if derived.x_only_public_key().0 != *pubkey => {
+                return Err(ScriptValidationError::DerivationMismatch {
+                    location,
+                    pubkey: pubkey.to_string(),
+                })
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn validate_input<C: secp256k1::Verification>(
+    secp: &secp256k1::Secp256k1<C>,
+    psbt: &Psbt,
+    input_index: usize,
+    input: &Input,
+    global_xpubs: &BTreeMap<Xpub, KeySource>,
+) -> Result<(), ScriptValidationError> {
+    let location = PsbtLocation::Input(input_index);
+    let has_scripts = input.redeem_script.is_some()
+        || input.witness_script.is_some()
+        || !input.tap_scripts.is_empty();
+
+    if has_scripts {
+        let prevout = psbt.unsigned_tx.input[input_index].previous_output;
+        let (script_pubkey, _) = get_output_script_and_value(input, prevout).map_err(|e| {
+            ScriptValidationError::MissingUtxo {
+                input_index,
+                error: e.to_string(),
+            }
+        })?;
+
+        validate_redeem_and_witness_scripts(
+            location,
+            script_pubkey,
+            input.redeem_script.as_ref(),
+            input.witness_script.as_ref(),
+        )?;
+
+        if !input.tap_scripts.is_empty() {
+            let output_key = p2tr_output_key(script_pubkey)
+                .ok_or(ScriptValidationError::NotTaproot { location })?;
+            for (control_block, (script, _leaf_version)) in &input.tap_scripts {
+                if !control_block.verify_taproot_commitment(secp, output_key, script) {
+                    return Err(ScriptValidationError::ControlBlockMismatch { input_index });
+                }
+            }
+        }
+    }
+
+    validate_key_origins(
+        secp,
+        location,
+        global_xpubs,
+        &input.bip32_derivation,
+        &input.tap_key_origins,
+    )
+}
+
+fn validate_output<C: secp256k1::Verification>(
+    secp: &secp256k1::Secp256k1<C>,
+    psbt: &Psbt,
+    output_index: usize,
+    output: &Output,
+    global_xpubs: &BTreeMap<Xpub, KeySource>,
+) -> Result<(), ScriptValidationError> {
+    let location = PsbtLocation::Output(output_index);
+    let script_pubkey = &psbt.unsigned_tx.output[output_index].script_pubkey;
+
+    validate_redeem_and_witness_scripts(
+        location,
+        script_pubkey,
+        output.redeem_script.as_ref(),
+        output.witness_script.as_ref(),
+    )?;
+
+    if let (Some(internal_key), Some(tap_tree)) = (output.tap_internal_key, &output.tap_tree) {
+        let output_key =
+            p2tr_output_key(script_pubkey).ok_or(ScriptValidationError::NotTaproot { location })?;
+        let spend_info =
+            TaprootSpendInfo::from_node_info(secp, internal_key, tap_tree.node_info().clone());
+        if spend_info.output_key().to_x_only_public_key() != output_key {
+            return Err(ScriptValidationError::TaprootOutputKeyMismatch { output_index });
+        }
+    }
+
+    let participants = parse_musig2_output_participants(output).map_err(|e| {
+        ScriptValidationError::InvalidMusig2Participants {
+            output_index,
+            error: e.to_string(),
+        }
+    })?;
+    if let Some(participants) = participants {
+        let output_key =
+            p2tr_output_key(script_pubkey).ok_or(ScriptValidationError::NotTaproot { location })?;
+        let aggregate_key = participants
+            .aggregate_pub_key()
+            .map_err(|e| ScriptValidationError::InvalidMusig2Participants {
+                output_index,
+                error: e.to_string(),
+            })?
+            .0
+            .x_only_public_key()
+            .0;
+        if participants.tap_output_key != output_key
+            || participants.tap_internal_key != aggregate_key
+            || output
+                .tap_internal_key
+                .is_some_and(|key| key != aggregate_key)
+        {
+            return Err(ScriptValidationError::Musig2ParticipantsMismatch { output_index });
+        }
+    }
+
+    validate_key_origins(
+        secp,
+        location,
+        global_xpubs,
+        &output.bip32_derivation,
+        &output.tap_key_origins,
+    )
+}
+
+/// Validate all redeem scripts, witness scripts, taproot commitments and key origins of `psbt`
+///
+/// Checks performed for each input and output:
+/// - `redeem_script` hashes to the P2SH scriptPubKey
+/// - `witness_script` hashes to the P2WSH program (native or nested in the redeem script)
+/// - input `tap_scripts` control blocks verify against the P2TR output key
+/// - output `tap_internal_key` and `tap_tree` tweak to the P2TR output key
+/// - output MuSig2 participants aggregate to the internal key of the P2TR output key
+/// - `bip32_derivation` and `tap_key_origins` entries that descend from the origin of a global
+///   xpub derive to the stated public key
+pub fn validate_psbt_scripts(psbt: &Psbt) -> Result<(), ScriptValidationError> {
+    let secp = secp256k1::Secp256k1::verification_only();
+    for (input_index, input) in psbt.inputs.iter().enumerate() {
+        validate_input(&secp, psbt, input_index, input, &psbt.xpub)?;
+    }
+    for (output_index, output) in psbt.outputs.iter().enumerate() {
+        validate_output(&secp, psbt, output_index, output, &psbt.xpub)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::{SignPath, SignerKey};
+    use crate::fixed_script_wallet::bitgo_psbt::{
+        BitGoPsbt, DeserializeError, DeserializeValidation, ScriptId, WalletInputOptions,
+    };
+    use crate::fixed_script_wallet::test_utils::fixtures;
+    use crate::fixed_script_wallet::test_utils::get_test_wallet_keys;
+    use crate::fixed_script_wallet::RootWalletKeys;
+    use crate::Network;
+    use miniscript::bitcoin::Txid;
+    use std::str::FromStr;
+
+    fn wallet_psbt() -> BitGoPsbt {
+        wallet_psbt_with_seed("script_validation")
+    }
+
+    fn wallet_psbt_with_seed(seed: &str) -> BitGoPsbt {
+        let wallet_keys = RootWalletKeys::new(get_test_wallet_keys(seed));
+        let mut psbt = BitGoPsbt::new(Network::Bitcoin, &wallet_keys, Some(2), Some(0));
+        let txid = Txid::from_str(&"01".repeat(32)).unwrap();
+        for (vout, chain) in [0, 10, 20, 30, 40].into_iter().enumerate() {
+            psbt.add_wallet_input(
+                txid,
+                vout as u32,
+                10_000,
+                &wallet_keys,
+                ScriptId { chain, index: 0 },
+                WalletInputOptions {
+                    sign_path: (chain >= 30).then_some(SignPath {
+                        signer: SignerKey::User,
+                        cosigner: SignerKey::Bitgo,
+                    }),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        }
+        for chain in [1, 11, 21, 31, 41] {
+            psbt.add_wallet_output(chain, 0, 1_000, &wallet_keys)
+                .unwrap();
+        }
+        psbt
+    }
+
+    fn deserialize_strict(psbt: &BitGoPsbt) -> Result<BitGoPsbt, DeserializeError> {
+        BitGoPsbt::deserialize_with_validation(
+            &psbt.serialize().unwrap(),
+            psbt.network(),
+            DeserializeValidation::Strict,
+        )
+    }
+
+    #[test]
+    fn test_wallet_psbt_passes() {
+        let psbt = wallet_psbt();
+        validate_psbt_scripts(psbt.psbt()).unwrap();
+        deserialize_strict(&psbt).unwrap();
+    }
+
+    crate::test_psbt_fixtures!(test_fixtures_pass, network, format, {
+        for state in [
+            fixtures::SignatureState::Unsigned,
+            fixtures::SignatureState::Fullsigned,
+        ] {
+            let fixture = fixtures::load_psbt_fixture_with_format_and_namespace(
+                network.to_utxolib_name(),
+                state,
+                format,
+                fixtures::FixtureNamespace::UtxolibCompat,
+            )
+            .unwrap();
+            BitGoPsbt::deserialize_with_validation(
+                &fixture.to_psbt_bytes().unwrap(),
+                network,
+                DeserializeValidation::Strict,
+            )
+            .unwrap_or_else(|e| panic!("{:?} {:?}: {}", network, state, e));
+        }
+    });
+
+    #[test]
+    fn test_tampered_redeem_script() {
+        let mut psbt = wallet_psbt();
+        let other = wallet_psbt();
+        // Swap in the redeem script of the p2shP2wsh input
+        psbt.psbt_mut().inputs[0].redeem_script = other.psbt().inputs[1].redeem_script.clone();
+        assert!(matches!(
+            validate_psbt_scripts(psbt.psbt()),
+            Err(ScriptValidationError::RedeemScriptMismatch {
+                location: PsbtLocation::Input(0)
+            })
+        ));
+        assert!(matches!(
+            deserialize_strict(&psbt),
+            Err(DeserializeError::Validation(_))
+        ));
+        // Lenient deserialization still succeeds
+        BitGoPsbt::deserialize(&psbt.serialize().unwrap(), Network::Bitcoin).unwrap();
+    }
+
+    #[test]
+    fn test_tampered_witness_script() {
+        let mut psbt = wallet_psbt();
+        let witness_script = psbt.psbt().inputs[1].witness_script.clone();
+        psbt.psbt_mut().inputs[2].witness_script = witness_script.clone();
+        psbt.psbt_mut().outputs[1].witness_script = witness_script.map(|mut s| {
+            s.push_opcode(miniscript::bitcoin::opcodes::OP_TRUE);
+            s
+        });
+        let mut inputs_only = psbt.clone();
+        inputs_only.psbt_mut().outputs[1].witness_script = None;
+        assert!(matches!(
+            validate_psbt_scripts(inputs_only.psbt()),
+            Err(ScriptValidationError::WitnessScriptMismatch {
+                location: PsbtLocation::Input(2)
+            })
+        ));
+
+        let mut outputs_only = wallet_psbt();
+        outputs_only.psbt_mut().outputs[1].witness_script =
+            psbt.psbt().outputs[1].witness_script.clone();
+        assert!(matches!(
+            validate_psbt_scripts(outputs_only.psbt()),
+            Err(ScriptValidationError::WitnessScriptMismatch {
+                location: PsbtLocation::Output(1)
+            })
+        ));
+    }
+
+    #[test]
+    fn test_tampered_control_block() {
+        let mut psbt = wallet_psbt();
+        // Move the leaf scripts of the p2tr input onto the p2trMusig2 input
+        let tap_scripts = psbt.psbt().inputs[3].tap_scripts.clone();
+        psbt.psbt_mut().inputs[4].tap_scripts = tap_scripts;
+        assert!(matches!(
+            validate_psbt_scripts(psbt.psbt()),
+            Err(ScriptValidationError::ControlBlockMismatch { input_index: 4 })
+        ));
+    }
+
+    #[test]
+    fn test_tampered_musig2_output_participants() {
+        let mut psbt = wallet_psbt();
+        // The p2trMusig2 change output carries participants that aggregate to its internal key
+        let output = &psbt.psbt().outputs[4];
+        let participants = parse_musig2_output_participants(output)
+            .unwrap()
+            .expect("p2trMusig2 output has participants");
+        assert_eq!(Some(participants.tap_internal_key), output.tap_internal_key);
+        assert!(parse_musig2_output_participants(&psbt.psbt().outputs[3])
+            .unwrap()
+            .is_none());
+
+        // Participants of another wallet do not match the output key
+        let other = wallet_psbt_with_seed("other");
+        psbt.psbt_mut().outputs[4].proprietary = other.psbt().outputs[4].proprietary.clone();
+        assert!(matches!(
+            validate_psbt_scripts(psbt.psbt()),
+            Err(ScriptValidationError::Musig2ParticipantsMismatch { output_index: 4 })
+        ));
+    }
+
+    #[test]
+    fn test_tampered_derivation() {
+        let mut psbt = wallet_psbt();
+        // Claim the keys of input 1 with the derivation paths of input 0
+        let input_0 = psbt.psbt().inputs[0].bip32_derivation.clone();
+        let input_1 = psbt.psbt().inputs[1].bip32_derivation.clone();
+        psbt.psbt_mut().inputs[1].bip32_derivation = input_1
+            .keys()
+            .zip(input_0.values())
+            .map(|(key, source)| (*key, source.clone()))
+            .collect();
+        assert!(matches!(
+            validate_psbt_scripts(psbt.psbt()),
+            Err(ScriptValidationError::DerivationMismatch {
+                location: PsbtLocation::Input(1),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_exported_key_origins() {
+        use crate::fixed_script_wallet::bitgo_psbt::key_origins::KeyOrigin;
+        use miniscript::bitcoin::bip32::{DerivationPath, Xpriv};
+
+        let secp = secp256k1::Secp256k1::new();
+        let root = Xpriv::new_master(miniscript::bitcoin::Network::Testnet, &[7u8; 32]).unwrap();
+        let account_path = DerivationPath::from_str("m/48'/1'/0'/2'").unwrap();
+        let user_xpub = Xpub::from_priv(&secp, &root.derive_priv(&secp, &account_path).unwrap());
+        let keys = get_test_wallet_keys("script_validation");
+        let wallet_keys = RootWalletKeys::new([user_xpub, keys[1], keys[2]]);
+        let mut psbt = BitGoPsbt::new(Network::Bitcoin, &wallet_keys, Some(2), Some(0));
+        psbt.add_wallet_input(
+            Txid::from_str(&"01".repeat(32)).unwrap(),
+            0,
+            10_000,
+            &wallet_keys,
+            ScriptId {
+                chain: 20,
+                index: 0,
+            },
+            WalletInputOptions::default(),
+        )
+        .unwrap();
+        let master_fingerprint = root.fingerprint(&secp);
+        psbt.export_key_origins(
+            &wallet_keys,
+            &[
+                Some(KeyOrigin {
+                    master_fingerprint,
+                    account_path: account_path.clone(),
+                }),
+                None,
+                None,
+            ],
+        )
+        .unwrap();
+        validate_psbt_scripts(psbt.psbt()).unwrap();
+
+        // Derivations rooted at the master fingerprint are checked below the account path
+        let (_, user_source) = psbt.psbt_mut().inputs[0]
+            .bip32_derivation
+            .iter_mut()
+            .find(|(_, (fingerprint, _))| *fingerprint == master_fingerprint)
+            .unwrap();
+        user_source.1 = account_path.extend(DerivationPath::from_str("m/0/0/20/1").unwrap());
+        assert!(matches!(
+            validate_psbt_scripts(psbt.psbt()),
+            Err(ScriptValidationError::DerivationMismatch {
+                location: PsbtLocation::Input(0),
+                ..
+            })
+        ));
+    }
+}
@@ -4,6 +4,12 @@
 //! bitcoin-like networks, including those with non-standard sighash types and
 //! transaction formats.
 
+use miniscript::bitcoin::hashes::Hash;
+use miniscript::bitcoin::psbt::Psbt;
+use miniscript::bitcoin::secp256k1::Message;
+use miniscript::bitcoin::sighash::SighashCache;
+use miniscript::bitcoin::{ScriptBuf, Transaction};
+
 use crate::networks::Network;
 
 /// Bitcoin Cash and related forks use SIGHASH_FORKID flag
@@ -98,6 +104,77 @@ pub fn validate_sighash_type(sighash_type: u32, network: Network) -> Result<(),
     }
 }
 
+/// Script code of a PSBT input for the BIP143-style FORKID sighash
+///
+/// - p2shP2wsh, p2wsh: the witness script
+/// - p2sh, p2shP2pk: the redeem script
+/// - p2pkh: the output script
+/// - p2wpkh: the corresponding p2pkh script
+fn forkid_script_code(psbt: &Psbt, input_index: usize) -> Result<ScriptBuf, String> {
+    let input = &psbt.inputs[input_index];
+    if let Some(witness_script) = &input.witness_script {
+        return Ok(witness_script.clone());
+    }
+    if let Some(redeem_script) = &input.redeem_script {
+        return Ok(redeem_script.clone());
+    }
+    let prevout = psbt.unsigned_tx.input[input_index].previous_output;
+    let (output_script, _) = super::psbt_wallet_input::get_output_script_and_value(input, prevout)
+        .map_err(|e| e.to_string())?;
+    if output_script.is_p2pkh() {
+        Ok(output_script.clone())
+    } else if let Some(script_code) = output_script.p2wpkh_script_code() {
+        Ok(script_code)
+    } else {
+        Err("Missing redeem script or witness script".to_string())
+    }
+}
+
+/// Compute the SIGHASH_FORKID signature hash of a PSBT input
+///
+/// All FORKID networks hash inputs the BIP143 way, whether the input is segwit or not. The
+/// sighash type committed to is `sighash_type | fork_id << 8`: 0 for BCH, XEC and BSV, 79 for
+/// BTG. The script code is chosen per script type, so p2shP2wsh and p2wsh inputs on BTG commit
+/// to their witness script.
+///
+/// # Returns
+/// The message to sign and the sighash type of the input (SIGHASH_ALL|SIGHASH_FORKID if unset)
+pub(crate) fn forkid_signature_hash(
+    psbt: &Psbt,
+    input_index: usize,
+    cache: &mut SighashCache<&Transaction>,
+    fork_id: u32,
+) -> Result<(Message, u32), String> {
+    let input = psbt
+        .inputs
+        .get(input_index)
+        .ok_or_else(|| format!("Input index {} out of bounds", input_index))?;
+    let sighash_type = input
+        .sighash_type
+        .map(|t| t.to_u32())
+        .unwrap_or(SIGHASH_ALL | SIGHASH_FORKID);
+    if sighash_type & SIGHASH_FORKID == 0 {
+        return Err(format!(
+            "SIGHASH_FORKID (0x40) is required. Sighash type: 0x{:02x}",
+            sighash_type
+        ));
+    }
+    let script_code = forkid_script_code(psbt, input_index)?;
+    let prevout = psbt.unsigned_tx.input[input_index].previous_output;
+    let (_, value) = super::psbt_wallet_input::get_output_script_and_value(input, prevout)
+        .map_err(|e| e.to_string())?;
+    let sighash = cache
+        .p2wsh_signature_hash_forkid(
+            input_index,
+            &script_code,
+            value,
+            sighash_type,
+            Some(fork_id),
+        )
+        .map_err(|e| format!("Failed to compute FORKID sighash: {}", e))?;
+    Ok((Message::from_digest(sighash.to_byte_array()), sighash_type))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -241,4 +318,196 @@ mod tests {
                 .is_err()
         );
     }
+
+    /// BIP143 FORKID digests computed independently of rust-bitcoin, over
+    /// `forkid_test_tx` with the script code `OP_DUP OP_HASH160 <0x33 * 20> OP_EQUALVERIFY
+    /// OP_CHECKSIG` and an input value of 10000 sat
+    const FORKID_SIGHASH_VECTORS: &[(Network, &str)] = &[
+        (
+            Network::BitcoinSV,
+            "fe61e95307066a0f57cddaf7fbd7c6564b06a294799cee79b36ac1e61032890f",
+        ),
+        (
+            Network::BitcoinCash,
+            "fe61e95307066a0f57cddaf7fbd7c6564b06a294799cee79b36ac1e61032890f",
+        ),
+        (
+            Network::BitcoinGold,
+            "fe65821f8e849eb39ffca40908e667a03d1a8d44037ee60b6ac37d7d812b3dbd",
+        ),
+    ];
+
+    fn forkid_test_tx() -> Transaction {
+        use miniscript::bitcoin::absolute::LockTime;
+        use miniscript::bitcoin::transaction::Version;
+        use miniscript::bitcoin::{Amount, OutPoint, Sequence, TxIn, TxOut, Txid};
+
+        Transaction {
+            version: Version::ONE,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint {
+                    txid: Txid::from_byte_array([0x11; 32]),
+                    vout: 1,
+                },
+                sequence: Sequence::MAX,
+                ..Default::default()
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(9_000),
+                script_pubkey: ScriptBuf::from_hex(&format!("0014{}", "22".repeat(20))).unwrap(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_forkid_sighash_vectors() {
+        use miniscript::bitcoin::Amount;
+
+        let tx = forkid_test_tx();
+        let script_code = ScriptBuf::from_hex(&format!("76a914{}88ac", "33".repeat(20))).unwrap();
+        for (network, expected) in FORKID_SIGHASH_VECTORS {
+            let fork_id = get_sighash_fork_id(*network).unwrap();
+            let sighash = SighashCache::new(&tx)
+                .p2wsh_signature_hash_forkid(
+                    0,
+                    &script_code,
+                    Amount::from_sat(10_000),
+                    SIGHASH_ALL | SIGHASH_FORKID,
+                    Some(fork_id),
+                )
+                .unwrap();
+            assert_eq!(
+                hex::encode(sighash.to_byte_array()),
+                *expected,
+                "{:?}",
+                network
+            );
+        }
+    }
+
+    /// The utxolib-compat fixtures are signed by utxo-lib. Every ECDSA signature, including
+    /// those of the p2shP2wsh and p2wsh inputs on BTG, must verify against
+    /// `forkid_signature_hash` with the fork ID of the network and fail with any other.
+    #[test]
+    fn test_forkid_signature_hash_verifies_utxolib_signatures() {
+        use crate::fixed_script_wallet::test_utils::fixtures::{
+            load_psbt_fixture_with_format_and_namespace, FixtureNamespace, SignatureState, TxFormat,
+        };
+
+        let secp = miniscript::bitcoin::secp256k1::Secp256k1::verification_only();
+        for network in [Network::BitcoinGold, Network::BitcoinCash, Network::Ecash] {
+            let fixture = load_psbt_fixture_with_format_and_namespace(
+                network.to_utxolib_name(),
+                SignatureState::Fullsigned,
+                TxFormat::Psbt,
+                FixtureNamespace::UtxolibCompat,
+            )
+            .unwrap();
+            let bitgo_psbt = fixture.to_bitgo_psbt(network).unwrap();
+            let psbt = bitgo_psbt.psbt();
+            let fork_id = get_sighash_fork_id(network).unwrap();
+            let other_fork_id = if fork_id == 0 { 79 } else { 0 };
+
+            let mut verified_segwit = 0;
+            for (input_index, input) in psbt.inputs.iter().enumerate() {
+                if input.partial_sigs.is_empty() {
+                    continue;
+                }
+                let mut cache = SighashCache::new(&psbt.unsigned_tx);
+                let (message, _) =
+                    forkid_signature_hash(psbt, input_index, &mut cache, fork_id).unwrap();
+                let (other_message, _) =
+                    forkid_signature_hash(psbt, input_index, &mut cache, other_fork_id).unwrap();
+                for (pubkey, signature) in &input.partial_sigs {
+                    assert!(
+                        secp.verify_ecdsa(&message, &signature.signature, &pubkey.inner)
+                            .is_ok(),
+                        "{:?} input {}",
+                        network,
+                        input_index
+                    );
+                    assert!(secp
+                        .verify_ecdsa(&other_message, &signature.signature, &pubkey.inner)
+                        .is_err());
+                }
+                if input.witness_script.is_some() {
+                    verified_segwit += 1;
+                }
+            }
+            if network == Network::BitcoinGold {
+                assert!(verified_segwit >= 2, "expected p2shP2wsh and p2wsh inputs");
+            }
+        }
+    }
+
+    /// BIP143 FORKID digest (fork ID 79) of the p2wsh input of the BTG utxolib-compat fixture.
+    /// Both signatures made by utxo-lib in the fullsigned fixture verify against it.
+    const BTG_P2WSH_FIXTURE_INPUT: usize = 2;
+    const BTG_P2WSH_FIXTURE_SIGHASH: &str =
+        "72172af2a3389010d8efc4604157d11043cbe8c1ed87498db6cd4537bea64253";
+
+    fn btg_p2wsh_fixture_message() -> Message {
+        let digest = hex::decode(BTG_P2WSH_FIXTURE_SIGHASH).unwrap();
+        Message::from_digest(digest.try_into().unwrap())
+    }
+
+    #[test]
+    fn test_btg_p2wsh_fixture_sighash() {
+        use crate::fixed_script_wallet::test_utils::fixtures::{PsbtStages, TxFormat};
+
+        let secp = miniscript::bitcoin::secp256k1::Secp256k1::verification_only();
+        let stages = PsbtStages::load_utxolib_compat(Network::BitcoinGold, TxFormat::Psbt).unwrap();
+        let fullsigned = stages
+            .fullsigned
+            .to_bitgo_psbt(Network::BitcoinGold)
+            .unwrap();
+        let psbt = fullsigned.psbt();
+        let input = &psbt.inputs[BTG_P2WSH_FIXTURE_INPUT];
+        assert!(input.witness_script.is_some() && input.redeem_script.is_none());
+
+        let mut cache = SighashCache::new(&psbt.unsigned_tx);
+        let (message, sighash_type) =
+            forkid_signature_hash(psbt, BTG_P2WSH_FIXTURE_INPUT, &mut cache, 79).unwrap();
+        assert_eq!(message, btg_p2wsh_fixture_message());
+        assert_eq!(sighash_type, SIGHASH_ALL | SIGHASH_FORKID);
+        assert_eq!(input.partial_sigs.len(), 2);
+        for (pubkey, signature) in &input.partial_sigs {
+            assert!(secp
+                .verify_ecdsa(&message, &signature.signature, &pubkey.inner)
+                .is_ok());
+        }
+    }
+
+    /// `BitGoPsbt::sign` signs BTG inputs with `sign_forkid`; its p2wsh signature must verify
+    /// against the fixture digest and match the one utxo-lib made (RFC 6979)
+    #[test]
+    fn test_btg_p2wsh_sign_and_verify() {
+        use crate::fixed_script_wallet::test_utils::fixtures::{PsbtStages, TxFormat};
+
+        let secp = miniscript::bitcoin::secp256k1::Secp256k1::new();
+        let stages = PsbtStages::load_utxolib_compat(Network::BitcoinGold, TxFormat::Psbt).unwrap();
+        let mut psbt = stages.unsigned.to_bitgo_psbt(Network::BitcoinGold).unwrap();
+        psbt.sign_all_with_xpriv(stages.wallet_keys.user_key())
+            .unwrap();
+
+        let input = &psbt.psbt().inputs[BTG_P2WSH_FIXTURE_INPUT];
+        let message = btg_p2wsh_fixture_message();
+        assert_eq!(input.partial_sigs.len(), 1);
+        for (pubkey, signature) in &input.partial_sigs {
+            assert_eq!(signature.sighash_type, SIGHASH_ALL | SIGHASH_FORKID);
+            assert!(secp
+                .verify_ecdsa(&message, &signature.signature, &pubkey.inner)
+                .is_ok());
+        }
+
+        let halfsigned = stages
+            .halfsigned
+            .to_bitgo_psbt(Network::BitcoinGold)
+            .unwrap();
+        assert_eq!(
+            input.partial_sigs,
+            halfsigned.psbt().inputs[BTG_P2WSH_FIXTURE_INPUT].partial_sigs
+        );
+    }
 }
@@ -0,0 +1,440 @@
+//! Sign path selection for taproot wallet inputs
+//!
+//! p2tr and p2trMusig2 inputs only carry the PSBT fields of the spend path chosen when the
+//! input is added: user and bitgo spend p2trMusig2 outputs through the MuSig2 key path, while
+//! every pair that includes the backup key spends its own script leaf. During recovery, the
+//! keys at hand are only known when signing.
+//!
+//! [`BitGoPsbt::plan_sign_path`] selects the sign path for the available xprivs and rewrites
+//! the taproot fields of inputs that were created for another path.
+
+use miniscript::bitcoin::bip32::{Xpriv, Xpub};
+use miniscript::bitcoin::psbt::Input;
+use miniscript::bitcoin::secp256k1;
+use miniscript::bitcoin::taproot::{LeafVersion, TapLeafHash};
+
+use super::p2tr_musig2_input::{Musig2Input, Musig2Participants};
+use super::propkv::is_musig2_key;
+use super::psbt_wallet_input::{
+    get_output_script_and_value, parse_shared_chain_and_index, SignPath, SignerKey,
+};
+use super::{create_tap_bip32_derivation, has_signatures, BitGoPsbt};
+use crate::fixed_script_wallet::wallet_scripts::{
+    build_p2tr_ns_script, chain_index_path, OutputScriptType, ScriptP2tr, WalletScripts,
+};
+use crate::fixed_script_wallet::{to_pub_triple, Chain, PubTriple, RootWalletKeys, ScriptId};
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum SignPathError {
+    InputIndexOutOfBounds {
+        index: usize,
+        len: usize,
+    },
+    /// Fewer than two wallet keys are available
+    InsufficientKeys,
+    /// The input does not spend a p2tr or p2trMusig2 script of the wallet
+    NotTaprootWalletInput {
+        index: usize,
+    },
+    /// The input has signatures or MuSig2 partial signatures for another sign path
+    AlreadySigned {
+        index: usize,
+    },
+    /// The sign path does not match a spend path of the input script
+    InvalidSignPath {
+        index: usize,
+        error: String,
+    },
+}
+
+impl std::fmt::Display for SignPathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignPathError::InputIndexOutOfBounds { index, len } => write!(
+                f,
+                "Input index {} out of bounds (total inputs: {})",
+                index, len
+            ),
+            SignPathError::InsufficientKeys => {
+                write!(f, "At least two wallet keys are required to sign")
+            }
+            SignPathError::NotTaprootWalletInput { index } => write!(
+                f,
+                "Input {} does not spend a p2tr or p2trMusig2 wallet script",
+                index
+            ),
+            SignPathError::AlreadySigned { index } => {
+                write!(f, "Input {} is already signed for another sign path", index)
+            }
+            SignPathError::InvalidSignPath { index, error } => {
+                write!(f, "Invalid sign path for input {}: {}", index, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SignPathError {}
+
+crate::impl_wasm_error_code!(SignPathError);
+
+impl SignPath {
+    /// Select the sign path for the keys that can sign
+    ///
+    /// User and bitgo are preferred, since they can spend p2trMusig2 outputs through the key
+    /// path, followed by user and backup, and backup and bitgo.
+    pub fn from_available_keys(keys: &[SignerKey]) -> Option<SignPath> {
+        [
+            (SignerKey::User, SignerKey::Bitgo),
+            (SignerKey::User, SignerKey::Backup),
+            (SignerKey::Backup, SignerKey::Bitgo),
+        ]
+        .into_iter()
+        .find(|(signer, cosigner)| keys.contains(signer) && keys.contains(cosigner))
+        .map(|(signer, cosigner)| SignPath { signer, cosigner })
+    }
+
+    /// Returns true if p2trMusig2 inputs are spent through the MuSig2 key path
+    pub fn is_musig2_key_path(&self) -> bool {
+        !self.signer.is_backup() && !self.cosigner.is_backup()
+    }
+}
+
+/// Identify the wallet keys that `xprivs` can sign for
+pub fn available_signer_keys(wallet_keys: &RootWalletKeys, xprivs: &[Xpriv]) -> Vec<SignerKey> {
+    let secp = secp256k1::Secp256k1::signing_only();
+    let xpubs: Vec<Xpub> = xprivs
+        .iter()
+        .map(|xpriv| Xpub::from_priv(&secp, xpriv))
+        .collect();
+    [SignerKey::User, SignerKey::Backup, SignerKey::Bitgo]
+        .into_iter()
+        .filter(|key| {
+            let wallet_xpub = &wallet_keys.xpubs[key.index()];
+            // Compare key material only, so that testnet and mainnet encodings match
+            xpubs.iter().any(|xpub| {
+                xpub.public_key == wallet_xpub.public_key
+                    && xpub.chain_code == wallet_xpub.chain_code
+            })
+        })
+        .collect()
+}
+
+/// Populate the taproot fields of a p2tr or p2trMusig2 input for `sign_path`
+///
+/// Script path inputs get the leaf script with its control block, key path inputs get the
+/// internal key, merkle root and MuSig2 participants.
+pub(crate) fn set_taproot_sign_path_fields(
+    psbt_input: &mut Input,
+    wallet_keys: &RootWalletKeys,
+    script_id: ScriptId,
+    script: &ScriptP2tr,
+    is_musig2: bool,
+    pub_triple: &PubTriple,
+    sign_path: SignPath,
+) -> Result<(), String> {
+    let signer_idx = sign_path.signer.index();
+    let cosigner_idx = sign_path.cosigner.index();
+
+    if !is_musig2 || !sign_path.is_musig2_key_path() {
+        let signer_keys = [pub_triple[signer_idx], pub_triple[cosigner_idx]];
+        let leaf_script = build_p2tr_ns_script(&signer_keys);
+        let leaf_hash = TapLeafHash::from_script(&leaf_script, LeafVersion::TapScript);
+
+        let control_block = script
+            .spend_info
+            .control_block(&(leaf_script.clone(), LeafVersion::TapScript))
+            .ok_or_else(|| "Could not find control block for leaf script".to_string())?;
+
+        psbt_input
+            .tap_scripts
+            .insert(control_block, (leaf_script, LeafVersion::TapScript));
+
+        psbt_input.tap_key_origins = create_tap_bip32_derivation(
+            wallet_keys,
+            script_id.chain,
+            script_id.index,
+            &[signer_idx, cosigner_idx],
+            Some(leaf_hash),
+        );
+    } else {
+        let internal_key = script.spend_info.internal_key();
+        let merkle_root = script.spend_info.merkle_root();
+
+        psbt_input.tap_internal_key = Some(internal_key);
+        psbt_input.tap_merkle_root = merkle_root;
+
+        psbt_input.tap_key_origins = create_tap_bip32_derivation(
+            wallet_keys,
+            script_id.chain,
+            script_id.index,
+            &[signer_idx, cosigner_idx],
+            None,
+        );
+
+        let user_key = pub_triple[0];
+        let bitgo_key = pub_triple[2];
+
+        let tap_output_key = script.spend_info.output_key().to_x_only_public_key();
+        let musig2_participants = Musig2Participants {
+            tap_output_key,
+            tap_internal_key: internal_key,
+            participant_pub_keys: [user_key, bitgo_key],
+        };
+
+        let (key, value) = musig2_participants.to_key_value().to_key_value();
+        psbt_input.proprietary.insert(key, value);
+    }
+    Ok(())
+}
+
+impl BitGoPsbt {
+    /// Rewrite the taproot fields of a p2tr or p2trMusig2 wallet input for `sign_path`
+    ///
+    /// The fields of the previous path are removed, including MuSig2 nonces. Inputs that are
+    /// signed for another path are rejected.
+    ///
+    /// # Returns
+    /// `false` if the input already had the fields of `sign_path`
+    pub fn set_input_sign_path(
+        &mut self,
+        input_index: usize,
+        wallet_keys: &RootWalletKeys,
+        sign_path: SignPath,
+    ) -> Result<bool, SignPathError> {
+        let network = self.network();
+        let psbt = self.psbt();
+        let input = psbt
+            .inputs
+            .get(input_index)
+            .ok_or(SignPathError::InputIndexOutOfBounds {
+                index: input_index,
+                len: psbt.inputs.len(),
+            })?;
+        let not_taproot = || SignPathError::NotTaprootWalletInput { index: input_index };
+
+        let (chain, index) = parse_shared_chain_and_index(input).map_err(|_| not_taproot())?;
+        let script_type = Chain::try_from(chain)
+            .map_err(|_| not_taproot())?
+            .script_type;
+        if !matches!(
+            script_type,
+            OutputScriptType::P2trLegacy | OutputScriptType::P2trMusig2
+        ) {
+            return Err(not_taproot());
+        }
+        let pub_triple = to_pub_triple(
+            &wallet_keys
+                .derive_path(&chain_index_path(chain, index))
+                .map_err(|_| not_taproot())?,
+        );
+        let scripts =
+            WalletScripts::new(&pub_triple, script_type, &network.output_script_support())
+                .map_err(|_| not_taproot())?;
+        let (output_script, _) =
+            get_output_script_and_value(input, psbt.unsigned_tx.input[input_index].previous_output)
+                .map_err(|_| not_taproot())?;
+        if *output_script != scripts.output_script() {
+            return Err(not_taproot());
+        }
+        let (WalletScripts::P2trLegacy(script) | WalletScripts::P2trMusig2(script)) = &scripts
+        else {
+            return Err(not_taproot());
+        };
+
+        let mut planned = Input::default();
+        set_taproot_sign_path_fields(
+            &mut planned,
+            wallet_keys,
+            ScriptId { chain, index },
+            script,
+            script_type == OutputScriptType::P2trMusig2,
+            &pub_triple,
+            sign_path,
+        )
+        .map_err(|error| SignPathError::InvalidSignPath {
+            index: input_index,
+            error,
+        })?;
+
+        if input.tap_scripts == planned.tap_scripts
+            && input.tap_key_origins == planned.tap_key_origins
+            && input.tap_internal_key == planned.tap_internal_key
+            && input.tap_merkle_root == planned.tap_merkle_root
+        {
+            return Ok(false);
+        }
+        if has_signatures(input) {
+            return Err(SignPathError::AlreadySigned { index: input_index });
+        }
+
+        let input = &mut self.psbt_mut().inputs[input_index];
+        input.tap_scripts = planned.tap_scripts;
+        input.tap_key_origins = planned.tap_key_origins;
+        input.tap_internal_key = planned.tap_internal_key;
+        input.tap_merkle_root = planned.tap_merkle_root;
+        input.proprietary.retain(|key, _| !is_musig2_key(key));
+        input.proprietary.extend(planned.proprietary);
+        Ok(true)
+    }
+
+    /// Select the sign path for the available xprivs and apply it to all taproot wallet inputs
+    ///
+    /// Inputs that do not spend p2tr or p2trMusig2 scripts of the wallet are left as is.
+    ///
+    /// # Returns
+    /// The selected sign path
+    pub fn plan_sign_path(
+        &mut self,
+        wallet_keys: &RootWalletKeys,
+        xprivs: &[Xpriv],
+    ) -> Result<SignPath, SignPathError> {
+        let sign_path = SignPath::from_available_keys(&available_signer_keys(wallet_keys, xprivs))
+            .ok_or(SignPathError::InsufficientKeys)?;
+        for input_index in 0..self.psbt().inputs.len() {
+            match self.set_input_sign_path(input_index, wallet_keys, sign_path) {
+                Ok(_) | Err(SignPathError::NotTaprootWalletInput { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(sign_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::bitgo_psbt::{InputScriptType, WalletInputOptions};
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::{
+        get_test_wallet_keys, get_test_wallet_xprvs,
+    };
+    use crate::Network;
+
+    fn sign_path_options<'a>(sign_path: SignPath) -> WalletInputOptions<'a> {
+        WalletInputOptions {
+            sign_path: Some(sign_path),
+            ..Default::default()
+        }
+    }
+
+    fn user_bitgo() -> SignPath {
+        SignPath {
+            signer: SignerKey::User,
+            cosigner: SignerKey::Bitgo,
+        }
+    }
+
+    fn user_backup() -> SignPath {
+        SignPath {
+            signer: SignerKey::User,
+            cosigner: SignerKey::Backup,
+        }
+    }
+
+    #[test]
+    fn test_from_available_keys() {
+        use SignerKey::*;
+        assert_eq!(
+            SignPath::from_available_keys(&[Bitgo, Backup, User]),
+            Some(user_bitgo())
+        );
+        assert_eq!(
+            SignPath::from_available_keys(&[Backup, User]),
+            Some(user_backup())
+        );
+        assert_eq!(
+            SignPath::from_available_keys(&[Bitgo, Backup]),
+            Some(SignPath {
+                signer: Backup,
+                cosigner: Bitgo
+            })
+        );
+        assert_eq!(SignPath::from_available_keys(&[User]), None);
+        assert_eq!(SignPath::from_available_keys(&[User, User]), None);
+    }
+
+    #[test]
+    fn test_plan_sign_path() {
+        let seed = "sign_path";
+        let wallet_keys = get_test_wallet_keys(seed);
+        let xprvs = get_test_wallet_xprvs(seed);
+        let network = Network::Bitcoin;
+
+        // Taproot inputs use the user and bitgo keys by default
+        let mut psbt = WalletPsbtBuilder::new(network, &wallet_keys)
+            .input(40, 0)
+            .input(30, 0)
+            .input(20, 0)
+            .build();
+        let script_type = |psbt: &BitGoPsbt, index: usize| {
+            InputScriptType::from_output_script_type(
+                OutputScriptType::P2trMusig2,
+                &psbt.psbt().inputs[index],
+            )
+        };
+        assert_eq!(script_type(&psbt, 0), InputScriptType::P2trMusig2KeyPath);
+
+        // A recovery with user and backup moves the key path input to the script path
+        let recovery = [xprvs[0], xprvs[1]];
+        assert_eq!(
+            psbt.plan_sign_path(&wallet_keys, &recovery).unwrap(),
+            user_backup()
+        );
+        let input = &psbt.psbt().inputs[0];
+        assert_eq!(script_type(&psbt, 0), InputScriptType::P2trMusig2ScriptPath);
+        assert!(input.tap_internal_key.is_none());
+        assert!(!Musig2Input::is_musig2_input(input));
+        assert_eq!(input.tap_scripts.len(), 1);
+
+        // The result is the same as adding the input for the script path
+        let expected = WalletPsbtBuilder::new(network, &wallet_keys)
+            .input_with(40, 0, 10_000, sign_path_options(user_backup()))
+            .input_with(30, 0, 10_000, sign_path_options(user_backup()))
+            .build();
+        assert_eq!(psbt.psbt().inputs[0], expected.psbt().inputs[0]);
+        assert_eq!(psbt.psbt().inputs[1], expected.psbt().inputs[1]);
+
+        // Planning again is a no-op, and the inputs can be signed
+        assert!(!psbt
+            .set_input_sign_path(0, &wallet_keys, user_backup())
+            .unwrap());
+        for xprv in &recovery {
+            psbt.sign_all_with_xpriv(xprv).unwrap();
+        }
+        assert_eq!(psbt.psbt().inputs[0].tap_script_sigs.len(), 2);
+
+        // Signed inputs cannot move to another path
+        assert!(matches!(
+            psbt.set_input_sign_path(0, &wallet_keys, user_bitgo()),
+            Err(SignPathError::AlreadySigned { index: 0 })
+        ));
+        assert!(matches!(
+            psbt.set_input_sign_path(2, &wallet_keys, user_bitgo()),
+            Err(SignPathError::NotTaprootWalletInput { index: 2 })
+        ));
+        assert!(matches!(
+            psbt.plan_sign_path(&wallet_keys, &[xprvs[2]]),
+            Err(SignPathError::InsufficientKeys)
+        ));
+    }
+
+    #[test]
+    fn test_plan_sign_path_to_key_path() {
+        let seed = "sign_path";
+        let wallet_keys = get_test_wallet_keys(seed);
+        let xprvs = get_test_wallet_xprvs(seed);
+
+        let mut psbt = WalletPsbtBuilder::new(Network::Bitcoin, &wallet_keys)
+            .input_with(41, 3, 10_000, sign_path_options(user_backup()))
+            .build();
+
+        assert_eq!(
+            psbt.plan_sign_path(&wallet_keys, &xprvs).unwrap(),
+            user_bitgo()
+        );
+        let input = &psbt.psbt().inputs[0];
+        assert!(input.tap_scripts.is_empty());
+        assert!(input.tap_internal_key.is_some());
+        assert!(Musig2Input::from_input(input).is_ok());
+    }
+}
@@ -0,0 +1,139 @@
+//! Role of a signing key in the wallet of a PSBT
+//!
+//! Wallet scripts list their keys in `[user, backup, bitgo]` order, while the global xpubs of a
+//! PSBT are unordered. A key triple passed in the wrong order, or a key of another wallet, only
+//! shows up as inputs that are not signed.
+//!
+//! [`BitGoPsbt::signer_role`] recovers the order of the global xpubs from the wallet inputs
+//! (see [`super::to_wallet_keys`]) and reports the role of a key.
+
+use miniscript::bitcoin::bip32::{Xpriv, Xpub};
+use miniscript::bitcoin::secp256k1;
+
+use super::psbt_wallet_input::SignerKey;
+use super::{to_wallet_keys, BitGoPsbt};
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum SignerRoleError {
+    /// The PSBT has no global xpubs to compare the key with
+    MissingGlobalXpubs,
+    /// The order of the global xpubs cannot be determined from the wallet inputs
+    UnknownKeyOrder(String),
+}
+
+impl std::fmt::Display for SignerRoleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignerRoleError::MissingGlobalXpubs => write!(f, "PSBT has no global xpubs"),
+            SignerRoleError::UnknownKeyOrder(error) => {
+                write!(
+                    f,
+                    "Cannot determine the order of the wallet keys: {}",
+                    error
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for SignerRoleError {}
+
+crate::impl_wasm_error_code!(SignerRoleError);
+crate::impl_from_coded_error!(SignerRoleError);
+
+/// Compare by key material only, ignoring depth, parent fingerprint and child number
+fn same_key(a: &Xpub, b: &Xpub) -> bool {
+    a.public_key == b.public_key && a.chain_code == b.chain_code
+}
+
+impl BitGoPsbt {
+    /// Role of the key `xpriv` in the wallet of this PSBT
+    ///
+    /// # Returns
+    /// - `Ok(Some(role))` if the key is one of the global xpubs
+    /// - `Ok(None)` if the key is not part of the wallet
+    /// - `Err(SignerRoleError)` if the PSBT has no global xpubs, or they cannot be ordered
+    pub fn signer_role(&self, xpriv: &Xpriv) -> Result<Option<SignerKey>, SignerRoleError> {
+        let secp = secp256k1::Secp256k1::signing_only();
+        self.signer_role_for_xpub(&Xpub::from_priv(&secp, xpriv))
+    }
+
+    /// Like [`BitGoPsbt::signer_role`], for a public key
+    pub fn signer_role_for_xpub(&self, xpub: &Xpub) -> Result<Option<SignerKey>, SignerRoleError> {
+        let global_xpubs: Vec<Xpub> = self.psbt().xpub.keys().copied().collect();
+        if global_xpubs.is_empty() {
+            return Err(SignerRoleError::MissingGlobalXpubs);
+        }
+        if !global_xpubs.iter().any(|x| same_key(x, xpub)) {
+            return Ok(None);
+        }
+        let xpubs: [Xpub; 3] = global_xpubs.try_into().map_err(|xpubs: Vec<Xpub>| {
+            SignerRoleError::UnknownKeyOrder(format!(
+                "expected 3 global xpubs, found {}",
+                xpubs.len()
+            ))
+        })?;
+        let wallet_keys = to_wallet_keys(self, xpubs).map_err(SignerRoleError::UnknownKeyOrder)?;
+        let index = wallet_keys
+            .xpubs
+            .iter()
+            .position(|x| same_key(x, xpub))
+            .expect("ordered wallet keys are a permutation of the global xpubs");
+        Ok(Some(
+            [SignerKey::User, SignerKey::Backup, SignerKey::Bitgo][index],
+        ))
+    }
+
+    /// Explanation to append to a signing error when `xpub` is not a key of this wallet
+    ///
+    /// Empty if the key is a wallet key or the PSBT has no global xpubs.
+    pub(crate) fn unknown_signer_hint(&self, xpub: &Xpub) -> String {
+        match self.signer_role_for_xpub(xpub) {
+            Ok(None) => format!(": {} is not one of the wallet keys of this PSBT", xpub),
+            _ => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::{
+        get_test_wallet_keys, get_test_wallet_xprvs,
+    };
+    use crate::Network;
+
+    #[test]
+    fn test_signer_role() {
+        let seed = "signer_role";
+        let wallet_keys = get_test_wallet_keys(seed);
+        let xprvs = get_test_wallet_xprvs(seed);
+        let mut psbt = WalletPsbtBuilder::new(Network::Bitcoin, &wallet_keys)
+            .input(0, 0)
+            .build();
+
+        let roles: Vec<_> = xprvs
+            .iter()
+            .map(|xprv| psbt.signer_role(xprv).unwrap().map(|role| role.as_str()))
+            .collect();
+        assert_eq!(roles, vec![Some("user"), Some("backup"), Some("bitgo")]);
+
+        let other = get_test_wallet_xprvs("signer_role_other");
+        assert!(psbt.signer_role(&other[0]).unwrap().is_none());
+        let secp = secp256k1::Secp256k1::new();
+        assert!(psbt
+            .unknown_signer_hint(&Xpub::from_priv(&secp, &other[0]))
+            .contains("is not one of the wallet keys"));
+        assert_eq!(
+            psbt.unknown_signer_hint(&Xpub::from_priv(&secp, &xprvs[0])),
+            ""
+        );
+
+        psbt.psbt_mut().xpub.clear();
+        assert!(matches!(
+            psbt.signer_role(&xprvs[0]),
+            Err(SignerRoleError::MissingGlobalXpubs)
+        ));
+    }
+}
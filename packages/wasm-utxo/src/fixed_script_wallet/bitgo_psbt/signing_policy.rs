@@ -0,0 +1,291 @@
+//! Fee and change guardrails checked before signing
+//!
+//! A coordinator can hand the signer a PSBT that pays an excessive fee, or that routes
+//! "change" to a script that only looks like it belongs to the wallet. [`SigningPolicy`]
+//! describes the limits the signer is willing to accept; [`BitGoPsbt::check_signing_policy`]
+//! evaluates them against the parsed transaction.
+//!
+//! [`BitGoPsbt::check_signing_policy`]: super::BitGoPsbt::check_signing_policy
+
+use miniscript::bitcoin::FeeRate;
+
+use super::{ParseTransactionError, ParsedTransaction};
+
+/// Limits checked before signing. Unset limits are not checked.
+#[derive(Debug, Clone, Default)]
+pub struct SigningPolicy {
+    /// Maximum absolute miner fee in satoshis
+    pub max_fee: Option<u64>,
+    /// Maximum fee rate, measured against [`ParsedTransaction::virtual_size`]
+    pub max_fee_rate: Option<FeeRate>,
+    /// Maximum miner fee as a percentage of the spend amount (e.g. `5.0` for 5%).
+    ///
+    /// Not checked for transactions without external outputs (consolidations), which
+    /// have a spend amount of zero; use `max_fee` or `max_fee_rate` to bound those.
+    pub max_fee_percent: Option<f64>,
+    /// Require every output that belongs to the wallet to use a recognized wallet chain
+    pub require_wallet_change: bool,
+}
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum SigningPolicyError {
+    /// The transaction could not be parsed with the wallet keys
+    Parse(ParseTransactionError),
+    /// The miner fee exceeds `max_fee`
+    FeeTooHigh { fee: u64, max_fee: u64 },
+    /// The miner fee exceeds `max_fee_rate` for the transaction size
+    FeeRateTooHigh {
+        fee: u64,
+        virtual_size: u32,
+        max_fee_rate: FeeRate,
+    },
+    /// The miner fee exceeds `max_fee_percent` of the spend amount
+    FeePercentTooHigh {
+        fee: u64,
+        spend_amount: u64,
+        max_fee_percent: f64,
+    },
+    /// A wallet output does not derive from a recognized wallet chain
+    UnrecognizedChange { output_index: usize },
+}
+
+impl std::fmt::Display for SigningPolicyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SigningPolicyError::Parse(e) => write!(f, "{}", e),
+            SigningPolicyError::FeeTooHigh { fee, max_fee } => {
+                write!(f, "Fee {} sat exceeds maximum of {} sat", fee, max_fee)
+            }
+            SigningPolicyError::FeeRateTooHigh {
+                fee,
+                virtual_size,
+                max_fee_rate,
+            } => write!(
+                f,
+                "Fee {} sat for {} vbytes exceeds maximum fee rate of {} sat/vB",
+                fee,
+                virtual_size,
+                max_fee_rate.to_sat_per_vb_ceil()
+            ),
+            SigningPolicyError::FeePercentTooHigh {
+                fee,
+                spend_amount,
+                max_fee_percent,
+            } => write!(
+                f,
+                "Fee {} sat exceeds {}% of spend amount {} sat",
+                fee, max_fee_percent, spend_amount
+            ),
+            SigningPolicyError::UnrecognizedChange { output_index } => write!(
+                f,
+                "Output {} belongs to the wallet but not to a recognized wallet chain",
+                output_index
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SigningPolicyError {}
+
+crate::impl_wasm_error_code!(SigningPolicyError);
+
+impl From<ParseTransactionError> for SigningPolicyError {
+    fn from(e: ParseTransactionError) -> Self {
+        SigningPolicyError::Parse(e)
+    }
+}
+
+impl SigningPolicy {
+    /// Check the policy against a parsed transaction
+    pub fn check(&self, parsed: &ParsedTransaction) -> Result<(), SigningPolicyError> {
+        let fee = parsed.miner_fee;
+
+        if let Some(max_fee) = self.max_fee {
+            if fee > max_fee {
+                return Err(SigningPolicyError::FeeTooHigh { fee, max_fee });
+            }
+        }
+
+        if let Some(max_fee_rate) = self.max_fee_rate {
+            let max = max_fee_rate
+                .fee_vb(parsed.virtual_size as u64)
+                .map(|amount| amount.to_sat())
+                .unwrap_or(u64::MAX);
+            if fee > max {
+                return Err(SigningPolicyError::FeeRateTooHigh {
+                    fee,
+                    virtual_size: parsed.virtual_size,
+                    max_fee_rate,
+                });
+            }
+        }
+
+        if let Some(max_fee_percent) = self.max_fee_percent {
+            let spend_amount = parsed.spend_amount;
+            if spend_amount > 0 && fee as f64 * 100.0 > spend_amount as f64 * max_fee_percent {
+                return Err(SigningPolicyError::FeePercentTooHigh {
+                    fee,
+                    spend_amount,
+                    max_fee_percent,
+                });
+            }
+        }
+
+        if self.require_wallet_change {
+            // Wallet outputs with a non-standard derivation path have no script id
+            if let Some(output_index) = parsed
+                .outputs
+                .iter()
+                .position(|output| !output.is_external() && output.script_id.is_none())
+            {
+                return Err(SigningPolicyError::UnrecognizedChange { output_index });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::bitgo_psbt::{
+        BitGoPsbt, ParsedOutput, ScriptId, WalletInputOptions,
+    };
+    use crate::fixed_script_wallet::wallet_keys::tests::{
+        get_test_wallet_keys, get_test_wallet_xprvs,
+    };
+    use crate::fixed_script_wallet::{ReplayProtection, RootWalletKeys};
+    use crate::Network;
+    use miniscript::bitcoin::bip32::DerivationPath;
+    use miniscript::bitcoin::{ScriptBuf, Txid};
+    use std::str::FromStr;
+
+    /// 20_000 sat in, 15_000 sat spend, 4_000 sat change, 1_000 sat fee
+    fn psbt_and_keys() -> (BitGoPsbt, RootWalletKeys) {
+        let wallet_keys = get_test_wallet_keys("signing_policy");
+        let mut psbt = BitGoPsbt::new(Network::Bitcoin, &wallet_keys, Some(2), Some(0));
+        let txid = Txid::from_str(&"01".repeat(32)).unwrap();
+        for vout in 0..2 {
+            psbt.add_wallet_input(
+                txid,
+                vout,
+                10_000,
+                &wallet_keys,
+                ScriptId {
+                    chain: 20,
+                    index: vout,
+                },
+                WalletInputOptions::default(),
+            )
+            .unwrap();
+        }
+        psbt.add_output(ScriptBuf::new_op_return([0u8; 4]), 15_000)
+            .unwrap();
+        psbt.add_wallet_output(21, 0, 4_000, &wallet_keys).unwrap();
+        (psbt, wallet_keys)
+    }
+
+    fn check(policy: SigningPolicy) -> Result<ParsedTransaction, SigningPolicyError> {
+        let (psbt, wallet_keys) = psbt_and_keys();
+        psbt.check_signing_policy(&wallet_keys, &ReplayProtection::new(vec![]), &policy)
+    }
+
+    #[test]
+    fn test_limits() {
+        let parsed = check(SigningPolicy::default()).unwrap();
+        assert_eq!(parsed.miner_fee, 1_000);
+        assert_eq!(parsed.spend_amount, 15_000);
+
+        check(SigningPolicy {
+            max_fee: Some(1_000),
+            max_fee_percent: Some(10.0),
+            require_wallet_change: true,
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert!(matches!(
+            check(SigningPolicy {
+                max_fee: Some(999),
+                ..Default::default()
+            }),
+            Err(SigningPolicyError::FeeTooHigh {
+                fee: 1_000,
+                max_fee: 999
+            })
+        ));
+        assert!(matches!(
+            check(SigningPolicy {
+                max_fee_percent: Some(5.0),
+                ..Default::default()
+            }),
+            Err(SigningPolicyError::FeePercentTooHigh { .. })
+        ));
+        assert!(matches!(
+            check(SigningPolicy {
+                max_fee_rate: Some(FeeRate::from_sat_per_vb_unchecked(1)),
+                ..Default::default()
+            }),
+            Err(SigningPolicyError::FeeRateTooHigh { .. })
+        ));
+        check(SigningPolicy {
+            max_fee_rate: Some(FeeRate::from_sat_per_vb_unchecked(1_000)),
+            ..Default::default()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_unrecognized_change() {
+        let mut parsed = check(SigningPolicy::default()).unwrap();
+        let policy = SigningPolicy {
+            require_wallet_change: true,
+            ..Default::default()
+        };
+        policy.check(&parsed).unwrap();
+
+        parsed.outputs.push(ParsedOutput {
+            address: None,
+            script: vec![],
+            value: 0,
+            script_id: None,
+            paygo: false,
+            derivation_path: Some(DerivationPath::from_str("m/5/0").unwrap()),
+            external_script_type: None,
+            label: None,
+        });
+        assert!(matches!(
+            policy.check(&parsed),
+            Err(SigningPolicyError::UnrecognizedChange { output_index: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_sign_refuses_unless_overridden() {
+        let (mut psbt, wallet_keys) = psbt_and_keys();
+        let xpriv = get_test_wallet_xprvs("signing_policy")[0];
+        let replay_protection = ReplayProtection::new(vec![]);
+        let policy = SigningPolicy {
+            max_fee: Some(500),
+            ..Default::default()
+        };
+
+        let err = psbt
+            .sign_all_with_xpriv_and_policy(
+                &xpriv,
+                &wallet_keys,
+                &replay_protection,
+                &policy,
+                false,
+            )
+            .unwrap_err();
+        assert!(err.contains("Signing policy violated"), "{}", err);
+        assert!(psbt.psbt().inputs.iter().all(|i| i.partial_sigs.is_empty()));
+
+        let signed = psbt
+            .sign_all_with_xpriv_and_policy(&xpriv, &wallet_keys, &replay_protection, &policy, true)
+            .unwrap();
+        assert_eq!(signed.len(), 2);
+    }
+}
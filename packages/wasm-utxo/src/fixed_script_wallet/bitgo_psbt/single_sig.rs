@@ -0,0 +1,465 @@
+//! Single-signature inputs locked to a single wallet key
+//!
+//! Funds are occasionally sent to single-key scripts derived from one of the wallet xpubs, e.g.
+//! after an xpub was imported into a third-party single-sig wallet. To recover them, the PSBT
+//! spends these outputs like any other input and the holder of the corresponding xprv signs
+//! with [`BitGoPsbt::sign_with_privkey`].
+//!
+//! Supported scripts are P2PKH, P2WPKH and BIP86 P2TR (key path only, no script tree). The
+//! derivation path of a single-sig input is relative to the wallet xpub itself; the derivation
+//! prefix of the wallet keys is not applied.
+
+use miniscript::bitcoin::bip32::DerivationPath;
+use miniscript::bitcoin::psbt::{Input, Psbt};
+use miniscript::bitcoin::{secp256k1, CompressedPublicKey, Script, ScriptBuf, Txid};
+
+use super::p2tr_musig2_input::Musig2Input;
+use super::psbt_wallet_input::{InputScriptType, SignerKey, WalletInputOptions};
+use super::BitGoPsbt;
+use super::{
+    check_value, get_default_ecdsa_sighash_type, psbt_wallet_input, sighash, SignInputError,
+};
+use crate::fixed_script_wallet::RootWalletKeys;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SingleSigScriptType {
+    P2pkh,
+    P2wpkh,
+    /// BIP86 taproot output without script tree, spent via the key path
+    P2trKeyPath,
+}
+
+impl SingleSigScriptType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::P2pkh => "p2pkh",
+            Self::P2wpkh => "p2wpkh",
+            Self::P2trKeyPath => "p2trKeyPath",
+        }
+    }
+
+    pub fn is_segwit(self) -> bool {
+        !matches!(self, Self::P2pkh)
+    }
+
+    /// The output script paying to `pubkey`
+    pub fn output_script<C: secp256k1::Verification>(
+        self,
+        secp: &secp256k1::Secp256k1<C>,
+        pubkey: &CompressedPublicKey,
+    ) -> ScriptBuf {
+        match self {
+            Self::P2pkh => ScriptBuf::new_p2pkh(&pubkey.pubkey_hash()),
+            Self::P2wpkh => ScriptBuf::new_p2wpkh(&pubkey.wpubkey_hash()),
+            Self::P2trKeyPath => {
+                let (x_only, _) = pubkey.0.x_only_public_key();
+                ScriptBuf::new_p2tr(secp, x_only, None)
+            }
+        }
+    }
+
+    /// Detect the single-sig script type an input could be spending
+    ///
+    /// Taproot inputs only qualify if they have no script tree and are not MuSig2 inputs.
+    /// Whether the key actually belongs to the wallet is checked by
+    /// [`SingleSigInput::from_psbt_input`].
+    pub fn from_input(psbt_input: &Input, output_script: &Script) -> Option<Self> {
+        if output_script.is_p2pkh() {
+            Some(Self::P2pkh)
+        } else if output_script.is_p2wpkh() {
+            Some(Self::P2wpkh)
+        } else if output_script.is_p2tr()
+            && psbt_input.tap_scripts.is_empty()
+            && psbt_input.tap_merkle_root.is_none()
+            && !Musig2Input::is_musig2_input(psbt_input)
+        {
+            Some(Self::P2trKeyPath)
+        } else {
+            None
+        }
+    }
+}
+
+impl std::str::FromStr for SingleSigScriptType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "p2pkh" => Ok(Self::P2pkh),
+            "p2wpkh" => Ok(Self::P2wpkh),
+            "p2trKeyPath" => Ok(Self::P2trKeyPath),
+            _ => Err(format!(
+                "Unknown single-sig script type '{}'. Expected 'p2pkh', 'p2wpkh' or 'p2trKeyPath'",
+                s
+            )),
+        }
+    }
+}
+
+impl From<SingleSigScriptType> for InputScriptType {
+    fn from(script_type: SingleSigScriptType) -> Self {
+        match script_type {
+            SingleSigScriptType::P2pkh => InputScriptType::P2pkh,
+            SingleSigScriptType::P2wpkh => InputScriptType::P2wpkh,
+            SingleSigScriptType::P2trKeyPath => InputScriptType::P2trKeyPath,
+        }
+    }
+}
+
+/// A PSBT input spending a single-sig output of one of the wallet keys
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SingleSigInput {
+    pub script_type: SingleSigScriptType,
+    pub signer: SignerKey,
+    /// Derivation path relative to the wallet xpub
+    pub derivation_path: DerivationPath,
+}
+
+impl SingleSigInput {
+    /// Match a PSBT input against the single-sig scripts of the wallet keys
+    ///
+    /// Returns `None` unless the output script is a single-sig script of a key whose origin
+    /// (`bip32_derivation`, or `tap_key_origins` for taproot) has the fingerprint of a wallet
+    /// xpub and derives to the key of the script.
+    pub fn from_psbt_input(
+        wallet_keys: &RootWalletKeys,
+        psbt_input: &Input,
+        output_script: &Script,
+    ) -> Option<Self> {
+        let script_type = SingleSigScriptType::from_input(psbt_input, output_script)?;
+        let origins: Vec<_> = match script_type {
+            SingleSigScriptType::P2pkh | SingleSigScriptType::P2wpkh => {
+                psbt_input.bip32_derivation.values().collect()
+            }
+            SingleSigScriptType::P2trKeyPath => psbt_input
+                .tap_key_origins
+                .values()
+                .map(|(_, origin)| origin)
+                .collect(),
+        };
+        let secp = secp256k1::Secp256k1::verification_only();
+        origins.into_iter().find_map(|(fingerprint, path)| {
+            let signer = [SignerKey::User, SignerKey::Backup, SignerKey::Bitgo]
+                .into_iter()
+                .find(|signer| wallet_keys.xpubs[signer.index()].fingerprint() == *fingerprint)?;
+            let derived = wallet_keys.xpubs[signer.index()]
+                .derive_pub(&secp, path)
+                .ok()?;
+            (script_type.output_script(&secp, &derived.to_pub()) == *output_script).then(|| {
+                SingleSigInput {
+                    script_type,
+                    signer,
+                    derivation_path: path.clone(),
+                }
+            })
+        })
+    }
+}
+
+impl BitGoPsbt {
+    /// Add an input spending a single-sig output of a wallet key
+    ///
+    /// # Arguments
+    /// * `signer` - The wallet key the output was derived from
+    /// * `derivation_path` - Path from the wallet xpub to the key of the output
+    /// * `script_type` - The single-sig script of the output
+    /// * `options` - Sequence and previous transaction; `sign_path` is ignored
+    ///
+    /// # Returns
+    /// The index of the newly added input
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_single_sig_input(
+        &mut self,
+        txid: Txid,
+        vout: u32,
+        value: u64,
+        wallet_keys: &RootWalletKeys,
+        signer: SignerKey,
+        derivation_path: &DerivationPath,
+        script_type: SingleSigScriptType,
+        options: WalletInputOptions,
+    ) -> Result<usize, String> {
+        use miniscript::bitcoin::sighash::TapSighashType;
+        use miniscript::bitcoin::{Amount, OutPoint, TxIn, TxOut, Witness};
+
+        let network = self.network();
+        if matches!(network.mainnet(), crate::Network::Zcash) {
+            return Err("Single-sig inputs are not supported for Zcash".to_string());
+        }
+        check_value(network, value)?;
+
+        let secp = secp256k1::Secp256k1::verification_only();
+        let xpub = &wallet_keys.xpubs[signer.index()];
+        let pubkey = xpub
+            .derive_pub(&secp, derivation_path)
+            .map_err(|e| format!("Failed to derive key: {}", e))?
+            .to_pub();
+        let output_script = script_type.output_script(&secp, &pubkey);
+        network
+            .output_script_support()
+            .assert_support(&output_script)
+            .map_err(|e| e.to_string())?;
+
+        let tx_in = TxIn {
+            previous_output: OutPoint { txid, vout },
+            script_sig: ScriptBuf::new(),
+            sequence: options.sequence.unwrap_or_default().to_sequence(),
+            witness: Witness::default(),
+        };
+
+        let mut psbt_input = Input::default();
+        if let (false, Some(tx_bytes)) = (script_type.is_segwit(), options.prev_tx) {
+            psbt_input.non_witness_utxo = Some(
+                miniscript::bitcoin::consensus::deserialize(tx_bytes)
+                    .map_err(|e| format!("Failed to deserialize previous transaction: {}", e))?,
+            );
+        } else {
+            psbt_input.witness_utxo = Some(TxOut {
+                value: Amount::from_sat(value),
+                script_pubkey: output_script,
+            });
+        }
+
+        let origin = (xpub.fingerprint(), derivation_path.clone());
+        match script_type {
+            SingleSigScriptType::P2pkh | SingleSigScriptType::P2wpkh => {
+                psbt_input.sighash_type = Some(get_default_ecdsa_sighash_type(network));
+                psbt_input.bip32_derivation.insert(pubkey.0, origin);
+            }
+            SingleSigScriptType::P2trKeyPath => {
+                let (x_only, _) = pubkey.0.x_only_public_key();
+                psbt_input.sighash_type = Some(TapSighashType::Default.into());
+                psbt_input.tap_internal_key = Some(x_only);
+                psbt_input.tap_key_origins.insert(x_only, (vec![], origin));
+            }
+        }
+
+        let index = self.psbt().inputs.len();
+        crate::psbt_ops::insert_input(self.psbt_mut(), index, tx_in, psbt_input)?;
+        Ok(index)
+    }
+}
+
+/// Sign a single-sig input with a raw private key
+///
+/// P2PKH and P2WPKH signatures are added to `partial_sigs`, using the `SIGHASH_FORKID`
+/// algorithm on networks that require it. The sighash type of the input is honored and defaults
+/// to `SIGHASH_ALL` (with `SIGHASH_FORKID` where required). P2TR signatures are made with the
+/// BIP86-tweaked key and set as `tap_key_sig`.
+pub(super) fn sign_single_sig_input<C: secp256k1::Signing + secp256k1::Verification>(
+    psbt: &mut Psbt,
+    input_index: usize,
+    script_type: SingleSigScriptType,
+    privkey: &secp256k1::SecretKey,
+    network: crate::Network,
+    secp: &secp256k1::Secp256k1<C>,
+) -> Result<(), SignInputError> {
+    use miniscript::bitcoin::hashes::Hash;
+    use miniscript::bitcoin::key::TapTweak;
+    use miniscript::bitcoin::sighash::{EcdsaSighashType, Prevouts, SighashCache};
+    use miniscript::bitcoin::{ecdsa, taproot, TapSighashType};
+
+    let pubkey = CompressedPublicKey(secp256k1::PublicKey::from_secret_key(secp, privkey));
+    let input = &psbt.inputs[input_index];
+    let prevout = psbt.unsigned_tx.input[input_index].previous_output;
+    let (output_script, value) = psbt_wallet_input::get_output_script_and_value(input, prevout)
+        .map_err(SignInputError::Utxo)?;
+    let output_script = output_script.clone();
+    if script_type.output_script(secp, &pubkey) != output_script {
+        return Err(SignInputError::ScriptMismatch {
+            script_type: script_type.as_str(),
+        });
+    }
+
+    let mut cache = SighashCache::new(&psbt.unsigned_tx);
+    if script_type == SingleSigScriptType::P2trKeyPath {
+        let sighash_type = input
+            .sighash_type
+            .map(|t| t.taproot_hash_ty())
+            .transpose()
+            .map_err(|e| SignInputError::InvalidSighashType(e.to_string()))?
+            .unwrap_or(TapSighashType::Default);
+        let prevouts =
+            psbt_wallet_input::collect_prevouts(psbt).map_err(SignInputError::Prevouts)?;
+        let sighash = cache
+            .taproot_key_spend_signature_hash(input_index, &Prevouts::All(&prevouts), sighash_type)
+            .map_err(|e| SignInputError::Sighash(e.to_string()))?;
+        let keypair = secp256k1::Keypair::from_secret_key(secp, privkey)
+            .tap_tweak(secp, None)
+            .to_inner();
+        let message = secp256k1::Message::from_digest(sighash.to_byte_array());
+        psbt.inputs[input_index].tap_key_sig = Some(taproot::Signature {
+            signature: secp.sign_schnorr_no_aux_rand(&message, &keypair),
+            sighash_type,
+        });
+        return Ok(());
+    }
+
+    let sighash_type = input
+        .sighash_type
+        .unwrap_or_else(|| get_default_ecdsa_sighash_type(network))
+        .to_u32();
+    let message = if let Some(fork_id) = sighash::get_sighash_fork_id(network) {
+        // BIP143-style sighash; the script code of both script types is the P2PKH script
+        if sighash_type & 0x40 == 0 {
+            return Err(SignInputError::MissingForkId { sighash_type });
+        }
+        let script_code = ScriptBuf::new_p2pkh(&pubkey.pubkey_hash());
+        let sighash = cache
+            .p2wsh_signature_hash_forkid(
+                input_index,
+                &script_code,
+                value,
+                sighash_type,
+                Some(fork_id),
+            )
+            .map_err(|e| SignInputError::Sighash(e.to_string()))?;
+        sighash.to_byte_array()
+    } else {
+        let ecdsa_type = EcdsaSighashType::from_standard(sighash_type)
+            .map_err(|e| SignInputError::InvalidSighashType(e.to_string()))?;
+        if script_type == SingleSigScriptType::P2wpkh {
+            let sighash = cache
+                .p2wpkh_signature_hash(input_index, &output_script, value, ecdsa_type)
+                .map_err(|e| SignInputError::Sighash(e.to_string()))?;
+            sighash.to_byte_array()
+        } else {
+            let sighash = cache
+                .legacy_signature_hash(input_index, &output_script, ecdsa_type.to_u32())
+                .map_err(|e| SignInputError::Sighash(e.to_string()))?;
+            sighash.to_byte_array()
+        }
+    };
+
+    let message = secp256k1::Message::from_digest(message);
+    let signature = ecdsa::Signature {
+        signature: secp.sign_ecdsa(&message, privkey),
+        sighash_type,
+    };
+    psbt.inputs[input_index]
+        .partial_sigs
+        .insert(pubkey.into(), signature);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::{
+        ExternalInputPolicy, ParsedInput,
+    };
+    use crate::fixed_script_wallet::wallet_keys::tests::{
+        get_test_wallet_keys, get_test_wallet_xprvs,
+    };
+    use crate::fixed_script_wallet::ReplayProtection;
+    use crate::Network;
+    use miniscript::bitcoin::hashes::Hash;
+    use std::str::FromStr;
+
+    const SEED: &str = "single_sig";
+
+    fn single_sig_psbt(network: Network, script_type: SingleSigScriptType) -> BitGoPsbt {
+        let wallet_keys = get_test_wallet_keys(SEED);
+        let mut psbt = BitGoPsbt::new(network, &wallet_keys, None, None);
+        psbt.add_single_sig_input(
+            Txid::all_zeros(),
+            0,
+            10_000,
+            &wallet_keys,
+            SignerKey::User,
+            &DerivationPath::from_str("m/0/7").unwrap(),
+            script_type,
+            WalletInputOptions::default(),
+        )
+        .unwrap();
+        psbt.add_wallet_output(0, 0, 9_000, &wallet_keys).unwrap();
+        psbt
+    }
+
+    fn user_privkey() -> secp256k1::SecretKey {
+        let secp = secp256k1::Secp256k1::new();
+        get_test_wallet_xprvs(SEED)[0]
+            .derive_priv(&secp, &DerivationPath::from_str("m/0/7").unwrap())
+            .unwrap()
+            .private_key
+    }
+
+    #[test]
+    fn test_single_sig_parse_sign_finalize() {
+        let wallet_keys = get_test_wallet_keys(SEED);
+        let cases = [
+            (Network::Bitcoin, SingleSigScriptType::P2pkh),
+            (Network::Bitcoin, SingleSigScriptType::P2wpkh),
+            (Network::Bitcoin, SingleSigScriptType::P2trKeyPath),
+            (Network::BitcoinCash, SingleSigScriptType::P2pkh),
+            (Network::BitcoinGold, SingleSigScriptType::P2wpkh),
+        ];
+        for (network, script_type) in cases {
+            let mut psbt = single_sig_psbt(network, script_type);
+            let parsed = ParsedInput::parse(
+                &psbt.psbt().inputs[0],
+                &psbt.psbt().unsigned_tx.input[0],
+                &wallet_keys,
+                &ReplayProtection::new(vec![]),
+                network,
+                ExternalInputPolicy::Reject,
+            )
+            .unwrap();
+            assert_eq!(parsed.script_type, InputScriptType::from(script_type));
+            assert_eq!(parsed.script_id, None);
+            assert_eq!(
+                parsed.derivation_path,
+                Some(DerivationPath::from_str("m/0/7").unwrap())
+            );
+
+            psbt.sign_with_privkey(0, &user_privkey()).unwrap();
+            psbt.finalize_mut(&secp256k1::Secp256k1::verification_only())
+                .unwrap_or_else(|e| panic!("{} {:?}: {:?}", network, script_type, e));
+        }
+    }
+
+    #[test]
+    fn test_single_sig_wrong_key() {
+        let mut psbt = single_sig_psbt(Network::Bitcoin, SingleSigScriptType::P2wpkh);
+        let other = get_test_wallet_xprvs(SEED)[1].private_key;
+        assert!(psbt.sign_with_privkey(0, &other).is_err());
+        assert!(psbt.psbt().inputs[0].partial_sigs.is_empty());
+    }
+
+    #[test]
+    fn test_single_sig_honors_sighash_type() {
+        use miniscript::bitcoin::psbt::PsbtSighashType;
+
+        let mut psbt = single_sig_psbt(Network::Bitcoin, SingleSigScriptType::P2wpkh);
+        psbt.psbt_mut().inputs[0].sighash_type = Some(PsbtSighashType::from_u32(0x81));
+        psbt.sign_with_privkey(0, &user_privkey()).unwrap();
+        let sig = psbt.psbt().inputs[0].partial_sigs.values().next().unwrap();
+        assert_eq!(sig.sighash_type, 0x81);
+        psbt.finalize_mut(&secp256k1::Secp256k1::verification_only())
+            .unwrap();
+
+        // FORKID networks require the FORKID bit
+        let mut psbt = single_sig_psbt(Network::BitcoinCash, SingleSigScriptType::P2pkh);
+        psbt.psbt_mut().inputs[0].sighash_type = Some(PsbtSighashType::from_u32(0x01));
+        assert!(psbt.sign_with_privkey(0, &user_privkey()).is_err());
+        assert!(psbt.psbt().inputs[0].partial_sigs.is_empty());
+    }
+
+    #[test]
+    fn test_single_sig_unsupported_script() {
+        let wallet_keys = get_test_wallet_keys(SEED);
+        let mut psbt = BitGoPsbt::new(Network::BitcoinCash, &wallet_keys, None, None);
+        assert!(psbt
+            .add_single_sig_input(
+                Txid::all_zeros(),
+                0,
+                10_000,
+                &wallet_keys,
+                SignerKey::Backup,
+                &DerivationPath::from_str("m/0/0").unwrap(),
+                SingleSigScriptType::P2wpkh,
+                WalletInputOptions::default(),
+            )
+            .is_err());
+    }
+}
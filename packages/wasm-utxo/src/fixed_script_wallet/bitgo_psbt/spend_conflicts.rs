@@ -0,0 +1,160 @@
+//! Inputs that spend the same outpoint
+//!
+//! A transaction that spends an outpoint twice is invalid, and of two transactions that spend
+//! the same outpoint only one can confirm. Neither is detected when the PSBTs are built or
+//! signed; the node only rejects the transaction at broadcast. [`find_spend_conflicts`]
+//! reports them for a set of in-flight PSBTs up front.
+//!
+//! A replacement (RBF) of a transaction spends the same outpoints as the transaction it
+//! replaces, so it is reported as a conflict with it as well.
+
+use std::collections::HashMap;
+
+use miniscript::bitcoin::OutPoint;
+
+use super::BitGoPsbt;
+
+/// An input of one of the PSBTs passed to [`find_spend_conflicts`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputRef {
+    pub psbt_index: usize,
+    pub input_index: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpendConflictKind {
+    /// All inputs spending the outpoint are in the same PSBT
+    DuplicateInput,
+    /// The outpoint is spent by more than one PSBT
+    ConflictingSpend,
+}
+
+impl SpendConflictKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SpendConflictKind::DuplicateInput => "duplicateInput",
+            SpendConflictKind::ConflictingSpend => "conflictingSpend",
+        }
+    }
+}
+
+/// An outpoint spent by more than one input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpendConflict {
+    pub outpoint: OutPoint,
+    pub kind: SpendConflictKind,
+    /// The inputs spending the outpoint, in PSBT and input order
+    pub inputs: Vec<InputRef>,
+}
+
+/// Find outpoints that are spent by more than one input of `psbts`
+///
+/// The conflicts are ordered by the first input spending the outpoint.
+pub fn find_spend_conflicts(psbts: &[BitGoPsbt]) -> Vec<SpendConflict> {
+    let mut spenders: Vec<(OutPoint, Vec<InputRef>)> = Vec::new();
+    let mut positions: HashMap<OutPoint, usize> = HashMap::new();
+    for (psbt_index, psbt) in psbts.iter().enumerate() {
+        for (input_index, tx_in) in psbt.psbt().unsigned_tx.input.iter().enumerate() {
+            let input = InputRef {
+                psbt_index,
+                input_index,
+            };
+            let outpoint = tx_in.previous_output;
+            match positions.get(&outpoint) {
+                Some(&position) => spenders[position].1.push(input),
+                None => {
+                    positions.insert(outpoint, spenders.len());
+                    spenders.push((outpoint, vec![input]));
+                }
+            }
+        }
+    }
+
+    spenders
+        .into_iter()
+        .filter(|(_, inputs)| inputs.len() > 1)
+        .map(|(outpoint, inputs)| {
+            let kind = if inputs
+                .iter()
+                .all(|input| input.psbt_index == inputs[0].psbt_index)
+            {
+                SpendConflictKind::DuplicateInput
+            } else {
+                SpendConflictKind::ConflictingSpend
+            };
+            SpendConflict {
+                outpoint,
+                kind,
+                inputs,
+            }
+        })
+        .collect()
+}
+
+impl BitGoPsbt {
+    /// Outpoints spent by more than one input of this PSBT
+    pub fn duplicate_inputs(&self) -> Vec<SpendConflict> {
+        find_spend_conflicts(std::slice::from_ref(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::bitgo_psbt::{ScriptId, WalletInputOptions};
+    use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+    use crate::Network;
+    use miniscript::bitcoin::hashes::Hash;
+    use miniscript::bitcoin::Txid;
+
+    fn create_psbt(vouts: &[u32]) -> BitGoPsbt {
+        let wallet_keys = get_test_wallet_keys("spend_conflicts");
+        let mut psbt = BitGoPsbt::new(Network::Bitcoin, &wallet_keys, None, None);
+        for &vout in vouts {
+            psbt.add_wallet_input(
+                Txid::all_zeros(),
+                vout,
+                10_000,
+                &wallet_keys,
+                ScriptId {
+                    chain: 20,
+                    index: 0,
+                },
+                WalletInputOptions::default(),
+            )
+            .unwrap();
+        }
+        psbt
+    }
+
+    fn input(psbt_index: usize, input_index: usize) -> InputRef {
+        InputRef {
+            psbt_index,
+            input_index,
+        }
+    }
+
+    #[test]
+    fn test_no_conflicts() {
+        assert!(find_spend_conflicts(&[]).is_empty());
+        assert!(find_spend_conflicts(&[create_psbt(&[0, 1]), create_psbt(&[2])]).is_empty());
+        assert!(create_psbt(&[0, 1]).duplicate_inputs().is_empty());
+    }
+
+    #[test]
+    fn test_find_spend_conflicts() {
+        let psbts = [create_psbt(&[0, 1, 0]), create_psbt(&[2, 1])];
+        let conflicts = find_spend_conflicts(&psbts);
+        assert_eq!(conflicts.len(), 2);
+
+        assert_eq!(conflicts[0].outpoint, OutPoint::new(Txid::all_zeros(), 0));
+        assert_eq!(conflicts[0].kind, SpendConflictKind::DuplicateInput);
+        assert_eq!(conflicts[0].inputs, vec![input(0, 0), input(0, 2)]);
+
+        assert_eq!(conflicts[1].outpoint, OutPoint::new(Txid::all_zeros(), 1));
+        assert_eq!(conflicts[1].kind, SpendConflictKind::ConflictingSpend);
+        assert_eq!(conflicts[1].inputs, vec![input(0, 1), input(1, 1)]);
+
+        assert_eq!(psbts[0].duplicate_inputs(), conflicts[..1]);
+    }
+}
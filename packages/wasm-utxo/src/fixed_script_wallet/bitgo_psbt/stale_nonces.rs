@@ -0,0 +1,212 @@
+//! Detection of stale MuSig2 nonces
+//!
+//! A MuSig2 public nonce is keyed by `<participantPubKey><tapOutputKey>`. When an input is
+//! replaced (for instance re-added with another script id), nonces of the previous input can
+//! remain in its proprietary map. They no longer match the input and only fail much later,
+//! when the signature is aggregated at finalization.
+//!
+//! A nonce is stale if its tap output key is not the output key of the input (taken from the
+//! P2TR `witness_utxo` if present, otherwise from the participants record), or if its
+//! participant is not one of the two participants of the input.
+
+use miniscript::bitcoin::psbt::Input;
+use miniscript::bitcoin::XOnlyPublicKey;
+
+use super::p2tr_musig2_input::{parse_musig2_participants, Musig2Error, Musig2PubNonce};
+use super::propkv::{find_kv, ProprietaryKeySubtype};
+use super::BitGoPsbt;
+
+/// Why a nonce does not belong to its input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::IntoStaticStr)]
+#[strum(serialize_all = "camelCase")]
+pub enum StaleNonceReason {
+    /// The input has no MuSig2 participants
+    MissingParticipants,
+    /// The nonce was made for another tap output key
+    TapOutputKeyMismatch,
+    /// The nonce was made by a key that is not a participant of the input
+    UnknownParticipant,
+}
+
+impl std::fmt::Display for StaleNonceReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StaleNonceReason::MissingParticipants => {
+                write!(f, "input has no MuSig2 participants")
+            }
+            StaleNonceReason::TapOutputKeyMismatch => {
+                write!(f, "tap output key does not match the input")
+            }
+            StaleNonceReason::UnknownParticipant => {
+                write!(f, "nonce key is not a participant of the input")
+            }
+        }
+    }
+}
+
+/// A MuSig2 nonce that does not match its input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleMusig2Nonce {
+    pub input_index: usize,
+    pub nonce: Musig2PubNonce,
+    pub reason: StaleNonceReason,
+}
+
+/// All nonces of an input
+///
+/// Unlike [`super::p2tr_musig2_input::parse_musig2_nonces`], this does not limit the number
+/// of nonces: an input with stale nonces can hold more than two.
+fn input_nonces(input: &Input) -> Result<Vec<Musig2PubNonce>, Musig2Error> {
+    find_kv(ProprietaryKeySubtype::Musig2PubNonce, &input.proprietary)
+        .map(|kv| Musig2PubNonce::from_key_value(&kv))
+        .collect()
+}
+
+/// Output key of a P2TR `witness_utxo`
+fn witness_utxo_output_key(input: &Input) -> Option<XOnlyPublicKey> {
+    let script = &input.witness_utxo.as_ref()?.script_pubkey;
+    if !script.is_p2tr() {
+        return None;
+    }
+    XOnlyPublicKey::from_slice(&script.as_bytes()[2..34]).ok()
+}
+
+/// Check `nonce` against `input`, returning why it is stale or `None` if it matches
+pub(crate) fn stale_nonce_reason(
+    input: &Input,
+    nonce: &Musig2PubNonce,
+) -> Result<Option<StaleNonceReason>, Musig2Error> {
+    let Some(participants) = parse_musig2_participants(input)? else {
+        return Ok(Some(StaleNonceReason::MissingParticipants));
+    };
+    let tap_output_key = witness_utxo_output_key(input).unwrap_or(participants.tap_output_key);
+    if nonce.tap_output_key != tap_output_key {
+        return Ok(Some(StaleNonceReason::TapOutputKeyMismatch));
+    }
+    if !participants
+        .participant_pub_keys
+        .contains(&nonce.participant_pub_key)
+    {
+        return Ok(Some(StaleNonceReason::UnknownParticipant));
+    }
+    Ok(None)
+}
+
+fn stale_input_nonces(
+    input_index: usize,
+    input: &Input,
+) -> Result<Vec<StaleMusig2Nonce>, Musig2Error> {
+    let mut stale = vec![];
+    for nonce in input_nonces(input)? {
+        if let Some(reason) = stale_nonce_reason(input, &nonce)? {
+            stale.push(StaleMusig2Nonce {
+                input_index,
+                nonce,
+                reason,
+            });
+        }
+    }
+    Ok(stale)
+}
+
+impl BitGoPsbt {
+    /// Find the MuSig2 nonces that do not match their input
+    pub fn find_stale_musig2_nonces(&self) -> Result<Vec<StaleMusig2Nonce>, Musig2Error> {
+        let mut stale = vec![];
+        for (input_index, input) in self.psbt().inputs.iter().enumerate() {
+            stale.extend(stale_input_nonces(input_index, input)?);
+        }
+        Ok(stale)
+    }
+
+    /// Remove the MuSig2 nonces that do not match their input
+    ///
+    /// # Returns
+    /// The removed nonces
+    pub fn purge_stale_musig2_nonces(&mut self) -> Result<Vec<StaleMusig2Nonce>, Musig2Error> {
+        let stale = self.find_stale_musig2_nonces()?;
+        let inputs = &mut self.psbt_mut().inputs;
+        for entry in &stale {
+            let (key, _) = entry.nonce.to_key_value().to_key_value();
+            inputs[entry.input_index].proprietary.remove(&key);
+        }
+        Ok(stale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::{
+        get_test_wallet_keys, get_test_wallet_xprvs,
+    };
+    use crate::fixed_script_wallet::RootWalletKeys;
+    use crate::Network;
+    use miniscript::bitcoin::ScriptBuf;
+
+    fn musig2_psbt(keys: &RootWalletKeys, index: u32) -> BitGoPsbt {
+        let mut psbt = WalletPsbtBuilder::new(Network::BitcoinTestnet3, keys)
+            .input(40, index)
+            .build();
+        psbt.add_output(ScriptBuf::new_op_return([0u8; 4]), 0)
+            .unwrap();
+        psbt
+    }
+
+    #[test]
+    fn test_combine_skips_and_purges_stale_nonces() {
+        let keys = get_test_wallet_keys("stale_nonces");
+        let xprvs = get_test_wallet_xprvs("stale_nonces");
+
+        // Nonces for an input that is later replaced by another script id
+        let mut replaced = musig2_psbt(&keys, 0);
+        replaced
+            .generate_nonce_first_round(0, &xprvs[0], [1u8; 32])
+            .unwrap();
+        assert!(replaced.find_stale_musig2_nonces().unwrap().is_empty());
+
+        let mut psbt = musig2_psbt(&keys, 1);
+        let stale = psbt.combine_musig2_nonces(&replaced).unwrap();
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].input_index, 0);
+        assert_eq!(stale[0].reason, StaleNonceReason::TapOutputKeyMismatch);
+        assert!(input_nonces(&psbt.psbt().inputs[0]).unwrap().is_empty());
+
+        // Nonces of the current input are kept
+        let mut current = musig2_psbt(&keys, 1);
+        current
+            .generate_nonce_first_round(0, &xprvs[2], [2u8; 32])
+            .unwrap();
+        assert!(psbt.combine_musig2_nonces(&current).unwrap().is_empty());
+        assert_eq!(input_nonces(&psbt.psbt().inputs[0]).unwrap().len(), 1);
+
+        // Stale nonces already in the destination are purged as well
+        let (key, value) = stale[0].nonce.to_key_value().to_key_value();
+        psbt.psbt_mut().inputs[0].proprietary.insert(key, value);
+        assert_eq!(psbt.find_stale_musig2_nonces().unwrap(), stale);
+        assert_eq!(psbt.combine_musig2_nonces(&current).unwrap(), stale);
+        assert!(psbt.find_stale_musig2_nonces().unwrap().is_empty());
+        assert_eq!(input_nonces(&psbt.psbt().inputs[0]).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_unknown_participant() {
+        let keys = get_test_wallet_keys("stale_nonces");
+        let xprvs = get_test_wallet_xprvs("stale_nonces");
+        let mut psbt = musig2_psbt(&keys, 0);
+        psbt.generate_nonce_first_round(0, &xprvs[0], [1u8; 32])
+            .unwrap();
+
+        let mut nonce = input_nonces(&psbt.psbt().inputs[0]).unwrap().remove(0);
+        let other = musig2_psbt(&get_test_wallet_keys("stale_nonces_other"), 0);
+        let other_participants = parse_musig2_participants(&other.psbt().inputs[0])
+            .unwrap()
+            .unwrap();
+        nonce.participant_pub_key = other_participants.participant_pub_keys[0];
+        assert_eq!(
+            stale_nonce_reason(&psbt.psbt().inputs[0], &nonce).unwrap(),
+            Some(StaleNonceReason::UnknownParticipant)
+        );
+    }
+}
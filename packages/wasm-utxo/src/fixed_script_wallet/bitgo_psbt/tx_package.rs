@@ -0,0 +1,369 @@
+//! Transaction packages for package relay (`submitpackage`)
+//!
+//! A package is a child transaction together with the unconfirmed parents it spends, e.g. a
+//! withdrawal and the CPFP spend of its anchor output. Nodes evaluate the fee rate of the
+//! package as a whole, so a low-fee parent can be bumped by its child.
+//!
+//! [`TxPackage`] orders the transactions parents first, checks the child-with-parents
+//! topology required by `submitpackage` and computes the aggregate fee rate.
+
+use std::collections::HashSet;
+
+use miniscript::bitcoin::Txid;
+
+use super::BitGoPsbt;
+
+/// Maximum number of transactions in a package (Bitcoin Core `MAX_PACKAGE_COUNT`)
+pub const MAX_PACKAGE_COUNT: usize = 25;
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum TxPackageError {
+    Empty,
+    TooManyTransactions {
+        count: usize,
+    },
+    /// All transactions must be on the same network
+    NetworkMismatch {
+        index: usize,
+    },
+    DuplicateTransaction {
+        txid: Txid,
+    },
+    /// The transactions spend each other in a cycle
+    Cycle,
+    /// The child does not spend an output of this parent
+    NotChildWithParents {
+        txid: Txid,
+    },
+    /// The value of an input is unknown (no witness_utxo or non_witness_utxo)
+    MissingInputValue {
+        txid: Txid,
+        input_index: usize,
+    },
+    /// Outputs exceed inputs
+    NegativeFee {
+        txid: Txid,
+    },
+    /// The number of vsizes does not match the number of transactions
+    VsizeCountMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    FeeRateTooLow {
+        fee_rate: f64,
+        min_fee_rate: f64,
+    },
+    Extract(String),
+}
+
+impl std::fmt::Display for TxPackageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TxPackageError::Empty => write!(f, "Package has no transactions"),
+            TxPackageError::TooManyTransactions { count } => write!(
+                f,
+                "Package has {} transactions, at most {} are allowed",
+                count, MAX_PACKAGE_COUNT
+            ),
+            TxPackageError::NetworkMismatch { index } => write!(
+                f,
+                "Transaction {} is on a different network than the package",
+                index
+            ),
+            TxPackageError::DuplicateTransaction { txid } => {
+                write!(f, "Transaction {} appears more than once", txid)
+            }
+            TxPackageError::Cycle => write!(f, "Package transactions spend each other in a cycle"),
+            TxPackageError::NotChildWithParents { txid } => write!(
+                f,
+                "Package is not child-with-parents: the child does not spend transaction {}",
+                txid
+            ),
+            TxPackageError::MissingInputValue { txid, input_index } => write!(
+                f,
+                "Transaction {} input {} has no witness_utxo or non_witness_utxo",
+                txid, input_index
+            ),
+            TxPackageError::NegativeFee { txid } => {
+                write!(f, "Transaction {} spends more than its inputs", txid)
+            }
+            TxPackageError::VsizeCountMismatch { expected, actual } => {
+                write!(f, "Expected {} vsizes, got {}", expected, actual)
+            }
+            TxPackageError::FeeRateTooLow {
+                fee_rate,
+                min_fee_rate,
+            } => write!(
+                f,
+                "Package fee rate {:.3} sat/vB is below the minimum of {:.3} sat/vB",
+                fee_rate, min_fee_rate
+            ),
+            TxPackageError::Extract(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for TxPackageError {}
+
+crate::impl_wasm_error_code!(TxPackageError);
+
+/// Aggregate fee of a package
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageFee {
+    /// Fee of each transaction, in package order
+    pub fees: Vec<u64>,
+    pub total_fee: u64,
+    pub total_vsize: u64,
+    /// Total fee divided by total vsize, in sat/vB
+    pub fee_rate: f64,
+}
+
+/// A child transaction and its unconfirmed parents, ordered parents first
+#[derive(Debug, Clone)]
+pub struct TxPackage {
+    transactions: Vec<BitGoPsbt>,
+}
+
+/// The txid the transaction will have once it is finalized
+///
+/// Uses the final scriptSigs that are already set, so for inputs without witness the txid
+/// only becomes stable once they are finalized.
+pub(crate) fn package_txid(psbt: &BitGoPsbt) -> Txid {
+    let BitGoPsbt::BitcoinLike(inner, _) = psbt else {
+        return psbt.unsigned_txid();
+    };
+    let mut tx = inner.unsigned_tx.clone();
+    for (tx_in, input) in tx.input.iter_mut().zip(&inner.inputs) {
+        if let Some(script_sig) = &input.final_script_sig {
+            tx_in.script_sig = script_sig.clone();
+        }
+    }
+    tx.compute_txid()
+}
+
+pub(crate) fn input_value(psbt: &BitGoPsbt, input_index: usize) -> Option<u64> {
+    let inner = psbt.psbt();
+    let input = &inner.inputs[input_index];
+    if let Some(utxo) = &input.witness_utxo {
+        return Some(utxo.value.to_sat());
+    }
+    let prevout = inner.unsigned_tx.input[input_index].previous_output;
+    input
+        .non_witness_utxo
+        .as_ref()
+        .and_then(|tx| tx.output.get(prevout.vout as usize))
+        .map(|output| output.value.to_sat())
+}
+
+impl TxPackage {
+    /// Create a package from a child and its parents, in any order
+    ///
+    /// The transactions are sorted parents first. The last transaction must spend an
+    /// output of every other transaction.
+    pub fn new(transactions: Vec<BitGoPsbt>) -> Result<Self, TxPackageError> {
+        if transactions.is_empty() {
+            return Err(TxPackageError::Empty);
+        }
+        if transactions.len() > MAX_PACKAGE_COUNT {
+            return Err(TxPackageError::TooManyTransactions {
+                count: transactions.len(),
+            });
+        }
+        let network = transactions[0].network();
+        if let Some(index) = transactions.iter().position(|tx| tx.network() != network) {
+            return Err(TxPackageError::NetworkMismatch { index });
+        }
+
+        let txids: Vec<Txid> = transactions.iter().map(package_txid).collect();
+        let mut seen = HashSet::new();
+        if let Some(txid) = txids.iter().find(|txid| !seen.insert(**txid)) {
+            return Err(TxPackageError::DuplicateTransaction { txid: *txid });
+        }
+        let spends = |child: usize, parent: usize| {
+            transactions[child]
+                .psbt()
+                .unsigned_tx
+                .input
+                .iter()
+                .any(|tx_in| tx_in.previous_output.txid == txids[parent])
+        };
+
+        // Topological sort, keeping the given order where possible
+        let count = transactions.len();
+        let mut order = Vec::with_capacity(count);
+        let mut placed = vec![false; count];
+        while order.len() < count {
+            let next = (0..count)
+                .find(|&i| !placed[i] && (0..count).all(|p| placed[p] || p == i || !spends(i, p)))
+                .ok_or(TxPackageError::Cycle)?;
+            placed[next] = true;
+            order.push(next);
+        }
+
+        let child = order[count - 1];
+        if let Some(&parent) = order[..count - 1].iter().find(|&&p| !spends(child, p)) {
+            return Err(TxPackageError::NotChildWithParents {
+                txid: txids[parent],
+            });
+        }
+
+        let mut transactions: Vec<Option<BitGoPsbt>> = transactions.into_iter().map(Some).collect();
+        Ok(TxPackage {
+            transactions: order
+                .into_iter()
+                .map(|i| transactions[i].take().expect("index is placed once"))
+                .collect(),
+        })
+    }
+
+    /// The transactions, parents first and the child last
+    pub fn transactions(&self) -> &[BitGoPsbt] {
+        &self.transactions
+    }
+
+    pub fn txids(&self) -> Vec<Txid> {
+        self.transactions.iter().map(package_txid).collect()
+    }
+
+    /// Fee of each transaction, in package order
+    pub fn fees(&self) -> Result<Vec<u64>, TxPackageError> {
+        self.transactions
+            .iter()
+            .map(|psbt| {
+                let txid = package_txid(psbt);
+                let input_count = psbt.psbt().inputs.len();
+                let input_total = (0..input_count).try_fold(0u64, |sum, input_index| {
+                    input_value(psbt, input_index)
+                        .map(|value| sum.saturating_add(value))
+                        .ok_or(TxPackageError::MissingInputValue { txid, input_index })
+                })?;
+                let output_total = psbt
+                    .psbt()
+                    .unsigned_tx
+                    .output
+                    .iter()
+                    .fold(0u64, |sum, output| {
+                        sum.saturating_add(output.value.to_sat())
+                    });
+                input_total
+                    .checked_sub(output_total)
+                    .ok_or(TxPackageError::NegativeFee { txid })
+            })
+            .collect()
+    }
+
+    /// Compute the package fee rate from the vsize of each transaction, in package order
+    pub fn package_fee(&self, vsizes: &[u64]) -> Result<PackageFee, TxPackageError> {
+        if vsizes.len() != self.transactions.len() {
+            return Err(TxPackageError::VsizeCountMismatch {
+                expected: self.transactions.len(),
+                actual: vsizes.len(),
+            });
+        }
+        let fees = self.fees()?;
+        let total_fee = fees.iter().sum();
+        let total_vsize = vsizes.iter().sum();
+        Ok(PackageFee {
+            fees,
+            total_fee,
+            total_vsize,
+            fee_rate: total_fee as f64 / total_vsize.max(1) as f64,
+        })
+    }
+
+    /// Check that the package fee rate is at least `min_fee_rate` sat/vB
+    pub fn check_fee_rate(
+        &self,
+        vsizes: &[u64],
+        min_fee_rate: f64,
+    ) -> Result<PackageFee, TxPackageError> {
+        let fee = self.package_fee(vsizes)?;
+        if fee.fee_rate < min_fee_rate {
+            return Err(TxPackageError::FeeRateTooLow {
+                fee_rate: fee.fee_rate,
+                min_fee_rate,
+            });
+        }
+        Ok(fee)
+    }
+
+    /// Extract the finalized transactions in `submitpackage` order
+    pub fn extract_transactions(&self) -> Result<Vec<Vec<u8>>, TxPackageError> {
+        self.transactions
+            .iter()
+            .map(|psbt| psbt.clone().extract_tx().map_err(TxPackageError::Extract))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::WalletInputOptions;
+    use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+    use crate::fixed_script_wallet::{RootWalletKeys, ScriptId};
+    use crate::networks::Network;
+    use miniscript::bitcoin::hashes::Hash;
+    use miniscript::bitcoin::ScriptBuf;
+
+    fn create_tx(keys: &RootWalletKeys, prevout: (Txid, u32, u64), outputs: &[u64]) -> BitGoPsbt {
+        let mut psbt = BitGoPsbt::new(Network::Bitcoin, keys, None, None);
+        psbt.add_wallet_input(
+            prevout.0,
+            prevout.1,
+            prevout.2,
+            keys,
+            ScriptId {
+                chain: 20,
+                index: 0,
+            },
+            WalletInputOptions::default(),
+        )
+        .unwrap();
+        for (i, value) in outputs.iter().enumerate() {
+            psbt.add_output(ScriptBuf::new_op_return([i as u8]), *value)
+                .unwrap();
+        }
+        psbt
+    }
+
+    #[test]
+    fn test_package() {
+        let keys = get_test_wallet_keys("tx_package");
+        let parent = create_tx(&keys, (Txid::all_zeros(), 0, 100_000), &[99_900, 0]);
+        let parent_txid = parent.unsigned_txid();
+        let child = create_tx(&keys, (parent_txid, 0, 99_900), &[98_900]);
+        let child_txid = child.unsigned_txid();
+
+        // Order is fixed regardless of input order
+        let package = TxPackage::new(vec![child.clone(), parent.clone()]).unwrap();
+        assert_eq!(package.txids(), vec![parent_txid, child_txid]);
+        assert_eq!(package.fees().unwrap(), vec![100, 1_000]);
+
+        let fee = package.package_fee(&[110, 110]).unwrap();
+        assert_eq!(fee.total_fee, 1_100);
+        assert_eq!(fee.total_vsize, 220);
+        assert_eq!(fee.fee_rate, 5.0);
+        assert!(package.check_fee_rate(&[110, 110], 5.0).is_ok());
+        assert!(matches!(
+            package.check_fee_rate(&[110, 110], 5.1),
+            Err(TxPackageError::FeeRateTooLow { .. })
+        ));
+        assert!(matches!(
+            package.package_fee(&[110]),
+            Err(TxPackageError::VsizeCountMismatch { .. })
+        ));
+
+        // A second parent the child does not spend
+        let unrelated = create_tx(&keys, (Txid::all_zeros(), 1, 50_000), &[49_000]);
+        assert!(matches!(
+            TxPackage::new(vec![parent.clone(), unrelated, child.clone()]),
+            Err(TxPackageError::NotChildWithParents { .. })
+        ));
+        assert!(matches!(
+            TxPackage::new(vec![parent.clone(), parent.clone()]),
+            Err(TxPackageError::DuplicateTransaction { .. })
+        ));
+        assert!(matches!(TxPackage::new(vec![]), Err(TxPackageError::Empty)));
+    }
+}
@@ -0,0 +1,247 @@
+//! Consistency of `witness_utxo` and `non_witness_utxo`
+//!
+//! PSBTs created by older utxo-lib versions can carry only the previous transaction for some
+//! inputs and only the spent output for others. Code paths that read the spent output expect
+//! `witness_utxo`, so [`BitGoPsbt::normalize_utxos`] fills it in from `non_witness_utxo` after
+//! checking that the previous transaction is the one the input spends.
+
+use miniscript::bitcoin::{Amount, ScriptBuf, Txid};
+
+use super::BitGoPsbt;
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum UtxoNormalizeError {
+    /// The `non_witness_utxo` is not the transaction the input spends
+    TxidMismatch {
+        input_index: usize,
+        expected: Txid,
+        actual: Txid,
+    },
+    /// The `non_witness_utxo` has no output at the spent index
+    VoutOutOfRange {
+        input_index: usize,
+        vout: u32,
+        output_count: usize,
+    },
+    /// `witness_utxo` and `non_witness_utxo` disagree on the spent amount
+    ValueMismatch {
+        input_index: usize,
+        witness_utxo: Amount,
+        non_witness_utxo: Amount,
+    },
+    /// `witness_utxo` and `non_witness_utxo` disagree on the spent script
+    ScriptMismatch {
+        input_index: usize,
+        witness_utxo: ScriptBuf,
+        non_witness_utxo: ScriptBuf,
+    },
+}
+
+impl std::fmt::Display for UtxoNormalizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UtxoNormalizeError::TxidMismatch {
+                input_index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Input {}: non_witness_utxo txid {} does not match outpoint txid {}",
+                input_index, actual, expected
+            ),
+            UtxoNormalizeError::VoutOutOfRange {
+                input_index,
+                vout,
+                output_count,
+            } => write!(
+                f,
+                "Input {}: vout {} out of range for non_witness_utxo with {} outputs",
+                input_index, vout, output_count
+            ),
+            UtxoNormalizeError::ValueMismatch {
+                input_index,
+                witness_utxo,
+                non_witness_utxo,
+            } => write!(
+                f,
+                "Input {}: witness_utxo value {} does not match non_witness_utxo value {}",
+                input_index,
+                witness_utxo.to_sat(),
+                non_witness_utxo.to_sat()
+            ),
+            UtxoNormalizeError::ScriptMismatch {
+                input_index,
+                witness_utxo,
+                non_witness_utxo,
+            } => write!(
+                f,
+                "Input {}: witness_utxo script {} does not match non_witness_utxo script {}",
+                input_index,
+                witness_utxo.to_hex_string(),
+                non_witness_utxo.to_hex_string()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for UtxoNormalizeError {}
+
+crate::impl_wasm_error_code!(UtxoNormalizeError);
+crate::impl_from_coded_error!(UtxoNormalizeError);
+
+impl BitGoPsbt {
+    /// Populate `witness_utxo` from `non_witness_utxo` where it is missing
+    ///
+    /// Every input with a `non_witness_utxo` is checked first: the previous transaction must
+    /// hash to the outpoint txid and have an output at the spent index, and an existing
+    /// `witness_utxo` must match that output. The PSBT is only modified if all inputs pass.
+    ///
+    /// The txid check is skipped for Zcash, whose txid is not the hash of the serialization.
+    ///
+    /// # Returns
+    /// The indices of the inputs that got a `witness_utxo`
+    pub fn normalize_utxos(&mut self) -> Result<Vec<usize>, UtxoNormalizeError> {
+        let check_txid = !matches!(self, BitGoPsbt::Zcash(..));
+        let psbt = self.psbt();
+
+        let mut backfill = Vec::new();
+        for (input_index, (input, tx_in)) in psbt
+            .inputs
+            .iter()
+            .zip(psbt.unsigned_tx.input.iter())
+            .enumerate()
+        {
+            let Some(prev_tx) = &input.non_witness_utxo else {
+                continue;
+            };
+            let outpoint = tx_in.previous_output;
+            if check_txid {
+                let actual = prev_tx.compute_txid();
+                if actual != outpoint.txid {
+                    return Err(UtxoNormalizeError::TxidMismatch {
+                        input_index,
+                        expected: outpoint.txid,
+                        actual,
+                    });
+                }
+            }
+            let prev_out = prev_tx.output.get(outpoint.vout as usize).ok_or_else(|| {
+                UtxoNormalizeError::VoutOutOfRange {
+                    input_index,
+                    vout: outpoint.vout,
+                    output_count: prev_tx.output.len(),
+                }
+            })?;
+            match &input.witness_utxo {
+                Some(witness_utxo) if witness_utxo.value != prev_out.value => {
+                    return Err(UtxoNormalizeError::ValueMismatch {
+                        input_index,
+                        witness_utxo: witness_utxo.value,
+                        non_witness_utxo: prev_out.value,
+                    });
+                }
+                Some(witness_utxo) if witness_utxo.script_pubkey != prev_out.script_pubkey => {
+                    return Err(UtxoNormalizeError::ScriptMismatch {
+                        input_index,
+                        witness_utxo: witness_utxo.script_pubkey.clone(),
+                        non_witness_utxo: prev_out.script_pubkey.clone(),
+                    });
+                }
+                Some(_) => {}
+                None => backfill.push((input_index, prev_out.clone())),
+            }
+        }
+
+        let psbt = self.psbt_mut();
+        Ok(backfill
+            .into_iter()
+            .map(|(input_index, prev_out)| {
+                psbt.inputs[input_index].witness_utxo = Some(prev_out);
+                input_index
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+    use crate::Network;
+    use miniscript::bitcoin::absolute::LockTime;
+    use miniscript::bitcoin::transaction::Version;
+    use miniscript::bitcoin::{Transaction, TxIn, TxOut};
+
+    /// A PSBT with one p2sh input that only has a `non_witness_utxo`
+    fn create_psbt() -> (BitGoPsbt, Transaction) {
+        let wallet_keys = get_test_wallet_keys("utxo_normalize");
+        let mut psbt = WalletPsbtBuilder::new(Network::Bitcoin, &wallet_keys)
+            .input(0, 0)
+            .build();
+        let input = &mut psbt.psbt_mut().inputs[0];
+        let prev_tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn::default()],
+            output: vec![input.witness_utxo.take().unwrap()],
+        };
+        input.non_witness_utxo = Some(prev_tx.clone());
+        psbt.psbt_mut().unsigned_tx.input[0].previous_output.txid = prev_tx.compute_txid();
+        (psbt, prev_tx)
+    }
+
+    #[test]
+    fn test_normalize_utxos_backfills_witness_utxo() {
+        let (mut psbt, prev_tx) = create_psbt();
+        assert_eq!(psbt.normalize_utxos().unwrap(), vec![0]);
+        assert_eq!(
+            psbt.psbt().inputs[0].witness_utxo.as_ref(),
+            Some(&prev_tx.output[0])
+        );
+        // Idempotent
+        assert_eq!(psbt.normalize_utxos().unwrap(), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_normalize_utxos_rejects_inconsistencies() {
+        let (mut psbt, prev_tx) = create_psbt();
+        psbt.psbt_mut().inputs[0].witness_utxo = Some(TxOut {
+            value: Amount::from_sat(9_999),
+            script_pubkey: prev_tx.output[0].script_pubkey.clone(),
+        });
+        assert!(matches!(
+            psbt.normalize_utxos(),
+            Err(UtxoNormalizeError::ValueMismatch { input_index: 0, .. })
+        ));
+
+        psbt.psbt_mut().inputs[0].witness_utxo = Some(TxOut {
+            value: Amount::from_sat(10_000),
+            script_pubkey: ScriptBuf::new(),
+        });
+        assert!(matches!(
+            psbt.normalize_utxos(),
+            Err(UtxoNormalizeError::ScriptMismatch { input_index: 0, .. })
+        ));
+
+        psbt.psbt_mut().inputs[0].witness_utxo = None;
+        psbt.psbt_mut().unsigned_tx.input[0].previous_output.vout = 1;
+        assert!(matches!(
+            psbt.normalize_utxos(),
+            Err(UtxoNormalizeError::VoutOutOfRange {
+                input_index: 0,
+                vout: 1,
+                output_count: 1
+            })
+        ));
+
+        let mut other_tx = prev_tx.clone();
+        other_tx.lock_time = LockTime::from_consensus(1);
+        psbt.psbt_mut().inputs[0].non_witness_utxo = Some(other_tx);
+        assert!(matches!(
+            psbt.normalize_utxos(),
+            Err(UtxoNormalizeError::TxidMismatch { input_index: 0, .. })
+        ));
+        assert!(psbt.psbt().inputs[0].witness_utxo.is_none());
+    }
+}
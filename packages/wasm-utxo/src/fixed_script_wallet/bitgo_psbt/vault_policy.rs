@@ -0,0 +1,189 @@
+//! Vault spend policy carried in BitGo proprietary global fields
+//!
+//! The policy a spend was created under travels with the PSBT, so the cosigner can enforce it
+//! from the PSBT alone instead of looking it up:
+//!
+//! | Subtype                    | Value                                     |
+//! |----------------------------|-------------------------------------------|
+//! | `SpendLimit`               | maximum spend amount, u64 little-endian   |
+//! | `DestinationWhitelistHash` | 32-byte hash of the destination whitelist |
+//! | `ApprovalQuorumId`         | UTF-8 id of the approval quorum           |
+//!
+//! All fields use an empty key. The fields are not signed: the cosigner must only trust them
+//! when the PSBT comes from an authenticated source.
+
+use miniscript::bitcoin::psbt::Psbt;
+
+use super::propkv::{find_kv, BitGoKeyValue, ProprietaryKeySubtype};
+use super::BitGoPsbt;
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum VaultPolicyError {
+    /// A policy field has a value of the wrong length or encoding
+    InvalidValue {
+        field: &'static str,
+        message: String,
+    },
+}
+
+impl std::fmt::Display for VaultPolicyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VaultPolicyError::InvalidValue { field, message } => {
+                write!(f, "Invalid vault policy {}: {}", field, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VaultPolicyError {}
+
+crate::impl_wasm_error_code!(VaultPolicyError);
+
+/// Spend policy of a vault wallet
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VaultPolicy {
+    /// Maximum spend amount in satoshis
+    pub spend_limit: Option<u64>,
+    /// Hash of the list of allowed destinations
+    pub destination_whitelist_hash: Option<[u8; 32]>,
+    /// Id of the quorum that must approve the spend
+    pub approval_quorum_id: Option<String>,
+}
+
+impl VaultPolicy {
+    pub fn is_empty(&self) -> bool {
+        self == &VaultPolicy::default()
+    }
+}
+
+fn get_value(psbt: &Psbt, subtype: ProprietaryKeySubtype) -> Option<Vec<u8>> {
+    find_kv(subtype, &psbt.proprietary)
+        .next()
+        .map(|kv| kv.value)
+}
+
+fn set_value(psbt: &mut Psbt, subtype: ProprietaryKeySubtype, value: Option<Vec<u8>>) {
+    let (key, _) = BitGoKeyValue::new(subtype, vec![], vec![]).to_key_value();
+    match value {
+        Some(value) => psbt.proprietary.insert(key, value),
+        None => psbt.proprietary.remove(&key),
+    };
+}
+
+fn invalid_length(field: &'static str, expected: usize, actual: usize) -> VaultPolicyError {
+    VaultPolicyError::InvalidValue {
+        field,
+        message: format!("expected {} bytes, got {}", expected, actual),
+    }
+}
+
+impl BitGoPsbt {
+    /// Read the vault policy fields of the PSBT
+    ///
+    /// Returns a policy with all fields unset if the PSBT has none.
+    pub fn vault_policy(&self) -> Result<VaultPolicy, VaultPolicyError> {
+        let psbt = self.psbt();
+        let spend_limit = get_value(psbt, ProprietaryKeySubtype::SpendLimit)
+            .map(|value| {
+                <[u8; 8]>::try_from(value.as_slice())
+                    .map(u64::from_le_bytes)
+                    .map_err(|_| invalid_length("spend limit", 8, value.len()))
+            })
+            .transpose()?;
+        let destination_whitelist_hash =
+            get_value(psbt, ProprietaryKeySubtype::DestinationWhitelistHash)
+                .map(|value| {
+                    <[u8; 32]>::try_from(value.as_slice())
+                        .map_err(|_| invalid_length("destination whitelist hash", 32, value.len()))
+                })
+                .transpose()?;
+        let approval_quorum_id = get_value(psbt, ProprietaryKeySubtype::ApprovalQuorumId)
+            .map(|value| {
+                String::from_utf8(value).map_err(|e| VaultPolicyError::InvalidValue {
+                    field: "approval quorum id",
+                    message: e.to_string(),
+                })
+            })
+            .transpose()?;
+        Ok(VaultPolicy {
+            spend_limit,
+            destination_whitelist_hash,
+            approval_quorum_id,
+        })
+    }
+
+    /// Replace the vault policy fields of the PSBT
+    ///
+    /// Unset fields of `policy` are removed from the PSBT.
+    pub fn set_vault_policy(&mut self, policy: &VaultPolicy) {
+        let psbt = self.psbt_mut();
+        set_value(
+            psbt,
+            ProprietaryKeySubtype::SpendLimit,
+            policy.spend_limit.map(|limit| limit.to_le_bytes().to_vec()),
+        );
+        set_value(
+            psbt,
+            ProprietaryKeySubtype::DestinationWhitelistHash,
+            policy.destination_whitelist_hash.map(|hash| hash.to_vec()),
+        );
+        set_value(
+            psbt,
+            ProprietaryKeySubtype::ApprovalQuorumId,
+            policy
+                .approval_quorum_id
+                .as_ref()
+                .map(|id| id.as_bytes().to_vec()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+    use crate::Network;
+
+    #[test]
+    fn test_vault_policy_roundtrip() {
+        let keys = get_test_wallet_keys("vault_policy");
+        let mut psbt = BitGoPsbt::new(Network::Bitcoin, &keys, None, None);
+        assert!(psbt.vault_policy().unwrap().is_empty());
+
+        let policy = VaultPolicy {
+            spend_limit: Some(1_000_000),
+            destination_whitelist_hash: Some([7; 32]),
+            approval_quorum_id: Some("quorum-1".to_string()),
+        };
+        psbt.set_vault_policy(&policy);
+        let bytes = psbt.serialize().unwrap();
+        let mut psbt = BitGoPsbt::deserialize(&bytes, Network::Bitcoin).unwrap();
+        assert_eq!(psbt.vault_policy().unwrap(), policy);
+
+        let policy = VaultPolicy {
+            spend_limit: Some(5),
+            ..Default::default()
+        };
+        psbt.set_vault_policy(&policy);
+        assert_eq!(psbt.vault_policy().unwrap(), policy);
+    }
+
+    #[test]
+    fn test_vault_policy_invalid_value() {
+        let keys = get_test_wallet_keys("vault_policy");
+        let mut psbt = BitGoPsbt::new(Network::Bitcoin, &keys, None, None);
+        set_value(
+            psbt.psbt_mut(),
+            ProprietaryKeySubtype::SpendLimit,
+            Some(vec![1, 2, 3]),
+        );
+        assert!(matches!(
+            psbt.vault_policy(),
+            Err(VaultPolicyError::InvalidValue {
+                field: "spend limit",
+                ..
+            })
+        ));
+    }
+}
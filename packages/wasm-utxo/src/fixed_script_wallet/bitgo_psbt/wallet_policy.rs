@@ -0,0 +1,329 @@
+//! PSBT inputs and outputs of m-of-n [`WalletPolicy`] wallets
+//!
+//! 2-of-3 policies delegate to the [`RootWalletKeys`](crate::fixed_script_wallet::RootWalletKeys)
+//! methods, so their PSBTs are identical to those of standard wallets. Other policies populate
+//! the multisig script and BIP32 derivation of every policy key.
+
+use miniscript::bitcoin::absolute::LockTime;
+use miniscript::bitcoin::bip32::DerivationPath;
+use miniscript::bitcoin::psbt::{Input, Output, Psbt};
+use miniscript::bitcoin::transaction::Version;
+use miniscript::bitcoin::{Amount, OutPoint, ScriptBuf, Transaction, TxIn, TxOut, Txid, Witness};
+
+use super::psbt_wallet_input::get_output_script_and_value;
+use super::{get_default_sighash_type, BitGoPsbt, WalletInputOptions};
+use crate::fixed_script_wallet::wallet_scripts::{OutputScriptType, WalletScripts};
+use crate::fixed_script_wallet::{Chain, ScriptId, WalletPolicy};
+use crate::Network;
+
+impl BitGoPsbt {
+    /// Create an empty PSBT with the global xpubs of `policy`
+    ///
+    /// Like [`BitGoPsbt::new`], this does not support Zcash networks.
+    pub fn new_with_policy(
+        network: Network,
+        policy: &WalletPolicy,
+        version: Option<i32>,
+        lock_time: Option<u32>,
+    ) -> Self {
+        if let Some(wallet_keys) = policy.to_root_wallet_keys() {
+            return Self::new(network, &wallet_keys, version, lock_time);
+        }
+        if matches!(network, Network::Zcash | Network::ZcashTestnet) {
+            panic!("Zcash PSBTs are not supported for wallet policies");
+        }
+        let tx = Transaction {
+            version: Version(version.unwrap_or(2)),
+            lock_time: LockTime::from_consensus(lock_time.unwrap_or(0)),
+            input: vec![],
+            output: vec![],
+        };
+        let mut psbt = Psbt::from_unsigned_tx(tx).expect("empty transaction is valid");
+        psbt.xpub = policy
+            .xpubs()
+            .iter()
+            .map(|xpub| (*xpub, (xpub.fingerprint(), DerivationPath::master())))
+            .collect();
+        BitGoPsbt::BitcoinLike(psbt, network)
+    }
+
+    /// Add an input that spends a script of `policy`
+    ///
+    /// Inputs of 2-of-3 policies are added like [`BitGoPsbt::add_wallet_input`].
+    pub fn add_policy_input(
+        &mut self,
+        txid: Txid,
+        vout: u32,
+        value: u64,
+        policy: &WalletPolicy,
+        script_id: ScriptId,
+        options: WalletInputOptions,
+    ) -> Result<usize, String> {
+        if let Some(wallet_keys) = policy.to_root_wallet_keys() {
+            return self
+                .add_wallet_input(txid, vout, value, &wallet_keys, script_id, options)
+                .map_err(|e| e.to_string());
+        }
+
+        let network = self.network();
+        let chain = Chain::try_from(script_id.chain)?;
+        let scripts = policy
+            .wallet_scripts(
+                script_id.chain,
+                script_id.index,
+                &network.output_script_support(),
+            )
+            .map_err(|e| e.to_string())?;
+
+        let mut psbt_input = Input {
+            bip32_derivation: policy
+                .bip32_derivation(script_id.chain, script_id.index)
+                .map_err(|e| e.to_string())?,
+            sighash_type: Some(get_default_sighash_type(network, chain)),
+            ..Default::default()
+        };
+        match (chain.script_type, options.prev_tx) {
+            (OutputScriptType::P2sh, Some(tx_bytes)) => {
+                psbt_input.non_witness_utxo = Some(
+                    miniscript::bitcoin::consensus::deserialize(tx_bytes).map_err(|e| {
+                        format!("Failed to deserialize previous transaction: {}", e)
+                    })?,
+                );
+            }
+            _ => {
+                psbt_input.witness_utxo = Some(TxOut {
+                    value: Amount::from_sat(value),
+                    script_pubkey: scripts.output_script(),
+                });
+            }
+        }
+        set_multisig_scripts(
+            &scripts,
+            &mut psbt_input.redeem_script,
+            &mut psbt_input.witness_script,
+        );
+
+        let tx_in = TxIn {
+            previous_output: OutPoint { txid, vout },
+            script_sig: ScriptBuf::new(),
+            sequence: options.sequence.unwrap_or_default().to_sequence(),
+            witness: Witness::default(),
+        };
+        let index = self.psbt().inputs.len();
+        crate::psbt_ops::insert_input(self.psbt_mut(), index, tx_in, psbt_input)
+    }
+
+    /// Add a change output to a script of `policy`
+    ///
+    /// Outputs of 2-of-3 policies are added like [`BitGoPsbt::add_wallet_output`].
+    pub fn add_policy_output(
+        &mut self,
+        chain: u32,
+        index: u32,
+        value: u64,
+        policy: &WalletPolicy,
+    ) -> Result<usize, String> {
+        if let Some(wallet_keys) = policy.to_root_wallet_keys() {
+            return self.add_wallet_output(chain, index, value, &wallet_keys);
+        }
+
+        let scripts = policy
+            .wallet_scripts(chain, index, &self.network().output_script_support())
+            .map_err(|e| e.to_string())?;
+        let mut psbt_output = Output {
+            bip32_derivation: policy
+                .bip32_derivation(chain, index)
+                .map_err(|e| e.to_string())?,
+            ..Default::default()
+        };
+        set_multisig_scripts(
+            &scripts,
+            &mut psbt_output.redeem_script,
+            &mut psbt_output.witness_script,
+        );
+
+        let tx_out = TxOut {
+            value: Amount::from_sat(value),
+            script_pubkey: scripts.output_script(),
+        };
+        let output_index = self.psbt().outputs.len();
+        crate::psbt_ops::insert_output(self.psbt_mut(), output_index, tx_out, psbt_output)
+    }
+
+    /// Identify the inputs that spend scripts of `policy`
+    ///
+    /// Returns the script id of each input, or `None` for inputs of other wallets.
+    /// Only inputs with BIP32 derivations (non-taproot script types) are recognized.
+    pub fn policy_input_script_ids(&self, policy: &WalletPolicy) -> Vec<Option<ScriptId>> {
+        let psbt = self.psbt();
+        let script_support = self.network().output_script_support();
+        psbt.inputs
+            .iter()
+            .zip(&psbt.unsigned_tx.input)
+            .map(|(input, tx_in)| {
+                let (script, _) = get_output_script_and_value(input, tx_in.previous_output).ok()?;
+                policy.find_script_id(&input.bip32_derivation, script, &script_support)
+            })
+            .collect()
+    }
+
+    /// Identify the outputs that pay to scripts of `policy`
+    pub fn policy_output_script_ids(&self, policy: &WalletPolicy) -> Vec<Option<ScriptId>> {
+        let psbt = self.psbt();
+        let script_support = self.network().output_script_support();
+        psbt.outputs
+            .iter()
+            .zip(&psbt.unsigned_tx.output)
+            .map(|(output, tx_out)| {
+                policy.find_script_id(
+                    &output.bip32_derivation,
+                    &tx_out.script_pubkey,
+                    &script_support,
+                )
+            })
+            .collect()
+    }
+}
+
+fn set_multisig_scripts(
+    scripts: &WalletScripts,
+    redeem_script: &mut Option<ScriptBuf>,
+    witness_script: &mut Option<ScriptBuf>,
+) {
+    match scripts {
+        WalletScripts::P2sh(script) => {
+            *redeem_script = Some(script.redeem_script.clone());
+        }
+        WalletScripts::P2shP2wsh(script) => {
+            *redeem_script = Some(script.redeem_script.clone());
+            *witness_script = Some(script.witness_script.clone());
+        }
+        WalletScripts::P2wsh(script) => {
+            *witness_script = Some(script.witness_script.clone());
+        }
+        // Taproot and p2mr scripts are only built for 2-of-3 policies, which are added
+        // through the RootWalletKeys methods
+        WalletScripts::P2trLegacy(_) | WalletScripts::P2trMusig2(_) | WalletScripts::P2mr(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+    use crate::fixed_script_wallet::wallet_policy::tests::get_test_policy;
+    use crate::fixed_script_wallet::wallet_scripts::parse_multisig_script;
+    use miniscript::bitcoin::hashes::Hash;
+    use miniscript::bitcoin::PubkeyHash;
+
+    #[test]
+    fn test_3_of_5_policy_psbt() {
+        let policy = get_test_policy(3, 5);
+        let mut psbt = BitGoPsbt::new_with_policy(Network::Bitcoin, &policy, None, None);
+        for (vout, chain) in [(0, 0), (1, 10), (2, 20)] {
+            psbt.add_policy_input(
+                Txid::all_zeros(),
+                vout,
+                50_000,
+                &policy,
+                ScriptId { chain, index: 9 },
+                WalletInputOptions::default(),
+            )
+            .unwrap();
+        }
+        psbt.add_policy_output(21, 2, 40_000, &policy).unwrap();
+        psbt.add_output(ScriptBuf::new_p2pkh(&PubkeyHash::all_zeros()), 1_000)
+            .unwrap();
+
+        let inner = psbt.psbt();
+        assert_eq!(inner.xpub.len(), 5);
+        assert_eq!(inner.inputs[2].bip32_derivation.len(), 5);
+        let witness_script = inner.inputs[2].witness_script.as_ref().unwrap();
+        assert_eq!(parse_multisig_script(witness_script).unwrap().0, 3);
+        assert!(inner.inputs[0].redeem_script.is_some());
+        assert!(inner.inputs[0].witness_script.is_none());
+
+        assert_eq!(
+            psbt.policy_input_script_ids(&policy),
+            [0, 10, 20]
+                .map(|chain| Some(ScriptId { chain, index: 9 }))
+                .to_vec()
+        );
+        assert_eq!(
+            psbt.policy_output_script_ids(&policy),
+            vec![
+                Some(ScriptId {
+                    chain: 21,
+                    index: 2
+                }),
+                None
+            ]
+        );
+
+        // Scripts of the policy are not recognized by another policy
+        let other = get_test_policy(2, 5);
+        assert_eq!(psbt.policy_input_script_ids(&other), vec![None; 3]);
+
+        // The PSBT survives a serialization round trip
+        let bytes = psbt.serialize().unwrap();
+        let decoded = BitGoPsbt::deserialize(&bytes, Network::Bitcoin).unwrap();
+        assert_eq!(
+            decoded.policy_output_script_ids(&policy)[0],
+            Some(ScriptId {
+                chain: 21,
+                index: 2
+            })
+        );
+
+        assert!(psbt
+            .add_policy_output(40, 0, 1_000, &policy)
+            .unwrap_err()
+            .contains("only supported for 2-of-3"));
+    }
+
+    #[test]
+    fn test_2_of_3_policy_psbt_matches_wallet_keys() {
+        let wallet_keys = get_test_wallet_keys("policy_psbt");
+        let policy = WalletPolicy::from(&wallet_keys);
+        let mut from_policy = BitGoPsbt::new_with_policy(Network::Bitcoin, &policy, None, None);
+        let mut from_keys = from_policy.clone();
+
+        let script_id = ScriptId {
+            chain: 20,
+            index: 1,
+        };
+        from_policy
+            .add_policy_input(
+                Txid::all_zeros(),
+                0,
+                10_000,
+                &policy,
+                script_id,
+                WalletInputOptions::default(),
+            )
+            .unwrap();
+        from_policy.add_policy_output(1, 2, 5_000, &policy).unwrap();
+        from_keys
+            .add_wallet_input(
+                Txid::all_zeros(),
+                0,
+                10_000,
+                &wallet_keys,
+                script_id,
+                WalletInputOptions::default(),
+            )
+            .unwrap();
+        from_keys
+            .add_wallet_output(1, 2, 5_000, &wallet_keys)
+            .unwrap();
+
+        assert_eq!(
+            from_policy.serialize().unwrap(),
+            from_keys.serialize().unwrap()
+        );
+        assert_eq!(
+            from_policy.policy_input_script_ids(&policy),
+            vec![Some(script_id)]
+        );
+    }
+}
@@ -12,6 +12,24 @@ pub use crate::zcash::transaction::{
     decode_zcash_transaction_meta, ZcashTransactionMeta, ZCASH_SAPLING_VERSION_GROUP_ID,
 };
 
+/// Reject transactions with Sapling spends or outputs or Sprout JoinSplits
+fn check_transparent(
+    parts: &crate::zcash::transaction::ZcashTransactionParts,
+) -> Result<(), super::DeserializeError> {
+    let counts = parts
+        .shielded_component_counts()
+        .map_err(super::DeserializeError::Network)?;
+    if counts.is_empty() {
+        Ok(())
+    } else {
+        Err(super::DeserializeError::UnsupportedShieldedComponents {
+            spends: counts.spends,
+            outputs: counts.outputs,
+            joinsplits: counts.joinsplits,
+        })
+    }
+}
+
 /// A Zcash-compatible PSBT that can handle overwintered transactions
 ///
 /// This struct handles Zcash-specific transaction formats including
@@ -299,6 +317,7 @@ impl ZcashBitGoPsbt {
                 found_tx = true;
                 let parts = crate::zcash::transaction::decode_zcash_transaction_parts(&val_data)
                     .map_err(super::DeserializeError::Network)?;
+                check_transparent(&parts)?;
                 version_group_id = parts.version_group_id;
                 expiry_height = parts.expiry_height;
                 sapling_fields = parts.sapling_fields;
@@ -519,6 +538,7 @@ impl ZcashBitGoPsbt {
         unspents: &[super::HydrationUnspentInput],
         consensus_branch_id: u32,
     ) -> Result<Self, String> {
+        check_transparent(parts).map_err(|e| e.to_string())?;
         let tx = &parts.transaction;
         let inputs = super::FixedScriptInput::parse_all(tx)?;
         let mut z = Self::from_tx_parts(
@@ -652,4 +672,40 @@ mod tests {
             "PSBT output count should match"
         );
     }
+
+    #[test]
+    fn test_deserialize_rejects_shielded_components() {
+        use crate::fixed_script_wallet::test_utils::fixtures::{
+            load_psbt_fixture_with_format_and_namespace, FixtureNamespace, SignatureState, TxFormat,
+        };
+        use crate::networks::Network;
+
+        let fixture = load_psbt_fixture_with_format_and_namespace(
+            "zcash",
+            SignatureState::Unsigned,
+            TxFormat::Psbt,
+            FixtureNamespace::UtxolibCompat,
+        )
+        .expect("Failed to load Zcash fixture");
+        let bytes = BASE64_STANDARD.decode(&fixture.psbt_base64).unwrap();
+        let mut zcash_psbt = ZcashBitGoPsbt::deserialize(&bytes, Network::Zcash).unwrap();
+
+        // valueBalance, no spends, one output description, no joinsplits
+        let mut sapling_fields = vec![0u8; 8];
+        sapling_fields.push(0);
+        sapling_fields.push(1);
+        sapling_fields.extend_from_slice(&[0u8; 948]);
+        sapling_fields.push(0);
+        zcash_psbt.sapling_fields = sapling_fields;
+        let bytes = zcash_psbt.serialize().unwrap();
+
+        assert!(matches!(
+            ZcashBitGoPsbt::deserialize(&bytes, Network::Zcash),
+            Err(crate::fixed_script_wallet::bitgo_psbt::DeserializeError::UnsupportedShieldedComponents {
+                spends: 0,
+                outputs: 1,
+                joinsplits: 0
+            })
+        ));
+    }
 }
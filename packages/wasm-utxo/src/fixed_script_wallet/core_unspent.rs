@@ -0,0 +1,263 @@
+//! Import of unspents exported by Bitcoin Core
+//!
+//! `listunspent` (and the unspent dumps of descriptor wallets) report each output with its
+//! script and an inferred descriptor such as
+//! `wsh(multi(2,[d34db33f/0/0/20/5]02...,[...]03...,[...]02...))#checksum`. The key origins
+//! of a fixed-script wallet end in `chain/index`, which identifies the wallet script.
+//!
+//! [`CoreUnspent::to_wallet_unspent`] recovers the script id and checks it against the wallet
+//! keys, so the result can be passed to `add_wallet_input` as is. Bitcoin Core reports amounts
+//! in BTC; callers convert them to satoshis before the import.
+
+use std::str::FromStr;
+
+use crate::bitcoin::bip32::DerivationPath;
+use crate::bitcoin::{ScriptBuf, Txid};
+use crate::fixed_script_wallet::descriptor::descriptor_checksum;
+use crate::fixed_script_wallet::wallet_keys::RootWalletKeys;
+use crate::fixed_script_wallet::wallet_scripts::{
+    chain_index_path, path_chain_index, OutputScriptType, WalletScripts,
+};
+use crate::fixed_script_wallet::{Chain, ScriptId};
+use crate::Network;
+
+/// An unspent output as reported by Bitcoin Core
+#[derive(Debug, Clone)]
+pub struct CoreUnspent {
+    pub txid: Txid,
+    pub vout: u32,
+    pub script_pubkey: ScriptBuf,
+    /// Value in satoshis
+    pub value: u64,
+    /// Inferred descriptor (`desc`), with or without checksum
+    pub descriptor: Option<String>,
+}
+
+/// A Bitcoin Core unspent matched to a wallet script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletUnspent {
+    pub txid: Txid,
+    pub vout: u32,
+    pub value: u64,
+    pub script_id: ScriptId,
+    pub script_type: OutputScriptType,
+}
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum CoreUnspentError {
+    /// The unspent has no `desc` field
+    MissingDescriptor,
+    /// The descriptor checksum does not match the descriptor
+    InvalidChecksum { expected: String, actual: String },
+    /// The descriptor has no key origins that end in `chain/index`
+    NoKeyOrigin,
+    /// The key origins disagree on the chain or index
+    AmbiguousKeyOrigin,
+    /// The wallet script at the recovered chain and index does not match the unspent
+    ScriptMismatch { chain: u32, index: u32 },
+}
+
+impl std::fmt::Display for CoreUnspentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CoreUnspentError::MissingDescriptor => write!(f, "Unspent has no descriptor"),
+            CoreUnspentError::InvalidChecksum { expected, actual } => write!(
+                f,
+                "Invalid descriptor checksum {}, expected {}",
+                actual, expected
+            ),
+            CoreUnspentError::NoKeyOrigin => {
+                write!(f, "Descriptor has no key origin with a chain and index")
+            }
+            CoreUnspentError::AmbiguousKeyOrigin => {
+                write!(f, "Descriptor key origins disagree on the chain and index")
+            }
+            CoreUnspentError::ScriptMismatch { chain, index } => write!(
+                f,
+                "Wallet script at chain {} index {} does not match the unspent",
+                chain, index
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CoreUnspentError {}
+
+crate::impl_wasm_error_code!(CoreUnspentError);
+
+/// Strip and verify the `#checksum` suffix of a descriptor
+fn strip_checksum(descriptor: &str) -> Result<&str, CoreUnspentError> {
+    let Some((body, actual)) = descriptor.rsplit_once('#') else {
+        return Ok(descriptor);
+    };
+    match descriptor_checksum(body) {
+        Some(expected) if expected == actual => Ok(body),
+        expected => Err(CoreUnspentError::InvalidChecksum {
+            expected: expected.unwrap_or_default(),
+            actual: actual.to_string(),
+        }),
+    }
+}
+
+/// Paths of the `[fingerprint/path]` key origins in a descriptor
+fn key_origin_paths(descriptor: &str) -> Vec<DerivationPath> {
+    descriptor
+        .split('[')
+        .skip(1)
+        .filter_map(|s| s.split_once(']'))
+        .filter_map(|(origin, _)| origin.split_once('/'))
+        .filter_map(|(_fingerprint, path)| DerivationPath::from_str(&format!("m/{}", path)).ok())
+        .collect()
+}
+
+/// Recover the `(chain, index)` shared by all key origins of a descriptor
+pub fn descriptor_chain_index(descriptor: &str) -> Result<(u32, u32), CoreUnspentError> {
+    let body = strip_checksum(descriptor)?;
+    let mut chain_indexes = key_origin_paths(body)
+        .iter()
+        .filter_map(path_chain_index)
+        .collect::<Vec<_>>();
+    chain_indexes.dedup();
+    match chain_indexes.as_slice() {
+        [] => Err(CoreUnspentError::NoKeyOrigin),
+        [chain_index] => Ok(*chain_index),
+        _ => Err(CoreUnspentError::AmbiguousKeyOrigin),
+    }
+}
+
+impl CoreUnspent {
+    /// Match the unspent to the wallet script identified by its descriptor
+    pub fn to_wallet_unspent(
+        &self,
+        wallet_keys: &RootWalletKeys,
+        network: Network,
+    ) -> Result<WalletUnspent, CoreUnspentError> {
+        let descriptor = self
+            .descriptor
+            .as_deref()
+            .ok_or(CoreUnspentError::MissingDescriptor)?;
+        let (chain, index) = descriptor_chain_index(descriptor)?;
+        let mismatch = || CoreUnspentError::ScriptMismatch { chain, index };
+        let script_type = Chain::try_from(chain).map_err(|_| mismatch())?.script_type;
+        let scripts = WalletScripts::from_wallet_keys(
+            wallet_keys,
+            script_type,
+            &chain_index_path(chain, index),
+            &network.output_script_support(),
+        )
+        .map_err(|_| mismatch())?;
+        if scripts.output_script() != self.script_pubkey {
+            return Err(mismatch());
+        }
+        Ok(WalletUnspent {
+            txid: self.txid,
+            vout: self.vout,
+            value: self.value,
+            script_id: ScriptId { chain, index },
+            script_type,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+    use crate::fixed_script_wallet::wallet_keys::to_pub_triple;
+    use miniscript::bitcoin::hashes::Hash;
+
+    /// Descriptor in the form that `listunspent` infers for a wallet script
+    fn inferred_descriptor(keys: &RootWalletKeys, chain: u32, index: u32) -> String {
+        let derived = to_pub_triple(&keys.derive_path(&chain_index_path(chain, index)).unwrap());
+        let [user, backup, bitgo] = std::array::from_fn(|i| {
+            format!(
+                "[{}/0/0/{}/{}]{}",
+                keys.xpubs[i].fingerprint(),
+                chain,
+                index,
+                derived[i]
+            )
+        });
+        let body = format!("wsh(multi(2,{},{},{}))", user, backup, bitgo);
+        let checksum = descriptor_checksum(&body).unwrap();
+        format!("{}#{}", body, checksum)
+    }
+
+    fn unspent(keys: &RootWalletKeys, descriptor: Option<String>) -> CoreUnspent {
+        let script_pubkey = WalletScripts::from_wallet_keys(
+            keys,
+            OutputScriptType::P2wsh,
+            &chain_index_path(20, 5),
+            &Network::Bitcoin.output_script_support(),
+        )
+        .unwrap()
+        .output_script();
+        CoreUnspent {
+            txid: Txid::all_zeros(),
+            vout: 1,
+            script_pubkey,
+            value: 100_000,
+            descriptor,
+        }
+    }
+
+    #[test]
+    fn test_to_wallet_unspent() {
+        let keys = get_test_wallet_keys("core_unspent");
+        let descriptor = inferred_descriptor(&keys, 20, 5);
+        assert_eq!(descriptor_chain_index(&descriptor).unwrap(), (20, 5));
+
+        let imported = unspent(&keys, Some(descriptor.clone()))
+            .to_wallet_unspent(&keys, Network::Bitcoin)
+            .unwrap();
+        assert_eq!(
+            imported.script_id,
+            ScriptId {
+                chain: 20,
+                index: 5
+            }
+        );
+        assert_eq!(imported.script_type, OutputScriptType::P2wsh);
+        assert_eq!(imported.value, 100_000);
+
+        // The checksum is optional, but must match when present
+        let (body, _) = descriptor.rsplit_once('#').unwrap();
+        assert!(unspent(&keys, Some(body.to_string()))
+            .to_wallet_unspent(&keys, Network::Bitcoin)
+            .is_ok());
+        assert!(matches!(
+            descriptor_chain_index(&format!("{}#qqqqqqqq", body)),
+            Err(CoreUnspentError::InvalidChecksum { .. })
+        ));
+
+        assert!(matches!(
+            unspent(&keys, None).to_wallet_unspent(&keys, Network::Bitcoin),
+            Err(CoreUnspentError::MissingDescriptor)
+        ));
+        assert!(matches!(
+            unspent(&keys, Some(inferred_descriptor(&keys, 20, 6)))
+                .to_wallet_unspent(&keys, Network::Bitcoin),
+            Err(CoreUnspentError::ScriptMismatch {
+                chain: 20,
+                index: 6
+            })
+        ));
+        let other_keys = get_test_wallet_keys("other");
+        assert!(matches!(
+            unspent(&keys, Some(descriptor)).to_wallet_unspent(&other_keys, Network::Bitcoin),
+            Err(CoreUnspentError::ScriptMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_descriptor_chain_index_errors() {
+        assert!(matches!(
+            descriptor_chain_index("addr(mkmZxiEcEd8ZqjQWVZuC6so5dFMKEFpN2j)"),
+            Err(CoreUnspentError::NoKeyOrigin)
+        ));
+        assert!(matches!(
+            descriptor_chain_index("sh(multi(2,[00000000/0/0/0/1]02aa,[00000000/0/0/0/2]02bb))"),
+            Err(CoreUnspentError::AmbiguousKeyOrigin)
+        ));
+    }
+}
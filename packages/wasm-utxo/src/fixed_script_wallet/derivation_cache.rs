@@ -0,0 +1,267 @@
+//! Memoization of wallet child key derivations.
+//!
+//! Parsing, signing and verifying a PSBT derive the same `(chain, index)` keys for the
+//! same wallet many times (once per input/output and per operation). Since callers
+//! typically construct fresh `RootWalletKeys` for every call, the cache is shared per
+//! thread and keyed by the prefix-derived xpubs rather than stored on the keys object.
+//!
+//! The cache is a bounded LRU: entries are also indexed by the tick of their last use, so
+//! finding the least recently used entry does not scan the cache. It can be disabled (and
+//! cleared) for memory-constrained environments via [`set_enabled`].
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+
+use super::XpubTriple;
+
+/// Default maximum number of cached derivations
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+type CacheKey = (XpubTriple, u32, u32);
+
+/// Snapshot of cache counters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DerivationCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub size: usize,
+    pub capacity: usize,
+    pub enabled: bool,
+}
+
+struct DerivationCache {
+    /// Cached derivation with the tick of its last use
+    entries: HashMap<CacheKey, (XpubTriple, u64)>,
+    /// Keys of `entries` ordered by the tick of their last use
+    recency: BTreeMap<u64, CacheKey>,
+    tick: u64,
+    capacity: usize,
+    enabled: bool,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl DerivationCache {
+    fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+            capacity: DEFAULT_CAPACITY,
+            enabled: true,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        }
+    }
+
+    fn get(&mut self, key: &CacheKey) -> Option<XpubTriple> {
+        if !self.enabled {
+            return None;
+        }
+        self.tick += 1;
+        match self.entries.get_mut(key) {
+            Some((value, last_used)) => {
+                self.recency.remove(last_used);
+                self.recency.insert(self.tick, *key);
+                *last_used = self.tick;
+                self.hits += 1;
+                Some(*value)
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn insert(&mut self, key: CacheKey, value: XpubTriple) {
+        if !self.enabled || self.capacity == 0 {
+            return;
+        }
+        while !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            self.evict_least_recently_used();
+        }
+        self.tick += 1;
+        if let Some((_, last_used)) = self.entries.insert(key, (value, self.tick)) {
+            self.recency.remove(&last_used);
+        }
+        self.recency.insert(self.tick, key);
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        if let Some((_, key)) = self.recency.pop_first() {
+            self.entries.remove(&key);
+            self.evictions += 1;
+        }
+    }
+
+    fn clear_entries(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+
+    fn stats(&self) -> DerivationCacheStats {
+        DerivationCacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            evictions: self.evictions,
+            size: self.entries.len(),
+            capacity: self.capacity,
+            enabled: self.enabled,
+        }
+    }
+}
+
+thread_local! {
+    static CACHE: RefCell<DerivationCache> = RefCell::new(DerivationCache::new());
+}
+
+/// Return the cached derivation of `xpubs` at `(chain, index)`, or compute and cache it.
+pub(crate) fn get_or_derive<E>(
+    xpubs: &XpubTriple,
+    chain: u32,
+    index: u32,
+    derive: impl FnOnce() -> Result<XpubTriple, E>,
+) -> Result<XpubTriple, E> {
+    let key = (*xpubs, chain, index);
+    if let Some(cached) = CACHE.with(|c| c.borrow_mut().get(&key)) {
+        return Ok(cached);
+    }
+    let derived = derive()?;
+    CACHE.with(|c| c.borrow_mut().insert(key, derived));
+    Ok(derived)
+}
+
+/// Get the cache counters for the current thread
+pub fn stats() -> DerivationCacheStats {
+    CACHE.with(|c| c.borrow().stats())
+}
+
+/// Enable or disable the cache. Disabling also drops all cached entries.
+pub fn set_enabled(enabled: bool) {
+    CACHE.with(|c| {
+        let mut cache = c.borrow_mut();
+        cache.enabled = enabled;
+        if !enabled {
+            cache.clear_entries();
+        }
+    });
+}
+
+/// Set the maximum number of cached derivations, evicting entries if needed
+pub fn set_capacity(capacity: usize) {
+    CACHE.with(|c| {
+        let mut cache = c.borrow_mut();
+        cache.capacity = capacity;
+        while cache.entries.len() > capacity {
+            cache.evict_least_recently_used();
+        }
+    });
+}
+
+/// Drop all cached entries and reset the counters
+pub fn clear() {
+    CACHE.with(|c| {
+        let mut cache = c.borrow_mut();
+        cache.clear_entries();
+        cache.hits = 0;
+        cache.misses = 0;
+        cache.evictions = 0;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+    use crate::fixed_script_wallet::wallet_scripts::chain_index_path;
+
+    // Tests run on separate threads, so each test sees its own cache.
+
+    #[test]
+    fn test_hits_and_misses() {
+        clear();
+        let keys = get_test_wallet_keys("derivation_cache");
+        let path = chain_index_path(0, 7);
+
+        let first = keys.derive_path(&path).unwrap();
+        let second = keys.derive_path(&path).unwrap();
+        assert_eq!(first, second);
+
+        let stats = stats();
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.size, 1);
+    }
+
+    #[test]
+    fn test_shared_across_instances() {
+        clear();
+        let path = chain_index_path(20, 3);
+        get_test_wallet_keys("derivation_cache")
+            .derive_path(&path)
+            .unwrap();
+        get_test_wallet_keys("derivation_cache")
+            .derive_path(&path)
+            .unwrap();
+        assert_eq!(stats().hits, 1);
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        clear();
+        set_capacity(2);
+        let keys = get_test_wallet_keys("derivation_cache");
+        keys.derive_path(&chain_index_path(0, 0)).unwrap();
+        keys.derive_path(&chain_index_path(0, 1)).unwrap();
+        // Touch (0, 0) so that (0, 1) is least recently used
+        keys.derive_path(&chain_index_path(0, 0)).unwrap();
+        keys.derive_path(&chain_index_path(0, 2)).unwrap();
+
+        let stats = stats();
+        assert_eq!(stats.evictions, 1);
+        assert_eq!(stats.size, 2);
+
+        keys.derive_path(&chain_index_path(0, 0)).unwrap();
+        assert_eq!(super::stats().hits, stats.hits + 1);
+        set_capacity(DEFAULT_CAPACITY);
+    }
+
+    #[test]
+    fn test_recency_index_matches_entries() {
+        let xpubs = get_test_wallet_keys("derivation_cache").xpubs;
+        let mut cache = DerivationCache::new();
+        cache.capacity = 3;
+        for index in 0..10 {
+            cache.insert((xpubs, 0, index), xpubs);
+            // Re-inserting an entry moves it instead of leaving a stale tick behind
+            cache.insert((xpubs, 0, index), xpubs);
+            cache.get(&(xpubs, 0, 0));
+        }
+        assert_eq!(cache.entries.len(), 3);
+        assert_eq!(cache.recency.len(), 3);
+        assert!(cache.get(&(xpubs, 0, 0)).is_some());
+        assert!(cache.get(&(xpubs, 0, 9)).is_some());
+        assert!(cache.get(&(xpubs, 0, 8)).is_some());
+    }
+
+    #[test]
+    fn test_disabled() {
+        clear();
+        set_enabled(false);
+        let keys = get_test_wallet_keys("derivation_cache");
+        let path = chain_index_path(1, 1);
+        let a = keys.derive_path(&path).unwrap();
+        let b = keys.derive_path(&path).unwrap();
+        assert_eq!(a, b);
+
+        let stats = stats();
+        assert!(!stats.enabled);
+        assert_eq!(stats.size, 0);
+        assert_eq!(stats.hits, 0);
+        set_enabled(true);
+    }
+}
@@ -0,0 +1,251 @@
+//! Output descriptors for fixed-script wallet chains
+//!
+//! Describes the scripts of a wallet chain as a ranged output descriptor, so that the
+//! wallet can be registered with descriptor-based software (Bitcoin Core watch-only
+//! wallets, hardware wallet policies). Each key is written as `[fingerprint]xpub/prefix/chain/*`,
+//! which matches the key origins that BitGo PSBTs record in their derivation maps.
+//!
+//! Reference: <https://github.com/bitcoin/bips/blob/master/bip-0380.mediawiki>
+
+use crate::bitcoin::bip32::{DerivationPath, Xpub};
+use crate::error::WasmUtxoError;
+use crate::fixed_script_wallet::wallet_keys::RootWalletKeys;
+use crate::fixed_script_wallet::wallet_scripts::OutputScriptType;
+use crate::fixed_script_wallet::Chain;
+
+/// Ranged key expression for a wallet xpub on `chain`
+pub(crate) fn key_expression(xpub: &Xpub, prefix: &DerivationPath, chain: u32) -> String {
+    let path: String = prefix.into_iter().map(|c| format!("/{}", c)).collect();
+    format!("[{}]{}{}/{}/*", xpub.fingerprint(), xpub, path, chain)
+}
+
+/// Leaf script `<a> OP_CHECKSIGVERIFY <b> OP_CHECKSIG`
+fn tap_leaf(a: &str, b: &str) -> String {
+    format!("and_v(v:pk({}),pk({}))", a, b)
+}
+
+/// Format the descriptor for `script_type` from the user, backup and bitgo key expressions.
+///
+/// `internal_key` is only used by taproot script types.
+fn format_descriptor(
+    script_type: OutputScriptType,
+    keys: &[String; 3],
+    internal_key: &str,
+) -> Result<String, WasmUtxoError> {
+    let [user, backup, bitgo] = keys;
+    let multi = format!("multi(2,{},{},{})", user, backup, bitgo);
+    match script_type {
+        OutputScriptType::P2sh => Ok(format!("sh({})", multi)),
+        OutputScriptType::P2shP2wsh => Ok(format!("sh(wsh({}))", multi)),
+        OutputScriptType::P2wsh => Ok(format!("wsh({})", multi)),
+        OutputScriptType::P2trMusig2 => Ok(format!(
+            "tr({},{{{},{}}})",
+            internal_key,
+            tap_leaf(user, backup),
+            tap_leaf(backup, bitgo)
+        )),
+        // The legacy internal key uses a pre-BIP327 aggregation that `musig()` cannot express
+        OutputScriptType::P2trLegacy | OutputScriptType::P2mr => Err(WasmUtxoError::new(&format!(
+            "No output descriptor for script type {}",
+            script_type
+        ))),
+    }
+}
+
+/// Get the ranged output descriptor for the scripts of `chain`
+///
+/// - p2sh: `sh(multi(2,user,backup,bitgo))`
+/// - p2shP2wsh: `sh(wsh(multi(2,user,backup,bitgo)))`
+/// - p2wsh: `wsh(multi(2,user,backup,bitgo))`
+/// - p2trMusig2: `tr(musig(user,bitgo),{and_v(v:pk(user),pk(backup)),and_v(v:pk(backup),pk(bitgo))})`
+///
+/// The descriptor has no checksum, see [`descriptor_checksum`]. p2trLegacy and p2mr chains
+/// cannot be described and return an error.
+pub fn descriptor_for_chain(keys: &RootWalletKeys, chain: u32) -> Result<String, WasmUtxoError> {
+    let chain =
+        Chain::try_from(chain).map_err(|e| WasmUtxoError::new(&format!("Invalid chain: {}", e)))?;
+    let expressions: [String; 3] = std::array::from_fn(|i| {
+        key_expression(&keys.xpubs[i], &keys.derivation_prefixes[i], chain.value())
+    });
+    let internal_key = format!("musig({},{})", expressions[0], expressions[2]);
+    format_descriptor(chain.script_type, &expressions, &internal_key)
+}
+
+/// Characters allowed in descriptors, in checksum symbol order
+const CHECKSUM_INPUT_CHARSET: &str =
+    "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+const CHECKSUM_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+fn checksum_polymod(c: u64, value: u64) -> u64 {
+    const GENERATORS: [u64; 5] = [
+        0xf5dee51989,
+        0xa9fdca3312,
+        0x1bab10e32d,
+        0x3706b1677a,
+        0x644d626ffd,
+    ];
+    let c0 = c >> 35;
+    let mut c = ((c & 0x7ffffffff) << 5) ^ value;
+    for (i, generator) in GENERATORS.iter().enumerate() {
+        if (c0 >> i) & 1 == 1 {
+            c ^= generator;
+        }
+    }
+    c
+}
+
+/// Compute the BIP-380 checksum of a descriptor (without the `#checksum` suffix)
+///
+/// Returns `None` if the descriptor contains characters outside the descriptor charset.
+pub fn descriptor_checksum(descriptor: &str) -> Option<String> {
+    let mut c = 1u64;
+    let mut class = 0u64;
+    let mut class_count = 0;
+    for ch in descriptor.chars() {
+        let pos = CHECKSUM_INPUT_CHARSET.find(ch)? as u64;
+        c = checksum_polymod(c, pos & 31);
+        class = class * 3 + (pos >> 5);
+        class_count += 1;
+        if class_count == 3 {
+            c = checksum_polymod(c, class);
+            class = 0;
+            class_count = 0;
+        }
+    }
+    if class_count > 0 {
+        c = checksum_polymod(c, class);
+    }
+    for _ in 0..8 {
+        c = checksum_polymod(c, 0);
+    }
+    c ^= 1;
+    Some(
+        (0..8)
+            .map(|j| CHECKSUM_CHARSET[((c >> (5 * (7 - j))) & 31) as usize] as char)
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+    use crate::fixed_script_wallet::wallet_keys::to_pub_triple;
+    use crate::fixed_script_wallet::wallet_scripts::bitgo_musig::key_agg_p2tr_musig2;
+    use crate::fixed_script_wallet::wallet_scripts::{chain_index_path, WalletScripts};
+    use crate::Network;
+    use miniscript::{Descriptor, DescriptorPublicKey};
+    use std::str::FromStr;
+
+    fn expected_script(keys: &RootWalletKeys, chain: Chain, index: u32) -> Vec<u8> {
+        WalletScripts::from_wallet_keys(
+            keys,
+            chain.script_type,
+            &chain_index_path(chain.value(), index),
+            &Network::Bitcoin.output_script_support(),
+        )
+        .unwrap()
+        .output_script()
+        .to_bytes()
+    }
+
+    #[test]
+    fn test_multisig_descriptors() {
+        let keys = get_test_wallet_keys("descriptor");
+        for chain in [0, 1, 10, 11, 20, 21] {
+            let descriptor = descriptor_for_chain(&keys, chain).unwrap();
+            let parsed = Descriptor::<DescriptorPublicKey>::from_str(&descriptor).unwrap();
+            assert!(parsed.has_wildcard());
+            let chain = Chain::try_from(chain).unwrap();
+            for index in [0, 7] {
+                let derived = parsed.at_derivation_index(index).unwrap();
+                assert_eq!(
+                    derived.script_pubkey().to_bytes(),
+                    expected_script(&keys, chain, index),
+                    "chain {} index {}",
+                    chain.value(),
+                    index
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_musig2_descriptor() {
+        let keys = get_test_wallet_keys("descriptor");
+        let descriptor = descriptor_for_chain(&keys, 40).unwrap();
+        let [user, backup, bitgo] = std::array::from_fn(|i| {
+            key_expression(&keys.xpubs[i], &keys.derivation_prefixes[i], 40)
+        });
+        assert_eq!(
+            descriptor,
+            format!(
+                "tr(musig({user},{bitgo}),{{and_v(v:pk({user}),pk({backup})),and_v(v:pk({backup}),pk({bitgo}))}})"
+            )
+        );
+
+        // The musig() internal key cannot be parsed here; check the template with the
+        // derived keys and the aggregated internal key instead
+        for chain in [40, 41] {
+            let chain = Chain::try_from(chain).unwrap();
+            for index in [0, 7] {
+                let derived = to_pub_triple(
+                    &keys
+                        .derive_path(&chain_index_path(chain.value(), index))
+                        .unwrap(),
+                );
+                let internal_key = key_agg_p2tr_musig2(&[derived[0], derived[2]]).unwrap();
+                let descriptor = format_descriptor(
+                    chain.script_type,
+                    &derived.map(|key| key.to_string()),
+                    &hex::encode(internal_key),
+                )
+                .unwrap();
+                let parsed = Descriptor::<DescriptorPublicKey>::from_str(&descriptor).unwrap();
+                assert_eq!(
+                    parsed
+                        .at_derivation_index(0)
+                        .unwrap()
+                        .script_pubkey()
+                        .to_bytes(),
+                    expected_script(&keys, chain, index)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_descriptor_checksum() {
+        // Test vectors from BIP-380
+        assert_eq!(descriptor_checksum("raw(deadbeef)").unwrap(), "89f8spxm");
+        assert_eq!(
+            descriptor_checksum("addr(mkmZxiEcEd8ZqjQWVZuC6so5dFMKEFpN2j)").unwrap(),
+            "02wpgw69"
+        );
+        assert_eq!(descriptor_checksum("raw(\u{e9})"), None);
+
+        let keys = get_test_wallet_keys("descriptor");
+        let descriptor = descriptor_for_chain(&keys, 20).unwrap();
+        let parsed = Descriptor::<DescriptorPublicKey>::from_str(&descriptor).unwrap();
+        assert_eq!(
+            parsed.to_string(),
+            format!(
+                "{}#{}",
+                descriptor,
+                descriptor_checksum(&descriptor).unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn test_unsupported_chains() {
+        let keys = get_test_wallet_keys("descriptor");
+        for chain in [30, 31, 360, 361, 2] {
+            assert!(
+                descriptor_for_chain(&keys, chain).is_err(),
+                "chain {}",
+                chain
+            );
+        }
+    }
+}
@@ -0,0 +1,491 @@
+//! 2-of-2 channel funding outputs
+//!
+//! Payment channels lock their funds in an output that both parties have to sign. Unlike the
+//! 2-of-3 wallet scripts, the keys are not derived from the wallet xpubs and are ordered by
+//! their serialization instead of by role:
+//!
+//! - [`FundingOutputType::P2wsh`]: `2 <key1> <key2> 2 OP_CHECKMULTISIG` (BOLT 3)
+//! - [`FundingOutputType::P2trMusig2`]: key-path only taproot output of the MuSig2 aggregate
+//!   of the keys (simple taproot channels)
+//!
+//! PSBT outputs and inputs carry the witness script or the MuSig2 participants, so that a
+//! funding output can be recognized without knowing the keys in advance.
+
+use std::collections::BTreeMap;
+
+use crate::address::networks::OutputScriptSupport;
+use crate::bitcoin::bip32::KeySource;
+use crate::bitcoin::psbt::{Input, Output};
+use crate::bitcoin::secp256k1::PublicKey;
+use crate::bitcoin::{CompressedPublicKey, ScriptBuf, TapLeafHash, XOnlyPublicKey};
+use crate::fixed_script_wallet::bitgo_psbt::p2tr_musig2_input::{
+    parse_musig2_output_participants, parse_musig2_participants, Musig2Participants,
+};
+use crate::fixed_script_wallet::wallet_scripts::{KeySet, ScriptP2tr, ScriptP2wsh};
+
+/// Script type of a funding output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FundingOutputType {
+    P2wsh,
+    P2trMusig2,
+}
+
+impl FundingOutputType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FundingOutputType::P2wsh => "p2wsh",
+            FundingOutputType::P2trMusig2 => "p2trMusig2",
+        }
+    }
+}
+
+impl std::str::FromStr for FundingOutputType {
+    type Err = FundingOutputError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "p2wsh" => Ok(FundingOutputType::P2wsh),
+            "p2trMusig2" => Ok(FundingOutputType::P2trMusig2),
+            _ => Err(FundingOutputError::UnknownType(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum FundingOutputError {
+    /// The funding output type is not "p2wsh" or "p2trMusig2"
+    UnknownType(String),
+    /// A key is not a valid compressed public key
+    InvalidPublicKey(String),
+    /// The PSBT has no input or output at the index
+    IndexOutOfBounds { index: usize, count: usize },
+    /// Both parties use the same key
+    DuplicateKeys,
+    /// The utxo or output script of a PSBT entry is not the funding output
+    ScriptMismatch,
+    /// A key source belongs to a key that is not a key of the funding output
+    ForeignKey(PublicKey),
+    /// Key aggregation or network support error
+    Script(String),
+}
+
+impl std::fmt::Display for FundingOutputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FundingOutputError::UnknownType(output_type) => {
+                write!(f, "Unknown funding output type: {}", output_type)
+            }
+            FundingOutputError::InvalidPublicKey(msg) => write!(f, "Invalid public key: {}", msg),
+            FundingOutputError::IndexOutOfBounds { index, count } => {
+                write!(f, "Index {} out of bounds ({} entries)", index, count)
+            }
+            FundingOutputError::DuplicateKeys => {
+                write!(f, "Funding output keys must be distinct")
+            }
+            FundingOutputError::ScriptMismatch => {
+                write!(f, "Output script does not match the funding output")
+            }
+            FundingOutputError::ForeignKey(key) => {
+                write!(f, "Key {} is not a key of the funding output", key)
+            }
+            FundingOutputError::Script(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for FundingOutputError {}
+
+crate::impl_wasm_error_code!(FundingOutputError);
+crate::impl_from_coded_error!(FundingOutputError);
+
+/// A 2-of-2 output spendable only by both parties together
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FundingOutput {
+    output_type: FundingOutputType,
+    keys: KeySet,
+}
+
+impl FundingOutput {
+    /// Create the funding output of two keys, in either order
+    pub fn new(
+        output_type: FundingOutputType,
+        keys: [CompressedPublicKey; 2],
+    ) -> Result<Self, FundingOutputError> {
+        if keys[0] == keys[1] {
+            return Err(FundingOutputError::DuplicateKeys);
+        }
+        let keys = KeySet::sorted(2, keys.to_vec()).map_err(FundingOutputError::Script)?;
+        Ok(Self { output_type, keys })
+    }
+
+    pub fn output_type(&self) -> FundingOutputType {
+        self.output_type
+    }
+
+    /// The 2-of-2 key set, in script and aggregation order
+    pub fn keys(&self) -> &KeySet {
+        &self.keys
+    }
+
+    /// The two keys, sorted
+    pub fn key_pair(&self) -> [CompressedPublicKey; 2] {
+        [self.keys.keys()[0], self.keys.keys()[1]]
+    }
+
+    /// Witness script of a p2wsh funding output, `None` for taproot
+    pub fn witness_script(&self) -> Option<ScriptBuf> {
+        match self.output_type {
+            FundingOutputType::P2wsh => Some(ScriptP2wsh::new(&self.keys).witness_script),
+            FundingOutputType::P2trMusig2 => None,
+        }
+    }
+
+    fn script_p2tr(&self) -> Result<ScriptP2tr, FundingOutputError> {
+        ScriptP2tr::new_musig2_key_path(&self.keys)
+            .map_err(|e| FundingOutputError::Script(e.to_string()))
+    }
+
+    fn script_pubkey(&self) -> Result<ScriptBuf, FundingOutputError> {
+        match self.output_type {
+            FundingOutputType::P2wsh => Ok(ScriptP2wsh::new(&self.keys).witness_script.to_p2wsh()),
+            FundingOutputType::P2trMusig2 => Ok(self.script_p2tr()?.output_script()),
+        }
+    }
+
+    /// Build the output script
+    pub fn output_script(
+        &self,
+        script_support: &OutputScriptSupport,
+    ) -> Result<ScriptBuf, FundingOutputError> {
+        match self.output_type {
+            FundingOutputType::P2wsh => script_support.assert_segwit(),
+            FundingOutputType::P2trMusig2 => script_support.assert_taproot(),
+        }
+        .map_err(|e| FundingOutputError::Script(e.to_string()))?;
+        self.script_pubkey()
+    }
+
+    /// Find the funding output of `keys` that has the output script `output_script`
+    pub fn classify(output_script: &ScriptBuf, keys: [CompressedPublicKey; 2]) -> Option<Self> {
+        [FundingOutputType::P2wsh, FundingOutputType::P2trMusig2]
+            .into_iter()
+            .filter_map(|output_type| Self::new(output_type, keys).ok())
+            .find(|funding| funding.script_pubkey().ok().as_ref() == Some(output_script))
+    }
+
+    /// Recognize a funding output from the witness script or MuSig2 participants recorded in
+    /// a PSBT output
+    ///
+    /// Returns `None` if the metadata is missing, is not that of a 2-of-2 funding output, or
+    /// does not produce `output_script`.
+    pub fn from_psbt_output(output: &Output, output_script: &ScriptBuf) -> Option<Self> {
+        let participants = parse_musig2_output_participants(output).ok().flatten();
+        Self::from_psbt_metadata(
+            output.witness_script.as_ref(),
+            participants.as_ref(),
+            output_script,
+        )
+    }
+
+    /// Recognize a funding output from the witness script or MuSig2 participants recorded in
+    /// a PSBT input
+    pub fn from_psbt_input(input: &Input, output_script: &ScriptBuf) -> Option<Self> {
+        let participants = parse_musig2_participants(input).ok().flatten();
+        Self::from_psbt_metadata(
+            input.witness_script.as_ref(),
+            participants.as_ref(),
+            output_script,
+        )
+    }
+
+    fn from_psbt_metadata(
+        witness_script: Option<&ScriptBuf>,
+        participants: Option<&Musig2Participants>,
+        output_script: &ScriptBuf,
+    ) -> Option<Self> {
+        let funding = if let Some(witness_script) = witness_script {
+            let keys = KeySet::from_multisig_script(witness_script).ok()?;
+            if keys.threshold() != 2 || !keys.is_sorted() {
+                return None;
+            }
+            let keys: [CompressedPublicKey; 2] = keys.keys().try_into().ok()?;
+            Self::new(FundingOutputType::P2wsh, keys).ok()?
+        } else {
+            Self::new(
+                FundingOutputType::P2trMusig2,
+                participants?.participant_pub_keys,
+            )
+            .ok()?
+        };
+        (funding.script_pubkey().ok()? == *output_script).then_some(funding)
+    }
+
+    /// Check that every key source belongs to a key of the funding output
+    fn check_key_sources(
+        &self,
+        key_sources: &BTreeMap<PublicKey, KeySource>,
+    ) -> Result<(), FundingOutputError> {
+        match key_sources
+            .keys()
+            .find(|key| !self.keys.keys().iter().any(|own| own.0 == **key))
+        {
+            Some(key) => Err(FundingOutputError::ForeignKey(*key)),
+            None => Ok(()),
+        }
+    }
+
+    fn musig2_participants(&self) -> Result<Musig2Participants, FundingOutputError> {
+        let spend_info = self.script_p2tr()?.spend_info;
+        Ok(Musig2Participants {
+            tap_output_key: spend_info.output_key().to_x_only_public_key(),
+            tap_internal_key: spend_info.internal_key(),
+            participant_pub_keys: self.key_pair(),
+        })
+    }
+
+    fn tap_key_origins(
+        key_sources: &BTreeMap<PublicKey, KeySource>,
+    ) -> BTreeMap<XOnlyPublicKey, (Vec<TapLeafHash>, KeySource)> {
+        key_sources
+            .iter()
+            .map(|(key, source)| (key.x_only_public_key().0, (vec![], source.clone())))
+            .collect()
+    }
+
+    /// Set the witness script or MuSig2 participants of a PSBT output paying to the funding
+    /// output
+    ///
+    /// `key_sources` are the derivations of the keys we know, usually only our own key.
+    pub fn update_output(
+        &self,
+        output: &mut Output,
+        output_script: &ScriptBuf,
+        key_sources: &BTreeMap<PublicKey, KeySource>,
+    ) -> Result<(), FundingOutputError> {
+        if *output_script != self.script_pubkey()? {
+            return Err(FundingOutputError::ScriptMismatch);
+        }
+        self.check_key_sources(key_sources)?;
+        match self.output_type {
+            FundingOutputType::P2wsh => {
+                output.witness_script = self.witness_script();
+                output.bip32_derivation = key_sources.clone();
+            }
+            FundingOutputType::P2trMusig2 => {
+                let participants = self.musig2_participants()?;
+                output.tap_internal_key = Some(participants.tap_internal_key);
+                output.tap_key_origins = Self::tap_key_origins(key_sources);
+                let (key, value) = participants.to_key_value().to_key_value();
+                output.proprietary.insert(key, value);
+            }
+        }
+        Ok(())
+    }
+
+    /// Set the witness script or MuSig2 participants of a PSBT input spending the funding
+    /// output
+    ///
+    /// If the input has a `witness_utxo`, its script must be the funding output script.
+    pub fn update_input(
+        &self,
+        input: &mut Input,
+        key_sources: &BTreeMap<PublicKey, KeySource>,
+    ) -> Result<(), FundingOutputError> {
+        if let Some(utxo) = &input.witness_utxo {
+            if utxo.script_pubkey != self.script_pubkey()? {
+                return Err(FundingOutputError::ScriptMismatch);
+            }
+        }
+        self.check_key_sources(key_sources)?;
+        match self.output_type {
+            FundingOutputType::P2wsh => {
+                input.witness_script = self.witness_script();
+                input.bip32_derivation = key_sources.clone();
+            }
+            FundingOutputType::P2trMusig2 => {
+                let participants = self.musig2_participants()?;
+                input.tap_internal_key = Some(participants.tap_internal_key);
+                input.tap_merkle_root = None;
+                input.tap_key_origins = Self::tap_key_origins(key_sources);
+                let (key, value) = participants.to_key_value().to_key_value();
+                input.proprietary.insert(key, value);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::bip32::DerivationPath;
+    use crate::bitcoin::{Amount, TxOut};
+    use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+    use crate::fixed_script_wallet::wallet_keys::to_pub_triple;
+    use crate::fixed_script_wallet::{OutputScriptType, WalletScripts};
+    use crate::Network;
+    use std::str::FromStr;
+
+    fn get_test_keys() -> [CompressedPublicKey; 2] {
+        let [a, b, _] = to_pub_triple(&get_test_wallet_keys("funding_output").xpubs);
+        [a, b]
+    }
+
+    fn get_other_key() -> CompressedPublicKey {
+        to_pub_triple(&get_test_wallet_keys("funding_output").xpubs)[2]
+    }
+
+    #[test]
+    fn test_p2wsh_script() {
+        let [a, b] = get_test_keys();
+        let funding = FundingOutput::new(FundingOutputType::P2wsh, [a, b]).unwrap();
+        assert_eq!(
+            funding,
+            FundingOutput::new(FundingOutputType::P2wsh, [b, a]).unwrap()
+        );
+        let (first, second) = if a.to_bytes() < b.to_bytes() {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        assert_eq!(
+            funding.witness_script().unwrap(),
+            crate::fixed_script_wallet::build_multisig_script(2, &[first, second])
+        );
+        let support = Network::Bitcoin.output_script_support();
+        let script = funding.output_script(&support).unwrap();
+        assert!(script.is_p2wsh());
+        assert!(funding
+            .output_script(&Network::Dogecoin.output_script_support())
+            .is_err());
+        assert!(matches!(
+            FundingOutput::new(FundingOutputType::P2wsh, [a, a]),
+            Err(FundingOutputError::DuplicateKeys)
+        ));
+    }
+
+    /// Funding witness script and output of the BOLT 3 test vectors (appendix B)
+    #[test]
+    fn test_bolt3_funding_script() {
+        let local = CompressedPublicKey::from_str(
+            "023da092f6980e58d2c037173180e9a465476026ee50f96695963e8efe436f54eb",
+        )
+        .unwrap();
+        let remote = CompressedPublicKey::from_str(
+            "030e9f7b623d2ccc7c9bd44d66d5ce21ce504c0acf6385a132cec6d3c39fa711c1",
+        )
+        .unwrap();
+        let funding = FundingOutput::new(FundingOutputType::P2wsh, [remote, local]).unwrap();
+        assert_eq!(
+            funding.witness_script().unwrap().to_hex_string(),
+            "5221023da092f6980e58d2c037173180e9a465476026ee50f96695963e8efe436f54eb21030e9f7b\
+             623d2ccc7c9bd44d66d5ce21ce504c0acf6385a132cec6d3c39fa711c152ae"
+        );
+        assert_eq!(
+            funding
+                .output_script(&Network::Bitcoin.output_script_support())
+                .unwrap()
+                .to_hex_string(),
+            "0020c015c4a6be010e21657068fc2e6a9d02b27ebe4d490a25846f7237f104d1a3cd"
+        );
+    }
+
+    #[test]
+    fn test_classify() {
+        let keys = get_test_keys();
+        let support = Network::Bitcoin.output_script_support();
+        for output_type in [FundingOutputType::P2wsh, FundingOutputType::P2trMusig2] {
+            let funding = FundingOutput::new(output_type, keys).unwrap();
+            let script = funding.output_script(&support).unwrap();
+            assert_eq!(FundingOutput::classify(&script, keys), Some(funding));
+        }
+        assert!(FundingOutput::new(FundingOutputType::P2trMusig2, keys)
+            .unwrap()
+            .output_script(&support)
+            .unwrap()
+            .is_p2tr());
+
+        // The 2-of-3 wallet script of the same keys is not a funding output
+        let wallet_keys = get_test_wallet_keys("funding_output");
+        let wallet_script = WalletScripts::new(
+            &to_pub_triple(&wallet_keys.xpubs),
+            OutputScriptType::P2wsh,
+            &support,
+        )
+        .unwrap()
+        .output_script();
+        assert_eq!(FundingOutput::classify(&wallet_script, keys), None);
+    }
+
+    #[test]
+    fn test_psbt_metadata() {
+        let keys = get_test_keys();
+        let support = Network::Bitcoin.output_script_support();
+        let key_source = (
+            get_test_wallet_keys("funding_output").xpubs[0].fingerprint(),
+            DerivationPath::from_str("m/1017/0/0").unwrap(),
+        );
+        let key_sources = BTreeMap::from([(keys[0].0, key_source)]);
+
+        for output_type in [FundingOutputType::P2wsh, FundingOutputType::P2trMusig2] {
+            let funding = FundingOutput::new(output_type, keys).unwrap();
+            let script = funding.output_script(&support).unwrap();
+
+            let mut output = Output::default();
+            funding
+                .update_output(&mut output, &script, &key_sources)
+                .unwrap();
+            assert_eq!(
+                FundingOutput::from_psbt_output(&output, &script),
+                Some(funding.clone())
+            );
+            assert_eq!(
+                FundingOutput::from_psbt_output(&Output::default(), &script),
+                None
+            );
+
+            let mut input = Input {
+                witness_utxo: Some(TxOut {
+                    value: Amount::from_sat(100_000),
+                    script_pubkey: script.clone(),
+                }),
+                ..Default::default()
+            };
+            funding.update_input(&mut input, &key_sources).unwrap();
+            assert_eq!(
+                FundingOutput::from_psbt_input(&input, &script),
+                Some(funding.clone())
+            );
+            match output_type {
+                FundingOutputType::P2wsh => {
+                    assert_eq!(input.bip32_derivation, key_sources);
+                    assert_eq!(input.witness_script, funding.witness_script());
+                }
+                FundingOutputType::P2trMusig2 => {
+                    assert_eq!(input.tap_key_origins.len(), 1);
+                    assert!(parse_musig2_participants(&input).unwrap().is_some());
+                }
+            }
+
+            let other = FundingOutput::new(output_type, [keys[1], get_other_key()])
+                .unwrap()
+                .output_script(&support)
+                .unwrap();
+            assert!(matches!(
+                funding.update_output(&mut Output::default(), &other, &key_sources),
+                Err(FundingOutputError::ScriptMismatch)
+            ));
+        }
+
+        let funding = FundingOutput::new(FundingOutputType::P2wsh, keys).unwrap();
+        let script = funding.output_script(&support).unwrap();
+        let foreign = BTreeMap::from([(
+            get_other_key().0,
+            key_sources.values().next().unwrap().clone(),
+        )]);
+        assert!(matches!(
+            funding.update_output(&mut Output::default(), &script, &foreign),
+            Err(FundingOutputError::ForeignKey(_))
+        ));
+    }
+}
@@ -0,0 +1,316 @@
+//! Consolidating legacy unspents into p2trMusig2
+//!
+//! [`plan_migration`] moves the unspents of the p2sh (chains 0/1) and p2shP2wsh (chains
+//! 10/11) scripts of a wallet to p2trMusig2 change addresses (chain 41). Spending a p2trMusig2
+//! output with the user and BitGo keys costs a fraction of a legacy 2-of-3 spend, so wallets
+//! with many small legacy unspents save fees on every later transaction.
+//!
+//! The unspents are split, in the given order, into transactions of at most
+//! [`MigrationOptions::max_inputs`] inputs. Each transaction has a single output that pays the
+//! value of its inputs minus the fee at [`MigrationOptions::fee_rate`], which must stay above
+//! the dust limit of the network.
+
+use crate::fixed_script_wallet::batch;
+use crate::fixed_script_wallet::bitgo_psbt::{BitGoPsbt, SequenceMode};
+use crate::fixed_script_wallet::core_unspent::WalletUnspent;
+use crate::fixed_script_wallet::wallet_scripts::OutputScriptType;
+use crate::fixed_script_wallet::{Chain, RootWalletKeys};
+use crate::Network;
+
+/// Chain of the p2trMusig2 change addresses the unspents are moved to
+pub const MIGRATION_CHAIN: u32 = 41;
+
+#[derive(Debug, Clone)]
+pub struct MigrationOptions {
+    /// Maximum number of inputs per transaction
+    pub max_inputs: usize,
+    /// Fee rate in sat/vB
+    pub fee_rate: f64,
+    /// Derivation index of the change address of the first transaction; each following
+    /// transaction uses the next index
+    pub change_index: u32,
+    /// Sequence of the inputs (default: [`SequenceMode::FinalNoRbf`])
+    pub sequence: Option<SequenceMode>,
+}
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum MigrationError {
+    InvalidMaxInputs,
+    InvalidFeeRate(f64),
+    /// The network does not support p2trMusig2 outputs
+    UnsupportedNetwork(Network),
+    /// The unspent is not on a p2sh or p2shP2wsh chain
+    NotLegacyUnspent {
+        unspent_index: usize,
+        chain: u32,
+    },
+    /// The inputs of a transaction do not cover its fee and a non-dust output
+    InsufficientValue {
+        psbt_index: usize,
+        value: u64,
+        fee: u64,
+    },
+    /// The change index of the transaction exceeds `u32::MAX`
+    ChangeIndexOverflow {
+        psbt_index: usize,
+    },
+    /// Adding an input or output, or estimating the size, failed
+    Psbt {
+        psbt_index: usize,
+        message: String,
+    },
+}
+
+impl std::fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrationError::InvalidMaxInputs => {
+                write!(f, "Maximum number of inputs must be at least 1")
+            }
+            MigrationError::InvalidFeeRate(fee_rate) => {
+                write!(f, "Invalid fee rate: {} sat/vB", fee_rate)
+            }
+            MigrationError::UnsupportedNetwork(network) => {
+                write!(f, "Network {} does not support p2trMusig2", network)
+            }
+            MigrationError::NotLegacyUnspent {
+                unspent_index,
+                chain,
+            } => write!(
+                f,
+                "Unspent {} is on chain {}, only p2sh and p2shP2wsh unspents can be migrated",
+                unspent_index, chain
+            ),
+            MigrationError::InsufficientValue {
+                psbt_index,
+                value,
+                fee,
+            } => write!(
+                f,
+                "Transaction {}: inputs of {} sat do not cover the fee of {} sat",
+                psbt_index, value, fee
+            ),
+            MigrationError::ChangeIndexOverflow { psbt_index } => {
+                write!(f, "Transaction {}: change index overflows u32", psbt_index)
+            }
+            MigrationError::Psbt {
+                psbt_index,
+                message,
+            } => write!(f, "Transaction {}: {}", psbt_index, message),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+crate::impl_wasm_error_code!(MigrationError);
+crate::impl_from_coded_error!(MigrationError);
+
+/// Build the unsigned PSBTs moving legacy `unspents` to p2trMusig2 change addresses
+///
+/// `estimate_vsize` returns the maximum virtual size of a PSBT once it is signed. The fee of
+/// each transaction is the fee rate times this size, rounded up.
+pub fn plan_migration<F>(
+    network: Network,
+    wallet_keys: &RootWalletKeys,
+    unspents: &[WalletUnspent],
+    options: &MigrationOptions,
+    estimate_vsize: F,
+) -> Result<Vec<BitGoPsbt>, MigrationError>
+where
+    F: Fn(&BitGoPsbt) -> Result<u64, String>,
+{
+    if options.max_inputs == 0 {
+        return Err(MigrationError::InvalidMaxInputs);
+    }
+    if !batch::is_valid_fee_rate(options.fee_rate) {
+        return Err(MigrationError::InvalidFeeRate(options.fee_rate));
+    }
+    if !network
+        .output_script_support()
+        .supports_script_type(OutputScriptType::P2trMusig2)
+    {
+        return Err(MigrationError::UnsupportedNetwork(network));
+    }
+    for (unspent_index, unspent) in unspents.iter().enumerate() {
+        let chain = unspent.script_id.chain;
+        let is_legacy = Chain::try_from(chain).is_ok_and(|chain| {
+            matches!(
+                chain.script_type,
+                OutputScriptType::P2sh | OutputScriptType::P2shP2wsh
+            )
+        });
+        if !is_legacy {
+            return Err(MigrationError::NotLegacyUnspent {
+                unspent_index,
+                chain,
+            });
+        }
+    }
+
+    unspents
+        .chunks(options.max_inputs)
+        .enumerate()
+        .map(|(psbt_index, unspents)| {
+            let psbt_error = |message: String| MigrationError::Psbt {
+                psbt_index,
+                message,
+            };
+            let mut psbt =
+                batch::batch_psbt(network, wallet_keys, unspents, None, options.sequence)
+                    .map_err(psbt_error)?;
+
+            let value: u64 = unspents.iter().map(|unspent| unspent.value).sum();
+            let change_index = u32::try_from(psbt_index)
+                .ok()
+                .and_then(|offset| options.change_index.checked_add(offset))
+                .ok_or(MigrationError::ChangeIndexOverflow { psbt_index })?;
+            // The output value does not change the size
+            let mut sized = psbt.clone();
+            sized
+                .add_wallet_output(MIGRATION_CHAIN, change_index, value, wallet_keys)
+                .map_err(psbt_error)?;
+            let vsize = estimate_vsize(&sized).map_err(psbt_error)?;
+            let fee = batch::fee_for_vsize(options.fee_rate, vsize);
+            let output_script = &sized.psbt().unsigned_tx.output[0].script_pubkey;
+            let output_value = batch::output_value(network, output_script, value, fee).ok_or(
+                MigrationError::InsufficientValue {
+                    psbt_index,
+                    value,
+                    fee,
+                },
+            )?;
+
+            psbt.add_wallet_output(MIGRATION_CHAIN, change_index, output_value, wallet_keys)
+                .map_err(psbt_error)?;
+            Ok(psbt)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::bitgo_psbt::ParsedOutput;
+    use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+    use crate::fixed_script_wallet::ScriptId;
+    use miniscript::bitcoin::hashes::Hash;
+    use miniscript::bitcoin::Txid;
+
+    fn unspent(vout: u32, chain: u32, value: u64) -> WalletUnspent {
+        WalletUnspent {
+            txid: Txid::all_zeros(),
+            vout,
+            value,
+            script_id: ScriptId { chain, index: vout },
+            script_type: Chain::try_from(chain).unwrap().script_type,
+        }
+    }
+
+    fn options(max_inputs: usize) -> MigrationOptions {
+        MigrationOptions {
+            max_inputs,
+            fee_rate: 2.5,
+            change_index: 7,
+            sequence: None,
+        }
+    }
+
+    /// 100 vbytes per input
+    fn estimate_vsize(psbt: &BitGoPsbt) -> Result<u64, String> {
+        Ok(100 * psbt.psbt().inputs.len() as u64)
+    }
+
+    #[test]
+    fn test_plan_migration() {
+        let wallet_keys = get_test_wallet_keys("migration");
+        let unspents: Vec<_> = [0, 1, 10, 11, 0]
+            .into_iter()
+            .enumerate()
+            .map(|(vout, chain)| unspent(vout as u32, chain, 10_000))
+            .collect();
+        let psbts = plan_migration(
+            Network::Bitcoin,
+            &wallet_keys,
+            &unspents,
+            &options(2),
+            estimate_vsize,
+        )
+        .unwrap();
+
+        assert_eq!(psbts.len(), 3);
+        for (i, psbt) in psbts.iter().enumerate() {
+            let inner = psbt.psbt();
+            let input_count = inner.inputs.len();
+            assert_eq!(input_count, if i < 2 { 2 } else { 1 });
+            assert_eq!(inner.outputs.len(), 1);
+
+            let output = ParsedOutput::parse(
+                &inner.outputs[0],
+                &inner.unsigned_tx.output[0],
+                &wallet_keys,
+                Network::Bitcoin,
+                &[],
+            )
+            .unwrap();
+            assert_eq!(
+                output.script_id,
+                Some(ScriptId {
+                    chain: MIGRATION_CHAIN,
+                    index: 7 + i as u32
+                })
+            );
+            assert_eq!(
+                output.value,
+                10_000 * input_count as u64 - 250 * input_count as u64
+            );
+        }
+    }
+
+    #[test]
+    fn test_plan_migration_errors() {
+        let wallet_keys = get_test_wallet_keys("migration");
+        let plan = |unspents: &[WalletUnspent], network, options: &MigrationOptions| {
+            plan_migration(network, &wallet_keys, unspents, options, estimate_vsize)
+        };
+
+        assert!(matches!(
+            plan(
+                &[unspent(0, 0, 10_000), unspent(1, 20, 10_000)],
+                Network::Bitcoin,
+                &options(2)
+            ),
+            Err(MigrationError::NotLegacyUnspent {
+                unspent_index: 1,
+                chain: 20
+            })
+        ));
+        assert!(matches!(
+            plan(&[unspent(0, 0, 500)], Network::Bitcoin, &options(2)),
+            Err(MigrationError::InsufficientValue {
+                psbt_index: 0,
+                value: 500,
+                fee: 250
+            })
+        ));
+        assert!(matches!(
+            plan(&[], Network::Bitcoin, &options(0)),
+            Err(MigrationError::InvalidMaxInputs)
+        ));
+        assert!(matches!(
+            plan(&[], Network::Litecoin, &options(2)),
+            Err(MigrationError::UnsupportedNetwork(Network::Litecoin))
+        ));
+        assert!(matches!(
+            plan(
+                &[unspent(0, 0, 10_000), unspent(1, 0, 10_000)],
+                Network::Bitcoin,
+                &MigrationOptions {
+                    change_index: u32::MAX,
+                    ..options(1)
+                }
+            ),
+            Err(MigrationError::ChangeIndexOverflow { psbt_index: 1 })
+        ));
+    }
+}
@@ -1,15 +1,30 @@
 /// This module contains code for the BitGo Fixed Script Wallets.
 /// These are not based on descriptors.
+mod batch;
 pub mod bitgo_psbt;
+pub mod core_unspent;
+pub mod derivation_cache;
+pub mod descriptor;
+pub mod funding_output;
+pub mod migration;
 pub mod replay_protection;
 pub mod script_id;
+pub mod sweep;
+pub mod timelocked_recovery;
 mod wallet_keys;
+pub mod wallet_policy;
 pub mod wallet_scripts;
 
 #[cfg(test)]
 pub mod test_utils;
 
+pub use descriptor::{descriptor_checksum, descriptor_for_chain};
+pub use funding_output::{FundingOutput, FundingOutputError, FundingOutputType};
+pub use migration::{plan_migration, MigrationError, MigrationOptions};
 pub use replay_protection::*;
 pub use script_id::{Chain, Scope, ScriptId, ScriptIdWithValue};
+pub use sweep::{plan_sweep, SweepError, SweepOptions, SweepPlan, SweepTransaction};
+pub use timelocked_recovery::{TimelockedRecoveryError, TimelockedRecoveryWallet};
 pub use wallet_keys::*;
+pub use wallet_policy::{WalletPolicy, WalletPolicyError};
 pub use wallet_scripts::*;
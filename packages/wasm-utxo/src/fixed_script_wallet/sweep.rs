@@ -0,0 +1,467 @@
+//! Splitting large sweeps into standard transactions
+//!
+//! Nodes do not relay transactions heavier than [`MAX_STANDARD_TX_WEIGHT`] weight units, so a
+//! recovery that spends all unspents of a large wallet in a single transaction is rejected when
+//! it is broadcast.
+//!
+//! [`plan_sweep`] fills each transaction with the unspents, in the given order, until the next
+//! input would take the signed transaction over [`SweepOptions::max_weight`]. This is the
+//! smallest number of transactions that keeps the order of the unspents. Each transaction has a
+//! single output paying the value of its inputs minus the fee at [`SweepOptions::fee_rate`] to
+//! the recipient, which must stay above the dust limit of the network for the recipient script.
+
+use crate::bitcoin::{ScriptBuf, VarInt};
+use crate::fixed_script_wallet::batch;
+use crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::SignPath;
+use crate::fixed_script_wallet::bitgo_psbt::{BitGoPsbt, SequenceMode};
+use crate::fixed_script_wallet::core_unspent::WalletUnspent;
+use crate::fixed_script_wallet::wallet_scripts::OutputScriptType;
+use crate::fixed_script_wallet::RootWalletKeys;
+use crate::Network;
+
+/// Maximum weight of a transaction relayed by Bitcoin Core (`MAX_STANDARD_TX_WEIGHT`)
+pub const MAX_STANDARD_TX_WEIGHT: u64 = 400_000;
+
+/// Weight of version, locktime and the output count, without the input count
+const TX_BASE_WEIGHT: u64 = 4 * (4 + 4 + 1);
+
+/// Weight of the segwit marker and flag
+const SEGWIT_MARKER_WEIGHT: u64 = 2;
+
+#[derive(Debug, Clone)]
+pub struct SweepOptions {
+    /// Output script the unspents are swept to
+    pub recipient: ScriptBuf,
+    /// Fee rate in sat/vB
+    pub fee_rate: f64,
+    /// Maximum weight of each signed transaction (default: [`MAX_STANDARD_TX_WEIGHT`])
+    pub max_weight: Option<u64>,
+    /// Signer and cosigner of the inputs; required for p2tr and p2trMusig2 unspents
+    pub sign_path: Option<SignPath>,
+    /// Sequence of the inputs (default: [`SequenceMode::FinalNoRbf`])
+    pub sequence: Option<SequenceMode>,
+}
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum SweepError {
+    InvalidFeeRate(f64),
+    /// Estimating the weight of an unspent failed
+    InputWeight {
+        unspent_index: usize,
+        message: String,
+    },
+    /// A transaction spending only this unspent is heavier than the maximum weight
+    UnspentTooHeavy {
+        unspent_index: usize,
+        weight: u64,
+        max_weight: u64,
+    },
+    /// The inputs of a transaction do not cover its fee and a non-dust output
+    InsufficientValue {
+        psbt_index: usize,
+        value: u64,
+        fee: u64,
+    },
+    /// Adding an input or output failed
+    Psbt {
+        psbt_index: usize,
+        message: String,
+    },
+}
+
+impl std::fmt::Display for SweepError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SweepError::InvalidFeeRate(fee_rate) => {
+                write!(f, "Invalid fee rate: {} sat/vB", fee_rate)
+            }
+            SweepError::InputWeight {
+                unspent_index,
+                message,
+            } => write!(f, "Unspent {}: {}", unspent_index, message),
+            SweepError::UnspentTooHeavy {
+                unspent_index,
+                weight,
+                max_weight,
+            } => write!(
+                f,
+                "Unspent {}: a transaction spending it weighs {} WU, more than the maximum of {} WU",
+                unspent_index, weight, max_weight
+            ),
+            SweepError::InsufficientValue {
+                psbt_index,
+                value,
+                fee,
+            } => write!(
+                f,
+                "Transaction {}: inputs of {} sat do not cover the fee of {} sat",
+                psbt_index, value, fee
+            ),
+            SweepError::Psbt {
+                psbt_index,
+                message,
+            } => write!(f, "Transaction {}: {}", psbt_index, message),
+        }
+    }
+}
+
+impl std::error::Error for SweepError {}
+
+crate::impl_wasm_error_code!(SweepError);
+
+/// Size and value of a sweep transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SweepTransaction {
+    pub input_count: usize,
+    /// Maximum weight of the signed transaction
+    pub weight: u64,
+    /// Maximum virtual size of the signed transaction
+    pub vsize: u64,
+    pub input_value: u64,
+    pub fee: u64,
+}
+
+impl SweepTransaction {
+    /// Value paid to the recipient
+    pub fn output_value(&self) -> u64 {
+        self.input_value - self.fee
+    }
+}
+
+/// The transactions of a sweep
+#[derive(Debug)]
+pub struct SweepPlan {
+    /// The unsigned PSBTs
+    pub psbts: Vec<BitGoPsbt>,
+    /// Size and value of each PSBT
+    pub transactions: Vec<SweepTransaction>,
+}
+
+impl SweepPlan {
+    pub fn input_value(&self) -> u64 {
+        self.transactions.iter().map(|tx| tx.input_value).sum()
+    }
+
+    pub fn fee(&self) -> u64 {
+        self.transactions.iter().map(|tx| tx.fee).sum()
+    }
+
+    pub fn output_value(&self) -> u64 {
+        self.input_value() - self.fee()
+    }
+}
+
+/// Running weight of a transaction with a single output
+#[derive(Clone, Copy)]
+struct WeightAccumulator {
+    output_weight: u64,
+    input_count: u64,
+    input_weight: u64,
+    legacy_inputs: u64,
+    segwit: bool,
+}
+
+impl WeightAccumulator {
+    fn new(recipient: &ScriptBuf) -> Self {
+        let script_len = recipient.len() as u64;
+        Self {
+            output_weight: 4 * (8 + VarInt(script_len).size() as u64 + script_len),
+            input_count: 0,
+            input_weight: 0,
+            legacy_inputs: 0,
+            segwit: false,
+        }
+    }
+
+    fn add(&mut self, weight: u64, is_segwit: bool) {
+        self.input_count += 1;
+        self.input_weight += weight;
+        if is_segwit {
+            self.segwit = true;
+        } else {
+            self.legacy_inputs += 1;
+        }
+    }
+
+    fn weight(&self) -> u64 {
+        let mut weight = TX_BASE_WEIGHT
+            + 4 * VarInt(self.input_count).size() as u64
+            + self.input_weight
+            + self.output_weight;
+        if self.segwit {
+            // Inputs without a witness still have an empty witness stack
+            weight += SEGWIT_MARKER_WEIGHT + self.legacy_inputs;
+        }
+        weight
+    }
+}
+
+/// Split `unspents` into the smallest number of in-order batches whose transactions stay
+/// within `max_weight`. Returns the index ranges and the weight of each batch.
+fn split_unspents(
+    unspents: &[WalletUnspent],
+    input_weights: &[u64],
+    recipient: &ScriptBuf,
+    max_weight: u64,
+) -> Result<Vec<(std::ops::Range<usize>, u64)>, SweepError> {
+    let mut batches = vec![];
+    let mut start = 0;
+    let mut current = WeightAccumulator::new(recipient);
+    for (unspent_index, unspent) in unspents.iter().enumerate() {
+        let is_segwit = unspent.script_type != OutputScriptType::P2sh;
+        let mut next = current;
+        next.add(input_weights[unspent_index], is_segwit);
+        if next.weight() > max_weight && unspent_index > start {
+            // Close the current transaction and start the next one with this unspent
+            batches.push((start..unspent_index, current.weight()));
+            start = unspent_index;
+            next = WeightAccumulator::new(recipient);
+            next.add(input_weights[unspent_index], is_segwit);
+        }
+        if next.weight() > max_weight {
+            return Err(SweepError::UnspentTooHeavy {
+                unspent_index,
+                weight: next.weight(),
+                max_weight,
+            });
+        }
+        current = next;
+    }
+    if start < unspents.len() {
+        batches.push((start..unspents.len(), current.weight()));
+    }
+    Ok(batches)
+}
+
+/// Build the unsigned PSBTs sweeping `unspents` to [`SweepOptions::recipient`]
+///
+/// `input_weight` returns the maximum weight of the signed input spending an unspent. The fee
+/// of each transaction is the fee rate times its maximum virtual size, rounded up.
+pub fn plan_sweep<F>(
+    network: Network,
+    wallet_keys: &RootWalletKeys,
+    unspents: &[WalletUnspent],
+    options: &SweepOptions,
+    input_weight: F,
+) -> Result<SweepPlan, SweepError>
+where
+    F: Fn(&WalletUnspent) -> Result<u64, String>,
+{
+    if !batch::is_valid_fee_rate(options.fee_rate) {
+        return Err(SweepError::InvalidFeeRate(options.fee_rate));
+    }
+    let max_weight = options.max_weight.unwrap_or(MAX_STANDARD_TX_WEIGHT);
+    let input_weights = unspents
+        .iter()
+        .enumerate()
+        .map(|(unspent_index, unspent)| {
+            input_weight(unspent).map_err(|message| SweepError::InputWeight {
+                unspent_index,
+                message,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut plan = SweepPlan {
+        psbts: vec![],
+        transactions: vec![],
+    };
+    for (psbt_index, (range, weight)) in
+        split_unspents(unspents, &input_weights, &options.recipient, max_weight)?
+            .into_iter()
+            .enumerate()
+    {
+        let psbt_error = |message: String| SweepError::Psbt {
+            psbt_index,
+            message,
+        };
+        let unspents = &unspents[range];
+        let mut psbt = batch::batch_psbt(
+            network,
+            wallet_keys,
+            unspents,
+            options.sign_path,
+            options.sequence,
+        )
+        .map_err(psbt_error)?;
+
+        let input_value: u64 = unspents.iter().map(|unspent| unspent.value).sum();
+        let vsize = weight.div_ceil(4);
+        let fee = batch::fee_for_vsize(options.fee_rate, vsize);
+        let output_value = batch::output_value(network, &options.recipient, input_value, fee)
+            .ok_or(SweepError::InsufficientValue {
+                psbt_index,
+                value: input_value,
+                fee,
+            })?;
+        psbt.add_output(options.recipient.clone(), output_value)
+            .map_err(psbt_error)?;
+
+        plan.psbts.push(psbt);
+        plan.transactions.push(SweepTransaction {
+            input_count: unspents.len(),
+            weight,
+            vsize,
+            input_value,
+            fee,
+        });
+    }
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+    use crate::fixed_script_wallet::{Chain, ScriptId};
+    use miniscript::bitcoin::hashes::Hash;
+    use miniscript::bitcoin::{Txid, WScriptHash};
+
+    fn unspent(vout: u32, chain: u32, value: u64) -> WalletUnspent {
+        WalletUnspent {
+            txid: Txid::all_zeros(),
+            vout,
+            value,
+            script_id: ScriptId { chain, index: vout },
+            script_type: Chain::try_from(chain).unwrap().script_type,
+        }
+    }
+
+    fn options(max_weight: Option<u64>) -> SweepOptions {
+        SweepOptions {
+            recipient: ScriptBuf::new_p2wsh(&WScriptHash::all_zeros()),
+            fee_rate: 2.0,
+            max_weight,
+            sign_path: None,
+            sequence: None,
+        }
+    }
+
+    /// 1000 WU per input
+    fn input_weight(_: &WalletUnspent) -> Result<u64, String> {
+        Ok(1000)
+    }
+
+    #[test]
+    fn test_weight_accumulator() {
+        let recipient = options(None).recipient;
+        let mut acc = WeightAccumulator::new(&recipient);
+        acc.add(1000, false);
+        // 10 bytes of overhead, 43 bytes of p2wsh output
+        assert_eq!(acc.weight(), 4 * 10 + 4 * 43 + 1000);
+        acc.add(1000, true);
+        assert_eq!(acc.weight(), 4 * 10 + 4 * 43 + 2000 + 2 + 1);
+    }
+
+    #[test]
+    fn test_plan_sweep() {
+        let wallet_keys = get_test_wallet_keys("sweep");
+        let unspents: Vec<_> = (0..5).map(|vout| unspent(vout, 0, 100_000)).collect();
+        // Overhead and output weigh 212 WU, so two inputs fit in 2212 WU
+        let plan = plan_sweep(
+            Network::Bitcoin,
+            &wallet_keys,
+            &unspents,
+            &options(Some(2212)),
+            input_weight,
+        )
+        .unwrap();
+
+        assert_eq!(plan.psbts.len(), 3);
+        assert_eq!(
+            plan.transactions
+                .iter()
+                .map(|tx| tx.input_count)
+                .collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+        assert_eq!(
+            plan.transactions[0],
+            SweepTransaction {
+                input_count: 2,
+                weight: 2212,
+                vsize: 553,
+                input_value: 200_000,
+                fee: 1106,
+            }
+        );
+        for (psbt, tx) in plan.psbts.iter().zip(&plan.transactions) {
+            let inner = psbt.psbt();
+            assert_eq!(inner.inputs.len(), tx.input_count);
+            assert_eq!(inner.unsigned_tx.output.len(), 1);
+            assert_eq!(
+                inner.unsigned_tx.output[0].script_pubkey,
+                options(None).recipient
+            );
+            assert_eq!(
+                inner.unsigned_tx.output[0].value.to_sat(),
+                tx.output_value()
+            );
+        }
+        assert_eq!(plan.input_value(), 500_000);
+        assert_eq!(plan.output_value(), plan.input_value() - plan.fee());
+
+        // Everything fits below the standard limit
+        let plan = plan_sweep(
+            Network::Bitcoin,
+            &wallet_keys,
+            &unspents,
+            &options(None),
+            input_weight,
+        )
+        .unwrap();
+        assert_eq!(plan.psbts.len(), 1);
+    }
+
+    #[test]
+    fn test_plan_sweep_errors() {
+        let wallet_keys = get_test_wallet_keys("sweep");
+        let plan = |unspents: &[WalletUnspent], options: &SweepOptions| {
+            plan_sweep(
+                Network::Bitcoin,
+                &wallet_keys,
+                unspents,
+                options,
+                input_weight,
+            )
+        };
+
+        assert!(matches!(
+            plan(&[unspent(0, 0, 100_000)], &options(Some(1000))),
+            Err(SweepError::UnspentTooHeavy {
+                unspent_index: 0,
+                weight: 1212,
+                max_weight: 1000
+            })
+        ));
+        assert!(matches!(
+            plan(&[unspent(0, 0, 500)], &options(None)),
+            Err(SweepError::InsufficientValue {
+                psbt_index: 0,
+                value: 500,
+                fee: 606
+            })
+        ));
+        // 0.01 DOGE dust limit instead of the 330 sat of a p2wsh output
+        assert!(matches!(
+            plan_sweep(
+                Network::Dogecoin,
+                &wallet_keys,
+                &[unspent(0, 0, 1_000_000)],
+                &options(None),
+                input_weight,
+            ),
+            Err(SweepError::InsufficientValue {
+                psbt_index: 0,
+                value: 1_000_000,
+                fee: 606
+            })
+        ));
+        let mut invalid = options(None);
+        invalid.fee_rate = f64::NAN;
+        assert!(matches!(
+            plan(&[], &invalid),
+            Err(SweepError::InvalidFeeRate(_))
+        ));
+    }
+}
@@ -0,0 +1,234 @@
+//! Proptest strategies for BitGo fixed-script PSBTs
+//!
+//! [`arb_psbt_spec`] generates a description of a wallet PSBT (network, wallet inputs and
+//! outputs, external outputs and which wallet keys have signed); [`PsbtSpec::build`] turns it
+//! into a [`BitGoPsbt`] using the API that production callers use.
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::sample::select;
+
+use crate::bitcoin::hashes::Hash;
+use crate::bitcoin::{PubkeyHash, ScriptBuf, Txid, WScriptHash};
+use crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::{SignPath, SignerKey};
+use crate::fixed_script_wallet::bitgo_psbt::{BitGoPsbt, SequenceMode, WalletInputOptions};
+use crate::fixed_script_wallet::wallet_keys::tests::{get_test_wallet_keys, get_test_wallet_xprvs};
+use crate::fixed_script_wallet::wallet_scripts::OutputScriptType;
+use crate::fixed_script_wallet::{Chain, RootWalletKeys, Scope, ScriptId};
+use crate::zcash::NetworkUpgrade;
+use crate::Network;
+
+/// Seed of the wallet keys used by generated PSBTs
+pub const SEED: &str = "arbitrary_psbt";
+
+/// Keep the sum of generated values well below `u64::MAX`
+const MAX_VALUE: u64 = u64::MAX >> 8;
+
+#[derive(Debug, Clone)]
+pub struct InputSpec {
+    pub txid: [u8; 32],
+    pub vout: u32,
+    pub value: u64,
+    pub script_id: ScriptId,
+    /// Signer and cosigner, used by taproot inputs
+    pub sign_path: (SignerKey, SignerKey),
+    pub sequence: u32,
+}
+
+#[derive(Debug, Clone)]
+pub enum OutputSpec {
+    Wallet { script_id: ScriptId, value: u64 },
+    External { script: ScriptBuf, value: u64 },
+}
+
+impl OutputSpec {
+    pub fn value(&self) -> u64 {
+        match self {
+            OutputSpec::Wallet { value, .. } | OutputSpec::External { value, .. } => *value,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PsbtSpec {
+    pub network: Network,
+    /// Transaction version (ignored for Zcash, which uses the Sapling version)
+    pub version: i32,
+    pub lock_time: u32,
+    /// Network upgrade that selects the consensus branch of Zcash PSBTs
+    pub zcash_upgrade: NetworkUpgrade,
+    pub inputs: Vec<InputSpec>,
+    pub outputs: Vec<OutputSpec>,
+    /// Which of the user, backup and bitgo keys sign all inputs
+    pub signers: [bool; 3],
+}
+
+impl PsbtSpec {
+    pub fn input_total(&self) -> u64 {
+        self.inputs.iter().map(|i| i.value).sum()
+    }
+
+    pub fn output_total(&self) -> u64 {
+        self.outputs.iter().map(OutputSpec::value).sum()
+    }
+
+    /// Build the PSBT and return it together with the wallet keys
+    pub fn build(&self) -> (BitGoPsbt, RootWalletKeys) {
+        let wallet_keys = get_test_wallet_keys(SEED);
+        let mut psbt = match self.network {
+            Network::Zcash | Network::ZcashTestnet => BitGoPsbt::new_zcash_at_height(
+                self.network,
+                &wallet_keys,
+                self.zcash_upgrade
+                    .activation_height(self.network == Network::Zcash),
+                None,
+                Some(self.lock_time),
+                None,
+                None,
+            )
+            .expect("new_zcash_at_height"),
+            network => BitGoPsbt::new(
+                network,
+                &wallet_keys,
+                Some(self.version),
+                Some(self.lock_time),
+            ),
+        };
+
+        for input in &self.inputs {
+            let (signer, cosigner) = input.sign_path;
+            psbt.add_wallet_input(
+                Txid::from_byte_array(input.txid),
+                input.vout,
+                input.value,
+                &wallet_keys,
+                input.script_id,
+                WalletInputOptions {
+                    sign_path: Some(SignPath { signer, cosigner }),
+                    sequence: Some(SequenceMode::from_consensus(input.sequence)),
+                    prev_tx: None,
+                },
+            )
+            .expect("add_wallet_input");
+        }
+
+        for output in &self.outputs {
+            match output {
+                OutputSpec::Wallet { script_id, value } => {
+                    psbt.add_wallet_output(script_id.chain, script_id.index, *value, &wallet_keys)
+                        .expect("add_wallet_output");
+                }
+                OutputSpec::External { script, value } => {
+                    psbt.add_output(script.clone(), *value).expect("add_output");
+                }
+            }
+        }
+
+        let xprvs = get_test_wallet_xprvs(SEED);
+        for (xpriv, _) in xprvs.iter().zip(self.signers).filter(|(_, sign)| *sign) {
+            psbt.sign_all_with_xpriv(xpriv)
+                .expect("sign_all_with_xpriv");
+        }
+
+        (psbt, wallet_keys)
+    }
+}
+
+pub fn arb_network() -> impl Strategy<Value = Network> {
+    select(Network::ALL)
+}
+
+/// Wallet chains whose script type is supported on `network`
+pub fn arb_chain(network: Network) -> impl Strategy<Value = Chain> {
+    let support = network.output_script_support();
+    let chains: Vec<Chain> = OutputScriptType::all()
+        .iter()
+        .filter(|script_type| support.supports_script_type(**script_type))
+        .flat_map(|script_type| {
+            [Scope::External, Scope::Internal].map(|scope| Chain::new(*script_type, scope))
+        })
+        .collect();
+    select(chains)
+}
+
+pub fn arb_script_id(network: Network) -> impl Strategy<Value = ScriptId> {
+    (arb_chain(network), 0u32..1_000).prop_map(|(chain, index)| ScriptId {
+        chain: chain.value(),
+        index,
+    })
+}
+
+pub fn arb_sign_path() -> impl Strategy<Value = (SignerKey, SignerKey)> {
+    select(vec![
+        (SignerKey::User, SignerKey::Bitgo),
+        (SignerKey::User, SignerKey::Backup),
+        (SignerKey::Backup, SignerKey::Bitgo),
+    ])
+}
+
+pub fn arb_input(network: Network) -> impl Strategy<Value = InputSpec> {
+    (
+        any::<[u8; 32]>(),
+        0u32..16,
+        1..=MAX_VALUE,
+        arb_script_id(network),
+        arb_sign_path(),
+        prop_oneof![Just(0xFFFF_FFFF), Just(0xFFFF_FFFE), any::<u32>()],
+    )
+        .prop_map(
+            |(txid, vout, value, script_id, sign_path, sequence)| InputSpec {
+                txid,
+                vout,
+                value,
+                script_id,
+                sign_path,
+                sequence,
+            },
+        )
+}
+
+pub fn arb_external_script() -> impl Strategy<Value = ScriptBuf> {
+    prop_oneof![
+        any::<[u8; 20]>().prop_map(|h| ScriptBuf::new_p2pkh(&PubkeyHash::from_byte_array(h))),
+        any::<[u8; 32]>().prop_map(|h| ScriptBuf::new_p2wsh(&WScriptHash::from_byte_array(h))),
+        vec(any::<u8>(), 0..40).prop_map(|data| {
+            let data: &crate::bitcoin::script::PushBytes =
+                data.as_slice().try_into().expect("short push");
+            ScriptBuf::new_op_return(data)
+        }),
+    ]
+}
+
+pub fn arb_output(network: Network) -> impl Strategy<Value = OutputSpec> {
+    prop_oneof![
+        (arb_script_id(network), 0..=MAX_VALUE)
+            .prop_map(|(script_id, value)| OutputSpec::Wallet { script_id, value }),
+        (arb_external_script(), 0..=MAX_VALUE)
+            .prop_map(|(script, value)| OutputSpec::External { script, value }),
+    ]
+}
+
+pub fn arb_psbt_spec() -> impl Strategy<Value = PsbtSpec> {
+    arb_network().prop_flat_map(|network| {
+        (
+            1i32..=2,
+            prop_oneof![Just(0u32), any::<u32>()],
+            // Sapling and later
+            select(&NetworkUpgrade::ALL[1..]),
+            vec(arb_input(network), 1..4),
+            vec(arb_output(network), 0..4),
+            any::<[bool; 3]>(),
+        )
+            .prop_map(
+                move |(version, lock_time, zcash_upgrade, inputs, outputs, signers)| PsbtSpec {
+                    network,
+                    version,
+                    lock_time,
+                    zcash_upgrade,
+                    inputs,
+                    outputs,
+                    signers,
+                },
+            )
+    })
+}
@@ -1,7 +1,9 @@
 //! Test utilities for fixed_script_wallet module
 
+pub mod arbitrary;
 pub mod fixtures;
 pub mod psbt_compare;
+pub mod wallet_psbt;
 
 use super::script_id::{Chain, Scope};
 use super::wallet_keys::XpubTriple;
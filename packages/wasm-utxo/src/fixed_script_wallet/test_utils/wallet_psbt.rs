@@ -0,0 +1,115 @@
+//! Builder of small wallet PSBTs for unit tests
+//!
+//! Inputs spend outputs of `Txid::all_zeros()` at consecutive vouts, so that tests only
+//! list the scripts they need. Taproot inputs use the user and bitgo keys unless the test
+//! passes other options.
+//!
+//! ```ignore
+//! let psbt = WalletPsbtBuilder::new(Network::Bitcoin, &wallet_keys)
+//!     .inputs(4, 20)
+//!     .input(40, 0)
+//!     .output(20, 0, 1_000)
+//!     .build();
+//! ```
+
+use crate::bitcoin::hashes::Hash;
+use crate::bitcoin::Txid;
+use crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::{SignPath, SignerKey};
+use crate::fixed_script_wallet::bitgo_psbt::{BitGoPsbt, WalletInputOptions};
+use crate::fixed_script_wallet::wallet_scripts::OutputScriptType;
+use crate::fixed_script_wallet::{Chain, RootWalletKeys, ScriptId};
+use crate::Network;
+
+/// Value of inputs added without an explicit value
+pub const DEFAULT_INPUT_VALUE: u64 = 10_000;
+
+/// Input options with the user and bitgo sign path for taproot chains
+pub fn default_input_options<'a>(chain: u32) -> WalletInputOptions<'a> {
+    let is_taproot = Chain::try_from(chain).is_ok_and(|chain| {
+        matches!(
+            chain.script_type,
+            OutputScriptType::P2trLegacy | OutputScriptType::P2trMusig2 | OutputScriptType::P2mr
+        )
+    });
+    WalletInputOptions {
+        sign_path: is_taproot.then_some(SignPath {
+            signer: SignerKey::User,
+            cosigner: SignerKey::Bitgo,
+        }),
+        ..Default::default()
+    }
+}
+
+/// Wallet inputs and outputs to add to a new PSBT
+pub struct WalletPsbtBuilder<'a> {
+    network: Network,
+    wallet_keys: &'a RootWalletKeys,
+    inputs: Vec<(ScriptId, u64, WalletInputOptions<'a>)>,
+    outputs: Vec<(ScriptId, u64)>,
+}
+
+impl<'a> WalletPsbtBuilder<'a> {
+    pub fn new(network: Network, wallet_keys: &'a RootWalletKeys) -> Self {
+        Self {
+            network,
+            wallet_keys,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+        }
+    }
+
+    /// Add an input spending the wallet script at `chain/index`
+    pub fn input(self, chain: u32, index: u32) -> Self {
+        self.input_with(
+            chain,
+            index,
+            DEFAULT_INPUT_VALUE,
+            default_input_options(chain),
+        )
+    }
+
+    /// Add `count` inputs spending the wallet script at `chain/0`
+    pub fn inputs(self, count: u32, chain: u32) -> Self {
+        (0..count).fold(self, |builder, _| builder.input(chain, 0))
+    }
+
+    /// Add an input with an explicit value and options
+    pub fn input_with(
+        mut self,
+        chain: u32,
+        index: u32,
+        value: u64,
+        options: WalletInputOptions<'a>,
+    ) -> Self {
+        self.inputs
+            .push((ScriptId { chain, index }, value, options));
+        self
+    }
+
+    /// Add an output paying `value` to the wallet script at `chain/index`
+    pub fn output(mut self, chain: u32, index: u32, value: u64) -> Self {
+        self.outputs.push((ScriptId { chain, index }, value));
+        self
+    }
+
+    /// Create the PSBT, panicking if an input or output cannot be added
+    pub fn build(&self) -> BitGoPsbt {
+        let mut psbt = BitGoPsbt::new(self.network, self.wallet_keys, None, None);
+        for (vout, (script_id, value, options)) in self.inputs.iter().enumerate() {
+            psbt.add_wallet_input(
+                Txid::all_zeros(),
+                vout as u32,
+                *value,
+                self.wallet_keys,
+                *script_id,
+                options.clone(),
+            )
+            .expect("add_wallet_input");
+        }
+        for (script_id, value) in &self.outputs {
+            psbt.add_wallet_output(script_id.chain, script_id.index, *value, self.wallet_keys)
+                .expect("add_wallet_output");
+        }
+        psbt
+    }
+}
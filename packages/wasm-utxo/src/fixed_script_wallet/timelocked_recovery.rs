@@ -0,0 +1,367 @@
+//! Degrading multisig wallets with a timelocked recovery path
+//!
+//! A [`TimelockedRecoveryWallet`] uses the keys of a [`RootWalletKeys`] wallet in a p2wsh
+//! script that is spendable 2-of-3 at any time, and by the backup key alone once the output
+//! is `delay` blocks old:
+//!
+//! ```text
+//! wsh(or_d(multi(2,user,backup,bitgo),and_v(v:pk(backup),older(delay))))
+//! ```
+//!
+//! The user can recover the funds with the backup key without a cosigner. Scripts are
+//! derived at `prefix/chain/index` like the other wallet scripts, with their own chain codes
+//! [`CHAIN_EXTERNAL`] and [`CHAIN_INTERNAL`].
+
+use std::collections::BTreeMap;
+
+use crate::address::networks::OutputScriptSupport;
+use crate::bitcoin::bip32::KeySource;
+use crate::bitcoin::blockdata::opcodes::all::{
+    OP_CHECKMULTISIG, OP_CHECKSIGVERIFY, OP_CSV, OP_ENDIF, OP_IFDUP, OP_NOTIF,
+};
+use crate::bitcoin::blockdata::script::Builder;
+use crate::bitcoin::psbt::{Input, Output};
+use crate::bitcoin::secp256k1::PublicKey;
+use crate::bitcoin::{ScriptBuf, Sequence};
+use crate::fixed_script_wallet::descriptor::key_expression;
+use crate::fixed_script_wallet::wallet_keys::{derivation_path, to_pub_triple, RootWalletKeys};
+use crate::fixed_script_wallet::wallet_scripts::{chain_index_path, path_chain_index};
+use crate::fixed_script_wallet::ScriptId;
+
+/// Chain code of receive addresses
+pub const CHAIN_EXTERNAL: u32 = 50;
+/// Chain code of change addresses
+pub const CHAIN_INTERNAL: u32 = 51;
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum TimelockedRecoveryError {
+    /// The relative timelock must be at least one block
+    InvalidDelay,
+    InvalidChain(u32),
+    /// The utxo or output script of a PSBT entry differs from the wallet script
+    ScriptMismatch {
+        chain: u32,
+        index: u32,
+    },
+    /// Key derivation or network support error
+    Script(String),
+}
+
+impl std::fmt::Display for TimelockedRecoveryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimelockedRecoveryError::InvalidDelay => {
+                write!(f, "Recovery delay must be at least one block")
+            }
+            TimelockedRecoveryError::InvalidChain(chain) => {
+                write!(f, "Invalid timelocked recovery chain: {}", chain)
+            }
+            TimelockedRecoveryError::ScriptMismatch { chain, index } => write!(
+                f,
+                "Output script does not match the wallet script at {}/{}",
+                chain, index
+            ),
+            TimelockedRecoveryError::Script(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for TimelockedRecoveryError {}
+
+crate::impl_wasm_error_code!(TimelockedRecoveryError);
+
+/// A 2-of-3 wallet that degrades to the backup key after a relative timelock
+#[derive(Debug, Clone)]
+pub struct TimelockedRecoveryWallet {
+    wallet_keys: RootWalletKeys,
+    delay: u16,
+}
+
+impl TimelockedRecoveryWallet {
+    /// Create a wallet whose backup key can spend alone `delay` blocks after confirmation
+    pub fn new(wallet_keys: RootWalletKeys, delay: u16) -> Result<Self, TimelockedRecoveryError> {
+        if delay == 0 {
+            return Err(TimelockedRecoveryError::InvalidDelay);
+        }
+        Ok(Self { wallet_keys, delay })
+    }
+
+    pub fn wallet_keys(&self) -> &RootWalletKeys {
+        &self.wallet_keys
+    }
+
+    /// Relative timelock of the recovery path in blocks
+    pub fn delay(&self) -> u16 {
+        self.delay
+    }
+
+    /// Minimum input sequence of a spend through the recovery path
+    pub fn recovery_sequence(&self) -> Sequence {
+        Sequence::from_height(self.delay)
+    }
+
+    fn check_chain(chain: u32) -> Result<(), TimelockedRecoveryError> {
+        match chain {
+            CHAIN_EXTERNAL | CHAIN_INTERNAL => Ok(()),
+            _ => Err(TimelockedRecoveryError::InvalidChain(chain)),
+        }
+    }
+
+    /// Build the witness script at `chain/index`
+    ///
+    /// `<2> <user> <backup> <bitgo> <3> OP_CHECKMULTISIG OP_IFDUP OP_NOTIF
+    /// <backup> OP_CHECKSIGVERIFY <delay> OP_CHECKSEQUENCEVERIFY OP_ENDIF`
+    pub fn witness_script(
+        &self,
+        chain: u32,
+        index: u32,
+    ) -> Result<ScriptBuf, TimelockedRecoveryError> {
+        Self::check_chain(chain)?;
+        let derived = self
+            .wallet_keys
+            .derive_path(&chain_index_path(chain, index))
+            .map_err(|e| TimelockedRecoveryError::Script(e.to_string()))?;
+        let [user, backup, bitgo] = to_pub_triple(&derived);
+        Ok(Builder::new()
+            .push_int(2)
+            .push_slice(user.to_bytes())
+            .push_slice(backup.to_bytes())
+            .push_slice(bitgo.to_bytes())
+            .push_int(3)
+            .push_opcode(OP_CHECKMULTISIG)
+            .push_opcode(OP_IFDUP)
+            .push_opcode(OP_NOTIF)
+            .push_slice(backup.to_bytes())
+            .push_opcode(OP_CHECKSIGVERIFY)
+            .push_int(self.delay as i64)
+            .push_opcode(OP_CSV)
+            .push_opcode(OP_ENDIF)
+            .into_script())
+    }
+
+    /// Build the p2wsh output script at `chain/index`
+    pub fn output_script(
+        &self,
+        chain: u32,
+        index: u32,
+        script_support: &OutputScriptSupport,
+    ) -> Result<ScriptBuf, TimelockedRecoveryError> {
+        script_support
+            .assert_segwit()
+            .map_err(|e| TimelockedRecoveryError::Script(e.to_string()))?;
+        Ok(self.witness_script(chain, index)?.to_p2wsh())
+    }
+
+    /// Ranged output descriptor for the scripts of `chain` (without checksum)
+    pub fn descriptor(&self, chain: u32) -> Result<String, TimelockedRecoveryError> {
+        Self::check_chain(chain)?;
+        let [user, backup, bitgo]: [String; 3] = std::array::from_fn(|i| {
+            key_expression(
+                &self.wallet_keys.xpubs[i],
+                &self.wallet_keys.derivation_prefixes[i],
+                chain,
+            )
+        });
+        Ok(format!(
+            "wsh(or_d(multi(2,{},{},{}),and_v(v:pk({}),older({}))))",
+            user, backup, bitgo, backup, self.delay
+        ))
+    }
+
+    /// BIP32 derivations of the keys at `chain/index`, keyed by derived public key
+    pub fn bip32_derivation(
+        &self,
+        chain: u32,
+        index: u32,
+    ) -> Result<BTreeMap<PublicKey, KeySource>, TimelockedRecoveryError> {
+        Self::check_chain(chain)?;
+        let derived = self
+            .wallet_keys
+            .derive_path(&chain_index_path(chain, index))
+            .map_err(|e| TimelockedRecoveryError::Script(e.to_string()))?;
+        Ok(self
+            .wallet_keys
+            .xpubs
+            .iter()
+            .zip(&self.wallet_keys.derivation_prefixes)
+            .zip(to_pub_triple(&derived))
+            .map(|((xpub, prefix), key)| {
+                (
+                    key.0,
+                    (xpub.fingerprint(), derivation_path(prefix, chain, index)),
+                )
+            })
+            .collect())
+    }
+
+    /// Identify a script of this wallet from the derivations recorded in a PSBT input or output
+    ///
+    /// Returns `None` if a derivation belongs to a different key, the recorded chain is not a
+    /// timelocked recovery chain, or the script at the recorded chain and index does not match
+    /// `output_script`.
+    pub fn find_script_id(
+        &self,
+        bip32_derivation: &BTreeMap<PublicKey, KeySource>,
+        output_script: &ScriptBuf,
+        script_support: &OutputScriptSupport,
+    ) -> Option<ScriptId> {
+        let is_own_key = |(fingerprint, _): &KeySource| {
+            self.wallet_keys
+                .xpubs
+                .iter()
+                .any(|xpub| xpub.fingerprint() == *fingerprint)
+        };
+        if bip32_derivation.is_empty() || !bip32_derivation.values().all(is_own_key) {
+            return None;
+        }
+        let (_, path) = bip32_derivation.values().next()?;
+        let (chain, index) = path_chain_index(path)?;
+        let script = self.output_script(chain, index, script_support).ok()?;
+        (script == *output_script).then_some(ScriptId { chain, index })
+    }
+
+    /// Set the witness script and key derivations of a PSBT input spending `script_id`
+    ///
+    /// If the input has a `witness_utxo`, its script must be the wallet script.
+    pub fn update_input(
+        &self,
+        input: &mut Input,
+        script_id: ScriptId,
+    ) -> Result<(), TimelockedRecoveryError> {
+        let ScriptId { chain, index } = script_id;
+        let witness_script = self.witness_script(chain, index)?;
+        if let Some(utxo) = &input.witness_utxo {
+            if utxo.script_pubkey != witness_script.to_p2wsh() {
+                return Err(TimelockedRecoveryError::ScriptMismatch { chain, index });
+            }
+        }
+        input.bip32_derivation = self.bip32_derivation(chain, index)?;
+        input.witness_script = Some(witness_script);
+        Ok(())
+    }
+
+    /// Set the witness script and key derivations of a PSBT output paying to `script_id`
+    ///
+    /// `output_script` is the script of the corresponding transaction output.
+    pub fn update_output(
+        &self,
+        output: &mut Output,
+        output_script: &ScriptBuf,
+        script_id: ScriptId,
+    ) -> Result<(), TimelockedRecoveryError> {
+        let ScriptId { chain, index } = script_id;
+        let witness_script = self.witness_script(chain, index)?;
+        if *output_script != witness_script.to_p2wsh() {
+            return Err(TimelockedRecoveryError::ScriptMismatch { chain, index });
+        }
+        output.bip32_derivation = self.bip32_derivation(chain, index)?;
+        output.witness_script = Some(witness_script);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::{Amount, TxOut};
+    use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+    use crate::fixed_script_wallet::WalletPolicy;
+    use crate::Network;
+    use miniscript::{Descriptor, DescriptorPublicKey};
+    use std::str::FromStr;
+
+    fn get_test_wallet() -> TimelockedRecoveryWallet {
+        TimelockedRecoveryWallet::new(get_test_wallet_keys("timelocked_recovery"), 144).unwrap()
+    }
+
+    #[test]
+    fn test_scripts_match_descriptor() {
+        let wallet = get_test_wallet();
+        let support = Network::Bitcoin.output_script_support();
+        for chain in [CHAIN_EXTERNAL, CHAIN_INTERNAL] {
+            let descriptor =
+                Descriptor::<DescriptorPublicKey>::from_str(&wallet.descriptor(chain).unwrap())
+                    .unwrap();
+            for index in [0, 7] {
+                let derived = descriptor.at_derivation_index(index).unwrap();
+                assert_eq!(
+                    derived.explicit_script().unwrap(),
+                    wallet.witness_script(chain, index).unwrap()
+                );
+                assert_eq!(
+                    derived.script_pubkey(),
+                    wallet.output_script(chain, index, &support).unwrap()
+                );
+            }
+        }
+        assert!(matches!(
+            wallet.witness_script(20, 0),
+            Err(TimelockedRecoveryError::InvalidChain(20))
+        ));
+        assert!(wallet
+            .output_script(
+                CHAIN_EXTERNAL,
+                0,
+                &Network::Dogecoin.output_script_support()
+            )
+            .is_err());
+        assert!(matches!(
+            TimelockedRecoveryWallet::new(get_test_wallet_keys("timelocked_recovery"), 0),
+            Err(TimelockedRecoveryError::InvalidDelay)
+        ));
+        assert_eq!(wallet.recovery_sequence(), Sequence(144));
+    }
+
+    #[test]
+    fn test_psbt_metadata() {
+        let wallet = get_test_wallet();
+        let support = Network::Bitcoin.output_script_support();
+        let script_id = ScriptId {
+            chain: CHAIN_INTERNAL,
+            index: 3,
+        };
+        let script = wallet.output_script(CHAIN_INTERNAL, 3, &support).unwrap();
+
+        let mut output = Output::default();
+        wallet
+            .update_output(&mut output, &script, script_id)
+            .unwrap();
+        assert_eq!(output.bip32_derivation.len(), 3);
+        assert_eq!(
+            wallet.find_script_id(&output.bip32_derivation, &script, &support),
+            Some(script_id)
+        );
+        let other_script = wallet.output_script(CHAIN_INTERNAL, 4, &support).unwrap();
+        assert_eq!(
+            wallet.find_script_id(&output.bip32_derivation, &other_script, &support),
+            None
+        );
+        assert!(matches!(
+            wallet.update_output(&mut Output::default(), &other_script, script_id),
+            Err(TimelockedRecoveryError::ScriptMismatch { .. })
+        ));
+
+        let mut input = Input {
+            witness_utxo: Some(TxOut {
+                value: Amount::from_sat(10_000),
+                script_pubkey: script.clone(),
+            }),
+            ..Default::default()
+        };
+        wallet.update_input(&mut input, script_id).unwrap();
+        assert_eq!(input.witness_script, output.witness_script);
+        assert_eq!(input.bip32_derivation, output.bip32_derivation);
+
+        // Standard wallet scripts of the same keys are not timelocked recovery scripts
+        let policy = WalletPolicy::from(wallet.wallet_keys());
+        let p2wsh = policy.wallet_scripts(21, 3, &support).unwrap();
+        assert_eq!(
+            wallet.find_script_id(
+                &policy.bip32_derivation(21, 3).unwrap(),
+                &p2wsh.output_script(),
+                &support
+            ),
+            None
+        );
+    }
+}
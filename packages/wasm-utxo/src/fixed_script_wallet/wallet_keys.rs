@@ -1,5 +1,3 @@
-use std::cell::RefCell;
-use std::collections::HashMap;
 use std::convert::TryInto;
 use std::str::FromStr;
 
@@ -26,16 +24,11 @@ pub fn derivation_path(prefix: &DerivationPath, chain: u32, index: u32) -> Deriv
         .child(ChildNumber::Normal { index })
 }
 
-/// Maximum number of (chain, index) pairs to cache
-const DERIVATION_CACHE_MAX_SIZE: usize = 128;
-
 pub struct RootWalletKeys {
     pub xpubs: XpubTriple,
     pub derivation_prefixes: [DerivationPath; 3],
     /// Keys derived to prefix level (computed once in constructor)
     prefix_derived: XpubTriple,
-    /// Keys derived to (chain, index) level (cached on-demand, bounded size)
-    derivation_cache: RefCell<HashMap<(u32, u32), XpubTriple>>,
     /// Shared secp256k1 context (avoids repeated allocation)
     secp: Secp256k1<crate::bitcoin::secp256k1::All>,
 }
@@ -63,7 +56,6 @@ impl RootWalletKeys {
             xpubs,
             derivation_prefixes,
             prefix_derived,
-            derivation_cache: RefCell::new(HashMap::new()),
             secp,
         }
     }
@@ -92,42 +84,26 @@ impl RootWalletKeys {
     }
 
     /// Derive keys from `self.prefix_derived` along `path`.
-    /// Caches using the last two Normal path components as `(chain, index)`.
+    /// Two-component `(chain, index)` paths are memoized in the shared
+    /// [`derivation_cache`](super::derivation_cache).
     pub fn derive_path(&self, path: &DerivationPath) -> Result<XpubTriple, WasmUtxoError> {
-        let cache_key: Option<(u32, u32)> = {
-            let children: Vec<_> = path.into_iter().cloned().collect();
-            let n = children.len();
-            match (children.get(n.wrapping_sub(2)), children.last()) {
-                (
-                    Some(ChildNumber::Normal { index: c }),
-                    Some(ChildNumber::Normal { index: i }),
-                ) => Some((*c, *i)),
-                _ => None,
-            }
+        let derive = || -> Result<XpubTriple, WasmUtxoError> {
+            self.prefix_derived
+                .iter()
+                .map(|xpub| {
+                    xpub.derive_pub(&self.secp, path)
+                        .map_err(|e| WasmUtxoError::new(&format!("Error deriving xpub: {}", e)))
+                })
+                .collect::<Result<Vec<_>, _>>()?
+                .try_into()
+                .map_err(|_| WasmUtxoError::new("Expected exactly 3 derived xpubs"))
         };
-        if let Some(key) = cache_key {
-            if let Some(cached) = self.derivation_cache.borrow().get(&key) {
-                return Ok(*cached);
-            }
-        }
-        let derived: XpubTriple = self
-            .prefix_derived
-            .iter()
-            .map(|xpub| {
-                xpub.derive_pub(&self.secp, path)
-                    .map_err(|e| WasmUtxoError::new(&format!("Error deriving xpub: {}", e)))
-            })
-            .collect::<Result<Vec<_>, _>>()?
-            .try_into()
-            .map_err(|_| WasmUtxoError::new("Expected exactly 3 derived xpubs"))?;
-        if let Some(key) = cache_key {
-            let mut cache = self.derivation_cache.borrow_mut();
-            if cache.len() >= DERIVATION_CACHE_MAX_SIZE {
-                cache.clear();
+        match path.as_ref() {
+            [ChildNumber::Normal { index: chain }, ChildNumber::Normal { index }] => {
+                super::derivation_cache::get_or_derive(&self.prefix_derived, *chain, *index, derive)
             }
-            cache.insert(key, derived);
+            _ => derive(),
         }
-        Ok(derived)
     }
 }
 
@@ -137,7 +113,6 @@ impl Clone for RootWalletKeys {
             xpubs: self.xpubs,
             derivation_prefixes: self.derivation_prefixes.clone(),
             prefix_derived: self.prefix_derived,
-            derivation_cache: RefCell::new(self.derivation_cache.borrow().clone()),
             secp: Secp256k1::new(),
         }
     }
@@ -149,10 +124,6 @@ impl std::fmt::Debug for RootWalletKeys {
             .field("xpubs", &self.xpubs)
             .field("derivation_prefixes", &self.derivation_prefixes)
             .field("prefix_derived", &self.prefix_derived)
-            .field(
-                "derivation_cache_size",
-                &self.derivation_cache.borrow().len(),
-            )
             .finish()
     }
 }
@@ -0,0 +1,401 @@
+//! Parameterized m-of-n multisig wallets
+//!
+//! [`RootWalletKeys`] describes the standard BitGo 2-of-3 wallet (user, backup, bitgo).
+//! A [`WalletPolicy`] generalizes it to a `threshold`-of-`n` wallet with the same chain codes
+//! and derivation layout (`prefix/chain/index`). The multisig script types (p2sh, p2shP2wsh,
+//! p2wsh) support any policy; the taproot and p2mr script types are only defined for 2-of-3
+//! policies, which produce exactly the scripts of the equivalent [`RootWalletKeys`].
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use crate::address::networks::OutputScriptSupport;
+use crate::bitcoin::bip32::{DerivationPath, KeySource, Xpub};
+use crate::bitcoin::secp256k1::{PublicKey, Secp256k1};
+use crate::bitcoin::{CompressedPublicKey, ScriptBuf};
+use crate::fixed_script_wallet::wallet_keys::{derivation_path, RootWalletKeys};
+use crate::fixed_script_wallet::wallet_scripts::{
+    build_multisig_script, chain_index_path, path_chain_index, OutputScriptType, ScriptP2sh,
+    ScriptP2shP2wsh, ScriptP2wsh, WalletScripts,
+};
+use crate::fixed_script_wallet::{Chain, ScriptId};
+
+/// Maximum number of keys in a p2sh multisig, limited by the 520-byte redeem script
+pub const MAX_KEYS_P2SH: usize = 15;
+/// Maximum number of keys of `OP_CHECKMULTISIG`
+pub const MAX_KEYS_SEGWIT: usize = 20;
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum WalletPolicyError {
+    /// The threshold is zero or larger than the number of keys
+    InvalidThreshold {
+        threshold: usize,
+        keys: usize,
+    },
+    /// The number of derivation prefixes differs from the number of keys
+    PrefixCountMismatch {
+        prefixes: usize,
+        keys: usize,
+    },
+    /// The script type cannot hold this many keys
+    TooManyKeys {
+        script_type: OutputScriptType,
+        keys: usize,
+        max: usize,
+    },
+    /// The script type is only defined for 2-of-3 policies
+    UnsupportedScriptType(OutputScriptType),
+    InvalidChain(u32),
+    /// Key derivation or network support error
+    Script(String),
+}
+
+impl std::fmt::Display for WalletPolicyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WalletPolicyError::InvalidThreshold { threshold, keys } => {
+                write!(f, "Invalid threshold {} for {} keys", threshold, keys)
+            }
+            WalletPolicyError::PrefixCountMismatch { prefixes, keys } => {
+                write!(f, "Expected {} derivation prefixes, got {}", keys, prefixes)
+            }
+            WalletPolicyError::TooManyKeys {
+                script_type,
+                keys,
+                max,
+            } => write!(
+                f,
+                "Script type {} supports at most {} keys, policy has {}",
+                script_type, max, keys
+            ),
+            WalletPolicyError::UnsupportedScriptType(script_type) => write!(
+                f,
+                "Script type {} is only supported for 2-of-3 policies",
+                script_type
+            ),
+            WalletPolicyError::InvalidChain(chain) => write!(f, "Invalid chain: {}", chain),
+            WalletPolicyError::Script(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for WalletPolicyError {}
+
+crate::impl_wasm_error_code!(WalletPolicyError);
+
+/// A `threshold`-of-`n` multisig wallet
+#[derive(Debug, Clone)]
+pub struct WalletPolicy {
+    threshold: usize,
+    xpubs: Vec<Xpub>,
+    derivation_prefixes: Vec<DerivationPath>,
+}
+
+impl WalletPolicy {
+    /// Create a policy with the default `m/0/0` derivation prefix for every key
+    pub fn new(threshold: usize, xpubs: Vec<Xpub>) -> Result<Self, WalletPolicyError> {
+        let prefixes = vec![DerivationPath::from_str("m/0/0").unwrap(); xpubs.len()];
+        Self::new_with_derivation_prefixes(threshold, xpubs, prefixes)
+    }
+
+    pub fn new_with_derivation_prefixes(
+        threshold: usize,
+        xpubs: Vec<Xpub>,
+        derivation_prefixes: Vec<DerivationPath>,
+    ) -> Result<Self, WalletPolicyError> {
+        if threshold == 0 || threshold > xpubs.len() {
+            return Err(WalletPolicyError::InvalidThreshold {
+                threshold,
+                keys: xpubs.len(),
+            });
+        }
+        if derivation_prefixes.len() != xpubs.len() {
+            return Err(WalletPolicyError::PrefixCountMismatch {
+                prefixes: derivation_prefixes.len(),
+                keys: xpubs.len(),
+            });
+        }
+        if xpubs.len() > MAX_KEYS_SEGWIT {
+            return Err(WalletPolicyError::TooManyKeys {
+                script_type: OutputScriptType::P2wsh,
+                keys: xpubs.len(),
+                max: MAX_KEYS_SEGWIT,
+            });
+        }
+        Ok(Self {
+            threshold,
+            xpubs,
+            derivation_prefixes,
+        })
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn xpubs(&self) -> &[Xpub] {
+        &self.xpubs
+    }
+
+    pub fn derivation_prefixes(&self) -> &[DerivationPath] {
+        &self.derivation_prefixes
+    }
+
+    pub fn is_2_of_3(&self) -> bool {
+        self.threshold == 2 && self.xpubs.len() == 3
+    }
+
+    /// The equivalent [`RootWalletKeys`] of a 2-of-3 policy
+    pub fn to_root_wallet_keys(&self) -> Option<RootWalletKeys> {
+        if !self.is_2_of_3() {
+            return None;
+        }
+        Some(RootWalletKeys::new_with_derivation_prefixes(
+            self.xpubs.clone().try_into().ok()?,
+            self.derivation_prefixes.clone().try_into().ok()?,
+        ))
+    }
+
+    /// Derive the public keys at `chain/index`, in policy key order
+    pub fn derive_keys(
+        &self,
+        chain: u32,
+        index: u32,
+    ) -> Result<Vec<CompressedPublicKey>, WalletPolicyError> {
+        let secp = Secp256k1::verification_only();
+        self.xpubs
+            .iter()
+            .zip(&self.derivation_prefixes)
+            .map(|(xpub, prefix)| {
+                xpub.derive_pub(&secp, &derivation_path(prefix, chain, index))
+                    .map(|derived| derived.to_pub())
+                    .map_err(|e| WalletPolicyError::Script(format!("Error deriving xpub: {}", e)))
+            })
+            .collect()
+    }
+
+    /// Build the scripts of the wallet at `chain/index`
+    pub fn wallet_scripts(
+        &self,
+        chain: u32,
+        index: u32,
+        script_support: &OutputScriptSupport,
+    ) -> Result<WalletScripts, WalletPolicyError> {
+        let script_type = Chain::try_from(chain)
+            .map_err(|_| WalletPolicyError::InvalidChain(chain))?
+            .script_type;
+
+        if let Some(wallet_keys) = self.to_root_wallet_keys() {
+            return WalletScripts::from_wallet_keys(
+                &wallet_keys,
+                script_type,
+                &chain_index_path(chain, index),
+                script_support,
+            )
+            .map_err(|e| WalletPolicyError::Script(e.to_string()));
+        }
+
+        let max_keys = match script_type {
+            OutputScriptType::P2sh => MAX_KEYS_P2SH,
+            OutputScriptType::P2shP2wsh | OutputScriptType::P2wsh => MAX_KEYS_SEGWIT,
+            _ => return Err(WalletPolicyError::UnsupportedScriptType(script_type)),
+        };
+        if self.xpubs.len() > max_keys {
+            return Err(WalletPolicyError::TooManyKeys {
+                script_type,
+                keys: self.xpubs.len(),
+                max: max_keys,
+            });
+        }
+
+        let script = build_multisig_script(self.threshold, &self.derive_keys(chain, index)?);
+        let unsupported =
+            |e: crate::address::AddressError| WalletPolicyError::Script(e.to_string());
+        Ok(match script_type {
+            OutputScriptType::P2sh => {
+                script_support.assert_legacy().map_err(unsupported)?;
+                WalletScripts::P2sh(ScriptP2sh {
+                    redeem_script: script,
+                })
+            }
+            OutputScriptType::P2shP2wsh => {
+                script_support.assert_segwit().map_err(unsupported)?;
+                WalletScripts::P2shP2wsh(ScriptP2shP2wsh {
+                    redeem_script: script.clone().to_p2wsh(),
+                    witness_script: script,
+                })
+            }
+            _ => {
+                script_support.assert_segwit().map_err(unsupported)?;
+                WalletScripts::P2wsh(ScriptP2wsh {
+                    witness_script: script,
+                })
+            }
+        })
+    }
+
+    /// BIP32 derivations of the keys at `chain/index`, keyed by derived public key
+    ///
+    /// Like [`RootWalletKeys`] PSBTs, each key source uses the fingerprint of the policy xpub.
+    pub fn bip32_derivation(
+        &self,
+        chain: u32,
+        index: u32,
+    ) -> Result<BTreeMap<PublicKey, KeySource>, WalletPolicyError> {
+        let derived = self.derive_keys(chain, index)?;
+        Ok(self
+            .xpubs
+            .iter()
+            .zip(&self.derivation_prefixes)
+            .zip(derived)
+            .map(|((xpub, prefix), key)| {
+                (
+                    key.0,
+                    (xpub.fingerprint(), derivation_path(prefix, chain, index)),
+                )
+            })
+            .collect())
+    }
+
+    /// Identify a script of this wallet from the derivations recorded in a PSBT input or output
+    ///
+    /// Returns `None` if a derivation belongs to a different key, or if the script at the
+    /// recorded chain and index does not match `output_script`.
+    pub fn find_script_id(
+        &self,
+        bip32_derivation: &BTreeMap<PublicKey, KeySource>,
+        output_script: &ScriptBuf,
+        script_support: &OutputScriptSupport,
+    ) -> Option<ScriptId> {
+        let is_own_key = |(fingerprint, _): &KeySource| {
+            self.xpubs
+                .iter()
+                .any(|xpub| xpub.fingerprint() == *fingerprint)
+        };
+        if bip32_derivation.is_empty() || !bip32_derivation.values().all(is_own_key) {
+            return None;
+        }
+        let (_, path) = bip32_derivation.values().next()?;
+        let (chain, index) = path_chain_index(path)?;
+        let scripts = self.wallet_scripts(chain, index, script_support).ok()?;
+        (scripts.output_script() == *output_script).then_some(ScriptId { chain, index })
+    }
+}
+
+impl From<&RootWalletKeys> for WalletPolicy {
+    fn from(wallet_keys: &RootWalletKeys) -> Self {
+        Self {
+            threshold: 2,
+            xpubs: wallet_keys.xpubs.to_vec(),
+            derivation_prefixes: wallet_keys.derivation_prefixes.to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::wallet_keys::tests::{
+        get_test_wallet_keys, get_xpriv_from_seed,
+    };
+    use crate::fixed_script_wallet::wallet_scripts::parse_multisig_script;
+    use crate::Network;
+
+    pub fn get_test_policy(threshold: usize, keys: usize) -> WalletPolicy {
+        let secp = Secp256k1::new();
+        let xpubs = (0..keys)
+            .map(|i| Xpub::from_priv(&secp, &get_xpriv_from_seed(&format!("policy/{}", i))))
+            .collect();
+        WalletPolicy::new(threshold, xpubs).unwrap()
+    }
+
+    #[test]
+    fn test_2_of_3_matches_root_wallet_keys() {
+        let wallet_keys = get_test_wallet_keys("policy");
+        let policy = WalletPolicy::from(&wallet_keys);
+        let support = Network::Bitcoin.output_script_support();
+        for chain in [0, 1, 10, 11, 20, 21, 30, 31, 40, 41, 360, 361] {
+            let expected = WalletScripts::from_wallet_keys(
+                &wallet_keys,
+                Chain::try_from(chain).unwrap().script_type,
+                &chain_index_path(chain, 4),
+                &support,
+            )
+            .unwrap();
+            let scripts = policy.wallet_scripts(chain, 4, &support).unwrap();
+            assert_eq!(scripts.output_script(), expected.output_script());
+        }
+    }
+
+    #[test]
+    fn test_3_of_5_scripts() {
+        let policy = get_test_policy(3, 5);
+        let support = Network::Bitcoin.output_script_support();
+
+        let scripts = policy.wallet_scripts(20, 7, &support).unwrap();
+        let WalletScripts::P2wsh(script) = &scripts else {
+            panic!("expected p2wsh scripts");
+        };
+        let (threshold, keys) = parse_multisig_script(&script.witness_script).unwrap();
+        assert_eq!(threshold, 3);
+        assert_eq!(keys, policy.derive_keys(20, 7).unwrap());
+
+        let derivation = policy.bip32_derivation(20, 7).unwrap();
+        assert_eq!(derivation.len(), 5);
+        assert_eq!(
+            policy.find_script_id(&derivation, &scripts.output_script(), &support),
+            Some(ScriptId {
+                chain: 20,
+                index: 7
+            })
+        );
+        // A script at another index does not match the derivations
+        let other = policy.wallet_scripts(20, 8, &support).unwrap();
+        assert_eq!(
+            policy.find_script_id(&derivation, &other.output_script(), &support),
+            None
+        );
+        // Derivations of another wallet are not ours
+        let other_policy = get_test_policy(2, 4);
+        assert_eq!(
+            other_policy.find_script_id(&derivation, &scripts.output_script(), &support),
+            None
+        );
+
+        for chain in [30, 40, 360] {
+            assert!(matches!(
+                policy.wallet_scripts(chain, 0, &support),
+                Err(WalletPolicyError::UnsupportedScriptType(_))
+            ));
+        }
+        assert!(policy
+            .wallet_scripts(10, 0, &Network::Dogecoin.output_script_support())
+            .is_err());
+    }
+
+    #[test]
+    fn test_key_limits() {
+        let support = Network::Bitcoin.output_script_support();
+        let policy = get_test_policy(9, 16);
+        assert!(matches!(
+            policy.wallet_scripts(0, 0, &support),
+            Err(WalletPolicyError::TooManyKeys { max: 15, .. })
+        ));
+        assert!(policy.wallet_scripts(20, 0, &support).is_ok());
+
+        let secp = Secp256k1::new();
+        let xpub = Xpub::from_priv(&secp, &get_xpriv_from_seed("policy"));
+        assert!(matches!(
+            WalletPolicy::new(1, vec![xpub; 21]),
+            Err(WalletPolicyError::TooManyKeys { .. })
+        ));
+        assert!(matches!(
+            WalletPolicy::new(0, vec![xpub; 2]),
+            Err(WalletPolicyError::InvalidThreshold { .. })
+        ));
+        assert!(matches!(
+            WalletPolicy::new(3, vec![xpub; 2]),
+            Err(WalletPolicyError::InvalidThreshold { .. })
+        ));
+    }
+}
@@ -1,22 +1,98 @@
-use crate::bitcoin::blockdata::opcodes::all::OP_CHECKMULTISIG;
+use crate::bitcoin::blockdata::opcodes::all::{OP_CHECKMULTISIG, OP_PUSHNUM_1, OP_PUSHNUM_16};
 use crate::bitcoin::blockdata::script::Builder;
 use crate::bitcoin::{CompressedPublicKey, ScriptBuf};
 use crate::fixed_script_wallet::wallet_keys::PubTriple;
 
+use super::key_set::KeySet;
+
 /// Build bare multisig script. Needs to wrapped to be useful as an output script.
 pub fn build_multisig_script_2_of_3(keys: &PubTriple) -> ScriptBuf {
-    let quorum = 2;
-    let total_count = 3;
-    let mut builder = Builder::default().push_int(quorum as i64);
+    KeySet::from(keys).multisig_script()
+}
+
+/// Build bare `threshold`-of-`keys.len()` multisig script, keeping the order of `keys`.
+pub fn build_multisig_script(threshold: usize, keys: &[CompressedPublicKey]) -> ScriptBuf {
+    let mut builder = Builder::default().push_int(threshold as i64);
     for key in keys {
         builder = builder.push_slice(key.to_bytes())
     }
     builder
-        .push_int(total_count as i64)
+        .push_int(keys.len() as i64)
         .push_opcode(OP_CHECKMULTISIG)
         .into_script()
 }
 
+/// Parse a bare m-of-n multisig script into the threshold and the keys.
+pub fn parse_multisig_script(
+    script: &ScriptBuf,
+) -> Result<(usize, Vec<CompressedPublicKey>), String> {
+    use crate::bitcoin::blockdata::script::Instruction;
+
+    let instructions: Vec<_> = script
+        .instructions()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to parse script instructions: {}", e))?;
+
+    let read_int = |instruction: &Instruction| -> Option<usize> {
+        match instruction {
+            Instruction::Op(op) => {
+                let code = op.to_u8();
+                let one = OP_PUSHNUM_1.to_u8();
+                (one..=OP_PUSHNUM_16.to_u8())
+                    .contains(&code)
+                    .then(|| (code - one + 1) as usize)
+            }
+            // Counts above 16 are pushed as minimal script numbers
+            Instruction::PushBytes(bytes) => match bytes.as_bytes() {
+                [n] if *n > 16 && *n < 0x80 => Some(*n as usize),
+                _ => None,
+            },
+        }
+    };
+
+    let (last, rest) = instructions
+        .split_last()
+        .ok_or_else(|| "Empty script".to_string())?;
+    if *last != Instruction::Op(OP_CHECKMULTISIG) {
+        return Err("Last instruction should be OP_CHECKMULTISIG".to_string());
+    }
+    let [first, pushes @ .., total] = rest else {
+        return Err("Script is too short for a multisig script".to_string());
+    };
+    let threshold = read_int(first).ok_or_else(|| "Invalid threshold".to_string())?;
+    let total = read_int(total).ok_or_else(|| "Invalid key count".to_string())?;
+    if total != pushes.len() {
+        return Err(format!(
+            "Key count {} does not match the {} pushed keys",
+            total,
+            pushes.len()
+        ));
+    }
+    if threshold == 0 || threshold > total {
+        return Err(format!("Invalid threshold {} of {}", threshold, total));
+    }
+
+    let keys = pushes
+        .iter()
+        .enumerate()
+        .map(|(idx, instruction)| match instruction {
+            Instruction::PushBytes(bytes) => CompressedPublicKey::from_slice(bytes.as_bytes())
+                .map_err(|e| {
+                    format!(
+                        "Failed to parse compressed public key at position {}: {}",
+                        idx + 1,
+                        e
+                    )
+                }),
+            _ => Err(format!(
+                "Instruction at position {} should be a push bytes instruction",
+                idx + 1
+            )),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((threshold, keys))
+}
+
 pub fn parse_multisig_script_2_of_3(script: &ScriptBuf) -> Result<PubTriple, String> {
     use crate::bitcoin::blockdata::opcodes::all::{OP_PUSHNUM_2, OP_PUSHNUM_3};
     use crate::bitcoin::blockdata::script::Instruction;
@@ -91,6 +167,32 @@ pub struct ScriptP2wsh {
     pub witness_script: ScriptBuf,
 }
 
+impl ScriptP2sh {
+    pub fn new(keys: &KeySet) -> Self {
+        ScriptP2sh {
+            redeem_script: keys.multisig_script(),
+        }
+    }
+}
+
+impl ScriptP2shP2wsh {
+    pub fn new(keys: &KeySet) -> Self {
+        let witness_script = keys.multisig_script();
+        ScriptP2shP2wsh {
+            redeem_script: witness_script.to_p2wsh(),
+            witness_script,
+        }
+    }
+}
+
+impl ScriptP2wsh {
+    pub fn new(keys: &KeySet) -> Self {
+        ScriptP2wsh {
+            witness_script: keys.multisig_script(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -269,4 +371,32 @@ mod tests {
             .unwrap_err()
             .contains("Instruction at position 1 should be a push bytes instruction"));
     }
+
+    #[test]
+    fn test_parse_multisig_script_m_of_n_roundtrip() {
+        let keys: Vec<CompressedPublicKey> = (0..7)
+            .flat_map(|seed| {
+                let wallet_keys = get_test_wallet_keys(&format!("m_of_n_{}", seed));
+                to_pub_triple(&wallet_keys.derive_path(&chain_index_path(0, 0)).unwrap())
+            })
+            .collect();
+
+        for (threshold, count) in [(1, 1), (2, 3), (3, 5), (11, 20)] {
+            let script = build_multisig_script(threshold, &keys[..count]);
+            assert_eq!(
+                parse_multisig_script(&script).unwrap(),
+                (threshold, keys[..count].to_vec())
+            );
+        }
+
+        // The 2-of-3 builder is the general builder with three keys
+        let triple: PubTriple = keys[..3].try_into().unwrap();
+        assert_eq!(
+            build_multisig_script_2_of_3(&triple),
+            build_multisig_script(2, &triple)
+        );
+
+        assert!(parse_multisig_script(&build_multisig_script(4, &keys[..3])).is_err());
+        assert!(parse_multisig_script(&build_multisig_script(0, &keys[..3])).is_err());
+    }
 }
@@ -1,3 +1,6 @@
+use std::sync::OnceLock;
+
+use miniscript::bitcoin::secp256k1::{Secp256k1, VerifyOnly};
 use miniscript::bitcoin::taproot::{TaprootBuilder, TaprootSpendInfo};
 
 use crate::bitcoin::blockdata::opcodes::all::{OP_CHECKSIG, OP_CHECKSIGVERIFY};
@@ -8,6 +11,15 @@ use crate::p2mr::{
     build_p2mr_script_pubkey, build_p2mr_tree, ScriptTreeNode, TAPSCRIPT_LEAF_VERSION,
 };
 
+use super::bitgo_musig::BitGoMusigError;
+use super::key_set::KeySet;
+
+/// Context of the taproot tweaks, created once instead of for every output script
+fn secp() -> &'static Secp256k1<VerifyOnly> {
+    static SECP: OnceLock<Secp256k1<VerifyOnly>> = OnceLock::new();
+    SECP.get_or_init(Secp256k1::verification_only)
+}
+
 /// Helper to convert CompressedPublicKey to x-only (32 bytes)
 fn to_xonly_pubkey(key: CompressedPublicKey) -> [u8; 32] {
     let bytes = key.to_bytes();
@@ -96,10 +108,8 @@ fn build_taproot_builder(keys: &PubTriple, is_musig2: bool) -> TaprootBuilder {
 fn build_p2tr_spend_info(keys: &PubTriple, p2tr_musig2: bool) -> TaprootSpendInfo {
     use super::bitgo_musig::key_agg_bitgo_p2tr_legacy;
     use super::bitgo_musig::key_agg_p2tr_musig2;
-    use crate::bitcoin::secp256k1::Secp256k1;
     use crate::bitcoin::XOnlyPublicKey;
 
-    let secp = Secp256k1::new();
     let [user, _backup, bitgo] = *keys;
 
     let agg_key_bytes = if p2tr_musig2 {
@@ -110,7 +120,7 @@ fn build_p2tr_spend_info(keys: &PubTriple, p2tr_musig2: bool) -> TaprootSpendInf
     let internal_key = XOnlyPublicKey::from_slice(&agg_key_bytes).expect("valid xonly key");
 
     build_taproot_builder(keys, p2tr_musig2)
-        .finalize(&secp, internal_key)
+        .finalize(secp(), internal_key)
         .expect("valid taptree")
 }
 
@@ -197,6 +207,28 @@ impl ScriptP2tr {
         ScriptP2tr { spend_info }
     }
 
+    /// Key-path only output of the MuSig2 (BIP-327) aggregate of an n-of-n key set.
+    ///
+    /// The keys are aggregated in key set order and the output key commits to no script
+    /// tree (BIP-86 tweak).
+    pub fn new_musig2_key_path(keys: &KeySet) -> Result<ScriptP2tr, BitGoMusigError> {
+        use super::bitgo_musig::key_agg_p2tr_musig2;
+        use crate::bitcoin::XOnlyPublicKey;
+
+        if keys.threshold() != keys.keys().len() {
+            return Err(BitGoMusigError::InvalidPubkeyCount(format!(
+                "MuSig2 key path requires an n-of-n key set, got {}-of-{}",
+                keys.threshold(),
+                keys.keys().len()
+            )));
+        }
+        let agg_key_bytes = key_agg_p2tr_musig2(keys.keys())?;
+        let internal_key = XOnlyPublicKey::from_slice(&agg_key_bytes)
+            .map_err(|e| BitGoMusigError::AggregationFailed(e.to_string()))?;
+        let spend_info = TaprootSpendInfo::new_key_spend(secp(), internal_key, None);
+        Ok(ScriptP2tr { spend_info })
+    }
+
     pub fn output_script(&self) -> ScriptBuf {
         let output_key = self.spend_info.output_key().to_x_only_public_key();
 
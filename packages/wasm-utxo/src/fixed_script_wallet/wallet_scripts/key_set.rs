@@ -0,0 +1,139 @@
+use crate::bitcoin::{CompressedPublicKey, ScriptBuf};
+use crate::fixed_script_wallet::wallet_keys::PubTriple;
+
+use super::checkmultisig::{build_multisig_script, parse_multisig_script};
+
+/// Maximum number of keys of an `OP_CHECKMULTISIG` script (`MAX_PUBKEYS_PER_MULTISIG`)
+pub const MAX_MULTISIG_KEYS: usize = 20;
+
+/// Public keys of a `threshold`-of-n multisig script, in script order.
+///
+/// BitGo wallet scripts use the 2-of-3 set `[user, backup, bitgo]` (see [`PubTriple`]);
+/// other scripts, such as 2-of-2 channel funding outputs, use their own sets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySet {
+    threshold: usize,
+    keys: Vec<CompressedPublicKey>,
+}
+
+impl KeySet {
+    /// Create a key set keeping the order of `keys`
+    pub fn new(threshold: usize, keys: Vec<CompressedPublicKey>) -> Result<Self, String> {
+        if keys.is_empty() || keys.len() > MAX_MULTISIG_KEYS {
+            return Err(format!(
+                "Invalid key count {}, expected 1 to {}",
+                keys.len(),
+                MAX_MULTISIG_KEYS
+            ));
+        }
+        if threshold == 0 || threshold > keys.len() {
+            return Err(format!("Invalid threshold {} of {}", threshold, keys.len()));
+        }
+        Ok(Self { threshold, keys })
+    }
+
+    /// Create a key set with the keys in lexicographic order of their serialization
+    /// (BIP-67, BOLT 3 funding scripts)
+    pub fn sorted(threshold: usize, mut keys: Vec<CompressedPublicKey>) -> Result<Self, String> {
+        keys.sort_by_key(|key| key.to_bytes());
+        Self::new(threshold, keys)
+    }
+
+    /// Parse the key set of a bare multisig script
+    pub fn from_multisig_script(script: &ScriptBuf) -> Result<Self, String> {
+        let (threshold, keys) = parse_multisig_script(script)?;
+        Self::new(threshold, keys)
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn keys(&self) -> &[CompressedPublicKey] {
+        &self.keys
+    }
+
+    /// True if the keys are in lexicographic order of their serialization
+    pub fn is_sorted(&self) -> bool {
+        self.keys
+            .windows(2)
+            .all(|pair| pair[0].to_bytes() <= pair[1].to_bytes())
+    }
+
+    /// True if no key appears twice
+    pub fn has_distinct_keys(&self) -> bool {
+        self.keys
+            .iter()
+            .enumerate()
+            .all(|(i, key)| !self.keys[..i].contains(key))
+    }
+
+    /// The keys as `[user, backup, bitgo]` if this is a 2-of-3 set
+    pub fn to_pub_triple(&self) -> Option<PubTriple> {
+        if self.threshold != 2 {
+            return None;
+        }
+        self.keys.as_slice().try_into().ok()
+    }
+
+    /// Build the bare multisig script. Needs to be wrapped to be useful as an output script.
+    pub fn multisig_script(&self) -> ScriptBuf {
+        build_multisig_script(self.threshold, &self.keys)
+    }
+}
+
+impl From<&PubTriple> for KeySet {
+    /// The 2-of-3 key set of a BitGo wallet
+    fn from(keys: &PubTriple) -> Self {
+        Self {
+            threshold: 2,
+            keys: keys.to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+    use crate::fixed_script_wallet::wallet_keys::to_pub_triple;
+    use crate::fixed_script_wallet::wallet_scripts::build_multisig_script_2_of_3;
+
+    fn get_test_triple() -> PubTriple {
+        to_pub_triple(&get_test_wallet_keys("key_set").xpubs)
+    }
+
+    #[test]
+    fn test_pub_triple_round_trip() {
+        let triple = get_test_triple();
+        let key_set = KeySet::from(&triple);
+        assert_eq!(
+            key_set.multisig_script(),
+            build_multisig_script_2_of_3(&triple)
+        );
+        assert_eq!(
+            KeySet::from_multisig_script(&key_set.multisig_script()).unwrap(),
+            key_set
+        );
+        assert_eq!(key_set.to_pub_triple(), Some(triple));
+        assert_eq!(
+            KeySet::new(3, triple.to_vec()).unwrap().to_pub_triple(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sorted_and_invalid() {
+        let [a, b, _] = get_test_triple();
+        let key_set = KeySet::sorted(2, vec![a, b]).unwrap();
+        assert!(key_set.is_sorted());
+        assert_eq!(key_set, KeySet::sorted(2, vec![b, a]).unwrap());
+        assert!(key_set.has_distinct_keys());
+        assert!(!KeySet::new(2, vec![a, a]).unwrap().has_distinct_keys());
+
+        assert!(KeySet::new(0, vec![a]).is_err());
+        assert!(KeySet::new(2, vec![a]).is_err());
+        assert!(KeySet::new(1, vec![]).is_err());
+        assert!(KeySet::new(1, vec![a; MAX_MULTISIG_KEYS + 1]).is_err());
+    }
+}
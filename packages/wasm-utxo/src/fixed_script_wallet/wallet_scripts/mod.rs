@@ -2,18 +2,23 @@
 pub mod bitgo_musig;
 mod checkmultisig;
 mod checksigverify;
+mod key_set;
 mod singlesig;
 
 pub use bitgo_musig::BitGoMusigError;
 pub use checkmultisig::{
-    build_multisig_script_2_of_3, parse_multisig_script_2_of_3, ScriptP2sh, ScriptP2shP2wsh,
-    ScriptP2wsh,
+    build_multisig_script, build_multisig_script_2_of_3, parse_multisig_script,
+    parse_multisig_script_2_of_3, ScriptP2sh, ScriptP2shP2wsh, ScriptP2wsh,
 };
 pub use checksigverify::{
     build_p2tr_ns_script, build_tap_tree_for_output, create_tap_bip32_derivation_for_output,
     ScriptP2mr, ScriptP2tr,
 };
-pub use singlesig::{build_p2pk_script, parse_p2pk_script, ScriptP2shP2pk};
+pub use key_set::{KeySet, MAX_MULTISIG_KEYS};
+pub use singlesig::{
+    build_p2pk_script, p2sh_p2pk_script_sig_size, parse_p2pk_script, ScriptP2shP2pk,
+    P2SH_P2PK_SCRIPT_SIG_MAX_SIZE,
+};
 
 use crate::address::networks::OutputScriptSupport;
 use crate::bitcoin::bip32::{ChildNumber, DerivationPath, Fingerprint};
@@ -51,25 +56,17 @@ impl WalletScripts {
         match script_type {
             OutputScriptType::P2sh => {
                 script_support.assert_legacy()?;
-                let script = build_multisig_script_2_of_3(keys);
-                Ok(WalletScripts::P2sh(ScriptP2sh {
-                    redeem_script: script,
-                }))
+                Ok(WalletScripts::P2sh(ScriptP2sh::new(&KeySet::from(keys))))
             }
             OutputScriptType::P2shP2wsh => {
                 script_support.assert_segwit()?;
-                let script = build_multisig_script_2_of_3(keys);
-                Ok(WalletScripts::P2shP2wsh(ScriptP2shP2wsh {
-                    redeem_script: script.clone().to_p2wsh(),
-                    witness_script: script,
-                }))
+                Ok(WalletScripts::P2shP2wsh(ScriptP2shP2wsh::new(
+                    &KeySet::from(keys),
+                )))
             }
             OutputScriptType::P2wsh => {
                 script_support.assert_segwit()?;
-                let script = build_multisig_script_2_of_3(keys);
-                Ok(WalletScripts::P2wsh(ScriptP2wsh {
-                    witness_script: script,
-                }))
+                Ok(WalletScripts::P2wsh(ScriptP2wsh::new(&KeySet::from(keys))))
             }
             OutputScriptType::P2trLegacy => {
                 script_support.assert_taproot()?;
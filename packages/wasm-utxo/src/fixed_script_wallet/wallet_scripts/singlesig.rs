@@ -25,6 +25,22 @@ pub fn parse_p2pk_script(script: &ScriptBuf) -> Option<CompressedPublicKey> {
     }
 }
 
+/// Size of a p2pk redeem script: push(1) + compressed pubkey(33) + OP_CHECKSIG(1)
+pub const P2PK_SCRIPT_SIZE: usize = 35;
+
+/// Largest DER-encoded ECDSA signature including the sighash byte
+pub const ECDSA_SIG_MAX_SIZE: usize = 73;
+
+/// Size of the scriptSig spending a p2sh-p2pk output: `<sig> <redeemScript>`
+///
+/// Both elements are below 76 bytes and use a single-byte direct push.
+pub const fn p2sh_p2pk_script_sig_size(sig_size: usize) -> usize {
+    1 + sig_size + 1 + P2PK_SCRIPT_SIZE
+}
+
+/// Size of the scriptSig of a p2sh-p2pk input signed with the largest signature
+pub const P2SH_P2PK_SCRIPT_SIG_MAX_SIZE: usize = p2sh_p2pk_script_sig_size(ECDSA_SIG_MAX_SIZE);
+
 #[derive(Debug)]
 pub struct ScriptP2shP2pk {
     pub redeem_script: ScriptBuf,
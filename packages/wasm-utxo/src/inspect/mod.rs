@@ -8,3 +8,7 @@ pub use psbt::{
     zcash_psbt_to_node, zcash_tx_to_node,
 };
 pub use psbt_raw::parse_psbt_bytes_raw_with_network;
+pub(crate) use psbt_raw::{
+    decode_psbt_maps, encode_psbt_maps, is_printable_ascii, key_type_name, parse_proprietary_key,
+    PsbtMapContext, RawPsbtMaps,
+};
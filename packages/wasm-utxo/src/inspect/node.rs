@@ -21,10 +21,18 @@ pub enum Primitive {
     I8(i8),
     I16(i16),
     I32(i32),
+    #[serde(
+        serialize_with = "serialize_decimal",
+        deserialize_with = "deserialize_decimal"
+    )]
     I64(i64),
     U8(u8),
     U16(u16),
     U32(u32),
+    #[serde(
+        serialize_with = "serialize_decimal",
+        deserialize_with = "deserialize_decimal"
+    )]
     U64(u64),
     Boolean(bool),
     None,
@@ -45,6 +53,26 @@ where
     hex::decode(&s).map_err(serde::de::Error::custom)
 }
 
+/// 64-bit integers are serialized as decimal strings: `JSON.parse` would round values
+/// above 2^53 (e.g. Dogecoin output values) to the nearest double.
+fn serialize_decimal<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: fmt::Display,
+    S: Serializer,
+{
+    serializer.serialize_str(&value.to_string())
+}
+
+fn deserialize_decimal<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: std::str::FromStr,
+    T::Err: fmt::Display,
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    s.parse().map_err(serde::de::Error::custom)
+}
+
 fn serialize_bigint<S>(bigint: &BigInt, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
@@ -325,6 +353,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_serde_64_bit_integers_as_strings() {
+        // 2^53 + 1 is not representable as a double
+        let node = Node::new("value", Primitive::U64(9_007_199_254_740_993));
+        let json = serde_json::to_string(&node).unwrap();
+        assert!(
+            json.contains(r#"{"type":"U64","value":"9007199254740993"}"#),
+            "{}",
+            json
+        );
+        let deserialized: Node = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            deserialized.value,
+            Primitive::U64(9_007_199_254_740_993)
+        ));
+
+        let node = Node::new("value", Primitive::I64(i64::MIN));
+        let json = serde_json::to_string(&node).unwrap();
+        assert!(
+            json.contains(r#""value":"-9223372036854775808""#),
+            "{}",
+            json
+        );
+        let deserialized: Node = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized.value, Primitive::I64(i64::MIN)));
+    }
+
     #[test]
     fn test_serde_with_children() {
         let child1 = Node::new("child1", Primitive::U32(42));
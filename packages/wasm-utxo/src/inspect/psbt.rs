@@ -152,6 +152,13 @@ fn bitgo_proprietary_to_node(
                             "paygo_address_attestation_proof"
                         }
                         ProprietaryKeySubtype::Bip322Message => "bip322_message",
+                        ProprietaryKeySubtype::SpendLimit => "spend_limit",
+                        ProprietaryKeySubtype::DestinationWhitelistHash => {
+                            "destination_whitelist_hash"
+                        }
+                        ProprietaryKeySubtype::ApprovalQuorumId => "approval_quorum_id",
+                        ProprietaryKeySubtype::Label => "label",
+                        ProprietaryKeySubtype::UnconfirmedParent => "unconfirmed_parent",
                         _ => "unknown",
                     };
                     raw_proprietary_to_node(subtype_name, prop_key, v)
@@ -26,7 +26,7 @@
 ///
 /// - [BIP-174: PSBT Format](https://github.com/bitcoin/bips/blob/master/bip-0174.mediawiki)
 /// - [bitcoin::psbt::raw](https://docs.rs/bitcoin/latest/bitcoin/psbt/raw/index.html)
-use crate::bitcoin::consensus::Decodable;
+use crate::bitcoin::consensus::{Decodable, Encodable};
 use crate::bitcoin::psbt::raw::{Key, Pair};
 use crate::bitcoin::{Transaction, VarInt};
 use crate::zcash::transaction::decode_zcash_transaction_parts;
@@ -35,19 +35,19 @@ pub use super::node::{Node, Primitive};
 
 /// Context for interpreting PSBT key types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum PsbtMapContext {
+pub(crate) enum PsbtMapContext {
     Global,
     Input,
     Output,
 }
 
 /// Check if bytes are printable ASCII
-fn is_printable_ascii(bytes: &[u8]) -> bool {
+pub(crate) fn is_printable_ascii(bytes: &[u8]) -> bool {
     bytes.iter().all(|&b| (0x20..=0x7E).contains(&b))
 }
 
 /// Parse proprietary key structure (0xFC type keys)
-fn parse_proprietary_key(key_data: &[u8]) -> Result<(Vec<u8>, u8, Vec<u8>), String> {
+pub(crate) fn parse_proprietary_key(key_data: &[u8]) -> Result<(Vec<u8>, u8, Vec<u8>), String> {
     if key_data.is_empty() {
         return Err("Empty proprietary key data".to_string());
     }
@@ -142,7 +142,7 @@ fn pair_to_node(pair: &Pair, index: usize, context: PsbtMapContext) -> Node {
 }
 
 /// Get human-readable name for PSBT key type based on context
-fn key_type_name(type_id: u8, context: PsbtMapContext) -> String {
+pub(crate) fn key_type_name(type_id: u8, context: PsbtMapContext) -> String {
     match context {
         PsbtMapContext::Global => match type_id {
             0x00 => "PSBT_GLOBAL_UNSIGNED_TX".to_string(),
@@ -307,14 +307,8 @@ fn extract_tx_counts(global_pairs: &[Pair], is_zcash: bool) -> Result<(usize, us
     Err("No unsigned transaction found in global map".to_string())
 }
 
-/// Decode a single map (set of key-value pairs terminated by 0x00)
-fn decode_map(
-    bytes: &[u8],
-    start_pos: usize,
-    map_name: &str,
-    context: PsbtMapContext,
-) -> Result<(Node, Vec<Pair>, usize), String> {
-    let mut map_node = Node::new(map_name, Primitive::None);
+/// Decode the key-value pairs of a single map (terminated by 0x00)
+fn decode_pairs(bytes: &[u8], start_pos: usize) -> Result<(Vec<Pair>, usize), String> {
     let mut pairs = Vec::new();
     let mut pos = start_pos;
 
@@ -346,6 +340,19 @@ fn decode_map(
         }
     }
 
+    Ok((pairs, pos))
+}
+
+/// Decode a single map (set of key-value pairs terminated by 0x00)
+fn decode_map(
+    bytes: &[u8],
+    start_pos: usize,
+    map_name: &str,
+    context: PsbtMapContext,
+) -> Result<(Node, Vec<Pair>, usize), String> {
+    let mut map_node = Node::new(map_name, Primitive::None);
+    let (pairs, pos) = decode_pairs(bytes, start_pos)?;
+
     // Add pair count first
     let pair_count = pairs.len();
     map_node.add_child(Node::new("pair_count", Primitive::U64(pair_count as u64)));
@@ -435,6 +442,72 @@ fn psbt_to_raw_node_internal(bytes: &[u8], is_zcash: bool) -> Result<Node, Strin
     Ok(psbt_node)
 }
 
+/// Raw key-value pairs of a PSBT, one map per global/input/output section
+pub(crate) struct RawPsbtMaps {
+    pub global: Vec<Pair>,
+    pub inputs: Vec<Vec<Pair>>,
+    pub outputs: Vec<Vec<Pair>>,
+}
+
+/// Decode PSBT bytes into their raw key-value maps without interpreting any values
+pub(crate) fn decode_psbt_maps(bytes: &[u8], is_zcash: bool) -> Result<RawPsbtMaps, String> {
+    if bytes.len() < 5 || &bytes[0..5] != b"psbt\xff" {
+        return Err("Invalid PSBT magic bytes".to_string());
+    }
+
+    let (global, mut pos) = decode_pairs(bytes, 5)?;
+    let (input_count, output_count) = extract_tx_counts(&global, is_zcash)?;
+
+    let mut inputs = Vec::with_capacity(input_count);
+    for _ in 0..input_count {
+        let (pairs, new_pos) = decode_pairs(bytes, pos)?;
+        inputs.push(pairs);
+        pos = new_pos;
+    }
+
+    let mut outputs = Vec::with_capacity(output_count);
+    for _ in 0..output_count {
+        let (pairs, new_pos) = decode_pairs(bytes, pos)?;
+        outputs.push(pairs);
+        pos = new_pos;
+    }
+
+    if pos != bytes.len() {
+        return Err(format!("{} trailing bytes after PSBT", bytes.len() - pos));
+    }
+
+    Ok(RawPsbtMaps {
+        global,
+        inputs,
+        outputs,
+    })
+}
+
+/// Encode raw key-value maps back into PSBT bytes
+pub(crate) fn encode_psbt_maps(maps: &RawPsbtMaps) -> Vec<u8> {
+    fn encode_map(out: &mut Vec<u8>, pairs: &[Pair]) {
+        for pair in pairs {
+            // `Key::key` already starts with the type byte
+            VarInt(pair.key.key.len() as u64)
+                .consensus_encode(out)
+                .unwrap();
+            out.extend_from_slice(&pair.key.key);
+            VarInt(pair.value.len() as u64)
+                .consensus_encode(out)
+                .unwrap();
+            out.extend_from_slice(&pair.value);
+        }
+        out.push(0x00);
+    }
+
+    let mut out = b"psbt\xff".to_vec();
+    encode_map(&mut out, &maps.global);
+    for pairs in maps.inputs.iter().chain(maps.outputs.iter()) {
+        encode_map(&mut out, pairs);
+    }
+    out
+}
+
 /// Parse raw PSBT bytes with network support
 pub fn parse_psbt_bytes_raw_with_network(
     bytes: &[u8],
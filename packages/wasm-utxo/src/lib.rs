@@ -1,6 +1,8 @@
 mod address;
 pub mod bip322;
+pub mod capabilities;
 pub mod dash;
+pub mod electrum_wallet;
 mod error;
 pub mod fixed_script_wallet;
 pub mod inscriptions;
@@ -10,9 +12,15 @@ pub mod message;
 mod networks;
 pub mod p2mr;
 pub mod paygo;
+pub mod payjoin;
+pub mod psbt_envelope;
 pub mod psbt_ops;
+pub mod relay_fee;
+pub mod silent_payments;
 #[cfg(test)]
 mod test_utils;
+pub mod timelock_preview;
+pub mod wif;
 pub mod zcash;
 
 // re-export bitcoin from the miniscript crate
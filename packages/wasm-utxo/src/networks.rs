@@ -298,6 +298,31 @@ impl Network {
         }
     }
 
+    /// The mainnet this network's chain split from, for chain forks that share history
+    ///
+    /// Outputs created before the fork exist on both chains, so spends of them can be
+    /// replayed unless the transactions are made chain-specific.
+    pub fn forked_from(self) -> Option<Network> {
+        match self.mainnet() {
+            Network::BitcoinCash | Network::BitcoinGold => Some(Network::Bitcoin),
+            Network::BitcoinSV | Network::Ecash => Some(Network::BitcoinCash),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the networks are distinct chains that split from a common chain
+    pub fn shares_history_with(self, other: Network) -> bool {
+        let ancestors = |network: Network| {
+            std::iter::successors(Some(network.mainnet()), |n| n.forked_from()).collect::<Vec<_>>()
+        };
+        let other_ancestors = ancestors(other);
+        self.is_mainnet() == other.is_mainnet()
+            && self.mainnet() != other.mainnet()
+            && ancestors(self)
+                .iter()
+                .any(|network| other_ancestors.contains(network))
+    }
+
     pub fn is_mainnet(self) -> bool {
         self == self.mainnet()
     }
@@ -328,6 +353,99 @@ impl Network {
     }
 }
 
+/// BIP-32 extended key version bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bip32Versions {
+    pub public: u32,
+    pub private: u32,
+}
+
+const BIP32_MAINNET: Bip32Versions = Bip32Versions {
+    public: 0x0488b21e,
+    private: 0x0488ade4,
+};
+
+const BIP32_TESTNET: Bip32Versions = Bip32Versions {
+    public: 0x043587cf,
+    private: 0x04358394,
+};
+
+impl Network {
+    /// Genesis block hash, for networks that share their chain parameters with bitcoin core
+    ///
+    /// All signets use the same genesis block; they differ only in the block challenge.
+    pub fn genesis_block_hash(self) -> Option<crate::bitcoin::BlockHash> {
+        let hex = match self {
+            Network::Bitcoin => "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f",
+            Network::BitcoinTestnet3 => {
+                "000000000933ea01ad0ee984209779baaec3ced90fa3f408719526f8d77f4943"
+            }
+            Network::BitcoinTestnet4 => {
+                "00000000da84f2bafbbc53dee25a72ae507ff4914b867c565be350b0da8bf043"
+            }
+            Network::BitcoinPublicSignet | Network::BitcoinBitGoSignet => {
+                "00000008819873e925422c1ff0f99f7cc9bbb232af63a077a480a3633bee1ef6"
+            }
+            Network::BitcoinRegtest => {
+                "0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e2206"
+            }
+            _ => return None,
+        };
+        Some(hex.parse().expect("valid block hash"))
+    }
+
+    /// Maximum amount in satoshis that a single value or a transaction total may have
+    /// (`MAX_MONEY` of the node implementation)
+    ///
+    /// Dogecoin has no supply cap and amounts beyond `i64::MAX` must round-trip, so its
+    /// values are not limited.
+    pub fn max_money(self) -> u64 {
+        const COIN: u64 = 100_000_000;
+        match self.mainnet() {
+            Network::Litecoin => 84_000_000 * COIN,
+            Network::Dogecoin => u64::MAX,
+            _ => 21_000_000 * COIN,
+        }
+    }
+
+    /// Human-readable part of segwit addresses, or `None` if the network has no segwit
+    pub fn bech32_hrp(self) -> Option<&'static str> {
+        match self {
+            Network::Bitcoin => Some("bc"),
+            Network::BitcoinTestnet3
+            | Network::BitcoinTestnet4
+            | Network::BitcoinPublicSignet
+            | Network::BitcoinBitGoSignet => Some("tb"),
+            Network::BitcoinRegtest => Some("bcrt"),
+            Network::BitcoinGold => Some("btg"),
+            Network::BitcoinGoldTestnet => Some("tbtg"),
+            Network::Litecoin => Some("ltc"),
+            Network::LitecoinTestnet => Some("tltc"),
+            _ => None,
+        }
+    }
+
+    /// BIP-32 extended key version bytes (matching utxo-lib)
+    pub fn bip32_versions(self) -> Bip32Versions {
+        match self {
+            Network::Dogecoin => Bip32Versions {
+                public: 0x02facafd,
+                private: 0x02fac398,
+            },
+            Network::DogecoinTestnet => Bip32Versions {
+                public: 0x0432a9a8,
+                private: 0x0432a243,
+            },
+            Network::LitecoinTestnet => Bip32Versions {
+                public: 0x0436f6e1,
+                private: 0x0436ef7d,
+            },
+            network if network.is_mainnet() => BIP32_MAINNET,
+            _ => BIP32_TESTNET,
+        }
+    }
+}
+
 impl fmt::Display for Network {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.as_str())
@@ -403,6 +521,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_shares_history_with() {
+        assert!(Network::Bitcoin.shares_history_with(Network::BitcoinCash));
+        assert!(Network::BitcoinSV.shares_history_with(Network::Bitcoin));
+        assert!(Network::BitcoinSV.shares_history_with(Network::BitcoinCash));
+        assert!(Network::BitcoinCashTestnet.shares_history_with(Network::EcashTestnet));
+        assert!(Network::BitcoinSV.shares_history_with(Network::BitcoinGold));
+        assert!(!Network::Bitcoin.shares_history_with(Network::Litecoin));
+        assert!(!Network::Bitcoin.shares_history_with(Network::BitcoinTestnet3));
+        assert!(!Network::BitcoinCash.shares_history_with(Network::BitcoinCashTestnet));
+    }
+
     #[test]
     fn test_display() {
         assert_eq!(Network::Bitcoin.to_string(), "Bitcoin");
@@ -512,6 +642,64 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_genesis_block_hash() {
+        use crate::bitcoin::constants::genesis_block;
+        use crate::bitcoin::Network as BitcoinNetwork;
+
+        for (network, bitcoin_network) in [
+            (Network::Bitcoin, BitcoinNetwork::Bitcoin),
+            (Network::BitcoinTestnet3, BitcoinNetwork::Testnet),
+            (Network::BitcoinPublicSignet, BitcoinNetwork::Signet),
+            (Network::BitcoinBitGoSignet, BitcoinNetwork::Signet),
+            (Network::BitcoinRegtest, BitcoinNetwork::Regtest),
+        ] {
+            assert_eq!(
+                network.genesis_block_hash(),
+                Some(genesis_block(bitcoin_network).block_hash()),
+                "{}",
+                network
+            );
+        }
+        assert_ne!(
+            Network::BitcoinTestnet4.genesis_block_hash(),
+            Network::BitcoinTestnet3.genesis_block_hash()
+        );
+        assert_eq!(Network::Litecoin.genesis_block_hash(), None);
+    }
+
+    #[test]
+    fn test_bip32_versions() {
+        use crate::bitcoin::bip32::{Xpriv, Xpub};
+        use crate::bitcoin::secp256k1::Secp256k1;
+        use crate::bitcoin::NetworkKind;
+
+        let secp = Secp256k1::new();
+        for (network, kind) in [
+            (Network::Bitcoin, NetworkKind::Main),
+            (Network::BitcoinTestnet3, NetworkKind::Test),
+            (Network::BitcoinTestnet4, NetworkKind::Test),
+            (Network::BitcoinBitGoSignet, NetworkKind::Test),
+        ] {
+            let xpriv = Xpriv::new_master(kind, &[1u8; 32]).unwrap();
+            let xpub = Xpub::from_priv(&secp, &xpriv);
+            let versions = network.bip32_versions();
+            assert_eq!(
+                versions.private.to_be_bytes(),
+                xpriv.encode()[..4],
+                "{}",
+                network
+            );
+            assert_eq!(
+                versions.public.to_be_bytes(),
+                xpub.encode()[..4],
+                "{}",
+                network
+            );
+        }
+        assert_eq!(Network::Dogecoin.bip32_versions().public, 0x02facafd);
+    }
+
     #[test]
     fn test_to_coin_name() {
         assert_eq!(Network::Bitcoin.to_coin_name(), "btc");
@@ -0,0 +1,646 @@
+//! BIP-78 Payjoin sender checks
+//!
+//! A payjoin sender sends an "original" PSBT paying the receiver and gets back a
+//! "proposal" PSBT in which the receiver has added its own (finalized) inputs and
+//! possibly adjusted outputs. Before signing the proposal, the sender must verify
+//! that the receiver did not tamper with the sender's inputs or outputs and did not
+//! take more fee from the sender than negotiated.
+//!
+//! The original PSBT must be finalized, as BIP-78 requires: its signed size gives the fee
+//! rate the sender agreed to, and the size of the sender inputs in the proposal once signed.
+//!
+//! See <https://github.com/bitcoin/bips/blob/master/bip-0078.mediawiki#senders-payjoin-proposal-checklist>
+
+use miniscript::bitcoin::psbt::Input;
+use miniscript::bitcoin::{FeeRate, Psbt, ScriptBuf, Transaction};
+
+use crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::get_output_script_and_value;
+use crate::fixed_script_wallet::bitgo_psbt::ExternalScriptType;
+
+/// Parameters the sender attached to the payjoin request
+#[derive(Debug, Clone)]
+pub struct PayjoinSenderParams {
+    /// Output script paying the receiver
+    pub payee_script: ScriptBuf,
+    /// Index of the sender output in the original PSBT that may be reduced to pay for
+    /// the receiver's inputs, with the maximum reduction in satoshis
+    /// (`additionalfeeoutputindex` and `maxadditionalfeecontribution`)
+    pub fee_contribution: Option<(usize, u64)>,
+    /// Forbid the receiver from substituting or reducing the payee output
+    /// (`disableoutputsubstitution`)
+    pub disable_output_substitution: bool,
+    /// Minimum fee rate of the proposal (`minfeerate`)
+    pub min_fee_rate: Option<FeeRate>,
+}
+
+/// Result of a successful proposal check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayjoinProposalCheck {
+    /// Indices of the inputs added by the receiver
+    pub receiver_inputs: Vec<usize>,
+    /// Amount taken from the sender's fee output, in satoshis
+    pub fee_contribution: u64,
+}
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum PayjoinError {
+    /// Transaction version differs from the original
+    VersionMismatch,
+    /// Transaction lock time differs from the original
+    LockTimeMismatch,
+    /// An original input is missing from the proposal or inputs were reordered
+    MissingOrShuffledInputs,
+    /// A sender input has a different sequence or utxo than in the original
+    SenderInputModified { index: usize },
+    /// An original input has no utxo information
+    SenderInputMissingUtxo { index: usize },
+    /// An original input is not finalized, so the original fee rate is unknown
+    SenderInputNotFinalized { index: usize },
+    /// A receiver input is not finalized
+    ReceiverInputNotFinalized { index: usize },
+    /// A receiver input has no utxo information
+    ReceiverInputMissingUtxo { index: usize },
+    /// A receiver input has a different script type than the sender inputs
+    MixedInputTypes { index: usize },
+    /// A receiver input has a different sequence than the sender inputs
+    MixedSequence { index: usize },
+    /// An original sender output is missing from the proposal or outputs were reordered
+    MissingOrShuffledOutputs,
+    /// A sender output (other than the fee output) has a lower value than in the original
+    OutputValueDecreased { index: usize },
+    /// The fee output was reduced by more than the allowed contribution
+    FeeContributionExceeded { contribution: u64, max: u64 },
+    /// The fee output was reduced by more than the fee the proposal adds, so the sender pays
+    /// the receiver's outputs
+    FeeContributionAboveAdditionalFee {
+        contribution: u64,
+        additional_fee: u64,
+    },
+    /// The fee output was reduced by more than the original fee rate for the receiver inputs
+    FeeContributionAboveFeeRate { contribution: u64, max: u64 },
+    /// The proposal pays a lower absolute fee than the original
+    FeeDecreased,
+    /// The proposal pays less than `minfeerate`
+    FeeRateBelowMinimum { fee: u64, min_fee: u64 },
+    /// Input or output values overflow
+    ValueOverflow,
+}
+
+impl std::fmt::Display for PayjoinError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PayjoinError::VersionMismatch => write!(f, "proposal changed transaction version"),
+            PayjoinError::LockTimeMismatch => write!(f, "proposal changed transaction lock time"),
+            PayjoinError::MissingOrShuffledInputs => {
+                write!(f, "proposal is missing original inputs or reordered them")
+            }
+            PayjoinError::SenderInputModified { index } => {
+                write!(f, "sender input {} was modified by the receiver", index)
+            }
+            PayjoinError::SenderInputMissingUtxo { index } => {
+                write!(f, "original input {} has no utxo information", index)
+            }
+            PayjoinError::SenderInputNotFinalized { index } => {
+                write!(f, "original input {} is not finalized", index)
+            }
+            PayjoinError::ReceiverInputNotFinalized { index } => {
+                write!(f, "receiver input {} is not finalized", index)
+            }
+            PayjoinError::ReceiverInputMissingUtxo { index } => {
+                write!(f, "receiver input {} has no utxo information", index)
+            }
+            PayjoinError::MixedInputTypes { index } => {
+                write!(
+                    f,
+                    "receiver input {} has a different script type than the sender inputs",
+                    index
+                )
+            }
+            PayjoinError::MixedSequence { index } => {
+                write!(
+                    f,
+                    "receiver input {} has a different sequence than the sender inputs",
+                    index
+                )
+            }
+            PayjoinError::MissingOrShuffledOutputs => {
+                write!(f, "proposal is missing original outputs or reordered them")
+            }
+            PayjoinError::OutputValueDecreased { index } => {
+                write!(f, "value of original output {} was decreased", index)
+            }
+            PayjoinError::FeeContributionExceeded { contribution, max } => {
+                write!(
+                    f,
+                    "fee contribution {} exceeds maximum {}",
+                    contribution, max
+                )
+            }
+            PayjoinError::FeeContributionAboveAdditionalFee {
+                contribution,
+                additional_fee,
+            } => {
+                write!(
+                    f,
+                    "fee contribution {} exceeds the additional fee {}",
+                    contribution, additional_fee
+                )
+            }
+            PayjoinError::FeeContributionAboveFeeRate { contribution, max } => {
+                write!(
+                    f,
+                    "fee contribution {} exceeds {}, the original fee rate for the receiver inputs",
+                    contribution, max
+                )
+            }
+            PayjoinError::FeeDecreased => write!(f, "proposal pays a lower fee than the original"),
+            PayjoinError::FeeRateBelowMinimum { fee, min_fee } => {
+                write!(
+                    f,
+                    "proposal fee {} is below the minimum fee {} at minfeerate",
+                    fee, min_fee
+                )
+            }
+            PayjoinError::ValueOverflow => write!(f, "value overflow"),
+        }
+    }
+}
+
+impl std::error::Error for PayjoinError {}
+crate::impl_wasm_error_code!(PayjoinError);
+
+fn is_finalized(input: &Input) -> bool {
+    input.final_script_sig.is_some() || input.final_script_witness.is_some()
+}
+
+/// `tx` with the final scriptSig and witness of `finalized` on its inputs
+fn finalized_tx<'a>(tx: &Transaction, finalized: impl Iterator<Item = &'a Input>) -> Transaction {
+    let mut tx = tx.clone();
+    for (tx_input, input) in tx.input.iter_mut().zip(finalized) {
+        tx_input.script_sig = input.final_script_sig.clone().unwrap_or_default();
+        tx_input.witness = input.final_script_witness.clone().unwrap_or_default();
+    }
+    tx
+}
+
+/// `fee * numerator / denominator`, rounded up
+fn scale_fee(fee: u64, numerator: u64, denominator: u64) -> u64 {
+    let scaled = (fee as u128 * numerator as u128).div_ceil(denominator.max(1) as u128);
+    u64::try_from(scaled).unwrap_or(u64::MAX)
+}
+
+/// Check a payjoin proposal against the original PSBT from the sender's perspective.
+///
+/// Sender inputs must appear in the proposal in their original order with unchanged
+/// sequence and utxo. Inputs interleaved between them are receiver inputs, which must
+/// be finalized (their signatures prove ownership), carry utxo information, and match
+/// the sender's sequence and (if uniform) script type.
+///
+/// Original outputs must appear in order with no lower value, except the fee output
+/// which may be reduced by up to the negotiated contribution. Unless output substitution
+/// is disabled, the payee output may be replaced by the receiver.
+///
+/// The reduction of the fee output may neither exceed the fee the proposal adds to the
+/// original fee, nor the original fee rate times the weight of the receiver inputs. The
+/// proposal, with the sender inputs signed as in the original, must pay at least
+/// `min_fee_rate`.
+pub fn check_proposal(
+    original: &Psbt,
+    proposal: &Psbt,
+    params: &PayjoinSenderParams,
+) -> Result<PayjoinProposalCheck, PayjoinError> {
+    let original_tx = &original.unsigned_tx;
+    let proposal_tx = &proposal.unsigned_tx;
+
+    if original_tx.version != proposal_tx.version {
+        return Err(PayjoinError::VersionMismatch);
+    }
+    if original_tx.lock_time != proposal_tx.lock_time {
+        return Err(PayjoinError::LockTimeMismatch);
+    }
+
+    let mut original_input_value: u64 = 0;
+    let mut sender_script_types = Vec::with_capacity(original_tx.input.len());
+    for (index, (tx_input, psbt_input)) in original_tx
+        .input
+        .iter()
+        .zip(original.inputs.iter())
+        .enumerate()
+    {
+        let (script, value) = get_output_script_and_value(psbt_input, tx_input.previous_output)
+            .map_err(|_| PayjoinError::SenderInputMissingUtxo { index })?;
+        if !is_finalized(psbt_input) {
+            return Err(PayjoinError::SenderInputNotFinalized { index });
+        }
+        original_input_value = original_input_value
+            .checked_add(value.to_sat())
+            .ok_or(PayjoinError::ValueOverflow)?;
+        sender_script_types.push(ExternalScriptType::from_script(script));
+    }
+    // Receiver inputs only need to match the sender's script type if the sender's is uniform
+    let sender_script_type = match sender_script_types.split_first() {
+        Some((first, rest)) if rest.iter().all(|t| t == first) => Some(*first),
+        _ => None,
+    };
+    let sender_sequence = original_tx.input.first().map(|i| i.sequence);
+
+    let mut original_inputs = original_tx
+        .input
+        .iter()
+        .zip(original.inputs.iter())
+        .peekable();
+    let mut receiver_inputs = Vec::new();
+    let mut receiver_input_value: u64 = 0;
+    // Sender inputs are signed as in the original, receiver inputs as in the proposal
+    let mut finalized_inputs = Vec::with_capacity(proposal.inputs.len());
+
+    for (index, (tx_input, psbt_input)) in proposal_tx
+        .input
+        .iter()
+        .zip(proposal.inputs.iter())
+        .enumerate()
+    {
+        match original_inputs.peek() {
+            Some((original_tx_input, original_psbt_input))
+                if original_tx_input.previous_output == tx_input.previous_output =>
+            {
+                if original_tx_input.sequence != tx_input.sequence {
+                    return Err(PayjoinError::SenderInputModified { index });
+                }
+                // The receiver may strip utxo information but must not alter it
+                if let Ok(proposed_utxo) =
+                    get_output_script_and_value(psbt_input, tx_input.previous_output)
+                {
+                    let original_utxo = get_output_script_and_value(
+                        original_psbt_input,
+                        original_tx_input.previous_output,
+                    )
+                    .map_err(|_| PayjoinError::SenderInputModified { index })?;
+                    if proposed_utxo != original_utxo {
+                        return Err(PayjoinError::SenderInputModified { index });
+                    }
+                }
+                finalized_inputs.push(*original_psbt_input);
+                original_inputs.next();
+            }
+            _ => {
+                if original_tx
+                    .input
+                    .iter()
+                    .any(|i| i.previous_output == tx_input.previous_output)
+                {
+                    return Err(PayjoinError::MissingOrShuffledInputs);
+                }
+                if !is_finalized(psbt_input) {
+                    return Err(PayjoinError::ReceiverInputNotFinalized { index });
+                }
+                let (script, value) =
+                    get_output_script_and_value(psbt_input, tx_input.previous_output)
+                        .map_err(|_| PayjoinError::ReceiverInputMissingUtxo { index })?;
+                if let Some(sender_type) = sender_script_type {
+                    if ExternalScriptType::from_script(script) != sender_type {
+                        return Err(PayjoinError::MixedInputTypes { index });
+                    }
+                }
+                if sender_sequence.is_some_and(|s| s != tx_input.sequence) {
+                    return Err(PayjoinError::MixedSequence { index });
+                }
+                receiver_input_value = receiver_input_value
+                    .checked_add(value.to_sat())
+                    .ok_or(PayjoinError::ValueOverflow)?;
+                receiver_inputs.push(index);
+                finalized_inputs.push(psbt_input);
+            }
+        }
+    }
+    if original_inputs.next().is_some() {
+        return Err(PayjoinError::MissingOrShuffledInputs);
+    }
+
+    // With output substitution allowed, the receiver may replace the payee output entirely
+    let mut original_outputs = original_tx
+        .output
+        .iter()
+        .enumerate()
+        .filter(|(_, o)| {
+            params.disable_output_substitution || o.script_pubkey != params.payee_script
+        })
+        .peekable();
+    let mut fee_contribution: u64 = 0;
+
+    for proposed in &proposal_tx.output {
+        // Outputs not matching the next original output belong to the receiver
+        let Some(&(index, original_output)) = original_outputs.peek() else {
+            continue;
+        };
+        if original_output.script_pubkey != proposed.script_pubkey {
+            continue;
+        }
+        let decrease = original_output
+            .value
+            .to_sat()
+            .saturating_sub(proposed.value.to_sat());
+        match params.fee_contribution {
+            Some((fee_index, max)) if fee_index == index => {
+                if decrease > max {
+                    return Err(PayjoinError::FeeContributionExceeded {
+                        contribution: decrease,
+                        max,
+                    });
+                }
+                fee_contribution = decrease;
+            }
+            _ if decrease > 0 => return Err(PayjoinError::OutputValueDecreased { index }),
+            _ => {}
+        }
+        original_outputs.next();
+    }
+    if original_outputs.next().is_some() {
+        return Err(PayjoinError::MissingOrShuffledOutputs);
+    }
+
+    let original_output_value = original_tx
+        .output
+        .iter()
+        .try_fold(0u64, |acc, o| acc.checked_add(o.value.to_sat()))
+        .ok_or(PayjoinError::ValueOverflow)?;
+    let proposal_output_value = proposal_tx
+        .output
+        .iter()
+        .try_fold(0u64, |acc, o| acc.checked_add(o.value.to_sat()))
+        .ok_or(PayjoinError::ValueOverflow)?;
+    let original_fee = original_input_value
+        .checked_sub(original_output_value)
+        .ok_or(PayjoinError::ValueOverflow)?;
+    let proposal_input_value = original_input_value
+        .checked_add(receiver_input_value)
+        .ok_or(PayjoinError::ValueOverflow)?;
+    let proposal_fee = proposal_input_value
+        .checked_sub(proposal_output_value)
+        .ok_or(PayjoinError::ValueOverflow)?;
+    if proposal_fee < original_fee {
+        return Err(PayjoinError::FeeDecreased);
+    }
+    let additional_fee = proposal_fee - original_fee;
+    if fee_contribution > additional_fee {
+        return Err(PayjoinError::FeeContributionAboveAdditionalFee {
+            contribution: fee_contribution,
+            additional_fee,
+        });
+    }
+
+    let original_weight = finalized_tx(original_tx, original.inputs.iter())
+        .weight()
+        .to_wu();
+    let signed_proposal = finalized_tx(proposal_tx, finalized_inputs.into_iter());
+    let proposal_weight = signed_proposal.weight().to_wu();
+    let receiver_weight: u64 = receiver_inputs
+        .iter()
+        .map(|&index| signed_proposal.input[index].segwit_weight().to_wu())
+        .sum();
+    let max_contribution = scale_fee(original_fee, receiver_weight, original_weight);
+    if fee_contribution > max_contribution {
+        return Err(PayjoinError::FeeContributionAboveFeeRate {
+            contribution: fee_contribution,
+            max: max_contribution,
+        });
+    }
+    if let Some(min_fee_rate) = params.min_fee_rate {
+        let min_fee = scale_fee(min_fee_rate.to_sat_per_kwu(), proposal_weight, 1000);
+        if proposal_fee < min_fee {
+            return Err(PayjoinError::FeeRateBelowMinimum {
+                fee: proposal_fee,
+                min_fee,
+            });
+        }
+    }
+
+    Ok(PayjoinProposalCheck {
+        receiver_inputs,
+        fee_contribution,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miniscript::bitcoin::hashes::Hash;
+    use miniscript::bitcoin::{
+        absolute::LockTime, transaction, Amount, OutPoint, Sequence, Transaction, TxIn, TxOut,
+        Txid, Witness,
+    };
+
+    fn p2wpkh(n: u8) -> ScriptBuf {
+        let mut bytes = vec![0x00, 0x14];
+        bytes.extend([n; 20]);
+        ScriptBuf::from(bytes)
+    }
+
+    fn tx_input(n: u8) -> TxIn {
+        TxIn {
+            previous_output: OutPoint {
+                txid: Txid::from_byte_array([n; 32]),
+                vout: 0,
+            },
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        }
+    }
+
+    fn tx_output(script: ScriptBuf, value: u64) -> TxOut {
+        TxOut {
+            value: Amount::from_sat(value),
+            script_pubkey: script,
+        }
+    }
+
+    fn psbt(inputs: Vec<(TxIn, TxOut)>, outputs: Vec<TxOut>) -> Psbt {
+        let tx = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: inputs.iter().map(|(i, _)| i.clone()).collect(),
+            output: outputs,
+        };
+        let mut psbt = Psbt::from_unsigned_tx(tx).unwrap();
+        for (psbt_input, (_, utxo)) in psbt.inputs.iter_mut().zip(inputs) {
+            psbt_input.witness_utxo = Some(utxo);
+        }
+        psbt
+    }
+
+    const PAYEE: u8 = 0xaa;
+    const CHANGE: u8 = 0xbb;
+
+    /// Signed p2wpkh witness: signature and public key
+    fn p2wpkh_witness() -> Witness {
+        Witness::from_slice(&[vec![1u8; 72], vec![2u8; 33]])
+    }
+
+    /// Sender spends 100_000 paying 60_000 to the payee with 39_000 change (fee 1_000 for
+    /// 562 WU)
+    fn original() -> Psbt {
+        let mut original = psbt(
+            vec![(tx_input(1), tx_output(p2wpkh(1), 100_000))],
+            vec![
+                tx_output(p2wpkh(PAYEE), 60_000),
+                tx_output(p2wpkh(CHANGE), 39_000),
+            ],
+        );
+        original.inputs[0].final_script_witness = Some(p2wpkh_witness());
+        original
+    }
+
+    /// Receiver adds a 50_000 input (230 WU signed) to the payee output; the sender change
+    /// pays the extra fee
+    fn proposal(change_value: u64) -> Psbt {
+        let mut proposal = psbt(
+            vec![
+                (tx_input(1), tx_output(p2wpkh(1), 100_000)),
+                (tx_input(2), tx_output(p2wpkh(2), 50_000)),
+            ],
+            vec![
+                tx_output(p2wpkh(PAYEE), 110_000),
+                tx_output(p2wpkh(CHANGE), change_value),
+            ],
+        );
+        proposal.inputs[1].final_script_witness = Some(Witness::from_slice(&[vec![1u8; 64]]));
+        proposal
+    }
+
+    fn params() -> PayjoinSenderParams {
+        PayjoinSenderParams {
+            payee_script: p2wpkh(PAYEE),
+            fee_contribution: Some((1, 1_000)),
+            disable_output_substitution: false,
+            min_fee_rate: None,
+        }
+    }
+
+    #[test]
+    fn test_valid_proposal() {
+        let check = check_proposal(&original(), &proposal(38_700), &params()).unwrap();
+        assert_eq!(check.receiver_inputs, vec![1]);
+        assert_eq!(check.fee_contribution, 300);
+    }
+
+    #[test]
+    fn test_fee_contribution_paid_to_payee() {
+        // Change drops by 300 and the payee gets the 300, so the fee does not increase
+        let mut proposal = proposal(38_700);
+        proposal.unsigned_tx.output[0].value = Amount::from_sat(110_300);
+        let result = check_proposal(&original(), &proposal, &params());
+        assert!(matches!(
+            result,
+            Err(PayjoinError::FeeContributionAboveAdditionalFee {
+                contribution: 300,
+                additional_fee: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn test_fee_contribution_above_fee_rate() {
+        // 1000 sat for 562 WU allows 410 sat for the 230 WU of the receiver input
+        let result = check_proposal(&original(), &proposal(38_500), &params());
+        assert!(matches!(
+            result,
+            Err(PayjoinError::FeeContributionAboveFeeRate {
+                contribution: 500,
+                max: 410
+            })
+        ));
+        assert!(check_proposal(&original(), &proposal(38_590), &params()).is_ok());
+    }
+
+    #[test]
+    fn test_min_fee_rate() {
+        // The signed proposal weighs 792 WU and pays 1300 sat
+        let with_min_fee_rate = |sat_per_kwu| PayjoinSenderParams {
+            min_fee_rate: Some(FeeRate::from_sat_per_kwu(sat_per_kwu)),
+            ..params()
+        };
+        assert!(check_proposal(&original(), &proposal(38_700), &with_min_fee_rate(1641)).is_ok());
+        let result = check_proposal(&original(), &proposal(38_700), &with_min_fee_rate(1642));
+        assert!(matches!(
+            result,
+            Err(PayjoinError::FeeRateBelowMinimum {
+                fee: 1300,
+                min_fee: 1301
+            })
+        ));
+    }
+
+    #[test]
+    fn test_original_not_finalized() {
+        let mut original = original();
+        original.inputs[0].final_script_witness = None;
+        let result = check_proposal(&original, &proposal(38_700), &params());
+        assert!(matches!(
+            result,
+            Err(PayjoinError::SenderInputNotFinalized { index: 0 })
+        ));
+    }
+
+    #[test]
+    fn test_fee_contribution_exceeded() {
+        let result = check_proposal(&original(), &proposal(37_000), &params());
+        assert!(matches!(
+            result,
+            Err(PayjoinError::FeeContributionExceeded {
+                contribution: 2_000,
+                max: 1_000
+            })
+        ));
+    }
+
+    #[test]
+    fn test_sender_output_decreased() {
+        let params = PayjoinSenderParams {
+            fee_contribution: None,
+            ..params()
+        };
+        let result = check_proposal(&original(), &proposal(38_700), &params);
+        assert!(matches!(
+            result,
+            Err(PayjoinError::OutputValueDecreased { index: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_receiver_input_not_finalized() {
+        let mut proposal = proposal(38_700);
+        proposal.inputs[1].final_script_witness = None;
+        let result = check_proposal(&original(), &proposal, &params());
+        assert!(matches!(
+            result,
+            Err(PayjoinError::ReceiverInputNotFinalized { index: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_missing_original_input() {
+        let mut proposal = proposal(38_700);
+        proposal.unsigned_tx.input.remove(0);
+        proposal.inputs.remove(0);
+        let result = check_proposal(&original(), &proposal, &params());
+        assert!(matches!(result, Err(PayjoinError::MissingOrShuffledInputs)));
+    }
+
+    #[test]
+    fn test_payee_substitution() {
+        let mut substituted = proposal(38_700);
+        substituted.unsigned_tx.output[0].script_pubkey = p2wpkh(0xcc);
+        assert!(check_proposal(&original(), &substituted, &params()).is_ok());
+
+        let params = PayjoinSenderParams {
+            disable_output_substitution: true,
+            ..params()
+        };
+        let result = check_proposal(&original(), &substituted, &params);
+        assert!(matches!(
+            result,
+            Err(PayjoinError::MissingOrShuffledOutputs)
+        ));
+    }
+}
@@ -0,0 +1,287 @@
+//! Encrypted envelope for transporting serialized PSBTs
+//!
+//! PSBTs carry wallet metadata and BitGo proprietary fields such as MuSig2 nonces, so they
+//! should not transit queues in the clear. An envelope encrypts a serialized PSBT with
+//! XChaCha20-Poly1305 under a key agreed by ECDH between the sender's and the recipient's
+//! secp256k1 signer keys.
+//!
+//! # Format
+//!
+//! ```text
+//! magic "PSBE" (4) || version (1) || sender key id (4) || recipient key id (4)
+//!   || nonce (24) || ciphertext || tag (16)
+//! ```
+//!
+//! Key ids are the first 4 bytes of HASH160 of the compressed public key, i.e. the BIP32
+//! fingerprint of the key, so the recipient can find the keys to open the envelope with.
+//! The header is authenticated as associated data.
+//!
+//! The key agreement is static-static: the envelope key is
+//! `tagged_hash("BitGoPsbtEnvelope/v1", ECDH(sender, recipient))`. Only the holders of the
+//! sender or the recipient private key can seal or open envelopes between them.
+
+use miniscript::bitcoin::hashes::{hash160, Hash};
+use miniscript::bitcoin::secp256k1::{PublicKey, Scalar, Secp256k1, SecretKey};
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+
+use crate::bip322::bip340_tagged_hash;
+
+const MAGIC: &[u8; 4] = b"PSBE";
+const VERSION: u8 = 1;
+const KDF_TAG: &str = "BitGoPsbtEnvelope/v1";
+
+pub const KEY_ID_LEN: usize = 4;
+pub const NONCE_LEN: usize = 24;
+const TAG_LEN: usize = 16;
+pub const HEADER_LEN: usize = MAGIC.len() + 1 + 2 * KEY_ID_LEN + NONCE_LEN;
+
+/// First 4 bytes of HASH160 of a compressed public key
+pub type KeyId = [u8; KEY_ID_LEN];
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum EnvelopeError {
+    /// The envelope is shorter than its header and tag, or has the wrong magic bytes
+    InvalidFormat,
+    UnsupportedVersion(u8),
+    /// The envelope is addressed to or from a different key
+    KeyMismatch {
+        role: &'static str,
+        expected: KeyId,
+        actual: KeyId,
+    },
+    /// The ciphertext or header was modified, or the keys are wrong
+    DecryptionFailed,
+    RandomnessUnavailable(String),
+}
+
+impl std::fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnvelopeError::InvalidFormat => write!(f, "Invalid PSBT envelope"),
+            EnvelopeError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported PSBT envelope version {}", version)
+            }
+            EnvelopeError::KeyMismatch {
+                role,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Envelope {} key id {} does not match key id {}",
+                role,
+                hex_key_id(actual),
+                hex_key_id(expected)
+            ),
+            EnvelopeError::DecryptionFailed => write!(f, "Failed to decrypt PSBT envelope"),
+            EnvelopeError::RandomnessUnavailable(e) => {
+                write!(f, "Failed to generate envelope nonce: {}", e)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EnvelopeError {}
+
+crate::impl_wasm_error_code!(EnvelopeError);
+
+fn hex_key_id(key_id: &KeyId) -> String {
+    key_id.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Header of an envelope
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnvelopeHeader {
+    pub sender: KeyId,
+    pub recipient: KeyId,
+    pub nonce: [u8; NONCE_LEN],
+}
+
+impl EnvelopeHeader {
+    fn serialize(&self) -> [u8; HEADER_LEN] {
+        let mut out = [0u8; HEADER_LEN];
+        out[..4].copy_from_slice(MAGIC);
+        out[4] = VERSION;
+        out[5..9].copy_from_slice(&self.sender);
+        out[9..13].copy_from_slice(&self.recipient);
+        out[13..].copy_from_slice(&self.nonce);
+        out
+    }
+
+    /// Read the header of an envelope without decrypting it
+    pub fn parse(envelope: &[u8]) -> Result<EnvelopeHeader, EnvelopeError> {
+        if envelope.len() < HEADER_LEN + TAG_LEN || &envelope[..4] != MAGIC {
+            return Err(EnvelopeError::InvalidFormat);
+        }
+        if envelope[4] != VERSION {
+            return Err(EnvelopeError::UnsupportedVersion(envelope[4]));
+        }
+        let mut header = EnvelopeHeader {
+            sender: [0; KEY_ID_LEN],
+            recipient: [0; KEY_ID_LEN],
+            nonce: [0; NONCE_LEN],
+        };
+        header.sender.copy_from_slice(&envelope[5..9]);
+        header.recipient.copy_from_slice(&envelope[9..13]);
+        header.nonce.copy_from_slice(&envelope[13..HEADER_LEN]);
+        Ok(header)
+    }
+}
+
+/// Key id of a public key
+pub fn key_id(pubkey: &PublicKey) -> KeyId {
+    let hash = hash160::Hash::hash(&pubkey.serialize());
+    let mut id = [0u8; KEY_ID_LEN];
+    id.copy_from_slice(&hash[..KEY_ID_LEN]);
+    id
+}
+
+fn envelope_cipher(secret_key: &SecretKey, pubkey: &PublicKey) -> XChaCha20Poly1305 {
+    let secp = Secp256k1::verification_only();
+    // Multiplying a valid point by a valid secret key can't give the point at infinity
+    let shared_point = pubkey
+        .mul_tweak(&secp, &Scalar::from(*secret_key))
+        .expect("ECDH with a valid secret key");
+    let key = bip340_tagged_hash(KDF_TAG, &shared_point.serialize());
+    XChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+/// Encrypt a serialized PSBT for `recipient`
+pub fn seal(
+    psbt: &[u8],
+    sender: &SecretKey,
+    recipient: &PublicKey,
+) -> Result<Vec<u8>, EnvelopeError> {
+    let mut nonce = [0u8; NONCE_LEN];
+    getrandom::getrandom(&mut nonce)
+        .map_err(|e| EnvelopeError::RandomnessUnavailable(e.to_string()))?;
+    Ok(seal_with_nonce(psbt, sender, recipient, nonce))
+}
+
+fn seal_with_nonce(
+    psbt: &[u8],
+    sender: &SecretKey,
+    recipient: &PublicKey,
+    nonce: [u8; NONCE_LEN],
+) -> Vec<u8> {
+    let secp = Secp256k1::signing_only();
+    let header = EnvelopeHeader {
+        sender: key_id(&sender.public_key(&secp)),
+        recipient: key_id(recipient),
+        nonce,
+    }
+    .serialize();
+    let ciphertext = envelope_cipher(sender, recipient)
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: psbt,
+                aad: &header,
+            },
+        )
+        // Only fails for plaintexts longer than 256 GiB
+        .expect("XChaCha20-Poly1305 encryption");
+    let mut envelope = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+    envelope.extend_from_slice(&header);
+    envelope.extend_from_slice(&ciphertext);
+    envelope
+}
+
+/// Decrypt an envelope sealed by `sender` for `recipient`
+///
+/// Fails if the key ids of the header do not match the keys, or if the envelope was
+/// modified.
+pub fn open(
+    envelope: &[u8],
+    recipient: &SecretKey,
+    sender: &PublicKey,
+) -> Result<Vec<u8>, EnvelopeError> {
+    let header = EnvelopeHeader::parse(envelope)?;
+    let secp = Secp256k1::signing_only();
+    for (role, expected, actual) in [
+        (
+            "recipient",
+            key_id(&recipient.public_key(&secp)),
+            header.recipient,
+        ),
+        ("sender", key_id(sender), header.sender),
+    ] {
+        if expected != actual {
+            return Err(EnvelopeError::KeyMismatch {
+                role,
+                expected,
+                actual,
+            });
+        }
+    }
+    envelope_cipher(recipient, sender)
+        .decrypt(
+            XNonce::from_slice(&header.nonce),
+            Payload {
+                msg: &envelope[HEADER_LEN..],
+                aad: &envelope[..HEADER_LEN],
+            },
+        )
+        .map_err(|_| EnvelopeError::DecryptionFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keypair(byte: u8) -> (SecretKey, PublicKey) {
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_slice(&[byte; 32]).unwrap();
+        (secret_key, secret_key.public_key(&secp))
+    }
+
+    #[test]
+    fn test_seal_open() {
+        let (sender_sk, sender_pk) = keypair(1);
+        let (recipient_sk, recipient_pk) = keypair(2);
+        let psbt = b"psbt\xff\x00\x00";
+
+        let envelope = seal(psbt, &sender_sk, &recipient_pk).unwrap();
+        assert_eq!(envelope.len(), HEADER_LEN + psbt.len() + TAG_LEN);
+        let header = EnvelopeHeader::parse(&envelope).unwrap();
+        assert_eq!(header.sender, key_id(&sender_pk));
+        assert_eq!(header.recipient, key_id(&recipient_pk));
+        assert_eq!(
+            open(&envelope, &recipient_sk, &sender_pk).unwrap(),
+            psbt.to_vec()
+        );
+
+        // Fresh nonce for every envelope
+        assert_ne!(seal(psbt, &sender_sk, &recipient_pk).unwrap(), envelope);
+    }
+
+    #[test]
+    fn test_open_rejects_tampering_and_wrong_keys() {
+        let (sender_sk, sender_pk) = keypair(1);
+        let (recipient_sk, recipient_pk) = keypair(2);
+        let (other_sk, other_pk) = keypair(3);
+        let envelope = seal_with_nonce(b"psbt", &sender_sk, &recipient_pk, [7; NONCE_LEN]);
+
+        for index in [4, 6, 10, 20, HEADER_LEN, envelope.len() - 1] {
+            let mut tampered = envelope.clone();
+            tampered[index] ^= 1;
+            assert!(open(&tampered, &recipient_sk, &sender_pk).is_err());
+        }
+        assert!(matches!(
+            open(&envelope, &other_sk, &sender_pk),
+            Err(EnvelopeError::KeyMismatch {
+                role: "recipient",
+                ..
+            })
+        ));
+        assert!(matches!(
+            open(&envelope, &recipient_sk, &other_pk),
+            Err(EnvelopeError::KeyMismatch { role: "sender", .. })
+        ));
+        assert!(matches!(
+            open(&envelope[..HEADER_LEN], &recipient_sk, &sender_pk),
+            Err(EnvelopeError::InvalidFormat)
+        ));
+    }
+}
@@ -297,3 +297,131 @@ pub fn insert_output(
     psbt.outputs.insert(index, psbt_output);
     Ok(index)
 }
+
+/// Index mapping produced by [`sort_bip69`]
+///
+/// `inputs[old_index] == new_index` (likewise for `outputs`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bip69Permutation {
+    pub inputs: Vec<usize>,
+    pub outputs: Vec<usize>,
+}
+
+/// Compute `old_index -> new_index` from a list of old indices in their new order
+fn invert_order(order: &[usize]) -> Vec<usize> {
+    let mut mapping = vec![0; order.len()];
+    for (new_index, &old_index) in order.iter().enumerate() {
+        mapping[old_index] = new_index;
+    }
+    mapping
+}
+
+/// Move inputs and outputs (with their PSBT maps) into the given order of old indices
+fn reorder(psbt: &mut Psbt, input_order: &[usize], output_order: &[usize]) {
+    let mut tx_inputs: Vec<Option<TxIn>> = psbt.unsigned_tx.input.drain(..).map(Some).collect();
+    let mut inputs: Vec<Option<psbt::Input>> = psbt.inputs.drain(..).map(Some).collect();
+    for &i in input_order {
+        psbt.unsigned_tx
+            .input
+            .push(tx_inputs[i].take().expect("index used once"));
+        psbt.inputs.push(inputs[i].take().expect("index used once"));
+    }
+
+    let mut tx_outputs: Vec<Option<TxOut>> = psbt.unsigned_tx.output.drain(..).map(Some).collect();
+    let mut outputs: Vec<Option<psbt::Output>> = psbt.outputs.drain(..).map(Some).collect();
+    for &i in output_order {
+        psbt.unsigned_tx
+            .output
+            .push(tx_outputs[i].take().expect("index used once"));
+        psbt.outputs
+            .push(outputs[i].take().expect("index used once"));
+    }
+}
+
+/// Sort inputs and outputs following BIP-69, keeping the PSBT input/output maps aligned
+/// with the transaction.
+///
+/// Inputs are sorted by previous txid (in its displayed, byte-reversed form) and then
+/// by output index. Outputs are sorted by amount and then by scriptPubKey bytes.
+/// Both sorts are stable.
+///
+/// See <https://github.com/bitcoin/bips/blob/master/bip-0069.mediawiki>
+pub fn sort_bip69(psbt: &mut Psbt) -> Bip69Permutation {
+    use miniscript::bitcoin::hashes::Hash;
+
+    let tx = &psbt.unsigned_tx;
+    let mut input_order: Vec<usize> = (0..tx.input.len()).collect();
+    input_order.sort_by_cached_key(|&i| {
+        let outpoint = tx.input[i].previous_output;
+        let mut txid = outpoint.txid.to_byte_array();
+        txid.reverse();
+        (txid, outpoint.vout)
+    });
+    let mut output_order: Vec<usize> = (0..tx.output.len()).collect();
+    output_order.sort_by(|&a, &b| {
+        let (a, b) = (&tx.output[a], &tx.output[b]);
+        a.value
+            .cmp(&b.value)
+            .then_with(|| a.script_pubkey.as_bytes().cmp(b.script_pubkey.as_bytes()))
+    });
+
+    reorder(psbt, &input_order, &output_order);
+
+    Bip69Permutation {
+        inputs: invert_order(&input_order),
+        outputs: invert_order(&output_order),
+    }
+}
+
+/// Deterministic random number generator: SHA-256 of the seed and a block counter
+struct SeededRng {
+    seed: [u8; 32],
+    counter: u64,
+}
+
+impl SeededRng {
+    fn next_u64(&mut self) -> u64 {
+        use miniscript::bitcoin::hashes::{sha256, Hash, HashEngine};
+
+        let mut engine = sha256::Hash::engine();
+        engine.input(&self.seed);
+        engine.input(&self.counter.to_le_bytes());
+        self.counter += 1;
+        let hash = sha256::Hash::from_engine(engine).to_byte_array();
+        u64::from_le_bytes(hash[..8].try_into().expect("8 bytes"))
+    }
+
+    /// Uniform value in `0..n`, without modulo bias
+    fn below(&mut self, n: u64) -> u64 {
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % n;
+            }
+        }
+    }
+}
+
+/// Shuffle the outputs with a Fisher-Yates shuffle driven by `seed`, keeping the PSBT
+/// output maps aligned with the transaction
+///
+/// The same seed and outputs always produce the same order, so that the shuffle can be
+/// replayed when auditing a transaction.
+///
+/// # Returns
+/// The mapping `old_index -> new_index`
+pub fn shuffle_outputs(psbt: &mut Psbt, seed: &[u8; 32]) -> Vec<usize> {
+    let mut rng = SeededRng {
+        seed: *seed,
+        counter: 0,
+    };
+    let mut output_order: Vec<usize> = (0..psbt.outputs.len()).collect();
+    for i in (1..output_order.len()).rev() {
+        let j = rng.below(i as u64 + 1) as usize;
+        output_order.swap(i, j);
+    }
+    let input_order: Vec<usize> = (0..psbt.inputs.len()).collect();
+    reorder(psbt, &input_order, &output_order);
+    invert_order(&output_order)
+}
@@ -0,0 +1,202 @@
+//! Minimum relay fees per network
+//!
+//! Nodes do not relay transactions that pay less than their minimum relay fee rate, and
+//! replacements have to pay the incremental relay fee rate on top of the fee they replace.
+//! Broadcasting such a transaction fails, often only after it has been signed.
+//!
+//! The rates are the defaults of the node implementations, in satoshis (or the smallest unit
+//! of the coin) per 1000 virtual bytes. Where releases differ, the table has the higher rate,
+//! so that transactions passing [`validate_fee_rate`] are relayed by all of them.
+//!
+//! Dogecoin additionally charges for dust: every output below the dust limit of 0.01 DOGE adds
+//! the fee of 1000 bytes to the minimum fee (`GetDogecoinDustFee` of Dogecoin Core).
+//!
+//! For Zcash, the ZIP-317 conventional fee (see [`crate::zcash::zip317`]) is usually higher
+//! than the relay fee and is what decides whether a transaction stays in the mempool.
+
+use crate::bitcoin::Script;
+use crate::networks::Network;
+
+/// Relay fee rates of a network, in satoshis per 1000 virtual bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelayFeeRates {
+    /// Minimum fee rate of a transaction (`-minrelaytxfee`)
+    pub min_relay_fee: u64,
+    /// Minimum fee rate increase of a replacement (`-incrementalrelayfee`)
+    pub incremental_relay_fee: u64,
+    /// Outputs below this value add `min_relay_fee` to the minimum fee (Dogecoin only)
+    pub dust_output_limit: Option<u64>,
+}
+
+/// 0.01 DOGE
+const DOGECOIN_CENT: u64 = 1_000_000;
+
+impl RelayFeeRates {
+    /// Minimum fee of a transaction of `vsize` virtual bytes with outputs of the given values
+    pub fn min_fee(&self, vsize: u64, output_values: &[u64]) -> u64 {
+        let dust_outputs = self.dust_output_limit.map_or(0, |limit| {
+            output_values.iter().filter(|&&value| value < limit).count() as u64
+        });
+        (self.min_relay_fee * vsize).div_ceil(1000) + self.min_relay_fee * dust_outputs
+    }
+
+    /// Minimum fee of a replacement of `vsize` virtual bytes for a transaction paying
+    /// `replaced_fee`
+    pub fn min_replacement_fee(&self, vsize: u64, replaced_fee: u64) -> u64 {
+        replaced_fee + (self.incremental_relay_fee * vsize).div_ceil(1000)
+    }
+}
+
+impl Network {
+    /// Default relay fee rates of the node implementation
+    ///
+    /// Returns `None` for Bitcoin SV, where the fee policy is set by each miner.
+    pub fn relay_fee_rates(self) -> Option<RelayFeeRates> {
+        let (min_relay_fee, incremental_relay_fee, dust_output_limit) = match self.mainnet() {
+            Network::Litecoin => (10_000, 10_000, None),
+            Network::Dogecoin => (DOGECOIN_CENT, DOGECOIN_CENT, Some(DOGECOIN_CENT)),
+            Network::Zcash => (100, 100, None),
+            Network::BitcoinSV => return None,
+            // Bitcoin, Bitcoin Cash, eCash, Bitcoin Gold and Dash
+            _ => (1_000, 1_000, None),
+        };
+        Some(RelayFeeRates {
+            min_relay_fee,
+            incremental_relay_fee,
+            dust_output_limit,
+        })
+    }
+
+    /// Value below which an output paying `script` is dust
+    ///
+    /// The `dust_output_limit` of the relay fee rates where the network has one (0.01 DOGE on
+    /// Dogecoin), otherwise the Bitcoin Core dust limit of the script (546 sat for P2PKH, 294
+    /// sat for P2WPKH, 330 sat for P2WSH and P2TR).
+    pub fn dust_limit(self, script: &Script) -> u64 {
+        self.relay_fee_rates()
+            .and_then(|rates| rates.dust_output_limit)
+            .unwrap_or_else(|| script.minimal_non_dust().to_sat())
+    }
+}
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum RelayFeeError {
+    /// The fee is below the minimum relay fee of the network
+    BelowMinRelayFee {
+        network: Network,
+        fee: u64,
+        min_fee: u64,
+    },
+}
+
+impl std::fmt::Display for RelayFeeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RelayFeeError::BelowMinRelayFee {
+                network,
+                fee,
+                min_fee,
+            } => write!(
+                f,
+                "Fee of {} is below the minimum relay fee of {} on {}",
+                fee, min_fee, network
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RelayFeeError {}
+
+crate::impl_wasm_error_code!(RelayFeeError);
+crate::impl_from_coded_error!(RelayFeeError);
+
+/// Check that a transaction pays at least the minimum relay fee of the network
+///
+/// # Arguments
+/// * `fee` - Fee of the transaction
+/// * `vsize` - Virtual size of the transaction (size for networks without segwit)
+/// * `output_values` - Values of the outputs, for the Dogecoin dust rule
+///
+/// Passes for networks without a relay fee table entry.
+pub fn validate_fee_rate(
+    network: Network,
+    fee: u64,
+    vsize: u64,
+    output_values: &[u64],
+) -> Result<(), RelayFeeError> {
+    let Some(rates) = network.relay_fee_rates() else {
+        return Ok(());
+    };
+    let min_fee = rates.min_fee(vsize, output_values);
+    if fee < min_fee {
+        return Err(RelayFeeError::BelowMinRelayFee {
+            network,
+            fee,
+            min_fee,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relay_fee_rates() {
+        for network in Network::ALL {
+            let rates = network.relay_fee_rates();
+            assert_eq!(rates, network.mainnet().relay_fee_rates());
+            assert_eq!(rates.is_none(), network.mainnet() == Network::BitcoinSV);
+        }
+        assert_eq!(
+            Network::Bitcoin.relay_fee_rates().unwrap().min_relay_fee,
+            1_000
+        );
+    }
+
+    #[test]
+    fn test_validate_fee_rate() {
+        // 1 sat/vB, rounded up
+        assert!(validate_fee_rate(Network::Bitcoin, 141, 141, &[10_000]).is_ok());
+        assert!(matches!(
+            validate_fee_rate(Network::Bitcoin, 140, 141, &[10_000]),
+            Err(RelayFeeError::BelowMinRelayFee { min_fee: 141, .. })
+        ));
+        assert_eq!(
+            Network::Litecoin
+                .relay_fee_rates()
+                .unwrap()
+                .min_fee(250, &[]),
+            2_500
+        );
+        assert!(validate_fee_rate(Network::BitcoinSV, 0, 250, &[]).is_ok());
+    }
+
+    #[test]
+    fn test_dogecoin_dust() {
+        let rates = Network::Dogecoin.relay_fee_rates().unwrap();
+        // 0.01 DOGE/kB, plus 0.01 DOGE for each output below 0.01 DOGE
+        assert_eq!(
+            rates.min_fee(226, &[DOGECOIN_CENT, 5 * DOGECOIN_CENT]),
+            226_000
+        );
+        assert_eq!(rates.min_fee(226, &[DOGECOIN_CENT - 1, 1]), 2_226_000);
+        assert!(validate_fee_rate(Network::DogecoinTestnet, 226_000, 226, &[1]).is_err());
+        assert_eq!(rates.min_replacement_fee(226, 226_000), 452_000);
+    }
+
+    #[test]
+    fn test_dust_limit() {
+        use crate::bitcoin::hashes::Hash;
+        use crate::bitcoin::{PubkeyHash, ScriptBuf, WPubkeyHash};
+
+        let p2pkh = ScriptBuf::new_p2pkh(&PubkeyHash::all_zeros());
+        let p2wpkh = ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros());
+        assert_eq!(Network::Bitcoin.dust_limit(&p2pkh), 546);
+        assert_eq!(Network::Bitcoin.dust_limit(&p2wpkh), 294);
+        assert_eq!(Network::BitcoinSV.dust_limit(&p2pkh), 546);
+        assert_eq!(Network::Dogecoin.dust_limit(&p2pkh), DOGECOIN_CENT);
+        assert_eq!(Network::DogecoinTestnet.dust_limit(&p2pkh), DOGECOIN_CENT);
+    }
+}
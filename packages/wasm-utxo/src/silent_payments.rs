@@ -0,0 +1,618 @@
+//! BIP-352 silent payments (sender side)
+//!
+//! A silent payment address encodes a scan key and a spend key. The sender derives a
+//! fresh taproot output key for each payment from an ECDH shared secret between the
+//! scan key and the private keys of the transaction inputs, so the receiver can find the
+//! payment by scanning without publishing a new address for every payment.
+//!
+//! See <https://github.com/bitcoin/bips/blob/master/bip-0352.mediawiki>
+
+use bech32::primitives::decode::CheckedHrpstring;
+use bech32::primitives::iter::{ByteIterExt, Fe32IterExt};
+use bech32::{Bech32m, Fe32, Hrp};
+use miniscript::bitcoin::key::{CompressedPublicKey, TweakedPublicKey};
+use miniscript::bitcoin::psbt::Input;
+use miniscript::bitcoin::secp256k1::{self, PublicKey, Scalar, SecretKey, XOnlyPublicKey};
+use miniscript::bitcoin::{consensus, Amount, OutPoint, Psbt, ScriptBuf, TxOut};
+
+use crate::bip322::bip340_tagged_hash;
+use crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::get_output_script_and_value;
+use crate::Network;
+
+/// Length of the payload of a version 0 address: the scan key and the spend key
+const ADDRESS_PAYLOAD_LEN: usize = 66;
+
+/// BIP-341 NUMS point `H`; inputs with this internal key are not eligible
+const NUMS_INTERNAL_KEY: [u8; 32] = [
+    0x50, 0x92, 0x9b, 0x74, 0xc1, 0xa0, 0x49, 0x54, 0xb7, 0x8b, 0x4b, 0x60, 0x35, 0xe9, 0x7a, 0x5e,
+    0x07, 0x8a, 0x5a, 0x0f, 0x28, 0xec, 0x96, 0xd5, 0x47, 0xbf, 0xee, 0x9a, 0xce, 0x80, 0x3a, 0xc0,
+];
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum SilentPaymentError {
+    /// Silent payments are only defined for bitcoin networks
+    UnsupportedNetwork { network: Network },
+    /// The address is not a valid bech32m string
+    InvalidEncoding { error: String },
+    /// The address prefix does not match the network
+    InvalidPrefix { expected: String, found: String },
+    /// The address version is not supported
+    UnsupportedVersion { version: u8 },
+    /// The address payload has the wrong length or contains invalid keys
+    InvalidPayload,
+    /// An input has no utxo information
+    MissingUtxo { input_index: usize },
+    /// A key was given for an input that does not exist
+    InputIndexOutOfBounds {
+        input_index: usize,
+        input_count: usize,
+    },
+    /// The key given for an input does not match its output script
+    KeyMismatch { input_index: usize },
+    /// An input that contributes to the shared secret has no key
+    MissingInputKey { input_index: usize },
+    /// The transaction has no input that contributes to the shared secret
+    NoEligibleInputs,
+    /// An input spends a segwit output with a version above 1, which receivers do not scan
+    UnsupportedWitnessVersion { input_index: usize },
+    /// The sum of the input keys is zero, or a derived tweak is out of range
+    InvalidKeySum,
+}
+
+impl std::fmt::Display for SilentPaymentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SilentPaymentError::UnsupportedNetwork { network } => {
+                write!(f, "Silent payments are not supported on {}", network)
+            }
+            SilentPaymentError::InvalidEncoding { error } => {
+                write!(f, "Invalid silent payment address: {}", error)
+            }
+            SilentPaymentError::InvalidPrefix { expected, found } => write!(
+                f,
+                "Invalid silent payment address prefix: expected {}, found {}",
+                expected, found
+            ),
+            SilentPaymentError::UnsupportedVersion { version } => {
+                write!(f, "Unsupported silent payment address version {}", version)
+            }
+            SilentPaymentError::InvalidPayload => {
+                write!(f, "Invalid silent payment address payload")
+            }
+            SilentPaymentError::MissingUtxo { input_index } => {
+                write!(f, "Input {} has no utxo information", input_index)
+            }
+            SilentPaymentError::InputIndexOutOfBounds {
+                input_index,
+                input_count,
+            } => write!(
+                f,
+                "Input index {} out of bounds (total inputs: {})",
+                input_index, input_count
+            ),
+            SilentPaymentError::KeyMismatch { input_index } => {
+                write!(
+                    f,
+                    "Key does not match the output script of input {}",
+                    input_index
+                )
+            }
+            SilentPaymentError::MissingInputKey { input_index } => write!(
+                f,
+                "Input {} is eligible for silent payments but no key was given",
+                input_index
+            ),
+            SilentPaymentError::NoEligibleInputs => {
+                write!(f, "Transaction has no inputs eligible for silent payments")
+            }
+            SilentPaymentError::UnsupportedWitnessVersion { input_index } => write!(
+                f,
+                "Input {} spends a witness version above 1 and is not eligible for silent payments",
+                input_index
+            ),
+            SilentPaymentError::InvalidKeySum => {
+                write!(f, "Input keys do not produce a valid shared secret")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SilentPaymentError {}
+
+crate::impl_wasm_error_code!(SilentPaymentError);
+
+/// A silent payment address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SilentPaymentAddress {
+    pub scan_pubkey: PublicKey,
+    pub spend_pubkey: PublicKey,
+}
+
+fn address_hrp(network: Network) -> Result<Hrp, SilentPaymentError> {
+    match network {
+        Network::Bitcoin => Ok(Hrp::parse_unchecked("sp")),
+        Network::BitcoinTestnet3
+        | Network::BitcoinTestnet4
+        | Network::BitcoinPublicSignet
+        | Network::BitcoinBitGoSignet => Ok(Hrp::parse_unchecked("tsp")),
+        Network::BitcoinRegtest => Ok(Hrp::parse_unchecked("sprt")),
+        _ => Err(SilentPaymentError::UnsupportedNetwork { network }),
+    }
+}
+
+impl SilentPaymentAddress {
+    /// Decode a bech32m silent payment address (`sp1...`, `tsp1...` or `sprt1...`)
+    ///
+    /// Addresses with a future version are accepted and only their first 66 bytes are read,
+    /// as required for forward compatibility.
+    pub fn decode(address: &str, network: Network) -> Result<Self, SilentPaymentError> {
+        let expected = address_hrp(network)?;
+        let mut checked = CheckedHrpstring::new::<Bech32m>(address).map_err(|e| {
+            SilentPaymentError::InvalidEncoding {
+                error: e.to_string(),
+            }
+        })?;
+        if checked.hrp() != expected {
+            return Err(SilentPaymentError::InvalidPrefix {
+                expected: expected.to_string(),
+                found: checked.hrp().to_string(),
+            });
+        }
+        let version = checked
+            .remove_witness_version()
+            .ok_or(SilentPaymentError::InvalidPayload)?
+            .to_u8();
+        let payload: Vec<u8> = checked.byte_iter().collect();
+        match version {
+            0 if payload.len() != ADDRESS_PAYLOAD_LEN => {
+                return Err(SilentPaymentError::InvalidPayload)
+            }
+            31 => return Err(SilentPaymentError::UnsupportedVersion { version }),
+            _ if payload.len() < ADDRESS_PAYLOAD_LEN => {
+                return Err(SilentPaymentError::InvalidPayload)
+            }
+            _ => {}
+        }
+        let parse = |bytes: &[u8]| {
+            PublicKey::from_slice(bytes).map_err(|_| SilentPaymentError::InvalidPayload)
+        };
+        Ok(SilentPaymentAddress {
+            scan_pubkey: parse(&payload[..33])?,
+            spend_pubkey: parse(&payload[33..ADDRESS_PAYLOAD_LEN])?,
+        })
+    }
+
+    /// Encode as a version 0 silent payment address
+    pub fn encode(&self, network: Network) -> Result<String, SilentPaymentError> {
+        let hrp = address_hrp(network)?;
+        let mut payload = Vec::with_capacity(ADDRESS_PAYLOAD_LEN);
+        payload.extend_from_slice(&self.scan_pubkey.serialize());
+        payload.extend_from_slice(&self.spend_pubkey.serialize());
+        Ok(payload
+            .into_iter()
+            .bytes_to_fes()
+            .with_checksum::<Bech32m>(&hrp)
+            .with_witness_version(Fe32::Q)
+            .chars()
+            .collect())
+    }
+}
+
+/// A payment to a silent payment address
+#[derive(Debug, Clone)]
+pub struct SilentPaymentRecipient {
+    pub address: SilentPaymentAddress,
+    pub value: u64,
+}
+
+/// Returns true if the input contributes its key to the shared secret.
+///
+/// For P2SH inputs this is only known if the key or the redeem script is available.
+fn is_eligible(input: &Input, script: &ScriptBuf) -> bool {
+    if script.is_p2tr() {
+        return input
+            .tap_internal_key
+            .map_or(true, |key| key.serialize() != NUMS_INTERNAL_KEY);
+    }
+    if script.is_p2sh() {
+        return input
+            .redeem_script
+            .as_ref()
+            .is_some_and(|redeem_script| redeem_script.is_p2wpkh());
+    }
+    script.is_p2wpkh() || script.is_p2pkh()
+}
+
+/// Check that `key` controls `script` and return the key that contributes to the shared
+/// secret (negated for taproot outputs with an odd y coordinate)
+fn input_secret_key<C: secp256k1::Signing>(
+    secp: &secp256k1::Secp256k1<C>,
+    script: &ScriptBuf,
+    key: &SecretKey,
+) -> Option<SecretKey> {
+    let pubkey = key.public_key(secp);
+    if script.is_p2tr() {
+        let (xonly, parity) = pubkey.x_only_public_key();
+        let expected =
+            ScriptBuf::new_p2tr_tweaked(TweakedPublicKey::dangerous_assume_tweaked(xonly));
+        return (*script == expected).then(|| match parity {
+            secp256k1::Parity::Even => *key,
+            secp256k1::Parity::Odd => key.negate(),
+        });
+    }
+    let pubkey = CompressedPublicKey(pubkey);
+    let wpkh = ScriptBuf::new_p2wpkh(&pubkey.wpubkey_hash());
+    let matches = *script == wpkh
+        || *script == ScriptBuf::new_p2sh(&wpkh.script_hash())
+        || *script == ScriptBuf::new_p2pkh(&pubkey.pubkey_hash());
+    matches.then_some(*key)
+}
+
+fn tweak_scalar(hash: [u8; 32]) -> Result<Scalar, SilentPaymentError> {
+    Scalar::from_be_bytes(hash).map_err(|_| SilentPaymentError::InvalidKeySum)
+}
+
+/// Sum the keys of the eligible inputs and tweak the sum with the input hash
+/// (`input_hash·a` in BIP-352)
+fn input_ecdh_key<C: secp256k1::Signing>(
+    secp: &secp256k1::Secp256k1<C>,
+    psbt: &Psbt,
+    input_keys: &[(usize, SecretKey)],
+) -> Result<SecretKey, SilentPaymentError> {
+    let input_count = psbt.inputs.len();
+    if let Some(&(input_index, _)) = input_keys.iter().find(|(i, _)| *i >= input_count) {
+        return Err(SilentPaymentError::InputIndexOutOfBounds {
+            input_index,
+            input_count,
+        });
+    }
+
+    let mut key_sum: Option<SecretKey> = None;
+    for (input_index, (tx_in, input)) in psbt.unsigned_tx.input.iter().zip(&psbt.inputs).enumerate()
+    {
+        let (script, _) = get_output_script_and_value(input, tx_in.previous_output)
+            .map_err(|_| SilentPaymentError::MissingUtxo { input_index })?;
+        if script.witness_version().is_some_and(|v| v.to_num() > 1) {
+            return Err(SilentPaymentError::UnsupportedWitnessVersion { input_index });
+        }
+        let key = match input_keys.iter().find(|(i, _)| *i == input_index) {
+            Some((_, key)) => input_secret_key(secp, script, key)
+                .ok_or(SilentPaymentError::KeyMismatch { input_index })?,
+            None if is_eligible(input, script) => {
+                return Err(SilentPaymentError::MissingInputKey { input_index })
+            }
+            None => continue,
+        };
+        key_sum = Some(match key_sum {
+            None => key,
+            Some(sum) => sum
+                .add_tweak(&Scalar::from(key))
+                .map_err(|_| SilentPaymentError::InvalidKeySum)?,
+        });
+    }
+    let key_sum = key_sum.ok_or(SilentPaymentError::NoEligibleInputs)?;
+
+    let smallest_outpoint = psbt
+        .unsigned_tx
+        .input
+        .iter()
+        .map(|tx_in| consensus::serialize::<OutPoint>(&tx_in.previous_output))
+        .min()
+        .ok_or(SilentPaymentError::NoEligibleInputs)?;
+    let mut inputs_msg = smallest_outpoint;
+    inputs_msg.extend_from_slice(&key_sum.public_key(secp).serialize());
+    let input_hash = tweak_scalar(bip340_tagged_hash("BIP0352/Inputs", &inputs_msg))?;
+    key_sum
+        .mul_tweak(&input_hash)
+        .map_err(|_| SilentPaymentError::InvalidKeySum)
+}
+
+/// ECDH shared secret between the inputs and the scan key of a recipient
+fn shared_secret<C: secp256k1::Verification>(
+    secp: &secp256k1::Secp256k1<C>,
+    ecdh_key: &SecretKey,
+    scan_pubkey: &PublicKey,
+) -> Result<PublicKey, SilentPaymentError> {
+    scan_pubkey
+        .mul_tweak(secp, &Scalar::from(*ecdh_key))
+        .map_err(|_| SilentPaymentError::InvalidKeySum)
+}
+
+/// Output key of the `k`-th payment to the same scan key
+fn output_key<C: secp256k1::Verification>(
+    secp: &secp256k1::Secp256k1<C>,
+    shared_secret: &PublicKey,
+    spend_pubkey: &PublicKey,
+    k: u32,
+) -> Result<XOnlyPublicKey, SilentPaymentError> {
+    let mut secret_msg = shared_secret.serialize().to_vec();
+    secret_msg.extend_from_slice(&k.to_be_bytes());
+    let t_k = tweak_scalar(bip340_tagged_hash("BIP0352/SharedSecret", &secret_msg))?;
+    Ok(spend_pubkey
+        .add_exp_tweak(secp, &t_k)
+        .map_err(|_| SilentPaymentError::InvalidKeySum)?
+        .x_only_public_key()
+        .0)
+}
+
+/// Derive the outputs paying silent payment recipients from the inputs of `psbt`
+///
+/// Every input whose output script is eligible under BIP-352 (P2TR, P2WPKH, P2SH-P2WPKH
+/// and P2PKH) must have a key in `input_keys`. Multisig inputs do not contribute to the
+/// shared secret and are ignored, but the transaction needs at least one eligible input.
+/// Inputs that spend a witness version above 1 are rejected, since receivers skip such
+/// transactions when scanning.
+///
+/// The outputs are returned in the order of `recipients`. Adding, removing or reordering
+/// inputs afterwards invalidates them.
+///
+/// # Arguments
+/// - `input_keys`: Private keys of the eligible inputs as `(input_index, key)` pairs
+/// - `recipients`: Silent payment recipients
+pub fn derive_outputs<C: secp256k1::Signing + secp256k1::Verification>(
+    secp: &secp256k1::Secp256k1<C>,
+    psbt: &Psbt,
+    input_keys: &[(usize, SecretKey)],
+    recipients: &[SilentPaymentRecipient],
+) -> Result<Vec<TxOut>, SilentPaymentError> {
+    let ecdh_key = input_ecdh_key(secp, psbt, input_keys)?;
+
+    let mut outputs = Vec::with_capacity(recipients.len());
+    for (index, recipient) in recipients.iter().enumerate() {
+        let address = &recipient.address;
+        let k = recipients[..index]
+            .iter()
+            .filter(|r| r.address.scan_pubkey == address.scan_pubkey)
+            .count() as u32;
+        let shared_secret = shared_secret(secp, &ecdh_key, &address.scan_pubkey)?;
+        let output_key = output_key(secp, &shared_secret, &address.spend_pubkey, k)?;
+        outputs.push(TxOut {
+            value: Amount::from_sat(recipient.value),
+            script_pubkey: ScriptBuf::new_p2tr_tweaked(TweakedPublicKey::dangerous_assume_tweaked(
+                output_key,
+            )),
+        });
+    }
+    Ok(outputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed_script_wallet::bitgo_psbt::BitGoPsbt;
+    use crate::fixed_script_wallet::test_utils::wallet_psbt::WalletPsbtBuilder;
+    use crate::fixed_script_wallet::wallet_keys::tests::get_test_wallet_keys;
+    use miniscript::bitcoin::hashes::Hash;
+    use miniscript::bitcoin::hex::DisplayHex;
+    use miniscript::bitcoin::Txid;
+    use std::str::FromStr;
+
+    fn secret_key(byte: u8) -> SecretKey {
+        SecretKey::from_slice(&[byte; 32]).unwrap()
+    }
+
+    /// Recipient of the BIP-352 "Simple send" test vectors
+    const VECTOR_ADDRESS: &str = "sp1qqgste7k9hx0qftg6qmwlkqtwuy6cycyavzmzj85c6qdfhjdpdjtdgqjuexzk6murw56suy3e0rd2cgqvycxttddwsvgxe2usfpxumr70xc9pkqwv";
+
+    #[test]
+    fn test_decode_vector_address() {
+        let address = SilentPaymentAddress::decode(VECTOR_ADDRESS, Network::Bitcoin).unwrap();
+        assert_eq!(
+            address.scan_pubkey.to_string(),
+            "0220bcfac5b99e04ad1a06ddfb016ee13582609d60b6291e98d01a9bc9a16c96d4"
+        );
+        assert_eq!(
+            address.spend_pubkey.to_string(),
+            "025cc9856d6f8375350e123978daac200c260cb5b5ae83106cab90484dcd8fcf36"
+        );
+        assert_eq!(address.encode(Network::Bitcoin).unwrap(), VECTOR_ADDRESS);
+    }
+
+    /// Known-answer vector for the shared secret and the output keys: a P2PKH, a P2WPKH and
+    /// a P2TR input (with an odd y coordinate, so its key is negated) paying the vector
+    /// address twice. The expected values were computed independently from the BIP-352
+    /// specification.
+    #[test]
+    fn test_shared_secret_and_outputs_known_answer() {
+        let secp = secp256k1::Secp256k1::new();
+        let wallet_keys = get_test_wallet_keys("silent_payments");
+        let mut psbt = BitGoPsbt::new(Network::Bitcoin, &wallet_keys, None, None);
+        let inputs = [
+            (
+                "f4184fc596403b9d638783cf57adfe4c75c605f6356fbc91338530e9831e9e16",
+                0,
+                "eadc78165ff1f8ea94ad7cfdc54990738a4c53f6e0507b42154201b8e5dff3b1",
+            ),
+            (
+                "a1075db55d416d3ca199f55b6084e2115b9345e16c5cf302fc80e9d5fbf5d48d",
+                1,
+                "93f5ed907ad5b2bdbbdcb6d9116ebc0a4e1f92f910d5260237fa45a9408aad16",
+            ),
+            (
+                "6f2d7b9b0bd2d4d1b0b7a0cfc8e5f2b1f3c5d1e4a8b2c9d0e1f2a3b4c5d6e7f8",
+                0,
+                "0378e95685b74565fa56751b84a32dfd18545d10d691641b8372e32164fad66a",
+            ),
+        ];
+        let mut input_keys = vec![];
+        for (input_index, (txid, vout, key)) in inputs.into_iter().enumerate() {
+            let key = SecretKey::from_str(key).unwrap();
+            let pubkey = key.public_key(&secp);
+            let script = match input_index {
+                0 => ScriptBuf::new_p2pkh(&CompressedPublicKey(pubkey).pubkey_hash()),
+                1 => ScriptBuf::new_p2wpkh(&CompressedPublicKey(pubkey).wpubkey_hash()),
+                _ => ScriptBuf::new_p2tr_tweaked(TweakedPublicKey::dangerous_assume_tweaked(
+                    pubkey.x_only_public_key().0,
+                )),
+            };
+            psbt.add_input(
+                Txid::from_str(txid).unwrap(),
+                vout,
+                10_000,
+                script,
+                None,
+                None,
+            )
+            .unwrap();
+            input_keys.push((input_index, key));
+        }
+
+        let address = SilentPaymentAddress::decode(VECTOR_ADDRESS, Network::Bitcoin).unwrap();
+        let ecdh_key = input_ecdh_key(&secp, psbt.psbt(), &input_keys).unwrap();
+        assert_eq!(
+            shared_secret(&secp, &ecdh_key, &address.scan_pubkey)
+                .unwrap()
+                .to_string(),
+            "03998c6f18455d525b50cd8c580372ecbc532ae7485aac72ce3be4585be1f9374b"
+        );
+
+        let recipient = SilentPaymentRecipient {
+            address,
+            value: 1_000,
+        };
+        let outputs = derive_outputs(
+            &secp,
+            psbt.psbt(),
+            &input_keys,
+            &[recipient.clone(), recipient],
+        )
+        .unwrap();
+        let output_keys: Vec<String> = outputs
+            .iter()
+            .map(|output| output.script_pubkey.as_bytes()[2..].to_lower_hex_string())
+            .collect();
+        assert_eq!(
+            output_keys,
+            [
+                "72b1fc70f947d471c76f81fba377c6f8b05147d3acb2fde7ff9012b9406c2f7c",
+                "e6c46bd2a4b39305d35bfce40770acbd28724ea0af52b3b0448a1f4f84e7411e",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reject_witness_version_above_1() {
+        let secp = secp256k1::Secp256k1::new();
+        let wallet_keys = get_test_wallet_keys("silent_payments");
+        let mut psbt = BitGoPsbt::new(Network::Bitcoin, &wallet_keys, None, None);
+        let input_key = secret_key(3);
+        let input_pubkey = CompressedPublicKey(input_key.public_key(&secp));
+        let script = ScriptBuf::new_p2wpkh(&input_pubkey.wpubkey_hash());
+        psbt.add_input(Txid::all_zeros(), 0, 5_000, script, None, None)
+            .unwrap();
+        let witness_v2 = ScriptBuf::builder()
+            .push_opcode(miniscript::bitcoin::opcodes::all::OP_PUSHNUM_2)
+            .push_slice([0u8; 32])
+            .into_script();
+        psbt.add_input(Txid::all_zeros(), 1, 5_000, witness_v2, None, None)
+            .unwrap();
+
+        let address = SilentPaymentAddress::decode(VECTOR_ADDRESS, Network::Bitcoin).unwrap();
+        assert!(matches!(
+            derive_outputs(
+                &secp,
+                psbt.psbt(),
+                &[(0, input_key)],
+                &[SilentPaymentRecipient {
+                    address,
+                    value: 1_000
+                }],
+            ),
+            Err(SilentPaymentError::UnsupportedWitnessVersion { input_index: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_address_round_trip() {
+        let secp = secp256k1::Secp256k1::new();
+        let address = SilentPaymentAddress {
+            scan_pubkey: secret_key(1).public_key(&secp),
+            spend_pubkey: secret_key(2).public_key(&secp),
+        };
+        let encoded = address.encode(Network::Bitcoin).unwrap();
+        assert!(encoded.starts_with("sp1q"));
+        assert_eq!(
+            SilentPaymentAddress::decode(&encoded, Network::Bitcoin).unwrap(),
+            address
+        );
+        assert!(matches!(
+            SilentPaymentAddress::decode(&encoded, Network::BitcoinTestnet3),
+            Err(SilentPaymentError::InvalidPrefix { .. })
+        ));
+        assert!(matches!(
+            address.encode(Network::Litecoin),
+            Err(SilentPaymentError::UnsupportedNetwork { .. })
+        ));
+        let testnet = address.encode(Network::BitcoinTestnet3).unwrap();
+        assert!(testnet.starts_with("tsp1q"));
+    }
+
+    #[test]
+    fn test_derive_outputs() {
+        let secp = secp256k1::Secp256k1::new();
+        let wallet_keys = get_test_wallet_keys("silent_payments");
+        // Multisig inputs do not contribute to the shared secret
+        let mut psbt = WalletPsbtBuilder::new(Network::Bitcoin, &wallet_keys)
+            .input(20, 0)
+            .build();
+
+        let input_key = secret_key(3);
+        let input_pubkey = CompressedPublicKey(input_key.public_key(&secp));
+        let script = ScriptBuf::new_p2wpkh(&input_pubkey.wpubkey_hash());
+        psbt.add_input(Txid::from_byte_array([1; 32]), 1, 5_000, script, None, None)
+            .unwrap();
+
+        let (scan_key, spend_key) = (secret_key(4), secret_key(5));
+        let address = SilentPaymentAddress {
+            scan_pubkey: scan_key.public_key(&secp),
+            spend_pubkey: spend_key.public_key(&secp),
+        };
+        let recipients = vec![
+            SilentPaymentRecipient {
+                address,
+                value: 1_000,
+            },
+            SilentPaymentRecipient {
+                address,
+                value: 2_000,
+            },
+        ];
+
+        assert!(matches!(
+            derive_outputs(&secp, psbt.psbt(), &[], &recipients),
+            Err(SilentPaymentError::MissingInputKey { input_index: 1 })
+        ));
+        assert!(matches!(
+            derive_outputs(&secp, psbt.psbt(), &[(1, secret_key(6))], &recipients),
+            Err(SilentPaymentError::KeyMismatch { input_index: 1 })
+        ));
+
+        let outputs = derive_outputs(&secp, psbt.psbt(), &[(1, input_key)], &recipients).unwrap();
+        assert_eq!(outputs.len(), 2);
+        assert_ne!(outputs[0].script_pubkey, outputs[1].script_pubkey);
+
+        // The receiver finds the outputs with the scan key and the sum of input pubkeys
+        let smallest_outpoint = consensus::serialize(&OutPoint::new(Txid::all_zeros(), 0));
+        let mut inputs_msg = smallest_outpoint;
+        inputs_msg.extend_from_slice(&input_pubkey.to_bytes());
+        let input_hash = tweak_scalar(bip340_tagged_hash("BIP0352/Inputs", &inputs_msg)).unwrap();
+        let shared_secret = input_pubkey
+            .0
+            .mul_tweak(&secp, &input_hash)
+            .unwrap()
+            .mul_tweak(&secp, &Scalar::from(scan_key))
+            .unwrap();
+        for (k, output) in outputs.iter().enumerate() {
+            let mut secret_msg = shared_secret.serialize().to_vec();
+            secret_msg.extend_from_slice(&(k as u32).to_be_bytes());
+            let t_k =
+                tweak_scalar(bip340_tagged_hash("BIP0352/SharedSecret", &secret_msg)).unwrap();
+            let spend = spend_key.add_tweak(&t_k).unwrap();
+            let (xonly, _) = spend.public_key(&secp).x_only_public_key();
+            assert_eq!(
+                output.script_pubkey,
+                ScriptBuf::new_p2tr_tweaked(TweakedPublicKey::dangerous_assume_tweaked(xonly))
+            );
+        }
+    }
+}
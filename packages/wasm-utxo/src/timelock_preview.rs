@@ -0,0 +1,359 @@
+//! When a timelocked descriptor becomes spendable
+//!
+//! Recovery paths of descriptor wallets are usually guarded by `older` (BIP-68/BIP-112
+//! relative timelock) or `after` (BIP-65 absolute timelock) conditions. [`satisfaction_preview`]
+//! lifts the descriptor to its semantic policy and finds the weakest timelocks under which the
+//! policy can be satisfied with the available keys, so that the time left until a recovery
+//! path opens can be shown before a spend is attempted.
+//!
+//! Hash preimages are never considered available.
+
+use miniscript::bitcoin::XOnlyPublicKey;
+use miniscript::policy::{Liftable, Semantic};
+use miniscript::{Descriptor, MiniscriptKey, ToPublicKey};
+
+/// Absolute lock times below this value are block heights, others are unix timestamps
+const LOCKTIME_THRESHOLD: u32 = 500_000_000;
+/// BIP-68: relative lock time is in units of 512 seconds
+const SEQUENCE_LOCKTIME_TYPE_FLAG: u32 = 1 << 22;
+const SEQUENCE_LOCKTIME_MASK: u32 = 0x0000_ffff;
+const SEQUENCE_LOCKTIME_GRANULARITY: u32 = 9;
+
+/// A timelock in blocks or seconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timelock {
+    /// Block height for `after`, number of blocks for `older`
+    Height(u32),
+    /// Unix timestamp for `after`, number of seconds for `older`
+    Time(u32),
+}
+
+impl Timelock {
+    fn from_absolute(lock_time: u32) -> Self {
+        if lock_time < LOCKTIME_THRESHOLD {
+            Timelock::Height(lock_time)
+        } else {
+            Timelock::Time(lock_time)
+        }
+    }
+
+    fn from_relative(sequence: u32) -> Self {
+        let value = sequence & SEQUENCE_LOCKTIME_MASK;
+        if sequence & SEQUENCE_LOCKTIME_TYPE_FLAG != 0 {
+            Timelock::Time(value << SEQUENCE_LOCKTIME_GRANULARITY)
+        } else {
+            Timelock::Height(value)
+        }
+    }
+
+    /// `self` is met whenever `other` is: same unit and not larger
+    fn is_implied_by(self, other: Timelock) -> bool {
+        match (self, other) {
+            (Timelock::Height(a), Timelock::Height(b)) | (Timelock::Time(a), Timelock::Time(b)) => {
+                a <= b
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Timelocks under which the descriptor can be satisfied with the available keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnlockCondition {
+    /// Required `after`, or `None` if the path has no absolute timelock
+    pub after: Option<Timelock>,
+    /// Required `older`, or `None` if the path has no relative timelock
+    pub older: Option<Timelock>,
+}
+
+/// Block of the chain at which a condition is met
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnlockAt {
+    /// Height of the first block that can include the spend
+    pub height: Option<u32>,
+    /// Median time past of the chain tip required to include the spend
+    pub time: Option<u32>,
+}
+
+/// Where the input being spent was confirmed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Confirmation {
+    /// Height of the block that includes the input
+    pub height: u32,
+    /// Median time past of the block before the one that includes the input
+    pub median_time_past: u32,
+}
+
+impl UnlockCondition {
+    /// The condition has no timelock and can be satisfied now
+    pub fn is_unlocked(&self) -> bool {
+        self.after.is_none() && self.older.is_none()
+    }
+
+    /// Height and time at which the condition is met
+    ///
+    /// Returns `None` if the condition has a relative timelock and the input is unconfirmed,
+    /// since relative timelocks start counting at the confirmation of the input.
+    pub fn unlock_at(&self, confirmation: Option<Confirmation>) -> Option<UnlockAt> {
+        let mut unlock = UnlockAt {
+            height: None,
+            time: None,
+        };
+        match self.after {
+            // nLockTime must be below the height of the block and the median time past
+            Some(Timelock::Height(height)) => unlock.height = Some(height.saturating_add(1)),
+            Some(Timelock::Time(time)) => unlock.time = Some(time.saturating_add(1)),
+            None => {}
+        }
+        if let Some(older) = self.older {
+            let confirmation = confirmation?;
+            match older {
+                Timelock::Height(blocks) => {
+                    let height = confirmation.height.saturating_add(blocks);
+                    unlock.height = Some(unlock.height.map_or(height, |h| h.max(height)));
+                }
+                Timelock::Time(seconds) => {
+                    let time = confirmation.median_time_past.saturating_add(seconds);
+                    unlock.time = Some(unlock.time.map_or(time, |t| t.max(time)));
+                }
+            }
+        }
+        Some(unlock)
+    }
+
+    /// `self` is met whenever `other` is
+    fn is_implied_by(&self, other: &UnlockCondition) -> bool {
+        fn implied(a: Option<Timelock>, b: Option<Timelock>) -> bool {
+            match (a, b) {
+                (None, _) => true,
+                (Some(_), None) => false,
+                (Some(a), Some(b)) => a.is_implied_by(b),
+            }
+        }
+        implied(self.after, other.after) && implied(self.older, other.older)
+    }
+}
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum TimelockPreviewError {
+    /// The descriptor cannot be lifted to a semantic policy
+    Lift(String),
+}
+
+impl std::fmt::Display for TimelockPreviewError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimelockPreviewError::Lift(message) => {
+                write!(f, "Cannot lift descriptor to a policy: {}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TimelockPreviewError {}
+
+crate::impl_wasm_error_code!(TimelockPreviewError);
+
+fn collect_timelocks<Pk: MiniscriptKey>(
+    policy: &Semantic<Pk>,
+    after: &mut Vec<u32>,
+    older: &mut Vec<u32>,
+) {
+    match policy {
+        Semantic::After(lock_time) => after.push(lock_time.to_consensus_u32()),
+        Semantic::Older(sequence) => older.push(sequence.to_consensus_u32()),
+        Semantic::Thresh(thresh) => {
+            for sub in thresh.iter() {
+                collect_timelocks(sub, after, older);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_satisfiable<Pk: MiniscriptKey + ToPublicKey>(
+    policy: &Semantic<Pk>,
+    keys: &[XOnlyPublicKey],
+    condition: &UnlockCondition,
+) -> bool {
+    match policy {
+        Semantic::Unsatisfiable => false,
+        Semantic::Trivial => true,
+        Semantic::Key(pk) => keys.contains(&pk.to_x_only_pubkey()),
+        Semantic::After(lock_time) => condition.after.is_some_and(|after| {
+            Timelock::from_absolute(lock_time.to_consensus_u32()).is_implied_by(after)
+        }),
+        Semantic::Older(sequence) => condition.older.is_some_and(|older| {
+            Timelock::from_relative(sequence.to_consensus_u32()).is_implied_by(older)
+        }),
+        Semantic::Sha256(_)
+        | Semantic::Hash256(_)
+        | Semantic::Ripemd160(_)
+        | Semantic::Hash160(_) => false,
+        Semantic::Thresh(thresh) => {
+            thresh
+                .iter()
+                .filter(|sub| is_satisfiable(sub, keys, condition))
+                .count()
+                >= thresh.k()
+        }
+    }
+}
+
+/// Weakest timelocks under which `descriptor` can be satisfied with signatures of `keys`
+///
+/// Each returned condition is a way to satisfy the descriptor that is not implied by another
+/// one, e.g. a path with `after` in blocks and one with `older` in seconds. An empty result
+/// means the descriptor cannot be satisfied with `keys` at any time, and a condition without
+/// timelocks that it can be satisfied now.
+///
+/// Keys are compared by their x-only public key.
+pub fn satisfaction_preview<Pk: MiniscriptKey + ToPublicKey>(
+    descriptor: &Descriptor<Pk>,
+    keys: &[XOnlyPublicKey],
+) -> Result<Vec<UnlockCondition>, TimelockPreviewError> {
+    let policy = descriptor
+        .lift()
+        .map_err(|e| TimelockPreviewError::Lift(e.to_string()))?;
+
+    let mut after = vec![];
+    let mut older = vec![];
+    collect_timelocks(&policy, &mut after, &mut older);
+    let candidates = |values: Vec<u32>, to_timelock: fn(u32) -> Timelock| {
+        let mut timelocks: Vec<Timelock> = values.into_iter().map(to_timelock).collect();
+        timelocks.sort_by_key(|timelock| match *timelock {
+            Timelock::Height(value) => (0, value),
+            Timelock::Time(value) => (1, value),
+        });
+        timelocks.dedup();
+        std::iter::once(None)
+            .chain(timelocks.into_iter().map(Some))
+            .collect::<Vec<_>>()
+    };
+    let after = candidates(after, Timelock::from_absolute);
+    let older = candidates(older, Timelock::from_relative);
+
+    let satisfiable: Vec<UnlockCondition> = after
+        .iter()
+        .flat_map(|&after| {
+            older
+                .iter()
+                .map(move |&older| UnlockCondition { after, older })
+        })
+        .filter(|condition| is_satisfiable(&policy, keys, condition))
+        .collect();
+
+    Ok(satisfiable
+        .iter()
+        .filter(|condition| {
+            !satisfiable
+                .iter()
+                .any(|other| other != *condition && other.is_implied_by(condition))
+        })
+        .copied()
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miniscript::DefiniteDescriptorKey;
+    use std::str::FromStr;
+
+    const KEY_A: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+    const KEY_B: &str = "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5";
+
+    fn preview(descriptor: &str, keys: &[&str]) -> Vec<UnlockCondition> {
+        let descriptor = descriptor.replace("KEY_A", KEY_A).replace("KEY_B", KEY_B);
+        let descriptor = Descriptor::<DefiniteDescriptorKey>::from_str(&descriptor).unwrap();
+        let keys: Vec<XOnlyPublicKey> = keys
+            .iter()
+            .map(|key| XOnlyPublicKey::from_str(&key[2..]).unwrap())
+            .collect();
+        satisfaction_preview(&descriptor, &keys).unwrap()
+    }
+
+    const UNLOCKED: UnlockCondition = UnlockCondition {
+        after: None,
+        older: None,
+    };
+
+    #[test]
+    fn test_relative_timelock() {
+        let descriptor = "wsh(or_d(pk(KEY_A),and_v(v:pk(KEY_B),older(144))))";
+        assert_eq!(preview(descriptor, &[KEY_A]), vec![UNLOCKED]);
+        assert_eq!(preview(descriptor, &[KEY_A, KEY_B]), vec![UNLOCKED]);
+        assert_eq!(
+            preview(descriptor, &[KEY_B]),
+            vec![UnlockCondition {
+                after: None,
+                older: Some(Timelock::Height(144)),
+            }]
+        );
+        assert!(preview(descriptor, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_alternative_timelocks() {
+        // after block 800000, or 512 seconds after confirmation
+        let descriptor =
+            "wsh(or_i(and_v(v:pk(KEY_A),after(800000)),and_v(v:pk(KEY_B),older(4194305))))";
+        let conditions = preview(descriptor, &[KEY_A, KEY_B]);
+        assert_eq!(
+            conditions,
+            vec![
+                UnlockCondition {
+                    after: None,
+                    older: Some(Timelock::Time(512)),
+                },
+                UnlockCondition {
+                    after: Some(Timelock::Height(800_000)),
+                    older: None,
+                },
+            ]
+        );
+
+        assert_eq!(conditions[0].unlock_at(None), None);
+        assert_eq!(
+            conditions[0].unlock_at(Some(Confirmation {
+                height: 799_000,
+                median_time_past: 1_700_000_000,
+            })),
+            Some(UnlockAt {
+                height: None,
+                time: Some(1_700_000_512),
+            })
+        );
+        assert_eq!(
+            conditions[1].unlock_at(None),
+            Some(UnlockAt {
+                height: Some(800_001),
+                time: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_taproot() {
+        let descriptor = "tr(KEY_A,and_v(v:pk(KEY_B),older(10)))";
+        assert_eq!(preview(descriptor, &[KEY_A]), vec![UNLOCKED]);
+        let conditions = preview(descriptor, &[KEY_B]);
+        assert_eq!(
+            conditions,
+            vec![UnlockCondition {
+                after: None,
+                older: Some(Timelock::Height(10)),
+            }]
+        );
+        assert_eq!(
+            conditions[0].unlock_at(Some(Confirmation {
+                height: 100,
+                median_time_past: 0,
+            })),
+            Some(UnlockAt {
+                height: Some(110),
+                time: None,
+            })
+        );
+    }
+}
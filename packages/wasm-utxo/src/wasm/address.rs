@@ -1,5 +1,6 @@
 use crate::address::networks::{
-    from_output_script_with_coin_and_format, to_output_script_with_coin, AddressFormat,
+    from_output_script_with_coin_and_format, to_cashaddr_with_coin, to_legacy_address_with_coin,
+    to_output_script_with_coin, AddressFormat,
 };
 use miniscript::bitcoin::Script;
 use wasm_bindgen::prelude::*;
@@ -34,4 +35,22 @@ impl AddressNamespace {
         from_output_script_with_coin_and_format(script_obj, coin, address_format)
             .map_err(|e| JsValue::from_str(&e.to_string()))
     }
+
+    /// Convert a Bitcoin Cash or eCash address (legacy or cashaddr) to cashaddr format
+    #[wasm_bindgen]
+    pub fn to_cashaddr_with_coin(
+        address: &str,
+        coin: &str,
+    ) -> std::result::Result<String, JsValue> {
+        to_cashaddr_with_coin(address, coin).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Convert a Bitcoin Cash or eCash address (legacy or cashaddr) to legacy base58check format
+    #[wasm_bindgen]
+    pub fn to_legacy_address_with_coin(
+        address: &str,
+        coin: &str,
+    ) -> std::result::Result<String, JsValue> {
+        to_legacy_address_with_coin(address, coin).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
 }
@@ -370,6 +370,11 @@ impl WasmBIP32 {
         WasmBIP32(BIP32Key::Public(xpub))
     }
 
+    /// Create from Xpriv (for internal Rust use, not exposed to JS)
+    pub(crate) fn from_xpriv_internal(xpriv: crate::bitcoin::bip32::Xpriv) -> WasmBIP32 {
+        WasmBIP32(BIP32Key::Private(xpriv))
+    }
+
     /// Convert to Xpub (for internal Rust use, not exposed to JS)
     pub(crate) fn to_xpub(&self) -> Result<crate::bitcoin::bip32::Xpub, WasmUtxoError> {
         Ok(self.0.to_xpub())
@@ -6,22 +6,11 @@ use crate::bip322::bitgo_psbt;
 use crate::error::WasmUtxoError;
 use crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::SignerKey;
 use crate::fixed_script_wallet::PubTriple;
+use crate::wasm::networks::parse_network;
 use crate::wasm::wallet_keys::WasmRootWalletKeys;
 use miniscript::bitcoin::hex::FromHex;
 use miniscript::bitcoin::CompressedPublicKey;
 
-/// Parse a network from a string that can be either a utxolib name or a coin name
-fn parse_network(network_str: &str) -> Result<crate::networks::Network, WasmUtxoError> {
-    crate::networks::Network::from_utxolib_name(network_str)
-        .or_else(|| crate::networks::Network::from_coin_name(network_str))
-        .ok_or_else(|| {
-            WasmUtxoError::new(&format!(
-                "Unknown network '{}'. Expected a utxolib name (e.g., 'bitcoin', 'testnet') or coin name (e.g., 'btc', 'tbtc')",
-                network_str
-            ))
-        })
-}
-
 /// Namespace for BIP-0322 functions
 #[wasm_bindgen]
 pub struct Bip322Namespace;
@@ -1,7 +1,8 @@
 use crate::error::WasmUtxoError;
-use crate::wasm::try_from_js_value::get_field;
+use crate::timelock_preview::Confirmation;
+use crate::wasm::try_from_js_value::{get_field, TryFromJsValue};
 use crate::wasm::try_into_js_value::TryIntoJsValue;
-use miniscript::bitcoin::secp256k1::{Secp256k1, Signing};
+use miniscript::bitcoin::secp256k1::{PublicKey, Secp256k1, Signing, XOnlyPublicKey};
 use miniscript::bitcoin::ScriptBuf;
 use miniscript::descriptor::KeyMap;
 use miniscript::miniscript::analyzable::ExtParams;
@@ -57,6 +58,34 @@ impl WrapDescriptor {
         }
     }
 
+    /// Descriptor checksum (the 8 characters after `#` in the string representation)
+    pub fn checksum(&self) -> Result<String, WasmUtxoError> {
+        let descriptor = self.to_string();
+        descriptor
+            .rsplit_once('#')
+            .map(|(_, checksum)| checksum.to_string())
+            .ok_or_else(|| WasmUtxoError::new("Descriptor has no checksum"))
+    }
+
+    /// Definite descriptor at the derivation index.
+    ///
+    /// Unlike `atDerivationIndex`, this accepts descriptors without wildcards, for which the
+    /// index is ignored.
+    pub fn derive(&self, index: u32) -> Result<WrapDescriptor, WasmUtxoError> {
+        match &self.0 {
+            WrapDescriptorEnum::Definite(desc) => {
+                Ok(WrapDescriptor(WrapDescriptorEnum::Definite(desc.clone())))
+            }
+            _ => self.at_derivation_index(index),
+        }
+    }
+
+    /// Output script of the descriptor at the derivation index
+    #[wasm_bindgen(js_name = scriptPubkeyAt)]
+    pub fn script_pubkey_at(&self, index: u32) -> Result<Vec<u8>, WasmUtxoError> {
+        self.derive(index)?.script_pubkey()
+    }
+
     #[wasm_bindgen(js_name = descType)]
     pub fn desc_type(&self) -> Result<JsValue, WasmUtxoError> {
         (match &self.0 {
@@ -109,6 +138,49 @@ impl WrapDescriptor {
             .map_err(|_| WasmUtxoError::new("Weight exceeds u32"))
     }
 
+    /// Timelocks under which the descriptor can be satisfied with signatures of `keys`
+    ///
+    /// # Arguments
+    /// * `keys` - Public keys that can sign, compressed (33 bytes) or x-only (32 bytes)
+    /// * `confirmation` - Optional `{ height, medianTimePast }` of the block that includes the
+    ///   input (`medianTimePast` of the block before it), to resolve relative timelocks
+    ///
+    /// # Returns
+    /// An array of `{ after, older, unlockAt }`, one per way to satisfy the descriptor, where
+    /// `after` and `older` are `{ height }`, `{ time }` or `null`, and `unlockAt` is the
+    /// `{ height, time }` the chain must reach, or `null` for a relative timelock of an
+    /// unconfirmed input. An empty array means the keys cannot satisfy the descriptor.
+    #[wasm_bindgen(js_name = satisfactionPreview, skip_typescript)]
+    pub fn satisfaction_preview(
+        &self,
+        keys: Vec<js_sys::Uint8Array>,
+        confirmation: JsValue,
+    ) -> Result<JsValue, WasmUtxoError> {
+        let WrapDescriptorEnum::Definite(desc) = &self.0 else {
+            return Err(WasmUtxoError::new(
+                "Cannot preview satisfaction of a non-definite descriptor",
+            ));
+        };
+        let keys = keys
+            .iter()
+            .map(|key| {
+                let bytes = key.to_vec();
+                match bytes.len() {
+                    32 => XOnlyPublicKey::from_slice(&bytes),
+                    _ => PublicKey::from_slice(&bytes).map(XOnlyPublicKey::from),
+                }
+                .map_err(|e| WasmUtxoError::new(&format!("Invalid public key: {}", e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let confirmation = Option::<Confirmation>::try_from_js_value(&confirmation)?;
+        crate::timelock_preview::satisfaction_preview(desc, &keys)
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))?
+            .into_iter()
+            .map(|condition| (condition, condition.unlock_at(confirmation)))
+            .collect::<Vec<_>>()
+            .try_to_js_value()
+    }
+
     fn from_string_derivable<C: Signing>(
         secp: &Secp256k1<C>,
         descriptor: &str,
@@ -299,4 +371,30 @@ mod tests {
             }
         ));
     }
+
+    #[test]
+    fn test_checksum_and_derive() {
+        let desc = WrapDescriptor::from_string(
+            "wpkh(xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8/*)",
+            "derivable",
+        )
+        .unwrap();
+        let checksum = desc.checksum().unwrap();
+        assert_eq!(checksum.len(), 8);
+        assert!(desc.to_string().ends_with(&format!("#{}", checksum)));
+
+        assert!(desc.script_pubkey().is_err());
+        assert_eq!(
+            desc.script_pubkey_at(1).unwrap(),
+            desc.at_derivation_index(1)
+                .unwrap()
+                .script_pubkey()
+                .unwrap()
+        );
+        let derived = desc.derive(1).unwrap();
+        assert_eq!(
+            derived.derive(5).unwrap().script_pubkey().unwrap(),
+            derived.script_pubkey().unwrap()
+        );
+    }
 }
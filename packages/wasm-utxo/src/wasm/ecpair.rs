@@ -1,6 +1,8 @@
 use crate::bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
 use crate::bitcoin::PrivateKey;
 use crate::error::WasmUtxoError;
+use crate::wasm::networks::parse_network;
+use crate::wasm::try_into_js_value::TryIntoJsValue;
 use wasm_bindgen::prelude::*;
 
 // Internal enum to hold either public-only or private+public keys
@@ -88,11 +90,13 @@ impl WasmECPair {
         wif_string: &str,
         expected_network: Option<crate::bitcoin::NetworkKind>,
     ) -> Result<WasmECPair, WasmUtxoError> {
-        let private_key = PrivateKey::from_wif(wif_string)
+        let info = crate::wif::decode(wif_string)
             .map_err(|e| WasmUtxoError::new(&format!("Invalid WIF: {}", e)))?;
 
         if let Some(expected) = expected_network {
-            if private_key.network != expected {
+            // All networks sharing a WIF version byte are either mainnets or testnets
+            let is_mainnet = info.networks.iter().all(|n| n.is_mainnet());
+            if is_mainnet != matches!(expected, crate::bitcoin::NetworkKind::Main) {
                 let network_name = match expected {
                     crate::bitcoin::NetworkKind::Main => "mainnet",
                     crate::bitcoin::NetworkKind::Test => "testnet",
@@ -104,16 +108,18 @@ impl WasmECPair {
             }
         }
 
+        Ok(Self::from_secret_key(info.secret_key))
+    }
+
+    fn from_secret_key(secret_key: SecretKey) -> WasmECPair {
         let secp = Secp256k1::new();
-        let secret_key = private_key.inner;
         let public_key = PublicKey::from_secret_key(&secp, &secret_key);
-
-        Ok(WasmECPair {
+        WasmECPair {
             key: ECPairKey::Private {
                 secret_key,
                 public_key,
             },
-        })
+        }
     }
 
     /// Create an ECPair from a WIF string (auto-detects network)
@@ -136,6 +142,31 @@ impl WasmECPair {
         Self::from_wif_with_network_check(wif_string, Some(NetworkKind::Test))
     }
 
+    /// Create an ECPair from a WIF string, checking the version byte against a network
+    ///
+    /// # Arguments
+    /// - `network`: utxolib name (e.g. "litecoin") or coin name (e.g. "ltc")
+    #[wasm_bindgen]
+    pub fn from_wif_with_network(
+        wif_string: &str,
+        network: &str,
+    ) -> Result<WasmECPair, WasmUtxoError> {
+        let info = crate::wif::decode_for_network(wif_string, parse_network(network)?)
+            .map_err(|e| WasmUtxoError::new(&format!("Invalid WIF: {}", e)))?;
+        Ok(Self::from_secret_key(info.secret_key))
+    }
+
+    /// Decode a WIF string without constructing a key pair
+    ///
+    /// Returns `{ compressed, version, networks }` where `networks` lists the coin names
+    /// of all networks using the WIF version byte.
+    #[wasm_bindgen]
+    pub fn decode_wif(wif_string: &str) -> Result<JsValue, WasmUtxoError> {
+        let info = crate::wif::decode(wif_string)
+            .map_err(|e| WasmUtxoError::new(&format!("Invalid WIF: {}", e)))?;
+        info.try_to_js_value()
+    }
+
     /// Get the private key as a Uint8Array (if available)
     #[wasm_bindgen(getter)]
     pub fn private_key(&self) -> Option<js_sys::Uint8Array> {
@@ -172,6 +203,23 @@ impl WasmECPair {
         self.to_wif_with_network(NetworkKind::Test)
     }
 
+    /// Convert to WIF string using the version byte of a network
+    ///
+    /// # Arguments
+    /// - `network`: utxolib name (e.g. "dogecoin") or coin name (e.g. "doge")
+    #[wasm_bindgen]
+    pub fn to_wif_for_network(&self, network: &str) -> Result<String, WasmUtxoError> {
+        let secret_key = self
+            .key
+            .secret_key()
+            .ok_or_else(|| WasmUtxoError::new("Cannot get WIF from public key"))?;
+        Ok(crate::wif::encode(
+            &secret_key,
+            parse_network(network)?,
+            true,
+        ))
+    }
+
     fn to_wif_with_network(
         &self,
         network: crate::bitcoin::NetworkKind,
@@ -0,0 +1,100 @@
+use crate::bitcoin::bip32::Xpriv;
+use crate::electrum_wallet::ElectrumWalletKeys;
+use crate::error::WasmUtxoError;
+use crate::wasm::bip32::WasmBIP32;
+use crate::wasm::networks::parse_network;
+use crate::wasm::wallet_keys::WasmRootWalletKeys;
+use wasm_bindgen::prelude::*;
+
+/// WASM wrapper for an Electrum 2.x wallet
+///
+/// Holds the master public key and, when imported from a seed, the matching xprv.
+#[wasm_bindgen]
+pub struct WasmElectrumWallet {
+    inner: ElectrumWalletKeys,
+    xprv: Option<Xpriv>,
+}
+
+#[wasm_bindgen]
+impl WasmElectrumWallet {
+    /// Import a master public key (xpub/tpub for standard, zpub/vpub for segwit wallets)
+    #[wasm_bindgen]
+    pub fn from_mpk(mpk: &str) -> Result<WasmElectrumWallet, WasmUtxoError> {
+        let inner =
+            ElectrumWalletKeys::from_mpk(mpk).map_err(|e| WasmUtxoError::new(&e.to_string()))?;
+        Ok(WasmElectrumWallet { inner, xprv: None })
+    }
+
+    /// Import an Electrum 2.x seed
+    #[wasm_bindgen]
+    pub fn from_seed(
+        mnemonic: &str,
+        passphrase: &str,
+        network: &str,
+    ) -> Result<WasmElectrumWallet, WasmUtxoError> {
+        let network = parse_network(network)?;
+        let (inner, xprv) = ElectrumWalletKeys::from_seed(mnemonic, passphrase, network)
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))?;
+        Ok(WasmElectrumWallet {
+            inner,
+            xprv: Some(xprv),
+        })
+    }
+
+    /// The master public key in Electrum's export format
+    #[wasm_bindgen]
+    pub fn mpk(&self) -> String {
+        self.inner.to_mpk_string()
+    }
+
+    /// "standard" or "segwit"
+    #[wasm_bindgen]
+    pub fn seed_type(&self) -> String {
+        self.inner.seed_type.as_str().to_string()
+    }
+
+    /// Single-sig script type of the wallet outputs ("p2pkh" or "p2wpkh")
+    #[wasm_bindgen]
+    pub fn script_type(&self) -> String {
+        self.inner.seed_type.script_type().as_str().to_string()
+    }
+
+    /// Pseudo wallet keys holding the mpk as user key, for use with BitGoPsbt
+    #[wasm_bindgen]
+    pub fn root_wallet_keys(&self) -> WasmRootWalletKeys {
+        WasmRootWalletKeys::from_inner(self.inner.root_wallet_keys().clone())
+    }
+
+    /// The extended private key of the mpk, if the wallet was imported from a seed
+    #[wasm_bindgen]
+    pub fn xprv(&self) -> Option<WasmBIP32> {
+        self.xprv.map(WasmBIP32::from_xpriv_internal)
+    }
+
+    /// Derivation path of an address relative to the mpk ("m/0/i" or "m/1/i")
+    #[wasm_bindgen]
+    pub fn derivation_path(change: bool, index: u32) -> String {
+        format!("m/{}", ElectrumWalletKeys::derivation_path(change, index))
+    }
+
+    #[wasm_bindgen]
+    pub fn output_script(&self, change: bool, index: u32) -> Result<Vec<u8>, WasmUtxoError> {
+        self.inner
+            .output_script(change, index)
+            .map(|script| script.to_bytes())
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))
+    }
+
+    #[wasm_bindgen]
+    pub fn address(
+        &self,
+        change: bool,
+        index: u32,
+        network: &str,
+    ) -> Result<String, WasmUtxoError> {
+        let network = parse_network(network)?;
+        self.inner
+            .address(change, index, network)
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))
+    }
+}
@@ -7,10 +7,14 @@ use std::str::FromStr;
 
 use crate::error::WasmUtxoError;
 use crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::{
-    parse_shared_chain_and_index, InputScriptType,
+    get_output_script_and_value, p2sh_p2pk_signed_script_sig_size, parse_shared_chain_and_index,
+    InputScriptType,
 };
+use crate::fixed_script_wallet::bitgo_psbt::SingleSigScriptType;
 use crate::fixed_script_wallet::wallet_scripts::OutputScriptType;
 use crate::fixed_script_wallet::Chain;
+use crate::wasm::try_from_js_value::{get_field, get_nested_field};
+use crate::wasm::try_into_js_value::TryIntoJsValue;
 use miniscript::bitcoin::VarInt;
 use wasm_bindgen::prelude::*;
 
@@ -38,6 +42,7 @@ const OP_CHECKSIGVERIFY_SIZE: usize = OP_SIZE;
 
 // Public key sizes
 const SCHNORR_PUBKEY_SIZE: usize = 32;
+const ECDSA_PUBKEY_SIZE: usize = 33;
 const P2MS_PUB_SCRIPT_SIZE: usize = 105; // 2-of-3 multisig script with compressed pubkeys
 const P2WSH_PUB_SCRIPT_SIZE: usize = 34;
 const P2PK_PUB_SCRIPT_SIZE: usize = 35;
@@ -185,6 +190,19 @@ fn get_p2sh_p2pk_components(sig_size: usize, compat: bool) -> Vec<usize> {
     ]
 }
 
+/// Get p2pkh input components (single signature and compressed pubkey)
+fn get_p2pkh_components(sig_size: usize) -> Vec<usize> {
+    vec![
+        OP_PUSH_SIZE + sig_size,          // signature
+        OP_PUSH_SIZE + ECDSA_PUBKEY_SIZE, // pubkey
+    ]
+}
+
+/// Get p2wpkh input components (single signature and compressed pubkey in witness)
+fn get_p2wpkh_components(sig_size: usize) -> (Vec<usize>, Vec<usize>) {
+    (vec![], vec![sig_size, ECDSA_PUBKEY_SIZE])
+}
+
 /// Get input weight range for a given script type
 ///
 /// # Arguments
@@ -279,6 +297,46 @@ fn get_input_weights_for_type(script_type: InputScriptType, compat: bool) -> Inp
                 is_segwit: false,
             }
         }
+        InputScriptType::P2pkh => {
+            let min = compute_input_weight(&get_p2pkh_components(ECDSA_SIG_MIN), &[]);
+            let max = compute_input_weight(&get_p2pkh_components(sig_max), &[]);
+            InputWeights {
+                min,
+                max,
+                is_segwit: false,
+            }
+        }
+        InputScriptType::P2wpkh => {
+            let (script_min, witness_min) = get_p2wpkh_components(ECDSA_SIG_MIN);
+            let (script_max, witness_max) = get_p2wpkh_components(sig_max);
+            let min = compute_input_weight(&script_min, &witness_min);
+            let max = compute_input_weight(&script_max, &witness_max);
+            InputWeights {
+                min,
+                max,
+                is_segwit: true,
+            }
+        }
+        InputScriptType::P2trKeyPath => {
+            // Schnorr signatures have no variance, compat flag has no effect
+            let (script, witness) = get_p2tr_keypath_components();
+            let w = compute_input_weight(&script, &witness);
+            InputWeights {
+                min: w,
+                max: w,
+                is_segwit: true,
+            }
+        }
+        InputScriptType::External(_) => {
+            // Spend conditions of external inputs are unknown: count the outpoint,
+            // sequence and an empty scriptSig only
+            let w = compute_input_weight(&[], &[]);
+            InputWeights {
+                min: w,
+                max: w,
+                is_segwit: false,
+            }
+        }
     }
 }
 
@@ -359,6 +417,9 @@ fn parse_script_type(script_type: &str) -> Result<InputScriptType, String> {
         "p2trMusig2ScriptPath" => Ok(InputScriptType::P2trMusig2ScriptPath),
         "p2shP2pk" => Ok(InputScriptType::P2shP2pk),
         "p2mr" => Ok(InputScriptType::P2mr),
+        "p2pkh" => Ok(InputScriptType::P2pkh),
+        "p2wpkh" => Ok(InputScriptType::P2wpkh),
+        "p2trKeyPath" => Ok(InputScriptType::P2trKeyPath),
         _ => Err(format!("Unknown script type: {}", script_type)),
     }
 }
@@ -373,6 +434,146 @@ fn compute_output_weight(script_length: usize) -> usize {
     4 * (8 + var_slice_size(script_length))
 }
 
+/// Get the output script length for a given script type
+fn output_script_length(script_type: OutputScriptType) -> usize {
+    match script_type {
+        // P2SH: OP_HASH160 [20 bytes] OP_EQUAL = 23 bytes
+        OutputScriptType::P2sh | OutputScriptType::P2shP2wsh => 23,
+        // P2WSH: OP_0 [32 bytes] = 34 bytes
+        OutputScriptType::P2wsh => 34,
+        // P2TR: OP_1 [32 bytes] = 34 bytes
+        OutputScriptType::P2trLegacy | OutputScriptType::P2trMusig2 => 34,
+        // P2MR: OP_2 [32 bytes] = 34 bytes
+        OutputScriptType::P2mr => 34,
+    }
+}
+
+// ============================================================================
+// Size estimation from input and output specs
+// ============================================================================
+
+/// Input kind of a size estimate
+enum InputSpec {
+    ScriptType(InputScriptType),
+    Chain {
+        chain: u32,
+        cosigner: Option<String>,
+    },
+}
+
+impl InputSpec {
+    fn weights(&self, compat: bool) -> Result<InputWeights, String> {
+        match self {
+            InputSpec::ScriptType(script_type) => {
+                Ok(get_input_weights_for_type(*script_type, compat))
+            }
+            InputSpec::Chain { chain, cosigner } => {
+                get_input_weights_for_chain(*chain, None, cosigner.as_deref(), compat)
+            }
+        }
+    }
+}
+
+/// Output kind of a size estimate
+enum OutputSpec {
+    ScriptType(OutputScriptType),
+    ScriptLength(usize),
+}
+
+impl OutputSpec {
+    fn script_length(&self) -> usize {
+        match self {
+            OutputSpec::ScriptType(script_type) => output_script_length(*script_type),
+            OutputSpec::ScriptLength(length) => *length,
+        }
+    }
+}
+
+/// Virtual size bounds of a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct VSizeEstimate {
+    pub(crate) min: u32,
+    pub(crate) max: u32,
+}
+
+/// Estimate the virtual size of a transaction with `count` inputs and outputs of each spec
+///
+/// With `compat`, the estimate matches @bitgo/unspents: signatures are counted as 72 bytes
+/// and each input is rounded up to whole vbytes before summing. The rounding makes the
+/// estimate up to one vbyte per segwit input larger than the vsize of the total weight.
+fn estimate_vsize(
+    inputs: &[(InputSpec, u32)],
+    outputs: &[(OutputSpec, u32)],
+    compat: bool,
+) -> Result<VSizeEstimate, String> {
+    let mut dimensions = WasmDimensions::empty();
+    let mut rounded_min = 0;
+    let mut rounded_max = 0;
+    for (spec, count) in inputs {
+        let weights = spec.weights(compat)?;
+        rounded_min += weights.min.div_ceil(4) * *count as usize;
+        rounded_max += weights.max.div_ceil(4) * *count as usize;
+        dimensions = dimensions.plus(&WasmDimensions::from_input_weights(weights).times(*count));
+    }
+    for (spec, count) in outputs {
+        let output = WasmDimensions::from_output_script_length(spec.script_length() as u32);
+        dimensions = dimensions.plus(&output.times(*count));
+    }
+
+    if !compat {
+        return Ok(VSizeEstimate {
+            min: dimensions.get_vsize(Some("min".to_string())),
+            max: dimensions.get_vsize(None),
+        });
+    }
+    let fixed = (dimensions.get_overhead_weight() + dimensions.output_weight).div_ceil(4);
+    Ok(VSizeEstimate {
+        min: (fixed + rounded_min) as u32,
+        max: (fixed + rounded_max) as u32,
+    })
+}
+
+/// Parse `{ scriptType, count? }` or `{ chain, signPath?, count? }`
+fn input_spec_from_js(value: &JsValue) -> Result<(InputSpec, u32), WasmUtxoError> {
+    let count = get_field::<Option<u32>>(value, "count")?.unwrap_or(1);
+    let spec = match get_field::<Option<String>>(value, "scriptType")? {
+        Some(script_type) => InputSpec::ScriptType(
+            parse_script_type(&script_type).map_err(|e| WasmUtxoError::new(&e))?,
+        ),
+        None => InputSpec::Chain {
+            chain: get_field(value, "chain")?,
+            cosigner: get_nested_field(value, "signPath.cosigner").unwrap_or(None),
+        },
+    };
+    Ok((spec, count))
+}
+
+/// Parse `{ scriptType, count? }` or `{ length, count? }`
+fn output_spec_from_js(value: &JsValue) -> Result<(OutputSpec, u32), WasmUtxoError> {
+    let count = get_field::<Option<u32>>(value, "count")?.unwrap_or(1);
+    let spec = match get_field::<Option<String>>(value, "scriptType")? {
+        Some(script_type) => OutputSpec::ScriptType(
+            OutputScriptType::from_str(&script_type).map_err(|e| WasmUtxoError::new(&e))?,
+        ),
+        None => OutputSpec::ScriptLength(get_field::<u32>(value, "length")? as usize),
+    };
+    Ok((spec, count))
+}
+
+fn specs_from_js<T>(
+    value: &JsValue,
+    name: &str,
+    parse: impl Fn(&JsValue) -> Result<T, WasmUtxoError>,
+) -> Result<Vec<T>, WasmUtxoError> {
+    if !js_sys::Array::is_array(value) {
+        return Err(WasmUtxoError::new(&format!("'{}' must be an array", name)));
+    }
+    js_sys::Array::from(value)
+        .iter()
+        .map(|v| parse(&v))
+        .collect()
+}
+
 // ============================================================================
 // WasmDimensions struct
 // ============================================================================
@@ -389,6 +590,17 @@ pub struct WasmDimensions {
     has_segwit: bool,
 }
 
+impl WasmDimensions {
+    fn from_input_weights(weights: InputWeights) -> WasmDimensions {
+        WasmDimensions {
+            input_weight_min: weights.min,
+            input_weight_max: weights.max,
+            output_weight: 0,
+            has_segwit: weights.is_segwit,
+        }
+    }
+}
+
 #[wasm_bindgen]
 impl WasmDimensions {
     /// Create empty dimensions (zero weight)
@@ -416,6 +628,19 @@ impl WasmDimensions {
 
         // Process inputs
         for (i, psbt_input) in inner_psbt.inputs.iter().enumerate() {
+            // Single-sig inputs have a single derivation that is not a chain/index path
+            let single_sig_type =
+                get_output_script_and_value(psbt_input, unsigned_tx.input[i].previous_output)
+                    .ok()
+                    .and_then(|(script, _)| SingleSigScriptType::from_input(psbt_input, script));
+            if let Some(script_type) = single_sig_type {
+                let weights = get_input_weights_for_type(script_type.into(), false);
+                input_weight_min += weights.min;
+                input_weight_max += weights.max;
+                has_segwit = has_segwit || weights.is_segwit;
+                continue;
+            }
+
             // Try to get chain from derivation paths
             let weights = match parse_shared_chain_and_index(psbt_input) {
                 Ok((chain, _index)) => {
@@ -451,8 +676,19 @@ impl WasmDimensions {
                 Err(_) => {
                     // No derivation path - check if it's a replay protection input
                     // Replay protection inputs have unknownKeyVals with specific markers
-                    // For now, assume p2shP2pk for inputs without derivation paths
-                    get_input_weights_for_type(InputScriptType::P2shP2pk, false)
+                    // For now, assume p2shP2pk for inputs without derivation paths.
+                    // Once signed, the scriptSig size is known exactly.
+                    match p2sh_p2pk_signed_script_sig_size(psbt_input) {
+                        Some(script_sig_size) => {
+                            let w = compute_input_weight(&[script_sig_size], &[]);
+                            InputWeights {
+                                min: w,
+                                max: w,
+                                is_segwit: false,
+                            }
+                        }
+                        None => get_input_weights_for_type(InputScriptType::P2shP2pk, false),
+                    }
                 }
             };
 
@@ -542,17 +778,31 @@ impl WasmDimensions {
     /// * `script_type` - One of: "p2sh", "p2shP2wsh", "p2wsh", "p2tr"/"p2trLegacy", "p2trMusig2"
     pub fn from_output_script_type(script_type: &str) -> Result<WasmDimensions, WasmUtxoError> {
         let parsed = OutputScriptType::from_str(script_type).map_err(|e| WasmUtxoError::new(&e))?;
-        let length = match parsed {
-            // P2SH: OP_HASH160 [20 bytes] OP_EQUAL = 23 bytes
-            OutputScriptType::P2sh | OutputScriptType::P2shP2wsh => 23,
-            // P2WSH: OP_0 [32 bytes] = 34 bytes
-            OutputScriptType::P2wsh => 34,
-            // P2TR: OP_1 [32 bytes] = 34 bytes
-            OutputScriptType::P2trLegacy | OutputScriptType::P2trMusig2 => 34,
-            // P2MR: OP_2 [32 bytes] = 34 bytes
-            OutputScriptType::P2mr => 34,
-        };
-        Ok(Self::from_output_script_length(length))
+        Ok(Self::from_output_script_length(
+            output_script_length(parsed) as u32,
+        ))
+    }
+
+    /// Estimate the virtual size of a transaction from input and output specs
+    ///
+    /// # Arguments
+    /// * `inputs` - Array of `{ scriptType, count? }` or `{ chain, signPath?, count? }`
+    /// * `outputs` - Array of `{ scriptType, count? }` or `{ length, count? }`
+    /// * `compat` - When true, match @bitgo/unspents: 72-byte signatures, and each input
+    ///   rounded up to whole vbytes
+    ///
+    /// # Returns
+    /// `{ min, max }` virtual sizes
+    pub fn estimate_vsize(
+        inputs: JsValue,
+        outputs: JsValue,
+        compat: Option<bool>,
+    ) -> Result<JsValue, WasmUtxoError> {
+        let inputs = specs_from_js(&inputs, "inputs", input_spec_from_js)?;
+        let outputs = specs_from_js(&outputs, "outputs", output_spec_from_js)?;
+        let estimate = estimate_vsize(&inputs, &outputs, compat.unwrap_or(false))
+            .map_err(|e| WasmUtxoError::new(&e))?;
+        estimate.try_to_js_value()
     }
 
     /// Combine with another Dimensions instance
@@ -652,3 +902,71 @@ impl WasmDimensions {
         (self.output_weight as u32).div_ceil(4)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input_spec(value: &serde_json::Value) -> (InputSpec, u32) {
+        let spec = match value["scriptType"].as_str() {
+            Some(script_type) => InputSpec::ScriptType(parse_script_type(script_type).unwrap()),
+            None => InputSpec::Chain {
+                chain: value["chain"].as_u64().unwrap() as u32,
+                cosigner: value["signPath"]["cosigner"].as_str().map(String::from),
+            },
+        };
+        (spec, value["count"].as_u64().unwrap_or(1) as u32)
+    }
+
+    fn output_spec(value: &serde_json::Value) -> (OutputSpec, u32) {
+        let script_type =
+            OutputScriptType::from_str(value["scriptType"].as_str().unwrap()).unwrap();
+        (
+            OutputSpec::ScriptType(script_type),
+            value["count"].as_u64().unwrap_or(1) as u32,
+        )
+    }
+
+    /// The fixture holds the vsize of @bitgo/unspents Dimensions for each case
+    #[test]
+    fn test_estimate_vsize_matches_utxolib_fixtures() {
+        let content = std::fs::read_to_string("test/fixtures/dimensions/utxolib-vsize.json")
+            .expect("Failed to load utxolib-vsize.json");
+        let fixture: serde_json::Value = serde_json::from_str(&content).unwrap();
+        for case in fixture["cases"].as_array().unwrap() {
+            let inputs: Vec<_> = case["inputs"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(input_spec)
+                .collect();
+            let outputs: Vec<_> = case["outputs"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(output_spec)
+                .collect();
+            let expected = case["vsize"].as_u64().unwrap() as u32;
+
+            let compat = estimate_vsize(&inputs, &outputs, true).unwrap();
+            assert_eq!(compat.max, expected, "{}", case);
+            let exact = estimate_vsize(&inputs, &outputs, false).unwrap();
+            assert!(
+                exact.min <= compat.min && compat.min <= compat.max,
+                "{}",
+                case
+            );
+        }
+    }
+
+    #[test]
+    fn test_compat_rounds_each_input() {
+        let inputs = [(InputSpec::ScriptType(InputScriptType::P2trMusig2KeyPath), 2)];
+        let outputs = [(OutputSpec::ScriptLength(34), 1)];
+        // 2 * 230 input weight: 115 vbytes in total, but 58 vbytes per input
+        let exact = estimate_vsize(&inputs, &outputs, false).unwrap();
+        assert_eq!(exact, VSizeEstimate { min: 169, max: 169 });
+        let compat = estimate_vsize(&inputs, &outputs, true).unwrap();
+        assert_eq!(compat, VSizeEstimate { min: 170, max: 170 });
+    }
+}
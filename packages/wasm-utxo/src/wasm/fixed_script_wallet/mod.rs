@@ -1,35 +1,79 @@
 mod dimensions;
+mod tx_package;
 
+pub(crate) use dimensions::VSizeEstimate;
 pub use dimensions::WasmDimensions;
+pub use tx_package::WasmTxPackage;
 
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsValue;
 
 use crate::address::networks::AddressFormat;
 use crate::error::WasmUtxoError;
-use crate::fixed_script_wallet::bitgo_psbt::ExtractFeePolicy;
+use crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::SignPath;
+use crate::fixed_script_wallet::bitgo_psbt::{
+    CancellationToken, ExternalInputPolicy, ExtractFeePolicy, InputProgress, ProgressError,
+    SequenceMode,
+};
 use crate::fixed_script_wallet::wallet_scripts::{chain_index_path, OutputScriptType};
-use crate::fixed_script_wallet::{Chain, Scope, WalletScripts};
+use crate::fixed_script_wallet::{Chain, FundingOutput, FundingOutputError, Scope, WalletScripts};
 use crate::utxolib_compat::UtxolibNetwork;
 use crate::wasm::bip32::WasmBIP32;
 use crate::wasm::ecpair::WasmECPair;
+use crate::wasm::networks::parse_network;
 use crate::wasm::psbt_ops::WasmPsbtOps;
 use crate::wasm::replay_protection::WasmReplayProtection;
-use crate::wasm::try_from_js_value::TryFromJsValue;
+use crate::wasm::try_from_js_value::{get_field, TryFromJsValue};
 use crate::wasm::try_into_js_value::TryIntoJsValue;
 use crate::wasm::wallet_keys::WasmRootWalletKeys;
 
-/// Parse a network from a string that can be either a utxolib name or a coin name
-fn parse_network(network_str: &str) -> Result<crate::networks::Network, WasmUtxoError> {
-    crate::networks::Network::from_utxolib_name(network_str)        .or_else(|| crate::networks::Network::from_coin_name(network_str))
-        .ok_or_else(|| {
-            WasmUtxoError::new(&format!(
-                "Unknown network '{}'. Expected a utxolib name (e.g., 'bitcoin', 'testnet') or coin name (e.g., 'btc', 'tbtc')",
-                network_str
-            ))
-        })
+/// Low-R grinding of the ECDSA signatures made through the bindings, see `set_low_r_grinding`
+static LOW_R_GRINDING: AtomicBool = AtomicBool::new(true);
+
+/// Parse the optional `signer`/`cosigner` pair of a wallet input
+fn parse_sign_path(
+    signer: Option<String>,
+    cosigner: Option<String>,
+) -> Result<Option<SignPath>, WasmUtxoError> {
+    use crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::SignerKey;
+
+    match (signer.as_deref(), cosigner.as_deref()) {
+        (Some(signer_str), Some(cosigner_str)) => {
+            let signer: SignerKey = signer_str
+                .parse()
+                .map_err(|e: String| WasmUtxoError::new(&e))?;
+            let cosigner: SignerKey = cosigner_str
+                .parse()
+                .map_err(|e: String| WasmUtxoError::new(&e))?;
+            Ok(Some(SignPath { signer, cosigner }))
+        }
+        (None, None) => Ok(None),
+        _ => Err(WasmUtxoError::new(
+            "Both signer and cosigner must be provided together or both omitted",
+        )),
+    }
+}
+
+/// Convert a JS-side fee rate in sat/vB into a [`FeeRate`]
+///
+/// The rate is converted to sat/kwu (1 sat/vB = 250 sat/kwu) and rounded to the nearest
+/// integer, so fractional rates such as 1.9 sat/vB are kept.
+///
+/// # Returns
+/// - `Err(WasmUtxoError)` if the rate is `NaN`, infinite or negative
+fn fee_rate_from_js(sat_per_vb: f64) -> Result<miniscript::bitcoin::FeeRate, WasmUtxoError> {
+    if !sat_per_vb.is_finite() || sat_per_vb < 0.0 {
+        return Err(WasmUtxoError::new(&format!(
+            "Invalid fee rate: {} sat/vB",
+            sat_per_vb
+        )));
+    }
+    Ok(miniscript::bitcoin::FeeRate::from_sat_per_kwu(
+        (sat_per_vb * 250.0).round() as u64,
+    ))
 }
 
 /// Convert a JS-side `maxFeeRate` (sat/vB, `number | undefined | Infinity`)
@@ -58,6 +102,166 @@ fn fee_policy_from_js(max_fee_rate_sat_per_vb: Option<f64>) -> ExtractFeePolicy
     }
 }
 
+/// Build a [`SigningPolicy`] from the JS-side limits
+///
+/// [`SigningPolicy`]: crate::fixed_script_wallet::bitgo_psbt::SigningPolicy
+fn signing_policy_from_js(
+    max_fee: Option<u64>,
+    max_fee_rate_sat_per_vb: Option<f64>,
+    max_fee_percent: Option<f64>,
+    require_wallet_change: Option<bool>,
+) -> Result<crate::fixed_script_wallet::bitgo_psbt::SigningPolicy, WasmUtxoError> {
+    if let Some(percent) = max_fee_percent.filter(|p| !p.is_finite() || *p < 0.0) {
+        return Err(WasmUtxoError::new(&format!(
+            "Invalid maximum fee percentage: {}",
+            percent
+        )));
+    }
+    Ok(crate::fixed_script_wallet::bitgo_psbt::SigningPolicy {
+        max_fee,
+        max_fee_rate: max_fee_rate_sat_per_vb.map(fee_rate_from_js).transpose()?,
+        max_fee_percent,
+        require_wallet_change: require_wallet_change.unwrap_or(false),
+    })
+}
+
+/// Build the 2-of-2 funding output of two compressed public keys
+fn parse_funding_output(
+    output_type: &str,
+    pubkey_a: &[u8],
+    pubkey_b: &[u8],
+) -> Result<FundingOutput, FundingOutputError> {
+    let key = |bytes: &[u8]| {
+        miniscript::bitcoin::CompressedPublicKey::from_slice(bytes)
+            .map_err(|e| FundingOutputError::InvalidPublicKey(e.to_string()))
+    };
+    FundingOutput::new(output_type.parse()?, [key(pubkey_a)?, key(pubkey_b)?])
+}
+
+/// Parse `{ pubkey, masterFingerprint, path }[]` into PSBT key sources
+fn parse_funding_key_sources(
+    items: Option<js_sys::Array>,
+) -> Result<
+    std::collections::BTreeMap<
+        miniscript::bitcoin::secp256k1::PublicKey,
+        miniscript::bitcoin::bip32::KeySource,
+    >,
+    WasmUtxoError,
+> {
+    use miniscript::bitcoin::bip32::{DerivationPath, Fingerprint};
+    use miniscript::bitcoin::secp256k1::PublicKey;
+
+    let Some(items) = items else {
+        return Ok(Default::default());
+    };
+    items
+        .iter()
+        .map(|item| -> Result<_, WasmUtxoError> {
+            let pubkey: Vec<u8> = get_field(&item, "pubkey")?;
+            let fingerprint: String = get_field(&item, "masterFingerprint")?;
+            let path: String = get_field(&item, "path")?;
+            let pubkey = PublicKey::from_slice(&pubkey)
+                .map_err(|e| FundingOutputError::InvalidPublicKey(e.to_string()))?;
+            let fingerprint = Fingerprint::from_str(&fingerprint).map_err(|e| {
+                WasmUtxoError::new(&format!(
+                    "Invalid master fingerprint '{}': {}",
+                    fingerprint, e
+                ))
+            })?;
+            let path = DerivationPath::from_str(&path)
+                .map_err(|e| WasmUtxoError::new(&format!("Invalid path '{}': {}", path, e)))?;
+            Ok((pubkey, (fingerprint, path)))
+        })
+        .collect()
+}
+
+/// Inputs between progress callbacks when no `progressInterval` is given
+const DEFAULT_PROGRESS_INTERVAL: u32 = 100;
+
+/// `{ onProgress?, progressInterval?, signal?, startInput? }` of the JS wrappers
+struct JsProgressOptions {
+    on_progress: Option<js_sys::Function>,
+    interval: u32,
+    /// An `AbortSignal`
+    signal: Option<JsValue>,
+    start_input: u32,
+}
+
+impl JsProgressOptions {
+    /// Returns `None` if neither a callback, a signal nor a start input is given
+    fn from_js(options: &JsValue) -> Result<Option<Self>, WasmUtxoError> {
+        if options.is_undefined() || options.is_null() {
+            return Ok(None);
+        }
+        let on_progress = js_sys::Reflect::get(options, &"onProgress".into())
+            .ok()
+            .and_then(|f| f.dyn_into::<js_sys::Function>().ok());
+        let signal = js_sys::Reflect::get(options, &"signal".into())
+            .ok()
+            .filter(|s| !s.is_undefined() && !s.is_null());
+        let start_input = get_field::<Option<u32>>(options, "startInput")?.unwrap_or(0);
+        if on_progress.is_none() && signal.is_none() && start_input == 0 {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            on_progress,
+            interval: get_field::<Option<u32>>(options, "progressInterval")?
+                .unwrap_or(DEFAULT_PROGRESS_INTERVAL),
+            signal,
+            start_input,
+        }))
+    }
+
+    fn is_aborted(&self) -> bool {
+        self.signal.as_ref().is_some_and(|signal| {
+            js_sys::Reflect::get(signal, &"aborted".into())
+                .map(|aborted| aborted.is_truthy())
+                .unwrap_or(false)
+        })
+    }
+}
+
+/// Run `f` with an [`InputProgress`] built from the JS progress options, or with `None` if
+/// there are none.
+///
+/// The `AbortSignal` is checked before the first chunk and after each progress report.
+/// Exceptions thrown by `onProgress` are ignored.
+fn with_js_progress<T, E>(
+    options: &JsValue,
+    f: impl FnOnce(Option<&mut InputProgress<'_>>) -> Result<T, ProgressError<E>>,
+    map_failed: impl FnOnce(E) -> WasmUtxoError,
+) -> Result<T, WasmUtxoError> {
+    let result = match JsProgressOptions::from_js(options)? {
+        None => f(None),
+        Some(options) => {
+            let token = CancellationToken::new();
+            if options.is_aborted() {
+                token.cancel();
+            }
+            let mut callback = |processed: usize, total: usize| {
+                if let Some(on_progress) = &options.on_progress {
+                    let _ = on_progress.call2(
+                        &JsValue::NULL,
+                        &JsValue::from(processed as u32),
+                        &JsValue::from(total as u32),
+                    );
+                }
+                if options.is_aborted() {
+                    token.cancel();
+                }
+            };
+            let mut progress = InputProgress::new(options.interval as usize, &mut callback)
+                .with_cancellation(token.clone())
+                .starting_at(options.start_input as usize);
+            f(Some(&mut progress))
+        }
+    };
+    result.map_err(|e| match e {
+        ProgressError::Cancelled(cancelled) => WasmUtxoError::from(cancelled),
+        ProgressError::Failed(e) => map_failed(e),
+    })
+}
+
 #[wasm_bindgen]
 pub struct FixedScriptWalletNamespace;
 
@@ -165,6 +369,165 @@ impl FixedScriptWalletNamespace {
         Ok(address)
     }
 
+    /// Get the ranged output descriptor (without checksum) for the scripts of a chain
+    ///
+    /// Fails for p2trLegacy and p2mr chains, which have no descriptor representation.
+    #[wasm_bindgen]
+    pub fn descriptor_for_chain(
+        keys: &WasmRootWalletKeys,
+        chain: u32,
+    ) -> Result<String, WasmUtxoError> {
+        crate::fixed_script_wallet::descriptor_for_chain(keys.inner(), chain)
+    }
+
+    /// Match Bitcoin Core `listunspent` entries to wallet scripts
+    ///
+    /// Each entry needs `txid`, `vout`, `scriptPubKey`, `amount` (BTC) and `desc`. The chain
+    /// and index are recovered from the descriptor key origins and checked against the
+    /// wallet keys. Returns `{ input: { txid, vout, value }, scriptId, scriptType }` objects.
+    #[wasm_bindgen]
+    pub fn import_core_unspents(
+        keys: &WasmRootWalletKeys,
+        unspents: js_sys::Array,
+        network: &str,
+    ) -> Result<JsValue, WasmUtxoError> {
+        use crate::fixed_script_wallet::core_unspent::CoreUnspent;
+
+        let network = parse_network(network)?;
+        let imported = unspents
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                CoreUnspent::try_from_js_value(&item)
+                    .and_then(|unspent| {
+                        unspent
+                            .to_wallet_unspent(keys.inner(), network)
+                            .map_err(|e| WasmUtxoError::new(&e.to_string()))
+                    })
+                    .map_err(|e| WasmUtxoError::new(&format!("Unspent {}: {}", i, e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        imported.try_to_js_value()
+    }
+
+    /// Plan the PSBTs moving p2sh and p2shP2wsh unspents to p2trMusig2 change addresses
+    ///
+    /// `unspents` are `{ input: { txid, vout, value }, scriptId }` objects, as returned by
+    /// `import_core_unspents`. Each PSBT spends at most `max_inputs` of them, in the given
+    /// order, and pays the fee for its maximum estimated vsize at `fee_rate` (sat/vB).
+    ///
+    /// # Returns
+    /// The serialized unsigned PSBTs
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn plan_migration(
+        keys: &WasmRootWalletKeys,
+        unspents: js_sys::Array,
+        network: &str,
+        max_inputs: u32,
+        fee_rate: f64,
+        change_index: u32,
+        sequence: JsValue,
+    ) -> Result<JsValue, WasmUtxoError> {
+        use crate::fixed_script_wallet::core_unspent::WalletUnspent;
+        use crate::fixed_script_wallet::MigrationOptions;
+
+        let network = parse_network(network)?;
+        let unspents = unspents
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                WalletUnspent::try_from_js_value(&item)
+                    .map_err(|e| WasmUtxoError::new(&format!("Unspent {}: {}", i, e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let options = MigrationOptions {
+            max_inputs: max_inputs as usize,
+            fee_rate,
+            change_index,
+            sequence: Option::<SequenceMode>::try_from_js_value(&sequence)?,
+        };
+        let psbts = crate::fixed_script_wallet::plan_migration(
+            network,
+            keys.inner(),
+            &unspents,
+            &options,
+            |psbt| tx_package::estimate_vsize(psbt).map_err(|e| e.to_string()),
+        )?;
+        psbts
+            .iter()
+            .map(|psbt| {
+                psbt.serialize()
+                    .map_err(|e| WasmUtxoError::new(&format!("Failed to serialize PSBT: {}", e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .try_to_js_value()
+    }
+
+    /// Plan the PSBTs sweeping unspents to a recipient address, split so that each signed
+    /// transaction stays below the maximum standard weight
+    ///
+    /// `unspents` are `{ input: { txid, vout, value }, scriptId }` objects, as returned by
+    /// `import_core_unspents`. The inputs are spent in the given order with the sign path
+    /// `signer`/`cosigner`, and each PSBT pays the fee for its maximum estimated vsize at
+    /// `fee_rate` (sat/vB).
+    ///
+    /// # Returns
+    /// `{ psbts, transactions: [{ inputCount, weight, vsize, inputValue, fee, outputValue }],
+    /// inputValue, fee, outputValue }`
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn plan_sweep(
+        keys: &WasmRootWalletKeys,
+        unspents: js_sys::Array,
+        network: &str,
+        recipient: &str,
+        fee_rate: f64,
+        max_weight: Option<u32>,
+        signer: Option<String>,
+        cosigner: Option<String>,
+        sequence: JsValue,
+    ) -> Result<JsValue, WasmUtxoError> {
+        use crate::fixed_script_wallet::core_unspent::WalletUnspent;
+        use crate::fixed_script_wallet::SweepOptions;
+
+        let network = parse_network(network)?;
+        let unspents = unspents
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                WalletUnspent::try_from_js_value(&item)
+                    .map_err(|e| WasmUtxoError::new(&format!("Unspent {}: {}", i, e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let options = SweepOptions {
+            recipient: crate::address::networks::to_output_script_with_network(recipient, network)
+                .map_err(|e| WasmUtxoError::new(&e.to_string()))?,
+            fee_rate,
+            max_weight: max_weight.map(u64::from),
+            sign_path: parse_sign_path(signer.clone(), cosigner.clone())?,
+            sequence: Option::<SequenceMode>::try_from_js_value(&sequence)?,
+        };
+        let plan = crate::fixed_script_wallet::plan_sweep(
+            network,
+            keys.inner(),
+            &unspents,
+            &options,
+            |unspent| {
+                WasmDimensions::from_input(
+                    unspent.script_id.chain,
+                    signer.clone(),
+                    cosigner.clone(),
+                    None,
+                )
+                .map(|dimensions| dimensions.get_input_weight(None) as u64)
+                .map_err(|e| e.to_string())
+            },
+        )
+        .map_err(|e| WasmUtxoError::new(&e.to_string()))?;
+        plan.try_to_js_value()
+    }
+
     /// Check if a network supports a given fixed-script wallet script type
     ///
     /// # Arguments
@@ -183,7 +546,7 @@ impl FixedScriptWalletNamespace {
         let network = crate::networks::Network::from_coin_name(coin)
             .ok_or_else(|| WasmUtxoError::new(&format!("Unknown coin: {}", coin)))?;
         let st = OutputScriptType::from_str(script_type).map_err(|e| WasmUtxoError::new(&e))?;
-        Ok(network.output_script_support().supports_script_type(st))
+        Ok(network.capabilities().supports_script_type(st))
     }
 
     /// Create an OP_RETURN output script with optional data
@@ -223,6 +586,54 @@ impl FixedScriptWalletNamespace {
         Ok(ScriptP2shP2pk::new(pubkey).output_script().into_bytes())
     }
 
+    /// Get the output script of a 2-of-2 channel funding output
+    ///
+    /// # Arguments
+    /// * `output_type` - "p2wsh" (BOLT 3) or "p2trMusig2" (MuSig2 key path)
+    /// * `pubkey_a`, `pubkey_b` - The compressed public keys of both parties, in either order
+    /// * `network` - Network name; must support the output type
+    #[wasm_bindgen]
+    pub fn funding_output_script(
+        output_type: &str,
+        pubkey_a: &[u8],
+        pubkey_b: &[u8],
+        network: &str,
+    ) -> Result<Vec<u8>, WasmUtxoError> {
+        let support = parse_network(network)?.output_script_support();
+        let funding = parse_funding_output(output_type, pubkey_a, pubkey_b)?;
+        Ok(funding.output_script(&support)?.into_bytes())
+    }
+
+    /// Get the `2 <key1> <key2> 2 OP_CHECKMULTISIG` witness script of a p2wsh funding
+    /// output, with the keys sorted
+    #[wasm_bindgen]
+    pub fn funding_witness_script(
+        pubkey_a: &[u8],
+        pubkey_b: &[u8],
+    ) -> Result<Vec<u8>, WasmUtxoError> {
+        let funding = parse_funding_output("p2wsh", pubkey_a, pubkey_b)?;
+        Ok(funding
+            .witness_script()
+            .map(|script| script.into_bytes())
+            .unwrap_or_default())
+    }
+
+    /// Get the type of the funding output of two keys that has the output script `script`
+    ///
+    /// # Returns
+    /// "p2wsh", "p2trMusig2", or `undefined` if `script` is not a funding output of the keys
+    #[wasm_bindgen]
+    pub fn classify_funding_output(
+        script: &[u8],
+        pubkey_a: &[u8],
+        pubkey_b: &[u8],
+    ) -> Result<Option<String>, WasmUtxoError> {
+        let keys = parse_funding_output("p2wsh", pubkey_a, pubkey_b)?.key_pair();
+        let script = miniscript::bitcoin::ScriptBuf::from_bytes(script.to_vec());
+        Ok(FundingOutput::classify(&script, keys)
+            .map(|funding| funding.output_type().as_str().to_string()))
+    }
+
     /// Get all chain code metadata for building TypeScript lookup tables
     ///
     /// Returns an array of [chainCode, scriptType, scope] tuples where:
@@ -272,6 +683,38 @@ impl FixedScriptWalletNamespace {
         Ok(WasmRootWalletKeys::from_inner(wallet_keys))
     }
 
+    /// Get hit/miss counters and size of the wallet key derivation cache
+    #[wasm_bindgen]
+    pub fn derivation_cache_stats() -> Result<JsValue, WasmUtxoError> {
+        crate::fixed_script_wallet::derivation_cache::stats().try_to_js_value()
+    }
+
+    /// Enable or disable the wallet key derivation cache (disabling drops cached entries)
+    #[wasm_bindgen]
+    pub fn set_derivation_cache_enabled(enabled: bool) {
+        crate::fixed_script_wallet::derivation_cache::set_enabled(enabled);
+    }
+
+    /// Set the maximum number of cached wallet key derivations
+    #[wasm_bindgen]
+    pub fn set_derivation_cache_capacity(capacity: u32) {
+        crate::fixed_script_wallet::derivation_cache::set_capacity(capacity as usize);
+    }
+
+    /// Drop all cached wallet key derivations and reset the counters
+    #[wasm_bindgen]
+    pub fn clear_derivation_cache() {
+        crate::fixed_script_wallet::derivation_cache::clear();
+    }
+
+    /// Enable or disable low-R grinding of ECDSA signatures (enabled by default)
+    ///
+    /// The setting is process-wide and applies to all `BitGoPsbt` signing methods.
+    #[wasm_bindgen]
+    pub fn set_low_r_grinding(enabled: bool) {
+        LOW_R_GRINDING.store(enabled, Ordering::Relaxed);
+    }
+
     /// Returns an object mapping BitGo proprietary key subtype names to their `u8` values.
     /// Values are loaded directly from the Rust enum at build time — no duplication in TypeScript.
     #[wasm_bindgen]
@@ -292,6 +735,14 @@ impl FixedScriptWalletNamespace {
             ),
             ("Bip322Message", S::Bip322Message as u8),
             ("WasmUtxoSignedWith", S::WasmUtxoSignedWith as u8),
+            ("SpendLimit", S::SpendLimit as u8),
+            (
+                "DestinationWhitelistHash",
+                S::DestinationWhitelistHash as u8,
+            ),
+            ("ApprovalQuorumId", S::ApprovalQuorumId as u8),
+            ("Label", S::Label as u8),
+            ("UnconfirmedParent", S::UnconfirmedParent as u8),
         ] {
             js_sys::Reflect::set(&obj, &name.into(), &JsValue::from_f64(val as f64)).unwrap();
         }
@@ -302,20 +753,106 @@ impl FixedScriptWalletNamespace {
 #[wasm_bindgen]
 pub struct BitGoPsbt {
     pub(crate) psbt: crate::fixed_script_wallet::bitgo_psbt::BitGoPsbt,
-    // Store FirstRound states per (input_index, xpub_string)
+    // Store FirstRound states and their session ids per (input_index, xpub_string)
     #[wasm_bindgen(skip)]
-    pub(crate) first_rounds: HashMap<(usize, String), musig2::FirstRound>,
+    pub(crate) first_rounds: HashMap<(usize, String), (musig2::FirstRound, [u8; 32])>,
+}
+
+impl BitGoPsbt {
+    /// Persisting MuSig2 sessions is allowed on testnets, or on mainnets when the caller
+    /// explicitly acknowledges the risk of nonce reuse
+    fn check_musig2_session_allowed(
+        &self,
+        acknowledge_risk: Option<bool>,
+    ) -> Result<(), WasmUtxoError> {
+        if self.psbt.network().is_testnet() || acknowledge_risk == Some(true) {
+            Ok(())
+        } else {
+            Err(WasmUtxoError::new(
+                "Persisting MuSig2 sessions on mainnets requires acknowledge_risk to be true",
+            ))
+        }
+    }
+
+    /// FirstRound states are stored by input index and bound to the sighash, so inputs and
+    /// outputs must not be reordered while any is kept
+    fn check_no_first_rounds(&self) -> Result<(), WasmUtxoError> {
+        match self
+            .first_rounds
+            .keys()
+            .map(|(input_index, _)| input_index)
+            .min()
+        {
+            Some(input_index) => Err(WasmUtxoError::new(&format!(
+                "Cannot reorder PSBT: input {} has MuSig2 nonces for the current order",
+                input_index
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Grind the ECDSA signatures of `k` on `input_indices` to low-R, unless disabled with
+    /// `set_low_r_grinding`
+    fn grind_low_r<K: miniscript::bitcoin::psbt::GetKey>(
+        &mut self,
+        k: &K,
+        input_indices: impl IntoIterator<Item = usize>,
+    ) {
+        if LOW_R_GRINDING.load(Ordering::Relaxed) {
+            let secp = miniscript::bitcoin::secp256k1::Secp256k1::new();
+            self.psbt.grind_low_r(k, &secp, input_indices);
+        }
+    }
+
+    /// Like `grind_low_r`, for signatures made with a raw private key
+    fn grind_low_r_with_privkey(
+        &mut self,
+        privkey: &miniscript::bitcoin::secp256k1::SecretKey,
+        input_indices: impl IntoIterator<Item = usize>,
+    ) {
+        let secp = miniscript::bitcoin::secp256k1::Secp256k1::signing_only();
+        let private_key =
+            miniscript::bitcoin::PrivateKey::new(*privkey, miniscript::bitcoin::Network::Bitcoin);
+        let key_map =
+            std::collections::BTreeMap::from_iter([(private_key.public_key(&secp), private_key)]);
+        self.grind_low_r(&key_map, input_indices);
+    }
+
+    /// Check a snapshot returned by `extract_invariants`, if one is given
+    fn check_extract_invariants(&self, invariants: &JsValue) -> Result<(), WasmUtxoError> {
+        use crate::fixed_script_wallet::bitgo_psbt::ExtractInvariants;
+
+        if let Some(expected) = Option::<ExtractInvariants>::try_from_js_value(invariants)? {
+            self.psbt.verify_extract_invariants(&expected)?;
+        }
+        Ok(())
+    }
 }
 
 #[wasm_bindgen]
 impl BitGoPsbt {
     /// Deserialize a PSBT from bytes with network-specific logic
-    pub fn from_bytes(bytes: &[u8], network: &str) -> Result<BitGoPsbt, WasmUtxoError> {
+    ///
+    /// If `strict` is true, redeem/witness scripts, taproot control blocks and key origins
+    /// are checked against the scripts they describe and the global xpubs.
+    pub fn from_bytes(
+        bytes: &[u8],
+        network: &str,
+        strict: Option<bool>,
+    ) -> Result<BitGoPsbt, WasmUtxoError> {
+        use crate::fixed_script_wallet::bitgo_psbt::DeserializeValidation;
+
         let network = parse_network(network)?;
+        let validation = if strict.unwrap_or(false) {
+            DeserializeValidation::Strict
+        } else {
+            DeserializeValidation::None
+        };
 
-        let psbt =
-            crate::fixed_script_wallet::bitgo_psbt::BitGoPsbt::deserialize(bytes, network)
-                .map_err(|e| WasmUtxoError::new(&format!("Failed to deserialize PSBT: {}", e)))?;
+        let psbt = crate::fixed_script_wallet::bitgo_psbt::BitGoPsbt::deserialize_with_validation(
+            bytes, network, validation,
+        )
+        .map_err(|e| WasmUtxoError::new(&format!("Failed to deserialize PSBT: {}", e)))?;
 
         Ok(BitGoPsbt {
             psbt,
@@ -352,6 +889,34 @@ impl BitGoPsbt {
         })
     }
 
+    /// Wrap an unsigned transaction created by another library
+    ///
+    /// The inputs have no metadata; set it with `enrich_input` and
+    /// `enrich_replay_protection_input` before signing.
+    ///
+    /// # Arguments
+    /// * `tx_bytes` - The unsigned transaction (not Zcash)
+    /// * `network` - Network name (utxolib or coin name)
+    /// * `wallet_keys` - The wallet's root keys (used to set global xpubs)
+    pub fn from_unsigned_tx(
+        tx_bytes: &[u8],
+        network: &str,
+        wallet_keys: &WasmRootWalletKeys,
+    ) -> Result<BitGoPsbt, WasmUtxoError> {
+        let network = parse_network(network)?;
+        let psbt = crate::fixed_script_wallet::bitgo_psbt::BitGoPsbt::from_unsigned_tx(
+            tx_bytes,
+            network,
+            wallet_keys.inner(),
+        )
+        .map_err(|e| WasmUtxoError::new(&e))?;
+
+        Ok(BitGoPsbt {
+            psbt,
+            first_rounds: HashMap::new(),
+        })
+    }
+
     /// Create an empty Zcash PSBT with the required consensus branch ID
     ///
     /// This method is specifically for Zcash networks which require additional
@@ -465,15 +1030,13 @@ impl BitGoPsbt {
             .collect::<Result<Vec<_>, _>>()?;
 
         let tx_bytes = tx.to_bytes();
-        let psbt = crate::fixed_script_wallet::bitgo_psbt::BitGoPsbt::from_network_format(
+        let psbt = crate::fixed_script_wallet::bitgo_psbt::BitGoPsbt::from_half_signed_legacy_tx(
             &tx_bytes,
-            network,
-            wallet_keys,
             &parsed_unspents,
+            wallet_keys,
+            network,
         )
         .map_err(|e| WasmUtxoError::new(&e))?;
-        crate::fixed_script_wallet::bitgo_psbt::BitGoPsbt::validate_half_signed(&psbt)
-            .map_err(|e| WasmUtxoError::new(&e))?;
 
         Ok(BitGoPsbt {
             psbt,
@@ -507,15 +1070,13 @@ impl BitGoPsbt {
             .collect::<Result<Vec<_>, _>>()?;
 
         let tx_bytes = tx.to_bytes()?;
-        let psbt = crate::fixed_script_wallet::bitgo_psbt::BitGoPsbt::from_network_format(
+        let psbt = crate::fixed_script_wallet::bitgo_psbt::BitGoPsbt::from_half_signed_legacy_tx(
             &tx_bytes,
-            network,
-            wallet_keys,
             &parsed_unspents,
+            wallet_keys,
+            network,
         )
         .map_err(|e| WasmUtxoError::new(&e))?;
-        crate::fixed_script_wallet::bitgo_psbt::BitGoPsbt::validate_half_signed(&psbt)
-            .map_err(|e| WasmUtxoError::new(&e))?;
 
         Ok(BitGoPsbt {
             psbt,
@@ -689,9 +1250,10 @@ impl BitGoPsbt {
     /// # Arguments
     /// * `txid` - The transaction ID (hex string) of the output being spent
     /// * `vout` - The output index being spent
-    /// * `value` - The value in satoshis of the output being spent
+    /// * `value` - The value in satoshis of the output being spent (bigint)
     /// * `script` - The output script (scriptPubKey) of the output being spent
-    /// * `sequence` - Optional sequence number (default: 0xFFFFFFFE for RBF)
+    /// * `sequence` - Optional sequence mode: a sequence number, `"finalNoRbf"` (default),
+    ///   `"rbfEnabled"`, `{ relativeLockBlocks }` or `{ relativeLockTime }`
     ///
     /// # Returns
     /// The index of the newly added input
@@ -701,15 +1263,18 @@ impl BitGoPsbt {
         index: usize,
         txid: &str,
         vout: u32,
-        value: u64,
+        value: JsValue,
         script: &[u8],
-        sequence: Option<u32>,
+        sequence: JsValue,
         prev_tx: Option<Vec<u8>>,
     ) -> Result<usize, WasmUtxoError> {
         use miniscript::bitcoin::consensus::Decodable;
         use miniscript::bitcoin::{ScriptBuf, Transaction, Txid};
         use std::str::FromStr;
 
+        let value = u64::try_from_js_value(&value)?;
+        let sequence = Option::<SequenceMode>::try_from_js_value(&sequence)?;
+
         let txid = Txid::from_str(txid)
             .map_err(|e| WasmUtxoError::new(&format!("Invalid txid: {}", e)))?;
         let script = ScriptBuf::from_bytes(script.to_vec());
@@ -729,9 +1294,9 @@ impl BitGoPsbt {
         &mut self,
         txid: &str,
         vout: u32,
-        value: u64,
+        value: JsValue,
         script: &[u8],
-        sequence: Option<u32>,
+        sequence: JsValue,
         prev_tx: Option<Vec<u8>>,
     ) -> Result<usize, WasmUtxoError> {
         let index = self.psbt.psbt().inputs.len();
@@ -742,16 +1307,18 @@ impl BitGoPsbt {
         &mut self,
         index: usize,
         script: &[u8],
-        value: u64,
+        value: JsValue,
     ) -> Result<usize, WasmUtxoError> {
         use miniscript::bitcoin::ScriptBuf;
+
+        let value = u64::try_from_js_value(&value)?;
         let script = ScriptBuf::from_bytes(script.to_vec());
         self.psbt
             .add_output_at_index(index, script, value)
             .map_err(|e| WasmUtxoError::new(&e))
     }
 
-    pub fn add_output(&mut self, script: &[u8], value: u64) -> Result<usize, WasmUtxoError> {
+    pub fn add_output(&mut self, script: &[u8], value: JsValue) -> Result<usize, WasmUtxoError> {
         let index = self.psbt.psbt().outputs.len();
         self.add_output_at_index(index, script, value)
     }
@@ -760,8 +1327,9 @@ impl BitGoPsbt {
         &mut self,
         index: usize,
         address: &str,
-        value: u64,
+        value: JsValue,
     ) -> Result<usize, WasmUtxoError> {
+        let value = u64::try_from_js_value(&value)?;
         Ok(self
             .psbt
             .add_output_with_address_at_index(index, address, value)?)
@@ -770,32 +1338,77 @@ impl BitGoPsbt {
     pub fn add_output_with_address(
         &mut self,
         address: &str,
-        value: u64,
+        value: JsValue,
     ) -> Result<usize, WasmUtxoError> {
+        let value = u64::try_from_js_value(&value)?;
         Ok(self.psbt.add_output_with_address(address, value)?)
     }
 
+    /// Sort inputs and outputs following BIP-69
+    ///
+    /// Fails if any input is already signed or has MuSig2 nonces, since the nonces and the
+    /// FirstRound states kept by this object are bound to the current order.
+    ///
+    /// # Returns
+    /// `{ inputs, outputs }` where `inputs[oldIndex]` is the new index of the input
+    /// (likewise for outputs)
+    pub fn sort_bip69(&mut self) -> Result<JsValue, WasmUtxoError> {
+        self.check_no_first_rounds()?;
+        let permutation = self.psbt.sort_bip69().map_err(|e| WasmUtxoError::new(&e))?;
+        permutation.try_to_js_value()
+    }
+
+    /// Shuffle the outputs in an order derived from a 32-byte seed
+    ///
+    /// Fails if any input is already signed or has MuSig2 nonces.
+    ///
+    /// # Returns
+    /// Array where `result[oldIndex]` is the new index of the output
+    pub fn shuffle_outputs(&mut self, seed: &[u8]) -> Result<JsValue, WasmUtxoError> {
+        let seed: &[u8; 32] = seed
+            .try_into()
+            .map_err(|_| WasmUtxoError::new("Shuffle seed must be 32 bytes"))?;
+        self.check_no_first_rounds()?;
+        let permutation = self
+            .psbt
+            .shuffle_outputs(seed)
+            .map_err(|e| WasmUtxoError::new(&e))?;
+        permutation.try_to_js_value()
+    }
+
+    /// Set the sequence of every input, e.g. to opt in to replace-by-fee after construction
+    ///
+    /// Fails if any input is already signed.
+    pub fn set_sequence_mode(&mut self, mode: JsValue) -> Result<(), WasmUtxoError> {
+        let mode = SequenceMode::try_from_js_value(&mode)?;
+        self.psbt
+            .set_sequence_mode(mode)
+            .map_err(|e| WasmUtxoError::new(&e))
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn add_wallet_input_at_index(
         &mut self,
         index: usize,
         txid: &str,
         vout: u32,
-        value: u64,
+        value: JsValue,
         wallet_keys: &WasmRootWalletKeys,
         chain: u32,
         derivation_index: u32,
         signer: Option<String>,
         cosigner: Option<String>,
-        sequence: Option<u32>,
+        sequence: JsValue,
         prev_tx: Option<Vec<u8>>,
     ) -> Result<usize, WasmUtxoError> {
-        use crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::{SignPath, SignerKey};
         use crate::fixed_script_wallet::bitgo_psbt::WalletInputOptions;
         use crate::fixed_script_wallet::ScriptId;
         use miniscript::bitcoin::Txid;
         use std::str::FromStr;
 
+        let value = u64::try_from_js_value(&value)?;
+        let sequence = Option::<SequenceMode>::try_from_js_value(&sequence)?;
+
         let txid = Txid::from_str(txid)
             .map_err(|e| WasmUtxoError::new(&format!("Invalid txid: {}", e)))?;
 
@@ -804,23 +1417,7 @@ impl BitGoPsbt {
             chain,
             index: derivation_index,
         };
-        let sign_path = match (signer.as_deref(), cosigner.as_deref()) {
-            (Some(signer_str), Some(cosigner_str)) => {
-                let signer: SignerKey = signer_str
-                    .parse()
-                    .map_err(|e: String| WasmUtxoError::new(&e))?;
-                let cosigner: SignerKey = cosigner_str
-                    .parse()
-                    .map_err(|e: String| WasmUtxoError::new(&e))?;
-                Some(SignPath { signer, cosigner })
-            }
-            (None, None) => None,
-            _ => {
-                return Err(WasmUtxoError::new(
-                    "Both signer and cosigner must be provided together or both omitted",
-                ))
-            }
-        };
+        let sign_path = parse_sign_path(signer, cosigner)?;
 
         self.psbt
             .add_wallet_input_at_index(
@@ -836,7 +1433,7 @@ impl BitGoPsbt {
                     prev_tx: prev_tx.as_deref(),
                 },
             )
-            .map_err(|e| WasmUtxoError::new(&e))
+            .map_err(WasmUtxoError::from)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -844,13 +1441,13 @@ impl BitGoPsbt {
         &mut self,
         txid: &str,
         vout: u32,
-        value: u64,
+        value: JsValue,
         wallet_keys: &WasmRootWalletKeys,
         chain: u32,
         index: u32,
         signer: Option<String>,
         cosigner: Option<String>,
-        sequence: Option<u32>,
+        sequence: JsValue,
         prev_tx: Option<Vec<u8>>,
     ) -> Result<usize, WasmUtxoError> {
         let insert_index = self.psbt.psbt().inputs.len();
@@ -869,46 +1466,189 @@ impl BitGoPsbt {
         )
     }
 
-    pub fn add_wallet_output_at_index(
+    /// Add an input spending wallet output `vout` of `parent`, a PSBT that is not broadcast yet
+    ///
+    /// Inputs of the parent that do not spend native segwit outputs must be finalized.
+    pub fn add_wallet_input_from_parent(
         &mut self,
-        index: usize,
-        chain: u32,
-        derivation_index: u32,
-        value: u64,
+        parent: &BitGoPsbt,
+        vout: u32,
         wallet_keys: &WasmRootWalletKeys,
+        signer: Option<String>,
+        cosigner: Option<String>,
+        sequence: JsValue,
     ) -> Result<usize, WasmUtxoError> {
-        let wallet_keys = wallet_keys.inner();
+        use crate::fixed_script_wallet::bitgo_psbt::WalletInputOptions;
+
+        let sequence = Option::<SequenceMode>::try_from_js_value(&sequence)?;
+        let sign_path = parse_sign_path(signer, cosigner)?;
+        Ok(self.psbt.add_wallet_input_from_parent(
+            &parent.psbt,
+            vout,
+            wallet_keys.inner(),
+            WalletInputOptions {
+                sign_path,
+                sequence,
+                prev_tx: None,
+            },
+        )?)
+    }
+
+    /// Txids of the unbroadcast parents spent by inputs added with
+    /// `add_wallet_input_from_parent`
+    pub fn unconfirmed_parents(&self) -> Vec<String> {
         self.psbt
-            .add_wallet_output_at_index(index, chain, derivation_index, value, wallet_keys)
-            .map_err(|e| WasmUtxoError::new(&e))
+            .unconfirmed_parents()
+            .iter()
+            .map(|txid| txid.to_string())
+            .collect()
     }
 
-    pub fn add_wallet_output(
+    /// Set the metadata of a wallet input of a PSBT created with `from_unsigned_tx`
+    #[allow(clippy::too_many_arguments)]
+    pub fn enrich_input(
         &mut self,
-        chain: u32,
-        index: u32,
-        value: u64,
+        input_index: usize,
         wallet_keys: &WasmRootWalletKeys,
-    ) -> Result<usize, WasmUtxoError> {
-        let insert_index = self.psbt.psbt().outputs.len();
-        self.add_wallet_output_at_index(insert_index, chain, index, value, wallet_keys)
-    }
+        chain: u32,
+        derivation_index: u32,
+        value: JsValue,
+        signer: Option<String>,
+        cosigner: Option<String>,
+        prev_tx: Option<Vec<u8>>,
+    ) -> Result<(), WasmUtxoError> {
+        use crate::fixed_script_wallet::ScriptId;
 
-    #[allow(clippy::too_many_arguments)]
-    pub fn add_replay_protection_input_at_index(
+        let value = u64::try_from_js_value(&value)?;
+        let sign_path = parse_sign_path(signer, cosigner)?;
+        self.psbt
+            .enrich_input(
+                input_index,
+                wallet_keys.inner(),
+                ScriptId {
+                    chain,
+                    index: derivation_index,
+                },
+                value,
+                sign_path,
+                prev_tx.as_deref(),
+            )
+            .map_err(|e| WasmUtxoError::new(&e))
+    }
+
+    /// Set the metadata of a replay protection input of a PSBT created with
+    /// `from_unsigned_tx`
+    pub fn enrich_replay_protection_input(
+        &mut self,
+        input_index: usize,
+        ecpair: &WasmECPair,
+        value: JsValue,
+        prev_tx: Option<Vec<u8>>,
+    ) -> Result<(), WasmUtxoError> {
+        use miniscript::bitcoin::CompressedPublicKey;
+
+        let value = u64::try_from_js_value(&value)?;
+        let pubkey = CompressedPublicKey(ecpair.get_public_key());
+        self.psbt
+            .enrich_replay_protection_input(input_index, pubkey, value, prev_tx.as_deref())
+            .map_err(|e| WasmUtxoError::new(&e))
+    }
+
+    /// Add an input spending a single-sig (p2pkh, p2wpkh or p2trKeyPath) output of a wallet key
+    ///
+    /// `derivation_path` is relative to the xpub of `signer` (e.g. "m/0/7").
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_single_sig_input(
+        &mut self,
+        txid: &str,
+        vout: u32,
+        value: JsValue,
+        wallet_keys: &WasmRootWalletKeys,
+        signer: &str,
+        derivation_path: &str,
+        script_type: &str,
+        sequence: JsValue,
+        prev_tx: Option<Vec<u8>>,
+    ) -> Result<usize, WasmUtxoError> {
+        use crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::SignerKey;
+        use crate::fixed_script_wallet::bitgo_psbt::{SingleSigScriptType, WalletInputOptions};
+        use miniscript::bitcoin::bip32::DerivationPath;
+        use miniscript::bitcoin::Txid;
+        use std::str::FromStr;
+
+        let value = u64::try_from_js_value(&value)?;
+        let sequence = Option::<SequenceMode>::try_from_js_value(&sequence)?;
+        let txid = Txid::from_str(txid)
+            .map_err(|e| WasmUtxoError::new(&format!("Invalid txid: {}", e)))?;
+        let signer: SignerKey = signer.parse().map_err(|e: String| WasmUtxoError::new(&e))?;
+        let derivation_path = DerivationPath::from_str(derivation_path)
+            .map_err(|e| WasmUtxoError::new(&format!("Invalid derivation path: {}", e)))?;
+        let script_type: SingleSigScriptType = script_type
+            .parse()
+            .map_err(|e: String| WasmUtxoError::new(&e))?;
+
+        self.psbt
+            .add_single_sig_input(
+                txid,
+                vout,
+                value,
+                wallet_keys.inner(),
+                signer,
+                &derivation_path,
+                script_type,
+                WalletInputOptions {
+                    sign_path: None,
+                    sequence,
+                    prev_tx: prev_tx.as_deref(),
+                },
+            )
+            .map_err(|e| WasmUtxoError::new(&e))
+    }
+
+    pub fn add_wallet_output_at_index(
+        &mut self,
+        index: usize,
+        chain: u32,
+        derivation_index: u32,
+        value: JsValue,
+        wallet_keys: &WasmRootWalletKeys,
+    ) -> Result<usize, WasmUtxoError> {
+        let value = u64::try_from_js_value(&value)?;
+        let wallet_keys = wallet_keys.inner();
+        self.psbt
+            .add_wallet_output_at_index(index, chain, derivation_index, value, wallet_keys)
+            .map_err(|e| WasmUtxoError::new(&e))
+    }
+
+    pub fn add_wallet_output(
+        &mut self,
+        chain: u32,
+        index: u32,
+        value: JsValue,
+        wallet_keys: &WasmRootWalletKeys,
+    ) -> Result<usize, WasmUtxoError> {
+        let insert_index = self.psbt.psbt().outputs.len();
+        self.add_wallet_output_at_index(insert_index, chain, index, value, wallet_keys)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_replay_protection_input_at_index(
         &mut self,
         index: usize,
         ecpair: &WasmECPair,
         txid: &str,
         vout: u32,
-        value: u64,
-        sequence: Option<u32>,
+        value: JsValue,
+        sequence: JsValue,
         prev_tx: Option<Vec<u8>>,
     ) -> Result<usize, WasmUtxoError> {
         use crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::ReplayProtectionOptions;
         use miniscript::bitcoin::{CompressedPublicKey, Txid};
         use std::str::FromStr;
 
+        let value = u64::try_from_js_value(&value)?;
+        let sequence = Option::<SequenceMode>::try_from_js_value(&sequence)?;
+
         let txid = Txid::from_str(txid)
             .map_err(|e| WasmUtxoError::new(&format!("Invalid txid: {}", e)))?;
 
@@ -939,8 +1679,8 @@ impl BitGoPsbt {
         ecpair: &WasmECPair,
         txid: &str,
         vout: u32,
-        value: u64,
-        sequence: Option<u32>,
+        value: JsValue,
+        sequence: JsValue,
         prev_tx: Option<Vec<u8>>,
     ) -> Result<usize, WasmUtxoError> {
         let index = self.psbt.psbt().inputs.len();
@@ -954,6 +1694,79 @@ impl BitGoPsbt {
         self.psbt.network().to_string()
     }
 
+    /// Returns true if the PSBT global xpubs are exactly the xpubs of `wallet_keys`
+    pub fn wallet_keys_match(&self, wallet_keys: &WasmRootWalletKeys) -> bool {
+        self.psbt.wallet_keys_match(wallet_keys.inner())
+    }
+
+    /// Rewrite the key origins of the wallet keys for hardware wallets
+    ///
+    /// # Arguments
+    /// - `wallet_keys`: The wallet's root keys
+    /// - `origins`: Array of three `{ masterFingerprint, path }` objects (or null), in
+    ///   user/backup/bitgo order
+    pub fn export_key_origins(
+        &mut self,
+        wallet_keys: &WasmRootWalletKeys,
+        origins: js_sys::Array,
+    ) -> Result<(), WasmUtxoError> {
+        use crate::fixed_script_wallet::bitgo_psbt::KeyOrigin;
+
+        if origins.length() != 3 {
+            return Err(WasmUtxoError::new(&format!(
+                "Expected 3 key origins, got {}",
+                origins.length()
+            )));
+        }
+        let origins = [0, 1, 2]
+            .map(|i| Option::<KeyOrigin>::try_from_js_value(&origins.get(i)))
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        let origins: [Option<KeyOrigin>; 3] = origins.try_into().expect("3 origins");
+        self.psbt
+            .export_key_origins(wallet_keys.inner(), &origins)
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))
+    }
+
+    /// Select the sign path for the available xprivs and apply it to all taproot wallet inputs
+    ///
+    /// p2tr and p2trMusig2 inputs that were added for another sign path are rewritten, e.g.
+    /// MuSig2 key path inputs move to the user/backup script leaf for a recovery.
+    ///
+    /// # Arguments
+    /// - `wallet_keys`: The wallet's root keys
+    /// - `xprivs`: The available private keys
+    ///
+    /// # Returns
+    /// - `{ signer, cosigner }` of the selected sign path
+    pub fn plan_sign_path(
+        &mut self,
+        wallet_keys: &WasmRootWalletKeys,
+        xprivs: Vec<WasmBIP32>,
+    ) -> Result<JsValue, WasmUtxoError> {
+        let xprivs = xprivs
+            .iter()
+            .map(|xpriv| xpriv.to_xpriv())
+            .collect::<Result<Vec<_>, _>>()?;
+        self.psbt
+            .plan_sign_path(wallet_keys.inner(), &xprivs)
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))?
+            .try_to_js_value()
+    }
+
+    /// Role of a key in the wallet of this PSBT
+    ///
+    /// # Returns
+    /// - `"user"`, `"backup"` or `"bitgo"`, or `undefined` if the key is not a wallet key
+    /// - Error if the PSBT has no global xpubs or their order cannot be determined
+    pub fn signer_role(&self, key: &WasmBIP32) -> Result<Option<String>, WasmUtxoError> {
+        let xpub = key.to_xpub()?;
+        self.psbt
+            .signer_role_for_xpub(&xpub)
+            .map(|role| role.map(|role| role.as_str().to_string()))
+            .map_err(WasmUtxoError::from)
+    }
+
     /// Get the network type for transaction extraction
     ///
     /// Returns "bitcoin", "dash", or "zcash" to indicate which transaction
@@ -1002,11 +1815,20 @@ impl BitGoPsbt {
     }
 
     /// Parse transaction with wallet keys to identify wallet inputs/outputs
+    ///
+    /// When `allow_external_inputs` is true, inputs that belong to neither the wallet
+    /// nor replay protection are returned with script type `external` instead of failing.
+    ///
+    /// `progress` takes `{ onProgress?, progressInterval?, signal? }`: `onProgress` is called
+    /// with `(parsedInputs, totalInputs)` every `progressInterval` inputs (default 100) and
+    /// parsing is aborted once the `AbortSignal` is aborted.
     pub fn parse_transaction_with_wallet_keys(
         &self,
         wallet_keys: &WasmRootWalletKeys,
         replay_protection: &WasmReplayProtection,
         paygo_pubkeys: Option<Vec<WasmECPair>>,
+        allow_external_inputs: Option<bool>,
+        progress: JsValue,
     ) -> Result<JsValue, WasmUtxoError> {
         // Get the inner RootWalletKeys and ReplayProtection
         let wallet_keys = wallet_keys.inner();
@@ -1019,11 +1841,35 @@ impl BitGoPsbt {
             .map(|ecpair| ecpair.get_public_key())
             .collect();
 
+        let external_policy = if allow_external_inputs.unwrap_or(false) {
+            ExternalInputPolicy::Allow
+        } else {
+            ExternalInputPolicy::Reject
+        };
+
         // Call the Rust implementation
-        let parsed_tx = self
-            .psbt
-            .parse_transaction_with_wallet_keys(wallet_keys, replay_protection, &pubkeys)
-            .map_err(WasmUtxoError::from)?;
+        let parsed_tx = with_js_progress(
+            &progress,
+            |progress| match progress {
+                Some(progress) => self.psbt.parse_transaction_with_progress(
+                    wallet_keys,
+                    replay_protection,
+                    &pubkeys,
+                    external_policy,
+                    progress,
+                ),
+                None => self
+                    .psbt
+                    .parse_transaction_with_wallet_keys_and_input_policy(
+                        wallet_keys,
+                        replay_protection,
+                        &pubkeys,
+                        external_policy,
+                    )
+                    .map_err(ProgressError::Failed),
+            },
+            WasmUtxoError::from,
+        )?;
 
         // Convert to JsValue directly using TryIntoJsValue
         parsed_tx.try_to_js_value()
@@ -1057,6 +1903,126 @@ impl BitGoPsbt {
         parsed_outputs.try_to_js_value()
     }
 
+    /// Collect the red flags of the transaction for compliance screening
+    ///
+    /// The transaction is parsed with external inputs allowed, so that they are reported
+    /// instead of failing the parse.
+    ///
+    /// # Arguments
+    /// - `max_fee`: Flag miner fees above this amount in satoshis
+    /// - `max_fee_rate_sat_per_vb`: Flag fee rates above this rate in sat/vB
+    /// - `dust_threshold`: Flag outputs below this value; defaults to the dust limit of the
+    ///   network for each output script
+    ///
+    /// # Returns
+    /// `{ nonDefaultSighash, externalInputs, opReturnOutputs, dustOutputs, minerFee,
+    /// virtualSize, feeAboveThreshold, reusedAddresses, hasFlags }`
+    pub fn risk_report(
+        &self,
+        wallet_keys: &WasmRootWalletKeys,
+        replay_protection: &WasmReplayProtection,
+        max_fee: Option<u64>,
+        max_fee_rate_sat_per_vb: Option<f64>,
+        dust_threshold: Option<u64>,
+    ) -> Result<JsValue, WasmUtxoError> {
+        use crate::fixed_script_wallet::bitgo_psbt::RiskReportOptions;
+
+        let parsed = self
+            .psbt
+            .parse_transaction_with_wallet_keys_and_input_policy(
+                wallet_keys.inner(),
+                replay_protection.inner(),
+                &[],
+                ExternalInputPolicy::Allow,
+            )?;
+        let options = RiskReportOptions {
+            max_fee,
+            max_fee_rate: max_fee_rate_sat_per_vb.map(fee_rate_from_js).transpose()?,
+            dust_threshold,
+        };
+        self.psbt.risk_report(&parsed, &options).try_to_js_value()
+    }
+
+    /// Parse outputs against several labeled wallets in one pass
+    ///
+    /// # Arguments
+    /// - `wallet_keys`: Wallet keys, in priority order
+    /// - `labels`: One label per wallet, returned as `wallet` for outputs it owns
+    /// - `paygo_pubkeys`: Optional public keys for PayGo attestation verification
+    pub fn parse_outputs_with_labeled_wallet_keys(
+        &self,
+        wallet_keys: Vec<WasmRootWalletKeys>,
+        labels: Vec<String>,
+        paygo_pubkeys: Option<Vec<WasmECPair>>,
+    ) -> Result<JsValue, WasmUtxoError> {
+        if wallet_keys.len() != labels.len() {
+            return Err(WasmUtxoError::new(&format!(
+                "Expected one label per wallet, got {} wallets and {} labels",
+                wallet_keys.len(),
+                labels.len()
+            )));
+        }
+        let wallets: Vec<_> = labels
+            .iter()
+            .map(String::as_str)
+            .zip(wallet_keys.iter().map(|keys| keys.inner()))
+            .collect();
+
+        let pubkeys: Vec<_> = paygo_pubkeys
+            .unwrap_or_default()
+            .iter()
+            .map(|ecpair| ecpair.get_public_key())
+            .collect();
+
+        let parsed_outputs = self
+            .psbt
+            .parse_outputs_with_labeled_wallet_keys(&wallets, &pubkeys)
+            .map_err(|e| WasmUtxoError::new(&format!("Failed to parse outputs: {}", e)))?;
+
+        parsed_outputs.try_to_js_value()
+    }
+
+    /// Check a BIP-78 payjoin proposal against this PSBT (the sender's original)
+    ///
+    /// # Arguments
+    /// - `proposal`: The receiver's payjoin proposal
+    /// - `payee_script`: Output script paying the receiver
+    /// - `fee_output_index`: Original output index the receiver may reduce to cover fees
+    /// - `max_fee_contribution`: Maximum reduction of the fee output in satoshis
+    /// - `disable_output_substitution`: Forbid the receiver from replacing the payee output
+    /// - `min_fee_rate`: Minimum fee rate of the proposal in sat/vB (`minfeerate`)
+    ///
+    /// # Returns
+    /// `{ receiverInputs, feeContribution }` if the proposal is acceptable
+    pub fn check_payjoin_proposal(
+        &self,
+        proposal: &BitGoPsbt,
+        payee_script: &[u8],
+        fee_output_index: Option<u32>,
+        max_fee_contribution: Option<u64>,
+        disable_output_substitution: Option<bool>,
+        min_fee_rate: Option<f64>,
+    ) -> Result<JsValue, WasmUtxoError> {
+        let fee_contribution = match (fee_output_index, max_fee_contribution) {
+            (Some(index), Some(max)) => Some((index as usize, max)),
+            (None, None) => None,
+            _ => {
+                return Err(WasmUtxoError::new(
+                    "fee_output_index and max_fee_contribution must be set together",
+                ))
+            }
+        };
+        let params = crate::payjoin::PayjoinSenderParams {
+            payee_script: miniscript::bitcoin::ScriptBuf::from(payee_script.to_vec()),
+            fee_contribution,
+            disable_output_substitution: disable_output_substitution.unwrap_or(false),
+            min_fee_rate: min_fee_rate.map(fee_rate_from_js).transpose()?,
+        };
+        crate::payjoin::check_proposal(self.psbt.psbt(), proposal.psbt.psbt(), &params)
+            .map_err(|e| WasmUtxoError::new(&format!("Invalid payjoin proposal: {}", e)))?
+            .try_to_js_value()
+    }
+
     /// Add a PayGo attestation to a PSBT output
     ///
     /// # Arguments
@@ -1145,6 +2111,45 @@ impl BitGoPsbt {
             .map_err(|e| WasmUtxoError::new(&format!("Failed to verify signature: {}", e)))
     }
 
+    /// Check that the ECDSA signature made by `xpriv` on an input uses an RFC 6979 nonce
+    ///
+    /// # Returns
+    /// - `"rfc6979"` or `"rfc6979LowR"` if the nonce is deterministic
+    /// - `null` if the signature uses any other nonce
+    pub fn audit_signature_nonce_with_xpriv(
+        &self,
+        input_index: usize,
+        xpriv: &WasmBIP32,
+    ) -> Result<Option<String>, WasmUtxoError> {
+        let xpriv = xpriv.to_xpriv()?;
+        let secp = miniscript::bitcoin::secp256k1::Secp256k1::new();
+        self.psbt
+            .audit_signature_nonce(&secp, input_index, &xpriv)
+            .map(|derivation| derivation.map(|d| <&'static str>::from(d).to_string()))
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))
+    }
+
+    /// Check that the ECDSA signature made by `ecpair` on an input uses an RFC 6979 nonce
+    ///
+    /// Same as `audit_signature_nonce_with_xpriv`, for a raw private key.
+    pub fn audit_signature_nonce_with_privkey(
+        &self,
+        input_index: usize,
+        ecpair: &WasmECPair,
+    ) -> Result<Option<String>, WasmUtxoError> {
+        let secp = miniscript::bitcoin::secp256k1::Secp256k1::new();
+        let private_key = miniscript::bitcoin::PrivateKey::new(
+            ecpair.get_private_key()?,
+            miniscript::bitcoin::Network::Bitcoin,
+        );
+        let key_map =
+            std::collections::BTreeMap::from_iter([(private_key.public_key(&secp), private_key)]);
+        self.psbt
+            .audit_signature_nonce(&secp, input_index, &key_map)
+            .map(|derivation| derivation.map(|d| <&'static str>::from(d).to_string()))
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))
+    }
+
     /// Verify if a replay protection input has a valid signature
     ///
     /// This method checks if a given input is a replay protection input and cryptographically verifies
@@ -1171,15 +2176,325 @@ impl BitGoPsbt {
         // Create secp context
         let secp = miniscript::bitcoin::secp256k1::Secp256k1::verification_only();
 
-        // Call the Rust implementation
-        self.psbt
-            .verify_replay_protection_signature(&secp, input_index, replay_protection)
-            .map_err(|e| {
-                WasmUtxoError::new(&format!(
-                    "Failed to verify replay protection signature: {}",
-                    e
-                ))
+        // Call the Rust implementation
+        self.psbt
+            .verify_replay_protection_signature(&secp, input_index, replay_protection)
+            .map_err(|e| {
+                WasmUtxoError::new(&format!(
+                    "Failed to verify replay protection signature: {}",
+                    e
+                ))
+            })
+    }
+
+    /// Compare this PSBT with the same spend prepared for a fork chain
+    ///
+    /// # Arguments
+    /// - `fork_psbt`: The PSBT for the fork network
+    /// - `replay_protection`: Scripts that identify replay protection inputs
+    ///
+    /// # Returns
+    /// The shared inputs and outputs and the replay protection inputs of the fork PSBT
+    pub fn fork_replay_report(
+        &self,
+        fork_psbt: &BitGoPsbt,
+        replay_protection: &WasmReplayProtection,
+    ) -> Result<JsValue, WasmUtxoError> {
+        let secp = miniscript::bitcoin::secp256k1::Secp256k1::verification_only();
+        self.psbt
+            .fork_replay_report(&secp, &fork_psbt.psbt, replay_protection.inner())
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))?
+            .try_to_js_value()
+    }
+
+    /// Check that the fork PSBT spends a signed replay protection input that this PSBT does
+    /// not spend
+    ///
+    /// # Returns
+    /// The same report as `fork_replay_report`
+    pub fn verify_fork_replay_protection(
+        &self,
+        fork_psbt: &BitGoPsbt,
+        replay_protection: &WasmReplayProtection,
+    ) -> Result<JsValue, WasmUtxoError> {
+        let secp = miniscript::bitcoin::secp256k1::Secp256k1::verification_only();
+        self.psbt
+            .verify_fork_replay_protection(&secp, &fork_psbt.psbt, replay_protection.inner())
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))?
+            .try_to_js_value()
+    }
+
+    /// List the non-segwit inputs that have no `non_witness_utxo`
+    ///
+    /// # Returns
+    /// An array of `{ inputIndex, txid }`, where `txid` is the previous transaction to fetch
+    pub fn missing_non_witness_utxos(&self) -> Result<JsValue, WasmUtxoError> {
+        self.psbt.missing_non_witness_utxos().try_to_js_value()
+    }
+
+    /// Populate `witness_utxo` from `non_witness_utxo` where it is missing
+    ///
+    /// Fails without modifying the PSBT if a previous transaction does not match its outpoint
+    /// or disagrees with an existing `witness_utxo`.
+    ///
+    /// # Returns
+    /// The indices of the inputs that got a `witness_utxo`
+    pub fn normalize_utxos(&mut self) -> Result<Vec<u32>, WasmUtxoError> {
+        let indices = self.psbt.normalize_utxos()?;
+        Ok(indices.into_iter().map(|i| i as u32).collect())
+    }
+
+    /// Clone this PSBT as an unsigned template for a chain that shares its history
+    ///
+    /// Keeps the inputs, output scripts and values, removes all signatures and resets the
+    /// sighash types to the defaults of the target network.
+    ///
+    /// # Arguments
+    /// - `network`: The target network name (e.g. "bch", "bitcoincash")
+    pub fn clone_for_network(&self, network: &str) -> Result<BitGoPsbt, WasmUtxoError> {
+        let network = parse_network(network)?;
+        let psbt = self
+            .psbt
+            .clone_for_network(network)
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))?;
+        Ok(BitGoPsbt {
+            psbt,
+            first_rounds: HashMap::new(),
+        })
+    }
+
+    /// Get the vault policy fields of the PSBT
+    ///
+    /// # Returns
+    /// `{ spendLimit, destinationWhitelistHash, approvalQuorumId }`, with null for unset fields
+    pub fn vault_policy(&self) -> Result<JsValue, WasmUtxoError> {
+        self.psbt
+            .vault_policy()
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))?
+            .try_to_js_value()
+    }
+
+    /// Replace the vault policy fields of the PSBT
+    ///
+    /// # Arguments
+    /// - `policy`: `{ spendLimit?, destinationWhitelistHash?, approvalQuorumId? }`; missing or
+    ///   null fields are removed from the PSBT
+    pub fn set_vault_policy(&mut self, policy: JsValue) -> Result<(), WasmUtxoError> {
+        use crate::fixed_script_wallet::bitgo_psbt::VaultPolicy;
+        use crate::wasm::try_from_js_value::Bytes;
+
+        let destination_whitelist_hash: Option<Bytes<32>> =
+            get_field(&policy, "destinationWhitelistHash")?;
+        self.psbt.set_vault_policy(&VaultPolicy {
+            spend_limit: get_field(&policy, "spendLimit")?,
+            destination_whitelist_hash: destination_whitelist_hash.map(Into::into),
+            approval_quorum_id: get_field(&policy, "approvalQuorumId")?,
+        });
+        Ok(())
+    }
+
+    /// Set the witness script or MuSig2 participants of an output paying to a 2-of-2
+    /// channel funding output
+    ///
+    /// # Arguments
+    /// - `output_type`: "p2wsh" or "p2trMusig2"
+    /// - `pubkey_a`, `pubkey_b`: The keys of both parties, in either order
+    /// - `key_sources`: `{ pubkey, masterFingerprint, path }[]` of the keys we know, usually
+    ///   only our own
+    pub fn set_funding_output(
+        &mut self,
+        output_index: u32,
+        output_type: &str,
+        pubkey_a: &[u8],
+        pubkey_b: &[u8],
+        key_sources: Option<js_sys::Array>,
+    ) -> Result<(), WasmUtxoError> {
+        let funding = parse_funding_output(output_type, pubkey_a, pubkey_b)?;
+        let key_sources = parse_funding_key_sources(key_sources)?;
+        let psbt = self.psbt.psbt_mut();
+        let index = output_index as usize;
+        let count = psbt.outputs.len();
+        let (Some(output), Some(tx_out)) = (
+            psbt.outputs.get_mut(index),
+            psbt.unsigned_tx.output.get(index),
+        ) else {
+            return Err(FundingOutputError::IndexOutOfBounds { index, count }.into());
+        };
+        Ok(funding.update_output(output, &tx_out.script_pubkey, &key_sources)?)
+    }
+
+    /// Set the witness script or MuSig2 participants of an input spending a 2-of-2 channel
+    /// funding output
+    ///
+    /// Same arguments as `set_funding_output`. If the input has a witness utxo, its script
+    /// must be the funding output script.
+    pub fn set_funding_input(
+        &mut self,
+        input_index: u32,
+        output_type: &str,
+        pubkey_a: &[u8],
+        pubkey_b: &[u8],
+        key_sources: Option<js_sys::Array>,
+    ) -> Result<(), WasmUtxoError> {
+        let funding = parse_funding_output(output_type, pubkey_a, pubkey_b)?;
+        let key_sources = parse_funding_key_sources(key_sources)?;
+        let inputs = &mut self.psbt.psbt_mut().inputs;
+        let index = input_index as usize;
+        let count = inputs.len();
+        let input = inputs
+            .get_mut(index)
+            .ok_or(FundingOutputError::IndexOutOfBounds { index, count })?;
+        Ok(funding.update_input(input, &key_sources)?)
+    }
+
+    /// Recognize a 2-of-2 channel funding output from the metadata of a PSBT output
+    ///
+    /// # Returns
+    /// `{ type, pubkeys }` with the keys sorted, or `null` if the output has no funding
+    /// output metadata
+    pub fn funding_output(&self, output_index: u32) -> Result<JsValue, WasmUtxoError> {
+        let psbt = self.psbt.psbt();
+        let index = output_index as usize;
+        let count = psbt.outputs.len();
+        let (Some(output), Some(tx_out)) =
+            (psbt.outputs.get(index), psbt.unsigned_tx.output.get(index))
+        else {
+            return Err(FundingOutputError::IndexOutOfBounds { index, count }.into());
+        };
+        FundingOutput::from_psbt_output(output, &tx_out.script_pubkey).try_to_js_value()
+    }
+
+    /// Recognize a 2-of-2 channel funding output from the metadata and witness utxo of a
+    /// PSBT input
+    ///
+    /// # Returns
+    /// `{ type, pubkeys }` with the keys sorted, or `null` if the input has no witness utxo
+    /// or no funding output metadata
+    pub fn funding_input(&self, input_index: u32) -> Result<JsValue, WasmUtxoError> {
+        let inputs = &self.psbt.psbt().inputs;
+        let index = input_index as usize;
+        let input = inputs
+            .get(index)
+            .ok_or(FundingOutputError::IndexOutOfBounds {
+                index,
+                count: inputs.len(),
+            })?;
+        input
+            .witness_utxo
+            .as_ref()
+            .and_then(|utxo| FundingOutput::from_psbt_input(input, &utxo.script_pubkey))
+            .try_to_js_value()
+    }
+
+    /// Get the label of an input, `undefined` if it has none
+    pub fn input_label(&self, input_index: u32) -> Result<Option<String>, WasmUtxoError> {
+        self.psbt
+            .input_label(input_index as usize)
+            .map_err(WasmUtxoError::from)
+    }
+
+    /// Set or, with `undefined`, remove the label of an input
+    pub fn set_input_label(
+        &mut self,
+        input_index: u32,
+        label: Option<String>,
+    ) -> Result<(), WasmUtxoError> {
+        self.psbt
+            .set_input_label(input_index as usize, label.as_deref())
+            .map_err(WasmUtxoError::from)
+    }
+
+    /// Get the label of an output, `undefined` if it has none
+    pub fn output_label(&self, output_index: u32) -> Result<Option<String>, WasmUtxoError> {
+        self.psbt
+            .output_label(output_index as usize)
+            .map_err(WasmUtxoError::from)
+    }
+
+    /// Set or, with `undefined`, remove the label of an output
+    pub fn set_output_label(
+        &mut self,
+        output_index: u32,
+        label: Option<String>,
+    ) -> Result<(), WasmUtxoError> {
+        self.psbt
+            .set_output_label(output_index as usize, label.as_deref())
+            .map_err(WasmUtxoError::from)
+    }
+
+    /// Return a copy of the PSBT that can be shared with an external signer
+    ///
+    /// Strips global xpubs, output metadata and the key origins and BitGo proprietary
+    /// fields of all inputs not listed in `signer_inputs`.
+    pub fn redact(&self, signer_inputs: Vec<u32>) -> Result<BitGoPsbt, WasmUtxoError> {
+        let signer_inputs: Vec<usize> = signer_inputs.into_iter().map(|i| i as usize).collect();
+        let psbt = self
+            .psbt
+            .redact(&signer_inputs)
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))?;
+        Ok(BitGoPsbt {
+            psbt,
+            first_rounds: HashMap::new(),
+        })
+    }
+
+    /// Add outputs paying BIP-352 silent payment addresses
+    ///
+    /// The output keys are derived from the private keys of the eligible inputs, so all
+    /// inputs must be added before calling this.
+    ///
+    /// # Arguments
+    /// - `recipients`: Array of `{ address, value }` objects
+    /// - `input_indices`: Indices of the inputs whose keys are given in `input_keys`
+    /// - `input_keys`: Private keys of the eligible inputs
+    ///
+    /// # Returns
+    /// The indices of the added outputs
+    pub fn add_silent_payment_outputs(
+        &mut self,
+        recipients: js_sys::Array,
+        input_indices: Vec<u32>,
+        input_keys: Vec<WasmECPair>,
+    ) -> Result<Vec<u32>, WasmUtxoError> {
+        use crate::silent_payments::{
+            derive_outputs, SilentPaymentAddress, SilentPaymentRecipient,
+        };
+
+        if input_indices.len() != input_keys.len() {
+            return Err(WasmUtxoError::new(
+                "input_indices and input_keys must have the same length",
+            ));
+        }
+        let network = self.psbt.network();
+        let recipients = recipients
+            .iter()
+            .map(|item| {
+                let address: String = get_field(&item, "address")?;
+                Ok(SilentPaymentRecipient {
+                    address: SilentPaymentAddress::decode(&address, network)
+                        .map_err(|e| WasmUtxoError::new(&e.to_string()))?,
+                    value: get_field(&item, "value")?,
+                })
+            })
+            .collect::<Result<Vec<_>, WasmUtxoError>>()?;
+        let input_keys = input_indices
+            .iter()
+            .zip(&input_keys)
+            .map(|(&index, ecpair)| Ok((index as usize, ecpair.get_private_key()?)))
+            .collect::<Result<Vec<_>, WasmUtxoError>>()?;
+
+        let secp = miniscript::bitcoin::secp256k1::Secp256k1::new();
+        let outputs = derive_outputs(&secp, self.psbt.psbt(), &input_keys, &recipients)
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))?;
+        outputs
+            .into_iter()
+            .map(|output| {
+                self.psbt
+                    .add_output(output.script_pubkey, output.value.to_sat())
+                    .map(|index| index as u32)
+                    .map_err(|e| WasmUtxoError::new(&e))
             })
+            .collect()
     }
 
     /// Serialize the PSBT to bytes
@@ -1192,6 +2507,72 @@ impl BitGoPsbt {
             .map_err(|e| WasmUtxoError::new(&format!("Failed to serialize PSBT: {}", e)))
     }
 
+    /// Serialize the PSBT to a readable JSON string
+    ///
+    /// Requires the `inspect` feature.
+    pub fn to_json(&self) -> Result<String, WasmUtxoError> {
+        #[cfg(feature = "inspect")]
+        {
+            self.psbt
+                .to_json()
+                .map_err(|e| WasmUtxoError::new(&format!("Failed to convert PSBT to JSON: {}", e)))
+        }
+
+        #[cfg(not(feature = "inspect"))]
+        {
+            Err(WasmUtxoError::new(
+                "inspect feature is not enabled. Rebuild with --features inspect",
+            ))
+        }
+    }
+
+    /// Rebuild a PSBT from a JSON string produced by `to_json`
+    ///
+    /// Requires the `inspect` feature.
+    pub fn from_json(json: &str) -> Result<BitGoPsbt, WasmUtxoError> {
+        #[cfg(feature = "inspect")]
+        {
+            let psbt = crate::fixed_script_wallet::bitgo_psbt::BitGoPsbt::from_json(json)
+                .map_err(|e| WasmUtxoError::new(&format!("Failed to parse PSBT JSON: {}", e)))?;
+            Ok(BitGoPsbt {
+                psbt,
+                first_rounds: HashMap::new(),
+            })
+        }
+
+        #[cfg(not(feature = "inspect"))]
+        {
+            let _ = json;
+            Err(WasmUtxoError::new(
+                "inspect feature is not enabled. Rebuild with --features inspect",
+            ))
+        }
+    }
+
+    /// List the key-value differences between this PSBT and `other`
+    ///
+    /// Requires the `inspect` feature.
+    ///
+    /// # Returns
+    /// An array of `{ map, index, change, type, name, key, oldValue, newValue }` objects
+    pub fn diff(&self, other: &BitGoPsbt) -> Result<JsValue, WasmUtxoError> {
+        #[cfg(feature = "inspect")]
+        {
+            self.psbt
+                .diff(&other.psbt)
+                .map_err(|e| WasmUtxoError::new(&format!("Failed to diff PSBTs: {}", e)))?
+                .try_to_js_value()
+        }
+
+        #[cfg(not(feature = "inspect"))]
+        {
+            let _ = other;
+            Err(WasmUtxoError::new(
+                "inspect feature is not enabled. Rebuild with --features inspect",
+            ))
+        }
+    }
+
     /// Generate and store MuSig2 nonces for all MuSig2 inputs
     ///
     /// This method generates nonces using the State-Machine API and stores them in the PSBT.
@@ -1288,12 +2669,131 @@ impl BitGoPsbt {
             // Store the FirstRound for later use in signing
             // Use (input_index, xpub) as key so multiple parties can store their FirstRounds
             self.first_rounds
-                .insert((input_index, xpub_str.clone()), first_round);
+                .insert((input_index, xpub_str.clone()), (first_round, session_id));
         }
 
         Ok(())
     }
 
+    /// Export the MuSig2 sessions created by `generate_musig2_nonces()` as an encrypted blob
+    ///
+    /// Together with the PSBT, the blob allows `import_musig2_session()` to rebuild the
+    /// FirstRound states in another process, so that a signing ceremony can outlive this
+    /// object. Sessions that have already been used for signing are not exported.
+    ///
+    /// # Arguments
+    /// - `passphrase`: Passphrase used to encrypt the session ids
+    /// - `acknowledge_risk`: Must be true on mainnets
+    ///
+    /// # Security
+    /// Whoever holds the blob, the passphrase and the xpriv can recreate the secret nonces.
+    /// Signing twice with the same session and different counterparty nonces leaks the
+    /// private key, so an exported session must be imported and signed at most once.
+    pub fn export_musig2_session(
+        &self,
+        passphrase: &str,
+        acknowledge_risk: Option<bool>,
+    ) -> Result<Vec<u8>, WasmUtxoError> {
+        use crate::fixed_script_wallet::bitgo_psbt::{Musig2Session, Musig2SessionEntry};
+
+        self.check_musig2_session_allowed(acknowledge_risk)?;
+        let mut entries = self
+            .first_rounds
+            .iter()
+            .map(|((input_index, xpub_str), (_, session_id))| {
+                Ok(Musig2SessionEntry {
+                    input_index: *input_index,
+                    xpub: xpub_str
+                        .parse()
+                        .map_err(|e| WasmUtxoError::new(&format!("Invalid xpub: {}", e)))?,
+                    session_id: *session_id,
+                })
+            })
+            .collect::<Result<Vec<_>, WasmUtxoError>>()?;
+        if entries.is_empty() {
+            return Err(WasmUtxoError::new(
+                "No MuSig2 session to export. You must call generate_musig2_nonces() first.",
+            ));
+        }
+        entries.sort_by_key(|entry| (entry.input_index, entry.xpub.encode()));
+
+        let session = Musig2Session {
+            txid: self.psbt.psbt().unsigned_tx.compute_txid(),
+            entries,
+        };
+        Ok(session.encrypt(passphrase)?)
+    }
+
+    /// Restore MuSig2 sessions exported with `export_musig2_session()`
+    ///
+    /// Rebuilds the FirstRound states of `xpriv` so that its MuSig2 inputs can be signed with
+    /// `sign_with_xpriv()` or `sign_all_musig2_inputs()`. The PSBT must be the same
+    /// transaction and must contain the nonces generated in the exported session.
+    ///
+    /// A session can be imported once: importing it again into this PSBT, or after its
+    /// signer has signed the input, fails.
+    ///
+    /// # Arguments
+    /// - `bytes`: The encrypted session
+    /// - `passphrase`: Passphrase the session was encrypted with
+    /// - `xpriv`: The signer's extended private key
+    /// - `acknowledge_risk`: Must be true on mainnets
+    ///
+    /// # Returns
+    /// The indices of the inputs whose FirstRound was restored
+    pub fn import_musig2_session(
+        &mut self,
+        bytes: &[u8],
+        passphrase: &str,
+        xpriv: &WasmBIP32,
+        acknowledge_risk: Option<bool>,
+    ) -> Result<Vec<u32>, WasmUtxoError> {
+        use crate::fixed_script_wallet::bitgo_psbt::{Musig2Session, Musig2SessionError};
+
+        self.check_musig2_session_allowed(acknowledge_risk)?;
+        let session = Musig2Session::decrypt(bytes, passphrase)?;
+        self.psbt.check_musig2_session(&session)?;
+
+        let xpriv = xpriv.to_xpriv()?;
+        let secp = miniscript::bitcoin::secp256k1::Secp256k1::new();
+        let xpub = miniscript::bitcoin::bip32::Xpub::from_priv(&secp, &xpriv);
+        let xpub_str = xpub.to_string();
+
+        let entries: Vec<_> = session
+            .entries
+            .iter()
+            .filter(|entry| entry.xpub == xpub)
+            .collect();
+        if let Some(entry) = entries.iter().find(|entry| {
+            self.first_rounds
+                .contains_key(&(entry.input_index, xpub_str.clone()))
+        }) {
+            return Err(Musig2SessionError::AlreadyImported {
+                input_index: entry.input_index,
+            }
+            .into());
+        }
+
+        let mut restored = Vec::new();
+        for entry in entries {
+            let first_round =
+                self.psbt
+                    .restore_nonce_first_round(entry.input_index, &xpriv, entry.session_id)?;
+            self.first_rounds.insert(
+                (entry.input_index, xpub_str.clone()),
+                (first_round, entry.session_id),
+            );
+            restored.push(entry.input_index as u32);
+        }
+        if restored.is_empty() {
+            return Err(WasmUtxoError::new(&format!(
+                "MuSig2 session has no entries for xpub {}",
+                xpub_str
+            )));
+        }
+        Ok(restored)
+    }
+
     /// Sign a single input with an extended private key (xpriv)
     ///
     /// This method signs a specific input using the provided xpriv. It accepts:
@@ -1339,7 +2839,7 @@ impl BitGoPsbt {
             let xpub_str = xpub.to_string();
 
             // Remove the stored FirstRound for this (input, xpub) pair (it can only be used once)
-            let first_round = self.first_rounds.remove(&(input_index, xpub_str.clone()))
+            let (first_round, _) = self.first_rounds.remove(&(input_index, xpub_str.clone()))
                 .ok_or_else(|| WasmUtxoError::new(&format!(
                     "No FirstRound found for input {} and xpub {}. You must call generate_musig2_nonces() first.",
                     input_index, xpub_str
@@ -1363,15 +2863,17 @@ impl BitGoPsbt {
             let result = self.psbt.sign(&xpriv, &secp);
 
             // Check if this specific input was signed successfully
-            match result {
+            let signed = match result {
                 Ok(signing_keys) => {
                     // Check if our input_index was in the successfully signed keys
                     if signing_keys.contains_key(&input_index) {
                         Ok(())
                     } else {
+                        let xpub = miniscript::bitcoin::bip32::Xpub::from_priv(&secp, &xpriv);
                         Err(WasmUtxoError::new(&format!(
-                            "Input {} was not signed (no key found or already signed)",
-                            input_index
+                            "Input {} was not signed (no key found or already signed){}",
+                            input_index,
+                            self.psbt.unknown_signer_hint(&xpub)
                         )))
                     }
                 }
@@ -1392,7 +2894,10 @@ impl BitGoPsbt {
                         )))
                     }
                 }
-            }
+            };
+            signed?;
+            self.grind_low_r(&xpriv, [input_index]);
+            Ok(())
         }
     }
 
@@ -1424,7 +2929,61 @@ impl BitGoPsbt {
         // Call the Rust implementation
         self.psbt
             .sign_with_privkey(input_index, &privkey)
-            .map_err(|e| WasmUtxoError::new(&format!("Failed to sign input: {}", e)))
+            .map_err(WasmUtxoError::from)?;
+        self.grind_low_r_with_privkey(&privkey, [input_index]);
+        Ok(())
+    }
+
+    /// Compute the digest that each signer of each unfinalized input has to sign
+    ///
+    /// For external signers (e.g. HSMs) that sign raw digests. Add their signatures with
+    /// `insert_external_signature`. MuSig2 key path inputs are not included.
+    ///
+    /// # Returns
+    /// - `Ok(JsValue)` with an array of `{ inputIndex, pubkey, leafHash, sighashType,
+    ///   algorithm, digest }`, where `algorithm` is one of `legacy`, `bip143`, `forkid`,
+    ///   `bip341` and `zip243`
+    /// - `Err(WasmUtxoError)` if UTXO data or scripts needed for a sighash are missing
+    pub fn compute_sighashes(&self) -> Result<JsValue, WasmUtxoError> {
+        self.psbt
+            .compute_sighashes()
+            .map_err(|e| WasmUtxoError::new(&e))?
+            .try_to_js_value()
+    }
+
+    /// Add a signature made over a digest from `compute_sighashes`
+    ///
+    /// # Arguments
+    /// - `input_index`: The index of the signed input
+    /// - `pubkey`: Compressed public key, or x-only public key for taproot
+    /// - `signature`: ECDSA signature (DER or 64-byte compact) or 64-byte Schnorr signature,
+    ///   optionally followed by the sighash type byte
+    /// - `sighash_type`: The sighash type the digest was computed for
+    ///
+    /// # Returns
+    /// - `Ok(())` if the signature verifies and was added
+    /// - `Err(WasmUtxoError)` if the key does not sign the input or the signature is invalid
+    pub fn insert_external_signature(
+        &mut self,
+        input_index: usize,
+        pubkey: &[u8],
+        signature: &[u8],
+        sighash_type: u32,
+    ) -> Result<(), WasmUtxoError> {
+        self.psbt
+            .insert_external_signature(input_index, pubkey, signature, sighash_type)
+            .map_err(|e| WasmUtxoError::new(&e))
+    }
+
+    /// Same as `insert_external_signature`, kept for existing callers
+    pub fn insert_signature(
+        &mut self,
+        input_index: usize,
+        pubkey: &[u8],
+        signature: &[u8],
+        sighash_type: u32,
+    ) -> Result<(), WasmUtxoError> {
+        self.insert_external_signature(input_index, pubkey, signature, sighash_type)
     }
 
     /// Sign all non-MuSig2 inputs with an extended private key (xpriv) in a single pass.
@@ -1438,19 +2997,37 @@ impl BitGoPsbt {
     ///
     /// # Arguments
     /// - `xpriv`: The extended private key as a WasmBIP32 instance
+    /// - `progress`: Optional `{ onProgress?, progressInterval?, signal?, startInput? }`.
+    ///   `onProgress` is called with `(signedInputs, totalInputs)` every `progressInterval`
+    ///   inputs (default 100), signing stops once the `AbortSignal` is aborted and resumes
+    ///   at input `startInput`.
     ///
     /// # Returns
     /// - `Ok(JsValue)` with an array of input indices that were signed
     /// - `Err(WasmUtxoError)` if signing fails
-    pub fn sign_all_with_xpriv(&mut self, xpriv: &WasmBIP32) -> Result<JsValue, WasmUtxoError> {
+    pub fn sign_all_with_xpriv(
+        &mut self,
+        xpriv: &WasmBIP32,
+        progress: JsValue,
+    ) -> Result<JsValue, WasmUtxoError> {
         // Extract Xpriv from WasmBIP32
         let xpriv = xpriv.to_xpriv()?;
 
         // Call the Rust implementation
-        let signing_keys = self
-            .psbt
-            .sign_all_with_xpriv(&xpriv)
-            .map_err(|e| WasmUtxoError::new(&format!("Failed to sign: {}", e)))?;
+        let signing_keys = with_js_progress(
+            &progress,
+            |progress| match progress {
+                Some(progress) => self
+                    .psbt
+                    .sign_all_with_xpriv_with_progress(&xpriv, progress),
+                None => self
+                    .psbt
+                    .sign_all_with_xpriv(&xpriv)
+                    .map_err(ProgressError::Failed),
+            },
+            |e| WasmUtxoError::new(&format!("Failed to sign: {}", e)),
+        )?;
+        self.grind_low_r(&xpriv, signing_keys.keys().copied());
 
         // Convert to JsValue - array of input indices that were signed
         let result = js_sys::Array::new();
@@ -1484,6 +3061,7 @@ impl BitGoPsbt {
             .psbt
             .sign_all_replay_protection_inputs(&privkey)
             .map_err(|e| WasmUtxoError::new(&format!("Failed to sign: {}", e)))?;
+        self.grind_low_r_with_privkey(&privkey, signed_indices.iter().copied());
 
         // Convert to JsValue array
         let result = js_sys::Array::new();
@@ -1524,7 +3102,9 @@ impl BitGoPsbt {
             .sign_single_input_with_xpriv(input_index, &xpriv)
             .map_err(|e| {
                 WasmUtxoError::new(&format!("Failed to sign input {}: {}", input_index, e))
-            })
+            })?;
+        self.grind_low_r(&xpriv, [input_index]);
+        Ok(())
     }
 
     /// Sign a single input with a raw private key, using save/restore for regular inputs.
@@ -1556,7 +3136,9 @@ impl BitGoPsbt {
             .sign_single_input_with_privkey(input_index, &privkey)
             .map_err(|e| {
                 WasmUtxoError::new(&format!("Failed to sign input {}: {}", input_index, e))
-            })
+            })?;
+        self.grind_low_r_with_privkey(&privkey, [input_index]);
+        Ok(())
     }
 
     // ==================== NEW CLEAN SIGNING API ====================
@@ -1592,17 +3174,131 @@ impl BitGoPsbt {
     ///
     /// # Arguments
     /// - `xpriv`: The extended private key as a WasmBIP32 instance
+    /// - `progress`: Optional `{ onProgress?, progressInterval?, signal?, startInput? }`.
+    ///   `onProgress` is called with `(signedInputs, totalInputs)` every `progressInterval`
+    ///   inputs (default 100), signing stops once the `AbortSignal` is aborted and resumes
+    ///   at input `startInput`.
+    ///
+    /// # Returns
+    /// - `Ok(JsValue)` with an array of input indices that were signed
+    /// - `Err(WasmUtxoError)` if signing fails
+    pub fn sign_all_wallet_inputs(
+        &mut self,
+        xpriv: &WasmBIP32,
+        progress: JsValue,
+    ) -> Result<JsValue, WasmUtxoError> {
+        self.sign_all_with_xpriv(xpriv, progress)
+    }
+
+    /// Like `sign_all_wallet_inputs`, sharding the inputs across the rayon thread pool
+    ///
+    /// The inputs are only signed concurrently if the module is built with the `parallel`
+    /// feature and the host has initialized the thread pool; otherwise they are signed
+    /// sequentially.
     ///
     /// # Returns
     /// - `Ok(JsValue)` with an array of input indices that were signed
     /// - `Err(WasmUtxoError)` if signing fails
-    pub fn sign_all_wallet_inputs(&mut self, xpriv: &WasmBIP32) -> Result<JsValue, WasmUtxoError> {
+    pub fn sign_all_wallet_inputs_parallel(
+        &mut self,
+        xpriv: &WasmBIP32,
+    ) -> Result<JsValue, WasmUtxoError> {
+        let xpriv = xpriv.to_xpriv()?;
+        let signing_keys = self
+            .psbt
+            .sign_all_with_xpriv_parallel(&xpriv)
+            .map_err(|e| WasmUtxoError::new(&format!("Failed to sign: {}", e)))?;
+        self.grind_low_r(&xpriv, signing_keys.keys().copied());
+
+        let result = js_sys::Array::new();
+        for input_index in signing_keys.keys() {
+            result.push(&JsValue::from(*input_index as u32));
+        }
+
+        Ok(JsValue::from(result))
+    }
+
+    /// Check the signing policy against this transaction without signing
+    ///
+    /// The JS `sign` wrapper calls this before signing when it is given a policy.
+    ///
+    /// # Arguments
+    /// - `wallet_keys`: The wallet's root keys, used to identify change outputs
+    /// - `replay_protection`: Scripts that are allowed as inputs without wallet validation
+    /// - `max_fee`: Maximum miner fee in satoshis
+    /// - `max_fee_rate_sat_per_vb`: Maximum fee rate in sat/vB
+    /// - `max_fee_percent`: Maximum miner fee as a percentage of the spend amount
+    /// - `require_wallet_change`: Require wallet outputs to use a recognized wallet chain
+    ///
+    /// # Returns
+    /// - `Err(WasmUtxoError)` if the policy is violated
+    pub fn check_signing_policy(
+        &self,
+        wallet_keys: &WasmRootWalletKeys,
+        replay_protection: &WasmReplayProtection,
+        max_fee: Option<u64>,
+        max_fee_rate_sat_per_vb: Option<f64>,
+        max_fee_percent: Option<f64>,
+        require_wallet_change: Option<bool>,
+    ) -> Result<(), WasmUtxoError> {
+        let policy = signing_policy_from_js(
+            max_fee,
+            max_fee_rate_sat_per_vb,
+            max_fee_percent,
+            require_wallet_change,
+        )?;
+        self.psbt
+            .check_signing_policy(wallet_keys.inner(), replay_protection.inner(), &policy)
+            .map(|_| ())
+            .map_err(|e| WasmUtxoError::new(&format!("Signing policy violated: {}", e)))
+    }
+
+    /// Like `sign_all_wallet_inputs`, but refuses to sign if the signing policy is violated.
+    ///
+    /// # Arguments
+    /// - `xpriv`: The extended private key as a WasmBIP32 instance
+    /// - `wallet_keys`: The wallet's root keys, used to identify change outputs
+    /// - `replay_protection`: Scripts that are allowed as inputs without wallet validation
+    /// - `max_fee`: Maximum miner fee in satoshis
+    /// - `max_fee_rate_sat_per_vb`: Maximum fee rate in sat/vB
+    /// - `max_fee_percent`: Maximum miner fee as a percentage of the spend amount
+    /// - `require_wallet_change`: Require wallet outputs to use a recognized wallet chain
+    /// - `override_policy`: Sign without checking the policy
+    ///
+    /// # Returns
+    /// - `Ok(JsValue)` with an array of input indices that were signed
+    /// - `Err(WasmUtxoError)` if the policy is violated or signing fails
+    #[allow(clippy::too_many_arguments)]
+    pub fn sign_all_wallet_inputs_with_policy(
+        &mut self,
+        xpriv: &WasmBIP32,
+        wallet_keys: &WasmRootWalletKeys,
+        replay_protection: &WasmReplayProtection,
+        max_fee: Option<u64>,
+        max_fee_rate_sat_per_vb: Option<f64>,
+        max_fee_percent: Option<f64>,
+        require_wallet_change: Option<bool>,
+        override_policy: Option<bool>,
+    ) -> Result<JsValue, WasmUtxoError> {
         let xpriv = xpriv.to_xpriv()?;
+        let policy = signing_policy_from_js(
+            max_fee,
+            max_fee_rate_sat_per_vb,
+            max_fee_percent,
+            require_wallet_change,
+        )?;
 
         let signing_keys = self
             .psbt
-            .sign_all_with_xpriv(&xpriv)
+            .sign_all_with_xpriv_and_policy(
+                &xpriv,
+                wallet_keys.inner(),
+                replay_protection.inner(),
+                &policy,
+                override_policy.unwrap_or(false),
+            )
             .map_err(|e| WasmUtxoError::new(&format!("Failed to sign: {}", e)))?;
+        self.grind_low_r(&xpriv, signing_keys.keys().copied());
 
         let result = js_sys::Array::new();
         for input_index in signing_keys.keys() {
@@ -1642,7 +3338,9 @@ impl BitGoPsbt {
             .sign_single_input_with_xpriv(input_index, &xpriv)
             .map_err(|e| {
                 WasmUtxoError::new(&format!("Failed to sign input {}: {}", input_index, e))
-            })
+            })?;
+        self.grind_low_r(&xpriv, [input_index]);
+        Ok(())
     }
 
     /// Sign a single MuSig2 keypath input.
@@ -1688,7 +3386,7 @@ impl BitGoPsbt {
         let xpub = miniscript::bitcoin::bip32::Xpub::from_priv(&secp, &xpriv);
         let xpub_str = xpub.to_string();
 
-        let first_round = self.first_rounds.remove(&(input_index, xpub_str.clone()))
+        let (first_round, _) = self.first_rounds.remove(&(input_index, xpub_str.clone()))
             .ok_or_else(|| WasmUtxoError::new(&format!(
                 "No FirstRound found for input {} and xpub {}. You must call generate_musig2_nonces() first.",
                 input_index, xpub_str
@@ -1759,7 +3457,7 @@ impl BitGoPsbt {
         for input_index in musig2_indices {
             // Remove the FirstRound (it can only be used once)
             let first_round = match self.first_rounds.remove(&(input_index, xpub_str.clone())) {
-                Some(fr) => fr,
+                Some((fr, _)) => fr,
                 None => continue, // Already consumed
             };
 
@@ -1810,6 +3508,7 @@ impl BitGoPsbt {
             .psbt
             .sign_all_replay_protection_inputs(&privkey)
             .map_err(|e| WasmUtxoError::new(&format!("Failed to sign: {}", e)))?;
+        self.grind_low_r_with_privkey(&privkey, signed_indices.iter().copied());
 
         let result = js_sys::Array::new();
         for index in signed_indices {
@@ -1830,15 +3529,62 @@ impl BitGoPsbt {
     /// # Arguments
     /// * `source_psbt` - The source PSBT containing data to merge
     ///
+    /// Nonces that do not match their input in this PSBT are skipped, and stale nonces
+    /// already present in this PSBT are removed.
+    ///
     /// # Returns
-    /// Ok(()) if data was successfully merged
+    /// An array of the stale nonces that were skipped or removed
     ///
     /// # Errors
     /// Returns error if networks don't match
-    pub fn combine_musig2_nonces(&mut self, source_psbt: &BitGoPsbt) -> Result<(), WasmUtxoError> {
+    pub fn combine_musig2_nonces(
+        &mut self,
+        source_psbt: &BitGoPsbt,
+    ) -> Result<JsValue, WasmUtxoError> {
         self.psbt
             .combine_musig2_nonces(&source_psbt.psbt)
-            .map_err(|e| WasmUtxoError::new(&format!("Failed to combine PSBTs: {}", e)))
+            .map_err(|e| WasmUtxoError::new(&format!("Failed to combine PSBTs: {}", e)))?
+            .try_to_js_value()
+    }
+
+    /// Remove MuSig2 nonces that do not match their input
+    ///
+    /// # Returns
+    /// An array of the removed nonces
+    pub fn purge_stale_musig2_nonces(&mut self) -> Result<JsValue, WasmUtxoError> {
+        self.psbt
+            .purge_stale_musig2_nonces()
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))?
+            .try_to_js_value()
+    }
+
+    /// Find outpoints that are spent by more than one input of the serialized PSBTs
+    ///
+    /// # Returns
+    /// An array of `{ txid, vout, kind, inputs: { psbtIndex, inputIndex }[] }`, where `kind`
+    /// is `"duplicateInput"` if all inputs are in the same PSBT and `"conflictingSpend"`
+    /// otherwise
+    pub fn find_spend_conflicts(
+        psbts: Vec<js_sys::Uint8Array>,
+        network: &str,
+    ) -> Result<JsValue, WasmUtxoError> {
+        let network = parse_network(network)?;
+        let psbts = psbts
+            .iter()
+            .map(|bytes| {
+                crate::fixed_script_wallet::bitgo_psbt::BitGoPsbt::deserialize(
+                    &bytes.to_vec(),
+                    network,
+                )
+                .map_err(|e| WasmUtxoError::new(&format!("Failed to deserialize PSBT: {}", e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        crate::fixed_script_wallet::bitgo_psbt::find_spend_conflicts(&psbts).try_to_js_value()
+    }
+
+    /// Outpoints spent by more than one input of this PSBT, like `find_spend_conflicts`
+    pub fn duplicate_inputs(&self) -> Result<JsValue, WasmUtxoError> {
+        self.psbt.duplicate_inputs().try_to_js_value()
     }
 
     /// Merge all input fields from a raw PSBT (given as bytes) into this PSBT.
@@ -1860,12 +3606,36 @@ impl BitGoPsbt {
     /// This method attempts to finalize all inputs in the PSBT, computing the final
     /// scriptSig and witness data for each input.
     ///
+    /// `progress` takes `{ onProgress?, progressInterval?, signal?, startInput? }`, see
+    /// `sign_all_with_xpriv`.
+    ///
     /// # Returns
     /// - `Ok(())` if all inputs were successfully finalized
     /// - `Err(WasmUtxoError)` if any input failed to finalize
-    pub fn finalize_all_inputs(&mut self) -> Result<(), WasmUtxoError> {
+    pub fn finalize_all_inputs(&mut self, progress: JsValue) -> Result<(), WasmUtxoError> {
+        let secp = miniscript::bitcoin::secp256k1::Secp256k1::verification_only();
+        with_js_progress(
+            &progress,
+            |progress| match progress {
+                Some(progress) => self.psbt.finalize_mut_with_progress(&secp, progress),
+                None => self.psbt.finalize_mut(&secp).map_err(ProgressError::Failed),
+            },
+            |errors| {
+                WasmUtxoError::new(&format!(
+                    "Failed to finalize {} input(s): {}",
+                    errors.len(),
+                    errors.join("; ")
+                ))
+            },
+        )
+    }
+
+    /// Like `finalize_all_inputs`, sharding the inputs across the rayon thread pool
+    ///
+    /// See `sign_all_wallet_inputs_parallel`.
+    pub fn finalize_all_inputs_parallel(&mut self) -> Result<(), WasmUtxoError> {
         let secp = miniscript::bitcoin::secp256k1::Secp256k1::verification_only();
-        self.psbt.finalize_mut(&secp).map_err(|errors| {
+        self.psbt.finalize_mut_parallel(&secp).map_err(|errors| {
             WasmUtxoError::new(&format!(
                 "Failed to finalize {} input(s): {}",
                 errors.len(),
@@ -1874,20 +3644,88 @@ impl BitGoPsbt {
         })
     }
 
+    /// Finalize an input with a caller-provided scriptSig and witness template
+    ///
+    /// For custom scripts that the default finalizer cannot satisfy, such as CHECKDATASIG
+    /// replay protection variants or CLTV-wrapped multisig. The template lists the stack
+    /// items bottom first; signature items are taken from the partial signatures of the input.
+    ///
+    /// # Arguments
+    /// - `input_index`: The input to finalize
+    /// - `template`: `{ scriptSig?: TemplateItem[], witness?: TemplateItem[] }`
+    pub fn finalize_input_with_template(
+        &mut self,
+        input_index: usize,
+        template: JsValue,
+    ) -> Result<(), WasmUtxoError> {
+        use crate::fixed_script_wallet::bitgo_psbt::WitnessTemplate;
+
+        let template = WitnessTemplate::try_from_js_value(&template)?;
+        let secp = miniscript::bitcoin::secp256k1::Secp256k1::verification_only();
+        self.psbt
+            .finalize_input_with_satisfier(&secp, input_index, &template)
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))
+    }
+
+    /// Execute the final scriptSig and witness of every input against its prevout
+    ///
+    /// Catches finalization bugs before the transaction is broadcast.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the scripts of all inputs succeed
+    /// - `Err(WasmUtxoError)` if an input is not finalized or its scripts fail
+    pub fn verify_input_scripts(&self) -> Result<(), WasmUtxoError> {
+        let secp = miniscript::bitcoin::secp256k1::Secp256k1::verification_only();
+        self.psbt
+            .verify_input_scripts(&secp)
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))
+    }
+
+    /// Take a snapshot of the inputs, outputs and fee at parse time
+    ///
+    /// Pass the returned object to the `invariants` argument of the extract methods to
+    /// check that the transaction did not change since.
+    ///
+    /// # Returns
+    /// - `Ok(JsValue)` with `{ inputs, outputsHash, fee }`
+    /// - `Err(WasmUtxoError)` if an input value is unknown or outputs exceed inputs
+    pub fn extract_invariants(&self) -> Result<JsValue, WasmUtxoError> {
+        self.psbt.extract_invariants()?.try_to_js_value()
+    }
+
+    /// Digest of the network, unsigned transaction, outputs and fee
+    ///
+    /// Stable across re-serialization and added signatures, nonces or labels, so that an
+    /// approval can be bound to exactly one transaction.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<u8>)` with the 32-byte SHA256 digest
+    /// - `Err(WasmUtxoError)` if an input value is unknown or outputs exceed inputs
+    pub fn approval_digest(&self) -> Result<Vec<u8>, WasmUtxoError> {
+        use miniscript::bitcoin::hashes::Hash;
+        Ok(self.psbt.approval_digest()?.to_byte_array().to_vec())
+    }
+
     /// Extract the final transaction from a finalized PSBT
     ///
     /// This method should be called after all inputs have been finalized.
     /// It extracts the fully signed transaction as a WASM transaction instance
     /// appropriate for the network (WasmTransaction, WasmDashTransaction, or WasmZcashTransaction).
     ///
+    /// If `invariants` (from `extract_invariants`) is given, the inputs, outputs and fee are
+    /// checked against it first.
+    ///
     /// # Returns
     /// - `Ok(JsValue)` containing the WASM transaction instance
-    /// - `Err(WasmUtxoError)` if the PSBT is not fully finalized or extraction fails
+    /// - `Err(WasmUtxoError)` if the PSBT is not fully finalized, the invariants do not hold,
+    ///   or extraction fails
     pub fn extract_transaction(
         &self,
         max_fee_rate_sat_per_vb: Option<f64>,
+        invariants: JsValue,
     ) -> Result<JsValue, WasmUtxoError> {
         use crate::fixed_script_wallet::bitgo_psbt::BitGoPsbt as InnerBitGoPsbt;
+        self.check_extract_invariants(&invariants)?;
         let policy = fee_policy_from_js(max_fee_rate_sat_per_vb);
         match &self.psbt {
             InnerBitGoPsbt::BitcoinLike(..) => {
@@ -1921,10 +3759,13 @@ impl BitGoPsbt {
     ///
     /// This avoids re-parsing bytes by returning the transaction directly.
     /// Only valid for Bitcoin-like networks (not Dash or Zcash).
+    /// `invariants` is checked like in `extract_transaction`.
     pub fn extract_bitcoin_transaction(
         &self,
         max_fee_rate_sat_per_vb: Option<f64>,
+        invariants: JsValue,
     ) -> Result<crate::wasm::transaction::WasmTransaction, WasmUtxoError> {
+        self.check_extract_invariants(&invariants)?;
         let tx = self
             .psbt
             .clone()
@@ -1937,10 +3778,13 @@ impl BitGoPsbt {
     ///
     /// This avoids re-parsing bytes by returning the transaction directly.
     /// Only valid for Dash networks.
+    /// `invariants` is checked like in `extract_transaction`.
     pub fn extract_dash_transaction(
         &self,
         max_fee_rate_sat_per_vb: Option<f64>,
+        invariants: JsValue,
     ) -> Result<crate::wasm::dash_transaction::WasmDashTransaction, WasmUtxoError> {
+        self.check_extract_invariants(&invariants)?;
         let parts = self
             .psbt
             .clone()
@@ -1953,10 +3797,13 @@ impl BitGoPsbt {
     ///
     /// This avoids re-parsing bytes by returning the transaction directly.
     /// Only valid for Zcash networks.
+    /// `invariants` is checked like in `extract_transaction`.
     pub fn extract_zcash_transaction(
         &self,
         max_fee_rate_sat_per_vb: Option<f64>,
+        invariants: JsValue,
     ) -> Result<crate::wasm::transaction::WasmZcashTransaction, WasmUtxoError> {
+        self.check_extract_invariants(&invariants)?;
         let parts = self
             .psbt
             .clone()
@@ -1985,9 +3832,28 @@ impl BitGoPsbt {
     /// # Errors
     /// - Returns error if any input is not a p2ms type (Taproot, replay protection, etc.)
     /// - Returns error if any input has 0 or more than 1 partial signature
-    pub fn extract_half_signed_legacy_tx(&self) -> Result<Vec<u8>, WasmUtxoError> {
+    ///
+    /// # Arguments
+    /// - `layout`: `"positional"` (default) for an `OP_0` placeholder per missing signature,
+    ///   `"compact"` to leave out the missing signatures
+    pub fn extract_half_signed_legacy_tx(
+        &self,
+        layout: Option<String>,
+    ) -> Result<Vec<u8>, WasmUtxoError> {
+        use crate::fixed_script_wallet::bitgo_psbt::HalfSignedLayout;
+
+        let layout = match layout.as_deref() {
+            None | Some("positional") => HalfSignedLayout::Positional,
+            Some("compact") => HalfSignedLayout::Compact,
+            Some(other) => {
+                return Err(WasmUtxoError::new(&format!(
+                    "Unknown half-signed layout: {}",
+                    other
+                )))
+            }
+        };
         self.psbt
-            .extract_half_signed_legacy_tx()
+            .extract_half_signed_legacy_tx_with_layout(layout)
             .map_err(|e| WasmUtxoError::new(&e))
     }
 
@@ -2020,3 +3886,22 @@ pub fn zcash_branch_id_for_height(network: &str, height: u32) -> Result<Option<u
     };
     Ok(crate::zcash::branch_id_for_height(height, is_mainnet))
 }
+
+/// Add Zcash network upgrades that are not in the built-in table yet
+///
+/// `upgrades`: `{ mainnet?: [...], testnet?: [...] }` with `{ branchId, activationHeight }`
+/// entries that activate after the built-in upgrades, in chronological order. Replaces the
+/// upgrades of a previous call. Affects `zcash_branch_id_for_height` and PSBTs created from a
+/// block height.
+#[wasm_bindgen]
+pub fn set_zcash_network_upgrades(upgrades: JsValue) -> Result<(), WasmUtxoError> {
+    let upgrades = crate::zcash::ExtendedNetworkUpgrades::try_from_js_value(&upgrades)?;
+    crate::zcash::set_network_upgrade_provider(std::rc::Rc::new(upgrades));
+    Ok(())
+}
+
+/// Remove the upgrades added with `set_zcash_network_upgrades`
+#[wasm_bindgen]
+pub fn reset_zcash_network_upgrades() {
+    crate::zcash::reset_network_upgrade_provider();
+}
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::WasmUtxoError;
+use crate::fixed_script_wallet::bitgo_psbt::{ExtractFeePolicy, TxPackage};
+use crate::wasm::try_into_js_value::TryIntoJsValue;
+
+use super::{parse_network, BitGoPsbt, WasmDimensions};
+
+/// Virtual size of a package transaction
+///
+/// Finalized transactions use their actual size, others the maximum estimated size.
+pub(super) fn estimate_vsize(
+    psbt: &crate::fixed_script_wallet::bitgo_psbt::BitGoPsbt,
+) -> Result<u64, WasmUtxoError> {
+    let finalized = psbt
+        .psbt()
+        .inputs
+        .iter()
+        .all(|input| input.final_script_sig.is_some() || input.final_script_witness.is_some());
+    if finalized {
+        if let Ok(tx) = psbt
+            .clone()
+            .extract_bitcoin_tx_with_fee_policy(ExtractFeePolicy::Unchecked)
+        {
+            return Ok(tx.vsize() as u64);
+        }
+    }
+    let wrapper = BitGoPsbt {
+        psbt: psbt.clone(),
+        first_rounds: HashMap::new(),
+    };
+    Ok(WasmDimensions::from_psbt(&wrapper)?.get_vsize(None) as u64)
+}
+
+/// A child transaction and its unconfirmed parents for package relay
+#[wasm_bindgen]
+pub struct WasmTxPackage {
+    package: TxPackage,
+}
+
+#[wasm_bindgen]
+impl WasmTxPackage {
+    /// Create a package from serialized PSBTs, in any order
+    ///
+    /// The transactions are sorted parents first; the child must spend an output of every
+    /// parent.
+    pub fn from_psbts(
+        psbts: Vec<js_sys::Uint8Array>,
+        network: &str,
+    ) -> Result<WasmTxPackage, WasmUtxoError> {
+        let network = parse_network(network)?;
+        let transactions = psbts
+            .iter()
+            .map(|bytes| {
+                crate::fixed_script_wallet::bitgo_psbt::BitGoPsbt::deserialize(
+                    &bytes.to_vec(),
+                    network,
+                )
+                .map_err(|e| WasmUtxoError::new(&format!("Failed to deserialize PSBT: {}", e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let package =
+            TxPackage::new(transactions).map_err(|e| WasmUtxoError::new(&e.to_string()))?;
+        Ok(WasmTxPackage { package })
+    }
+
+    /// The txids of the transactions, parents first
+    pub fn txids(&self) -> Vec<String> {
+        self.package
+            .txids()
+            .iter()
+            .map(|txid| txid.to_string())
+            .collect()
+    }
+
+    /// Compute the aggregate fee and fee rate of the package
+    ///
+    /// Unsigned transactions are counted with their maximum estimated vsize.
+    ///
+    /// # Returns
+    /// `{ fees: bigint[], totalFee: bigint, totalVsize: number, feeRate: number }`
+    pub fn package_fee(&self) -> Result<JsValue, WasmUtxoError> {
+        let vsizes = self.vsizes()?;
+        self.package
+            .package_fee(&vsizes)
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))?
+            .try_to_js_value()
+    }
+
+    /// Check that the package fee rate is at least `min_fee_rate` sat/vB
+    pub fn check_fee_rate(&self, min_fee_rate: f64) -> Result<JsValue, WasmUtxoError> {
+        let vsizes = self.vsizes()?;
+        self.package
+            .check_fee_rate(&vsizes, min_fee_rate)
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))?
+            .try_to_js_value()
+    }
+
+    /// Extract the finalized transactions in `submitpackage` order (parents first)
+    pub fn extract_transactions(&self) -> Result<Vec<js_sys::Uint8Array>, WasmUtxoError> {
+        let transactions = self
+            .package
+            .extract_transactions()
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))?;
+        Ok(transactions
+            .iter()
+            .map(|tx| js_sys::Uint8Array::from(tx.as_slice()))
+            .collect())
+    }
+}
+
+impl WasmTxPackage {
+    fn vsizes(&self) -> Result<Vec<u64>, WasmUtxoError> {
+        self.package
+            .transactions()
+            .iter()
+            .map(estimate_vsize)
+            .collect()
+    }
+}
@@ -5,16 +5,13 @@
 
 use wasm_bindgen::prelude::*;
 
+#[cfg(feature = "inspect")]
+use crate::wasm::networks::parse_network;
+
 #[cfg(not(feature = "inspect"))]
 const FEATURE_NOT_ENABLED_ERROR: &str =
     "inspect feature is not enabled. Rebuild with --features inspect";
 
-#[cfg(feature = "inspect")]
-fn parse_network(coin_name: &str) -> Result<crate::networks::Network, JsError> {
-    crate::networks::Network::from_coin_name(coin_name)
-        .ok_or_else(|| JsError::new(&format!("Unknown network: {}", coin_name)))
-}
-
 /// Parse a PSBT and return a JSON representation of its structure.
 ///
 /// This function parses the PSBT using the standard bitcoin crate parser
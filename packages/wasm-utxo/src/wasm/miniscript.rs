@@ -3,7 +3,10 @@ use crate::wasm::try_from_js_value::get_field;
 use crate::wasm::try_into_js_value::TryIntoJsValue;
 use miniscript::bitcoin::{PublicKey, XOnlyPublicKey};
 use miniscript::miniscript::analyzable::ExtParams;
-use miniscript::{bitcoin, Legacy, Miniscript, Segwitv0, Tap};
+use miniscript::policy::Concrete;
+use miniscript::{
+    bitcoin, Descriptor, DescriptorPublicKey, Legacy, Miniscript, ScriptContext, Segwitv0, Tap,
+};
 use std::fmt;
 use std::str::FromStr;
 use wasm_bindgen::prelude::wasm_bindgen;
@@ -21,6 +24,21 @@ macro_rules! unwrap_apply {
     };
 }
 
+/// BIP-341 NUMS point, used as the internal key of compiled taproot policies that have no
+/// key spend path
+const NUMS_INTERNAL_KEY: &str = "50929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0";
+
+/// Result of compiling a concrete spending policy
+pub(crate) struct CompiledPolicy {
+    pub descriptor: String,
+    /// Compiled miniscript (not set for taproot, where the policy is split into leaves)
+    pub miniscript: Option<String>,
+    /// Size of the compiled script in bytes (not set for taproot)
+    pub script_size: Option<usize>,
+    /// Maximum weight of the witness or scriptSig that satisfies the descriptor
+    pub max_weight_to_satisfy: u32,
+}
+
 pub enum WrapMiniscriptEnum {
     Tap(Miniscript<XOnlyPublicKey, Tap>),
     Segwit(Miniscript<PublicKey, Segwitv0>),
@@ -137,6 +155,81 @@ impl WrapMiniscript {
             _ => Err(WasmUtxoError::new("Invalid context type")),
         }
     }
+
+    /// Compile a concrete spending policy (e.g. `thresh(2,pk(A),pk(B),pk(C))`) to a descriptor.
+    ///
+    /// The policy is compiled to miniscript for the script context and wrapped in a `sh`
+    /// (legacy), `wsh` (segwitv0) or `tr` (tap) descriptor. For taproot, a key that can be
+    /// extracted from the policy becomes the internal key; otherwise `unspendable_key`
+    /// (default: the BIP-341 NUMS point) is used.
+    ///
+    /// # Arguments
+    /// * `policy` - Concrete policy; keys may be hex public keys or xpubs with derivation paths
+    /// * `context_type` - "tap", "segwitv0" or "legacy"
+    /// * `unspendable_key` - Internal key for taproot policies without a key spend path
+    ///
+    /// # Returns
+    /// `{ descriptor, miniscript, scriptSize, maxWeightToSatisfy }`
+    #[wasm_bindgen(js_name = compilePolicy, skip_typescript)]
+    pub fn compile_policy(
+        policy: &str,
+        context_type: &str,
+        unspendable_key: Option<String>,
+    ) -> Result<JsValue, WasmUtxoError> {
+        let policy = Concrete::<DescriptorPublicKey>::from_str(policy)
+            .map_err(|e| WasmUtxoError::new(&format!("Invalid policy: {}", e)))?;
+        let compiled = match context_type {
+            "tap" => {
+                let unspendable_key = DescriptorPublicKey::from_str(
+                    unspendable_key.as_deref().unwrap_or(NUMS_INTERNAL_KEY),
+                )
+                .map_err(|e| WasmUtxoError::new(&format!("Invalid unspendable key: {}", e)))?;
+                let descriptor = policy
+                    .compile_tr(Some(unspendable_key))
+                    .map_err(|e| WasmUtxoError::new(&format!("Failed to compile policy: {}", e)))?;
+                CompiledPolicy {
+                    max_weight_to_satisfy: max_weight_to_satisfy(&descriptor)?,
+                    descriptor: descriptor.to_string(),
+                    miniscript: None,
+                    script_size: None,
+                }
+            }
+            "segwitv0" => compile_with_context::<Segwitv0>(&policy, Descriptor::new_wsh)?,
+            "legacy" => compile_with_context::<Legacy>(&policy, Descriptor::new_sh)?,
+            _ => return Err(WasmUtxoError::new("Invalid context type")),
+        };
+        compiled.try_to_js_value()
+    }
+}
+
+fn max_weight_to_satisfy(
+    descriptor: &Descriptor<DescriptorPublicKey>,
+) -> Result<u32, WasmUtxoError> {
+    descriptor
+        .max_weight_to_satisfy()?
+        .to_wu()
+        .try_into()
+        .map_err(|_| WasmUtxoError::new("Weight exceeds u32"))
+}
+
+fn compile_with_context<Ctx: ScriptContext>(
+    policy: &Concrete<DescriptorPublicKey>,
+    new_descriptor: impl Fn(
+        Miniscript<DescriptorPublicKey, Ctx>,
+    ) -> Result<Descriptor<DescriptorPublicKey>, miniscript::Error>,
+) -> Result<CompiledPolicy, WasmUtxoError> {
+    let miniscript = policy
+        .compile::<Ctx>()
+        .map_err(|e| WasmUtxoError::new(&format!("Failed to compile policy: {}", e)))?;
+    let script_size = miniscript.script_size();
+    let miniscript_string = miniscript.to_string();
+    let descriptor = new_descriptor(miniscript)?;
+    Ok(CompiledPolicy {
+        max_weight_to_satisfy: max_weight_to_satisfy(&descriptor)?,
+        descriptor: descriptor.to_string(),
+        miniscript: Some(miniscript_string),
+        script_size: Some(script_size),
+    })
 }
 
 fn build_ext_params(config: &JsValue) -> Result<ExtParams, WasmUtxoError> {
@@ -4,6 +4,8 @@ mod bip322;
 mod dash_transaction;
 mod descriptor;
 mod ecpair;
+mod electrum_wallet;
+mod psbt_envelope;
 mod psbt_ops;
 #[macro_use]
 mod psbt;
@@ -12,9 +14,11 @@ mod inscriptions;
 mod inspect;
 mod message;
 mod miniscript;
+mod networks;
 mod package_info;
 mod recursive_tap_tree;
 mod replay_protection;
+mod trace_hook;
 mod transaction;
 mod try_from_js_value;
 mod try_into_js_value;
@@ -27,12 +31,17 @@ pub use bip322::Bip322Namespace;
 pub use dash_transaction::WasmDashTransaction;
 pub use descriptor::WrapDescriptor;
 pub use ecpair::WasmECPair;
-pub use fixed_script_wallet::{BitGoPsbt, FixedScriptWalletNamespace, WasmDimensions};
+pub use electrum_wallet::WasmElectrumWallet;
+pub use fixed_script_wallet::{
+    BitGoPsbt, FixedScriptWalletNamespace, WasmDimensions, WasmTxPackage,
+};
 pub use inscriptions::InscriptionsNamespace;
 pub use message::MessageNamespace;
 pub use miniscript::WrapMiniscript;
+pub use networks::NetworksNamespace;
 pub use package_info::WasmUtxoNamespace;
 pub use psbt::WrapPsbt;
+pub use psbt_envelope::PsbtEnvelopeNamespace;
 pub use replay_protection::WasmReplayProtection;
 pub use transaction::{WasmTransaction, WasmZcashTransaction};
 pub use utxolib_compat::UtxolibCompatNamespace;
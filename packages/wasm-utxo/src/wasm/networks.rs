@@ -0,0 +1,76 @@
+use wasm_bindgen::prelude::*;
+
+use crate::error::WasmUtxoError;
+use crate::networks::Network;
+use crate::wasm::try_into_js_value::TryIntoJsValue;
+
+/// Parse a network from a string that can be either a utxolib name or a coin name
+pub(crate) fn parse_network(network: &str) -> Result<Network, WasmUtxoError> {
+    Network::from_utxolib_name(network)
+        .or_else(|| Network::from_coin_name(network))
+        .ok_or_else(|| {
+            WasmUtxoError::new(&format!(
+                "Unknown network '{}'. Expected a utxolib name (e.g., 'bitcoin', 'testnet') or coin name (e.g., 'btc', 'tbtc')",
+                network
+            ))
+        })
+}
+
+#[wasm_bindgen]
+pub struct NetworksNamespace;
+
+#[wasm_bindgen]
+impl NetworksNamespace {
+    /// Get the chain parameters of a network
+    ///
+    /// # Arguments
+    /// * `network` - A utxolib name (e.g. "bitcoinTestnet4") or coin name (e.g. "tbtc4")
+    ///
+    /// # Returns
+    /// `{ coinName, utxolibName, mainnet, genesisBlockHash, bech32Hrp, bip32: { public, private } }`
+    #[wasm_bindgen]
+    pub fn get_network_params(network: &str) -> Result<JsValue, WasmUtxoError> {
+        parse_network(network)?.try_to_js_value()
+    }
+
+    /// Get the capability matrix of a network
+    ///
+    /// # Returns
+    /// `{ segwit, taproot, p2mr, sighashForkId, csv, scriptTypes, addressEncodings,
+    /// relayFeeRates, maxMoney, psbtFormat }`
+    #[wasm_bindgen]
+    pub fn get_capabilities(network: &str) -> Result<JsValue, WasmUtxoError> {
+        parse_network(network)?.capabilities().try_to_js_value()
+    }
+
+    /// Get the default relay fee rates of a network, in satoshis per 1000 virtual bytes
+    ///
+    /// # Returns
+    /// `{ minRelayFee, incrementalRelayFee, dustOutputLimit }`, or `null` for networks
+    /// without a relay fee table entry (Bitcoin SV)
+    #[wasm_bindgen]
+    pub fn get_relay_fee_rates(network: &str) -> Result<JsValue, WasmUtxoError> {
+        parse_network(network)?.relay_fee_rates().try_to_js_value()
+    }
+
+    /// Check that a transaction pays at least the minimum relay fee of the network
+    ///
+    /// # Arguments
+    /// * `fee` - Fee of the transaction
+    /// * `vsize` - Virtual size of the transaction
+    /// * `output_values` - Values of the outputs, for the Dogecoin dust rule
+    #[wasm_bindgen]
+    pub fn validate_fee_rate(
+        network: &str,
+        fee: u64,
+        vsize: u64,
+        output_values: Vec<u64>,
+    ) -> Result<(), WasmUtxoError> {
+        Ok(crate::relay_fee::validate_fee_rate(
+            parse_network(network)?,
+            fee,
+            vsize,
+            &output_values,
+        )?)
+    }
+}
@@ -0,0 +1,61 @@
+use crate::error::WasmUtxoError;
+use crate::psbt_envelope::{self, EnvelopeHeader};
+use crate::wasm::ecpair::WasmECPair;
+use crate::wasm::try_into_js_value::TryIntoJsValue;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub struct PsbtEnvelopeNamespace;
+
+#[wasm_bindgen]
+impl PsbtEnvelopeNamespace {
+    /// Encrypt a serialized PSBT for `recipient`
+    ///
+    /// `sender` must have a private key; `recipient` only needs a public key.
+    #[wasm_bindgen]
+    pub fn seal(
+        psbt: &[u8],
+        sender: &WasmECPair,
+        recipient: &WasmECPair,
+    ) -> Result<js_sys::Uint8Array, WasmUtxoError> {
+        let envelope = psbt_envelope::seal(
+            psbt,
+            &sender.get_private_key()?,
+            &recipient.get_public_key(),
+        )
+        .map_err(|e| WasmUtxoError::new(&e.to_string()))?;
+        Ok(js_sys::Uint8Array::from(&envelope[..]))
+    }
+
+    /// Decrypt an envelope sealed by `sender` for `recipient`
+    ///
+    /// `recipient` must have a private key; `sender` only needs a public key.
+    #[wasm_bindgen]
+    pub fn open(
+        envelope: &[u8],
+        recipient: &WasmECPair,
+        sender: &WasmECPair,
+    ) -> Result<js_sys::Uint8Array, WasmUtxoError> {
+        let psbt = psbt_envelope::open(
+            envelope,
+            &recipient.get_private_key()?,
+            &sender.get_public_key(),
+        )
+        .map_err(|e| WasmUtxoError::new(&e.to_string()))?;
+        Ok(js_sys::Uint8Array::from(&psbt[..]))
+    }
+
+    /// Read the sender and recipient key ids of an envelope without decrypting it
+    #[wasm_bindgen]
+    pub fn header(envelope: &[u8]) -> Result<JsValue, WasmUtxoError> {
+        EnvelopeHeader::parse(envelope)
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))?
+            .try_to_js_value()
+    }
+
+    /// Key id (first 4 bytes of HASH160 of the compressed public key) of a key
+    #[wasm_bindgen]
+    pub fn key_id(key: &WasmECPair) -> js_sys::Uint8Array {
+        js_sys::Uint8Array::from(&psbt_envelope::key_id(&key.get_public_key())[..])
+    }
+}
@@ -70,6 +70,37 @@ impl WasmReplayProtection {
         })
     }
 
+    /// Create from addresses of any supported network
+    ///
+    /// Each address is decoded with the network it is valid on, so addresses of different
+    /// networks and formats (e.g. legacy and cashaddr) can be mixed.
+    #[wasm_bindgen]
+    // Box<[T]> is required by wasm-bindgen for passing JavaScript arrays
+    #[allow(clippy::boxed_local)]
+    pub fn from_addresses_any_network(
+        addresses: Box<[JsValue]>,
+    ) -> Result<WasmReplayProtection, WasmUtxoError> {
+        let scripts = addresses
+            .iter()
+            .enumerate()
+            .map(|(i, addr)| {
+                let address_str = addr.as_string().ok_or_else(|| {
+                    WasmUtxoError::new(&format!("Address at index {} is not a string", i))
+                })?;
+                crate::address::to_output_script_any_network(&address_str).map_err(|e| {
+                    WasmUtxoError::new(&format!(
+                        "Failed to decode address '{}': {}",
+                        address_str, e
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(WasmReplayProtection {
+            inner: ReplayProtection::new(scripts),
+        })
+    }
+
     /// Create from public keys (derives P2SH-P2PK output scripts)
     #[wasm_bindgen]
     // Box<[T]> is required by wasm-bindgen for passing JavaScript arrays
@@ -101,6 +132,16 @@ impl WasmReplayProtection {
             inner: ReplayProtection::from_public_keys(compressed_keys),
         })
     }
+
+    /// Get the permitted output scripts, as an array of Uint8Array
+    #[wasm_bindgen]
+    pub fn output_scripts(&self) -> js_sys::Array {
+        self.inner
+            .permitted_output_scripts
+            .iter()
+            .map(|script| js_sys::Uint8Array::from(script.as_bytes()))
+            .collect()
+    }
 }
 
 // Non-WASM methods for internal use
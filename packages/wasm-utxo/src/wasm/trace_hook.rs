@@ -0,0 +1,280 @@
+//! WASM hook for the `tracing` spans emitted by the core library
+//!
+//! These bindings are always available but will throw runtime errors
+//! if the `tracing` feature is not enabled.
+
+use wasm_bindgen::prelude::*;
+
+#[cfg(not(feature = "tracing"))]
+const FEATURE_NOT_ENABLED_ERROR: &str =
+    "tracing feature is not enabled. Rebuild with --features tracing";
+
+/// Install a callback that receives trace records.
+///
+/// Instrumented operations (deserialize, parse, sign, finalize, extract) are reported when
+/// they complete, as `{ kind: "span", name, target, level, fields, durationMs }`. Events
+/// are reported as `{ kind: "event", name, target, level, fields, span }`, where `span` is
+/// the name of the enclosing operation.
+///
+/// Exceptions thrown by the callback are ignored. Pass `undefined` to remove the hook.
+///
+/// # Errors
+/// Returns an error if the `tracing` feature is not enabled
+#[wasm_bindgen(js_name = setTraceHook)]
+pub fn set_trace_hook(callback: Option<js_sys::Function>) -> Result<(), JsError> {
+    #[cfg(feature = "tracing")]
+    {
+        hook::set(callback);
+        Ok(())
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = callback;
+        Err(JsError::new(FEATURE_NOT_ENABLED_ERROR))
+    }
+}
+
+/// Check if the tracing feature is enabled.
+///
+/// # Returns
+/// `true` if the feature is enabled, `false` otherwise
+#[wasm_bindgen(js_name = isTracingEnabled)]
+pub fn is_tracing_enabled() -> bool {
+    cfg!(feature = "tracing")
+}
+
+#[cfg(feature = "tracing")]
+mod hook {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Mutex, Once};
+
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::subscriber::Interest;
+    use tracing::{Event, Metadata, Subscriber};
+    use wasm_bindgen::JsValue;
+
+    thread_local! {
+        static HOOK: RefCell<Option<js_sys::Function>> = const { RefCell::new(None) };
+    }
+
+    static INSTALL: Once = Once::new();
+
+    pub(super) fn set(callback: Option<js_sys::Function>) {
+        INSTALL.call_once(|| {
+            // Fails only if the embedding application installed its own subscriber
+            let _ = tracing::subscriber::set_global_default(HookSubscriber::default());
+        });
+        HOOK.with(|hook| *hook.borrow_mut() = callback);
+    }
+
+    fn is_set() -> bool {
+        HOOK.with(|hook| hook.borrow().is_some())
+    }
+
+    fn emit(record: &JsValue) {
+        // Clone the callback so that it may replace the hook while it runs
+        if let Some(callback) = HOOK.with(|hook| hook.borrow().clone()) {
+            let _ = callback.call1(&JsValue::NULL, record);
+        }
+    }
+
+    enum FieldValue {
+        Number(f64),
+        Bool(bool),
+        String(String),
+    }
+
+    impl From<&FieldValue> for JsValue {
+        fn from(value: &FieldValue) -> Self {
+            match value {
+                FieldValue::Number(n) => JsValue::from_f64(*n),
+                FieldValue::Bool(b) => JsValue::from_bool(*b),
+                FieldValue::String(s) => JsValue::from_str(s),
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct Fields(Vec<(&'static str, FieldValue)>);
+
+    impl Fields {
+        fn set(&mut self, field: &Field, value: FieldValue) {
+            match self.0.iter_mut().find(|(name, _)| *name == field.name()) {
+                Some(entry) => entry.1 = value,
+                None => self.0.push((field.name(), value)),
+            }
+        }
+
+        fn to_js(&self) -> JsValue {
+            let obj = js_sys::Object::new();
+            for (name, value) in &self.0 {
+                let _ = js_sys::Reflect::set(&obj, &JsValue::from_str(name), &value.into());
+            }
+            obj.into()
+        }
+    }
+
+    impl Visit for Fields {
+        fn record_f64(&mut self, field: &Field, value: f64) {
+            self.set(field, FieldValue::Number(value));
+        }
+
+        fn record_i64(&mut self, field: &Field, value: i64) {
+            self.set(field, FieldValue::Number(value as f64));
+        }
+
+        fn record_u64(&mut self, field: &Field, value: u64) {
+            self.set(field, FieldValue::Number(value as f64));
+        }
+
+        fn record_bool(&mut self, field: &Field, value: bool) {
+            self.set(field, FieldValue::Bool(value));
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.set(field, FieldValue::String(value.to_string()));
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.set(field, FieldValue::String(format!("{:?}", value)));
+        }
+    }
+
+    struct SpanState {
+        metadata: &'static Metadata<'static>,
+        fields: Fields,
+        start_ms: f64,
+        ref_count: usize,
+    }
+
+    /// Subscriber that forwards closed spans and events to the JS hook
+    #[derive(Default)]
+    struct HookSubscriber {
+        next_id: AtomicU64,
+        spans: Mutex<HashMap<u64, SpanState>>,
+        /// Entered spans, innermost last
+        stack: Mutex<Vec<u64>>,
+    }
+
+    fn to_record(
+        kind: &str,
+        metadata: &Metadata<'_>,
+        fields: &Fields,
+        extra: (&str, JsValue),
+    ) -> JsValue {
+        let obj = js_sys::Object::new();
+        let entries = [
+            ("kind", JsValue::from_str(kind)),
+            ("name", JsValue::from_str(metadata.name())),
+            ("target", JsValue::from_str(metadata.target())),
+            ("level", JsValue::from_str(metadata.level().as_str())),
+            ("fields", fields.to_js()),
+            extra,
+        ];
+        for (key, value) in entries {
+            let _ = js_sys::Reflect::set(&obj, &JsValue::from_str(key), &value);
+        }
+        obj.into()
+    }
+
+    impl Subscriber for HookSubscriber {
+        fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+            // The hook can be installed and removed at any time
+            Interest::sometimes()
+        }
+
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            is_set()
+        }
+
+        fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+            let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+            let mut fields = Fields::default();
+            attrs.record(&mut fields);
+            self.spans.lock().unwrap().insert(
+                id,
+                SpanState {
+                    metadata: attrs.metadata(),
+                    fields,
+                    start_ms: js_sys::Date::now(),
+                    ref_count: 1,
+                },
+            );
+            Id::from_u64(id)
+        }
+
+        fn record(&self, span: &Id, values: &Record<'_>) {
+            if let Some(state) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+                values.record(&mut state.fields);
+            }
+        }
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields::default();
+            event.record(&mut fields);
+            let current = self.stack.lock().unwrap().last().copied();
+            let span = current
+                .and_then(|id| {
+                    let spans = self.spans.lock().unwrap();
+                    spans.get(&id).map(|state| state.metadata.name())
+                })
+                .map_or(JsValue::NULL, JsValue::from_str);
+            emit(&to_record(
+                "event",
+                event.metadata(),
+                &fields,
+                ("span", span),
+            ));
+        }
+
+        fn enter(&self, span: &Id) {
+            self.stack.lock().unwrap().push(span.into_u64());
+        }
+
+        fn exit(&self, span: &Id) {
+            let mut stack = self.stack.lock().unwrap();
+            if let Some(pos) = stack.iter().rposition(|id| *id == span.into_u64()) {
+                stack.remove(pos);
+            }
+        }
+
+        fn clone_span(&self, span: &Id) -> Id {
+            if let Some(state) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+                state.ref_count += 1;
+            }
+            span.clone()
+        }
+
+        fn try_close(&self, span: &Id) -> bool {
+            let closed = {
+                let mut spans = self.spans.lock().unwrap();
+                match spans.get_mut(&span.into_u64()) {
+                    Some(state) if state.ref_count > 1 => {
+                        state.ref_count -= 1;
+                        None
+                    }
+                    Some(_) => spans.remove(&span.into_u64()),
+                    None => None,
+                }
+            };
+            // The lock is released before calling into JS, which may start new spans
+            let Some(state) = closed else {
+                return false;
+            };
+            let duration_ms = js_sys::Date::now() - state.start_ms;
+            emit(&to_record(
+                "span",
+                state.metadata,
+                &state.fields,
+                ("durationMs", JsValue::from_f64(duration_ms)),
+            ));
+            true
+        }
+    }
+}
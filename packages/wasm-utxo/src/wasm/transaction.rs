@@ -371,4 +371,11 @@ impl WasmZcashTransaction {
             .ok_or_else(|| WasmUtxoError::new(&format!("Unknown coin: {}", coin)))?;
         tx_outputs_with_address_from(&self.parts.transaction, network)?.try_to_js_value()
     }
+
+    /// Whether the transaction has Sapling spends or outputs or Sprout JoinSplits
+    ///
+    /// Such transactions cannot be converted to a PSBT.
+    pub fn has_shielded_components(&self) -> bool {
+        self.parts.has_shielded_components()
+    }
 }
@@ -95,6 +95,46 @@ impl TryFromJsValue for u32 {
     }
 }
 
+/// Error converting a JS value to a satoshi amount
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum AmountError {
+    /// The value is not a bigint (e.g. a number or a decimal string)
+    NotBigInt { js_type: String },
+    /// The bigint is negative or larger than `u64::MAX`
+    OutOfRange,
+}
+
+impl std::fmt::Display for AmountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AmountError::NotBigInt { js_type } => {
+                write!(f, "Expected a bigint amount, got {}", js_type)
+            }
+            AmountError::OutOfRange => write!(f, "Amount bigint is out of range for u64"),
+        }
+    }
+}
+
+impl std::error::Error for AmountError {}
+
+crate::impl_wasm_error_code!(AmountError);
+crate::impl_from_coded_error!(AmountError);
+
+/// Satoshi amounts must be bigints.
+///
+/// Numbers cannot represent values above 2^53 exactly (common for Dogecoin), and strings are
+/// converted by the caller, outside the wasm boundary.
+impl TryFromJsValue for u64 {
+    fn try_from_js_value(value: &JsValue) -> Result<Self, WasmUtxoError> {
+        let bigint = value
+            .dyn_ref::<js_sys::BigInt>()
+            .ok_or_else(|| AmountError::NotBigInt {
+                js_type: value.js_typeof().as_string().unwrap_or_default(),
+            })?;
+        Ok(u64::try_from(bigint.clone()).map_err(|_| AmountError::OutOfRange)?)
+    }
+}
+
 impl TryFromJsValue for Vec<u8> {
     fn try_from_js_value(value: &JsValue) -> Result<Self, WasmUtxoError> {
         let buffer = js_sys::Uint8Array::new(value);
@@ -135,8 +175,7 @@ fn get_nested_raw(obj: &JsValue, path: &str) -> Result<JsValue, WasmUtxoError> {
 /// Get a field and convert it using TryFromJsValue
 pub(crate) fn get_field<T: TryFromJsValue>(obj: &JsValue, key: &str) -> Result<T, WasmUtxoError> {
     let field_value = get_raw_field(obj, key)?;
-    T::try_from_js_value(&field_value)
-        .map_err(|e| WasmUtxoError::new(&format!("{} (field: {})", e, key)))
+    T::try_from_js_value(&field_value).map_err(|e| e.with_context(&format!("field: {}", key)))
 }
 
 /// Get a nested field using dot notation (e.g., "network.bip32.public")
@@ -145,8 +184,7 @@ pub(crate) fn get_nested_field<T: TryFromJsValue>(
     path: &str,
 ) -> Result<T, WasmUtxoError> {
     let field_value = get_nested_raw(obj, path)?;
-    T::try_from_js_value(&field_value)
-        .map_err(|e| WasmUtxoError::new(&format!("{} (path: {})", e, path)))
+    T::try_from_js_value(&field_value).map_err(|e| e.with_context(&format!("path: {}", path)))
 }
 
 // =============================================================================
@@ -236,6 +274,69 @@ impl TryFromJsValue for crate::networks::Network {
     }
 }
 
+impl TryFromJsValue for crate::fixed_script_wallet::bitgo_psbt::KeyOrigin {
+    fn try_from_js_value(value: &JsValue) -> Result<Self, WasmUtxoError> {
+        use miniscript::bitcoin::bip32::{DerivationPath, Fingerprint};
+        use std::str::FromStr;
+
+        let fingerprint: String = get_field(value, "masterFingerprint")?;
+        let path: String = get_field(value, "path")?;
+        Ok(crate::fixed_script_wallet::bitgo_psbt::KeyOrigin {
+            master_fingerprint: Fingerprint::from_str(&fingerprint).map_err(|e| {
+                WasmUtxoError::new(&format!(
+                    "Invalid master fingerprint '{}': {}",
+                    fingerprint, e
+                ))
+            })?,
+            account_path: DerivationPath::from_str(&path).map_err(|e| {
+                WasmUtxoError::new(&format!("Invalid account path '{}': {}", path, e))
+            })?,
+        })
+    }
+}
+
+/// Sequence modes: a raw sequence number, `"finalNoRbf"`, `"rbfEnabled"`,
+/// `{ relativeLockBlocks: n }` or `{ relativeLockTime: n }`
+impl TryFromJsValue for crate::fixed_script_wallet::bitgo_psbt::SequenceMode {
+    fn try_from_js_value(value: &JsValue) -> Result<Self, WasmUtxoError> {
+        use crate::fixed_script_wallet::bitgo_psbt::SequenceMode;
+        use std::str::FromStr;
+
+        if let Some(n) = value.as_f64() {
+            if n < 0.0 || n > u32::MAX as f64 || n.fract() != 0.0 {
+                return Err(WasmUtxoError::new(&format!(
+                    "Invalid sequence number: {}",
+                    n
+                )));
+            }
+            return Ok(SequenceMode::from_consensus(n as u32));
+        }
+        if let Some(s) = value.as_string() {
+            return SequenceMode::from_str(&s).map_err(|e| WasmUtxoError::new(&e));
+        }
+        let relative_lock = |key: &str| -> Result<Option<u16>, WasmUtxoError> {
+            get_field::<Option<u32>>(value, key)?
+                .map(|n| {
+                    u16::try_from(n)
+                        .map_err(|_| WasmUtxoError::new(&format!("{} out of range: {}", key, n)))
+                })
+                .transpose()
+        };
+        if value.is_object() {
+            if let Some(n) = relative_lock("relativeLockBlocks")? {
+                return Ok(SequenceMode::RelativeLockBlocks(n));
+            }
+            if let Some(n) = relative_lock("relativeLockTime")? {
+                return Ok(SequenceMode::RelativeLockTime(n));
+            }
+        }
+        Err(WasmUtxoError::new(
+            "Expected a sequence number, 'finalNoRbf', 'rbfEnabled', \
+             { relativeLockBlocks } or { relativeLockTime }",
+        ))
+    }
+}
+
 // =============================================================================
 // HydrationUnspentInput: Wallet or replay protection input
 // =============================================================================
@@ -244,11 +345,11 @@ impl TryFromJsValue for crate::fixed_script_wallet::bitgo_psbt::HydrationUnspent
     fn try_from_js_value(item: &JsValue) -> Result<Self, WasmUtxoError> {
         use crate::fixed_script_wallet::ScriptIdWithValue;
 
-        // Read 'value' as BigInt (required)
+        // Read 'value' as an amount (required)
         let value_js = js_sys::Reflect::get(item, &"value".into())
             .map_err(|_| WasmUtxoError::new("Missing 'value' field on unspent"))?;
-        let value = u64::try_from(js_sys::BigInt::unchecked_from_js(value_js))
-            .map_err(|_| WasmUtxoError::new("'value' must be a bigint convertible to u64"))?;
+        let value = u64::try_from_js_value(&value_js)
+            .map_err(|e| WasmUtxoError::new(&format!("Invalid 'value' on unspent: {}", e)))?;
 
         // Check if 'chain' is present; if missing → ReplayProtection, else → Wallet
         let chain_val = js_sys::Reflect::get(item, &"chain".into()).unwrap_or(JsValue::UNDEFINED);
@@ -291,3 +392,188 @@ impl TryFromJsValue for crate::fixed_script_wallet::bitgo_psbt::HydrationUnspent
         }
     }
 }
+
+// =============================================================================
+// ExtractInvariants: snapshot returned by `extract_invariants`
+// =============================================================================
+
+impl TryFromJsValue for crate::fixed_script_wallet::bitgo_psbt::ExtractInvariants {
+    fn try_from_js_value(value: &JsValue) -> Result<Self, WasmUtxoError> {
+        use miniscript::bitcoin::{OutPoint, Txid};
+        use std::str::FromStr;
+
+        let inputs_js = get_raw_field(value, "inputs")?;
+        if !js_sys::Array::is_array(&inputs_js) {
+            return Err(WasmUtxoError::new("'inputs' must be an array"));
+        }
+        let inputs = js_sys::Array::from(&inputs_js)
+            .iter()
+            .map(|input| {
+                let txid: String = get_field(&input, "txid")?;
+                let vout: u32 = get_field(&input, "vout")?;
+                let txid = Txid::from_str(&txid)
+                    .map_err(|e| WasmUtxoError::new(&format!("Invalid txid: {}", e)))?;
+                Ok(OutPoint { txid, vout })
+            })
+            .collect::<Result<Vec<_>, WasmUtxoError>>()?;
+        let outputs_hash: String = get_field(value, "outputsHash")?;
+        let outputs_hash = miniscript::bitcoin::hashes::sha256::Hash::from_str(&outputs_hash)
+            .map_err(|e| WasmUtxoError::new(&format!("Invalid outputsHash: {}", e)))?;
+        Ok(Self {
+            inputs,
+            outputs_hash,
+            fee: get_field(value, "fee")?,
+        })
+    }
+}
+
+// =============================================================================
+// CoreUnspent: Bitcoin Core `listunspent` entry
+// =============================================================================
+
+impl TryFromJsValue for crate::fixed_script_wallet::core_unspent::CoreUnspent {
+    fn try_from_js_value(value: &JsValue) -> Result<Self, WasmUtxoError> {
+        use crate::fixed_script_wallet::core_unspent::CoreUnspent;
+        use miniscript::bitcoin::{ScriptBuf, Txid};
+        use std::str::FromStr;
+
+        let txid: String = get_field(value, "txid")?;
+        let script_pubkey: String = get_field(value, "scriptPubKey")?;
+        Ok(CoreUnspent {
+            txid: Txid::from_str(&txid)
+                .map_err(|e| WasmUtxoError::new(&format!("Invalid txid '{}': {}", txid, e)))?,
+            vout: get_field(value, "vout")?,
+            script_pubkey: ScriptBuf::from_hex(&script_pubkey).map_err(|e| {
+                WasmUtxoError::new(&format!("Invalid scriptPubKey '{}': {}", script_pubkey, e))
+            })?,
+            value: get_field(value, "value")?,
+            descriptor: get_field(value, "desc")?,
+        })
+    }
+}
+
+/// The `{ input: { txid, vout, value }, scriptId }` objects returned by `import_core_unspents`
+impl TryFromJsValue for crate::fixed_script_wallet::core_unspent::WalletUnspent {
+    fn try_from_js_value(value: &JsValue) -> Result<Self, WasmUtxoError> {
+        use crate::fixed_script_wallet::core_unspent::WalletUnspent;
+        use crate::fixed_script_wallet::{Chain, ScriptId};
+        use miniscript::bitcoin::Txid;
+        use std::str::FromStr;
+
+        let txid: String = get_nested_field(value, "input.txid")?;
+        let chain: u32 = get_nested_field(value, "scriptId.chain")?;
+        Ok(WalletUnspent {
+            txid: Txid::from_str(&txid)
+                .map_err(|e| WasmUtxoError::new(&format!("Invalid txid '{}': {}", txid, e)))?,
+            vout: get_nested_field(value, "input.vout")?,
+            value: get_nested_field(value, "input.value")?,
+            script_id: ScriptId {
+                chain,
+                index: get_nested_field(value, "scriptId.index")?,
+            },
+            script_type: Chain::try_from(chain)
+                .map_err(|e| WasmUtxoError::new(&e))?
+                .script_type,
+        })
+    }
+}
+
+// =============================================================================
+// WitnessTemplate: Caller-provided scriptSig and witness stack items
+// =============================================================================
+
+/// Template items: `{ type: "signature", pubkey }`, `{ type: "data", data }`,
+/// `{ type: "redeemScript" }` or `{ type: "witnessScript" }`
+impl TryFromJsValue for crate::fixed_script_wallet::bitgo_psbt::TemplateItem {
+    fn try_from_js_value(value: &JsValue) -> Result<Self, WasmUtxoError> {
+        use crate::fixed_script_wallet::bitgo_psbt::TemplateItem;
+
+        let typ: String = get_field(value, "type")?;
+        match typ.as_str() {
+            "signature" => {
+                let pubkey: Vec<u8> = get_field(value, "pubkey")?;
+                miniscript::bitcoin::PublicKey::from_slice(&pubkey)
+                    .map(TemplateItem::Signature)
+                    .map_err(|e| WasmUtxoError::new(&format!("Invalid pubkey: {}", e)))
+            }
+            "data" => Ok(TemplateItem::Data(get_field(value, "data")?)),
+            "redeemScript" => Ok(TemplateItem::RedeemScript),
+            "witnessScript" => Ok(TemplateItem::WitnessScript),
+            _ => Err(WasmUtxoError::new(&format!(
+                "Unknown template item type: {typ}"
+            ))),
+        }
+    }
+}
+
+impl TryFromJsValue for crate::fixed_script_wallet::bitgo_psbt::WitnessTemplate {
+    fn try_from_js_value(value: &JsValue) -> Result<Self, WasmUtxoError> {
+        use crate::fixed_script_wallet::bitgo_psbt::TemplateItem;
+
+        let items = |key: &str| -> Result<Vec<TemplateItem>, WasmUtxoError> {
+            let items = get_raw_field(value, key)?;
+            if items.is_undefined() || items.is_null() {
+                return Ok(vec![]);
+            }
+            if !js_sys::Array::is_array(&items) {
+                return Err(WasmUtxoError::new(&format!("'{}' must be an array", key)));
+            }
+            js_sys::Array::from(&items)
+                .iter()
+                .map(|item| TemplateItem::try_from_js_value(&item))
+                .collect()
+        };
+        Ok(crate::fixed_script_wallet::bitgo_psbt::WitnessTemplate {
+            script_sig: items("scriptSig")?,
+            witness: items("witness")?,
+        })
+    }
+}
+
+// =============================================================================
+// ExtendedNetworkUpgrades: Zcash network upgrades that are not built in
+// =============================================================================
+
+impl TryFromJsValue for crate::zcash::UpgradeActivation {
+    fn try_from_js_value(value: &JsValue) -> Result<Self, WasmUtxoError> {
+        Ok(Self {
+            branch_id: get_field(value, "branchId")?,
+            activation_height: get_field(value, "activationHeight")?,
+        })
+    }
+}
+
+impl TryFromJsValue for crate::zcash::ExtendedNetworkUpgrades {
+    fn try_from_js_value(value: &JsValue) -> Result<Self, WasmUtxoError> {
+        use crate::zcash::UpgradeActivation;
+
+        let upgrades = |key: &str| -> Result<Vec<UpgradeActivation>, WasmUtxoError> {
+            let items = get_raw_field(value, key)?;
+            if items.is_undefined() || items.is_null() {
+                return Ok(Vec::new());
+            }
+            if !js_sys::Array::is_array(&items) {
+                return Err(WasmUtxoError::new(&format!("'{}' must be an array", key)));
+            }
+            js_sys::Array::from(&items)
+                .iter()
+                .map(|item| UpgradeActivation::try_from_js_value(&item))
+                .collect()
+        };
+        Self::new(upgrades("mainnet")?, upgrades("testnet")?)
+            .map_err(|e| WasmUtxoError::new(&e.to_string()))
+    }
+}
+
+// =============================================================================
+// Confirmation: block that includes an input, for relative timelocks
+// =============================================================================
+
+impl TryFromJsValue for crate::timelock_preview::Confirmation {
+    fn try_from_js_value(value: &JsValue) -> Result<Self, WasmUtxoError> {
+        Ok(Self {
+            height: get_field(value, "height")?,
+            median_time_past: get_field(value, "medianTimePast")?,
+        })
+    }
+}
@@ -36,11 +36,60 @@ macro_rules! js_arr {
     }};
 }
 
+/// Fields of errors that callers handle programmatically, set as `details` on the JS error
+fn error_details(err: &WasmUtxoError) -> Result<Option<JsValue>, WasmUtxoError> {
+    use crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::ParseInputError;
+    use crate::fixed_script_wallet::bitgo_psbt::{BitGoPsbtError, ParseTransactionError};
+
+    let details = match err {
+        WasmUtxoError::Parse(ParseTransactionError::Input {
+            index,
+            error:
+                ParseInputError::DerivationMismatch {
+                    expected_script,
+                    actual_script,
+                    path,
+                },
+        }) => js_obj!(
+            "inputIndex" => *index,
+            "expectedScript" => expected_script.to_bytes(),
+            "actualScript" => actual_script.to_bytes(),
+            "derivationPath" => path.clone()
+        )?,
+        WasmUtxoError::BitGoPsbt(e) => match e {
+            BitGoPsbtError::InputIndexOutOfBounds { index, input_count } => js_obj!(
+                "inputIndex" => *index,
+                "inputCount" => *input_count
+            )?,
+            BitGoPsbtError::ValueExceedsMaxMoney {
+                value, max_money, ..
+            } => js_obj!("value" => *value, "maxMoney" => *max_money)?,
+            BitGoPsbtError::InvalidChain(chain) => js_obj!("chain" => *chain)?,
+            e => match e.input_index() {
+                Some(index) => js_obj!("inputIndex" => index)?,
+                None => return Ok(None),
+            },
+        },
+        _ => return Ok(None),
+    };
+    Ok(Some(details))
+}
+
 impl From<WasmUtxoError> for JsValue {
     fn from(err: WasmUtxoError) -> Self {
         let code = err.code();
         let js_err = js_sys::Error::new(&err.to_string());
+        // The error type of the code, e.g. `BitGoPsbtError` for `BitGoPsbtError.NotSigned`.
+        // Plain string errors keep the name `Error`.
+        if !matches!(err, WasmUtxoError::StringError(_)) {
+            if let Some((name, _)) = code.split_once('.') {
+                js_err.set_name(name);
+            }
+        }
         let _ = js_sys::Reflect::set(&js_err, &"code".into(), &code.into());
+        if let Ok(Some(details)) = error_details(&err) {
+            let _ = js_sys::Reflect::set(&js_err, &"details".into(), &details);
+        }
         let marker = js_sys::Symbol::for_("@bitgo/wasm-utxo/error");
         let _ = js_sys::Reflect::set(&js_err, &marker.into(), &JsValue::TRUE);
         js_err.into()
@@ -352,6 +401,15 @@ impl TryIntoJsValue for crate::fixed_script_wallet::bitgo_psbt::ScriptId {
     }
 }
 
+impl TryIntoJsValue for crate::fixed_script_wallet::bitgo_psbt::psbt_wallet_input::SignPath {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "signer" => self.signer.as_str().to_string(),
+            "cosigner" => self.cosigner.as_str().to_string()
+        )
+    }
+}
+
 impl TryIntoJsValue for crate::fixed_script_wallet::bitgo_psbt::InputScriptType {
     fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
         use crate::fixed_script_wallet::bitgo_psbt::InputScriptType;
@@ -364,21 +422,45 @@ impl TryIntoJsValue for crate::fixed_script_wallet::bitgo_psbt::InputScriptType
             InputScriptType::P2trMusig2ScriptPath => "p2trMusig2ScriptPath",
             InputScriptType::P2trMusig2KeyPath => "p2trMusig2KeyPath",
             InputScriptType::P2mr => "p2mr",
+            InputScriptType::P2pkh => "p2pkh",
+            InputScriptType::P2wpkh => "p2wpkh",
+            InputScriptType::P2trKeyPath => "p2trKeyPath",
+            InputScriptType::External(_) => "external",
         };
         Ok(JsValue::from_str(script_type))
     }
 }
 
+impl TryIntoJsValue for crate::fixed_script_wallet::derivation_cache::DerivationCacheStats {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "hits" => self.hits,
+            "misses" => self.misses,
+            "evictions" => self.evictions,
+            "size" => self.size,
+            "capacity" => self.capacity,
+            "enabled" => self.enabled
+        )
+    }
+}
+
 impl TryIntoJsValue for crate::fixed_script_wallet::bitgo_psbt::ParsedInput {
     fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        use crate::fixed_script_wallet::bitgo_psbt::InputScriptType;
+        let external_script_type = match self.script_type {
+            InputScriptType::External(t) => Some(t.as_str().to_string()),
+            _ => None,
+        };
         js_obj!(
             "previousOutput" => js_obj!("txid" => self.previous_output.txid.to_string(), "vout" => self.previous_output.vout)?,
             "address" => self.address.clone(),
             "value" => self.value,
             "scriptId" => self.script_id,
             "scriptType" => self.script_type,
+            "externalScriptType" => external_script_type,
             "sequence" => self.sequence,
-            "derivationPath" => self.derivation_path.clone()
+            "derivationPath" => self.derivation_path.clone(),
+            "label" => self.label.clone()
         )
     }
 }
@@ -391,7 +473,25 @@ impl TryIntoJsValue for crate::fixed_script_wallet::bitgo_psbt::ParsedOutput {
             "value" => self.value,
             "scriptId" => self.script_id,
             "paygo" => self.paygo,
-            "derivationPath" => self.derivation_path.clone()
+            "derivationPath" => self.derivation_path.clone(),
+            "externalScriptType" => self.external_script_type.map(|t| t.as_str().to_string()),
+            "label" => self.label.clone()
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::fixed_script_wallet::bitgo_psbt::LabeledParsedOutput {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "wallet" => self.wallet.clone(),
+            "address" => self.output.address.clone(),
+            "script" => self.output.script.clone(),
+            "value" => self.output.value,
+            "scriptId" => self.output.script_id,
+            "paygo" => self.output.paygo,
+            "derivationPath" => self.output.derivation_path.clone(),
+            "externalScriptType" => self.output.external_script_type.map(|t| t.as_str().to_string()),
+            "label" => self.output.label.clone()
         )
     }
 }
@@ -403,7 +503,428 @@ impl TryIntoJsValue for crate::fixed_script_wallet::bitgo_psbt::ParsedTransactio
             "outputs" => self.outputs.clone(),
             "spendAmount" => self.spend_amount,
             "minerFee" => self.miner_fee,
-            "virtualSize" => self.virtual_size
+            "virtualSize" => self.virtual_size,
+            "zip317Fee" => self.zip317_fee,
+            "vaultPolicy" => self.vault_policy
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::zcash::zip317::Zip317Fee {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "logicalActions" => self.logical_actions,
+            "conventionalFee" => self.conventional_fee,
+            "meetsMinimum" => self.meets_minimum
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::fixed_script_wallet::bitgo_psbt::VaultPolicy {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "spendLimit" => self.spend_limit,
+            "destinationWhitelistHash" => self.destination_whitelist_hash.map(|hash| hash.to_vec()),
+            "approvalQuorumId" => self.approval_quorum_id.clone()
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::fixed_script_wallet::core_unspent::WalletUnspent {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "input" => js_obj!(
+                "txid" => self.txid.to_string(),
+                "vout" => self.vout,
+                "value" => self.value
+            )?,
+            "scriptId" => self.script_id,
+            "scriptType" => self.script_type.as_str().to_string()
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::wasm::miniscript::CompiledPolicy {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "descriptor" => self.descriptor.clone(),
+            "miniscript" => self.miniscript.clone(),
+            "scriptSize" => self.script_size,
+            "maxWeightToSatisfy" => self.max_weight_to_satisfy
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::payjoin::PayjoinProposalCheck {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "receiverInputs" => self.receiver_inputs.clone(),
+            "feeContribution" => self.fee_contribution
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::fixed_script_wallet::bitgo_psbt::PackageFee {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "fees" => self.fees.clone(),
+            "totalFee" => self.total_fee,
+            "totalVsize" => self.total_vsize as usize,
+            "feeRate" => JsValue::from_f64(self.fee_rate)
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::fixed_script_wallet::FundingOutput {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        let pubkeys: Vec<Vec<u8>> = self
+            .key_pair()
+            .iter()
+            .map(|key| key.to_bytes().to_vec())
+            .collect();
+        js_obj!(
+            "type" => self.output_type().as_str().to_string(),
+            "pubkeys" => pubkeys
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::fixed_script_wallet::SweepTransaction {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "inputCount" => self.input_count,
+            "weight" => self.weight as usize,
+            "vsize" => self.vsize as usize,
+            "inputValue" => self.input_value,
+            "fee" => self.fee,
+            "outputValue" => self.output_value()
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::fixed_script_wallet::SweepPlan {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        let psbts = self
+            .psbts
+            .iter()
+            .map(|psbt| {
+                psbt.serialize()
+                    .map_err(|e| WasmUtxoError::new(&format!("Failed to serialize PSBT: {}", e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        js_obj!(
+            "psbts" => psbts,
+            "transactions" => self.transactions.clone(),
+            "inputValue" => self.input_value(),
+            "fee" => self.fee(),
+            "outputValue" => self.output_value()
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::psbt_envelope::EnvelopeHeader {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "sender" => self.sender.to_vec(),
+            "recipient" => self.recipient.to_vec(),
+            "nonce" => self.nonce.to_vec()
+        )
+    }
+}
+
+#[cfg(feature = "inspect")]
+impl TryIntoJsValue for crate::fixed_script_wallet::bitgo_psbt::PsbtDiffEntry {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        use crate::fixed_script_wallet::bitgo_psbt::PsbtDiffMap;
+        let (map, index) = match self.map {
+            PsbtDiffMap::Global => ("global", None),
+            PsbtDiffMap::Input(i) => ("input", Some(i)),
+            PsbtDiffMap::Output(i) => ("output", Some(i)),
+        };
+        let change: &'static str = self.change.into();
+        js_obj!(
+            "map" => map.to_string(),
+            "index" => index,
+            "change" => change.to_string(),
+            "type" => self.key_type as u32,
+            "name" => self.name.clone(),
+            "key" => self.key.clone(),
+            "oldValue" => self.old_value.clone(),
+            "newValue" => self.new_value.clone()
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::fixed_script_wallet::bitgo_psbt::ForkReplayReport {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        let outpoint = |o: &miniscript::bitcoin::OutPoint| js_obj!("txid" => o.txid.to_string(), "vout" => o.vout);
+        let shared_inputs = Array::new();
+        for o in &self.shared_inputs {
+            shared_inputs.push(&outpoint(o)?);
+        }
+        let replay_protection_inputs = Array::new();
+        for input in &self.replay_protection_inputs {
+            replay_protection_inputs.push(&js_obj!(
+                "inputIndex" => input.input_index,
+                "previousOutput" => outpoint(&input.previous_output)?,
+                "signed" => input.signed
+            )?);
+        }
+        js_obj!(
+            "sharedInputs" => JsValue::from(shared_inputs),
+            "sharedOutputs" => self.shared_outputs.clone(),
+            "sharedSighash" => self.shared_sighash,
+            "replayProtectionInputs" => JsValue::from(replay_protection_inputs),
+            "replayProtected" => self.is_replay_protected(),
+            "replayable" => self.is_replayable()
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::fixed_script_wallet::bitgo_psbt::MissingNonWitnessUtxo {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "inputIndex" => self.input_index,
+            "txid" => self.txid.to_string()
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::fixed_script_wallet::bitgo_psbt::StaleMusig2Nonce {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "inputIndex" => self.input_index,
+            "participantPubKey" => self.nonce.participant_pub_key.to_string(),
+            "tapOutputKey" => self.nonce.tap_output_key,
+            "reason" => <&'static str>::from(self.reason).to_string()
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::fixed_script_wallet::bitgo_psbt::InputRef {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "psbtIndex" => self.psbt_index,
+            "inputIndex" => self.input_index
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::fixed_script_wallet::bitgo_psbt::SpendConflict {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "txid" => self.outpoint.txid.to_string(),
+            "vout" => self.outpoint.vout,
+            "kind" => self.kind.as_str().to_string(),
+            "inputs" => self.inputs
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::fixed_script_wallet::bitgo_psbt::RiskReport {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        let non_default_sighash = Array::new();
+        for s in &self.non_default_sighash {
+            non_default_sighash.push(&js_obj!(
+                "inputIndex" => s.input_index,
+                "sighashType" => s.sighash_type
+            )?);
+        }
+        let op_return_outputs = Array::new();
+        for o in &self.op_return_outputs {
+            op_return_outputs.push(&js_obj!(
+                "outputIndex" => o.output_index,
+                "data" => o.data.clone(),
+                "value" => o.value
+            )?);
+        }
+        let dust_outputs = Array::new();
+        for o in &self.dust_outputs {
+            dust_outputs.push(&js_obj!(
+                "outputIndex" => o.output_index,
+                "value" => o.value,
+                "dustThreshold" => o.dust_threshold
+            )?);
+        }
+        let reused_addresses = Array::new();
+        for a in &self.reused_addresses {
+            reused_addresses.push(&js_obj!(
+                "address" => a.address.clone(),
+                "inputIndices" => a.input_indices.clone(),
+                "outputIndices" => a.output_indices.clone()
+            )?);
+        }
+        js_obj!(
+            "nonDefaultSighash" => JsValue::from(non_default_sighash),
+            "externalInputs" => self.external_inputs.clone(),
+            "opReturnOutputs" => JsValue::from(op_return_outputs),
+            "dustOutputs" => JsValue::from(dust_outputs),
+            "minerFee" => self.miner_fee,
+            "virtualSize" => self.virtual_size,
+            "feeAboveThreshold" => self.fee_above_threshold,
+            "reusedAddresses" => JsValue::from(reused_addresses),
+            "hasFlags" => self.has_flags()
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::fixed_script_wallet::bitgo_psbt::InputSighash {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        use crate::fixed_script_wallet::bitgo_psbt::SignerKey;
+        use miniscript::bitcoin::hashes::Hash;
+
+        let leaf_hash = match self.signer {
+            SignerKey::Schnorr {
+                leaf_hash: Some(leaf_hash),
+                ..
+            } => Some(leaf_hash.to_byte_array().to_vec()),
+            _ => None,
+        };
+        js_obj!(
+            "inputIndex" => self.input_index,
+            "pubkey" => self.signer.to_bytes(),
+            "leafHash" => leaf_hash,
+            "sighashType" => self.sighash_type,
+            "algorithm" => self.algorithm.as_str().to_string(),
+            "digest" => self.digest.to_vec()
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::fixed_script_wallet::bitgo_psbt::ExtractInvariants {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        let inputs = Array::new();
+        for o in &self.inputs {
+            inputs.push(&js_obj!("txid" => o.txid.to_string(), "vout" => o.vout)?);
+        }
+        js_obj!(
+            "inputs" => JsValue::from(inputs),
+            "outputsHash" => self.outputs_hash.to_string(),
+            "fee" => self.fee
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::wasm::fixed_script_wallet::VSizeEstimate {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!("min" => self.min, "max" => self.max)
+    }
+}
+
+impl TryIntoJsValue for crate::psbt_ops::Bip69Permutation {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "inputs" => self.inputs.clone(),
+            "outputs" => self.outputs.clone()
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::networks::Bip32Versions {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "public" => self.public,
+            "private" => self.private
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::networks::Network {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "coinName" => self.to_coin_name().to_string(),
+            "utxolibName" => self.to_utxolib_name().to_string(),
+            "mainnet" => self.is_mainnet(),
+            "genesisBlockHash" => self.genesis_block_hash().map(|h| h.to_string()),
+            "bech32Hrp" => self.bech32_hrp().map(str::to_string),
+            "bip32" => self.bip32_versions()
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::relay_fee::RelayFeeRates {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "minRelayFee" => self.min_relay_fee,
+            "incrementalRelayFee" => self.incremental_relay_fee,
+            "dustOutputLimit" => self.dust_output_limit
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::capabilities::NetworkCapabilities {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        let script_types: Vec<String> = self
+            .script_types
+            .iter()
+            .map(|t| t.as_str().to_string())
+            .collect();
+        let address_encodings: Vec<String> = self
+            .address_encodings
+            .iter()
+            .map(|e| e.as_str().to_string())
+            .collect();
+        js_obj!(
+            "segwit" => self.segwit,
+            "taproot" => self.taproot,
+            "p2mr" => self.p2mr,
+            "sighashForkId" => self.sighash_fork_id,
+            "csv" => self.csv,
+            "scriptTypes" => script_types,
+            "addressEncodings" => address_encodings,
+            "relayFeeRates" => self.relay_fee_rates,
+            "maxMoney" => self.max_money,
+            "psbtFormat" => self.psbt_format.as_str().to_string()
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::timelock_preview::Timelock {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        use crate::timelock_preview::Timelock;
+        match *self {
+            Timelock::Height(height) => js_obj!("height" => height),
+            Timelock::Time(time) => js_obj!("time" => time),
+        }
+    }
+}
+
+impl TryIntoJsValue for crate::timelock_preview::UnlockAt {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        js_obj!(
+            "height" => self.height,
+            "time" => self.time
+        )
+    }
+}
+
+impl TryIntoJsValue
+    for (
+        crate::timelock_preview::UnlockCondition,
+        Option<crate::timelock_preview::UnlockAt>,
+    )
+{
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        let (condition, unlock_at) = self;
+        js_obj!(
+            "after" => condition.after,
+            "older" => condition.older,
+            "unlockAt" => *unlock_at
+        )
+    }
+}
+
+impl TryIntoJsValue for crate::wif::WifInfo {
+    fn try_to_js_value(&self) -> Result<JsValue, WasmUtxoError> {
+        let networks: Vec<String> = self
+            .networks
+            .iter()
+            .map(|n| n.to_coin_name().to_string())
+            .collect();
+        js_obj!(
+            "compressed" => self.compressed,
+            "version" => u32::from(self.version),
+            "networks" => networks
         )
     }
 }
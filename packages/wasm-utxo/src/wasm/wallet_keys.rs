@@ -97,6 +97,15 @@ impl WasmRootWalletKeys {
         Ok(WasmRootWalletKeys { inner })
     }
 
+    /// Create a copy of these wallet keys
+    ///
+    /// Useful when passing keys by value (e.g. in arrays), which invalidates the original.
+    #[allow(clippy::should_implement_trait)]
+    #[wasm_bindgen]
+    pub fn clone(&self) -> WasmRootWalletKeys {
+        Clone::clone(self)
+    }
+
     /// Get the user key (first xpub)
     #[wasm_bindgen]
     pub fn user_key(&self) -> WasmBIP32 {
@@ -0,0 +1,229 @@
+//! Wallet Import Format (WIF) encoding with network-specific version bytes
+//!
+//! `bitcoin::PrivateKey` only knows the Bitcoin version bytes (`0x80`/`0xef`). Several
+//! of the supported altcoins use their own version byte, and many networks share one,
+//! so decoding a WIF yields a list of candidate networks rather than a single network.
+
+use crate::bitcoin::base58;
+use crate::bitcoin::secp256k1::SecretKey;
+use crate::networks::Network;
+
+const SECRET_KEY_LEN: usize = 32;
+const COMPRESSED_FLAG: u8 = 0x01;
+
+/// WIF version byte used by `network`
+pub fn wif_version(network: Network) -> u8 {
+    match network {
+        Network::Litecoin => 0xb0,
+        Network::Dogecoin => 0x9e,
+        Network::DogecoinTestnet => 0xf1,
+        Network::Dash => 0xcc,
+        network if network.is_mainnet() => 0x80,
+        _ => 0xef,
+    }
+}
+
+/// Decoded WIF
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WifInfo {
+    pub secret_key: SecretKey,
+    /// Whether the WIF marks the public key as compressed
+    pub compressed: bool,
+    /// The WIF version byte
+    pub version: u8,
+    /// All networks using this version byte
+    pub networks: Vec<Network>,
+}
+
+impl WifInfo {
+    /// Returns true if the WIF is valid for `network`
+    pub fn is_valid_for(&self, network: Network) -> bool {
+        self.networks.contains(&network)
+    }
+}
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum WifError {
+    /// Invalid base58 or checksum
+    Base58(String),
+    /// Payload is neither 33 nor 34 bytes
+    InvalidLength(usize),
+    /// Trailing byte of a 34-byte payload is not `0x01`
+    InvalidCompressionFlag(u8),
+    /// No supported network uses this version byte
+    UnknownVersion(u8),
+    /// Version byte does not match the expected network
+    NetworkMismatch { version: u8, network: Network },
+    /// The key is not a valid secp256k1 secret key
+    InvalidSecretKey(String),
+}
+
+impl std::fmt::Display for WifError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WifError::Base58(e) => write!(f, "Invalid WIF encoding: {}", e),
+            WifError::InvalidLength(len) => write!(f, "Invalid WIF payload length: {}", len),
+            WifError::InvalidCompressionFlag(flag) => {
+                write!(f, "Invalid WIF compression flag: 0x{:02x}", flag)
+            }
+            WifError::UnknownVersion(version) => {
+                write!(f, "Unknown WIF version byte: 0x{:02x}", version)
+            }
+            WifError::NetworkMismatch { version, network } => write!(
+                f,
+                "WIF version byte 0x{:02x} is not valid for {} (expected 0x{:02x})",
+                version,
+                network,
+                wif_version(*network)
+            ),
+            WifError::InvalidSecretKey(e) => write!(f, "Invalid WIF secret key: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for WifError {}
+
+crate::impl_wasm_error_code!(WifError);
+
+/// Decode a WIF string, returning the key and all networks matching its version byte
+pub fn decode(wif: &str) -> Result<WifInfo, WifError> {
+    let payload = base58::decode_check(wif).map_err(|e| WifError::Base58(e.to_string()))?;
+
+    let compressed = match payload.len() {
+        33 => false,
+        34 if payload[33] == COMPRESSED_FLAG => true,
+        34 => return Err(WifError::InvalidCompressionFlag(payload[33])),
+        len => return Err(WifError::InvalidLength(len)),
+    };
+
+    let version = payload[0];
+    let networks: Vec<Network> = Network::ALL
+        .iter()
+        .copied()
+        .filter(|n| wif_version(*n) == version)
+        .collect();
+    if networks.is_empty() {
+        return Err(WifError::UnknownVersion(version));
+    }
+
+    let secret_key = SecretKey::from_slice(&payload[1..1 + SECRET_KEY_LEN])
+        .map_err(|e| WifError::InvalidSecretKey(e.to_string()))?;
+
+    Ok(WifInfo {
+        secret_key,
+        compressed,
+        version,
+        networks,
+    })
+}
+
+/// Decode a WIF string and check that it is valid for `network`
+pub fn decode_for_network(wif: &str, network: Network) -> Result<WifInfo, WifError> {
+    let info = decode(wif)?;
+    if !info.is_valid_for(network) {
+        return Err(WifError::NetworkMismatch {
+            version: info.version,
+            network,
+        });
+    }
+    Ok(info)
+}
+
+/// Encode a secret key as WIF for `network`
+pub fn encode(secret_key: &SecretKey, network: Network, compressed: bool) -> String {
+    let mut payload = Vec::with_capacity(SECRET_KEY_LEN + 2);
+    payload.push(wif_version(network));
+    payload.extend_from_slice(&secret_key.secret_bytes());
+    if compressed {
+        payload.push(COMPRESSED_FLAG);
+    }
+    base58::encode_check(&payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::{NetworkKind, PrivateKey};
+
+    fn test_key() -> SecretKey {
+        SecretKey::from_slice(&[0x11; 32]).unwrap()
+    }
+
+    #[test]
+    fn test_matches_bitcoin_private_key() {
+        let key = test_key();
+        for (network, kind) in [
+            (Network::Bitcoin, NetworkKind::Main),
+            (Network::BitcoinTestnet3, NetworkKind::Test),
+        ] {
+            let expected = PrivateKey::new(key, kind).to_wif();
+            assert_eq!(encode(&key, network, true), expected);
+            let info = decode(&expected).unwrap();
+            assert_eq!(info.secret_key, key);
+            assert!(info.compressed);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_all_networks() {
+        let key = test_key();
+        for &network in Network::ALL {
+            for compressed in [true, false] {
+                let wif = encode(&key, network, compressed);
+                let info = decode_for_network(&wif, network).unwrap();
+                assert_eq!(info.secret_key, key);
+                assert_eq!(info.compressed, compressed);
+                assert_eq!(info.version, wif_version(network));
+            }
+        }
+    }
+
+    #[test]
+    fn test_network_candidates() {
+        let key = test_key();
+
+        let info = decode(&encode(&key, Network::Litecoin, true)).unwrap();
+        assert_eq!(info.networks, vec![Network::Litecoin]);
+
+        let info = decode(&encode(&key, Network::DogecoinTestnet, true)).unwrap();
+        assert_eq!(info.networks, vec![Network::DogecoinTestnet]);
+
+        let info = decode(&encode(&key, Network::DashTestnet, true)).unwrap();
+        assert!(info.is_valid_for(Network::BitcoinTestnet3));
+        assert!(info.is_valid_for(Network::LitecoinTestnet));
+        assert!(info.is_valid_for(Network::ZcashTestnet));
+        assert!(!info.is_valid_for(Network::DogecoinTestnet));
+    }
+
+    #[test]
+    fn test_network_mismatch() {
+        let wif = encode(&test_key(), Network::Dogecoin, true);
+        assert!(matches!(
+            decode_for_network(&wif, Network::Bitcoin),
+            Err(WifError::NetworkMismatch { version: 0x9e, .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_payloads() {
+        let mut payload = vec![0x80];
+        payload.extend_from_slice(&[0x11; 32]);
+        payload.push(0x02);
+        assert!(matches!(
+            decode(&base58::encode_check(&payload)),
+            Err(WifError::InvalidCompressionFlag(0x02))
+        ));
+
+        payload[0] = 0x42;
+        payload.pop();
+        assert!(matches!(
+            decode(&base58::encode_check(&payload)),
+            Err(WifError::UnknownVersion(0x42))
+        ));
+
+        assert!(matches!(
+            decode(&base58::encode_check(&[0x80; 20])),
+            Err(WifError::InvalidLength(20))
+        ));
+    }
+}
@@ -8,8 +8,18 @@
 //! - <https://github.com/ZcashFoundation/zebra/blob/main/zebra-chain/src/parameters/constants.rs>
 //!
 //! Tests verify parity with `zebra-chain` crate.
+//!
+//! Branch ids of block heights are looked up through the provider of [`upgrade_provider`],
+//! which can add upgrades that are not in this table yet.
 
 pub mod transaction;
+pub mod upgrade_provider;
+pub mod zip317;
+
+pub use upgrade_provider::{
+    reset_network_upgrade_provider, set_network_upgrade_provider, BuiltinNetworkUpgrades,
+    ExtendedNetworkUpgrades, NetworkUpgradeError, NetworkUpgradeProvider, UpgradeActivation,
+};
 
 /// Zcash network upgrade identifiers
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -141,9 +151,10 @@ pub fn network_upgrade_at_height(height: u32, is_mainnet: bool) -> Option<Networ
 
 /// Get the consensus branch ID for a given block height
 ///
+/// Uses the provider set with [`set_network_upgrade_provider`], by default the built-in table.
 /// Returns `None` if the height is before Overwinter activation.
 pub fn branch_id_for_height(height: u32, is_mainnet: bool) -> Option<u32> {
-    network_upgrade_at_height(height, is_mainnet).map(|u| u.branch_id())
+    upgrade_provider::network_upgrade_provider().branch_id_for_height(height, is_mainnet)
 }
 
 #[cfg(test)]
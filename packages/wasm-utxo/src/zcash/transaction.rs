@@ -5,7 +5,7 @@
 //! standard Bitcoin transaction consensus encoding.
 
 use miniscript::bitcoin::consensus::{Decodable, Encodable};
-use miniscript::bitcoin::{Transaction, TxIn, TxOut};
+use miniscript::bitcoin::{Transaction, TxIn, TxOut, VarInt};
 
 /// Zcash Sapling version group ID
 pub const ZCASH_SAPLING_VERSION_GROUP_ID: u32 = 0x892F2085;
@@ -27,6 +27,94 @@ pub struct ZcashTransactionParts {
     pub sapling_fields: Vec<u8>,
 }
 
+/// Sapling spend description size in v4 transactions
+const SAPLING_SPEND_SIZE: usize = 384;
+/// Sapling output description size in v4 transactions
+const SAPLING_OUTPUT_SIZE: usize = 948;
+
+/// Number of shielded components of a Zcash transaction
+///
+/// BitGo wallets only spend and create transparent outputs, so any non-zero count means the
+/// transaction cannot be handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShieldedComponentCounts {
+    /// Sapling spend descriptions
+    pub spends: usize,
+    /// Sapling output descriptions
+    pub outputs: usize,
+    /// Sprout JoinSplit descriptions
+    pub joinsplits: usize,
+}
+
+impl ShieldedComponentCounts {
+    pub fn is_empty(&self) -> bool {
+        self.spends == 0 && self.outputs == 0 && self.joinsplits == 0
+    }
+}
+
+impl ZcashTransactionParts {
+    /// Count the shielded components in `sapling_fields`
+    ///
+    /// v4 transactions carry `valueBalance`, the Sapling spends and outputs and then the
+    /// JoinSplits; v2 and v3 transactions only carry JoinSplits. Empty `sapling_fields` count
+    /// as no shielded components.
+    pub fn shielded_component_counts(&self) -> Result<ShieldedComponentCounts, String> {
+        let mut slice = self.sapling_fields.as_slice();
+        let mut counts = ShieldedComponentCounts::default();
+        if slice.is_empty() {
+            return Ok(counts);
+        }
+
+        fn read_count(slice: &mut &[u8], name: &str) -> Result<usize, String> {
+            VarInt::consensus_decode(slice)
+                .map(|n| n.0 as usize)
+                .map_err(|e| format!("Failed to decode {} count: {}", name, e))
+        }
+
+        fn skip(slice: &mut &[u8], count: usize, size: usize, name: &str) -> Result<(), String> {
+            let len = count
+                .checked_mul(size)
+                .filter(|&len| len <= slice.len())
+                .ok_or_else(|| format!("Truncated {} descriptions", name))?;
+            *slice = &slice[len..];
+            Ok(())
+        }
+
+        let version = self.transaction.version.0;
+        if self.is_overwintered && version >= 4 {
+            i64::consensus_decode(&mut slice)
+                .map_err(|e| format!("Failed to decode valueBalance: {}", e))?;
+            counts.spends = read_count(&mut slice, "shielded spend")?;
+            skip(
+                &mut slice,
+                counts.spends,
+                SAPLING_SPEND_SIZE,
+                "shielded spend",
+            )?;
+            counts.outputs = read_count(&mut slice, "shielded output")?;
+            skip(
+                &mut slice,
+                counts.outputs,
+                SAPLING_OUTPUT_SIZE,
+                "shielded output",
+            )?;
+        }
+        if version >= 2 {
+            counts.joinsplits = read_count(&mut slice, "joinsplit")?;
+        }
+        Ok(counts)
+    }
+
+    /// Whether the transaction has Sapling spends or outputs or Sprout JoinSplits
+    ///
+    /// Undecodable shielded fields count as shielded components.
+    pub fn has_shielded_components(&self) -> bool {
+        !self
+            .shielded_component_counts()
+            .is_ok_and(|counts| counts.is_empty())
+    }
+}
+
 /// Zcash transaction metadata extracted from transaction bytes
 ///
 /// This struct provides the Zcash-specific fields without requiring
@@ -190,3 +278,57 @@ pub fn encode_zcash_transaction_parts(parts: &ZcashTransactionParts) -> Result<V
 
     Ok(bytes)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miniscript::bitcoin::absolute::LockTime;
+    use miniscript::bitcoin::transaction::Version;
+
+    fn parts_with_sapling_fields(sapling_fields: Vec<u8>) -> ZcashTransactionParts {
+        ZcashTransactionParts {
+            transaction: Transaction {
+                version: Version::non_standard(4),
+                lock_time: LockTime::ZERO,
+                input: vec![],
+                output: vec![],
+            },
+            is_overwintered: true,
+            version_group_id: Some(ZCASH_SAPLING_VERSION_GROUP_ID),
+            expiry_height: Some(0),
+            sapling_fields,
+        }
+    }
+
+    #[test]
+    fn test_shielded_component_counts() {
+        // valueBalance, nShieldedSpend, nShieldedOutput, nJoinSplit
+        let transparent = parts_with_sapling_fields(vec![0u8; 11]);
+        assert_eq!(
+            transparent.shielded_component_counts().unwrap(),
+            ShieldedComponentCounts::default()
+        );
+        assert!(!transparent.has_shielded_components());
+
+        let mut fields = vec![0u8; 8];
+        fields.push(1);
+        fields.extend_from_slice(&[0u8; SAPLING_SPEND_SIZE]);
+        fields.push(2);
+        fields.extend_from_slice(&[0u8; 2 * SAPLING_OUTPUT_SIZE]);
+        fields.push(0);
+        let shielded = parts_with_sapling_fields(fields);
+        assert_eq!(
+            shielded.shielded_component_counts().unwrap(),
+            ShieldedComponentCounts {
+                spends: 1,
+                outputs: 2,
+                joinsplits: 0
+            }
+        );
+        assert!(shielded.has_shielded_components());
+
+        let truncated = parts_with_sapling_fields(vec![0, 0, 0, 0, 0, 0, 0, 0, 1, 0]);
+        assert!(truncated.shielded_component_counts().is_err());
+        assert!(truncated.has_shielded_components());
+    }
+}
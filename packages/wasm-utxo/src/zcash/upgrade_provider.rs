@@ -0,0 +1,251 @@
+//! Source of the Zcash network upgrade table
+//!
+//! The consensus branch id of a height is looked up through a [`NetworkUpgradeProvider`].
+//! The default, [`BuiltinNetworkUpgrades`], is the table of [`NetworkUpgrade`].
+//!
+//! After a network upgrade activates, transactions signed with the previous branch id are
+//! rejected. If the upgrade is not in the built-in table yet, the provider can be replaced at
+//! runtime with [`set_network_upgrade_provider`], e.g. with [`ExtendedNetworkUpgrades`] built
+//! from the branch id and activation height in the application config. The setting is per
+//! thread.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::NetworkUpgrade;
+
+/// Branch id and activation height of a network upgrade on one network
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UpgradeActivation {
+    pub branch_id: u32,
+    pub activation_height: u32,
+}
+
+pub trait NetworkUpgradeProvider {
+    /// Activations of the network upgrades, in chronological order
+    fn activations(&self, is_mainnet: bool) -> Vec<UpgradeActivation>;
+
+    /// Consensus branch id active at `height`
+    ///
+    /// Returns `None` if the height is before the first upgrade.
+    fn branch_id_for_height(&self, height: u32, is_mainnet: bool) -> Option<u32> {
+        self.activations(is_mainnet)
+            .iter()
+            .rev()
+            .find(|activation| height >= activation.activation_height)
+            .map(|activation| activation.branch_id)
+    }
+}
+
+/// The upgrades of [`NetworkUpgrade`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BuiltinNetworkUpgrades;
+
+impl NetworkUpgradeProvider for BuiltinNetworkUpgrades {
+    fn activations(&self, is_mainnet: bool) -> Vec<UpgradeActivation> {
+        NetworkUpgrade::ALL
+            .iter()
+            .map(|upgrade| UpgradeActivation {
+                branch_id: upgrade.branch_id(),
+                activation_height: upgrade.activation_height(is_mainnet),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, strum::IntoStaticStr)]
+pub enum NetworkUpgradeError {
+    /// An upgrade does not activate after the upgrade before it
+    ActivationHeightNotIncreasing {
+        activation_height: u32,
+        previous_activation_height: u32,
+    },
+    /// An upgrade has the branch id of another upgrade
+    DuplicateBranchId(u32),
+}
+
+impl std::fmt::Display for NetworkUpgradeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NetworkUpgradeError::ActivationHeightNotIncreasing {
+                activation_height,
+                previous_activation_height,
+            } => write!(
+                f,
+                "Activation height {} is not after the previous activation height {}",
+                activation_height, previous_activation_height
+            ),
+            NetworkUpgradeError::DuplicateBranchId(branch_id) => {
+                write!(f, "Duplicate branch id 0x{:08x}", branch_id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NetworkUpgradeError {}
+
+crate::impl_wasm_error_code!(NetworkUpgradeError);
+
+/// The built-in upgrades followed by upgrades that are not in the built-in table yet
+#[derive(Debug, Clone)]
+pub struct ExtendedNetworkUpgrades {
+    mainnet: Vec<UpgradeActivation>,
+    testnet: Vec<UpgradeActivation>,
+}
+
+impl ExtendedNetworkUpgrades {
+    /// Add `mainnet` and `testnet` upgrades after the built-in upgrades
+    ///
+    /// The upgrades of each network must activate after the last built-in upgrade, in
+    /// increasing order, and have new branch ids.
+    pub fn new(
+        mainnet: Vec<UpgradeActivation>,
+        testnet: Vec<UpgradeActivation>,
+    ) -> Result<Self, NetworkUpgradeError> {
+        Ok(Self {
+            mainnet: Self::extend(true, mainnet)?,
+            testnet: Self::extend(false, testnet)?,
+        })
+    }
+
+    fn extend(
+        is_mainnet: bool,
+        upgrades: Vec<UpgradeActivation>,
+    ) -> Result<Vec<UpgradeActivation>, NetworkUpgradeError> {
+        let mut activations = BuiltinNetworkUpgrades.activations(is_mainnet);
+        for upgrade in upgrades {
+            if let Some(previous) = activations.last() {
+                if upgrade.activation_height <= previous.activation_height {
+                    return Err(NetworkUpgradeError::ActivationHeightNotIncreasing {
+                        activation_height: upgrade.activation_height,
+                        previous_activation_height: previous.activation_height,
+                    });
+                }
+            }
+            if activations
+                .iter()
+                .any(|activation| activation.branch_id == upgrade.branch_id)
+            {
+                return Err(NetworkUpgradeError::DuplicateBranchId(upgrade.branch_id));
+            }
+            activations.push(upgrade);
+        }
+        Ok(activations)
+    }
+}
+
+impl NetworkUpgradeProvider for ExtendedNetworkUpgrades {
+    fn activations(&self, is_mainnet: bool) -> Vec<UpgradeActivation> {
+        if is_mainnet {
+            self.mainnet.clone()
+        } else {
+            self.testnet.clone()
+        }
+    }
+}
+
+thread_local! {
+    static PROVIDER: RefCell<Rc<dyn NetworkUpgradeProvider>> =
+        RefCell::new(Rc::new(BuiltinNetworkUpgrades));
+}
+
+/// Look up branch ids with `provider` instead of the built-in table
+pub fn set_network_upgrade_provider(provider: Rc<dyn NetworkUpgradeProvider>) {
+    PROVIDER.with(|cell| *cell.borrow_mut() = provider);
+}
+
+/// Go back to the built-in table
+pub fn reset_network_upgrade_provider() {
+    set_network_upgrade_provider(Rc::new(BuiltinNetworkUpgrades));
+}
+
+/// The provider set with [`set_network_upgrade_provider`]
+pub fn network_upgrade_provider() -> Rc<dyn NetworkUpgradeProvider> {
+    PROVIDER.with(|cell| cell.borrow().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zcash::branch_id_for_height;
+
+    const NEXT_UPGRADE: UpgradeActivation = UpgradeActivation {
+        branch_id: 0x12345678,
+        activation_height: 5_000_000,
+    };
+
+    #[test]
+    fn test_builtin_provider() {
+        for is_mainnet in [true, false] {
+            let activations = BuiltinNetworkUpgrades.activations(is_mainnet);
+            assert_eq!(activations.len(), NetworkUpgrade::ALL.len());
+            for height in [0, 419_200, 1_687_104, 3_000_000, u32::MAX] {
+                assert_eq!(
+                    BuiltinNetworkUpgrades.branch_id_for_height(height, is_mainnet),
+                    crate::zcash::network_upgrade_at_height(height, is_mainnet)
+                        .map(NetworkUpgrade::branch_id)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_extended_provider() {
+        let upgrades = ExtendedNetworkUpgrades::new(vec![NEXT_UPGRADE], vec![]).unwrap();
+        let last = NetworkUpgrade::ALL[NetworkUpgrade::ALL.len() - 1];
+        assert_eq!(
+            upgrades.branch_id_for_height(NEXT_UPGRADE.activation_height - 1, true),
+            Some(last.branch_id())
+        );
+        assert_eq!(
+            upgrades.branch_id_for_height(NEXT_UPGRADE.activation_height, true),
+            Some(NEXT_UPGRADE.branch_id)
+        );
+        assert_eq!(
+            upgrades.branch_id_for_height(NEXT_UPGRADE.activation_height, false),
+            Some(last.branch_id())
+        );
+
+        assert!(matches!(
+            ExtendedNetworkUpgrades::new(
+                vec![UpgradeActivation {
+                    activation_height: last.mainnet_activation_height(),
+                    ..NEXT_UPGRADE
+                }],
+                vec![]
+            ),
+            Err(NetworkUpgradeError::ActivationHeightNotIncreasing { .. })
+        ));
+        assert!(matches!(
+            ExtendedNetworkUpgrades::new(
+                vec![],
+                vec![UpgradeActivation {
+                    branch_id: last.branch_id(),
+                    ..NEXT_UPGRADE
+                }]
+            ),
+            Err(NetworkUpgradeError::DuplicateBranchId(_))
+        ));
+    }
+
+    #[test]
+    fn test_set_network_upgrade_provider() {
+        let height = NEXT_UPGRADE.activation_height;
+        assert_ne!(
+            branch_id_for_height(height, true),
+            Some(NEXT_UPGRADE.branch_id)
+        );
+        set_network_upgrade_provider(Rc::new(
+            ExtendedNetworkUpgrades::new(vec![NEXT_UPGRADE], vec![]).unwrap(),
+        ));
+        assert_eq!(
+            branch_id_for_height(height, true),
+            Some(NEXT_UPGRADE.branch_id)
+        );
+        reset_network_upgrade_provider();
+        assert_ne!(
+            branch_id_for_height(height, true),
+            Some(NEXT_UPGRADE.branch_id)
+        );
+    }
+}
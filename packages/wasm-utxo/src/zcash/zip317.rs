@@ -0,0 +1,77 @@
+//! ZIP-317 conventional fee for transparent-only transactions
+//!
+//! Zcash nodes evict transactions paying less than the conventional fee from the mempool
+//! without reporting an error to the sender. For transactions without shielded
+//! components, the number of logical actions is determined by the serialized size of the
+//! transparent inputs and outputs.
+//!
+//! Reference: <https://zips.z.cash/zip-0317>
+
+/// Fee per logical action, in zatoshis
+pub const MARGINAL_FEE: u64 = 5_000;
+/// Number of logical actions that are covered by the minimum fee
+pub const GRACE_ACTIONS: u64 = 2;
+/// Size of a standard P2PKH input, in bytes
+pub const P2PKH_STANDARD_INPUT_SIZE: usize = 150;
+/// Size of a standard P2PKH output, in bytes
+pub const P2PKH_STANDARD_OUTPUT_SIZE: usize = 34;
+
+/// ZIP-317 fee check of a transparent-only transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Zip317Fee {
+    pub logical_actions: u64,
+    /// Conventional fee in zatoshis
+    pub conventional_fee: u64,
+    /// Whether the transaction fee is at least the conventional fee
+    pub meets_minimum: bool,
+}
+
+impl Zip317Fee {
+    /// Evaluate `fee` against the conventional fee of a transaction whose transparent
+    /// inputs and outputs serialize to `tx_in_total_size` and `tx_out_total_size` bytes.
+    pub fn transparent(tx_in_total_size: usize, tx_out_total_size: usize, fee: u64) -> Self {
+        let logical_actions = transparent_logical_actions(tx_in_total_size, tx_out_total_size);
+        let conventional_fee = conventional_fee(logical_actions);
+        Zip317Fee {
+            logical_actions,
+            conventional_fee,
+            meets_minimum: fee >= conventional_fee,
+        }
+    }
+}
+
+/// Logical actions of the transparent part of a transaction
+pub fn transparent_logical_actions(tx_in_total_size: usize, tx_out_total_size: usize) -> u64 {
+    tx_in_total_size
+        .div_ceil(P2PKH_STANDARD_INPUT_SIZE)
+        .max(tx_out_total_size.div_ceil(P2PKH_STANDARD_OUTPUT_SIZE)) as u64
+}
+
+/// Conventional fee for a number of logical actions
+pub fn conventional_fee(logical_actions: u64) -> u64 {
+    MARGINAL_FEE * logical_actions.max(GRACE_ACTIONS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conventional_fee() {
+        // One p2pkh input and two p2pkh outputs fall within the grace actions
+        assert_eq!(transparent_logical_actions(150, 68), 2);
+        assert_eq!(
+            Zip317Fee::transparent(150, 68, 10_000).conventional_fee,
+            10_000
+        );
+
+        // A 2-of-3 p2sh input (~297 bytes) counts as two logical actions
+        let fee = Zip317Fee::transparent(297 * 3, 32 * 2, 25_000);
+        assert_eq!(fee.logical_actions, 6);
+        assert_eq!(fee.conventional_fee, 30_000);
+        assert!(!fee.meets_minimum);
+
+        assert!(Zip317Fee::transparent(0, 0, 10_000).meets_minimum);
+        assert!(!Zip317Fee::transparent(0, 0, 9_999).meets_minimum);
+    }
+}
@@ -0,0 +1,22 @@
+import assert from "node:assert";
+import { address as addressNs } from "../../js/index.js";
+
+describe("cashaddr conversion", function () {
+  const legacy = "1BpEi6DfDAUFd7GtittLSdBeYJvcoaVggu";
+  const cashaddr = "bitcoincash:qpm2qsznhks23z7629mms6s4cwef74vcwvy22gdx6a";
+
+  it("converts between legacy and cashaddr", function () {
+    assert.strictEqual(addressNs.toCashaddrWithCoin(legacy, "bch"), cashaddr);
+    assert.strictEqual(addressNs.toLegacyAddressWithCoin(cashaddr, "bch"), legacy);
+  });
+
+  it("accepts cashaddr without prefix", function () {
+    const unprefixed = cashaddr.replace("bitcoincash:", "");
+    assert.strictEqual(addressNs.toLegacyAddressWithCoin(unprefixed, "bch"), legacy);
+    assert.strictEqual(addressNs.toCashaddrWithCoin(unprefixed, "bch"), cashaddr);
+  });
+
+  it("rejects non-cashaddr networks", function () {
+    assert.throws(() => addressNs.toCashaddrWithCoin(legacy, "btc"));
+  });
+});
@@ -0,0 +1,25 @@
+import assert from "node:assert";
+import { address as addressNs } from "../../js/index.js";
+
+describe("zcash addresses", function () {
+  it("round-trips transparent addresses", function () {
+    for (const address of [
+      "t1LcxLfh8seLbbhwEwLYwMFoY3n944fm3Zu",
+      "t3L9BWgkrgFVS6wCSK3w5vwjdhMHtfHnT6r",
+    ]) {
+      const script = addressNs.toOutputScriptWithCoin(address, "zec");
+      assert.strictEqual(addressNs.fromOutputScriptWithCoin(script, "zec"), address);
+    }
+  });
+
+  it("rejects shielded addresses", function () {
+    assert.throws(
+      () =>
+        addressNs.toOutputScriptWithCoin(
+          "zs1z7rejlpsa98s2rrrfkwmaxu53e4ue0ulcrw0h4x5g8jl04tak0d3mm47vdtahatqrlkngh9slya",
+          "zec",
+        ),
+      /Unsupported Zcash Sapling shielded address/,
+    );
+  });
+});
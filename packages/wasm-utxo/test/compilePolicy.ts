@@ -0,0 +1,46 @@
+import * as assert from "assert";
+import { Descriptor, Miniscript } from "../js/index.js";
+
+// 1G, 2G and 3G
+const KEYS = [
+  "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+  "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+  "02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+];
+const POLICY = `thresh(2,pk(${KEYS[0]}),pk(${KEYS[1]}),pk(${KEYS[2]}))`;
+
+describe("Miniscript.compilePolicy", function () {
+  it("compiles to wsh and sh descriptors", function () {
+    for (const [ctx, prefix] of [
+      ["segwitv0", "wsh("],
+      ["legacy", "sh("],
+    ] as const) {
+      const compiled = Miniscript.compilePolicy(POLICY, ctx);
+      assert.ok(compiled.descriptor.startsWith(prefix), compiled.descriptor);
+      assert.ok(compiled.miniscript);
+      const miniscript = Miniscript.fromString(compiled.miniscript, ctx);
+      assert.strictEqual(compiled.scriptSize, miniscript.encode().length);
+      const descriptor = Descriptor.fromStringDetectType(compiled.descriptor);
+      assert.strictEqual(compiled.maxWeightToSatisfy, descriptor.maxWeightToSatisfy());
+    }
+  });
+
+  it("compiles to a tr descriptor", function () {
+    const compiled = Miniscript.compilePolicy(POLICY, "tap");
+    assert.ok(compiled.descriptor.startsWith("tr("), compiled.descriptor);
+    assert.strictEqual(compiled.miniscript, undefined);
+    assert.strictEqual(compiled.scriptSize, undefined);
+    assert.ok(compiled.maxWeightToSatisfy > 0);
+  });
+
+  it("uses an extracted key as the taproot internal key", function () {
+    const policy = `or(99@pk(${KEYS[0]}),1@and(pk(${KEYS[1]}),pk(${KEYS[2]})))`;
+    const compiled = Miniscript.compilePolicy(policy, "tap");
+    assert.ok(compiled.descriptor.startsWith(`tr(${KEYS[0]}`), compiled.descriptor);
+  });
+
+  it("rejects invalid input", function () {
+    assert.throws(() => Miniscript.compilePolicy("thresh(2,pk(A))", "segwitv0"), /Invalid policy/);
+    assert.throws(() => Miniscript.compilePolicy(POLICY, "p2sh" as "legacy"), /context type/);
+  });
+});
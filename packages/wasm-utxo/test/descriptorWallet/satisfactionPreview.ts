@@ -0,0 +1,34 @@
+import * as assert from "assert";
+import { Descriptor } from "../../js/index.js";
+
+describe("Descriptor.satisfactionPreview", () => {
+  const keyA = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+  const keyB = "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5";
+  const descriptor = Descriptor.fromString(
+    `wsh(or_d(pk(${keyA}),and_v(v:pk(${keyB}),older(144))))`,
+    "definite",
+  );
+
+  it("returns no timelock for the key path", () => {
+    assert.deepStrictEqual(descriptor.satisfactionPreview([Buffer.from(keyA, "hex")]), [
+      { after: null, older: null, unlockAt: { height: null, time: null } },
+    ]);
+  });
+
+  it("returns the relative timelock of the recovery path", () => {
+    const recoveryKey = Buffer.from(keyB, "hex");
+    assert.deepStrictEqual(descriptor.satisfactionPreview([recoveryKey]), [
+      { after: null, older: { height: 144 }, unlockAt: null },
+    ]);
+    // x-only key, confirmed input
+    const [preview] = descriptor.satisfactionPreview([recoveryKey.subarray(1)], {
+      height: 800_000,
+      medianTimePast: 1_700_000_000,
+    });
+    assert.deepStrictEqual(preview.unlockAt, { height: 800_144, time: null });
+  });
+
+  it("returns nothing if the keys cannot satisfy the descriptor", () => {
+    assert.deepStrictEqual(descriptor.satisfactionPreview([]), []);
+  });
+});
@@ -8,6 +8,8 @@ import {
   type Output,
 } from "./fixedScript/fixtureUtil.js";
 import { mainnetCoinNames } from "./fixedScript/networkSupport.util.js";
+import { ECPair } from "../js/ecpair.js";
+import { getDefaultWalletKeys, getKeyTriple } from "../js/testutils/keys.js";
 import type { InputScriptType } from "../js/fixedScriptWallet/BitGoPsbt.js";
 
 /**
@@ -495,5 +497,32 @@ describe("Dimensions", function () {
         });
       });
     });
+
+    it("counts the actual scriptSig of a signed replay protection input", function () {
+      const walletKeys = getDefaultWalletKeys();
+      const [userXprv] = getKeyTriple("default");
+      const key = ECPair.fromPrivateKey(userXprv.privateKey as Uint8Array);
+      const replayProtection = { publicKeys: [key] };
+
+      const psbt = fixedScriptWallet.BitGoPsbt.createEmpty("bch", walletKeys, {
+        version: 2,
+        lockTime: 0,
+      });
+      psbt.addReplayProtectionInput({ txid: "aa".repeat(32), vout: 0, value: 10_000n }, key);
+      psbt.addWalletOutput(walletKeys, { chain: 0, index: 0, value: 9_000n });
+      const unsigned = psbt.parseTransactionWithWalletKeys(walletKeys, { replayProtection });
+
+      psbt.signInput(0, key);
+      const signed = psbt.parseTransactionWithWalletKeys(walletKeys, { replayProtection });
+      const dim = Dimensions.fromPsbt(psbt);
+      psbt.finalizeAllInputs();
+      const actualVSize = Transaction.fromBytes(psbt.extractTransaction().toBytes()).getVSize();
+
+      assert.strictEqual(signed.virtualSize, actualVSize);
+      assert.strictEqual(dim.getVSize("min"), actualVSize);
+      assert.strictEqual(dim.getVSize("max"), actualVSize);
+      // Unsigned inputs are counted with the largest signature
+      assert.ok(unsigned.virtualSize >= actualVSize && unsigned.virtualSize <= actualVSize + 2);
+    });
   });
 });
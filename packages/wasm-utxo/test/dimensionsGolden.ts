@@ -0,0 +1,108 @@
+/**
+ * Golden tests for transaction size estimation.
+ *
+ * `fixtures/dimensions/utxolib-vsize.json` holds the vsize that @bitgo/unspents `Dimensions`
+ * computes for a matrix of input and output combinations. The same fixture is checked by the
+ * Rust tests of the estimator, so both sides catch a divergence from utxo-lib.
+ */
+import assert from "node:assert";
+import * as fs from "node:fs";
+import * as path from "node:path";
+import { fileURLToPath } from "node:url";
+import { Dimensions as UnspentsDimensions } from "@bitgo/unspents";
+import { Dimensions, type InputSpec, type OutputSpec } from "../js/fixedScriptWallet/Dimensions.js";
+
+const __dirname = path.dirname(fileURLToPath(import.meta.url));
+
+type GoldenCase = {
+  inputs: InputSpec[];
+  outputs: OutputSpec[];
+  vsize: number;
+};
+
+function loadCases(): GoldenCase[] {
+  const filePath = path.join(__dirname, "fixtures", "dimensions", "utxolib-vsize.json");
+  return (JSON.parse(fs.readFileSync(filePath, "utf8")) as { cases: GoldenCase[] }).cases;
+}
+
+function unspentsInput(spec: InputSpec): UnspentsDimensions {
+  if ("chain" in spec) {
+    assert.strictEqual(spec.chain, 30);
+    return spec.signPath?.cosigner === "backup"
+      ? UnspentsDimensions.SingleInput.p2trScriptPathLevel2
+      : UnspentsDimensions.SingleInput.p2trScriptPathLevel1;
+  }
+  switch (spec.scriptType) {
+    case "p2sh":
+      return UnspentsDimensions.SingleInput.p2sh;
+    case "p2shP2wsh":
+      return UnspentsDimensions.SingleInput.p2shP2wsh;
+    case "p2wsh":
+      return UnspentsDimensions.SingleInput.p2wsh;
+    case "p2shP2pk":
+      return UnspentsDimensions.SingleInput.p2shP2pk;
+    case "p2trMusig2KeyPath":
+      return UnspentsDimensions.SingleInput.p2trKeypath;
+    case "p2trLegacy":
+      return UnspentsDimensions.SingleInput.p2trScriptPathLevel1;
+    default:
+      throw new Error(`no @bitgo/unspents input for ${spec.scriptType}`);
+  }
+}
+
+function unspentsOutput(spec: OutputSpec): UnspentsDimensions {
+  const scriptType = "scriptType" in spec ? spec.scriptType : undefined;
+  switch (scriptType) {
+    case "p2sh":
+      return UnspentsDimensions.SingleOutput.p2sh;
+    case "p2wsh":
+      return UnspentsDimensions.SingleOutput.p2wsh;
+    case "p2trMusig2":
+      return UnspentsDimensions.SingleOutput.p2tr;
+    default:
+      throw new Error(`no @bitgo/unspents output for ${JSON.stringify(spec)}`);
+  }
+}
+
+function describeCase({ inputs, outputs }: GoldenCase): string {
+  const describeSpec = (spec: InputSpec | OutputSpec) => {
+    const { count, ...kind } = spec;
+    return `${count ?? 1}x${JSON.stringify(kind)}`;
+  };
+  return `${inputs.map(describeSpec).join(" + ")} -> ${outputs.map(describeSpec).join(" + ")}`;
+}
+
+describe("Dimensions golden tests against @bitgo/unspents", function () {
+  for (const goldenCase of loadCases()) {
+    describe(describeCase(goldenCase), function () {
+      it("fixture matches @bitgo/unspents", function () {
+        const dimensions = [
+          ...goldenCase.inputs.map((spec) => unspentsInput(spec).times(spec.count ?? 1)),
+          ...goldenCase.outputs.map((spec) => unspentsOutput(spec).times(spec.count ?? 1)),
+        ].reduce((sum, d) => sum.plus(d));
+        assert.strictEqual(dimensions.getVSize(), goldenCase.vsize);
+      });
+
+      it("estimateVSize with utxolibCompat matches the fixture", function () {
+        const { max } = Dimensions.estimateVSize(goldenCase.inputs, goldenCase.outputs, {
+          utxolibCompat: true,
+        });
+        assert.strictEqual(max, goldenCase.vsize);
+      });
+    });
+  }
+
+  it("estimateVSize without utxolibCompat matches combined Dimensions", function () {
+    const inputs: InputSpec[] = [{ chain: 20, count: 2 }, { scriptType: "p2trMusig2KeyPath" }];
+    const outputs: OutputSpec[] = [{ scriptType: "p2wsh" }, { length: 22, count: 2 }];
+    const dimensions = Dimensions.fromInput({ chain: 20 })
+      .times(2)
+      .plus(Dimensions.fromInput({ scriptType: "p2trMusig2KeyPath" }))
+      .plus(Dimensions.fromOutput({ scriptType: "p2wsh" }))
+      .plus(Dimensions.fromOutput({ length: 22 }).times(2));
+    assert.deepStrictEqual(Dimensions.estimateVSize(inputs, outputs), {
+      min: dimensions.getVSize("min"),
+      max: dimensions.getVSize("max"),
+    });
+  });
+});
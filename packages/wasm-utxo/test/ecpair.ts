@@ -171,6 +171,37 @@ describe("WasmECPair", () => {
     assert.ok(key2.publicKey[0] === 0x02 || key2.publicKey[0] === 0x03);
   });
 
+  it("should roundtrip WIF for altcoin networks", () => {
+    const key1 = ECPair.fromPrivateKey(testPrivateKey);
+    for (const network of ["ltc", "doge", "tdoge", "dash", "zec", "tbch"] as const) {
+      const wif = key1.toWIFForNetwork(network);
+      const key2 = ECPair.fromWIFWithNetwork(wif, network);
+      assert.deepStrictEqual(key1.privateKey, key2.privateKey);
+      assert.ok(ECPair.decodeWIF(wif).networks.includes(network));
+    }
+  });
+
+  it("should decode WIF network candidates", () => {
+    const info = ECPair.decodeWIF(ECPair.fromPrivateKey(testPrivateKey).toWIFForNetwork("ltc"));
+    assert.strictEqual(info.compressed, true);
+    assert.strictEqual(info.version, 0xb0);
+    assert.deepStrictEqual(info.networks, ["ltc"]);
+
+    const btcInfo = ECPair.decodeWIF(testWifMainnet);
+    assert.strictEqual(btcInfo.version, 0x80);
+    assert.ok(btcInfo.networks.includes("btc"));
+    assert.ok(btcInfo.networks.includes("bch"));
+  });
+
+  it("should reject WIF for the wrong network", () => {
+    const wif = ECPair.fromPrivateKey(testPrivateKey).toWIFForNetwork("doge");
+    assert.throws(() => {
+      ECPair.fromWIFWithNetwork(wif, "btc");
+    });
+    // Dogecoin mainnet WIFs are still recognized as mainnet
+    assert.ok(ECPair.fromWIFMainnet(wif).privateKey);
+  });
+
   it("should derive same public key from same private key", () => {
     const key1 = ECPair.fromPrivateKey(testPrivateKey);
     const key2 = ECPair.fromPrivateKey(testPrivateKey);
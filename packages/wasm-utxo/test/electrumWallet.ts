@@ -0,0 +1,69 @@
+import * as assert from "assert";
+import { electrumWallet, fixedScriptWallet } from "../js/index.js";
+
+const { ElectrumWallet } = electrumWallet;
+
+// Test vectors from Electrum's test_wallet_vertical.py
+const standardSeed = "cycle rocket west magnet parrot shuffle foot correct salt library feed song";
+const standardMpk =
+  "xpub661MyMwAqRbcFWohJWt7PHsFEJfZAvw9ZxwQoDa4SoMgsDDM1T7WK3u9E4edkC4ugRnZ8E4xDZRpk8Rnts3Nbt97dPwT52CwBdDWroaZf8U";
+const segwitSeed = "bitter grass shiver impose acquire brush forget axis eager alone wine silver";
+
+describe("electrumWallet", () => {
+  it("imports a standard seed", () => {
+    const wallet = ElectrumWallet.fromSeed(standardSeed, "", "btc");
+    assert.strictEqual(wallet.seedType, "standard");
+    assert.strictEqual(wallet.scriptType, "p2pkh");
+    assert.strictEqual(wallet.mpk, standardMpk);
+    assert.strictEqual(
+      wallet.address({ change: false, index: 0 }, "btc"),
+      "1NNkttn1YvVGdqBW4PR6zvc3Zx3H5owKRf",
+    );
+    assert.strictEqual(
+      wallet.address({ change: true, index: 0 }, "btc"),
+      "1KSezYMhAJMWqFbVFB2JshYg69UpmEXR4D",
+    );
+    assert.strictEqual(ElectrumWallet.fromMpk(standardMpk).mpk, standardMpk);
+    assert.strictEqual(ElectrumWallet.fromMpk(standardMpk).xprv, undefined);
+  });
+
+  it("imports a segwit seed", () => {
+    const wallet = ElectrumWallet.fromSeed(segwitSeed, "", "btc");
+    assert.strictEqual(wallet.seedType, "segwit");
+    assert.ok(wallet.mpk.startsWith("zpub"));
+    assert.strictEqual(
+      wallet.address({ change: false, index: 0 }, "btc"),
+      "bc1q3g5tmkmlvxryhh843v4dz026avatc0zzr6h3af",
+    );
+    assert.strictEqual(ElectrumWallet.fromMpk(wallet.mpk).seedType, "segwit");
+  });
+
+  it("rejects non-Electrum seeds", () => {
+    assert.throws(() => ElectrumWallet.fromSeed("not an electrum seed", "", "btc"), /seed/);
+  });
+
+  it("sweeps the wallet with a BitGoPsbt", () => {
+    const wallet = ElectrumWallet.fromSeed(standardSeed, "", "btc");
+    const psbt = fixedScriptWallet.BitGoPsbt.createEmpty("btc", wallet.rootWalletKeys, {
+      version: 2,
+      lockTime: 0,
+    });
+    const ids = [
+      { change: false, index: 0 },
+      { change: true, index: 3 },
+    ];
+    ids.forEach((id, vout) => {
+      wallet.addInput(psbt, { txid: "00".repeat(32), vout, value: 10_000n }, id);
+    });
+    psbt.addOutput(wallet.outputScript({ change: false, index: 1 }), 19_000n);
+
+    const parsed = psbt.parseTransactionWithWalletKeys(wallet.rootWalletKeys, {
+      replayProtection: { publicKeys: [] },
+    });
+    assert.deepStrictEqual(parsed.inputs.map((input) => input.derivationPath), ["0/0", "1/3"]);
+
+    ids.forEach((id, inputIndex) => psbt.sign(inputIndex, wallet.signingKey(id)));
+    psbt.finalizeAllInputs();
+    assert.ok(psbt.extractTransaction().toBytes().length > 0);
+  });
+});
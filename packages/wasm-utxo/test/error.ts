@@ -1,5 +1,9 @@
 import * as assert from "assert";
-import { Descriptor, isWasmUtxoError } from "../js/index.js";
+import { ECPair } from "../js/ecpair.js";
+import { Descriptor, isWasmUtxoError, type DerivationMismatchDetails } from "../js/index.js";
+import { BitGoPsbt } from "../js/fixedScriptWallet/BitGoPsbt.js";
+import { outputScript } from "../js/fixedScriptWallet/address.js";
+import { getWalletKeysForSeed } from "../js/testutils/keys.js";
 
 describe("isWasmUtxoError", function () {
   describe("returns false for non-WasmUtxoError values", function () {
@@ -58,5 +62,106 @@ describe("isWasmUtxoError", function () {
     it("is still an Error instance", function () {
       assert.ok(error instanceof Error);
     });
+
+    it("keeps the name Error", function () {
+      assert.ok(error instanceof Error);
+      assert.strictEqual(error.name, "Error");
+    });
+  });
+
+  describe("for a BitGoPsbtError", function () {
+    const walletKeys = getWalletKeysForSeed("error");
+
+    function catchError(f: () => unknown): unknown {
+      try {
+        f();
+      } catch (e) {
+        return e;
+      }
+      assert.fail("expected an error to be thrown");
+    }
+
+    it("has name, code and details", function () {
+      const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+      const error = catchError(() =>
+        psbt.addWalletInput({ txid: "00".repeat(32), vout: 0, value: 10_000n }, walletKeys, {
+          scriptId: { chain: 2, index: 0 },
+        }),
+      );
+      assert.ok(isWasmUtxoError(error));
+      assert.strictEqual(error.name, "BitGoPsbtError");
+      assert.strictEqual(error.code, "BitGoPsbtError.InvalidChain");
+      assert.deepStrictEqual(error.details, { chain: 2 });
+    });
+
+    it("has the code of the cause", function () {
+      const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+      const error = catchError(() =>
+        psbt.addWalletInput({ txid: "00".repeat(32), vout: 0, value: 10_000n }, walletKeys, {
+          scriptId: { chain: 30, index: 0 },
+        }),
+      );
+      assert.ok(isWasmUtxoError(error));
+      assert.strictEqual(error.name, "BitGoPsbtError");
+      assert.strictEqual(error.code, "BitGoPsbtError.WalletInput/WalletInputError.MissingSignPath");
+    });
+
+    it("reports the index of the input", function () {
+      const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+      const input = { txid: "00".repeat(32), vout: 0, value: 10_000n };
+      const scriptId = { chain: 20, index: 0 };
+      const outOfBounds = catchError(() =>
+        psbt.addWalletInputAtIndex(1, input, walletKeys, { scriptId }),
+      );
+      assert.ok(isWasmUtxoError(outOfBounds));
+      assert.strictEqual(outOfBounds.code, "BitGoPsbtError.InputIndexOutOfBounds");
+      assert.deepStrictEqual(outOfBounds.details, { inputIndex: 1, inputCount: 0 });
+
+      psbt.addWalletInput(input, walletKeys, { scriptId });
+      const replayProtectionKey = ECPair.fromPrivateKey(new Uint8Array(32).fill(1));
+      psbt.addReplayProtectionInput({ ...input, vout: 1 }, replayProtectionKey);
+      const otherKey = ECPair.fromPrivateKey(new Uint8Array(32).fill(2));
+      const mismatch = catchError(() => psbt.signInput(1, otherKey));
+      assert.ok(isWasmUtxoError(mismatch));
+      assert.strictEqual(mismatch.code, "BitGoPsbtError.PublicKeyMismatch");
+      assert.deepStrictEqual(mismatch.details, { inputIndex: 1 });
+    });
+
+    it("reports the scripts and path of a derivation mismatch", function () {
+      const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+      for (const index of [0, 1]) {
+        psbt.addWalletInput({ txid: "00".repeat(32), vout: index, value: 10_000n }, walletKeys, {
+          scriptId: { chain: 20, index },
+        });
+      }
+      psbt.addWalletOutput(walletKeys, { chain: 20, index: 2, value: 19_000n });
+      // Rewrite the key origins of input 1 (chain 20, index 1) to claim index 0
+      const hex = Buffer.from(psbt.serialize())
+        .toString("hex")
+        .replaceAll("1400000001000000", "1400000000000000");
+      const tampered = BitGoPsbt.fromBytes(Buffer.from(hex, "hex"), "btc");
+
+      const error = catchError(() =>
+        tampered.parseTransactionWithWalletKeys(walletKeys, {
+          replayProtection: { publicKeys: [] },
+        }),
+      );
+      assert.ok(isWasmUtxoError(error));
+      assert.strictEqual(
+        error.code,
+        "ParseTransactionError.Input/ParseInputError.DerivationMismatch",
+      );
+      const details = error.details as DerivationMismatchDetails;
+      assert.strictEqual(details.inputIndex, 1);
+      assert.deepStrictEqual(
+        Buffer.from(details.expectedScript),
+        Buffer.from(outputScript(walletKeys, 20, 0, "btc")),
+      );
+      assert.deepStrictEqual(
+        Buffer.from(details.actualScript),
+        Buffer.from(outputScript(walletKeys, 20, 1, "btc")),
+      );
+      assert.ok(details.derivationPath.endsWith("0/0/20/0"), details.derivationPath);
+    });
   });
 });
@@ -0,0 +1,32 @@
+/**
+ * Tests for auditing the nonces of ECDSA signatures
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { getKeyTriple, getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt.auditSignatureNonce", function () {
+  const [userKey, backupKey] = getKeyTriple("nonce_audit");
+  const walletKeys = getWalletKeysForSeed("nonce_audit");
+
+  function createSignedPsbt(): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    psbt.addWalletInput({ txid: "00".repeat(32), vout: 0, value: 10_000n }, walletKeys, {
+      scriptId: { chain: 20, index: 0 },
+    });
+    psbt.sign(userKey);
+    return psbt;
+  }
+
+  it("recognizes deterministic nonces", function () {
+    const derivation = createSignedPsbt().auditSignatureNonce(0, userKey);
+    assert.ok(derivation === "rfc6979" || derivation === "rfc6979LowR", String(derivation));
+  });
+
+  it("rejects keys without a signature", function () {
+    const psbt = createSignedPsbt();
+    assert.throws(() => psbt.auditSignatureNonce(0, backupKey), /no ECDSA signature/);
+    assert.throws(() => psbt.auditSignatureNonce(1, userKey), /out of bounds/);
+  });
+});
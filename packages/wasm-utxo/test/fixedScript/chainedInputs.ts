@@ -0,0 +1,59 @@
+/**
+ * Tests for spending the change of a PSBT that is not broadcast yet
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { TxPackage } from "../../js/fixedScriptWallet/TxPackage.js";
+import { getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt.addWalletInputFromParent", function () {
+  const walletKeys = getWalletKeysForSeed("chained_inputs");
+  const opReturn = new Uint8Array([0x6a, 0x01, 0x00]);
+
+  function createParent(chain: number): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    psbt.addWalletInput({ txid: "00".repeat(32), vout: 0, value: 100_000n }, walletKeys, {
+      scriptId: { chain, index: 0 },
+    });
+    psbt.addOutput(opReturn, 0n);
+    psbt.addWalletOutput(walletKeys, { chain: 21, index: 3, value: 90_000n });
+    return psbt;
+  }
+
+  it("spends the change of a segwit parent", function () {
+    const parent = createParent(20);
+    const child = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    assert.strictEqual(child.addWalletInputFromParent(parent, 1, walletKeys), 0);
+    assert.deepStrictEqual(child.unconfirmedParents(), [parent.unsignedTxId()]);
+    child.addOutput(opReturn, 89_000n);
+
+    const pkg = TxPackage.create([child, parent], "btc");
+    assert.deepStrictEqual(pkg.txids, [parent.unsignedTxId(), child.unsignedTxId()]);
+    assert.deepStrictEqual(pkg.packageFee().fees, [10_000n, 1_000n]);
+  });
+
+  it("rejects outputs that do not belong to the wallet", function () {
+    const child = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    assert.throws(
+      () => child.addWalletInputFromParent(createParent(20), 0, walletKeys),
+      (e: Error & { code?: string }) => {
+        assert.strictEqual(e.code, "ChainedInputError.NotWalletOutput");
+        assert.match(e.message, /does not belong to the wallet/);
+        return true;
+      },
+    );
+  });
+
+  it("requires non-segwit parent inputs to be finalized", function () {
+    const child = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    assert.throws(
+      () => child.addWalletInputFromParent(createParent(0), 1, walletKeys),
+      (e: Error & { code?: string }) => {
+        assert.strictEqual(e.code, "ChainedInputError.ParentNotFinal");
+        assert.match(e.message, /must be finalized/);
+        return true;
+      },
+    );
+  });
+});
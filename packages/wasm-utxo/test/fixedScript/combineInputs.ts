@@ -276,6 +276,22 @@ describe("BitGoPsbt.combineInputs", function () {
       assert.strictEqual(branchId, undefined);
     });
 
+    it("branchIdForHeight uses the upgrades added with setNetworkUpgrades", function () {
+      const upgrade = { branchId: 0x12345678, activationHeight: 5_000_000 };
+      ZcashBitGoPsbt.setNetworkUpgrades({ mainnet: [upgrade] });
+      try {
+        assert.strictEqual(ZcashBitGoPsbt.branchIdForHeight("zec", 5_000_000), 0x12345678);
+        assert.notStrictEqual(ZcashBitGoPsbt.branchIdForHeight("tzec", 5_000_000), 0x12345678);
+        assert.throws(
+          () => ZcashBitGoPsbt.setNetworkUpgrades({ mainnet: [upgrade, upgrade] }),
+          /not after the previous activation height/,
+        );
+      } finally {
+        ZcashBitGoPsbt.resetNetworkUpgrades();
+      }
+      assert.notStrictEqual(ZcashBitGoPsbt.branchIdForHeight("zec", 5_000_000), 0x12345678);
+    });
+
     it("ZcashBitGoPsbt.fromBytes throws when ZecConsensusBranchId is absent", function () {
       const btcBytes = makeAcidTest(
         "btc",
@@ -0,0 +1,49 @@
+import assert from "node:assert";
+
+import * as utxolib from "@bitgo/utxo-lib";
+
+import { Descriptor, fixedScriptWallet } from "../../js/index.js";
+
+type Triple<T> = [T, T, T];
+
+describe("descriptorForChain", function () {
+  const rootWalletKeys = new utxolib.bitgo.RootWalletKeys(
+    utxolib.testutil
+      .getKeyTriple("wasm")
+      .map((k) => k.neutered()) as Triple<utxolib.BIP32Interface>,
+  );
+
+  for (const chain of [0, 1, 10, 11, 20, 21]) {
+    it(`descriptor for chain ${chain} matches the wallet output scripts`, function () {
+      const descriptor = Descriptor.fromString(
+        fixedScriptWallet.descriptorForChain(rootWalletKeys, chain),
+        "derivable",
+      );
+      for (const index of [0, 1, 100]) {
+        assert.deepStrictEqual(
+          Buffer.from(descriptor.atDerivationIndex(index).scriptPubkey()).toString("hex"),
+          Buffer.from(fixedScriptWallet.outputScript(rootWalletKeys, chain, index, "btc")).toString(
+            "hex",
+          ),
+        );
+      }
+    });
+  }
+
+  it("uses key origins and a musig() internal key for p2trMusig2", function () {
+    const [user, backup, bitgo] = rootWalletKeys.triple.map(
+      (xpub) => `[${xpub.fingerprint.toString("hex")}]${xpub.toBase58()}/0/0/40/*`,
+    );
+    assert.strictEqual(
+      fixedScriptWallet.descriptorForChain(rootWalletKeys, 40),
+      `tr(musig(${user},${bitgo}),{and_v(v:pk(${user}),pk(${backup})),` +
+        `and_v(v:pk(${backup}),pk(${bitgo}))})`,
+    );
+  });
+
+  it("throws for chains without a descriptor", function () {
+    for (const chain of [30, 31, 360, 2]) {
+      assert.throws(() => fixedScriptWallet.descriptorForChain(rootWalletKeys, chain));
+    }
+  });
+});
@@ -1,6 +1,7 @@
 import assert from "node:assert";
 import * as utxolib from "@bitgo/utxo-lib";
-import { BIP32, fixedScriptWallet } from "../../js/index.js";
+import { BIP32, Transaction, fixedScriptWallet } from "../../js/index.js";
+import { validateFeeRate } from "../../js/networks.js";
 import type { RootWalletKeys } from "../../js/fixedScriptWallet/RootWalletKeys.js";
 
 function getWalletKeysForSeed(seed: string): RootWalletKeys {
@@ -55,4 +56,89 @@ describe("Dogecoin large output limit amount (LOL amounts) (1-in/1-out)", functi
     const extractedTx = psbt.extractTransaction();
     assert.ok(extractedTx.toBytes().length > 0, "expected extracted tx bytes");
   });
+
+  /** PSBT whose values, spend amount and fee are all above 2^53 */
+  function createLargeAmountPsbt(walletKeys: RootWalletKeys): fixedScriptWallet.BitGoPsbt {
+    const psbt = fixedScriptWallet.BitGoPsbt.createEmpty("dogecoin", walletKeys, {
+      version: 2,
+      lockTime: 0,
+    });
+
+    // None of these values are representable as a JS number
+    const txid = "00".repeat(32);
+    psbt.addWalletInput({ txid, vout: 0, value: 9_010_000_000_000_000_001n }, walletKeys, {
+      scriptId: { chain: 0, index: 0 },
+    });
+    psbt.addWalletOutput(walletKeys, { chain: 1, index: 0, value: 9_000_000_000_000_000_000n });
+    psbt.addOutput({
+      script: new Uint8Array([0x6a, 0x01, 0x00]),
+      value: 9_007_199_254_740_993n,
+    });
+    return psbt;
+  }
+
+  it("should accept bigint amounts above 2^53 without precision loss", function () {
+    const walletKeys = getWalletKeysForSeed("doge_bigint");
+    const psbt = createLargeAmountPsbt(walletKeys);
+
+    const parsed = psbt.parseTransactionWithWalletKeys(walletKeys, {
+      replayProtection: { publicKeys: [] },
+    });
+    assert.strictEqual(parsed.inputs[0].value, 9_010_000_000_000_000_001n);
+    assert.strictEqual(parsed.outputs[0].value, 9_000_000_000_000_000_000n);
+    assert.strictEqual(parsed.outputs[1].value, 9_007_199_254_740_993n);
+    assert.strictEqual(parsed.spendAmount, 9_007_199_254_740_993n);
+    assert.strictEqual(parsed.minerFee, 992_800_745_259_008n);
+  });
+
+  it("should take and return fees above 2^53 as exact bigints", function () {
+    const walletKeys = getWalletKeysForSeed("doge_bigint");
+    const psbt = createLargeAmountPsbt(walletKeys);
+    const minerFee = 992_800_745_259_008n;
+    const replayProtection = { publicKeys: [] };
+
+    const report = psbt.riskReport(walletKeys, { replayProtection, maxFee: minerFee });
+    assert.strictEqual(report.minerFee, minerFee);
+    assert.strictEqual(report.feeAboveThreshold, false);
+    // One satoshi below the fee, which a JS number could not tell apart
+    const below = psbt.riskReport(walletKeys, { replayProtection, maxFee: minerFee - 1n });
+    assert.strictEqual(below.feeAboveThreshold, true);
+
+    const outputValues = psbt.getOutputs().map((output) => output.value);
+    assert.deepStrictEqual(outputValues, [9_000_000_000_000_000_000n, 9_007_199_254_740_993n]);
+    validateFeeRate("doge", minerFee, report.virtualSize, outputValues);
+  });
+
+  it("should keep output values above 2^53 of a transaction", function () {
+    const tx = Transaction.create();
+    tx.addOutput(new Uint8Array([0x6a, 0x01, 0x00]), 9_007_199_254_740_993n);
+    tx.addOutput(new Uint8Array([0x6a, 0x01, 0x01]), 18_446_744_073_709_551_615n);
+    assert.deepStrictEqual(
+      tx.getOutputs().map((output) => output.value),
+      [9_007_199_254_740_993n, 18_446_744_073_709_551_615n],
+    );
+  });
+
+  it("should reject amounts that are not bigints", function () {
+    const walletKeys = getWalletKeysForSeed("doge_bigint");
+    const psbt = fixedScriptWallet.BitGoPsbt.createEmpty("dogecoin", walletKeys);
+    const script = new Uint8Array([0x6a, 0x01, 0x00]);
+
+    const notBigInt: unknown[] = [1000, 9_007_199_254_740_993, "1000", "9007199254740993"];
+    for (const value of notBigInt) {
+      assert.throws(
+        () => psbt.addOutput(script, value as bigint),
+        (e: Error & { code?: string }) => e.code === "AmountError.NotBigInt",
+        `expected ${String(value)} to be rejected`,
+      );
+    }
+    for (const value of [-1n, 18_446_744_073_709_551_616n]) {
+      assert.throws(
+        () => psbt.addOutput(script, value),
+        (e: Error & { code?: string }) => e.code === "AmountError.OutOfRange",
+        `expected ${String(value)} to be rejected`,
+      );
+    }
+    assert.strictEqual(psbt.addOutput(script, 18_446_744_073_709_551_615n), 0);
+  });
 });
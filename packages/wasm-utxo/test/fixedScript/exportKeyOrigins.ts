@@ -0,0 +1,72 @@
+/**
+ * Tests for re-rooting wallet key origins for hardware wallets
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { RootWalletKeys } from "../../js/fixedScriptWallet/RootWalletKeys.js";
+import { getKey, getKeyTriple } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt.exportKeyOrigins", function () {
+  const accountPath = "m/48'/1'/0'/2'";
+  const root = getKey("hardware_wallet");
+  const masterFingerprint = Buffer.from(root.fingerprint).toString("hex");
+  const userKey = root.derivePath(accountPath).neutered();
+  const [, backupKey, bitgoKey] = getKeyTriple("key_origins");
+  const walletKeys = RootWalletKeys.from({
+    triple: [userKey, backupKey.neutered(), bitgoKey.neutered()],
+    derivationPrefixes: ["m/0/0", "m/0/0", "m/0/0"],
+  });
+
+  // Derivation paths may or may not be rendered with the "m/" prefix
+  function getPaths(derivations: { path: string }[]): string[] {
+    return derivations.map((d) => d.path.replace(/^m\//, ""));
+  }
+
+  function createPsbt(): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    psbt.addWalletInput({ txid: "00".repeat(32), vout: 0, value: 10_000n }, walletKeys, {
+      scriptId: { chain: 20, index: 3 },
+    });
+    psbt.addWalletOutput(walletKeys, { chain: 21, index: 4, value: 9_000n });
+    return psbt;
+  }
+
+  it("re-roots derivations of keys with an origin", function () {
+    const psbt = createPsbt();
+    psbt.exportKeyOrigins(walletKeys, [{ masterFingerprint, path: accountPath }, null, null]);
+
+    const inputPaths = getPaths(psbt.getInputs()[0].bip32Derivation);
+    assert.ok(inputPaths.includes("48'/1'/0'/2'/0/0/20/3"), inputPaths.join(", "));
+    assert.ok(inputPaths.includes("0/0/20/3"), inputPaths.join(", "));
+
+    const outputPaths = getPaths(psbt.getOutputs()[0].bip32Derivation);
+    assert.ok(outputPaths.includes("48'/1'/0'/2'/0/0/21/4"), outputPaths.join(", "));
+  });
+
+  it("rejects origins that contradict the xpub", function () {
+    const psbt = createPsbt();
+    assert.throws(
+      () =>
+        psbt.exportKeyOrigins(walletKeys, [
+          { masterFingerprint, path: "m/48'/1'/0'" },
+          null,
+          null,
+        ]),
+      /depth/,
+    );
+    assert.throws(
+      () =>
+        psbt.exportKeyOrigins(walletKeys, [
+          { masterFingerprint, path: "m/48'/1'/0'/1'" },
+          null,
+          null,
+        ]),
+      /child number/,
+    );
+    assert.throws(
+      () => psbt.exportKeyOrigins(walletKeys, [{ masterFingerprint: "zz", path: "m" }, null, null]),
+      /Invalid master fingerprint/,
+    );
+  });
+});
@@ -0,0 +1,58 @@
+/**
+ * Tests for sighash computation for external signers
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt.computeSighashes", function () {
+  const walletKeys = getWalletKeysForSeed("externalSigning");
+
+  function createPsbt(): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    for (const [vout, chain] of [0, 20, 30].entries()) {
+      psbt.addWalletInput({ txid: "00".repeat(32), vout, value: 10_000n }, walletKeys, {
+        scriptId: { chain, index: 0 },
+        signPath: chain === 30 ? { signer: "user", cosigner: "bitgo" } : undefined,
+      });
+    }
+    psbt.addWalletOutput(walletKeys, { chain: 20, index: 1, value: 29_000n });
+    return psbt;
+  }
+
+  it("returns a digest per input and signer", function () {
+    const sighashes = createPsbt().computeSighashes();
+    assert.deepStrictEqual(
+      sighashes.map((s) => [s.inputIndex, s.algorithm, s.sighashType]),
+      [
+        [0, "legacy", 1],
+        [0, "legacy", 1],
+        [0, "legacy", 1],
+        [1, "bip143", 1],
+        [1, "bip143", 1],
+        [1, "bip143", 1],
+        [2, "bip341", 0],
+        [2, "bip341", 0],
+      ],
+    );
+    for (const s of sighashes) {
+      assert.strictEqual(s.digest.length, 32);
+      assert.strictEqual(s.pubkey.length, s.algorithm === "bip341" ? 32 : 33);
+      assert.strictEqual(s.leafHash === null, s.algorithm !== "bip341");
+    }
+  });
+
+  it("rejects signatures that do not verify", function () {
+    const psbt = createPsbt();
+    const [sighash] = psbt.computeSighashes();
+    assert.throws(
+      () => psbt.insertExternalSignature(0, sighash.pubkey, new Uint8Array(64).fill(1), 1),
+      /does not verify/,
+    );
+    assert.throws(
+      () => psbt.insertExternalSignature(0, new Uint8Array(33).fill(2), new Uint8Array(64), 1),
+      /does not sign input 0/,
+    );
+  });
+});
@@ -74,6 +74,14 @@ describe("finalize and extract transaction", function () {
         );
       });
 
+      it("should verify the input scripts of the finalized PSBT", function () {
+        const psbt = fixedScriptWallet.BitGoPsbt.fromBytes(fullsignedPsbtBuffer, networkName);
+        assert.throws(() => psbt.verifyInputScripts(), /not finalized/);
+
+        psbt.finalizeAllInputs();
+        psbt.verifyInputScripts();
+      });
+
       it("should extract transaction from finalized PSBT", function () {
         // Create a fresh instance for extraction
         const psbt = fixedScriptWallet.BitGoPsbt.fromBytes(fullsignedPsbtBuffer, networkName);
@@ -95,6 +103,48 @@ describe("finalize and extract transaction", function () {
         );
       });
 
+      it("should re-check invariants taken before finalization", function () {
+        const psbt = fixedScriptWallet.BitGoPsbt.fromBytes(fullsignedPsbtBuffer, networkName);
+        const invariants = psbt.extractInvariants();
+        assert.strictEqual(invariants.inputs.length, psbt.inputCount());
+
+        psbt.finalizeAllInputs();
+        const extractedTx = psbt.extractTransaction(undefined, invariants);
+        assert.strictEqual(
+          Buffer.from(extractedTx.toBytes()).toString("hex"),
+          getExtractedTransactionHex(fullsignedFixture),
+        );
+
+        assert.throws(
+          () => psbt.extractTransaction(undefined, { ...invariants, fee: invariants.fee + 1n }),
+          (e: Error & { code?: string }) => {
+            assert.strictEqual(e.code, "ExtractInvariantError.FeeChanged");
+            assert.match(e.message, /Fee changed since parsing/);
+            return true;
+          },
+        );
+        assert.throws(
+          () => psbt.extractTransaction(undefined, { ...invariants, outputsHash: "00".repeat(32) }),
+          /Outputs changed since parsing/,
+        );
+        assert.throws(
+          () => psbt.extractTransaction(undefined, { ...invariants, inputs: [] }),
+          /was not part of the parsed transaction/,
+        );
+      });
+
+      it("should have the same approval digest before and after signing", async function () {
+        const unsignedFixture = await loadPsbtFixture(networkName, "unsigned");
+        const unsigned = fixedScriptWallet.BitGoPsbt.fromBytes(
+          getPsbtBuffer(unsignedFixture),
+          networkName,
+        );
+        const digest = unsigned.approvalDigest();
+        assert.ok(digest instanceof Uint8Array);
+        assert.strictEqual(digest.length, 32);
+        assert.deepStrictEqual(fullsignedBitgoPsbt.approvalDigest(), digest);
+      });
+
       it("should have extracted transaction with valid getId()", function () {
         const psbt = fixedScriptWallet.BitGoPsbt.fromBytes(fullsignedPsbtBuffer, networkName);
 
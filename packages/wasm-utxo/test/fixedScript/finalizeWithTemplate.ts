@@ -0,0 +1,55 @@
+/**
+ * Tests for finalizing inputs with custom scriptSig and witness templates
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt.finalizeInputWithTemplate", function () {
+  const walletKeys = getWalletKeysForSeed("finalize_with_template");
+
+  function createPsbt(chain: number): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    psbt.addWalletInput({ txid: "00".repeat(32), vout: 0, value: 10_000n }, walletKeys, {
+      scriptId: { chain, index: 0 },
+    });
+    return psbt;
+  }
+
+  it("builds the scriptSig from the template", function () {
+    const psbt = createPsbt(0);
+    psbt.finalizeInputWithTemplate(0, {
+      scriptSig: [{ type: "data", data: new Uint8Array() }, { type: "redeemScript" }],
+    });
+    // The template output is not checked against the prevout script
+    assert.throws(() => psbt.verifyInputScripts());
+  });
+
+  it("rejects missing signatures and scripts", function () {
+    const psbt = createPsbt(0);
+    assert.throws(
+      () =>
+        psbt.finalizeInputWithTemplate(0, {
+          scriptSig: [{ type: "signature", pubkey: walletKeys.userKey().publicKey }],
+        }),
+      /No partial signature/,
+    );
+    assert.throws(
+      () => psbt.finalizeInputWithTemplate(0, { witness: [{ type: "witnessScript" }] }),
+      /Input has no witness script/,
+    );
+    assert.throws(() => psbt.finalizeInputWithTemplate(1, { witness: [] }), /out of bounds/);
+  });
+
+  it("rejects unknown item types", function () {
+    const psbt = createPsbt(20);
+    assert.throws(
+      () =>
+        psbt.finalizeInputWithTemplate(0, {
+          witness: [{ type: "locktime" } as unknown as { type: "witnessScript" }],
+        }),
+      /Unknown template item type/,
+    );
+  });
+});
@@ -0,0 +1,78 @@
+/**
+ * Tests for replay detection of a spend prepared for two forked networks
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { ECPair } from "../../js/ecpair.js";
+import { getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt fork replay protection", function () {
+  const walletKeys = getWalletKeysForSeed("fork_replay");
+  const replayProtectionKey = ECPair.fromPrivateKey(new Uint8Array(32).fill(1));
+  const replayProtection = { publicKeys: [replayProtectionKey] };
+
+  function createPsbt(network: "bch" | "bsv" | "btc"): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty(network, walletKeys, { version: 2, lockTime: 0 });
+    psbt.addWalletInput({ txid: "00".repeat(32), vout: 0, value: 10_000n }, walletKeys, {
+      scriptId: { chain: 0, index: 0 },
+    });
+    psbt.addWalletOutput(walletKeys, { chain: 1, index: 0, value: 9_000n });
+    return psbt;
+  }
+
+  it("detects replayable spends", function () {
+    const report = createPsbt("bch").forkReplayReport(createPsbt("bsv"), replayProtection);
+    assert.strictEqual(report.sharedInputs.length, 1);
+    assert.deepStrictEqual(report.sharedOutputs, [0]);
+    assert.strictEqual(report.sharedSighash, true);
+    assert.strictEqual(report.replayProtected, false);
+    assert.strictEqual(report.replayable, true);
+  });
+
+  it("does not report replay across different sighash algorithms", function () {
+    const report = createPsbt("btc").forkReplayReport(createPsbt("bch"), replayProtection);
+    assert.strictEqual(report.sharedSighash, false);
+    assert.strictEqual(report.replayable, false);
+  });
+
+  it("requires a signed replay protection input on the fork side", function () {
+    const bch = createPsbt("bch");
+    const bsv = createPsbt("bsv");
+    assert.throws(
+      () => bch.verifyForkReplayProtection(bsv, replayProtection),
+      /does not spend a replay protection input/,
+    );
+
+    const inputIndex = bsv.addReplayProtectionInput(
+      { txid: "01".repeat(32), vout: 0, value: 1_000n },
+      replayProtectionKey,
+    );
+    assert.throws(() => bch.verifyForkReplayProtection(bsv, replayProtection), /is not signed/);
+
+    assert.deepStrictEqual(bsv.sign(replayProtectionKey), [inputIndex]);
+    const report = bch.verifyForkReplayProtection(bsv, replayProtection);
+    assert.strictEqual(report.replayProtected, true);
+    assert.strictEqual(report.replayable, false);
+    assert.deepStrictEqual(
+      report.replayProtectionInputs.map((input) => [input.inputIndex, input.signed]),
+      [[inputIndex, true]],
+    );
+  });
+
+  it("clones a template for a fork network", function () {
+    const btc = createPsbt("btc");
+    const bch = btc.cloneForNetwork("bch");
+    assert.strictEqual(bch.unsignedTxId(), btc.unsignedTxId());
+    assert.deepStrictEqual(bch.serialize(), createPsbt("bch").serialize());
+    assert.deepStrictEqual(bch.cloneForNetwork("btc").serialize(), btc.serialize());
+    assert.throws(() => btc.cloneForNetwork("ltc"), /do not share chain history/);
+  });
+
+  it("rejects unrelated networks", function () {
+    assert.throws(
+      () => createPsbt("btc").forkReplayReport(createPsbt("btc"), replayProtection),
+      /do not share chain history/,
+    );
+  });
+});
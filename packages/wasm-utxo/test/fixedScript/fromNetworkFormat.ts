@@ -232,6 +232,7 @@ describe("BitGoPsbt.fromNetworkFormat", function () {
       const rootWalletKeys = getDefaultWalletKeys();
       const { txBytes, unspents } = createSignedP2msPsbt("zec", 1);
       const tx = ZcashTransaction.fromBytes(txBytes);
+      assert.strictEqual(tx.hasShieldedComponents(), false);
       const reconstructed = ZcashBitGoPsbt.fromNetworkFormat(tx, "zec", rootWalletKeys, unspents, {
         blockHeight: ZCASH_NU5_HEIGHT,
       });
@@ -0,0 +1,62 @@
+/**
+ * Tests for wrapping an externally created unsigned transaction
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt.fromUnsignedTransaction", function () {
+  const walletKeys = getWalletKeysForSeed("fromUnsignedTransaction");
+  const signPath = { signer: "user", cosigner: "bitgo" } as const;
+
+  function createPsbt(): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    psbt.addWalletInput({ txid: "00".repeat(32), vout: 0, value: 10_000n }, walletKeys, {
+      scriptId: { chain: 20, index: 0 },
+    });
+    psbt.addWalletInput({ txid: "00".repeat(32), vout: 1, value: 20_000n }, walletKeys, {
+      scriptId: { chain: 40, index: 1 },
+      signPath,
+    });
+    psbt.addWalletOutput(walletKeys, { chain: 20, index: 2, value: 29_000n });
+    return psbt;
+  }
+
+  it("restores the metadata of the inputs", function () {
+    const expected = createPsbt();
+    const psbt = BitGoPsbt.fromUnsignedTransaction(
+      expected.getUnsignedTransaction(),
+      "btc",
+      walletKeys,
+    );
+    assert.strictEqual(psbt.unsignedTxId(), expected.unsignedTxId());
+
+    psbt.enrichInput(0, walletKeys, { scriptId: { chain: 20, index: 0 }, value: 10_000n });
+    assert.throws(
+      () => psbt.enrichInput(1, walletKeys, { scriptId: { chain: 40, index: 1 }, value: 20_000n }),
+      /sign_path is required/,
+    );
+    psbt.enrichInput(1, walletKeys, {
+      scriptId: { chain: 40, index: 1 },
+      signPath,
+      value: 20_000n,
+    });
+    assert.throws(
+      () => psbt.enrichInput(2, walletKeys, { scriptId: { chain: 20, index: 0 }, value: 1n }),
+      /out of bounds/,
+    );
+
+    const parsed = psbt.parseTransactionWithWalletKeys(walletKeys, {
+      replayProtection: { publicKeys: [] },
+    });
+    assert.strictEqual(parsed.minerFee, 1_000n);
+    assert.deepStrictEqual(
+      parsed.inputs.map((input) => input.scriptId),
+      [
+        { chain: 20, index: 0 },
+        { chain: 40, index: 1 },
+      ],
+    );
+  });
+});
@@ -0,0 +1,116 @@
+import assert from "node:assert";
+import { fixedScriptWallet } from "../../js/index.js";
+import { getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+// Funding keys of the BOLT 3 test vectors (Appendix B)
+const LOCAL_FUNDING_PUBKEY = Buffer.from(
+  "023da092f6980e58d2c037173180e9a465476026ee50f96695963e8efe436f54eb",
+  "hex",
+);
+const REMOTE_FUNDING_PUBKEY = Buffer.from(
+  "030e9f7b623d2ccc7c9bd44d66d5ce21ce504c0acf6385a132cec6d3c39fa711c1",
+  "hex",
+);
+const PUBKEYS: [Uint8Array, Uint8Array] = [REMOTE_FUNDING_PUBKEY, LOCAL_FUNDING_PUBKEY];
+
+describe("funding outputs", function () {
+  it("should match the BOLT 3 funding witness script and output", function () {
+    assert.strictEqual(
+      Buffer.from(fixedScriptWallet.fundingWitnessScript(PUBKEYS)).toString("hex"),
+      "5221023da092f6980e58d2c037173180e9a465476026ee50f96695963e8efe436f54eb21030e9f7b" +
+        "623d2ccc7c9bd44d66d5ce21ce504c0acf6385a132cec6d3c39fa711c152ae",
+    );
+    const script = fixedScriptWallet.fundingOutputScript("p2wsh", PUBKEYS, "btc");
+    assert.strictEqual(
+      Buffer.from(script).toString("hex"),
+      "0020c015c4a6be010e21657068fc2e6a9d02b27ebe4d490a25846f7237f104d1a3cd",
+    );
+    assert.strictEqual(fixedScriptWallet.classifyFundingOutput(script, PUBKEYS), "p2wsh");
+  });
+
+  it("should classify p2trMusig2 funding outputs", function () {
+    const script = fixedScriptWallet.fundingOutputScript("p2trMusig2", PUBKEYS, "btc");
+    assert.strictEqual(fixedScriptWallet.classifyFundingOutput(script, PUBKEYS), "p2trMusig2");
+    const other = fixedScriptWallet.p2shP2pkOutputScript(LOCAL_FUNDING_PUBKEY);
+    assert.strictEqual(fixedScriptWallet.classifyFundingOutput(other, PUBKEYS), null);
+  });
+
+  it("should reject invalid keys and unknown types", function () {
+    assert.throws(
+      () => fixedScriptWallet.fundingOutputScript("p2wsh", [PUBKEYS[0], PUBKEYS[0]], "btc"),
+      (e: Error & { code?: string }) => {
+        assert.strictEqual(e.code, "FundingOutputError.DuplicateKeys");
+        return true;
+      },
+    );
+    assert.throws(
+      () => fixedScriptWallet.fundingOutputScript("p2wsh", [PUBKEYS[0], new Uint8Array(33)], "btc"),
+      (e: Error & { code?: string }) => {
+        assert.strictEqual(e.code, "FundingOutputError.InvalidPublicKey");
+        return true;
+      },
+    );
+    assert.throws(
+      () =>
+        fixedScriptWallet.fundingOutputScript(
+          "p2sh" as fixedScriptWallet.FundingOutputType,
+          PUBKEYS,
+          "btc",
+        ),
+      (e: Error & { code?: string }) => {
+        assert.strictEqual(e.code, "FundingOutputError.UnknownType");
+        return true;
+      },
+    );
+  });
+
+  for (const type of ["p2wsh", "p2trMusig2"] as const) {
+    it(`should round-trip ${type} funding metadata through a PSBT`, function () {
+      const walletKeys = getWalletKeysForSeed("funding_output");
+      const script = fixedScriptWallet.fundingOutputScript(type, PUBKEYS, "btc");
+      const keySources = [
+        { pubkey: LOCAL_FUNDING_PUBKEY, masterFingerprint: "deadbeef", path: "m/1017'/0'/0'" },
+      ];
+
+      const funding = fixedScriptWallet.BitGoPsbt.createEmpty("btc", walletKeys);
+      funding.addOutput(script, 100_000n);
+      assert.strictEqual(funding.getFundingOutput(0), null);
+      funding.setFundingOutput(0, type, PUBKEYS, keySources);
+
+      const spend = fixedScriptWallet.BitGoPsbt.createEmpty("btc", walletKeys);
+      spend.addInput({ txid: "00".repeat(32), vout: 0, value: 100_000n }, script);
+      spend.setFundingInput(0, type, PUBKEYS, keySources);
+
+      const sortedKeys = [LOCAL_FUNDING_PUBKEY, REMOTE_FUNDING_PUBKEY].map(
+        (key) => new Uint8Array(key),
+      );
+      for (const psbt of [funding, spend]) {
+        const restored = fixedScriptWallet.BitGoPsbt.fromBytes(psbt.serialize(), "btc");
+        const found = psbt === funding ? restored.getFundingOutput(0) : restored.getFundingInput(0);
+        assert.ok(found);
+        assert.strictEqual(found.type, type);
+        assert.deepStrictEqual(found.pubkeys.map((key) => new Uint8Array(key)), sortedKeys);
+      }
+    });
+  }
+
+  it("should reject metadata for an output with another script", function () {
+    const walletKeys = getWalletKeysForSeed("funding_output");
+    const psbt = fixedScriptWallet.BitGoPsbt.createEmpty("btc", walletKeys);
+    psbt.addOutput(fixedScriptWallet.fundingOutputScript("p2wsh", PUBKEYS, "btc"), 100_000n);
+    assert.throws(
+      () => psbt.setFundingOutput(0, "p2trMusig2", PUBKEYS),
+      (e: Error & { code?: string }) => {
+        assert.strictEqual(e.code, "FundingOutputError.ScriptMismatch");
+        return true;
+      },
+    );
+    assert.throws(
+      () => psbt.setFundingOutput(1, "p2wsh", PUBKEYS),
+      (e: Error & { code?: string }) => {
+        assert.strictEqual(e.code, "FundingOutputError.IndexOutOfBounds");
+        return true;
+      },
+    );
+  });
+});
@@ -1,7 +1,7 @@
 import { AcidTest, type TxFormat } from "../../js/testutils/AcidTest.js";
 import { getKeyTriple, getWalletKeysForSeed } from "../../js/testutils/keys.js";
 import { ECPair } from "../../js/ecpair.js";
-import { BitGoPsbt, type InputScriptType } from "../../js/fixedScriptWallet/index.js";
+import { BitGoPsbt, type ParsedInput } from "../../js/fixedScriptWallet/index.js";
 import type { CoinName } from "../../js/coinName.js";
 import { RootWalletKeys } from "../../js/fixedScriptWallet/RootWalletKeys.js";
 import type {
@@ -17,7 +17,7 @@ export function createOtherWalletKeys(): RootWalletKeys {
   return getWalletKeysForSeed("too many secrets");
 }
 
-function toFixtureType(scriptType: InputScriptType): string {
+function toFixtureType(scriptType: ParsedInput["scriptType"]): string {
   switch (scriptType) {
     case "p2sh":
     case "p2shP2wsh":
@@ -198,6 +198,35 @@ describe("getHalfSignedLegacyFormat", function () {
     });
   });
 
+  describe("Compact layout", function () {
+    it("leaves out the placeholders of the missing signatures", function () {
+      const psbt = createHalfSignedP2msPsbt(utxolib.networks.bitcoin);
+      const tx = utxolib.bitgo.createTransactionFromBuffer(
+        Buffer.from(psbt.getHalfSignedLegacyFormat({ layout: "compact" })),
+        utxolib.networks.bitcoin,
+        { amountType: "bigint" },
+      );
+
+      for (let i = 0; i < tx.ins.length; i++) {
+        const input = tx.ins[i];
+        // [OP_0, sig, script], in the witness for p2shP2wsh and p2wsh
+        const items =
+          input.witness.length > 0 ? input.witness : utxolib.script.decompile(input.script);
+        assert.ok(items);
+        assert.strictEqual(items.length, 3, `Input ${i}: expected dummy, signature and script`);
+        assert.ok(Buffer.isBuffer(items[1]) && items[1].length > 0, `Input ${i}: signature`);
+      }
+    });
+
+    it("rejects unknown layouts", function () {
+      const psbt = createHalfSignedP2msPsbt(utxolib.networks.bitcoin);
+      assert.throws(
+        () => psbt.getHalfSignedLegacyFormat({ layout: "sparse" as "compact" }),
+        /Unknown half-signed layout/,
+      );
+    });
+  });
+
   describe("Error handling", function () {
     it("should throw descriptive error for empty PSBT", function () {
       const rootWalletKeys = getDefaultWalletKeys();
@@ -0,0 +1,100 @@
+import assert from "node:assert";
+
+import * as utxolib from "@bitgo/utxo-lib";
+
+import { fixedScriptWallet } from "../../js/index.js";
+
+type Triple<T> = [T, T, T];
+
+describe("importCoreUnspents", function () {
+  const rootWalletKeys = new utxolib.bitgo.RootWalletKeys(
+    utxolib.testutil
+      .getKeyTriple("wasm")
+      .map((k) => k.neutered()) as Triple<utxolib.BIP32Interface>,
+  );
+  const txid = "00".repeat(31) + "01";
+
+  /** Build a `listunspent` entry in the form Bitcoin Core reports for a p2wsh wallet script */
+  function getCoreUnspent(chain: number, index: number): fixedScriptWallet.CoreUnspent {
+    const derived = rootWalletKeys.deriveForChainAndIndex(chain, index);
+    const keys = rootWalletKeys.triple.map(
+      (xpub, i) =>
+        `[${xpub.fingerprint.toString("hex")}/0/0/${chain}/${index}]` +
+        derived.publicKeys[i].toString("hex"),
+    );
+    const scriptPubKey = fixedScriptWallet.outputScript(rootWalletKeys, chain, index, "btc");
+    return {
+      txid,
+      vout: 3,
+      scriptPubKey: Buffer.from(scriptPubKey).toString("hex"),
+      value: 120_000n,
+      desc: `wsh(multi(2,${keys.join(",")}))`,
+    };
+  }
+
+  it("recovers the script id from the descriptor key origins", function () {
+    const [imported] = fixedScriptWallet.importCoreUnspents(
+      rootWalletKeys,
+      [getCoreUnspent(20, 7)],
+      "btc",
+    );
+    assert.deepStrictEqual(imported, {
+      input: { txid, vout: 3, value: 120_000n },
+      scriptId: { chain: 20, index: 7 },
+      scriptType: "p2wsh",
+    });
+  });
+
+  it("keeps values above 2^53 exact", function () {
+    const [imported] = fixedScriptWallet.importCoreUnspents(
+      rootWalletKeys,
+      [{ ...getCoreUnspent(21, 0), value: 9_007_199_254_740_993n }],
+      "btc",
+    );
+    assert.strictEqual(imported.input.value, 9_007_199_254_740_993n);
+  });
+
+  it("rejects values that are not bigints", function () {
+    assert.throws(
+      () =>
+        fixedScriptWallet.importCoreUnspents(
+          rootWalletKeys,
+          [{ ...getCoreUnspent(20, 7), value: 0.0012 as unknown as bigint }],
+          "btc",
+        ),
+      /Expected a bigint amount/,
+    );
+  });
+
+  it("rejects unspents that do not belong to the wallet", function () {
+    const unspent = getCoreUnspent(20, 7);
+    const other = getCoreUnspent(20, 8);
+    assert.throws(
+      () =>
+        fixedScriptWallet.importCoreUnspents(
+          rootWalletKeys,
+          [{ ...unspent, scriptPubKey: other.scriptPubKey }],
+          "btc",
+        ),
+      /Unspent 0: Wallet script at chain 20 index 7 does not match/,
+    );
+    assert.throws(
+      () =>
+        fixedScriptWallet.importCoreUnspents(
+          rootWalletKeys,
+          [{ ...unspent, desc: undefined }],
+          "btc",
+        ),
+      /no descriptor/,
+    );
+    assert.throws(
+      () =>
+        fixedScriptWallet.importCoreUnspents(
+          rootWalletKeys,
+          [{ ...unspent, desc: `${unspent.desc}#qqqqqqqq` }],
+          "btc",
+        ),
+      /checksum/,
+    );
+  });
+});
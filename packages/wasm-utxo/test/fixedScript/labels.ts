@@ -0,0 +1,58 @@
+/**
+ * Tests for input and output labels stored in BitGo proprietary PSBT fields
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt labels", function () {
+  const walletKeys = getWalletKeysForSeed("labels");
+
+  function createPsbt(): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    psbt.addWalletInput({ txid: "00".repeat(32), vout: 0, value: 10_000n }, walletKeys, {
+      scriptId: { chain: 0, index: 0 },
+    });
+    psbt.addOutput("1BoatSLRHtKNngkdXEeobR76b53LETtpyT", 9_000n);
+    return psbt;
+  }
+
+  it("round-trips labels through serialization", function () {
+    const psbt = createPsbt();
+    assert.strictEqual(psbt.getInputLabel(0), null);
+    assert.strictEqual(psbt.getOutputLabel(0), null);
+
+    psbt.setInputLabel(0, "consolidation");
+    psbt.setOutputLabel(0, "customer-withdrawal:1234");
+    const restored = BitGoPsbt.fromBytes(psbt.serialize(), "btc");
+    assert.strictEqual(restored.getInputLabel(0), "consolidation");
+    assert.strictEqual(restored.getOutputLabel(0), "customer-withdrawal:1234");
+
+    restored.setInputLabel(0, null);
+    assert.strictEqual(restored.getInputLabel(0), null);
+  });
+
+  it("surfaces labels in the parsed transaction", function () {
+    const psbt = createPsbt();
+    psbt.setInputLabel(0, "consolidation");
+    psbt.setOutputLabel(0, "customer-withdrawal:1234");
+    const parsed = psbt.parseTransactionWithWalletKeys(walletKeys, {
+      replayProtection: { publicKeys: [] },
+    });
+    assert.strictEqual(parsed.inputs[0].label, "consolidation");
+    assert.strictEqual(parsed.outputs[0].label, "customer-withdrawal:1234");
+  });
+
+  it("rejects out-of-range indices", function () {
+    const psbt = createPsbt();
+    assert.throws(
+      () => psbt.setInputLabel(1, "x"),
+      (e: Error & { code?: string }) => e.code === "LabelError.InputIndexOutOfBounds",
+    );
+    assert.throws(
+      () => psbt.getOutputLabel(1),
+      (e: Error & { code?: string }) => e.code === "LabelError.OutputIndexOutOfBounds",
+    );
+  });
+});
@@ -0,0 +1,37 @@
+/**
+ * Tests for listing the previous transactions that non-segwit inputs are missing
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { ZcashBitGoPsbt } from "../../js/fixedScriptWallet/ZcashBitGoPsbt.js";
+import { getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+const ZCASH_NU5_HEIGHT = 1687105;
+
+describe("BitGoPsbt.missingNonWitnessUtxos", function () {
+  const walletKeys = getWalletKeysForSeed("missing_non_witness_utxos");
+
+  it("lists non-segwit inputs without a previous transaction", function () {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    const txids = ["11", "22", "33"].map((b) => b.repeat(32));
+    for (const [i, chain] of [0, 10, 20].entries()) {
+      psbt.addWalletInput({ txid: txids[i], vout: 0, value: 10_000n }, walletKeys, {
+        scriptId: { chain, index: 0 },
+      });
+    }
+    assert.deepStrictEqual(psbt.missingNonWitnessUtxos(), [{ inputIndex: 0, txid: txids[0] }]);
+  });
+
+  it("is empty for Zcash", function () {
+    const psbt = ZcashBitGoPsbt.createEmpty("zec", walletKeys, {
+      version: 4,
+      lockTime: 0,
+      blockHeight: ZCASH_NU5_HEIGHT,
+    });
+    psbt.addWalletInput({ txid: "00".repeat(32), vout: 0, value: 10_000n }, walletKeys, {
+      scriptId: { chain: 0, index: 0 },
+    });
+    assert.deepStrictEqual(psbt.missingNonWitnessUtxos(), []);
+  });
+});
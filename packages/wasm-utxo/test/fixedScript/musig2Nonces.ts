@@ -1,5 +1,7 @@
 import assert from "assert";
 import { BIP32 } from "../../js/bip32.js";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { getKeyTriple, getWalletKeysForSeed } from "../../js/testutils/keys.js";
 import { loadPsbtFixture, getBitGoPsbt, type Fixture } from "./fixtureUtil.js";
 
 describe("MuSig2 nonce management", function () {
@@ -41,6 +43,17 @@ describe("MuSig2 nonce management", function () {
       }, "Should throw error when generating nonces for backup key");
     });
 
+    it("should not reorder inputs or outputs after generating nonces", function () {
+      const psbt = getBitGoPsbt(fixture, networkName);
+      psbt.generateMusig2Nonces(userKey);
+      assert.throws(() => psbt.sortBip69(), /MuSig2 nonces/);
+      assert.throws(() => psbt.shuffleOutputs(new Uint8Array(32)), /MuSig2 nonces/);
+
+      // Nonces received from the counterparty are bound to the order as well
+      const withNonces = BitGoPsbt.fromBytes(psbt.serialize(), networkName);
+      assert.throws(() => withNonces.sortBip69(), /MuSig2 nonces/);
+    });
+
     it("implements combineMusig2Nonces", function () {
       const unsignedBitgoPsbtWithUserNonces = getBitGoPsbt(fixture, networkName);
       unsignedBitgoPsbtWithUserNonces.generateMusig2Nonces(userKey);
@@ -105,5 +118,94 @@ describe("MuSig2 nonce management", function () {
         "Should throw error when providing custom session_id on mainnet",
       );
     });
+
+    it("exports and imports MuSig2 sessions", function () {
+      const psbt = getBitGoPsbt(fixture, networkName);
+      assert.throws(() => psbt.exportMusig2Session("pw"), /acknowledge_risk/);
+      assert.throws(
+        () => psbt.exportMusig2Session("pw", { acknowledgeRisk: true }),
+        /No MuSig2 session to export/,
+      );
+
+      psbt.generateMusig2Nonces(userKey);
+      psbt.generateMusig2Nonces(bitgoKey);
+      const session = psbt.exportMusig2Session("pw", { acknowledgeRisk: true });
+
+      const restored = BitGoPsbt.fromBytes(psbt.serialize(), networkName);
+      assert.throws(
+        () => restored.importMusig2Session(session, "pw", userKey),
+        /acknowledge_risk/,
+      );
+      assert.throws(
+        () => restored.importMusig2Session(session, "wrong", userKey, { acknowledgeRisk: true }),
+        (e: Error & { code?: string }) => {
+          assert.strictEqual(e.code, "Musig2SessionError.AuthenticationFailed");
+          return true;
+        },
+      );
+      const userInputs = restored.importMusig2Session(session, "pw", userKey, {
+        acknowledgeRisk: true,
+      });
+      assert.ok(userInputs.length > 0);
+      const bitgoInputs = restored.importMusig2Session(session, "pw", bitgoKey, {
+        acknowledgeRisk: true,
+      });
+      assert.deepStrictEqual(bitgoInputs, userInputs);
+      assert.throws(
+        () => restored.importMusig2Session(session, "pw", userKey, { acknowledgeRisk: true }),
+        (e: Error & { code?: string }) => {
+          assert.strictEqual(e.code, "Musig2SessionError.AlreadyImported");
+          return true;
+        },
+      );
+
+      // The restored FirstRounds can be used for signing
+      assert.doesNotThrow(() => restored.sign(userKey));
+      assert.doesNotThrow(() => restored.sign(bitgoKey));
+
+      // A session whose signer has signed cannot be imported again
+      const signed = BitGoPsbt.fromBytes(restored.serialize(), networkName);
+      assert.throws(
+        () => signed.importMusig2Session(session, "pw", userKey, { acknowledgeRisk: true }),
+        (e: Error & { code?: string }) => {
+          assert.strictEqual(e.code, "Musig2SessionError.AlreadySigned");
+          return true;
+        },
+      );
+    });
+  });
+
+  describe("stale nonces", function () {
+    const [userKey, , bitgoKey] = getKeyTriple("stale_nonces");
+    const walletKeys = getWalletKeysForSeed("stale_nonces");
+
+    function createPsbt(index: number): BitGoPsbt {
+      const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+      psbt.addWalletInput({ txid: "00".repeat(32), vout: 0, value: 10_000n }, walletKeys, {
+        scriptId: { chain: 40, index },
+        signPath: { signer: "user", cosigner: "bitgo" },
+      });
+      psbt.addOutput("1BoatSLRHtKNngkdXEeobR76b53LETtpyT", 9_000n);
+      return psbt;
+    }
+
+    it("skips nonces made for a replaced input", function () {
+      const replaced = createPsbt(0);
+      replaced.generateMusig2Nonces(userKey);
+
+      const psbt = createPsbt(1);
+      const unsignedLength = psbt.serialize().length;
+      const stale = psbt.combineMusig2Nonces(replaced);
+      assert.strictEqual(stale.length, 1);
+      assert.strictEqual(stale[0].inputIndex, 0);
+      assert.strictEqual(stale[0].reason, "tapOutputKeyMismatch");
+      assert.strictEqual(psbt.serialize().length, unsignedLength);
+      assert.deepStrictEqual(psbt.purgeStaleMusig2Nonces(), []);
+
+      const current = createPsbt(1);
+      current.generateMusig2Nonces(bitgoKey);
+      assert.deepStrictEqual(psbt.combineMusig2Nonces(current), []);
+      assert.ok(psbt.serialize().length > unsignedLength);
+    });
   });
 });
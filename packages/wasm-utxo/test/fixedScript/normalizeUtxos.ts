@@ -0,0 +1,44 @@
+/**
+ * Tests for backfilling witness_utxo from non_witness_utxo
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { outputScript } from "../../js/fixedScriptWallet/index.js";
+import { Transaction } from "../../js/transaction.js";
+import { getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt.normalizeUtxos", function () {
+  const walletKeys = getWalletKeysForSeed("normalize_utxos");
+  const prevTx = Transaction.create();
+  prevTx.addInput("00".repeat(32), 0);
+  prevTx.addOutput(outputScript(walletKeys, 0, 0, "btc"), 10_000n);
+
+  function createPsbt(txid: string): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    psbt.addWalletInput(
+      { txid, vout: 0, value: 10_000n, prevTx: prevTx.toBytes() },
+      walletKeys,
+      { scriptId: { chain: 0, index: 0 } },
+    );
+    return psbt;
+  }
+
+  it("backfills witness_utxo once", function () {
+    const psbt = createPsbt(prevTx.getId());
+    assert.deepStrictEqual(psbt.normalizeUtxos(), [0]);
+    assert.deepStrictEqual(psbt.normalizeUtxos(), []);
+  });
+
+  it("rejects a previous transaction that does not match the outpoint", function () {
+    const psbt = createPsbt("11".repeat(32));
+    assert.throws(
+      () => psbt.normalizeUtxos(),
+      (e: Error & { code?: string }) => {
+        assert.strictEqual(e.code, "UtxoNormalizeError.TxidMismatch");
+        assert.match(e.message, /does not match outpoint txid/);
+        return true;
+      },
+    );
+  });
+});
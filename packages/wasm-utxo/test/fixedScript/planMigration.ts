@@ -0,0 +1,81 @@
+/**
+ * Tests for the p2sh/p2shP2wsh to p2trMusig2 migration planner
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { planMigration } from "../../js/fixedScriptWallet/migration.js";
+import { getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("planMigration", function () {
+  const walletKeys = getWalletKeysForSeed("migration");
+
+  function unspent(vout: number, chain: number, value: bigint) {
+    return {
+      input: { txid: "00".repeat(32), vout, value },
+      scriptId: { chain, index: vout },
+    };
+  }
+
+  it("splits the unspents and moves them to chain 41", function () {
+    const unspents = [0, 1, 10, 11, 0].map((chain, vout) => unspent(vout, chain, 100_000n));
+    const psbts = planMigration(walletKeys, unspents, "btc", {
+      maxInputs: 2,
+      feeRate: 2,
+      changeIndex: 5,
+    });
+    assert.deepStrictEqual(psbts.map((psbt) => psbt.inputCount()), [2, 2, 1]);
+    psbts.forEach((psbt, i) => {
+      const [output] = psbt.parseOutputsWithWalletKeys(walletKeys);
+      assert.deepStrictEqual(output.scriptId, { chain: 41, index: 5 + i });
+      const inputValue = 100_000n * BigInt(psbt.inputCount());
+      assert.ok(output.value < inputValue);
+    });
+  });
+
+  it("rejects segwit and taproot unspents", function () {
+    assert.throws(
+      () =>
+        planMigration(walletKeys, [unspent(0, 20, 100_000n)], "btc", {
+          maxInputs: 10,
+          feeRate: 1,
+          changeIndex: 0,
+        }),
+      (e: Error & { code?: string }) => {
+        assert.strictEqual(e.code, "MigrationError.NotLegacyUnspent");
+        assert.match(e.message, /only p2sh and p2shP2wsh/);
+        return true;
+      },
+    );
+  });
+
+  it("rejects transactions that cannot pay their fee", function () {
+    assert.throws(
+      () =>
+        planMigration(walletKeys, [unspent(0, 0, 1_000n)], "btc", {
+          maxInputs: 10,
+          feeRate: 100,
+          changeIndex: 0,
+        }),
+      (e: Error & { code?: string }) => {
+        assert.strictEqual(e.code, "MigrationError.InsufficientValue");
+        assert.match(e.message, /do not cover the fee/);
+        return true;
+      },
+    );
+  });
+
+  it("rejects a change index that overflows", function () {
+    assert.throws(
+      () =>
+        planMigration(walletKeys, [unspent(0, 0, 100_000n), unspent(1, 0, 100_000n)], "btc", {
+          maxInputs: 1,
+          feeRate: 1,
+          changeIndex: 0xffffffff,
+        }),
+      (e: Error & { code?: string }) => {
+        assert.strictEqual(e.code, "MigrationError.ChangeIndexOverflow");
+        return true;
+      },
+    );
+  });
+});
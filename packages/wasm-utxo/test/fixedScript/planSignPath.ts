@@ -0,0 +1,56 @@
+/**
+ * Tests for selecting the taproot sign path at signing time
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { getKeyTriple, getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt.planSignPath", function () {
+  const [userKey, backupKey, bitgoKey] = getKeyTriple("plan_sign_path");
+  const walletKeys = getWalletKeysForSeed("plan_sign_path");
+  const replayProtection = { publicKeys: [] };
+
+  function createPsbt(): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    psbt.addWalletInput({ txid: "00".repeat(32), vout: 0, value: 10_000n }, walletKeys, {
+      scriptId: { chain: 40, index: 0 },
+      signPath: { signer: "user", cosigner: "bitgo" },
+    });
+    return psbt;
+  }
+
+  function getInputScriptType(psbt: BitGoPsbt): string {
+    return psbt.parseTransactionWithWalletKeys(walletKeys, { replayProtection }).inputs[0]
+      .scriptType;
+  }
+
+  it("moves key path inputs to the script path for a recovery", function () {
+    const psbt = createPsbt();
+    assert.strictEqual(getInputScriptType(psbt), "p2trMusig2KeyPath");
+
+    assert.deepStrictEqual(psbt.planSignPath(walletKeys, [backupKey, userKey]), {
+      signer: "user",
+      cosigner: "backup",
+    });
+    assert.strictEqual(getInputScriptType(psbt), "p2trMusig2ScriptPath");
+
+    psbt.sign(userKey);
+    psbt.sign(backupKey);
+    assert.ok(psbt.verifySignature(0, userKey));
+    assert.ok(psbt.verifySignature(0, backupKey));
+  });
+
+  it("keeps the key path when user and bitgo are available", function () {
+    const psbt = createPsbt();
+    assert.deepStrictEqual(psbt.planSignPath(walletKeys, [userKey, backupKey, bitgoKey]), {
+      signer: "user",
+      cosigner: "bitgo",
+    });
+    assert.strictEqual(getInputScriptType(psbt), "p2trMusig2KeyPath");
+  });
+
+  it("requires two wallet keys", function () {
+    assert.throws(() => createPsbt().planSignPath(walletKeys, [userKey]), /two wallet keys/);
+  });
+});
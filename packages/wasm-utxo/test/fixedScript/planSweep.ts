@@ -0,0 +1,80 @@
+/**
+ * Tests for the sweep planner
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { address } from "../../js/fixedScriptWallet/address.js";
+import { planSweep } from "../../js/fixedScriptWallet/sweep.js";
+import { getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("planSweep", function () {
+  const walletKeys = getWalletKeysForSeed("sweep");
+  const recipient = address(getWalletKeysForSeed("recipient"), 20, 0, "btc");
+
+  function unspent(vout: number, chain: number, value: bigint) {
+    return {
+      input: { txid: "00".repeat(32), vout, value },
+      scriptId: { chain, index: vout },
+    };
+  }
+
+  const unspents = [0, 1, 2, 3, 4].map((vout) => unspent(vout, 0, 100_000n));
+
+  it("sweeps all unspents in a single transaction by default", function () {
+    const plan = planSweep(walletKeys, unspents, "btc", { recipient, feeRate: 2 });
+    assert.strictEqual(plan.psbts.length, 1);
+    assert.strictEqual(plan.psbts[0].inputCount(), 5);
+    const [tx] = plan.transactions;
+    assert.strictEqual(tx.inputValue, 500_000n);
+    assert.strictEqual(tx.fee, BigInt(2 * tx.vsize));
+    assert.strictEqual(plan.outputValue, plan.inputValue - plan.fee);
+  });
+
+  it("splits the unspents to stay below the maximum weight", function () {
+    const [pair] = planSweep(walletKeys, unspents.slice(0, 2), "btc", {
+      recipient,
+      feeRate: 2,
+    }).transactions;
+    const plan = planSweep(walletKeys, unspents, "btc", {
+      recipient,
+      feeRate: 2,
+      maxWeight: pair.weight,
+    });
+    assert.deepStrictEqual(plan.psbts.map((psbt) => psbt.inputCount()), [2, 2, 1]);
+    assert.deepStrictEqual(plan.transactions[0], pair);
+    assert.strictEqual(plan.inputValue, 500_000n);
+    assert.ok(plan.transactions.every((tx) => tx.weight <= pair.weight));
+  });
+
+  it("rejects unspents heavier than the maximum weight", function () {
+    assert.throws(
+      () => planSweep(walletKeys, unspents, "btc", { recipient, feeRate: 1, maxWeight: 100 }),
+      /more than the maximum/,
+    );
+  });
+
+  it("rejects transactions that cannot pay their fee", function () {
+    assert.throws(
+      () => planSweep(walletKeys, [unspent(0, 0, 1_000n)], "btc", { recipient, feeRate: 100 }),
+      /do not cover the fee/,
+    );
+  });
+
+  it("uses the dust limit of the network", function () {
+    const dogeRecipient = address(getWalletKeysForSeed("recipient"), 0, 0, "doge");
+    // Covers the fee and a btc output, but leaves less than 0.01 DOGE
+    assert.throws(
+      () =>
+        planSweep(walletKeys, [unspent(0, 0, 1_000_000n)], "doge", {
+          recipient: dogeRecipient,
+          feeRate: 1,
+        }),
+      /do not cover the fee/,
+    );
+    const plan = planSweep(walletKeys, [unspent(0, 0, 2_000_000n)], "doge", {
+      recipient: dogeRecipient,
+      feeRate: 1,
+    });
+    assert.ok(plan.outputValue >= 1_000_000n);
+  });
+});
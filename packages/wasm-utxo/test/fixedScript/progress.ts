@@ -0,0 +1,144 @@
+/**
+ * Tests for progress callbacks of operations over all inputs
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { setLowRGrinding } from "../../js/fixedScriptWallet/index.js";
+import { getKeyTriple, getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt progress callbacks", function () {
+  const seed = "progress";
+  const walletKeys = getWalletKeysForSeed(seed);
+  const [userKey, , bitgoKey] = getKeyTriple(seed);
+
+  function createPsbt(numInputs: number): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    for (let index = 0; index < numInputs; index++) {
+      psbt.addWalletInput({ txid: "00".repeat(32), vout: index, value: 10_000n }, walletKeys, {
+        scriptId: { chain: 10, index },
+      });
+    }
+    psbt.addWalletOutput(walletKeys, { chain: 20, index: 0, value: 1_000n });
+    return psbt;
+  }
+
+  it("reports progress every progressInterval inputs", function () {
+    const psbt = createPsbt(5);
+    const reports: [number, number][] = [];
+    const options = {
+      onProgress: (processed: number, total: number) => reports.push([processed, total]),
+      progressInterval: 2,
+    };
+    const expected = [
+      [2, 5],
+      [4, 5],
+      [5, 5],
+    ];
+
+    const parsed = psbt.parseTransactionWithWalletKeys(walletKeys, {
+      replayProtection: { publicKeys: [] },
+      ...options,
+    });
+    assert.strictEqual(parsed.inputs.length, 5);
+    assert.deepStrictEqual(reports.splice(0), expected);
+
+    assert.deepStrictEqual(psbt.sign(userKey, options), [0, 1, 2, 3, 4]);
+    assert.deepStrictEqual(reports.splice(0), expected);
+    psbt.sign(bitgoKey, options);
+    reports.splice(0);
+
+    psbt.finalizeAllInputs(options);
+    assert.deepStrictEqual(reports, expected);
+    assert.ok(psbt.extractTransaction().toBytes().length > 0);
+  });
+
+  it("produces the same signatures as signing without a callback", function () {
+    const withProgress = createPsbt(3);
+    const withoutProgress = createPsbt(3);
+    withProgress.sign(userKey, { onProgress: () => undefined, progressInterval: 1 });
+    withoutProgress.sign(userKey);
+    assert.deepStrictEqual(withProgress.serialize(), withoutProgress.serialize());
+  });
+
+  it("cancels between chunks and resumes at the last reported input", function () {
+    const psbt = createPsbt(5);
+    const controller = new AbortController();
+    const onProgress = (processed: number) => {
+      if (processed === 2) {
+        controller.abort();
+      }
+    };
+    assert.throws(
+      () => psbt.sign(userKey, { onProgress, progressInterval: 2, signal: controller.signal }),
+      (e: Error & { code?: string }) => {
+        assert.strictEqual(e.code, "WasmUtxoError.Cancelled");
+        assert.strictEqual(e.message, "Operation cancelled after 2 of 5 inputs");
+        return true;
+      },
+    );
+    assert.deepStrictEqual(psbt.sign(userKey, { progressInterval: 2, startInput: 2 }), [2, 3, 4]);
+
+    const expected = createPsbt(5);
+    expected.sign(userKey);
+    assert.deepStrictEqual(psbt.serialize(), expected.serialize());
+  });
+
+  it("skips the inputs before startInput without other progress options", function () {
+    const psbt = createPsbt(5);
+    assert.deepStrictEqual(psbt.sign(userKey, { startInput: 3 }), [3, 4]);
+    assert.deepStrictEqual(
+      [0, 1, 2, 3, 4].map((index) => psbt.verifySignature(index, userKey)),
+      [false, false, false, true, true],
+    );
+  });
+
+  it("does not start if the signal is already aborted", function () {
+    const psbt = createPsbt(3);
+    const signal = AbortSignal.abort();
+    assert.throws(
+      () =>
+        psbt.parseTransactionWithWalletKeys(walletKeys, {
+          replayProtection: { publicKeys: [] },
+          signal,
+        }),
+      /Operation cancelled after 0 of 3 inputs/,
+    );
+    assert.throws(() => psbt.finalizeAllInputs({ signal }), /cancelled/);
+  });
+
+  it("signs and finalizes with the parallel option like without it", function () {
+    const parallel = createPsbt(5);
+    const sequential = createPsbt(5);
+    assert.deepStrictEqual(parallel.sign(userKey, { parallel: true }), [0, 1, 2, 3, 4]);
+    parallel.sign(bitgoKey, { parallel: true });
+    sequential.sign(userKey);
+    sequential.sign(bitgoKey);
+    assert.deepStrictEqual(parallel.serialize(), sequential.serialize());
+
+    parallel.finalizeAllInputs({ parallel: true });
+    sequential.finalizeAllInputs();
+    assert.deepStrictEqual(parallel.serialize(), sequential.serialize());
+  });
+
+  it("signs with the parallel option like without it with low-R grinding disabled", function () {
+    setLowRGrinding(false);
+    try {
+      const parallel = createPsbt(5);
+      const sequential = createPsbt(5);
+      parallel.sign(userKey, { parallel: true });
+      sequential.sign(userKey);
+      assert.deepStrictEqual(parallel.serialize(), sequential.serialize());
+    } finally {
+      setLowRGrinding(true);
+    }
+  });
+
+  it("rejects the parallel option combined with progress options", function () {
+    const psbt = createPsbt(2);
+    assert.throws(
+      () => psbt.sign(userKey, { parallel: true, onProgress: () => undefined }),
+      /cannot be combined with progress options/,
+    );
+  });
+});
@@ -0,0 +1,43 @@
+/**
+ * Tests for redacting a PSBT before sharing it with an external signer
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { getKeyTriple, getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt redact", function () {
+  const [userKey] = getKeyTriple("redact");
+  const walletKeys = getWalletKeysForSeed("redact");
+
+  function createPsbt(): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    [0, 20].forEach((chain, vout) => {
+      psbt.addWalletInput({ txid: "00".repeat(32), vout, value: 10_000n }, walletKeys, {
+        scriptId: { chain, index: 0 },
+      });
+    });
+    psbt.addWalletOutput(walletKeys, { chain: 21, index: 0, value: 15_000n });
+    return psbt;
+  }
+
+  it("removes wallet structure but keeps the transaction", function () {
+    const psbt = createPsbt();
+    const redacted = psbt.redact([1]);
+    assert.strictEqual(psbt.walletKeysMatch(walletKeys), true);
+    assert.strictEqual(redacted.walletKeysMatch(walletKeys), false);
+    assert.deepStrictEqual(redacted.getUnsignedTransaction(), psbt.getUnsignedTransaction());
+    assert.ok(redacted.serialize().length < psbt.serialize().length);
+  });
+
+  it("keeps the selected inputs signable", function () {
+    const redacted = createPsbt().redact([1]);
+    redacted.sign(userKey);
+    assert.strictEqual(redacted.verifySignature(0, userKey), false);
+    assert.strictEqual(redacted.verifySignature(1, userKey), true);
+  });
+
+  it("rejects out of bounds inputs", function () {
+    assert.throws(() => createPsbt().redact([2]), /out of bounds/);
+  });
+});
@@ -0,0 +1,53 @@
+/**
+ * Tests for constructing ReplayProtection from addresses
+ */
+import assert from "node:assert";
+import { address as addressNs, fixedScriptWallet } from "../../js/index.js";
+
+const { ReplayProtection } = fixedScriptWallet;
+
+// Compressed public key for private key 0x01 * 32
+const PUBKEY = Buffer.from(
+  "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f",
+  "hex",
+);
+
+describe("ReplayProtection.fromAddresses", function () {
+  const script = fixedScriptWallet.p2shP2pkOutputScript(PUBKEY);
+
+  it("decodes addresses of any network without a network argument", function () {
+    const addresses = [
+      addressNs.fromOutputScriptWithCoin(script, "btc"),
+      addressNs.fromOutputScriptWithCoin(script, "bch", "cashaddr"),
+      addressNs.fromOutputScriptWithCoin(script, "ltc"),
+      addressNs.fromOutputScriptWithCoin(script, "tbtc"),
+    ];
+    const rp = ReplayProtection.fromAddresses(addresses);
+    assert.strictEqual(rp.outputScripts.length, addresses.length);
+    for (const outputScript of rp.outputScripts) {
+      assert.deepStrictEqual(Buffer.from(outputScript), Buffer.from(script));
+    }
+  });
+
+  it("matches the public key and output script constructors", function () {
+    const fromAddresses = ReplayProtection.fromAddresses([
+      addressNs.fromOutputScriptWithCoin(script, "bch"),
+    ]);
+    const fromPublicKeys = ReplayProtection.fromPublicKeys([PUBKEY]);
+    const fromOutputScripts = ReplayProtection.fromOutputScripts([script]);
+    assert.deepStrictEqual(fromAddresses.outputScripts, fromPublicKeys.outputScripts);
+    assert.deepStrictEqual(fromAddresses.outputScripts, fromOutputScripts.outputScripts);
+  });
+
+  it("still checks the network when one is given", function () {
+    const btcAddress = addressNs.fromOutputScriptWithCoin(script, "btc");
+    assert.throws(() => ReplayProtection.fromAddresses([btcAddress], "ltc"), /Failed to decode/);
+  });
+
+  it("rejects invalid addresses", function () {
+    assert.throws(
+      () => ReplayProtection.fromAddresses(["not-an-address"]),
+      /not an address of any supported network/,
+    );
+  });
+});
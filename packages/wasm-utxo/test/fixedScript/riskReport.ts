@@ -0,0 +1,53 @@
+/**
+ * Tests for the risk report of a parsed PSBT
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt riskReport", function () {
+  const walletKeys = getWalletKeysForSeed("riskReport");
+  const replayProtection = { publicKeys: [] };
+  // p2wpkh of an all-zero key hash
+  const externalScript = new Uint8Array([0x00, 0x14, ...new Array<number>(20).fill(0)]);
+
+  function createPsbt(): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    psbt.addWalletInput({ txid: "00".repeat(32), vout: 0, value: 100_000n }, walletKeys, {
+      scriptId: { chain: 20, index: 0 },
+    });
+    return psbt;
+  }
+
+  it("reports no flags for a plain spend", function () {
+    const psbt = createPsbt();
+    psbt.addWalletOutput(walletKeys, { chain: 20, index: 1, value: 50_000n });
+    psbt.addOutput(externalScript, 49_000n);
+    const report = psbt.riskReport(walletKeys, { replayProtection });
+    assert.strictEqual(report.hasFlags, false);
+    assert.strictEqual(report.minerFee, 1_000n);
+  });
+
+  it("reports red flags", function () {
+    const psbt = createPsbt();
+    psbt.addInput({ txid: "00".repeat(32), vout: 1, value: 10_000n }, externalScript);
+    psbt.addWalletOutput(walletKeys, { chain: 20, index: 0, value: 50_000n });
+    psbt.addOutput(externalScript, 200n);
+    psbt.addOutput(new Uint8Array([0x6a, 0x02, 0xca, 0xfe]), 0n);
+
+    const report = psbt.riskReport(walletKeys, { replayProtection, maxFee: 10_000n });
+    assert.strictEqual(report.hasFlags, true);
+    assert.deepStrictEqual(report.externalInputs, [1]);
+    assert.deepStrictEqual(report.dustOutputs, [
+      { outputIndex: 1, value: 200n, dustThreshold: 294n },
+    ]);
+    assert.strictEqual(report.opReturnOutputs.length, 1);
+    assert.deepStrictEqual(report.opReturnOutputs[0].data, new Uint8Array([0xca, 0xfe]));
+    assert.strictEqual(report.feeAboveThreshold, true);
+    assert.deepStrictEqual(
+      report.reusedAddresses.map((a) => [a.inputIndices, a.outputIndices]),
+      [[[0], [0]]],
+    );
+  });
+});
@@ -0,0 +1,67 @@
+/**
+ * Tests for input sequence modes (replace-by-fee signaling and relative lock times)
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt, type SequenceMode } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { getKeyTriple, getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt sequence modes", function () {
+  const [userKey] = getKeyTriple("sequence_mode");
+  const walletKeys = getWalletKeysForSeed("sequence_mode");
+  const replayProtection = { publicKeys: [] };
+
+  function createPsbt(sequences: (SequenceMode | undefined)[]): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    sequences.forEach((sequence, vout) => {
+      psbt.addWalletInput({ txid: "00".repeat(32), vout, value: 10_000n, sequence }, walletKeys, {
+        scriptId: { chain: 20, index: 0 },
+      });
+    });
+    return psbt;
+  }
+
+  function getSequences(psbt: BitGoPsbt): number[] {
+    return psbt
+      .parseTransactionWithWalletKeys(walletKeys, { replayProtection })
+      .inputs.map((input) => input.sequence);
+  }
+
+  it("encodes sequence modes", function () {
+    const psbt = createPsbt([
+      undefined,
+      "finalNoRbf",
+      "rbfEnabled",
+      { relativeLockBlocks: 144 },
+      { relativeLockTime: 10 },
+      0xffffffff,
+    ]);
+    assert.deepStrictEqual(getSequences(psbt), [
+      0xfffffffe,
+      0xfffffffe,
+      0xfffffffd,
+      144,
+      (1 << 22) | 10,
+      0xffffffff,
+    ]);
+  });
+
+  it("rejects invalid sequence modes", function () {
+    assert.throws(() => createPsbt(["rbf" as SequenceMode]), /Invalid sequence mode/);
+    assert.throws(() => createPsbt([{ relativeLockBlocks: 70_000 }]), /out of range/);
+  });
+
+  it("sets the sequence of all inputs", function () {
+    const psbt = createPsbt([undefined, { relativeLockBlocks: 6 }]);
+    psbt.setSequenceMode("rbfEnabled");
+    assert.deepStrictEqual(getSequences(psbt), [0xfffffffd, 0xfffffffd]);
+    psbt.setSequenceMode("finalNoRbf");
+    assert.deepStrictEqual(getSequences(psbt), [0xfffffffe, 0xfffffffe]);
+  });
+
+  it("rejects changing the sequence of a signed PSBT", function () {
+    const psbt = createPsbt([undefined]);
+    psbt.sign(userKey);
+    assert.throws(() => psbt.setSequenceMode("rbfEnabled"), /has signatures/);
+  });
+});
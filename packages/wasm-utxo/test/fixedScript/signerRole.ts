@@ -0,0 +1,49 @@
+/**
+ * Tests for inferring the role of a signing key from the PSBT global xpubs
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { getKeyTriple, getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt.getSignerRole", function () {
+  const walletKeys = getWalletKeysForSeed("signer_role");
+  const xprvs = getKeyTriple("signer_role");
+
+  function createPsbt(): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    psbt.addWalletInput({ txid: "00".repeat(32), vout: 0, value: 10_000n }, walletKeys, {
+      scriptId: { chain: 20, index: 0 },
+    });
+    psbt.addOutput("1BoatSLRHtKNngkdXEeobR76b53LETtpyT", 9_000n);
+    return psbt;
+  }
+
+  it("reports the role of each wallet key", function () {
+    const psbt = createPsbt();
+    assert.deepStrictEqual(
+      xprvs.map((key) => psbt.getSignerRole(key)),
+      ["user", "backup", "bitgo"],
+    );
+    assert.strictEqual(psbt.getSignerRole(xprvs[2].neutered()), "bitgo");
+  });
+
+  it("returns null for a key of another wallet", function () {
+    const psbt = createPsbt();
+    const [otherKey] = getKeyTriple("signer_role_other");
+    assert.strictEqual(psbt.getSignerRole(otherKey), null);
+    assert.throws(() => psbt.signInput(0, otherKey), /is not one of the wallet keys/);
+  });
+
+  it("throws a coded error if the PSBT has no global xpubs", function () {
+    const psbt = createPsbt().redact([0]);
+    assert.throws(
+      () => psbt.getSignerRole(xprvs[0]),
+      (e: Error & { code?: string }) => {
+        assert.strictEqual(e.code, "SignerRoleError.MissingGlobalXpubs");
+        assert.strictEqual(e.message, "PSBT has no global xpubs");
+        return true;
+      },
+    );
+  });
+});
@@ -0,0 +1,81 @@
+/**
+ * Tests for the signing policy checked by sign() and signWithPolicy()
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { getKeyTriple, getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt signing policy", function () {
+  const [userKey] = getKeyTriple("signingPolicy");
+  const walletKeys = getWalletKeysForSeed("signingPolicy");
+  const replayProtection = { publicKeys: [] };
+
+  function createPsbt(fee: bigint): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    psbt.addWalletInput({ txid: "00".repeat(32), vout: 0, value: 100_000n }, walletKeys, {
+      scriptId: { chain: 20, index: 0 },
+    });
+    psbt.addWalletOutput(walletKeys, { chain: 20, index: 1, value: 100_000n - fee });
+    return psbt;
+  }
+
+  it("accepts a fractional maximum fee rate", function () {
+    // 20 sats is well below 0.5 sat/vB for any 1-in-1-out transaction; truncating the rate to
+    // 0 sat/vB would reject it
+    const psbt = createPsbt(20n);
+    const signed = psbt.sign(userKey, {
+      signingPolicy: { walletKeys, replayProtection, policy: { maxFeeRateSatPerVb: 0.5 } },
+    });
+    assert.deepStrictEqual(signed, [0]);
+  });
+
+  it("rejects a fee rate above a fractional maximum", function () {
+    const psbt = createPsbt(1_000n);
+    assert.throws(
+      () =>
+        psbt.signWithPolicy(userKey, walletKeys, {
+          replayProtection,
+          policy: { maxFeeRateSatPerVb: 0.5 },
+        }),
+      /Signing policy violated/,
+    );
+    assert.strictEqual(psbt.verifySignature(0, userKey), false);
+  });
+
+  it("rejects an invalid maximum fee rate", function () {
+    const psbt = createPsbt(20n);
+    for (const maxFeeRateSatPerVb of [NaN, -1]) {
+      assert.throws(
+        () =>
+          psbt.sign(userKey, {
+            signingPolicy: { walletKeys, replayProtection, policy: { maxFeeRateSatPerVb } },
+          }),
+        /Invalid fee rate/,
+      );
+    }
+  });
+
+  it("checks the policy in sign() before signing any input", function () {
+    const psbt = createPsbt(1_000n);
+    assert.throws(
+      () =>
+        psbt.sign(userKey, {
+          signingPolicy: { walletKeys, replayProtection, policy: { maxFee: 999n } },
+        }),
+      /Signing policy violated/,
+    );
+    assert.strictEqual(psbt.verifySignature(0, userKey), false);
+    assert.deepStrictEqual(
+      psbt.sign(userKey, {
+        signingPolicy: {
+          walletKeys,
+          replayProtection,
+          policy: { maxFee: 999n },
+          overridePolicy: true,
+        },
+      }),
+      [0],
+    );
+  });
+});
@@ -0,0 +1,40 @@
+/**
+ * Tests for BIP-352 silent payment outputs
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt silent payments", function () {
+  const walletKeys = getWalletKeysForSeed("silent_payments");
+
+  function createPsbt(chain: number): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    psbt.addWalletInput({ txid: "00".repeat(32), vout: 0, value: 10_000n }, walletKeys, {
+      scriptId: { chain, index: 0 },
+      signPath: chain >= 30 ? { signer: "user", cosigner: "bitgo" } : undefined,
+    });
+    return psbt;
+  }
+
+  it("rejects invalid addresses", function () {
+    const psbt = createPsbt(20);
+    assert.throws(
+      () => psbt.addSilentPaymentOutputs([{ address: "sp1qinvalid", value: 1_000n }], []),
+      /Invalid silent payment address/,
+    );
+  });
+
+  it("requires an eligible input", function () {
+    // Multisig inputs do not contribute to the shared secret
+    assert.throws(() => createPsbt(20).addSilentPaymentOutputs([], []), /no inputs eligible/);
+  });
+
+  it("requires keys for all taproot inputs", function () {
+    assert.throws(
+      () => createPsbt(30).addSilentPaymentOutputs([], []),
+      /Input 0 is eligible for silent payments but no key was given/,
+    );
+  });
+});
@@ -0,0 +1,65 @@
+/**
+ * Tests for single-sig inputs spending outputs of a single wallet key
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { Dimensions } from "../../js/fixedScriptWallet/Dimensions.js";
+import { singleSigScriptTypes } from "../../js/fixedScriptWallet/scriptType.js";
+import { getKeyTriple, getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt single-sig inputs", function () {
+  const seed = "single_sig";
+  const walletKeys = getWalletKeysForSeed(seed);
+  const userPrivateKey = getKeyTriple(seed)[0].derivePath("m/0/7").privateKey as Uint8Array;
+
+  for (const scriptType of singleSigScriptTypes) {
+    it(`adds, parses, signs and finalizes a ${scriptType} input`, function () {
+      const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+      psbt.addSingleSigInput({ txid: "00".repeat(32), vout: 0, value: 10_000n }, walletKeys, {
+        signer: "user",
+        derivationPath: "m/0/7",
+        scriptType,
+      });
+      psbt.addWalletOutput(walletKeys, { chain: 20, index: 0, value: 9_000n });
+
+      const parsed = psbt.parseTransactionWithWalletKeys(walletKeys, {
+        replayProtection: { publicKeys: [] },
+      });
+      assert.strictEqual(parsed.inputs[0].scriptType, scriptType);
+      assert.strictEqual(parsed.inputs[0].scriptId, null);
+      assert.strictEqual(parsed.inputs[0].derivationPath, "0/7");
+      assert.strictEqual(parsed.minerFee, 1_000n);
+
+      assert.strictEqual(
+        Dimensions.fromPsbt(psbt).getInputWeight(),
+        Dimensions.fromInput({ scriptType }).getInputWeight(),
+      );
+
+      psbt.sign(0, userPrivateKey);
+      psbt.finalizeAllInputs();
+      assert.ok(psbt.extractTransaction().toBytes().length > 0);
+    });
+  }
+
+  it("rejects a key that does not match the input script", function () {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    psbt.addSingleSigInput({ txid: "00".repeat(32), vout: 0, value: 10_000n }, walletKeys, {
+      signer: "user",
+      derivationPath: "m/0/8",
+      scriptType: "p2wpkh",
+    });
+    assert.throws(() => psbt.sign(0, userPrivateKey), /does not match/);
+  });
+
+  it("rejects script types the network does not support", function () {
+    const psbt = BitGoPsbt.createEmpty("bch", walletKeys, { version: 2, lockTime: 0 });
+    assert.throws(() =>
+      psbt.addSingleSigInput({ txid: "00".repeat(32), vout: 0, value: 10_000n }, walletKeys, {
+        signer: "backup",
+        derivationPath: "m/0/0",
+        scriptType: "p2trKeyPath",
+      }),
+    );
+  });
+});
@@ -0,0 +1,49 @@
+/**
+ * Tests for detecting inputs that spend the same outpoint
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt.findSpendConflicts", function () {
+  const walletKeys = getWalletKeysForSeed("spendConflicts");
+  const txid = "00".repeat(32);
+
+  function createPsbt(vouts: number[]): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    for (const vout of vouts) {
+      psbt.addWalletInput({ txid, vout, value: 10_000n }, walletKeys, {
+        scriptId: { chain: 20, index: 0 },
+      });
+    }
+    return psbt;
+  }
+
+  it("reports duplicate inputs and conflicting spends", function () {
+    const psbts = [createPsbt([0, 1, 0]), createPsbt([2, 1])];
+    const conflicts = BitGoPsbt.findSpendConflicts(psbts.map((psbt) => psbt.serialize()), "btc");
+    assert.deepStrictEqual(conflicts, [
+      {
+        txid,
+        vout: 0,
+        kind: "duplicateInput",
+        inputs: [
+          { psbtIndex: 0, inputIndex: 0 },
+          { psbtIndex: 0, inputIndex: 2 },
+        ],
+      },
+      {
+        txid,
+        vout: 1,
+        kind: "conflictingSpend",
+        inputs: [
+          { psbtIndex: 0, inputIndex: 1 },
+          { psbtIndex: 1, inputIndex: 1 },
+        ],
+      },
+    ]);
+    assert.deepStrictEqual(psbts[0].duplicateInputs(), conflicts.slice(0, 1));
+    assert.deepStrictEqual(psbts[1].duplicateInputs(), []);
+  });
+});
@@ -0,0 +1,52 @@
+/**
+ * Tests for transaction packages (parent + child)
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { TxPackage } from "../../js/fixedScriptWallet/TxPackage.js";
+import { getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("TxPackage", function () {
+  const walletKeys = getWalletKeysForSeed("tx_package");
+  const opReturn = new Uint8Array([0x6a, 0x01, 0x00]);
+
+  function createPsbt(txid: string, value: bigint, outputValue: bigint): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    psbt.addWalletInput({ txid, vout: 0, value }, walletKeys, {
+      scriptId: { chain: 20, index: 0 },
+    });
+    psbt.addOutput(opReturn, outputValue);
+    return psbt;
+  }
+
+  it("orders parents first and computes the package fee", function () {
+    const parent = createPsbt("00".repeat(32), 100_000n, 99_900n);
+    const child = createPsbt(parent.unsignedTxId(), 99_900n, 98_900n);
+
+    const pkg = TxPackage.create([child, parent], "btc");
+    assert.deepStrictEqual(pkg.txids, [parent.unsignedTxId(), child.unsignedTxId()]);
+
+    const fee = pkg.packageFee();
+    assert.deepStrictEqual(fee.fees, [100n, 1_000n]);
+    assert.strictEqual(fee.totalFee, 1_100n);
+    assert.ok(fee.totalVsize > 0);
+    assert.strictEqual(fee.feeRate, 1_100 / fee.totalVsize);
+
+    assert.doesNotThrow(() => pkg.checkFeeRate(1));
+    assert.throws(() => pkg.checkFeeRate(1_000), /below the minimum/);
+    // Unsigned transactions cannot be extracted
+    assert.throws(() => pkg.toSubmitPackage());
+  });
+
+  it("requires the child to spend every parent", function () {
+    const parent = createPsbt("00".repeat(32), 100_000n, 99_900n);
+    const unrelated = createPsbt("11".repeat(32), 50_000n, 49_000n);
+    const child = createPsbt(parent.unsignedTxId(), 99_900n, 98_900n);
+    assert.throws(
+      () => TxPackage.create([parent, unrelated, child], "btc"),
+      /not child-with-parents/,
+    );
+    assert.throws(() => TxPackage.create([], "btc"), /no transactions/);
+  });
+});
@@ -0,0 +1,82 @@
+/**
+ * Tests for vault policy fields stored in BitGo proprietary PSBT fields
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt vault policy", function () {
+  const walletKeys = getWalletKeysForSeed("vault_policy");
+
+  function createPsbt(): BitGoPsbt {
+    const psbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+    psbt.addWalletInput({ txid: "00".repeat(32), vout: 0, value: 10_000n }, walletKeys, {
+      scriptId: { chain: 0, index: 0 },
+    });
+    psbt.addOutput("1BoatSLRHtKNngkdXEeobR76b53LETtpyT", 9_000n);
+    return psbt;
+  }
+
+  it("round-trips the policy through serialization", function () {
+    const psbt = createPsbt();
+    assert.deepStrictEqual(psbt.getVaultPolicy(), {
+      spendLimit: null,
+      destinationWhitelistHash: null,
+      approvalQuorumId: null,
+    });
+
+    const policy = {
+      spendLimit: 100_000n,
+      destinationWhitelistHash: new Uint8Array(32).fill(7),
+      approvalQuorumId: "quorum-1",
+    };
+    psbt.setVaultPolicy(policy);
+    const restored = BitGoPsbt.fromBytes(psbt.serialize(), "btc");
+    assert.deepStrictEqual(restored.getVaultPolicy(), policy);
+
+    restored.setVaultPolicy({ spendLimit: 5n });
+    assert.deepStrictEqual(restored.getVaultPolicy(), {
+      spendLimit: 5n,
+      destinationWhitelistHash: null,
+      approvalQuorumId: null,
+    });
+  });
+
+  it("includes the policy in the parsed transaction", function () {
+    const psbt = createPsbt();
+    const options = { replayProtection: { publicKeys: [] } };
+    assert.strictEqual(psbt.parseTransactionWithWalletKeys(walletKeys, options).vaultPolicy, null);
+
+    psbt.setVaultPolicy({ spendLimit: 8_000n });
+    const parsed = psbt.parseTransactionWithWalletKeys(walletKeys, options);
+    assert.strictEqual(parsed.vaultPolicy?.spendLimit, 8_000n);
+    assert.ok(parsed.spendAmount > (parsed.vaultPolicy?.spendLimit as bigint));
+  });
+
+  it("rejects a spend limit that is not a bigint", function () {
+    assert.throws(
+      () => createPsbt().setVaultPolicy({ spendLimit: "100000" as unknown as bigint }),
+      (e: Error & { code?: string }) => {
+        assert.strictEqual(e.code, "AmountError.NotBigInt");
+        assert.strictEqual(e.message, "Expected a bigint amount, got string (field: spendLimit)");
+        return true;
+      },
+    );
+    assert.throws(
+      () => createPsbt().setVaultPolicy({ spendLimit: 100_000 as unknown as bigint }),
+      (e: Error & { code?: string }) => {
+        assert.strictEqual(e.code, "AmountError.NotBigInt");
+        assert.match(e.message, /Expected a bigint amount, got number/);
+        return true;
+      },
+    );
+  });
+
+  it("rejects a whitelist hash of the wrong length", function () {
+    assert.throws(
+      () => createPsbt().setVaultPolicy({ destinationWhitelistHash: new Uint8Array(31) }),
+      /Expected 32 bytes/,
+    );
+  });
+});
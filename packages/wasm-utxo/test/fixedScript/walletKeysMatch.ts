@@ -0,0 +1,31 @@
+/**
+ * Tests for matching PSBT global xpubs against wallet keys
+ */
+import { describe, it } from "mocha";
+import * as assert from "assert";
+import { BitGoPsbt } from "../../js/fixedScriptWallet/BitGoPsbt.js";
+import { getDefaultWalletKeys, getWalletKeysForSeed } from "../../js/testutils/keys.js";
+
+describe("BitGoPsbt wallet keys match", function () {
+  const walletKeys = getDefaultWalletKeys();
+  const otherWalletKeys = getWalletKeysForSeed("other");
+  const emptyPsbt = BitGoPsbt.createEmpty("btc", walletKeys, { version: 2, lockTime: 0 });
+  const psbtBytes = emptyPsbt.serialize();
+
+  it("walletKeysMatch compares global xpubs", function () {
+    const psbt = BitGoPsbt.fromBytes(psbtBytes, "btc");
+    assert.strictEqual(psbt.walletKeysMatch(walletKeys), true);
+    assert.strictEqual(psbt.walletKeysMatch(otherWalletKeys), false);
+  });
+
+  it("fromBytes accepts matching wallet keys", function () {
+    BitGoPsbt.fromBytes(psbtBytes, "btc", { walletKeys });
+  });
+
+  it("fromBytes rejects other wallet keys", function () {
+    assert.throws(
+      () => BitGoPsbt.fromBytes(psbtBytes, "btc", { walletKeys: otherWalletKeys }),
+      /PSBT global xpubs do not match the wallet keys/,
+    );
+  });
+});
@@ -0,0 +1,47 @@
+/**
+ * Tests for the inspect node tree
+ */
+import { before, describe, it } from "mocha";
+import * as assert from "assert";
+import { isInspectEnabled, parseTxToNode, type Node } from "../js/inspect/index.js";
+
+function findNode(node: Node, label: string): Node | undefined {
+  if (node.label === label) {
+    return node;
+  }
+  for (const child of node.children) {
+    const found = findNode(child, label);
+    if (found) {
+      return found;
+    }
+  }
+  return undefined;
+}
+
+describe("inspect", function () {
+  before(function () {
+    if (!isInspectEnabled()) {
+      this.skip();
+    }
+  });
+
+  it("returns output values above 2^53 as exact bigints", function () {
+    // 1 input, 1 OP_RETURN output of 2^53 + 1
+    const tx = Buffer.from(
+      "01000000" +
+        "01" +
+        "00".repeat(32) +
+        "00000000" +
+        "00" +
+        "ffffffff" +
+        "01" +
+        "0100000000002000" +
+        "016a" +
+        "00000000",
+      "hex",
+    );
+    const node = parseTxToNode(tx, "doge");
+    const value = findNode(node, "value");
+    assert.deepStrictEqual(value?.value, { type: "U64", value: 9_007_199_254_740_993n });
+  });
+});
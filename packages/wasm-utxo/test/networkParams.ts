@@ -0,0 +1,93 @@
+import * as assert from "assert";
+import * as utxolib from "@bitgo/utxo-lib";
+import {
+  getNetworkCapabilities,
+  getNetworkParams,
+  getRelayFeeRates,
+  validateFeeRate,
+} from "../js/networks.js";
+import type { UtxolibName } from "../js/utxolibCompat.js";
+
+describe("getNetworkParams", function () {
+  for (const name of Object.keys(utxolib.networks) as UtxolibName[]) {
+    it(`matches utxo-lib for ${name}`, function () {
+      const network = utxolib.networks[name];
+      const params = getNetworkParams(name);
+      assert.strictEqual(params.utxolibName, name);
+      assert.strictEqual(params.mainnet, utxolib.isMainnet(network));
+      assert.strictEqual(params.bech32Hrp, network.bech32 ?? null);
+      assert.deepStrictEqual(params.bip32, network.bip32);
+    });
+  }
+
+  it("returns signet and testnet4 genesis hashes", function () {
+    const signet = getNetworkParams("tbtcbgsig");
+    assert.strictEqual(signet.bech32Hrp, "tb");
+    assert.strictEqual(
+      signet.genesisBlockHash,
+      "00000008819873e925422c1ff0f99f7cc9bbb232af63a077a480a3633bee1ef6",
+    );
+    assert.strictEqual(getNetworkParams("tbtcsig").genesisBlockHash, signet.genesisBlockHash);
+    assert.strictEqual(
+      getNetworkParams("bitcoinTestnet4").genesisBlockHash,
+      "00000000da84f2bafbbc53dee25a72ae507ff4914b867c565be350b0da8bf043",
+    );
+    assert.strictEqual(getNetworkParams("ltc").genesisBlockHash, null);
+  });
+});
+
+describe("relay fees", function () {
+  it("returns the relay fee rates", function () {
+    assert.deepStrictEqual(getRelayFeeRates("btc"), {
+      minRelayFee: 1_000n,
+      incrementalRelayFee: 1_000n,
+      dustOutputLimit: null,
+    });
+    assert.strictEqual(getRelayFeeRates("tdoge")?.dustOutputLimit, 1_000_000n);
+    assert.strictEqual(getRelayFeeRates("bsv"), null);
+  });
+
+  it("validates the fee against the minimum relay fee", function () {
+    validateFeeRate("btc", 141n, 141, [10_000n]);
+    assert.throws(
+      () => validateFeeRate("btc", 140n, 141, [10_000n]),
+      (e: Error & { code?: string }) => {
+        assert.strictEqual(e.code, "RelayFeeError.BelowMinRelayFee");
+        assert.match(e.message, /minimum relay fee of 141/);
+        return true;
+      },
+    );
+    // Each output below 0.01 DOGE adds 0.01 DOGE
+    validateFeeRate("doge", 226_000n, 226, [1_000_000n]);
+    assert.throws(() => validateFeeRate("doge", 226_000n, 226, [1n]), /minimum relay fee/);
+  });
+});
+
+describe("getNetworkCapabilities", function () {
+  it("returns the capabilities of bitcoin", function () {
+    assert.deepStrictEqual(getNetworkCapabilities("btc"), {
+      segwit: true,
+      taproot: true,
+      p2mr: true,
+      sighashForkId: null,
+      csv: true,
+      scriptTypes: ["p2sh", "p2shP2wsh", "p2wsh", "p2trLegacy", "p2trMusig2", "p2mr"],
+      addressEncodings: ["base58check", "bech32", "bech32m"],
+      relayFeeRates: getRelayFeeRates("btc"),
+      maxMoney: 2_100_000_000_000_000n,
+      psbtFormat: "bitcoin",
+    });
+  });
+
+  it("returns the quirks of forks", function () {
+    const bch = getNetworkCapabilities("tbch");
+    assert.deepStrictEqual(bch.scriptTypes, ["p2sh"]);
+    assert.deepStrictEqual(bch.addressEncodings, ["base58check", "cashaddr"]);
+    assert.strictEqual(bch.sighashForkId, 0);
+    assert.strictEqual(getNetworkCapabilities("btg").sighashForkId, 79);
+    assert.strictEqual(getNetworkCapabilities("bsv").csv, false);
+    assert.strictEqual(getNetworkCapabilities("doge").relayFeeRates?.dustOutputLimit, 1_000_000n);
+    assert.strictEqual(getNetworkCapabilities("dash").psbtFormat, "dash");
+    assert.strictEqual(getNetworkCapabilities("zec").psbtFormat, "zcash");
+  });
+});
@@ -0,0 +1,30 @@
+import * as assert from "assert";
+import { ECPair } from "../js/ecpair.js";
+import { psbtEnvelope } from "../js/index.js";
+
+describe("psbtEnvelope", () => {
+  const sender = ECPair.fromPrivateKey(new Uint8Array(32).fill(1));
+  const recipient = ECPair.fromPrivateKey(new Uint8Array(32).fill(2));
+  const other = ECPair.fromPrivateKey(new Uint8Array(32).fill(3));
+  const psbt = new Uint8Array([0x70, 0x73, 0x62, 0x74, 0xff, 0x00, 0x00]);
+
+  it("round-trips a PSBT between sender and recipient", () => {
+    const envelope = psbtEnvelope.seal(psbt, sender, ECPair.fromPublicKey(recipient.publicKey));
+    const header = psbtEnvelope.readHeader(envelope);
+    assert.deepStrictEqual(header.sender, psbtEnvelope.keyId(sender));
+    assert.deepStrictEqual(header.recipient, psbtEnvelope.keyId(recipient));
+
+    const opened = psbtEnvelope.open(envelope, recipient, ECPair.fromPublicKey(sender.publicKey));
+    assert.deepStrictEqual(opened, psbt);
+  });
+
+  it("rejects wrong keys and modified envelopes", () => {
+    const envelope = psbtEnvelope.seal(psbt, sender, recipient);
+    assert.throws(() => psbtEnvelope.open(envelope, other, sender), /recipient key id/);
+    assert.throws(() => psbtEnvelope.open(envelope, recipient, other), /sender key id/);
+
+    const tampered = envelope.slice();
+    tampered[tampered.length - 1] ^= 1;
+    assert.throws(() => psbtEnvelope.open(tampered, recipient, sender), /Failed to decrypt/);
+  });
+});
@@ -117,6 +117,10 @@ describe("Descriptor fixtures", function () {
             descriptor.atDerivationIndex(fixture.index ?? 0).scriptPubkey(),
           );
           assert.strictEqual(scriptPubKey.toString("hex"), fixture.script);
+          assert.deepStrictEqual(
+            Buffer.from(descriptor.scriptPubkeyAt(fixture.index ?? 0)),
+            scriptPubKey,
+          );
           const descType = descriptor.descType() as string;
           if (descType !== "Bare") {
             assert.strictEqual(
@@ -131,6 +135,7 @@ describe("Descriptor fixtures", function () {
         }
 
         assert.ok(Number.isInteger(descriptor.maxWeightToSatisfy()));
+        assert.strictEqual(descriptor.checksum(), descriptor.toString().split("#")[1]);
         assertKnownDescriptorType(descriptor);
       });
 
@@ -0,0 +1,62 @@
+import assert from "node:assert";
+import * as utxolib from "@bitgo/utxo-lib";
+import { fixedScriptWallet, tracing } from "../js/index.js";
+
+function createPsbt(): fixedScriptWallet.BitGoPsbt {
+  const triple = utxolib.testutil.getKeyTriple("tracing").map((k) => k.neutered());
+  const walletKeys = fixedScriptWallet.RootWalletKeys.from({
+    triple: triple as [utxolib.BIP32Interface, utxolib.BIP32Interface, utxolib.BIP32Interface],
+    derivationPrefixes: ["0/0", "0/0", "0/0"],
+  });
+  const psbt = fixedScriptWallet.BitGoPsbt.createEmpty("bitcoin", walletKeys);
+  psbt.addWalletInput({ txid: "00".repeat(32), vout: 0, value: 10_000n }, walletKeys, {
+    scriptId: { chain: 20, index: 0 },
+  });
+  psbt.addWalletOutput(walletKeys, { chain: 21, index: 0, value: 9_000n });
+  return psbt;
+}
+
+describe("tracing", function () {
+  if (!tracing.isTracingEnabled()) {
+    it("should throw when the feature is not enabled", function () {
+      assert.throws(() => tracing.setTraceHook(() => undefined), /tracing feature is not enabled/);
+    });
+    return;
+  }
+
+  afterEach(function () {
+    tracing.setTraceHook(undefined);
+  });
+
+  it("should report deserialize spans with input counts and durations", function () {
+    const bytes = createPsbt().serialize();
+    const records: tracing.TraceRecord[] = [];
+    tracing.setTraceHook((record) => records.push(record));
+
+    fixedScriptWallet.BitGoPsbt.fromBytes(bytes, "bitcoin");
+
+    const span = records.find((r) => r.kind === "span" && r.name === "deserialize");
+    assert.ok(span && span.kind === "span", "expected a deserialize span");
+    assert.strictEqual(span.fields.inputs, 1);
+    assert.strictEqual(span.fields.bytes, bytes.length);
+    assert.ok(span.durationMs >= 0);
+  });
+
+  it("should stop reporting once the hook is removed", function () {
+    const bytes = createPsbt().serialize();
+    const records: tracing.TraceRecord[] = [];
+    tracing.setTraceHook((record) => records.push(record));
+    tracing.setTraceHook(undefined);
+
+    fixedScriptWallet.BitGoPsbt.fromBytes(bytes, "bitcoin");
+    assert.strictEqual(records.length, 0);
+  });
+
+  it("should ignore exceptions thrown by the hook", function () {
+    const bytes = createPsbt().serialize();
+    tracing.setTraceHook(() => {
+      throw new Error("hook failure");
+    });
+    fixedScriptWallet.BitGoPsbt.fromBytes(bytes, "bitcoin");
+  });
+});